@@ -0,0 +1,152 @@
+use crate::{
+	error::Result,
+	sync::{
+		branch::get_branch_name, remotes::fetch_upstream_unattended,
+	},
+	AsyncGitNotification, CWD,
+};
+use crossbeam_channel::{
+	unbounded, Receiver, RecvTimeoutError, Sender,
+};
+use std::{
+	sync::{
+		atomic::{AtomicBool, AtomicUsize, Ordering},
+		Arc, Mutex,
+	},
+	thread,
+	time::Duration,
+};
+
+/// periodically fetches the upstream of the current branch in the
+/// background. it never asks for credentials and only logs errors.
+/// the background thread ends once this is dropped
+pub struct AsyncAutoFetch {
+	interval: Arc<Mutex<Option<Duration>>>,
+	paused: Arc<AtomicBool>,
+	abort: Arc<AtomicBool>,
+	new_commits: Arc<AtomicUsize>,
+	reschedule: Sender<()>,
+}
+
+impl AsyncAutoFetch {
+	///
+	pub fn new(sender: &Sender<AsyncGitNotification>) -> Self {
+		let (reschedule, receiver) = unbounded();
+
+		let this = Self {
+			interval: Arc::new(Mutex::new(None)),
+			paused: Arc::new(AtomicBool::new(false)),
+			abort: Arc::new(AtomicBool::new(false)),
+			new_commits: Arc::new(AtomicUsize::new(0)),
+			reschedule,
+		};
+
+		let interval = Arc::clone(&this.interval);
+		let paused = Arc::clone(&this.paused);
+		let abort = Arc::clone(&this.abort);
+		let new_commits = Arc::clone(&this.new_commits);
+		let sender = sender.clone();
+
+		thread::spawn(move || {
+			Self::run(
+				&receiver,
+				&interval,
+				&paused,
+				&abort,
+				&new_commits,
+				&sender,
+			);
+		});
+
+		this
+	}
+
+	/// `None` disables auto fetching, changing the interval restarts
+	/// the countdown
+	pub fn set_interval(
+		&self,
+		interval: Option<Duration>,
+	) -> Result<()> {
+		let mut current = self.interval.lock()?;
+		if *current != interval {
+			*current = interval;
+			self.reschedule.send(()).ok();
+		}
+
+		Ok(())
+	}
+
+	/// while paused due fetches are skipped,
+	/// use this during user initiated remote operations
+	pub fn set_paused(&self, paused: bool) {
+		self.paused.store(paused, Ordering::Relaxed);
+	}
+
+	/// number of commits that arrived upstream since the last call
+	pub fn take_new_commits(&self) -> usize {
+		self.new_commits.swap(0, Ordering::Relaxed)
+	}
+
+	fn run(
+		receiver: &Receiver<()>,
+		interval: &Mutex<Option<Duration>>,
+		paused: &AtomicBool,
+		abort: &Arc<AtomicBool>,
+		new_commits: &AtomicUsize,
+		sender: &Sender<AsyncGitNotification>,
+	) {
+		loop {
+			let wait = interval.lock().map_or(None, |i| *i);
+
+			let res = match wait {
+				Some(wait) => receiver.recv_timeout(wait),
+				None => receiver
+					.recv()
+					.map_err(|_| RecvTimeoutError::Disconnected),
+			};
+
+			match res {
+				// interval changed, start waiting again
+				Ok(_) => continue,
+				Err(RecvTimeoutError::Disconnected) => break,
+				Err(RecvTimeoutError::Timeout) => (),
+			}
+
+			if abort.load(Ordering::Relaxed) {
+				break;
+			}
+
+			if paused.load(Ordering::Relaxed) {
+				log::debug!("auto fetch skipped: remote op running");
+				continue;
+			}
+
+			match Self::fetch(abort) {
+				Ok(Some(0)) => (),
+				Ok(Some(count)) => {
+					new_commits.fetch_add(count, Ordering::Relaxed);
+					sender.send(AsyncGitNotification::AutoFetch).ok();
+				}
+				Ok(None) => {
+					log::debug!(
+						"auto fetch skipped: no upstream or credentials required"
+					);
+				}
+				Err(e) => log::error!("auto fetch error: {}", e),
+			}
+		}
+	}
+
+	fn fetch(abort: &Arc<AtomicBool>) -> Result<Option<usize>> {
+		let branch = get_branch_name(CWD)?;
+		fetch_upstream_unattended(CWD, &branch, Arc::clone(abort))
+	}
+}
+
+impl Drop for AsyncAutoFetch {
+	fn drop(&mut self) {
+		// cancels a transfer that is in flight right now,
+		// dropping `reschedule` ends the waiting thread
+		self.abort.store(true, Ordering::Relaxed);
+	}
+}
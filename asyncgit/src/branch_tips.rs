@@ -0,0 +1,141 @@
+//!
+
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	sync::{get_commits_info, merged_tips, CommitId, CommitInfo},
+	AsyncGitNotification, CWD,
+};
+
+use std::{
+	collections::HashSet,
+	sync::{Arc, Mutex},
+};
+
+/// subjects are cut here, the branch list never shows more
+const MESSAGE_LIMIT: usize = 200;
+
+enum JobState {
+	Request(Vec<CommitId>),
+	Response(Result<Vec<CommitInfo>>),
+}
+
+/// looks up the tip commits of branches
+#[derive(Clone, Default)]
+pub struct AsyncBranchTipsJob {
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+///
+impl AsyncBranchTipsJob {
+	///
+	pub fn new(ids: Vec<CommitId>) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request(ids)))),
+		}
+	}
+
+	///
+	pub fn result(&self) -> Option<Result<Vec<CommitInfo>>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request(_) => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+}
+
+impl AsyncJob for AsyncBranchTipsJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		if let Ok(mut state) = self.state.lock() {
+			*state = state.take().map(|state| match state {
+				JobState::Request(ids) => JobState::Response(
+					get_commits_info(CWD, &ids, MESSAGE_LIMIT),
+				),
+				JobState::Response(result) => {
+					JobState::Response(result)
+				}
+			});
+		}
+
+		Ok(AsyncGitNotification::BranchTips)
+	}
+}
+
+enum MergedJobState {
+	Request(CommitId, Vec<CommitId>),
+	Response(CommitId, Result<HashSet<CommitId>>),
+}
+
+/// finds which branch tips are merged into a base commit
+#[derive(Clone, Default)]
+pub struct AsyncMergedTipsJob {
+	state: Arc<Mutex<Option<MergedJobState>>>,
+}
+
+///
+impl AsyncMergedTipsJob {
+	///
+	pub fn new(base: CommitId, tips: Vec<CommitId>) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(
+				MergedJobState::Request(base, tips),
+			))),
+		}
+	}
+
+	/// the base the job ran against and the merged tips
+	pub fn result(
+		&self,
+	) -> Option<(CommitId, Result<HashSet<CommitId>>)> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					MergedJobState::Request(..) => None,
+					MergedJobState::Response(base, result) => {
+						Some((base, result))
+					}
+				};
+			}
+		}
+
+		None
+	}
+}
+
+impl AsyncJob for AsyncMergedTipsJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		if let Ok(mut state) = self.state.lock() {
+			*state = state.take().map(|state| match state {
+				MergedJobState::Request(base, tips) => {
+					MergedJobState::Response(
+						base,
+						merged_tips(CWD, base, &tips),
+					)
+				}
+				MergedJobState::Response(base, result) => {
+					MergedJobState::Response(base, result)
+				}
+			});
+		}
+
+		Ok(AsyncGitNotification::MergedTips)
+	}
+}
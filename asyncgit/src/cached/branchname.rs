@@ -6,7 +6,7 @@ use sync::Head;
 
 ///
 pub struct BranchName {
-	last_result: Option<(Head, String)>,
+	last_result: Option<(Option<Head>, String)>,
 	repo_path: String,
 }
 
@@ -21,13 +21,14 @@ impl BranchName {
 
 	///
 	pub fn lookup(&mut self) -> Result<String> {
+		// `None` while HEAD is unborn, which is never cached
 		let current_head =
-			sync::get_head_tuple(self.repo_path.as_str())?;
+			sync::get_head_tuple(self.repo_path.as_str()).ok();
 
 		if let Some((last_head, branch_name)) =
 			self.last_result.as_ref()
 		{
-			if *last_head == current_head {
+			if current_head.is_some() && *last_head == current_head {
 				return Ok(branch_name.clone());
 			}
 		}
@@ -40,7 +41,15 @@ impl BranchName {
 		self.last_result.as_ref().map(|last| last.1.clone())
 	}
 
-	fn fetch(&mut self, head: Head) -> Result<String> {
+	/// `true` if HEAD pointed to a branch without commits at the
+	/// last lookup, e.g. a new orphan branch
+	pub fn is_unborn(&self) -> bool {
+		self.last_result
+			.as_ref()
+			.map_or(false, |(head, _)| head.is_none())
+	}
+
+	fn fetch(&mut self, head: Option<Head>) -> Result<String> {
 		let name = get_branch_name(self.repo_path.as_str())?;
 		self.last_result = Some((head, name.clone()));
 		Ok(name)
@@ -0,0 +1,99 @@
+//!
+
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	sync::{
+		cherry_pick_range, continue_cherry_pick, skip_cherry_pick,
+		CherryPickProgress, CherryPickResult, CommitId,
+	},
+	AsyncGitNotification, CWD,
+};
+
+use std::sync::{Arc, Mutex};
+
+/// what to do with the cherry-pick sequence
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CherryPickRequest {
+	/// picks the commits, oldest first
+	Start(Vec<CommitId>),
+	/// once the conflicts of the stopped pick are resolved
+	Continue,
+	/// drops the stopped pick
+	Skip,
+}
+
+enum JobState {
+	Request(CherryPickRequest),
+	Response(Result<CherryPickResult>),
+}
+
+/// runs a cherry-pick sequence, the progress is the pick being
+/// applied
+#[derive(Clone, Default)]
+pub struct AsyncCherryPickJob {
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+impl AsyncCherryPickJob {
+	///
+	pub fn new(request: CherryPickRequest) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request(
+				request,
+			)))),
+		}
+	}
+
+	///
+	pub fn result(&self) -> Option<Result<CherryPickResult>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request(_) => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+}
+
+impl AsyncJob for AsyncCherryPickJob {
+	type Notification = AsyncGitNotification;
+	type Progress = CherryPickProgress;
+
+	fn run(
+		&mut self,
+		params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		let request =
+			self.state.lock().ok().and_then(|mut state| state.take());
+
+		if let Some(JobState::Request(request)) = request {
+			let progress = |step: &CherryPickProgress| {
+				params.set_progress(step.clone()).ok();
+				params.send(AsyncGitNotification::CherryPick).ok();
+			};
+
+			let result = match request {
+				CherryPickRequest::Start(commits) => {
+					cherry_pick_range(CWD, &commits, progress)
+				}
+				CherryPickRequest::Continue => {
+					continue_cherry_pick(CWD, progress)
+				}
+				CherryPickRequest::Skip => {
+					skip_cherry_pick(CWD, progress)
+				}
+			};
+
+			if let Ok(mut state) = self.state.lock() {
+				*state = Some(JobState::Response(result));
+			}
+		}
+
+		Ok(AsyncGitNotification::CherryPick)
+	}
+}
@@ -1,6 +1,6 @@
 use crate::{
 	error::Result,
-	sync::{self, CommitId},
+	sync::{self, CommitId, MergeDiffMode},
 	AsyncGitNotification, StatusItem, CWD,
 };
 use crossbeam_channel::Sender;
@@ -19,11 +19,22 @@ pub struct CommitFilesParams {
 	pub id: CommitId,
 	///
 	pub other: Option<CommitId>,
+	/// ignored when comparing two commits
+	pub merge_mode: MergeDiffMode,
+	/// files changed since `id` up to the working tree instead,
+	/// `other` is only shown. fetched again on every request as the
+	/// working tree changes under the same params
+	pub workdir: bool,
 }
 
 impl From<CommitId> for CommitFilesParams {
 	fn from(id: CommitId) -> Self {
-		Self { id, other: None }
+		Self {
+			id,
+			other: None,
+			merge_mode: MergeDiffMode::default(),
+			workdir: false,
+		}
 	}
 }
 
@@ -32,6 +43,8 @@ impl From<(CommitId, CommitId)> for CommitFilesParams {
 		Self {
 			id,
 			other: Some(other),
+			merge_mode: MergeDiffMode::default(),
+			workdir: false,
 		}
 	}
 }
@@ -80,7 +93,7 @@ impl AsyncCommitFiles {
 		{
 			let current = self.current.lock()?;
 			if let Some(c) = &*current {
-				if c.0 == params {
+				if c.0 == params && !params.workdir {
 					return Ok(());
 				}
 			}
@@ -112,8 +125,16 @@ impl AsyncCommitFiles {
 			Mutex<Option<Request<CommitFilesParams, ResultType>>>,
 		>,
 	) -> Result<()> {
-		let res =
-			sync::get_commit_files(CWD, params.id, params.other)?;
+		let res = if params.workdir {
+			sync::get_commit_workdir_files(CWD, params.id)?
+		} else {
+			sync::get_commit_files(
+				CWD,
+				params.id,
+				params.other,
+				params.merge_mode,
+			)?
+		};
 
 		log::trace!("get_commit_files: {:?} ({})", params, res.len());
 
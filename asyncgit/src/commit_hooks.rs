@@ -0,0 +1,137 @@
+//!
+
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	sync::{
+		hooks_commit_msg_streamed, hooks_pre_commit_streamed,
+		HookResult,
+	},
+	AsyncGitNotification, CWD,
+};
+use std::{
+	cell::Cell,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc, Mutex,
+	},
+	time::{Duration, Instant},
+};
+
+/// limits how often hook output triggers a redraw
+const NOTIFY_INTERVAL: Duration = Duration::from_millis(200);
+
+enum JobState {
+	Request(String),
+	Response(Result<(HookResult, String)>),
+}
+
+/// runs the `pre-commit` and `commit-msg` hooks collecting their output
+#[derive(Clone)]
+pub struct AsyncCommitHooksJob {
+	state: Arc<Mutex<Option<JobState>>>,
+	output: Arc<Mutex<String>>,
+	cancel: Arc<AtomicBool>,
+}
+
+///
+impl AsyncCommitHooksJob {
+	///
+	pub fn new(message: String) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request(
+				message,
+			)))),
+			output: Arc::new(Mutex::new(String::new())),
+			cancel: Arc::new(AtomicBool::new(false)),
+		}
+	}
+
+	/// everything the hooks wrote so far
+	pub fn output(&self) -> String {
+		self.output
+			.lock()
+			.map(|output| output.clone())
+			.unwrap_or_default()
+	}
+
+	/// terminates the running hook
+	pub fn cancel(&self) {
+		self.cancel.store(true, Ordering::Relaxed);
+	}
+
+	///
+	pub fn is_canceled(&self) -> bool {
+		self.cancel.load(Ordering::Relaxed)
+	}
+
+	/// hook result and the message as altered by `commit-msg`
+	pub fn result(&self) -> Option<Result<(HookResult, String)>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request(_) => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+
+	fn run_hooks(
+		&self,
+		mut message: String,
+		params: &RunParams<AsyncGitNotification, ()>,
+	) -> Result<(HookResult, String)> {
+		let last_notify = Cell::new(Instant::now());
+		let output = |line: &str| {
+			if let Ok(mut output) = self.output.lock() {
+				output.push_str(line);
+			}
+
+			if last_notify.get().elapsed() >= NOTIFY_INTERVAL {
+				last_notify.set(Instant::now());
+				params.send(AsyncGitNotification::CommitHooks).ok();
+			}
+		};
+
+		let res =
+			hooks_pre_commit_streamed(CWD, &output, &self.cancel)?;
+		if res != HookResult::Ok {
+			return Ok((res, message));
+		}
+
+		let res = hooks_commit_msg_streamed(
+			CWD,
+			&mut message,
+			&output,
+			&self.cancel,
+		)?;
+
+		Ok((res, message))
+	}
+}
+
+impl AsyncJob for AsyncCommitHooksJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		let request =
+			self.state.lock().ok().and_then(|mut state| state.take());
+
+		if let Some(JobState::Request(message)) = request {
+			let result = self.run_hooks(message, &params);
+
+			if let Ok(mut state) = self.state.lock() {
+				*state = Some(JobState::Response(result));
+			}
+		}
+
+		Ok(AsyncGitNotification::CommitHooks)
+	}
+}
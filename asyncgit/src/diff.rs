@@ -1,7 +1,8 @@
 use crate::{
+	diff_cache::{root_attributes, DiffCache, DiffCacheKey},
 	error::Result,
 	hash,
-	sync::{self, diff::DiffOptions, CommitId},
+	sync::{self, diff::DiffOptions, CommitId, MergeDiffMode},
 	AsyncGitNotification, FileDiff, CWD,
 };
 use crossbeam_channel::Sender;
@@ -16,14 +17,16 @@ use std::{
 ///
 #[derive(Debug, Hash, Clone, PartialEq)]
 pub enum DiffType {
-	/// diff two commits
+	/// diff two commits, (old, new)
 	Commits((CommitId, CommitId)),
 	/// diff in a given commit
-	Commit(CommitId),
+	Commit(CommitId, MergeDiffMode),
 	/// diff against staged file
 	Stage,
 	/// diff against file in workdir
 	WorkDir,
+	/// changes since a commit, uncommitted ones included
+	CommitWorkdir(CommitId),
 }
 
 ///
@@ -31,6 +34,8 @@ pub enum DiffType {
 pub struct DiffParams {
 	/// path to the file to diff
 	pub path: String,
+	/// path the file was renamed from if staged as a rename
+	pub old_path: Option<String>,
 	/// what kind of diff
 	pub diff_type: DiffType,
 	/// diff options
@@ -49,6 +54,7 @@ struct LastResult<P, R> {
 pub struct AsyncDiff {
 	current: Arc<Mutex<Request<u64, FileDiff>>>,
 	last: Arc<Mutex<Option<LastResult<DiffParams, FileDiff>>>>,
+	cache: Arc<Mutex<DiffCache>>,
 	sender: Sender<AsyncGitNotification>,
 	pending: Arc<AtomicUsize>,
 }
@@ -59,6 +65,7 @@ impl AsyncDiff {
 		Self {
 			current: Arc::new(Mutex::new(Request(0, None))),
 			last: Arc::new(Mutex::new(None)),
+			cache: Arc::new(Mutex::new(DiffCache::new(CWD))),
 			sender: sender.clone(),
 			pending: Arc::new(AtomicUsize::new(0)),
 		}
@@ -107,6 +114,7 @@ impl AsyncDiff {
 
 		let arc_current = Arc::clone(&self.current);
 		let arc_last = Arc::clone(&self.last);
+		let arc_cache = Arc::clone(&self.cache);
 		let sender = self.sender.clone();
 		let arc_pending = Arc::clone(&self.pending);
 
@@ -117,6 +125,7 @@ impl AsyncDiff {
 				params,
 				&arc_last,
 				&arc_current,
+				&arc_cache,
 				hash,
 			);
 
@@ -148,32 +157,19 @@ impl AsyncDiff {
 			Mutex<Option<LastResult<DiffParams, FileDiff>>>,
 		>,
 		arc_current: &Arc<Mutex<Request<u64, FileDiff>>>,
+		arc_cache: &Arc<Mutex<DiffCache>>,
 		hash: u64,
 	) -> Result<bool> {
-		let res = match params.diff_type {
-			DiffType::Stage => sync::diff::get_diff(
-				CWD,
-				&params.path,
-				true,
-				Some(params.options),
-			)?,
-			DiffType::WorkDir => sync::diff::get_diff(
-				CWD,
-				&params.path,
-				false,
-				Some(params.options),
-			)?,
-			DiffType::Commit(id) => sync::diff::get_diff_commit(
-				CWD,
-				id,
-				params.path.clone(),
-			)?,
-			DiffType::Commits(ids) => sync::diff::get_diff_commits(
-				CWD,
-				ids,
-				params.path.clone(),
-			)?,
-		};
+		// shown in place of the diff, not cached so it is retried
+		let res =
+			get_diff_cached(CWD, &params, arc_cache, compute_diff)
+				.unwrap_or_else(|e| {
+					log::error!("diff error: {}", e);
+					FileDiff {
+						error: Some(e.to_string()),
+						..FileDiff::default()
+					}
+				});
 
 		let mut notify = false;
 		{
@@ -206,3 +202,253 @@ impl AsyncDiff {
 		Ok(())
 	}
 }
+
+/// looks up the diff in `cache` first, `compute` is only called on a
+/// miss or if the diff cannot be cached
+fn get_diff_cached(
+	repo_path: &str,
+	params: &DiffParams,
+	cache: &Mutex<DiffCache>,
+	compute: impl FnOnce(&str, &DiffParams) -> Result<FileDiff>,
+) -> Result<FileDiff> {
+	let key =
+		DiffCacheKey::new(repo_path, params).unwrap_or_else(|e| {
+			log::error!("diff cache key error: {}", e);
+			None
+		});
+
+	if let Some(key) = &key {
+		let attributes = root_attributes(repo_path)?;
+		let cached = {
+			let mut cache = cache.lock()?;
+			cache.check_attributes(attributes);
+			cache.get(key)
+		};
+
+		if let Some(diff) = cached {
+			return Ok(diff);
+		}
+	}
+
+	let diff = compute(repo_path, params)?;
+
+	if let Some(key) = key {
+		cache.lock()?.insert(key, diff.clone());
+	}
+
+	Ok(diff)
+}
+
+fn compute_diff(
+	repo_path: &str,
+	params: &DiffParams,
+) -> Result<FileDiff> {
+	match params.diff_type {
+		DiffType::Stage => params.old_path.as_ref().map_or_else(
+			|| {
+				sync::diff::get_diff(
+					repo_path,
+					&params.path,
+					true,
+					Some(params.options),
+				)
+			},
+			|old_path| {
+				sync::diff::get_diff_renamed(
+					repo_path,
+					old_path,
+					&params.path,
+					Some(params.options),
+				)
+			},
+		),
+		DiffType::WorkDir => sync::diff::get_diff(
+			repo_path,
+			&params.path,
+			false,
+			Some(params.options),
+		),
+		DiffType::Commit(id, merge_mode) => {
+			sync::diff::get_diff_commit(
+				repo_path,
+				id,
+				merge_mode,
+				params.path.clone(),
+			)
+		}
+		DiffType::Commits(ids) => sync::diff::get_diff_commits(
+			repo_path,
+			ids,
+			params.path.clone(),
+		),
+		DiffType::CommitWorkdir(id) => {
+			sync::diff::get_diff_commit_workdir(
+				repo_path,
+				id,
+				&params.path,
+				Some(params.options),
+			)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		stage_add_file,
+		tests::{repo_init, write_commit_file},
+		utils::repo_write_file,
+	};
+	use std::{cell::Cell, path::Path};
+
+	fn params(path: &str, diff_type: DiffType) -> DiffParams {
+		DiffParams {
+			path: path.to_string(),
+			old_path: None,
+			diff_type,
+			options: DiffOptions::default(),
+		}
+	}
+
+	/// `compute_diff` counting its calls
+	fn counted(
+		calls: &Cell<usize>,
+	) -> impl Fn(&str, &DiffParams) -> Result<FileDiff> + '_ {
+		move |repo_path, params| {
+			calls.set(calls.get() + 1);
+			compute_diff(repo_path, params)
+		}
+	}
+
+	#[test]
+	fn test_cached_workdir() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+		let cache = Mutex::new(DiffCache::with_limits(8, usize::MAX));
+		let calls = Cell::new(0);
+
+		write_commit_file(&repo, "a.txt", "a\n", "a");
+		repo_write_file(&repo, "a.txt", "a\nb\n").unwrap();
+
+		let params = params("a.txt", DiffType::WorkDir);
+		let first = get_diff_cached(
+			repo_path,
+			&params,
+			&cache,
+			counted(&calls),
+		)
+		.unwrap();
+		let second = get_diff_cached(
+			repo_path,
+			&params,
+			&cache,
+			counted(&calls),
+		)
+		.unwrap();
+
+		assert_eq!(calls.get(), 1);
+		assert_eq!(first.lines, 3);
+		assert_eq!(hash(&first), hash(&second));
+
+		// new content, new key
+		repo_write_file(&repo, "a.txt", "a\nc\n").unwrap();
+		let third = get_diff_cached(
+			repo_path,
+			&params,
+			&cache,
+			counted(&calls),
+		)
+		.unwrap();
+
+		assert_eq!(calls.get(), 2);
+		assert_eq!(&*third.hunks[0].lines[2].content, "c");
+
+		// other options, new key
+		let params = DiffParams {
+			options: DiffOptions {
+				context: 0,
+				..DiffOptions::default()
+			},
+			..params
+		};
+		get_diff_cached(repo_path, &params, &cache, counted(&calls))
+			.unwrap();
+
+		assert_eq!(calls.get(), 3);
+	}
+
+	#[test]
+	fn test_cached_stage_and_commit() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+		let cache = Mutex::new(DiffCache::with_limits(8, usize::MAX));
+		let calls = Cell::new(0);
+
+		let id = write_commit_file(&repo, "a.txt", "a\n", "a");
+		repo_write_file(&repo, "a.txt", "b\n").unwrap();
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+
+		for diff_type in [
+			DiffType::Stage,
+			DiffType::Stage,
+			DiffType::Commit(id, MergeDiffMode::FirstParent),
+			DiffType::Commit(id, MergeDiffMode::FirstParent),
+		]
+		.iter()
+		.cloned()
+		{
+			get_diff_cached(
+				repo_path,
+				&params("a.txt", diff_type),
+				&cache,
+				counted(&calls),
+			)
+			.unwrap();
+		}
+
+		assert_eq!(calls.get(), 2);
+		assert_eq!(cache.lock().unwrap().counters(), (2, 2));
+	}
+
+	#[test]
+	fn test_cached_attributes() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+		let cache = Mutex::new(DiffCache::with_limits(8, usize::MAX));
+		let calls = Cell::new(0);
+
+		write_commit_file(&repo, "a.txt", "a\n", "a");
+		write_commit_file(&repo, "a.bin", "a\n", "a");
+		repo_write_file(&repo, ".gitattributes", "*.bin binary\n")
+			.unwrap();
+		repo_write_file(&repo, "a.txt", "b\n").unwrap();
+		repo_write_file(&repo, "a.bin", "b\n").unwrap();
+
+		let get = |path: &str| {
+			get_diff_cached(
+				repo_path,
+				&params(path, DiffType::WorkDir),
+				&cache,
+				counted(&calls),
+			)
+			.unwrap()
+		};
+
+		// same blobs, the path decides about the attributes
+		assert!(!get("a.txt").hunks.is_empty());
+		assert!(get("a.bin").hunks.is_empty());
+		assert_eq!(calls.get(), 2);
+
+		get("a.txt");
+		assert_eq!(calls.get(), 2);
+
+		repo_write_file(&repo, ".gitattributes", "*.txt binary\n")
+			.unwrap();
+		assert!(get("a.txt").hunks.is_empty());
+		assert_eq!(calls.get(), 3);
+	}
+}
@@ -0,0 +1,499 @@
+use crate::{
+	diff::{DiffParams, DiffType},
+	error::Result,
+	hash,
+	sync::{
+		self,
+		diff::{DiffLine, FileDiff, Hunk},
+		stash::is_stash_commit,
+		utils::{get_head_repo, repo, work_dir},
+		CommitId, MergeDiffMode,
+	},
+};
+use git2::{ObjectType, Oid, Repository, Tree};
+use std::{
+	collections::HashMap, convert::TryFrom, fs, mem::size_of,
+	path::Path,
+};
+
+/// git config entries overriding the size of the cache
+const ENTRIES_CONFIG: &str = "gitui.diffCacheEntries";
+const BYTES_CONFIG: &str = "gitui.diffCacheBytes";
+
+const ENTRIES_DEFAULT: usize = 64;
+const BYTES_DEFAULT: usize = 32 * 1024 * 1024;
+
+/// submodules diff by their checked out commit, not by content
+const MODE_GITLINK: u32 = 0o160_000;
+const MODE_LINK: u32 = 0o120_000;
+const MODE_EXECUTABLE: u32 = 0o100_755;
+const MODE_BLOB: u32 = 0o100_644;
+
+const ATTRIBUTES_FILE: &str = ".gitattributes";
+
+/// content and mode of one side of a diff, `None` if missing
+type Side = Option<(Oid, u32)>;
+
+/// blobs are content addressed, so a diff between the same two
+/// blobs (with the same options and attributes) never changes
+#[derive(Hash, PartialEq, Eq, Clone, Debug)]
+pub struct DiffCacheKey {
+	/// attributes (binary, `-diff`, lfs) are looked up by path
+	path: String,
+	old: Side,
+	new: Side,
+	/// hash of the diff options and whether the file is untracked
+	options: u64,
+	/// hash of the attribute files in the dirs of the path, the
+	/// root ones are checked by `DiffCache::check_attributes`
+	attributes: u64,
+}
+
+impl DiffCacheKey {
+	/// `None` if the diff cannot be cached (conflicts, submodules,
+	/// stashes, combined merge diffs and changes since a commit)
+	pub fn new(
+		repo_path: &str,
+		params: &DiffParams,
+	) -> Result<Option<Self>> {
+		let repo = repo(repo_path)?;
+
+		let sides = match params.diff_type {
+			DiffType::Stage => {
+				let old_path =
+					params.old_path.as_ref().unwrap_or(&params.path);
+
+				index_side(&repo, &params.path)?.map(|new| {
+					(
+						head_side(&repo, old_path),
+						new,
+						hash(&(params.options, false)),
+					)
+				})
+			}
+			DiffType::WorkDir => {
+				let old = index_side(&repo, &params.path)?;
+				let untracked = matches!(old, Some(None));
+				let new = workdir_side(&repo, &params.path)?;
+
+				old.zip(new).map(|(old, new)| {
+					(old, new, hash(&(params.options, untracked)))
+				})
+			}
+			DiffType::Commit(id, merge_mode) => commit_sides(
+				repo_path,
+				&repo,
+				id,
+				merge_mode,
+				&params.path,
+			)?
+			.map(|(old, new)| (old, new, 0)),
+			DiffType::Commits(ids) => {
+				let (old, new) = compare_trees(&repo, ids)?;
+
+				Some((
+					tree_side(&old, &params.path),
+					tree_side(&new, &params.path),
+					0,
+				))
+			}
+			// spans index and working tree, always computed
+			DiffType::CommitWorkdir(_) => None,
+		};
+
+		Ok(sides
+			.filter(|(old, new, _)| {
+				!is_gitlink(*old) && !is_gitlink(*new)
+			})
+			.map(|(old, new, options)| Self {
+				path: params.path.clone(),
+				old,
+				new,
+				options,
+				attributes: hash(&(
+					nested_attributes(&repo, &params.path),
+					params
+						.old_path
+						.as_ref()
+						.map(|path| nested_attributes(&repo, path)),
+				)),
+			}))
+	}
+}
+
+/// contents of the attribute files that apply to every path
+pub fn root_attributes(repo_path: &str) -> Result<u64> {
+	let repo = repo(repo_path)?;
+
+	Ok(hash(&(
+		repo.workdir()
+			.and_then(|dir| fs::read(dir.join(ATTRIBUTES_FILE)).ok()),
+		fs::read(repo.path().join("info").join("attributes")).ok(),
+	)))
+}
+
+/// contents of the attribute files between the root and `path`
+fn nested_attributes(repo: &Repository, path: &str) -> u64 {
+	let work_dir = match repo.workdir() {
+		Some(dir) => dir,
+		None => return 0,
+	};
+
+	let contents: Vec<_> = Path::new(path)
+		.ancestors()
+		.skip(1)
+		.filter(|dir| !dir.as_os_str().is_empty())
+		.map(|dir| {
+			fs::read(work_dir.join(dir).join(ATTRIBUTES_FILE)).ok()
+		})
+		.collect();
+
+	hash(&contents)
+}
+
+fn is_gitlink(side: Side) -> bool {
+	side.map_or(false, |(_, mode)| mode == MODE_GITLINK)
+}
+
+fn tree_side(tree: &Tree, path: &str) -> Side {
+	tree.get_path(Path::new(path)).ok().map(|entry| {
+		(
+			entry.id(),
+			u32::try_from(entry.filemode()).unwrap_or_default(),
+		)
+	})
+}
+
+fn head_side(repo: &Repository, path: &str) -> Side {
+	let tree = get_head_repo(repo)
+		.ok()
+		.and_then(|id| repo.find_commit(id.into()).ok())
+		.and_then(|commit| commit.tree().ok())?;
+
+	tree_side(&tree, path)
+}
+
+/// `None` if conflicted
+fn index_side(repo: &Repository, path: &str) -> Result<Option<Side>> {
+	let index = repo.index()?;
+	let path = Path::new(path);
+
+	if (1..=3).any(|stage| index.get_path(path, stage).is_some()) {
+		return Ok(None);
+	}
+
+	Ok(Some(
+		index.get_path(path, 0).map(|entry| (entry.id, entry.mode)),
+	))
+}
+
+/// hashes the file the way git would store it, `None` for dirs
+fn workdir_side(
+	repo: &Repository,
+	path: &str,
+) -> Result<Option<Side>> {
+	let path = work_dir(repo)?.join(path);
+
+	let meta = match fs::symlink_metadata(&path) {
+		Ok(meta) => meta,
+		Err(_) => return Ok(Some(None)),
+	};
+
+	let (content, mode) = if meta.file_type().is_symlink() {
+		let target = fs::read_link(&path)?;
+		(target.to_string_lossy().as_bytes().to_vec(), MODE_LINK)
+	} else if meta.is_dir() {
+		return Ok(None);
+	} else {
+		(fs::read(&path)?, file_mode(&meta))
+	};
+
+	Ok(Some(Some((
+		Oid::hash_object(ObjectType::Blob, &content)?,
+		mode,
+	))))
+}
+
+#[cfg(unix)]
+fn file_mode(meta: &fs::Metadata) -> u32 {
+	use std::os::unix::fs::PermissionsExt;
+
+	if meta.permissions().mode() & 0o111 == 0 {
+		MODE_BLOB
+	} else {
+		MODE_EXECUTABLE
+	}
+}
+
+#[cfg(not(unix))]
+const fn file_mode(_meta: &fs::Metadata) -> u32 {
+	MODE_BLOB
+}
+
+/// mirrors the parent choice of `get_commit_diff`
+fn commit_sides(
+	repo_path: &str,
+	repo: &Repository,
+	id: CommitId,
+	merge_mode: MergeDiffMode,
+	path: &str,
+) -> Result<Option<(Side, Side)>> {
+	if is_stash_commit(repo_path, &id)? {
+		return Ok(None);
+	}
+
+	let commit = repo.find_commit(id.into())?;
+
+	let parent = match merge_mode {
+		MergeDiffMode::Combined if commit.parent_count() > 1 => {
+			return Ok(None);
+		}
+		MergeDiffMode::SecondParent if commit.parent_count() > 1 => 1,
+		_ => 0,
+	};
+
+	let old = commit
+		.parent(parent)
+		.ok()
+		.and_then(|parent| parent.tree().ok())
+		.and_then(|tree| tree_side(&tree, path));
+
+	Ok(Some((old, tree_side(&commit.tree()?, path))))
+}
+
+/// (old, new) like `get_compare_commits_diff`
+fn compare_trees(
+	repo: &Repository,
+	ids: (CommitId, CommitId),
+) -> Result<(Tree<'_>, Tree<'_>)> {
+	Ok((
+		repo.find_commit(ids.0.into())?.tree()?,
+		repo.find_commit(ids.1.into())?.tree()?,
+	))
+}
+
+/// rough heap usage of a parsed diff
+fn diff_size(diff: &FileDiff) -> usize {
+	size_of::<FileDiff>()
+		+ diff.hunks.len() * size_of::<Hunk>()
+		+ diff
+			.hunks
+			.iter()
+			.flat_map(|hunk| hunk.lines.iter())
+			.map(|line| size_of::<DiffLine>() + line.content.len())
+			.sum::<usize>()
+}
+
+struct CacheEntry {
+	diff: FileDiff,
+	size: usize,
+	last_used: u64,
+}
+
+/// least recently used parsed diffs, limited by entry count and
+/// (estimated) bytes
+pub struct DiffCache {
+	entries: HashMap<DiffCacheKey, CacheEntry>,
+	max_entries: usize,
+	max_bytes: usize,
+	bytes: usize,
+	tick: u64,
+	hits: usize,
+	misses: usize,
+	/// `root_attributes` the entries were computed with
+	attributes: Option<u64>,
+}
+
+impl DiffCache {
+	/// limits from the git config of the repo at `repo_path`
+	pub fn new(repo_path: &str) -> Self {
+		let limit = |key: &str, default: usize| {
+			sync::get_config_string(repo_path, key)
+				.ok()
+				.flatten()
+				.and_then(|value| value.trim().parse().ok())
+				.unwrap_or(default)
+		};
+
+		Self::with_limits(
+			limit(ENTRIES_CONFIG, ENTRIES_DEFAULT),
+			limit(BYTES_CONFIG, BYTES_DEFAULT),
+		)
+	}
+
+	/// `max_entries` of `0` disables the cache
+	pub fn with_limits(max_entries: usize, max_bytes: usize) -> Self {
+		Self {
+			entries: HashMap::new(),
+			max_entries,
+			max_bytes,
+			bytes: 0,
+			tick: 0,
+			hits: 0,
+			misses: 0,
+			attributes: None,
+		}
+	}
+
+	/// drops every entry if the attribute files changed since
+	pub fn check_attributes(&mut self, attributes: u64) {
+		if self.attributes.map_or(false, |old| old != attributes) {
+			self.entries.clear();
+			self.bytes = 0;
+		}
+		self.attributes = Some(attributes);
+	}
+
+	///
+	pub fn get(&mut self, key: &DiffCacheKey) -> Option<FileDiff> {
+		self.tick += 1;
+		let tick = self.tick;
+
+		let diff = self.entries.get_mut(key).map(|entry| {
+			entry.last_used = tick;
+			entry.diff.clone()
+		});
+
+		if diff.is_some() {
+			self.hits += 1;
+		} else {
+			self.misses += 1;
+		}
+
+		log::debug!(
+			"diff cache {} (hits: {}, misses: {})",
+			if diff.is_some() { "hit" } else { "miss" },
+			self.hits,
+			self.misses
+		);
+
+		diff
+	}
+
+	/// diffs bigger than the whole budget are not kept
+	pub fn insert(&mut self, key: DiffCacheKey, diff: FileDiff) {
+		let size = diff_size(&diff);
+		if self.max_entries == 0 || size > self.max_bytes {
+			return;
+		}
+
+		self.tick += 1;
+		if let Some(old) = self.entries.insert(
+			key,
+			CacheEntry {
+				diff,
+				size,
+				last_used: self.tick,
+			},
+		) {
+			self.bytes -= old.size;
+		}
+		self.bytes += size;
+
+		while self.entries.len() > self.max_entries
+			|| self.bytes > self.max_bytes
+		{
+			self.evict_oldest();
+		}
+	}
+
+	fn evict_oldest(&mut self) {
+		let oldest = self
+			.entries
+			.iter()
+			.min_by_key(|(_, entry)| entry.last_used)
+			.map(|(key, _)| key.clone());
+
+		if let Some(entry) =
+			oldest.and_then(|key| self.entries.remove(&key))
+		{
+			self.bytes -= entry.size;
+		}
+	}
+
+	#[cfg(test)]
+	pub const fn counters(&self) -> (usize, usize) {
+		(self.hits, self.misses)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::diff::DiffLinePosition;
+
+	fn key(n: u8) -> DiffCacheKey {
+		DiffCacheKey {
+			path: String::from("a.txt"),
+			old: None,
+			new: Some((
+				Oid::from_bytes(&[n; 20]).unwrap(),
+				MODE_BLOB,
+			)),
+			options: 0,
+			attributes: 0,
+		}
+	}
+
+	fn diff(content: &str) -> FileDiff {
+		FileDiff {
+			hunks: vec![Hunk {
+				header_hash: 0,
+				split_from: None,
+				lines: vec![DiffLine {
+					content: content.into(),
+					line_type: sync::diff::DiffLineType::Add,
+					position: DiffLinePosition::default(),
+				}],
+			}],
+			lines: 1,
+			..FileDiff::default()
+		}
+	}
+
+	#[test]
+	fn test_lru_entries() {
+		let mut cache = DiffCache::with_limits(2, usize::MAX);
+
+		cache.insert(key(1), diff("a"));
+		cache.insert(key(2), diff("b"));
+		// 1 is now more recent than 2
+		assert!(cache.get(&key(1)).is_some());
+		cache.insert(key(3), diff("c"));
+
+		assert!(cache.get(&key(2)).is_none());
+		assert_eq!(
+			&*cache.get(&key(1)).unwrap().hunks[0].lines[0].content,
+			"a"
+		);
+		assert!(cache.get(&key(3)).is_some());
+		assert_eq!(cache.counters(), (3, 1));
+	}
+
+	#[test]
+	fn test_lru_bytes() {
+		let one = diff_size(&diff("aaaa"));
+		let mut cache = DiffCache::with_limits(10, one * 2);
+
+		cache.insert(key(1), diff("aaaa"));
+		cache.insert(key(2), diff("bbbb"));
+		cache.insert(key(3), diff("cccc"));
+
+		assert!(cache.get(&key(1)).is_none());
+		assert!(cache.get(&key(2)).is_some());
+		assert!(cache.get(&key(3)).is_some());
+
+		// bigger than the budget
+		cache.insert(key(4), diff(&"d".repeat(one * 2)));
+		assert!(cache.get(&key(4)).is_none());
+		assert!(cache.get(&key(3)).is_some());
+	}
+
+	#[test]
+	fn test_disabled() {
+		let mut cache = DiffCache::with_limits(0, usize::MAX);
+
+		cache.insert(key(1), diff("a"));
+		assert!(cache.get(&key(1)).is_none());
+	}
+}
@@ -34,6 +34,10 @@ pub enum Error {
 	#[error("git: uncommitted changes")]
 	UncommittedChanges,
 
+	/// the index matches `HEAD` and empty commits were not asked for
+	#[error("git: nothing to commit")]
+	NothingToCommit,
+
 	///
 	#[error("git: can\u{2019}t run blame on a binary file")]
 	NoBlameOnBinaryFile,
@@ -42,6 +46,29 @@ pub enum Error {
 	#[error("binary file")]
 	BinaryFile,
 
+	/// `index.lock` was held by another process beyond the timeout
+	#[error(
+		"git: another git process is running (index.lock exists)"
+	)]
+	IndexLocked,
+
+	/// the certificate of the host was rejected, with the reason
+	#[error("git: certificate of `{0}` rejected: {1}")]
+	Certificate(String, String),
+
+	/// a remote operation made no progress for that many seconds
+	#[error("timed out after {0}s, check your connection or increase gitui.networkTimeout")]
+	NetworkTimeout(u64),
+
+	/// the branch is checked out in another worktree (its work dir)
+	#[error("git: branch is checked out in worktree `{0}`")]
+	BranchCheckedOut(String),
+
+	/// restoring discarded content would overwrite later edits of
+	/// the file (its path)
+	#[error("`{0}` changed since it was discarded")]
+	ChangedSinceDiscard(String),
+
 	///
 	#[error("io error:{0}")]
 	Io(#[from] std::io::Error),
@@ -17,8 +17,8 @@ use std::{
 pub struct FetchRequest {
 	///
 	pub remote: String,
-	///
-	pub branch: String,
+	/// empty to use the refspecs configured for `remote`
+	pub refspecs: Vec<String>,
 	///
 	pub basic_credential: Option<BasicAuthCredential>,
 }
@@ -92,7 +92,8 @@ impl AsyncFetch {
 
 			let res = fetch(
 				CWD,
-				&params.branch,
+				&params.remote,
+				&params.refspecs,
 				params.basic_credential,
 				Some(progress_sender.clone()),
 			);
@@ -23,23 +23,34 @@
 // #![deny(clippy::expect_used)]
 
 pub mod asyncjob;
+mod auto_fetch;
 mod blame;
+pub mod branch_tips;
 pub mod cached;
+pub mod cherry_pick;
 mod commit_files;
+pub mod commit_hooks;
 mod diff;
+mod diff_cache;
 mod error;
 mod fetch;
+pub mod moved_blocks;
+pub mod pre_push_hook;
 mod progress;
 mod push;
+pub mod push_preview;
 mod push_tags;
+pub mod remote_branches;
 pub mod remote_progress;
 pub mod remote_tags;
 mod revlog;
 mod status;
 pub mod sync;
 mod tags;
+mod timings;
 
 pub use crate::{
+	auto_fetch::AsyncAutoFetch,
 	blame::{AsyncBlame, BlameParams},
 	commit_files::{AsyncCommitFiles, CommitFilesParams},
 	diff::{AsyncDiff, DiffParams, DiffType},
@@ -56,6 +67,7 @@ pub use crate::{
 		status::{StatusItem, StatusItemType},
 	},
 	tags::AsyncTags,
+	timings::{OperationTiming, Timings},
 };
 use std::{
 	collections::hash_map::DefaultHasher,
@@ -82,11 +94,31 @@ pub enum AsyncGitNotification {
 	///
 	PushTags,
 	///
+	PushPreview,
+	///
 	Fetch,
 	///
 	Blame,
 	///
 	RemoteTags,
+	///
+	RemoteBranches,
+	///
+	StaleBranches,
+	///
+	AutoFetch,
+	///
+	BranchTips,
+	///
+	MergedTips,
+	///
+	CommitHooks,
+	///
+	PrePushHook,
+	///
+	CherryPick,
+	///
+	MovedBlocks,
 }
 
 /// current working directory `./`
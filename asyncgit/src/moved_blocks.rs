@@ -0,0 +1,92 @@
+//!
+
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	sync::{moved_blocks, CommitId, MergeDiffMode, MovedBlock},
+	AsyncGitNotification, CWD,
+};
+
+use std::sync::{Arc, Mutex};
+
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MovedBlocksParams {
+	///
+	pub id: CommitId,
+	///
+	pub merge_mode: MergeDiffMode,
+	/// shorter blocks are not reported, 0 turns the search off
+	pub min_lines: usize,
+}
+
+enum JobState {
+	Request,
+	Response(Result<Vec<MovedBlock>>),
+}
+
+/// looks for the blocks a commit moves between files, started once
+/// its diff is shown
+#[derive(Clone)]
+pub struct AsyncMovedBlocksJob {
+	params: MovedBlocksParams,
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+impl AsyncMovedBlocksJob {
+	///
+	pub fn new(params: MovedBlocksParams) -> Self {
+		Self {
+			params,
+			state: Arc::new(Mutex::new(Some(JobState::Request))),
+		}
+	}
+
+	///
+	pub const fn params(&self) -> MovedBlocksParams {
+		self.params
+	}
+
+	///
+	pub fn result(&self) -> Option<Result<Vec<MovedBlock>>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+}
+
+impl AsyncJob for AsyncMovedBlocksJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		if let Ok(mut state) = self.state.lock() {
+			let params = self.params;
+			*state = state.take().map(|state| match state {
+				JobState::Request => {
+					JobState::Response(moved_blocks(
+						CWD,
+						params.id,
+						params.merge_mode,
+						params.min_lines,
+					))
+				}
+				JobState::Response(result) => {
+					JobState::Response(result)
+				}
+			});
+		}
+
+		Ok(AsyncGitNotification::MovedBlocks)
+	}
+}
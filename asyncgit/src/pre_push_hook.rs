@@ -0,0 +1,150 @@
+//!
+
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	sync::{
+		get_remote_push_url, hooks_pre_push_streamed, push_updates,
+		HookResult,
+	},
+	AsyncGitNotification, CWD,
+};
+use std::{
+	cell::Cell,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc, Mutex,
+	},
+	time::{Duration, Instant},
+};
+
+/// limits how often hook output triggers a redraw
+const NOTIFY_INTERVAL: Duration = Duration::from_millis(200);
+
+enum JobState {
+	Request {
+		remote: String,
+		branch: String,
+		delete: bool,
+	},
+	Response(Result<HookResult>),
+}
+
+/// runs the `pre-push` hook for pushing `branch` to `remote`
+/// collecting its output
+#[derive(Clone)]
+pub struct AsyncPrePushHookJob {
+	state: Arc<Mutex<Option<JobState>>>,
+	output: Arc<Mutex<String>>,
+	cancel: Arc<AtomicBool>,
+}
+
+///
+impl AsyncPrePushHookJob {
+	///
+	pub fn new(remote: String, branch: String, delete: bool) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request {
+				remote,
+				branch,
+				delete,
+			}))),
+			output: Arc::new(Mutex::new(String::new())),
+			cancel: Arc::new(AtomicBool::new(false)),
+		}
+	}
+
+	/// everything the hook wrote so far
+	pub fn output(&self) -> String {
+		self.output
+			.lock()
+			.map(|output| output.clone())
+			.unwrap_or_default()
+	}
+
+	/// terminates the running hook
+	pub fn cancel(&self) {
+		self.cancel.store(true, Ordering::Relaxed);
+	}
+
+	///
+	pub fn is_canceled(&self) -> bool {
+		self.cancel.load(Ordering::Relaxed)
+	}
+
+	///
+	pub fn result(&self) -> Option<Result<HookResult>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request { .. } => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+
+	fn run_hook(
+		&self,
+		remote: &str,
+		branch: &str,
+		delete: bool,
+		params: &RunParams<AsyncGitNotification, ()>,
+	) -> Result<HookResult> {
+		let last_notify = Cell::new(Instant::now());
+		let output = |line: &str| {
+			if let Ok(mut output) = self.output.lock() {
+				output.push_str(line);
+			}
+
+			if last_notify.get().elapsed() >= NOTIFY_INTERVAL {
+				last_notify.set(Instant::now());
+				params.send(AsyncGitNotification::PrePushHook).ok();
+			}
+		};
+
+		// the hook gets the updates before anything is sent
+		let url = get_remote_push_url(CWD, remote)?;
+		let updates = push_updates(CWD, remote, branch, delete)?;
+
+		hooks_pre_push_streamed(
+			CWD,
+			remote,
+			&url,
+			&updates,
+			&output,
+			&self.cancel,
+		)
+	}
+}
+
+impl AsyncJob for AsyncPrePushHookJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		let request =
+			self.state.lock().ok().and_then(|mut state| state.take());
+
+		if let Some(JobState::Request {
+			remote,
+			branch,
+			delete,
+		}) = request
+		{
+			let result =
+				self.run_hook(&remote, &branch, delete, &params);
+
+			if let Ok(mut state) = self.state.lock() {
+				*state = Some(JobState::Response(result));
+			}
+		}
+
+		Ok(AsyncGitNotification::PrePushHook)
+	}
+}
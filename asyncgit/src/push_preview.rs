@@ -0,0 +1,97 @@
+//!
+
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	sync::{
+		cred::BasicAuthCredential, push_preview_advertised,
+		PushPreview,
+	},
+	AsyncGitNotification, CWD,
+};
+
+use std::{
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+/// how long to wait for a remote to list its branches
+const LIST_TIMEOUT: Duration = Duration::from_secs(10);
+
+enum JobState {
+	Request {
+		remote: String,
+		branch: String,
+		basic_credential: Option<BasicAuthCredential>,
+	},
+	Response(Result<PushPreview>),
+}
+
+/// previews a push asking the remote where its branches are
+#[derive(Clone, Default)]
+pub struct AsyncPushPreviewJob {
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+///
+impl AsyncPushPreviewJob {
+	///
+	pub fn new(
+		remote: String,
+		branch: String,
+		basic_credential: Option<BasicAuthCredential>,
+	) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request {
+				remote,
+				branch,
+				basic_credential,
+			}))),
+		}
+	}
+
+	///
+	pub fn result(&self) -> Option<Result<PushPreview>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request { .. } => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+}
+
+impl AsyncJob for AsyncPushPreviewJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		if let Ok(mut state) = self.state.lock() {
+			*state = state.take().map(|state| match state {
+				JobState::Request {
+					remote,
+					branch,
+					basic_credential,
+				} => JobState::Response(push_preview_advertised(
+					CWD,
+					&remote,
+					&branch,
+					basic_credential,
+					LIST_TIMEOUT,
+				)),
+				JobState::Response(result) => {
+					JobState::Response(result)
+				}
+			});
+		}
+
+		Ok(AsyncGitNotification::PushPreview)
+	}
+}
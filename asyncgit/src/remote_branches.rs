@@ -0,0 +1,160 @@
+//!
+
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	sync::cred::BasicAuthCredential,
+	sync::remotes::{
+		get_remote_branches_advertised, stale_remote_branches,
+	},
+	AsyncGitNotification, CWD,
+};
+
+use std::{
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+/// how long to wait for a remote to list its branches
+const LIST_TIMEOUT: Duration = Duration::from_secs(10);
+
+enum JobState {
+	Request(String, Option<BasicAuthCredential>),
+	Response(String, Result<Vec<String>>),
+}
+
+///
+#[derive(Clone, Default)]
+pub struct AsyncRemoteBranchesJob {
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+///
+impl AsyncRemoteBranchesJob {
+	///
+	pub fn new(
+		remote: String,
+		basic_credential: Option<BasicAuthCredential>,
+	) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request(
+				remote,
+				basic_credential,
+			)))),
+		}
+	}
+
+	/// returns the remote the request was for and the listed branches
+	pub fn result(&self) -> Option<(String, Result<Vec<String>>)> {
+		take_response(&self.state)
+	}
+}
+
+impl AsyncJob for AsyncRemoteBranchesJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		respond(&self.state, |remote, basic_credential| {
+			get_remote_branches_advertised(
+				CWD,
+				remote,
+				basic_credential,
+				LIST_TIMEOUT,
+			)
+		});
+
+		Ok(AsyncGitNotification::RemoteBranches)
+	}
+}
+
+/// lists the remote tracking refs of a remote that `fetch --prune`
+/// would delete
+#[derive(Clone, Default)]
+pub struct AsyncStaleBranchesJob {
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+///
+impl AsyncStaleBranchesJob {
+	///
+	pub fn new(
+		remote: String,
+		basic_credential: Option<BasicAuthCredential>,
+	) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request(
+				remote,
+				basic_credential,
+			)))),
+		}
+	}
+
+	/// returns the remote the request was for and its stale refs
+	pub fn result(&self) -> Option<(String, Result<Vec<String>>)> {
+		take_response(&self.state)
+	}
+}
+
+impl AsyncJob for AsyncStaleBranchesJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		respond(&self.state, |remote, basic_credential| {
+			stale_remote_branches(
+				CWD,
+				remote,
+				basic_credential,
+				LIST_TIMEOUT,
+			)
+		});
+
+		Ok(AsyncGitNotification::StaleBranches)
+	}
+}
+
+fn take_response(
+	state: &Mutex<Option<JobState>>,
+) -> Option<(String, Result<Vec<String>>)> {
+	if let Ok(mut state) = state.lock() {
+		if let Some(state) = state.take() {
+			return match state {
+				JobState::Request(..) => None,
+				JobState::Response(remote, result) => {
+					Some((remote, result))
+				}
+			};
+		}
+	}
+
+	None
+}
+
+/// answers a pending request using `list`
+fn respond<F>(state: &Mutex<Option<JobState>>, list: F)
+where
+	F: FnOnce(
+		&str,
+		Option<BasicAuthCredential>,
+	) -> Result<Vec<String>>,
+{
+	if let Ok(mut state) = state.lock() {
+		*state = state.take().map(|state| match state {
+			JobState::Request(remote, basic_credential) => {
+				let result = list(&remote, basic_credential);
+
+				JobState::Response(remote, result)
+			}
+			JobState::Response(remote, result) => {
+				JobState::Response(remote, result)
+			}
+		});
+	}
+}
@@ -1,10 +1,13 @@
 use crate::{
 	error::Result,
-	sync::{utils::repo, CommitId, LogWalker, LogWalkerFilter},
-	AsyncGitNotification, CWD,
+	sync::{
+		self, utils::repo, CommitId, LogWalker, LogWalkerFilter,
+		WalkState,
+	},
+	AsyncGitNotification,
 };
 use crossbeam_channel::Sender;
-use git2::Oid;
+use git2::Repository;
 use scopetime::scope_time;
 use std::{
 	sync::{
@@ -16,7 +19,7 @@ use std::{
 };
 
 ///
-#[derive(PartialEq)]
+#[derive(Debug, PartialEq, Eq)]
 pub enum FetchStatus {
 	/// previous fetch still running
 	Pending,
@@ -26,13 +29,294 @@ pub enum FetchStatus {
 	Started,
 }
 
-///
+/// git config entry overriding how many log entries are kept
+const RETENTION_CONFIG: &str = "gitui.logRetention";
+const RETENTION_DEFAULT: usize = 20_000;
+
+/// walk state at an index of the log
+#[derive(Clone)]
+struct Checkpoint {
+	index: usize,
+	state: WalkState,
+}
+
+/// commit looked for by `AsyncLog::locate`
+struct Sought {
+	id: CommitId,
+	position: Option<usize>,
+	/// the walked part of the log was searched (the walk itself
+	/// looks at what comes after)
+	searched: bool,
+}
+
+/// background work of `LogWindow::next_job`
+enum Job {
+	Locate(CommitId, usize),
+	Window(usize, usize),
+}
+
+/// the part of the walked log kept in memory: at most `retention`
+/// entries around `center`, with the walk state every `chunk`
+/// entries to walk an evicted range again without starting over
+/// at `HEAD`
+struct LogWindow {
+	/// index of `ids[0]` in the log
+	offset: usize,
+	ids: Vec<CommitId>,
+	/// entries walked so far
+	total: usize,
+	retention: usize,
+	chunk: usize,
+	/// index the ui looks at
+	center: usize,
+	checkpoints: Vec<Checkpoint>,
+	filter: Option<LogWalkerFilter>,
+	sought: Option<Sought>,
+	job_running: bool,
+	/// bumped by `clear`, results of walks started before are
+	/// dropped
+	generation: usize,
+}
+
+impl LogWindow {
+	fn new(retention: usize, chunk: usize) -> Self {
+		Self {
+			offset: 0,
+			ids: Vec::new(),
+			total: 0,
+			retention,
+			chunk: chunk.min(retention).max(1),
+			center: 0,
+			checkpoints: Vec::new(),
+			filter: None,
+			sought: None,
+			job_running: false,
+			generation: 0,
+		}
+	}
+
+	/// forgets the walk, a commit looked for is found by the next
+	fn clear(&mut self, filter: Option<LogWalkerFilter>) {
+		self.offset = 0;
+		self.ids = Vec::new();
+		self.total = 0;
+		self.center = 0;
+		self.checkpoints.clear();
+		self.filter = filter;
+		self.generation += 1;
+
+		if let Some(sought) = &mut self.sought {
+			sought.position = None;
+			sought.searched = true;
+		}
+	}
+
+	/// range of the log to keep
+	const fn bounds(&self) -> (usize, usize) {
+		let start = self.center.saturating_sub(self.retention / 2);
+		(start, start + self.retention)
+	}
+
+	/// the walk goes on from `state`, `ids` were walked before
+	fn push_walked(&mut self, ids: &[CommitId], state: WalkState) {
+		let (start, end) = self.bounds();
+
+		for id in ids {
+			let index = self.total;
+
+			if index == self.offset + self.ids.len()
+				&& index >= start
+				&& index < end
+			{
+				self.ids.push(*id);
+			}
+
+			if let Some(sought) = &mut self.sought {
+				if sought.id == *id && sought.position.is_none() {
+					sought.position = Some(index);
+				}
+			}
+
+			self.total += 1;
+		}
+
+		self.checkpoints.push(Checkpoint {
+			index: self.total,
+			state,
+		});
+	}
+
+	/// `None` if part of the range got evicted
+	fn slice(
+		&self,
+		start: usize,
+		amount: usize,
+	) -> Option<Vec<CommitId>> {
+		let end = start.saturating_add(amount).min(self.total);
+		if start >= end {
+			return Some(Vec::new());
+		}
+
+		(start >= self.offset && end <= self.offset + self.ids.len())
+			.then(|| {
+				self.ids[start - self.offset..end - self.offset]
+					.to_vec()
+			})
+	}
+
+	/// keeps the entries around `center`, evicting the others
+	fn recenter(&mut self, center: usize) {
+		self.center = center;
+		let (start, end) = self.bounds();
+
+		let keep_start = start.max(self.offset);
+		let keep_end = end.min(self.offset + self.ids.len());
+
+		if keep_start < keep_end {
+			self.ids.truncate(keep_end - self.offset);
+			self.ids.drain(..keep_start - self.offset);
+			self.offset = keep_start;
+		} else {
+			self.ids = Vec::new();
+			self.offset = start;
+		}
+	}
+
+	fn position(&self, id: CommitId) -> Option<usize> {
+		self.ids
+			.iter()
+			.position(|x| *x == id)
+			.map(|pos| self.offset + pos)
+			.or_else(|| {
+				self.sought
+					.as_ref()
+					.filter(|sought| sought.id == id)
+					.and_then(|sought| sought.position)
+			})
+	}
+
+	/// walked range to keep that is not kept (anymore)
+	fn missing(&self) -> Option<(usize, usize)> {
+		let (start, end) = self.bounds();
+		let end = end.min(self.total);
+
+		(start < end
+			&& (start < self.offset
+				|| end > self.offset + self.ids.len()))
+		.then(|| (start, end))
+	}
+
+	fn next_job(&mut self) -> Option<Job> {
+		if let Some(sought) = &mut self.sought {
+			if !sought.searched {
+				sought.searched = true;
+				return Some(Job::Locate(sought.id, self.total));
+			}
+		}
+
+		self.missing().map(|(start, end)| Job::Window(start, end))
+	}
+
+	fn located(&mut self, id: CommitId, position: Option<usize>) {
+		if let Some(sought) = &mut self.sought {
+			if sought.id == id && sought.position.is_none() {
+				sought.position = position;
+			}
+		}
+	}
+
+	/// keeps `ids` walked again for `range` unless the ui moved on,
+	/// what the walk got to meanwhile is missing on the next call
+	fn walked_again(
+		&mut self,
+		range: (usize, usize),
+		ids: Vec<CommitId>,
+	) {
+		let (start, end) = self.bounds();
+
+		if start == range.0
+			&& range.1 <= end
+			&& ids.len() == range.1 - range.0
+		{
+			self.offset = range.0;
+			self.ids = ids;
+		}
+	}
+
+	/// last checkpoint at or before `index`
+	fn checkpoint(&self, index: usize) -> Option<Checkpoint> {
+		self.checkpoints
+			.iter()
+			.rev()
+			.find(|checkpoint| checkpoint.index <= index)
+			.cloned()
+	}
+}
+
+/// walks the log from `checkpoint` calling `f` with the index of
+/// each entry until it returns `false` or the log ends
+fn walk_from(
+	repo: &Repository,
+	filter: Option<LogWalkerFilter>,
+	checkpoint: &Checkpoint,
+	chunk: usize,
+	mut f: impl FnMut(usize, CommitId) -> bool,
+) -> Result<()> {
+	let mut walker =
+		LogWalker::resume(repo, &checkpoint.state, chunk)?
+			.filter(filter);
+	let mut index = checkpoint.index;
+	let mut entries = Vec::with_capacity(chunk);
+
+	loop {
+		entries.clear();
+		let count = walker.read(&mut entries)?;
+
+		for id in &entries {
+			if !f(index, *id) {
+				return Ok(());
+			}
+			index += 1;
+		}
+
+		if count < chunk {
+			return Ok(());
+		}
+	}
+}
+
+/// the entries `start..end` of the log
+fn walk_range(
+	repo: &Repository,
+	filter: Option<LogWalkerFilter>,
+	checkpoint: &Checkpoint,
+	chunk: usize,
+	(start, end): (usize, usize),
+) -> Result<Vec<CommitId>> {
+	let mut ids = Vec::with_capacity(end - start);
+
+	walk_from(repo, filter, checkpoint, chunk, |index, id| {
+		if index >= start {
+			ids.push(id);
+		}
+		index + 1 < end
+	})?;
+
+	Ok(ids)
+}
+
+/// keeps at most `gitui.logRetention` entries of the log in memory
+/// around the index last asked for, evicted ones are walked again
+/// in the background when needed
 pub struct AsyncLog {
-	current: Arc<Mutex<Vec<CommitId>>>,
+	repo_path: String,
+	window: Arc<Mutex<LogWindow>>,
 	sender: Sender<AsyncGitNotification>,
 	pending: Arc<AtomicBool>,
 	background: Arc<AtomicBool>,
 	filter: Option<LogWalkerFilter>,
+	/// head the current walk started from
+	walked_head: Option<CommitId>,
 }
 
 static LIMIT_COUNT: usize = 3000;
@@ -42,48 +326,122 @@ static SLEEP_BACKGROUND: Duration = Duration::from_millis(1000);
 impl AsyncLog {
 	///
 	pub fn new(
+		repo_path: &str,
 		sender: &Sender<AsyncGitNotification>,
 		filter: Option<LogWalkerFilter>,
 	) -> Self {
+		let retention =
+			sync::get_config_string(repo_path, RETENTION_CONFIG)
+				.ok()
+				.flatten()
+				.and_then(|value| value.trim().parse().ok())
+				.unwrap_or(RETENTION_DEFAULT)
+				.max(LIMIT_COUNT);
+
 		Self {
-			current: Arc::new(Mutex::new(Vec::new())),
+			repo_path: repo_path.to_string(),
+			window: Arc::new(Mutex::new(LogWindow::new(
+				retention,
+				LIMIT_COUNT,
+			))),
 			sender: sender.clone(),
 			pending: Arc::new(AtomicBool::new(false)),
 			background: Arc::new(AtomicBool::new(false)),
 			filter,
+			walked_head: None,
 		}
 	}
 
-	///
+	/// next `fetch` walks the log again using `filter`
+	pub fn set_filter(&mut self, filter: Option<LogWalkerFilter>) {
+		self.filter = filter;
+		self.walked_head = None;
+	}
+
+	/// entries walked so far, not all of them are kept
 	pub fn count(&mut self) -> Result<usize> {
-		Ok(self.current.lock()?.len())
+		Ok(self.window.lock()?.total)
 	}
 
-	///
+	/// `None` while the range is walked again, it got evicted
 	pub fn get_slice(
 		&self,
 		start_index: usize,
 		amount: usize,
-	) -> Result<Vec<CommitId>> {
-		let list = self.current.lock()?;
-		let list_len = list.len();
-		let min = start_index.min(list_len);
-		let max = min + amount;
-		let max = max.min(list_len);
-		Ok(list[min..max].to_vec())
+	) -> Result<Option<Vec<CommitId>>> {
+		let mut window = self.window.lock()?;
+
+		let slice = window.slice(start_index, amount);
+		if slice.is_none() {
+			window.recenter(start_index.saturating_add(amount / 2));
+			self.start_job(&mut window);
+		}
+		drop(window);
+
+		Ok(slice)
 	}
 
-	///
+	/// every entry walked so far, evicted ones are walked again
+	pub fn get_all(&self) -> Result<Vec<CommitId>> {
+		let (slice, filter, total, first) = {
+			let window = self.window.lock()?;
+			(
+				window.slice(0, window.total),
+				window.filter.clone(),
+				window.total,
+				window.checkpoint(0),
+			)
+		};
+
+		match (slice, first) {
+			(Some(ids), _) => Ok(ids),
+			(None, Some(first)) => walk_range(
+				&repo(&self.repo_path)?,
+				filter,
+				&first,
+				LIMIT_COUNT,
+				(0, total),
+			),
+			(None, None) => Ok(Vec::new()),
+		}
+	}
+
+	/// index of `id` if it is kept or was found by `locate`
 	pub fn position(&self, id: CommitId) -> Result<Option<usize>> {
-		let list = self.current.lock()?;
-		let position = list.iter().position(|&x| x == id);
+		Ok(self.window.lock()?.position(id))
+	}
 
-		Ok(position)
+	/// looks for `id` in the evicted part of the log and in what
+	/// is walked from now on, see `position`
+	pub fn locate(&self, id: CommitId) -> Result<()> {
+		let mut window = self.window.lock()?;
+
+		let sought =
+			window.sought.as_ref().map_or(false, |s| s.id == id);
+
+		if !sought && window.position(id).is_none() {
+			window.sought = Some(Sought {
+				id,
+				position: None,
+				searched: false,
+			});
+			self.start_job(&mut window);
+		}
+		drop(window);
+
+		Ok(())
 	}
 
-	///
+	/// walking or walking evicted entries again
 	pub fn is_pending(&self) -> bool {
-		self.pending.load(Ordering::Relaxed)
+		self.pending.load(Ordering::Relaxed) || self.is_loading()
+	}
+
+	/// evicted entries are walked again
+	pub fn is_loading(&self) -> bool {
+		self.window
+			.lock()
+			.map_or(false, |window| window.job_running)
 	}
 
 	///
@@ -91,40 +449,39 @@ impl AsyncLog {
 		self.background.store(true, Ordering::Relaxed);
 	}
 
-	///
-	fn current_head(&self) -> Result<CommitId> {
-		Ok(self
-			.current
-			.lock()?
-			.first()
-			.map_or(Oid::zero().into(), |f| *f))
-	}
-
-	///
-	fn head_changed(&self) -> Result<bool> {
-		if let Ok(head) = repo(CWD)?.head() {
-			if let Some(head) = head.target() {
-				return Ok(head != self.current_head()?.into());
-			}
-		}
-		Ok(false)
+	/// with a filter the first entry is not necessarily the head
+	fn head(&self) -> Result<Option<CommitId>> {
+		Ok(repo(&self.repo_path)?
+			.head()
+			.ok()
+			.and_then(|head| head.target())
+			.map(CommitId::new))
 	}
 
 	///
 	pub fn fetch(&mut self) -> Result<FetchStatus> {
 		self.background.store(false, Ordering::Relaxed);
 
-		if self.is_pending() {
+		if self.pending.load(Ordering::Relaxed) {
 			return Ok(FetchStatus::Pending);
 		}
 
-		if !self.head_changed()? {
+		let head = self.head()?;
+		if head == self.walked_head {
 			return Ok(FetchStatus::NoChange);
 		}
 
-		self.clear()?;
+		self.walked_head = head;
+		self.window.lock()?.clear(self.filter.clone());
+
+		// HEAD became unborn (e.g. a new orphan branch), the log of
+		// the branch before is gone and there is nothing to walk
+		if head.is_none() {
+			Self::notify(&self.sender);
+			return Ok(FetchStatus::Started);
+		}
 
-		let arc_current = Arc::clone(&self.current);
+		let arc_window = Arc::clone(&self.window);
 		let sender = self.sender.clone();
 		let arc_pending = Arc::clone(&self.pending);
 		let arc_background = Arc::clone(&self.background);
@@ -132,12 +489,14 @@ impl AsyncLog {
 		self.pending.store(true, Ordering::Relaxed);
 
 		let filter = self.filter.clone();
+		let repo_path = self.repo_path.clone();
 
 		rayon_core::spawn(move || {
 			scope_time!("async::revlog");
 
 			Self::fetch_helper(
-				&arc_current,
+				&repo_path,
+				&arc_window,
 				&arc_background,
 				&sender,
 				filter,
@@ -153,25 +512,35 @@ impl AsyncLog {
 	}
 
 	fn fetch_helper(
-		arc_current: &Arc<Mutex<Vec<CommitId>>>,
+		repo_path: &str,
+		arc_window: &Arc<Mutex<LogWindow>>,
 		arc_background: &Arc<AtomicBool>,
 		sender: &Sender<AsyncGitNotification>,
 		filter: Option<LogWalkerFilter>,
 	) -> Result<()> {
-		let mut entries = Vec::with_capacity(LIMIT_COUNT);
-		let r = repo(CWD)?;
-		let mut walker =
-			LogWalker::new(&r, LIMIT_COUNT)?.filter(filter);
+		let r = repo(repo_path)?;
+		let chunk = arc_window.lock()?.chunk;
+		let mut entries = Vec::with_capacity(chunk);
+		let mut walker = LogWalker::new(&r, chunk)?.filter(filter);
+
+		arc_window.lock()?.checkpoints.push(Checkpoint {
+			index: 0,
+			state: walker.state(),
+		});
+
 		loop {
 			entries.clear();
-			let res_is_err = walker.read(&mut entries).is_err();
+			let res = walker.read(&mut entries);
 
-			if !res_is_err {
-				let mut current = arc_current.lock()?;
-				current.extend(entries.iter());
+			if res.is_ok() {
+				arc_window
+					.lock()?
+					.push_walked(&entries, walker.state());
 			}
 
-			if res_is_err || entries.len() <= 1 {
+			// a filter might drop every entry of a chunk,
+			// only the walked count tells whether we are done
+			if res.map_or(true, |walked| walked < chunk) {
 				break;
 			}
 			Self::notify(sender);
@@ -188,9 +557,107 @@ impl AsyncLog {
 		Ok(())
 	}
 
-	fn clear(&mut self) -> Result<()> {
-		self.current.lock()?.clear();
-		Ok(())
+	/// walks evicted ranges again and looks for `locate`d commits
+	/// until there is nothing left to do
+	fn start_job(&self, window: &mut LogWindow) {
+		if window.job_running || window.checkpoints.is_empty() {
+			return;
+		}
+		window.job_running = true;
+
+		let arc_window = Arc::clone(&self.window);
+		let sender = self.sender.clone();
+		let repo_path = self.repo_path.clone();
+
+		rayon_core::spawn(move || {
+			scope_time!("async::revlog::rewalk");
+
+			if let Err(e) =
+				Self::job_helper(&repo_path, &arc_window, &sender)
+			{
+				log::error!("log rewalk error: {}", e);
+				if let Ok(mut window) = arc_window.lock() {
+					window.job_running = false;
+				}
+			}
+
+			Self::notify(&sender);
+		});
+	}
+
+	fn job_helper(
+		repo_path: &str,
+		arc_window: &Arc<Mutex<LogWindow>>,
+		sender: &Sender<AsyncGitNotification>,
+	) -> Result<()> {
+		let r = repo(repo_path)?;
+
+		loop {
+			let (job, generation, filter, chunk, checkpoints) = {
+				let mut window = arc_window.lock()?;
+				let job = if let Some(job) = window.next_job() {
+					job
+				} else {
+					window.job_running = false;
+					return Ok(());
+				};
+				let start = match job {
+					Job::Locate(..) => 0,
+					Job::Window(start, _) => start,
+				};
+				(
+					job,
+					window.generation,
+					window.filter.clone(),
+					window.chunk,
+					window.checkpoint(start),
+				)
+			};
+
+			let checkpoint = match checkpoints {
+				Some(checkpoint) => checkpoint,
+				None => continue,
+			};
+
+			match job {
+				Job::Locate(id, end) => {
+					let mut found = None;
+					walk_from(
+						&r,
+						filter,
+						&checkpoint,
+						chunk,
+						|index, walked| {
+							if walked == id {
+								found = Some(index);
+							}
+							found.is_none() && index + 1 < end
+						},
+					)?;
+
+					let mut window = arc_window.lock()?;
+					if window.generation == generation {
+						window.located(id, found);
+					}
+				}
+				Job::Window(start, end) => {
+					let ids = walk_range(
+						&r,
+						filter,
+						&checkpoint,
+						chunk,
+						(start, end),
+					)?;
+
+					let mut window = arc_window.lock()?;
+					if window.generation == generation {
+						window.walked_again((start, end), ids);
+					}
+				}
+			}
+
+			Self::notify(sender);
+		}
 	}
 
 	fn notify(sender: &Sender<AsyncGitNotification>) {
@@ -199,3 +666,246 @@ impl AsyncLog {
 			.expect("error sending");
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		cached::BranchName,
+		sync::tests::{
+			debug_cmd_print, repo_init_empty, write_history,
+		},
+	};
+	use crossbeam_channel::{unbounded, Receiver};
+	use std::time::Instant;
+
+	const RETENTION: usize = 100;
+	const CHUNK: usize = 30;
+
+	fn walk(repo: &Repository, window: &mut LogWindow) {
+		let mut walker = LogWalker::new(repo, CHUNK).unwrap();
+		window.checkpoints.push(Checkpoint {
+			index: 0,
+			state: walker.state(),
+		});
+
+		let mut entries = Vec::new();
+		loop {
+			entries.clear();
+			let count = walker.read(&mut entries).unwrap();
+			window.push_walked(&entries, walker.state());
+
+			assert!(window.ids.len() <= RETENTION);
+
+			if count < CHUNK {
+				break;
+			}
+		}
+	}
+
+	fn run_jobs(repo: &Repository, window: &mut LogWindow) {
+		while let Some(job) = window.next_job() {
+			match job {
+				Job::Locate(id, end) => {
+					let mut found = None;
+					walk_from(
+						repo,
+						None,
+						&window.checkpoint(0).unwrap(),
+						CHUNK,
+						|index, walked| {
+							if walked == id {
+								found = Some(index);
+							}
+							found.is_none() && index + 1 < end
+						},
+					)
+					.unwrap();
+					window.located(id, found);
+				}
+				Job::Window(start, end) => {
+					let ids = walk_range(
+						repo,
+						None,
+						&window.checkpoint(start).unwrap(),
+						CHUNK,
+						(start, end),
+					)
+					.unwrap();
+					window.walked_again((start, end), ids);
+				}
+			}
+
+			assert!(window.ids.len() <= RETENTION);
+		}
+	}
+
+	#[test]
+	fn test_retention() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		write_history(&repo, 2000);
+
+		let mut full = Vec::new();
+		let mut walker = LogWalker::new(&repo, 5000).unwrap();
+		walker.read(&mut full).unwrap();
+		assert_eq!(full.len(), 2000);
+
+		let mut window = LogWindow::new(RETENTION, CHUNK);
+		walk(&repo, &mut window);
+
+		assert_eq!(window.total, 2000);
+		assert_eq!(window.slice(0, 50), Some(full[..50].to_vec()));
+		assert_eq!(window.slice(1000, 50), None);
+
+		// scrolling down
+		window.recenter(1000);
+		run_jobs(&repo, &mut window);
+		assert_eq!(
+			window.slice(950, 100),
+			Some(full[950..1050].to_vec())
+		);
+		assert_eq!(window.slice(0, 50), None);
+
+		// and back up
+		window.recenter(10);
+		run_jobs(&repo, &mut window);
+		assert_eq!(window.slice(0, 60), Some(full[..60].to_vec()));
+
+		// selection by id of an evicted entry
+		assert_eq!(window.position(full[1500]), None);
+		window.sought = Some(Sought {
+			id: full[1500],
+			position: None,
+			searched: false,
+		});
+		run_jobs(&repo, &mut window);
+		assert_eq!(window.position(full[1500]), Some(1500));
+	}
+
+	#[test]
+	fn test_sought_while_walking() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		write_history(&repo, 300);
+
+		let mut full = Vec::new();
+		let mut walker = LogWalker::new(&repo, 500).unwrap();
+		walker.read(&mut full).unwrap();
+
+		let mut window = LogWindow::new(RETENTION, CHUNK);
+		window.sought = Some(Sought {
+			id: full[250],
+			position: None,
+			searched: true,
+		});
+		walk(&repo, &mut window);
+
+		assert_eq!(window.position(full[250]), Some(250));
+		assert!(window.next_job().is_none());
+	}
+
+	/// waits for the walk to end, fetches again if HEAD moved
+	/// meanwhile
+	fn wait_walked(
+		log: &mut AsyncLog,
+		rx: &Receiver<AsyncGitNotification>,
+	) {
+		loop {
+			while log.is_pending() {
+				rx.recv_timeout(Duration::from_secs(10)).unwrap();
+			}
+			if log.fetch().unwrap() == FetchStatus::NoChange {
+				break;
+			}
+		}
+	}
+
+	#[test]
+	fn test_external_head_changes() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		// more than one chunk, the walk pauses between them
+		write_history(&repo, LIMIT_COUNT * 2);
+		let old_head =
+			CommitId::new(repo.head().unwrap().target().unwrap());
+
+		let (tx, rx) = unbounded();
+		let mut log = AsyncLog::new(repo_path, &tx, None);
+		let mut branch = BranchName::new(repo_path);
+
+		assert_eq!(log.fetch().unwrap(), FetchStatus::Started);
+		assert_eq!(branch.lookup().unwrap(), "master");
+
+		// another tool switches branches and rewrites the head
+		// while the walk is running
+		debug_cmd_print(repo_path, "git checkout -b feature");
+		debug_cmd_print(
+			repo_path,
+			"git commit --amend --allow-empty -m amended",
+		);
+
+		let new_head =
+			CommitId::new(repo.head().unwrap().target().unwrap());
+		assert_ne!(new_head, old_head);
+
+		wait_walked(&mut log, &rx);
+
+		assert_eq!(branch.lookup().unwrap(), "feature");
+
+		let ids = log.get_all().unwrap();
+		assert_eq!(log.count().unwrap(), ids.len());
+		assert_eq!(ids[0], new_head);
+		assert!(!ids.contains(&old_head));
+	}
+
+	/// time from `fetch` until the first chunk can be drawn, the
+	/// best of a few runs against a history of `count` commits
+	fn time_to_first_chunk(count: usize) -> Duration {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_history(&repo, count);
+
+		(0..3)
+			.map(|_| {
+				let (tx, rx) = unbounded();
+				let mut log = AsyncLog::new(repo_path, &tx, None);
+
+				let start = Instant::now();
+				assert_eq!(
+					log.fetch().unwrap(),
+					FetchStatus::Started
+				);
+				rx.recv_timeout(Duration::from_secs(10)).unwrap();
+				let elapsed = start.elapsed();
+
+				assert!(log.count().unwrap() >= LIMIT_COUNT);
+				assert!(log.get_slice(0, 100).unwrap().is_some());
+
+				// the walk has to end before the repo is dropped
+				wait_walked(&mut log, &rx);
+				assert_eq!(log.count().unwrap(), count);
+
+				elapsed
+			})
+			.min()
+			.unwrap()
+	}
+
+	#[test]
+	#[ignore = "benchmark, builds a 50k commit history"]
+	fn test_first_chunk_independent_of_history() {
+		let small = time_to_first_chunk(LIMIT_COUNT * 2);
+		let large = time_to_first_chunk(50_000);
+
+		assert!(
+			large < small * 3 + Duration::from_millis(50),
+			"first chunk took {:?} with 50k commits, {:?} with {}",
+			large,
+			small,
+			LIMIT_COUNT * 2
+		);
+	}
+}
@@ -21,9 +21,11 @@ fn current_tick() -> u128 {
 		.as_millis()
 }
 
-#[derive(Default, Hash, Clone)]
+#[derive(Default, Hash, Clone, PartialEq)]
 pub struct Status {
 	pub items: Vec<StatusItem>,
+	/// see `sync::status::StatusItems::untracked_truncated`
+	pub untracked_truncated: bool,
 }
 
 ///
@@ -32,6 +34,9 @@ pub struct StatusParams {
 	tick: u128,
 	status_type: StatusType,
 	config: Option<ShowUntrackedFilesConfig>,
+	untracked_limit: Option<usize>,
+	case_insensitive: bool,
+	empty_dirs: bool,
 }
 
 impl StatusParams {
@@ -44,8 +49,35 @@ impl StatusParams {
 			tick: current_tick(),
 			status_type,
 			config,
+			untracked_limit: None,
+			case_insensitive: false,
+			empty_dirs: false,
 		}
 	}
+
+	/// stop enumerating untracked files after `limit` entries
+	#[must_use]
+	pub const fn untracked_limit(
+		mut self,
+		limit: Option<usize>,
+	) -> Self {
+		self.untracked_limit = limit;
+		self
+	}
+
+	/// report case-only renames (see `sync::is_fs_case_insensitive`)
+	#[must_use]
+	pub const fn case_insensitive(mut self, value: bool) -> Self {
+		self.case_insensitive = value;
+		self
+	}
+
+	/// list empty directories (see `sync::add_empty_dirs`)
+	#[must_use]
+	pub const fn empty_dirs(mut self, value: bool) -> Self {
+		self.empty_dirs = value;
+		self
+	}
 }
 
 struct Request<R, A>(R, Option<A>);
@@ -53,9 +85,11 @@ struct Request<R, A>(R, Option<A>);
 ///
 pub struct AsyncStatus {
 	current: Arc<Mutex<Request<u64, Status>>>,
-	last: Arc<Mutex<Status>>,
+	/// `None` until the first fetch finished
+	last: Arc<Mutex<Option<Status>>>,
 	sender: Sender<AsyncGitNotification>,
 	pending: Arc<AtomicUsize>,
+	untracked_progress: Arc<AtomicUsize>,
 }
 
 impl AsyncStatus {
@@ -63,16 +97,17 @@ impl AsyncStatus {
 	pub fn new(sender: Sender<AsyncGitNotification>) -> Self {
 		Self {
 			current: Arc::new(Mutex::new(Request(0, None))),
-			last: Arc::new(Mutex::new(Status::default())),
+			last: Arc::new(Mutex::new(None)),
 			sender,
 			pending: Arc::new(AtomicUsize::new(0)),
+			untracked_progress: Arc::new(AtomicUsize::new(0)),
 		}
 	}
 
 	///
 	pub fn last(&mut self) -> Result<Status> {
 		let last = self.last.lock()?;
-		Ok(last.clone())
+		Ok(last.clone().unwrap_or_default())
 	}
 
 	///
@@ -80,6 +115,11 @@ impl AsyncStatus {
 		self.pending.load(Ordering::Relaxed) > 0
 	}
 
+	/// amount of untracked files found so far by the pending request
+	pub fn untracked_progress(&self) -> usize {
+		self.untracked_progress.load(Ordering::Relaxed)
+	}
+
 	///
 	pub fn fetch(
 		&mut self,
@@ -113,26 +153,38 @@ impl AsyncStatus {
 		let arc_last = Arc::clone(&self.last);
 		let sender = self.sender.clone();
 		let arc_pending = Arc::clone(&self.pending);
-		let status_type = params.status_type;
-		let config = params.config;
+		let arc_progress = Arc::clone(&self.untracked_progress);
+		let params = *params;
 
 		self.pending.fetch_add(1, Ordering::Relaxed);
+		self.untracked_progress.store(0, Ordering::Relaxed);
 
 		rayon_core::spawn(move || {
-			let ok = Self::fetch_helper(
-				status_type,
-				config,
+			let progress_sender = sender.clone();
+			let progress = move |count| {
+				arc_progress.store(count, Ordering::Relaxed);
+				progress_sender
+					.send(AsyncGitNotification::Status)
+					.expect("error sending status");
+			};
+
+			let changed = Self::fetch_helper(
+				&params,
 				hash_request,
 				&arc_current,
 				&arc_last,
-			)
-			.is_ok();
+				&progress,
+			);
 
 			arc_pending.fetch_sub(1, Ordering::Relaxed);
 
-			if ok {
+			if let Ok(changed) = changed {
 				sender
-					.send(AsyncGitNotification::Status)
+					.send(if changed {
+						AsyncGitNotification::Status
+					} else {
+						AsyncGitNotification::FinishUnchanged
+					})
 					.expect("error sending status");
 			}
 		});
@@ -140,18 +192,19 @@ impl AsyncStatus {
 		Ok(None)
 	}
 
+	/// `true` if the result differs from the last one
 	fn fetch_helper(
-		status_type: StatusType,
-		config: Option<ShowUntrackedFilesConfig>,
+		params: &StatusParams,
 		hash_request: u64,
 		arc_current: &Arc<Mutex<Request<u64, Status>>>,
-		arc_last: &Arc<Mutex<Status>>,
-	) -> Result<()> {
-		let res = Self::get_status(status_type, config)?;
+		arc_last: &Arc<Mutex<Option<Status>>>,
+		progress: &dyn Fn(usize),
+	) -> Result<bool> {
+		let res = Self::get_status(params, progress)?;
 		log::trace!(
 			"status fetched: {} (type: {:?})",
 			hash_request,
-			status_type,
+			params.status_type,
 		);
 
 		{
@@ -161,24 +214,45 @@ impl AsyncStatus {
 			}
 		}
 
-		{
+		let changed = {
 			let mut last = arc_last.lock()?;
-			*last = res;
-		}
+			let changed = last.as_ref() != Some(&res);
+			*last = Some(res);
+			changed
+		};
 
-		Ok(())
+		Ok(changed)
 	}
 
 	fn get_status(
-		status_type: StatusType,
-		config: Option<ShowUntrackedFilesConfig>,
+		params: &StatusParams,
+		progress: &dyn Fn(usize),
 	) -> Result<Status> {
+		let mut res = sync::status::get_status_limited(
+			CWD,
+			params.status_type,
+			params.config,
+			params.untracked_limit,
+			progress,
+		)?;
+
+		if params.case_insensitive {
+			sync::status::apply_case_only_renames(
+				&mut res.items,
+				sync::status::case_only_renames(
+					CWD,
+					params.status_type,
+				)?,
+			);
+		}
+
+		if params.empty_dirs {
+			sync::add_empty_dirs(CWD, params.config, &mut res.items)?;
+		}
+
 		Ok(Status {
-			items: sync::status::get_status(
-				CWD,
-				status_type,
-				config,
-			)?,
+			items: res.items,
+			untracked_truncated: res.untracked_truncated,
 		})
 	}
 }
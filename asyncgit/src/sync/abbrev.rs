@@ -0,0 +1,283 @@
+//! unambiguous abbreviated object ids respecting `core.abbrev`
+
+use super::{config::get_config_string_repo, utils::repo, CommitId};
+use crate::error::Result;
+use git2::{ErrorCode, Oid, Repository};
+use scopetime::scope_time;
+use std::{
+	cell::RefCell,
+	collections::HashMap,
+	convert::TryInto,
+	fs,
+	io::Read,
+	mem,
+	path::{Path, PathBuf},
+};
+
+const MIN_LEN: usize = 4;
+const MAX_LEN: usize = 40;
+/// git's fallback for `auto` in small repos
+const DEFAULT_LEN: usize = 7;
+
+/// abbreviations are dropped once the cache gets this big
+const CACHE_CAPACITY: usize = 4096;
+
+#[derive(Default)]
+struct AbbrevCache {
+	repo_path: String,
+	/// `core.abbrev` as of the first lookup
+	min_len: Option<usize>,
+	ids: HashMap<CommitId, String>,
+}
+
+thread_local! {
+	static CACHE: RefCell<AbbrevCache> = RefCell::new(AbbrevCache::default());
+}
+
+/// shortest prefix of `id` that is at least `core.abbrev` long and
+/// does not match any other object in the repo,
+/// abbreviations are cached per repo
+pub fn abbreviate_oid(
+	repo_path: &str,
+	id: CommitId,
+) -> Result<String> {
+	CACHE.with(|cache| {
+		let mut cache = cache.borrow_mut();
+
+		if cache.repo_path != repo_path {
+			*cache = AbbrevCache {
+				repo_path: repo_path.to_string(),
+				..AbbrevCache::default()
+			};
+		}
+
+		if let Some(short) = cache.ids.get(&id) {
+			return Ok(short.clone());
+		}
+
+		scope_time!("abbreviate_oid");
+
+		let repo = repo(repo_path)?;
+
+		let min_len = if let Some(len) = cache.min_len {
+			len
+		} else {
+			let len = abbrev_len(&repo)?;
+			cache.min_len = Some(len);
+			len
+		};
+
+		let short = unique_prefix(&repo, id.into(), min_len)?;
+
+		if cache.ids.len() >= CACHE_CAPACITY {
+			cache.ids.clear();
+		}
+		cache.ids.insert(id, short.clone());
+
+		Ok(short)
+	})
+}
+
+/// minimal abbreviation length as configured by `core.abbrev`
+fn abbrev_len(repo: &Repository) -> Result<usize> {
+	let value = get_config_string_repo(repo, "core.abbrev")?
+		.map(|value| value.trim().to_lowercase());
+
+	Ok(match value.as_deref() {
+		None | Some("auto") => auto_len(repo),
+		Some("false") | Some("no") | Some("off") => MAX_LEN,
+		Some(value) => value
+			.parse::<usize>()
+			.ok()
+			.filter(|len| (MIN_LEN..=MAX_LEN).contains(len))
+			.unwrap_or(DEFAULT_LEN),
+	})
+}
+
+/// like git: expecting a collision at 2^(bits/2) objects
+/// while every hex char covers 4 bits
+fn auto_len(repo: &Repository) -> usize {
+	let count = approximate_object_count(repo);
+
+	// bits needed to represent `count`
+	let bits =
+		mem::size_of::<usize>() * 8 - count.leading_zeros() as usize;
+
+	((bits + 1) / 2).max(DEFAULT_LEN)
+}
+
+/// objects in packs as listed by their index, loose objects
+/// are not counted (git does the same)
+fn approximate_object_count(repo: &Repository) -> usize {
+	let pack_dir = objects_dir(repo).join("pack");
+
+	fs::read_dir(pack_dir).map_or(0, |entries| {
+		entries
+			.filter_map(std::result::Result::ok)
+			.map(|entry| entry.path())
+			.filter(|path| {
+				path.extension().map_or(false, |ext| ext == "idx")
+			})
+			.filter_map(|path| pack_index_count(&path))
+			.sum()
+	})
+}
+
+/// worktrees keep their objects in the common dir
+fn objects_dir(repo: &Repository) -> PathBuf {
+	let git_dir = repo.path();
+
+	fs::read_to_string(git_dir.join("commondir"))
+		.map_or_else(
+			|_| git_dir.to_path_buf(),
+			|common| git_dir.join(common.trim()),
+		)
+		.join("objects")
+}
+
+/// number of objects from the last fanout entry of a v2 pack index
+fn pack_index_count(path: &Path) -> Option<usize> {
+	const MAGIC: [u8; 4] = [0xff, b't', b'O', b'c'];
+	const COUNT_OFFSET: usize = 8 + 255 * 4;
+
+	// magic, version and the fanout table up to its last entry
+	let mut header = [0_u8; COUNT_OFFSET + 4];
+	fs::File::open(path).ok()?.read_exact(&mut header).ok()?;
+
+	if header[0..4] != MAGIC {
+		return None;
+	}
+
+	let count: [u8; 4] = header[COUNT_OFFSET..].try_into().ok()?;
+
+	Some(u32::from_be_bytes(count) as usize)
+}
+
+fn unique_prefix(
+	repo: &Repository,
+	id: Oid,
+	min_len: usize,
+) -> Result<String> {
+	let odb = repo.odb()?;
+	let hex = id.to_string();
+
+	for len in min_len..MAX_LEN {
+		let prefix = &hex[..len];
+
+		// not in the odb (yet) at all is as unique as it gets
+		let ambiguous = matches!(
+			odb.exists_prefix(Oid::from_str(prefix)?, len),
+			Err(e) if e.code() == ErrorCode::Ambiguous
+		);
+
+		if !ambiguous {
+			return Ok(prefix.to_string());
+		}
+	}
+
+	Ok(hex)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::tests::{
+		debug_cmd_print, repo_init, write_commit_file,
+	};
+	use git2::ObjectType;
+
+	/// a blob whose id starts with the same `len` chars as `id`
+	fn colliding_blob(id: Oid, len: usize) -> Vec<u8> {
+		let prefix = &id.to_string()[..len];
+
+		(0..u32::MAX)
+			.map(|i| format!("collide {}", i).into_bytes())
+			.find(|data| {
+				Oid::hash_object(ObjectType::Blob, data)
+					.unwrap()
+					.to_string()
+					.starts_with(prefix)
+			})
+			.unwrap()
+	}
+
+	#[test]
+	fn test_abbrev_config() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let id = write_commit_file(&repo, "foo", "a", "c1");
+		let hex = id.to_string();
+
+		let mut config = repo.config().unwrap();
+
+		assert_eq!(abbrev_len(&repo).unwrap(), DEFAULT_LEN);
+		assert_eq!(abbreviate_oid(repo_path, id).unwrap(), hex[..7]);
+
+		config.set_str("core.abbrev", "12").unwrap();
+		assert_eq!(abbrev_len(&repo).unwrap(), 12);
+		config.set_str("core.abbrev", "no").unwrap();
+		assert_eq!(abbrev_len(&repo).unwrap(), MAX_LEN);
+		config.set_str("core.abbrev", "2").unwrap();
+		assert_eq!(abbrev_len(&repo).unwrap(), DEFAULT_LEN);
+		config.set_str("core.abbrev", "auto").unwrap();
+		assert_eq!(abbrev_len(&repo).unwrap(), DEFAULT_LEN);
+	}
+
+	#[test]
+	fn test_collision() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		repo.config().unwrap().set_str("core.abbrev", "4").unwrap();
+
+		let id = write_commit_file(&repo, "foo", "a", "c1");
+		let hex = id.to_string();
+
+		let blob = repo
+			.blob(&colliding_blob(id.into(), MIN_LEN))
+			.unwrap()
+			.to_string();
+
+		let short = unique_prefix(&repo, id.into(), MIN_LEN).unwrap();
+		assert!(short.len() > MIN_LEN);
+		assert!(hex.starts_with(&short));
+		assert!(!blob.starts_with(&short));
+		assert_eq!(short.len(), common_prefix(&hex, &blob) + 1);
+
+		assert_eq!(abbreviate_oid(repo_path, id).unwrap(), short);
+	}
+
+	#[test]
+	fn test_pack_index_count() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "foo", "a", "c1");
+		assert_eq!(approximate_object_count(&repo), 0);
+
+		debug_cmd_print(repo_path, "git repack -a -d");
+
+		let mut objects = 0;
+		repo.odb()
+			.unwrap()
+			.foreach(|_| {
+				objects += 1;
+				true
+			})
+			.unwrap();
+		assert_eq!(approximate_object_count(&repo), objects);
+
+		// shorter than the header
+		let idx = root.join("short.idx");
+		fs::write(&idx, [0xff, b't', b'O', b'c']).unwrap();
+		assert_eq!(pack_index_count(&idx), None);
+	}
+
+	fn common_prefix(a: &str, b: &str) -> usize {
+		a.chars().zip(b.chars()).take_while(|(a, b)| a == b).count()
+	}
+}
@@ -0,0 +1,140 @@
+//! authors of the commits reachable from head, as mapped by the
+//! mailmap
+
+use super::{
+	mailmap::{mailmap_repo, resolve},
+	utils::{is_head_unborn, repo},
+};
+use crate::error::Result;
+use git2::Sort;
+use scopetime::scope_time;
+use std::collections::HashMap;
+
+/// commits looked at to rank authors, keeps it quick in huge repos
+const WALK_LIMIT: usize = 10_000;
+
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthorInfo {
+	/// as of the author's most recent commit
+	pub name: String,
+	///
+	pub email: String,
+	/// number of commits authored
+	pub commits: usize,
+}
+
+/// `limit` authors with the most commits among the most recent ones
+/// on head, most commits first
+pub fn get_top_authors(
+	repo_path: &str,
+	limit: usize,
+) -> Result<Vec<AuthorInfo>> {
+	scope_time!("get_top_authors");
+
+	let repo = repo(repo_path)?;
+
+	if is_head_unborn(&repo)? {
+		return Ok(Vec::new());
+	}
+
+	let mut walk = repo.revwalk()?;
+	walk.push_head()?;
+	walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+
+	let mut authors: HashMap<String, AuthorInfo> = HashMap::new();
+
+	let mailmap = mailmap_repo(&repo)?;
+
+	for id in walk.take(WALK_LIMIT) {
+		let commit = repo.find_commit(id?)?;
+		let author = resolve(&mailmap, &commit.author());
+		let email = String::from_utf8_lossy(author.email_bytes());
+
+		authors
+			.entry(email.to_string())
+			.or_insert_with(|| AuthorInfo {
+				name: String::from_utf8_lossy(author.name_bytes())
+					.to_string(),
+				email: email.to_string(),
+				commits: 0,
+			})
+			.commits += 1;
+	}
+
+	let mut authors = authors
+		.into_iter()
+		.map(|(_, author)| author)
+		.collect::<Vec<_>>();
+	authors.sort_by(|a, b| {
+		b.commits.cmp(&a.commits).then_with(|| a.name.cmp(&b.name))
+	});
+	authors.truncate(limit);
+
+	Ok(authors)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::tests::{repo_init, repo_init_empty};
+	use git2::{Repository, Signature};
+
+	fn commit_by(repo: &Repository, name: &str, email: &str) {
+		let sig = Signature::now(name, email).unwrap();
+		let tree_id = repo.index().unwrap().write_tree().unwrap();
+		let tree = repo.find_tree(tree_id).unwrap();
+		let parent = repo.head().unwrap().peel_to_commit().unwrap();
+
+		repo.commit(
+			Some("HEAD"),
+			&sig,
+			&sig,
+			"msg",
+			&tree,
+			&[&parent],
+		)
+		.unwrap();
+	}
+
+	#[test]
+	fn test_top_authors() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		commit_by(&repo, "b", "b@example.com");
+		commit_by(&repo, "b", "b@example.com");
+		commit_by(&repo, "c", "c@example.com");
+		commit_by(&repo, "new b", "b@example.com");
+
+		let authors = get_top_authors(repo_path, 50).unwrap();
+
+		assert_eq!(
+			authors
+				.iter()
+				.map(|a| (
+					a.name.as_str(),
+					a.email.as_str(),
+					a.commits
+				))
+				.collect::<Vec<_>>(),
+			vec![
+				("new b", "b@example.com", 3),
+				("c", "c@example.com", 1),
+				("name", "email", 1),
+			]
+		);
+
+		assert_eq!(get_top_authors(repo_path, 1).unwrap().len(), 1);
+	}
+
+	#[test]
+	fn test_unborn_head() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		assert!(get_top_authors(repo_path, 50).unwrap().is_empty());
+	}
+}
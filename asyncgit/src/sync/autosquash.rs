@@ -0,0 +1,768 @@
+//! rebase squashing `fixup!`/`squash!` commits into the commits they
+//! name, like `git rebase -i --autosquash`.
+//!
+//! the todo is kept in `rebase-merge` the way git keeps it, so the
+//! state shows up (and can be aborted) like any interactive rebase
+
+use super::{
+	commit::signature_allow_undefined_name, utils, CommitId,
+};
+use crate::error::{Error, Result};
+use git2::{
+	build::CheckoutBuilder, Commit, Oid, Repository, RepositoryState,
+	Sort, Status, StatusOptions,
+};
+use scopetime::scope_time;
+use std::{
+	fs,
+	io::{self, ErrorKind},
+	path::{Path, PathBuf},
+};
+
+const FIXUP_PREFIX: &str = "fixup! ";
+const SQUASH_PREFIX: &str = "squash! ";
+
+/// marks a rebase run by us, other interactive rebases are git's
+const MARKER_FILE: &str = "gitui-autosquash";
+const TODO_FILE: &str = "git-rebase-todo";
+const DONE_FILE: &str = "done";
+
+/// shortest hex prefix accepted as a target id
+const MIN_ID_PREFIX: usize = 4;
+
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TodoAction {
+	///
+	Pick,
+	/// into the previous commit, keeping its message
+	Fixup,
+	/// into the previous commit, appending the message
+	Squash,
+}
+
+impl TodoAction {
+	const fn as_str(self) -> &'static str {
+		match self {
+			Self::Pick => "pick",
+			Self::Fixup => "fixup",
+			Self::Squash => "squash",
+		}
+	}
+
+	fn parse(s: &str) -> Option<Self> {
+		match s {
+			"pick" | "p" => Some(Self::Pick),
+			"fixup" | "f" => Some(Self::Fixup),
+			"squash" | "s" => Some(Self::Squash),
+			_ => None,
+		}
+	}
+}
+
+/// line of the rebase todo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TodoEntry {
+	///
+	pub action: TodoAction,
+	///
+	pub id: CommitId,
+	///
+	pub subject: String,
+}
+
+impl TodoEntry {
+	fn to_line(&self) -> String {
+		format!(
+			"{} {} {}",
+			self.action.as_str(),
+			self.id.to_string(),
+			self.subject
+		)
+	}
+
+	fn parse(line: &str) -> Result<Self> {
+		let invalid = || {
+			Error::Generic(format!(
+				"unsupported rebase todo: {}",
+				line
+			))
+		};
+
+		let mut parts = line.splitn(3, ' ');
+		let action = parts
+			.next()
+			.and_then(TodoAction::parse)
+			.ok_or_else(invalid)?;
+		let id = parts
+			.next()
+			.and_then(|id| CommitId::from_hex(id).ok())
+			.ok_or_else(invalid)?;
+
+		Ok(Self {
+			action,
+			id,
+			subject: parts.next().unwrap_or_default().to_string(),
+		})
+	}
+}
+
+/// todo of an autosquash rebase
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AutosquashTodo {
+	///
+	pub entries: Vec<TodoEntry>,
+	/// `fixup!`/`squash!` commits whose target was not found,
+	/// they stay as they are
+	pub unmatched: Vec<TodoEntry>,
+}
+
+///
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AutosquashResult {
+	/// stopped at a conflict, to be continued or aborted like any
+	/// other rebase
+	pub conflict: bool,
+	/// see `AutosquashTodo::unmatched`
+	pub unmatched: Vec<TodoEntry>,
+}
+
+/// action and target named by a subject, `fixup! fixup! a` targets
+/// `a` like in git
+fn parse_fixup(subject: &str) -> Option<(TodoAction, &str)> {
+	let action = if subject.starts_with(FIXUP_PREFIX) {
+		TodoAction::Fixup
+	} else if subject.starts_with(SQUASH_PREFIX) {
+		TodoAction::Squash
+	} else {
+		return None;
+	};
+
+	let mut target = subject;
+	loop {
+		if let Some(rest) = target.strip_prefix(FIXUP_PREFIX) {
+			target = rest;
+		} else if let Some(rest) = target.strip_prefix(SQUASH_PREFIX)
+		{
+			target = rest;
+		} else {
+			return Some((action, target.trim()));
+		}
+	}
+}
+
+/// full subject first, then id prefix, then subject prefix
+fn find_target(picks: &[TodoEntry], target: &str) -> Option<usize> {
+	if target.is_empty() {
+		return None;
+	}
+
+	let is_id_prefix = target.len() >= MIN_ID_PREFIX
+		&& target.chars().all(|c| c.is_ascii_hexdigit());
+
+	picks
+		.iter()
+		.position(|pick| pick.subject == target)
+		.or_else(|| {
+			picks.iter().position(|pick| {
+				is_id_prefix
+					&& pick.id.to_string().starts_with(target)
+			})
+		})
+		.or_else(|| {
+			picks
+				.iter()
+				.position(|pick| pick.subject.starts_with(target))
+		})
+}
+
+/// moves each `fixup!`/`squash!` commit behind the (earlier) commit
+/// it names, after any fixups already there. `commits` are
+/// `(id, subject)`, oldest first
+pub fn autosquash_todo(
+	commits: &[(CommitId, String)],
+) -> AutosquashTodo {
+	let mut picks: Vec<TodoEntry> = Vec::new();
+	// going into the pick of the same index
+	let mut fixups: Vec<Vec<TodoEntry>> = Vec::new();
+	let mut unmatched = Vec::new();
+
+	for (id, subject) in commits {
+		let pick = TodoEntry {
+			action: TodoAction::Pick,
+			id: *id,
+			subject: subject.clone(),
+		};
+
+		if let Some((action, target)) = parse_fixup(subject) {
+			if let Some(idx) = find_target(&picks, target) {
+				fixups[idx].push(TodoEntry { action, ..pick });
+				continue;
+			}
+
+			unmatched.push(pick.clone());
+		}
+
+		picks.push(pick);
+		fixups.push(Vec::new());
+	}
+
+	AutosquashTodo {
+		entries: picks
+			.into_iter()
+			.zip(fixups)
+			.flat_map(|(pick, fixups)| {
+				std::iter::once(pick).chain(fixups)
+			})
+			.collect(),
+		unmatched,
+	}
+}
+
+fn first_line(message: &str) -> &str {
+	message.lines().next().unwrap_or_default()
+}
+
+/// the squashed message is appended without its `squash! ` subject
+fn squash_message(target: &str, squash: &str) -> String {
+	let body = if first_line(squash).starts_with(SQUASH_PREFIX) {
+		squash.splitn(2, '\n').nth(1).unwrap_or_default()
+	} else {
+		squash
+	};
+
+	if body.trim().is_empty() {
+		return target.to_string();
+	}
+
+	format!("{}\n\n{}\n", target.trim_end(), body.trim())
+}
+
+/// one entry per line, like git writes them
+fn join_lines<S: AsRef<str>>(
+	lines: impl Iterator<Item = S>,
+) -> String {
+	lines.fold(String::new(), |mut res, line| {
+		res.push_str(line.as_ref());
+		res.push('\n');
+		res
+	})
+}
+
+fn rebase_dir(repo: &Repository) -> PathBuf {
+	repo.path().join("rebase-merge")
+}
+
+/// an interactive rebase started by `autosquash_rebase`
+pub fn is_autosquash_rebase(repo: &Repository) -> bool {
+	repo.state() == RepositoryState::RebaseInteractive
+		&& rebase_dir(repo).join(MARKER_FILE).is_file()
+}
+
+fn remove_file_if_exists(path: &Path) -> io::Result<()> {
+	match fs::remove_file(path) {
+		Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+		_ => Ok(()),
+	}
+}
+
+fn read_file(path: &Path) -> Result<String> {
+	Ok(fs::read_to_string(path)?.trim().to_string())
+}
+
+/// no operation in progress and no changes to tracked files
+pub fn ensure_clean(repo: &Repository) -> Result<()> {
+	if repo.state() != RepositoryState::Clean {
+		return Err(Error::Generic(String::from(
+			"finish the operation in progress first",
+		)));
+	}
+
+	let mut options = StatusOptions::new();
+	options.include_untracked(false).include_ignored(false);
+
+	let dirty = repo
+		.statuses(Some(&mut options))?
+		.iter()
+		.any(|entry| entry.status() != Status::CURRENT);
+
+	if dirty {
+		return Err(Error::Generic(String::from(
+			"commit or stash your changes first",
+		)));
+	}
+
+	Ok(())
+}
+
+/// commits of `HEAD` not in `onto`, oldest first
+fn commits_to_rebase(
+	repo: &Repository,
+	onto: CommitId,
+) -> Result<Vec<(CommitId, String)>> {
+	let mut walk = repo.revwalk()?;
+	walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+	walk.push_head()?;
+	walk.hide(onto.into())?;
+
+	walk.map(|id| {
+		let commit = repo.find_commit(id?)?;
+
+		if commit.parent_count() > 1 {
+			return Err(Error::Generic(format!(
+				"cannot autosquash merge commit {}",
+				CommitId::from(commit.id()).get_short_string()
+			)));
+		}
+
+		Ok((
+			commit.id().into(),
+			first_line(&String::from_utf8_lossy(
+				commit.message_bytes(),
+			))
+			.to_string(),
+		))
+	})
+	.collect()
+}
+
+/// rebases the current branch onto `onto` squashing `fixup!` and
+/// `squash!` commits into their targets. stops at the first
+/// conflict, see `continue_pending_state`
+pub fn autosquash_rebase(
+	repo_path: &str,
+	onto: CommitId,
+) -> Result<AutosquashResult> {
+	scope_time!("autosquash_rebase");
+
+	let repo = utils::repo(repo_path)?;
+	ensure_clean(&repo)?;
+
+	let commits = commits_to_rebase(&repo, onto)?;
+	if commits.is_empty() {
+		return Err(Error::Generic(String::from(
+			"nothing to rebase",
+		)));
+	}
+
+	let todo = autosquash_todo(&commits);
+
+	let head = repo.head()?;
+	let head_name = if repo.head_detached()? {
+		String::from("detached HEAD")
+	} else {
+		head.name().unwrap_or_default().to_string()
+	};
+	let orig_head = head.peel_to_commit()?.id();
+
+	let dir = rebase_dir(&repo);
+	fs::create_dir(&dir)?;
+	fs::write(dir.join("head-name"), format!("{}\n", head_name))?;
+	fs::write(dir.join("onto"), format!("{}\n", onto.to_string()))?;
+	fs::write(dir.join("orig-head"), format!("{}\n", orig_head))?;
+	fs::write(dir.join("interactive"), "")?;
+	fs::write(dir.join(MARKER_FILE), "")?;
+	fs::write(dir.join(DONE_FILE), "")?;
+	fs::write(dir.join("msgnum"), "0\n")?;
+	fs::write(dir.join("end"), format!("{}\n", todo.entries.len()))?;
+	fs::write(
+		dir.join(TODO_FILE),
+		join_lines(todo.entries.iter().map(TodoEntry::to_line)),
+	)?;
+
+	let onto_commit = repo.find_commit(onto.into())?;
+	repo.checkout_tree(
+		onto_commit.as_object(),
+		Some(CheckoutBuilder::new().safe()),
+	)?;
+	repo.set_head_detached(onto.into())?;
+
+	let conflict = run_todo(&repo)?;
+
+	Ok(AutosquashResult {
+		conflict,
+		unmatched: todo.unmatched,
+	})
+}
+
+/// commits the entry stopped at once its conflicts are resolved and
+/// runs the rest of the todo
+pub fn continue_autosquash(repo: &Repository) -> Result<()> {
+	let done = fs::read_to_string(rebase_dir(repo).join(DONE_FILE))?;
+
+	if let Some(line) = done.lines().last() {
+		commit_entry(repo, &TodoEntry::parse(line)?)?;
+	}
+
+	run_todo(repo)?;
+
+	Ok(())
+}
+
+/// `true` if stopped at a conflict
+fn run_todo(repo: &Repository) -> Result<bool> {
+	let dir = rebase_dir(repo);
+
+	loop {
+		let todo = fs::read_to_string(dir.join(TODO_FILE))?;
+		let mut lines = todo.lines().map(str::trim).filter(|line| {
+			!line.is_empty() && !line.starts_with('#')
+		});
+
+		let line = if let Some(line) = lines.next() {
+			line
+		} else {
+			finish(repo)?;
+			return Ok(false);
+		};
+		let entry = TodoEntry::parse(line)?;
+
+		let mut done = fs::read_to_string(dir.join(DONE_FILE))?;
+		done.push_str(line);
+		done.push('\n');
+		fs::write(dir.join(DONE_FILE), done)?;
+		fs::write(dir.join(TODO_FILE), join_lines(lines))?;
+
+		let msgnum = read_file(&dir.join("msgnum"))
+			.ok()
+			.and_then(|n| n.parse::<usize>().ok())
+			.unwrap_or_default();
+		fs::write(dir.join("msgnum"), format!("{}\n", msgnum + 1))?;
+
+		let commit = repo.find_commit(entry.id.into())?;
+		repo.cherrypick(&commit, None)?;
+		// we are not cherry-picking as far as git is concerned
+		remove_file_if_exists(&repo.path().join("CHERRY_PICK_HEAD"))?;
+
+		if repo.index()?.has_conflicts() {
+			return Ok(true);
+		}
+
+		commit_entry(repo, &entry)?;
+	}
+}
+
+/// commits the index, fixups and squashes amend `HEAD`. picks that
+/// turn out empty (applied upstream already) are dropped
+fn commit_entry(repo: &Repository, entry: &TodoEntry) -> Result<()> {
+	let commit = repo.find_commit(entry.id.into())?;
+	let head = repo.head()?.peel_to_commit()?;
+	let tree = repo.find_tree(repo.index()?.write_tree()?)?;
+	let committer = signature_allow_undefined_name(repo)?;
+	let message = String::from_utf8_lossy(commit.message_bytes());
+
+	let new_id = match entry.action {
+		TodoAction::Pick => {
+			if tree.id() == head.tree_id()
+				&& !is_empty_commit(&commit)
+			{
+				None
+			} else {
+				Some(repo.commit(
+					None,
+					&commit.author(),
+					&committer,
+					&message,
+					&tree,
+					&[&head],
+				)?)
+			}
+		}
+		TodoAction::Fixup | TodoAction::Squash => {
+			let head_message =
+				String::from_utf8_lossy(head.message_bytes());
+			let message = if entry.action == TodoAction::Squash {
+				squash_message(&head_message, &message)
+			} else {
+				head_message.to_string()
+			};
+			let parents = head.parents().collect::<Vec<_>>();
+
+			Some(repo.commit(
+				None,
+				&head.author(),
+				&committer,
+				&message,
+				&tree,
+				&parents.iter().collect::<Vec<_>>(),
+			)?)
+		}
+	};
+
+	if let Some(id) = new_id {
+		repo.set_head_detached(id)?;
+	}
+
+	remove_file_if_exists(&repo.path().join("MERGE_MSG"))?;
+
+	Ok(())
+}
+
+fn is_empty_commit(commit: &Commit) -> bool {
+	commit
+		.parent(0)
+		.map(|parent| parent.tree_id() == commit.tree_id())
+		.unwrap_or_default()
+}
+
+/// moves the branch to the rebased commits
+fn finish(repo: &Repository) -> Result<()> {
+	let dir = rebase_dir(repo);
+	let head_name = read_file(&dir.join("head-name"))?;
+	let head: Oid = repo.head()?.peel_to_commit()?.id();
+
+	if head_name.starts_with("refs/") {
+		repo.reference(
+			&head_name,
+			head,
+			true,
+			"rebase (autosquash): finished",
+		)?;
+		repo.set_head(&head_name)?;
+	}
+
+	fs::remove_dir_all(&dir)?;
+	repo.cleanup_state()?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		abort_pending_state, continue_pending_state,
+		get_commits_info, repo_state, stage_add_file,
+		tests::{get_commit_ids, repo_init, write_commit_file},
+		utils::get_head_repo,
+		RepoState,
+	};
+	use pretty_assertions::assert_eq;
+
+	fn id(n: u8) -> CommitId {
+		CommitId::from(Oid::from_bytes(&[n; 20]).unwrap())
+	}
+
+	fn plan(subjects: &[&str]) -> Vec<(TodoAction, String)> {
+		let commits: Vec<(CommitId, String)> = (1..)
+			.zip(subjects)
+			.map(|(n, s)| (id(n), (*s).to_string()))
+			.collect();
+
+		autosquash_todo(&commits)
+			.entries
+			.into_iter()
+			.map(|entry| (entry.action, entry.subject))
+			.collect()
+	}
+
+	fn entry(
+		action: TodoAction,
+		subject: &str,
+	) -> (TodoAction, String) {
+		(action, subject.to_string())
+	}
+
+	fn messages(repo_path: &str, count: usize) -> Vec<String> {
+		let repo = utils::repo(repo_path).unwrap();
+		let ids = get_commit_ids(&repo, count);
+		get_commits_info(repo_path, &ids, usize::MAX)
+			.unwrap()
+			.into_iter()
+			.map(|info| info.message)
+			.collect()
+	}
+
+	fn read(repo_path: &str, file: &str) -> String {
+		fs::read_to_string(Path::new(repo_path).join(file)).unwrap()
+	}
+
+	#[test]
+	fn test_todo_reorders() {
+		assert_eq!(
+			plan(&["a", "b", "fixup! a", "squash! b", "fixup! a"]),
+			vec![
+				entry(TodoAction::Pick, "a"),
+				entry(TodoAction::Fixup, "fixup! a"),
+				entry(TodoAction::Fixup, "fixup! a"),
+				entry(TodoAction::Pick, "b"),
+				entry(TodoAction::Squash, "squash! b"),
+			]
+		);
+	}
+
+	#[test]
+	fn test_todo_nested_and_prefix() {
+		assert_eq!(
+			plan(&[
+				"add parser",
+				"fixup! fixup! add parser",
+				"fixup! add"
+			]),
+			vec![
+				entry(TodoAction::Pick, "add parser"),
+				entry(TodoAction::Fixup, "fixup! fixup! add parser"),
+				entry(TodoAction::Fixup, "fixup! add"),
+			]
+		);
+	}
+
+	#[test]
+	fn test_todo_by_id() {
+		let todo = autosquash_todo(&[
+			(id(0xab), String::from("a")),
+			(id(2), String::from("fixup! abab")),
+		]);
+
+		assert_eq!(todo.entries[1].action, TodoAction::Fixup);
+		assert!(todo.unmatched.is_empty());
+	}
+
+	#[test]
+	fn test_todo_unmatched() {
+		let todo = autosquash_todo(&[
+			(id(1), String::from("fixup! b")),
+			(id(2), String::from("b")),
+			(id(3), String::from("squash! nothing")),
+		]);
+
+		// targets have to come first
+		assert_eq!(
+			todo.entries
+				.iter()
+				.map(|entry| entry.action)
+				.collect::<Vec<_>>(),
+			vec![TodoAction::Pick; 3]
+		);
+		assert_eq!(
+			todo.unmatched
+				.iter()
+				.map(|entry| entry.id)
+				.collect::<Vec<_>>(),
+			vec![id(1), id(3)]
+		);
+	}
+
+	#[test]
+	fn test_todo_line() {
+		let entry = TodoEntry {
+			action: TodoAction::Squash,
+			id: id(1),
+			subject: String::from("squash! a b"),
+		};
+
+		assert_eq!(
+			TodoEntry::parse(&entry.to_line()).unwrap(),
+			entry
+		);
+		assert!(TodoEntry::parse("exec make").is_err());
+	}
+
+	#[test]
+	fn test_squash_message() {
+		assert_eq!(squash_message("a\n", "squash! a\n"), "a\n");
+		assert_eq!(
+			squash_message("a\n\nbody\n", "squash! a\n\nmore\n"),
+			"a\n\nbody\n\nmore\n"
+		);
+	}
+
+	#[test]
+	fn test_rebase() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let base = write_commit_file(&repo, "a.txt", "0", "base");
+		write_commit_file(&repo, "a.txt", "1", "change a");
+		write_commit_file(&repo, "b.txt", "1", "add b");
+		write_commit_file(&repo, "a.txt", "2", "fixup! change a");
+		write_commit_file(&repo, "b.txt", "2", "squash! add b");
+		write_commit_file(&repo, "c.txt", "1", "fixup! unknown");
+
+		let res = autosquash_rebase(repo_path, base).unwrap();
+
+		assert!(!res.conflict);
+		assert_eq!(res.unmatched.len(), 1);
+		assert_eq!(res.unmatched[0].subject, "fixup! unknown");
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		assert!(!repo.head_detached().unwrap());
+		assert_eq!(
+			messages(repo_path, 4),
+			vec!["fixup! unknown", "add b", "change a", "base"]
+		);
+		assert_eq!(read(repo_path, "a.txt"), "2");
+		assert_eq!(read(repo_path, "b.txt"), "2");
+	}
+
+	#[test]
+	fn test_rebase_conflict() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let base = write_commit_file(&repo, "a.txt", "0", "base");
+		write_commit_file(&repo, "a.txt", "1", "change a");
+		write_commit_file(&repo, "a.txt", "2", "change a again");
+		write_commit_file(&repo, "a.txt", "3", "fixup! change a");
+
+		let orig_head = get_head_repo(&repo).unwrap();
+
+		// the fixup now applies on top of `change a`
+		let res = autosquash_rebase(repo_path, base).unwrap();
+
+		assert!(res.conflict);
+		assert_eq!(
+			repo_state(repo_path).unwrap(),
+			RepoState::Rebase(Some(crate::sync::RebaseProgress {
+				current: 2,
+				total: 3
+			}))
+		);
+
+		abort_pending_state(repo_path).unwrap();
+
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		assert_eq!(get_head_repo(&repo).unwrap(), orig_head);
+		assert_eq!(read(repo_path, "a.txt"), "3");
+
+		// and once more, resolving this time
+		let res = autosquash_rebase(repo_path, base).unwrap();
+		assert!(res.conflict);
+
+		fs::write(root.join("a.txt"), "resolved").unwrap();
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+		continue_pending_state(repo_path).unwrap();
+
+		// `change a again` conflicts with the fixed up commit next
+		assert!(matches!(
+			repo_state(repo_path).unwrap(),
+			RepoState::Rebase(_)
+		));
+
+		fs::write(root.join("a.txt"), "3").unwrap();
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+		continue_pending_state(repo_path).unwrap();
+
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		assert_eq!(
+			messages(repo_path, 3),
+			vec!["change a again", "change a", "base"]
+		);
+		assert_eq!(read(repo_path, "a.txt"), "3");
+	}
+
+	#[test]
+	fn test_rebase_dirty() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let base = write_commit_file(&repo, "a.txt", "0", "base");
+		write_commit_file(&repo, "a.txt", "1", "change a");
+		fs::write(root.join("a.txt"), "dirty").unwrap();
+
+		assert!(autosquash_rebase(repo_path, base).is_err());
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+	}
+}
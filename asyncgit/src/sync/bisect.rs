@@ -0,0 +1,555 @@
+//! bisecting the history for the commit that introduced a bug, like
+//! `git bisect`.
+//!
+//! marks and the original `HEAD` are kept in the files and refs git
+//! uses, so a bisect started here can be continued on the command
+//! line and the other way round
+
+use super::{utils, CommitId};
+use crate::error::{Error, Result};
+use git2::{
+	build::CheckoutBuilder, Oid, Repository, RepositoryState, Sort,
+	Status, StatusOptions,
+};
+use scopetime::scope_time;
+use std::{
+	collections::HashSet,
+	fs::{self, OpenOptions},
+	io::{self, ErrorKind, Write},
+	path::Path,
+};
+
+const START_FILE: &str = "BISECT_START";
+const LOG_FILE: &str = "BISECT_LOG";
+const TERMS_FILE: &str = "BISECT_TERMS";
+/// written by git, removed on reset along with ours
+const GIT_FILES: [&str; 4] = [
+	"BISECT_EXPECTED_REV",
+	"BISECT_ANCESTORS_OK",
+	"BISECT_NAMES",
+	"BISECT_RUN",
+];
+const REFS_PREFIX: &str = "refs/bisect/";
+
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BisectMark {
+	///
+	Good,
+	///
+	Bad,
+	/// can not be tested
+	Skip,
+}
+
+/// where a bisect stands
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BisectStep {
+	/// `HEAD` is to be tested and marked, `left` commits remain
+	/// after it, roughly `steps` more marks are needed
+	Test {
+		///
+		left: usize,
+		///
+		steps: usize,
+	},
+	/// a good or the bad commit is not marked yet
+	Incomplete,
+	/// first bad commit
+	Found(CommitId),
+	/// only skipped commits are left, the first bad one is
+	/// among them
+	Inconclusive(Vec<CommitId>),
+}
+
+/// names of the marks, git allows `old`/`new` and custom ones
+struct Terms {
+	bad: String,
+	good: String,
+}
+
+impl Terms {
+	fn read(repo: &Repository) -> Self {
+		let terms = fs::read_to_string(repo.path().join(TERMS_FILE))
+			.unwrap_or_default();
+		let mut lines = terms.lines().map(str::trim);
+
+		match (lines.next(), lines.next()) {
+			(Some(bad), Some(good))
+				if !bad.is_empty() && !good.is_empty() =>
+			{
+				Self {
+					bad: bad.to_string(),
+					good: good.to_string(),
+				}
+			}
+			_ => Self {
+				bad: String::from("bad"),
+				good: String::from("good"),
+			},
+		}
+	}
+
+	fn name(&self, mark: BisectMark) -> &str {
+		match mark {
+			BisectMark::Good => &self.good,
+			BisectMark::Bad => &self.bad,
+			BisectMark::Skip => "skip",
+		}
+	}
+}
+
+struct Marks {
+	bad: Option<Oid>,
+	good: Vec<Oid>,
+	skip: HashSet<Oid>,
+}
+
+/// `None` if no bisect is in progress
+pub fn bisect_state(repo_path: &str) -> Result<Option<BisectStep>> {
+	scope_time!("bisect_state");
+
+	let repo = utils::repo(repo_path)?;
+
+	if !is_bisecting(&repo) {
+		return Ok(None);
+	}
+
+	Ok(Some(next_step(&repo)?.0))
+}
+
+/// starts bisecting between `bad` and `good` and checks out the
+/// first commit to test. needs a clean worktree, untracked files
+/// are fine
+pub fn bisect_start(
+	repo_path: &str,
+	bad: CommitId,
+	good: CommitId,
+) -> Result<BisectStep> {
+	scope_time!("bisect_start");
+
+	let repo = utils::repo(repo_path)?;
+
+	if is_bisecting(&repo) {
+		return Err(Error::Generic(String::from(
+			"a bisect is in progress already",
+		)));
+	}
+	ensure_clean(&repo)?;
+
+	let head = repo.head()?;
+	let original = if head.is_branch() {
+		head.shorthand().map(String::from)
+	} else {
+		None
+	};
+	let original = match original {
+		Some(branch) => branch,
+		None => utils::get_head_repo(&repo)?.to_string(),
+	};
+
+	let git_dir = repo.path();
+	fs::write(git_dir.join(START_FILE), format!("{}\n", original))?;
+	fs::write(git_dir.join(TERMS_FILE), "bad\ngood\n")?;
+	fs::write(git_dir.join(LOG_FILE), "git bisect start\n")?;
+
+	let terms = Terms::read(&repo);
+	mark(&repo, &terms, BisectMark::Bad, bad.into())?;
+	mark(&repo, &terms, BisectMark::Good, good.into())?;
+
+	checkout_next(&repo)
+}
+
+/// marks `HEAD` and checks out the next commit to test
+pub fn bisect_mark(
+	repo_path: &str,
+	mark_as: BisectMark,
+) -> Result<BisectStep> {
+	scope_time!("bisect_mark");
+
+	let repo = utils::repo(repo_path)?;
+
+	if !is_bisecting(&repo) {
+		return Err(Error::Generic(String::from(
+			"no bisect in progress",
+		)));
+	}
+
+	let head = utils::get_head_repo(&repo)?;
+	mark(&repo, &Terms::read(&repo), mark_as, head.into())?;
+
+	checkout_next(&repo)
+}
+
+/// ends the bisect and checks out what was checked out before
+/// it started
+pub fn bisect_reset(repo_path: &str) -> Result<()> {
+	scope_time!("bisect_reset");
+
+	let repo = utils::repo(repo_path)?;
+	let git_dir = repo.path();
+
+	let original = fs::read_to_string(git_dir.join(START_FILE))
+		.map_err(|_| {
+			Error::Generic(String::from("no bisect in progress"))
+		})?;
+	let original = original.trim();
+
+	let branch_ref = format!("refs/heads/{}", original);
+	if let Ok(branch) = repo.find_reference(&branch_ref) {
+		repo.checkout_tree(
+			&branch.peel(git2::ObjectType::Commit)?,
+			Some(CheckoutBuilder::new().safe()),
+		)?;
+		repo.set_head(&branch_ref)?;
+	} else {
+		let id = Oid::from_str(original)?;
+		checkout_detached(&repo, id)?;
+	}
+
+	let refs = repo
+		.references_glob(&format!("{}*", REFS_PREFIX))?
+		.filter_map(|reference| {
+			reference.ok()?.name().map(String::from)
+		})
+		.collect::<Vec<_>>();
+	for name in refs {
+		repo.find_reference(&name)?.delete()?;
+	}
+
+	for file in [START_FILE, LOG_FILE, TERMS_FILE]
+		.iter()
+		.chain(GIT_FILES.iter())
+	{
+		remove_file_if_exists(&git_dir.join(file))?;
+	}
+
+	Ok(())
+}
+
+fn is_bisecting(repo: &Repository) -> bool {
+	repo.path().join(START_FILE).exists()
+}
+
+fn ensure_clean(repo: &Repository) -> Result<()> {
+	if repo.state() != RepositoryState::Clean {
+		return Err(Error::Generic(String::from(
+			"finish the operation in progress first",
+		)));
+	}
+
+	let mut options = StatusOptions::new();
+	options.include_untracked(false).include_ignored(false);
+
+	let dirty = repo
+		.statuses(Some(&mut options))?
+		.iter()
+		.any(|entry| entry.status() != Status::CURRENT);
+
+	if dirty {
+		return Err(Error::UncommittedChanges);
+	}
+
+	Ok(())
+}
+
+/// stores the mark as a ref and logs it the way git does
+fn mark(
+	repo: &Repository,
+	terms: &Terms,
+	mark_as: BisectMark,
+	id: Oid,
+) -> Result<()> {
+	let name = terms.name(mark_as);
+	let refname = if mark_as == BisectMark::Bad {
+		format!("{}{}", REFS_PREFIX, name)
+	} else {
+		format!("{}{}-{}", REFS_PREFIX, name, id)
+	};
+	repo.reference(&refname, id, true, "bisect")?;
+
+	let summary = repo
+		.find_commit(id)?
+		.summary()
+		.unwrap_or_default()
+		.to_string();
+
+	let mut log = OpenOptions::new()
+		.create(true)
+		.append(true)
+		.open(repo.path().join(LOG_FILE))?;
+	writeln!(log, "# {}: [{}] {}", name, id, summary)?;
+	writeln!(log, "git bisect {} {}", name, id)?;
+
+	Ok(())
+}
+
+fn read_marks(repo: &Repository, terms: &Terms) -> Result<Marks> {
+	let bad = repo
+		.find_reference(&format!("{}{}", REFS_PREFIX, terms.bad))
+		.ok()
+		.and_then(|reference| reference.target());
+
+	let targets = |prefix: &str| -> Result<Vec<Oid>> {
+		Ok(repo
+			.references_glob(&format!("{}{}-*", REFS_PREFIX, prefix))?
+			.filter_map(|reference| reference.ok()?.target())
+			.collect())
+	};
+
+	Ok(Marks {
+		bad,
+		good: targets(&terms.good)?,
+		skip: targets("skip")?.into_iter().collect(),
+	})
+}
+
+/// the step and, while testing, the commit to check out next
+fn next_step(repo: &Repository) -> Result<(BisectStep, Option<Oid>)> {
+	let marks = read_marks(repo, &Terms::read(repo))?;
+
+	let bad = match marks.bad {
+		Some(bad) if !marks.good.is_empty() => bad,
+		_ => return Ok((BisectStep::Incomplete, None)),
+	};
+
+	// newest first, the bad commit itself is always a candidate
+	let mut walk = repo.revwalk()?;
+	walk.set_sorting(Sort::TOPOLOGICAL)?;
+	walk.push(bad)?;
+	for good in &marks.good {
+		walk.hide(*good)?;
+	}
+
+	let mut testable = Vec::new();
+	let mut skipped = Vec::new();
+	for id in walk {
+		let id = id?;
+		if id == bad {
+			continue;
+		}
+		if marks.skip.contains(&id) {
+			skipped.push(id);
+		} else {
+			testable.push(id);
+		}
+	}
+
+	if testable.is_empty() {
+		if skipped.is_empty() {
+			return Ok((BisectStep::Found(bad.into()), None));
+		}
+
+		skipped.insert(0, bad);
+		return Ok((
+			BisectStep::Inconclusive(
+				skipped.into_iter().map(CommitId::from).collect(),
+			),
+			None,
+		));
+	}
+
+	let next = testable[testable.len() / 2];
+	let left = testable.len() - 1;
+
+	Ok((
+		BisectStep::Test {
+			left,
+			steps: steps_for(left),
+		},
+		Some(next),
+	))
+}
+
+/// marks needed to narrow `left` commits down to one
+const fn steps_for(left: usize) -> usize {
+	let mut steps = 0;
+	let mut remaining = left;
+	while remaining > 0 {
+		remaining /= 2;
+		steps += 1;
+	}
+	steps
+}
+
+fn checkout_next(repo: &Repository) -> Result<BisectStep> {
+	let (step, next) = next_step(repo)?;
+
+	if let Some(next) = next {
+		checkout_detached(repo, next)?;
+	}
+
+	Ok(step)
+}
+
+fn checkout_detached(repo: &Repository, id: Oid) -> Result<()> {
+	let commit = repo.find_commit(id)?;
+	repo.checkout_tree(
+		commit.as_object(),
+		Some(CheckoutBuilder::new().safe()),
+	)?;
+	repo.set_head_detached(id)?;
+
+	Ok(())
+}
+
+fn remove_file_if_exists(path: &Path) -> io::Result<()> {
+	match fs::remove_file(path) {
+		Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+		_ => Ok(()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		get_head,
+		tests::{repo_init, write_commit_file},
+		utils::{get_head_refname, repo_write_file},
+	};
+	use pretty_assertions::assert_eq;
+
+	/// `count` commits on top of the initial one, oldest first
+	fn linear_history(
+		repo: &Repository,
+		count: usize,
+	) -> Vec<CommitId> {
+		(0..count)
+			.map(|idx| {
+				write_commit_file(
+					repo,
+					"a.txt",
+					&idx.to_string(),
+					&format!("c{}", idx),
+				)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_bisect_finds_first_bad() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let commits = linear_history(&repo, 8);
+		let first_bad = commits[5];
+
+		assert_eq!(bisect_state(repo_path).unwrap(), None);
+
+		let mut step =
+			bisect_start(repo_path, commits[7], commits[0]).unwrap();
+		assert_eq!(step, BisectStep::Test { left: 5, steps: 3 });
+
+		let mut marks = 0;
+		while let BisectStep::Test { .. } = step {
+			let head = get_head(repo_path).unwrap();
+			let position =
+				commits.iter().position(|id| *id == head).unwrap();
+			let mark_as = if position >= 5 {
+				BisectMark::Bad
+			} else {
+				BisectMark::Good
+			};
+
+			step = bisect_mark(repo_path, mark_as).unwrap();
+			marks += 1;
+		}
+
+		assert_eq!(step, BisectStep::Found(first_bad));
+		assert!(marks <= 3);
+		assert_eq!(
+			bisect_state(repo_path).unwrap(),
+			Some(BisectStep::Found(first_bad))
+		);
+
+		bisect_reset(repo_path).unwrap();
+
+		assert_eq!(bisect_state(repo_path).unwrap(), None);
+		assert_eq!(get_head(repo_path).unwrap(), commits[7]);
+		assert_eq!(
+			get_head_refname(&repo).unwrap(),
+			"refs/heads/master"
+		);
+		assert!(repo
+			.references_glob("refs/bisect/*")
+			.unwrap()
+			.next()
+			.is_none());
+		assert!(!repo.path().join(LOG_FILE).exists());
+	}
+
+	#[test]
+	fn test_bisect_skip() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let commits = linear_history(&repo, 3);
+
+		let step =
+			bisect_start(repo_path, commits[2], commits[0]).unwrap();
+		assert_eq!(step, BisectStep::Test { left: 0, steps: 0 });
+		assert_eq!(get_head(repo_path).unwrap(), commits[1]);
+
+		assert_eq!(
+			bisect_mark(repo_path, BisectMark::Skip).unwrap(),
+			BisectStep::Inconclusive(vec![commits[2], commits[1]])
+		);
+	}
+
+	#[test]
+	fn test_bisect_needs_clean_worktree() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let commits = linear_history(&repo, 3);
+		repo_write_file(&repo, "a.txt", "changed").unwrap();
+
+		assert!(matches!(
+			bisect_start(repo_path, commits[2], commits[0]),
+			Err(Error::UncommittedChanges)
+		));
+		assert_eq!(bisect_state(repo_path).unwrap(), None);
+	}
+
+	#[test]
+	fn test_bisect_reads_git_state() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let commits = linear_history(&repo, 4);
+
+		// as left behind by `git bisect start` with custom terms
+		fs::write(repo.path().join(START_FILE), "master\n").unwrap();
+		fs::write(repo.path().join(TERMS_FILE), "new\nold\n")
+			.unwrap();
+		assert_eq!(
+			bisect_state(repo_path).unwrap(),
+			Some(BisectStep::Incomplete)
+		);
+
+		repo.reference(
+			"refs/bisect/new",
+			commits[3].into(),
+			true,
+			"",
+		)
+		.unwrap();
+		repo.reference(
+			&format!("refs/bisect/old-{}", commits[0].to_string()),
+			commits[0].into(),
+			true,
+			"",
+		)
+		.unwrap();
+
+		assert_eq!(
+			bisect_state(repo_path).unwrap(),
+			Some(BisectStep::Test { left: 1, steps: 1 })
+		);
+	}
+}
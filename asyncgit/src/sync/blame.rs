@@ -5,8 +5,10 @@ use crate::{
 	error::{Error, Result},
 	sync::get_commits_info,
 };
+use git2::{BlameOptions, Patch};
 use scopetime::scope_time;
 use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 
@@ -39,16 +41,20 @@ pub struct FileBlame {
 	pub lines: Vec<(Option<BlameHunk>, String)>,
 }
 
-///
+/// blames `file_path` as of `commit`, `None` for HEAD
 pub fn blame_file(
 	repo_path: &str,
 	file_path: &str,
+	commit: Option<CommitId>,
 ) -> Result<FileBlame> {
 	scope_time!("blame_file");
 
 	let repo = utils::repo(repo_path)?;
 
-	let commit_id = utils::get_head_repo(&repo)?;
+	let commit_id = match commit {
+		Some(commit) => commit,
+		None => utils::get_head_repo(&repo)?,
+	};
 
 	let spec = format!("{}:{}", commit_id.to_string(), file_path);
 
@@ -59,7 +65,10 @@ pub fn blame_file(
 		return Err(Error::NoBlameOnBinaryFile);
 	}
 
-	let blame = repo.blame_file(Path::new(file_path), None)?;
+	let blame = repo.blame_file(
+		Path::new(file_path),
+		Some(BlameOptions::new().newest_commit(commit_id.into())),
+	)?;
 
 	let reader = BufReader::new(blob.content());
 
@@ -122,6 +131,130 @@ pub fn blame_file(
 	Ok(file_blame)
 }
 
+/// a line of a diff (1-based) and the version of the file it is in,
+/// see `blame_target`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlameLine {
+	/// line of the working copy
+	WorkDir(usize),
+	/// line of the staged version
+	Index(usize),
+	/// line of the file in HEAD
+	Head(usize),
+	/// line of the file in a commit
+	Commit(CommitId, usize),
+	/// line of the file in the (first) parent of a commit,
+	/// removed by it
+	Parent(CommitId, usize),
+}
+
+/// revision to blame `file_path` at for `line` and the line there.
+/// lines of the working copy or the index are looked up in HEAD,
+/// `None` if the line is not committed yet
+pub fn blame_target(
+	repo_path: &str,
+	file_path: &str,
+	line: BlameLine,
+) -> Result<Option<(CommitId, usize)>> {
+	scope_time!("blame_target");
+
+	let repo = utils::repo(repo_path)?;
+
+	let uncommitted = match line {
+		BlameLine::Commit(id, line) => return Ok(Some((id, line))),
+		BlameLine::Parent(id, line) => {
+			let commit = repo.find_commit(id.into())?;
+			return Ok(commit
+				.parent_ids()
+				.next()
+				.map(|parent| (CommitId::new(parent), line)));
+		}
+		BlameLine::WorkDir(line) => {
+			let path = utils::work_dir(&repo)?.join(file_path);
+			(std::fs::read(path)?, line)
+		}
+		BlameLine::Index(line) => {
+			let index = repo.index()?;
+			match index.get_path(Path::new(file_path), 0) {
+				Some(entry) => (
+					repo.find_blob(entry.id)?.content().to_vec(),
+					line,
+				),
+				None => return Ok(None),
+			}
+		}
+		BlameLine::Head(line) => {
+			if utils::is_head_unborn(&repo)? {
+				return Ok(None);
+			}
+			return Ok(Some((utils::get_head_repo(&repo)?, line)));
+		}
+	};
+
+	if utils::is_head_unborn(&repo)? {
+		return Ok(None);
+	}
+
+	let head = utils::get_head_repo(&repo)?;
+	let tree = repo.find_commit(head.into())?.tree()?;
+	let head_content = match tree.get_path(Path::new(file_path)) {
+		Ok(entry) => entry.to_object(&repo)?.peel_to_blob()?,
+		Err(_) => return Ok(None),
+	};
+
+	let (content, line) = uncommitted;
+
+	Ok(old_line(head_content.content(), &content, line)?
+		.map(|line| (head, line)))
+}
+
+/// line of `old` that line `line` of `new` is unchanged from
+fn old_line(
+	old: &[u8],
+	new: &[u8],
+	line: usize,
+) -> Result<Option<usize>> {
+	let lines = old
+		.split(|b| *b == b'\n')
+		.count()
+		.max(new.split(|b| *b == b'\n').count());
+
+	// all of the file as context
+	let mut opt = git2::DiffOptions::new();
+	opt.context_lines(
+		u32::try_from(lines).unwrap_or(u32::MAX).saturating_add(1),
+	);
+
+	let patch =
+		Patch::from_buffers(old, None, new, None, Some(&mut opt))?;
+	if patch.num_hunks() == 0 {
+		return Ok(Some(line));
+	}
+
+	for hunk_idx in 0..patch.num_hunks() {
+		for line_idx in 0..patch.num_lines_in_hunk(hunk_idx)? {
+			let diff_line = patch.line_in_hunk(hunk_idx, line_idx)?;
+			if diff_line.new_lineno().map(|lineno| lineno as usize)
+				== Some(line)
+			{
+				return Ok(
+					if diff_line.origin_value()
+						== git2::DiffLineType::Context
+					{
+						diff_line
+							.old_lineno()
+							.map(|lineno| lineno as usize)
+					} else {
+						None
+					},
+				);
+			}
+		}
+	}
+
+	Ok(None)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -142,15 +275,18 @@ mod tests {
 		let root = repo.path().parent().unwrap();
 		let repo_path = root.as_os_str().to_str().unwrap();
 
-		assert!(matches!(blame_file(&repo_path, "foo"), Err(_)));
+		assert!(matches!(
+			blame_file(&repo_path, "foo", None),
+			Err(_)
+		));
 
 		File::create(&root.join(file_path))?
 			.write_all(b"line 1\n")?;
 
 		stage_add_file(repo_path, file_path)?;
-		commit(repo_path, "first commit")?;
+		commit(repo_path, "first commit", false)?;
 
-		let blame = blame_file(&repo_path, "foo")?;
+		let blame = blame_file(&repo_path, "foo", None)?;
 
 		assert!(matches!(
 			blame.lines.as_slice(),
@@ -172,9 +308,9 @@ mod tests {
 		file.write(b"line 2\n")?;
 
 		stage_add_file(repo_path, file_path)?;
-		commit(repo_path, "second commit")?;
+		commit(repo_path, "second commit", false)?;
 
-		let blame = blame_file(&repo_path, "foo")?;
+		let blame = blame_file(&repo_path, "foo", None)?;
 
 		assert!(matches!(
 			blame.lines.as_slice(),
@@ -201,17 +337,77 @@ mod tests {
 
 		file.write(b"line 3\n")?;
 
-		let blame = blame_file(&repo_path, "foo")?;
+		let blame = blame_file(&repo_path, "foo", None)?;
 
 		assert_eq!(blame.lines.len(), 2);
 
 		stage_add_file(repo_path, file_path)?;
-		commit(repo_path, "third commit")?;
+		commit(repo_path, "third commit", false)?;
 
-		let blame = blame_file(&repo_path, "foo")?;
+		let blame = blame_file(&repo_path, "foo", None)?;
 
 		assert_eq!(blame.lines.len(), 3);
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_blame_target() -> Result<()> {
+		use crate::sync::tests::write_commit_file;
+
+		let (_td, repo) = repo_init_empty()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		assert_eq!(
+			blame_target(repo_path, "foo", BlameLine::Head(1))?,
+			None
+		);
+
+		let first = write_commit_file(&repo, "foo", "a\nb\n", "c1");
+		let second =
+			write_commit_file(&repo, "foo", "a\nb\nc\n", "c2");
+
+		// "x" is new, "a" moved down a line
+		File::create(root.join("foo"))?.write_all(b"x\na\nb\nc\n")?;
+
+		assert_eq!(
+			blame_target(repo_path, "foo", BlameLine::WorkDir(1))?,
+			None
+		);
+		assert_eq!(
+			blame_target(repo_path, "foo", BlameLine::WorkDir(2))?,
+			Some((second, 1))
+		);
+		assert_eq!(
+			blame_target(repo_path, "foo", BlameLine::WorkDir(4))?,
+			Some((second, 3))
+		);
+		assert_eq!(
+			blame_target(repo_path, "foo", BlameLine::Index(3))?,
+			Some((second, 3))
+		);
+		assert_eq!(
+			blame_target(
+				repo_path,
+				"foo",
+				BlameLine::Parent(second, 2)
+			)?,
+			Some((first, 2))
+		);
+		assert_eq!(
+			blame_target(
+				repo_path,
+				"foo",
+				BlameLine::Parent(first, 1)
+			)?,
+			None
+		);
+
+		let blame = blame_file(repo_path, "foo", Some(first))?;
+		assert_eq!(blame.commit_id, first);
+		assert_eq!(blame.lines.len(), 2);
+
+		Ok(())
+	}
 }
@@ -0,0 +1,221 @@
+//! base branch a feature branch is compared against, the choice is
+//! kept in `gitui.baseBranch`
+
+use super::get_branch_name_repo;
+use crate::{
+	error::Result,
+	sync::{
+		config::{get_config_string_repo, get_config_strings},
+		set_string_scoped, utils, CommitId, ConfigScope,
+	},
+};
+use git2::{BranchType, Repository};
+use scopetime::scope_time;
+
+const BASE_BRANCH_CONFIG: &str = "gitui.baseBranch";
+/// multi-valued, replaces `DEFAULT_CANDIDATES` if set
+const CANDIDATES_CONFIG: &str = "gitui.baseBranchCandidates";
+const DEFAULT_CANDIDATES: &[&str] = &["main", "master", "develop"];
+
+/// branch to compare the current one against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaseBranch {
+	/// local (`main`) or remote tracking (`origin/main`) branch
+	pub name: String,
+	///
+	pub tip: CommitId,
+	/// the chosen base if it does not exist (anymore), `name` is
+	/// the fallback then
+	pub missing: Option<String>,
+}
+
+/// tip of the local or remote tracking branch `name`
+fn branch_tip(repo: &Repository, name: &str) -> Option<CommitId> {
+	repo.find_branch(name, BranchType::Local)
+		.or_else(|_| repo.find_branch(name, BranchType::Remote))
+		.ok()?
+		.get()
+		.peel_to_commit()
+		.ok()
+		.map(|commit| commit.id().into())
+}
+
+/// existing candidates (or main, master and develop) followed by the
+/// upstream of the current branch, which is never part of it
+fn default_candidates(
+	repo_path: &str,
+	repo: &Repository,
+	head: Option<&str>,
+) -> Result<Vec<String>> {
+	let mut names = get_config_strings(repo_path, CANDIDATES_CONFIG)?;
+	if names.is_empty() {
+		names = DEFAULT_CANDIDATES
+			.iter()
+			.map(|name| (*name).to_string())
+			.collect();
+	}
+
+	if let Some(upstream) = head.and_then(|head| {
+		let branch =
+			repo.find_branch(head, BranchType::Local).ok()?;
+		let upstream = branch.upstream().ok()?;
+		upstream.name().ok().flatten().map(String::from)
+	}) {
+		names.push(upstream);
+	}
+
+	let mut res: Vec<String> = Vec::new();
+	for name in names {
+		if Some(name.as_str()) != head
+			&& !res.contains(&name)
+			&& branch_tip(repo, &name).is_some()
+		{
+			res.push(name);
+		}
+	}
+
+	Ok(res)
+}
+
+/// branches to pick the base from: the default candidates first,
+/// then every other local branch
+pub fn base_branch_candidates(
+	repo_path: &str,
+) -> Result<Vec<String>> {
+	scope_time!("base_branch_candidates");
+
+	let repo = utils::repo(repo_path)?;
+	let head = get_branch_name_repo(&repo).ok();
+	let mut res =
+		default_candidates(repo_path, &repo, head.as_deref())?;
+
+	for branch in repo.branches(Some(BranchType::Local))? {
+		if let Some(name) = branch?.0.name()? {
+			if Some(name) != head.as_deref()
+				&& !res.iter().any(|known| known == name)
+			{
+				res.push(name.to_string());
+			}
+		}
+	}
+
+	Ok(res)
+}
+
+/// the chosen base or the first default candidate, `None` if there is
+/// neither
+pub fn get_base_branch(
+	repo_path: &str,
+) -> Result<Option<BaseBranch>> {
+	scope_time!("get_base_branch");
+
+	let repo = utils::repo(repo_path)?;
+	let chosen = get_config_string_repo(&repo, BASE_BRANCH_CONFIG)?;
+
+	if let Some(name) = chosen.as_ref() {
+		if let Some(tip) = branch_tip(&repo, name) {
+			return Ok(Some(BaseBranch {
+				name: name.clone(),
+				tip,
+				missing: None,
+			}));
+		}
+	}
+
+	let head = get_branch_name_repo(&repo).ok();
+	let fallback =
+		default_candidates(repo_path, &repo, head.as_deref())?
+			.into_iter()
+			.next();
+
+	Ok(fallback.and_then(|name| {
+		branch_tip(&repo, &name).map(|tip| BaseBranch {
+			name,
+			tip,
+			missing: chosen,
+		})
+	}))
+}
+
+/// remembers `name` as the base of this repo
+pub fn set_base_branch(repo_path: &str, name: &str) -> Result<()> {
+	scope_time!("set_base_branch");
+
+	set_string_scoped(
+		repo_path,
+		BASE_BRANCH_CONFIG,
+		name,
+		ConfigScope::Local,
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		add_config_string, create_branch, delete_branch,
+		tests::repo_init,
+	};
+
+	#[test]
+	fn test_default_base() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		// the current branch is no base
+		assert_eq!(get_base_branch(repo_path).unwrap(), None);
+
+		create_branch(repo_path, "develop").unwrap();
+		create_branch(repo_path, "feature").unwrap();
+
+		let base = get_base_branch(repo_path).unwrap().unwrap();
+		assert_eq!(base.name, "master");
+		assert_eq!(base.missing, None);
+
+		assert_eq!(
+			base_branch_candidates(repo_path).unwrap(),
+			vec![String::from("master"), String::from("develop")]
+		);
+	}
+
+	#[test]
+	fn test_configured_candidates() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		create_branch(repo_path, "trunk").unwrap();
+		create_branch(repo_path, "feature").unwrap();
+		add_config_string(
+			repo_path,
+			CANDIDATES_CONFIG,
+			"trunk",
+			ConfigScope::Local,
+		)
+		.unwrap();
+
+		let base = get_base_branch(repo_path).unwrap().unwrap();
+		assert_eq!(base.name, "trunk");
+	}
+
+	#[test]
+	fn test_chosen_base_fallback() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		create_branch(repo_path, "topic").unwrap();
+		create_branch(repo_path, "feature").unwrap();
+		set_base_branch(repo_path, "topic").unwrap();
+
+		let base = get_base_branch(repo_path).unwrap().unwrap();
+		assert_eq!(base.name, "topic");
+
+		delete_branch(repo_path, "refs/heads/topic").unwrap();
+
+		let base = get_base_branch(repo_path).unwrap().unwrap();
+		assert_eq!(base.name, "master");
+		assert_eq!(base.missing, Some(String::from("topic")));
+	}
+}
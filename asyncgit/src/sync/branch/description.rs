@@ -0,0 +1,150 @@
+//! branch descriptions (`branch.<name>.description`)
+
+use crate::{
+	error::Result,
+	sync::{config::get_config_string_repo, utils},
+};
+use git2::ErrorCode;
+use scopetime::scope_time;
+use std::collections::HashMap;
+
+fn description_key(branch: &str) -> String {
+	format!("branch.{}.description", branch)
+}
+
+/// description of the local `branch`, `None` if it has none
+pub fn branch_get_description(
+	repo_path: &str,
+	branch: &str,
+) -> Result<Option<String>> {
+	scope_time!("branch_get_description");
+
+	let repo = utils::repo(repo_path)?;
+	get_config_string_repo(&repo, &description_key(branch))
+}
+
+/// descriptions of all local branches by branch name
+pub fn get_branch_descriptions(
+	repo_path: &str,
+) -> Result<HashMap<String, String>> {
+	scope_time!("get_branch_descriptions");
+
+	let repo = utils::repo(repo_path)?;
+	let config = repo.config()?;
+
+	let mut res = HashMap::new();
+	let entries =
+		config.entries(Some(r"^branch\..*\.description$"))?;
+	for entry in &entries {
+		let entry = entry?;
+		if let (Some(name), Some(value)) =
+			(entry.name(), entry.value())
+		{
+			if let Some(branch) = name
+				.strip_prefix("branch.")
+				.and_then(|name| name.strip_suffix(".description"))
+			{
+				res.insert(branch.to_string(), value.to_string());
+			}
+		}
+	}
+
+	Ok(res)
+}
+
+/// sets the description of the local `branch` verbatim,
+/// an empty `text` removes it
+pub fn branch_set_description(
+	repo_path: &str,
+	branch: &str,
+	text: &str,
+) -> Result<()> {
+	scope_time!("branch_set_description");
+
+	let repo = utils::repo(repo_path)?;
+	let mut config = repo.config()?;
+	let key = description_key(branch);
+
+	if text.is_empty() {
+		if let Err(e) = config.remove(&key) {
+			if e.code() != ErrorCode::NotFound {
+				return Err(e.into());
+			}
+		}
+	} else {
+		config.set_str(&key, text)?;
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::sync::tests::repo_init;
+
+	#[test]
+	fn test_description_roundtrip() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		assert_eq!(
+			branch_get_description(repo_path, "master").unwrap(),
+			None
+		);
+
+		let text = "first \"line\"\n\n\tsecond paragraph\\\n\n";
+		branch_set_description(repo_path, "master", text).unwrap();
+
+		assert_eq!(
+			branch_get_description(repo_path, "master")
+				.unwrap()
+				.as_deref(),
+			Some(text)
+		);
+	}
+
+	#[test]
+	fn test_descriptions() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		assert!(get_branch_descriptions(repo_path)
+			.unwrap()
+			.is_empty());
+
+		branch_set_description(repo_path, "master", "a\nb").unwrap();
+		branch_set_description(repo_path, "feature/x.y", "c")
+			.unwrap();
+
+		let res = get_branch_descriptions(repo_path).unwrap();
+		assert_eq!(res.len(), 2);
+		assert_eq!(res["master"], "a\nb");
+		assert_eq!(res["feature/x.y"], "c");
+	}
+
+	#[test]
+	fn test_description_remove() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		// removing a missing description is fine
+		branch_set_description(repo_path, "master", "").unwrap();
+
+		branch_set_description(repo_path, "master", "desc").unwrap();
+		branch_set_description(repo_path, "master", "").unwrap();
+
+		assert_eq!(
+			branch_get_description(repo_path, "master").unwrap(),
+			None
+		);
+		assert!(repo
+			.config()
+			.unwrap()
+			.get_entry("branch.master.description")
+			.is_err());
+	}
+}
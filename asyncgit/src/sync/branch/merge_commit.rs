@@ -99,7 +99,7 @@ mod test {
 	use super::*;
 	use crate::sync::{
 		branch_compare_upstream,
-		remotes::{fetch, push::push},
+		remotes::{fetch_branch, push::push},
 		tests::{
 			debug_cmd_print, get_commit_ids, repo_clone,
 			repo_init_bare, write_commit_file, write_commit_file_at,
@@ -157,7 +157,8 @@ mod test {
 		.is_err());
 
 		//lets fetch from origin
-		let bytes = fetch(clone2_dir, "master", None, None).unwrap();
+		let bytes =
+			fetch_branch(clone2_dir, "master", None, None).unwrap();
 		assert!(bytes > 0);
 
 		//we should be one commit behind
@@ -238,7 +239,7 @@ mod test {
 			"commit2",
 		);
 
-		let bytes = fetch(
+		let bytes = fetch_branch(
 			clone2_dir.path().to_str().unwrap(),
 			"master",
 			None,
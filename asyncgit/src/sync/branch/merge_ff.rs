@@ -53,7 +53,7 @@ pub fn branch_merge_upstream_fastforward(
 pub mod test {
 	use super::*;
 	use crate::sync::{
-		remotes::{fetch, push::push},
+		remotes::{fetch_branch, push::push},
 		tests::{
 			debug_cmd_print, get_commit_ids, repo_clone,
 			repo_init_bare, write_commit_file,
@@ -112,7 +112,7 @@ pub mod test {
 
 		// clone1 again
 
-		let bytes = fetch(
+		let bytes = fetch_branch(
 			clone1_dir.path().to_str().unwrap(),
 			"master",
 			None,
@@ -121,7 +121,7 @@ pub mod test {
 		.unwrap();
 		assert!(bytes > 0);
 
-		let bytes = fetch(
+		let bytes = fetch_branch(
 			clone1_dir.path().to_str().unwrap(),
 			"master",
 			None,
@@ -35,7 +35,7 @@ mod test {
 	use super::*;
 	use crate::sync::{
 		branch_compare_upstream, get_commits_info,
-		remotes::{fetch, push::push},
+		remotes::{fetch_branch, push::push},
 		tests::{
 			debug_cmd_print, get_commit_ids, repo_clone,
 			repo_init_bare, write_commit_file, write_commit_file_at,
@@ -122,7 +122,8 @@ mod test {
 		assert_eq!(clone1.head_detached().unwrap(), false);
 
 		//lets fetch from origin
-		let bytes = fetch(clone1_dir, "master", None, None).unwrap();
+		let bytes =
+			fetch_branch(clone1_dir, "master", None, None).unwrap();
 		assert!(bytes > 0);
 
 		//we should be one commit behind
@@ -220,7 +221,7 @@ mod test {
 
 		//lets fetch from origin
 
-		fetch(clone1_dir, "master", None, None).unwrap();
+		fetch_branch(clone1_dir, "master", None, None).unwrap();
 
 		merge_upstream_rebase(clone1_dir, "master").unwrap();
 
@@ -286,7 +287,8 @@ mod test {
 		let _commit3 =
 			write_commit_file(&clone1, "test2.txt", "foo", "commit3");
 
-		let bytes = fetch(clone1_dir, "master", None, None).unwrap();
+		let bytes =
+			fetch_branch(clone1_dir, "master", None, None).unwrap();
 		assert!(bytes > 0);
 
 		assert_eq!(
@@ -0,0 +1,85 @@
+//! which branch tips are merged into a base commit
+
+use crate::{
+	error::Result,
+	sync::{utils, CommitId},
+};
+use git2::Oid;
+use scopetime::scope_time;
+use std::collections::HashSet;
+
+/// the `tips` that are ancestors of (or equal to) `base`.
+/// a single walk from `base` marks off the tips it passes instead of
+/// a merge base lookup per tip, it ends once all tips were seen
+pub fn merged_tips(
+	repo_path: &str,
+	base: CommitId,
+	tips: &[CommitId],
+) -> Result<HashSet<CommitId>> {
+	scope_time!("merged_tips");
+
+	let repo = utils::repo(repo_path)?;
+
+	let mut pending =
+		tips.iter().map(|id| Oid::from(*id)).collect::<HashSet<_>>();
+	let mut merged = HashSet::with_capacity(pending.len());
+
+	if pending.is_empty() {
+		return Ok(merged);
+	}
+
+	let mut walk = repo.revwalk()?;
+	walk.push(base.into())?;
+
+	for id in walk {
+		let id = id?;
+
+		if pending.remove(&id) {
+			merged.insert(CommitId::new(id));
+
+			if pending.is_empty() {
+				break;
+			}
+		}
+	}
+
+	Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		checkout_branch, create_branch,
+		tests::{repo_init, write_commit_file},
+	};
+
+	#[test]
+	fn test_merged_tips() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let c1 = write_commit_file(&repo, "a.txt", "a", "c1");
+
+		create_branch(repo_path, "feature").unwrap();
+		let feature = write_commit_file(&repo, "b.txt", "b", "c2");
+
+		checkout_branch(repo_path, "refs/heads/master").unwrap();
+		let c3 = write_commit_file(&repo, "c.txt", "c", "c3");
+
+		let merged =
+			merged_tips(repo_path, c3, &[c1, c3, feature]).unwrap();
+
+		assert_eq!(merged, vec![c1, c3].into_iter().collect());
+
+		// `feature` is merged into itself
+		let merged =
+			merged_tips(repo_path, feature, &[c1, c3, feature])
+				.unwrap();
+
+		assert_eq!(merged, vec![c1, feature].into_iter().collect());
+
+		assert!(merged_tips(repo_path, c3, &[]).unwrap().is_empty());
+	}
+}
@@ -1,22 +1,28 @@
 //! branch functions
 
+pub mod base;
+pub mod description;
 pub mod merge_commit;
 pub mod merge_ff;
 pub mod merge_rebase;
+pub mod merged;
+pub mod name;
+pub mod recent;
 pub mod rename;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use super::{
-	remotes::get_default_remote_in_repo, utils::bytes2string,
-};
+use super::utils::bytes2string;
 use crate::{
 	error::{Error, Result},
-	sync::{utils, CommitId},
+	sync::{
+		config::get_config_string_repo,
+		remotes::get_upstream_branch_in_repo, utils, CommitId,
+	},
 };
-use git2::{Branch, BranchType, Repository};
+use git2::{BranchType, Repository};
 use scopetime::scope_time;
-use utils::get_head_repo;
+use utils::{get_head_repo, is_head_unborn};
 
 /// returns the branch-name head is currently pointing to
 /// this might be expensive, see `cached::BranchName`
@@ -43,11 +49,15 @@ pub(crate) fn get_branch_name_repo(
 		}
 	}
 
-	Err(Error::NoHead)
+	// no commits yet: the branch only exists as HEAD's target
+	let head = repo.find_reference("HEAD")?;
+	head.symbolic_target()
+		.and_then(|target| target.strip_prefix("refs/heads/"))
+		.map_or(Err(Error::NoHead), |name| Ok(name.into()))
 }
 
 ///
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LocalBranch {
 	///
 	pub is_head: bool,
@@ -58,14 +68,14 @@ pub struct LocalBranch {
 }
 
 ///
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RemoteBranch {
 	///
 	pub has_tracking: bool,
 }
 
 ///
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum BranchDetails {
 	///
 	Local(LocalBranch),
@@ -74,16 +84,14 @@ pub enum BranchDetails {
 }
 
 ///
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BranchInfo {
 	///
 	pub name: String,
 	///
 	pub reference: String,
-	///
-	pub top_commit_message: String,
-	///
-	pub top_commit: CommitId,
+	/// `None` for a branch without any commits yet (unborn)
+	pub top_commit: Option<CommitId>,
 	///
 	pub details: BranchDetails,
 }
@@ -99,17 +107,10 @@ impl BranchInfo {
 	}
 }
 
-///
-pub fn validate_branch_name(name: &str) -> Result<bool> {
-	scope_time!("validate_branch_name");
-
-	let valid = Branch::name_is_valid(name)?;
-
-	Ok(valid)
-}
-
 /// returns a list of `BranchInfo` with a simple summary on each branch
-/// `local` filters for local branches otherwise remote branches will be returned
+/// `local` filters for local branches otherwise remote branches will be returned.
+/// tips are not peeled to commits here as that gets slow with many
+/// branches, use `get_commits_info` on `top_commit` for details
 pub fn get_branches_info(
 	repo_path: &str,
 	local: bool,
@@ -140,7 +141,16 @@ pub fn get_branches_info(
 		.branches(Some(filter))?
 		.map(|b| {
 			let branch = b?.0;
-			let top_commit = branch.get().peel_to_commit()?;
+			let top_commit = match branch.get().target() {
+				Some(id) => id,
+				None => branch.get().resolve()?.target().ok_or_else(
+					|| {
+						Error::Generic(String::from(
+							"no branch target",
+						))
+					},
+				)?,
+			};
 			let reference = bytes2string(branch.get().name_bytes())?;
 			let upstream = branch.upstream();
 
@@ -169,23 +179,39 @@ pub fn get_branches_info(
 			Ok(BranchInfo {
 				name: bytes2string(name_bytes)?,
 				reference,
-				top_commit_message: bytes2string(
-					top_commit.summary_bytes().unwrap_or_default(),
-				)?,
-				top_commit: top_commit.id().into(),
+				top_commit: Some(top_commit.into()),
 				details,
 			})
 		})
 		.filter_map(Result::ok)
 		.collect();
 
+	if local && is_head_unborn(&repo)? {
+		let head = repo.find_reference("HEAD")?;
+		if let Some(reference) = head.symbolic_target() {
+			branches_for_display.push(BranchInfo {
+				name: reference
+					.strip_prefix("refs/heads/")
+					.unwrap_or(reference)
+					.to_string(),
+				reference: reference.to_string(),
+				top_commit: None,
+				details: BranchDetails::Local(LocalBranch {
+					is_head: true,
+					has_upstream: false,
+					remote: None,
+				}),
+			});
+		}
+	}
+
 	branches_for_display.sort_by(|a, b| a.name.cmp(&b.name));
 
 	Ok(branches_for_display)
 }
 
 ///
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct BranchCompare {
 	///
 	pub ahead: usize,
@@ -193,10 +219,13 @@ pub struct BranchCompare {
 	pub behind: usize,
 }
 
-///
+/// sets `remote`/`remote_branch` as upstream of `branch_name`
+/// unless it already has one
 pub(crate) fn branch_set_upstream(
 	repo: &Repository,
 	branch_name: &str,
+	remote: &str,
+	remote_branch: &str,
 ) -> Result<()> {
 	scope_time!("branch_set_upstream");
 
@@ -204,8 +233,7 @@ pub(crate) fn branch_set_upstream(
 		repo.find_branch(branch_name, BranchType::Local)?;
 
 	if branch.upstream().is_err() {
-		let remote = get_default_remote_in_repo(repo)?;
-		let upstream_name = format!("{}/{}", remote, branch_name);
+		let upstream_name = format!("{}/{}", remote, remote_branch);
 		branch.set_upstream(Some(upstream_name.as_str()))?;
 	}
 
@@ -267,6 +295,71 @@ pub fn branch_compare_upstream(
 	Ok(BranchCompare { ahead, behind })
 }
 
+/// `branch_compare_upstream` of every local branch whose upstream
+/// exists, by branch name
+pub fn branches_compare_upstream(
+	repo_path: &str,
+) -> Result<HashMap<String, BranchCompare>> {
+	scope_time!("branches_compare_upstream");
+
+	let repo = utils::repo(repo_path)?;
+
+	let mut res = HashMap::new();
+	for branch in repo.branches(Some(BranchType::Local))? {
+		let branch = branch?.0;
+
+		let upstream = match branch.upstream() {
+			Ok(upstream) => upstream,
+			Err(_) => continue,
+		};
+
+		let (local, upstream) =
+			match (branch.get().target(), upstream.get().target()) {
+				(Some(local), Some(upstream)) => (local, upstream),
+				_ => continue,
+			};
+
+		let (ahead, behind) =
+			repo.graph_ahead_behind(local, upstream)?;
+
+		res.insert(
+			bytes2string(branch.name_bytes()?)?,
+			BranchCompare { ahead, behind },
+		);
+	}
+
+	Ok(res)
+}
+
+/// the upstream `branch` is configured to track (like
+/// `origin/main`) if its remote tracking branch is gone, e.g. after
+/// it got deleted on the remote and pruned
+pub fn upstream_gone(
+	repo_path: &str,
+	branch: &str,
+) -> Result<Option<String>> {
+	scope_time!("upstream_gone");
+
+	let repo = utils::repo(repo_path)?;
+
+	let remote = get_config_string_repo(
+		&repo,
+		&format!("branch.{}.remote", branch),
+	)?;
+	let upstream = get_upstream_branch_in_repo(&repo, branch)?;
+
+	Ok(match (remote, upstream) {
+		// `.` tracks a local branch
+		(Some(remote), Some(upstream)) if remote != "." => {
+			let name = format!("{}/{}", remote, upstream);
+			repo.find_reference(&format!("refs/remotes/{}", name))
+				.is_err()
+				.then(|| name)
+		}
+		_ => None,
+	})
+}
+
 /// Modify HEAD to point to a branch then checkout head, does not work if there are uncommitted changes
 pub fn checkout_branch(
 	repo_path: &str,
@@ -277,21 +370,28 @@ pub fn checkout_branch(
 	// This defaults to a safe checkout, so don't delete anything that
 	// hasn't been committed or stashed, in this case it will Err
 	let repo = utils::repo(repo_path)?;
-	let cur_ref = repo.head()?;
-	let statuses = repo.statuses(Some(
-		git2::StatusOptions::new().include_ignored(false),
-	))?;
+	let cur_ref = head_ref_name(&repo)?;
 
-	if statuses.is_empty() {
+	let clean = if is_head_unborn(&repo)? {
+		// e.g. an orphan branch before its first commit: everything
+		// in the index counts as added, but nothing is lost as long
+		// as it is what `branch_ref` holds anyway
+		matches_branch_tree(&repo, branch_ref)?
+	} else {
+		repo.statuses(Some(
+			git2::StatusOptions::new().include_ignored(false),
+		))?
+		.is_empty()
+	};
+
+	if clean {
 		repo.set_head(branch_ref)?;
 
 		if let Err(e) = repo.checkout_head(Some(
 			git2::build::CheckoutBuilder::new().force(),
 		)) {
 			// This is safe beacuse cur_ref was just found
-			repo.set_head(
-				bytes2string(cur_ref.name_bytes())?.as_str(),
-			)?;
+			repo.set_head(cur_ref.as_str())?;
 			return Err(Error::Git(e));
 		}
 		Ok(())
@@ -300,6 +400,28 @@ pub fn checkout_branch(
 	}
 }
 
+/// the ref HEAD points to, also if that branch is unborn
+fn head_ref_name(repo: &Repository) -> Result<String> {
+	let head = repo.find_reference("HEAD")?;
+
+	match head.symbolic_target() {
+		Some(target) => Ok(target.to_string()),
+		None => bytes2string(repo.head()?.name_bytes()),
+	}
+}
+
+/// `true` if index and workdir hold exactly the tree of `branch_ref`
+fn matches_branch_tree(
+	repo: &Repository,
+	branch_ref: &str,
+) -> Result<bool> {
+	let tree = repo.find_reference(branch_ref)?.peel_to_tree()?;
+	let diff =
+		repo.diff_tree_to_workdir_with_index(Some(&tree), None)?;
+
+	Ok(diff.deltas().len() == 0)
+}
+
 ///
 pub fn checkout_remote_branch(
 	repo_path: &str,
@@ -324,7 +446,10 @@ pub fn checkout_remote_branch(
 		|pos| branch.name[pos..].to_string(),
 	);
 
-	let commit = repo.find_commit(branch.top_commit.into())?;
+	let top_commit = branch.top_commit.ok_or_else(|| {
+		Error::Generic(String::from("remote branch without commit"))
+	})?;
+	let commit = repo.find_commit(top_commit.into())?;
 	let mut new_branch = repo.branch(&name, &commit, false)?;
 	new_branch.set_upstream(Some(&branch.name))?;
 
@@ -377,6 +502,30 @@ pub fn create_branch(repo_path: &str, name: &str) -> Result<String> {
 	Ok(branch_ref_name)
 }
 
+/// points HEAD at the new branch `name` without creating it, like
+/// `git checkout --orphan`: index and workdir stay as they are and
+/// the first commit creates the branch without any parent
+pub fn create_orphan_branch(
+	repo_path: &str,
+	name: &str,
+) -> Result<String> {
+	scope_time!("create_orphan_branch");
+
+	let repo = utils::repo(repo_path)?;
+
+	if repo.find_branch(name, BranchType::Local).is_ok() {
+		return Err(Error::Generic(format!(
+			"a branch named '{}' already exists",
+			name
+		)));
+	}
+
+	let branch_ref_name = format!("refs/heads/{}", name);
+	repo.set_head(branch_ref_name.as_str())?;
+
+	Ok(branch_ref_name)
+}
+
 #[cfg(test)]
 mod tests_branch_name {
 	use super::*;
@@ -400,6 +549,21 @@ mod tests_branch_name {
 		let root = repo.path().parent().unwrap();
 		let repo_path = root.as_os_str().to_str().unwrap();
 
+		assert_eq!(
+			get_branch_name(repo_path).unwrap().as_str(),
+			"master"
+		);
+	}
+
+	#[test]
+	fn test_detached_head() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let head = repo.head().unwrap().target().unwrap();
+		repo.set_head_detached(head).unwrap();
+
 		assert!(matches!(
 			get_branch_name(repo_path),
 			Err(Error::NoHead)
@@ -427,10 +591,71 @@ mod tests_create_branch {
 	}
 }
 
+#[cfg(test)]
+mod tests_create_orphan_branch {
+	use super::*;
+	use crate::sync::{
+		commit, repo_state,
+		tests::{get_statuses, repo_init, write_commit_file},
+		RepoState,
+	};
+
+	#[test]
+	fn test_smoke() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", "a", "c1");
+
+		assert_eq!(
+			create_orphan_branch(repo_path, "pages").unwrap(),
+			"refs/heads/pages"
+		);
+
+		// HEAD points to the branch which does not exist yet
+		assert!(is_head_unborn(&repo).unwrap());
+		assert!(repo
+			.find_branch("pages", BranchType::Local)
+			.is_err());
+		assert_eq!(get_branch_name(repo_path).unwrap(), "pages");
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+
+		// the index is kept, so its content is staged as new
+		assert_eq!(get_statuses(repo_path), (0, 1));
+
+		let id = commit(repo_path, "root", false).unwrap();
+		assert_eq!(
+			repo.find_commit(id.into()).unwrap().parent_count(),
+			0
+		);
+		assert_eq!(
+			repo.find_branch("pages", BranchType::Local)
+				.unwrap()
+				.get()
+				.target(),
+			Some(id.into())
+		);
+	}
+
+	#[test]
+	fn test_existing_or_invalid_name() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		assert!(create_orphan_branch(repo_path, "master").is_err());
+		assert!(create_orphan_branch(repo_path, "a..b").is_err());
+		assert!(!is_head_unborn(&repo).unwrap());
+	}
+}
+
 #[cfg(test)]
 mod tests_branch_compare {
 	use super::*;
-	use crate::sync::tests::repo_init;
+	use crate::sync::tests::{
+		repo_clone, repo_init, write_commit_file,
+	};
 
 	#[test]
 	fn test_smoke() {
@@ -444,6 +669,88 @@ mod tests_branch_compare {
 
 		assert_eq!(res.is_err(), true);
 	}
+
+	#[test]
+	fn test_configured_upstream() {
+		let (remote_dir, remote) = repo_init().unwrap();
+		let remote_path = remote_dir.path().to_str().unwrap();
+
+		let head = remote.head().unwrap().peel_to_commit().unwrap();
+		remote.branch("other", &head, false).unwrap();
+		write_commit_file(&remote, "a.txt", "a", "c1");
+
+		let (clone_dir, clone) = repo_clone(remote_path).unwrap();
+		let clone_path = clone_dir.path().to_str().unwrap();
+
+		let res =
+			branch_compare_upstream(clone_path, "master").unwrap();
+		assert_eq!((res.ahead, res.behind), (0, 0));
+
+		// compares against whatever the upstream is named
+		clone
+			.config()
+			.unwrap()
+			.set_str("branch.master.merge", "refs/heads/other")
+			.unwrap();
+
+		let res =
+			branch_compare_upstream(clone_path, "master").unwrap();
+		assert_eq!((res.ahead, res.behind), (1, 0));
+	}
+
+	#[test]
+	fn test_all_branches() {
+		let (remote_dir, _remote) = repo_init().unwrap();
+		let remote_path = remote_dir.path().to_str().unwrap();
+
+		let (clone_dir, clone) = repo_clone(remote_path).unwrap();
+		let clone_path = clone_dir.path().to_str().unwrap();
+
+		write_commit_file(&clone, "a.txt", "a", "c1");
+		create_branch(clone_path, "local").unwrap();
+
+		let res = branches_compare_upstream(clone_path).unwrap();
+
+		// `local` has no upstream
+		assert_eq!(res.len(), 1);
+		assert_eq!(
+			res.get("master"),
+			Some(&BranchCompare {
+				ahead: 1,
+				behind: 0
+			})
+		);
+	}
+
+	#[test]
+	fn test_upstream_gone() {
+		let (remote_dir, _remote) = repo_init().unwrap();
+		let remote_path = remote_dir.path().to_str().unwrap();
+
+		let (clone_dir, clone) = repo_clone(remote_path).unwrap();
+		let clone_path = clone_dir.path().to_str().unwrap();
+
+		assert_eq!(
+			upstream_gone(clone_path, "master").unwrap(),
+			None
+		);
+
+		// pruned after the branch got deleted on the remote
+		clone
+			.find_reference("refs/remotes/origin/master")
+			.unwrap()
+			.delete()
+			.unwrap();
+
+		assert_eq!(
+			upstream_gone(clone_path, "master").unwrap(),
+			Some(String::from("origin/master"))
+		);
+
+		// never had an upstream
+		create_branch(clone_path, "other").unwrap();
+		assert_eq!(upstream_gone(clone_path, "other").unwrap(), None);
+	}
 }
 
 #[cfg(test)]
@@ -454,7 +761,7 @@ mod tests_branches {
 		rename_branch,
 		tests::{
 			debug_cmd_print, repo_clone, repo_init, repo_init_bare,
-			write_commit_file,
+			repo_init_empty, write_commit_file,
 		},
 	};
 
@@ -492,12 +799,47 @@ mod tests_branches {
 		);
 	}
 
+	#[test]
+	fn test_tips() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let head = get_head_repo(&repo).unwrap();
+		create_branch(repo_path, "test").unwrap();
+
+		assert!(get_branches_info(repo_path, true)
+			.unwrap()
+			.iter()
+			.all(|b| b.top_commit == Some(head)));
+	}
+
+	#[test]
+	fn test_unborn_head() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let branches = get_branches_info(repo_path, true).unwrap();
+
+		assert_eq!(branches.len(), 1);
+		assert_eq!(branches[0].name, "master");
+		assert_eq!(branches[0].reference, "refs/heads/master");
+		assert_eq!(branches[0].top_commit, None);
+		assert!(branches[0].local_details().unwrap().is_head);
+
+		assert!(get_branches_info(repo_path, false)
+			.unwrap()
+			.is_empty());
+	}
+
 	fn clone_branch_commit_push(target: &str, branch_name: &str) {
 		let (dir, repo) = repo_clone(target).unwrap();
 		let dir = dir.path().to_str().unwrap();
 
 		write_commit_file(&repo, "f1.txt", "foo", "c1");
-		rename_branch(dir, "refs/heads/master", branch_name).unwrap();
+		rename_branch(dir, "refs/heads/master", branch_name, false)
+			.unwrap();
 		push(dir, "origin", branch_name, false, false, None, None)
 			.unwrap();
 	}
@@ -609,7 +951,10 @@ mod tests_branches {
 #[cfg(test)]
 mod tests_checkout {
 	use super::*;
-	use crate::sync::tests::repo_init;
+	use crate::sync::{
+		tests::{repo_init, write_commit_file},
+		utils::repo_write_file,
+	};
 
 	#[test]
 	fn test_smoke() {
@@ -639,6 +984,30 @@ mod tests_checkout {
 		);
 		assert!(checkout_branch(repo_path, "refs/heads/test").is_ok());
 	}
+
+	#[test]
+	fn test_back_from_orphan() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", "a", "c1");
+
+		// nothing changed since creating it, so nothing gets lost
+		create_orphan_branch(repo_path, "pages").unwrap();
+		checkout_branch(repo_path, "refs/heads/master").unwrap();
+		assert_eq!(get_branch_name(repo_path).unwrap(), "master");
+		assert!(!is_head_unborn(&repo).unwrap());
+
+		// changes made on the orphan branch block switching back
+		create_orphan_branch(repo_path, "pages").unwrap();
+		repo_write_file(&repo, "a.txt", "b").unwrap();
+		assert!(matches!(
+			checkout_branch(repo_path, "refs/heads/master"),
+			Err(Error::UncommittedChanges)
+		));
+		assert_eq!(get_branch_name(repo_path).unwrap(), "pages");
+	}
 }
 
 #[cfg(test)]
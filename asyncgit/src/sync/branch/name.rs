@@ -0,0 +1,252 @@
+//! branch names as `git check-ref-format --branch` accepts them
+
+/// characters never allowed in a ref name, besides whitespace and
+/// control characters
+const FORBIDDEN_CHARS: &[char] =
+	&['~', '^', ':', '?', '*', '[', '\\'];
+
+/// first rule a branch name breaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BranchNameError {
+	///
+	Empty,
+	/// `HEAD` and `@` mean the current commit
+	Reserved,
+	/// would be taken for an option
+	LeadingDash,
+	/// a whitespace or control character (unicode ones included,
+	/// which git itself lets through) or one of `~^:?*[\`
+	InvalidChar(char),
+	/// `..`
+	DoubleDot,
+	/// `@{`, the reflog syntax
+	AtBrace,
+	/// leading, trailing or consecutive `/`
+	EmptyComponent,
+	/// a component starting with `.`
+	LeadingDot,
+	/// a component ending with `.lock`
+	LockSuffix,
+	///
+	TrailingDot,
+}
+
+/// checks `name` against git's ref name rules for branches
+pub fn validate_branch_name(
+	name: &str,
+) -> std::result::Result<(), BranchNameError> {
+	if name.is_empty() {
+		return Err(BranchNameError::Empty);
+	}
+
+	if name == "HEAD" || name == "@" {
+		return Err(BranchNameError::Reserved);
+	}
+
+	if name.starts_with('-') {
+		return Err(BranchNameError::LeadingDash);
+	}
+
+	if let Some(c) = name.chars().find(|c| is_invalid_char(*c)) {
+		return Err(BranchNameError::InvalidChar(c));
+	}
+
+	if name.contains("..") {
+		return Err(BranchNameError::DoubleDot);
+	}
+
+	if name.contains("@{") {
+		return Err(BranchNameError::AtBrace);
+	}
+
+	// `.LOCK` is fine, ref names are case-sensitive
+	#[allow(clippy::case_sensitive_file_extension_comparisons)]
+	for component in name.split('/') {
+		if component.is_empty() {
+			return Err(BranchNameError::EmptyComponent);
+		}
+		if component.starts_with('.') {
+			return Err(BranchNameError::LeadingDot);
+		}
+		if component.ends_with(".lock") {
+			return Err(BranchNameError::LockSuffix);
+		}
+	}
+
+	if name.ends_with('.') {
+		return Err(BranchNameError::TrailingDot);
+	}
+
+	Ok(())
+}
+
+fn is_invalid_char(c: char) -> bool {
+	c.is_whitespace()
+		|| c.is_control()
+		|| FORBIDDEN_CHARS.contains(&c)
+}
+
+/// `name` made valid: whitespace turned into `-` and whatever git
+/// rejects dropped, `None` if it is valid already or nothing is left
+pub fn branch_name_slug(name: &str) -> Option<String> {
+	if validate_branch_name(name).is_ok() {
+		return None;
+	}
+
+	let mut cleaned = String::with_capacity(name.len());
+	let mut pending_dash = false;
+	for c in name.trim().chars() {
+		if c.is_whitespace() {
+			pending_dash = true;
+		} else if !is_invalid_char(c) {
+			if pending_dash && c != '/' && !cleaned.ends_with('/') {
+				cleaned.push('-');
+			}
+			pending_dash = false;
+			cleaned.push(c);
+		}
+	}
+
+	let cleaned = cleaned.replace("@{", "@");
+
+	let components: Vec<String> = cleaned
+		.split('/')
+		.map(slug_component)
+		.filter(|component| !component.is_empty())
+		.collect();
+
+	let slug = components.join("/");
+	let slug = slug.trim_start_matches('-');
+
+	validate_branch_name(slug).is_ok().then(|| slug.to_string())
+}
+
+/// no `..`, no leading `.` and no trailing `.` or `.lock`
+fn slug_component(component: &str) -> String {
+	let mut res = String::with_capacity(component.len());
+	for c in component.chars() {
+		if !(c == '.' && res.ends_with('.')) {
+			res.push(c);
+		}
+	}
+
+	let mut res = res.trim_start_matches('.');
+	loop {
+		let trimmed = res.trim_end_matches('.');
+		match trimmed.strip_suffix(".lock") {
+			Some(stripped) => res = stripped,
+			None => break trimmed.to_string(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_valid_names() {
+		for name in &[
+			"feature",
+			"feature/foo",
+			"foo-",
+			"a@b",
+			"head",
+			"\u{fc}n\u{ef}code",
+			"a.b",
+			"foo.lockx",
+			"lock",
+			"x/HEAD",
+			"a/-b",
+			"@/a",
+			"a/@",
+			"{a}",
+			"a]",
+		] {
+			assert_eq!(
+				validate_branch_name(name),
+				Ok(()),
+				"{}",
+				name
+			);
+		}
+	}
+
+	#[test]
+	fn test_invalid_names() {
+		use BranchNameError::*;
+
+		for (name, err) in &[
+			("", Empty),
+			("HEAD", Reserved),
+			("@", Reserved),
+			("-foo", LeadingDash),
+			("-", LeadingDash),
+			("a b", InvalidChar(' ')),
+			("a\tb", InvalidChar('\t')),
+			("a\u{7f}b", InvalidChar('\u{7f}')),
+			("a\u{85}b", InvalidChar('\u{85}')),
+			("foo~1", InvalidChar('~')),
+			("foo^", InvalidChar('^')),
+			("foo:bar", InvalidChar(':')),
+			("foo?", InvalidChar('?')),
+			("foo*", InvalidChar('*')),
+			("foo[", InvalidChar('[')),
+			("foo\\bar", InvalidChar('\\')),
+			("a..b", DoubleDot),
+			("..", DoubleDot),
+			("@{", AtBrace),
+			("a@{b", AtBrace),
+			("foo/", EmptyComponent),
+			("/foo", EmptyComponent),
+			("foo//bar", EmptyComponent),
+			(".foo", LeadingDot),
+			("foo/.bar", LeadingDot),
+			(".", LeadingDot),
+			(".lock", LeadingDot),
+			("a/.lock", LeadingDot),
+			("foo.lock", LockSuffix),
+			("foo.lock/bar", LockSuffix),
+			("foo/bar.lock", LockSuffix),
+			("foo.", TrailingDot),
+		] {
+			assert_eq!(
+				validate_branch_name(name),
+				Err(*err),
+				"{}",
+				name
+			);
+		}
+	}
+
+	#[test]
+	fn test_slug() {
+		assert_eq!(branch_name_slug("feature"), None);
+		assert_eq!(
+			branch_name_slug("  fix the  login bug "),
+			Some(String::from("fix-the-login-bug"))
+		);
+		assert_eq!(
+			branch_name_slug("feature / new thing"),
+			Some(String::from("feature/new-thing"))
+		);
+		assert_eq!(
+			branch_name_slug("-a..b~^:?*[\\c"),
+			Some(String::from("a.bc"))
+		);
+		assert_eq!(
+			branch_name_slug(".hidden/foo.lock."),
+			Some(String::from("hidden/foo"))
+		);
+		assert_eq!(
+			branch_name_slug("a//b@{1}/"),
+			Some(String::from("a/b@1}"))
+		);
+		assert_eq!(branch_name_slug("HEAD"), None);
+		assert_eq!(branch_name_slug("..."), None);
+		assert_eq!(
+			branch_name_slug("a\u{85}b\u{1}c"),
+			Some(String::from("a-bc"))
+		);
+	}
+}
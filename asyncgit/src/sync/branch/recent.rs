@@ -0,0 +1,99 @@
+//! branches recently checked out according to the reflog
+
+use crate::{error::Result, sync::utils};
+use git2::BranchType;
+use scopetime::scope_time;
+
+const CHECKOUT_PREFIX: &str = "checkout: moving from ";
+
+/// `(from, to)` of a reflog message written by a checkout
+fn parse_checkout(msg: &str) -> Option<(&str, &str)> {
+	let rest = msg.strip_prefix(CHECKOUT_PREFIX)?;
+	let sep = rest.find(" to ")?;
+
+	Some((&rest[..sep], &rest[sep + 4..]))
+}
+
+/// local branches HEAD was on according to its reflog, most recent
+/// first and at most `limit`. detached checkouts and branches that
+/// do not exist anymore are left out
+pub fn recent_branches(
+	repo_path: &str,
+	limit: usize,
+) -> Result<Vec<String>> {
+	scope_time!("recent_branches");
+
+	let repo = utils::repo(repo_path)?;
+	let reflog = repo.reflog("HEAD")?;
+
+	let mut res: Vec<String> = Vec::with_capacity(limit);
+
+	for entry in reflog.iter() {
+		if res.len() >= limit {
+			break;
+		}
+
+		if let Some((from, to)) =
+			entry.message().and_then(parse_checkout)
+		{
+			for name in &[to, from] {
+				if res.len() < limit
+					&& !res.iter().any(|known| known == name)
+					&& repo
+						.find_branch(name, BranchType::Local)
+						.is_ok()
+				{
+					res.push((*name).to_string());
+				}
+			}
+		}
+	}
+
+	Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		checkout_branch, create_branch, delete_branch,
+		tests::repo_init,
+	};
+
+	#[test]
+	fn test_parse_checkout() {
+		assert_eq!(
+			parse_checkout("checkout: moving from master to foo"),
+			Some(("master", "foo"))
+		);
+		assert_eq!(parse_checkout("commit: foo"), None);
+	}
+
+	#[test]
+	fn test_recent_branches() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		create_branch(repo_path, "foo").unwrap();
+		create_branch(repo_path, "bar").unwrap();
+		checkout_branch(repo_path, "refs/heads/master").unwrap();
+		checkout_branch(repo_path, "refs/heads/foo").unwrap();
+
+		assert_eq!(
+			recent_branches(repo_path, 10).unwrap(),
+			vec!["foo", "master", "bar"]
+		);
+		assert_eq!(
+			recent_branches(repo_path, 2).unwrap(),
+			vec!["foo", "master"]
+		);
+
+		delete_branch(repo_path, "refs/heads/bar").unwrap();
+
+		assert_eq!(
+			recent_branches(repo_path, 10).unwrap(),
+			vec!["foo", "master"]
+		);
+	}
+}
@@ -1,19 +1,39 @@
 //! renaming of branches
 
-use crate::{error::Result, sync::utils};
+use crate::{
+	error::{Error, Result},
+	sync::{utils, worktrees_with_branch},
+};
 use scopetime::scope_time;
 
-/// Rename the branch reference
+/// Rename the branch reference along with its config
+///
+/// `branch.<name>.*` (upstream, push remote, description) moves to
+/// the new name. a branch checked out in another worktree is only
+/// renamed with `force`, the `HEAD` of that worktree then follows it
 pub fn rename_branch(
 	repo_path: &str,
 	branch_ref: &str,
 	new_name: &str,
+	force: bool,
 ) -> Result<()> {
-	scope_time!("delete_branch");
+	scope_time!("rename_branch");
+
+	if !force {
+		if let Some(dir) =
+			worktrees_with_branch(repo_path, branch_ref)?.first()
+		{
+			return Err(Error::BranchCheckedOut(
+				dir.to_string_lossy().into_owned(),
+			));
+		}
+	}
 
 	let repo = utils::repo(repo_path)?;
 	let branch_as_ref = repo.find_reference(branch_ref)?;
 	let mut branch = git2::Branch::wrap(branch_as_ref);
+	// renames the `branch.<name>` config section and updates the
+	// `HEAD` of every worktree pointing at it
 	branch.rename(new_name, true)?;
 
 	Ok(())
@@ -23,7 +43,16 @@ pub fn rename_branch(
 mod test {
 	use super::super::*;
 	use super::rename_branch;
-	use crate::sync::tests::repo_init;
+	use crate::{
+		error::Error,
+		sync::{
+			config::get_config_string, tests::repo_init,
+			utils::repo_work_dir, worktrees_with_branch,
+		},
+	};
+	use git2::Repository;
+	use std::path::{Path, PathBuf};
+	use tempfile::TempDir;
 
 	#[test]
 	fn test_rename_branch() {
@@ -48,8 +77,13 @@ mod test {
 			"branch1"
 		);
 
-		rename_branch(repo_path, "refs/heads/branch1", "AnotherName")
-			.unwrap();
+		rename_branch(
+			repo_path,
+			"refs/heads/branch1",
+			"AnotherName",
+			false,
+		)
+		.unwrap();
 
 		assert_eq!(
 			repo.branches(None)
@@ -64,4 +98,86 @@ mod test {
 			"AnotherName"
 		);
 	}
+
+	#[test]
+	fn test_rename_moves_config() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		create_branch(repo_path, "old").unwrap();
+		let mut config = repo.config().unwrap();
+		for (key, value) in &[
+			("remote", "origin"),
+			("merge", "refs/heads/upstream"),
+			("pushRemote", "fork"),
+			("description", "what it is for"),
+		] {
+			config
+				.set_str(&format!("branch.old.{}", key), value)
+				.unwrap();
+		}
+
+		rename_branch(repo_path, "refs/heads/old", "new", false)
+			.unwrap();
+
+		let get =
+			|key: &str| get_config_string(repo_path, key).unwrap();
+		assert_eq!(get("branch.new.remote").unwrap(), "origin");
+		assert_eq!(
+			get("branch.new.merge").unwrap(),
+			"refs/heads/upstream"
+		);
+		assert_eq!(get("branch.new.pushRemote").unwrap(), "fork");
+		assert_eq!(
+			get("branch.new.description").unwrap(),
+			"what it is for"
+		);
+		assert_eq!(get("branch.old.remote"), None);
+		assert_eq!(get("branch.old.description"), None);
+	}
+
+	#[test]
+	fn test_rename_checked_out_in_worktree() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		// checks out a new branch `linked` there
+		let linked_dir = TempDir::new().unwrap();
+		let linked = linked_dir.path().join("linked");
+		repo.worktree("linked", &linked, None).unwrap();
+		let linked_path = linked.to_str().unwrap();
+
+		assert!(matches!(
+			rename_branch(repo_path, "refs/heads/linked", "new", false),
+			Err(Error::BranchCheckedOut(dir)) if Path::new(&dir) == linked
+		));
+		assert!(repo.find_reference("refs/heads/linked").is_ok());
+
+		// the own worktree does not count, the main one does
+		assert!(worktrees_with_branch(
+			linked_path,
+			"refs/heads/linked"
+		)
+		.unwrap()
+		.is_empty());
+		assert_eq!(
+			worktrees_with_branch(linked_path, "refs/heads/master")
+				.unwrap(),
+			vec![PathBuf::from(repo_work_dir(repo_path).unwrap())]
+		);
+
+		rename_branch(repo_path, "refs/heads/linked", "new", true)
+			.unwrap();
+
+		let linked_repo = Repository::open(&linked).unwrap();
+		assert_eq!(
+			linked_repo
+				.find_reference("HEAD")
+				.unwrap()
+				.symbolic_target(),
+			Some("refs/heads/new")
+		);
+	}
 }
@@ -0,0 +1,594 @@
+//! cherry-picking a range of commits onto `HEAD`, oldest first.
+//!
+//! the todo is kept in `sequencer` the way git keeps it, so a sequence
+//! stopped at a conflict shows up as cherry-pick in progress (after a
+//! restart as well) and can be continued, skipped or aborted
+
+use super::{
+	autosquash::ensure_clean, commit::signature_allow_undefined_name,
+	utils, CommitId,
+};
+use crate::error::{Error, Result};
+use git2::{build::CheckoutBuilder, Oid, Repository, Sort};
+use scopetime::scope_time;
+use std::{
+	fs,
+	io::ErrorKind,
+	path::{Path, PathBuf},
+};
+
+const TODO_FILE: &str = "todo";
+/// not read by git, we keep it for the progress
+const DONE_FILE: &str = "done";
+const HEAD_FILE: &str = "head";
+const ABORT_SAFETY_FILE: &str = "abort-safety";
+
+/// commit the sequence is at, `current` is 1-based
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CherryPickProgress {
+	///
+	pub current: usize,
+	///
+	pub total: usize,
+	///
+	pub subject: String,
+}
+
+///
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CherryPickResult {
+	/// the commit the sequence stopped at, to be continued, skipped
+	/// or aborted once its conflicts are looked at
+	pub conflict: Option<CherryPickProgress>,
+	/// commits applied already, nothing was left to pick
+	pub empty: usize,
+}
+
+/// line of the sequencer todo
+struct Pick {
+	id: CommitId,
+	subject: String,
+}
+
+impl Pick {
+	fn to_line(&self) -> String {
+		format!("pick {} {}", self.id.to_string(), self.subject)
+	}
+
+	fn parse(line: &str) -> Result<Self> {
+		let invalid = || {
+			Error::Generic(format!(
+				"unsupported cherry-pick todo: {}",
+				line
+			))
+		};
+
+		let mut parts = line.splitn(3, ' ');
+		if !matches!(parts.next(), Some("pick") | Some("p")) {
+			return Err(invalid());
+		}
+		let id = parts
+			.next()
+			.and_then(|id| CommitId::from_hex(id).ok())
+			.ok_or_else(invalid)?;
+
+		Ok(Self {
+			id,
+			subject: parts.next().unwrap_or_default().to_string(),
+		})
+	}
+}
+
+fn sequencer_dir(repo: &Repository) -> PathBuf {
+	repo.path().join("sequencer")
+}
+
+fn todo_lines(todo: &str) -> impl Iterator<Item = &str> {
+	todo.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+fn read_lines(path: &Path) -> Result<Vec<String>> {
+	match fs::read_to_string(path) {
+		Ok(content) => {
+			Ok(todo_lines(&content).map(String::from).collect())
+		}
+		Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+		Err(e) => Err(e.into()),
+	}
+}
+
+fn write_lines(path: &Path, lines: &[String]) -> Result<()> {
+	fs::write(
+		path,
+		lines.iter().fold(String::new(), |mut res, line| {
+			res.push_str(line);
+			res.push('\n');
+			res
+		}),
+	)?;
+
+	Ok(())
+}
+
+fn remove_file_if_exists(path: &Path) -> Result<()> {
+	match fs::remove_file(path) {
+		Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+		_ => Ok(()),
+	}
+}
+
+fn subject(repo: &Repository, id: CommitId) -> Result<String> {
+	let commit = repo.find_commit(id.into())?;
+	Ok(String::from_utf8_lossy(commit.message_bytes())
+		.lines()
+		.next()
+		.unwrap_or_default()
+		.to_string())
+}
+
+/// commits from the older of `one` and `two` to the newer one (both
+/// included), oldest first
+pub fn cherry_pick_range_commits(
+	repo_path: &str,
+	one: CommitId,
+	two: CommitId,
+) -> Result<Vec<CommitId>> {
+	scope_time!("cherry_pick_range_commits");
+
+	let repo = utils::repo(repo_path)?;
+
+	let base: CommitId =
+		repo.merge_base(one.into(), two.into())?.into();
+	let (oldest, newest) = if base == one {
+		(one, two)
+	} else if base == two {
+		(two, one)
+	} else {
+		return Err(Error::Generic(String::from(
+			"one of the commits has to be an ancestor of the other",
+		)));
+	};
+
+	let mut walk = repo.revwalk()?;
+	walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+	walk.push(newest.into())?;
+	walk.hide(oldest.into())?;
+
+	std::iter::once(Ok(oldest))
+		.chain(walk.map(|id| Ok(CommitId::from(id?))))
+		.collect()
+}
+
+/// picks `commits` (oldest first) onto `HEAD` one after another
+///
+/// `progress` is told about each before it is applied. stops at the
+/// first conflict, see `continue_cherry_pick`, `skip_cherry_pick`
+/// and `abort_pending_state`
+pub fn cherry_pick_range(
+	repo_path: &str,
+	commits: &[CommitId],
+	progress: impl FnMut(&CherryPickProgress),
+) -> Result<CherryPickResult> {
+	scope_time!("cherry_pick_range");
+
+	let repo = utils::repo(repo_path)?;
+	ensure_clean(&repo)?;
+
+	if commits.is_empty() {
+		return Err(Error::Generic(String::from(
+			"nothing to cherry-pick",
+		)));
+	}
+
+	let mut todo = Vec::with_capacity(commits.len());
+	for id in commits {
+		let commit = repo.find_commit((*id).into())?;
+		if commit.parent_count() != 1 {
+			return Err(Error::Generic(format!(
+				"cannot cherry-pick {} commit {}",
+				if commit.parent_count() == 0 {
+					"root"
+				} else {
+					"merge"
+				},
+				id.get_short_string()
+			)));
+		}
+
+		todo.push(
+			Pick {
+				id: *id,
+				subject: subject(&repo, *id)?,
+			}
+			.to_line(),
+		);
+	}
+
+	let head = repo.head()?.peel_to_commit()?.id();
+
+	let dir = sequencer_dir(&repo);
+	fs::create_dir(&dir)?;
+	fs::write(dir.join(HEAD_FILE), format!("{}\n", head))?;
+	fs::write(dir.join(ABORT_SAFETY_FILE), format!("{}\n", head))?;
+	fs::write(dir.join(DONE_FILE), "")?;
+	write_lines(&dir.join(TODO_FILE), &todo)?;
+
+	run_todo(&repo, progress)
+}
+
+/// the cherry-pick sequence in progress, `None` if there is none
+pub fn cherry_pick_progress(
+	repo_path: &str,
+) -> Result<Option<CherryPickProgress>> {
+	let repo = utils::repo(repo_path)?;
+	let dir = sequencer_dir(&repo);
+
+	let todo = read_lines(&dir.join(TODO_FILE))?;
+	let line = match todo.first() {
+		Some(line) => line,
+		None => return Ok(None),
+	};
+
+	let done = read_lines(&dir.join(DONE_FILE))?.len();
+
+	Ok(Some(CherryPickProgress {
+		current: done + 1,
+		total: done + todo.len(),
+		subject: Pick::parse(line)?.subject,
+	}))
+}
+
+/// commits the pick the sequence stopped at once its conflicts are
+/// resolved and picks the rest
+pub fn continue_cherry_pick(
+	repo_path: &str,
+	progress: impl FnMut(&CherryPickProgress),
+) -> Result<CherryPickResult> {
+	scope_time!("continue_cherry_pick");
+
+	let repo = utils::repo(repo_path)?;
+
+	if repo.index()?.has_conflicts() {
+		return Err(Error::Generic(String::from(
+			"resolve all conflicts first",
+		)));
+	}
+
+	let pick = stopped_pick(&repo)?;
+	let empty = commit_pick(&repo, &pick)?;
+	pick_done(&repo)?;
+
+	let mut result = run_todo(&repo, progress)?;
+	result.empty += usize::from(empty);
+
+	Ok(result)
+}
+
+/// drops the pick the sequence stopped at (and its changes) and picks
+/// the rest
+pub fn skip_cherry_pick(
+	repo_path: &str,
+	progress: impl FnMut(&CherryPickProgress),
+) -> Result<CherryPickResult> {
+	scope_time!("skip_cherry_pick");
+
+	let repo = utils::repo(repo_path)?;
+
+	stopped_pick(&repo)?;
+
+	// a hard reset would clean up the sequencer as well
+	repo.checkout_head(Some(CheckoutBuilder::new().force()))?;
+	remove_pick_state(&repo)?;
+	pick_done(&repo)?;
+
+	run_todo(&repo, progress)
+}
+
+fn stopped_pick(repo: &Repository) -> Result<Pick> {
+	read_lines(&sequencer_dir(repo).join(TODO_FILE))?
+		.first()
+		.map_or_else(
+			|| {
+				Err(Error::Generic(String::from(
+					"no cherry-pick sequence in progress",
+				)))
+			},
+			|line| Pick::parse(line),
+		)
+}
+
+/// moves the first line of the todo to the done ones
+fn pick_done(repo: &Repository) -> Result<()> {
+	let dir = sequencer_dir(repo);
+
+	let mut todo = read_lines(&dir.join(TODO_FILE))?;
+	if todo.is_empty() {
+		return Ok(());
+	}
+	let line = todo.remove(0);
+
+	let mut done = read_lines(&dir.join(DONE_FILE))?;
+	done.push(line);
+
+	write_lines(&dir.join(DONE_FILE), &done)?;
+	write_lines(&dir.join(TODO_FILE), &todo)?;
+
+	let head = repo.head()?.peel_to_commit()?.id();
+	fs::write(dir.join(ABORT_SAFETY_FILE), format!("{}\n", head))?;
+
+	Ok(())
+}
+
+fn run_todo(
+	repo: &Repository,
+	mut progress: impl FnMut(&CherryPickProgress),
+) -> Result<CherryPickResult> {
+	let dir = sequencer_dir(repo);
+	let mut result = CherryPickResult::default();
+
+	loop {
+		let todo = read_lines(&dir.join(TODO_FILE))?;
+		let line = if let Some(line) = todo.first() {
+			line
+		} else {
+			fs::remove_dir_all(&dir)?;
+			repo.cleanup_state()?;
+			return Ok(result);
+		};
+		let pick = Pick::parse(line)?;
+
+		let done = read_lines(&dir.join(DONE_FILE))?.len();
+		let step = CherryPickProgress {
+			current: done + 1,
+			total: done + todo.len(),
+			subject: pick.subject.clone(),
+		};
+		progress(&step);
+
+		let commit = repo.find_commit(pick.id.into())?;
+		repo.cherrypick(&commit, None)?;
+
+		if repo.index()?.has_conflicts() {
+			// `CHERRY_PICK_HEAD` stays, like git leaves it
+			result.conflict = Some(step);
+			return Ok(result);
+		}
+
+		if commit_pick(repo, &pick)? {
+			result.empty += 1;
+		}
+		pick_done(repo)?;
+	}
+}
+
+/// commits the index with the author and message of the pick, `true`
+/// if there was nothing to commit (the change is applied already)
+fn commit_pick(repo: &Repository, pick: &Pick) -> Result<bool> {
+	let commit = repo.find_commit(pick.id.into())?;
+	let head = repo.head()?.peel_to_commit()?;
+	let tree = repo.find_tree(repo.index()?.write_tree()?)?;
+
+	let empty = tree.id() == head.tree_id();
+	if !empty {
+		let committer = signature_allow_undefined_name(repo)?;
+		repo.commit(
+			Some("HEAD"),
+			&commit.author(),
+			&committer,
+			&String::from_utf8_lossy(commit.message_bytes()),
+			&tree,
+			&[&head],
+		)?;
+	}
+
+	remove_pick_state(repo)?;
+
+	Ok(empty)
+}
+
+/// what `cherrypick` leaves besides the changes, without the
+/// sequencer (unlike `cleanup_state`)
+fn remove_pick_state(repo: &Repository) -> Result<()> {
+	remove_file_if_exists(&repo.path().join("CHERRY_PICK_HEAD"))?;
+	remove_file_if_exists(&repo.path().join("MERGE_MSG"))?;
+
+	Ok(())
+}
+
+/// `HEAD` the cherry-pick or revert sequence in progress started
+/// from
+pub fn sequencer_start(repo: &Repository) -> Option<Oid> {
+	fs::read_to_string(sequencer_dir(repo).join(HEAD_FILE))
+		.ok()
+		.and_then(|id| Oid::from_str(id.trim()).ok())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		abort_pending_state, checkout_branch, create_branch,
+		repo_state, stage_add_file,
+		tests::{repo_init, write_commit_file},
+		utils::get_head_repo,
+		RepoState,
+	};
+	use std::path::Path;
+	use tempfile::TempDir;
+
+	/// `foo` has `a.txt` changed in three commits, the middle one
+	/// conflicts with `master`
+	fn setup() -> (TempDir, Repository, Vec<CommitId>) {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", "base", "base");
+		create_branch(repo_path, "foo").unwrap();
+		let picks = vec![
+			write_commit_file(&repo, "b.txt", "b", "add b"),
+			write_commit_file(&repo, "a.txt", "foo", "change a"),
+			write_commit_file(&repo, "c.txt", "c", "add c"),
+		];
+
+		checkout_branch(repo_path, "refs/heads/master").unwrap();
+		write_commit_file(&repo, "a.txt", "master", "master a");
+
+		(td, repo, picks)
+	}
+
+	fn subjects(repo: &Repository, count: usize) -> Vec<String> {
+		let mut walk = repo.revwalk().unwrap();
+		walk.push_head().unwrap();
+		walk.take(count)
+			.map(|id| subject(repo, id.unwrap().into()).unwrap())
+			.collect()
+	}
+
+	#[test]
+	fn test_range_commits() {
+		let (_td, repo, picks) = setup();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		assert_eq!(
+			cherry_pick_range_commits(repo_path, picks[2], picks[0])
+				.unwrap(),
+			picks
+		);
+
+		let head = get_head_repo(&repo).unwrap();
+		assert!(cherry_pick_range_commits(repo_path, head, picks[0])
+			.is_err());
+	}
+
+	#[test]
+	fn test_clean_range() {
+		let (_td, repo, picks) = setup();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let mut steps = Vec::new();
+		let result = cherry_pick_range(
+			repo_path,
+			&[picks[0], picks[2]],
+			|step| steps.push(step.clone()),
+		)
+		.unwrap();
+
+		assert_eq!(result, CherryPickResult::default());
+		assert_eq!(
+			steps,
+			vec![
+				CherryPickProgress {
+					current: 1,
+					total: 2,
+					subject: String::from("add b"),
+				},
+				CherryPickProgress {
+					current: 2,
+					total: 2,
+					subject: String::from("add c"),
+				},
+			]
+		);
+		assert_eq!(
+			subjects(&repo, 3),
+			["add c", "add b", "master a"]
+		);
+		assert!(!sequencer_dir(&repo).exists());
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+	}
+
+	#[test]
+	fn test_skip_conflict() {
+		let (_td, repo, picks) = setup();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let result =
+			cherry_pick_range(repo_path, &picks, |_| ()).unwrap();
+
+		let stopped = CherryPickProgress {
+			current: 2,
+			total: 3,
+			subject: String::from("change a"),
+		};
+		assert_eq!(result.conflict.as_ref(), Some(&stopped));
+		assert_eq!(
+			repo_state(repo_path).unwrap(),
+			RepoState::CherryPick
+		);
+		// like after a restart
+		assert_eq!(
+			cherry_pick_progress(repo_path).unwrap(),
+			Some(stopped)
+		);
+		assert!(continue_cherry_pick(repo_path, |_| ()).is_err());
+
+		let result = skip_cherry_pick(repo_path, |_| ()).unwrap();
+
+		assert_eq!(result, CherryPickResult::default());
+		assert_eq!(
+			subjects(&repo, 3),
+			["add c", "add b", "master a"]
+		);
+		assert_eq!(
+			fs::read_to_string(root.join("a.txt")).unwrap(),
+			"master"
+		);
+		assert!(!sequencer_dir(&repo).exists());
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+	}
+
+	#[test]
+	fn test_continue_conflict() {
+		let (_td, repo, picks) = setup();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		cherry_pick_range(repo_path, &picks, |_| ()).unwrap();
+
+		fs::write(root.join("a.txt"), "resolved").unwrap();
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+
+		let result = continue_cherry_pick(repo_path, |_| ()).unwrap();
+
+		assert_eq!(result, CherryPickResult::default());
+		assert_eq!(
+			subjects(&repo, 4),
+			["add c", "change a", "add b", "master a"]
+		);
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+	}
+
+	#[test]
+	fn test_abort_restores_head() {
+		let (_td, repo, picks) = setup();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let head = get_head_repo(&repo).unwrap();
+
+		cherry_pick_range(repo_path, &picks, |_| ()).unwrap();
+		assert_ne!(get_head_repo(&repo).unwrap(), head);
+		assert_eq!(sequencer_start(&repo), Some(head.into()));
+
+		abort_pending_state(repo_path).unwrap();
+
+		assert_eq!(get_head_repo(&repo).unwrap(), head);
+		assert_eq!(
+			repo.head().unwrap().name(),
+			Some("refs/heads/master")
+		);
+		assert_eq!(
+			fs::read_to_string(root.join("a.txt")).unwrap(),
+			"master"
+		);
+		assert!(!root.join("b.txt").exists());
+		assert!(!sequencer_dir(&repo).exists());
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+	}
+}
@@ -1,33 +1,112 @@
-use super::{utils::repo, CommitId};
-use crate::{error::Result, sync::utils::get_head_repo};
-use git2::{ErrorCode, ObjectType, Repository, Signature};
-use scopetime::scope_time;
+use super::{
+	index_lock::{ensure_index_unlocked, retry_on_index_lock},
+	utils::repo,
+	CommitId,
+};
+use crate::{
+	error::{Error, Result},
+	sync::utils::{get_head_repo, is_head_unborn},
+};
+use git2::{
+	Commit, ErrorCode, ObjectType, Repository, Signature, Time,
+};
+use scopetime::{scope_stats, scope_time};
+
+/// what amending does with the dates of the amended commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmendDates {
+	/// like git: keeps the author, the committer date is now
+	Default,
+	/// keeps the author and the committer date
+	KeepCommitterDate,
+	/// author and committer date are now
+	AuthorDateNow,
+	/// author and committer date are set to `time` (secs since
+	/// epoch) at `offset` (minutes east of UTC)
+	Explicit {
+		///
+		time: i64,
+		///
+		offset: i32,
+	},
+}
 
-///
+impl Default for AmendDates {
+	fn default() -> Self {
+		Self::Default
+	}
+}
+
+/// the committer always becomes the current user
 pub fn amend(
 	repo_path: &str,
 	id: CommitId,
 	msg: &str,
+	dates: AmendDates,
 ) -> Result<CommitId> {
-	scope_time!("amend");
+	scope_stats!("amend");
 
 	let repo = repo(repo_path)?;
 	let commit = repo.find_commit(id.into())?;
 
-	let mut index = repo.index()?;
-	let tree_id = index.write_tree()?;
-	let tree = repo.find_tree(tree_id)?;
+	let (author, committer) =
+		amend_signatures(&repo, &commit, dates)?;
+
+	retry_on_index_lock(repo_path, || {
+		ensure_index_unlocked(&repo)?;
+
+		let mut index = repo.index()?;
+		let tree_id = index.write_tree()?;
+		let tree = repo.find_tree(tree_id)?;
+
+		let new_id = commit.amend(
+			Some("HEAD"),
+			Some(&author),
+			Some(&committer),
+			None,
+			Some(msg),
+			Some(&tree),
+		)?;
+
+		Ok(CommitId::new(new_id))
+	})
+}
+
+fn amend_signatures(
+	repo: &Repository,
+	commit: &Commit,
+	dates: AmendDates,
+) -> Result<(Signature<'static>, Signature<'static>)> {
+	let now = signature_allow_undefined_name(repo)?;
+	let author = commit.author();
+
+	let (author_time, committer_time) = match dates {
+		AmendDates::Default => (author.when(), now.when()),
+		AmendDates::KeepCommitterDate => {
+			(author.when(), commit.committer().when())
+		}
+		AmendDates::AuthorDateNow => (now.when(), now.when()),
+		AmendDates::Explicit { time, offset } => {
+			let time = Time::new(time, offset);
+			(time, time)
+		}
+	};
 
-	let new_id = commit.amend(
-		Some("HEAD"),
-		None,
-		None,
-		None,
-		Some(msg),
-		Some(&tree),
-	)?;
+	Ok((
+		signature_at(&author, &author_time)?,
+		signature_at(&now, &committer_time)?,
+	))
+}
 
-	Ok(CommitId::new(new_id))
+fn signature_at(
+	signature: &Signature,
+	time: &Time,
+) -> Result<Signature<'static>> {
+	Ok(Signature::new(
+		&String::from_utf8_lossy(signature.name_bytes()),
+		&String::from_utf8_lossy(signature.email_bytes()),
+		time,
+	)?)
 }
 
 /// Wrap `Repository::signature` to allow unknown user.name.
@@ -57,35 +136,53 @@ pub(crate) fn signature_allow_undefined_name(
 	signature
 }
 
-/// this does not run any git hooks
-pub fn commit(repo_path: &str, msg: &str) -> Result<CommitId> {
-	scope_time!("commit");
+/// this does not run any git hooks, fails with
+/// `Error::NothingToCommit` if nothing is staged unless `allow_empty`
+pub fn commit(
+	repo_path: &str,
+	msg: &str,
+	allow_empty: bool,
+) -> Result<CommitId> {
+	scope_stats!("commit");
 
 	let repo = repo(repo_path)?;
 
 	let signature = signature_allow_undefined_name(&repo)?;
-	let mut index = repo.index()?;
-	let tree_id = index.write_tree()?;
-	let tree = repo.find_tree(tree_id)?;
-
-	let parents = if let Ok(id) = get_head_repo(&repo) {
-		vec![repo.find_commit(id.into())?]
-	} else {
-		Vec::new()
-	};
 
-	let parents = parents.iter().collect::<Vec<_>>();
+	retry_on_index_lock(repo_path, || {
+		ensure_index_unlocked(&repo)?;
 
-	Ok(repo
-		.commit(
-			Some("HEAD"),
-			&signature,
-			&signature,
-			msg,
-			&tree,
-			parents.as_slice(),
-		)?
-		.into())
+		let mut index = repo.index()?;
+		let tree_id = index.write_tree()?;
+		let tree = repo.find_tree(tree_id)?;
+
+		let parents = if is_head_unborn(&repo)? {
+			Vec::new()
+		} else {
+			vec![repo.find_commit(get_head_repo(&repo)?.into())?]
+		};
+
+		let empty = parents.first().map_or_else(
+			|| index.is_empty(),
+			|parent| parent.tree_id() == tree_id,
+		);
+		if empty && !allow_empty {
+			return Err(Error::NothingToCommit);
+		}
+
+		let parents = parents.iter().collect::<Vec<_>>();
+
+		Ok(repo
+			.commit(
+				Some("HEAD"),
+				&signature,
+				&signature,
+				msg,
+				&tree,
+				parents.as_slice(),
+			)?
+			.into())
+	})
 }
 
 /// Tag a commit.
@@ -112,17 +209,23 @@ pub fn tag(
 #[cfg(test)]
 mod tests {
 
-	use crate::error::Result;
+	use crate::error::{Error, Result};
 	use crate::sync::{
 		commit, get_commit_details, get_commit_files, stage_add_file,
 		tags::get_tags,
 		tests::{get_statuses, repo_init, repo_init_empty},
 		utils::get_head,
-		LogWalker,
+		CommitId, LogWalker, MergeDiffMode,
+	};
+	use commit::{amend, tag, AmendDates};
+	use git2::{Repository, Signature, Time};
+	use std::{
+		convert::TryFrom,
+		fs::File,
+		io::Write,
+		path::Path,
+		time::{SystemTime, UNIX_EPOCH},
 	};
-	use commit::{amend, tag};
-	use git2::Repository;
-	use std::{fs::File, io::Write, path::Path};
 
 	fn count_commits(repo: &Repository, max: usize) -> usize {
 		let mut items = Vec::new();
@@ -149,7 +252,7 @@ mod tests {
 
 		assert_eq!(get_statuses(repo_path), (0, 1));
 
-		commit(repo_path, "commit msg").unwrap();
+		commit(repo_path, "commit msg", false).unwrap();
 
 		assert_eq!(get_statuses(repo_path), (0, 0));
 	}
@@ -174,9 +277,50 @@ mod tests {
 
 		assert_eq!(get_statuses(repo_path), (0, 1));
 
-		commit(repo_path, "commit msg").unwrap();
+		let id = commit(repo_path, "commit msg", false).unwrap();
 
 		assert_eq!(get_statuses(repo_path), (0, 0));
+		assert_eq!(get_head(repo_path).unwrap(), id);
+		assert_eq!(count_commits(&repo, 10), 1);
+		assert_eq!(
+			repo.find_commit(id.into()).unwrap().parent_count(),
+			0
+		);
+	}
+
+	#[test]
+	fn test_commit_nothing_staged() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		File::create(root.join("foo"))
+			.unwrap()
+			.write_all(b"unstaged")
+			.unwrap();
+
+		let res = commit(repo_path, "commit msg", false);
+
+		assert!(matches!(res, Err(Error::NothingToCommit)));
+		assert_eq!(count_commits(&repo, 10), 1);
+	}
+
+	#[test]
+	fn test_commit_allow_empty() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let head = get_head(repo_path).unwrap();
+		let id = commit(repo_path, "trigger ci", true).unwrap();
+		let commit = repo.find_commit(id.into()).unwrap();
+
+		assert_eq!(count_commits(&repo, 10), 2);
+		assert_eq!(commit.parent_id(0).unwrap(), head.into());
+		assert_eq!(
+			commit.tree_id(),
+			repo.find_commit(head.into()).unwrap().tree_id()
+		);
 	}
 
 	#[test]
@@ -190,7 +334,7 @@ mod tests {
 		File::create(&root.join(file_path1))?.write_all(b"test1")?;
 
 		stage_add_file(repo_path, file_path1)?;
-		let id = commit(repo_path, "commit msg")?;
+		let id = commit(repo_path, "commit msg", false)?;
 
 		assert_eq!(count_commits(&repo, 10), 1);
 
@@ -198,14 +342,20 @@ mod tests {
 
 		stage_add_file(repo_path, file_path2)?;
 
-		let new_id = amend(repo_path, id, "amended")?;
+		let new_id =
+			amend(repo_path, id, "amended", AmendDates::Default)?;
 
 		assert_eq!(count_commits(&repo, 10), 1);
 
 		let details = get_commit_details(repo_path, new_id)?;
 		assert_eq!(details.message.unwrap().subject, "amended");
 
-		let files = get_commit_files(repo_path, new_id, None)?;
+		let files = get_commit_files(
+			repo_path,
+			new_id,
+			None,
+			MergeDiffMode::default(),
+		)?;
 
 		assert_eq!(files.len(), 2);
 
@@ -216,6 +366,102 @@ mod tests {
 		Ok(())
 	}
 
+	/// a commit by someone else, authored and committed long ago
+	fn commit_in_the_past(repo: &Repository) -> CommitId {
+		let author = Signature::new(
+			"author",
+			"author@mail",
+			&Time::new(1000, 60),
+		)
+		.unwrap();
+		let committer = Signature::new(
+			"committer",
+			"committer@mail",
+			&Time::new(2000, 120),
+		)
+		.unwrap();
+		let tree = repo
+			.find_tree(repo.index().unwrap().write_tree().unwrap())
+			.unwrap();
+
+		repo.commit(
+			Some("HEAD"),
+			&author,
+			&committer,
+			"old",
+			&tree,
+			&[],
+		)
+		.unwrap()
+		.into()
+	}
+
+	/// author and committer time of the amended commit as secs and
+	/// offset
+	fn amend_times(dates: AmendDates) -> ((i64, i32), (i64, i32)) {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let id = commit_in_the_past(&repo);
+		let new_id = amend(repo_path, id, "amended", dates).unwrap();
+
+		let commit = repo.find_commit(new_id.into()).unwrap();
+		let author = commit.author();
+		let committer = commit.committer();
+		assert_eq!(author.name(), Some("author"));
+		assert_eq!(committer.name(), Some("name"));
+
+		let time =
+			|time: Time| (time.seconds(), time.offset_minutes());
+		(time(author.when()), time(committer.when()))
+	}
+
+	fn is_recent((secs, _): (i64, i32)) -> bool {
+		let now = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.unwrap()
+			.as_secs();
+		(i64::try_from(now).unwrap() - secs).abs() < 60
+	}
+
+	#[test]
+	fn test_amend_dates_default() {
+		let (author, committer) = amend_times(AmendDates::Default);
+
+		assert_eq!(author, (1000, 60));
+		assert!(is_recent(committer));
+	}
+
+	#[test]
+	fn test_amend_dates_keep_committer_date() {
+		let (author, committer) =
+			amend_times(AmendDates::KeepCommitterDate);
+
+		assert_eq!(author, (1000, 60));
+		assert_eq!(committer, (2000, 120));
+	}
+
+	#[test]
+	fn test_amend_dates_author_date_now() {
+		let (author, committer) =
+			amend_times(AmendDates::AuthorDateNow);
+
+		assert!(is_recent(author));
+		assert!(is_recent(committer));
+	}
+
+	#[test]
+	fn test_amend_dates_explicit() {
+		let (author, committer) = amend_times(AmendDates::Explicit {
+			time: 1_600_000_000,
+			offset: -300,
+		});
+
+		assert_eq!(author, (1_600_000_000, -300));
+		assert_eq!(committer, (1_600_000_000, -300));
+	}
+
 	#[test]
 	fn test_tag() -> Result<()> {
 		let file_path = Path::new("foo");
@@ -228,7 +474,7 @@ mod tests {
 
 		stage_add_file(repo_path, file_path)?;
 
-		let new_id = commit(repo_path, "commit msg")?;
+		let new_id = commit(repo_path, "commit msg", false)?;
 
 		tag(repo_path, &new_id, "tag")?;
 
@@ -274,13 +520,13 @@ mod tests {
 
 		repo.config()?.remove("user.email")?;
 
-		let error = commit(repo_path, "commit msg");
+		let error = commit(repo_path, "commit msg", false);
 
 		assert!(matches!(error, Err(_)));
 
 		repo.config()?.set_str("user.email", "email")?;
 
-		let success = commit(repo_path, "commit msg");
+		let success = commit(repo_path, "commit msg", false);
 
 		assert!(matches!(success, Ok(_)));
 		assert_eq!(count_commits(&repo, 10), 1);
@@ -309,7 +555,7 @@ mod tests {
 
 		repo.config()?.remove("user.name")?;
 
-		let mut success = commit(repo_path, "commit msg");
+		let mut success = commit(repo_path, "commit msg", false);
 
 		assert!(matches!(success, Ok(_)));
 		assert_eq!(count_commits(&repo, 10), 1);
@@ -322,7 +568,7 @@ mod tests {
 
 		repo.config()?.set_str("user.name", "name")?;
 
-		success = commit(repo_path, "commit msg");
+		success = commit(repo_path, "commit msg", true);
 
 		assert!(matches!(success, Ok(_)));
 		assert_eq!(count_commits(&repo, 10), 2);
@@ -1,4 +1,10 @@
-use super::{commits_info::get_message, utils::repo, CommitId};
+use super::{
+	commits_info::get_message,
+	mailmap::{mailmap_repo, resolve},
+	stash::is_stash_commit,
+	utils::repo,
+	CommitId,
+};
 use crate::error::Result;
 use git2::Signature;
 use scopetime::scope_time;
@@ -78,12 +84,16 @@ pub struct CommitDetails {
 	pub message: Option<CommitMessage>,
 	///
 	pub hash: String,
+	/// abbreviated as of `core.abbrev`
+	pub hash_short: String,
+	/// more than one parent (stashes excluded)
+	pub is_merge: bool,
 }
 
 impl CommitDetails {
 	///
 	pub fn short_hash(&self) -> &str {
-		&self.hash[0..7]
+		&self.hash_short
 	}
 }
 
@@ -98,8 +108,13 @@ pub fn get_commit_details(
 
 	let commit = repo.find_commit(id.into())?;
 
-	let author = CommitSignature::from(&commit.author());
-	let committer = CommitSignature::from(&commit.committer());
+	let mailmap = mailmap_repo(&repo)?;
+	let author =
+		CommitSignature::from(&resolve(&mailmap, &commit.author()));
+	let committer = CommitSignature::from(&resolve(
+		&mailmap,
+		&commit.committer(),
+	));
 	let committer = if author == committer {
 		None
 	} else {
@@ -114,6 +129,9 @@ pub fn get_commit_details(
 		committer,
 		message: Some(msg),
 		hash: id.to_string(),
+		hash_short: id.abbreviated(repo_path),
+		is_merge: commit.parent_count() > 1
+			&& !is_stash_commit(repo_path, &id)?,
 	};
 
 	Ok(details)
@@ -140,7 +158,7 @@ mod tests {
 		stage_add_file(repo_path, file_path).unwrap();
 
 		let msg = invalidstring::invalid_utf8("test msg");
-		let id = commit(repo_path, msg.as_str()).unwrap();
+		let id = commit(repo_path, msg.as_str(), false).unwrap();
 
 		let res = get_commit_details(repo_path, id).unwrap();
 
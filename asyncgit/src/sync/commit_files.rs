@@ -1,17 +1,43 @@
-use std::cmp::Ordering;
+use std::{
+	collections::HashSet,
+	path::{Path, PathBuf},
+};
 
-use super::{stash::is_stash_commit, utils::repo, CommitId};
+use super::{
+	large_files::blob_binary_size, stash::is_stash_commit,
+	utils::repo, CommitId,
+};
 use crate::{
 	error::Error, error::Result, StatusItem, StatusItemType,
 };
-use git2::{Diff, DiffDelta, DiffOptions, Repository};
+use git2::{Commit, Diff, DiffDelta, DiffOptions, Repository};
 use scopetime::scope_time;
 
+/// what the changes of a merge commit are compared against
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MergeDiffMode {
+	/// changes relative to the first parent
+	FirstParent,
+	/// changes relative to the second parent,
+	/// falls back to the first one for regular commits
+	SecondParent,
+	/// only files differing from every parent,
+	/// shown relative to the first parent
+	Combined,
+}
+
+impl Default for MergeDiffMode {
+	fn default() -> Self {
+		Self::FirstParent
+	}
+}
+
 /// get all files that are part of a commit
 pub fn get_commit_files(
 	repo_path: &str,
 	id: CommitId,
 	other: Option<CommitId>,
+	merge_mode: MergeDiffMode,
 ) -> Result<Vec<StatusItem>> {
 	scope_time!("get_commit_files");
 
@@ -20,20 +46,53 @@ pub fn get_commit_files(
 	let diff = if let Some(other) = other {
 		get_compare_commits_diff(&repo, (id, other), None)?
 	} else {
-		get_commit_diff(&repo, id, None)?
+		get_commit_diff(&repo, id, merge_mode, None)?
 	};
 
+	diff_files(&repo, &diff)
+}
+
+/// files changed since `id` including the uncommitted changes,
+/// see `get_commit_workdir_diff`
+pub fn get_commit_workdir_files(
+	repo_path: &str,
+	id: CommitId,
+) -> Result<Vec<StatusItem>> {
+	scope_time!("get_commit_workdir_files");
+
+	let repo = repo(repo_path)?;
+	let diff = get_commit_workdir_diff(&repo, id, None)?;
+
+	diff_files(&repo, &diff)
+}
+
+fn diff_files(
+	repo: &Repository,
+	diff: &Diff,
+) -> Result<Vec<StatusItem>> {
 	let mut res = Vec::new();
 
 	diff.foreach(
 		&mut |delta: DiffDelta<'_>, _progress| {
+			let status = StatusItemType::from(delta.status());
 			res.push(StatusItem {
 				path: delta
 					.new_file()
 					.path()
 					.map(|p| p.to_str().unwrap_or("").to_string())
 					.unwrap_or_default(),
-				status: StatusItemType::from(delta.status()),
+				old_path: delta
+					.old_file()
+					.path()
+					.filter(|_| status == StatusItemType::Renamed)
+					.and_then(|p| p.to_str())
+					.map(String::from),
+				status,
+				binary_size: if status == StatusItemType::Deleted {
+					None
+				} else {
+					blob_binary_size(repo, delta.new_file().id())
+				},
 			});
 			true
 		},
@@ -45,6 +104,7 @@ pub fn get_commit_files(
 	Ok(res)
 }
 
+/// `ids` are (old, new)
 #[allow(clippy::needless_pass_by_value)]
 pub fn get_compare_commits_diff(
 	repo: &Repository,
@@ -58,14 +118,6 @@ pub fn get_compare_commits_diff(
 		repo.find_commit(ids.1.into())?,
 	);
 
-	let commits = if commits.0.time().cmp(&commits.1.time())
-		== Ordering::Greater
-	{
-		(commits.1, commits.0)
-	} else {
-		commits
-	};
-
 	let trees = (commits.0.tree()?, commits.1.tree()?);
 
 	let mut opts = DiffOptions::new();
@@ -83,36 +135,36 @@ pub fn get_compare_commits_diff(
 	Ok(diff)
 }
 
+/// tree of `id` against the working tree (staged or not),
+/// untracked files included
+#[allow(clippy::redundant_pub_crate)]
+pub(crate) fn get_commit_workdir_diff(
+	repo: &Repository,
+	id: CommitId,
+	options: Option<git2::DiffOptions>,
+) -> Result<Diff<'_>> {
+	let tree = repo.find_commit(id.into())?.tree()?;
+
+	let mut opts = options.unwrap_or_else(|| diff_options(None));
+	opts.include_untracked(true);
+	opts.recurse_untracked_dirs(true);
+
+	Ok(repo.diff_tree_to_workdir_with_index(
+		Some(&tree),
+		Some(&mut opts),
+	)?)
+}
+
 #[allow(clippy::redundant_pub_crate)]
 pub(crate) fn get_commit_diff(
 	repo: &Repository,
 	id: CommitId,
+	merge_mode: MergeDiffMode,
 	pathspec: Option<String>,
 ) -> Result<Diff<'_>> {
 	// scope_time!("get_commit_diff");
 
 	let commit = repo.find_commit(id.into())?;
-	let commit_tree = commit.tree()?;
-
-	let parent = if commit.parent_count() > 0 {
-		repo.find_commit(commit.parent_id(0)?)
-			.ok()
-			.and_then(|c| c.tree().ok())
-	} else {
-		None
-	};
-
-	let mut opts = DiffOptions::new();
-	if let Some(p) = &pathspec {
-		opts.pathspec(p.clone());
-	}
-	opts.show_binary(true);
-
-	let mut diff = repo.diff_tree_to_tree(
-		parent.as_ref(),
-		Some(&commit_tree),
-		Some(&mut opts),
-	)?;
 
 	if is_stash_commit(
 		repo.path().to_str().map_or_else(
@@ -121,28 +173,128 @@ pub(crate) fn get_commit_diff(
 		)?,
 		&id,
 	)? {
+		// stashes keep the index and untracked files as extra parents
+		let mut diff = get_parent_diff(
+			repo,
+			&commit,
+			0,
+			&mut diff_options(pathspec.as_deref()),
+		)?;
+
 		if let Ok(untracked_commit) = commit.parent_id(2) {
 			let untracked_diff = get_commit_diff(
 				repo,
 				CommitId::new(untracked_commit),
+				MergeDiffMode::FirstParent,
 				pathspec,
 			)?;
 
 			diff.merge(&untracked_diff)?;
 		}
+
+		return Ok(diff);
 	}
 
-	Ok(diff)
+	let mut opts = diff_options(pathspec.as_deref());
+
+	match merge_mode {
+		MergeDiffMode::SecondParent if commit.parent_count() > 1 => {
+			get_parent_diff(repo, &commit, 1, &mut opts)
+		}
+		MergeDiffMode::FirstParent | MergeDiffMode::SecondParent => {
+			get_parent_diff(repo, &commit, 0, &mut opts)
+		}
+		MergeDiffMode::Combined => {
+			get_combined_diff(repo, &commit, &mut opts)
+		}
+	}
+}
+
+fn diff_options(pathspec: Option<&str>) -> DiffOptions {
+	let mut opts = DiffOptions::new();
+	if let Some(p) = pathspec {
+		opts.pathspec(p);
+	}
+	opts.show_binary(true);
+	opts
+}
+
+/// diff of `commit` against its parent at `parent` (empty tree if missing)
+fn get_parent_diff<'a>(
+	repo: &'a Repository,
+	commit: &Commit,
+	parent: usize,
+	opts: &mut DiffOptions,
+) -> Result<Diff<'a>> {
+	let parent_tree =
+		commit.parent(parent).ok().and_then(|c| c.tree().ok());
+
+	Ok(repo.diff_tree_to_tree(
+		parent_tree.as_ref(),
+		Some(&commit.tree()?),
+		Some(opts),
+	)?)
+}
+
+/// diff against the first parent limited to the files that differ
+/// from every single parent
+fn get_combined_diff<'a>(
+	repo: &'a Repository,
+	commit: &Commit,
+	opts: &mut DiffOptions,
+) -> Result<Diff<'a>> {
+	let mut common: Option<HashSet<PathBuf>> = None;
+
+	for parent in 0..commit.parent_count() {
+		let diff = get_parent_diff(repo, commit, parent, opts)?;
+		let paths = diff
+			.deltas()
+			.filter_map(|delta| delta.new_file().path())
+			.map(Path::to_path_buf)
+			.collect::<HashSet<_>>();
+
+		common = Some(match common {
+			Some(common) => {
+				common.intersection(&paths).cloned().collect()
+			}
+			None => paths,
+		});
+	}
+
+	let common = common.unwrap_or_default();
+
+	let mut opts = diff_options(None);
+	opts.disable_pathspec_match(true);
+
+	if common.is_empty() {
+		// an empty pathspec would match everything
+		let tree = commit.tree()?;
+		return Ok(repo.diff_tree_to_tree(
+			Some(&tree),
+			Some(&tree),
+			Some(&mut opts),
+		)?);
+	}
+
+	for path in &common {
+		opts.pathspec(path.as_path());
+	}
+
+	get_parent_diff(repo, commit, 0, &mut opts)
 }
 
 #[cfg(test)]
 mod tests {
-	use super::get_commit_files;
+	use super::{
+		get_commit_files, get_commit_workdir_files, MergeDiffMode,
+	};
 	use crate::{
 		error::Result,
 		sync::{
-			commit, stage_add_file, stash_save,
-			tests::{get_statuses, repo_init},
+			checkout_branch, commit, create_branch,
+			get_commit_details, merge_branch, merge_commit,
+			stage_add_file, stash_save,
+			tests::{get_statuses, repo_init, write_commit_file},
 		},
 		StatusItemType,
 	};
@@ -160,9 +312,14 @@ mod tests {
 
 		stage_add_file(repo_path, file_path)?;
 
-		let id = commit(repo_path, "commit msg")?;
+		let id = commit(repo_path, "commit msg", false)?;
 
-		let diff = get_commit_files(repo_path, id, None)?;
+		let diff = get_commit_files(
+			repo_path,
+			id,
+			None,
+			MergeDiffMode::FirstParent,
+		)?;
 
 		assert_eq!(diff.len(), 1);
 		assert_eq!(diff[0].status, StatusItemType::New);
@@ -182,7 +339,12 @@ mod tests {
 
 		let id = stash_save(repo_path, None, true, false)?;
 
-		let diff = get_commit_files(repo_path, id, None)?;
+		let diff = get_commit_files(
+			repo_path,
+			id,
+			None,
+			MergeDiffMode::FirstParent,
+		)?;
 
 		assert_eq!(diff.len(), 1);
 		assert_eq!(diff[0].status, StatusItemType::New);
@@ -200,7 +362,7 @@ mod tests {
 
 		File::create(&root.join(file_path1))?.write_all(b"test")?;
 		stage_add_file(repo_path, file_path1)?;
-		commit(repo_path, "c1")?;
+		commit(repo_path, "c1", false)?;
 
 		File::create(&root.join(file_path1))?
 			.write_all(b"modified")?;
@@ -210,7 +372,12 @@ mod tests {
 
 		let id = stash_save(repo_path, None, true, false)?;
 
-		let diff = get_commit_files(repo_path, id, None)?;
+		let diff = get_commit_files(
+			repo_path,
+			id,
+			None,
+			MergeDiffMode::FirstParent,
+		)?;
 
 		assert_eq!(diff.len(), 2);
 		assert_eq!(diff[0].status, StatusItemType::Modified);
@@ -218,4 +385,105 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_merge_modes() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		create_branch(repo_path, "foo")?;
+		let foo = write_commit_file(&repo, "b.txt", "b", "on foo");
+
+		checkout_branch(repo_path, "refs/heads/master")?;
+		write_commit_file(&repo, "c.txt", "c", "on master");
+
+		merge_branch(repo_path, "foo")?;
+
+		// touched by the merge itself, so it differs from both parents
+		File::create(&root.join("d.txt"))?.write_all(b"d")?;
+		stage_add_file(repo_path, Path::new("d.txt"))?;
+
+		let id = merge_commit(repo_path, "merge", &[foo])?;
+
+		assert!(get_commit_details(repo_path, id)?.is_merge);
+
+		let paths = |mode| -> Result<Vec<String>> {
+			Ok(get_commit_files(repo_path, id, None, mode)?
+				.into_iter()
+				.map(|item| item.path)
+				.collect())
+		};
+
+		assert_eq!(
+			paths(MergeDiffMode::FirstParent)?,
+			vec![String::from("b.txt"), String::from("d.txt")]
+		);
+		assert_eq!(
+			paths(MergeDiffMode::SecondParent)?,
+			vec![String::from("c.txt"), String::from("d.txt")]
+		);
+		assert_eq!(
+			paths(MergeDiffMode::Combined)?,
+			vec![String::from("d.txt")]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_merge_modes_single_parent() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+
+		let id = write_commit_file(&repo, "a.txt", "a", "commit");
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		for mode in &[
+			MergeDiffMode::FirstParent,
+			MergeDiffMode::SecondParent,
+			MergeDiffMode::Combined,
+		] {
+			let files = get_commit_files(repo_path, id, None, *mode)?;
+
+			assert_eq!(files.len(), 1);
+			assert_eq!(files[0].path, "a.txt");
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_workdir_files() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let base = write_commit_file(&repo, "a.txt", "a", "base");
+		write_commit_file(&repo, "b.txt", "b", "committed");
+
+		// staged, modified and untracked on top
+		File::create(root.join("c.txt"))?.write_all(b"c")?;
+		stage_add_file(repo_path, Path::new("c.txt"))?;
+		File::create(root.join("a.txt"))?.write_all(b"changed")?;
+		File::create(root.join("d.txt"))?.write_all(b"d")?;
+
+		let mut files = get_commit_workdir_files(repo_path, base)?
+			.into_iter()
+			.map(|item| (item.path, item.status))
+			.collect::<Vec<_>>();
+		files.sort_by(|a, b| a.0.cmp(&b.0));
+
+		assert_eq!(
+			files,
+			vec![
+				(String::from("a.txt"), StatusItemType::Modified),
+				(String::from("b.txt"), StatusItemType::New),
+				(String::from("c.txt"), StatusItemType::New),
+				(String::from("d.txt"), StatusItemType::New),
+			]
+		);
+
+		Ok(())
+	}
 }
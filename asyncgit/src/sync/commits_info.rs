@@ -1,6 +1,10 @@
-use super::utils::repo;
+use super::{
+	abbreviate_oid,
+	mailmap::{mailmap_repo, resolve},
+	utils::repo,
+};
 use crate::error::Result;
-use git2::{Commit, Error, Oid};
+use git2::{Commit, Oid};
 use scopetime::scope_time;
 use unicode_truncate::UnicodeTruncateStr;
 
@@ -16,6 +20,11 @@ impl CommitId {
 		Self(id)
 	}
 
+	/// parses a full hex hash
+	pub fn from_hex(hex: &str) -> Result<Self> {
+		Ok(Self(Oid::from_str(hex)?))
+	}
+
 	///
 	pub(crate) const fn get_oid(self) -> Oid {
 		self.0
@@ -25,6 +34,13 @@ impl CommitId {
 	pub fn get_short_string(&self) -> String {
 		self.to_string().chars().take(7).collect()
 	}
+
+	/// short hash as of `abbreviate_oid`,
+	/// 7 chars if the repo cannot be read
+	pub fn abbreviated(&self, repo_path: &str) -> String {
+		abbreviate_oid(repo_path, *self)
+			.unwrap_or_else(|_| self.get_short_string())
+	}
 }
 
 //TODO: remove once clippy fixed: https://github.com/rust-lang/rust-clippy/issues/6983
@@ -48,18 +64,42 @@ impl From<Oid> for CommitId {
 }
 
 ///
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CommitInfo {
 	///
 	pub message: String,
-	///
+	/// committer time in seconds since epoch
 	pub time: i64,
+	/// committer time offset to utc in minutes
+	pub time_offset: i32,
+	/// author time in seconds since epoch
+	pub author_time: i64,
+	/// author time offset to utc in minutes
+	pub author_time_offset: i32,
 	///
 	pub author: String,
 	///
 	pub id: CommitId,
 }
 
+impl CommitInfo {
+	/// stands in for a commit whose object cannot be read
+	pub fn unreadable(id: CommitId) -> Self {
+		Self {
+			message: format!(
+				"<unreadable object {}>",
+				id.get_short_string()
+			),
+			time: 0,
+			time_offset: 0,
+			author_time: 0,
+			author_time_offset: 0,
+			author: String::new(),
+			id,
+		}
+	}
+}
+
 ///
 pub fn get_commits_info(
 	repo_path: &str,
@@ -69,25 +109,36 @@ pub fn get_commits_info(
 	scope_time!("get_commits_info");
 
 	let repo = repo(repo_path)?;
+	let mailmap = mailmap_repo(&repo)?;
 
-	let commits = ids
+	let res = ids
 		.iter()
-		.map(|id| repo.find_commit((*id).into()))
-		.collect::<std::result::Result<Vec<Commit>, Error>>()?
-		.into_iter();
-
-	let res = commits
-		.map(|c: Commit| {
-			let message = get_message(&c, Some(message_length_limit));
-			let author = c.author().name().map_or_else(
-				|| String::from("<unknown>"),
-				String::from,
-			);
-			CommitInfo {
-				message,
-				author,
-				time: c.time().seconds(),
-				id: CommitId(c.id()),
+		.map(|id| match repo.find_commit((*id).into()) {
+			Ok(c) => {
+				let message =
+					get_message(&c, Some(message_length_limit));
+				let author = resolve(&mailmap, &c.author())
+					.name()
+					.map_or_else(
+						|| String::from("<unknown>"),
+						String::from,
+					);
+				CommitInfo {
+					message,
+					author,
+					time: c.time().seconds(),
+					time_offset: c.time().offset_minutes(),
+					author_time: c.author().when().seconds(),
+					author_time_offset: c
+						.author()
+						.when()
+						.offset_minutes(),
+					id: CommitId(c.id()),
+				}
+			}
+			Err(e) => {
+				log::warn!("unreadable commit {}: {}", id.0, e);
+				CommitInfo::unreadable(*id)
 			}
 		})
 		.collect::<Vec<_>>();
@@ -105,12 +156,16 @@ pub fn get_commit_info(
 	let repo = repo(repo_path)?;
 
 	let commit = repo.find_commit((*commit_id).into())?;
-	let author = commit.author();
+	let mailmap = mailmap_repo(&repo)?;
+	let author = resolve(&mailmap, &commit.author());
 
 	Ok(CommitInfo {
 		message: commit.message().unwrap_or("").into(),
 		author: author.name().unwrap_or("<unknown>").into(),
 		time: commit.time().seconds(),
+		time_offset: commit.time().offset_minutes(),
+		author_time: author.when().seconds(),
+		author_time_offset: author.when().offset_minutes(),
 		id: CommitId(commit.id()),
 	})
 }
@@ -152,10 +207,10 @@ mod tests {
 
 		File::create(&root.join(file_path))?.write_all(b"a")?;
 		stage_add_file(repo_path, file_path).unwrap();
-		let c1 = commit(repo_path, "commit1").unwrap();
+		let c1 = commit(repo_path, "commit1", false).unwrap();
 		File::create(&root.join(file_path))?.write_all(b"a")?;
 		stage_add_file(repo_path, file_path).unwrap();
-		let c2 = commit(repo_path, "commit2").unwrap();
+		let c2 = commit(repo_path, "commit2", true).unwrap();
 
 		let res =
 			get_commits_info(repo_path, &vec![c2, c1], 50).unwrap();
@@ -177,7 +232,7 @@ mod tests {
 
 		File::create(&root.join(file_path))?.write_all(b"a")?;
 		stage_add_file(repo_path, file_path).unwrap();
-		let c1 = commit(repo_path, "subject\nbody").unwrap();
+		let c1 = commit(repo_path, "subject\nbody", false).unwrap();
 
 		let res = get_commits_info(repo_path, &vec![c1], 50).unwrap();
 
@@ -198,7 +253,7 @@ mod tests {
 		stage_add_file(repo_path, file_path).unwrap();
 
 		let msg = invalidstring::invalid_utf8("test msg");
-		commit(repo_path, msg.as_str()).unwrap();
+		commit(repo_path, msg.as_str(), false).unwrap();
 
 		let res = get_commits_info(
 			repo_path,
@@ -1,7 +1,12 @@
+//! reading and writing git config values
 use super::utils::repo;
-use crate::error::Result;
-use git2::Repository;
+use crate::error::{Error, Result};
+use git2::{Config, ConfigLevel, ErrorCode, Repository};
 use scopetime::scope_time;
+use std::{
+	env,
+	path::{Path, PathBuf},
+};
 
 // see https://git-scm.com/docs/git-config#Documentation/git-config.txt-statusshowUntrackedFiles
 /// represents the `status.showUntrackedFiles` git config state
@@ -38,6 +43,7 @@ impl ShowUntrackedFilesConfig {
 	}
 }
 
+///
 pub fn untracked_files_config_repo(
 	repo: &Repository,
 ) -> Result<ShowUntrackedFilesConfig> {
@@ -72,6 +78,7 @@ pub fn get_config_string(
 	get_config_string_repo(&repo, key)
 }
 
+/// get string from config of an already opened `repo`
 pub fn get_config_string_repo(
 	repo: &Repository,
 	key: &str,
@@ -96,10 +103,231 @@ pub fn get_config_string_repo(
 	}
 }
 
+/// get all values of a multi-valued config entry
+pub fn get_config_strings(
+	repo_path: &str,
+	key: &str,
+) -> Result<Vec<String>> {
+	scope_time!("get_config_strings");
+
+	let repo = repo(repo_path)?;
+	let cfg = repo.config()?;
+
+	let mut res = Vec::new();
+	let entries = cfg.multivar(key, None)?;
+	for entry in &entries {
+		if let Some(value) = entry?.value() {
+			res.push(value.to_string());
+		}
+	}
+
+	Ok(res)
+}
+
+/// values of `key` in the global and system config only, for
+/// keys like `safe.directory` that git ignores in repo config
+pub fn get_config_strings_global(key: &str) -> Result<Vec<String>> {
+	scope_time!("get_config_strings_global");
+
+	let mut cfg = Config::open_default()?;
+
+	if let Some(path) = env::var_os(GLOBAL_CONFIG_ENV) {
+		cfg.add_file(Path::new(&path), ConfigLevel::Global, true)?;
+	}
+
+	let mut res = Vec::new();
+	let entries = cfg.multivar(key, None)?;
+	for entry in &entries {
+		if let Some(value) = entry?.value() {
+			res.push(value.to_string());
+		}
+	}
+
+	Ok(res)
+}
+
+/// overrides the path of the global config, like git does
+const GLOBAL_CONFIG_ENV: &str = "GIT_CONFIG_GLOBAL";
+
+/// which config file a value comes from or gets written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigScope {
+	/// the repo's own `.git/config`
+	Local,
+	/// the user's `~/.gitconfig`
+	Global,
+	/// the installation wide config, only ever read
+	System,
+}
+
+/// a config value along with the scope it is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopedValue {
+	///
+	pub value: String,
+	///
+	pub scope: ConfigScope,
+}
+
+/// `~/.gitconfig` (or `GIT_CONFIG_GLOBAL`), even if it does not
+/// exist yet
+fn global_config_path() -> Result<PathBuf> {
+	if let Some(path) = env::var_os(GLOBAL_CONFIG_ENV) {
+		return Ok(PathBuf::from(path));
+	}
+
+	if let Ok(path) = Config::find_global() {
+		return Ok(path);
+	}
+
+	let home = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+	env::var_os(home)
+		.map(|home| PathBuf::from(home).join(".gitconfig"))
+		.ok_or_else(|| {
+			Error::Generic(String::from("home directory not found"))
+		})
+}
+
+fn open_config(
+	repo_path: &str,
+	scope: ConfigScope,
+) -> Result<Config> {
+	Ok(match scope {
+		ConfigScope::Local => repo(repo_path)?
+			.config()?
+			.open_level(ConfigLevel::Local)?,
+		ConfigScope::Global => Config::open(&global_config_path()?)?,
+		ConfigScope::System => {
+			return Err(Error::Generic(String::from(
+				"system config is read-only",
+			)))
+		}
+	})
+}
+
+/// all levels of the repo config, with the global one honoring
+/// `GIT_CONFIG_GLOBAL` which libgit2 ignores
+fn scoped_config(repo: &Repository) -> Result<Config> {
+	let mut cfg = repo.config()?;
+
+	if let Some(path) = env::var_os(GLOBAL_CONFIG_ENV) {
+		cfg.add_file(Path::new(&path), ConfigLevel::Global, true)?;
+	}
+
+	Ok(cfg)
+}
+
+const fn scope_of(level: ConfigLevel) -> ConfigScope {
+	match level {
+		ConfigLevel::ProgramData | ConfigLevel::System => {
+			ConfigScope::System
+		}
+		ConfigLevel::XDG | ConfigLevel::Global => ConfigScope::Global,
+		ConfigLevel::Local
+		| ConfigLevel::App
+		| ConfigLevel::Highest => ConfigScope::Local,
+	}
+}
+
+/// effective value of `key` and the scope it is set in
+pub fn get_with_scope(
+	repo_path: &str,
+	key: &str,
+) -> Result<Option<ScopedValue>> {
+	scope_time!("get_with_scope");
+
+	let repo = repo(repo_path)?;
+	let cfg = scoped_config(&repo)?;
+
+	let entry = match cfg.get_entry(key) {
+		Ok(entry) if entry.has_value() => entry,
+		_ => return Ok(None),
+	};
+
+	Ok(entry.value().map(|value| ScopedValue {
+		value: value.to_string(),
+		scope: scope_of(entry.level()),
+	}))
+}
+
+/// set `key` to `value` in the config file of `scope`
+pub fn set_string_scoped(
+	repo_path: &str,
+	key: &str,
+	value: &str,
+	scope: ConfigScope,
+) -> Result<()> {
+	scope_time!("set_string_scoped");
+
+	open_config(repo_path, scope)?.set_str(key, value)?;
+
+	Ok(())
+}
+
+/// interprets `value` like git does for boolean keys, `None` if it
+/// is no boolean (like `pull.rebase = merges`)
+pub fn parse_bool(value: &str) -> Option<bool> {
+	Config::parse_bool(value).ok()
+}
+
+/// remove `key` from the config file of `scope`, nothing happens if
+/// it is not set there
+pub fn unset(
+	repo_path: &str,
+	key: &str,
+	scope: ConfigScope,
+) -> Result<()> {
+	scope_time!("config_unset");
+
+	match open_config(repo_path, scope)?.remove(key) {
+		Err(e) if e.code() == ErrorCode::NotFound => Ok(()),
+		res => res.map_err(Into::into),
+	}
+}
+
+/// add `value` to the multi-valued `key` in the config file of
+/// `scope`, nothing happens if it is there already
+pub fn add_config_string(
+	repo_path: &str,
+	key: &str,
+	value: &str,
+	scope: ConfigScope,
+) -> Result<()> {
+	scope_time!("add_config_string");
+
+	let mut cfg = open_config(repo_path, scope)?;
+
+	let mut known = false;
+	if let Ok(entries) = cfg.multivar(key, None) {
+		for entry in &entries {
+			known |= entry?.value() == Some(value);
+		}
+	}
+
+	if !known {
+		// matches no value, so it is added
+		cfg.set_multivar(key, "^$", value)?;
+	}
+
+	Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use crate::sync::tests::repo_init;
+	use serial_test::serial;
+	use tempfile::TempDir;
+
+	/// points `GIT_CONFIG_GLOBAL` to a new file with `content`
+	fn temp_global_config(content: &str) -> TempDir {
+		let td = TempDir::new().unwrap();
+		let path = td.path().join("gitconfig");
+		std::fs::write(&path, content).unwrap();
+		env::set_var(GLOBAL_CONFIG_ENV, &path);
+
+		td
+	}
 
 	#[test]
 	fn test_get_config() {
@@ -118,4 +346,200 @@ mod tests {
 		assert!(good_cfg.is_ok());
 		assert!(good_cfg.unwrap().is_some());
 	}
+
+	#[test]
+	fn test_get_config_strings() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		assert!(get_config_strings(repo_path, "gitui.commitTrailer")
+			.unwrap()
+			.is_empty());
+
+		let mut config = repo.config().unwrap();
+		config
+			.set_multivar("gitui.commitTrailer", "^$", "Team: core")
+			.unwrap();
+		config
+			.set_multivar(
+				"gitui.commitTrailer",
+				"^$",
+				"Change-Id: I0",
+			)
+			.unwrap();
+
+		assert_eq!(
+			get_config_strings(repo_path, "gitui.commitTrailer")
+				.unwrap(),
+			vec![
+				String::from("Team: core"),
+				String::from("Change-Id: I0")
+			]
+		);
+	}
+
+	#[test]
+	fn test_set_string_scoped() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		set_string_scoped(
+			repo_path,
+			"user.name",
+			"other",
+			ConfigScope::Local,
+		)
+		.unwrap();
+		set_string_scoped(
+			repo_path,
+			"core.editor",
+			"vim",
+			ConfigScope::Local,
+		)
+		.unwrap();
+
+		assert_eq!(
+			get_config_string(repo_path, "user.name").unwrap(),
+			Some(String::from("other"))
+		);
+		assert_eq!(
+			get_config_string(repo_path, "core.editor").unwrap(),
+			Some(String::from("vim"))
+		);
+	}
+
+	#[test]
+	fn test_add_config_string() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		for value in &["/a", "/b", "/a"] {
+			add_config_string(
+				repo_path,
+				"safe.directory",
+				value,
+				ConfigScope::Local,
+			)
+			.unwrap();
+		}
+
+		assert_eq!(
+			get_config_strings(repo_path, "safe.directory").unwrap(),
+			vec![String::from("/a"), String::from("/b")]
+		);
+	}
+
+	#[test]
+	#[serial]
+	fn test_get_config_strings_global() {
+		let _global =
+			temp_global_config("[safe]\n\tdirectory = /global\n");
+		let (_td, repo) = repo_init().unwrap();
+
+		repo.config()
+			.unwrap()
+			.set_multivar("safe.directory", "^$", "/local")
+			.unwrap();
+
+		assert_eq!(
+			get_config_strings_global("safe.directory").unwrap(),
+			vec![String::from("/global")]
+		);
+	}
+
+	#[test]
+	#[serial]
+	fn test_get_with_scope() {
+		let _global = temp_global_config(
+			"[user]\n\tname = global\n[core]\n\teditor = nano\n",
+		);
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		// repo init sets user.name locally, shadowing the global one
+		assert_eq!(
+			get_with_scope(repo_path, "user.name").unwrap(),
+			Some(ScopedValue {
+				value: String::from("name"),
+				scope: ConfigScope::Local,
+			})
+		);
+		assert_eq!(
+			get_with_scope(repo_path, "core.editor").unwrap(),
+			Some(ScopedValue {
+				value: String::from("nano"),
+				scope: ConfigScope::Global,
+			})
+		);
+		assert_eq!(
+			get_with_scope(repo_path, "push.default").unwrap(),
+			None
+		);
+	}
+
+	#[test]
+	#[serial]
+	fn test_set_unset_scoped() {
+		let _global = temp_global_config("");
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let get = || {
+			get_with_scope(repo_path, "pull.rebase")
+				.unwrap()
+				.map(|v| (v.value, v.scope))
+		};
+
+		set_string_scoped(
+			repo_path,
+			"pull.rebase",
+			"true",
+			ConfigScope::Global,
+		)
+		.unwrap();
+		assert_eq!(
+			get(),
+			Some((String::from("true"), ConfigScope::Global))
+		);
+
+		set_string_scoped(
+			repo_path,
+			"pull.rebase",
+			"false",
+			ConfigScope::Local,
+		)
+		.unwrap();
+		assert_eq!(
+			get(),
+			Some((String::from("false"), ConfigScope::Local))
+		);
+
+		// the inherited value shows up again
+		unset(repo_path, "pull.rebase", ConfigScope::Local).unwrap();
+		assert_eq!(
+			get(),
+			Some((String::from("true"), ConfigScope::Global))
+		);
+
+		unset(repo_path, "pull.rebase", ConfigScope::Global).unwrap();
+		assert_eq!(get(), None);
+
+		// unsetting what is not set is fine
+		unset(repo_path, "pull.rebase", ConfigScope::Local).unwrap();
+
+		assert!(set_string_scoped(
+			repo_path,
+			"pull.rebase",
+			"true",
+			ConfigScope::System,
+		)
+		.is_err());
+		assert!(unset(repo_path, "pull.rebase", ConfigScope::System)
+			.is_err());
+	}
 }
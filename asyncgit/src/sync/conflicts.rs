@@ -0,0 +1,268 @@
+//! the versions of a conflicted file kept in the index
+
+use super::{
+	index_lock::retry_on_index_lock,
+	utils::{repo, work_dir},
+};
+use crate::error::{Error, Result};
+use git2::{Index, Repository};
+use scopetime::scope_time;
+use std::{fs, io, path::Path};
+
+const STAGE_BASE: i32 = 1;
+const STAGE_OURS: i32 = 2;
+const STAGE_THEIRS: i32 = 3;
+
+/// side of a conflict a file is resolved to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictSide {
+	/// `HEAD`, what the stash is applied to or the merge happens on
+	Ours,
+	/// the stash applied or the branch merged in
+	Theirs,
+}
+
+/// raw content of each stage of a conflicted file, `None` where the
+/// side has no version of it: not in the base if added on both
+/// sides, else deleted by that side
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ConflictStages {
+	///
+	pub base: Option<Vec<u8>>,
+	///
+	pub ours: Option<Vec<u8>>,
+	///
+	pub theirs: Option<Vec<u8>>,
+}
+
+fn stage_blob(
+	repo: &Repository,
+	index: &Index,
+	path: &Path,
+	stage: i32,
+) -> Result<Option<Vec<u8>>> {
+	index
+		.get_path(path, stage)
+		.map(|entry| Ok(repo.find_blob(entry.id)?.content().to_vec()))
+		.transpose()
+}
+
+fn is_conflicted(index: &Index, path: &Path) -> bool {
+	[STAGE_BASE, STAGE_OURS, STAGE_THEIRS]
+		.iter()
+		.any(|stage| index.get_path(path, *stage).is_some())
+}
+
+/// base, ours and theirs of the conflicted `file_path`
+pub fn conflict_stages(
+	repo_path: &str,
+	file_path: &str,
+) -> Result<ConflictStages> {
+	scope_time!("conflict_stages");
+
+	let repo = repo(repo_path)?;
+	let index = repo.index()?;
+	let path = Path::new(file_path);
+
+	if !is_conflicted(&index, path) {
+		return Err(Error::Generic(format!(
+			"'{}' is not conflicted",
+			file_path
+		)));
+	}
+
+	Ok(ConflictStages {
+		base: stage_blob(&repo, &index, path, STAGE_BASE)?,
+		ours: stage_blob(&repo, &index, path, STAGE_OURS)?,
+		theirs: stage_blob(&repo, &index, path, STAGE_THEIRS)?,
+	})
+}
+
+/// resolves the conflicted `file_path` by taking the whole file
+/// from `side`, the working copy and any manual resolution in it is
+/// overwritten. a side that deleted the file deletes it
+pub fn resolve_conflict(
+	repo_path: &str,
+	file_path: &str,
+	side: ConflictSide,
+) -> Result<()> {
+	scope_time!("resolve_conflict");
+
+	let repo = repo(repo_path)?;
+	let path = Path::new(file_path);
+	let full_path = work_dir(&repo)?.join(path);
+
+	retry_on_index_lock(repo_path, || {
+		let mut index = repo.index()?;
+
+		if !is_conflicted(&index, path) {
+			return Err(Error::Generic(format!(
+				"'{}' is not conflicted",
+				file_path
+			)));
+		}
+
+		let stage = match side {
+			ConflictSide::Ours => STAGE_OURS,
+			ConflictSide::Theirs => STAGE_THEIRS,
+		};
+
+		if let Some(content) = stage_blob(&repo, &index, path, stage)?
+		{
+			fs::write(&full_path, content)?;
+			index.add_path(path)?;
+		} else {
+			match fs::remove_file(&full_path) {
+				Err(e) if e.kind() != io::ErrorKind::NotFound => {
+					return Err(e.into());
+				}
+				_ => (),
+			}
+			index.remove_path(path)?;
+		}
+
+		index.write()?;
+
+		Ok(())
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		checkout_branch, create_branch, stash_apply, stash_save,
+		tests::{debug_cmd_print, repo_init, write_commit_file},
+		utils::repo_write_file,
+	};
+	use git2::Repository;
+	use tempfile::TempDir;
+
+	fn init() -> (TempDir, Repository, String) {
+		let (td, repo) = repo_init().unwrap();
+		let repo_path = repo
+			.path()
+			.parent()
+			.unwrap()
+			.to_str()
+			.unwrap()
+			.to_string();
+
+		(td, repo, repo_path)
+	}
+
+	/// `foo` deletes `test.txt`, `master` changes it
+	fn deleted_by_them() -> (TempDir, Repository, String) {
+		let (td, repo, repo_path) = init();
+
+		write_commit_file(&repo, "test.txt", "base\n", "c1");
+		create_branch(&repo_path, "foo").unwrap();
+		debug_cmd_print(&repo_path, "git rm test.txt");
+		debug_cmd_print(&repo_path, "git commit -m deleted");
+		checkout_branch(&repo_path, "refs/heads/master").unwrap();
+		write_commit_file(&repo, "test.txt", "changed\n", "c2");
+
+		debug_cmd_print(&repo_path, "git merge foo");
+
+		(td, repo, repo_path)
+	}
+
+	fn read_file(repo_path: &str) -> Option<String> {
+		fs::read_to_string(Path::new(repo_path).join("test.txt")).ok()
+	}
+
+	fn stages(
+		base: Option<&str>,
+		ours: Option<&str>,
+		theirs: Option<&str>,
+	) -> ConflictStages {
+		let blob = |s: Option<&str>| s.map(|s| s.as_bytes().to_vec());
+
+		ConflictStages {
+			base: blob(base),
+			ours: blob(ours),
+			theirs: blob(theirs),
+		}
+	}
+
+	#[test]
+	fn test_stash_apply_conflict() {
+		let (_td, repo, repo_path) = init();
+
+		write_commit_file(&repo, "test.txt", "base\n", "c1");
+		repo_write_file(&repo, "test.txt", "stashed\n").unwrap();
+		let stash =
+			stash_save(&repo_path, None, false, false).unwrap();
+		write_commit_file(&repo, "test.txt", "committed\n", "c2");
+
+		stash_apply(&repo_path, stash, true).unwrap();
+
+		assert_eq!(
+			conflict_stages(&repo_path, "test.txt").unwrap(),
+			stages(
+				Some("base\n"),
+				Some("committed\n"),
+				Some("stashed\n")
+			)
+		);
+
+		resolve_conflict(
+			&repo_path,
+			"test.txt",
+			ConflictSide::Theirs,
+		)
+		.unwrap();
+
+		assert!(!repo.index().unwrap().has_conflicts());
+		assert_eq!(read_file(&repo_path).unwrap(), "stashed\n");
+		assert!(conflict_stages(&repo_path, "test.txt").is_err());
+	}
+
+	#[test]
+	fn test_deleted_by_them() {
+		let (_td, repo, repo_path) = deleted_by_them();
+
+		assert_eq!(
+			conflict_stages(&repo_path, "test.txt").unwrap(),
+			stages(Some("base\n"), Some("changed\n"), None)
+		);
+
+		resolve_conflict(&repo_path, "test.txt", ConflictSide::Ours)
+			.unwrap();
+		assert_eq!(read_file(&repo_path).unwrap(), "changed\n");
+		assert!(!repo.index().unwrap().has_conflicts());
+	}
+
+	#[test]
+	fn test_take_deleting_side() {
+		let (_td, repo, repo_path) = deleted_by_them();
+
+		resolve_conflict(
+			&repo_path,
+			"test.txt",
+			ConflictSide::Theirs,
+		)
+		.unwrap();
+
+		let index = repo.index().unwrap();
+		assert!(!index.has_conflicts());
+		assert!(index.get_path(Path::new("test.txt"), 0).is_none());
+		assert_eq!(read_file(&repo_path), None);
+	}
+
+	#[test]
+	fn test_not_conflicted() {
+		let (_td, repo, repo_path) = init();
+
+		write_commit_file(&repo, "test.txt", "base\n", "c1");
+
+		assert!(conflict_stages(&repo_path, "test.txt").is_err());
+		assert!(resolve_conflict(
+			&repo_path,
+			"test.txt",
+			ConflictSide::Ours
+		)
+		.is_err());
+		assert_eq!(read_file(&repo_path).unwrap(), "base\n");
+	}
+}
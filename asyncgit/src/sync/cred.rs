@@ -33,11 +33,18 @@ impl BasicAuthCredential {
 /// know if username and password are needed for this url
 pub fn need_username_password() -> Result<bool> {
 	let repo = crate::sync::utils::repo(CWD)?;
-	let url = repo
-		.find_remote(&get_default_remote_in_repo(&repo)?)?
-		.url()
-		.ok_or(Error::UnknownRemote)?
-		.to_owned();
+	need_username_password_for_remote(
+		CWD,
+		&get_default_remote_in_repo(&repo)?,
+	)
+}
+
+/// same as `need_username_password` for a specific `remote`
+pub fn need_username_password_for_remote(
+	repo_path: &str,
+	remote: &str,
+) -> Result<bool> {
+	let url = remote_url(repo_path, remote)?;
 	let is_http = url.starts_with("http");
 	Ok(is_http)
 }
@@ -45,11 +52,18 @@ pub fn need_username_password() -> Result<bool> {
 /// extract username and password
 pub fn extract_username_password() -> Result<BasicAuthCredential> {
 	let repo = crate::sync::utils::repo(CWD)?;
-	let url = repo
-		.find_remote(&get_default_remote_in_repo(&repo)?)?
-		.url()
-		.ok_or(Error::UnknownRemote)?
-		.to_owned();
+	extract_username_password_for_remote(
+		CWD,
+		&get_default_remote_in_repo(&repo)?,
+	)
+}
+
+/// same as `extract_username_password` for a specific `remote`
+pub fn extract_username_password_for_remote(
+	repo_path: &str,
+	remote: &str,
+) -> Result<BasicAuthCredential> {
+	let url = remote_url(repo_path, remote)?;
 	let mut helper = CredentialHelper::new(&url);
 
 	if let Ok(config) = Config::open_default() {
@@ -63,6 +77,16 @@ pub fn extract_username_password() -> Result<BasicAuthCredential> {
 	})
 }
 
+fn remote_url(repo_path: &str, remote: &str) -> Result<String> {
+	let repo = crate::sync::utils::repo(repo_path)?;
+	let url = repo
+		.find_remote(remote)?
+		.url()
+		.ok_or(Error::UnknownRemote)?
+		.to_owned();
+	Ok(url)
+}
+
 /// extract credentials from url
 pub fn extract_cred_from_url(url: &str) -> BasicAuthCredential {
 	if let Ok(url) = url::Url::parse(url) {
@@ -1,17 +1,25 @@
 //! sync git api for fetching a diff
 
 use super::{
-	commit_files::{get_commit_diff, get_compare_commits_diff},
+	commit_files::{
+		get_commit_diff, get_commit_workdir_diff,
+		get_compare_commits_diff, MergeDiffMode,
+	},
+	lfs::{lfs_pointer, LfsPointer, POINTER_MAX_SIZE},
 	utils::{self, get_head_repo, work_dir},
 	CommitId,
 };
 use crate::{error::Error, error::Result, hash};
 use easy_cast::Conv;
 use git2::{
-	Delta, Diff, DiffDelta, DiffFormat, DiffHunk, Patch, Repository,
+	Delta, Diff, DiffDelta, DiffFile, DiffFindOptions, DiffFormat,
+	DiffHunk, FileMode, Patch, Repository,
+};
+use scopetime::{scope_stats, scope_time};
+use std::{
+	cell::RefCell, convert::TryFrom, fs, io, ops::Range, path::Path,
+	rc::Rc,
 };
-use scopetime::scope_time;
-use std::{cell::RefCell, fs, path::Path, rc::Rc};
 
 /// type of diff of a single line
 #[derive(Copy, Clone, PartialEq, Hash, Debug)]
@@ -100,6 +108,57 @@ impl From<DiffHunk<'_>> for HunkHeader {
 	}
 }
 
+/// `start,lines` of a hunk header, the count is optional for one line
+fn parse_header_range(range: &str) -> Option<(u32, u32)> {
+	let mut parts = range.splitn(2, ',');
+	let start = parts.next()?.parse().ok()?;
+	let lines = match parts.next() {
+		Some(lines) => lines.parse().ok()?,
+		None => 1,
+	};
+
+	Some((start, lines))
+}
+
+fn header_range(start: u32, lines: u32) -> String {
+	if lines == 1 {
+		start.to_string()
+	} else {
+		format!("{},{}", start, lines)
+	}
+}
+
+impl HunkHeader {
+	/// from the text of a header line (`@@ -1,5 +1,6 @@ fn foo`)
+	fn parse(header: &str) -> Option<Self> {
+		let mut parts = header.split_whitespace();
+		if parts.next()? != "@@" {
+			return None;
+		}
+
+		let (old_start, old_lines) =
+			parse_header_range(parts.next()?.strip_prefix('-')?)?;
+		let (new_start, new_lines) =
+			parse_header_range(parts.next()?.strip_prefix('+')?)?;
+
+		Some(Self {
+			old_start,
+			old_lines,
+			new_start,
+			new_lines,
+		})
+	}
+
+	/// header line, without any function context
+	pub(crate) fn text(&self) -> String {
+		format!(
+			"@@ -{} +{} @@",
+			header_range(self.old_start, self.old_lines),
+			header_range(self.new_start, self.new_lines)
+		)
+	}
+}
+
 /// single diff hunk
 #[derive(Default, Clone, Hash, Debug)]
 pub struct Hunk {
@@ -107,6 +166,154 @@ pub struct Hunk {
 	pub header_hash: u64,
 	/// list of `DiffLine`s
 	pub lines: Vec<DiffLine>,
+	/// `header_hash` of the hunk this part was split off,
+	/// see `split_hunk`
+	pub split_from: Option<u64>,
+}
+
+/// line ranges (into `lines`, the lines of a hunk without its
+/// header) of the parts a hunk splits into, with their headers: each
+/// run of added and deleted lines together with the context lines
+/// before it, the last part keeps the trailing context as well.
+/// `None` if there are no context lines between changes to split at
+pub(crate) fn split_hunk_lines(
+	header: HunkHeader,
+	lines: &[DiffLine],
+) -> Option<Vec<(HunkHeader, Range<usize>)>> {
+	let mut starts = Vec::new();
+	let mut run_end = 0;
+	let mut in_run = false;
+
+	for (idx, line) in lines.iter().enumerate() {
+		let change = line.line_type != DiffLineType::None;
+
+		if change && !in_run {
+			starts.push(if starts.is_empty() { 0 } else { run_end });
+		} else if !change && in_run {
+			run_end = idx;
+		}
+
+		in_run = change;
+	}
+
+	if starts.len() < 2 {
+		return None;
+	}
+
+	let mut old_next = header.old_start;
+	let mut new_next = header.new_start;
+
+	let parts = starts
+		.iter()
+		.enumerate()
+		.map(|(idx, start)| {
+			let end =
+				starts.get(idx + 1).copied().unwrap_or(lines.len());
+			let part = &lines[*start..end];
+
+			// the markers of a missing newline at the end of the
+			// file repeat the number of the line before them
+			let count =
+				|side: fn(&DiffLinePosition) -> Option<u32>| {
+					part.iter()
+						.filter_map(|line| side(&line.position))
+						.fold((0, None), |(count, last), lineno| {
+							if last == Some(lineno) {
+								(count, last)
+							} else {
+								(count + 1, Some(lineno))
+							}
+						})
+						.0
+				};
+			let old_lines = count(|pos| pos.old_lineno);
+			let new_lines = count(|pos| pos.new_lineno);
+
+			// an empty side starts after the line before it
+			let part_header = HunkHeader {
+				old_start: if old_lines == 0 {
+					old_next.saturating_sub(1)
+				} else {
+					old_next
+				},
+				old_lines,
+				new_start: if new_lines == 0 {
+					new_next.saturating_sub(1)
+				} else {
+					new_next
+				},
+				new_lines,
+			};
+
+			old_next += old_lines;
+			new_next += new_lines;
+
+			(part_header, *start..end)
+		})
+		.collect();
+
+	Some(parts)
+}
+
+/// splits `hunk` into parts at the context lines between its
+/// changes (see `split_hunk_lines`), each with a header of its own.
+/// `None` if it cannot be split further
+pub fn split_hunk(hunk: &Hunk) -> Option<Vec<Hunk>> {
+	let (first, lines) = hunk.lines.split_first()?;
+	if first.line_type != DiffLineType::Header {
+		return None;
+	}
+
+	let header = HunkHeader::parse(&first.content)?;
+
+	Some(
+		split_hunk_lines(header, lines)?
+			.into_iter()
+			.map(|(header, range)| {
+				let mut part = Vec::with_capacity(range.len() + 1);
+				part.push(DiffLine {
+					content: header.text().into(),
+					line_type: DiffLineType::Header,
+					position: DiffLinePosition::default(),
+				});
+				part.extend_from_slice(&lines[range]);
+
+				Hunk {
+					header_hash: hash(&header),
+					lines: part,
+					split_from: Some(hunk.header_hash),
+				}
+			})
+			.collect(),
+	)
+}
+
+/// file mode (as in the index, e.g. `0o100755`) before and after
+#[derive(Default, Clone, Copy, Hash, Debug, PartialEq)]
+pub struct ModeChange {
+	///
+	pub old: u32,
+	///
+	pub new: u32,
+}
+
+/// target of a symlink before and after
+#[derive(Default, Clone, Hash, Debug, PartialEq)]
+pub struct LinkChange {
+	///
+	pub old: String,
+	///
+	pub new: String,
+}
+
+/// lfs pointer file before and after, a side that is no pointer
+/// (or missing) is `None`
+#[derive(Default, Clone, Hash, Debug, PartialEq)]
+pub struct LfsChange {
+	///
+	pub old: Option<LfsPointer>,
+	///
+	pub new: Option<LfsPointer>,
 }
 
 /// collection of hunks, sum of all diff lines
@@ -122,6 +329,15 @@ pub struct FileDiff {
 	pub sizes: (u64, u64),
 	/// size delta in bytes
 	pub size_delta: i64,
+	/// mode of a file that exists on both sides changed (`chmod +x`)
+	pub mode_change: Option<ModeChange>,
+	/// symlink got a new target, `hunks` are left empty then
+	pub link_change: Option<LinkChange>,
+	/// one side is an lfs pointer, `hunks` still diff the pointers
+	pub lfs_change: Option<LfsChange>,
+	/// why the diff could not be computed (e.g. a missing blob),
+	/// everything else is left empty then
+	pub error: Option<String>,
 }
 
 /// see <https://libgit2.org/libgit2/#HEAD/type/git_diff_options>
@@ -145,6 +361,18 @@ impl Default for DiffOptions {
 	}
 }
 
+fn raw_diff_options(
+	options: Option<DiffOptions>,
+) -> git2::DiffOptions {
+	let mut opt = git2::DiffOptions::new();
+	if let Some(options) = options {
+		opt.context_lines(options.context);
+		opt.ignore_whitespace(options.ignore_whitespace);
+		opt.interhunk_lines(options.interhunk_lines);
+	}
+	opt
+}
+
 pub(crate) fn get_diff_raw<'a>(
 	repo: &'a Repository,
 	p: &str,
@@ -154,12 +382,7 @@ pub(crate) fn get_diff_raw<'a>(
 ) -> Result<Diff<'a>> {
 	// scope_time!("get_diff_raw");
 
-	let mut opt = git2::DiffOptions::new();
-	if let Some(options) = options {
-		opt.context_lines(options.context);
-		opt.ignore_whitespace(options.ignore_whitespace);
-		opt.interhunk_lines(options.interhunk_lines);
-	}
+	let mut opt = raw_diff_options(options);
 	opt.pathspec(p);
 	opt.reverse(reverse);
 
@@ -190,6 +413,35 @@ pub(crate) fn get_diff_raw<'a>(
 	Ok(diff)
 }
 
+/// staged diff of a renamed file: `old_p` in HEAD against `p` in
+/// the index, paired up into a single renamed delta
+pub(crate) fn get_diff_raw_renamed<'a>(
+	repo: &'a Repository,
+	old_p: &str,
+	p: &str,
+	options: Option<DiffOptions>,
+) -> Result<Diff<'a>> {
+	let mut opt = raw_diff_options(options);
+	opt.pathspec(old_p);
+	opt.pathspec(p);
+
+	let tree = if let Ok(id) = get_head_repo(repo) {
+		Some(repo.find_commit(id.into())?.tree()?)
+	} else {
+		None
+	};
+
+	let mut diff = repo.diff_tree_to_index(
+		tree.as_ref(),
+		Some(&repo.index()?),
+		Some(&mut opt),
+	)?;
+
+	diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+
+	Ok(diff)
+}
+
 /// returns diff of a specific file either in `stage` or workdir
 pub fn get_diff(
 	repo_path: &str,
@@ -197,13 +449,29 @@ pub fn get_diff(
 	stage: bool,
 	options: Option<DiffOptions>,
 ) -> Result<FileDiff> {
-	scope_time!("get_diff");
+	scope_stats!("get_diff");
 
 	let repo = utils::repo(repo_path)?;
 	let work_dir = work_dir(&repo)?;
 	let diff = get_diff_raw(&repo, p, stage, false, options)?;
 
-	raw_diff_to_file_diff(&diff, work_dir)
+	raw_diff_to_file_diff(&repo, &diff, work_dir)
+}
+
+/// returns the staged diff of a file renamed from `old_p` to `p`
+pub fn get_diff_renamed(
+	repo_path: &str,
+	old_p: &str,
+	p: &str,
+	options: Option<DiffOptions>,
+) -> Result<FileDiff> {
+	scope_time!("get_diff_renamed");
+
+	let repo = utils::repo(repo_path)?;
+	let work_dir = work_dir(&repo)?;
+	let diff = get_diff_raw_renamed(&repo, old_p, p, options)?;
+
+	raw_diff_to_file_diff(&repo, &diff, work_dir)
 }
 
 /// returns diff of a specific file inside a commit
@@ -211,15 +479,16 @@ pub fn get_diff(
 pub fn get_diff_commit(
 	repo_path: &str,
 	id: CommitId,
+	merge_mode: MergeDiffMode,
 	p: String,
 ) -> Result<FileDiff> {
-	scope_time!("get_diff_commit");
+	scope_stats!("get_diff_commit");
 
 	let repo = utils::repo(repo_path)?;
 	let work_dir = work_dir(&repo)?;
-	let diff = get_commit_diff(&repo, id, Some(p))?;
+	let diff = get_commit_diff(&repo, id, merge_mode, Some(p))?;
 
-	raw_diff_to_file_diff(&diff, work_dir)
+	raw_diff_to_file_diff(&repo, &diff, work_dir)
 }
 
 /// get file changes of a diff between two commits
@@ -228,20 +497,213 @@ pub fn get_diff_commits(
 	ids: (CommitId, CommitId),
 	p: String,
 ) -> Result<FileDiff> {
-	scope_time!("get_diff_commits");
+	scope_stats!("get_diff_commits");
 
 	let repo = utils::repo(repo_path)?;
 	let work_dir = work_dir(&repo)?;
 	let diff =
 		get_compare_commits_diff(&repo, (ids.0, ids.1), Some(p))?;
 
-	raw_diff_to_file_diff(&diff, work_dir)
+	raw_diff_to_file_diff(&repo, &diff, work_dir)
+}
+
+/// changes of a file since commit `id` including the uncommitted
+/// ones, see `get_commit_workdir_diff`
+pub fn get_diff_commit_workdir(
+	repo_path: &str,
+	id: CommitId,
+	p: &str,
+	options: Option<DiffOptions>,
+) -> Result<FileDiff> {
+	scope_time!("get_diff_commit_workdir");
+
+	let repo = utils::repo(repo_path)?;
+	let work_dir = work_dir(&repo)?;
+	let mut opt = raw_diff_options(options);
+	opt.pathspec(p);
+	opt.show_binary(true);
+	let diff = get_commit_workdir_diff(&repo, id, Some(opt))?;
+
+	raw_diff_to_file_diff(&repo, &diff, work_dir)
+}
+
+/// a file at a commit next to its working copy,
+/// see `diff_blob_to_workdir`
+#[derive(Default, Clone, Debug)]
+pub struct WorkdirDiff {
+	/// whole file as context: every line of both sides is in here
+	pub diff: FileDiff,
+	/// file does not exist in the commit
+	pub old_missing: bool,
+	/// file was deleted from the working tree
+	pub new_missing: bool,
+	/// no lines to show for binary files
+	pub binary: bool,
+}
+
+/// diffs `file_path` as it was in `commit` against the file in the
+/// working tree using the whole file as context. a side the file is
+/// missing on is diffed as empty, an unchanged file is all context
+/// lines
+pub fn diff_blob_to_workdir(
+	repo_path: &str,
+	commit: CommitId,
+	file_path: &str,
+) -> Result<WorkdirDiff> {
+	scope_time!("diff_blob_to_workdir");
+
+	let repo = utils::repo(repo_path)?;
+	let tree = repo.find_commit(commit.into())?.tree()?;
+
+	let old = tree
+		.get_path(Path::new(file_path))
+		.ok()
+		.map(|entry| -> Result<Vec<u8>> {
+			Ok(entry
+				.to_object(&repo)?
+				.peel_to_blob()?
+				.content()
+				.to_vec())
+		})
+		.transpose()?;
+
+	let new = match fs::read(work_dir(&repo)?.join(file_path)) {
+		Ok(content) => Some(content),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+		Err(e) => return Err(e.into()),
+	};
+
+	let old_content = old.as_deref().unwrap_or_default();
+	let new_content = new.as_deref().unwrap_or_default();
+
+	let context =
+		line_count(old_content).max(line_count(new_content));
+	let mut opt = git2::DiffOptions::new();
+	opt.context_lines(
+		u32::try_from(context).unwrap_or(u32::MAX).saturating_add(1),
+	);
+
+	let patch = Patch::from_buffers(
+		old_content,
+		Some(Path::new(file_path)),
+		new_content,
+		Some(Path::new(file_path)),
+		Some(&mut opt),
+	)?;
+
+	let binary = patch.delta().flags().is_binary();
+
+	let mut diff = if !binary && patch.num_hunks() == 0 {
+		unchanged_file_diff(old_content)
+	} else {
+		patch_to_file_diff(&patch)?
+	};
+	diff.sizes =
+		(u64::conv(old_content.len()), u64::conv(new_content.len()));
+	diff.size_delta = i64::conv(diff.sizes.1)
+		.saturating_sub(i64::conv(diff.sizes.0));
+
+	Ok(WorkdirDiff {
+		diff,
+		old_missing: old.is_none(),
+		new_missing: new.is_none(),
+		binary,
+	})
+}
+
+fn line_count(content: &[u8]) -> usize {
+	content.split(|b| *b == b'\n').count()
+}
+
+fn patch_to_file_diff(patch: &Patch) -> Result<FileDiff> {
+	let mut res = FileDiff::default();
+
+	for hunk_idx in 0..patch.num_hunks() {
+		let (hunk, line_count) = patch.hunk(hunk_idx)?;
+		let mut lines = Vec::with_capacity(line_count + 1);
+
+		lines.push(DiffLine {
+			content: String::from_utf8_lossy(hunk.header())
+				.trim_matches(is_newline)
+				.into(),
+			line_type: DiffLineType::Header,
+			position: DiffLinePosition::default(),
+		});
+
+		for line_idx in 0..line_count {
+			let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+
+			// the "no newline at end of file" markers have no line
+			// of their own on either side
+			if matches!(
+				line.origin_value(),
+				git2::DiffLineType::ContextEOFNL
+					| git2::DiffLineType::AddEOFNL
+					| git2::DiffLineType::DeleteEOFNL
+			) {
+				continue;
+			}
+
+			lines.push(DiffLine {
+				position: DiffLinePosition::from(&line),
+				content: String::from_utf8_lossy(line.content())
+					.trim_matches(is_newline)
+					.into(),
+				line_type: line.origin_value().into(),
+			});
+		}
+
+		res.lines += lines.len();
+		res.hunks.push(Hunk {
+			header_hash: hash(&HunkHeader::from(hunk)),
+			split_from: None,
+			lines,
+		});
+	}
+
+	Ok(res)
+}
+
+/// a single hunk of context lines, libgit2 has no hunks for
+/// identical buffers
+fn unchanged_file_diff(content: &[u8]) -> FileDiff {
+	let content = String::from_utf8_lossy(content);
+	let lines = content
+		.lines()
+		.enumerate()
+		.map(|(idx, line)| {
+			let lineno = u32::try_from(idx + 1).ok();
+			DiffLine {
+				content: line.trim_matches(is_newline).into(),
+				line_type: DiffLineType::None,
+				position: DiffLinePosition {
+					old_lineno: lineno,
+					new_lineno: lineno,
+				},
+			}
+		})
+		.collect::<Vec<_>>();
+
+	if lines.is_empty() {
+		return FileDiff::default();
+	}
+
+	FileDiff {
+		lines: lines.len(),
+		hunks: vec![Hunk {
+			header_hash: 0,
+			split_from: None,
+			lines,
+		}],
+		..FileDiff::default()
+	}
 }
 
 ///
 //TODO: refactor into helper type with the inline closures as dedicated functions
 #[allow(clippy::too_many_lines)]
 fn raw_diff_to_file_diff<'a>(
+	repo: &Repository,
 	diff: &'a Diff,
 	work_dir: &Path,
 ) -> Result<FileDiff> {
@@ -256,6 +718,7 @@ fn raw_diff_to_file_diff<'a>(
 			let mut res = res_cell.borrow_mut();
 			res.hunks.push(Hunk {
 				header_hash: hash(header),
+				split_from: None,
 				lines: lines.clone(),
 			});
 			res.lines += lines.len();
@@ -372,12 +835,102 @@ fn raw_diff_to_file_diff<'a>(
 		if new_file_diff {
 			res.borrow_mut().untracked = true;
 		}
+
+		if diff.deltas().len() == 1 {
+			if let Some(delta) = diff.deltas().next() {
+				let mut res = res.borrow_mut();
+				res.mode_change = mode_change(&delta);
+
+				if delta.old_file().mode() == FileMode::Link
+					&& delta.new_file().mode() == FileMode::Link
+				{
+					res.link_change = Some(link_change(&res.hunks));
+					res.hunks.clear();
+					res.lines = 0;
+				} else {
+					res.lfs_change =
+						lfs_change(repo, work_dir, &delta);
+				}
+			}
+		}
 	}
 	let res = Rc::try_unwrap(res)
 		.map_err(|_| Error::Generic("rc unwrap error".to_owned()))?;
 	Ok(res.into_inner())
 }
 
+fn mode_change(delta: &DiffDelta) -> Option<ModeChange> {
+	let old = u32::from(delta.old_file().mode());
+	let new = u32::from(delta.new_file().mode());
+
+	// added or deleted files have no mode on one side
+	if old == 0 || new == 0 || old == new {
+		None
+	} else {
+		Some(ModeChange { old, new })
+	}
+}
+
+/// the content of a symlink blob is its target
+fn link_change(hunks: &[Hunk]) -> LinkChange {
+	let mut res = LinkChange::default();
+
+	for line in hunks.iter().flat_map(|hunk| hunk.lines.iter()) {
+		match (line.line_type, line.position) {
+			(
+				DiffLineType::Delete,
+				DiffLinePosition {
+					old_lineno: Some(_),
+					..
+				},
+			) => res.old.push_str(&line.content),
+			(
+				DiffLineType::Add,
+				DiffLinePosition {
+					new_lineno: Some(_),
+					..
+				},
+			) => res.new.push_str(&line.content),
+			_ => (),
+		}
+	}
+
+	res
+}
+
+fn lfs_change(
+	repo: &Repository,
+	work_dir: &Path,
+	delta: &DiffDelta,
+) -> Option<LfsChange> {
+	let old = lfs_pointer_of(repo, work_dir, &delta.old_file());
+	let new = lfs_pointer_of(repo, work_dir, &delta.new_file());
+
+	(old.is_some() || new.is_some()).then(|| LfsChange { old, new })
+}
+
+/// the blob of a side of the diff, the working copy is no blob (yet)
+fn lfs_pointer_of(
+	repo: &Repository,
+	work_dir: &Path,
+	file: &DiffFile,
+) -> Option<LfsPointer> {
+	if !file.exists() || file.size() > POINTER_MAX_SIZE {
+		return None;
+	}
+
+	if let Ok(blob) = repo.find_blob(file.id()) {
+		return lfs_pointer(blob.content());
+	}
+
+	let path = work_dir.join(file.path()?);
+	if fs::metadata(&path).ok()?.len() > POINTER_MAX_SIZE {
+		return None;
+	}
+
+	lfs_pointer(&fs::read(path).ok()?)
+}
+
 const fn is_newline(c: char) -> bool {
 	c == '\n' || c == '\r'
 }
@@ -402,7 +955,7 @@ fn new_file_content(path: &Path) -> Option<Vec<u8>> {
 
 #[cfg(test)]
 mod tests {
-	use super::{get_diff, get_diff_commit};
+	use super::{get_diff, get_diff_commit, MergeDiffMode};
 	use crate::error::Result;
 	use crate::sync::{
 		commit, stage_add_file,
@@ -572,7 +1125,7 @@ mod tests {
 
 		stage_add_file(repo_path, file_path).unwrap();
 
-		commit(repo_path, "commit").unwrap();
+		commit(repo_path, "commit", false).unwrap();
 
 		File::create(&root.join(file_path))?
 			.write_all(b"\x00\x02")?;
@@ -628,17 +1181,22 @@ mod tests {
 
 		stage_add_file(repo_path, file_path).unwrap();
 
-		commit(repo_path, "").unwrap();
+		commit(repo_path, "", false).unwrap();
 
 		File::create(&root.join(file_path))?
 			.write_all(b"\x00\x02")?;
 
 		stage_add_file(repo_path, file_path).unwrap();
 
-		let id = commit(repo_path, "").unwrap();
+		let id = commit(repo_path, "", false).unwrap();
 
-		let diff =
-			get_diff_commit(repo_path, id, String::new()).unwrap();
+		let diff = get_diff_commit(
+			repo_path,
+			id,
+			MergeDiffMode::FirstParent,
+			String::new(),
+		)
+		.unwrap();
 
 		dbg!(&diff);
 		assert_eq!(diff.sizes, (1, 2));
@@ -646,4 +1204,302 @@ mod tests {
 
 		Ok(())
 	}
+	#[test]
+	#[cfg(unix)]
+	fn test_mode_change() {
+		use super::ModeChange;
+		use crate::sync::reset_stage;
+		use std::os::unix::fs::PermissionsExt;
+
+		let file_path = Path::new("run.sh");
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::write(root.join(file_path), "echo").unwrap();
+		stage_add_file(repo_path, file_path).unwrap();
+		commit(repo_path, "c1", false).unwrap();
+
+		fs::set_permissions(
+			root.join(file_path),
+			fs::Permissions::from_mode(0o755),
+		)
+		.unwrap();
+
+		let exec = Some(ModeChange {
+			old: 0o100_644,
+			new: 0o100_755,
+		});
+		let index_mode = || {
+			let mut index = repo.index().unwrap();
+			index.read(true).unwrap();
+			index.get_path(file_path, 0).unwrap().mode
+		};
+
+		let diff =
+			get_diff(repo_path, "run.sh", false, None).unwrap();
+		assert_eq!(diff.mode_change, exec);
+		assert!(diff.hunks.is_empty());
+
+		stage_add_file(repo_path, file_path).unwrap();
+		assert_eq!(index_mode(), 0o100_755);
+
+		let diff = get_diff(repo_path, "run.sh", true, None).unwrap();
+		assert_eq!(diff.mode_change, exec);
+		let diff =
+			get_diff(repo_path, "run.sh", false, None).unwrap();
+		assert_eq!(diff.mode_change, None);
+
+		reset_stage(repo_path, "run.sh").unwrap();
+		assert_eq!(index_mode(), 0o100_644);
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_link_change() {
+		use super::LinkChange;
+		use std::os::unix::fs::symlink;
+
+		let file_path = Path::new("link");
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		symlink("a", root.join(file_path)).unwrap();
+		stage_add_file(repo_path, file_path).unwrap();
+		commit(repo_path, "c1", false).unwrap();
+
+		fs::remove_file(root.join(file_path)).unwrap();
+		symlink("b", root.join(file_path)).unwrap();
+
+		let retarget = Some(LinkChange {
+			old: String::from("a"),
+			new: String::from("b"),
+		});
+
+		let diff = get_diff(repo_path, "link", false, None).unwrap();
+		assert_eq!(diff.link_change, retarget);
+		assert_eq!(diff.mode_change, None);
+		assert!(diff.hunks.is_empty());
+
+		stage_add_file(repo_path, file_path).unwrap();
+
+		let mut index = repo.index().unwrap();
+		index.read(true).unwrap();
+		let entry = index.get_path(file_path, 0).unwrap();
+		assert_eq!(entry.mode, 0o120_000);
+		assert_eq!(repo.find_blob(entry.id).unwrap().content(), b"b");
+
+		let diff = get_diff(repo_path, "link", true, None).unwrap();
+		assert_eq!(diff.link_change, retarget);
+	}
+
+	#[test]
+	fn test_lfs_change() {
+		use super::LfsChange;
+		use crate::sync::{lfs_pointer, tests::write_commit_file};
+
+		let pointer = |oid: &str, size: u64| {
+			format!(
+				"version https://git-lfs.github.com/spec/v1\noid sha256:{}\nsize {}\n",
+				oid, size
+			)
+		};
+
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let old = pointer("aa11", 100);
+		let new = pointer("bb22", 2048);
+		write_commit_file(&repo, "img.png", &old, "c1");
+		fs::write(root.join("img.png"), &new).unwrap();
+
+		let change = Some(LfsChange {
+			old: lfs_pointer(old.as_bytes()),
+			new: lfs_pointer(new.as_bytes()),
+		});
+
+		let diff =
+			get_diff(repo_path, "img.png", false, None).unwrap();
+		assert_eq!(diff.lfs_change, change);
+		assert_eq!(diff.hunks.len(), 1);
+
+		stage_add_file(repo_path, Path::new("img.png")).unwrap();
+
+		let diff =
+			get_diff(repo_path, "img.png", true, None).unwrap();
+		assert_eq!(diff.lfs_change, change);
+
+		// a new pointer file
+		fs::write(root.join("new.png"), &old).unwrap();
+		let diff =
+			get_diff(repo_path, "new.png", false, None).unwrap();
+		assert_eq!(
+			diff.lfs_change,
+			Some(LfsChange {
+				old: None,
+				new: lfs_pointer(old.as_bytes()),
+			})
+		);
+
+		// no pointer at all
+		write_commit_file(&repo, "f.txt", "a\n", "c2");
+		fs::write(root.join("f.txt"), "b\n").unwrap();
+		let diff = get_diff(repo_path, "f.txt", false, None).unwrap();
+		assert_eq!(diff.lfs_change, None);
+	}
+
+	#[test]
+	fn test_blob_to_workdir() {
+		use super::{diff_blob_to_workdir, DiffLineType};
+		use crate::sync::{
+			tests::write_commit_file, utils::repo_write_file,
+		};
+
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let id = write_commit_file(&repo, "f.txt", "a\nb\nc\n", "c1");
+		repo_write_file(&repo, "f.txt", "a\nB\nc\nd\n").unwrap();
+
+		let res =
+			diff_blob_to_workdir(repo_path, id, "f.txt").unwrap();
+		assert!(!res.old_missing && !res.new_missing && !res.binary);
+
+		let lines = res
+			.diff
+			.hunks
+			.iter()
+			.flat_map(|hunk| hunk.lines.iter())
+			.filter(|line| line.line_type != DiffLineType::Header)
+			.map(|line| (line.line_type, &*line.content))
+			.collect::<Vec<_>>();
+
+		assert_eq!(
+			lines,
+			vec![
+				(DiffLineType::None, "a"),
+				(DiffLineType::Delete, "b"),
+				(DiffLineType::Add, "B"),
+				(DiffLineType::None, "c"),
+				(DiffLineType::Add, "d"),
+			]
+		);
+	}
+
+	#[test]
+	fn test_blob_to_workdir_unchanged() {
+		use super::{diff_blob_to_workdir, DiffLineType};
+		use crate::sync::tests::write_commit_file;
+
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let id = write_commit_file(&repo, "f.txt", "a\nb", "c1");
+
+		let res =
+			diff_blob_to_workdir(repo_path, id, "f.txt").unwrap();
+		assert_eq!(res.diff.lines, 2);
+		assert!(res.diff.hunks[0]
+			.lines
+			.iter()
+			.all(|line| line.line_type == DiffLineType::None));
+	}
+
+	#[test]
+	fn test_blob_to_workdir_deleted() {
+		use super::{diff_blob_to_workdir, DiffLineType};
+		use crate::sync::tests::write_commit_file;
+
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let id = write_commit_file(&repo, "f.txt", "a\nb\n", "c1");
+		fs::remove_file(root.join("f.txt")).unwrap();
+
+		let res =
+			diff_blob_to_workdir(repo_path, id, "f.txt").unwrap();
+		assert!(res.new_missing);
+		assert!(!res.old_missing);
+		assert_eq!(
+			res.diff.hunks[0]
+				.lines
+				.iter()
+				.filter(|line| line.line_type == DiffLineType::Delete)
+				.count(),
+			2
+		);
+
+		// neither in the commit nor in the working tree
+		let res =
+			diff_blob_to_workdir(repo_path, id, "x.txt").unwrap();
+		assert!(res.old_missing && res.new_missing);
+		assert!(res.diff.hunks.is_empty());
+	}
+
+	#[test]
+	fn test_split_hunk() {
+		use super::split_hunk;
+		use crate::sync::tests::write_commit_file;
+
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let lines = |changed: &[usize]| {
+			(1..=15)
+				.map(|idx| {
+					if changed.contains(&idx) {
+						format!("changed {}\n", idx)
+					} else {
+						format!("{}\n", idx)
+					}
+				})
+				.collect::<String>()
+		};
+
+		write_commit_file(&repo, "f.txt", &lines(&[]), "c1");
+		fs::write(root.join("f.txt"), lines(&[5, 7, 9])).unwrap();
+
+		let diff = get_diff(repo_path, "f.txt", false, None).unwrap();
+		let hunk = &diff.hunks[0];
+		assert_eq!(&*hunk.lines[0].content, "@@ -2,11 +2,11 @@");
+
+		let parts = split_hunk(hunk).unwrap();
+		let headers = parts
+			.iter()
+			.map(|part| part.lines[0].content.to_string())
+			.collect::<Vec<_>>();
+		assert_eq!(
+			headers,
+			vec![
+				"@@ -2,4 +2,4 @@",
+				"@@ -6,2 +6,2 @@",
+				"@@ -8,5 +8,5 @@"
+			]
+		);
+
+		// every line is in exactly one part
+		assert_eq!(
+			parts
+				.iter()
+				.map(|part| part.lines.len() - 1)
+				.sum::<usize>(),
+			hunk.lines.len() - 1
+		);
+		assert!(parts
+			.iter()
+			.all(|part| part.split_from == Some(hunk.header_hash)));
+
+		// a single run of changes cannot be split further
+		assert!(split_hunk(&parts[0]).is_none());
+
+		fs::write(root.join("f.txt"), lines(&[5, 6])).unwrap();
+		let diff = get_diff(repo_path, "f.txt", false, None).unwrap();
+		assert!(split_hunk(&diff.hunks[0]).is_none());
+	}
 }
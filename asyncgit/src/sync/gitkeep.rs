@@ -0,0 +1,290 @@
+//! git does not track directories, an empty one only shows up once
+//! it contains a placeholder file
+
+use super::{
+	config::untracked_files_config_repo,
+	status::{StatusItem, StatusItemType},
+	utils::{repo, stage_add_file, work_dir},
+	ShowUntrackedFilesConfig,
+};
+use crate::error::{Error, Result};
+use git2::Repository;
+use scopetime::scope_time;
+use std::{
+	collections::HashSet,
+	fs::{self, File},
+	path::{Path, PathBuf},
+};
+
+static GITKEEP: &str = ".gitkeep";
+
+/// adds the empty directories to a working dir status (`items`)
+/// unless untracked files are hidden. empty directories inside an
+/// untracked directory listed as a whole are part of that entry
+pub fn add_empty_dirs(
+	repo_path: &str,
+	show_untracked: Option<ShowUntrackedFilesConfig>,
+	items: &mut Vec<StatusItem>,
+) -> Result<()> {
+	scope_time!("add_empty_dirs");
+
+	let repo = repo(repo_path)?;
+
+	let show_untracked = if let Some(config) = show_untracked {
+		config
+	} else {
+		untracked_files_config_repo(&repo)?
+	};
+
+	if !show_untracked.include_untracked() {
+		return Ok(());
+	}
+
+	let untracked_dirs = items
+		.iter()
+		.filter(|item| {
+			item.status == StatusItemType::New
+				&& item.path.ends_with('/')
+		})
+		.map(|item| item.path.clone())
+		.collect::<Vec<_>>();
+
+	items.extend(empty_dirs(&repo)?.into_iter().filter(|item| {
+		!untracked_dirs.iter().any(|dir| item.path.starts_with(dir))
+	}));
+
+	items.sort_by(|a, b| {
+		Path::new(a.path.as_str()).cmp(Path::new(b.path.as_str()))
+	});
+
+	Ok(())
+}
+
+/// empty directories of the working dir without tracked content,
+/// as `dir/` like untracked directories. parents of an empty
+/// directory are not reported themselves, ignored directories are
+/// not looked into
+fn empty_dirs(repo: &Repository) -> Result<Vec<StatusItem>> {
+	scope_time!("empty_dirs");
+
+	let work_dir = work_dir(repo)?;
+
+	// a directory emptied by deleting its last tracked file still
+	// has tracked content, the deletion shows up instead
+	let tracked = tracked_dirs(repo)?;
+
+	let mut res = Vec::new();
+	let mut stack = vec![PathBuf::new()];
+
+	while let Some(rel_dir) = stack.pop() {
+		let mut is_empty = true;
+
+		for entry in fs::read_dir(work_dir.join(&rel_dir))? {
+			let entry = entry?;
+			is_empty = false;
+
+			if !entry.file_type()?.is_dir()
+				|| entry.file_name() == ".git"
+			{
+				continue;
+			}
+
+			let rel_path = rel_dir.join(entry.file_name());
+			let path = dir_path(&rel_path)?;
+
+			// nested repositories are not ours to look into
+			if repo.is_path_ignored(&path)?
+				|| entry.path().join(".git").exists()
+			{
+				continue;
+			}
+
+			stack.push(rel_path);
+		}
+
+		if is_empty && !rel_dir.as_os_str().is_empty() {
+			let path = dir_path(&rel_dir)?;
+
+			if !tracked.contains(path.trim_end_matches('/')) {
+				res.push(StatusItem {
+					path,
+					old_path: None,
+					status: StatusItemType::EmptyDir,
+					binary_size: None,
+				});
+			}
+		}
+	}
+
+	Ok(res)
+}
+
+/// every directory containing an index entry, without trailing `/`
+fn tracked_dirs(repo: &Repository) -> Result<HashSet<String>> {
+	let mut res = HashSet::new();
+
+	let mut index = repo.index()?;
+	index.read(false)?;
+
+	for entry in index.iter() {
+		let path = String::from_utf8_lossy(&entry.path).to_string();
+
+		let mut dir = path.as_str();
+		while let Some(idx) = dir.rfind('/') {
+			dir = &dir[..idx];
+			if !res.insert(dir.to_string()) {
+				break;
+			}
+		}
+	}
+
+	Ok(res)
+}
+
+/// `dir/` with `/` as separator, like libgit2 reports directories
+fn dir_path(rel_path: &Path) -> Result<String> {
+	rel_path
+		.to_str()
+		.map(|p| format!("{}/", p.replace('\\', "/")))
+		.ok_or_else(|| {
+			Error::Generic(String::from(
+				"failed to get path of directory.",
+			))
+		})
+}
+
+/// creates an empty `.gitkeep` in `dir` and stages it
+pub fn add_gitkeep(repo_path: &str, dir: &str) -> Result<()> {
+	scope_time!("add_gitkeep");
+
+	let repo = repo(repo_path)?;
+
+	let dir = dir.trim_end_matches('/');
+	let file = Path::new(dir).join(GITKEEP);
+	let full_path = work_dir(&repo)?.join(&file);
+
+	if !full_path.exists() {
+		File::create(&full_path)?;
+	}
+
+	stage_add_file(repo_path, &file)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		status::{get_status, StatusType},
+		tests::{repo_init, write_commit_file},
+	};
+	use std::fs::create_dir_all;
+
+	fn empty_paths(repo: &Repository) -> Vec<String> {
+		let mut paths = empty_dirs(repo)
+			.unwrap()
+			.into_iter()
+			.map(|item| item.path)
+			.collect::<Vec<_>>();
+		paths.sort();
+		paths
+	}
+
+	#[test]
+	fn test_nested() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+
+		create_dir_all(root.join("a/b/c")).unwrap();
+		create_dir_all(root.join("d")).unwrap();
+		create_dir_all(root.join("e/g")).unwrap();
+		File::create(root.join("e/f.txt")).unwrap();
+
+		assert_eq!(
+			empty_paths(&repo),
+			vec![
+				String::from("a/b/c/"),
+				String::from("d/"),
+				String::from("e/g/"),
+			]
+		);
+	}
+
+	#[test]
+	fn test_ignored_parent() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+
+		write_commit_file(&repo, ".gitignore", "build/\n", "ignore");
+		create_dir_all(root.join("build/out")).unwrap();
+		create_dir_all(root.join("src/build")).unwrap();
+
+		assert!(empty_paths(&repo).is_empty());
+	}
+
+	#[test]
+	fn test_emptied_tracked_dir() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+
+		create_dir_all(root.join("t")).unwrap();
+		write_commit_file(&repo, "t/x.txt", "x", "x");
+		fs::remove_file(root.join("t/x.txt")).unwrap();
+
+		assert!(empty_paths(&repo).is_empty());
+	}
+
+	#[test]
+	fn test_add_empty_dirs() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		create_dir_all(root.join("a/b")).unwrap();
+		create_dir_all(root.join("u/empty")).unwrap();
+		File::create(root.join("u/f.txt")).unwrap();
+
+		let paths = |config| {
+			let mut items =
+				get_status(repo_path, StatusType::WorkingDir, config)
+					.unwrap();
+			add_empty_dirs(repo_path, config, &mut items).unwrap();
+			items
+				.into_iter()
+				.map(|item| item.path)
+				.collect::<Vec<_>>()
+		};
+
+		assert_eq!(
+			paths(Some(ShowUntrackedFilesConfig::Normal)),
+			vec![String::from("a/b/"), String::from("u/")]
+		);
+		assert_eq!(
+			paths(Some(ShowUntrackedFilesConfig::All)),
+			vec![
+				String::from("a/b/"),
+				String::from("u/empty/"),
+				String::from("u/f.txt"),
+			]
+		);
+		assert!(paths(Some(ShowUntrackedFilesConfig::No)).is_empty());
+	}
+
+	#[test]
+	fn test_add_gitkeep() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		create_dir_all(root.join("a/b")).unwrap();
+
+		add_gitkeep(repo_path, "a/b/").unwrap();
+
+		assert!(root.join("a/b/.gitkeep").exists());
+		assert!(empty_paths(&repo).is_empty());
+
+		let staged =
+			get_status(repo_path, StatusType::Stage, None).unwrap();
+		assert_eq!(staged.len(), 1);
+		assert_eq!(staged[0].path, "a/b/.gitkeep");
+	}
+}
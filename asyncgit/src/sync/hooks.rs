@@ -1,17 +1,33 @@
-use super::utils::{repo, work_dir};
+use super::{
+	config::get_config_string_repo,
+	utils::{repo, work_dir},
+	PushUpdate,
+};
 use crate::error::{Error, Result};
-use scopetime::scope_time;
+use crossbeam_channel::{RecvTimeoutError, Sender};
+use git2::Oid;
+use scopetime::scope_stats;
 use std::{
 	fs::File,
-	io::{Read, Write},
+	io::{BufRead, BufReader, Read, Write},
 	path::Path,
-	process::Command,
+	process::{Child, Command, Stdio},
+	sync::atomic::{AtomicBool, Ordering},
+	thread,
+	time::{Duration, Instant},
 };
 
 const HOOK_POST_COMMIT: &str = ".git/hooks/post-commit";
 const HOOK_PRE_COMMIT: &str = ".git/hooks/pre-commit";
 const HOOK_COMMIT_MSG: &str = ".git/hooks/commit-msg";
 const HOOK_COMMIT_MSG_TEMP_FILE: &str = ".git/COMMIT_EDITMSG";
+const HOOK_PRE_PUSH: &str = "pre-push";
+const HOOKS_DIR: &str = ".git/hooks";
+
+/// how often a running hook is checked for cancellation
+const HOOK_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// time a canceled hook gets to terminate before it is killed
+const HOOK_KILL_GRACE: Duration = Duration::from_secs(2);
 
 /// this hook is documented here <https://git-scm.com/docs/githooks#_commit_msg>
 /// we use the same convention as other git clients to create a temp file containing
@@ -21,7 +37,24 @@ pub fn hooks_commit_msg(
 	repo_path: &str,
 	msg: &mut String,
 ) -> Result<HookResult> {
-	scope_time!("hooks_commit_msg");
+	hooks_commit_msg_streamed(
+		repo_path,
+		msg,
+		&|_| {},
+		&AtomicBool::new(false),
+	)
+}
+
+/// like `hooks_commit_msg` but passes every line the hook writes
+/// to `output` right away (and an empty one while it is silent),
+/// setting `cancel` terminates the hook
+pub fn hooks_commit_msg_streamed(
+	repo_path: &str,
+	msg: &mut String,
+	output: &dyn Fn(&str),
+	cancel: &AtomicBool,
+) -> Result<HookResult> {
+	scope_stats!("hooks_commit_msg");
 
 	let work_dir = work_dir_as_string(repo_path)?;
 
@@ -34,6 +67,9 @@ pub fn hooks_commit_msg(
 			work_dir.as_str(),
 			HOOK_COMMIT_MSG,
 			&[HOOK_COMMIT_MSG_TEMP_FILE],
+			&[],
+			output,
+			cancel,
 		)?;
 
 		// load possibly altered msg
@@ -49,30 +85,134 @@ pub fn hooks_commit_msg(
 /// this hook is documented here <https://git-scm.com/docs/githooks#_pre_commit>
 ///
 pub fn hooks_pre_commit(repo_path: &str) -> Result<HookResult> {
-	scope_time!("hooks_pre_commit");
+	hooks_pre_commit_streamed(
+		repo_path,
+		&|_| {},
+		&AtomicBool::new(false),
+	)
+}
+
+/// see `hooks_commit_msg_streamed`
+pub fn hooks_pre_commit_streamed(
+	repo_path: &str,
+	output: &dyn Fn(&str),
+	cancel: &AtomicBool,
+) -> Result<HookResult> {
+	scope_stats!("hooks_pre_commit");
 
 	let work_dir = work_dir_as_string(repo_path)?;
 
 	if hook_runable(work_dir.as_str(), HOOK_PRE_COMMIT) {
-		Ok(run_hook(work_dir.as_str(), HOOK_PRE_COMMIT, &[])?)
+		Ok(run_hook(
+			work_dir.as_str(),
+			HOOK_PRE_COMMIT,
+			&[],
+			&[],
+			output,
+			cancel,
+		)?)
 	} else {
 		Ok(HookResult::Ok)
 	}
 }
 ///
 pub fn hooks_post_commit(repo_path: &str) -> Result<HookResult> {
-	scope_time!("hooks_post_commit");
+	scope_stats!("hooks_post_commit");
 
 	let work_dir = work_dir_as_string(repo_path)?;
 	let work_dir_str = work_dir.as_str();
 
 	if hook_runable(work_dir_str, HOOK_POST_COMMIT) {
-		Ok(run_hook(work_dir_str, HOOK_POST_COMMIT, &[])?)
+		Ok(run_hook(
+			work_dir_str,
+			HOOK_POST_COMMIT,
+			&[],
+			&[],
+			&|_| {},
+			&AtomicBool::new(false),
+		)?)
 	} else {
 		Ok(HookResult::Ok)
 	}
 }
 
+/// this hook is documented here <https://git-scm.com/docs/githooks#_pre_push>
+///
+/// `url` is where `remote` is pushed to, `updates` are written to
+/// the hook's stdin one per line. unlike the other hooks it is looked
+/// up in `core.hooksPath` if set
+pub fn hooks_pre_push(
+	repo_path: &str,
+	remote: &str,
+	url: &str,
+	updates: &[PushUpdate],
+) -> Result<HookResult> {
+	hooks_pre_push_streamed(
+		repo_path,
+		remote,
+		url,
+		updates,
+		&|_| {},
+		&AtomicBool::new(false),
+	)
+}
+
+/// see `hooks_pre_push` and `hooks_commit_msg_streamed`
+pub fn hooks_pre_push_streamed(
+	repo_path: &str,
+	remote: &str,
+	url: &str,
+	updates: &[PushUpdate],
+	output: &dyn Fn(&str),
+	cancel: &AtomicBool,
+) -> Result<HookResult> {
+	scope_stats!("hooks_pre_push");
+
+	let work_dir = work_dir_as_string(repo_path)?;
+	let hook = hook_path(repo_path, HOOK_PRE_PUSH)?;
+
+	if hook_runable(work_dir.as_str(), &hook) {
+		let zero = Oid::zero().to_string();
+		let id = |id: Option<super::CommitId>| {
+			id.map_or_else(|| zero.clone(), |id| id.to_string())
+		};
+
+		let mut stdin = Vec::new();
+		for update in updates {
+			writeln!(
+				stdin,
+				"{} {} {} {}",
+				update.local_ref,
+				id(update.local_id),
+				update.remote_ref,
+				id(update.remote_id)
+			)?;
+		}
+
+		Ok(run_hook(
+			work_dir.as_str(),
+			&hook,
+			&[remote, url],
+			&stdin,
+			output,
+			cancel,
+		)?)
+	} else {
+		Ok(HookResult::Ok)
+	}
+}
+
+/// path of the hook `name` in `core.hooksPath` or `.git/hooks`,
+/// relative ones are relative to the work dir
+fn hook_path(repo_path: &str, name: &str) -> Result<String> {
+	let repo = repo(repo_path)?;
+
+	let dir = get_config_string_repo(&repo, "core.hooksPath")?
+		.unwrap_or_else(|| HOOKS_DIR.to_string());
+
+	Ok(format!("{}/{}", dir.trim_end_matches('/'), name))
+}
+
 fn work_dir_as_string(repo_path: &str) -> Result<String> {
 	let repo = repo(repo_path)?;
 	work_dir(&repo)?
@@ -107,12 +247,18 @@ fn run_hook(
 	path: &str,
 	hook_script: &str,
 	args: &[&str],
+	stdin: &[u8],
+	output: &dyn Fn(&str),
+	cancel: &AtomicBool,
 ) -> Result<HookResult> {
-	let arg_str = format!("{} {}", hook_script, args.join(" "));
-	let bash_args = vec!["-c".to_string(), arg_str];
-
-	let output = Command::new("bash")
-		.args(bash_args)
+	// the arguments are passed on as they are, without going
+	// through the shell's word splitting
+	let mut command = Command::new("bash");
+	command
+		.arg("-c")
+		.arg(format!("\"{}\" \"$@\"", hook_script))
+		.arg(hook_script)
+		.args(args)
 		.current_dir(path)
 		// This call forces Command to handle the Path environment correctly on windows,
 		// the specific env set here does not matter
@@ -121,19 +267,149 @@ fn run_hook(
 			"DUMMY_ENV_TO_FIX_WINDOWS_CMD_RUNS",
 			"FixPathHandlingOnWindows",
 		)
-		.output()?;
+		.stdin(if stdin.is_empty() {
+			Stdio::null()
+		} else {
+			Stdio::piped()
+		})
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped());
+	own_process_group(&mut command);
+
+	let mut child = command.spawn()?;
+
+	// written in the background, a hook that does not read its
+	// stdin would block us otherwise
+	if let Some(mut pipe) = child.stdin.take() {
+		let stdin = stdin.to_vec();
+		thread::spawn(move || {
+			pipe.write_all(&stdin).ok();
+		});
+	}
 
-	if output.status.success() {
+	let (tx, rx) = crossbeam_channel::unbounded();
+	let mut readers = Vec::new();
+	if let Some(stdout) = child.stdout.take() {
+		readers.push(forward_lines(stdout, tx.clone()));
+	}
+	if let Some(stderr) = child.stderr.take() {
+		readers.push(forward_lines(stderr, tx));
+	}
+
+	let mut collected = String::new();
+	let mut canceled_at: Option<Instant> = None;
+
+	loop {
+		match rx.recv_timeout(HOOK_POLL_INTERVAL) {
+			Ok(line) => {
+				output(&line);
+				collected.push_str(&line);
+			}
+			// both pipes closed
+			Err(RecvTimeoutError::Disconnected) => break,
+			// lets the caller know the hook is still alive
+			Err(RecvTimeoutError::Timeout) => output(""),
+		}
+
+		if cancel.load(Ordering::Relaxed) {
+			match canceled_at {
+				None => {
+					terminate(&child);
+					canceled_at = Some(Instant::now());
+				}
+				Some(at) if at.elapsed() > HOOK_KILL_GRACE => {
+					kill(&mut child);
+				}
+				Some(_) => (),
+			}
+		}
+	}
+
+	let status = child.wait()?;
+
+	for reader in readers {
+		reader.join().ok();
+	}
+
+	if status.success() {
 		Ok(HookResult::Ok)
 	} else {
-		let err = String::from_utf8_lossy(&output.stderr);
-		let out = String::from_utf8_lossy(&output.stdout);
-		let formatted = format!("{}{}", out, err);
+		Ok(HookResult::NotOk(collected))
+	}
+}
+
+fn forward_lines<R: Read + Send + 'static>(
+	pipe: R,
+	tx: Sender<String>,
+) -> thread::JoinHandle<()> {
+	thread::spawn(move || {
+		let mut reader = BufReader::new(pipe);
+		let mut line = Vec::new();
+
+		while let Ok(read) = reader.read_until(b'\n', &mut line) {
+			if read == 0
+				|| tx
+					.send(String::from_utf8_lossy(&line).to_string())
+					.is_err()
+			{
+				break;
+			}
+			line.clear();
+		}
+	})
+}
+
+/// the hook and everything it spawns share a new process group,
+/// so canceling reaches all of it
+#[cfg(unix)]
+fn own_process_group(command: &mut Command) {
+	use std::os::unix::process::CommandExt;
+
+	// safety: `setpgid` is async-signal-safe
+	unsafe {
+		command.pre_exec(|| {
+			if libc::setpgid(0, 0) == 0 {
+				Ok(())
+			} else {
+				Err(std::io::Error::last_os_error())
+			}
+		});
+	}
+}
+
+#[cfg(not(unix))]
+const fn own_process_group(_: &mut Command) {}
+
+#[cfg(unix)]
+fn signal_group(child: &Child, signal: libc::c_int) {
+	use std::convert::TryFrom;
 
-		Ok(HookResult::NotOk(formatted))
+	if let Ok(pid) = i32::try_from(child.id()) {
+		// safety: only signals the process group we created
+		unsafe {
+			libc::kill(-pid, signal);
+		}
 	}
 }
 
+#[cfg(unix)]
+fn terminate(child: &Child) {
+	signal_group(child, libc::SIGTERM);
+}
+
+/// there is no gentler way, `kill` follows after the grace
+#[cfg(not(unix))]
+const fn terminate(_: &Child) {}
+
+/// killing only the shell would leave whatever it spawned holding
+/// the pipes open
+fn kill(child: &mut Child) {
+	#[cfg(unix)]
+	signal_group(child, libc::SIGKILL);
+
+	child.kill().ok();
+}
+
 #[cfg(not(windows))]
 fn is_executable(path: &Path) -> bool {
 	use std::os::unix::fs::PermissionsExt;
@@ -363,6 +639,108 @@ exit 0
 		assert_eq!(msg, String::from("msg\n"));
 	}
 
+	#[test]
+	fn test_pre_commit_streamed() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let hook = b"#!/bin/sh
+echo 'first'
+echo 'second' 1>&2
+exit 1
+        ";
+
+		create_hook(root, HOOK_PRE_COMMIT, hook);
+
+		let lines = std::cell::RefCell::new(Vec::new());
+		let res = hooks_pre_commit_streamed(
+			repo_path,
+			&|line| {
+				if !line.is_empty() {
+					lines.borrow_mut().push(line.to_string());
+				}
+			},
+			&AtomicBool::new(false),
+		)
+		.unwrap();
+
+		let mut lines = lines.into_inner();
+		lines.sort();
+		assert_eq!(lines, vec!["first\n", "second\n"]);
+		// stdout and stderr are both collected
+		assert!(matches!(res, HookResult::NotOk(out)
+			if out.contains("first\n") && out.contains("second\n")));
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_pre_commit_cancel() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		// the sleep runs in a child of the hook
+		let hook = b"#!/bin/sh
+echo 'started'
+sleep 30
+exit 0
+        ";
+
+		create_hook(root, HOOK_PRE_COMMIT, hook);
+
+		let cancel = AtomicBool::new(false);
+		let start = Instant::now();
+		let res = hooks_pre_commit_streamed(
+			repo_path,
+			&|line| {
+				if line == "started\n" {
+					cancel.store(true, Ordering::Relaxed);
+				}
+			},
+			&cancel,
+		)
+		.unwrap();
+
+		assert_eq!(res, HookResult::NotOk(String::from("started\n")));
+		assert!(start.elapsed() < Duration::from_secs(10));
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_pre_commit_cancel_ignoring_term() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		// the sleep inherits the ignored SIGTERM and keeps the
+		// pipes open until the group is killed
+		let hook = b"#!/bin/sh
+trap '' TERM
+echo 'started'
+sleep 30
+exit 0
+        ";
+
+		create_hook(root, HOOK_PRE_COMMIT, hook);
+
+		let cancel = AtomicBool::new(false);
+		let start = Instant::now();
+		let res = hooks_pre_commit_streamed(
+			repo_path,
+			&|line| {
+				if line == "started\n" {
+					cancel.store(true, Ordering::Relaxed);
+				}
+			},
+			&cancel,
+		)
+		.unwrap();
+
+		assert_eq!(res, HookResult::NotOk(String::from("started\n")));
+		assert!(start.elapsed() < Duration::from_secs(10));
+	}
+
 	#[test]
 	fn test_post_commit_hook_reject_in_subfolder() {
 		let (_td, repo) = repo_init().unwrap();
@@ -386,4 +764,111 @@ exit 1
 			HookResult::NotOk(String::from("rejected\n"))
 		);
 	}
+
+	/// accepts pushing `master` to a new branch of `origin` only
+	const PRE_PUSH_HOOK: &[u8] = b"#!/bin/sh
+test \"$1\" = origin || exit 1
+test \"$2\" = 'https://example.com/my repo.git' || exit 1
+zero=0000000000000000000000000000000000000000
+while read local_ref local_sha remote_ref remote_sha
+do
+	if [ \"$local_ref\" != refs/heads/master ] || [ \"$remote_sha\" != $zero ]
+	then
+		echo \"rejected $local_ref $remote_ref\"
+		exit 1
+	fi
+done
+exit 0
+";
+
+	fn pre_push_updates(
+		repo_path: &str,
+		delete: bool,
+	) -> Vec<PushUpdate> {
+		crate::sync::push_updates(
+			repo_path, "origin", "master", delete,
+		)
+		.unwrap()
+	}
+
+	#[test]
+	fn test_pre_push() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+		let url = "https://example.com/my repo.git";
+
+		repo.remote("origin", url).unwrap();
+		let updates = pre_push_updates(repo_path, false);
+
+		// no hook
+		let res = hooks_pre_push(repo_path, "origin", url, &updates)
+			.unwrap();
+		assert_eq!(res, HookResult::Ok);
+
+		fs::create_dir_all(root.join(HOOKS_DIR)).unwrap();
+		create_hook(root, ".git/hooks/pre-push", PRE_PUSH_HOOK);
+
+		let res = hooks_pre_push(repo_path, "origin", url, &updates)
+			.unwrap();
+		assert_eq!(res, HookResult::Ok);
+
+		let res = hooks_pre_push(
+			repo_path,
+			"origin",
+			url,
+			&pre_push_updates(repo_path, true),
+		)
+		.unwrap();
+		assert_eq!(
+			res,
+			HookResult::NotOk(String::from(
+				"rejected (delete) refs/heads/master\n"
+			))
+		);
+
+		let res =
+			hooks_pre_push(repo_path, "upstream", url, &updates)
+				.unwrap();
+		assert!(res != HookResult::Ok);
+	}
+
+	#[test]
+	fn test_pre_push_hooks_path() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+		let url = "https://example.com/my repo.git";
+
+		repo.remote("origin", url).unwrap();
+		let updates = pre_push_updates(repo_path, true);
+
+		// rejects everything, but is not where hooks are looked up
+		fs::create_dir_all(root.join(HOOKS_DIR)).unwrap();
+		create_hook(
+			root,
+			".git/hooks/pre-push",
+			b"#!/bin/sh\nexit 1\n",
+		);
+
+		fs::create_dir(root.join("hooks")).unwrap();
+		create_hook(root, "hooks/pre-push", PRE_PUSH_HOOK);
+		repo.config()
+			.unwrap()
+			.set_str("core.hooksPath", "hooks")
+			.unwrap();
+
+		let res = hooks_pre_push(
+			repo_path,
+			"origin",
+			url,
+			&pre_push_updates(repo_path, false),
+		)
+		.unwrap();
+		assert_eq!(res, HookResult::Ok);
+
+		let res = hooks_pre_push(repo_path, "origin", url, &updates)
+			.unwrap();
+		assert!(res != HookResult::Ok);
+	}
 }
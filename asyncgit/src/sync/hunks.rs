@@ -1,13 +1,21 @@
 use super::{
-	diff::{get_diff_raw, HunkHeader},
-	utils::repo,
+	diff::{
+		self, get_diff_raw, get_diff_raw_renamed, split_hunk_lines,
+		HunkHeader,
+	},
+	index_lock::retry_on_index_lock,
+	utils::{get_head_repo, repo},
 };
 use crate::{
 	error::{Error, Result},
 	hash,
 };
-use git2::{ApplyLocation, ApplyOptions, Diff};
+use git2::{
+	ApplyLocation, ApplyOptions, Delta, Diff, DiffLine, ObjectType,
+	Patch,
+};
 use scopetime::scope_time;
+use std::{convert::TryFrom, path::Path};
 
 ///
 pub fn stage_hunk(
@@ -21,17 +29,19 @@ pub fn stage_hunk(
 
 	let diff = get_diff_raw(&repo, file_path, false, false, None)?;
 
-	let mut opt = ApplyOptions::new();
-	opt.hunk_callback(|hunk| {
-		hunk.map_or(false, |hunk| {
-			let header = HunkHeader::from(hunk);
-			hash(&header) == hunk_hash
-		})
-	});
+	retry_on_index_lock(repo_path, || {
+		let mut opt = ApplyOptions::new();
+		opt.hunk_callback(|hunk| {
+			hunk.map_or(false, |hunk| {
+				let header = HunkHeader::from(hunk);
+				hash(&header) == hunk_hash
+			})
+		});
 
-	repo.apply(&diff, ApplyLocation::Index, Some(&mut opt))?;
+		repo.apply(&diff, ApplyLocation::Index, Some(&mut opt))?;
 
-	Ok(())
+		Ok(())
+	})
 }
 
 /// this will fail for an all untracked file
@@ -66,6 +76,59 @@ pub fn reset_hunk(
 	}
 }
 
+/// discards the working dir hunk `hunk_hash` like `reset_hunk` and
+/// returns the patch of it, `apply_discarded_hunk` brings it back
+pub fn discard_hunk(
+	repo_path: &str,
+	file_path: &str,
+	hunk_hash: u64,
+) -> Result<Vec<u8>> {
+	scope_time!("discard_hunk");
+
+	let repo = repo(repo_path)?;
+
+	let diff = get_diff_raw(&repo, file_path, false, false, None)?;
+	let patch = Patch::from_diff(&diff, 0)?.ok_or_else(|| {
+		Error::Generic("hunk not found".to_string())
+	})?;
+
+	let hunk_idx = find_patch_hunk(&patch, hunk_hash)?;
+	let (hunk, _) = patch.hunk(hunk_idx)?;
+	let header = HunkHeader::from(hunk);
+	let lines = hunk_lines(&patch, hunk_idx)?;
+
+	// the lines point into the working dir file, so the patch has to
+	// be written before the file is changed
+	let discarded = hunk_part_patch(file_path, header, &lines, false);
+
+	let reverse = Diff::from_buffer(&hunk_part_patch(
+		file_path, header, &lines, true,
+	))?;
+	repo.apply(&reverse, ApplyLocation::WorkDir, None)?;
+
+	Ok(discarded)
+}
+
+/// applies a patch returned by `discard_hunk` to the working dir
+/// again, fails without changing anything if the lines around it
+/// changed in the meantime
+pub fn apply_discarded_hunk(
+	repo_path: &str,
+	patch: &[u8],
+) -> Result<()> {
+	scope_time!("apply_discarded_hunk");
+
+	let repo = repo(repo_path)?;
+
+	repo.apply(
+		&Diff::from_buffer(patch)?,
+		ApplyLocation::WorkDir,
+		None,
+	)?;
+
+	Ok(())
+}
+
 fn find_hunk_index(diff: &Diff, hunk_hash: u64) -> Option<usize> {
 	let mut result = None;
 
@@ -92,18 +155,302 @@ fn find_hunk_index(diff: &Diff, hunk_hash: u64) -> Option<usize> {
 	}
 }
 
-///
+fn find_patch_hunk(patch: &Patch, hunk_hash: u64) -> Result<usize> {
+	(0..patch.num_hunks())
+		.find(|idx| {
+			patch.hunk(*idx).map_or(false, |(hunk, _)| {
+				hash(&HunkHeader::from(hunk)) == hunk_hash
+			})
+		})
+		.ok_or_else(|| Error::Generic("hunk not found".to_string()))
+}
+
+/// lines of a hunk, without the markers of a missing newline at the
+/// end which `hunk_part_patch` writes along with the line they
+/// belong to
+fn hunk_lines<'a>(
+	patch: &'a Patch,
+	hunk_idx: usize,
+) -> Result<Vec<DiffLine<'a>>> {
+	let count = patch.num_lines_in_hunk(hunk_idx)?;
+
+	let mut lines = Vec::with_capacity(count);
+	for line_idx in 0..count {
+		let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+		if !matches!(line.origin(), '=' | '>' | '<') {
+			lines.push(line);
+		}
+	}
+
+	Ok(lines)
+}
+
+/// a patch of `file_path` with a single hunk of `header` and
+/// `lines`, turned around if `reverse`
+fn hunk_part_patch(
+	file_path: &str,
+	header: HunkHeader,
+	lines: &[DiffLine],
+	reverse: bool,
+) -> Vec<u8> {
+	let header = if reverse {
+		HunkHeader {
+			old_start: header.new_start,
+			old_lines: header.new_lines,
+			new_start: header.old_start,
+			new_lines: header.old_lines,
+		}
+	} else {
+		header
+	};
+
+	let mut res = format!(
+		"diff --git a/{0} b/{0}\n--- a/{0}\n+++ b/{0}\n{1}\n",
+		file_path,
+		header.text()
+	)
+	.into_bytes();
+
+	// removed lines have to come before the added ones of the same
+	// change, which turning them around would break
+	let mut added = Vec::new();
+	for line in lines {
+		let origin = match (line.origin(), reverse) {
+			('+', true) => '-',
+			('-', true) => '+',
+			(origin, _) => origin,
+		};
+
+		match origin {
+			'+' => added.push(line),
+			'-' => push_patch_line(&mut res, origin, line),
+			_ => {
+				for added in std::mem::take(&mut added) {
+					push_patch_line(&mut res, '+', added);
+				}
+				push_patch_line(&mut res, origin, line);
+			}
+		}
+	}
+	for added in added {
+		push_patch_line(&mut res, '+', added);
+	}
+
+	res
+}
+
+fn push_patch_line(res: &mut Vec<u8>, origin: char, line: &DiffLine) {
+	res.push(origin as u8);
+	res.extend_from_slice(line.content());
+	if !line.content().ends_with(b"\n") {
+		res.extend_from_slice(b"\n\\ No newline at end of file\n");
+	}
+}
+
+/// stages the part `part_hash` of the hunk `hunk_hash` as split by
+/// `diff::split_hunk`, a part of a staged hunk (`is_stage`) is
+/// unstaged. only the index is changed, like staging lines
+pub fn stage_hunk_part(
+	repo_path: &str,
+	file_path: &str,
+	is_stage: bool,
+	hunk_hash: u64,
+	part_hash: u64,
+) -> Result<()> {
+	scope_time!("stage_hunk_part");
+
+	let repo = repo(repo_path)?;
+
+	let diff = get_diff_raw(&repo, file_path, is_stage, false, None)?;
+	let patch = Patch::from_diff(&diff, 0)?.ok_or_else(|| {
+		Error::Generic("hunk not found".to_string())
+	})?;
+
+	let hunk_idx = find_patch_hunk(&patch, hunk_hash)?;
+	let (hunk, _) = patch.hunk(hunk_idx)?;
+	let lines = hunk_lines(&patch, hunk_idx)?;
+
+	let positions = lines
+		.iter()
+		.map(|line| diff::DiffLine {
+			line_type: line.origin_value().into(),
+			position: line.into(),
+			..diff::DiffLine::default()
+		})
+		.collect::<Vec<_>>();
+
+	let (header, range) =
+		split_hunk_lines(HunkHeader::from(hunk), &positions)
+			.and_then(|parts| {
+				parts
+					.into_iter()
+					.find(|(header, _)| hash(header) == part_hash)
+			})
+			.ok_or_else(|| {
+				Error::Generic("hunk part not found".to_string())
+			})?;
+
+	let part = Diff::from_buffer(&hunk_part_patch(
+		file_path,
+		header,
+		&lines[range],
+		is_stage,
+	))?;
+
+	retry_on_index_lock(repo_path, || {
+		repo.apply(&part, ApplyLocation::Index, None)?;
+		Ok(())
+	})
+}
+
+/// splits after each `\n`, keeping it
+fn split_lines(data: &[u8]) -> Vec<&[u8]> {
+	let mut res = Vec::new();
+	let mut start = 0;
+	for (idx, b) in data.iter().enumerate() {
+		if *b == b'\n' {
+			res.push(&data[start..=idx]);
+			start = idx + 1;
+		}
+	}
+	if start < data.len() {
+		res.push(&data[start..]);
+	}
+	res
+}
+
+/// header and pre-image (context and removed lines) of a hunk
+fn hunk_old_side(
+	patch: &Patch,
+	hunk_idx: usize,
+) -> Result<(HunkHeader, Vec<u8>)> {
+	let (hunk, lines) = patch.hunk(hunk_idx)?;
+	let header = HunkHeader::from(hunk);
+
+	let mut old_side = Vec::new();
+	for line_idx in 0..lines {
+		let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+		if matches!(line.origin(), ' ' | '-') {
+			old_side.extend_from_slice(line.content());
+		}
+	}
+
+	Ok((header, old_side))
+}
+
+/// `content` with the post-image of a hunk replaced by its pre-image
+fn unapply_hunk(
+	content: &[u8],
+	header: HunkHeader,
+	old_side: &[u8],
+) -> Result<Vec<u8>> {
+	let lines = split_lines(content);
+
+	// an empty post-image starts after `new_start`
+	let start = if header.new_lines == 0 {
+		header.new_start
+	} else {
+		header.new_start.saturating_sub(1)
+	} as usize;
+	let end = start + header.new_lines as usize;
+
+	if end > lines.len() {
+		return Err(Error::Generic(String::from(
+			"hunk does not match the index",
+		)));
+	}
+
+	let mut res = lines[..start].concat();
+	res.extend_from_slice(old_side);
+	res.extend(lines[end..].concat());
+
+	Ok(res)
+}
+
+/// reverse-applies the staged hunk to the index only, the working
+/// dir stays untouched. a new or deleted file has a single hunk, it
+/// is unstaged as a whole
 pub fn unstage_hunk(
 	repo_path: &str,
 	file_path: &str,
 	hunk_hash: u64,
 ) -> Result<bool> {
-	scope_time!("revert_hunk");
+	scope_time!("unstage_hunk");
 
 	let repo = repo(repo_path)?;
 
 	let diff = get_diff_raw(&repo, file_path, true, false, None)?;
-	let diff_count_positive = diff.deltas().len();
+	let patch = Patch::from_diff(&diff, 0)?.ok_or_else(|| {
+		Error::Generic("hunk not found".to_string())
+	})?;
+
+	let hunk_idx = find_patch_hunk(&patch, hunk_hash)?;
+
+	if matches!(patch.delta().status(), Delta::Added | Delta::Deleted)
+	{
+		let head =
+			if let Ok(id) = get_head_repo(&repo) {
+				Some(repo.find_object(
+					id.into(),
+					Some(ObjectType::Commit),
+				)?)
+			} else {
+				None
+			};
+
+		retry_on_index_lock(repo_path, || {
+			repo.reset_default(head.as_ref(), &[file_path])?;
+			Ok(())
+		})?;
+
+		return Ok(true);
+	}
+
+	let (header, old_side) = hunk_old_side(&patch, hunk_idx)?;
+
+	retry_on_index_lock(repo_path, || {
+		let mut index = repo.index()?;
+		let mut entry = index
+			.get_path(Path::new(file_path), 0)
+			.ok_or_else(|| {
+				Error::Generic(format!(
+					"'{}' not in index",
+					file_path
+				))
+			})?;
+
+		let content = unapply_hunk(
+			repo.find_blob(entry.id)?.content(),
+			header,
+			&old_side,
+		)?;
+
+		entry.id = repo.blob(&content)?;
+		entry.file_size = u32::try_from(content.len())?;
+
+		index.add(&entry)?;
+		index.write()?;
+
+		Ok(())
+	})?;
+
+	Ok(true)
+}
+
+/// same as `unstage_hunk` for a file staged as renamed from
+/// `old_path`, the rename itself stays staged
+pub fn unstage_hunk_renamed(
+	repo_path: &str,
+	old_path: &str,
+	file_path: &str,
+	hunk_hash: u64,
+) -> Result<bool> {
+	scope_time!("unstage_hunk_renamed");
+
+	let repo = repo(repo_path)?;
+
+	let diff =
+		get_diff_raw_renamed(&repo, old_path, file_path, None)?;
 
 	let hunk_index = find_hunk_index(&diff, hunk_hash);
 	let hunk_index = hunk_index.map_or_else(
@@ -111,35 +458,51 @@ pub fn unstage_hunk(
 		Ok,
 	)?;
 
-	let diff = get_diff_raw(&repo, file_path, true, true, None)?;
+	// reversing the renamed delta would move the file back in the
+	// index, so revert the content of the new path only
+	let head = repo.head()?.peel_to_tree()?;
+	let old_blob =
+		repo.find_blob(head.get_path(Path::new(old_path))?.id())?;
+	let new_entry = repo
+		.index()?
+		.get_path(Path::new(file_path), 0)
+		.ok_or_else(|| {
+		Error::Generic(format!("'{}' not in index", file_path))
+	})?;
+	let new_blob = repo.find_blob(new_entry.id)?;
+
+	let mut patch = Patch::from_blobs(
+		&new_blob,
+		Some(Path::new(file_path)),
+		&old_blob,
+		Some(Path::new(file_path)),
+		None,
+	)?;
+	let diff = Diff::from_buffer(&patch.to_buf()?)?;
 
-	if diff.deltas().len() != diff_count_positive {
-		return Err(Error::Generic(format!(
-			"hunk error: {}!={}",
-			diff.deltas().len(),
-			diff_count_positive
-		)));
-	}
+	let count = retry_on_index_lock(repo_path, || {
+		let mut count = 0;
+		{
+			let mut hunk_idx = 0;
+			let mut opt = ApplyOptions::new();
+			opt.hunk_callback(|_hunk| {
+				let res = if hunk_idx == hunk_index {
+					count += 1;
+					true
+				} else {
+					false
+				};
 
-	let mut count = 0;
-	{
-		let mut hunk_idx = 0;
-		let mut opt = ApplyOptions::new();
-		opt.hunk_callback(|_hunk| {
-			let res = if hunk_idx == hunk_index {
-				count += 1;
-				true
-			} else {
-				false
-			};
+				hunk_idx += 1;
 
-			hunk_idx += 1;
+				res
+			});
 
-			res
-		});
+			repo.apply(&diff, ApplyLocation::Index, Some(&mut opt))?;
+		}
 
-		repo.apply(&diff, ApplyLocation::Index, Some(&mut opt))?;
-	}
+		Ok(count)
+	})?;
 
 	Ok(count == 1)
 }
@@ -149,14 +512,40 @@ mod tests {
 	use super::*;
 	use crate::{
 		error::Result,
-		sync::{diff::get_diff, tests::repo_init_empty},
+		sync::{
+			diff::{get_diff, get_diff_renamed, split_hunk},
+			stage_add_file, stage_addremoved,
+			tests::{repo_init, repo_init_empty, write_commit_file},
+		},
 	};
+	use git2::Repository;
 	use std::{
 		fs::{self, File},
 		io::Write,
 		path::Path,
 	};
 
+	fn index_content(repo_path: &str, path: &str) -> Option<String> {
+		let repo = Repository::open(repo_path).unwrap();
+		let entry =
+			repo.index().unwrap().get_path(Path::new(path), 0)?;
+		let blob = repo.find_blob(entry.id).unwrap();
+		Some(String::from_utf8(blob.content().to_vec()).unwrap())
+	}
+
+	/// 20 numbered lines, `changes` replace single lines
+	fn numbered_lines(changes: &[(usize, &str)]) -> String {
+		let mut res = String::new();
+		for idx in 1..=20 {
+			match changes.iter().find(|(line, _)| *line == idx) {
+				Some((_, text)) => res.push_str(text),
+				None => res.push_str(&idx.to_string()),
+			}
+			res.push('\n');
+		}
+		res
+	}
+
 	#[test]
 	fn reset_untracked_file_which_will_not_find_hunk() -> Result<()> {
 		let file_path = Path::new("foo/foo.txt");
@@ -185,4 +574,360 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_unstage_hunk_renamed() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "foo.txt", "a\nb\nc\nd\ne\n", "c1");
+
+		fs::remove_file(root.join("foo.txt"))?;
+		File::create(&root.join("bar.txt"))?
+			.write_all(b"a\nb\nc\nd\nf\n")?;
+
+		stage_addremoved(repo_path, Path::new("foo.txt"))?;
+		stage_add_file(repo_path, Path::new("bar.txt"))?;
+
+		let diff =
+			get_diff_renamed(repo_path, "foo.txt", "bar.txt", None)?;
+		assert_eq!(diff.hunks.len(), 1);
+
+		assert!(unstage_hunk_renamed(
+			repo_path,
+			"foo.txt",
+			"bar.txt",
+			diff.hunks[0].header_hash,
+		)?);
+
+		// rename stays staged, content change is back in the workdir
+		let diff =
+			get_diff_renamed(repo_path, "foo.txt", "bar.txt", None)?;
+		assert_eq!(diff.hunks.len(), 0);
+
+		let diff = get_diff(repo_path, "bar.txt", false, None)?;
+		assert_eq!(diff.hunks.len(), 1);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_unstage_one_of_two_hunks() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(
+			&repo,
+			"foo.txt",
+			&numbered_lines(&[]),
+			"c1",
+		);
+
+		let changed = numbered_lines(&[(2, "two"), (18, "eighteen")]);
+		fs::write(root.join("foo.txt"), &changed)?;
+		stage_add_file(repo_path, Path::new("foo.txt"))?;
+
+		let diff = get_diff(repo_path, "foo.txt", true, None)?;
+		assert_eq!(diff.hunks.len(), 2);
+
+		assert!(unstage_hunk(
+			repo_path,
+			"foo.txt",
+			diff.hunks[0].header_hash
+		)?);
+
+		assert_eq!(
+			index_content(repo_path, "foo.txt").unwrap(),
+			numbered_lines(&[(18, "eighteen")])
+		);
+		assert_eq!(
+			fs::read_to_string(root.join("foo.txt"))?,
+			changed
+		);
+
+		assert_eq!(
+			get_diff(repo_path, "foo.txt", true, None)?.hunks.len(),
+			1
+		);
+		assert_eq!(
+			get_diff(repo_path, "foo.txt", false, None)?.hunks.len(),
+			1
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_unstage_hunk_lines_added_and_removed() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "foo.txt", "a\nb\nc", "c1");
+
+		// insert at the top, drop the last line (without newline)
+		fs::write(root.join("foo.txt"), "new\na\nb\n")?;
+		stage_add_file(repo_path, Path::new("foo.txt"))?;
+
+		let diff = get_diff(repo_path, "foo.txt", true, None)?;
+		assert_eq!(diff.hunks.len(), 1);
+
+		unstage_hunk(
+			repo_path,
+			"foo.txt",
+			diff.hunks[0].header_hash,
+		)?;
+
+		assert_eq!(
+			index_content(repo_path, "foo.txt").unwrap(),
+			"a\nb\nc"
+		);
+		assert_eq!(
+			fs::read_to_string(root.join("foo.txt"))?,
+			"new\na\nb\n"
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_stage_hunk_parts() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(
+			&repo,
+			"foo.txt",
+			&numbered_lines(&[]),
+			"c1",
+		);
+
+		let changed =
+			numbered_lines(&[(5, "five"), (7, "seven"), (9, "nine")]);
+		fs::write(root.join("foo.txt"), &changed)?;
+
+		let diff = get_diff(repo_path, "foo.txt", false, None)?;
+		assert_eq!(diff.hunks.len(), 1);
+		let parts = split_hunk(&diff.hunks[0]).unwrap();
+		assert_eq!(parts.len(), 3);
+
+		stage_hunk_part(
+			repo_path,
+			"foo.txt",
+			false,
+			diff.hunks[0].header_hash,
+			parts[0].header_hash,
+		)?;
+
+		assert_eq!(
+			index_content(repo_path, "foo.txt").unwrap(),
+			numbered_lines(&[(5, "five")])
+		);
+
+		// the rest of the hunk splits again, stage its last part
+		let diff = get_diff(repo_path, "foo.txt", false, None)?;
+		let parts = split_hunk(&diff.hunks[0]).unwrap();
+		assert_eq!(parts.len(), 2);
+
+		stage_hunk_part(
+			repo_path,
+			"foo.txt",
+			false,
+			diff.hunks[0].header_hash,
+			parts[1].header_hash,
+		)?;
+
+		assert_eq!(
+			index_content(repo_path, "foo.txt").unwrap(),
+			numbered_lines(&[(5, "five"), (9, "nine")])
+		);
+
+		// and unstage it again
+		let diff = get_diff(repo_path, "foo.txt", true, None)?;
+		let parts = split_hunk(&diff.hunks[0]).unwrap();
+		assert_eq!(parts.len(), 2);
+
+		stage_hunk_part(
+			repo_path,
+			"foo.txt",
+			true,
+			diff.hunks[0].header_hash,
+			parts[1].header_hash,
+		)?;
+
+		assert_eq!(
+			index_content(repo_path, "foo.txt").unwrap(),
+			numbered_lines(&[(5, "five")])
+		);
+		assert_eq!(
+			fs::read_to_string(root.join("foo.txt"))?,
+			changed
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_stage_hunk_part_no_newline() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "foo.txt", "a\nb\nc", "c1");
+		fs::write(root.join("foo.txt"), "A\nb\nC")?;
+
+		let diff = get_diff(repo_path, "foo.txt", false, None)?;
+		let parts = split_hunk(&diff.hunks[0]).unwrap();
+		assert_eq!(parts.len(), 2);
+
+		stage_hunk_part(
+			repo_path,
+			"foo.txt",
+			false,
+			diff.hunks[0].header_hash,
+			parts[1].header_hash,
+		)?;
+
+		assert_eq!(
+			index_content(repo_path, "foo.txt").unwrap(),
+			"a\nb\nC"
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_unstage_hunk_new_file() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::write(root.join("new.txt"), "a\n")?;
+		stage_add_file(repo_path, Path::new("new.txt"))?;
+
+		let diff = get_diff(repo_path, "new.txt", true, None)?;
+		unstage_hunk(
+			repo_path,
+			"new.txt",
+			diff.hunks[0].header_hash,
+		)?;
+
+		assert_eq!(index_content(repo_path, "new.txt"), None);
+		assert!(root.join("new.txt").exists());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_discard_hunk_undo() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(
+			&repo,
+			"foo.txt",
+			&numbered_lines(&[]),
+			"c1",
+		);
+
+		let changed = numbered_lines(&[(2, "two"), (18, "eighteen")]);
+		fs::write(root.join("foo.txt"), &changed)?;
+
+		let diff = get_diff(repo_path, "foo.txt", false, None)?;
+		assert_eq!(diff.hunks.len(), 2);
+
+		let patch = discard_hunk(
+			repo_path,
+			"foo.txt",
+			diff.hunks[1].header_hash,
+		)?;
+
+		assert_eq!(
+			fs::read_to_string(root.join("foo.txt"))?,
+			numbered_lines(&[(2, "two")])
+		);
+
+		apply_discarded_hunk(repo_path, &patch)?;
+
+		assert_eq!(
+			fs::read_to_string(root.join("foo.txt"))?,
+			changed
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_discard_hunk_undo_no_newline() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "foo.txt", "a\nb\nc", "c1");
+
+		let changed = "new\na\nb\nd";
+		fs::write(root.join("foo.txt"), changed)?;
+
+		let diff = get_diff(repo_path, "foo.txt", false, None)?;
+		assert_eq!(diff.hunks.len(), 1);
+
+		let patch = discard_hunk(
+			repo_path,
+			"foo.txt",
+			diff.hunks[0].header_hash,
+		)?;
+
+		assert_eq!(
+			fs::read_to_string(root.join("foo.txt"))?,
+			"a\nb\nc"
+		);
+
+		apply_discarded_hunk(repo_path, &patch)?;
+
+		assert_eq!(
+			fs::read_to_string(root.join("foo.txt"))?,
+			changed
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_discard_hunk_undo_after_change() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(
+			&repo,
+			"foo.txt",
+			&numbered_lines(&[]),
+			"c1",
+		);
+
+		fs::write(
+			root.join("foo.txt"),
+			numbered_lines(&[(10, "ten")]),
+		)?;
+
+		let diff = get_diff(repo_path, "foo.txt", false, None)?;
+		let patch = discard_hunk(
+			repo_path,
+			"foo.txt",
+			diff.hunks[0].header_hash,
+		)?;
+
+		// the lines the hunk needs to apply are gone
+		let edited = numbered_lines(&[(9, "nine"), (11, "eleven")]);
+		fs::write(root.join("foo.txt"), &edited)?;
+
+		assert!(apply_discarded_hunk(repo_path, &patch).is_err());
+		assert_eq!(fs::read_to_string(root.join("foo.txt"))?, edited);
+
+		Ok(())
+	}
 }
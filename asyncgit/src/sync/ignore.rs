@@ -2,9 +2,10 @@ use super::utils::{repo, work_dir};
 use crate::error::{Error, Result};
 use scopetime::scope_time;
 use std::{
+	collections::HashSet,
 	fs::{File, OpenOptions},
 	io::{Read, Seek, SeekFrom, Write},
-	path::Path,
+	path::{Path, PathBuf},
 };
 
 static GITIGNORE: &str = ".gitignore";
@@ -46,6 +47,26 @@ pub fn add_to_ignore(
 	Ok(())
 }
 
+/// returns those of `paths` the current ignore rules would ignore
+pub fn paths_ignored<'a>(
+	repo_path: &str,
+	paths: impl Iterator<Item = &'a Path>,
+) -> Result<HashSet<PathBuf>> {
+	scope_time!("paths_ignored");
+
+	let repo = repo(repo_path)?;
+
+	let mut res = HashSet::new();
+	for path in paths {
+		let relative = path.strip_prefix("./").unwrap_or(path);
+		if repo.is_path_ignored(relative)? {
+			res.insert(path.to_path_buf());
+		}
+	}
+
+	Ok(res)
+}
+
 fn file_ends_with_newline(file: &Path) -> Result<bool> {
 	let mut file = File::open(file)?;
 	let size = file.metadata()?.len();
@@ -134,6 +155,32 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_paths_ignored() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		repo_write_file(&repo, ".gitignore", "*.log\nbuild/\n")?;
+
+		let paths = [
+			Path::new("./foo.txt"),
+			Path::new("./bar.log"),
+			Path::new("./build/out"),
+			Path::new("./src/baz.log"),
+		];
+
+		let ignored =
+			paths_ignored(repo_path, paths.iter().copied())?;
+
+		assert_eq!(ignored.len(), 3);
+		assert!(!ignored.contains(Path::new("./foo.txt")));
+		assert!(ignored.contains(Path::new("./bar.log")));
+		assert!(ignored.contains(Path::new("./build/out")));
+
+		Ok(())
+	}
+
 	#[test]
 	fn test_ignore_ignore() {
 		let ignore_file_path = Path::new(".gitignore");
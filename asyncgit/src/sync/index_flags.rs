@@ -0,0 +1,259 @@
+//! index entries flagged to hide their working dir changes
+//! (`git update-index --assume-unchanged` / `--skip-worktree`)
+
+use super::{index_lock::retry_on_index_lock, utils::repo};
+use crate::error::{Error, Result};
+use git2::{IndexEntryExtendedFlag, IndexEntryFlag};
+use scopetime::scope_time;
+use std::path::Path;
+
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexFlag {
+	/// `--assume-unchanged`, a promise not to change the file
+	AssumeUnchanged,
+	/// `--skip-worktree`, the working dir version is to be kept
+	SkipWorktree,
+}
+
+/// an index entry with at least one of the flags set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlaggedFile {
+	///
+	pub path: String,
+	///
+	pub assume_unchanged: bool,
+	///
+	pub skip_worktree: bool,
+}
+
+impl FlaggedFile {
+	/// whether `path` (a file, a folder or `*` for all) contains it
+	pub fn is_in(&self, path: &str) -> bool {
+		path_contains(path, &self.path)
+	}
+}
+
+/// whether `path` (a file, a folder or `*` for all) contains `file`
+pub fn path_contains(path: &str, file: &str) -> bool {
+	let path = path.trim_end_matches('/');
+	path == "*"
+		|| file == path
+		|| file
+			.strip_prefix(path)
+			.map_or(false, |rest| rest.starts_with('/'))
+}
+
+/// index entries with `assume-unchanged` or `skip-worktree` set,
+/// ordered by path
+pub fn index_flagged_files(
+	repo_path: &str,
+) -> Result<Vec<FlaggedFile>> {
+	scope_time!("index_flagged_files");
+
+	let repo = repo(repo_path)?;
+	let index = repo.index()?;
+
+	Ok(index
+		.iter()
+		.filter_map(|entry| {
+			let assume_unchanged =
+				IndexEntryFlag::from_bits_truncate(entry.flags)
+					.is_valid();
+			let skip_worktree =
+				IndexEntryExtendedFlag::from_bits_truncate(
+					entry.flags_extended,
+				)
+				.is_skip_worktree();
+
+			(assume_unchanged || skip_worktree).then(|| FlaggedFile {
+				path: String::from_utf8_lossy(&entry.path)
+					.to_string(),
+				assume_unchanged,
+				skip_worktree,
+			})
+		})
+		.collect())
+}
+
+/// sets or clears `flag` on the index entry of `path`
+pub fn set_index_flag(
+	repo_path: &str,
+	path: &str,
+	flag: IndexFlag,
+	set: bool,
+) -> Result<()> {
+	scope_time!("set_index_flag");
+
+	let repo = repo(repo_path)?;
+
+	retry_on_index_lock(repo_path, || {
+		let mut index = repo.index()?;
+
+		let mut entry =
+			index.get_path(Path::new(path), 0).ok_or_else(|| {
+				Error::Generic(format!("'{}' is not tracked", path))
+			})?;
+
+		match flag {
+			IndexFlag::AssumeUnchanged => {
+				let mut flags =
+					IndexEntryFlag::from_bits_truncate(entry.flags);
+				flags.set(IndexEntryFlag::VALID, set);
+				entry.flags = flags.bits()
+					| (entry.flags & !IndexEntryFlag::all().bits());
+			}
+			IndexFlag::SkipWorktree => {
+				let mut flags =
+					IndexEntryExtendedFlag::from_bits_truncate(
+						entry.flags_extended,
+					);
+				flags.set(IndexEntryExtendedFlag::SKIP_WORKTREE, set);
+				entry.flags_extended = flags.bits()
+					| (entry.flags_extended
+						& !IndexEntryExtendedFlag::all().bits());
+			}
+		}
+
+		index.add(&entry)?;
+		index.write()?;
+
+		Ok(())
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		status::{get_status, StatusType},
+		tests::{debug_cmd_print, repo_init, write_commit_file},
+		utils::repo_write_file,
+	};
+
+	#[test]
+	fn test_list_and_clear() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		std::fs::create_dir(root.join("dir")).unwrap();
+		write_commit_file(&repo, "a.txt", "a", "a");
+		write_commit_file(&repo, "dir/b.txt", "b", "b");
+		write_commit_file(&repo, "c.txt", "c", "c");
+
+		debug_cmd_print(
+			repo_path,
+			"git update-index --skip-worktree dir/b.txt",
+		);
+		debug_cmd_print(
+			repo_path,
+			"git update-index --assume-unchanged a.txt",
+		);
+
+		assert_eq!(
+			index_flagged_files(repo_path).unwrap(),
+			vec![
+				FlaggedFile {
+					path: String::from("a.txt"),
+					assume_unchanged: true,
+					skip_worktree: false,
+				},
+				FlaggedFile {
+					path: String::from("dir/b.txt"),
+					assume_unchanged: false,
+					skip_worktree: true,
+				},
+			]
+		);
+
+		// hidden while flagged
+		repo_write_file(&repo, "dir/b.txt", "changed").unwrap();
+		assert!(get_status(repo_path, StatusType::WorkingDir, None)
+			.unwrap()
+			.is_empty());
+
+		set_index_flag(
+			repo_path,
+			"dir/b.txt",
+			IndexFlag::SkipWorktree,
+			false,
+		)
+		.unwrap();
+		set_index_flag(
+			repo_path,
+			"a.txt",
+			IndexFlag::AssumeUnchanged,
+			false,
+		)
+		.unwrap();
+
+		assert!(index_flagged_files(repo_path).unwrap().is_empty());
+
+		// changes show up again
+		assert_eq!(
+			get_status(repo_path, StatusType::WorkingDir, None)
+				.unwrap()
+				.len(),
+			1
+		);
+	}
+
+	#[test]
+	fn test_set() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", "a", "a");
+
+		set_index_flag(
+			repo_path,
+			"a.txt",
+			IndexFlag::SkipWorktree,
+			true,
+		)
+		.unwrap();
+
+		assert_eq!(
+			index_flagged_files(repo_path).unwrap(),
+			vec![FlaggedFile {
+				path: String::from("a.txt"),
+				assume_unchanged: false,
+				skip_worktree: true,
+			}]
+		);
+
+		// git itself sees the flag
+		let out = std::process::Command::new("git")
+			.args(&["ls-files", "-v", "a.txt"])
+			.current_dir(repo_path)
+			.output()
+			.unwrap();
+		assert_eq!(String::from_utf8_lossy(&out.stdout), "S a.txt\n");
+
+		assert!(set_index_flag(
+			repo_path,
+			"missing.txt",
+			IndexFlag::SkipWorktree,
+			true
+		)
+		.is_err());
+	}
+
+	#[test]
+	fn test_is_in() {
+		let file = FlaggedFile {
+			path: String::from("dir/b.txt"),
+			assume_unchanged: false,
+			skip_worktree: true,
+		};
+
+		assert!(file.is_in("*"));
+		assert!(file.is_in("dir"));
+		assert!(file.is_in("dir/"));
+		assert!(file.is_in("dir/b.txt"));
+		assert!(!file.is_in("di"));
+		assert!(!file.is_in("dir/b"));
+	}
+}
@@ -0,0 +1,123 @@
+//! retrying index changes while another git process holds `index.lock`
+
+use super::config::get_config_string;
+use crate::error::{Error, Result};
+use git2::{ErrorClass, ErrorCode, Repository};
+use std::{
+	thread,
+	time::{Duration, Instant},
+};
+
+/// git config entry overriding how long (in milliseconds) index
+/// changes wait for a concurrent git process to release the index
+const TIMEOUT_CONFIG: &str = "gitui.indexLockTimeout";
+const TIMEOUT_DEFAULT: Duration = Duration::from_secs(2);
+
+const BACKOFF_START: Duration = Duration::from_millis(10);
+const BACKOFF_MAX: Duration = Duration::from_millis(250);
+
+fn timeout(repo_path: &str) -> Duration {
+	get_config_string(repo_path, TIMEOUT_CONFIG)
+		.ok()
+		.flatten()
+		.and_then(|ms| ms.trim().parse::<u64>().ok())
+		.map_or(TIMEOUT_DEFAULT, Duration::from_millis)
+}
+
+fn is_locked(err: &Error) -> bool {
+	matches!(err, Error::Git(e) if e.code() == ErrorCode::Locked)
+}
+
+/// fails like libgit2 does on a locked index, for operations only
+/// reading the index that should still not race a concurrent writer
+#[allow(clippy::redundant_pub_crate)]
+pub(crate) fn ensure_index_unlocked(repo: &Repository) -> Result<()> {
+	if repo.path().join("index.lock").exists() {
+		return Err(Error::Git(git2::Error::new(
+			ErrorCode::Locked,
+			ErrorClass::Index,
+			"index.lock exists",
+		)));
+	}
+
+	Ok(())
+}
+
+/// runs `f` again with backoff as long as it fails on a lock held by
+/// another process, gives up with `Error::IndexLocked` after the timeout
+#[allow(clippy::redundant_pub_crate)]
+pub(crate) fn retry_on_index_lock<T, F>(
+	repo_path: &str,
+	mut f: F,
+) -> Result<T>
+where
+	F: FnMut() -> Result<T>,
+{
+	let mut deadline = None;
+	let mut backoff = BACKOFF_START;
+
+	loop {
+		match f() {
+			Err(e) if is_locked(&e) => {
+				let now = Instant::now();
+				let deadline = *deadline
+					.get_or_insert_with(|| now + timeout(repo_path));
+
+				if now >= deadline {
+					log::warn!("index locked: {}", e);
+					return Err(Error::IndexLocked);
+				}
+
+				thread::sleep(backoff.min(deadline - now));
+				backoff = (backoff * 2).min(BACKOFF_MAX);
+			}
+			res => return res,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		error::Error,
+		sync::{
+			commit, stage_add_file,
+			tests::{repo_init, write_commit_file},
+			utils::repo_write_file,
+		},
+	};
+	use std::{fs, path::Path, time::Instant};
+
+	#[test]
+	fn test_stale_index_lock() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		repo.config()
+			.unwrap()
+			.set_str("gitui.indexLockTimeout", "100")
+			.unwrap();
+
+		write_commit_file(&repo, "foo", "a", "c1");
+		repo_write_file(&repo, "foo", "b").unwrap();
+
+		let lock = repo.path().join("index.lock");
+		fs::write(&lock, "").unwrap();
+
+		let start = Instant::now();
+		let res = stage_add_file(repo_path, Path::new("foo"));
+		assert!(matches!(res, Err(Error::IndexLocked)));
+		assert!(start.elapsed().as_millis() >= 100);
+
+		assert!(matches!(
+			commit(repo_path, "c2", false),
+			Err(Error::IndexLocked)
+		));
+
+		fs::remove_file(&lock).unwrap();
+
+		stage_add_file(repo_path, Path::new("foo")).unwrap();
+		commit(repo_path, "c2", false).unwrap();
+	}
+}
@@ -0,0 +1,323 @@
+//! files too large to be committed by accident
+//!
+//! sizes come from the object headers and the file metadata, the
+//! content is only read to tell binary files apart
+
+use super::{
+	lfs::is_binary,
+	utils::{repo, work_dir},
+};
+use crate::error::Result;
+use git2::{
+	Delta, Oid, Repository, Status, StatusOptions, StatusShow, Tree,
+};
+use scopetime::scope_time;
+use std::{convert::TryFrom, fs, path::Path};
+
+/// a file of at least the threshold size that was not as large in
+/// `HEAD`: either new or grown past the threshold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LargeFile {
+	///
+	pub path: String,
+	/// in bytes
+	pub size: u64,
+	/// size in `HEAD`, `None` if the file is new
+	pub head_size: Option<u64>,
+}
+
+/// files in the index that are new or grown past `threshold` bytes
+/// compared to `HEAD`
+pub fn staged_large_files(
+	repo_path: &str,
+	threshold: u64,
+) -> Result<Vec<LargeFile>> {
+	scope_time!("staged_large_files");
+
+	let repo = repo(repo_path)?;
+	let head = head_tree(&repo)?;
+	let diff = repo.diff_tree_to_index(head.as_ref(), None, None)?;
+	let odb = repo.odb()?;
+
+	let mut res = Vec::new();
+	for delta in diff.deltas() {
+		if delta.status() == Delta::Deleted {
+			continue;
+		}
+
+		let path =
+			match delta.new_file().path().and_then(Path::to_str) {
+				Some(path) => path,
+				None => continue,
+			};
+
+		let size =
+			size_u64(odb.read_header(delta.new_file().id())?.0);
+		if let Some(file) =
+			large_file(&repo, head.as_ref(), path, size, threshold)?
+		{
+			res.push(file);
+		}
+	}
+
+	Ok(res)
+}
+
+/// working dir files matching the pathspec `pattern` that staging
+/// would add as new or grown past `threshold` bytes compared to `HEAD`
+pub fn large_files_to_stage(
+	repo_path: &str,
+	pattern: &str,
+	threshold: u64,
+) -> Result<Vec<LargeFile>> {
+	scope_time!("large_files_to_stage");
+
+	let repo = repo(repo_path)?;
+	let work_dir = work_dir(&repo)?;
+	let head = head_tree(&repo)?;
+
+	let mut options = StatusOptions::default();
+	options
+		.show(StatusShow::Workdir)
+		.pathspec(pattern)
+		.include_untracked(true)
+		.recurse_untracked_dirs(true);
+
+	let statuses = repo.statuses(Some(&mut options))?;
+
+	let mut res = Vec::new();
+	for entry in statuses.iter() {
+		if !entry.status().intersects(
+			Status::WT_NEW
+				| Status::WT_MODIFIED
+				| Status::WT_TYPECHANGE,
+		) {
+			continue;
+		}
+
+		let path = match entry.path() {
+			Some(path) => path,
+			None => continue,
+		};
+
+		let size = match file_size(&work_dir.join(path)) {
+			Some(size) => size,
+			None => continue,
+		};
+
+		if let Some(file) =
+			large_file(&repo, head.as_ref(), path, size, threshold)?
+		{
+			res.push(file);
+		}
+	}
+
+	Ok(res)
+}
+
+/// size of the file at `path` if it is binary
+pub fn workdir_binary_size(path: &Path) -> Option<u64> {
+	file_size(path).filter(|_| is_binary(path))
+}
+
+/// size of the blob `id` if it is binary, loads the blob like
+/// showing its diff would
+pub fn blob_binary_size(repo: &Repository, id: Oid) -> Option<u64> {
+	if id.is_zero() {
+		return None;
+	}
+
+	repo.find_blob(id)
+		.ok()
+		.filter(git2::Blob::is_binary)
+		.map(|blob| size_u64(blob.size()))
+}
+
+/// size of the blob `id` staged for the file at `path` if it is
+/// binary, the file is checked instead of loading the blob if it has
+/// the same size (the status is refreshed often)
+pub fn staged_binary_size(
+	repo: &Repository,
+	path: &Path,
+	id: Oid,
+) -> Option<u64> {
+	if id.is_zero() {
+		return None;
+	}
+
+	let size = size_u64(repo.odb().ok()?.read_header(id).ok()?.0);
+	if file_size(path) == Some(size) {
+		return is_binary(path).then(|| size);
+	}
+
+	blob_binary_size(repo, id)
+}
+
+/// `None` if the file is below `threshold` or was already as large
+/// in `HEAD`
+fn large_file(
+	repo: &Repository,
+	head: Option<&Tree>,
+	path: &str,
+	size: u64,
+	threshold: u64,
+) -> Result<Option<LargeFile>> {
+	if size < threshold {
+		return Ok(None);
+	}
+
+	let head_size =
+		match head.map(|tree| tree.get_path(Path::new(path))) {
+			Some(Ok(entry)) => {
+				Some(size_u64(repo.odb()?.read_header(entry.id())?.0))
+			}
+			_ => None,
+		};
+
+	if head_size.map_or(false, |head_size| head_size >= threshold) {
+		return Ok(None);
+	}
+
+	Ok(Some(LargeFile {
+		path: path.to_string(),
+		size,
+		head_size,
+	}))
+}
+
+/// `None` while the head is unborn
+fn head_tree(repo: &Repository) -> Result<Option<Tree>> {
+	Ok(match repo.head() {
+		Ok(head) => Some(head.peel_to_tree()?),
+		Err(_) => None,
+	})
+}
+
+/// `None` for anything but a regular file
+fn file_size(path: &Path) -> Option<u64> {
+	fs::symlink_metadata(path)
+		.ok()
+		.filter(fs::Metadata::is_file)
+		.map(|meta| meta.len())
+}
+
+fn size_u64(size: usize) -> u64 {
+	u64::try_from(size).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		commit,
+		status::{get_status, StatusType},
+		tests::repo_init,
+		utils::stage_add_file,
+	};
+
+	const THRESHOLD: u64 = 1000;
+
+	fn write(root: &Path, name: &str, size: usize) {
+		fs::write(root.join(name), vec![b'a'; size]).unwrap();
+	}
+
+	fn paths(files: &[LargeFile]) -> Vec<&str> {
+		files.iter().map(|file| file.path.as_str()).collect()
+	}
+
+	#[test]
+	fn test_large_files() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write(root, "big.txt", 2000);
+		write(root, "grows.txt", 10);
+		write(root, "small.txt", 10);
+
+		assert_eq!(
+			paths(
+				&large_files_to_stage(repo_path, "*", THRESHOLD)
+					.unwrap()
+			),
+			vec!["big.txt"]
+		);
+
+		for name in &["big.txt", "grows.txt", "small.txt"] {
+			stage_add_file(repo_path, Path::new(name)).unwrap();
+		}
+
+		assert_eq!(
+			staged_large_files(repo_path, THRESHOLD).unwrap(),
+			vec![LargeFile {
+				path: String::from("big.txt"),
+				size: 2000,
+				head_size: None,
+			}]
+		);
+
+		commit(repo_path, "large", false).unwrap();
+
+		// already large in HEAD: no warning
+		write(root, "big.txt", 3000);
+		write(root, "grows.txt", 1500);
+		write(root, "small.txt", 20);
+
+		assert_eq!(
+			paths(
+				&large_files_to_stage(repo_path, "*", THRESHOLD)
+					.unwrap()
+			),
+			vec!["grows.txt"]
+		);
+
+		for name in &["big.txt", "grows.txt", "small.txt"] {
+			stage_add_file(repo_path, Path::new(name)).unwrap();
+		}
+
+		assert_eq!(
+			staged_large_files(repo_path, THRESHOLD).unwrap(),
+			vec![LargeFile {
+				path: String::from("grows.txt"),
+				size: 1500,
+				head_size: Some(10),
+			}]
+		);
+	}
+
+	#[test]
+	fn test_binary_size() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::write(root.join("data.bin"), [1_u8, 0, 2]).unwrap();
+		write(root, "text.txt", 5);
+
+		let sizes = |status_type| {
+			get_status(repo_path, status_type, None)
+				.unwrap()
+				.into_iter()
+				.map(|item| (item.path, item.binary_size))
+				.collect::<Vec<_>>()
+		};
+
+		let expected = vec![
+			(String::from("data.bin"), Some(3)),
+			(String::from("text.txt"), None),
+		];
+
+		assert_eq!(sizes(StatusType::WorkingDir), expected);
+
+		stage_add_file(repo_path, Path::new("data.bin")).unwrap();
+		stage_add_file(repo_path, Path::new("text.txt")).unwrap();
+
+		assert_eq!(sizes(StatusType::Stage), expected);
+
+		// the staged blobs are checked once the files differ
+		write(root, "data.bin", 10);
+		fs::write(root.join("text.txt"), [0_u8; 20]).unwrap();
+
+		assert_eq!(sizes(StatusType::Stage), expected);
+	}
+}
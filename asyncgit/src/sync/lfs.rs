@@ -0,0 +1,208 @@
+//! git lfs pointer files
+//!
+//! see <https://github.com/git-lfs/git-lfs/blob/main/docs/spec.md>.
+//! libgit2 runs no lfs filter: what is staged is the working copy
+//! as it is, whether it is a pointer or the object itself
+
+use super::utils::{repo, work_dir};
+use crate::error::Result;
+use git2::{AttrCheckFlags, Status, StatusOptions, StatusShow};
+use scopetime::scope_time;
+use std::{
+	convert::TryFrom,
+	fs::{self, File},
+	io::Read,
+	path::Path,
+};
+
+const POINTER_VERSION: &str = "version https://git-lfs";
+
+/// pointer files are never larger than this
+pub(crate) const POINTER_MAX_SIZE: u64 = 1024;
+
+/// files to go into lfs from this size on are not expected to be
+/// staged with their raw content by accident
+pub const LFS_RAW_WARN_SIZE: u64 = 1024 * 1024;
+
+/// git checks this many bytes for a `\0` to call a file binary
+const BINARY_CHECK_LEN: u64 = 8000;
+
+/// content of a pointer file: the object it stands for
+#[derive(Default, Clone, Hash, Debug, PartialEq)]
+pub struct LfsPointer {
+	/// sha256 of the object (without the `sha256:` prefix)
+	pub oid: String,
+	/// size of the object in bytes
+	pub size: u64,
+}
+
+/// parses `content` as a pointer file, `None` if it is none
+pub fn lfs_pointer(content: &[u8]) -> Option<LfsPointer> {
+	if u64::try_from(content.len()).ok()? > POINTER_MAX_SIZE {
+		return None;
+	}
+
+	let content = std::str::from_utf8(content).ok()?;
+	let mut lines = content.lines();
+
+	if !lines.next()?.starts_with(POINTER_VERSION) {
+		return None;
+	}
+
+	let mut oid = None;
+	let mut size = None;
+
+	for line in lines {
+		let mut parts = line.splitn(2, ' ');
+		match (parts.next(), parts.next()) {
+			(Some("oid"), Some(value)) => {
+				oid = value
+					.strip_prefix("sha256:")
+					.map(ToString::to_string);
+			}
+			(Some("size"), Some(value)) => {
+				size = value.parse().ok();
+			}
+			_ => (),
+		}
+	}
+
+	Some(LfsPointer {
+		oid: oid?,
+		size: size?,
+	})
+}
+
+/// the working dir files matching the pathspec `pattern` that
+/// `.gitattributes` puts into lfs (`filter=lfs`) but that are still
+/// large binary files instead of pointers: staging them would commit
+/// the raw object
+pub fn lfs_unconverted(
+	repo_path: &str,
+	pattern: &str,
+) -> Result<Vec<String>> {
+	scope_time!("lfs_unconverted");
+
+	let repo = repo(repo_path)?;
+	let work_dir = work_dir(&repo)?;
+
+	let mut options = StatusOptions::default();
+	options
+		.show(StatusShow::Workdir)
+		.pathspec(pattern)
+		.include_untracked(true)
+		.recurse_untracked_dirs(true);
+
+	let statuses = repo.statuses(Some(&mut options))?;
+
+	let mut res = Vec::new();
+	for entry in statuses.iter() {
+		if !entry
+			.status()
+			.intersects(Status::WT_NEW | Status::WT_MODIFIED)
+		{
+			continue;
+		}
+
+		let path = match entry.path() {
+			Some(path) => path,
+			None => continue,
+		};
+
+		let lfs = repo.get_attr(
+			Path::new(path),
+			"filter",
+			AttrCheckFlags::default(),
+		)? == Some("lfs");
+
+		if lfs && is_raw_object(&work_dir.join(path)) {
+			res.push(path.to_string());
+		}
+	}
+
+	Ok(res)
+}
+
+/// large and binary, so certainly no pointer
+fn is_raw_object(path: &Path) -> bool {
+	let large = fs::symlink_metadata(path).map_or(false, |meta| {
+		meta.is_file() && meta.len() >= LFS_RAW_WARN_SIZE
+	});
+
+	large && is_binary(path)
+}
+
+pub fn is_binary(path: &Path) -> bool {
+	let mut head = Vec::new();
+
+	File::open(path)
+		.and_then(|file| {
+			file.take(BINARY_CHECK_LEN).read_to_end(&mut head)
+		})
+		.map_or(false, |_| head.contains(&0))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{lfs_pointer, lfs_unconverted, LFS_RAW_WARN_SIZE};
+	use crate::sync::tests::repo_init;
+	use std::fs;
+
+	const POINTER: &str = "version https://git-lfs.github.com/spec/v1
+oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393
+size 12345
+";
+
+	#[test]
+	fn test_pointer() {
+		let pointer = lfs_pointer(POINTER.as_bytes()).unwrap();
+
+		assert_eq!(pointer.size, 12345);
+		assert_eq!(
+			pointer.oid,
+			"4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393"
+		);
+
+		assert_eq!(lfs_pointer(b"some text\nsize 12\n"), None);
+		assert_eq!(
+			lfs_pointer(
+				b"version https://git-lfs.github.com/spec/v1\nsize 1\n"
+			),
+			None
+		);
+		assert_eq!(lfs_pointer(&[0_u8; 2000]), None);
+	}
+
+	#[test]
+	fn test_unconverted() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::write(
+			root.join(".gitattributes"),
+			"*.bin filter=lfs diff=lfs merge=lfs -text\n",
+		)
+		.unwrap();
+
+		let mut raw = vec![0_u8; LFS_RAW_WARN_SIZE as usize];
+		raw[0] = 1;
+		fs::create_dir(root.join("assets")).unwrap();
+		fs::write(root.join("assets/big.bin"), &raw).unwrap();
+		fs::write(root.join("assets/small.bin"), [0_u8; 10]).unwrap();
+		fs::write(root.join("assets/ptr.bin"), POINTER).unwrap();
+		fs::write(root.join("big.dat"), &raw).unwrap();
+
+		assert_eq!(
+			lfs_unconverted(repo_path, "*").unwrap(),
+			vec![String::from("assets/big.bin")]
+		);
+		assert_eq!(
+			lfs_unconverted(repo_path, "assets").unwrap(),
+			vec![String::from("assets/big.bin")]
+		);
+		assert!(lfs_unconverted(repo_path, "assets/ptr.bin")
+			.unwrap()
+			.is_empty());
+	}
+}
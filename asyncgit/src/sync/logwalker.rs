@@ -1,42 +1,113 @@
-use super::CommitId;
+use super::{
+	mailmap::{mailmap_repo, resolve},
+	utils::is_head_unborn,
+	CommitId,
+};
 use crate::error::Result;
 use git2::{Commit, Oid, Repository};
+use scopetime::scope_stats;
 use std::{
 	cmp::Ordering,
 	collections::{BinaryHeap, HashSet},
 	sync::Arc,
 };
 
+/// ordered by time, commits of the same second by id so the order
+/// only depends on the commits pending and not on how they got into
+/// the heap (see `LogWalker::resume`)
 struct TimeOrderedCommit<'a>(Commit<'a>);
 
 impl<'a> Eq for TimeOrderedCommit<'a> {}
 
 impl<'a> PartialEq for TimeOrderedCommit<'a> {
 	fn eq(&self, other: &Self) -> bool {
-		self.0.time().eq(&other.0.time())
+		self.cmp(other) == Ordering::Equal
 	}
 }
 
 impl<'a> PartialOrd for TimeOrderedCommit<'a> {
 	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-		self.0.time().partial_cmp(&other.0.time())
+		Some(self.cmp(other))
 	}
 }
 
 impl<'a> Ord for TimeOrderedCommit<'a> {
 	fn cmp(&self, other: &Self) -> Ordering {
-		self.0.time().cmp(&other.0.time())
+		self.0
+			.time()
+			.cmp(&other.0.time())
+			.then_with(|| self.0.id().cmp(&other.0.id()))
 	}
 }
 
+/// the commits a walk goes on with, see `LogWalker::state`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WalkState {
+	pending: Vec<CommitId>,
+	unreadable: Vec<CommitId>,
+}
+
 ///
 pub type LogWalkerFilter = Arc<
 	Box<dyn Fn(&Repository, &CommitId) -> Result<bool> + Send + Sync>,
 >;
 
-///
+/// lets commits pass whose committer time (secs since epoch)
+/// lies within `since..=until`, either end can be left open
+pub fn filter_by_time(
+	since: Option<i64>,
+	until: Option<i64>,
+) -> LogWalkerFilter {
+	Arc::new(Box::new(move |repo: &Repository, id: &CommitId| {
+		let time = repo.find_commit((*id).into())?.committer().when();
+		let time = time.seconds();
+
+		Ok(since.map_or(true, |since| time >= since)
+			&& until.map_or(true, |until| time <= until))
+	}))
+}
+
+/// lets commits pass whose author email equals `email`,
+/// as mapped by the mailmap
+pub fn filter_by_author_email(email: String) -> LogWalkerFilter {
+	Arc::new(Box::new(move |repo: &Repository, id: &CommitId| {
+		let commit = repo.find_commit((*id).into())?;
+		let mailmap = mailmap_repo(repo)?;
+		let author = resolve(&mailmap, &commit.author());
+
+		Ok(author.email_bytes() == email.as_bytes())
+	}))
+}
+
+/// lets commits pass that pass all of `filters`,
+/// `None` if there is nothing to filter by
+pub fn filter_all(
+	filters: Vec<LogWalkerFilter>,
+) -> Option<LogWalkerFilter> {
+	match filters.len() {
+		0 => None,
+		1 => filters.into_iter().next(),
+		_ => Some(Arc::new(Box::new(
+			move |repo: &Repository, id: &CommitId| {
+				for filter in &filters {
+					if !filter(repo, id)? {
+						return Ok(false);
+					}
+				}
+
+				Ok(true)
+			},
+		))),
+	}
+}
+
+/// commits that cannot be read (e.g. a missing object) do not end
+/// the walk, their id is returned in place of the commit (unless a
+/// filter is set) and their history is skipped
 pub struct LogWalker<'a> {
 	commits: BinaryHeap<TimeOrderedCommit<'a>>,
+	/// ids of unreadable commits, returned right after their child
+	unreadable: Vec<Oid>,
 	visited: HashSet<Oid>,
 	limit: usize,
 	repo: &'a Repository,
@@ -46,13 +117,25 @@ pub struct LogWalker<'a> {
 impl<'a> LogWalker<'a> {
 	///
 	pub fn new(repo: &'a Repository, limit: usize) -> Result<Self> {
-		let c = repo.head()?.peel_to_commit()?;
-
 		let mut commits = BinaryHeap::with_capacity(10);
-		commits.push(TimeOrderedCommit(c));
+		let mut unreadable = Vec::new();
+
+		// a freshly initialized repo has nothing to walk yet
+		if !is_head_unborn(repo)? {
+			let head = repo.head()?;
+			match (head.peel_to_commit(), head.target()) {
+				(Ok(c), _) => commits.push(TimeOrderedCommit(c)),
+				(Err(e), Some(id)) => {
+					log::warn!("unreadable commit {}: {}", id, e);
+					unreadable.push(id);
+				}
+				(Err(e), None) => return Err(e.into()),
+			}
+		}
 
 		Ok(Self {
 			commits,
+			unreadable,
 			limit,
 			visited: HashSet::with_capacity(1000),
 			repo,
@@ -60,6 +143,50 @@ impl<'a> LogWalker<'a> {
 		})
 	}
 
+	/// goes on with a walk where `state` was taken. the same as the
+	/// walk taking it unless a commit is older than one of its
+	/// parents (clock skew), then the parent might be listed twice
+	pub fn resume(
+		repo: &'a Repository,
+		state: &WalkState,
+		limit: usize,
+	) -> Result<Self> {
+		let mut walker = Self {
+			commits: BinaryHeap::with_capacity(state.pending.len()),
+			unreadable: state
+				.unreadable
+				.iter()
+				.map(|id| (*id).into())
+				.collect(),
+			limit,
+			visited: HashSet::with_capacity(1000),
+			repo,
+			filter: None,
+		};
+
+		for id in &state.pending {
+			walker.visit((*id).into());
+		}
+
+		Ok(walker)
+	}
+
+	/// where the walk is at, to `resume` it from there later
+	pub fn state(&self) -> WalkState {
+		WalkState {
+			pending: self
+				.commits
+				.iter()
+				.map(|c| c.0.id().into())
+				.collect(),
+			unreadable: self
+				.unreadable
+				.iter()
+				.map(|id| (*id).into())
+				.collect(),
+		}
+	}
+
 	///
 	pub fn filter(self, filter: Option<LogWalkerFilter>) -> Self {
 		Self { filter, ..self }
@@ -67,10 +194,31 @@ impl<'a> LogWalker<'a> {
 
 	///
 	pub fn read(&mut self, out: &mut Vec<CommitId>) -> Result<usize> {
+		scope_stats!("log_walker_read");
+
 		let mut count = 0_usize;
 
-		while let Some(c) = self.commits.pop() {
-			for p in c.0.parents() {
+		loop {
+			if let Some(id) = self.unreadable.pop() {
+				// a filter cannot look into it
+				if self.filter.is_none() {
+					out.push(id.into());
+				}
+
+				count += 1;
+				if count == self.limit {
+					break;
+				}
+
+				continue;
+			}
+
+			let c = match self.commits.pop() {
+				Some(c) => c,
+				None => break,
+			};
+
+			for p in c.0.parent_ids() {
 				self.visit(p);
 			}
 
@@ -96,10 +244,15 @@ impl<'a> LogWalker<'a> {
 	}
 
 	//
-	fn visit(&mut self, c: Commit<'a>) {
-		if !self.visited.contains(&c.id()) {
-			self.visited.insert(c.id());
-			self.commits.push(TimeOrderedCommit(c));
+	fn visit(&mut self, id: Oid) {
+		if self.visited.insert(id) {
+			match self.repo.find_commit(id) {
+				Ok(c) => self.commits.push(TimeOrderedCommit(c)),
+				Err(e) => {
+					log::warn!("unreadable commit {}: {}", id, e);
+					self.unreadable.push(id);
+				}
+			}
 		}
 	}
 }
@@ -109,12 +262,46 @@ mod tests {
 	use super::*;
 	use crate::error::Result;
 	use crate::sync::{
-		commit, commit_files::get_commit_diff, get_commits_info,
-		stage_add_file, tests::repo_init_empty,
+		commit,
+		commit_files::{get_commit_diff, MergeDiffMode},
+		get_commits_info, stage_add_file,
+		tests::{repo_init_empty, write_history},
 	};
+	use git2::{Signature, Time};
 	use pretty_assertions::assert_eq;
 	use std::{fs::File, io::Write, path::Path};
 
+	fn commit_at(repo: &Repository, time: i64) -> CommitId {
+		commit_by(repo, time, "email")
+	}
+
+	fn commit_by(
+		repo: &Repository,
+		time: i64,
+		email: &str,
+	) -> CommitId {
+		let sig = Signature::new("name", email, &Time::new(time, 0))
+			.unwrap();
+		let tree_id = repo.index().unwrap().write_tree().unwrap();
+		let tree = repo.find_tree(tree_id).unwrap();
+		let parent = repo
+			.head()
+			.ok()
+			.and_then(|head| head.peel_to_commit().ok());
+		let parents = parent.iter().collect::<Vec<_>>();
+
+		repo.commit(
+			Some("HEAD"),
+			&sig,
+			&sig,
+			&time.to_string(),
+			&tree,
+			parents.as_slice(),
+		)
+		.unwrap()
+		.into()
+	}
+
 	#[test]
 	fn test_limit() -> Result<()> {
 		let file_path = Path::new("foo");
@@ -124,10 +311,10 @@ mod tests {
 
 		File::create(&root.join(file_path))?.write_all(b"a")?;
 		stage_add_file(repo_path, file_path).unwrap();
-		commit(repo_path, "commit1").unwrap();
+		commit(repo_path, "commit1", false).unwrap();
 		File::create(&root.join(file_path))?.write_all(b"a")?;
 		stage_add_file(repo_path, file_path).unwrap();
-		let oid2 = commit(repo_path, "commit2").unwrap();
+		let oid2 = commit(repo_path, "commit2", true).unwrap();
 
 		let mut items = Vec::new();
 		let mut walk = LogWalker::new(&repo, 1)?;
@@ -139,6 +326,19 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_unborn_head() -> Result<()> {
+		let (_td, repo) = repo_init_empty().unwrap();
+
+		let mut items = Vec::new();
+		let mut walk = LogWalker::new(&repo, 100)?;
+		walk.read(&mut items).unwrap();
+
+		assert_eq!(items.len(), 0);
+
+		Ok(())
+	}
+
 	#[test]
 	fn test_logwalker() -> Result<()> {
 		let file_path = Path::new("foo");
@@ -148,10 +348,10 @@ mod tests {
 
 		File::create(&root.join(file_path))?.write_all(b"a")?;
 		stage_add_file(repo_path, file_path).unwrap();
-		commit(repo_path, "commit1").unwrap();
+		commit(repo_path, "commit1", false).unwrap();
 		File::create(&root.join(file_path))?.write_all(b"a")?;
 		stage_add_file(repo_path, file_path).unwrap();
-		let oid2 = commit(repo_path, "commit2").unwrap();
+		let oid2 = commit(repo_path, "commit2", true).unwrap();
 
 		let mut items = Vec::new();
 		let mut walk = LogWalker::new(&repo, 100)?;
@@ -182,18 +382,21 @@ mod tests {
 		File::create(&root.join(file_path))?.write_all(b"a")?;
 		stage_add_file(repo_path, file_path).unwrap();
 
-		let _first_commit_id = commit(repo_path, "commit1").unwrap();
+		let _first_commit_id =
+			commit(repo_path, "commit1", false).unwrap();
 
 		File::create(&root.join(second_file_path))?
 			.write_all(b"a")?;
 		stage_add_file(repo_path, second_file_path).unwrap();
 
-		let second_commit_id = commit(repo_path, "commit2").unwrap();
+		let second_commit_id =
+			commit(repo_path, "commit2", false).unwrap();
 
 		File::create(&root.join(file_path))?.write_all(b"b")?;
 		stage_add_file(repo_path, file_path).unwrap();
 
-		let _third_commit_id = commit(repo_path, "commit3").unwrap();
+		let _third_commit_id =
+			commit(repo_path, "commit3", false).unwrap();
 
 		let diff_contains_baz = |repo: &Repository,
 		                         commit_id: &CommitId|
@@ -201,6 +404,7 @@ mod tests {
 			let diff = get_commit_diff(
 				&repo,
 				*commit_id,
+				MergeDiffMode::FirstParent,
 				Some("baz".into()),
 			)?;
 
@@ -228,6 +432,7 @@ mod tests {
 			let diff = get_commit_diff(
 				&repo,
 				*commit_id,
+				MergeDiffMode::FirstParent,
 				Some("bar".into()),
 			)?;
 
@@ -245,4 +450,155 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_resume() -> Result<()> {
+		let (_td, repo) = repo_init_empty().unwrap();
+		write_history(&repo, 200);
+
+		let mut all = Vec::new();
+		let mut walker = LogWalker::new(&repo, usize::MAX)?;
+		walker.read(&mut all)?;
+		assert_eq!(all.len(), 200);
+
+		let mut walker = LogWalker::new(&repo, 50)?;
+		let mut items = Vec::new();
+		walker.read(&mut items)?;
+		walker.read(&mut items)?;
+
+		let mut rest = Vec::new();
+		LogWalker::resume(&repo, &walker.state(), usize::MAX)?
+			.read(&mut rest)?;
+
+		assert_eq!(&all[..100], items.as_slice());
+		assert_eq!(&all[100..], rest.as_slice());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_filter_by_time() -> Result<()> {
+		let (_td, repo) = repo_init_empty().unwrap();
+
+		let c1 = commit_at(&repo, 100);
+		let c2 = commit_at(&repo, 200);
+		let c3 = commit_at(&repo, 300);
+
+		let walk = |since, until| {
+			let mut items = Vec::new();
+			LogWalker::new(&repo, 100)
+				.unwrap()
+				.filter(Some(filter_by_time(since, until)))
+				.read(&mut items)
+				.unwrap();
+			items
+		};
+
+		assert_eq!(walk(None, None), vec![c3, c2, c1]);
+		assert_eq!(walk(Some(200), None), vec![c3, c2]);
+		assert_eq!(walk(None, Some(200)), vec![c2, c1]);
+		assert_eq!(walk(Some(150), Some(250)), vec![c2]);
+		assert_eq!(walk(Some(400), None), vec![]);
+
+		Ok(())
+	}
+	#[test]
+	fn test_filter_by_author() -> Result<()> {
+		let (_td, repo) = repo_init_empty().unwrap();
+
+		let c1 = commit_by(&repo, 100, "a@example.com");
+		let c2 = commit_by(&repo, 200, "b@example.com");
+		let c3 = commit_by(&repo, 300, "a@example.com");
+
+		let walk = |filter| {
+			let mut items = Vec::new();
+			LogWalker::new(&repo, 100)
+				.unwrap()
+				.filter(filter)
+				.read(&mut items)
+				.unwrap();
+			items
+		};
+
+		let author =
+			|email: &str| filter_by_author_email(email.to_string());
+
+		assert_eq!(walk(Some(author("a@example.com"))), vec![c3, c1]);
+		assert_eq!(walk(Some(author("b@example.com"))), vec![c2]);
+		assert_eq!(walk(Some(author("a@example"))), vec![]);
+
+		assert!(filter_all(Vec::new()).is_none());
+		assert_eq!(
+			walk(filter_all(vec![
+				author("a@example.com"),
+				filter_by_time(None, Some(200)),
+			])),
+			vec![c1]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_missing_object() -> Result<()> {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let c1 = commit_at(&repo, 100);
+		let c2 = commit_at(&repo, 200);
+
+		// side branch off `c1` merged back in
+		let sig =
+			Signature::new("name", "email", &Time::new(300, 0))?;
+		let tree = repo.find_commit(c1.into())?.tree()?;
+		let parent = repo.find_commit(c1.into())?;
+		let c3: CommitId = repo
+			.commit(None, &sig, &sig, "side", &tree, &[&parent])?
+			.into();
+		let sig =
+			Signature::new("name", "email", &Time::new(400, 0))?;
+		let parents = [
+			&repo.find_commit(c2.into())?,
+			&repo.find_commit(c3.into())?,
+		];
+		let merge: CommitId = repo
+			.commit(
+				Some("HEAD"),
+				&sig,
+				&sig,
+				"merge",
+				&tree,
+				&parents,
+			)?
+			.into();
+
+		// drop the loose object of `c2`
+		let hex = c2.to_string();
+		std::fs::remove_file(
+			repo.path()
+				.join("objects")
+				.join(&hex[..2])
+				.join(&hex[2..]),
+		)?;
+
+		// without the objects `repo` has cached already
+		let repo = Repository::open(repo_path)?;
+
+		let mut items = Vec::new();
+		LogWalker::new(&repo, 100)?.read(&mut items)?;
+
+		assert_eq!(items, vec![merge, c2, c3, c1]);
+
+		let info = get_commits_info(repo_path, &items, 50)?;
+
+		assert_eq!(info.len(), 4);
+		assert_eq!(
+			info[1].message,
+			format!("<unreadable object {}>", c2.get_short_string())
+		);
+		assert_eq!(info[2].message, "side");
+
+		Ok(())
+	}
 }
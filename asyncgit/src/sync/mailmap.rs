@@ -0,0 +1,205 @@
+//! `.mailmap` support: authors are shown and compared by the name
+//! and email the mailmap maps them to
+
+use super::utils::repo;
+use crate::error::Result;
+use git2::{Mailmap, Repository, Signature};
+use scopetime::scope_time;
+use std::{
+	cell::RefCell,
+	fs,
+	path::PathBuf,
+	rc::Rc,
+	time::{Duration, Instant, SystemTime},
+};
+
+/// filters resolve an author per commit of a walk,
+/// the `.mailmap` is not looked at more often than this
+const RECHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// modification time and size of the `.mailmap`, `None` if missing
+type Stamp = Option<(SystemTime, u64)>;
+
+struct MailmapCache {
+	/// git dir of the repo
+	repo: PathBuf,
+	stamp: Stamp,
+	checked: Instant,
+	mailmap: Rc<Mailmap>,
+}
+
+thread_local! {
+	static CACHE: RefCell<Option<MailmapCache>> = RefCell::new(None);
+}
+
+/// mailmap of the repo (`.mailmap`, `mailmap.file` and
+/// `mailmap.blob`), cached per repo until the `.mailmap` changes
+pub fn mailmap(repo_path: &str) -> Result<Rc<Mailmap>> {
+	mailmap_repo(&repo(repo_path)?)
+}
+
+///
+pub fn mailmap_repo(repo: &Repository) -> Result<Rc<Mailmap>> {
+	CACHE.with(|cache| {
+		let mut cache = cache.borrow_mut();
+
+		if let Some(cached) =
+			cache.as_mut().filter(|cached| cached.repo == repo.path())
+		{
+			if cached.checked.elapsed() < RECHECK_INTERVAL {
+				return Ok(cached.mailmap.clone());
+			}
+
+			cached.checked = Instant::now();
+			if cached.stamp == stamp(repo) {
+				return Ok(cached.mailmap.clone());
+			}
+		}
+
+		scope_time!("mailmap");
+
+		let mailmap = Rc::new(repo.mailmap()?);
+		*cache = Some(MailmapCache {
+			repo: repo.path().to_path_buf(),
+			stamp: stamp(repo),
+			checked: Instant::now(),
+			mailmap: mailmap.clone(),
+		});
+
+		Ok(mailmap)
+	})
+}
+
+/// `sig` as mapped by `mailmap`, unchanged if it is not mapped
+pub fn resolve(
+	mailmap: &Mailmap,
+	sig: &Signature,
+) -> Signature<'static> {
+	mailmap
+		.resolve_signature(sig)
+		.unwrap_or_else(|_| sig.to_owned())
+}
+
+fn stamp(repo: &Repository) -> Stamp {
+	let meta = fs::metadata(repo.workdir()?.join(".mailmap")).ok()?;
+	Some((meta.modified().ok()?, meta.len()))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::sync::{
+		blame_file, filter_by_author_email, get_commit_details,
+		get_commits_info, get_top_authors, stage_add_file,
+		tests::repo_init_empty, utils::repo_write_file, CommitId,
+		LogWalker,
+	};
+	use git2::{Repository, Signature};
+	use std::{fs, path::Path};
+
+	fn commit_by(
+		repo: &Repository,
+		name: &str,
+		email: &str,
+	) -> CommitId {
+		let sig = Signature::now(name, email).unwrap();
+		// staged through another handle
+		let mut index = repo.index().unwrap();
+		index.read(true).unwrap();
+		let tree_id = index.write_tree().unwrap();
+		let tree = repo.find_tree(tree_id).unwrap();
+		let parents = repo
+			.head()
+			.ok()
+			.map(|head| head.peel_to_commit().unwrap());
+		let parents = parents.iter().collect::<Vec<_>>();
+
+		CommitId::new(
+			repo.commit(
+				Some("HEAD"),
+				&sig,
+				&sig,
+				"msg",
+				&tree,
+				&parents,
+			)
+			.unwrap(),
+		)
+	}
+
+	#[test]
+	fn test_mailmap() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		repo_write_file(&repo, "foo.txt", "a\n").unwrap();
+		stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+		let old = commit_by(&repo, "Old Name", "old@example.com");
+
+		let author = |id| {
+			let info =
+				get_commits_info(repo_path, &[id], 50).unwrap();
+			let details = get_commit_details(repo_path, id).unwrap();
+			(info[0].author.clone(), details.author.email)
+		};
+
+		assert_eq!(
+			author(old),
+			(
+				String::from("Old Name"),
+				String::from("old@example.com")
+			)
+		);
+
+		fs::write(
+			root.join(".mailmap"),
+			"New Name <new@example.com> <old@example.com>\n",
+		)
+		.unwrap();
+
+		// the cache notices the new `.mailmap` after a while
+		std::thread::sleep(super::RECHECK_INTERVAL);
+
+		assert_eq!(
+			author(old),
+			(
+				String::from("New Name"),
+				String::from("new@example.com")
+			)
+		);
+
+		repo_write_file(&repo, "foo.txt", "a\nb\n").unwrap();
+		stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+		let new = commit_by(&repo, "New Name", "new@example.com");
+
+		let authors = get_top_authors(repo_path, 10).unwrap();
+		assert_eq!(authors.len(), 1);
+		assert_eq!(authors[0].name, "New Name");
+		assert_eq!(authors[0].email, "new@example.com");
+		assert_eq!(authors[0].commits, 2);
+
+		let mut items = Vec::new();
+		LogWalker::new(&repo, 10)
+			.unwrap()
+			.filter(Some(filter_by_author_email(String::from(
+				"new@example.com",
+			))))
+			.read(&mut items)
+			.unwrap();
+		assert_eq!(items, vec![new, old]);
+
+		let blame = blame_file(repo_path, "foo.txt", None).unwrap();
+		assert_eq!(
+			blame
+				.lines
+				.iter()
+				.map(|(hunk, _)| hunk
+					.as_ref()
+					.unwrap()
+					.author
+					.as_str())
+				.collect::<Vec<_>>(),
+			vec!["New Name", "New Name"]
+		);
+	}
+}
@@ -102,6 +102,19 @@ pub fn rebase_branch_repo(
 	conflict_free_rebase(repo, &annotated)
 }
 
+/// best common ancestor of `one` and `two`
+pub fn merge_base(
+	repo_path: &str,
+	one: CommitId,
+	two: CommitId,
+) -> Result<CommitId> {
+	scope_time!("merge_base");
+
+	let repo = utils::repo(repo_path)?;
+
+	Ok(repo.merge_base(one.into(), two.into())?.into())
+}
+
 ///
 pub fn merge_msg(repo_path: &str) -> Result<String> {
 	scope_time!("merge_msg");
@@ -137,7 +150,7 @@ pub fn merge_commit(
 mod tests {
 	use super::*;
 	use crate::sync::{
-		create_branch,
+		checkout_branch, create_branch,
 		tests::{repo_init, write_commit_file},
 	};
 	use pretty_assertions::assert_eq;
@@ -165,4 +178,22 @@ mod tests {
 
 		assert_eq!(mergeheads[0], c1);
 	}
+
+	#[test]
+	fn test_merge_base() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let c1 = write_commit_file(&repo, "test.txt", "test", "c1");
+
+		create_branch(repo_path, "foo").unwrap();
+		let c2 = write_commit_file(&repo, "foo.txt", "foo", "c2");
+
+		checkout_branch(repo_path, "refs/heads/master").unwrap();
+		let c3 = write_commit_file(&repo, "bar.txt", "bar", "c3");
+
+		assert_eq!(merge_base(repo_path, c2, c3).unwrap(), c1);
+		assert_eq!(merge_base(repo_path, c3, c1).unwrap(), c1);
+	}
 }
@@ -3,86 +3,208 @@
 //TODO: remove once we have this activated on the toplevel
 #![deny(clippy::expect_used)]
 
+mod abbrev;
+mod authors;
+mod autosquash;
+mod bisect;
 pub mod blame;
 pub mod branch;
+mod cherry_pick;
 mod commit;
 mod commit_details;
 mod commit_files;
 mod commits_info;
-mod config;
+pub mod config;
+mod conflicts;
 pub mod cred;
 pub mod diff;
+mod gitkeep;
 mod hooks;
 mod hunks;
 mod ignore;
+mod index_flags;
+mod index_lock;
+mod large_files;
+mod lfs;
 mod logwalker;
+mod mailmap;
 mod merge;
+mod moved;
+mod notes;
 mod patches;
+mod pathspec;
+mod preflight;
 mod rebase;
+mod refs;
 pub mod remotes;
 mod reset;
+mod snapshot;
 mod staging;
-mod stash;
+mod stale_state;
+pub(crate) mod stash;
 mod state;
 pub mod status;
 mod tags;
+mod trailers;
 mod tree;
 pub mod utils;
+mod worktrees;
 
-pub use blame::{blame_file, BlameHunk, FileBlame};
+pub use abbrev::abbreviate_oid;
+pub use authors::{get_top_authors, AuthorInfo};
+pub use autosquash::{
+	autosquash_rebase, autosquash_todo, AutosquashResult,
+	AutosquashTodo, TodoAction, TodoEntry,
+};
+pub use bisect::{
+	bisect_mark, bisect_reset, bisect_start, bisect_state,
+	BisectMark, BisectStep,
+};
+pub use blame::{
+	blame_file, blame_target, BlameHunk, BlameLine, FileBlame,
+};
 pub use branch::{
-	branch_compare_upstream, checkout_branch, config_is_pull_rebase,
-	create_branch, delete_branch, get_branch_remote,
-	get_branches_info, merge_commit::merge_upstream_commit,
+	base::{
+		base_branch_candidates, get_base_branch, set_base_branch,
+		BaseBranch,
+	},
+	branch_compare_upstream, branches_compare_upstream,
+	checkout_branch, config_is_pull_rebase, create_branch,
+	create_orphan_branch, delete_branch,
+	description::{
+		branch_get_description, branch_set_description,
+		get_branch_descriptions,
+	},
+	get_branch_remote, get_branches_info,
+	merge_commit::merge_upstream_commit,
 	merge_ff::branch_merge_upstream_fastforward,
-	merge_rebase::merge_upstream_rebase, rename::rename_branch,
-	validate_branch_name, BranchCompare, BranchInfo,
+	merge_rebase::merge_upstream_rebase,
+	merged::merged_tips,
+	name::{branch_name_slug, validate_branch_name, BranchNameError},
+	recent::recent_branches,
+	rename::rename_branch,
+	upstream_gone, BranchCompare, BranchInfo,
 };
-pub use commit::{amend, commit, tag};
+pub use cherry_pick::{
+	cherry_pick_progress, cherry_pick_range,
+	cherry_pick_range_commits, continue_cherry_pick,
+	skip_cherry_pick, CherryPickProgress, CherryPickResult,
+};
+pub use commit::{amend, commit, tag, AmendDates};
 pub use commit_details::{
 	get_commit_details, CommitDetails, CommitMessage, CommitSignature,
 };
-pub use commit_files::get_commit_files;
+pub use commit_files::{
+	get_commit_files, get_commit_workdir_files, MergeDiffMode,
+};
 pub use commits_info::{
 	get_commit_info, get_commits_info, CommitId, CommitInfo,
 };
 pub use config::{
-	get_config_string, untracked_files_config,
+	add_config_string, get_config_string, get_config_strings,
+	set_string_scoped, untracked_files_config, ConfigScope,
 	ShowUntrackedFilesConfig,
 };
-pub use diff::get_diff_commit;
+pub use conflicts::{
+	conflict_stages, resolve_conflict, ConflictSide, ConflictStages,
+};
+pub use diff::{diff_blob_to_workdir, get_diff_commit, WorkdirDiff};
+pub use gitkeep::{add_empty_dirs, add_gitkeep};
 pub use hooks::{
-	hooks_commit_msg, hooks_post_commit, hooks_pre_commit, HookResult,
+	hooks_commit_msg, hooks_commit_msg_streamed, hooks_post_commit,
+	hooks_pre_commit, hooks_pre_commit_streamed, hooks_pre_push,
+	hooks_pre_push_streamed, HookResult,
+};
+pub use hunks::{
+	apply_discarded_hunk, discard_hunk, reset_hunk, stage_hunk,
+	stage_hunk_part, unstage_hunk, unstage_hunk_renamed,
+};
+pub use ignore::{add_to_ignore, paths_ignored};
+pub use index_flags::{
+	index_flagged_files, set_index_flag, FlaggedFile, IndexFlag,
+};
+pub use large_files::{
+	large_files_to_stage, staged_large_files, LargeFile,
+};
+pub use lfs::{
+	lfs_pointer, lfs_unconverted, LfsPointer, LFS_RAW_WARN_SIZE,
 };
-pub use hunks::{reset_hunk, stage_hunk, unstage_hunk};
-pub use ignore::add_to_ignore;
-pub use logwalker::{LogWalker, LogWalkerFilter};
+pub use logwalker::{
+	filter_all, filter_by_author_email, filter_by_time, LogWalker,
+	LogWalkerFilter, WalkState,
+};
+pub use mailmap::mailmap;
 pub use merge::{
-	abort_merge, merge_branch, merge_commit, merge_msg,
+	abort_merge, merge_base, merge_branch, merge_commit, merge_msg,
 	mergehead_ids, rebase_branch,
 };
+pub use moved::{
+	moved_blocks, MovedBlock, MovedRange, MOVED_MAX_CHANGED_LINES,
+};
+pub use notes::{
+	commits_with_notes, get_commit_note, set_commit_note,
+};
+pub use pathspec::{
+	count_pathspec_matches, stage_pathspec, unstage_pathspec,
+};
+pub use preflight::{
+	preflight_check, PreflightIssue, SAFE_DIRECTORY,
+};
+pub use refs::{
+	get_ref_targets, is_reachable_from_head, RefKind, RefTarget,
+};
 pub use remotes::{
-	get_default_remote, get_remotes, push::AsyncProgress,
+	delete_remote_tracking_refs, get_default_remote,
+	get_remote_branches_advertised, get_remote_push_url, get_remotes,
+	get_upstream_branch, get_upstream_remote,
+	push::{
+		push_preview, push_preview_advertised,
+		push_target_for_branch, push_target_on_remote, push_updates,
+		AsyncProgress, PushPreview, PushTarget, PushUpdate,
+		PUSH_PREVIEW_MAX_COMMITS,
+	},
+	stale_remote_branches,
 	tags::PushTagsProgress,
 };
-pub use reset::{reset_stage, reset_workdir};
-pub use staging::{discard_lines, stage_lines};
+pub use reset::{reset_stage, reset_stage_renamed, reset_workdir};
+pub use snapshot::{
+	backup_discarded, restore_discarded, restore_index,
+	snapshot_index, DiscardBackup, IndexSnapshot,
+};
+pub use staging::{
+	discard_lines, stage_lines, stage_lines_untracked,
+};
+pub use stale_state::{
+	cleanup_stale_state, detect_stale_state, StaleState,
+};
 pub use stash::{
 	get_stashes, stash_apply, stash_drop, stash_pop, stash_save,
 };
-pub use state::{repo_state, RepoState};
+pub use state::{
+	abort_pending_state, continue_pending_state, repo_state,
+	RebaseProgress, RepoState,
+};
 pub use tags::{
 	delete_tag, get_tags, get_tags_with_metadata, CommitTags,
 	TagWithMetadata, Tags,
 };
-pub use tree::{tree_file_content, tree_files, TreeFile};
+pub use trailers::{apply_trailers, signoff_trailer};
+pub use tree::{
+	commit_file_blob, index_file_blob, tree_file_content, tree_files,
+	TreeFile, TreeFileKind,
+};
 pub use utils::{
-	get_head, get_head_tuple, is_bare_repo, is_repo, repo_dir,
-	stage_add_all, stage_add_file, stage_addremoved, Head,
+	default_initial_branch, enclosing_repo, get_head, get_head_tuple,
+	init_repo, is_bare_repo, is_fs_case_insensitive, is_repo,
+	repo_dir, stage_add_all, stage_add_file, stage_addremoved,
+	stage_renamed, Head,
+};
+pub use worktrees::{
+	repo_summary, worktree_dirs, worktrees_with_branch, RepoSummary,
 };
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
 	use super::{
 		commit, stage_add_file,
 		status::{get_status, StatusType},
@@ -98,7 +220,7 @@ mod tests {
 	/// is no git config interfering with our tests (for example user-local
 	/// `.gitconfig`).
 	#[allow(unsafe_code)]
-	fn sandbox_config_files() {
+	pub fn sandbox_config_files() {
 		use git2::{opts::set_search_path, ConfigLevel};
 		use std::sync::Once;
 
@@ -131,8 +253,12 @@ mod tests {
 		)
 		.unwrap();
 
-		commit(repo.workdir().unwrap().to_str().unwrap(), commit_name)
-			.unwrap()
+		commit(
+			repo.workdir().unwrap().to_str().unwrap(),
+			commit_name,
+			false,
+		)
+		.unwrap()
 	}
 
 	/// write, stage and commit a file giving the commit a specific timestamp
@@ -286,6 +412,68 @@ mod tests {
 		commit_ids
 	}
 
+	/// `count` commits on `HEAD` without any files, every fifth one
+	/// merges a side branch forked two commits before. three commits
+	/// share a second, so the log order depends on more than time
+	pub fn write_history(repo: &Repository, count: usize) {
+		use std::convert::TryFrom;
+
+		let tree = repo
+			.find_tree(
+				repo.treebuilder(None).unwrap().write().unwrap(),
+			)
+			.unwrap();
+		let sig = |i: usize| {
+			git2::Signature::new(
+				"name",
+				"email",
+				&git2::Time::new(i64::try_from(i / 3).unwrap(), 0),
+			)
+			.unwrap()
+		};
+
+		let mut main = Vec::<git2::Oid>::new();
+		let mut i = 0;
+		while i < count {
+			let mut parents: Vec<_> = main
+				.last()
+				.map(|id| repo.find_commit(*id).unwrap())
+				.into_iter()
+				.collect();
+
+			if i % 5 == 4 && main.len() > 2 && i + 1 < count {
+				let fork =
+					repo.find_commit(main[main.len() - 3]).unwrap();
+				let side = repo
+					.commit(
+						None,
+						&sig(i),
+						&sig(i),
+						&format!("side {}", i),
+						&tree,
+						&[&fork],
+					)
+					.unwrap();
+				parents.push(repo.find_commit(side).unwrap());
+				i += 1;
+			}
+
+			let parents: Vec<_> = parents.iter().collect();
+			main.push(
+				repo.commit(
+					Some("HEAD"),
+					&sig(i),
+					&sig(i),
+					&format!("commit {}", i),
+					&tree,
+					&parents,
+				)
+				.unwrap(),
+			);
+			i += 1;
+		}
+	}
+
 	fn debug_cmd(path: &str, cmd: &str) -> String {
 		let output = if cfg!(target_os = "windows") {
 			Command::new("cmd")
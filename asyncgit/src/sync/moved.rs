@@ -0,0 +1,315 @@
+//! blocks of lines a commit removes from one file and adds to
+//! another one
+
+use super::{
+	commit_files::{get_commit_diff, MergeDiffMode},
+	utils::repo,
+	CommitId,
+};
+use crate::error::Result;
+use scopetime::scope_time;
+use std::{
+	collections::{HashMap, HashSet},
+	convert::TryFrom,
+};
+
+/// diffs with more changed lines are not searched for moves, the
+/// search would take too long to be of any help
+pub const MOVED_MAX_CHANGED_LINES: usize = 20_000;
+
+/// consecutive lines on one side of a diff
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MovedRange {
+	///
+	pub path: String,
+	/// old line number for removed lines, new one for added lines
+	pub start: u32,
+	///
+	pub len: u32,
+}
+
+impl MovedRange {
+	///
+	pub fn contains(&self, path: &str, lineno: u32) -> bool {
+		self.path == path
+			&& lineno >= self.start
+			&& lineno < self.start.saturating_add(self.len)
+	}
+}
+
+/// lines removed in one file and added in another one, equal when
+/// ignoring whitespace
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MovedBlock {
+	///
+	pub removed: MovedRange,
+	///
+	pub added: MovedRange,
+}
+
+struct ChangedLine {
+	path: String,
+	lineno: u32,
+	content: String,
+}
+
+impl ChangedLine {
+	/// `other` directly follows `self` in the same file
+	fn followed_by(&self, other: &Self) -> bool {
+		self.path == other.path && self.lineno + 1 == other.lineno
+	}
+}
+
+/// the blocks of at least `min_lines` lines commit `id` moves between
+/// files, none if the diff changes more than
+/// `MOVED_MAX_CHANGED_LINES` lines
+pub fn moved_blocks(
+	repo_path: &str,
+	id: CommitId,
+	merge_mode: MergeDiffMode,
+	min_lines: usize,
+) -> Result<Vec<MovedBlock>> {
+	scope_time!("moved_blocks");
+
+	if min_lines == 0 {
+		return Ok(Vec::new());
+	}
+
+	let repo = repo(repo_path)?;
+	let diff = get_commit_diff(&repo, id, merge_mode, None)?;
+
+	let mut removed = Vec::new();
+	let mut added = Vec::new();
+	let mut too_large = false;
+
+	let res = diff.foreach(
+		&mut |_, _| true,
+		None,
+		None,
+		Some(&mut |delta, _hunk, line| {
+			if removed.len() + added.len() > MOVED_MAX_CHANGED_LINES {
+				too_large = true;
+				return false;
+			}
+
+			let (side, lineno, file) = match line.origin() {
+				'-' => (
+					&mut removed,
+					line.old_lineno(),
+					delta.old_file(),
+				),
+				'+' => {
+					(&mut added, line.new_lineno(), delta.new_file())
+				}
+				_ => return true,
+			};
+
+			if let (Some(lineno), Some(path)) =
+				(lineno, file.path().and_then(|p| p.to_str()))
+			{
+				side.push(ChangedLine {
+					path: path.to_string(),
+					lineno,
+					content: normalize(&String::from_utf8_lossy(
+						line.content(),
+					)),
+				});
+			}
+
+			true
+		}),
+	);
+
+	if too_large {
+		log::info!(
+			"moved_blocks: skipped, more than {} changed lines",
+			MOVED_MAX_CHANGED_LINES
+		);
+		return Ok(Vec::new());
+	}
+	res?;
+
+	Ok(match_blocks(&removed, &added, min_lines))
+}
+
+fn normalize(content: &str) -> String {
+	content.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// greedy: every added line starts the longest block it can, each
+/// line is part of one block at most
+fn match_blocks(
+	removed: &[ChangedLine],
+	added: &[ChangedLine],
+	min_lines: usize,
+) -> Vec<MovedBlock> {
+	let mut index: HashMap<&str, Vec<usize>> = HashMap::new();
+	for (idx, line) in removed.iter().enumerate() {
+		if !line.content.is_empty() {
+			index.entry(&line.content).or_default().push(idx);
+		}
+	}
+
+	let mut used = HashSet::new();
+	let mut res = Vec::new();
+	let mut start = 0;
+
+	while start < added.len() {
+		let first = &added[start];
+
+		let best = index
+			.get(first.content.as_str())
+			.into_iter()
+			.flatten()
+			.filter(|idx| {
+				!used.contains(*idx)
+					&& removed[**idx].path != first.path
+			})
+			.map(|idx| {
+				let len = (1..added.len() - start)
+					.take_while(|offset| {
+						let (a, r) = (start + offset, idx + offset);
+						r < removed.len()
+							&& !used.contains(&r) && added[a - 1]
+							.followed_by(&added[a])
+							&& removed[r - 1].followed_by(&removed[r])
+							&& added[a].content == removed[r].content
+					})
+					.count() + 1;
+				(*idx, len)
+			})
+			.max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)));
+
+		match best {
+			Some((idx, len)) if len >= min_lines => {
+				used.extend(idx..idx + len);
+				res.push(MovedBlock {
+					removed: range(&removed[idx], len),
+					added: range(first, len),
+				});
+				start += len;
+			}
+			_ => start += 1,
+		}
+	}
+
+	res
+}
+
+fn range(first: &ChangedLine, len: usize) -> MovedRange {
+	MovedRange {
+		path: first.path.clone(),
+		start: first.lineno,
+		len: u32::try_from(len).unwrap_or(u32::MAX),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		commit, stage_add_file,
+		tests::{repo_init, write_commit_file},
+		utils::repo_write_file,
+	};
+	use std::path::Path;
+
+	fn block(name: &str) -> String {
+		(0..30)
+			.map(|idx| format!("\tlet {}_{} = {};\n", name, idx, idx))
+			.collect::<Vec<_>>()
+			.concat()
+	}
+
+	#[test]
+	fn test_moved_between_files() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let moved = block("moved");
+		write_commit_file(
+			&repo,
+			"a.rs",
+			&format!("fn a() {{\n{}}}\n", moved),
+			"c1",
+		);
+		write_commit_file(&repo, "b.rs", "fn b() {\n}\n", "c2");
+
+		// re-indented on the way, still the same code
+		repo_write_file(&repo, "a.rs", "fn a() {\n}\n")?;
+		repo_write_file(
+			&repo,
+			"b.rs",
+			&format!(
+				"fn b() {{\n}}\n\nfn moved() {{\n{}}}\n",
+				moved.replace('\t', "    ")
+			),
+		)?;
+		stage_add_file(repo_path, Path::new("a.rs"))?;
+		stage_add_file(repo_path, Path::new("b.rs"))?;
+		let id = commit(repo_path, "move", false)?;
+
+		let blocks = moved_blocks(
+			repo_path,
+			id,
+			MergeDiffMode::FirstParent,
+			5,
+		)?;
+
+		assert_eq!(
+			blocks,
+			vec![MovedBlock {
+				removed: MovedRange {
+					path: String::from("a.rs"),
+					start: 2,
+					len: 30,
+				},
+				added: MovedRange {
+					path: String::from("b.rs"),
+					start: 5,
+					len: 30,
+				},
+			}]
+		);
+
+		assert!(blocks[0].removed.contains("a.rs", 31));
+		assert!(!blocks[0].removed.contains("b.rs", 31));
+		assert!(blocks[0].added.contains("b.rs", 5));
+		assert!(!blocks[0].added.contains("b.rs", 35));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_moved_min_lines() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", "x\ny\nz\nkeep\n", "c1");
+
+		repo_write_file(&repo, "a.txt", "keep\n")?;
+		repo_write_file(&repo, "b.txt", "x\ny\nz\n")?;
+		stage_add_file(repo_path, Path::new("a.txt"))?;
+		stage_add_file(repo_path, Path::new("b.txt"))?;
+		let id = commit(repo_path, "move", false)?;
+
+		let moved = |min_lines| {
+			moved_blocks(
+				repo_path,
+				id,
+				MergeDiffMode::FirstParent,
+				min_lines,
+			)
+			.unwrap()
+			.len()
+		};
+
+		assert_eq!(moved(3), 1);
+		assert_eq!(moved(4), 0);
+		assert_eq!(moved(0), 0);
+
+		Ok(())
+	}
+}
@@ -0,0 +1,170 @@
+//! notes attached to commits (`git notes`), `notes_ref` `None`
+//! stands for the default: `core.notesRef` or `refs/notes/commits`
+
+use super::{
+	commit::signature_allow_undefined_name, utils::repo, CommitId,
+};
+use crate::error::Result;
+use git2::ErrorCode;
+use scopetime::scope_time;
+use std::collections::HashSet;
+
+/// note of commit `id`, `None` if it has none
+pub fn get_commit_note(
+	repo_path: &str,
+	id: CommitId,
+	notes_ref: Option<&str>,
+) -> Result<Option<String>> {
+	scope_time!("get_commit_note");
+
+	let repo = repo(repo_path)?;
+
+	let note = match repo.find_note(notes_ref, id.into()) {
+		Ok(note) => note,
+		Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+		Err(e) => return Err(e.into()),
+	};
+
+	Ok(Some(
+		String::from_utf8_lossy(note.message_bytes()).to_string(),
+	))
+}
+
+/// sets the note of commit `id`, replacing an existing one
+pub fn set_commit_note(
+	repo_path: &str,
+	id: CommitId,
+	note: &str,
+	notes_ref: Option<&str>,
+) -> Result<()> {
+	scope_time!("set_commit_note");
+
+	let repo = repo(repo_path)?;
+	let signature = signature_allow_undefined_name(&repo)?;
+
+	repo.note(
+		&signature,
+		&signature,
+		notes_ref,
+		id.into(),
+		note,
+		true,
+	)?;
+
+	Ok(())
+}
+
+/// those of `ids` that have a note, meant for the few commits shown
+/// at a time instead of all notes of the repo
+pub fn commits_with_notes(
+	repo_path: &str,
+	ids: &[CommitId],
+	notes_ref: Option<&str>,
+) -> Result<HashSet<CommitId>> {
+	scope_time!("commits_with_notes");
+
+	let repo = repo(repo_path)?;
+
+	let notes_ref = match notes_ref {
+		Some(notes_ref) => notes_ref.to_string(),
+		None => repo.note_default_ref()?,
+	};
+
+	// no note added yet
+	if repo.find_reference(&notes_ref).is_err() {
+		return Ok(HashSet::new());
+	}
+
+	Ok(ids
+		.iter()
+		.filter(|id| {
+			repo.find_note(Some(&notes_ref), (**id).into()).is_ok()
+		})
+		.copied()
+		.collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::tests::{repo_init, write_commit_file};
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_note_round_trip() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let c1 = write_commit_file(&repo, "a.txt", "a", "c1");
+		let c2 = write_commit_file(&repo, "a.txt", "b", "c2");
+
+		assert_eq!(
+			get_commit_note(repo_path, c1, None).unwrap(),
+			None
+		);
+		assert!(commits_with_notes(repo_path, &[c1, c2], None)
+			.unwrap()
+			.is_empty());
+
+		set_commit_note(repo_path, c1, "build: ok\n", None).unwrap();
+
+		assert_eq!(
+			get_commit_note(repo_path, c1, None).unwrap().as_deref(),
+			Some("build: ok\n")
+		);
+		assert_eq!(
+			get_commit_note(repo_path, c2, None).unwrap(),
+			None
+		);
+		assert_eq!(
+			commits_with_notes(repo_path, &[c1, c2], None).unwrap(),
+			vec![c1].into_iter().collect()
+		);
+
+		set_commit_note(repo_path, c1, "build: failed", None)
+			.unwrap();
+
+		assert_eq!(
+			get_commit_note(repo_path, c1, None).unwrap().as_deref(),
+			Some("build: failed")
+		);
+	}
+
+	#[test]
+	fn test_notes_ref() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let c1 = write_commit_file(&repo, "a.txt", "a", "c1");
+
+		set_commit_note(repo_path, c1, "ci", Some("refs/notes/ci"))
+			.unwrap();
+
+		assert_eq!(
+			get_commit_note(repo_path, c1, None).unwrap(),
+			None
+		);
+		assert_eq!(
+			get_commit_note(repo_path, c1, Some("refs/notes/ci"))
+				.unwrap()
+				.as_deref(),
+			Some("ci")
+		);
+
+		repo.config()
+			.unwrap()
+			.set_str("core.notesRef", "refs/notes/ci")
+			.unwrap();
+
+		assert_eq!(
+			get_commit_note(repo_path, c1, None).unwrap().as_deref(),
+			Some("ci")
+		);
+		assert_eq!(
+			commits_with_notes(repo_path, &[c1], None).unwrap().len(),
+			1
+		);
+	}
+}
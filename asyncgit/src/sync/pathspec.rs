@@ -0,0 +1,206 @@
+//! (un)staging by pathspec, e.g. `*.rs` or `src/**`
+
+use super::{
+	config::untracked_files_config_repo,
+	index_lock::retry_on_index_lock,
+	utils::{get_head_repo, repo},
+};
+use crate::error::Result;
+use git2::{
+	IndexAddOption, ObjectType, Pathspec, PathspecFlags, Repository,
+	StatusOptions, StatusShow,
+};
+use scopetime::scope_time;
+use std::path::Path;
+
+/// number of `paths` matching the pathspec `pattern`, uses the same
+/// matching as staging by pathspec
+pub fn count_pathspec_matches(
+	pattern: &str,
+	paths: &[String],
+) -> Result<usize> {
+	let pathspec = Pathspec::new(vec![pattern])?;
+
+	Ok(paths
+		.iter()
+		.filter(|path| {
+			pathspec.matches_path(
+				Path::new(path.as_str()),
+				PathspecFlags::DEFAULT,
+			)
+		})
+		.count())
+}
+
+fn count_status_matches(
+	repo: &Repository,
+	pattern: &str,
+	show: StatusShow,
+	include_untracked: bool,
+) -> Result<usize> {
+	let mut options = StatusOptions::default();
+	options
+		.show(show)
+		.pathspec(pattern)
+		.include_untracked(include_untracked)
+		.recurse_untracked_dirs(true)
+		.renames_head_to_index(true);
+
+	Ok(repo.statuses(Some(&mut options))?.len())
+}
+
+/// stages all working dir changes (including new and deleted files)
+/// matching the pathspec `pattern`, returns the number of files
+/// staged, `0` if nothing matched
+pub fn stage_pathspec(
+	repo_path: &str,
+	pattern: &str,
+) -> Result<usize> {
+	scope_time!("stage_pathspec");
+
+	let repo = repo(repo_path)?;
+
+	let config = untracked_files_config_repo(&repo)?;
+
+	let count = count_status_matches(
+		&repo,
+		pattern,
+		StatusShow::Workdir,
+		config.include_untracked(),
+	)?;
+	if count == 0 {
+		return Ok(0);
+	}
+
+	retry_on_index_lock(repo_path, || {
+		let mut index = repo.index()?;
+
+		if !config.include_none() {
+			index.add_all(
+				vec![pattern],
+				IndexAddOption::DEFAULT,
+				None,
+			)?;
+		}
+		// picks up deletions
+		index.update_all(vec![pattern], None)?;
+
+		index.write()?;
+
+		Ok(())
+	})?;
+
+	Ok(count)
+}
+
+/// unstages all staged changes matching the pathspec `pattern`,
+/// returns the number of files unstaged, `0` if nothing matched
+pub fn unstage_pathspec(
+	repo_path: &str,
+	pattern: &str,
+) -> Result<usize> {
+	scope_time!("unstage_pathspec");
+
+	let repo = repo(repo_path)?;
+
+	let count = count_status_matches(
+		&repo,
+		pattern,
+		StatusShow::Index,
+		false,
+	)?;
+	if count == 0 {
+		return Ok(0);
+	}
+
+	let head = if let Ok(id) = get_head_repo(&repo) {
+		Some(repo.find_object(id.into(), Some(ObjectType::Commit))?)
+	} else {
+		None
+	};
+
+	retry_on_index_lock(repo_path, || {
+		repo.reset_default(head.as_ref(), &[pattern])?;
+		Ok(())
+	})?;
+
+	Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		status::{get_status, StatusType},
+		tests::{repo_init, write_commit_file},
+	};
+	use std::{fs, path::Path};
+
+	fn status_paths(repo_path: &str, typ: StatusType) -> Vec<String> {
+		let mut paths = get_status(repo_path, typ, None)
+			.unwrap()
+			.into_iter()
+			.map(|item| item.path)
+			.collect::<Vec<_>>();
+		paths.sort();
+		paths
+	}
+
+	#[test]
+	fn test_count_matches() {
+		let paths = vec![
+			String::from("a.rs"),
+			String::from("src/b.rs"),
+			String::from("src/c.txt"),
+		];
+
+		assert_eq!(
+			count_pathspec_matches("*.rs", &paths).unwrap(),
+			2
+		);
+		assert_eq!(
+			count_pathspec_matches("src/*", &paths).unwrap(),
+			2
+		);
+		assert_eq!(count_pathspec_matches("src", &paths).unwrap(), 2);
+		assert_eq!(
+			count_pathspec_matches("*.md", &paths).unwrap(),
+			0
+		);
+	}
+
+	#[test]
+	fn test_stage_unstage() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "gone.rs", "x", "c1");
+
+		fs::create_dir(root.join("src")).unwrap();
+		fs::write(root.join("src/a.rs"), "a").unwrap();
+		fs::write(root.join("src/b.txt"), "b").unwrap();
+		fs::write(root.join("c.rs"), "c").unwrap();
+		fs::remove_file(root.join(Path::new("gone.rs"))).unwrap();
+
+		assert_eq!(stage_pathspec(repo_path, "*.md").unwrap(), 0);
+		assert_eq!(stage_pathspec(repo_path, "*.rs").unwrap(), 3);
+
+		assert_eq!(
+			status_paths(repo_path, StatusType::Stage),
+			vec!["c.rs", "gone.rs", "src/a.rs"]
+		);
+		assert_eq!(
+			status_paths(repo_path, StatusType::WorkingDir),
+			vec!["src/b.txt"]
+		);
+
+		assert_eq!(unstage_pathspec(repo_path, "src/*").unwrap(), 1);
+		assert_eq!(unstage_pathspec(repo_path, "src/*").unwrap(), 0);
+
+		assert_eq!(
+			status_paths(repo_path, StatusType::Stage),
+			vec!["c.rs", "gone.rs"]
+		);
+	}
+}
@@ -0,0 +1,187 @@
+//! checks for setup problems that get in the way of committing,
+//! mostly hit by new users
+
+use super::{
+	config::{get_config_string_repo, get_config_strings_global},
+	utils::{self, work_dir},
+};
+use crate::error::Result;
+use scopetime::scope_time;
+use std::{env, path::Path};
+
+/// git config entry listing repos owned by others that are trusted
+pub const SAFE_DIRECTORY: &str = "safe.directory";
+
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreflightIssue {
+	/// `user.name` is not set
+	MissingUserName,
+	/// `user.email` is not set, committing fails without it
+	MissingUserEmail,
+	/// the editor program (arg) cannot be found
+	UnusableEditor(String),
+	/// the repo (path) belongs to another user and is not listed in
+	/// `safe.directory`, git refuses to work in it then
+	UnsafeDirectory(String),
+}
+
+/// whether `program` is a path to a file or found in `PATH`
+fn find_program(program: &str) -> bool {
+	let path = Path::new(program);
+	if path.components().count() > 1 {
+		return path.is_file();
+	}
+
+	env::var_os("PATH").map_or(false, |paths| {
+		env::split_paths(&paths).any(|dir| {
+			let file = dir.join(program);
+			file.is_file()
+				|| (cfg!(windows)
+					&& file.with_extension("exe").is_file())
+		})
+	})
+}
+
+#[cfg(unix)]
+fn owned_by_other(path: &Path) -> bool {
+	use std::os::unix::fs::MetadataExt;
+
+	// safe: `geteuid` cannot fail
+	let uid = unsafe { libc::geteuid() };
+
+	path.metadata().map_or(false, |meta| meta.uid() != uid)
+}
+
+#[cfg(not(unix))]
+const fn owned_by_other(_path: &Path) -> bool {
+	false
+}
+
+/// `path` is listed in the `safe.directory` values (or all are)
+fn is_safe_directory(listed: &[String], path: &str) -> bool {
+	listed
+		.iter()
+		.any(|dir| dir == "*" || dir.trim_end_matches('/') == path)
+}
+
+/// problems found in the identity config, with `editor` (the
+/// program of the configured editor) and the ownership of the repo
+pub fn preflight_check(
+	repo_path: &str,
+	editor: &str,
+) -> Result<Vec<PreflightIssue>> {
+	scope_time!("preflight_check");
+
+	let repo = utils::repo(repo_path)?;
+	let mut issues = Vec::new();
+
+	if get_config_string_repo(&repo, "user.name")?.is_none() {
+		issues.push(PreflightIssue::MissingUserName);
+	}
+	if get_config_string_repo(&repo, "user.email")?.is_none() {
+		issues.push(PreflightIssue::MissingUserEmail);
+	}
+
+	if !find_program(editor) {
+		issues
+			.push(PreflightIssue::UnusableEditor(editor.to_string()));
+	}
+
+	let dir = work_dir(&repo).unwrap_or_else(|_| repo.path());
+	let dir = dir.canonicalize()?;
+	let path =
+		dir.to_string_lossy().trim_end_matches('/').to_string();
+
+	if owned_by_other(&dir)
+		&& !is_safe_directory(
+			&get_config_strings_global(SAFE_DIRECTORY)?,
+			&path,
+		) {
+		issues.push(PreflightIssue::UnsafeDirectory(path));
+	}
+
+	Ok(issues)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		set_string_scoped, tests::repo_init_empty, ConfigScope,
+	};
+	use git2::Repository;
+
+	/// an editor that exists on every test machine
+	const EDITOR: &str = if cfg!(windows) { "cmd" } else { "sh" };
+
+	#[test]
+	fn test_identity_set() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		assert_eq!(
+			preflight_check(repo_path, EDITOR).unwrap(),
+			vec![]
+		);
+	}
+
+	#[test]
+	fn test_identity_missing() {
+		let (td, _repo) = repo_init_empty().unwrap();
+		let repo_path = td.path().to_str().unwrap();
+
+		// a repo without the identity `repo_init_empty` sets
+		std::fs::remove_dir_all(td.path().join(".git")).unwrap();
+		Repository::init(td.path()).unwrap();
+
+		assert_eq!(
+			preflight_check(repo_path, EDITOR).unwrap(),
+			vec![
+				PreflightIssue::MissingUserName,
+				PreflightIssue::MissingUserEmail
+			]
+		);
+
+		set_string_scoped(
+			repo_path,
+			"user.email",
+			"mail@example.com",
+			ConfigScope::Local,
+		)
+		.unwrap();
+
+		assert_eq!(
+			preflight_check(repo_path, EDITOR).unwrap(),
+			vec![PreflightIssue::MissingUserName]
+		);
+	}
+
+	#[test]
+	fn test_unusable_editor() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		assert_eq!(
+			preflight_check(repo_path, "no-such-editor-4711")
+				.unwrap(),
+			vec![PreflightIssue::UnusableEditor(String::from(
+				"no-such-editor-4711"
+			))]
+		);
+		assert!(!find_program("/no/such/editor"));
+	}
+
+	#[test]
+	fn test_is_safe_directory() {
+		let listed =
+			vec![String::from("/other"), String::from("/repo/")];
+
+		assert!(is_safe_directory(&listed, "/repo"));
+		assert!(!is_safe_directory(&listed, "/repo2"));
+		assert!(!is_safe_directory(&[], "/repo"));
+		assert!(is_safe_directory(&[String::from("*")], "/repo"));
+	}
+}
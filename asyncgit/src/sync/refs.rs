@@ -0,0 +1,198 @@
+//! all refs of a repo with the commit they point to
+
+use super::{utils::repo, CommitId};
+use crate::error::Result;
+use git2::BranchType;
+use scopetime::scope_time;
+
+/// kind of a `RefTarget`, in the order they are listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RefKind {
+	///
+	Head,
+	///
+	LocalBranch,
+	///
+	RemoteBranch,
+	///
+	Tag,
+	///
+	Stash,
+}
+
+/// a named ref peeled to its commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefTarget {
+	///
+	pub kind: RefKind,
+	/// short name (e.g. `origin/master`, `stash@{0}`)
+	pub name: String,
+	///
+	pub id: CommitId,
+}
+
+/// lists `HEAD`, branches, tags and stashes ordered by kind and name,
+/// stashes keep their stack order. refs not pointing to a commit
+/// and the `HEAD` of remotes are skipped
+pub fn get_ref_targets(repo_path: &str) -> Result<Vec<RefTarget>> {
+	scope_time!("get_ref_targets");
+
+	let mut repo = repo(repo_path)?;
+	let mut res = Vec::new();
+
+	if let Ok(commit) =
+		repo.head().and_then(|head| head.peel_to_commit())
+	{
+		res.push(RefTarget {
+			kind: RefKind::Head,
+			name: String::from("HEAD"),
+			id: commit.id().into(),
+		});
+	}
+
+	for (kind, branch_type) in [
+		(RefKind::LocalBranch, BranchType::Local),
+		(RefKind::RemoteBranch, BranchType::Remote),
+	] {
+		for branch in repo.branches(Some(branch_type))? {
+			let (branch, _) = branch?;
+			let name = match branch.name() {
+				Ok(Some(name)) => name.to_string(),
+				_ => continue,
+			};
+
+			if kind == RefKind::RemoteBranch
+				&& name.ends_with("/HEAD")
+			{
+				continue;
+			}
+
+			if let Ok(commit) = branch.get().peel_to_commit() {
+				res.push(RefTarget {
+					kind,
+					name,
+					id: commit.id().into(),
+				});
+			}
+		}
+	}
+
+	for name in repo.tag_names(None)?.iter().flatten() {
+		if let Ok(commit) = repo
+			.revparse_single(&format!("refs/tags/{}", name))
+			.and_then(|obj| obj.peel_to_commit())
+		{
+			res.push(RefTarget {
+				kind: RefKind::Tag,
+				name: name.to_string(),
+				id: commit.id().into(),
+			});
+		}
+	}
+
+	// stable, so stashes stay in stack order
+	res.sort_by(|a, b| {
+		a.kind.cmp(&b.kind).then_with(|| a.name.cmp(&b.name))
+	});
+
+	repo.stash_foreach(|index, _msg, id| {
+		res.push(RefTarget {
+			kind: RefKind::Stash,
+			name: format!("stash@{{{}}}", index),
+			id: (*id).into(),
+		});
+		true
+	})?;
+
+	Ok(res)
+}
+
+/// whether the log walked from `HEAD` gets to `id` eventually
+pub fn is_reachable_from_head(
+	repo_path: &str,
+	id: CommitId,
+) -> Result<bool> {
+	scope_time!("is_reachable_from_head");
+
+	let repo = repo(repo_path)?;
+	let head =
+		match repo.head().and_then(|head| head.peel_to_commit()) {
+			Ok(head) => head.id(),
+			// unborn, nothing to walk
+			Err(_) => return Ok(false),
+		};
+
+	Ok(head == id.into()
+		|| repo.graph_descendant_of(head, id.into())?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		create_branch, stash_save, tag,
+		tests::{repo_init, write_commit_file},
+		utils::repo_write_file,
+	};
+	use git2::Signature;
+
+	#[test]
+	fn test_ref_targets() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let first = write_commit_file(&repo, "a.txt", "a", "first");
+		tag(repo_path, &first, "v1").unwrap();
+		// checks out `zeta`
+		create_branch(repo_path, "zeta").unwrap();
+		let second = write_commit_file(&repo, "a.txt", "b", "second");
+		tag(repo_path, &second, "v0").unwrap();
+
+		repo_write_file(&repo, "a.txt", "c").unwrap();
+		let stash =
+			stash_save(repo_path, None, false, false).unwrap();
+
+		let refs = get_ref_targets(repo_path).unwrap();
+		let listed: Vec<_> = refs
+			.iter()
+			.map(|r| (r.kind, r.name.as_str(), r.id))
+			.collect();
+
+		assert_eq!(
+			listed,
+			vec![
+				(RefKind::Head, "HEAD", second),
+				(RefKind::LocalBranch, "master", first),
+				(RefKind::LocalBranch, "zeta", second),
+				(RefKind::Tag, "v0", second),
+				(RefKind::Tag, "v1", first),
+				(RefKind::Stash, "stash@{0}", stash),
+			]
+		);
+	}
+
+	#[test]
+	fn test_reachable_from_head() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let first = write_commit_file(&repo, "a.txt", "a", "first");
+		let second = write_commit_file(&repo, "a.txt", "b", "second");
+
+		// a commit with an unrelated history
+		let sig = Signature::now("name", "email").unwrap();
+		let tree = repo
+			.find_tree(repo.index().unwrap().write_tree().unwrap())
+			.unwrap();
+		let orphan = repo
+			.commit(None, &sig, &sig, "orphan", &tree, &[])
+			.unwrap();
+
+		assert!(is_reachable_from_head(repo_path, first).unwrap());
+		assert!(is_reachable_from_head(repo_path, second).unwrap());
+		assert!(!is_reachable_from_head(repo_path, orphan.into())
+			.unwrap());
+	}
+}
@@ -1,14 +1,69 @@
 #![allow(dead_code)]
 
 use super::push::ProgressNotification;
-use crate::{error::Result, sync::cred::BasicAuthCredential};
-use crossbeam_channel::Sender;
+use crate::{
+	error::{Error, Result},
+	sync::cred::BasicAuthCredential,
+};
+use crossbeam_channel::{bounded, RecvTimeoutError, Sender};
 use git2::{Cred, Error as GitError, RemoteCallbacks};
-use std::sync::{
-	atomic::{AtomicBool, Ordering},
-	Arc, Mutex,
+use std::{
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc, Mutex,
+	},
+	thread,
+	time::{Duration, Instant},
 };
 
+/// how often `Callbacks::watch` looks for a stalled transfer
+const WATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// notices transfers going without any progress for too long
+struct Stall {
+	timeout: Duration,
+	last_progress: Mutex<Instant>,
+	timed_out: AtomicBool,
+}
+
+impl Stall {
+	fn new(timeout: Duration) -> Self {
+		Self {
+			timeout,
+			last_progress: Mutex::new(Instant::now()),
+			timed_out: AtomicBool::new(false),
+		}
+	}
+
+	fn progress(&self) {
+		if let Ok(mut last_progress) = self.last_progress.lock() {
+			*last_progress = Instant::now();
+		}
+	}
+
+	/// once timed out this sticks, whatever progress comes later
+	fn is_timed_out(&self) -> bool {
+		if self.timed_out.load(Ordering::Relaxed) {
+			return true;
+		}
+
+		let stalled = self
+			.last_progress
+			.lock()
+			.map_or(false, |last| last.elapsed() > self.timeout);
+		if stalled {
+			self.timed_out.store(true, Ordering::Relaxed);
+		}
+
+		stalled
+	}
+}
+
+fn timeout_error(timeout: Duration) -> Error {
+	log::warn!("remote operation timed out after {:?}", timeout);
+	Error::NetworkTimeout(timeout.as_secs())
+}
+
 ///
 #[derive(Default, Clone)]
 pub struct CallbackStats {
@@ -22,6 +77,9 @@ pub struct Callbacks {
 	basic_credential: Option<BasicAuthCredential>,
 	stats: Arc<Mutex<CallbackStats>>,
 	first_call_to_credentials: Arc<AtomicBool>,
+	abort: Option<Arc<AtomicBool>>,
+	stall: Option<Arc<Stall>>,
+	ssl_verify: bool,
 }
 
 impl Callbacks {
@@ -39,9 +97,86 @@ impl Callbacks {
 			first_call_to_credentials: Arc::new(AtomicBool::new(
 				true,
 			)),
+			abort: None,
+			stall: None,
+			ssl_verify: true,
+		}
+	}
+
+	/// transfers stop once `abort` is set
+	pub fn abort_flag(mut self, abort: Arc<AtomicBool>) -> Self {
+		self.abort = Some(abort);
+		self
+	}
+
+	/// transfers stop once none of the callbacks got called for
+	/// `timeout`, `None` waits forever
+	#[must_use]
+	pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+		self.stall =
+			timeout.map(|timeout| Arc::new(Stall::new(timeout)));
+		self
+	}
+
+	/// runs the transfer `f` on its own thread and gives up on it
+	/// once it stalls beyond the timeout. libgit2 offers no way to
+	/// cancel a hanging connect or read, the abandoned transfer stops
+	/// at its next callback instead
+	pub fn watch<T, F>(&self, f: F) -> Result<T>
+	where
+		T: Send + 'static,
+		F: FnOnce(Self) -> Result<T> + Send + 'static,
+	{
+		let stall = match &self.stall {
+			Some(stall) => Arc::clone(stall),
+			None => return f(self.clone()),
+		};
+
+		stall.progress();
+
+		let (tx, rx) = bounded(1);
+		let this = self.clone();
+		thread::spawn(move || {
+			tx.send(f(this)).ok();
+		});
+
+		loop {
+			match rx.recv_timeout(WATCH_INTERVAL) {
+				Ok(res) => return res,
+				Err(RecvTimeoutError::Timeout) => {
+					if stall.is_timed_out() {
+						return Err(timeout_error(stall.timeout));
+					}
+				}
+				Err(RecvTimeoutError::Disconnected) => {
+					return Err(Error::Generic(String::from(
+						"remote operation failed unexpectedly",
+					)));
+				}
+			}
+		}
+	}
+
+	/// `error` of an aborted transfer, replaced by the timeout if
+	/// that caused it
+	pub fn check_timeout(&self, error: Error) -> Error {
+		match &self.stall {
+			Some(stall)
+				if stall.timed_out.load(Ordering::Relaxed) =>
+			{
+				timeout_error(stall.timeout)
+			}
+			_ => error,
 		}
 	}
 
+	/// `false` accepts any certificate (`http.sslVerify`)
+	#[must_use]
+	pub const fn ssl_verify(mut self, verify: bool) -> Self {
+		self.ssl_verify = verify;
+		self
+	}
+
 	///
 	pub fn get_stats(&self) -> Result<CallbackStats> {
 		let stats = self.stats.lock()?;
@@ -55,6 +190,7 @@ impl Callbacks {
 		let this = self.clone();
 		callbacks.push_transfer_progress(
 			move |current, total, bytes| {
+				this.made_progress();
 				this.push_transfer_progress(current, total, bytes);
 			},
 		);
@@ -62,17 +198,25 @@ impl Callbacks {
 		let this = self.clone();
 		callbacks.update_tips(move |name, a, b| {
 			this.update_tips(name, a, b);
-			true
+			!this.is_aborted()
 		});
 
 		let this = self.clone();
 		callbacks.transfer_progress(move |p| {
+			this.made_progress();
 			this.transfer_progress(&p);
-			true
+			!this.is_aborted()
+		});
+
+		let this = self.clone();
+		callbacks.sideband_progress(move |_| {
+			this.made_progress();
+			!this.is_aborted()
 		});
 
 		let this = self.clone();
 		callbacks.pack_progress(move |stage, current, total| {
+			this.made_progress();
 			this.pack_progress(stage, total, current);
 		});
 
@@ -93,9 +237,35 @@ impl Callbacks {
 			},
 		);
 
+		if !self.ssl_verify {
+			callbacks.certificate_check(|_cert, host| {
+				log::warn!("certificate of '{}' not verified", host);
+				true
+			});
+		}
+
 		callbacks
 	}
 
+	fn is_aborted(&self) -> bool {
+		self.abort
+			.as_ref()
+			.map_or(false, |abort| abort.load(Ordering::Relaxed))
+			|| self.is_timed_out()
+	}
+
+	fn is_timed_out(&self) -> bool {
+		self.stall
+			.as_ref()
+			.map_or(false, |stall| stall.is_timed_out())
+	}
+
+	fn made_progress(&self) {
+		if let Some(stall) = &self.stall {
+			stall.progress();
+		}
+	}
+
 	fn push_update_reference(
 		&self,
 		reference: &str,
@@ -187,6 +357,10 @@ impl Callbacks {
 			allowed_types
 		);
 
+		if self.is_timed_out() {
+			return Err(GitError::from_str("timed out"));
+		}
+
 		// This boolean is used to avoid multiple calls to credentials callback.
 		if self.first_call_to_credentials.load(Ordering::Relaxed) {
 			self.first_call_to_credentials
@@ -221,3 +395,64 @@ impl Callbacks {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn with_timeout(millis: u64) -> Callbacks {
+		Callbacks::new(None, None)
+			.timeout(Some(Duration::from_millis(millis)))
+	}
+
+	#[test]
+	fn test_watch_stalled() {
+		let start = Instant::now();
+
+		let res = with_timeout(200).watch(|_| {
+			thread::sleep(Duration::from_secs(2));
+			Ok(())
+		});
+
+		assert!(matches!(res, Err(Error::NetworkTimeout(_))));
+		assert!(start.elapsed() < Duration::from_secs(2));
+	}
+
+	#[test]
+	fn test_watch_progressing() {
+		let res = with_timeout(200).watch(|callbacks| {
+			for _ in 0..10 {
+				thread::sleep(Duration::from_millis(50));
+				callbacks.made_progress();
+			}
+			Ok(42)
+		});
+
+		assert_eq!(res.unwrap(), 42);
+	}
+
+	#[test]
+	fn test_stalled_callbacks_abort() {
+		let callbacks = with_timeout(50);
+		assert!(!callbacks.is_aborted());
+
+		thread::sleep(Duration::from_millis(100));
+		assert!(callbacks.is_aborted());
+
+		// progress arriving too late does not revive the transfer
+		callbacks.made_progress();
+		assert!(callbacks.is_aborted());
+		assert!(matches!(
+			callbacks.check_timeout(Error::NoHead),
+			Error::NetworkTimeout(_)
+		));
+
+		let callbacks = Callbacks::new(None, None);
+		thread::sleep(Duration::from_millis(100));
+		assert!(!callbacks.is_aborted());
+		assert!(matches!(
+			callbacks.check_timeout(Error::NoHead),
+			Error::NoHead
+		));
+	}
+}
@@ -1,27 +1,65 @@
 //!
 
 mod callbacks;
+mod prune;
 pub(crate) mod push;
 pub(crate) mod tags;
+mod transport;
 
 use crate::{
 	error::{Error, Result},
 	sync::{
-		cred::BasicAuthCredential,
-		remotes::push::ProgressNotification, utils,
+		config::get_config_string_repo,
+		cred::{
+			extract_username_password_for_remote,
+			need_username_password_for_remote, BasicAuthCredential,
+		},
+		remotes::push::ProgressNotification,
+		utils,
 	},
 };
-use crossbeam_channel::Sender;
-use git2::{BranchType, FetchOptions, Repository};
-use scopetime::scope_time;
+use crossbeam_channel::{bounded, Sender};
+use git2::{BranchType, Direction, FetchOptions, Oid, Repository};
+use scopetime::{scope_stats, scope_time};
+use std::{
+	sync::{atomic::AtomicBool, Arc},
+	thread,
+	time::Duration,
+};
 use utils::bytes2string;
 
 pub use callbacks::Callbacks;
+pub use prune::{delete_remote_tracking_refs, stale_remote_branches};
 pub use tags::tags_missing_remote;
+pub use transport::HttpSettings;
 
 /// origin
 pub const DEFAULT_REMOTE_NAME: &str = "origin";
 
+/// git config key of the seconds a fetch or push may go without any
+/// progress, `0` waits forever
+pub const NETWORK_TIMEOUT_CONFIG: &str = "gitui.networkTimeout";
+
+const DEFAULT_NETWORK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// how long remote operations in `repo` may stall,
+/// see `NETWORK_TIMEOUT_CONFIG`
+pub(crate) fn network_timeout(repo: &Repository) -> Option<Duration> {
+	let config = get_config_string_repo(repo, NETWORK_TIMEOUT_CONFIG)
+		.ok()
+		.flatten();
+
+	match config.map(|secs| secs.trim().parse::<u64>()) {
+		None => Some(DEFAULT_NETWORK_TIMEOUT),
+		Some(Ok(0)) => None,
+		Some(Ok(secs)) => Some(Duration::from_secs(secs)),
+		Some(Err(e)) => {
+			log::error!("invalid {}: {}", NETWORK_TIMEOUT_CONFIG, e);
+			Some(DEFAULT_NETWORK_TIMEOUT)
+		}
+	}
+}
+
 ///
 pub fn get_remotes(repo_path: &str) -> Result<Vec<String>> {
 	scope_time!("get_remotes");
@@ -34,6 +72,21 @@ pub fn get_remotes(repo_path: &str) -> Result<Vec<String>> {
 	Ok(remotes)
 }
 
+/// url pushing to `remote` goes to, its `pushurl` if set
+pub fn get_remote_push_url(
+	repo_path: &str,
+	remote: &str,
+) -> Result<String> {
+	let repo = utils::repo(repo_path)?;
+	let remote = repo.find_remote(remote)?;
+
+	remote
+		.pushurl()
+		.or_else(|| remote.url())
+		.map(String::from)
+		.ok_or(Error::UnknownRemote)
+}
+
 /// tries to find origin or the only remote that is defined if any
 /// in case of multiple remotes and none named *origin* we fail
 pub fn get_default_remote(repo_path: &str) -> Result<String> {
@@ -75,38 +128,280 @@ pub(crate) fn get_default_remote_in_repo(
 	Err(Error::NoDefaultRemoteFound)
 }
 
-/// fetches from upstream/remote for `branch`
-pub(crate) fn fetch(
+/// returns the remote `branch` is tracking
+pub fn get_upstream_remote(
 	repo_path: &str,
 	branch: &str,
-	basic_credential: Option<BasicAuthCredential>,
-	progress_sender: Option<Sender<ProgressNotification>>,
-) -> Result<usize> {
-	scope_time!("fetch_origin");
-
+) -> Result<String> {
 	let repo = utils::repo(repo_path)?;
 	let branch_ref = repo
 		.find_branch(branch, BranchType::Local)?
 		.into_reference();
 	let branch_ref = bytes2string(branch_ref.name_bytes())?;
 	let remote_name = repo.branch_upstream_remote(&branch_ref)?;
-	let remote_name = bytes2string(&*remote_name)?;
-	let mut remote = repo.find_remote(&remote_name)?;
+
+	bytes2string(&*remote_name)
+}
+
+/// returns the name the upstream of `branch` has on its remote
+/// (`branch.<name>.merge`), which does not have to match `branch`
+pub fn get_upstream_branch(
+	repo_path: &str,
+	branch: &str,
+) -> Result<Option<String>> {
+	let repo = utils::repo(repo_path)?;
+	get_upstream_branch_in_repo(&repo, branch)
+}
+
+/// see `get_upstream_branch`
+pub(crate) fn get_upstream_branch_in_repo(
+	repo: &Repository,
+	branch: &str,
+) -> Result<Option<String>> {
+	let merge = get_config_string_repo(
+		repo,
+		&format!("branch.{}.merge", branch),
+	)?;
+
+	Ok(merge.map(|merge| {
+		merge
+			.strip_prefix("refs/heads/")
+			.map(String::from)
+			.unwrap_or(merge)
+	}))
+}
+
+/// fetches from upstream/remote for `branch`
+#[cfg(test)]
+pub(crate) fn fetch_branch(
+	repo_path: &str,
+	branch: &str,
+	basic_credential: Option<BasicAuthCredential>,
+	progress_sender: Option<Sender<ProgressNotification>>,
+) -> Result<usize> {
+	let remote_name = get_upstream_remote(repo_path, branch)?;
+	let upstream_branch = get_upstream_branch(repo_path, branch)?
+		.unwrap_or_else(|| branch.to_string());
+
+	fetch(
+		repo_path,
+		&remote_name,
+		&[upstream_branch],
+		basic_credential,
+		progress_sender,
+	)
+}
+
+/// fetches `refspecs` from `remote`.
+/// an empty list of `refspecs` fetches using the remotes configured
+/// refspecs (usually all branches).
+/// fails once the transfer stalls beyond `network_timeout`
+pub(crate) fn fetch(
+	repo_path: &str,
+	remote: &str,
+	refspecs: &[String],
+	basic_credential: Option<BasicAuthCredential>,
+	progress_sender: Option<Sender<ProgressNotification>>,
+) -> Result<usize> {
+	scope_stats!("fetch");
+
+	let repo = utils::repo(repo_path)?;
+	let callbacks = Callbacks::new(progress_sender, basic_credential)
+		.timeout(network_timeout(&repo));
+
+	let repo_path = repo_path.to_string();
+	let remote = remote.to_string();
+	let refspecs = refspecs.to_vec();
+	callbacks.watch(move |callbacks| {
+		fetch_watched(&repo_path, &remote, &refspecs, callbacks)
+	})
+}
+
+fn fetch_watched(
+	repo_path: &str,
+	remote: &str,
+	refspecs: &[String],
+	callbacks: Callbacks,
+) -> Result<usize> {
+	let repo = utils::repo(repo_path)?;
+	let mut remote = repo.find_remote(remote)?;
+	let settings = HttpSettings::for_remote(&repo, &remote, false)?;
 
 	let mut options = FetchOptions::new();
-	let callbacks = Callbacks::new(progress_sender, basic_credential);
+	let callbacks = callbacks.ssl_verify(settings.ssl_verify);
 	options.remote_callbacks(callbacks.callbacks());
+	options.proxy_options(settings.proxy_options());
 
-	remote.fetch(&[branch], Some(&mut options), None)?;
+	remote.fetch(refspecs, Some(&mut options), None).map_err(
+		|e| callbacks.check_timeout(settings.map_error(e)),
+	)?;
 
 	Ok(remote.stats().received_bytes())
 }
 
+/// fetches the upstream of `branch` without any user interaction.
+/// returns `None` if there is no upstream or credentials would have
+/// to be entered, otherwise the number of commits that arrived on
+/// the upstream branch.
+/// the transfer is cancelled once `abort` is set
+pub fn fetch_upstream_unattended(
+	repo_path: &str,
+	branch: &str,
+	abort: Arc<AtomicBool>,
+) -> Result<Option<usize>> {
+	scope_time!("fetch_upstream_unattended");
+
+	let repo = utils::repo(repo_path)?;
+	if repo
+		.find_branch(branch, BranchType::Local)?
+		.upstream()
+		.is_err()
+	{
+		return Ok(None);
+	}
+
+	let remote_name = get_upstream_remote(repo_path, branch)?;
+	let upstream_branch = get_upstream_branch_in_repo(&repo, branch)?
+		.unwrap_or_else(|| branch.to_string());
+
+	let basic_credential = if need_username_password_for_remote(
+		repo_path,
+		&remote_name,
+	)? {
+		let cred = extract_username_password_for_remote(
+			repo_path,
+			&remote_name,
+		)?;
+		if !cred.is_complete() {
+			return Ok(None);
+		}
+		Some(cred)
+	} else {
+		None
+	};
+
+	let upstream_head = || -> Result<Option<Oid>> {
+		Ok(repo
+			.find_branch(branch, BranchType::Local)?
+			.upstream()?
+			.get()
+			.target())
+	};
+
+	let before = upstream_head()?;
+
+	let mut remote = repo.find_remote(&remote_name)?;
+	let settings = HttpSettings::for_remote(&repo, &remote, false)?;
+	let mut options = FetchOptions::new();
+	let callbacks = Callbacks::new(None, basic_credential)
+		.abort_flag(abort)
+		.timeout(network_timeout(&repo))
+		.ssl_verify(settings.ssl_verify);
+	options.remote_callbacks(callbacks.callbacks());
+	options.proxy_options(settings.proxy_options());
+	remote
+		.fetch(&[upstream_branch], Some(&mut options), None)
+		.map_err(|e| {
+			callbacks.check_timeout(settings.map_error(e))
+		})?;
+
+	let new_commits = match (before, upstream_head()?) {
+		(Some(before), Some(after)) if before != after => {
+			repo.graph_ahead_behind(after, before)?.0
+		}
+		_ => 0,
+	};
+
+	Ok(Some(new_commits))
+}
+
+/// lists the branches `remote` advertises.
+/// fails if the remote does not answer within `timeout`
+pub fn get_remote_branches_advertised(
+	repo_path: &str,
+	remote: &str,
+	basic_credential: Option<BasicAuthCredential>,
+	timeout: Duration,
+) -> Result<Vec<String>> {
+	scope_time!("get_remote_branches_advertised");
+
+	let branches = get_refs_advertised(
+		repo_path,
+		remote,
+		basic_credential,
+		timeout,
+	)?
+	.iter()
+	.filter_map(|(name, _)| {
+		name.strip_prefix("refs/heads/").map(String::from)
+	})
+	.collect::<Vec<_>>();
+
+	Ok(branches)
+}
+
+/// lists the full names and targets of all refs `remote` advertises
+/// (like `git ls-remote`).
+/// fails if the remote does not answer within `timeout`
+pub(crate) fn get_refs_advertised(
+	repo_path: &str,
+	remote: &str,
+	basic_credential: Option<BasicAuthCredential>,
+	timeout: Duration,
+) -> Result<Vec<(String, Oid)>> {
+	let (tx, rx) = bounded(1);
+	let repo_path = repo_path.to_string();
+	let remote_name = remote.to_string();
+
+	// libgit2 offers no way to cancel a hanging connect so we run it
+	// on its own thread and just stop waiting for it on timeout
+	thread::spawn(move || {
+		let res =
+			remote_refs(&repo_path, &remote_name, basic_credential);
+		tx.send(res).ok();
+	});
+
+	rx.recv_timeout(timeout).unwrap_or_else(|_| {
+		Err(Error::Generic(format!(
+			"remote '{}' did not respond within {}s",
+			remote,
+			timeout.as_secs()
+		)))
+	})
+}
+
+fn remote_refs(
+	repo_path: &str,
+	remote: &str,
+	basic_credential: Option<BasicAuthCredential>,
+) -> Result<Vec<(String, Oid)>> {
+	let repo = utils::repo(repo_path)?;
+	let mut remote = repo.find_remote(remote)?;
+	let settings = HttpSettings::for_remote(&repo, &remote, false)?;
+	let callbacks = Callbacks::new(None, basic_credential)
+		.ssl_verify(settings.ssl_verify);
+	let conn = remote
+		.connect_auth(
+			Direction::Fetch,
+			Some(callbacks.callbacks()),
+			Some(settings.proxy_options()),
+		)
+		.map_err(|e| settings.map_error(e))?;
+
+	let refs = conn
+		.list()?
+		.iter()
+		.map(|head| (head.name().to_string(), head.oid()))
+		.collect::<Vec<_>>();
+
+	Ok(refs)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use crate::sync::tests::{
-		debug_cmd_print, repo_clone, repo_init,
+		debug_cmd_print, repo_clone, repo_init, write_commit_file,
 	};
 
 	#[test]
@@ -120,7 +415,166 @@ mod tests {
 
 		assert_eq!(remotes, vec![String::from("origin")]);
 
-		fetch(repo_path, "master", None, None).unwrap();
+		fetch_branch(repo_path, "master", None, None).unwrap();
+	}
+
+	#[test]
+	fn test_fetch_all_and_list_branches() {
+		let (remote_dir, remote) = repo_init().unwrap();
+		let remote_path = remote_dir.path().to_str().unwrap();
+		let (repo_dir, repo) = repo_clone(remote_path).unwrap();
+		let repo_path = repo_dir.path().as_os_str().to_str().unwrap();
+
+		let head = remote.head().unwrap().peel_to_commit().unwrap();
+		remote.branch("feature", &head, false).unwrap();
+
+		let branches = get_remote_branches_advertised(
+			repo_path,
+			"origin",
+			None,
+			Duration::from_secs(10),
+		)
+		.unwrap();
+		assert_eq!(
+			branches,
+			vec![String::from("feature"), String::from("master")]
+		);
+
+		fetch(repo_path, "origin", &[], None, None).unwrap();
+
+		assert!(repo
+			.find_branch("origin/feature", BranchType::Remote)
+			.is_ok());
+	}
+
+	#[test]
+	fn test_fetch_upstream_unattended() {
+		let (remote_dir, remote) = repo_init().unwrap();
+		let remote_path = remote_dir.path().to_str().unwrap();
+		let (repo_dir, repo) = repo_clone(remote_path).unwrap();
+		let repo_path = repo_dir.path().as_os_str().to_str().unwrap();
+
+		write_commit_file(&remote, "a.txt", "a", "c1");
+		write_commit_file(&remote, "b.txt", "b", "c2");
+
+		let abort = Arc::new(AtomicBool::new(false));
+
+		assert_eq!(
+			fetch_upstream_unattended(
+				repo_path,
+				"master",
+				Arc::clone(&abort)
+			)
+			.unwrap(),
+			Some(2)
+		);
+		assert_eq!(
+			fetch_upstream_unattended(
+				repo_path,
+				"master",
+				Arc::clone(&abort)
+			)
+			.unwrap(),
+			Some(0)
+		);
+
+		// no upstream to fetch from
+		let head = repo.head().unwrap().peel_to_commit().unwrap();
+		repo.branch("local", &head, false).unwrap();
+
+		assert_eq!(
+			fetch_upstream_unattended(repo_path, "local", abort)
+				.unwrap(),
+			None
+		);
+	}
+
+	#[test]
+	fn test_list_branches_timeout() {
+		let listener =
+			std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+		let port = listener.local_addr().unwrap().port();
+
+		let (repo_dir, _repo) = repo_init().unwrap();
+		let repo_path = repo_dir.path().as_os_str().to_str().unwrap();
+
+		// the listener accepts connections but never answers
+		debug_cmd_print(
+			repo_path,
+			&format!(
+				"git remote add hanging git://127.0.0.1:{}/repo",
+				port
+			),
+		);
+
+		let res = get_remote_branches_advertised(
+			repo_path,
+			"hanging",
+			None,
+			Duration::from_millis(500),
+		);
+
+		assert!(res.is_err());
+		drop(listener);
+	}
+
+	#[test]
+	fn test_fetch_timeout() {
+		let listener =
+			std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+		let port = listener.local_addr().unwrap().port();
+
+		let (repo_dir, _repo) = repo_init().unwrap();
+		let repo_path = repo_dir.path().as_os_str().to_str().unwrap();
+
+		// the listener accepts connections but never answers
+		debug_cmd_print(
+			repo_path,
+			&format!(
+				"git remote add hanging git://127.0.0.1:{}/repo",
+				port
+			),
+		);
+		debug_cmd_print(
+			repo_path,
+			&format!("git config {} 1", NETWORK_TIMEOUT_CONFIG),
+		);
+
+		let res = fetch(repo_path, "hanging", &[], None, None);
+		assert!(matches!(res, Err(Error::NetworkTimeout(1))));
+
+		let res = push::push(
+			repo_path, "hanging", "master", false, true, None, None,
+		);
+		assert!(matches!(res, Err(Error::NetworkTimeout(1))));
+
+		drop(listener);
+	}
+
+	#[test]
+	fn test_network_timeout_config() {
+		let (_repo_dir, repo) = repo_init().unwrap();
+		let mut config = repo.config().unwrap();
+
+		assert_eq!(
+			network_timeout(&repo),
+			Some(DEFAULT_NETWORK_TIMEOUT)
+		);
+
+		config.set_str(NETWORK_TIMEOUT_CONFIG, "5").unwrap();
+		assert_eq!(
+			network_timeout(&repo),
+			Some(Duration::from_secs(5))
+		);
+
+		config.set_str(NETWORK_TIMEOUT_CONFIG, "0").unwrap();
+		assert_eq!(network_timeout(&repo), None);
+
+		config.set_str(NETWORK_TIMEOUT_CONFIG, "soon").unwrap();
+		assert_eq!(
+			network_timeout(&repo),
+			Some(DEFAULT_NETWORK_TIMEOUT)
+		);
 	}
 
 	#[test]
@@ -0,0 +1,168 @@
+//!
+
+use super::{get_refs_advertised, utils};
+use crate::{
+	error::{Error, Result},
+	sync::cred::BasicAuthCredential,
+};
+use git2::{Direction, ReferenceType};
+use scopetime::scope_time;
+use std::{collections::HashSet, time::Duration};
+use utils::bytes2string;
+
+/// remote tracking refs of `remote` whose branch is gone on the
+/// remote, the refs `git fetch --prune` would delete.
+/// fails if the remote does not answer within `timeout`
+pub fn stale_remote_branches(
+	repo_path: &str,
+	remote: &str,
+	basic_credential: Option<BasicAuthCredential>,
+	timeout: Duration,
+) -> Result<Vec<String>> {
+	scope_time!("stale_remote_branches");
+
+	let advertised = get_refs_advertised(
+		repo_path,
+		remote,
+		basic_credential,
+		timeout,
+	)?;
+
+	let repo = utils::repo(repo_path)?;
+	let remote = repo.find_remote(remote)?;
+	let refspecs = remote
+		.refspecs()
+		.filter(|spec| matches!(spec.direction(), Direction::Fetch))
+		.collect::<Vec<_>>();
+
+	// where the advertised refs end up locally
+	let alive = advertised
+		.iter()
+		.flat_map(|(name, _)| {
+			refspecs
+				.iter()
+				.filter(move |spec| spec.src_matches(name))
+				.filter_map(move |spec| spec.transform(name).ok())
+		})
+		.filter_map(|local| local.as_str().map(String::from))
+		.collect::<HashSet<_>>();
+
+	let mut stale = Vec::new();
+	for reference in repo.references()? {
+		let reference = reference?;
+
+		// symbolic ones like `origin/HEAD` are left alone
+		if reference.kind() != Some(ReferenceType::Direct) {
+			continue;
+		}
+
+		let name = bytes2string(reference.name_bytes())?;
+		if refspecs.iter().any(|spec| spec.dst_matches(&name))
+			&& !alive.contains(&name)
+		{
+			stale.push(name);
+		}
+	}
+
+	stale.sort();
+
+	Ok(stale)
+}
+
+/// deletes the remote tracking `refs` (full names), refuses anything
+/// outside of `refs/remotes/`
+pub fn delete_remote_tracking_refs(
+	repo_path: &str,
+	refs: &[String],
+) -> Result<()> {
+	scope_time!("delete_remote_tracking_refs");
+
+	let repo = utils::repo(repo_path)?;
+
+	for name in refs {
+		if !name.starts_with("refs/remotes/") {
+			return Err(Error::Generic(format!(
+				"not a remote tracking ref: {}",
+				name
+			)));
+		}
+
+		repo.find_reference(name)?.delete()?;
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		remotes::fetch,
+		tests::{repo_clone, repo_init},
+	};
+	use git2::BranchType;
+
+	const TIMEOUT: Duration = Duration::from_secs(10);
+
+	#[test]
+	fn test_stale_remote_branches() {
+		let (remote_dir, remote) = repo_init().unwrap();
+		let remote_path = remote_dir.path().to_str().unwrap();
+		let (repo_dir, repo) = repo_clone(remote_path).unwrap();
+		let repo_path = repo_dir.path().as_os_str().to_str().unwrap();
+
+		let head = remote.head().unwrap().peel_to_commit().unwrap();
+		remote.branch("gone", &head, false).unwrap();
+		remote.branch("kept", &head, false).unwrap();
+
+		fetch(repo_path, "origin", &[], None, None).unwrap();
+
+		assert!(stale_remote_branches(
+			repo_path, "origin", None, TIMEOUT
+		)
+		.unwrap()
+		.is_empty());
+
+		remote
+			.find_branch("gone", BranchType::Local)
+			.unwrap()
+			.delete()
+			.unwrap();
+
+		assert_eq!(
+			stale_remote_branches(repo_path, "origin", None, TIMEOUT)
+				.unwrap(),
+			vec![String::from("refs/remotes/origin/gone")]
+		);
+
+		delete_remote_tracking_refs(
+			repo_path,
+			&[String::from("refs/remotes/origin/gone")],
+		)
+		.unwrap();
+
+		assert!(repo
+			.find_branch("origin/gone", BranchType::Remote)
+			.is_err());
+		assert!(repo
+			.find_branch("origin/kept", BranchType::Remote)
+			.is_ok());
+		assert!(stale_remote_branches(
+			repo_path, "origin", None, TIMEOUT
+		)
+		.unwrap()
+		.is_empty());
+	}
+
+	#[test]
+	fn test_delete_only_remote_tracking_refs() {
+		let (repo_dir, _repo) = repo_init().unwrap();
+		let repo_path = repo_dir.path().as_os_str().to_str().unwrap();
+
+		assert!(delete_remote_tracking_refs(
+			repo_path,
+			&[String::from("refs/heads/master")],
+		)
+		.is_err());
+	}
+}
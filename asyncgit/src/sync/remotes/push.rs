@@ -3,13 +3,24 @@ use crate::{
 	error::{Error, Result},
 	progress::ProgressPercent,
 	sync::{
-		branch::branch_set_upstream, cred::BasicAuthCredential,
-		remotes::Callbacks, CommitId,
+		branch::branch_set_upstream,
+		commits_info::get_commits_info,
+		config::get_config_string_repo,
+		cred::BasicAuthCredential,
+		remotes::{
+			get_default_remote_in_repo, get_refs_advertised,
+			get_upstream_branch_in_repo, network_timeout, Callbacks,
+			HttpSettings,
+		},
+		CommitId, CommitInfo,
 	},
 };
 use crossbeam_channel::Sender;
-use git2::{PackBuilderStage, PushOptions};
-use scopetime::scope_time;
+use git2::{
+	BranchType, Oid, PackBuilderStage, PushOptions, Repository,
+};
+use scopetime::{scope_stats, scope_time};
+use std::time::Duration;
 
 ///
 pub trait AsyncProgress: Clone + Send + Sync {
@@ -89,6 +100,323 @@ impl AsyncProgress for ProgressNotification {
 	}
 }
 
+/// where pushing a local branch goes
+#[derive(Debug, Clone, PartialEq)]
+pub struct PushTarget {
+	///
+	pub remote: String,
+	/// branch name on `remote`
+	pub branch: String,
+}
+
+/// resolves remote and remote branch for pushing `branch` like git
+/// does.
+///
+/// the remote is taken from `branch.<name>.pushRemote`,
+/// `remote.pushDefault` or `branch.<name>.remote` (in that order),
+/// the remote branch follows `push.default`
+pub fn push_target_for_branch(
+	repo_path: &str,
+	branch: &str,
+) -> Result<PushTarget> {
+	scope_time!("push_target_for_branch");
+
+	let repo = utils::repo(repo_path)?;
+
+	let config = |key: String| get_config_string_repo(&repo, &key);
+
+	let remote = if let Some(remote) =
+		config(format!("branch.{}.pushRemote", branch))?
+	{
+		remote
+	} else if let Some(remote) =
+		config(String::from("remote.pushDefault"))?
+	{
+		remote
+	} else if let Some(remote) =
+		config(format!("branch.{}.remote", branch))?
+	{
+		remote
+	} else {
+		get_default_remote_in_repo(&repo)?
+	};
+
+	let remote_branch = push_target_branch(&repo, &remote, branch)?;
+
+	Ok(PushTarget {
+		remote,
+		branch: remote_branch,
+	})
+}
+
+/// like `push_target_for_branch` but to the chosen `remote`
+pub fn push_target_on_remote(
+	repo_path: &str,
+	branch: &str,
+	remote: &str,
+) -> Result<PushTarget> {
+	let repo = utils::repo(repo_path)?;
+
+	Ok(PushTarget {
+		remote: remote.to_string(),
+		branch: push_target_branch(&repo, remote, branch)?,
+	})
+}
+
+/// name `branch` gets on `remote` according to `push.default`
+fn push_target_branch(
+	repo: &Repository,
+	remote: &str,
+	branch: &str,
+) -> Result<String> {
+	let push_default = get_config_string_repo(repo, "push.default")?
+		.unwrap_or_default();
+
+	let upstream_remote = get_config_string_repo(
+		repo,
+		&format!("branch.{}.remote", branch),
+	)?;
+	let upstream_branch = get_upstream_branch_in_repo(repo, branch)?;
+
+	// triangular workflows push somewhere else than they fetch from
+	let is_upstream_remote =
+		upstream_remote.map_or(false, |upstream| upstream == remote);
+
+	match push_default.as_str() {
+		"nothing" => Err(Error::Generic(String::from(
+			"push.default is 'nothing', refusing to push",
+		))),
+		"upstream" | "tracking" => match upstream_branch {
+			Some(upstream) if is_upstream_remote => Ok(upstream),
+			Some(_) => Err(Error::Generic(format!(
+				"push.default is 'upstream' but '{}' is not the upstream remote of '{}'",
+				remote, branch
+			))),
+			// first push, the upstream gets set afterwards
+			None => Ok(branch.to_string()),
+		},
+		// `current`, `matching` (gitui only ever pushes the one branch)
+		// and `simple` (the default). unlike git `simple` does not
+		// refuse a differently named upstream but pushes to the same
+		// name, like it always did after renaming a branch
+		_ => Ok(branch.to_string()),
+	}
+}
+
+/// at most this many of the commits to push are loaded for a preview
+pub const PUSH_PREVIEW_MAX_COMMITS: usize = 1000;
+
+/// what pushing a branch would do, found out without sending anything
+#[derive(Debug, Clone)]
+pub struct PushPreview {
+	/// where the remote branch is, `None` if it does not exist yet
+	pub remote_tip: Option<CommitId>,
+	/// the commits the remote branch does not contain yet, newest
+	/// first and at most `PUSH_PREVIEW_MAX_COMMITS`
+	pub commits: Vec<CommitInfo>,
+	/// number of all commits the remote branch does not contain yet
+	pub commit_count: usize,
+	/// the push needs no force
+	pub fast_forward: bool,
+	/// number of remote commits a force push throws away, `None` if
+	/// the remote tip was not fetched yet
+	pub discarded: Option<usize>,
+}
+
+/// previews pushing `branch` to `remote` based on the remote tracking
+/// refs, so the result is only as recent as the last fetch
+pub fn push_preview(
+	repo_path: &str,
+	remote: &str,
+	branch: &str,
+) -> Result<PushPreview> {
+	scope_stats!("push_preview");
+
+	let repo = utils::repo(repo_path)?;
+	let remote_branch = push_target_branch(&repo, remote, branch)?;
+
+	let remote_tip = repo
+		.find_reference(&format!(
+			"refs/remotes/{}/{}",
+			remote, remote_branch
+		))
+		.ok()
+		.and_then(|reference| reference.target());
+
+	let remote_tips = tracking_tips(&repo, remote)?;
+
+	preview(repo_path, &repo, branch, remote_tip, &remote_tips)
+}
+
+/// like `push_preview` but asks the remote where its branches are
+/// (like `git ls-remote`).
+/// fails if the remote does not answer within `timeout`
+pub fn push_preview_advertised(
+	repo_path: &str,
+	remote: &str,
+	branch: &str,
+	basic_credential: Option<BasicAuthCredential>,
+	timeout: Duration,
+) -> Result<PushPreview> {
+	scope_stats!("push_preview_advertised");
+
+	let advertised = get_refs_advertised(
+		repo_path,
+		remote,
+		basic_credential,
+		timeout,
+	)?;
+
+	let repo = utils::repo(repo_path)?;
+	let remote_branch = format!(
+		"refs/heads/{}",
+		push_target_branch(&repo, remote, branch)?
+	);
+
+	let remote_tip = advertised
+		.iter()
+		.find(|(name, _)| *name == remote_branch)
+		.map(|(_, id)| *id);
+	// the last fetched state is the best guess for what is not
+	// fetched yet
+	let mut remote_tips = tracking_tips(&repo, remote)?;
+	remote_tips.extend(advertised.into_iter().map(|(_, id)| id));
+
+	preview(repo_path, &repo, branch, remote_tip, &remote_tips)
+}
+
+/// targets of the remote tracking refs of `remote`
+fn tracking_tips(
+	repo: &Repository,
+	remote: &str,
+) -> Result<Vec<Oid>> {
+	let mut tips = Vec::new();
+	for reference in
+		repo.references_glob(&format!("refs/remotes/{}/*", remote))?
+	{
+		if let Some(id) = reference?.target() {
+			tips.push(id);
+		}
+	}
+
+	Ok(tips)
+}
+
+fn preview(
+	repo_path: &str,
+	repo: &Repository,
+	branch: &str,
+	remote_tip: Option<Oid>,
+	remote_tips: &[Oid],
+) -> Result<PushPreview> {
+	let local = repo
+		.find_branch(branch, BranchType::Local)?
+		.get()
+		.peel_to_commit()?
+		.id();
+
+	// tips of commits that were never fetched cannot be looked at
+	let fetched = |id: &Oid| repo.find_commit(*id).is_ok();
+
+	let mut walk = repo.revwalk()?;
+	walk.push(local)?;
+	if let Some(tip) = remote_tip.filter(fetched) {
+		walk.hide(tip)?;
+	} else {
+		// a new branch (or one whose tip is unknown) only brings
+		// along what no other remote branch has yet
+		for tip in remote_tips.iter().filter(|id| fetched(id)) {
+			walk.hide(*tip)?;
+		}
+	}
+
+	let ids = walk.collect::<std::result::Result<Vec<_>, _>>()?;
+
+	let (fast_forward, discarded) = match remote_tip {
+		None => (true, Some(0)),
+		Some(tip) if fetched(&tip) => {
+			let behind = repo.graph_ahead_behind(local, tip)?.1;
+			(behind == 0, Some(behind))
+		}
+		// the remote moved on to commits we do not have
+		Some(_) => (false, None),
+	};
+
+	let shown = ids
+		.iter()
+		.take(PUSH_PREVIEW_MAX_COMMITS)
+		.map(|id| CommitId::new(*id))
+		.collect::<Vec<_>>();
+
+	Ok(PushPreview {
+		remote_tip: remote_tip.map(CommitId::new),
+		commits: get_commits_info(repo_path, &shown, usize::MAX)?,
+		commit_count: ids.len(),
+		fast_forward,
+		discarded,
+	})
+}
+
+/// a ref update pushing would make, as the `pre-push` hook gets it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushUpdate {
+	/// `(delete)` when deleting the remote ref
+	pub local_ref: String,
+	/// `None` when deleting the remote ref
+	pub local_id: Option<CommitId>,
+	///
+	pub remote_ref: String,
+	/// `None` if the remote ref does not exist yet (as far as the
+	/// remote tracking refs know)
+	pub remote_id: Option<CommitId>,
+}
+
+/// the update pushing `branch` to `remote` (or deleting the remote
+/// branch `branch`) would make, found out without connecting to the
+/// remote like `push_preview`
+pub fn push_updates(
+	repo_path: &str,
+	remote: &str,
+	branch: &str,
+	delete: bool,
+) -> Result<Vec<PushUpdate>> {
+	scope_time!("push_updates");
+
+	let repo = utils::repo(repo_path)?;
+
+	let remote_branch = if delete {
+		branch.to_string()
+	} else {
+		push_target_branch(&repo, remote, branch)?
+	};
+
+	let remote_id = repo
+		.find_reference(&format!(
+			"refs/remotes/{}/{}",
+			remote, remote_branch
+		))
+		.ok()
+		.and_then(|reference| reference.target())
+		.map(CommitId::new);
+
+	let (local_ref, local_id) = if delete {
+		(String::from("(delete)"), None)
+	} else {
+		let local_ref = format!("refs/heads/{}", branch);
+		let local_id =
+			repo.find_reference(&local_ref)?.peel_to_commit()?.id();
+		(local_ref, Some(CommitId::new(local_id)))
+	};
+
+	Ok(vec![PushUpdate {
+		local_ref,
+		local_id,
+		remote_ref: format!("refs/heads/{}", remote_branch),
+		remote_id,
+	}])
+}
+
+/// fails once the transfer stalls beyond `network_timeout`
 #[allow(clippy::redundant_pub_crate)]
 pub(crate) fn push(
 	repo_path: &str,
@@ -99,15 +427,47 @@ pub(crate) fn push(
 	basic_credential: Option<BasicAuthCredential>,
 	progress_sender: Option<Sender<ProgressNotification>>,
 ) -> Result<()> {
-	scope_time!("push");
+	scope_stats!("push");
+
+	let repo = utils::repo(repo_path)?;
+	let callbacks = Callbacks::new(progress_sender, basic_credential)
+		.timeout(network_timeout(&repo));
 
+	let repo_path = repo_path.to_string();
+	let remote = remote.to_string();
+	let branch = branch.to_string();
+	callbacks.watch(move |callbacks| {
+		push_watched(
+			&repo_path, &remote, &branch, force, delete, callbacks,
+		)
+	})
+}
+
+fn push_watched(
+	repo_path: &str,
+	remote: &str,
+	branch: &str,
+	force: bool,
+	delete: bool,
+	callbacks: Callbacks,
+) -> Result<()> {
 	let repo = utils::repo(repo_path)?;
+
+	let remote_branch = if delete {
+		branch.to_string()
+	} else {
+		push_target_branch(&repo, remote, branch)?
+	};
+
+	let remote_name = remote;
 	let mut remote = repo.find_remote(remote)?;
+	let settings = HttpSettings::for_remote(&repo, &remote, true)?;
 
 	let mut options = PushOptions::new();
 
-	let callbacks = Callbacks::new(progress_sender, basic_credential);
+	let callbacks = callbacks.ssl_verify(settings.ssl_verify);
 	options.remote_callbacks(callbacks.callbacks());
+	options.proxy_options(settings.proxy_options());
 	options.packbuilder_parallelism(0);
 
 	let branch_modifier = match (force, delete) {
@@ -116,9 +476,19 @@ pub(crate) fn push(
 		(true, false) => "+",
 		(false, false) => "",
 	};
-	let branch_name =
-		format!("{}refs/heads/{}", branch_modifier, branch);
-	remote.push(&[branch_name.as_str()], Some(&mut options))?;
+	let refspec = if delete {
+		format!("{}refs/heads/{}", branch_modifier, branch)
+	} else {
+		format!(
+			"{}refs/heads/{}:refs/heads/{}",
+			branch_modifier, branch, remote_branch
+		)
+	};
+	remote
+		.push(&[refspec.as_str()], Some(&mut options))
+		.map_err(|e| {
+			callbacks.check_timeout(settings.map_error(e))
+		})?;
 
 	if let Some((reference, msg)) =
 		callbacks.get_stats()?.push_rejected_msg
@@ -130,7 +500,12 @@ pub(crate) fn push(
 	}
 
 	if !delete {
-		branch_set_upstream(&repo, branch)?;
+		branch_set_upstream(
+			&repo,
+			branch,
+			remote_name,
+			&remote_branch,
+		)?;
 	}
 
 	Ok(())
@@ -141,6 +516,7 @@ mod tests {
 	use super::*;
 	use crate::sync::{
 		self,
+		remotes::fetch,
 		tests::{
 			get_commit_ids, repo_clone, repo_init, repo_init_bare,
 			write_commit_file,
@@ -180,6 +556,7 @@ mod tests {
 		sync::commit(
 			tmp_repo_dir.path().to_str().unwrap(),
 			"repo_1_commit",
+			true,
 		)
 		.unwrap();
 
@@ -203,6 +580,7 @@ mod tests {
 		sync::commit(
 			tmp_other_repo_dir.path().to_str().unwrap(),
 			"repo_2_commit",
+			true,
 		)
 		.unwrap();
 
@@ -277,6 +655,7 @@ mod tests {
 		let repo_1_commit = sync::commit(
 			tmp_repo_dir.path().to_str().unwrap(),
 			"repo_1_commit",
+			true,
 		)
 		.unwrap();
 
@@ -285,7 +664,8 @@ mod tests {
 			sync::get_commit_files(
 				tmp_repo_dir.path().to_str().unwrap(),
 				repo_1_commit,
-				None
+				None,
+				sync::MergeDiffMode::default(),
 			)
 			.unwrap()[0]
 				.path,
@@ -321,6 +701,7 @@ mod tests {
 		let repo_2_commit = sync::commit(
 			tmp_other_repo_dir.path().to_str().unwrap(),
 			"repo_2_commit",
+			true,
 		)
 		.unwrap();
 
@@ -477,4 +858,282 @@ mod tests {
 			false
 		);
 	}
+
+	fn push_target_repo(
+		config: &[(&str, &str)],
+	) -> (tempfile::TempDir, Repository) {
+		let (td, repo) = repo_init().unwrap();
+		repo.remote("origin", "http://example.com/origin").unwrap();
+		repo.remote("fork", "http://example.com/fork").unwrap();
+
+		let mut cfg = repo.config().unwrap();
+		for (key, value) in config {
+			cfg.set_str(key, value).unwrap();
+		}
+
+		(td, repo)
+	}
+
+	fn target(remote: &str, branch: &str) -> PushTarget {
+		PushTarget {
+			remote: remote.to_string(),
+			branch: branch.to_string(),
+		}
+	}
+
+	fn resolve(td: &tempfile::TempDir) -> Result<PushTarget> {
+		push_target_for_branch(td.path().to_str().unwrap(), "master")
+	}
+
+	const UPSTREAM_RENAMED: [(&str, &str); 2] = [
+		("branch.master.remote", "origin"),
+		("branch.master.merge", "refs/heads/main"),
+	];
+
+	#[test]
+	fn test_push_target_default() {
+		let (td, _repo) = push_target_repo(&[]);
+		assert_eq!(resolve(&td).unwrap(), target("origin", "master"));
+	}
+
+	#[test]
+	fn test_push_target_simple() {
+		let (td, _repo) = push_target_repo(&[
+			("branch.master.remote", "fork"),
+			("branch.master.merge", "refs/heads/master"),
+		]);
+		assert_eq!(resolve(&td).unwrap(), target("fork", "master"));
+
+		// never pushes to a differently named upstream
+		let (td, _repo) = push_target_repo(&UPSTREAM_RENAMED);
+		assert_eq!(resolve(&td).unwrap(), target("origin", "master"));
+	}
+
+	#[test]
+	fn test_push_target_current() {
+		let (td, repo) = push_target_repo(&UPSTREAM_RENAMED);
+		repo.config()
+			.unwrap()
+			.set_str("push.default", "current")
+			.unwrap();
+		assert_eq!(resolve(&td).unwrap(), target("origin", "master"));
+	}
+
+	#[test]
+	fn test_push_target_upstream() {
+		let (td, repo) = push_target_repo(&UPSTREAM_RENAMED);
+		repo.config()
+			.unwrap()
+			.set_str("push.default", "upstream")
+			.unwrap();
+		assert_eq!(resolve(&td).unwrap(), target("origin", "main"));
+
+		// no upstream yet
+		let (td, _repo) =
+			push_target_repo(&[("push.default", "upstream")]);
+		assert_eq!(resolve(&td).unwrap(), target("origin", "master"));
+	}
+
+	#[test]
+	fn test_push_target_matching() {
+		let (td, _repo) =
+			push_target_repo(&[("push.default", "matching")]);
+		assert_eq!(resolve(&td).unwrap(), target("origin", "master"));
+	}
+
+	#[test]
+	fn test_push_target_nothing() {
+		let (td, _repo) =
+			push_target_repo(&[("push.default", "nothing")]);
+		assert!(resolve(&td).is_err());
+	}
+
+	#[test]
+	fn test_push_target_push_remote() {
+		let (td, repo) = push_target_repo(&UPSTREAM_RENAMED);
+
+		repo.config()
+			.unwrap()
+			.set_str("remote.pushDefault", "fork")
+			.unwrap();
+		// triangular: `simple` pushes to the same name
+		assert_eq!(resolve(&td).unwrap(), target("fork", "master"));
+
+		repo.config()
+			.unwrap()
+			.set_str("branch.master.pushRemote", "origin")
+			.unwrap();
+		// `pushRemote` wins over `remote.pushDefault`
+		assert_eq!(resolve(&td).unwrap(), target("origin", "master"));
+
+		repo.config()
+			.unwrap()
+			.set_str("push.default", "upstream")
+			.unwrap();
+		assert_eq!(resolve(&td).unwrap(), target("origin", "main"));
+
+		repo.config()
+			.unwrap()
+			.set_str("branch.master.pushRemote", "fork")
+			.unwrap();
+		// `upstream` refuses triangular workflows
+		assert!(resolve(&td).is_err());
+	}
+
+	#[test]
+	fn test_push_target_on_remote() {
+		let (td, repo) = push_target_repo(&UPSTREAM_RENAMED);
+		let path = td.path().to_str().unwrap();
+
+		assert_eq!(
+			push_target_on_remote(path, "master", "fork").unwrap(),
+			target("fork", "master")
+		);
+
+		repo.config()
+			.unwrap()
+			.set_str("push.default", "upstream")
+			.unwrap();
+		assert_eq!(
+			push_target_on_remote(path, "master", "origin").unwrap(),
+			target("origin", "main")
+		);
+		assert!(
+			push_target_on_remote(path, "master", "fork").is_err()
+		);
+	}
+
+	#[test]
+	fn test_push_to_renamed_upstream() {
+		let (upstream_dir, upstream) = repo_init_bare().unwrap();
+		let (repo_dir, repo) = repo_init().unwrap();
+		let repo_path = repo_dir.path().to_str().unwrap();
+
+		repo.remote("origin", upstream_dir.path().to_str().unwrap())
+			.unwrap();
+		let mut cfg = repo.config().unwrap();
+		cfg.set_str("push.default", "upstream").unwrap();
+		cfg.set_str("branch.master.remote", "origin").unwrap();
+		cfg.set_str("branch.master.merge", "refs/heads/main")
+			.unwrap();
+
+		push(repo_path, "origin", "master", false, false, None, None)
+			.unwrap();
+
+		assert!(upstream.find_reference("refs/heads/main").is_ok());
+		assert!(upstream
+			.find_reference("refs/heads/master")
+			.is_err());
+	}
+
+	#[test]
+	fn test_push_preview() {
+		let (upstream_dir, _upstream) = repo_init_bare().unwrap();
+		let upstream_path = upstream_dir.path().to_str().unwrap();
+		let (repo_dir, repo) = repo_clone(upstream_path).unwrap();
+		let repo_path = repo_dir.path().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", "a", "c1");
+		let preview =
+			push_preview(repo_path, "origin", "master").unwrap();
+		assert_eq!(preview.remote_tip, None);
+		assert_eq!(preview.commit_count, 1);
+		assert!(preview.fast_forward);
+
+		push(repo_path, "origin", "master", false, false, None, None)
+			.unwrap();
+
+		let (other_dir, other) = repo_clone(upstream_path).unwrap();
+		let other_path = other_dir.path().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", "b", "c2");
+		write_commit_file(&repo, "a.txt", "c", "c3");
+
+		let preview =
+			push_preview(repo_path, "origin", "master").unwrap();
+		assert_eq!(
+			preview
+				.commits
+				.iter()
+				.map(|c| c.message.as_str())
+				.collect::<Vec<_>>(),
+			vec!["c3", "c2"]
+		);
+		assert!(preview.fast_forward);
+		assert_eq!(preview.discarded, Some(0));
+
+		// someone else pushes first
+		write_commit_file(&other, "b.txt", "b", "other");
+		push(
+			other_path, "origin", "master", false, false, None, None,
+		)
+		.unwrap();
+
+		// not fetched yet: only the remote knows
+		assert!(
+			push_preview(repo_path, "origin", "master")
+				.unwrap()
+				.fast_forward
+		);
+		let preview = push_preview_advertised(
+			repo_path,
+			"origin",
+			"master",
+			None,
+			Duration::from_secs(10),
+		)
+		.unwrap();
+		assert!(!preview.fast_forward);
+		assert_eq!(preview.discarded, None);
+		assert_eq!(preview.commit_count, 2);
+
+		fetch(repo_path, "origin", &[], None, None).unwrap();
+		let preview =
+			push_preview(repo_path, "origin", "master").unwrap();
+		assert!(!preview.fast_forward);
+		assert_eq!(preview.discarded, Some(1));
+		assert_eq!(preview.commit_count, 2);
+	}
+
+	#[test]
+	fn test_push_updates() {
+		let (upstream_dir, _upstream) = repo_init_bare().unwrap();
+		let upstream_path = upstream_dir.path().to_str().unwrap();
+		let (repo_dir, repo) = repo_clone(upstream_path).unwrap();
+		let repo_path = repo_dir.path().to_str().unwrap();
+
+		let c1 = write_commit_file(&repo, "a.txt", "a", "c1");
+
+		assert_eq!(
+			push_updates(repo_path, "origin", "master", false)
+				.unwrap(),
+			vec![PushUpdate {
+				local_ref: String::from("refs/heads/master"),
+				local_id: Some(c1),
+				remote_ref: String::from("refs/heads/master"),
+				remote_id: None,
+			}]
+		);
+
+		push(repo_path, "origin", "master", false, false, None, None)
+			.unwrap();
+		let c2 = write_commit_file(&repo, "a.txt", "b", "c2");
+
+		let updates =
+			push_updates(repo_path, "origin", "master", false)
+				.unwrap();
+		assert_eq!(updates[0].local_id, Some(c2));
+		assert_eq!(updates[0].remote_id, Some(c1));
+
+		assert_eq!(
+			push_updates(repo_path, "origin", "master", true)
+				.unwrap(),
+			vec![PushUpdate {
+				local_ref: String::from("(delete)"),
+				local_id: None,
+				remote_ref: String::from("refs/heads/master"),
+				remote_id: Some(c1),
+			}]
+		);
+	}
 }
@@ -4,7 +4,10 @@ use super::{push::AsyncProgress, utils};
 use crate::{
 	error::Result,
 	progress::ProgressPercent,
-	sync::{cred::BasicAuthCredential, remotes::Callbacks},
+	sync::{
+		cred::BasicAuthCredential,
+		remotes::{network_timeout, Callbacks, HttpSettings},
+	},
 };
 use crossbeam_channel::Sender;
 use git2::{Direction, PushOptions};
@@ -52,12 +55,16 @@ fn remote_tag_refs(
 
 	let repo = utils::repo(repo_path)?;
 	let mut remote = repo.find_remote(remote)?;
-	let callbacks = Callbacks::new(None, basic_credential);
-	let conn = remote.connect_auth(
-		Direction::Fetch,
-		Some(callbacks.callbacks()),
-		None,
-	)?;
+	let settings = HttpSettings::for_remote(&repo, &remote, false)?;
+	let callbacks = Callbacks::new(None, basic_credential)
+		.ssl_verify(settings.ssl_verify);
+	let conn = remote
+		.connect_auth(
+			Direction::Fetch,
+			Some(callbacks.callbacks()),
+			Some(settings.proxy_options()),
+		)
+		.map_err(|e| settings.map_error(e))?;
 
 	let remote_heads = conn.list()?;
 	let remote_tags = remote_heads
@@ -117,7 +124,9 @@ pub fn push_tags(
 
 	let repo = utils::repo(repo_path)?;
 	let mut remote = repo.find_remote(remote)?;
+	let settings = HttpSettings::for_remote(&repo, &remote, true)?;
 
+	let timeout = network_timeout(&repo);
 	let total = tags_missing.len();
 
 	progress_sender.as_ref().map(|sender| {
@@ -127,10 +136,15 @@ pub fn push_tags(
 	for (idx, tag) in tags_missing.into_iter().enumerate() {
 		let mut options = PushOptions::new();
 		let callbacks =
-			Callbacks::new(None, basic_credential.clone());
+			Callbacks::new(None, basic_credential.clone())
+				.timeout(timeout)
+				.ssl_verify(settings.ssl_verify);
 		options.remote_callbacks(callbacks.callbacks());
+		options.proxy_options(settings.proxy_options());
 		options.packbuilder_parallelism(0);
-		remote.push(&[tag.as_str()], Some(&mut options))?;
+		remote.push(&[tag.as_str()], Some(&mut options)).map_err(
+			|e| callbacks.check_timeout(settings.map_error(e)),
+		)?;
 
 		progress_sender.as_ref().map(|sender| {
 			sender.send(PushTagsProgress::Push {
@@ -152,7 +166,7 @@ mod tests {
 	use super::*;
 	use crate::sync::{
 		self,
-		remotes::{fetch, push::push},
+		remotes::{fetch_branch, push::push},
 		tests::{repo_clone, repo_init_bare},
 	};
 	use sync::tests::write_commit_file;
@@ -195,7 +209,8 @@ mod tests {
 		assert_eq!(sync::get_tags(clone2_dir).unwrap().len(), 0);
 
 		//lets fetch from origin
-		let bytes = fetch(clone2_dir, "master", None, None).unwrap();
+		let bytes =
+			fetch_branch(clone2_dir, "master", None, None).unwrap();
 		assert!(bytes > 0);
 
 		sync::merge_upstream_commit(clone2_dir, "master").unwrap();
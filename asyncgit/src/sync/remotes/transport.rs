@@ -0,0 +1,568 @@
+//! proxy and certificate settings git applies to http(s) remotes
+
+use crate::error::{Error, Result};
+use git2::{ErrorClass, ErrorCode, ProxyOptions, Remote, Repository};
+use std::{
+	env,
+	ffi::CString,
+	os::raw::c_int,
+	path::PathBuf,
+	ptr,
+	sync::atomic::{AtomicBool, Ordering},
+};
+use url::Url;
+
+/// CA locations of a remote were handed to libgit2 since the defaults
+static CUSTOM_CA_APPLIED: AtomicBool = AtomicBool::new(false);
+
+/// proxy and certificate settings for one remote url, resolved like
+/// git does:
+///
+/// * the proxy is taken from `remote.<name>.proxy`, `http.<url>.proxy`
+///   or `http.proxy` (in that order, an empty value disables it) and
+///   only without any of those from `https_proxy`/`http_proxy`/
+///   `all_proxy` (unless the host is listed in `no_proxy`)
+/// * `GIT_SSL_CAINFO`, `GIT_SSL_CAPATH` and `GIT_SSL_NO_VERIFY`
+///   override `http.<url>.sslCAInfo`, `http.<url>.sslCAPath` and
+///   `http.<url>.sslVerify`, which override their `http.*` versions
+///
+/// the most specific matching `http.<url>` wins, see `url_specificity`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpSettings {
+	/// of the remote url, named in certificate errors
+	pub host: Option<String>,
+	/// `None` connects directly
+	pub proxy: Option<String>,
+	/// CA bundle file
+	pub ssl_ca_info: Option<String>,
+	/// dir of CA certificates
+	pub ssl_ca_path: Option<String>,
+	///
+	pub ssl_verify: bool,
+}
+
+impl Default for HttpSettings {
+	fn default() -> Self {
+		Self {
+			host: None,
+			proxy: None,
+			ssl_ca_info: None,
+			ssl_ca_path: None,
+			ssl_verify: true,
+		}
+	}
+}
+
+impl HttpSettings {
+	/// settings for the (push) url of `remote`, also points libgit2
+	/// at the configured CA certificates (a process wide setting)
+	pub fn for_remote(
+		repo: &Repository,
+		remote: &Remote,
+		push: bool,
+	) -> Result<Self> {
+		let url = if push {
+			remote.pushurl().or_else(|| remote.url())
+		} else {
+			remote.url()
+		};
+
+		let url = match url.and_then(|url| Url::parse(url).ok()) {
+			Some(url) => url,
+			// ssh (`user@host:path`) and local remotes
+			None => return Ok(Self::default()),
+		};
+
+		let config = config_entries(repo)?;
+		let settings =
+			resolve(&url, remote.name(), &config, &|name| {
+				env::var(name).ok()
+			});
+
+		settings.apply_ca_locations();
+
+		Ok(settings)
+	}
+
+	///
+	pub fn proxy_options(&self) -> ProxyOptions<'_> {
+		let mut options = ProxyOptions::new();
+		if let Some(proxy) = &self.proxy {
+			options.url(proxy);
+		}
+		options
+	}
+
+	/// tells certificate problems apart from other transport errors
+	pub fn map_error(&self, error: git2::Error) -> Error {
+		let is_certificate = error.code() == ErrorCode::Certificate
+			|| error.class() == ErrorClass::Ssl;
+
+		if is_certificate {
+			Error::Certificate(
+				self.host.clone().unwrap_or_default(),
+				error.message().to_string(),
+			)
+		} else {
+			Error::Git(error)
+		}
+	}
+
+	/// the locations are process wide, without any configured the
+	/// defaults are restored so the ones of another remote do not
+	/// stick
+	fn apply_ca_locations(&self) {
+		let (file, path) = if self.ssl_ca_info.is_none()
+			&& self.ssl_ca_path.is_none()
+		{
+			if !CUSTOM_CA_APPLIED.swap(false, Ordering::Relaxed) {
+				return;
+			}
+
+			let defaults = openssl_probe::probe();
+			let to_string = |path: Option<PathBuf>| {
+				path.and_then(|path| path.to_str().map(String::from))
+			};
+			(
+				to_string(defaults.cert_file),
+				to_string(defaults.cert_dir),
+			)
+		} else {
+			CUSTOM_CA_APPLIED.store(true, Ordering::Relaxed);
+			(self.ssl_ca_info.clone(), self.ssl_ca_path.clone())
+		};
+
+		if file.is_none() && path.is_none() {
+			return;
+		}
+
+		let to_c = |s: &Option<String>| {
+			s.as_ref().and_then(|s| CString::new(s.as_str()).ok())
+		};
+		let file = to_c(&file);
+		let path = to_c(&path);
+
+		// the options need an initialized libgit2
+		libgit2_sys::init();
+
+		#[allow(unsafe_code, clippy::cast_possible_wrap)]
+		let res = unsafe {
+			libgit2_sys::git_libgit2_opts(
+				libgit2_sys::GIT_OPT_SET_SSL_CERT_LOCATIONS as c_int,
+				file.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+				path.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+			)
+		};
+
+		if res < 0 {
+			log::error!(
+				"failed to set ssl cert locations: {:?}",
+				git2::Error::last_error(res)
+			);
+		}
+	}
+}
+
+fn config_entries(
+	repo: &Repository,
+) -> Result<Vec<(String, String)>> {
+	let config = repo.config()?;
+	let entries = config.entries(None)?;
+
+	let mut res = Vec::new();
+	for entry in &entries {
+		let entry = entry?;
+		if let (Some(name), Some(value)) =
+			(entry.name(), entry.value())
+		{
+			res.push((name.to_string(), value.to_string()));
+		}
+	}
+
+	Ok(res)
+}
+
+/// `None` if `config_url` does not apply to `url`, otherwise how
+/// specific it is: scheme, host, port and user have to match and
+/// the longest matching path (on `/` boundaries) wins
+fn url_specificity(config_url: &str, url: &Url) -> Option<usize> {
+	let config_url = Url::parse(config_url).ok()?;
+
+	let host_matches = match (config_url.host_str(), url.host_str()) {
+		(Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+		_ => false,
+	};
+
+	if config_url.scheme() != url.scheme()
+		|| !host_matches
+		|| config_url.port_or_known_default()
+			!= url.port_or_known_default()
+		|| (!config_url.username().is_empty()
+			&& config_url.username() != url.username())
+	{
+		return None;
+	}
+
+	let prefix = config_url.path().trim_end_matches('/');
+	let rest = url.path().strip_prefix(prefix)?;
+
+	if rest.is_empty() || rest.starts_with('/') {
+		Some(prefix.len() + 1)
+	} else {
+		None
+	}
+}
+
+/// value of `http.<key>` or the most specific `http.<url>.<key>`,
+/// later entries win on equal specificity
+fn http_config<'a>(
+	config: &'a [(String, String)],
+	url: &Url,
+	key: &str,
+) -> Option<&'a str> {
+	let mut best: Option<(usize, &str)> = None;
+
+	for (name, value) in config {
+		let rest = match name.strip_prefix("http.") {
+			Some(rest) => rest,
+			None => continue,
+		};
+
+		// section and key are case-insensitive, the url is not
+		let specificity = match rest.rfind('.') {
+			Some(idx)
+				if rest[idx + 1..].eq_ignore_ascii_case(key) =>
+			{
+				url_specificity(&rest[..idx], url)
+			}
+			None if rest.eq_ignore_ascii_case(key) => Some(0),
+			_ => None,
+		};
+
+		if let Some(specificity) = specificity {
+			if best.map_or(true, |(best, _)| specificity >= best) {
+				best = Some((specificity, value.as_str()));
+			}
+		}
+	}
+
+	best.map(|(_, value)| value)
+}
+
+fn remote_config<'a>(
+	config: &'a [(String, String)],
+	remote: &str,
+	key: &str,
+) -> Option<&'a str> {
+	let name = format!("remote.{}.{}", remote, key);
+
+	config
+		.iter()
+		.rev()
+		.find(|(n, _)| n.eq_ignore_ascii_case(&name))
+		.map(|(_, value)| value.as_str())
+}
+
+/// git's boolean config values, `None` if not one
+fn parse_bool(value: &str) -> Option<bool> {
+	match value.to_ascii_lowercase().as_str() {
+		"true" | "yes" | "on" | "1" | "" => Some(true),
+		"false" | "no" | "off" | "0" => Some(false),
+		_ => None,
+	}
+}
+
+fn is_no_proxy(host: &str, no_proxy: &str) -> bool {
+	no_proxy.split(',').map(str::trim).any(|entry| {
+		let entry = entry.trim_start_matches('.');
+		entry == "*"
+			|| (!entry.is_empty()
+				&& (host.eq_ignore_ascii_case(entry)
+					|| host.to_ascii_lowercase().ends_with(
+						&format!(".{}", entry.to_ascii_lowercase()),
+					)))
+	})
+}
+
+fn env_proxy(
+	url: &Url,
+	env: &dyn Fn(&str) -> Option<String>,
+) -> Option<String> {
+	let var = |names: &[&str]| {
+		names
+			.iter()
+			.find_map(|name| env(name).filter(|v| !v.is_empty()))
+	};
+
+	let no_proxy = var(&["no_proxy", "NO_PROXY"]).unwrap_or_default();
+	if url
+		.host_str()
+		.map_or(false, |host| is_no_proxy(host, &no_proxy))
+	{
+		return None;
+	}
+
+	// like curl the uppercase `HTTP_PROXY` is ignored
+	let scheme_proxy = if url.scheme() == "https" {
+		var(&["https_proxy", "HTTPS_PROXY"])
+	} else {
+		var(&["http_proxy"])
+	};
+
+	scheme_proxy.or_else(|| var(&["all_proxy", "ALL_PROXY"]))
+}
+
+fn resolve(
+	url: &Url,
+	remote: Option<&str>,
+	config: &[(String, String)],
+	env: &dyn Fn(&str) -> Option<String>,
+) -> HttpSettings {
+	let configured_proxy = remote
+		.and_then(|remote| remote_config(config, remote, "proxy"))
+		.or_else(|| http_config(config, url, "proxy"));
+
+	let proxy = match configured_proxy {
+		Some("") => None,
+		Some(proxy) => Some(proxy.to_string()),
+		None => env_proxy(url, env),
+	};
+
+	let ssl_ca_info = env("GIT_SSL_CAINFO").or_else(|| {
+		http_config(config, url, "sslCAInfo").map(String::from)
+	});
+	let ssl_ca_path = env("GIT_SSL_CAPATH").or_else(|| {
+		http_config(config, url, "sslCAPath").map(String::from)
+	});
+
+	let ssl_verify = if env("GIT_SSL_NO_VERIFY").is_some() {
+		false
+	} else {
+		http_config(config, url, "sslVerify")
+			.and_then(parse_bool)
+			.unwrap_or(true)
+	};
+
+	HttpSettings {
+		host: url.host_str().map(String::from),
+		proxy,
+		ssl_ca_info,
+		ssl_ca_path,
+		ssl_verify,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use std::collections::HashMap;
+
+	fn config(entries: &[(&str, &str)]) -> Vec<(String, String)> {
+		entries
+			.iter()
+			.map(|(name, value)| {
+				(String::from(*name), String::from(*value))
+			})
+			.collect()
+	}
+
+	fn settings(
+		url: &str,
+		config: &[(String, String)],
+		env: &[(&str, &str)],
+	) -> HttpSettings {
+		let env = env
+			.iter()
+			.map(|(k, v)| (String::from(*k), String::from(*v)))
+			.collect::<HashMap<_, _>>();
+
+		resolve(
+			&Url::parse(url).unwrap(),
+			Some("origin"),
+			config,
+			&|name| env.get(name).cloned(),
+		)
+	}
+
+	const URL: &str = "https://git.example.com/team/repo.git";
+
+	#[test]
+	fn test_defaults() {
+		assert_eq!(
+			settings(URL, &[], &[]),
+			HttpSettings {
+				host: Some(String::from("git.example.com")),
+				..HttpSettings::default()
+			}
+		);
+	}
+
+	#[test]
+	fn test_proxy_env() {
+		let s = settings(
+			URL,
+			&[],
+			&[
+				("http_proxy", "http://plain:8080"),
+				("HTTPS_PROXY", "http://secure:8080"),
+			],
+		);
+		assert_eq!(s.proxy.as_deref(), Some("http://secure:8080"));
+
+		let s = settings(
+			"http://git.example.com/repo.git",
+			&[],
+			&[
+				("HTTP_PROXY", "http://upper:8080"),
+				("ALL_PROXY", "http://all:8080"),
+			],
+		);
+		assert_eq!(s.proxy.as_deref(), Some("http://all:8080"));
+
+		let s = settings(
+			URL,
+			&[],
+			&[
+				("https_proxy", "http://secure:8080"),
+				("no_proxy", "localhost, .example.com"),
+			],
+		);
+		assert_eq!(s.proxy, None);
+	}
+
+	#[test]
+	fn test_proxy_config_over_env() {
+		let env = [("https_proxy", "http://env:8080")];
+
+		let s = settings(
+			URL,
+			&config(&[("http.proxy", "http://config:8080")]),
+			&env,
+		);
+		assert_eq!(s.proxy.as_deref(), Some("http://config:8080"));
+
+		let s = settings(
+			URL,
+			&config(&[
+				(
+					"http.https://git.example.com.proxy",
+					"http://url:8080",
+				),
+				("http.proxy", "http://config:8080"),
+			]),
+			&env,
+		);
+		assert_eq!(s.proxy.as_deref(), Some("http://url:8080"));
+
+		let s = settings(
+			URL,
+			&config(&[
+				("remote.origin.proxy", "http://remote:8080"),
+				(
+					"http.https://git.example.com.proxy",
+					"http://url:8080",
+				),
+			]),
+			&env,
+		);
+		assert_eq!(s.proxy.as_deref(), Some("http://remote:8080"));
+
+		// an empty value disables the proxy, the env included
+		let s = settings(URL, &config(&[("http.proxy", "")]), &env);
+		assert_eq!(s.proxy, None);
+	}
+
+	#[test]
+	fn test_url_specific() {
+		let config = config(&[
+			("http.sslverify", "true"),
+			("http.https://git.example.com/team.sslVerify", "false"),
+			("http.https://git.example.com.sslverify", "true"),
+			("http.https://other.example.com.sslverify", "false"),
+			("http.https://git.example.com/te.sslverify", "false"),
+		]);
+
+		// the path match wins over the later host-only match
+		assert!(!settings(URL, &config, &[]).ssl_verify);
+		assert!(
+			settings("https://git.example.com/teams/x", &config, &[])
+				.ssl_verify
+		);
+		// different port
+		assert!(
+			settings(
+				"https://git.example.com:8443/team/x",
+				&config,
+				&[]
+			)
+			.ssl_verify
+		);
+		assert!(
+			!settings("https://other.example.com/x", &config, &[])
+				.ssl_verify
+		);
+	}
+
+	#[test]
+	fn test_ssl_env_over_config() {
+		let config = config(&[
+			("http.sslcainfo", "/etc/config.pem"),
+			("http.https://git.example.com.sslcapath", "/etc/certs"),
+		]);
+
+		let s = settings(URL, &config, &[]);
+		assert_eq!(s.ssl_ca_info.as_deref(), Some("/etc/config.pem"));
+		assert_eq!(s.ssl_ca_path.as_deref(), Some("/etc/certs"));
+		assert!(s.ssl_verify);
+
+		let s = settings(
+			URL,
+			&config,
+			&[
+				("GIT_SSL_CAINFO", "/env.pem"),
+				("GIT_SSL_NO_VERIFY", "1"),
+			],
+		);
+		assert_eq!(s.ssl_ca_info.as_deref(), Some("/env.pem"));
+		assert_eq!(s.ssl_ca_path.as_deref(), Some("/etc/certs"));
+		assert!(!s.ssl_verify);
+	}
+
+	#[test]
+	fn test_certificate_error() {
+		let s = settings(URL, &[], &[]);
+
+		let err = s.map_error(git2::Error::new(
+			ErrorCode::Certificate,
+			ErrorClass::Ssl,
+			"self signed certificate in certificate chain",
+		));
+		assert_eq!(
+			err.to_string(),
+			"git: certificate of `git.example.com` rejected: self signed certificate in certificate chain"
+		);
+
+		let err = s.map_error(git2::Error::new(
+			ErrorCode::GenericError,
+			ErrorClass::Net,
+			"failed to connect",
+		));
+		assert!(matches!(err, Error::Git(_)));
+	}
+
+	#[test]
+	fn test_ca_locations_restored() {
+		let custom = HttpSettings {
+			ssl_ca_path: Some(String::from("/etc/ssl/certs")),
+			..HttpSettings::default()
+		};
+
+		custom.apply_ca_locations();
+		assert!(CUSTOM_CA_APPLIED.load(Ordering::Relaxed));
+
+		// a remote without any goes back to the defaults
+		HttpSettings::default().apply_ca_locations();
+		assert!(!CUSTOM_CA_APPLIED.load(Ordering::Relaxed));
+	}
+}
@@ -1,4 +1,10 @@
-use super::utils::{get_head_repo, repo};
+use super::{
+	index_flags::{index_flagged_files, path_contains},
+	index_lock::retry_on_index_lock,
+	status::{get_status, StatusType},
+	utils::{get_head_repo, repo},
+	ShowUntrackedFilesConfig,
+};
 use crate::error::Result;
 use git2::{build::CheckoutBuilder, ObjectType};
 use scopetime::scope_time;
@@ -9,30 +15,80 @@ pub fn reset_stage(repo_path: &str, path: &str) -> Result<()> {
 
 	let repo = repo(repo_path)?;
 
-	if let Ok(id) = get_head_repo(&repo) {
-		let obj =
-			repo.find_object(id.into(), Some(ObjectType::Commit))?;
-
-		repo.reset_default(Some(&obj), &[path])?;
+	let obj = if let Ok(id) = get_head_repo(&repo) {
+		Some(repo.find_object(id.into(), Some(ObjectType::Commit))?)
 	} else {
-		repo.reset_default(None, &[path])?;
-	}
+		None
+	};
 
-	Ok(())
+	retry_on_index_lock(repo_path, || {
+		repo.reset_default(obj.as_ref(), &[path])?;
+		Ok(())
+	})
 }
 
-///
+/// unstage a file staged as renamed from `old_path`, restoring
+/// both the deleted and the added side in one go
+pub fn reset_stage_renamed(
+	repo_path: &str,
+	old_path: &str,
+	path: &str,
+) -> Result<()> {
+	scope_time!("reset_stage_renamed");
+
+	let repo = repo(repo_path)?;
+
+	let obj = repo.find_object(
+		get_head_repo(&repo)?.into(),
+		Some(ObjectType::Commit),
+	)?;
+
+	retry_on_index_lock(repo_path, || {
+		repo.reset_default(Some(&obj), &[old_path, path])?;
+		Ok(())
+	})
+}
+
+/// discards the working dir changes in `path`, files flagged
+/// `assume-unchanged` or `skip-worktree` are left as they are (like
+/// `git checkout -- <path>` does)
 pub fn reset_workdir(repo_path: &str, path: &str) -> Result<()> {
 	scope_time!("reset_workdir");
 
+	let any_flagged = index_flagged_files(repo_path)?
+		.iter()
+		.any(|file| file.is_in(path));
+
 	let repo = repo(repo_path)?;
 
 	let mut checkout_opts = CheckoutBuilder::new();
 	checkout_opts
 		.update_index(true) // windows: needs this to be true WTF?!
 		.remove_untracked(true)
-		.force()
-		.path(path);
+		.force();
+
+	if any_flagged {
+		// pathspecs cannot exclude files, so the changed ones (the
+		// status leaves out flagged files) are named one by one
+		let changed: Vec<_> = get_status(
+			repo_path,
+			StatusType::WorkingDir,
+			Some(ShowUntrackedFilesConfig::All),
+		)?
+		.into_iter()
+		.filter(|item| path_contains(path, &item.path))
+		.collect();
+
+		if changed.is_empty() {
+			return Ok(());
+		}
+
+		for item in changed {
+			checkout_opts.path(item.path);
+		}
+	} else {
+		checkout_opts.path(path);
+	}
 
 	repo.checkout_index(None, Some(&mut checkout_opts))?;
 	Ok(())
@@ -40,15 +96,17 @@ pub fn reset_workdir(repo_path: &str, path: &str) -> Result<()> {
 
 #[cfg(test)]
 mod tests {
-	use super::{reset_stage, reset_workdir};
+	use super::{reset_stage, reset_stage_renamed, reset_workdir};
 	use crate::error::Result;
 	use crate::sync::{
 		commit,
-		status::{get_status, StatusType},
+		diff::get_diff_renamed,
+		status::{get_status, StatusItemType, StatusType},
 		tests::{
-			debug_cmd_print, get_statuses, repo_init, repo_init_empty,
+			debug_cmd_print, get_statuses, repo_init,
+			repo_init_empty, write_commit_file,
 		},
-		utils::{stage_add_all, stage_add_file},
+		utils::{stage_add_all, stage_add_file, stage_addremoved},
 	};
 	use std::{
 		fs::{self, File},
@@ -151,6 +209,32 @@ mod tests {
 		assert_eq!(get_statuses(repo_path), (0, 0));
 	}
 
+	#[test]
+	fn test_reset_untracked_unborn_head() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		File::create(&root.join("foo.txt"))
+			.unwrap()
+			.write_all(b"test\nfoo")
+			.unwrap();
+		File::create(&root.join("bar.txt"))
+			.unwrap()
+			.write_all(b"test")
+			.unwrap();
+
+		stage_add_file(repo_path, Path::new("bar.txt")).unwrap();
+
+		assert_eq!(get_statuses(repo_path), (1, 1));
+
+		reset_workdir(repo_path, "foo.txt").unwrap();
+		reset_stage(repo_path, "bar.txt").unwrap();
+
+		assert_eq!(get_statuses(repo_path), (1, 0));
+		assert!(!root.join("foo.txt").exists());
+	}
+
 	#[test]
 	fn test_reset_folder() -> Result<()> {
 		let (_td, repo) = repo_init().unwrap();
@@ -168,7 +252,7 @@ mod tests {
 		}
 
 		stage_add_all(repo_path, "*").unwrap();
-		commit(repo_path, "msg").unwrap();
+		commit(repo_path, "msg", false).unwrap();
 
 		{
 			File::create(&root.join("foo/file1.txt"))?
@@ -196,6 +280,40 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_reset_folder_keeps_flagged() -> Result<()> {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::create_dir(root.join("foo"))?;
+		write_commit_file(&repo, "foo/file1.txt", "file1", "c1");
+		write_commit_file(&repo, "foo/file2.txt", "file2", "c2");
+
+		debug_cmd_print(
+			repo_path,
+			"git update-index --skip-worktree foo/file1.txt",
+		);
+
+		File::create(root.join("foo/file1.txt"))?
+			.write_all(b"local")?;
+		File::create(root.join("foo/file2.txt"))?
+			.write_all(b"changed")?;
+
+		reset_workdir(repo_path, "foo").unwrap();
+
+		assert_eq!(
+			fs::read_to_string(root.join("foo/file1.txt"))?,
+			"local"
+		);
+		assert_eq!(
+			fs::read_to_string(root.join("foo/file2.txt"))?,
+			"file2"
+		);
+
+		Ok(())
+	}
+
 	#[test]
 	fn test_reset_untracked_in_subdir_and_index() {
 		let (_td, repo) = repo_init().unwrap();
@@ -311,4 +429,64 @@ mod tests {
 
 		assert_eq!(get_statuses(repo_path), (0, 0));
 	}
+
+	fn stage_rename(repo_path: &str) {
+		stage_addremoved(repo_path, Path::new("foo.txt")).unwrap();
+		stage_add_file(repo_path, Path::new("bar.txt")).unwrap();
+
+		let staged =
+			get_status(repo_path, StatusType::Stage, None).unwrap();
+
+		assert_eq!(staged.len(), 1);
+		assert_eq!(staged[0].path, "bar.txt");
+		assert_eq!(staged[0].old_path.as_deref(), Some("foo.txt"));
+		assert_eq!(staged[0].status, StatusItemType::Renamed);
+	}
+
+	#[test]
+	fn test_reset_stage_renamed() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let content = (0..20).map(|i| format!("line {}\n", i));
+		let old: String = content.clone().collect();
+		let new: String = content
+			.map(|l| {
+				if l == "line 10\n" {
+					"changed\n".into()
+				} else {
+					l
+				}
+			})
+			.collect();
+
+		write_commit_file(&repo, "foo.txt", &old, "c1");
+
+		fs::remove_file(root.join("foo.txt")).unwrap();
+		File::create(&root.join("bar.txt"))
+			.unwrap()
+			.write_all(new.as_bytes())
+			.unwrap();
+
+		stage_rename(repo_path);
+
+		let diff =
+			get_diff_renamed(repo_path, "foo.txt", "bar.txt", None)
+				.unwrap();
+
+		// only the content change, not the whole file
+		assert_eq!(diff.hunks.len(), 1);
+		// header, 3 lines of context each side, delete and add
+		assert_eq!(diff.lines, 9);
+
+		reset_stage_renamed(repo_path, "foo.txt", "bar.txt").unwrap();
+
+		// neither half of the rename stays in the index
+		assert_eq!(get_statuses(repo_path), (2, 0));
+
+		stage_rename(repo_path);
+
+		assert_eq!(get_statuses(repo_path), (0, 1));
+	}
 }
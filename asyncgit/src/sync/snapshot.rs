@@ -0,0 +1,269 @@
+//! snapshots of the index and of discarded working dir content to
+//! take back staging and discarding
+
+use super::{
+	index_lock::retry_on_index_lock,
+	utils::{repo, work_dir},
+};
+use crate::error::{Error, Result};
+use git2::{
+	ErrorCode, Oid, Repository, Status, StatusOptions, StatusShow,
+};
+use scopetime::scope_time;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// the index written as a tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexSnapshot(Oid);
+
+/// fails if the index cannot be written as a tree, e.g. while it has
+/// conflicts
+pub fn snapshot_index(repo_path: &str) -> Result<IndexSnapshot> {
+	scope_time!("snapshot_index");
+
+	let repo = repo(repo_path)?;
+	let mut index = repo.index()?;
+
+	Ok(IndexSnapshot(index.write_tree()?))
+}
+
+/// replaces the index with `snapshot`, the working dir is untouched
+pub fn restore_index(
+	repo_path: &str,
+	snapshot: IndexSnapshot,
+) -> Result<()> {
+	scope_time!("restore_index");
+
+	let repo = repo(repo_path)?;
+	let tree = repo.find_tree(snapshot.0)?;
+
+	retry_on_index_lock(repo_path, || {
+		let mut index = repo.index()?;
+		index.read_tree(&tree)?;
+		index.write()?;
+
+		Ok(())
+	})
+}
+
+/// working dir content of the files a discard is about to change,
+/// kept as blobs in the object database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscardBackup {
+	/// `None` for a file that did not exist
+	files: Vec<(PathBuf, Option<Oid>)>,
+}
+
+impl DiscardBackup {
+	/// number of files restored by `restore_discarded`
+	pub fn file_count(&self) -> usize {
+		self.files.len()
+	}
+}
+
+/// backs up all files with working dir changes matching `path` (a
+/// file or a folder), symlinks are left out
+pub fn backup_discarded(
+	repo_path: &str,
+	path: &str,
+) -> Result<DiscardBackup> {
+	scope_time!("backup_discarded");
+
+	let repo = repo(repo_path)?;
+	let work_dir = work_dir(&repo)?;
+
+	let mut options = StatusOptions::default();
+	options
+		.show(StatusShow::Workdir)
+		.pathspec(path)
+		.disable_pathspec_match(true)
+		.include_untracked(true)
+		.recurse_untracked_dirs(true);
+
+	let mut files = Vec::new();
+	for entry in repo.statuses(Some(&mut options))?.iter() {
+		let path = match entry.path() {
+			Some(path) => PathBuf::from(path),
+			None => continue,
+		};
+
+		let full_path = work_dir.join(&path);
+		let blob = match fs::symlink_metadata(&full_path) {
+			Ok(meta) if meta.file_type().is_symlink() => continue,
+			Ok(_) => Some(repo.blob_path(&full_path)?),
+			Err(_) => None,
+		};
+
+		files.push((path, blob));
+	}
+
+	Ok(DiscardBackup { files })
+}
+
+/// as left by the discard: matching the index or gone if untracked
+fn is_discarded(repo: &Repository, path: &Path) -> Result<bool> {
+	match repo.status_file(path) {
+		Ok(status) => Ok(!status.intersects(
+			Status::WT_NEW
+				| Status::WT_MODIFIED
+				| Status::WT_DELETED
+				| Status::WT_TYPECHANGE
+				| Status::WT_RENAMED,
+		)),
+		Err(e) if e.code() == ErrorCode::NotFound => Ok(true),
+		Err(e) => Err(e.into()),
+	}
+}
+
+/// writes the backed up content back into the working dir, fails
+/// without touching anything if one of the files was edited since
+pub fn restore_discarded(
+	repo_path: &str,
+	backup: &DiscardBackup,
+) -> Result<()> {
+	scope_time!("restore_discarded");
+
+	let repo = repo(repo_path)?;
+	let work_dir = work_dir(&repo)?;
+
+	for (path, _) in &backup.files {
+		if !is_discarded(&repo, path)? {
+			return Err(Error::ChangedSinceDiscard(
+				path.to_string_lossy().into_owned(),
+			));
+		}
+	}
+
+	for (path, blob) in &backup.files {
+		let full_path = work_dir.join(path);
+
+		if let Some(blob) = blob {
+			if let Some(parent) = full_path.parent() {
+				fs::create_dir_all(parent)?;
+			}
+			fs::write(&full_path, repo.find_blob(*blob)?.content())?;
+		} else if full_path.exists() {
+			fs::remove_file(&full_path)?;
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		reset_workdir, stage_add_all, stage_add_file,
+		status::{get_status, StatusType},
+		tests::{get_statuses, repo_init},
+		utils::{repo_read_file, repo_write_file},
+	};
+
+	fn index_entries(repo_path: &str) -> Vec<(String, Oid)> {
+		let repo = repo(repo_path).unwrap();
+		let index = repo.index().unwrap();
+		index
+			.iter()
+			.map(|entry| {
+				(String::from_utf8(entry.path).unwrap(), entry.id)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_undo_stage_all() {
+		let (path, repo) = repo_init().unwrap();
+		let path = path.path().to_str().unwrap();
+
+		repo_write_file(&repo, "a.txt", "a").unwrap();
+		repo_write_file(&repo, "b.txt", "b").unwrap();
+		stage_add_file(path, Path::new("a.txt")).unwrap();
+		repo_write_file(&repo, "a.txt", "a2").unwrap();
+
+		let before = index_entries(path);
+		assert_eq!(get_statuses(path), (2, 1));
+
+		let snapshot = snapshot_index(path).unwrap();
+		stage_add_all(path, "*").unwrap();
+		assert_eq!(get_statuses(path), (0, 2));
+
+		restore_index(path, snapshot).unwrap();
+
+		assert_eq!(index_entries(path), before);
+		assert_eq!(get_statuses(path), (2, 1));
+		assert_eq!(repo_read_file(&repo, "a.txt").unwrap(), "a2");
+	}
+
+	#[test]
+	fn test_restore_discarded() {
+		let (path, repo) = repo_init().unwrap();
+		let root = path.path();
+		let path = root.to_str().unwrap();
+
+		fs::create_dir(root.join("dir")).unwrap();
+		repo_write_file(&repo, "dir/tracked.txt", "1").unwrap();
+		stage_add_all(path, "*").unwrap();
+		repo_write_file(&repo, "dir/tracked.txt", "2").unwrap();
+		repo_write_file(&repo, "dir/new.txt", "new").unwrap();
+		repo_write_file(&repo, "other.txt", "other").unwrap();
+
+		let backup = backup_discarded(path, "dir").unwrap();
+		assert_eq!(backup.file_count(), 2);
+
+		reset_workdir(path, "dir").unwrap();
+		assert_eq!(
+			get_status(path, StatusType::WorkingDir, None)
+				.unwrap()
+				.len(),
+			1
+		);
+
+		restore_discarded(path, &backup).unwrap();
+
+		assert_eq!(
+			repo_read_file(&repo, "dir/tracked.txt").unwrap(),
+			"2"
+		);
+		assert_eq!(
+			repo_read_file(&repo, "dir/new.txt").unwrap(),
+			"new"
+		);
+		assert_eq!(get_statuses(path), (3, 1));
+	}
+
+	#[test]
+	fn test_backup_literal_path() {
+		let (path, repo) = repo_init().unwrap();
+		let path = path.path().to_str().unwrap();
+
+		repo_write_file(&repo, "a*.txt", "glob").unwrap();
+		repo_write_file(&repo, "ab.txt", "other").unwrap();
+
+		let backup = backup_discarded(path, "a*.txt").unwrap();
+		assert_eq!(backup.file_count(), 1);
+	}
+
+	#[test]
+	fn test_restore_refuses_later_edits() {
+		let (path, repo) = repo_init().unwrap();
+		let path = path.path().to_str().unwrap();
+
+		repo_write_file(&repo, "a.txt", "1").unwrap();
+		stage_add_all(path, "*").unwrap();
+		repo_write_file(&repo, "a.txt", "2").unwrap();
+
+		let backup = backup_discarded(path, "a.txt").unwrap();
+		reset_workdir(path, "a.txt").unwrap();
+		repo_write_file(&repo, "a.txt", "3").unwrap();
+
+		assert!(matches!(
+			restore_discarded(path, &backup),
+			Err(Error::ChangedSinceDiscard(file)) if file == "a.txt"
+		));
+		assert_eq!(repo_read_file(&repo, "a.txt").unwrap(), "3");
+	}
+}
@@ -2,7 +2,7 @@ use super::apply_selection;
 use crate::{
 	error::{Error, Result},
 	sync::{
-		diff::DiffLinePosition,
+		diff::DiffLinePosition, index_lock::retry_on_index_lock,
 		patches::get_file_diff_patch_and_hunklines, utils::repo,
 	},
 };
@@ -53,7 +53,7 @@ pub fn stage_lines(
 	idx.file_size = u32::try_conv(new_content.as_bytes().len())?;
 	index.add(&idx)?;
 
-	index.write()?;
+	retry_on_index_lock(repo_path, || Ok(index.write()?))?;
 	index.read(true)?;
 
 	Ok(())
@@ -0,0 +1,183 @@
+use crate::{
+	error::{Error, Result},
+	sync::{
+		diff::DiffLinePosition, index_lock::retry_on_index_lock,
+		utils::repo, utils::work_dir,
+	},
+};
+use easy_cast::Conv;
+use git2::IndexTime;
+use scopetime::scope_time;
+use std::{collections::HashSet, fs, path::Path};
+
+/// stages the selected lines of an untracked file: the file gets
+/// tracked with only these lines in the index, the rest stays a
+/// modification in the working dir
+pub fn stage_lines_untracked(
+	repo_path: &str,
+	file_path: &str,
+	lines: &[DiffLinePosition],
+) -> Result<()> {
+	scope_time!("stage_lines_untracked");
+
+	if lines.is_empty() {
+		return Ok(());
+	}
+
+	let repo = repo(repo_path)?;
+
+	let full_path = work_dir(&repo)?.join(file_path);
+	if fs::symlink_metadata(&full_path)?.file_type().is_symlink() {
+		return Err(Error::Generic(String::from(
+			"lines of a symlink cannot be staged",
+		)));
+	}
+
+	let content = String::from_utf8(fs::read(full_path)?)?;
+	let new_content = selected_content(&content, lines);
+
+	let mut index = repo.index()?;
+	index.read(true)?;
+
+	// intent to add: brings in mode and path of the worktree file
+	index.add_path(Path::new(file_path))?;
+	let mut idx =
+		index.get_path(Path::new(file_path), 0).ok_or_else(|| {
+			Error::Generic(String::from("file not added to index"))
+		})?;
+
+	idx.id = repo.blob(new_content.as_bytes())?;
+	idx.file_size = u32::try_conv(new_content.len())?;
+	// the stat data is that of the full file, make sure it is not
+	// taken as unchanged
+	idx.mtime = IndexTime::new(0, 0);
+	index.add(&idx)?;
+
+	retry_on_index_lock(repo_path, || Ok(index.write()?))?;
+	index.read(true)?;
+
+	Ok(())
+}
+
+/// the lines of `content` selected by their new line number
+fn selected_content(
+	content: &str,
+	lines: &[DiffLinePosition],
+) -> String {
+	let selected = lines
+		.iter()
+		.filter_map(|line| line.new_lineno)
+		.collect::<HashSet<_>>();
+
+	// keeps `\r` of crlf line endings
+	let content_lines = content.split_terminator('\n');
+	let count = content_lines.clone().count();
+
+	let mut out = String::with_capacity(content.len());
+	for (idx, line) in content_lines.enumerate() {
+		if selected.contains(&u32::try_conv(idx + 1).unwrap_or(0)) {
+			out.push_str(line);
+
+			// keep a missing newline at the end of the file
+			if idx + 1 < count || content.ends_with('\n') {
+				out.push('\n');
+			}
+		}
+	}
+
+	out
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::sync::{
+		diff::get_diff,
+		tests::{get_statuses, repo_init},
+		utils::{repo_read_file, repo_write_file},
+	};
+	use git2::Repository;
+
+	fn indexed_content(repo: &Repository, file: &str) -> String {
+		let mut index = repo.index().unwrap();
+		index.read(true).unwrap();
+		let entry = index.get_path(Path::new(file), 0).unwrap();
+		let blob = repo.find_blob(entry.id).unwrap();
+		String::from_utf8(blob.content().into()).unwrap()
+	}
+
+	fn numbered_lines(count: usize) -> String {
+		(1..=count)
+			.map(|i| format!("line {}", i))
+			.collect::<Vec<_>>()
+			.join("\n")
+			+ "\n"
+	}
+
+	#[test]
+	fn test_stage_half_of_new_file() {
+		let (path, repo) = repo_init().unwrap();
+		let path = path.path().to_str().unwrap();
+
+		let content = numbered_lines(100);
+		repo_write_file(&repo, "new.txt", &content).unwrap();
+
+		let diff = get_diff(path, "new.txt", false, None).unwrap();
+		assert!(diff.untracked);
+		assert_eq!(diff.lines, 101);
+
+		let first_half = (1..=50)
+			.map(|i| DiffLinePosition {
+				old_lineno: None,
+				new_lineno: Some(i),
+			})
+			.collect::<Vec<_>>();
+
+		stage_lines_untracked(path, "new.txt", &first_half).unwrap();
+
+		assert_eq!(get_statuses(path), (1, 1));
+		assert_eq!(
+			indexed_content(&repo, "new.txt"),
+			numbered_lines(50)
+		);
+		assert_eq!(
+			repo_read_file(&repo, "new.txt").unwrap(),
+			content
+		);
+
+		let unstaged =
+			get_diff(path, "new.txt", false, None).unwrap();
+		assert!(!unstaged.untracked);
+		assert_eq!(unstaged.hunks.len(), 1);
+		assert_eq!(
+			&*unstaged.hunks[0].lines[0].content,
+			"@@ -48,3 +48,53 @@ line 47"
+		);
+	}
+
+	#[test]
+	fn test_stage_last_line_without_newline() {
+		let (path, repo) = repo_init().unwrap();
+		let path = path.path().to_str().unwrap();
+
+		repo_write_file(&repo, "new.txt", "a\nb\nc").unwrap();
+
+		stage_lines_untracked(
+			path,
+			"new.txt",
+			&[
+				DiffLinePosition {
+					old_lineno: None,
+					new_lineno: Some(1),
+				},
+				DiffLinePosition {
+					old_lineno: None,
+					new_lineno: Some(3),
+				},
+			],
+		)
+		.unwrap();
+
+		assert_eq!(indexed_content(&repo, "new.txt"), "a\nc");
+	}
+}
@@ -0,0 +1,443 @@
+//! leftovers of crashed git processes, like an old `index.lock` or a
+//! `MERGE_HEAD` of a merge that was committed already
+
+use super::{
+	config::get_config_string_repo,
+	utils::{self, get_head_repo},
+};
+use crate::error::{Error, Result};
+use git2::{Oid, Repository, RepositoryState};
+use scopetime::scope_time;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+	time::{Duration, SystemTime},
+};
+
+/// git config entry overriding after how many minutes an unheld lock
+/// file is considered stale
+const LOCK_AGE_CONFIG: &str = "gitui.staleLockMinutes";
+const LOCK_AGE_DEFAULT: Duration = Duration::from_secs(10 * 60);
+
+/// lock files git takes in the git dir (besides the ones of refs)
+const LOCK_FILES: [&str; 5] = [
+	"index.lock",
+	"HEAD.lock",
+	"ORIG_HEAD.lock",
+	"config.lock",
+	"packed-refs.lock",
+];
+
+const MERGE_FILES: [&str; 4] =
+	["MERGE_HEAD", "MERGE_MSG", "MERGE_MODE", "AUTO_MERGE"];
+
+const REBASE_DIRS: [&str; 2] = ["rebase-merge", "rebase-apply"];
+
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum StaleState {
+	/// lock file no process holds, `path` is relative to the git dir
+	Lock {
+		///
+		path: String,
+		///
+		age: Duration,
+	},
+	/// `MERGE_HEAD` of a merge that is committed already (or points
+	/// to nothing) while there are no conflicts
+	Merge,
+	/// rebase dir (named) missing what is needed to continue or abort
+	Rebase(String),
+	/// `sequencer` dir without a cherry-pick or revert in progress
+	/// and nothing left to do
+	Sequencer,
+}
+
+impl StaleState {
+	/// same condition, ignoring details that change over time
+	fn same_condition(&self, other: &Self) -> bool {
+		match (self, other) {
+			(
+				Self::Lock { path: a, .. },
+				Self::Lock { path: b, .. },
+			) => a == b,
+			_ => self == other,
+		}
+	}
+}
+
+fn lock_age_limit(repo: &Repository) -> Duration {
+	get_config_string_repo(repo, LOCK_AGE_CONFIG)
+		.ok()
+		.flatten()
+		.and_then(|minutes| minutes.trim().parse::<u64>().ok())
+		.map_or(LOCK_AGE_DEFAULT, |minutes| {
+			Duration::from_secs(minutes * 60)
+		})
+}
+
+fn find_ref_locks(dir: &Path, res: &mut Vec<PathBuf>) {
+	if let Ok(entries) = fs::read_dir(dir) {
+		for entry in entries.flatten() {
+			let path = entry.path();
+			if path.is_dir() {
+				find_ref_locks(&path, res);
+			} else if path
+				.extension()
+				.map_or(false, |ext| ext == "lock")
+			{
+				res.push(path);
+			}
+		}
+	}
+}
+
+/// git does not record the pid of a lock holder, on linux we can at
+/// least see whether any process has the lock file open
+#[cfg(target_os = "linux")]
+fn lock_is_held(lock: &Path) -> bool {
+	let lock = match fs::canonicalize(lock) {
+		Ok(lock) => lock,
+		Err(_) => return false,
+	};
+
+	let procs = match fs::read_dir("/proc") {
+		Ok(procs) => procs,
+		Err(_) => return false,
+	};
+
+	procs.flatten().any(|proc| {
+		fs::read_dir(proc.path().join("fd")).map_or(false, |fds| {
+			fds.flatten().any(|fd| {
+				fs::read_link(fd.path())
+					.map_or(false, |target| target == lock)
+			})
+		})
+	})
+}
+
+/// elsewhere only the age of a lock file tells
+#[cfg(not(target_os = "linux"))]
+const fn lock_is_held(_lock: &Path) -> bool {
+	false
+}
+
+fn stale_locks(repo: &Repository) -> Vec<StaleState> {
+	let git_dir = repo.path();
+	let limit = lock_age_limit(repo);
+	let now = SystemTime::now();
+
+	let mut locks: Vec<PathBuf> =
+		LOCK_FILES.iter().map(|file| git_dir.join(file)).collect();
+	find_ref_locks(&git_dir.join("refs"), &mut locks);
+
+	locks
+		.into_iter()
+		.filter_map(|lock| {
+			let modified =
+				fs::metadata(&lock).ok()?.modified().ok()?;
+			let age =
+				now.duration_since(modified).unwrap_or_default();
+
+			if age < limit || lock_is_held(&lock) {
+				return None;
+			}
+
+			let path = lock.strip_prefix(git_dir).ok()?;
+			Some(StaleState::Lock {
+				path: path.to_string_lossy().replace('\\', "/"),
+				age,
+			})
+		})
+		.collect()
+}
+
+/// every merge head is part of `HEAD` already, or unreadable
+fn merge_is_stale(repo: &Repository) -> Result<bool> {
+	if repo.index()?.has_conflicts() {
+		return Ok(false);
+	}
+
+	let head: Oid = match get_head_repo(repo) {
+		Ok(head) => head.into(),
+		Err(_) => return Ok(false),
+	};
+
+	let ids: Option<Vec<Oid>> =
+		fs::read_to_string(repo.path().join("MERGE_HEAD"))
+			.ok()
+			.and_then(|content| {
+				content
+					.lines()
+					.map(|line| Oid::from_str(line.trim()).ok())
+					.collect()
+			});
+	let ids = match ids {
+		Some(ids) if !ids.is_empty() => ids,
+		_ => return Ok(true),
+	};
+
+	Ok(ids.iter().all(|id| {
+		*id == head
+			|| repo.graph_descendant_of(head, *id).unwrap_or(false)
+	}))
+}
+
+/// without these neither git nor gitui can continue or abort
+fn rebase_dir_is_stale(dir: &Path) -> bool {
+	!dir.join("orig-head").is_file()
+		|| !dir.join("head-name").is_file()
+}
+
+/// between the picks of a sequence (after committing a resolved one)
+/// there is no `CHERRY_PICK_HEAD` either, only the todo tells
+fn sequencer_is_stale(repo: &Repository) -> bool {
+	let git_dir = repo.path();
+	let dir = git_dir.join("sequencer");
+
+	dir.is_dir()
+		&& !git_dir.join("CHERRY_PICK_HEAD").exists()
+		&& !git_dir.join("REVERT_HEAD").exists()
+		&& fs::read_to_string(dir.join("todo"))
+			.map_or(true, |todo| todo.trim().is_empty())
+}
+
+fn detect(repo: &Repository) -> Result<Vec<StaleState>> {
+	let mut res = stale_locks(repo);
+
+	if repo.state() == RepositoryState::Merge && merge_is_stale(repo)?
+	{
+		res.push(StaleState::Merge);
+	}
+
+	for dir in &REBASE_DIRS {
+		let path = repo.path().join(dir);
+		if path.is_dir() && rebase_dir_is_stale(&path) {
+			res.push(StaleState::Rebase((*dir).to_string()));
+		}
+	}
+
+	if sequencer_is_stale(repo) {
+		res.push(StaleState::Sequencer);
+	}
+
+	Ok(res)
+}
+
+/// leftovers of crashed git processes in the repo
+pub fn detect_stale_state(
+	repo_path: &str,
+) -> Result<Vec<StaleState>> {
+	scope_time!("detect_stale_state");
+
+	let repo = utils::repo(repo_path)?;
+	detect(&repo)
+}
+
+fn remove_file_if_exists(path: &Path) -> Result<()> {
+	if path.exists() {
+		fs::remove_file(path)?;
+	}
+
+	Ok(())
+}
+
+/// removes `state` after verifying it is still stale, so a git
+/// process that started in the meantime is not interfered with
+pub fn cleanup_stale_state(
+	repo_path: &str,
+	state: &StaleState,
+) -> Result<()> {
+	scope_time!("cleanup_stale_state");
+
+	let repo = utils::repo(repo_path)?;
+
+	if !detect(&repo)?.iter().any(|s| s.same_condition(state)) {
+		return Err(Error::Generic(String::from(
+			"no longer stale, nothing was removed",
+		)));
+	}
+
+	let git_dir = repo.path();
+
+	match state {
+		StaleState::Lock { path, .. } => {
+			fs::remove_file(git_dir.join(path))?;
+		}
+		StaleState::Merge => {
+			for file in &MERGE_FILES {
+				remove_file_if_exists(&git_dir.join(file))?;
+			}
+		}
+		StaleState::Rebase(dir) => {
+			fs::remove_dir_all(git_dir.join(dir))?;
+		}
+		StaleState::Sequencer => {
+			fs::remove_dir_all(git_dir.join("sequencer"))?;
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		create_branch, repo_state,
+		tests::{repo_init, write_commit_file},
+		RepoState,
+	};
+	use std::fs::File;
+
+	fn set_lock_age(repo: &Repository, minutes: i64) {
+		repo.config()
+			.unwrap()
+			.set_i64(LOCK_AGE_CONFIG, minutes)
+			.unwrap();
+	}
+
+	#[test]
+	fn test_clean() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		assert!(detect_stale_state(repo_path).unwrap().is_empty());
+		assert!(cleanup_stale_state(repo_path, &StaleState::Merge)
+			.is_err());
+	}
+
+	#[test]
+	fn test_locks() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::write(repo.path().join("index.lock"), "").unwrap();
+		fs::write(repo.path().join("refs/heads/master.lock"), "")
+			.unwrap();
+
+		// too young
+		assert!(detect_stale_state(repo_path).unwrap().is_empty());
+
+		set_lock_age(&repo, 0);
+
+		let res = detect_stale_state(repo_path).unwrap();
+		let paths: Vec<&str> = res
+			.iter()
+			.filter_map(|s| match s {
+				StaleState::Lock { path, .. } => Some(path.as_str()),
+				_ => None,
+			})
+			.collect();
+		assert_eq!(
+			paths,
+			vec!["index.lock", "refs/heads/master.lock"]
+		);
+
+		cleanup_stale_state(repo_path, &res[0]).unwrap();
+		assert!(!repo.path().join("index.lock").exists());
+
+		// already gone
+		assert!(cleanup_stale_state(repo_path, &res[0]).is_err());
+
+		assert_eq!(detect_stale_state(repo_path).unwrap().len(), 1);
+	}
+
+	#[cfg(target_os = "linux")]
+	#[test]
+	fn test_held_lock() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		set_lock_age(&repo, 0);
+
+		let lock =
+			File::create(repo.path().join("index.lock")).unwrap();
+		assert!(detect_stale_state(repo_path).unwrap().is_empty());
+
+		drop(lock);
+		assert_eq!(detect_stale_state(repo_path).unwrap().len(), 1);
+	}
+
+	#[test]
+	fn test_merged_merge_head() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "test.txt", "a", "c1");
+		create_branch(repo_path, "foo").unwrap();
+		let foo = write_commit_file(&repo, "test.txt", "b", "c2");
+
+		let merge_head = repo.path().join("MERGE_HEAD");
+		fs::write(&merge_head, format!("{}\n", foo.to_string()))
+			.unwrap();
+		fs::write(repo.path().join("MERGE_MSG"), "msg").unwrap();
+
+		// merging `foo` into `master` still has to be committed
+		repo.set_head("refs/heads/master").unwrap();
+		assert!(detect_stale_state(repo_path).unwrap().is_empty());
+
+		// `foo` is part of HEAD already
+		repo.set_head("refs/heads/foo").unwrap();
+		assert_eq!(
+			detect_stale_state(repo_path).unwrap(),
+			vec![StaleState::Merge]
+		);
+
+		cleanup_stale_state(repo_path, &StaleState::Merge).unwrap();
+
+		assert!(!merge_head.exists());
+		assert!(!repo.path().join("MERGE_MSG").exists());
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+	}
+
+	#[test]
+	fn test_state_dirs() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::create_dir(repo.path().join("rebase-merge")).unwrap();
+		fs::create_dir(repo.path().join("sequencer")).unwrap();
+
+		let res = detect_stale_state(repo_path).unwrap();
+		assert_eq!(
+			res,
+			vec![
+				StaleState::Rebase(String::from("rebase-merge")),
+				StaleState::Sequencer
+			]
+		);
+
+		for state in &res {
+			cleanup_stale_state(repo_path, state).unwrap();
+		}
+
+		assert!(!repo.path().join("rebase-merge").exists());
+		assert!(!repo.path().join("sequencer").exists());
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+	}
+
+	#[test]
+	fn test_sequencer_with_todo() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let dir = repo.path().join("sequencer");
+		fs::create_dir(&dir).unwrap();
+		fs::write(dir.join("todo"), "\n").unwrap();
+		assert_eq!(
+			detect_stale_state(repo_path).unwrap(),
+			vec![StaleState::Sequencer]
+		);
+
+		// the rest of a sequence, between two picks
+		fs::write(dir.join("todo"), "pick 1234567 next\n").unwrap();
+		assert!(detect_stale_state(repo_path).unwrap().is_empty());
+	}
+}
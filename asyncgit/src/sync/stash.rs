@@ -1,4 +1,4 @@
-use super::{utils::repo, CommitId};
+use super::{index_lock::retry_on_index_lock, utils::repo, CommitId};
 use crate::error::{Error, Result};
 use git2::{
 	build::CheckoutBuilder, Oid, Repository, StashApplyOptions,
@@ -52,9 +52,10 @@ pub fn stash_pop(repo_path: &str, stash_id: CommitId) -> Result<()> {
 
 	let index = get_stash_index(&mut repo, stash_id.into())?;
 
-	repo.stash_pop(index, None)?;
-
-	Ok(())
+	retry_on_index_lock(repo_path, || {
+		repo.stash_pop(index, None)?;
+		Ok(())
+	})
 }
 
 ///
@@ -69,14 +70,16 @@ pub fn stash_apply(
 
 	let index = get_stash_index(&mut repo, stash_id.get_oid())?;
 
-	let mut checkout = CheckoutBuilder::new();
-	checkout.allow_conflicts(allow_conflicts);
+	retry_on_index_lock(repo_path, || {
+		let mut checkout = CheckoutBuilder::new();
+		checkout.allow_conflicts(allow_conflicts);
 
-	let mut opt = StashApplyOptions::default();
-	opt.checkout_options(checkout);
-	repo.stash_apply(index, Some(&mut opt))?;
+		let mut opt = StashApplyOptions::default();
+		opt.checkout_options(checkout);
+		repo.stash_apply(index, Some(&mut opt))?;
 
-	Ok(())
+		Ok(())
+	})
 }
 
 fn get_stash_index(
@@ -121,7 +124,9 @@ pub fn stash_save(
 		options.insert(StashFlags::KEEP_INDEX);
 	}
 
-	let id = repo.stash_save2(&sig, message, Some(options))?;
+	let id = retry_on_index_lock(repo_path, || {
+		Ok(repo.stash_save2(&sig, message, Some(options))?)
+	})?;
 
 	Ok(CommitId::new(id))
 }
@@ -136,6 +141,7 @@ mod tests {
 			write_commit_file,
 		},
 		utils::{repo_read_file, repo_write_file},
+		MergeDiffMode,
 	};
 	use std::{fs::File, io::Write, path::Path};
 
@@ -219,7 +225,7 @@ mod tests {
 
 		File::create(&root.join(file_path1))?.write_all(b"test")?;
 		stage_add_file(repo_path, file_path1)?;
-		commit(repo_path, "c1")?;
+		commit(repo_path, "c1", false)?;
 
 		File::create(&root.join(file_path1))?
 			.write_all(b"modified")?;
@@ -231,7 +237,12 @@ mod tests {
 
 		let stash = get_stashes(repo_path)?[0];
 
-		let diff = get_commit_files(repo_path, stash, None)?;
+		let diff = get_commit_files(
+			repo_path,
+			stash,
+			None,
+			MergeDiffMode::default(),
+		)?;
 
 		assert_eq!(diff.len(), 1);
 
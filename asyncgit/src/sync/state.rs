@@ -1,23 +1,61 @@
-use crate::{error::Result, sync::utils};
-use git2::RepositoryState;
+use crate::{
+	error::{Error, Result},
+	sync::{
+		abort_merge,
+		autosquash::{continue_autosquash, is_autosquash_rebase},
+		cherry_pick::{continue_cherry_pick, sequencer_start},
+		commit, merge_commit, merge_msg, mergehead_ids, utils,
+	},
+};
+use git2::{ErrorCode, Oid, Repository, RepositoryState, ResetType};
 use scopetime::scope_time;
+use std::{fs, path::Path};
+
+/// steps of a rebase in progress, `current` is 1-based
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct RebaseProgress {
+	///
+	pub current: usize,
+	///
+	pub total: usize,
+}
 
 ///
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Copy, Clone)]
 pub enum RepoState {
 	///
 	Clean,
 	///
 	Merge,
+	/// progress is `None` if git did not record it
+	Rebase(Option<RebaseProgress>),
 	///
+	CherryPick,
+	///
+	Revert,
+	/// bisect, applying mailboxes and the like
 	Other,
 }
 
+impl RepoState {
+	/// can be handled by `abort_pending_state`/`continue_pending_state`
+	pub const fn is_pending(self) -> bool {
+		!matches!(self, Self::Clean | Self::Other)
+	}
+}
+
 impl From<RepositoryState> for RepoState {
 	fn from(state: RepositoryState) -> Self {
 		match state {
 			RepositoryState::Clean => Self::Clean,
 			RepositoryState::Merge => Self::Merge,
+			RepositoryState::Rebase
+			| RepositoryState::RebaseInteractive
+			| RepositoryState::RebaseMerge => Self::Rebase(None),
+			RepositoryState::CherryPick
+			| RepositoryState::CherryPickSequence => Self::CherryPick,
+			RepositoryState::Revert
+			| RepositoryState::RevertSequence => Self::Revert,
 			_ => Self::Other,
 		}
 	}
@@ -29,5 +67,441 @@ pub fn repo_state(repo_path: &str) -> Result<RepoState> {
 
 	let repo = utils::repo(repo_path)?;
 
-	Ok(repo.state().into())
+	let state = match repo.state().into() {
+		RepoState::Rebase(_) => {
+			RepoState::Rebase(rebase_progress(&repo))
+		}
+		state => state,
+	};
+
+	Ok(state)
+}
+
+/// aborts the merge, rebase, cherry-pick or revert in progress and
+/// restores the state from before it was started
+pub fn abort_pending_state(repo_path: &str) -> Result<()> {
+	scope_time!("abort_pending_state");
+
+	let repo = utils::repo(repo_path)?;
+
+	match repo.state().into() {
+		RepoState::Merge => abort_merge(repo_path),
+		RepoState::Rebase(_) => abort_rebase(&repo),
+		RepoState::CherryPick | RepoState::Revert => {
+			abort_sequencer(&repo)
+		}
+		RepoState::Clean | RepoState::Other => Err(Error::Generic(
+			String::from("no operation in progress to abort"),
+		)),
+	}
+}
+
+/// concludes the merge, rebase, cherry-pick or revert in progress
+/// once all conflicts are resolved. a rebase stops again at the
+/// next conflicting commit
+pub fn continue_pending_state(repo_path: &str) -> Result<()> {
+	scope_time!("continue_pending_state");
+
+	let repo = utils::repo(repo_path)?;
+
+	if repo.index()?.has_conflicts() {
+		return Err(Error::Generic(String::from(
+			"resolve all conflicts first",
+		)));
+	}
+
+	match repo.state() {
+		RepositoryState::Merge => {
+			merge_commit(
+				repo_path,
+				&merge_msg(repo_path)?,
+				&mergehead_ids(repo_path)?,
+			)?;
+			Ok(())
+		}
+		RepositoryState::CherryPick | RepositoryState::Revert => {
+			commit(repo_path, &merge_msg(repo_path)?, true)?;
+			repo.cleanup_state()?;
+			Ok(())
+		}
+		RepositoryState::CherryPickSequence => {
+			continue_cherry_pick(repo_path, |_| ())?;
+			Ok(())
+		}
+		RepositoryState::RevertSequence => {
+			Err(Error::Generic(String::from(
+				"continuing a sequence of reverts is not supported",
+			)))
+		}
+		RepositoryState::RebaseMerge => continue_rebase(&repo),
+		RepositoryState::RebaseInteractive
+			if is_autosquash_rebase(&repo) =>
+		{
+			continue_autosquash(&repo)
+		}
+		RepositoryState::Rebase
+		| RepositoryState::RebaseInteractive => {
+			Err(Error::Generic(String::from(
+				"continuing this kind of rebase is not supported",
+			)))
+		}
+		_ => Err(Error::Generic(String::from(
+			"no operation in progress to continue",
+		))),
+	}
+}
+
+fn rebase_dir(repo: &Repository) -> Option<std::path::PathBuf> {
+	["rebase-merge", "rebase-apply"]
+		.iter()
+		.map(|dir| repo.path().join(dir))
+		.find(|dir| dir.is_dir())
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+	fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_number(path: &Path) -> Option<usize> {
+	read_trimmed(path)?.parse().ok()
+}
+
+/// `rebase-merge` keeps `msgnum`/`end`, `rebase-apply` `next`/`last`
+fn rebase_progress(repo: &Repository) -> Option<RebaseProgress> {
+	let dir = rebase_dir(repo)?;
+
+	let (current, total) = if dir.ends_with("rebase-merge") {
+		(dir.join("msgnum"), dir.join("end"))
+	} else {
+		(dir.join("next"), dir.join("last"))
+	};
+
+	Some(RebaseProgress {
+		current: read_number(&current)?,
+		total: read_number(&total)?,
+	})
+}
+
+/// does what `git rebase --abort` does, this works for rebases
+/// git2 cannot open itself (like interactive ones)
+fn abort_rebase(repo: &Repository) -> Result<()> {
+	let dir = rebase_dir(repo).ok_or_else(|| {
+		Error::Generic(String::from("rebase dir not found"))
+	})?;
+
+	let orig_head = read_trimmed(&dir.join("orig-head"))
+		.and_then(|id| Oid::from_str(&id).ok())
+		.ok_or_else(|| {
+			Error::Generic(String::from("rebase orig-head not found"))
+		})?;
+	let head_name =
+		read_trimmed(&dir.join("head-name")).unwrap_or_default();
+
+	if head_name.starts_with("refs/") {
+		repo.reference(
+			&head_name,
+			orig_head,
+			true,
+			"rebase: aborting",
+		)?;
+		repo.set_head(&head_name)?;
+	} else {
+		repo.set_head_detached(orig_head)?;
+	}
+
+	let commit = repo.find_object(orig_head, None)?;
+	repo.reset(&commit, ResetType::Hard, None)?;
+
+	// git2 only cleans up the rebase dirs it knows about
+	if dir.exists() {
+		fs::remove_dir_all(&dir)?;
+	}
+	repo.cleanup_state()?;
+
+	Ok(())
+}
+
+/// resets to where a cherry-pick or revert (sequence) started
+fn abort_sequencer(repo: &Repository) -> Result<()> {
+	let target = match sequencer_start(repo) {
+		Some(id) => repo.find_object(id, None)?,
+		None => repo.head()?.peel(git2::ObjectType::Commit)?,
+	};
+
+	repo.reset(&target, ResetType::Hard, None)?;
+	repo.cleanup_state()?;
+
+	Ok(())
+}
+
+fn continue_rebase(repo: &Repository) -> Result<()> {
+	let mut rebase = repo.open_rebase(None)?;
+	let signature =
+		crate::sync::commit::signature_allow_undefined_name(repo)?;
+
+	// the stopped commit might have been committed already
+	if let Err(e) = rebase.commit(None, &signature, None) {
+		if e.code() != ErrorCode::Applied {
+			return Err(e.into());
+		}
+	}
+
+	while let Some(op) = rebase.next() {
+		op?;
+
+		if repo.index()?.has_conflicts() {
+			// stop here for the user to resolve
+			return Ok(());
+		}
+
+		if let Err(e) = rebase.commit(None, &signature, None) {
+			if e.code() != ErrorCode::Applied {
+				return Err(e.into());
+			}
+		}
+	}
+
+	rebase.finish(Some(&signature))?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		checkout_branch, create_branch, get_commit_details,
+		stage_add_file,
+		tests::{debug_cmd_print, repo_init, write_commit_file},
+		utils::get_head_repo,
+		CommitId,
+	};
+	use git2::Repository;
+	use std::{fs::File, io::Write, path::Path};
+	use tempfile::TempDir;
+
+	/// `master` and `foo` both change `test.txt`
+	fn setup_conflict() -> (TempDir, Repository, CommitId, CommitId) {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "test.txt", "base", "c1");
+		create_branch(repo_path, "foo").unwrap();
+		let foo = write_commit_file(&repo, "test.txt", "foo", "c2");
+
+		checkout_branch(repo_path, "refs/heads/master").unwrap();
+		let master =
+			write_commit_file(&repo, "test.txt", "master", "c3");
+
+		(td, repo, master, foo)
+	}
+
+	fn resolve(repo_path: &str) {
+		File::create(Path::new(repo_path).join("test.txt"))
+			.unwrap()
+			.write_all(b"resolved")
+			.unwrap();
+		stage_add_file(repo_path, Path::new("test.txt")).unwrap();
+	}
+
+	fn read_file(repo_path: &str) -> String {
+		fs::read_to_string(Path::new(repo_path).join("test.txt"))
+			.unwrap()
+	}
+
+	#[test]
+	fn test_merge() {
+		let (_td, repo, master, _foo) = setup_conflict();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		debug_cmd_print(repo_path, "git merge foo");
+
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Merge);
+
+		abort_pending_state(repo_path).unwrap();
+
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		assert_eq!(get_head_repo(&repo).unwrap(), master);
+		assert_eq!(read_file(repo_path), "master");
+	}
+
+	#[test]
+	fn test_merge_continue() {
+		let (_td, repo, master, foo) = setup_conflict();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		debug_cmd_print(repo_path, "git merge foo");
+
+		assert!(continue_pending_state(repo_path).is_err());
+
+		resolve(repo_path);
+		continue_pending_state(repo_path).unwrap();
+
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+
+		let head = get_head_repo(&repo).unwrap();
+		let parents: Vec<CommitId> = repo
+			.find_commit(head.into())
+			.unwrap()
+			.parent_ids()
+			.map(CommitId::from)
+			.collect();
+		assert_eq!(parents, vec![master, foo]);
+	}
+
+	#[test]
+	fn test_rebase() {
+		let (_td, repo, _master, foo) = setup_conflict();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		checkout_branch(repo_path, "refs/heads/foo").unwrap();
+		debug_cmd_print(repo_path, "git rebase master");
+
+		assert_eq!(
+			repo_state(repo_path).unwrap(),
+			RepoState::Rebase(Some(RebaseProgress {
+				current: 1,
+				total: 1
+			}))
+		);
+
+		abort_pending_state(repo_path).unwrap();
+
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		assert_eq!(get_head_repo(&repo).unwrap(), foo);
+		assert_eq!(
+			repo.head().unwrap().name().unwrap(),
+			"refs/heads/foo"
+		);
+		assert_eq!(read_file(repo_path), "foo");
+	}
+
+	#[test]
+	fn test_rebase_continue() {
+		let (_td, repo, master, _foo) = setup_conflict();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		checkout_branch(repo_path, "refs/heads/foo").unwrap();
+
+		// a rebase git2 can continue
+		{
+			// fresh handle, `repo` caches the index of `master`
+			let repo = Repository::open(repo_path).unwrap();
+			let onto = repo
+				.reference_to_annotated_commit(
+					&repo
+						.find_reference("refs/heads/master")
+						.unwrap(),
+				)
+				.unwrap();
+			let mut rebase =
+				repo.rebase(None, Some(&onto), None, None).unwrap();
+			rebase.next().unwrap().unwrap();
+		}
+
+		assert_eq!(
+			repo_state(repo_path).unwrap(),
+			RepoState::Rebase(Some(RebaseProgress {
+				current: 1,
+				total: 1
+			}))
+		);
+
+		resolve(repo_path);
+		continue_pending_state(repo_path).unwrap();
+
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+
+		let head = get_head_repo(&repo).unwrap();
+		let commit = repo.find_commit(head.into()).unwrap();
+		assert_eq!(commit.parent_id(0).unwrap(), master.into());
+		assert_eq!(
+			repo.head().unwrap().name().unwrap(),
+			"refs/heads/foo"
+		);
+	}
+
+	#[test]
+	fn test_cherry_pick() {
+		let (_td, repo, master, foo) = setup_conflict();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		debug_cmd_print(
+			repo_path,
+			&format!("git cherry-pick {}", foo.to_string()),
+		);
+
+		assert_eq!(
+			repo_state(repo_path).unwrap(),
+			RepoState::CherryPick
+		);
+
+		abort_pending_state(repo_path).unwrap();
+
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		assert_eq!(get_head_repo(&repo).unwrap(), master);
+		assert_eq!(read_file(repo_path), "master");
+	}
+
+	#[test]
+	fn test_cherry_pick_continue() {
+		let (_td, repo, master, foo) = setup_conflict();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		debug_cmd_print(
+			repo_path,
+			&format!("git cherry-pick {}", foo.to_string()),
+		);
+
+		resolve(repo_path);
+		continue_pending_state(repo_path).unwrap();
+
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+
+		let head = get_head_repo(&repo).unwrap();
+		assert_ne!(head, master);
+		let details = get_commit_details(repo_path, head).unwrap();
+		assert_eq!(details.message.unwrap().subject, "c2");
+	}
+
+	#[test]
+	fn test_revert() {
+		let (_td, repo, master, _foo) = setup_conflict();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		// reverting c1 conflicts with c3 changing the same line
+		let c1 = repo
+			.find_commit(master.into())
+			.unwrap()
+			.parent_id(0)
+			.unwrap();
+		debug_cmd_print(repo_path, &format!("git revert {}", c1));
+
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Revert);
+
+		abort_pending_state(repo_path).unwrap();
+
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		assert_eq!(get_head_repo(&repo).unwrap(), master);
+		assert_eq!(read_file(repo_path), "master");
+	}
+
+	#[test]
+	fn test_clean() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		assert!(abort_pending_state(repo_path).is_err());
+		assert!(continue_pending_state(repo_path).is_err());
+	}
 }
@@ -3,11 +3,22 @@
 use crate::{
 	error::Error,
 	error::Result,
-	sync::{config::untracked_files_config_repo, utils},
+	sync::{
+		config::untracked_files_config_repo,
+		large_files::{staged_binary_size, workdir_binary_size},
+		utils,
+	},
+};
+use git2::{
+	Delta, Repository, Status, StatusEntry, StatusOptions,
+	StatusShow, TreeWalkMode, TreeWalkResult,
+};
+use scopetime::{scope_stats, scope_time};
+use std::{
+	collections::{HashMap, HashSet},
+	fs,
+	path::{Path, PathBuf},
 };
-use git2::{Delta, Status, StatusOptions, StatusShow};
-use scopetime::scope_time;
-use std::path::Path;
 
 use super::ShowUntrackedFilesConfig;
 
@@ -26,6 +37,9 @@ pub enum StatusItemType {
 	Typechange,
 	///
 	Conflicted,
+	/// a directory without content, git cannot track it (see
+	/// `sync::add_gitkeep`)
+	EmptyDir,
 }
 
 impl From<Status> for StatusItemType {
@@ -49,7 +63,7 @@ impl From<Status> for StatusItemType {
 impl From<Delta> for StatusItemType {
 	fn from(d: Delta) -> Self {
 		match d {
-			Delta::Added => Self::New,
+			Delta::Added | Delta::Untracked => Self::New,
 			Delta::Deleted => Self::Deleted,
 			Delta::Renamed => Self::Renamed,
 			Delta::Typechange => Self::Typechange,
@@ -63,8 +77,13 @@ impl From<Delta> for StatusItemType {
 pub struct StatusItem {
 	///
 	pub path: String,
+	/// path the file was renamed from (only set for renames)
+	pub old_path: Option<String>,
 	///
 	pub status: StatusItemType,
+	/// size of a binary file (as staged or in the working dir),
+	/// `None` for text files and deletions
+	pub binary_size: Option<u64>,
 }
 
 ///
@@ -94,15 +113,50 @@ impl From<StatusType> for StatusShow {
 	}
 }
 
+/// result of a status query that might have stopped enumerating
+/// untracked files early (see `get_status_limited`)
+#[derive(Default, Clone, Hash, PartialEq, Debug)]
+pub struct StatusItems {
+	///
+	pub items: Vec<StatusItem>,
+	/// `true` if untracked files beyond the limit were skipped
+	pub untracked_truncated: bool,
+}
+
 /// gurantees sorting
 pub fn get_status(
 	repo_path: &str,
 	status_type: StatusType,
 	show_untracked: Option<ShowUntrackedFilesConfig>,
 ) -> Result<Vec<StatusItem>> {
-	scope_time!("get_status");
+	get_status_limited(
+		repo_path,
+		status_type,
+		show_untracked,
+		None,
+		&|_| (),
+	)
+	.map(|res| res.items)
+}
+
+/// same as `get_status` but stops enumerating untracked files once
+/// `untracked_limit` is reached.
+///
+/// libgit2 has no way to cap the untracked enumeration, so instead of
+/// letting it recurse we ask for untracked dirs as single entries and
+/// expand them ourselves. `progress` is called with the number of
+/// untracked files found so far.
+pub fn get_status_limited(
+	repo_path: &str,
+	status_type: StatusType,
+	show_untracked: Option<ShowUntrackedFilesConfig>,
+	untracked_limit: Option<usize>,
+	progress: &dyn Fn(usize),
+) -> Result<StatusItems> {
+	scope_stats!("get_status");
 
 	let repo = utils::repo(repo_path)?;
+	let work_dir = utils::work_dir(&repo)?;
 
 	let show_untracked = if let Some(config) = show_untracked {
 		config
@@ -110,6 +164,10 @@ pub fn get_status(
 		untracked_files_config_repo(&repo)?
 	};
 
+	let expand_untracked = show_untracked.recurse_untracked_dirs()
+		&& untracked_limit.is_some();
+	let limit = untracked_limit.unwrap_or(usize::MAX);
+
 	let mut options = StatusOptions::default();
 	options
 		.show(status_type.into())
@@ -117,16 +175,27 @@ pub fn get_status(
 		.include_untracked(show_untracked.include_untracked())
 		.renames_head_to_index(true)
 		.recurse_untracked_dirs(
-			show_untracked.recurse_untracked_dirs(),
+			show_untracked.recurse_untracked_dirs()
+				&& !expand_untracked,
 		);
 
 	let statuses = repo.statuses(Some(&mut options))?;
 
-	let mut res = Vec::with_capacity(statuses.len());
+	let mut res = Vec::with_capacity(statuses.len().min(limit));
+	let mut untracked_dirs = Vec::new();
+	let mut untracked_count = 0_usize;
+	let mut untracked_truncated = false;
 
 	for e in statuses.iter() {
 		let status: Status = e.status();
 
+		let old_path = e
+			.head_to_index()
+			.filter(|_| status.is_index_renamed())
+			.and_then(|diff| diff.old_file().path())
+			.and_then(Path::to_str)
+			.map(String::from);
+
 		let path = match e.head_to_index() {
 			Some(diff) => diff
 				.new_file()
@@ -147,15 +216,512 @@ pub fn get_status(
 			})?,
 		};
 
+		if status.is_wt_new() {
+			if expand_untracked && path.ends_with('/') {
+				untracked_dirs.push(path);
+				continue;
+			}
+
+			if untracked_count >= limit {
+				untracked_truncated = true;
+				continue;
+			}
+
+			untracked_count += 1;
+		}
+
+		let binary_size =
+			entry_binary_size(&repo, work_dir, &e, &path);
+
 		res.push(StatusItem {
 			path,
+			old_path,
 			status: StatusItemType::from(status),
+			binary_size,
 		});
 	}
 
+	for dir in untracked_dirs {
+		if untracked_truncated {
+			break;
+		}
+
+		untracked_truncated = expand_untracked_dir(
+			&repo,
+			&dir,
+			limit,
+			&mut untracked_count,
+			&mut res,
+			progress,
+		)?;
+	}
+
 	res.sort_by(|a, b| {
 		Path::new(a.path.as_str()).cmp(Path::new(b.path.as_str()))
 	});
 
+	Ok(StatusItems {
+		items: res,
+		untracked_truncated,
+	})
+}
+
+/// the working dir file takes precedence over the staged one
+fn entry_binary_size(
+	repo: &Repository,
+	work_dir: &Path,
+	entry: &StatusEntry,
+	path: &str,
+) -> Option<u64> {
+	let status = entry.status();
+
+	if status.intersects(
+		Status::WT_NEW | Status::WT_MODIFIED | Status::WT_TYPECHANGE,
+	) {
+		workdir_binary_size(&work_dir.join(path))
+	} else {
+		entry
+			.head_to_index()
+			.filter(|_| !status.is_index_deleted())
+			.and_then(|diff| {
+				staged_binary_size(
+					repo,
+					&work_dir.join(path),
+					diff.new_file().id(),
+				)
+			})
+	}
+}
+
+/// walks an untracked directory (as reported by libgit2 without
+/// recursion) adding every non-ignored file to `res`.
+/// returns `true` if the walk stopped because `limit` was reached.
+fn expand_untracked_dir(
+	repo: &Repository,
+	dir: &str,
+	limit: usize,
+	count: &mut usize,
+	res: &mut Vec<StatusItem>,
+	progress: &dyn Fn(usize),
+) -> Result<bool> {
+	const PROGRESS_STEP: usize = 500;
+
+	let work_dir = utils::work_dir(repo)?;
+	let mut stack = vec![PathBuf::from(dir)];
+
+	while let Some(rel_dir) = stack.pop() {
+		let mut entries = fs::read_dir(work_dir.join(&rel_dir))?
+			.filter_map(std::result::Result::ok)
+			.collect::<Vec<_>>();
+		entries.sort_by_key(fs::DirEntry::file_name);
+
+		for entry in entries {
+			let rel_path = rel_dir.join(entry.file_name());
+			let is_dir = entry.file_type()?.is_dir();
+
+			let mut path = rel_path
+				.to_str()
+				.map(|p| p.replace('\\', "/"))
+				.ok_or_else(|| {
+					Error::Generic(
+						"failed to get path of untracked file."
+							.to_string(),
+					)
+				})?;
+
+			// directory only ignore rules need the trailing slash
+			if is_dir {
+				path.push('/');
+			}
+
+			if repo.is_path_ignored(&path)? {
+				continue;
+			}
+
+			// nested repositories show up as a single entry just like
+			// libgit2 reports them
+			if is_dir && !entry.path().join(".git").exists() {
+				stack.push(rel_path);
+				continue;
+			}
+
+			if *count >= limit {
+				return Ok(true);
+			}
+
+			res.push(StatusItem {
+				binary_size: workdir_binary_size(&entry.path()),
+				path,
+				old_path: None,
+				status: StatusItemType::New,
+			});
+
+			*count += 1;
+			if *count % PROGRESS_STEP == 0 {
+				progress(*count);
+			}
+		}
+	}
+
+	Ok(false)
+}
+
+/// `(old, new)` paths that only differ in case, between `HEAD` and
+/// the index (`Stage`) or the index and the files on disk
+/// (`WorkingDir`).
+///
+/// only meaningful on a case-insensitive filesystem (see
+/// `is_fs_case_insensitive`), renames of directories are not detected
+pub fn case_only_renames(
+	repo_path: &str,
+	status_type: StatusType,
+) -> Result<Vec<(String, String)>> {
+	scope_time!("case_only_renames");
+
+	let repo = utils::repo(repo_path)?;
+
+	match status_type {
+		StatusType::WorkingDir => workdir_case_renames(&repo),
+		StatusType::Stage => index_case_renames(&repo),
+		StatusType::Both => Ok(Vec::new()),
+	}
+}
+
+/// replaces whatever libgit2 reported for either side of the
+/// `renames` by a single `Renamed` item
+pub fn apply_case_only_renames(
+	items: &mut Vec<StatusItem>,
+	renames: Vec<(String, String)>,
+) {
+	if renames.is_empty() {
+		return;
+	}
+
+	let paths = renames
+		.iter()
+		.flat_map(|(old, new)| vec![old.as_str(), new.as_str()])
+		.collect::<HashSet<_>>();
+	items.retain(|item| !paths.contains(item.path.as_str()));
+
+	items.extend(renames.into_iter().map(|(old, new)| StatusItem {
+		path: new,
+		old_path: Some(old),
+		status: StatusItemType::Renamed,
+		binary_size: None,
+	}));
+
+	items.sort_by(|a, b| {
+		Path::new(a.path.as_str()).cmp(Path::new(b.path.as_str()))
+	});
+}
+
+fn split_path(path: &str) -> (&str, &str) {
+	path.rfind('/')
+		.map_or(("", path), |idx| (&path[..idx], &path[idx + 1..]))
+}
+
+fn join_path(dir: &str, name: &str) -> String {
+	if dir.is_empty() {
+		name.to_string()
+	} else {
+		format!("{}/{}", dir, name)
+	}
+}
+
+/// tracked files missing on disk by their exact name but present
+/// with a different case (and not tracked by that name)
+fn workdir_case_renames(
+	repo: &Repository,
+) -> Result<Vec<(String, String)>> {
+	let work_dir = utils::work_dir(repo)?;
+	let index = repo.index()?;
+
+	let tracked = index
+		.iter()
+		.filter_map(|entry| String::from_utf8(entry.path).ok())
+		.collect::<HashSet<_>>();
+
+	// names in each dir, read once
+	let mut dirs: HashMap<String, Vec<String>> = HashMap::new();
+	let mut res = Vec::new();
+
+	for path in &tracked {
+		let (dir, name) = split_path(path);
+
+		let names =
+			dirs.entry(dir.to_string()).or_insert_with(|| {
+				fs::read_dir(work_dir.join(dir))
+					.map(|entries| {
+						entries
+							.filter_map(std::result::Result::ok)
+							.filter_map(|e| {
+								e.file_name().into_string().ok()
+							})
+							.collect()
+					})
+					.unwrap_or_default()
+			});
+
+		if names.iter().any(|n| n == name) {
+			continue;
+		}
+
+		let lower = name.to_lowercase();
+		if let Some(found) =
+			names.iter().find(|n| n.to_lowercase() == lower)
+		{
+			let new_path = join_path(dir, found);
+			if !tracked.contains(&new_path) {
+				res.push((path.clone(), new_path));
+			}
+		}
+	}
+
+	res.sort();
+
 	Ok(res)
 }
+
+/// files of `HEAD` missing in the index by their exact name but
+/// staged with a different case (and not in `HEAD` by that name)
+fn index_case_renames(
+	repo: &Repository,
+) -> Result<Vec<(String, String)>> {
+	let tree = match utils::get_head_repo(repo) {
+		Ok(id) => repo.find_commit(id.into())?.tree()?,
+		// nothing to rename from on an unborn branch
+		Err(_) => return Ok(Vec::new()),
+	};
+
+	let mut head_paths = HashSet::new();
+	tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+		if entry.kind() == Some(git2::ObjectType::Blob) {
+			if let Some(name) = entry.name() {
+				head_paths.insert(format!("{}{}", dir, name));
+			}
+		}
+		TreeWalkResult::Ok
+	})?;
+
+	let index = repo.index()?;
+	let staged = index
+		.iter()
+		.filter_map(|entry| String::from_utf8(entry.path).ok())
+		.collect::<HashSet<_>>();
+
+	let missing = head_paths
+		.iter()
+		.filter(|path| !staged.contains(*path))
+		.map(|path| (path.to_lowercase(), path))
+		.collect::<HashMap<_, _>>();
+
+	let mut res = staged
+		.iter()
+		.filter(|path| !head_paths.contains(*path))
+		.filter_map(|path| {
+			missing
+				.get(&path.to_lowercase())
+				.map(|old| ((*old).clone(), path.clone()))
+		})
+		.collect::<Vec<_>>();
+
+	res.sort();
+
+	Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		stage_renamed,
+		tests::{repo_init, write_commit_file},
+	};
+	use std::{fs::File, io::Write, time::Instant};
+
+	fn create_untracked_files(
+		root: &Path,
+		dirs: usize,
+		files: usize,
+	) {
+		for d in 0..dirs {
+			let dir = root.join(format!("node_modules/pkg{}", d));
+			fs::create_dir_all(&dir).unwrap();
+			for f in 0..files {
+				File::create(dir.join(format!("file{}.js", f)))
+					.unwrap()
+					.write_all(b"x")
+					.unwrap();
+			}
+		}
+	}
+
+	#[test]
+	fn test_untracked_limit() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		create_untracked_files(root, 100, 100);
+
+		let start = Instant::now();
+		let res = get_status_limited(
+			repo_path,
+			StatusType::WorkingDir,
+			Some(ShowUntrackedFilesConfig::All),
+			Some(1000),
+			&|_| (),
+		)
+		.unwrap();
+		let elapsed = start.elapsed();
+
+		assert_eq!(res.items.len(), 1000);
+		assert!(res.untracked_truncated);
+		assert!(elapsed.as_secs() < 5, "took {:?}", elapsed);
+	}
+
+	#[test]
+	fn test_untracked_limit_not_reached() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		create_untracked_files(root, 2, 3);
+		File::create(root.join(".gitignore"))
+			.unwrap()
+			.write_all(b"node_modules/pkg1\n")
+			.unwrap();
+
+		let res = get_status_limited(
+			repo_path,
+			StatusType::WorkingDir,
+			Some(ShowUntrackedFilesConfig::All),
+			Some(1000),
+			&|_| (),
+		)
+		.unwrap();
+
+		let unlimited = get_status(
+			repo_path,
+			StatusType::WorkingDir,
+			Some(ShowUntrackedFilesConfig::All),
+		)
+		.unwrap();
+
+		assert!(!res.untracked_truncated);
+		assert_eq!(res.items.len(), 4);
+		assert_eq!(res.items, unlimited);
+	}
+
+	#[test]
+	fn test_untracked_progress() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		create_untracked_files(root, 10, 100);
+
+		let reported = std::cell::Cell::new(0);
+		let res = get_status_limited(
+			repo_path,
+			StatusType::WorkingDir,
+			Some(ShowUntrackedFilesConfig::All),
+			Some(usize::MAX),
+			&|count| reported.set(count),
+		)
+		.unwrap();
+
+		assert!(!res.untracked_truncated);
+		assert_eq!(res.items.len(), 1000);
+		assert_eq!(reported.get(), 1000);
+	}
+
+	/// status as shown with case-only rename detection enabled
+	fn case_status(
+		repo_path: &str,
+		status_type: StatusType,
+	) -> Vec<StatusItem> {
+		let mut items =
+			get_status(repo_path, status_type, None).unwrap();
+		apply_case_only_renames(
+			&mut items,
+			case_only_renames(repo_path, status_type).unwrap(),
+		);
+		items
+	}
+
+	fn renamed(old: &str, new: &str) -> StatusItem {
+		StatusItem {
+			path: new.to_string(),
+			old_path: Some(old.to_string()),
+			status: StatusItemType::Renamed,
+			binary_size: None,
+		}
+	}
+
+	// a case-sensitive filesystem shows what a case-insensitive one
+	// would hide: the old name missing and the new one untracked
+	#[test]
+	fn test_case_only_rename() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "Readme.md", "test", "c1");
+		fs::create_dir(root.join("sub")).unwrap();
+		write_commit_file(&repo, "sub/a.txt", "test", "c2");
+
+		fs::rename(root.join("Readme.md"), root.join("README.md"))
+			.unwrap();
+		fs::rename(root.join("sub/a.txt"), root.join("sub/A.txt"))
+			.unwrap();
+
+		assert_eq!(
+			case_status(repo_path, StatusType::WorkingDir),
+			vec![
+				renamed("Readme.md", "README.md"),
+				renamed("sub/a.txt", "sub/A.txt"),
+			]
+		);
+
+		stage_renamed(
+			repo_path,
+			Path::new("Readme.md"),
+			Path::new("README.md"),
+		)
+		.unwrap();
+
+		assert_eq!(
+			case_status(repo_path, StatusType::WorkingDir),
+			vec![renamed("sub/a.txt", "sub/A.txt")]
+		);
+		assert_eq!(
+			case_status(repo_path, StatusType::Stage),
+			vec![renamed("Readme.md", "README.md")]
+		);
+
+		let mut index = repo.index().unwrap();
+		index.read(true).unwrap();
+		assert!(index.get_path(Path::new("Readme.md"), 0).is_none());
+		assert!(index.get_path(Path::new("README.md"), 0).is_some());
+	}
+
+	#[test]
+	fn test_case_only_rename_both_tracked() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", "a", "c1");
+		write_commit_file(&repo, "A.txt", "b", "c2");
+
+		fs::remove_file(root.join("a.txt")).unwrap();
+
+		assert!(case_only_renames(repo_path, StatusType::WorkingDir)
+			.unwrap()
+			.is_empty());
+		assert!(case_only_renames(repo_path, StatusType::Stage)
+			.unwrap()
+			.is_empty());
+	}
+}
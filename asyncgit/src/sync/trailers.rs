@@ -0,0 +1,168 @@
+use super::{commit::signature_allow_undefined_name, utils::repo};
+use crate::error::Result;
+use scopetime::scope_time;
+
+/// `Signed-off-by` trailer built from `user.name` and `user.email`
+pub fn signoff_trailer(repo_path: &str) -> Result<String> {
+	scope_time!("signoff_trailer");
+
+	let repo = repo(repo_path)?;
+	let signature = signature_allow_undefined_name(&repo)?;
+
+	Ok(format!(
+		"Signed-off-by: {} <{}>",
+		signature.name().unwrap_or_default(),
+		signature.email().unwrap_or_default()
+	))
+}
+
+/// appends all `trailers` that are not yet part of `message`.
+///
+/// trailers are added to an existing trailer block at the end of the
+/// message or after a blank line otherwise, see
+/// <https://git-scm.com/docs/git-interpret-trailers>
+pub fn apply_trailers(message: &str, trailers: &[String]) -> String {
+	let mut missing: Vec<&str> = Vec::new();
+	for trailer in trailers.iter().map(|t| t.trim()) {
+		if !trailer.is_empty()
+			&& !missing.contains(&trailer)
+			&& !message.lines().any(|l| l.trim_end() == trailer)
+		{
+			missing.push(trailer);
+		}
+	}
+
+	if missing.is_empty() {
+		return message.to_string();
+	}
+
+	let message = message.trim_end();
+
+	if message.is_empty() {
+		return missing.join("\n");
+	}
+
+	let separator = if ends_with_trailers(message) {
+		"\n"
+	} else {
+		"\n\n"
+	};
+
+	format!("{}{}{}", message, separator, missing.join("\n"))
+}
+
+/// the subject line alone never counts as a trailer block
+fn ends_with_trailers(message: &str) -> bool {
+	message.rfind("\n\n").map_or(false, |idx| {
+		let mut lines = message[idx..]
+			.lines()
+			.filter(|l| !l.is_empty())
+			.filter(|l| !l.starts_with('#'))
+			.peekable();
+
+		lines.peek().is_some() && lines.all(is_trailer)
+	})
+}
+
+fn is_trailer(line: &str) -> bool {
+	line.find(':').map_or(false, |idx| {
+		idx > 0
+			&& line[..idx]
+				.chars()
+				.all(|c| c.is_ascii_alphanumeric() || c == '-')
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::tests::repo_init;
+
+	fn signoff() -> Vec<String> {
+		vec![String::from("Signed-off-by: name <email>")]
+	}
+
+	#[test]
+	fn test_subject_only() {
+		assert_eq!(
+			apply_trailers("subject", &signoff()),
+			"subject\n\nSigned-off-by: name <email>"
+		);
+	}
+
+	#[test]
+	fn test_subject_looks_like_trailer() {
+		assert_eq!(
+			apply_trailers("fix: subject\n", &signoff()),
+			"fix: subject\n\nSigned-off-by: name <email>"
+		);
+	}
+
+	#[test]
+	fn test_empty() {
+		assert_eq!(
+			apply_trailers("", &signoff()),
+			"Signed-off-by: name <email>"
+		);
+		assert_eq!(
+			apply_trailers(" \n\n", &signoff()),
+			"Signed-off-by: name <email>"
+		);
+	}
+
+	#[test]
+	fn test_multi_paragraph_body() {
+		let msg =
+			"subject\n\nfirst paragraph\n\nsecond paragraph\nmore";
+
+		assert_eq!(
+			apply_trailers(msg, &signoff()),
+			format!("{}\n\nSigned-off-by: name <email>", msg)
+		);
+	}
+
+	#[test]
+	fn test_existing_trailers() {
+		let msg = "subject\n\nbody\n\nChange-Id: I123\nReviewed-by: other <o@o>\n";
+
+		assert_eq!(
+			apply_trailers(msg, &signoff()),
+			"subject\n\nbody\n\nChange-Id: I123\nReviewed-by: other <o@o>\nSigned-off-by: name <email>"
+		);
+	}
+
+	#[test]
+	fn test_idempotent() {
+		let once = apply_trailers("subject\n\nbody", &signoff());
+		let twice = apply_trailers(&once, &signoff());
+
+		assert_eq!(once, twice);
+	}
+
+	#[test]
+	fn test_multiple_trailers() {
+		let trailers = vec![
+			String::from("Team: core"),
+			String::from(" "),
+			String::from("Team: core"),
+			String::from("Change-Id: I0"),
+		];
+
+		assert_eq!(
+			apply_trailers("subject\n\nChange-Id: I0", &trailers),
+			"subject\n\nChange-Id: I0\nTeam: core"
+		);
+	}
+
+	#[test]
+	fn test_signoff_trailer() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		assert_eq!(
+			signoff_trailer(repo_path).unwrap(),
+			"Signed-off-by: name <email>"
+		);
+	}
+}
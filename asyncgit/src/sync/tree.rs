@@ -10,6 +10,32 @@ use std::{
 	path::{Path, PathBuf},
 };
 
+/// symlink targets are only read up to this blob size
+const SYMLINK_TARGET_MAX_SIZE: usize = 1024;
+
+///
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TreeFileKind {
+	///
+	File,
+	///
+	Executable,
+	///
+	Symlink,
+}
+
+impl From<i32> for TreeFileKind {
+	fn from(filemode: i32) -> Self {
+		if filemode == i32::from(git2::FileMode::BlobExecutable) {
+			Self::Executable
+		} else if filemode == i32::from(git2::FileMode::Link) {
+			Self::Symlink
+		} else {
+			Self::File
+		}
+	}
+}
+
 /// `tree_files` returns a list of `FileTree`
 #[derive(Debug, PartialEq, Clone)]
 pub struct TreeFile {
@@ -17,6 +43,12 @@ pub struct TreeFile {
 	pub path: PathBuf,
 	/// unix filemode
 	pub filemode: i32,
+	///
+	pub kind: TreeFileKind,
+	/// blob size in bytes
+	pub size: usize,
+	/// target of small enough symlinks
+	pub link_target: Option<String>,
 	// internal object id
 	id: Oid,
 }
@@ -91,6 +123,43 @@ pub fn tree_file_content(
 	Ok(content)
 }
 
+/// raw content of `file_path` as staged in the index
+pub fn index_file_blob(
+	repo_path: &str,
+	file_path: &str,
+) -> Result<Vec<u8>> {
+	scope_time!("index_file_blob");
+
+	let repo = repo(repo_path)?;
+	let index = repo.index()?;
+
+	let entry =
+		index.get_path(Path::new(file_path), 0).ok_or_else(|| {
+			Error::Generic(format!("'{}' is not staged", file_path))
+		})?;
+
+	let blob = repo.find_blob(entry.id)?;
+
+	Ok(blob.content().to_vec())
+}
+
+/// raw content of `file_path` in the tree of `commit`
+pub fn commit_file_blob(
+	repo_path: &str,
+	commit: CommitId,
+	file_path: &str,
+) -> Result<Vec<u8>> {
+	scope_time!("commit_file_blob");
+
+	let repo = repo(repo_path)?;
+
+	let tree = repo.find_commit(commit.into())?.tree()?;
+	let entry = tree.get_path(Path::new(file_path))?;
+	let blob = entry.to_object(&repo)?.peel_to_blob()?;
+
+	Ok(blob.content().to_vec())
+}
+
 ///
 fn tree_recurse(
 	repo: &Repository,
@@ -100,6 +169,8 @@ fn tree_recurse(
 ) -> Result<()> {
 	out.reserve(tree.len());
 
+	let odb = repo.odb()?;
+
 	for e in tree {
 		let p = String::from_utf8_lossy(e.name_bytes());
 		let path = path.join(p.to_string());
@@ -107,7 +178,29 @@ fn tree_recurse(
 			Some(git2::ObjectType::Blob) => {
 				let id = e.id();
 				let filemode = e.filemode();
-				out.push(TreeFile { path, filemode, id });
+				let kind = TreeFileKind::from(filemode);
+				let (size, _) = odb.read_header(id)?;
+
+				let link_target = if kind == TreeFileKind::Symlink
+					&& size <= SYMLINK_TARGET_MAX_SIZE
+				{
+					let blob = repo.find_blob(id)?;
+					Some(
+						String::from_utf8_lossy(blob.content())
+							.to_string(),
+					)
+				} else {
+					None
+				};
+
+				out.push(TreeFile {
+					path,
+					filemode,
+					kind,
+					size,
+					link_target,
+					id,
+				});
 			}
 			Some(git2::ObjectType::Tree) => {
 				let obj = e.to_object(repo)?;
@@ -153,6 +246,71 @@ mod tests {
 		assert_ne!(files_c2[0], files[0]);
 	}
 
+	#[test]
+	fn test_file_blobs() {
+		use crate::sync::stage_add_file;
+
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let c1 =
+			write_commit_file(&repo, "test.txt", "content", "c1");
+
+		std::fs::write(root.join("test.txt"), "staged").unwrap();
+		stage_add_file(repo_path, Path::new("test.txt")).unwrap();
+
+		assert_eq!(
+			commit_file_blob(repo_path, c1, "test.txt").unwrap(),
+			b"content"
+		);
+		assert_eq!(
+			index_file_blob(repo_path, "test.txt").unwrap(),
+			b"staged"
+		);
+		assert!(index_file_blob(repo_path, "missing.txt").is_err());
+		assert!(
+			commit_file_blob(repo_path, c1, "missing.txt").is_err()
+		);
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_kinds() {
+		use crate::sync::{commit, stage_add_file};
+		use std::{fs, os::unix::fs::PermissionsExt};
+
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::write(root.join("run.sh"), "echo").unwrap();
+		fs::set_permissions(
+			root.join("run.sh"),
+			fs::Permissions::from_mode(0o755),
+		)
+		.unwrap();
+		std::os::unix::fs::symlink("run.sh", root.join("link"))
+			.unwrap();
+
+		stage_add_file(repo_path, Path::new("run.sh")).unwrap();
+		stage_add_file(repo_path, Path::new("link")).unwrap();
+		let c1 = commit(repo_path, "c1", false).unwrap();
+
+		let files = tree_files(repo_path, c1).unwrap();
+
+		assert_eq!(files.len(), 2);
+		assert_eq!(files[0].path, PathBuf::from("./link"));
+		assert_eq!(files[0].kind, TreeFileKind::Symlink);
+		assert_eq!(
+			files[0].link_target,
+			Some(String::from("run.sh"))
+		);
+		assert_eq!(files[1].kind, TreeFileKind::Executable);
+		assert_eq!(files[1].size, 4);
+		assert_eq!(files[1].link_target, None);
+	}
+
 	#[test]
 	fn test_sorting() {
 		let mut list = vec!["file", "folder/file", "folder/afile"]
@@ -160,6 +318,9 @@ mod tests {
 			.map(|f| TreeFile {
 				path: PathBuf::from(f),
 				filemode: 0,
+				kind: TreeFileKind::File,
+				size: 0,
+				link_target: None,
 				id: Oid::zero(),
 			})
 			.collect();
@@ -185,6 +346,9 @@ mod tests {
 			.map(|f| TreeFile {
 				path: PathBuf::from(f),
 				filemode: 0,
+				kind: TreeFileKind::File,
+				size: 0,
+				link_target: None,
 				id: Oid::zero(),
 			})
 			.collect();
@@ -209,6 +373,9 @@ mod tests {
 			.map(|f| TreeFile {
 				path: PathBuf::from(f),
 				filemode: 0,
+				kind: TreeFileKind::File,
+				size: 0,
+				link_target: None,
 				id: Oid::zero(),
 			})
 			.collect();
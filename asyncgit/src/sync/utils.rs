@@ -3,9 +3,15 @@
 use super::CommitId;
 use crate::{
 	error::{Error, Result},
-	sync::config::untracked_files_config_repo,
+	sync::{
+		config::untracked_files_config_repo,
+		index_lock::retry_on_index_lock,
+	},
+};
+use git2::{
+	Branch, Config, ErrorCode, IndexAddOption, Repository,
+	RepositoryInitOptions, RepositoryOpenFlags,
 };
-use git2::{IndexAddOption, Repository, RepositoryOpenFlags};
 use scopetime::scope_time;
 use std::{
 	fs::File,
@@ -43,6 +49,48 @@ pub fn is_bare_repo(repo_path: &str) -> Result<bool> {
 	Ok(repo.is_bare())
 }
 
+/// git config naming the branch of new repos
+const INIT_DEFAULT_BRANCH: &str = "init.defaultBranch";
+const DEFAULT_BRANCH: &str = "main";
+
+/// branch new repos start on: `init.defaultBranch` of the global
+/// config or `main`
+pub fn default_initial_branch() -> String {
+	Config::open_default()
+		.and_then(|config| config.get_string(INIT_DEFAULT_BRANCH))
+		.ok()
+		.map(|name| name.trim().to_string())
+		.filter(|name| !name.is_empty())
+		.unwrap_or_else(|| String::from(DEFAULT_BRANCH))
+}
+
+/// work dir (or git dir if bare) of the repo `path` is part of
+pub fn enclosing_repo(path: &str) -> Option<PathBuf> {
+	let repo = Repository::discover(path).ok()?;
+
+	Some(repo.workdir().unwrap_or_else(|| repo.path()).to_path_buf())
+}
+
+/// creates a repo at `path`, HEAD points to the unborn
+/// `initial_branch`
+pub fn init_repo(path: &str, initial_branch: &str) -> Result<()> {
+	scope_time!("init_repo");
+
+	if !Branch::name_is_valid(initial_branch)? {
+		return Err(Error::Generic(format!(
+			"invalid branch name: {}",
+			initial_branch
+		)));
+	}
+
+	let mut options = RepositoryInitOptions::new();
+	options.initial_head(initial_branch);
+
+	Repository::init_opts(path, &options)?;
+
+	Ok(())
+}
+
 ///
 pub(crate) fn repo(repo_path: &str) -> Result<Repository> {
 	let repo = Repository::open_ext(
@@ -110,18 +158,30 @@ pub fn get_head_repo(repo: &Repository) -> Result<CommitId> {
 	head.map_or(Err(Error::NoHead), |head_id| Ok(head_id.into()))
 }
 
+/// `true` if HEAD points to a branch without any commits yet
+/// (e.g. right after `git init`)
+pub fn is_head_unborn(repo: &Repository) -> Result<bool> {
+	match repo.head() {
+		Ok(_) => Ok(false),
+		Err(e) if e.code() == ErrorCode::UnbornBranch => Ok(true),
+		Err(e) => Err(e.into()),
+	}
+}
+
 /// add a file diff from workingdir to stage (will not add removed files see `stage_addremoved`)
 pub fn stage_add_file(repo_path: &str, path: &Path) -> Result<()> {
 	scope_time!("stage_add_file");
 
 	let repo = repo(repo_path)?;
 
-	let mut index = repo.index()?;
+	retry_on_index_lock(repo_path, || {
+		let mut index = repo.index()?;
 
-	index.add_path(path)?;
-	index.write()?;
+		index.add_path(path)?;
+		index.write()?;
 
-	Ok(())
+		Ok(())
+	})
 }
 
 /// like `stage_add_file` but uses a pattern to match/glob multiple files/folders
@@ -130,23 +190,25 @@ pub fn stage_add_all(repo_path: &str, pattern: &str) -> Result<()> {
 
 	let repo = repo(repo_path)?;
 
-	let mut index = repo.index()?;
-
 	let config = untracked_files_config_repo(&repo)?;
 
-	if config.include_none() {
-		index.update_all(vec![pattern], None)?;
-	} else {
-		index.add_all(
-			vec![pattern],
-			IndexAddOption::DEFAULT,
-			None,
-		)?;
-	}
+	retry_on_index_lock(repo_path, || {
+		let mut index = repo.index()?;
 
-	index.write()?;
+		if config.include_none() {
+			index.update_all(vec![pattern], None)?;
+		} else {
+			index.add_all(
+				vec![pattern],
+				IndexAddOption::DEFAULT,
+				None,
+			)?;
+		}
 
-	Ok(())
+		index.write()?;
+
+		Ok(())
+	})
 }
 
 /// Undo last commit in repo
@@ -170,12 +232,52 @@ pub fn stage_addremoved(repo_path: &str, path: &Path) -> Result<()> {
 
 	let repo = repo(repo_path)?;
 
-	let mut index = repo.index()?;
+	retry_on_index_lock(repo_path, || {
+		let mut index = repo.index()?;
 
-	index.remove_path(path)?;
-	index.write()?;
+		index.remove_path(path)?;
+		index.write()?;
 
-	Ok(())
+		Ok(())
+	})
+}
+
+/// stage a file renamed on disk (see `case_only_renames`): the old
+/// entry is replaced by the new one instead of staging only the
+/// new side
+pub fn stage_renamed(
+	repo_path: &str,
+	old_path: &Path,
+	path: &Path,
+) -> Result<()> {
+	scope_time!("stage_renamed");
+
+	let repo = repo(repo_path)?;
+
+	retry_on_index_lock(repo_path, || {
+		let mut index = repo.index()?;
+
+		index.remove_path(old_path)?;
+		index.add_path(path)?;
+		index.write()?;
+
+		Ok(())
+	})
+}
+
+/// creates a mixed case file in the git dir and checks whether it
+/// can be found by another case
+pub fn is_fs_case_insensitive(repo_path: &str) -> Result<bool> {
+	let repo = repo(repo_path)?;
+
+	let name = format!("gitui-Case-Probe-{}", std::process::id());
+	let probe = repo.path().join(&name);
+
+	File::create(&probe)?;
+	let insensitive = repo.path().join(name.to_uppercase()).exists();
+	std::fs::remove_file(&probe)?;
+
+	Ok(insensitive)
 }
 
 pub(crate) fn bytes2string(bytes: &[u8]) -> Result<String> {
@@ -225,7 +327,7 @@ mod tests {
 		status::{get_status, StatusType},
 		tests::{
 			debug_cmd_print, get_statuses, repo_init,
-			repo_init_empty, write_commit_file,
+			repo_init_empty, sandbox_config_files, write_commit_file,
 		},
 	};
 	use std::{
@@ -233,6 +335,76 @@ mod tests {
 		io::Write,
 		path::Path,
 	};
+	use tempfile::TempDir;
+
+	#[test]
+	fn test_init_repo() {
+		let td = TempDir::new().unwrap();
+		let repo_path = td.path().join("new");
+		let repo_path = repo_path.to_str().unwrap();
+
+		assert!(!is_repo(repo_path));
+		init_repo(repo_path, "trunk").unwrap();
+		assert!(is_repo(repo_path));
+		assert!(!is_bare_repo(repo_path).unwrap());
+
+		let repo = repo(repo_path).unwrap();
+		assert!(is_head_unborn(&repo).unwrap());
+		assert_eq!(
+			repo.find_reference("HEAD")
+				.unwrap()
+				.symbolic_target()
+				.unwrap(),
+			"refs/heads/trunk"
+		);
+
+		// the first commit creates the branch
+		repo.config().unwrap().set_str("user.name", "name").unwrap();
+		repo.config()
+			.unwrap()
+			.set_str("user.email", "email")
+			.unwrap();
+		let id = write_commit_file(&repo, "a.txt", "a", "initial");
+		assert_eq!(get_head(repo_path).unwrap(), id);
+		assert!(repo
+			.find_branch("trunk", git2::BranchType::Local)
+			.is_ok());
+	}
+
+	#[test]
+	fn test_init_repo_invalid_branch() {
+		let td = TempDir::new().unwrap();
+		let repo_path = td.path().to_str().unwrap();
+
+		assert!(init_repo(repo_path, "no..name").is_err());
+		assert!(init_repo(repo_path, "").is_err());
+		assert!(!is_repo(repo_path));
+	}
+
+	#[test]
+	fn test_enclosing_repo() {
+		let (td, _repo) = repo_init().unwrap();
+		let sub = td.path().join("sub");
+		fs::create_dir(&sub).unwrap();
+
+		assert_eq!(
+			enclosing_repo(sub.to_str().unwrap())
+				.unwrap()
+				.canonicalize()
+				.unwrap(),
+			td.path().canonicalize().unwrap()
+		);
+
+		let td = TempDir::new().unwrap();
+		assert!(enclosing_repo(td.path().to_str().unwrap()).is_none());
+	}
+
+	#[test]
+	fn test_default_initial_branch() {
+		sandbox_config_files();
+
+		assert_eq!(default_initial_branch(), "main");
+	}
 
 	#[test]
 	fn test_stage_add_smoke() {
@@ -381,7 +553,7 @@ mod tests {
 
 		stage_add_file(repo_path, file_path).unwrap();
 
-		commit(repo_path, "commit msg").unwrap();
+		commit(repo_path, "commit msg", false).unwrap();
 
 		// delete the file now
 		assert_eq!(remove_file(full_path).is_ok(), true);
@@ -436,6 +608,17 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_head_unborn() -> Result<()> {
+		let (_td, repo) = repo_init_empty()?;
+		assert!(is_head_unborn(&repo)?);
+
+		let (_td, repo) = repo_init()?;
+		assert!(!is_head_unborn(&repo)?);
+
+		Ok(())
+	}
+
 	#[test]
 	fn test_head() -> Result<()> {
 		let (_td, repo) = repo_init()?;
@@ -446,4 +629,18 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_case_probe_cleanup() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let before = fs::read_dir(repo.path())?.count();
+		is_fs_case_insensitive(repo_path)?;
+
+		assert_eq!(fs::read_dir(repo.path())?.count(), before);
+
+		Ok(())
+	}
 }
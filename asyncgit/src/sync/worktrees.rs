@@ -0,0 +1,192 @@
+//! the worktrees of a repo and a short summary of a repo to choose
+//! one to switch to
+
+use super::{
+	branch::get_branch_name_repo,
+	utils::{repo, work_dir},
+};
+use crate::error::{Error, Result};
+use git2::{Repository, Status, StatusOptions};
+use scopetime::scope_time;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// the repo of the main worktree, `repo` itself if it is not a
+/// linked worktree
+fn main_repo(repo: Repository) -> Result<Repository> {
+	if repo.is_worktree() {
+		// the git dir of a linked worktree is `<common>/worktrees/<name>`
+		let common =
+			repo.path().ancestors().nth(2).ok_or_else(|| {
+				Error::Generic(String::from("no common dir"))
+			})?;
+		Ok(Repository::open(common)?)
+	} else {
+		Ok(repo)
+	}
+}
+
+/// work dirs of all worktrees sharing the repository of `repo_path`,
+/// the main worktree first (if it is not bare)
+pub fn worktree_dirs(repo_path: &str) -> Result<Vec<PathBuf>> {
+	scope_time!("worktree_dirs");
+
+	let main = main_repo(repo(repo_path)?)?;
+
+	let mut dirs = Vec::new();
+	if let Some(dir) = main.workdir() {
+		dirs.push(dir.to_path_buf());
+	}
+
+	for name in main.worktrees()?.iter().flatten() {
+		let worktree = main.find_worktree(name)?;
+		dirs.push(worktree.path().to_path_buf());
+	}
+
+	Ok(dirs)
+}
+
+fn head_is(repo: &Repository, branch_ref: &str) -> bool {
+	repo.find_reference("HEAD")
+		.ok()
+		.and_then(|head| head.symbolic_target().map(String::from))
+		.as_deref()
+		== Some(branch_ref)
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+	match (fs::canonicalize(a), fs::canonicalize(b)) {
+		(Ok(a), Ok(b)) => a == b,
+		_ => a == b,
+	}
+}
+
+/// work dirs of the worktrees other than the one of `repo_path` that
+/// have `branch_ref` checked out, worktrees whose dir is gone are
+/// skipped
+pub fn worktrees_with_branch(
+	repo_path: &str,
+	branch_ref: &str,
+) -> Result<Vec<PathBuf>> {
+	scope_time!("worktrees_with_branch");
+
+	let repo = repo(repo_path)?;
+	let own = repo.path().to_path_buf();
+	let main = main_repo(repo)?;
+
+	let mut res = Vec::new();
+
+	if let Some(dir) = main.workdir() {
+		if !same_dir(main.path(), &own) && head_is(&main, branch_ref)
+		{
+			res.push(dir.to_path_buf());
+		}
+	}
+
+	for name in main.worktrees()?.iter().flatten() {
+		let worktree = main.find_worktree(name)?;
+		if worktree.validate().is_err() {
+			continue;
+		}
+
+		let linked = Repository::open_from_worktree(&worktree)?;
+		if !same_dir(linked.path(), &own)
+			&& head_is(&linked, branch_ref)
+		{
+			res.push(worktree.path().to_path_buf());
+		}
+	}
+
+	Ok(res)
+}
+
+/// what a repo is at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoSummary {
+	/// `None` for a detached head
+	pub branch: Option<String>,
+	/// staged, unstaged and untracked files
+	pub changes: usize,
+}
+
+///
+pub fn repo_summary(repo_path: &str) -> Result<RepoSummary> {
+	scope_time!("repo_summary");
+
+	let repo = repo(repo_path)?;
+	work_dir(&repo)?;
+
+	let mut options = StatusOptions::default();
+	options
+		.include_untracked(true)
+		.recurse_untracked_dirs(false)
+		.exclude_submodules(true);
+
+	let changes = repo
+		.statuses(Some(&mut options))?
+		.iter()
+		.filter(|entry| !entry.status().contains(Status::IGNORED))
+		.count();
+
+	Ok(RepoSummary {
+		branch: get_branch_name_repo(&repo).ok(),
+		changes,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		tests::repo_init,
+		utils::{repo_work_dir, repo_write_file},
+	};
+	use tempfile::TempDir;
+
+	#[test]
+	fn test_linked_worktrees() {
+		let (main_dir, repo) = repo_init().unwrap();
+		let main_path = main_dir.path().to_str().unwrap();
+
+		let linked_dir = TempDir::new().unwrap();
+		let linked = linked_dir.path().join("linked");
+		repo.worktree("linked", &linked, None).unwrap();
+
+		let expected = vec![
+			PathBuf::from(repo_work_dir(main_path).unwrap()),
+			linked.clone(),
+		];
+
+		assert_eq!(worktree_dirs(main_path).unwrap(), expected);
+		assert_eq!(
+			worktree_dirs(linked.to_str().unwrap()).unwrap(),
+			expected
+		);
+	}
+
+	#[test]
+	fn test_summary() {
+		let (path, repo) = repo_init().unwrap();
+		let path = path.path().to_str().unwrap();
+
+		assert_eq!(
+			repo_summary(path).unwrap(),
+			RepoSummary {
+				branch: Some(String::from("master")),
+				changes: 0,
+			}
+		);
+
+		repo_write_file(&repo, "a.txt", "a").unwrap();
+		repo_write_file(&repo, "b.txt", "b").unwrap();
+
+		assert_eq!(repo_summary(path).unwrap().changes, 2);
+
+		let no_repo = TempDir::new().unwrap();
+		assert!(
+			repo_summary(no_repo.path().to_str().unwrap()).is_err()
+		);
+	}
+}
@@ -0,0 +1,203 @@
+//! runtime stats of the operations measured with `scope_stats!`,
+//! they are only kept in memory
+
+use scopetime::{set_reporter, ScopeSample};
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::mpsc::{channel, Receiver},
+	time::Duration,
+};
+
+/// recent runtimes per operation the median is taken of
+const MEDIAN_SAMPLES: usize = 1000;
+
+/// runtime stats of one operation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationTiming {
+	///
+	pub name: &'static str,
+	///
+	pub count: usize,
+	///
+	pub total: Duration,
+	///
+	pub last: Duration,
+	/// of the last `MEDIAN_SAMPLES` runs
+	pub median: Duration,
+	///
+	pub max: Duration,
+}
+
+#[derive(Default)]
+struct Samples {
+	count: usize,
+	total: Duration,
+	max: Duration,
+	recent: VecDeque<Duration>,
+}
+
+impl Samples {
+	fn push(&mut self, duration: Duration) {
+		self.count += 1;
+		self.total += duration;
+		self.max = self.max.max(duration);
+
+		if self.recent.len() >= MEDIAN_SAMPLES {
+			self.recent.pop_front();
+		}
+		self.recent.push_back(duration);
+	}
+
+	fn timing(&self, name: &'static str) -> OperationTiming {
+		let mut sorted =
+			self.recent.iter().copied().collect::<Vec<_>>();
+		sorted.sort_unstable();
+
+		OperationTiming {
+			name,
+			count: self.count,
+			total: self.total,
+			last: self.recent.back().copied().unwrap_or_default(),
+			median: sorted
+				.get(sorted.len() / 2)
+				.copied()
+				.unwrap_or_default(),
+			max: self.max,
+		}
+	}
+}
+
+/// collects the runtime of every scope measured with
+/// `scope_stats!` while recording. only one instance should record
+/// at a time, starting to record takes over the reporter
+#[derive(Default)]
+pub struct Timings {
+	receiver: Option<Receiver<ScopeSample>>,
+	samples: HashMap<&'static str, Samples>,
+}
+
+impl Timings {
+	///
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	///
+	pub const fn is_recording(&self) -> bool {
+		self.receiver.is_some()
+	}
+
+	///
+	pub fn set_recording(&mut self, recording: bool) {
+		if recording == self.is_recording() {
+			return;
+		}
+
+		if recording {
+			let (tx, rx) = channel();
+			set_reporter(Some(tx));
+			self.receiver = Some(rx);
+		} else {
+			self.update();
+			set_reporter(None);
+			self.receiver = None;
+		}
+	}
+
+	/// forgets everything measured so far
+	pub fn reset(&mut self) {
+		self.update();
+		self.samples.clear();
+	}
+
+	/// takes in the runtimes measured since the last call
+	pub fn update(&mut self) {
+		if let Some(receiver) = &self.receiver {
+			for sample in receiver.try_iter() {
+				self.samples
+					.entry(sample.title)
+					.or_default()
+					.push(sample.duration);
+			}
+		}
+	}
+
+	/// the operations by total runtime, longest first
+	pub fn get(&self) -> Vec<OperationTiming> {
+		let mut res = self
+			.samples
+			.iter()
+			.map(|(name, samples)| samples.timing(name))
+			.collect::<Vec<_>>();
+
+		res.sort_by(|a, b| {
+			b.total.cmp(&a.total).then_with(|| a.name.cmp(b.name))
+		});
+
+		res
+	}
+}
+
+impl Drop for Timings {
+	fn drop(&mut self) {
+		if self.is_recording() {
+			set_reporter(None);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use scopetime::scope_stats;
+
+	fn measured() {
+		scope_stats!("timings_test");
+	}
+
+	fn measured_count(timings: &Timings) -> usize {
+		timings
+			.get()
+			.iter()
+			.find(|timing| timing.name == "timings_test")
+			.map_or(0, |timing| timing.count)
+	}
+
+	#[test]
+	fn test_recording() {
+		let mut timings = Timings::new();
+
+		measured();
+		timings.update();
+		assert_eq!(measured_count(&timings), 0);
+
+		timings.set_recording(true);
+		measured();
+		measured();
+		timings.update();
+		assert_eq!(measured_count(&timings), 2);
+
+		timings.set_recording(false);
+		measured();
+		timings.update();
+		assert_eq!(measured_count(&timings), 2);
+
+		timings.reset();
+		assert_eq!(measured_count(&timings), 0);
+	}
+
+	#[test]
+	fn test_median() {
+		let mut samples = Samples::default();
+		for ms in &[5, 1, 9, 3, 7] {
+			samples.push(Duration::from_millis(*ms));
+		}
+
+		let timing = samples.timing("op");
+		assert_eq!(timing.count, 5);
+		assert_eq!(timing.total, Duration::from_millis(25));
+		assert_eq!(timing.last, Duration::from_millis(7));
+		assert_eq!(timing.median, Duration::from_millis(5));
+		assert_eq!(timing.max, Duration::from_millis(9));
+	}
+}
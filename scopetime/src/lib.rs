@@ -6,7 +6,77 @@
 #![deny(clippy::unwrap_used)]
 #![deny(clippy::perf)]
 
-use std::time::Instant;
+use lazy_static::lazy_static;
+use std::{
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		mpsc::Sender,
+		Mutex,
+	},
+	time::{Duration, Instant},
+};
+
+/// runtime of a scope measured by `scope_stats!`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScopeSample {
+	///
+	pub title: &'static str,
+	///
+	pub duration: Duration,
+}
+
+static REPORTING: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+	static ref REPORTER: Mutex<Option<Sender<ScopeSample>>> =
+		Mutex::new(None);
+}
+
+/// every scope measured by `scope_stats!` is sent to `reporter`
+/// from now on, `None` stops reporting
+pub fn set_reporter(reporter: Option<Sender<ScopeSample>>) {
+	if let Ok(mut current) = REPORTER.lock() {
+		REPORTING.store(reporter.is_some(), Ordering::Relaxed);
+		*current = reporter;
+	}
+}
+
+/// reports the runtime of its scope if a reporter is set,
+/// see [`set_reporter`]
+pub struct ScopeStats {
+	title: &'static str,
+	time: Option<Instant>,
+}
+
+impl ScopeStats {
+	///
+	pub fn new(title: &'static str) -> Self {
+		Self {
+			title,
+			time: REPORTING
+				.load(Ordering::Relaxed)
+				.then(Instant::now),
+		}
+	}
+}
+
+impl Drop for ScopeStats {
+	fn drop(&mut self) {
+		if let Some(time) = self.time {
+			let sample = ScopeSample {
+				title: self.title,
+				duration: time.elapsed(),
+			};
+
+			if let Ok(reporter) = REPORTER.lock() {
+				if let Some(reporter) = reporter.as_ref() {
+					// a gone receiver just misses samples
+					reporter.send(sample).ok();
+				}
+			}
+		}
+	}
+}
 
 ///
 pub struct ScopeTimeLog<'a> {
@@ -70,3 +140,39 @@ macro_rules! scope_time {
 macro_rules! scope_time {
 	($target:literal) => {};
 }
+
+/// like `scope_time!` and reports the runtime to the reporter set
+/// via [`set_reporter`], even without the `enabled` feature
+#[macro_export]
+macro_rules! scope_stats {
+	($target:literal) => {
+		$crate::scope_time!($target);
+		#[allow(unused_variables)]
+		let stats = $crate::ScopeStats::new($target);
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::mpsc::channel;
+
+	fn measured() {
+		scope_stats!("measured");
+	}
+
+	#[test]
+	fn test_reporter() {
+		measured();
+
+		let (tx, rx) = channel();
+		set_reporter(Some(tx));
+		measured();
+		set_reporter(None);
+		measured();
+
+		let samples = rx.try_iter().collect::<Vec<_>>();
+		assert_eq!(samples.len(), 1);
+		assert_eq!(samples[0].title, "measured");
+	}
+}
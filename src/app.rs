@@ -1,68 +1,157 @@
 use crate::{
 	accessors,
+	bookmarks::Location,
+	branch_history::{self, SharedBranchHistory},
 	cmdbar::CommandBar,
 	components::{
-		event_pump, AppOption, BlameFileComponent,
-		BranchListComponent, CommandBlocking, CommandInfo,
-		CommitComponent, CompareCommitsComponent, Component,
-		ConfirmComponent, CreateBranchComponent, DrawableComponent,
-		ExternalEditorComponent, FileFindPopup, HelpComponent,
-		InspectCommitComponent, MsgComponent, OptionsPopupComponent,
-		PullComponent, PushComponent, PushTagsComponent,
-		RenameBranchComponent, RevisionFilesPopup, SharedOptions,
-		StashMsgComponent, TagCommitComponent, TagListComponent,
+		event_pump, paste_pump, run_index_op, AppOption,
+		BlameFileComponent, BookmarksPopup, BranchDescriptionPopup,
+		BranchListComponent, CherryPickPopup, CommandBlocking,
+		CommandInfo, CommandPalettePopup, CommandText,
+		CommitComponent, CompareCommitsComponent,
+		CompareWorkdirComponent, Component, ConfirmComponent,
+		ConflictPopup, CreateBranchComponent, DrawableComponent,
+		ExternalEditorComponent, FetchComponent, FileFindPopup,
+		FileViewPopup, GitConfigPopup, HelpComponent,
+		IndexFlagsPopup, InspectCommitComponent, JumpToRefPopup,
+		LogAuthorsPopup, LogExportPopup, LogFilterPopup,
+		MsgComponent, MsgHistoryComponent, Options,
+		OptionsPopupComponent, PaletteEntry, PaletteState,
+		PreflightPopup, PruneRemoteComponent, PullComponent,
+		PushComponent, PushTagsComponent, RecentBranchesComponent,
+		RenameBranchComponent, RepoMaintenanceComponent,
+		RepoSwitcherPopup, RevisionFilesPopup, SharedOptions,
+		StagePatternPopup, StashMsgComponent, TagCommitComponent,
+		TagListComponent, TimingsComponent,
 	},
-	input::{Input, InputEvent, InputState},
-	keys::{KeyConfig, SharedKeyConfig},
+	custom_commands::{
+		self, AsyncCustomCommandJob, CommandSelection, CustomCommand,
+	},
+	input::{self, Input, InputEvent, InputState},
+	keys::SharedKeyConfig,
+	messages::Severity,
 	queue::{Action, InternalEvent, NeedsUpdate, Queue},
+	refresh::{
+		GitDirWatcher, RefreshDomains, RefreshScheduler,
+		MAX_STALENESS,
+	},
+	repo_session::SharedRepoSession,
 	setup_popups,
 	strings::{self, order},
-	tabs::{FilesTab, Revlog, StashList, Stashing, Status},
-	ui::style::{SharedTheme, Theme},
+	tabs::{
+		FilesTab, Pane, PaneFocus, Revlog, StashList, Stashing,
+		Status,
+	},
+	ui::style::SharedTheme,
+	ui_state::{self, UiState},
 	AsyncAppNotification, AsyncNotification,
 };
 use anyhow::{bail, Result};
-use asyncgit::{sync, AsyncGitNotification, CWD};
+use asyncgit::{
+	asyncjob::AsyncSingleJob,
+	sync::{
+		self, utils::repo_work_dir, CommitId, DiscardBackup,
+		IndexFlag, IndexSnapshot, StaleState,
+	},
+	AsyncGitNotification, CWD,
+};
 use crossbeam_channel::Sender;
 use crossterm::event::{Event, KeyEvent};
 use std::{
 	cell::{Cell, RefCell},
-	path::Path,
+	collections::VecDeque,
+	convert::TryFrom,
+	path::{Path, PathBuf},
 	rc::Rc,
+	time::{Duration, Instant},
 };
 use tui::{
 	backend::Backend,
-	layout::{Constraint, Direction, Layout, Margin, Rect},
+	layout::{
+		Alignment, Constraint, Direction, Layout, Margin, Rect,
+	},
 	text::{Span, Spans},
-	widgets::{Block, Borders, Tabs},
+	widgets::{Block, Borders, Clear, Paragraph, Tabs},
 	Frame,
 };
+use unicode_width::UnicodeWidthStr;
+
+/// below this terminal size only a hint is drawn instead of the ui
+const MIN_SIZE: (u16, u16) = (60, 15);
+
+/// how long toasts stay in the command bar
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// index changes that can be undone
+const INDEX_HISTORY_CAPACITY: usize = 20;
+
+/// how long a discarded hunk can be applied again
+const HUNK_UNDO_GRACE: Duration = Duration::from_secs(5);
+
+/// discarded hunks that can be applied again at most
+const HUNK_UNDO_CAPACITY: usize = 10;
+
+/// patch of a hunk discarded within `HUNK_UNDO_GRACE`
+struct DiscardedHunk {
+	path: String,
+	patch: Vec<u8>,
+	since: Instant,
+}
 
 /// the main app type
 pub struct App {
 	do_quit: bool,
+	/// the main panel as plain lines while in selection mode
+	selection_text: Option<Vec<String>>,
 	help: HelpComponent,
 	msg: MsgComponent,
+	msg_history_popup: MsgHistoryComponent,
+	timings_popup: TimingsComponent,
+	repo_maintenance_popup: RepoMaintenanceComponent,
+	preflight_popup: PreflightPopup,
+	git_config_popup: GitConfigPopup,
 	reset: ConfirmComponent,
 	commit: CommitComponent,
 	blame_file_popup: BlameFileComponent,
+	compare_workdir_popup: CompareWorkdirComponent,
 	stashmsg_popup: StashMsgComponent,
 	inspect_commit_popup: InspectCommitComponent,
 	compare_commits_popup: CompareCommitsComponent,
 	external_editor_popup: ExternalEditorComponent,
 	revision_files_popup: RevisionFilesPopup,
 	find_file_popup: FileFindPopup,
+	file_view_popup: FileViewPopup,
+	conflict_popup: ConflictPopup,
+	log_filter_popup: LogFilterPopup,
+	log_authors_popup: LogAuthorsPopup,
+	jump_to_ref_popup: JumpToRefPopup,
+	bookmarks_popup: BookmarksPopup,
+	index_flags_popup: IndexFlagsPopup,
+	log_export_popup: LogExportPopup,
+	cherry_pick_popup: CherryPickPopup,
 	push_popup: PushComponent,
 	push_tags_popup: PushTagsComponent,
 	pull_popup: PullComponent,
+	fetch_popup: FetchComponent,
+	prune_remote_popup: PruneRemoteComponent,
 	tag_commit_popup: TagCommitComponent,
 	create_branch_popup: CreateBranchComponent,
 	rename_branch_popup: RenameBranchComponent,
+	branch_description_popup: BranchDescriptionPopup,
+	stage_pattern_popup: StagePatternPopup,
 	select_branch_popup: BranchListComponent,
+	recent_branches_popup: RecentBranchesComponent,
+	repo_switcher_popup: RepoSwitcherPopup,
+	command_palette_popup: CommandPalettePopup,
 	options_popup: OptionsPopupComponent,
 	tags_popup: TagListComponent,
 	cmdbar: RefCell<CommandBar>,
+	options: SharedOptions,
+	branch_history: SharedBranchHistory,
 	tab: usize,
+	/// tab and pane focused when the popups on top opened,
+	/// focused again once they are all closed
+	focus_before_popup: Option<(usize, Pane)>,
 	revlog: Revlog,
 	status_tab: Status,
 	stashing_tab: Stashing,
@@ -73,9 +162,31 @@ pub struct App {
 	key_config: SharedKeyConfig,
 	input: Input,
 
+	refresh: RefreshScheduler,
+	git_dir_watcher: Option<GitDirWatcher>,
+
 	// "Flags"
 	requires_redraw: Cell<bool>,
+	/// something visible changed since the last frame
+	needs_draw: Cell<bool>,
 	file_to_open: Option<String>,
+	/// name and command line to run once the input is paused
+	command_to_run: Option<(String, String)>,
+	/// picked in the command palette, runs once it closed
+	palette_command: Option<CommandText>,
+	custom_commands: Vec<CustomCommand>,
+	custom_command_job: AsyncSingleJob<AsyncCustomCommandJob>,
+	/// shown until the instant
+	toast: Option<(Severity, String, Instant)>,
+	/// the index before each change made in this session, latest last
+	index_history: VecDeque<IndexSnapshot>,
+	/// working dir content destroyed by the last discard
+	last_discard: Option<DiscardBackup>,
+	/// hunks discarded since the last other discard, latest last
+	discarded_hunks: VecDeque<DiscardedHunk>,
+	repo_session: SharedRepoSession,
+	/// work dir of the repo to continue with once this app quits
+	repo_to_open: Option<PathBuf>,
 }
 
 // public interface
@@ -86,15 +197,25 @@ impl App {
 		sender: &Sender<AsyncGitNotification>,
 		sender_app: &Sender<AsyncAppNotification>,
 		input: Input,
-		theme: Theme,
-		key_config: KeyConfig,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+		repo_session: SharedRepoSession,
 	) -> Self {
 		let queue = Queue::new();
-		let theme = Rc::new(theme);
-		let key_config = Rc::new(key_config);
-		let options = SharedOptions::default();
+		let options = Rc::new(RefCell::new(Options::load()));
+		let branch_history =
+			Rc::new(RefCell::new(branch_history::load()));
+		let custom_commands =
+			custom_commands::load().unwrap_or_else(|e| {
+				queue.push(InternalEvent::ShowErrorMsg(
+					strings::custom_commands_load_failed(
+						&e.to_string(),
+					),
+				));
+				Vec::new()
+			});
 
-		Self {
+		let mut app = Self {
 			input,
 			reset: ConfirmComponent::new(
 				queue.clone(),
@@ -103,8 +224,10 @@ impl App {
 			),
 			commit: CommitComponent::new(
 				queue.clone(),
+				sender,
 				theme.clone(),
 				key_config.clone(),
+				options.clone(),
 			),
 			blame_file_popup: BlameFileComponent::new(
 				&queue,
@@ -113,6 +236,10 @@ impl App {
 				theme.clone(),
 				key_config.clone(),
 			),
+			compare_workdir_popup: CompareWorkdirComponent::new(
+				theme.clone(),
+				key_config.clone(),
+			),
 			revision_files_popup: RevisionFilesPopup::new(
 				&queue,
 				sender_app,
@@ -123,18 +250,21 @@ impl App {
 				queue.clone(),
 				theme.clone(),
 				key_config.clone(),
+				options.clone(),
 			),
 			inspect_commit_popup: InspectCommitComponent::new(
 				&queue,
 				sender,
 				theme.clone(),
 				key_config.clone(),
+				options.clone(),
 			),
 			compare_commits_popup: CompareCommitsComponent::new(
 				&queue,
 				sender,
 				theme.clone(),
 				key_config.clone(),
+				options.clone(),
 			),
 			external_editor_popup: ExternalEditorComponent::new(
 				theme.clone(),
@@ -158,6 +288,18 @@ impl App {
 				theme.clone(),
 				key_config.clone(),
 			),
+			fetch_popup: FetchComponent::new(
+				&queue,
+				sender,
+				theme.clone(),
+				key_config.clone(),
+			),
+			prune_remote_popup: PruneRemoteComponent::new(
+				&queue,
+				sender,
+				theme.clone(),
+				key_config.clone(),
+			),
 			tag_commit_popup: TagCommitComponent::new(
 				queue.clone(),
 				theme.clone(),
@@ -173,8 +315,38 @@ impl App {
 				theme.clone(),
 				key_config.clone(),
 			),
+			branch_description_popup: BranchDescriptionPopup::new(
+				queue.clone(),
+				theme.clone(),
+				key_config.clone(),
+			),
+			stage_pattern_popup: StagePatternPopup::new(
+				queue.clone(),
+				theme.clone(),
+				key_config.clone(),
+			),
 			select_branch_popup: BranchListComponent::new(
 				queue.clone(),
+				sender,
+				options.clone(),
+				branch_history.clone(),
+				theme.clone(),
+				key_config.clone(),
+			),
+			recent_branches_popup: RecentBranchesComponent::new(
+				&queue,
+				branch_history.clone(),
+				theme.clone(),
+				key_config.clone(),
+			),
+			repo_switcher_popup: RepoSwitcherPopup::new(
+				&queue,
+				repo_session.clone(),
+				theme.clone(),
+				key_config.clone(),
+			),
+			command_palette_popup: CommandPalettePopup::new(
+				&queue,
 				theme.clone(),
 				key_config.clone(),
 			),
@@ -195,7 +367,18 @@ impl App {
 				theme.clone(),
 				key_config.clone(),
 			),
+			file_view_popup: FileViewPopup::new(
+				&queue,
+				theme.clone(),
+				key_config.clone(),
+			),
+			conflict_popup: ConflictPopup::new(
+				&queue,
+				theme.clone(),
+				key_config.clone(),
+			),
 			do_quit: false,
+			selection_text: None,
 			cmdbar: RefCell::new(CommandBar::new(
 				theme.clone(),
 				key_config.clone(),
@@ -205,30 +388,95 @@ impl App {
 				key_config.clone(),
 			),
 			msg: MsgComponent::new(theme.clone(), key_config.clone()),
+			msg_history_popup: MsgHistoryComponent::new(
+				&queue,
+				theme.clone(),
+				key_config.clone(),
+			),
+			timings_popup: TimingsComponent::new(
+				theme.clone(),
+				key_config.clone(),
+			),
+			repo_maintenance_popup: RepoMaintenanceComponent::new(
+				&queue,
+				theme.clone(),
+				key_config.clone(),
+			),
+			preflight_popup: PreflightPopup::new(
+				&queue,
+				theme.clone(),
+				key_config.clone(),
+			),
+			git_config_popup: GitConfigPopup::new(
+				&queue,
+				theme.clone(),
+				key_config.clone(),
+			),
+			log_filter_popup: LogFilterPopup::new(
+				&queue,
+				theme.clone(),
+				key_config.clone(),
+			),
+			log_authors_popup: LogAuthorsPopup::new(
+				&queue,
+				theme.clone(),
+				key_config.clone(),
+			),
+			jump_to_ref_popup: JumpToRefPopup::new(
+				&queue,
+				theme.clone(),
+				key_config.clone(),
+			),
+			bookmarks_popup: BookmarksPopup::new(
+				&queue,
+				theme.clone(),
+				key_config.clone(),
+			),
+			index_flags_popup: IndexFlagsPopup::new(
+				&queue,
+				theme.clone(),
+				key_config.clone(),
+			),
+			log_export_popup: LogExportPopup::new(
+				&queue,
+				sender_app,
+				theme.clone(),
+				key_config.clone(),
+			),
+			cherry_pick_popup: CherryPickPopup::new(
+				&queue,
+				sender,
+				theme.clone(),
+				key_config.clone(),
+			),
 			tab: 0,
+			focus_before_popup: None,
 			revlog: Revlog::new(
 				&queue,
 				sender,
 				theme.clone(),
 				key_config.clone(),
+				options.clone(),
 			),
 			status_tab: Status::new(
 				&queue,
 				sender,
 				theme.clone(),
 				key_config.clone(),
-				options,
+				options.clone(),
 			),
 			stashing_tab: Stashing::new(
 				sender,
 				&queue,
 				theme.clone(),
 				key_config.clone(),
+				options.clone(),
 			),
 			stashlist_tab: StashList::new(
 				&queue,
 				theme.clone(),
 				key_config.clone(),
+				options.clone(),
 			),
 			files_tab: FilesTab::new(
 				sender_app,
@@ -239,15 +487,47 @@ impl App {
 			queue,
 			theme,
 			key_config,
+			refresh: RefreshScheduler::new(
+				Instant::now(),
+				MAX_STALENESS,
+			),
+			git_dir_watcher: sync::repo_dir(CWD)
+				.ok()
+				.map(|dir| GitDirWatcher::new(&dir)),
 			requires_redraw: Cell::new(false),
+			needs_draw: Cell::new(true),
 			file_to_open: None,
-		}
+			command_to_run: None,
+			palette_command: None,
+			custom_commands,
+			custom_command_job: AsyncSingleJob::new(
+				sender_app.clone(),
+			),
+			toast: None,
+			index_history: VecDeque::new(),
+			last_discard: None,
+			discarded_hunks: VecDeque::new(),
+			repo_session,
+			repo_to_open: None,
+			options,
+			branch_history,
+		};
+
+		app.restore_ui_state();
+		app.status_tab.pause_auto_fetch(app.auto_fetch_paused());
+
+		app
 	}
 
 	///
 	pub fn draw<B: Backend>(&self, f: &mut Frame<B>) -> Result<()> {
 		let fsize = f.size();
 
+		if fsize.width < MIN_SIZE.0 || fsize.height < MIN_SIZE.1 {
+			Self::draw_too_small(f, fsize);
+			return Ok(());
+		}
+
 		self.cmdbar.borrow_mut().refresh_width(fsize.width);
 
 		let chunks_main = Layout::default()
@@ -263,8 +543,10 @@ impl App {
 			.split(fsize);
 
 		self.cmdbar.borrow().draw(f, chunks_main[2]);
+		self.draw_toast(f, chunks_main[2]);
 
 		self.draw_tabs(f, chunks_main[0]);
+		self.draw_banner(f, chunks_main[0]);
 
 		//TODO: macro because of generic draw call
 		match self.tab {
@@ -285,63 +567,95 @@ impl App {
 	pub fn event(&mut self, ev: InputEvent) -> Result<()> {
 		log::trace!("event: {:?}", ev);
 
+		self.needs_draw.set(true);
+
+		let ev = match ev {
+			InputEvent::Paste(_) if self.selection_text.is_some() => {
+				return Ok(())
+			}
+			InputEvent::Paste(text) => return self.paste(&text),
+			ev => ev,
+		};
+
 		if let InputEvent::Input(ev) = ev {
+			if self.selection_text.is_some() {
+				self.selection_mode_event(ev);
+				return Ok(());
+			}
+
 			if self.check_hard_exit(ev) || self.check_quit(ev) {
 				return Ok(());
 			}
 
+			if let Event::Resize(..) = ev {
+				self.on_resize();
+				return Ok(());
+			}
+
 			let mut flags = NeedsUpdate::empty();
 
+			let focus = if self.any_popup_visible() {
+				None
+			} else {
+				Some((self.tab, self.tab_panes().focused_pane()))
+			};
+
+			// worktree edits are not watched, catch them up
+			// with the next tick while the user is interacting
+			if let Event::Key(k) = ev {
+				self.refresh.mark(RefreshDomains::STATUS);
+
+				if let Some(label) = self.readonly_rejected_key(k) {
+					self.queue.push(InternalEvent::ShowToast(
+						Severity::Error,
+						strings::readonly_rejected(&label),
+					));
+					return self.process_queue(NeedsUpdate::empty());
+				}
+
+				if let Some(label) = self.offline_rejected_key(k) {
+					self.queue.push(InternalEvent::ShowToast(
+						Severity::Error,
+						strings::offline_rejected(&label),
+					));
+					return self.process_queue(NeedsUpdate::empty());
+				}
+
+				// takes precedence over the key's other uses only
+				// while the toast offering it is up
+				if k == self.key_config.undo_hunk_discard
+					&& self.hunk_undo_available()
+				{
+					self.undo_hunk_discard();
+					return self.process_queue(
+						NeedsUpdate::ALL | NeedsUpdate::COMMANDS,
+					);
+				}
+			}
+
 			if event_pump(ev, self.components_mut().as_mut_slice())?
 				.is_consumed()
 			{
 				flags.insert(NeedsUpdate::COMMANDS);
 			} else if let Event::Key(k) = ev {
-				let new_flags = if k == self.key_config.tab_toggle {
-					self.toggle_tabs(false)?;
-					NeedsUpdate::COMMANDS
-				} else if k == self.key_config.tab_toggle_reverse {
-					self.toggle_tabs(true)?;
-					NeedsUpdate::COMMANDS
-				} else if k == self.key_config.tab_status
-					|| k == self.key_config.tab_log
-					|| k == self.key_config.tab_files
-					|| k == self.key_config.tab_stashing
-					|| k == self.key_config.tab_stashes
-				{
-					self.switch_tab(k)?;
-					NeedsUpdate::COMMANDS
-				} else if k == self.key_config.cmd_bar_toggle {
-					self.cmdbar.borrow_mut().toggle_more();
-					NeedsUpdate::empty()
-				} else if k == self.key_config.open_options {
-					self.options_popup.show()?;
-					NeedsUpdate::ALL
-				} else {
-					NeedsUpdate::empty()
-				};
-
-				flags.insert(new_flags);
+				flags.insert(self.global_key_event(k)?);
 			}
 
 			self.process_queue(flags)?;
+			self.restore_focus_after_popup(focus)?;
+
+			if let Some(command) = self.palette_command.take() {
+				self.run_palette_command(&command)?;
+			}
 		} else if let InputEvent::State(polling_state) = ev {
 			self.external_editor_popup.hide();
 			if let InputState::Paused = polling_state {
-				let result = match self.file_to_open.take() {
-					Some(path) => {
-						ExternalEditorComponent::open_file_in_editor(
-							Path::new(&path),
-						)
-					}
-					None => self.commit.show_editor(),
-				};
-
-				if let Err(e) = result {
-					let msg =
-						format!("failed to launch editor:\n{}", e);
-					log::error!("{}", msg.as_str());
-					self.msg.show_error(msg.as_str())?;
+				if let Some((name, command)) =
+					self.command_to_run.take()
+				{
+					self.run_terminal_command(&name, &command)?;
+				} else {
+					self.open_editor()?;
 				}
 
 				self.requires_redraw.set(true);
@@ -352,17 +666,66 @@ impl App {
 		Ok(())
 	}
 
-	//TODO: do we need this?
-	/// forward ticking to components that require it
+	fn open_editor(&mut self) -> Result<()> {
+		let result = match self.file_to_open.take() {
+			Some(path) => {
+				ExternalEditorComponent::open_file_in_editor(
+					Path::new(&path),
+				)
+			}
+			None => self.commit.show_editor(),
+		};
+
+		if let Err(e) = result {
+			let msg = format!("failed to launch editor:\n{}", e);
+			log::error!("{}", msg.as_str());
+			self.queue.log_message(Severity::Error, &msg);
+			self.msg.show_error(msg.as_str())?;
+		}
+
+		Ok(())
+	}
+
+	fn paste(&mut self, text: &str) -> Result<()> {
+		if paste_pump(text, self.components_mut().as_mut_slice())?
+			.is_consumed()
+		{
+			self.refresh.mark(RefreshDomains::STATUS);
+			self.process_queue(NeedsUpdate::COMMANDS)?;
+		} else {
+			for ev in input::paste_keys(text) {
+				self.event(InputEvent::Input(ev))?;
+
+				if self.do_quit {
+					break;
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// refreshes the parts of the repo state that are dirty or stale
 	pub fn update(&mut self) -> Result<()> {
 		log::trace!("update");
 
-		self.commit.update();
-		self.status_tab.update()?;
-		self.revlog.update()?;
-		self.files_tab.update()?;
-		self.stashing_tab.update()?;
-		self.stashlist_tab.update()?;
+		if let Some(watcher) = &mut self.git_dir_watcher {
+			self.refresh.mark(watcher.poll());
+		}
+
+		let due = self.refresh.take_due(Instant::now());
+		self.update_domains(due)?;
+
+		// ticks that neither refresh anything nor let a notice
+		// expire leave the screen as it is
+		let expired = self.expire_notices();
+		if expired
+			|| !due.is_empty()
+			|| self.timings_popup.is_visible()
+		{
+			self.needs_draw.set(true);
+		}
+		self.timings_popup.update();
 
 		self.update_commands();
 
@@ -376,8 +739,25 @@ impl App {
 	) -> Result<()> {
 		log::trace!("update_async: {:?}", ev);
 
+		self.needs_draw.set(true);
+		self.timings_popup.update();
+
 		if let AsyncNotification::Git(ev) = ev {
+			if matches!(
+				ev,
+				AsyncGitNotification::Push
+					| AsyncGitNotification::PushTags
+					| AsyncGitNotification::Fetch
+					| AsyncGitNotification::AutoFetch
+			) {
+				self.refresh.mark(RefreshDomains::BRANCHES);
+			}
+
 			self.status_tab.update_git(ev)?;
+			// an auto fetch can land while the commit popup is open
+			self.commit.set_upstream_behind(
+				self.status_tab.upstream_behind(),
+			);
 			self.stashing_tab.update_git(ev)?;
 			self.revlog.update_git(ev)?;
 			self.blame_file_popup.update_git(ev)?;
@@ -387,11 +767,26 @@ impl App {
 			self.push_tags_popup.update_git(ev)?;
 			self.pull_popup.update_git(ev)?;
 			self.select_branch_popup.update_git(ev)?;
+			self.commit.update_git(ev)?;
+			self.cherry_pick_popup.update_git(ev);
 		}
 
 		self.files_tab.update_async(ev);
 		self.revision_files_popup.update(ev);
 		self.tags_popup.update(ev);
+		self.fetch_popup.update(ev);
+		self.push_popup.update_preview(ev);
+		self.prune_remote_popup.update(ev);
+		self.log_export_popup.update_async(ev);
+
+		if ev
+			== AsyncNotification::App(
+				AsyncAppNotification::CustomCommand,
+			) {
+			self.custom_command_done();
+		}
+
+		self.status_tab.pause_auto_fetch(self.auto_fetch_paused());
 
 		//TODO: better system for this
 		// can we simply process the queue here and everyone just uses the queue to schedule a cmd update?
@@ -400,11 +795,63 @@ impl App {
 		Ok(())
 	}
 
+	/// remembers the ui state for the next start, forgets it if
+	/// disabled in the options. switching back to the repo in this
+	/// session restores it either way
+	pub fn save_ui_state(&self) {
+		let state = UiState {
+			tab: self.tab,
+			revlog_selection: self
+				.revlog
+				.selection()
+				.map(|id| id.to_string()),
+			status_selection: self.status_tab.selection(),
+			branches_remote: self.select_branch_popup.shows_remote(),
+			diff: Some(self.options.borrow().diff.into()),
+			status_split: Some(self.status_tab.split()),
+			bookmarks: self.bookmarks_popup.bookmarks().to_vec(),
+		};
+
+		self.repo_session.borrow_mut().remember(state.clone());
+
+		let options = self.options.borrow();
+		let state = options.remember_ui_state.then(|| state);
+		if let Err(e) =
+			ui_state::save(state, options.persist_bookmarks)
+		{
+			log::error!("save ui state: {}", e);
+		}
+	}
+
+	/// work dir of the repo picked in the switcher, after quitting
+	pub fn take_repo_to_open(&mut self) -> Option<PathBuf> {
+		self.repo_to_open.take()
+	}
+
+	/// hands the input on to the app of the next repo
+	pub fn into_input(self) -> Input {
+		self.input
+	}
+
 	///
 	pub fn is_quit(&self) -> bool {
 		self.do_quit || self.input.is_aborted()
 	}
 
+	/// auto fetches change remote refs, not while a user initiated
+	/// remote op runs or in read-only and offline mode
+	fn auto_fetch_paused(&self) -> bool {
+		self.readonly() || self.offline() || self.remote_op_pending()
+	}
+
+	fn remote_op_pending(&self) -> bool {
+		self.push_popup.any_work_pending()
+			|| self.push_tags_popup.any_work_pending()
+			|| self.pull_popup.any_work_pending()
+			|| self.fetch_popup.any_work_pending()
+			|| self.prune_remote_popup.any_work_pending()
+	}
+
 	///
 	pub fn any_work_pending(&self) -> bool {
 		self.status_tab.anything_pending()
@@ -418,8 +865,29 @@ impl App {
 			|| self.push_popup.any_work_pending()
 			|| self.push_tags_popup.any_work_pending()
 			|| self.pull_popup.any_work_pending()
+			|| self.fetch_popup.any_work_pending()
+			|| self.prune_remote_popup.any_work_pending()
 			|| self.revision_files_popup.any_work_pending()
 			|| self.tags_popup.any_work_pending()
+			|| self.commit.any_work_pending()
+			|| self.log_export_popup.any_work_pending()
+			|| self.cherry_pick_popup.any_work_pending()
+			|| self.custom_command_job.is_pending()
+	}
+
+	/// resets the flag, the main loop skips drawing if not set
+	pub fn needs_draw(&self) -> bool {
+		self.needs_draw.replace(false)
+	}
+
+	/// the lines to draw instead of the ui while in selection mode
+	pub fn selection_text(&self) -> Option<&[String]> {
+		self.selection_text.as_deref()
+	}
+
+	///
+	pub fn selection_hint(&self) -> String {
+		strings::selection_mode_hint(&self.key_config)
 	}
 
 	///
@@ -439,9 +907,24 @@ impl App {
 		self,
 		[
 			find_file_popup,
+			log_filter_popup,
+			log_authors_popup,
+			jump_to_ref_popup,
+			bookmarks_popup,
+			index_flags_popup,
+			log_export_popup,
+			cherry_pick_popup,
 			msg,
 			reset,
+			msg_history_popup,
+			timings_popup,
+			repo_maintenance_popup,
+			preflight_popup,
+			git_config_popup,
 			commit,
+			file_view_popup,
+			conflict_popup,
+			compare_workdir_popup,
 			blame_file_popup,
 			stashmsg_popup,
 			inspect_commit_popup,
@@ -450,10 +933,17 @@ impl App {
 			push_popup,
 			push_tags_popup,
 			pull_popup,
+			fetch_popup,
+			prune_remote_popup,
 			tag_commit_popup,
 			create_branch_popup,
 			rename_branch_popup,
+			branch_description_popup,
+			stage_pattern_popup,
 			select_branch_popup,
+			recent_branches_popup,
+			repo_switcher_popup,
+			command_palette_popup,
 			revision_files_popup,
 			tags_popup,
 			options_popup,
@@ -472,28 +962,97 @@ impl App {
 			commit,
 			stashmsg_popup,
 			help,
+			select_branch_popup,
 			inspect_commit_popup,
 			compare_commits_popup,
 			blame_file_popup,
 			external_editor_popup,
 			tag_commit_popup,
-			select_branch_popup,
 			tags_popup,
 			create_branch_popup,
 			rename_branch_popup,
+			branch_description_popup,
+			stage_pattern_popup,
 			revision_files_popup,
+			compare_workdir_popup,
 			find_file_popup,
+			file_view_popup,
+			conflict_popup,
+			log_filter_popup,
+			log_authors_popup,
+			jump_to_ref_popup,
+			bookmarks_popup,
+			index_flags_popup,
+			log_export_popup,
+			cherry_pick_popup,
 			push_popup,
 			push_tags_popup,
 			pull_popup,
+			fetch_popup,
+			prune_remote_popup,
+			recent_branches_popup,
+			repo_switcher_popup,
+			command_palette_popup,
 			options_popup,
+			msg_history_popup,
+			timings_popup,
+			repo_maintenance_popup,
+			preflight_popup,
+			git_config_popup,
 			reset,
 			msg
 		]
 	);
 
+	fn update_domains(&mut self, due: RefreshDomains) -> Result<()> {
+		log::trace!("update domains: {:?}", due);
+
+		if due.intersects(
+			RefreshDomains::STATUS | RefreshDomains::BRANCHES,
+		) {
+			self.commit.update();
+		}
+
+		if due.contains(RefreshDomains::STATUS) {
+			// also updates branch state and the diff
+			self.status_tab.update()?;
+			self.stashing_tab.update()?;
+		} else {
+			if due.contains(RefreshDomains::BRANCHES) {
+				self.status_tab.update_branch_state();
+			}
+			if due.contains(RefreshDomains::DIFF) {
+				self.status_tab.update_diff()?;
+			}
+		}
+
+		if due.intersects(
+			RefreshDomains::STATUS
+				| RefreshDomains::BRANCHES
+				| RefreshDomains::LOG,
+		) {
+			self.revlog.update_bisect();
+			self.compare_commits_popup.update_since_branching()?;
+		}
+
+		if due.contains(RefreshDomains::LOG) {
+			self.revlog.update()?;
+			self.files_tab.update()?;
+		}
+
+		if due.contains(RefreshDomains::STASHES) {
+			self.stashlist_tab.update()?;
+		}
+
+		if due.contains(RefreshDomains::BRANCHES) {
+			self.select_branch_popup.update_branches()?;
+		}
+
+		Ok(())
+	}
+
 	fn check_quit(&mut self, ev: Event) -> bool {
-		if self.any_popup_visible() {
+		if self.any_popup_visible() || self.status_tab.is_typing() {
 			return false;
 		}
 		if let Event::Key(e) = ev {
@@ -525,6 +1084,48 @@ impl App {
 		]
 	}
 
+	fn tab_panes(&self) -> &dyn PaneFocus {
+		match self.tab {
+			1 => &self.revlog,
+			2 => &self.files_tab,
+			3 => &self.stashing_tab,
+			4 => &self.stashlist_tab,
+			_ => &self.status_tab,
+		}
+	}
+
+	fn tab_panes_mut(&mut self) -> &mut dyn PaneFocus {
+		match self.tab {
+			1 => &mut self.revlog,
+			2 => &mut self.files_tab,
+			3 => &mut self.stashing_tab,
+			4 => &mut self.stashlist_tab,
+			_ => &mut self.status_tab,
+		}
+	}
+
+	/// remembers the pane focused before the first popup opened and
+	/// focuses it again once the last one closed, unless the popup
+	/// switched to another tab
+	fn restore_focus_after_popup(
+		&mut self,
+		focus: Option<(usize, Pane)>,
+	) -> Result<()> {
+		if self.any_popup_visible() {
+			if self.focus_before_popup.is_none() {
+				self.focus_before_popup = focus;
+			}
+		} else if let Some((tab, pane)) =
+			self.focus_before_popup.take()
+		{
+			if tab == self.tab {
+				self.tab_panes_mut().focus_pane(pane)?;
+			}
+		}
+
+		Ok(())
+	}
+
 	fn toggle_tabs(&mut self, reverse: bool) -> Result<()> {
 		let tabs_len = self.get_tabs().len();
 		let new_tab = if reverse {
@@ -552,24 +1153,709 @@ impl App {
 		Ok(())
 	}
 
-	fn set_tab(&mut self, tab: usize) -> Result<()> {
-		let tabs = self.get_tabs();
-		for (i, t) in tabs.into_iter().enumerate() {
-			if tab == i {
-				t.show()?;
-			} else {
-				t.hide();
-			}
+	fn custom_commands_info(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) {
+		if self.custom_commands.is_empty() {
+			return;
 		}
 
-		self.tab = tab;
+		let selection = self.command_selection();
+
+		for command in &self.custom_commands {
+			out.push(CommandInfo::new(
+				strings::commands::custom_command(
+					&self.key_config,
+					&command.name,
+					command.key,
+				),
+				command.expand(&selection).is_some(),
+				(!self.any_popup_visible()
+					&& command.is_available(&selection))
+					|| force_all,
+			));
+		}
+	}
+
+	/// popups that open from any tab
+	fn global_popups_info(&self, out: &mut Vec<CommandInfo>) {
+		out.push(
+			CommandInfo::new(
+				strings::commands::repo_maintenance_open(
+					&self.key_config,
+				),
+				true,
+				!self.any_popup_visible(),
+			)
+			.order(order::RARE_ACTION),
+		);
+
+		out.push(
+			CommandInfo::new(
+				strings::commands::repo_switcher_open(
+					&self.key_config,
+				),
+				true,
+				!self.any_popup_visible(),
+			)
+			.order(order::NAV),
+		);
+
+		out.push(
+			CommandInfo::new(
+				strings::commands::command_palette_open(
+					&self.key_config,
+				),
+				true,
+				!self.any_popup_visible(),
+			)
+			.order(order::NAV),
+		);
+
+		out.push(
+			CommandInfo::new(
+				strings::commands::preflight_open(&self.key_config),
+				true,
+				!self.any_popup_visible(),
+			)
+			.order(order::RARE_ACTION),
+		);
+
+		out.push(
+			CommandInfo::new(
+				strings::commands::bookmark_add(&self.key_config),
+				self.bookmark_location().is_some(),
+				!self.any_popup_visible(),
+			)
+			.order(order::RARE_ACTION),
+		);
+
+		out.push(
+			CommandInfo::new(
+				strings::commands::bookmarks_open(&self.key_config),
+				true,
+				!self.any_popup_visible(),
+			)
+			.order(order::NAV),
+		);
+
+		out.push(
+			CommandInfo::new(
+				strings::commands::git_config_open(&self.key_config),
+				true,
+				!self.any_popup_visible(),
+			)
+			.order(order::RARE_ACTION),
+		);
+
+		out.push(
+			CommandInfo::new(
+				strings::commands::timings_open(&self.key_config),
+				true,
+				!self.any_popup_visible(),
+			)
+			.order(order::RARE_ACTION),
+		);
+
+		out.push(
+			CommandInfo::new(
+				strings::commands::selection_mode(&self.key_config),
+				true,
+				!self.any_popup_visible(),
+			)
+			.order(order::RARE_ACTION),
+		);
+
+		out.push(
+			CommandInfo::new(
+				strings::commands::toggle_offline(
+					&self.key_config,
+					self.offline(),
+				),
+				true,
+				!self.any_popup_visible(),
+			)
+			.order(order::RARE_ACTION),
+		);
+	}
+
+	fn undo_info(&self, out: &mut Vec<CommandInfo>, force_all: bool) {
+		let visible = (!self.any_popup_visible()
+			&& self.status_tab.is_visible())
+			|| force_all;
+
+		out.push(CommandInfo::new(
+			strings::commands::undo_index_change(
+				&self.key_config,
+				self.index_history.len(),
+			),
+			!self.index_history.is_empty(),
+			visible,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::restore_discarded(&self.key_config),
+			self.last_discard.is_some(),
+			visible,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::undo_hunk_discard(&self.key_config),
+			self.hunk_undo_available(),
+			self.hunk_undo_available(),
+		));
+	}
+
+	/// `false` if `key` is not an undo key of the status tab
+	fn undo_for_key(&mut self, key: KeyEvent) -> bool {
+		if !self.status_tab.is_visible() {
+			false
+		} else if key == self.key_config.undo_index_change {
+			self.undo_index_change();
+			true
+		} else if key == self.key_config.restore_discarded {
+			self.restore_discarded();
+			true
+		} else {
+			false
+		}
+	}
+
+	/// restores the index as it was before the latest change
+	fn undo_index_change(&mut self) {
+		let snapshot = match self.index_history.pop_back() {
+			Some(snapshot) => snapshot,
+			None => return,
+		};
+
+		if let Err(e) = sync::restore_index(CWD, snapshot) {
+			self.index_history.push_back(snapshot);
+			self.queue.push(InternalEvent::ShowErrorMsg(
+				strings::undo_index_change_failed(&e.to_string()),
+			));
+		} else {
+			self.queue.push(InternalEvent::ShowToast(
+				Severity::Success,
+				strings::toast_index_change_undone(
+					self.index_history.len(),
+				),
+			));
+		}
+	}
+
+	/// checks the grace itself, `expire_notices` only runs on the tick
+	fn hunk_undo_available(&self) -> bool {
+		self.discarded_hunks.back().map_or(false, |hunk| {
+			hunk.since.elapsed() < HUNK_UNDO_GRACE
+		}) && !self.any_popup_visible()
+			&& self.status_tab.is_visible()
+	}
+
+	fn hunk_discarded(&mut self, path: String, discarded: Vec<u8>) {
+		if self.discarded_hunks.len() == HUNK_UNDO_CAPACITY {
+			self.discarded_hunks.pop_front();
+		}
+
+		let msg =
+			strings::toast_hunk_discarded(&path, &self.key_config);
+		self.queue.log_message(Severity::Info, &msg);
+		self.toast = Some((
+			Severity::Info,
+			msg,
+			Instant::now() + HUNK_UNDO_GRACE,
+		));
+
+		self.discarded_hunks.push_back(DiscardedHunk {
+			path,
+			patch: discarded,
+			since: Instant::now(),
+		});
+	}
+
+	/// applies the latest discarded hunk again, offers to copy its
+	/// patch if the file changed so it does not apply anymore
+	fn undo_hunk_discard(&mut self) {
+		let hunk = match self.discarded_hunks.pop_back() {
+			Some(hunk) => hunk,
+			None => return,
+		};
+
+		if let Err(e) = sync::apply_discarded_hunk(CWD, &hunk.patch) {
+			self.queue.push(InternalEvent::ConfirmAction(
+				Action::CopyDiscardedHunk(
+					String::from_utf8_lossy(&hunk.patch).into_owned(),
+					e.to_string(),
+				),
+			));
+		} else {
+			self.queue.push(InternalEvent::ShowToast(
+				Severity::Success,
+				strings::toast_hunk_discard_undone(&hunk.path),
+			));
+		}
+	}
+
+	/// keeps what a discard of `path` is about to destroy, a failing
+	/// backup does not stop the discard
+	fn backup_discard(&mut self, path: &str) {
+		self.last_discard = sync::backup_discarded(CWD, path)
+			.map_err(|e| log::error!("discard backup: {}", e))
+			.ok()
+			.filter(|backup| backup.file_count() > 0);
+	}
+
+	fn restore_discarded(&mut self) {
+		let backup = match self.last_discard.take() {
+			Some(backup) => backup,
+			None => return,
+		};
+
+		if let Err(e) = sync::restore_discarded(CWD, &backup) {
+			self.queue.push(InternalEvent::ShowErrorMsg(
+				strings::restore_discarded_failed(&e.to_string()),
+			));
+			self.last_discard = Some(backup);
+		} else {
+			self.queue.push(InternalEvent::ShowToast(
+				Severity::Success,
+				strings::toast_discard_restored(backup.file_count()),
+			));
+		}
+	}
+
+	/// what the placeholders of custom commands are filled with
+	fn command_selection(&self) -> CommandSelection {
+		let mut selection = CommandSelection {
+			branch: self.status_tab.branch_name(),
+			repo_root: repo_work_dir(CWD).ok(),
+			..CommandSelection::default()
+		};
+
+		if self.status_tab.is_visible() {
+			selection.file_list = true;
+			selection.file =
+				self.status_tab.selected_path().map(|(path, _)| path);
+		} else if self.files_tab.is_visible() {
+			selection.file_list = true;
+			selection.file = self.files_tab.selected_path();
+		} else if self.revlog.is_visible() {
+			selection.commit_list = true;
+			selection.commit = self
+				.revlog
+				.selected_commit()
+				.map(|id| id.to_string());
+		}
+
+		selection
+	}
+
+	/// keys no component handled
+	fn global_key_event(
+		&mut self,
+		k: KeyEvent,
+	) -> Result<NeedsUpdate> {
+		Ok(if k == self.key_config.tab_toggle {
+			self.toggle_tabs(false)?;
+			NeedsUpdate::COMMANDS
+		} else if k == self.key_config.tab_toggle_reverse {
+			self.toggle_tabs(true)?;
+			NeedsUpdate::COMMANDS
+		} else if k == self.key_config.tab_status
+			|| k == self.key_config.tab_log
+			|| k == self.key_config.tab_files
+			|| k == self.key_config.tab_stashing
+			|| k == self.key_config.tab_stashes
+		{
+			self.switch_tab(k)?;
+			NeedsUpdate::COMMANDS
+		} else if k == self.key_config.focus_next
+			|| k == self.key_config.focus_prev
+		{
+			let reverse = k == self.key_config.focus_prev;
+			if self.tab_panes_mut().cycle_focus(reverse)? {
+				NeedsUpdate::COMMANDS
+			} else {
+				NeedsUpdate::empty()
+			}
+		} else if k == self.key_config.cmd_bar_toggle {
+			self.cmdbar.borrow_mut().toggle_more();
+			NeedsUpdate::empty()
+		} else if k == self.key_config.open_options {
+			self.options_popup.show()?;
+			NeedsUpdate::ALL
+		} else if k == self.key_config.open_msg_history {
+			self.msg_history_popup.show()?;
+			NeedsUpdate::COMMANDS
+		} else if k == self.key_config.open_timings {
+			self.timings_popup.show()?;
+			NeedsUpdate::COMMANDS
+		} else if k == self.key_config.selection_mode {
+			self.enter_selection_mode();
+			NeedsUpdate::empty()
+		} else if k == self.key_config.toggle_offline {
+			self.repo_session.borrow_mut().toggle_offline();
+			self.status_tab
+				.pause_auto_fetch(self.auto_fetch_paused());
+			NeedsUpdate::COMMANDS
+		} else if k == self.key_config.open_repo_maintenance {
+			self.repo_maintenance_popup.show()?;
+			NeedsUpdate::COMMANDS
+		} else if k == self.key_config.open_repo_switcher {
+			self.repo_switcher_popup.open()?;
+			NeedsUpdate::COMMANDS
+		} else if k == self.key_config.open_command_palette {
+			self.command_palette_popup
+				.open(self.palette_entries())?;
+			NeedsUpdate::COMMANDS
+		} else if k == self.key_config.open_preflight {
+			self.queue.push(InternalEvent::OpenPreflight(None));
+			NeedsUpdate::COMMANDS
+		} else if k == self.key_config.open_git_config {
+			self.git_config_popup.open()?;
+			NeedsUpdate::COMMANDS
+		} else if k == self.key_config.open_bookmarks {
+			self.bookmarks_popup.open()?;
+			NeedsUpdate::COMMANDS
+		} else if k == self.key_config.bookmark_add {
+			self.add_bookmark()?;
+			NeedsUpdate::COMMANDS
+		} else if self.undo_for_key(k) {
+			NeedsUpdate::ALL | NeedsUpdate::COMMANDS
+		} else if self.run_custom_command_for_key(k) {
+			NeedsUpdate::COMMANDS
+		} else {
+			NeedsUpdate::empty()
+		})
+	}
+
+	/// `false` if no custom command is bound to `key`
+	fn run_custom_command_for_key(&mut self, key: KeyEvent) -> bool {
+		self.custom_commands
+			.iter()
+			.find(|command| command.key == Some(key))
+			.cloned()
+			.map_or(false, |command| {
+				self.run_custom_command_if_available(&command);
+				true
+			})
+	}
+
+	/// every command by name, with whether it can run in the
+	/// current context
+	fn palette_entries(&self) -> Vec<PaletteEntry> {
+		let context = self.allowed_commands(false);
+
+		let mut all = self.allowed_commands(true);
+		all.sort_by_cached_key(|command| command.text.id());
+		all.dedup_by_key(|command| command.text.id());
+
+		all.into_iter()
+			.filter(|command| {
+				command.text.key
+					!= Some(self.key_config.open_command_palette)
+			})
+			.map(|command| {
+				let id = command.text.id();
+				let runnable = command.text.key.is_some()
+					|| self
+						.keyless_custom_command(&command.text)
+						.is_some();
+				let state = match context
+					.iter()
+					.find(|c| c.available && c.text.id() == id)
+				{
+					_ if !runnable => PaletteState::KeysOnly,
+					Some(c) if c.enabled => PaletteState::Runnable,
+					Some(_) => PaletteState::Disabled,
+					None => PaletteState::Unavailable,
+				};
+
+				PaletteEntry {
+					text: command.text,
+					state,
+				}
+			})
+			.collect()
+	}
+
+	/// custom commands without a key only run from the palette
+	fn keyless_custom_command(
+		&self,
+		text: &CommandText,
+	) -> Option<&CustomCommand> {
+		self.custom_commands.iter().find(|command| {
+			command.key.is_none() && command.name == text.name
+		})
+	}
+
+	/// runs `command` as if its key was pressed
+	fn run_palette_command(
+		&mut self,
+		command: &CommandText,
+	) -> Result<()> {
+		log::info!("command palette: {}", command.id());
+
+		if let Some(key) = command.key {
+			return self.event(InputEvent::Input(Event::Key(key)));
+		}
+
+		if let Some(custom) =
+			self.keyless_custom_command(command).cloned()
+		{
+			self.run_custom_command_if_available(&custom);
+			self.process_queue(NeedsUpdate::COMMANDS)?;
+		}
+
+		Ok(())
+	}
+
+	fn run_custom_command_if_available(
+		&mut self,
+		command: &CustomCommand,
+	) {
+		let selection = self.command_selection();
+		if command.is_available(&selection) {
+			if let Some(line) = command.expand(&selection) {
+				self.run_custom_command(command, line);
+			} else {
+				self.queue.push(InternalEvent::ShowToast(
+					Severity::Info,
+					strings::custom_command_unavailable(
+						&command.name,
+					),
+				));
+			}
+		}
+	}
+
+	fn run_custom_command(
+		&mut self,
+		command: &CustomCommand,
+		line: String,
+	) {
+		log::info!("custom command '{}': {}", command.name, line);
+
+		if command.capture_output {
+			self.custom_command_job.spawn(
+				AsyncCustomCommandJob::new(
+					command.name.clone(),
+					line,
+				),
+			);
+			self.queue.push(InternalEvent::ShowToast(
+				Severity::Info,
+				strings::custom_command_started(&command.name),
+			));
+		} else {
+			self.command_to_run = Some((command.name.clone(), line));
+			self.input.set_polling(false);
+		}
+	}
+
+	/// the command may have changed anything
+	fn refresh_after_custom_command(&mut self) {
+		self.refresh.mark(RefreshDomains::all());
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+	}
+
+	fn run_terminal_command(
+		&mut self,
+		name: &str,
+		command: &str,
+	) -> Result<()> {
+		let error = match custom_commands::run_in_terminal(command) {
+			Ok(status) if status.success() => None,
+			Ok(status) => Some(status.to_string()),
+			Err(e) => Some(e.to_string()),
+		};
+
+		if let Some(error) = error {
+			let msg = strings::custom_command_failed(name, &error);
+			log::error!("{}", msg);
+			self.queue.log_message(Severity::Error, &msg);
+			self.msg.show_error(&msg)?;
+		}
+
+		self.refresh_after_custom_command();
+		self.process_queue(NeedsUpdate::ALL)
+	}
+
+	fn custom_command_done(&mut self) {
+		let (name, result) = match self
+			.custom_command_job
+			.take_last()
+			.and_then(|job| job.result())
+		{
+			Some(result) => result,
+			None => return,
+		};
+
+		match result {
+			Ok(output) if output.success => {
+				let text =
+					format!("{}{}", output.stdout, output.stderr);
+				if !text.trim().is_empty() {
+					self.queue.log_message(
+						Severity::Info,
+						&strings::custom_command_output(
+							&name,
+							text.trim_end(),
+						),
+					);
+				}
+				self.queue.push(InternalEvent::ShowToast(
+					Severity::Success,
+					strings::custom_command_finished(&name),
+				));
+			}
+			Ok(output) => {
+				// some tools report their errors on stdout
+				let error = if output.stderr.trim().is_empty() {
+					&output.stdout
+				} else {
+					&output.stderr
+				};
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					strings::custom_command_failed(
+						&name,
+						error.trim_end(),
+					),
+				));
+			}
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					strings::custom_command_failed(
+						&name,
+						&e.to_string(),
+					),
+				));
+			}
+		}
+
+		self.refresh_after_custom_command();
+	}
+
+	/// references that became stale since are ignored
+	fn restore_ui_state(&mut self) {
+		let saved = match ui_state::load() {
+			Ok((remember, state)) => {
+				let mut options = self.options.borrow_mut();
+				options.remember_ui_state = remember.ui_state;
+				options.persist_bookmarks = remember.bookmarks;
+				state.filter(|_| remember.ui_state)
+			}
+			Err(e) => {
+				log::error!("load ui state: {}", e);
+				None
+			}
+		};
+
+		let state = match self.repo_session.borrow().state().or(saved)
+		{
+			Some(state) => state,
+			None => return,
+		};
+
+		if let Some(diff) = state.diff {
+			self.options.borrow_mut().diff = diff.into();
+		}
+		if let Some(id) = state
+			.revlog_selection
+			.and_then(|id| CommitId::from_hex(&id).ok())
+		{
+			self.revlog.restore_selection(id);
+		}
+		if let Some(split) = state.status_split {
+			self.status_tab.set_split(split);
+		}
+		if let Some(path) = state.status_selection {
+			self.status_tab.restore_selection(path);
+		}
+		self.select_branch_popup
+			.set_show_remote(state.branches_remote);
+		self.bookmarks_popup.set_bookmarks(state.bookmarks);
+
+		if state.tab != self.tab && state.tab < self.get_tabs().len()
+		{
+			if let Err(e) = self.set_tab(state.tab) {
+				log::error!("restore tab: {}", e);
+			}
+		}
+	}
+
+	/// what is selected in the current tab, to bookmark it
+	fn bookmark_location(&self) -> Option<Location> {
+		match self.tab {
+			0 => self.status_tab.location(),
+			1 => self.revlog.selected_commit().map(Location::commit),
+			2 => {
+				self.files_tab.selected_path().map(Location::TreeFile)
+			}
+			_ => None,
+		}
+	}
+
+	fn add_bookmark(&mut self) -> Result<()> {
+		let location = match self.bookmark_location() {
+			Some(location) => location,
+			None => return Ok(()),
+		};
+
+		let context = match &location {
+			Location::Commit(id) => CommitId::from_hex(id)
+				.and_then(|id| sync::get_commit_details(CWD, id))
+				.ok()
+				.and_then(|details| details.message)
+				.map(|message| message.subject)
+				.unwrap_or_default(),
+			Location::StatusFile { path, .. }
+			| Location::TreeFile(path) => path.clone(),
+		};
+
+		self.bookmarks_popup.add(location, context)
+	}
+
+	/// switches to the view of `location` and selects it there
+	fn goto_location(&mut self, location: Location) -> Result<()> {
+		match location {
+			Location::Commit(id) => {
+				self.set_tab(1)?;
+				self.revlog
+					.restore_selection(CommitId::from_hex(&id)?);
+			}
+			Location::StatusFile { path, line } => {
+				self.set_tab(0)?;
+				self.status_tab.goto_file(path, line);
+			}
+			Location::TreeFile(path) => {
+				self.set_tab(2)?;
+				self.files_tab.select_file(&path)?;
+			}
+		}
+
+		Ok(())
+	}
+
+	fn set_tab(&mut self, tab: usize) -> Result<()> {
+		let tabs = self.get_tabs();
+		for (i, t) in tabs.into_iter().enumerate() {
+			if tab == i {
+				t.show()?;
+			} else {
+				t.hide();
+			}
+		}
+
+		self.tab = tab;
 
 		Ok(())
 	}
 
 	fn update_commands(&mut self) {
-		self.help.set_cmds(self.commands(true));
-		self.cmdbar.borrow_mut().set_cmds(self.commands(false));
+		self.help.set_cmds(self.allowed_commands(true));
+		self.cmdbar
+			.borrow_mut()
+			.set_cmds(self.allowed_commands(false));
 	}
 
 	fn process_queue(&mut self, flags: NeedsUpdate) -> Result<()> {
@@ -578,12 +1864,16 @@ impl App {
 		flags.insert(new_flags);
 
 		if flags.contains(NeedsUpdate::ALL) {
+			self.refresh.mark(RefreshDomains::all());
+		}
+		if flags.intersects(NeedsUpdate::ALL | NeedsUpdate::REFRESH) {
 			self.update()?;
 		}
 		//TODO: make this a queue event?
 		//NOTE: set when any tree component changed selection
 		if flags.contains(NeedsUpdate::DIFF) {
 			self.status_tab.update_diff()?;
+			self.stashing_tab.update_diff()?;
 			self.inspect_commit_popup.update_diff()?;
 			self.compare_commits_popup.update_diff()?;
 		}
@@ -619,21 +1909,87 @@ impl App {
 		ev: InternalEvent,
 	) -> Result<NeedsUpdate> {
 		let mut flags = NeedsUpdate::empty();
+
+		// whatever path it was triggered from
+		if ev.mutating() && self.readonly() {
+			log::warn!("read-only mode: {} rejected", ev.name());
+			self.queue.push(InternalEvent::ShowToast(
+				Severity::Error,
+				strings::readonly_rejected(ev.name()),
+			));
+			return Ok(flags);
+		}
+
+		if ev.remote() && self.offline() {
+			log::warn!("offline mode: {} rejected", ev.name());
+			self.queue.push(InternalEvent::ShowToast(
+				Severity::Error,
+				strings::offline_rejected(ev.name()),
+			));
+			return Ok(flags);
+		}
+
 		match ev {
 			InternalEvent::ConfirmedAction(action) => {
 				self.process_confirmed_action(action, &mut flags)?;
 			}
 			InternalEvent::ConfirmAction(action) => {
-				self.reset.open(action)?;
-				flags.insert(NeedsUpdate::COMMANDS);
+				let needs_confirmation = action.destructive().map_or(
+					true,
+					|(kind, count)| {
+						self.options
+							.borrow()
+							.confirm_policy
+							.needs_confirmation(kind, count)
+					},
+				);
+
+				if needs_confirmation {
+					self.reset.open(action)?;
+					flags.insert(NeedsUpdate::COMMANDS);
+				} else {
+					self.process_confirmed_action(
+						action, &mut flags,
+					)?;
+				}
 			}
 			InternalEvent::ShowErrorMsg(msg) => {
-				self.msg.show_error(msg.as_str())?;
+				// an error every refresh runs into again would
+				// otherwise be reported (and refresh) over and over
+				if !self.msg.is_showing(&msg) {
+					self.queue.log_message(Severity::Error, &msg);
+					self.msg.show_error(msg.as_str())?;
+					flags.insert(
+						NeedsUpdate::ALL | NeedsUpdate::COMMANDS,
+					);
+				}
+			}
+			InternalEvent::ShowInfoMsg(msg) => {
+				self.queue.log_message(Severity::Info, &msg);
+				self.msg.show_info(msg.as_str())?;
 				flags
 					.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
 			}
+			InternalEvent::ShowToast(severity, msg) => {
+				self.queue.log_message(severity, &msg);
+				self.toast = Some((
+					severity,
+					msg,
+					Instant::now() + TOAST_DURATION,
+				));
+			}
 			InternalEvent::Update(u) => flags.insert(u),
-			InternalEvent::OpenCommit => self.commit.show()?,
+			InternalEvent::RefreshInPlace(domains) => {
+				self.status_tab.keep_selection();
+				self.refresh.mark(domains);
+				flags.insert(NeedsUpdate::REFRESH);
+			}
+			InternalEvent::OpenCommit => {
+				self.commit.set_upstream_behind(
+					self.status_tab.upstream_behind(),
+				);
+				self.commit.show()?;
+			}
 			InternalEvent::PopupStashing(opts) => {
 				self.stashmsg_popup.options(opts);
 				self.stashmsg_popup.show()?;
@@ -641,11 +1997,49 @@ impl App {
 			InternalEvent::TagCommit(id) => {
 				self.tag_commit_popup.open(id)?;
 			}
-			InternalEvent::BlameFile(path) => {
-				self.blame_file_popup.open(&path)?;
+			InternalEvent::BlameFile(file) => {
+				self.blame_file_popup.open(file)?;
 				flags
 					.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
 			}
+			InternalEvent::CompareWorkdir(id, path) => {
+				if let Err(error) =
+					self.compare_workdir_popup.open(id, path)
+				{
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("compare error:\n{}", error),
+					));
+				} else {
+					flags.insert(NeedsUpdate::COMMANDS);
+				}
+			}
+			InternalEvent::ViewFile(source) => {
+				if let Err(error) = self.file_view_popup.open(source)
+				{
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("view file error:\n{}", error),
+					));
+				} else {
+					flags.insert(NeedsUpdate::COMMANDS);
+				}
+			}
+			InternalEvent::OpenConflict(path) => {
+				if let Err(error) = self.conflict_popup.open(path) {
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("conflict error:\n{}", error),
+					));
+				} else {
+					flags.insert(NeedsUpdate::COMMANDS);
+				}
+			}
+			InternalEvent::OpenMergeTool(path) => {
+				self.conflict_popup.hide();
+				self.command_to_run = Some((
+					strings::MERGETOOL_NAME.to_string(),
+					custom_commands::mergetool_command(&path),
+				));
+				self.input.set_polling(false);
+			}
 			InternalEvent::CreateBranch => {
 				self.create_branch_popup.open()?;
 			}
@@ -653,13 +2047,39 @@ impl App {
 				self.rename_branch_popup
 					.open(branch_ref, cur_name)?;
 			}
+			InternalEvent::EditBranchDescription(branch) => {
+				self.branch_description_popup.open(branch)?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::OpenStagePattern(paths, unstage) => {
+				self.stage_pattern_popup.open(paths, unstage)?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
 			InternalEvent::SelectBranch => {
 				self.select_branch_popup.open()?;
 			}
+			InternalEvent::CheckoutPreviousBranch => {
+				self.checkout_previous_branch();
+				flags.insert(NeedsUpdate::ALL);
+			}
+			InternalEvent::RecentBranches => {
+				if let Err(e) = self.recent_branches_popup.open() {
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("recent branches error:\n{}", e),
+					));
+				}
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
 			InternalEvent::Tags => {
 				self.tags_popup.open()?;
 			}
 			InternalEvent::TabSwitch => self.set_tab(0)?,
+			InternalEvent::FocusWorkdir => {
+				self.set_tab(0)?;
+				self.status_tab.focus_workdir()?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::ShowStashList => self.set_tab(4)?,
 			InternalEvent::InspectCommit(id, tags) => {
 				self.inspect_commit_popup.open(id, tags)?;
 				flags
@@ -675,6 +2095,13 @@ impl App {
 					flags.insert(NeedsUpdate::ALL);
 				}
 			}
+			InternalEvent::SelectFileInRevlog(id, path) => {
+				self.inspect_commit_popup.hide();
+				self.compare_commits_popup.hide();
+				self.set_tab(1)?;
+				self.revlog.select_commit_file(id, path)?;
+				flags.insert(NeedsUpdate::ALL);
+			}
 			InternalEvent::OpenExternalEditor(path) => {
 				self.input.set_polling(false);
 				self.external_editor_popup.show()?;
@@ -689,6 +2116,18 @@ impl App {
 				self.pull_popup.fetch(branch)?;
 				flags.insert(NeedsUpdate::ALL);
 			}
+			InternalEvent::FetchPopup(branch) => {
+				self.fetch_popup.open(branch)?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::FetchRemote(remote, refspecs) => {
+				self.pull_popup.fetch_remote(remote, refspecs)?;
+				flags.insert(NeedsUpdate::ALL);
+			}
+			InternalEvent::PruneRemote(remote) => {
+				self.prune_remote_popup.open(remote)?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
 			InternalEvent::PushTags => {
 				self.push_tags_popup.push_tags()?;
 				flags.insert(NeedsUpdate::ALL);
@@ -708,7 +2147,8 @@ impl App {
 			}
 			InternalEvent::OptionSwitched(o) => {
 				match o {
-					AppOption::StatusShowUntracked => {
+					AppOption::StatusShowUntracked
+					| AppOption::StatusUntrackedLimit => {
 						self.status_tab.update()?;
 					}
 					AppOption::DiffContextLines
@@ -716,26 +2156,210 @@ impl App {
 					| AppOption::DiffInterhunkLines => {
 						self.status_tab.update_diff()?;
 					}
+					AppOption::DiffMovedMinLines => {
+						self.inspect_commit_popup.update_diff()?;
+					}
+					// log, commit and diffs are redrawn from the
+					// options on next draw, indicators from the
+					// theme, stash reads them once stashing, the
+					// commit prefill when opening the commit popup,
+					// trailers and the large file threshold when
+					// committing, the latter also when staging, ui
+					// state is saved on exit
+					AppOption::LogDateFormat
+					| AppOption::LogDateSource
+					| AppOption::LogGrouping
+					| AppOption::DiffTabWidth
+					| AppOption::DiffTrailingWhitespace
+					| AppOption::DiffShowWhitespace
+					| AppOption::StashShowList
+					| AppOption::ProtectedBranches
+					| AppOption::CommitLint
+					| AppOption::CommitLintTypes
+					| AppOption::CommitPrefillPattern
+					| AppOption::CommitPrefillTemplate
+					| AppOption::CommitTrailers
+					| AppOption::LargeFileThreshold
+					| AppOption::Confirm(_)
+					| AppOption::HighContrast
+					| AppOption::DiffGlyphs
+					| AppOption::SelectionMarker
+					| AppOption::StatusLetters
+					| AppOption::RememberUiState
+					| AppOption::PersistBookmarks => (),
+					AppOption::AutoFetchInterval => {
+						self.status_tab
+							.update_auto_fetch_interval()?;
+					}
 				}
 
 				flags.insert(NeedsUpdate::ALL);
 			}
-			InternalEvent::CompareCommits(id, other) => {
-				self.compare_commits_popup.open(id, other)?;
+			InternalEvent::CompareCommits(id, other) => {
+				self.compare_commits_popup.open(id, other)?;
+				flags
+					.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::CompareBranches(base, target) => {
+				if let Err(e) = self
+					.compare_commits_popup
+					.open_branches(base, target)
+				{
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("compare branches error:\n{}", e),
+					));
+				}
+				flags
+					.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::OpenSinceBranching => {
+				if let Err(e) =
+					self.compare_commits_popup.open_since_branching()
+				{
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!(
+							"changes since branching error:\n{}",
+							e
+						),
+					));
+				}
+				flags
+					.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::FileFinderChanged(file) => {
+				self.files_tab.file_finder_update(&file);
+				self.revision_files_popup.file_finder_update(&file);
+				flags
+					.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::OpenLogFilter(range) => {
+				self.log_filter_popup.open(&range)?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::FilterLog(range) => {
+				self.revlog.set_date_range(range)?;
 				flags
 					.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
 			}
-			InternalEvent::FileFinderChanged(file) => {
-				self.files_tab.file_finder_update(&file);
-				self.revision_files_popup.file_finder_update(&file);
+			InternalEvent::OpenLogAuthors => {
+				self.log_authors_popup.open()?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::FilterLogAuthor(email) => {
+				self.revlog.set_author(Some(email))?;
+				flags
+					.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::GotoLocation(location) => {
+				self.goto_location(location)?;
+				flags
+					.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::OpenJumpToRef => {
+				self.jump_to_ref_popup.open()?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::JumpToRef(target) => {
+				self.revlog.jump_to(target)?;
 				flags
 					.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
 			}
+			InternalEvent::OpenIndexFlags => {
+				self.index_flags_popup.open()?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::OpenLogExport(ids) => {
+				self.log_export_popup.open(ids)?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::OpenPreflight(issues) => {
+				if let Err(e) = self.preflight_popup.open(issues) {
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("preflight check error:\n{}", e),
+					));
+				}
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::ResumeCommit => {
+				if let Err(e) = self.commit.resume() {
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("commit error:\n{}", e),
+					));
+				}
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::SwitchRepo(path) => {
+				if self.remote_op_pending() {
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						strings::REPO_SWITCH_PENDING.to_string(),
+					));
+				} else {
+					self.repo_to_open = Some(path);
+					self.do_quit = true;
+				}
+			}
+			InternalEvent::IndexChanged(snapshot) => {
+				if self.index_history.len() == INDEX_HISTORY_CAPACITY
+				{
+					self.index_history.pop_front();
+				}
+				self.index_history.push_back(snapshot);
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::CherryPick(request) => {
+				self.cherry_pick_popup.open(request)?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::RunCommand(command) => {
+				self.palette_command = Some(command);
+			}
 		};
 
 		Ok(flags)
 	}
 
+	/// switches to the most recent branch of the history that still
+	/// exists (other than the current one)
+	fn checkout_previous_branch(&mut self) {
+		let targets = branch_history::existing_targets(
+			&self.branch_history.borrow(),
+		);
+		let res = targets.and_then(|targets| {
+			targets
+				.into_iter()
+				.next()
+				.map(|branch| {
+					branch_history::checkout(
+						&self.branch_history,
+						&branch,
+					)
+					.map(|()| branch)
+				})
+				.transpose()
+		});
+
+		match res {
+			Ok(Some(branch)) => {
+				self.queue.push(InternalEvent::ShowToast(
+					Severity::Success,
+					strings::toast_checked_out(&branch),
+				));
+			}
+			Ok(None) => {
+				self.queue.push(InternalEvent::ShowToast(
+					Severity::Info,
+					strings::toast_no_previous_branch(),
+				));
+			}
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("checkout error:\n{}", e),
+				));
+			}
+		}
+	}
+
+	#[allow(clippy::too_many_lines)]
 	fn process_confirmed_action(
 		&mut self,
 		action: Action,
@@ -743,6 +2367,8 @@ impl App {
 	) -> Result<()> {
 		match action {
 			Action::Reset(r) => {
+				self.discarded_hunks.clear();
+				self.backup_discard(&r.path);
 				if self.status_tab.reset(&r) {
 					flags.insert(NeedsUpdate::ALL);
 				}
@@ -757,13 +2383,41 @@ impl App {
 				flags.insert(NeedsUpdate::ALL);
 			}
 			Action::ResetHunk(path, hash) => {
-				sync::reset_hunk(CWD, &path, hash)?;
+				self.backup_discard(&path);
+				let discarded = sync::discard_hunk(CWD, &path, hash)?;
+				self.hunk_discarded(path, discarded);
 				flags.insert(NeedsUpdate::ALL);
 			}
 			Action::ResetLines(path, lines) => {
+				self.discarded_hunks.clear();
+				self.backup_discard(&path);
 				sync::discard_lines(CWD, &path, &lines)?;
 				flags.insert(NeedsUpdate::ALL);
 			}
+			Action::ResolveConflict(path, side) => {
+				if let Err(e) =
+					sync::resolve_conflict(CWD, &path, side)
+				{
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						strings::resolve_conflict_failed(
+							&e.to_string(),
+						),
+					));
+				}
+				self.conflict_popup.hide();
+				flags.insert(NeedsUpdate::ALL);
+			}
+			Action::RenameCheckedOutBranch(branch_ref, name, _) => {
+				if let Err(e) =
+					sync::rename_branch(CWD, &branch_ref, &name, true)
+				{
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("rename branch error:\n{}", e),
+					));
+				}
+				flags.insert(NeedsUpdate::ALL);
+				self.select_branch_popup.update_branches()?;
+			}
 			Action::DeleteBranch(branch_ref, true) => {
 				if let Err(e) = sync::delete_branch(CWD, &branch_ref)
 				{
@@ -795,6 +2449,34 @@ impl App {
 				flags.insert(NeedsUpdate::ALL);
 				self.select_branch_popup.update_branches()?;
 			}
+			Action::DeleteMergedBranches(branch_refs) => {
+				let failures = branch_refs
+					.iter()
+					.filter_map(|branch_ref| {
+						sync::delete_branch(CWD, branch_ref)
+							.err()
+							.map(|e| format!("{}: {}", branch_ref, e))
+					})
+					.collect::<Vec<_>>();
+
+				self.queue.push(if failures.is_empty() {
+					InternalEvent::ShowToast(
+						Severity::Success,
+						strings::toast_deleted_merged(
+							branch_refs.len(),
+						),
+					)
+				} else {
+					InternalEvent::ShowErrorMsg(
+						strings::delete_merged_failed(
+							&failures,
+							branch_refs.len(),
+						),
+					)
+				});
+				flags.insert(NeedsUpdate::ALL);
+				self.select_branch_popup.update_branches()?;
+			}
 			Action::DeleteTag(tag_name) => {
 				if let Err(error) = sync::delete_tag(CWD, &tag_name) {
 					self.queue.push(InternalEvent::ShowErrorMsg(
@@ -809,12 +2491,96 @@ impl App {
 				self.queue
 					.push(InternalEvent::Push(branch, force, false));
 			}
+			Action::PushProtected(branch, force) => {
+				self.queue.push(if force {
+					InternalEvent::ConfirmAction(Action::ForcePush(
+						branch, force,
+					))
+				} else {
+					InternalEvent::Push(branch, force, false)
+				});
+			}
 			Action::PullMerge { rebase, .. } => {
 				self.pull_popup.try_conflict_free_merge(rebase);
 				flags.insert(NeedsUpdate::ALL);
 			}
-			Action::AbortMerge => {
-				self.status_tab.abort_merge();
+			Action::AbortRepoState(_) => {
+				self.status_tab.abort_repo_state();
+				flags.insert(NeedsUpdate::ALL);
+			}
+			Action::RetryIndexOp(op) => {
+				run_index_op(
+					&self.queue,
+					strings::INDEX_RETRY_ERROR,
+					op,
+				);
+				flags.insert(NeedsUpdate::ALL);
+			}
+			Action::StageLfsUnconverted(_, op)
+			| Action::StageLargeFiles(_, op) => {
+				run_index_op(
+					&self.queue,
+					strings::STAGE_LFS_ERROR,
+					op,
+				);
+				flags.insert(NeedsUpdate::ALL);
+			}
+			Action::CommitLargeFiles(_) => {
+				if let Err(e) = self.commit.commit_large_files() {
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("commit error:\n{}", e),
+					));
+				}
+			}
+			Action::CommitEmpty => {
+				if let Err(e) = self.commit.commit_empty() {
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("commit error:\n{}", e),
+					));
+				}
+			}
+			Action::CleanupStaleState(state) => {
+				self.cleanup_stale_state(&state);
+				flags.insert(NeedsUpdate::ALL);
+			}
+			Action::LogExportOverwrite(path) => {
+				self.log_export_popup.export(path);
+			}
+			Action::CopyDiscardedHunk(patch, _) => {
+				if let Err(e) = crate::clipboard::copy_string(&patch)
+				{
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						e.to_string(),
+					));
+				}
+			}
+			Action::SkipWorktree(path) => {
+				if let Err(e) = sync::set_index_flag(
+					CWD,
+					&path,
+					IndexFlag::SkipWorktree,
+					true,
+				) {
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						strings::skip_worktree_failed(&e.to_string()),
+					));
+				}
+				flags.insert(NeedsUpdate::ALL);
+			}
+			Action::AutosquashRebase(onto, _) => {
+				self.select_branch_popup.hide();
+				self.autosquash(onto);
+				flags.insert(NeedsUpdate::ALL);
+			}
+			Action::BisectFound(id, _) => {
+				if self.bisect_reset() {
+					self.revlog.restore_selection(id);
+					self.set_tab(1)?;
+				}
+				flags.insert(NeedsUpdate::ALL);
+			}
+			Action::BisectReset => {
+				self.bisect_reset();
 				flags.insert(NeedsUpdate::ALL);
 			}
 		};
@@ -822,6 +2588,125 @@ impl App {
 		Ok(())
 	}
 
+	/// `false` if the reset failed, the error is shown
+	fn bisect_reset(&mut self) -> bool {
+		let res = sync::bisect_reset(CWD);
+		self.revlog.update_bisect();
+
+		if let Err(e) = res {
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"bisect reset error:\n{}",
+				e
+			)));
+			return false;
+		}
+
+		true
+	}
+
+	fn autosquash(&self, onto: CommitId) {
+		match sync::autosquash_rebase(CWD, onto) {
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("autosquash error:\n{}", e),
+				));
+			}
+			Ok(result) => {
+				self.queue.push(InternalEvent::ShowToast(
+					if result.conflict {
+						Severity::Error
+					} else {
+						Severity::Success
+					},
+					strings::toast_autosquash(result.conflict),
+				));
+
+				if !result.unmatched.is_empty() {
+					self.queue.push(InternalEvent::ShowInfoMsg(
+						strings::autosquash_unmatched(
+							&result.unmatched,
+						),
+					));
+				}
+			}
+		}
+	}
+
+	fn cleanup_stale_state(&mut self, state: &StaleState) {
+		if let Err(e) = sync::cleanup_stale_state(CWD, state) {
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"cleanup error:\n{}",
+				e
+			)));
+		} else {
+			self.queue.push(InternalEvent::ShowToast(
+				Severity::Success,
+				strings::toast_cleaned_up_stale_state(state),
+			));
+		}
+
+		self.repo_maintenance_popup.update();
+	}
+
+	/// `commands` without the ones changing the repo in read-only
+	/// mode, the ones talking to a remote greyed out in offline mode
+	fn allowed_commands(&self, force_all: bool) -> Vec<CommandInfo> {
+		let mut commands = self.commands(force_all);
+		if self.readonly() {
+			commands.retain(|command| !command.text.mutating);
+		}
+		if self.offline() {
+			for command in &mut commands {
+				command.enabled &= !command.text.remote;
+			}
+		}
+
+		commands
+	}
+
+	fn readonly(&self) -> bool {
+		self.repo_session.borrow().readonly()
+	}
+
+	fn offline(&self) -> bool {
+		self.repo_session.borrow().offline()
+	}
+
+	/// label of the command talking to a remote that `key` runs in
+	/// the current context, if rejected in offline mode
+	fn offline_rejected_key(&self, key: KeyEvent) -> Option<String> {
+		if !self.offline() || self.status_tab.is_typing() {
+			return None;
+		}
+
+		self.commands(false)
+			.into_iter()
+			.find(|command| {
+				command.available
+					&& command.text.remote
+					&& command.text.key == Some(key)
+			})
+			.map(|command| command.text.label().to_string())
+	}
+
+	/// label of the command changing the repo that `key` runs in the
+	/// current context, if rejected in read-only mode
+	fn readonly_rejected_key(&self, key: KeyEvent) -> Option<String> {
+		// the search input takes every key
+		if !self.readonly() || self.status_tab.is_typing() {
+			return None;
+		}
+
+		self.commands(false)
+			.into_iter()
+			.find(|command| {
+				command.available
+					&& command.text.mutating
+					&& command.text.key == Some(key)
+			})
+			.map(|command| command.text.label().to_string())
+	}
+
 	fn commands(&self, force_all: bool) -> Vec<CommandInfo> {
 		let mut res = Vec::new();
 
@@ -861,6 +2746,14 @@ impl App {
 			)
 			.order(order::NAV),
 		);
+		res.push(
+			CommandInfo::new(
+				strings::commands::focus_cycle(&self.key_config),
+				self.tab_panes().focusable_panes().len() > 1,
+				!self.any_popup_visible(),
+			)
+			.order(order::NAV),
+		);
 		res.push(
 			CommandInfo::new(
 				strings::commands::options_popup(&self.key_config),
@@ -870,6 +2763,19 @@ impl App {
 			.order(order::NAV),
 		);
 
+		res.push(
+			CommandInfo::new(
+				strings::commands::msg_history_open(&self.key_config),
+				true,
+				!self.any_popup_visible(),
+			)
+			.order(order::NAV),
+		);
+
+		self.global_popups_info(&mut res);
+		self.undo_info(&mut res, force_all);
+		self.custom_commands_info(&mut res, force_all);
+
 		res.push(
 			CommandInfo::new(
 				strings::commands::quit(&self.key_config),
@@ -882,6 +2788,152 @@ impl App {
 		res
 	}
 
+	/// everything drawn before is stale now, so the whole
+	/// terminal gets cleared on the next draw
+	fn on_resize(&mut self) {
+		for c in self.components() {
+			c.on_resize();
+		}
+
+		self.requires_redraw.set(true);
+	}
+
+	/// the main panel of the tab as plain lines
+	fn selectable_text(&self) -> Option<Vec<String>> {
+		match self.tab {
+			0 => self.status_tab.selectable_text(),
+			1 => self.revlog.selectable_text(),
+			2 => self.files_tab.selectable_text(),
+			3 => self.stashing_tab.selectable_text(),
+			4 => self.stashlist_tab.selectable_text(),
+			_ => None,
+		}
+	}
+
+	fn enter_selection_mode(&mut self) {
+		self.selection_text = self.selectable_text();
+
+		if self.selection_text.is_none() {
+			self.queue.push(InternalEvent::ShowToast(
+				Severity::Info,
+				strings::SELECTION_MODE_EMPTY.to_string(),
+			));
+		}
+	}
+
+	/// every key but the ones leaving it is swallowed, so copy
+	/// shortcuts of the terminal do not reach the ui (or quit it)
+	fn selection_mode_event(&mut self, ev: Event) {
+		match ev {
+			Event::Key(k)
+				if k == self.key_config.selection_mode
+					|| k == self.key_config.exit_popup =>
+			{
+				self.selection_text = None;
+				self.requires_redraw.set(true);
+			}
+			Event::Resize(..) => self.on_resize(),
+			_ => (),
+		}
+	}
+
+	/// drops the toast, notices and discarded hunks that timed out,
+	/// `true` if any was visible
+	fn expire_notices(&mut self) -> bool {
+		let now = Instant::now();
+
+		let toast_expired =
+			self.toast.as_ref().map_or(false, |toast| now >= toast.2);
+		if toast_expired {
+			self.toast = None;
+		}
+
+		let hunks = self.discarded_hunks.len();
+		self.discarded_hunks.retain(|hunk| {
+			now.duration_since(hunk.since) < HUNK_UNDO_GRACE
+		});
+		let hunks_expired = hunks != self.discarded_hunks.len();
+
+		self.status_tab.expire_auto_fetch_notice()
+			| toast_expired
+			| hunks_expired
+	}
+
+	/// draws the latest toast over the right end of the command bar
+	fn draw_toast<B: Backend>(&self, f: &mut Frame<B>, r: Rect) {
+		if let Some((severity, txt, until)) = &self.toast {
+			if Instant::now() >= *until {
+				return;
+			}
+
+			let txt = format!(
+				" {} ",
+				txt.lines().next().unwrap_or_default()
+			);
+			let width = u16::try_from(txt.width())
+				.unwrap_or(u16::MAX)
+				.min(r.width / 2);
+
+			let area = Rect::new(
+				r.x + r.width.saturating_sub(width),
+				r.y,
+				width,
+				r.height.min(1),
+			);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(txt)
+					.style(self.theme.message(*severity, true)),
+				area,
+			);
+		}
+	}
+
+	fn draw_too_small<B: Backend>(f: &mut Frame<B>, r: Rect) {
+		let txt = strings::terminal_too_small(
+			r.width, r.height, MIN_SIZE.0, MIN_SIZE.1,
+		);
+
+		let mut area = r;
+		area.y += r.height / 2;
+		area.height = r.height.min(1);
+
+		f.render_widget(
+			Paragraph::new(txt).alignment(Alignment::Center),
+			area,
+		);
+	}
+
+	/// right of the tabs, so it stays visible on all of them
+	/// read-only mode, offline mode and bisecting, right of the tabs
+	fn draw_banner<B: Backend>(&self, f: &mut Frame<B>, r: Rect) {
+		let banner = self
+			.readonly()
+			.then(|| strings::READONLY_BANNER.to_string())
+			.into_iter()
+			.chain(
+				self.offline()
+					.then(|| strings::OFFLINE_BANNER.to_string()),
+			)
+			.chain(self.revlog.bisect().map(strings::bisect_banner))
+			.collect::<Vec<_>>();
+
+		if !banner.is_empty() {
+			let r = r.inner(&Margin {
+				vertical: 0,
+				horizontal: 1,
+			});
+
+			f.render_widget(
+				Paragraph::new(banner.join(" | "))
+					.style(self.theme.text_warning())
+					.alignment(Alignment::Right),
+				Rect { height: 1, ..r },
+			);
+		}
+	}
+
 	//TODO: make this dynamic
 	fn draw_tabs<B: Backend>(&self, f: &mut Frame<B>, r: Rect) {
 		let r = r.inner(&Margin {
@@ -916,3 +2968,275 @@ impl App {
 		);
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::repo_session::RepoSession;
+	use crossbeam_channel::unbounded;
+	use tui::{backend::TestBackend, Terminal};
+
+	fn test_app() -> App {
+		test_app_with(SharedRepoSession::default())
+	}
+
+	/// the receivers are leaked so sending to them never fails
+	fn test_app_with(session: SharedRepoSession) -> App {
+		let (tx_git, rx_git) = unbounded();
+		let (tx_app, rx_app) = unbounded();
+		std::mem::forget(rx_git);
+		std::mem::forget(rx_app);
+
+		App::new(
+			&tx_git,
+			&tx_app,
+			Input::new(),
+			SharedTheme::default(),
+			SharedKeyConfig::default(),
+			session,
+		)
+	}
+
+	fn draw_sizes(app: &App) {
+		for width in 0..=MIN_SIZE.0 + 20 {
+			for height in 0..=MIN_SIZE.1 + 10 {
+				let mut terminal =
+					Terminal::new(TestBackend::new(width, height))
+						.unwrap();
+
+				terminal.draw(|f| app.draw(f).unwrap()).unwrap();
+			}
+		}
+	}
+
+	#[test]
+	fn test_draw_tiny_sizes() {
+		let mut app = test_app();
+
+		draw_sizes(&app);
+
+		app.on_resize();
+		app.help.show().unwrap();
+		draw_sizes(&app);
+
+		app.help.hide();
+		app.options_popup.show().unwrap();
+		draw_sizes(&app);
+
+		app.options_popup.hide();
+		app.select_branch_popup.open().unwrap();
+		draw_sizes(&app);
+
+		app.select_branch_popup.hide();
+		app.commit.show().unwrap();
+		draw_sizes(&app);
+
+		app.commit.hide();
+		app.queue.log_message(Severity::Error, "first\nsecond line");
+		app.msg_history_popup.show().unwrap();
+		app.toast = Some((
+			Severity::Success,
+			String::from("toast"),
+			Instant::now() + TOAST_DURATION,
+		));
+		draw_sizes(&app);
+
+		app.msg_history_popup.hide();
+		app.timings_popup.show().unwrap();
+		draw_sizes(&app);
+	}
+
+	#[test]
+	fn test_startup_defers_hidden_tabs() {
+		let mut app = test_app();
+
+		// the first frame is drawn before any git data is requested
+		assert!(!app.any_work_pending());
+
+		let mut terminal =
+			Terminal::new(TestBackend::new(80, 30)).unwrap();
+		terminal.draw(|f| app.draw(f).unwrap()).unwrap();
+
+		app.update().unwrap();
+
+		// log, files and stashes load once their tab is shown, so
+		// startup does not depend on the size of the history
+		assert!(!app.revlog.any_work_pending());
+		assert!(!app.files_tab.anything_pending());
+		assert!(!app.stashlist_tab.is_visible());
+	}
+
+	#[test]
+	fn test_idle_tick_skips_draw() {
+		let mut app = test_app();
+
+		assert!(app.needs_draw());
+		assert!(!app.needs_draw());
+
+		// the first tick refreshes everything
+		app.update().unwrap();
+		assert!(app.needs_draw());
+
+		app.update().unwrap();
+		assert!(!app.needs_draw());
+
+		app.toast = Some((
+			Severity::Success,
+			String::from("toast"),
+			Instant::now(),
+		));
+		app.update().unwrap();
+		assert!(app.needs_draw());
+		assert!(app.toast.is_none());
+
+		app.event(InputEvent::Input(Event::Resize(80, 30))).unwrap();
+		assert!(app.needs_draw());
+	}
+
+	#[test]
+	fn test_discarded_hunks_expire() {
+		let mut app = test_app();
+
+		for _ in 0..=HUNK_UNDO_CAPACITY {
+			app.hunk_discarded(String::from("foo.rs"), Vec::new());
+		}
+		assert_eq!(app.discarded_hunks.len(), HUNK_UNDO_CAPACITY);
+		assert!(app
+			.toast
+			.as_ref()
+			.map_or(false, |toast| toast.1.contains("foo.rs")));
+
+		assert!(!app.expire_notices());
+		assert_eq!(app.discarded_hunks.len(), HUNK_UNDO_CAPACITY);
+
+		if let Some(hunk) = app.discarded_hunks.front_mut() {
+			hunk.since =
+				Instant::now().checked_sub(HUNK_UNDO_GRACE).unwrap();
+		}
+		assert!(app.expire_notices());
+		assert_eq!(app.discarded_hunks.len(), HUNK_UNDO_CAPACITY - 1);
+	}
+
+	#[test]
+	fn test_expired_hunk_undo_unavailable() {
+		let mut app = test_app();
+
+		app.hunk_discarded(String::from("foo.rs"), Vec::new());
+		assert!(app.hunk_undo_available());
+
+		// not expired by the tick yet, the key goes to undo commit
+		if let Some(hunk) = app.discarded_hunks.back_mut() {
+			hunk.since =
+				Instant::now().checked_sub(HUNK_UNDO_GRACE).unwrap();
+		}
+		assert_eq!(app.discarded_hunks.len(), 1);
+		assert!(!app.hunk_undo_available());
+	}
+
+	#[test]
+	fn test_readonly() {
+		let mut app = test_app_with(Rc::new(RefCell::new(
+			RepoSession::new(Vec::new(), true),
+		)));
+
+		assert!(app
+			.commands(true)
+			.iter()
+			.any(|command| command.text.mutating));
+		assert!(!app
+			.allowed_commands(true)
+			.iter()
+			.any(|command| command.text.mutating));
+
+		let key = app
+			.commands(false)
+			.into_iter()
+			.find(|command| {
+				command.available && command.text.mutating
+			})
+			.and_then(|command| command.text.key)
+			.unwrap();
+		app.event(InputEvent::Input(Event::Key(key))).unwrap();
+		assert!(!app.any_popup_visible());
+		assert!(app.toast.take().map_or(false, |toast| toast
+			.1
+			.contains("read-only mode")));
+
+		// rejected from other paths as well
+		app.queue.push(InternalEvent::OpenCommit);
+		app.process_queue(NeedsUpdate::empty()).unwrap();
+		assert!(!app.commit.is_visible());
+		assert!(app.toast.is_some());
+	}
+
+	#[test]
+	fn test_offline() {
+		let mut app = test_app_with(Rc::new(RefCell::new(
+			RepoSession::new(Vec::new(), false),
+		)));
+
+		let remote_enabled = |app: &App| {
+			app.allowed_commands(true)
+				.iter()
+				.any(|command| command.text.remote && command.enabled)
+		};
+
+		assert!(remote_enabled(&app));
+
+		let toggle = app.key_config.toggle_offline;
+		app.event(InputEvent::Input(Event::Key(toggle))).unwrap();
+		assert!(app.offline());
+		assert!(app.auto_fetch_paused());
+		assert!(!remote_enabled(&app));
+
+		app.event(InputEvent::Input(Event::Key(
+			app.key_config.fetch,
+		)))
+		.unwrap();
+		assert!(!app.any_popup_visible());
+		assert!(app
+			.toast
+			.take()
+			.map_or(false, |toast| toast.1.contains("offline mode")));
+
+		// rejected from other paths as well
+		app.queue.push(InternalEvent::PushTags);
+		app.process_queue(NeedsUpdate::empty()).unwrap();
+		assert!(app.toast.is_some());
+
+		app.event(InputEvent::Input(Event::Key(toggle))).unwrap();
+		assert!(!app.offline());
+		assert!(remote_enabled(&app));
+	}
+
+	#[test]
+	fn test_selection_mode() {
+		let mut app = test_app_with(Rc::new(RefCell::new(
+			RepoSession::new(Vec::new(), false),
+		)));
+		let keys = app.key_config.clone();
+		let key = |app: &mut App, key: KeyEvent| {
+			app.event(InputEvent::Input(Event::Key(key))).unwrap();
+		};
+
+		// the file lists are still loading
+		key(&mut app, keys.selection_mode);
+		assert!(app.selection_text().is_none());
+		assert!(app.toast.take().is_some());
+
+		app.set_tab(1).unwrap();
+		key(&mut app, keys.selection_mode);
+		assert!(app.selection_text().is_some());
+
+		// swallowed, even the ones quitting
+		key(&mut app, keys.exit);
+		key(&mut app, keys.tab_status);
+		assert!(!app.do_quit);
+		assert_eq!(app.tab, 1);
+		assert!(app.selection_text().is_some());
+
+		key(&mut app, keys.exit_popup);
+		assert!(app.selection_text().is_none());
+		assert_eq!(app.tab, 1);
+	}
+}
@@ -13,6 +13,9 @@ use std::{
 
 pub struct CliArgs {
 	pub theme: PathBuf,
+	/// absolute paths given with `--repo` to switch between
+	pub repos: Vec<PathBuf>,
+	pub readonly: bool,
 }
 
 pub fn process_cmdline() -> Result<CliArgs> {
@@ -45,6 +48,20 @@ pub fn process_cmdline() -> Result<CliArgs> {
 				.short("d")
 				.long("directory")
 				.takes_value(true),
+		)
+		.arg(
+			Arg::with_name("repo")
+				.help("Add a repo to switch to in the session (repeatable), the first one is opened unless a directory is set")
+				.long("repo")
+				.value_name("PATH")
+				.takes_value(true)
+				.multiple(true)
+				.number_of_values(1),
+		)
+		.arg(
+			Arg::with_name("readonly")
+				.help("Browse without changing the repo: staging, committing, branches, stashes and remotes are disabled")
+				.long("readonly"),
 		);
 
 	let arg_matches = app.get_matches();
@@ -55,22 +72,33 @@ pub fn process_cmdline() -> Result<CliArgs> {
 	if arg_matches.is_present("logging") {
 		setup_logging()?;
 	}
+	// relative to where gitui was started
+	let cwd = env::current_dir()?;
+	let repos = arg_matches
+		.values_of("repo")
+		.map(|repos| repos.map(|repo| cwd.join(repo)).collect())
+		.unwrap_or_else(Vec::new);
+
 	if arg_matches.is_present("directory") {
 		let directory =
 			arg_matches.value_of("directory").unwrap_or(".");
 		env::set_current_dir(directory)?;
+	} else if let Some(repo) = repos.first() {
+		env::set_current_dir(repo)?;
 	}
 	let arg_theme =
 		arg_matches.value_of("theme").unwrap_or("theme.ron");
-	if get_app_config_path()?.join(arg_theme).is_file() {
-		Ok(CliArgs {
-			theme: get_app_config_path()?.join(arg_theme),
-		})
+	let theme = if get_app_config_path()?.join(arg_theme).is_file() {
+		get_app_config_path()?.join(arg_theme)
 	} else {
-		Ok(CliArgs {
-			theme: get_app_config_path()?.join("theme.ron"),
-		})
-	}
+		get_app_config_path()?.join("theme.ron")
+	};
+
+	Ok(CliArgs {
+		theme,
+		repos,
+		readonly: arg_matches.is_present("readonly"),
+	})
 }
 
 fn setup_logging() -> Result<()> {
@@ -86,7 +114,7 @@ fn setup_logging() -> Result<()> {
 	Ok(())
 }
 
-fn get_app_cache_path() -> Result<PathBuf> {
+pub fn get_app_cache_path() -> Result<PathBuf> {
 	let mut path = dirs_next::cache_dir()
 		.ok_or_else(|| anyhow!("failed to find os cache dir."))?;
 
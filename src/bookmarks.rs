@@ -0,0 +1,94 @@
+//! named places to go back to while looking around a repo,
+//! kept for the session and saved with the ui state if enabled
+
+use crate::strings;
+use asyncgit::{
+	sync::{self, utils::repo_work_dir, CommitId},
+	CWD,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// a view and the position in it, commits as full hex ids like in
+/// the ui state
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub enum Location {
+	/// commit selected in the log
+	Commit(String),
+	/// file selected in the status tab, its diff at `line`
+	StatusFile { path: String, line: usize },
+	/// file selected in the tree of the files tab
+	TreeFile(String),
+}
+
+impl Location {
+	///
+	pub fn commit(id: CommitId) -> Self {
+		Self::Commit(id.to_string())
+	}
+
+	/// the reason it cannot be gone to anymore, `None` if it can
+	pub fn stale_reason(&self) -> Option<String> {
+		match self {
+			Self::Commit(id) => {
+				let reachable = CommitId::from_hex(id)
+					.and_then(|id| {
+						sync::is_reachable_from_head(CWD, id)
+					})
+					.unwrap_or(false);
+				(!reachable)
+					.then(|| strings::bookmark_stale_commit(id))
+			}
+			Self::StatusFile { path, .. } | Self::TreeFile(path) => {
+				let exists = repo_work_dir(CWD)
+					.map_or(false, |dir| {
+						Path::new(&dir).join(path).exists()
+					});
+				(!exists).then(|| strings::bookmark_stale_file(path))
+			}
+		}
+	}
+}
+
+///
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Bookmark {
+	/// typed or the next free number
+	pub label: String,
+	pub location: Location,
+	/// commit subject or file path, as of creating the bookmark
+	pub context: String,
+}
+
+/// the label of a bookmark created without typing one
+pub fn next_label(bookmarks: &[Bookmark]) -> String {
+	let max = bookmarks
+		.iter()
+		.filter_map(|bookmark| bookmark.label.parse::<usize>().ok())
+		.max()
+		.unwrap_or(0);
+
+	(max + 1).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn bookmark(label: &str) -> Bookmark {
+		Bookmark {
+			label: label.to_string(),
+			location: Location::TreeFile(String::from("a.txt")),
+			context: String::from("a.txt"),
+		}
+	}
+
+	#[test]
+	fn test_next_label() {
+		assert_eq!(next_label(&[]), "1");
+		assert_eq!(
+			next_label(&[bookmark("2"), bookmark("todo")]),
+			"3"
+		);
+	}
+}
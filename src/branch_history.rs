@@ -0,0 +1,199 @@
+//! recently checked out branches of each repo, to switch back to
+//! them without the branch list (like `git checkout -`)
+
+use crate::{args::get_app_cache_path, strings};
+use anyhow::{bail, Result};
+use asyncgit::{
+	cached::BranchName,
+	sync::{self, RepoState},
+	CWD,
+};
+use ron::ser::{to_string_pretty, PrettyConfig};
+use std::{
+	cell::RefCell, collections::HashMap, fs, path::PathBuf, rc::Rc,
+};
+
+/// branches remembered per repo
+pub const MAX_BRANCHES: usize = 10;
+
+///
+pub type SharedBranchHistory = Rc<RefCell<BranchHistory>>;
+
+/// most recently checked out first
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct BranchHistory {
+	branches: Vec<String>,
+}
+
+impl BranchHistory {
+	pub fn new(mut branches: Vec<String>) -> Self {
+		branches.truncate(MAX_BRANCHES);
+		Self { branches }
+	}
+
+	/// `to` was checked out coming from `from` (`None` if detached)
+	pub fn checked_out(&mut self, from: Option<&str>, to: &str) {
+		if let Some(from) = from {
+			self.push(from);
+		}
+		self.push(to);
+	}
+
+	fn push(&mut self, branch: &str) {
+		self.branches.retain(|known| known != branch);
+		self.branches.insert(0, branch.to_string());
+		self.branches.truncate(MAX_BRANCHES);
+	}
+
+	/// branches to switch to, most recent first, never `current`
+	pub fn targets<'a>(
+		&'a self,
+		current: Option<&'a str>,
+	) -> impl Iterator<Item = &'a str> {
+		self.branches
+			.iter()
+			.map(String::as_str)
+			.filter(move |branch| Some(*branch) != current)
+	}
+}
+
+fn history_file() -> Result<PathBuf> {
+	Ok(get_app_cache_path()?.join("branch_history.ron"))
+}
+
+fn read_file() -> Result<HashMap<String, Vec<String>>> {
+	let file = history_file()?;
+	if !file.exists() {
+		return Ok(HashMap::new());
+	}
+
+	Ok(ron::de::from_bytes(&fs::read(file)?)?)
+}
+
+/// history of the current repo, seeded from the reflog the first
+/// time the repo is opened
+pub fn load() -> BranchHistory {
+	let stored = sync::utils::repo_work_dir(CWD)
+		.ok()
+		.and_then(|repo| read_file().ok()?.remove(&repo));
+
+	BranchHistory::new(stored.unwrap_or_else(|| {
+		sync::recent_branches(CWD, MAX_BRANCHES).unwrap_or_else(|e| {
+			log::error!("read recent branches: {}", e);
+			Vec::new()
+		})
+	}))
+}
+
+fn save(history: &BranchHistory) -> Result<()> {
+	let repo = sync::utils::repo_work_dir(CWD)?;
+	let mut file = read_file().unwrap_or_default();
+	file.insert(repo, history.branches.clone());
+
+	let data = to_string_pretty(&file, PrettyConfig::default())?;
+	fs::write(history_file()?, data)?;
+
+	Ok(())
+}
+
+/// runs `checkout` and records the switch of branches if it
+/// succeeded, a failed checkout leaves the history untouched
+pub fn record_checkout<T>(
+	history: &SharedBranchHistory,
+	checkout: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+	let mut head = BranchName::new(CWD);
+	let from = head.lookup().ok();
+
+	let res = checkout()?;
+
+	if let Ok(to) = head.lookup() {
+		if from.as_ref() != Some(&to) {
+			let mut history = history.borrow_mut();
+			history.checked_out(from.as_deref(), &to);
+
+			if let Err(e) = save(&history) {
+				log::error!("save branch history: {}", e);
+			}
+		}
+	}
+
+	Ok(res)
+}
+
+/// checks out the local `branch`, see `record_checkout`
+pub fn checkout(
+	history: &SharedBranchHistory,
+	branch: &str,
+) -> Result<()> {
+	let repo_state = sync::repo_state(CWD)?;
+	if repo_state != RepoState::Clean {
+		bail!(strings::repo_state_blocks(
+			repo_state,
+			"switch branches"
+		));
+	}
+
+	record_checkout(history, || {
+		sync::checkout_branch(CWD, &format!("refs/heads/{}", branch))
+			.map_err(Into::into)
+	})
+}
+
+/// `targets` of `history` that still exist as local branches
+pub fn existing_targets(
+	history: &BranchHistory,
+) -> Result<Vec<String>> {
+	let current = BranchName::new(CWD).lookup().ok();
+	let local = sync::get_branches_info(CWD, true)?;
+
+	Ok(history
+		.targets(current.as_deref())
+		.filter(|branch| local.iter().any(|b| b.name == *branch))
+		.map(String::from)
+		.collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	fn targets(
+		history: &BranchHistory,
+		current: &str,
+	) -> Vec<String> {
+		history.targets(Some(current)).map(String::from).collect()
+	}
+
+	#[test]
+	fn test_checked_out() {
+		let mut history = BranchHistory::default();
+		history.checked_out(Some("master"), "foo");
+
+		assert_eq!(targets(&history, "foo"), vec!["master"]);
+
+		history.checked_out(Some("foo"), "bar");
+		history.checked_out(Some("bar"), "master");
+
+		assert_eq!(targets(&history, "master"), vec!["bar", "foo"]);
+		assert_eq!(
+			history.targets(None).collect::<Vec<_>>(),
+			vec!["master", "bar", "foo"]
+		);
+	}
+
+	#[test]
+	fn test_limit() {
+		let mut history = BranchHistory::default();
+		for idx in 0..=MAX_BRANCHES {
+			history.checked_out(None, &idx.to_string());
+		}
+
+		assert_eq!(history.targets(None).count(), MAX_BRANCHES);
+		assert_eq!(
+			history.targets(None).last(),
+			Some(1.to_string().as_str())
+		);
+	}
+}
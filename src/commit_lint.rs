@@ -0,0 +1,306 @@
+//! checks commit subjects against a (conventional commits) pattern
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// `type(scope): description`, `!` marks breaking changes
+pub const DEFAULT_PATTERN: &str = r"^(?P<type>[^\s():!]+)(?:\((?P<scope>[^()]*)\))?!?: (?P<description>\S.*)$";
+
+pub const DEFAULT_TYPES: &[&str] = &[
+	"build", "chore", "ci", "docs", "feat", "fix", "perf",
+	"refactor", "revert", "style", "test",
+];
+
+const DEFAULT_MAX_SUBJECT_LENGTH: usize = 72;
+
+/// persisted lint settings, `pattern` and `max_subject_length` are
+/// only edited in the file
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct CommitLintConfig {
+	pub enabled: bool,
+	/// allowed values of the `type` group, empty allows any
+	pub types: Vec<String>,
+	pub pattern: String,
+	/// in chars, `0` means no limit
+	pub max_subject_length: usize,
+}
+
+impl Default for CommitLintConfig {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			types: DEFAULT_TYPES
+				.iter()
+				.map(|t| (*t).to_string())
+				.collect(),
+			pattern: DEFAULT_PATTERN.to_string(),
+			max_subject_length: DEFAULT_MAX_SUBJECT_LENGTH,
+		}
+	}
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum LintProblem {
+	/// no `:` separating the type from the description
+	MissingColon,
+	/// nothing after the `:`
+	MissingDescription,
+	/// the `type` group is not in the known types
+	UnknownType(String),
+	/// does not match the pattern for any other reason
+	NoMatch,
+	TooLong {
+		length: usize,
+		max: usize,
+	},
+}
+
+/// compiled `CommitLintConfig`
+#[derive(Clone, Debug)]
+pub struct CommitLint {
+	pattern: Regex,
+	types: Vec<String>,
+	max_subject_length: usize,
+}
+
+impl CommitLint {
+	pub fn new(
+		config: &CommitLintConfig,
+	) -> Result<Self, regex::Error> {
+		Ok(Self {
+			pattern: Regex::new(&config.pattern)?,
+			types: config.types.clone(),
+			max_subject_length: config.max_subject_length,
+		})
+	}
+
+	///
+	pub fn check(&self, subject: &str) -> Vec<LintProblem> {
+		lint_subject(
+			subject,
+			&self.pattern,
+			&self.types,
+			self.max_subject_length,
+		)
+	}
+}
+
+/// problems of `subject` (the first line of a message), in the order
+/// they should be fixed. an empty subject has none, it cannot be
+/// committed anyway. the syntax problems are only told apart for the
+/// default pattern
+pub fn lint_subject(
+	subject: &str,
+	pattern: &Regex,
+	types: &[String],
+	max_length: usize,
+) -> Vec<LintProblem> {
+	let mut problems = Vec::new();
+
+	if subject.trim().is_empty() {
+		return problems;
+	}
+
+	if let Some(captures) = pattern.captures(subject) {
+		if let Some(kind) = captures.name("type") {
+			if !types.is_empty()
+				&& !types.iter().any(|t| t == kind.as_str())
+			{
+				problems.push(LintProblem::UnknownType(
+					kind.as_str().to_string(),
+				));
+			}
+		}
+	} else if pattern.as_str() != DEFAULT_PATTERN {
+		// nothing to tell about the parts of a custom pattern
+		problems.push(LintProblem::NoMatch);
+	} else if let Some(colon) = subject.find(':') {
+		problems.push(if subject[colon + 1..].trim().is_empty() {
+			LintProblem::MissingDescription
+		} else {
+			LintProblem::NoMatch
+		});
+	} else {
+		problems.push(LintProblem::MissingColon);
+	}
+
+	let length = subject.chars().count();
+	if max_length > 0 && length > max_length {
+		problems.push(LintProblem::TooLong {
+			length,
+			max: max_length,
+		});
+	}
+
+	problems
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	fn lint(subject: &str) -> Vec<LintProblem> {
+		CommitLint::new(&CommitLintConfig::default())
+			.unwrap()
+			.check(subject)
+	}
+
+	#[test]
+	fn test_valid() {
+		assert_eq!(lint("feat: add export"), vec![]);
+		assert_eq!(lint("fix(log): off by one"), vec![]);
+		assert_eq!(lint("refactor(app)!: drop old api"), vec![]);
+		assert_eq!(lint("docs!: rewrite readme"), vec![]);
+		assert_eq!(lint("chore(): empty scope"), vec![]);
+	}
+
+	#[test]
+	fn test_empty() {
+		assert_eq!(lint(""), vec![]);
+		assert_eq!(lint("  "), vec![]);
+	}
+
+	#[test]
+	fn test_missing_colon() {
+		assert_eq!(
+			lint("feat add export"),
+			vec![LintProblem::MissingColon]
+		);
+		assert_eq!(
+			lint("add export"),
+			vec![LintProblem::MissingColon]
+		);
+	}
+
+	#[test]
+	fn test_missing_description() {
+		assert_eq!(
+			lint("feat:"),
+			vec![LintProblem::MissingDescription]
+		);
+		assert_eq!(
+			lint("fix(log):   "),
+			vec![LintProblem::MissingDescription]
+		);
+	}
+
+	#[test]
+	fn test_no_match() {
+		assert_eq!(
+			lint("feat:add export"),
+			vec![LintProblem::NoMatch]
+		);
+		assert_eq!(
+			lint("fix(log: off by one"),
+			vec![LintProblem::NoMatch]
+		);
+		assert_eq!(
+			lint("my feat: export"),
+			vec![LintProblem::NoMatch]
+		);
+		assert_eq!(lint("feat:  export"), vec![LintProblem::NoMatch]);
+	}
+
+	#[test]
+	fn test_unknown_type() {
+		assert_eq!(
+			lint("feature: add export"),
+			vec![LintProblem::UnknownType(String::from("feature"))]
+		);
+		// types are case sensitive
+		assert_eq!(
+			lint("Fix(log): off by one"),
+			vec![LintProblem::UnknownType(String::from("Fix"))]
+		);
+	}
+
+	#[test]
+	fn test_any_type() {
+		let lint = CommitLint::new(&CommitLintConfig {
+			types: Vec::new(),
+			..CommitLintConfig::default()
+		})
+		.unwrap();
+
+		assert_eq!(lint.check("feature: add export"), vec![]);
+	}
+
+	#[test]
+	fn test_too_long() {
+		let subject = format!("feat: {}", "a".repeat(66));
+		assert_eq!(lint(&subject), vec![]);
+
+		let subject = format!("feat: {}", "ä".repeat(67));
+		assert_eq!(
+			lint(&subject),
+			vec![LintProblem::TooLong {
+				length: 73,
+				max: 72
+			}]
+		);
+
+		assert_eq!(
+			lint(&format!("feature {}", "a".repeat(70))),
+			vec![
+				LintProblem::MissingColon,
+				LintProblem::TooLong {
+					length: 78,
+					max: 72
+				}
+			]
+		);
+	}
+
+	#[test]
+	fn test_no_limit() {
+		let lint = CommitLint::new(&CommitLintConfig {
+			max_subject_length: 0,
+			..CommitLintConfig::default()
+		})
+		.unwrap();
+
+		assert_eq!(
+			lint.check(&format!("feat: {}", "a".repeat(200))),
+			vec![]
+		);
+	}
+
+	#[test]
+	fn test_custom_pattern() {
+		let lint = CommitLint::new(&CommitLintConfig {
+			pattern: String::from(r"^\[(?P<type>\w+)\] .+$"),
+			..CommitLintConfig::default()
+		})
+		.unwrap();
+
+		assert_eq!(lint.check("[fix] off by one"), vec![]);
+		assert_eq!(
+			lint.check("[bug] off by one"),
+			vec![LintProblem::UnknownType(String::from("bug"))]
+		);
+		assert_eq!(
+			lint.check("fix: off by one"),
+			vec![LintProblem::NoMatch]
+		);
+
+		// without a `type` group only the pattern counts
+		let lint = CommitLint::new(&CommitLintConfig {
+			pattern: String::from(r"^[A-Z]"),
+			..CommitLintConfig::default()
+		})
+		.unwrap();
+
+		assert_eq!(lint.check("Off by one"), vec![]);
+	}
+
+	#[test]
+	fn test_invalid_pattern() {
+		assert!(CommitLint::new(&CommitLintConfig {
+			pattern: String::from("(unclosed"),
+			..CommitLintConfig::default()
+		})
+		.is_err());
+	}
+}
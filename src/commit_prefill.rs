@@ -0,0 +1,216 @@
+//! prefills the commit subject with parts of the branch name (e.g.
+//! the ticket id of `feature/ABC-123-foo`)
+
+use anyhow::{bail, Result};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+/// `{1}` is replaced with the first capture group of the pattern
+pub const DEFAULT_TEMPLATE: &str = "[{1}] ";
+
+/// persisted prefill settings, an empty `pattern` turns it off
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct CommitPrefillConfig {
+	/// applied to the current branch name
+	pub pattern: String,
+	/// `{n}` is replaced with capture group `n`
+	pub template: String,
+}
+
+impl Default for CommitPrefillConfig {
+	fn default() -> Self {
+		Self {
+			pattern: String::new(),
+			template: DEFAULT_TEMPLATE.to_string(),
+		}
+	}
+}
+
+impl CommitPrefillConfig {
+	pub fn is_enabled(&self) -> bool {
+		!self.pattern.is_empty()
+	}
+}
+
+/// compiled `CommitPrefillConfig`
+#[derive(Clone, Debug)]
+pub struct CommitPrefill {
+	pattern: Regex,
+	template: String,
+}
+
+impl CommitPrefill {
+	/// fails on an invalid pattern or if the template refers to a
+	/// group the pattern does not have
+	pub fn new(config: &CommitPrefillConfig) -> Result<Self> {
+		let pattern = Regex::new(&config.pattern)?;
+		let groups = pattern.captures_len() - 1;
+
+		if let Some(group) = placeholders(&config.template)
+			.into_iter()
+			.find(|group| *group > groups)
+		{
+			bail!(
+				"template uses {{{}}} but the pattern has {} capture group(s)",
+				group,
+				groups
+			);
+		}
+
+		Ok(Self {
+			pattern,
+			template: config.template.clone(),
+		})
+	}
+
+	/// `None` if `branch` does not match the pattern
+	pub fn prefill(&self, branch: &str) -> Option<String> {
+		self.pattern
+			.captures(branch)
+			.map(|captures| expand(&self.template, &captures))
+	}
+}
+
+/// group numbers of all `{n}` in `template`
+pub fn placeholders(template: &str) -> Vec<usize> {
+	let mut groups = Vec::new();
+	let mut rest = template;
+
+	while let Some(start) = rest.find('{') {
+		rest = &rest[start + 1..];
+		if let Some((group, len)) = placeholder(rest) {
+			groups.push(group);
+			rest = &rest[len..];
+		}
+	}
+
+	groups
+}
+
+/// replaces every `{n}` with capture group `n`, groups that did not
+/// participate in the match are left empty
+pub fn expand(template: &str, captures: &Captures) -> String {
+	let mut out = String::with_capacity(template.len());
+	let mut rest = template;
+
+	while let Some(start) = rest.find('{') {
+		out.push_str(&rest[..start]);
+		rest = &rest[start + 1..];
+
+		if let Some((group, len)) = placeholder(rest) {
+			out.push_str(
+				captures.get(group).map_or("", |m| m.as_str()),
+			);
+			rest = &rest[len..];
+		} else {
+			out.push('{');
+		}
+	}
+
+	out.push_str(rest);
+	out
+}
+
+/// group number and length of `n}` at the start of `text`
+fn placeholder(text: &str) -> Option<(usize, usize)> {
+	let end = text.find('}')?;
+	let group = text[..end].parse().ok()?;
+
+	Some((group, end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	fn prefill(
+		pattern: &str,
+		template: &str,
+		branch: &str,
+	) -> Option<String> {
+		CommitPrefill::new(&CommitPrefillConfig {
+			pattern: pattern.to_string(),
+			template: template.to_string(),
+		})
+		.unwrap()
+		.prefill(branch)
+	}
+
+	const TICKET: &str = r"([A-Z]+-\d+)";
+
+	#[test]
+	fn test_ticket() {
+		assert_eq!(
+			prefill(TICKET, DEFAULT_TEMPLATE, "feature/ABC-123-foo"),
+			Some(String::from("[ABC-123] "))
+		);
+		assert_eq!(
+			prefill(TICKET, DEFAULT_TEMPLATE, "ABC-123"),
+			Some(String::from("[ABC-123] "))
+		);
+		assert_eq!(
+			prefill(TICKET, DEFAULT_TEMPLATE, "bugfix/XY-7_crash"),
+			Some(String::from("[XY-7] "))
+		);
+	}
+
+	#[test]
+	fn test_no_match() {
+		assert_eq!(prefill(TICKET, DEFAULT_TEMPLATE, "master"), None);
+		assert_eq!(
+			prefill(TICKET, DEFAULT_TEMPLATE, "feature/abc-123"),
+			None
+		);
+		assert_eq!(prefill(TICKET, DEFAULT_TEMPLATE, ""), None);
+	}
+
+	#[test]
+	fn test_multiple_groups() {
+		let pattern = r"^(\w+)/([A-Z]+-\d+)";
+
+		assert_eq!(
+			prefill(pattern, "{1}({2}): ", "fix/ABC-1-typo"),
+			Some(String::from("fix(ABC-1): "))
+		);
+		assert_eq!(
+			prefill(pattern, "{2} {2} {0}", "fix/ABC-1-typo"),
+			Some(String::from("ABC-1 ABC-1 fix/ABC-1"))
+		);
+	}
+
+	#[test]
+	fn test_optional_group() {
+		let pattern = r"^(?:(\w+)/)?([A-Z]+-\d+)";
+
+		assert_eq!(
+			prefill(pattern, "[{2}{1}] ", "ABC-1"),
+			Some(String::from("[ABC-1] "))
+		);
+	}
+
+	#[test]
+	fn test_literal_braces() {
+		assert_eq!(
+			prefill(TICKET, "{x} {1} {", "ABC-1"),
+			Some(String::from("{x} ABC-1 {"))
+		);
+	}
+
+	#[test]
+	fn test_invalid() {
+		assert!(CommitPrefill::new(&CommitPrefillConfig {
+			pattern: String::from("(unclosed"),
+			..CommitPrefillConfig::default()
+		})
+		.is_err());
+
+		// only one group to refer to
+		assert!(CommitPrefill::new(&CommitPrefillConfig {
+			pattern: String::from(TICKET),
+			template: String::from("{2}"),
+		})
+		.is_err());
+	}
+}
@@ -1,18 +1,19 @@
 use super::{
-	utils, visibility_blocking, CommandBlocking, CommandInfo,
-	Component, DrawableComponent, EventState,
+	utils::{self, selected_path::SelectedPath},
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState,
 };
 use crate::{
 	components::{utils::string_width_align, ScrollType},
 	keys::SharedKeyConfig,
-	queue::{InternalEvent, Queue},
+	queue::{FileLine, InternalEvent, Queue},
 	strings,
 	ui::{self, style::SharedTheme},
 };
 use anyhow::Result;
 use asyncgit::{
 	sync::{BlameHunk, CommitId, FileBlame},
-	AsyncBlame, AsyncGitNotification, BlameParams,
+	AsyncBlame, AsyncGitNotification, BlameParams, CWD,
 };
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
@@ -33,6 +34,8 @@ pub struct BlameFileComponent {
 	async_blame: AsyncBlame,
 	visible: bool,
 	file_path: Option<String>,
+	/// `None` blames HEAD
+	commit: Option<CommitId>,
 	file_blame: Option<FileBlame>,
 	table_state: std::cell::Cell<TableState>,
 	key_config: SharedKeyConfig,
@@ -141,6 +144,12 @@ impl DrawableComponent for BlameFileComponent {
 	}
 }
 
+impl SelectedPath for BlameFileComponent {
+	fn selected_path(&self) -> Option<String> {
+		self.file_path.clone()
+	}
+}
+
 impl Component for BlameFileComponent {
 	fn commands(
 		&self,
@@ -174,6 +183,27 @@ impl Component for BlameFileComponent {
 				)
 				.order(1),
 			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::blame_select_in_log(
+						&self.key_config,
+					),
+					true,
+					self.file_blame.is_some(),
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::compare_workdir(
+						&self.key_config,
+					),
+					true,
+					self.file_blame.is_some(),
+				)
+				.order(1),
+			);
+			self.path_commands(&self.key_config, true, out);
 		}
 
 		visibility_blocking(self)
@@ -185,6 +215,13 @@ impl Component for BlameFileComponent {
 	) -> Result<EventState> {
 		if self.is_visible() {
 			if let Event::Key(key) = event {
+				if self
+					.path_event(key, &self.key_config, &self.queue)
+					.is_consumed()
+				{
+					return Ok(EventState::Consumed);
+				}
+
 				if key == self.key_config.exit_popup {
 					self.hide();
 				} else if key == self.key_config.move_up {
@@ -203,6 +240,27 @@ impl Component for BlameFileComponent {
 					self.move_selection(ScrollType::PageDown);
 				} else if key == self.key_config.page_up {
 					self.move_selection(ScrollType::PageUp);
+				} else if key == self.key_config.compare_workdir {
+					if let (Some(id), Some(path)) = (
+						self.selected_commit(),
+						self.file_path.clone(),
+					) {
+						self.queue.push(
+							InternalEvent::CompareWorkdir(id, path),
+						);
+					}
+				} else if key == self.key_config.enter {
+					if let (Some(id), Some(path)) = (
+						self.selected_commit(),
+						self.file_path.clone(),
+					) {
+						self.hide();
+						self.queue.push(
+							InternalEvent::SelectFileInRevlog(
+								id, path,
+							),
+						);
+					}
 				} else if key == self.key_config.focus_right {
 					self.hide();
 
@@ -239,6 +297,10 @@ impl Component for BlameFileComponent {
 
 		Ok(())
 	}
+
+	fn on_resize(&self) {
+		self.current_height.set(0);
+	}
 }
 
 impl BlameFileComponent {
@@ -257,6 +319,7 @@ impl BlameFileComponent {
 			queue: queue.clone(),
 			visible: false,
 			file_path: None,
+			commit: None,
 			file_blame: None,
 			table_state: std::cell::Cell::new(TableState::default()),
 			key_config,
@@ -264,11 +327,14 @@ impl BlameFileComponent {
 		}
 	}
 
-	///
-	pub fn open(&mut self, file_path: &str) -> Result<()> {
-		self.file_path = Some(file_path.into());
+	/// blames the file at its revision, scrolled to its line
+	pub fn open(&mut self, file: FileLine) -> Result<()> {
+		self.file_path = Some(file.path);
+		self.commit = file.commit;
 		self.file_blame = None;
-		self.table_state.get_mut().select(Some(0));
+		self.table_state
+			.get_mut()
+			.select(Some(file.line.unwrap_or(1).saturating_sub(1)));
 		self.show()?;
 
 		self.update()?;
@@ -300,6 +366,7 @@ impl BlameFileComponent {
 			if let Some(file_path) = &self.file_path {
 				let blame_params = BlameParams {
 					file_path: file_path.into(),
+					commit_id: self.commit,
 				};
 
 				if let Some((
@@ -309,6 +376,7 @@ impl BlameFileComponent {
 				{
 					if previous_blame_params == blame_params {
 						self.file_blame = Some(last_file_blame);
+						self.clamp_selection();
 
 						return Ok(());
 					}
@@ -339,7 +407,7 @@ impl BlameFileComponent {
 					"{} -- {} -- {}",
 					self.title,
 					file_path,
-					file_blame.commit_id.get_short_string()
+					file_blame.commit_id.abbreviated(CWD)
 				)
 			}
 			(false, Some(file_path), None) => {
@@ -427,7 +495,7 @@ impl BlameFileComponent {
 	) -> Vec<Cell> {
 		let commit_hash = blame_hunk.map_or_else(
 			|| NO_COMMIT_ID.into(),
-			|hunk| hunk.commit_id.get_short_string(),
+			|hunk| hunk.commit_id.abbreviated(CWD),
 		);
 		let author_width = get_author_width(width);
 		let truncated_author: String = blame_hunk.map_or_else(
@@ -475,6 +543,14 @@ impl BlameFileComponent {
 		number_of_digits(max_line_number)
 	}
 
+	/// the line opened at might be past the end of the file
+	fn clamp_selection(&mut self) {
+		let max_selection = self.get_max_line_number();
+		let table_state = self.table_state.get_mut();
+		let selected = table_state.selected().unwrap_or(0);
+		table_state.select(Some(selected.min(max_selection)));
+	}
+
 	fn move_selection(&mut self, scroll_type: ScrollType) -> bool {
 		let mut table_state = self.table_state.take();
 
@@ -0,0 +1,361 @@
+use super::{
+	textinput::InputType, utils::scroll_vertical::VerticalScroll,
+	utils::string_width_align, visibility_blocking, CommandBlocking,
+	CommandInfo, Component, DrawableComponent, EventState,
+	TextInputComponent,
+};
+use crate::{
+	bookmarks::{self, Bookmark, Location},
+	keys::SharedKeyConfig,
+	messages::Severity,
+	queue::{InternalEvent, Queue},
+	strings, ui,
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use std::cell::Cell;
+use tui::{
+	backend::Backend,
+	layout::Rect,
+	text::Span,
+	widgets::{Block, BorderType, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// width of the label column
+const LABEL_WIDTH: usize = 12;
+
+/// the bookmarks of the session, lists them to go back to and asks
+/// for the label of new ones
+pub struct BookmarksPopup {
+	queue: Queue,
+	visible: bool,
+	bookmarks: Vec<Bookmark>,
+	/// why each bookmark cannot be gone to, as of opening the list
+	stale: Vec<Option<String>>,
+	selection: usize,
+	scroll: VerticalScroll,
+	current_height: Cell<usize>,
+	/// bookmark waiting for its label
+	adding: Option<(Location, String)>,
+	input: TextInputComponent,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl BookmarksPopup {
+	///
+	pub fn new(
+		queue: &Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			queue: queue.clone(),
+			visible: false,
+			bookmarks: Vec::new(),
+			stale: Vec::new(),
+			selection: 0,
+			scroll: VerticalScroll::new(),
+			current_height: Cell::new(0),
+			adding: None,
+			input: TextInputComponent::new(
+				theme.clone(),
+				key_config.clone(),
+				&strings::bookmark_label_title(),
+				"",
+				false,
+			)
+			.with_input_type(InputType::Singleline),
+			theme,
+			key_config,
+		}
+	}
+
+	///
+	pub fn bookmarks(&self) -> &[Bookmark] {
+		&self.bookmarks
+	}
+
+	/// restores the bookmarks of a previous visit or session
+	pub fn set_bookmarks(&mut self, bookmarks: Vec<Bookmark>) {
+		self.bookmarks = bookmarks;
+		self.selection = 0;
+	}
+
+	/// asks for the label of a new bookmark of `location`
+	pub fn add(
+		&mut self,
+		location: Location,
+		context: String,
+	) -> Result<()> {
+		self.input.set_default_msg(strings::bookmark_label_hint(
+			&bookmarks::next_label(&self.bookmarks),
+		));
+		self.input.clear();
+		self.adding = Some((location, context));
+
+		self.input.show()
+	}
+
+	fn confirm_label(&mut self) {
+		if let Some((location, context)) = self.adding.take() {
+			let label = match self.input.get_text().trim() {
+				"" => bookmarks::next_label(&self.bookmarks),
+				label => label.to_string(),
+			};
+
+			self.queue.push(InternalEvent::ShowToast(
+				Severity::Success,
+				strings::toast_bookmark_added(
+					&label,
+					&self.key_config,
+				),
+			));
+			self.bookmarks.push(Bookmark {
+				label,
+				location,
+				context,
+			});
+		}
+
+		self.input.hide();
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.stale = self
+			.bookmarks
+			.iter()
+			.map(|bookmark| bookmark.location.stale_reason())
+			.collect();
+		self.selection = self
+			.selection
+			.min(self.bookmarks.len().saturating_sub(1));
+
+		self.show()
+	}
+
+	fn move_selection(&mut self, up: bool, lines: usize) {
+		let max = self.bookmarks.len().saturating_sub(1);
+
+		self.selection = if up {
+			self.selection.saturating_sub(lines)
+		} else {
+			self.selection.saturating_add(lines).min(max)
+		};
+	}
+
+	fn goto_selected(&mut self) {
+		let bookmark = match self.bookmarks.get(self.selection) {
+			Some(bookmark) => bookmark,
+			None => return,
+		};
+
+		if let Some(reason) =
+			self.stale.get(self.selection).cloned().flatten()
+		{
+			self.queue.push(InternalEvent::ShowErrorMsg(reason));
+			return;
+		}
+
+		self.queue.push(InternalEvent::GotoLocation(
+			bookmark.location.clone(),
+		));
+		self.hide();
+	}
+
+	fn delete_selected(&mut self) {
+		if self.selection < self.bookmarks.len() {
+			self.bookmarks.remove(self.selection);
+			self.stale.remove(self.selection);
+			self.move_selection(true, 0);
+		}
+	}
+
+	fn list_entry(&self, idx: usize, width: usize) -> Span<'static> {
+		let bookmark = &self.bookmarks[idx];
+		let stale =
+			self.stale.get(idx).map_or(false, Option::is_some);
+
+		Span::styled(
+			string_width_align(
+				&format!(
+					"{} {} {}",
+					string_width_align(&bookmark.label, LABEL_WIDTH),
+					strings::bookmark_kind(&bookmark.location),
+					bookmark.context
+				),
+				width,
+			),
+			self.theme.text(!stale, idx == self.selection),
+		)
+	}
+}
+
+impl DrawableComponent for BookmarksPopup {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		if self.visible {
+			let area = ui::centered_rect(60, 50, f.size());
+
+			let height = usize::from(area.height.saturating_sub(2));
+			let width = usize::from(area.width.saturating_sub(2));
+			self.current_height.set(height);
+
+			self.scroll.update(
+				self.selection,
+				self.bookmarks.len(),
+				height,
+			);
+
+			let block = Block::default()
+				.title(Span::styled(
+					strings::bookmarks_title(),
+					self.theme.title(true),
+				))
+				.borders(Borders::ALL)
+				.border_type(BorderType::Thick)
+				.border_style(self.theme.block(true));
+
+			f.render_widget(Clear, area);
+
+			if self.bookmarks.is_empty() {
+				f.render_widget(
+					Paragraph::new(strings::bookmarks_empty(
+						&self.key_config,
+					))
+					.style(self.theme.text(false, false))
+					.block(block),
+					area,
+				);
+			} else {
+				let items = (0..self.bookmarks.len())
+					.skip(self.scroll.get_top())
+					.take(height)
+					.map(|idx| self.list_entry(idx, width));
+
+				ui::draw_list_block(f, area, block, items);
+
+				self.scroll.draw(f, area, &self.theme);
+			}
+		}
+
+		self.input.draw(f, rect)?;
+
+		Ok(())
+	}
+}
+
+impl Component for BookmarksPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.input.is_visible() {
+			return self.input.commands(out, force_all);
+		}
+
+		if self.visible || force_all {
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::bookmark_goto(&self.key_config),
+				!self.bookmarks.is_empty(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::bookmark_delete(&self.key_config),
+				!self.bookmarks.is_empty(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.input.is_visible() {
+			if let Event::Key(e) = ev {
+				if e == self.key_config.enter {
+					self.confirm_label();
+				} else if e == self.key_config.exit_popup {
+					self.adding = None;
+					self.input.hide();
+				} else {
+					self.input.event(ev)?;
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		if self.visible {
+			if let Event::Key(e) = ev {
+				let page =
+					self.current_height.get().saturating_sub(1);
+
+				if e == self.key_config.exit_popup
+					|| e == self.key_config.open_bookmarks
+				{
+					self.hide();
+				} else if e == self.key_config.enter {
+					self.goto_selected();
+				} else if e == self.key_config.bookmark_delete {
+					self.delete_selected();
+				} else if e == self.key_config.move_up {
+					self.move_selection(true, 1);
+				} else if e == self.key_config.move_down {
+					self.move_selection(false, 1);
+				} else if e == self.key_config.page_up {
+					self.move_selection(true, page);
+				} else if e == self.key_config.page_down {
+					self.move_selection(false, page);
+				} else if e == self.key_config.home {
+					self.selection = 0;
+				} else if e == self.key_config.end {
+					self.move_selection(false, usize::MAX);
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn paste(&mut self, text: &str) -> Result<EventState> {
+		if self.input.is_visible() {
+			return self.input.paste(text);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible || self.input.is_visible()
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
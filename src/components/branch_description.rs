@@ -0,0 +1,162 @@
+use super::{
+	textinput::TextInputComponent, visibility_blocking,
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, Queue},
+	strings,
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{sync, CWD};
+use crossterm::event::Event;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// edits `branch.<name>.description`
+pub struct BranchDescriptionPopup {
+	input: TextInputComponent,
+	branch: Option<String>,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for BranchDescriptionPopup {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		self.input.draw(f, rect)?;
+
+		Ok(())
+	}
+}
+
+impl Component for BranchDescriptionPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			self.input.commands(out, force_all);
+
+			out.push(CommandInfo::new(
+				strings::commands::branch_description_confirm(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if self.input.event(ev)?.is_consumed() {
+				return Ok(EventState::Consumed);
+			}
+
+			if let Event::Key(e) = ev {
+				if e == self.key_config.enter {
+					self.save();
+				}
+
+				return Ok(EventState::Consumed);
+			}
+		}
+		Ok(EventState::NotConsumed)
+	}
+
+	fn paste(&mut self, text: &str) -> Result<EventState> {
+		self.input.paste(text)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.input.is_visible()
+	}
+
+	fn hide(&mut self) {
+		self.input.hide();
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.input.show()?;
+
+		Ok(())
+	}
+
+	fn on_resize(&self) {
+		self.input.on_resize();
+	}
+}
+
+impl BranchDescriptionPopup {
+	///
+	pub fn new(
+		queue: Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			queue,
+			input: TextInputComponent::new(
+				theme,
+				key_config.clone(),
+				"",
+				&strings::branch_description_popup_msg(),
+				false,
+			),
+			branch: None,
+			key_config,
+		}
+	}
+
+	/// `branch` is the name of a local branch
+	pub fn open(&mut self, branch: String) -> Result<()> {
+		let description = sync::branch_get_description(CWD, &branch)?;
+
+		self.input.set_title(
+			strings::branch_description_popup_title(&branch),
+		);
+		self.input.set_text(description.unwrap_or_default());
+		self.branch = Some(branch);
+		self.show()?;
+
+		Ok(())
+	}
+
+	fn save(&mut self) {
+		if let Some(branch) = self.branch.take() {
+			let res = sync::branch_set_description(
+				CWD,
+				&branch,
+				self.input.get_text(),
+			);
+
+			self.hide();
+
+			match res {
+				Ok(()) => {
+					// reopen to show the new description
+					self.queue.push(InternalEvent::SelectBranch);
+				}
+				Err(e) => {
+					log::error!("set branch description: {}", e);
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!(
+							"set branch description error:\n{}",
+							e
+						),
+					));
+				}
+			}
+		}
+
+		self.input.clear();
+	}
+}
@@ -1,29 +1,44 @@
 use super::{
-	utils::scroll_vertical::VerticalScroll, visibility_blocking,
-	CommandBlocking, CommandInfo, Component, DrawableComponent,
-	EventState,
+	utils::{
+		logitems::relative_time_since,
+		scroll_vertical::VerticalScroll, string_width_align,
+	},
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState, SharedOptions,
 };
 use crate::{
+	branch_history::{record_checkout, SharedBranchHistory},
 	components::ScrollType,
 	keys::SharedKeyConfig,
+	messages::Severity,
 	queue::{Action, InternalEvent, NeedsUpdate, Queue},
-	strings, try_or_popup,
+	strings::{self, symbol},
+	try_or_popup,
 	ui::{self, Size},
 };
 use anyhow::Result;
 use asyncgit::{
+	asyncjob::AsyncSingleJob,
+	branch_tips::{AsyncBranchTipsJob, AsyncMergedTipsJob},
 	sync::{
 		self,
 		branch::{
-			checkout_remote_branch, BranchDetails, LocalBranch,
-			RemoteBranch,
+			checkout_remote_branch, BranchCompare, BranchDetails,
+			LocalBranch, RemoteBranch,
 		},
 		checkout_branch, get_branches_info, BranchInfo, CommitId,
+		CommitInfo, RepoState,
 	},
 	AsyncGitNotification, CWD,
 };
+use crossbeam_channel::Sender;
 use crossterm::event::Event;
-use std::{cell::Cell, convert::TryInto};
+use std::{
+	cell::Cell,
+	cmp::Reverse,
+	collections::{HashMap, HashSet},
+	convert::TryInto,
+};
 use tui::{
 	backend::Backend,
 	layout::{
@@ -36,15 +51,103 @@ use tui::{
 use ui::style::SharedTheme;
 use unicode_truncate::UnicodeTruncateStr;
 
+/// rows to look up tips for before the list was drawn once
+const TIPS_MIN_ROWS: usize = 50;
+
+#[derive(Copy, Clone, PartialEq)]
+enum BranchSort {
+	Name,
+	Age,
+}
+
+/// which branches to list by whether they are merged into the base
+#[derive(Copy, Clone, PartialEq)]
+enum MergedFilter {
+	All,
+	Merged,
+	Unmerged,
+}
+
+impl MergedFilter {
+	const fn next(self) -> Self {
+		match self {
+			Self::All => Self::Merged,
+			Self::Merged => Self::Unmerged,
+			Self::Unmerged => Self::All,
+		}
+	}
+}
+
+/// a branch kept across list updates (merge base, compare mark)
+struct MarkedBranch {
+	reference: String,
+	name: String,
+	id: CommitId,
+}
+
+impl MarkedBranch {
+	/// `None` for a branch without commits
+	fn new(branch: &BranchInfo) -> Option<Self> {
+		branch.top_commit.map(|id| Self {
+			reference: branch.reference.clone(),
+			name: branch.name.clone(),
+			id,
+		})
+	}
+
+	fn is(&self, branch: &BranchInfo) -> bool {
+		self.reference == branch.reference
+	}
+
+	/// takes the tip from `branches` if it is listed there
+	fn follow(&mut self, branches: &[BranchInfo]) {
+		if let Some(id) = branches
+			.iter()
+			.find(|b| self.is(b))
+			.and_then(|b| b.top_commit)
+		{
+			self.id = id;
+		}
+	}
+}
+
 ///
+#[allow(clippy::struct_excessive_bools)]
 pub struct BranchListComponent {
+	/// all fetched branches, `branches` are the listed ones
+	all_branches: Vec<BranchInfo>,
 	branches: Vec<BranchInfo>,
+	/// tip commits by branch reference and tip id
+	tips: HashMap<(String, CommitId), CommitInfo>,
+	tips_requested: HashSet<CommitId>,
+	async_tips: AsyncSingleJob<AsyncBranchTipsJob>,
+	merged_filter: MergedFilter,
+	/// checked for merging into instead of HEAD
+	merged_base: Option<MarkedBranch>,
+	/// commit the `merged` cache is valid for
+	merged_base_id: Option<CommitId>,
+	/// whether a tip is merged into `merged_base_id`, by tip id
+	merged: HashMap<CommitId, bool>,
+	merged_requested: HashSet<CommitId>,
+	async_merged: AsyncSingleJob<AsyncMergedTipsJob>,
+	/// bulk delete waits for all local branches to be checked
+	delete_merged_pending: bool,
+	/// base to compare the selected branch with
+	compare_mark: Option<MarkedBranch>,
+	sort: BranchSort,
 	local: bool,
+	/// descriptions of local branches by name
+	descriptions: HashMap<String, String>,
+	show_descriptions: bool,
+	/// ahead/behind of local branches to their upstream by name
+	compare: HashMap<String, BranchCompare>,
 	visible: bool,
 	selection: u16,
 	scroll: VerticalScroll,
 	current_height: Cell<u16>,
 	queue: Queue,
+	options: SharedOptions,
+	branch_history: SharedBranchHistory,
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
 }
@@ -72,7 +175,7 @@ impl DrawableComponent for BranchListComponent {
 
 			f.render_widget(
 				Block::default()
-					.title(strings::title_branches())
+					.title(self.title())
 					.border_type(BorderType::Thick)
 					.borders(Borders::ALL),
 				area,
@@ -130,11 +233,30 @@ impl Component for BranchListComponent {
 				true,
 			));
 
+			if self.compare_mark.is_some() {
+				out.push(CommandInfo::new(
+					strings::commands::compare_with_marked(
+						&self.key_config,
+					),
+					self.compare_target().is_some(),
+					true,
+				));
+			} else {
+				out.push(CommandInfo::new(
+					strings::commands::compare_with_head(
+						&self.key_config,
+					),
+					!self.selection_is_cur_branch(),
+					true,
+				));
+			}
+
 			out.push(CommandInfo::new(
-				strings::commands::compare_with_head(
+				strings::commands::branch_mark_compare(
 					&self.key_config,
+					self.selected_is_marked(),
 				),
-				!self.selection_is_cur_branch(),
+				self.valid_selection(),
 				true,
 			));
 
@@ -147,6 +269,17 @@ impl Component for BranchListComponent {
 				true,
 			));
 
+			out.push(CommandInfo::new(
+				strings::commands::branch_popup_sort(
+					&self.key_config,
+					self.sort == BranchSort::Age,
+				),
+				true,
+				true,
+			));
+
+			self.commands_merged(out);
+
 			out.push(CommandInfo::new(
 				strings::commands::select_branch_popup(
 					&self.key_config,
@@ -188,6 +321,14 @@ impl Component for BranchListComponent {
 				self.local,
 			));
 
+			out.push(CommandInfo::new(
+				strings::commands::branch_popup_autosquash(
+					&self.key_config,
+				),
+				!self.selection_is_cur_branch(),
+				true,
+			));
+
 			out.push(CommandInfo::new(
 				strings::commands::rename_branch_popup(
 					&self.key_config,
@@ -195,6 +336,8 @@ impl Component for BranchListComponent {
 				true,
 				self.local,
 			));
+
+			self.commands_description(out);
 		}
 		visibility_blocking(self)
 	}
@@ -227,7 +370,27 @@ impl Component for BranchListComponent {
 					.map(Into::into);
 			} else if e == self.key_config.tab_toggle {
 				self.local = !self.local;
+				self.delete_merged_pending = false;
 				self.update_branches()?;
+			} else if e == self.key_config.branch_sort {
+				self.sort = match self.sort {
+					BranchSort::Name => BranchSort::Age,
+					BranchSort::Age => BranchSort::Name,
+				};
+				self.update_list()?;
+				self.fetch_tips();
+			} else if e == self.key_config.branch_merged_filter {
+				self.merged_filter = self.merged_filter.next();
+				self.update_list()?;
+				self.fetch_merged();
+			} else if e == self.key_config.branch_merged_base
+				&& self.valid_selection()
+			{
+				self.toggle_merged_base()?;
+			} else if e == self.key_config.branch_delete_merged
+				&& self.local
+			{
+				self.delete_merged();
 			} else if e == self.key_config.enter {
 				try_or_popup!(
 					self,
@@ -241,6 +404,15 @@ impl Component for BranchListComponent {
 				&& self.valid_selection()
 			{
 				self.rename_branch();
+			} else if e == self.key_config.branch_edit_description
+				&& self.local
+				&& self.valid_selection()
+			{
+				self.edit_description();
+			} else if e == self.key_config.branch_toggle_descriptions
+				&& self.local
+			{
+				self.show_descriptions = !self.show_descriptions;
 			} else if e == self.key_config.delete_branch
 				&& !self.selection_is_cur_branch()
 				&& self.valid_selection()
@@ -264,6 +436,11 @@ impl Component for BranchListComponent {
 					"rebase error:",
 					self.rebase_branch()
 				);
+			} else if e == self.key_config.rebase_autosquash
+				&& !self.selection_is_cur_branch()
+				&& self.valid_selection()
+			{
+				self.autosquash_onto_selected();
 			} else if e == self.key_config.move_right
 				&& self.valid_selection()
 			{
@@ -272,13 +449,30 @@ impl Component for BranchListComponent {
 					self.queue
 						.push(InternalEvent::InspectCommit(b, None));
 				}
+			} else if e == self.key_config.log_mark_commit
+				&& self.valid_selection()
+			{
+				self.toggle_compare_mark();
 			} else if e == self.key_config.compare_commits
 				&& self.valid_selection()
 			{
-				self.hide();
-				if let Some(b) = self.get_selected() {
-					self.queue
-						.push(InternalEvent::CompareCommits(b, None));
+				if let Some(target) = self.compare_target() {
+					// stays open to come back to with the mark
+					if let Some(mark) = &self.compare_mark {
+						self.queue.push(
+							InternalEvent::CompareBranches(
+								(mark.name.clone(), mark.id),
+								(target.name, target.id),
+							),
+						);
+					}
+				} else {
+					self.hide();
+					if let Some(b) = self.get_selected() {
+						self.queue.push(
+							InternalEvent::CompareCommits(b, None),
+						);
+					}
 				}
 			}
 		}
@@ -299,21 +493,46 @@ impl Component for BranchListComponent {
 
 		Ok(())
 	}
+
+	fn on_resize(&self) {
+		self.current_height.set(0);
+	}
 }
 
 impl BranchListComponent {
 	pub fn new(
 		queue: Queue,
+		sender: &Sender<AsyncGitNotification>,
+		options: SharedOptions,
+		branch_history: SharedBranchHistory,
 		theme: SharedTheme,
 		key_config: SharedKeyConfig,
 	) -> Self {
 		Self {
+			all_branches: Vec::new(),
 			branches: Vec::new(),
+			tips: HashMap::new(),
+			tips_requested: HashSet::new(),
+			async_tips: AsyncSingleJob::new(sender.clone()),
+			merged_filter: MergedFilter::All,
+			merged_base: None,
+			merged_base_id: None,
+			merged: HashMap::new(),
+			merged_requested: HashSet::new(),
+			async_merged: AsyncSingleJob::new(sender.clone()),
+			delete_merged_pending: false,
+			compare_mark: None,
+			sort: BranchSort::Name,
 			local: true,
+			descriptions: HashMap::new(),
+			show_descriptions: true,
+			compare: HashMap::new(),
 			visible: false,
 			selection: 0,
 			scroll: VerticalScroll::new(),
 			queue,
+			options,
+			branch_history,
 			theme,
 			key_config,
 			current_height: Cell::new(0),
@@ -328,18 +547,67 @@ impl BranchListComponent {
 		Ok(())
 	}
 
+	/// remote branches are listed instead of the local ones
+	pub const fn shows_remote(&self) -> bool {
+		!self.local
+	}
+
+	/// takes effect on the next `open`
+	pub fn set_show_remote(&mut self, remote: bool) {
+		self.local = !remote;
+	}
+
 	/// fetch list of branches
 	pub fn update_branches(&mut self) -> Result<()> {
 		if self.is_visible() {
-			self.branches = get_branches_info(CWD, self.local)?;
+			self.all_branches = get_branches_info(CWD, self.local)?;
 			//remove remote branch called `HEAD`
 			if !self.local {
-				self.branches
+				self.all_branches
 					.iter()
 					.position(|b| b.name.ends_with("/HEAD"))
-					.map(|idx| self.branches.remove(idx));
+					.map(|idx| self.all_branches.remove(idx));
+			}
+
+			self.descriptions = if self.local {
+				sync::get_branch_descriptions(CWD).unwrap_or_else(
+					|e| {
+						log::error!("branch descriptions: {}", e);
+						HashMap::new()
+					},
+				)
+			} else {
+				HashMap::new()
+			};
+
+			self.compare = if self.local {
+				sync::branches_compare_upstream(CWD).unwrap_or_else(
+					|e| {
+						log::error!("branch compare: {}", e);
+						HashMap::new()
+					},
+				)
+			} else {
+				HashMap::new()
+			};
+
+			let branches = &self.all_branches;
+			self.tips.retain(|(reference, id), _| {
+				branches.iter().any(|b| {
+					&b.reference == reference
+						&& b.top_commit == Some(*id)
+				})
+			});
+			self.tips_requested.clear();
+
+			if let Some(mark) = &mut self.compare_mark {
+				mark.follow(&self.all_branches);
 			}
-			self.set_selection(self.selection)?;
+
+			self.update_merged_base();
+			self.update_list()?;
+			self.fetch_tips();
+			self.fetch_merged();
 		}
 		Ok(())
 	}
@@ -350,14 +618,136 @@ impl BranchListComponent {
 		ev: AsyncGitNotification,
 	) -> Result<()> {
 		if self.is_visible() {
-			if let AsyncGitNotification::Push = ev {
-				self.update_branches()?;
+			match ev {
+				AsyncGitNotification::Push => {
+					self.update_branches()?;
+				}
+				AsyncGitNotification::BranchTips => {
+					self.update_tips()?;
+				}
+				AsyncGitNotification::MergedTips => {
+					self.update_merged()?;
+				}
+				_ => (),
 			}
 		}
 
 		Ok(())
 	}
 
+	fn update_tips(&mut self) -> Result<()> {
+		if let Some(job) = self.async_tips.take_last() {
+			match job.result() {
+				Some(Ok(infos)) => {
+					for info in infos {
+						for b in self
+							.all_branches
+							.iter()
+							.filter(|b| b.top_commit == Some(info.id))
+						{
+							self.tips.insert(
+								(b.reference.clone(), info.id),
+								info.clone(),
+							);
+						}
+					}
+				}
+				Some(Err(e)) => {
+					log::error!("branch tips error: {}", e);
+				}
+				None => (),
+			}
+		}
+
+		if self.sort == BranchSort::Age {
+			self.update_list()?;
+		}
+
+		self.fetch_tips();
+
+		Ok(())
+	}
+
+	fn tip(&self, branch: &BranchInfo) -> Option<&CommitInfo> {
+		branch.top_commit.and_then(|id| {
+			self.tips.get(&(branch.reference.clone(), id))
+		})
+	}
+
+	/// requests tips of the rows around the selection,
+	/// sorting by age needs all of them
+	fn fetch_tips(&mut self) {
+		if self.async_tips.is_pending() {
+			return;
+		}
+
+		let rows = if self.sort == BranchSort::Age {
+			0..self.branches.len()
+		} else {
+			let height = usize::from(self.current_height.get())
+				.max(TIPS_MIN_ROWS);
+			let selection = usize::from(self.selection);
+			selection.saturating_sub(height)..selection + height
+		};
+
+		let ids: Vec<CommitId> = self
+			.branches
+			.iter()
+			.take(rows.end)
+			.skip(rows.start)
+			.filter(|b| self.tip(b).is_none())
+			.filter_map(|b| b.top_commit)
+			.filter(|id| !self.tips_requested.contains(id))
+			.collect::<HashSet<_>>()
+			.into_iter()
+			.collect();
+
+		if !ids.is_empty() {
+			self.tips_requested.extend(ids.iter().copied());
+			self.async_tips.spawn(AsyncBranchTipsJob::new(ids));
+		}
+	}
+
+	/// lists the branches passing the filter sorted,
+	/// keeps the selected branch selected
+	fn update_list(&mut self) -> Result<()> {
+		let selected = self
+			.branches
+			.get(usize::from(self.selection))
+			.map(|b| b.reference.clone());
+
+		let mut branches = self
+			.all_branches
+			.iter()
+			.filter(|b| self.is_listed(b))
+			.cloned()
+			.collect::<Vec<_>>();
+
+		match self.sort {
+			BranchSort::Name => {
+				branches.sort_by(|a, b| a.name.cmp(&b.name));
+			}
+			BranchSort::Age => {
+				// newest first, branches without known tip last
+				branches.sort_by_key(|b| {
+					Reverse(self.tip(b).map(|tip| tip.time))
+				});
+			}
+		}
+
+		self.branches = branches;
+
+		let selection = selected
+			.and_then(|selected| {
+				self.branches
+					.iter()
+					.position(|b| b.reference == selected)
+			})
+			.map_or(Ok(self.selection), TryInto::try_into)?;
+
+		self.set_selection(selection)
+	}
+
 	fn valid_selection(&self) -> bool {
 		!self.branches.is_empty()
 	}
@@ -389,6 +779,18 @@ impl BranchListComponent {
 		Ok(())
 	}
 
+	fn autosquash_onto_selected(&self) {
+		if let Some(branch) =
+			self.branches.get(usize::from(self.selection))
+		{
+			if let Some(id) = branch.top_commit {
+				self.queue.push(InternalEvent::ConfirmAction(
+					Action::AutosquashRebase(id, branch.name.clone()),
+				));
+			}
+		}
+	}
+
 	fn selection_is_cur_branch(&self) -> bool {
 		self.branches
 			.iter()
@@ -407,7 +809,7 @@ impl BranchListComponent {
 	fn get_selected(&self) -> Option<CommitId> {
 		self.branches
 			.get(usize::from(self.selection))
-			.map(|b| b.top_commit)
+			.and_then(|b| b.top_commit)
 	}
 
 	///
@@ -425,6 +827,7 @@ impl BranchListComponent {
 		};
 
 		self.set_selection(new_selection)?;
+		self.fetch_tips();
 
 		Ok(true)
 	}
@@ -457,6 +860,8 @@ impl BranchListComponent {
 		const EMPTY_SYMBOL: char = ' ';
 		const THREE_DOTS: &str = "...";
 		const COMMIT_HASH_LENGTH: usize = 8;
+		const COMMIT_AGE_LENGTH: usize = 14; // "59 minutes ago"
+		const COMMIT_AUTHOR_LENGTH: usize = 10;
 		const IS_HEAD_STAR_LENGTH: usize = 3; // "*  "
 		const THREE_DOTS_LENGTH: usize = THREE_DOTS.len(); // "..."
 
@@ -465,6 +870,8 @@ impl BranchListComponent {
 		// commit message takes up the remaining width
 		let commit_message_length: usize = (width_available as usize)
 			.saturating_sub(COMMIT_HASH_LENGTH)
+			.saturating_sub(COMMIT_AGE_LENGTH + 1)
+			.saturating_sub(COMMIT_AUTHOR_LENGTH + 1)
 			.saturating_sub(branch_name_length)
 			.saturating_sub(IS_HEAD_STAR_LENGTH)
 			.saturating_sub(THREE_DOTS_LENGTH);
@@ -477,8 +884,35 @@ impl BranchListComponent {
 			.take(height)
 			.enumerate()
 		{
-			let mut commit_message =
-				displaybranch.top_commit_message.clone();
+			let tip = self.tip(displaybranch);
+
+			let mut commit_message = tip.map_or_else(
+				|| {
+					if displaybranch.top_commit.is_none() {
+						String::from(strings::BRANCH_NO_COMMITS)
+					} else {
+						String::new()
+					}
+				},
+				|tip| tip.message.clone(),
+			);
+
+			let compare = self
+				.compare
+				.get(&displaybranch.name)
+				.filter(|c| c.ahead > 0 || c.behind > 0)
+				.map_or_else(String::new, |c| {
+					format!(
+						"{}{} {}{} ",
+						UPSTREAM_SYMBOL,
+						c.ahead,
+						TRACKING_SYMBOL,
+						c.behind
+					)
+				});
+			let commit_message_length = commit_message_length
+				.saturating_sub(compare.chars().count());
+
 			if commit_message.len() > commit_message_length {
 				commit_message.unicode_truncate(
 					commit_message_length
@@ -524,19 +958,50 @@ impl BranchListComponent {
 			};
 
 			let span_prefix = Span::styled(
-				format!("{}{} ", is_head_str, upstream_tracking_str),
+				format!("{}{}", is_head_str, upstream_tracking_str),
 				theme.commit_author(selected),
 			);
+			let span_mark = Span::styled(
+				if self
+					.compare_mark
+					.as_ref()
+					.map_or(false, |mark| mark.is(displaybranch))
+				{
+					symbol::CHECKMARK
+				} else {
+					symbol::EMPTY_SPACE
+				},
+				theme.log_marker(selected),
+			);
 			let span_hash = Span::styled(
-				format!(
-					"{} ",
-					displaybranch.top_commit.get_short_string()
-				),
+				displaybranch
+					.top_commit
+					.map_or_else(String::new, |id| {
+						format!("{} ", id.abbreviated(CWD))
+					}),
 				theme.commit_hash(selected),
 			);
+			let span_details = Span::styled(
+				tip.map_or_else(String::new, |tip| {
+					format!(
+						"{} {} ",
+						string_width_align(
+							&relative_time_since(tip.time),
+							COMMIT_AGE_LENGTH
+						),
+						string_width_align(
+							&tip.author,
+							COMMIT_AUTHOR_LENGTH
+						),
+					)
+				}),
+				theme.text(false, selected),
+			);
+			let span_compare =
+				Span::styled(compare, theme.commit_author(selected));
 			let span_msg = Span::styled(
 				commit_message.to_string(),
-				theme.text(true, selected),
+				theme.text(false, selected),
 			);
 			let span_name = Span::styled(
 				format!(
@@ -547,12 +1012,41 @@ impl BranchListComponent {
 				theme.branch(selected, is_head),
 			);
 
-			txt.push(Spans::from(vec![
+			let mut spans: Vec<Span> = theme
+				.selection_marker(selected)
+				.into_iter()
+				.collect();
+			spans.extend(vec![
 				span_prefix,
+				span_mark,
 				span_name,
 				span_hash,
+				span_details,
+				span_compare,
 				span_msg,
-			]));
+			]);
+			txt.push(Spans::from(spans));
+
+			if let Some(description) =
+				self.description_line(displaybranch)
+			{
+				txt.push(Spans::from(Span::styled(
+					format!(
+						"{:w$}{}",
+						"",
+						description
+							.unicode_truncate(
+								(width_available as usize)
+									.saturating_sub(
+										IS_HEAD_STAR_LENGTH
+									),
+							)
+							.0,
+						w = IS_HEAD_STAR_LENGTH
+					),
+					theme.text(false, false),
+				)));
+			}
 		}
 
 		Text::from(txt)
@@ -564,17 +1058,31 @@ impl BranchListComponent {
 			anyhow::bail!("no valid branch selected");
 		}
 
+		let repo_state = sync::repo_state(CWD)?;
+		if repo_state != RepoState::Clean {
+			anyhow::bail!(strings::repo_state_blocks(
+				repo_state,
+				"switch branches"
+			));
+		}
+
 		if self.local {
-			checkout_branch(
-				asyncgit::CWD,
-				&self.branches[self.selection as usize].reference,
-			)?;
+			record_checkout(&self.branch_history, || {
+				checkout_branch(
+					asyncgit::CWD,
+					&self.branches[self.selection as usize].reference,
+				)
+				.map_err(Into::into)
+			})?;
 			self.hide();
 		} else {
-			checkout_remote_branch(
-				CWD,
-				&self.branches[self.selection as usize],
-			)?;
+			record_checkout(&self.branch_history, || {
+				checkout_remote_branch(
+					CWD,
+					&self.branches[self.selection as usize],
+				)
+				.map_err(Into::into)
+			})?;
 			self.local = true;
 			self.update_branches()?;
 		}
@@ -612,19 +1120,22 @@ impl BranchListComponent {
 		r: Rect,
 	) -> Result<()> {
 		let height_in_lines = r.height as usize;
-		self.current_height.set(height_in_lines.try_into()?);
+		// rows with a description line take up two lines
+		let height_in_rows =
+			height_in_lines / self.lines_per_branch();
+		self.current_height.set(height_in_rows.try_into()?);
 
 		self.scroll.update(
 			self.selection as usize,
 			self.branches.len(),
-			height_in_lines,
+			height_in_rows,
 		);
 
 		f.render_widget(
 			Paragraph::new(self.get_text(
 				&self.theme,
 				r.width,
-				height_in_lines,
+				height_in_rows,
 			))
 			.alignment(Alignment::Left),
 			r,
@@ -640,6 +1151,56 @@ impl BranchListComponent {
 		Ok(())
 	}
 
+	fn commands_description(&self, out: &mut Vec<CommandInfo>) {
+		out.push(CommandInfo::new(
+			strings::commands::branch_edit_description(
+				&self.key_config,
+			),
+			self.valid_selection(),
+			self.local,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::branch_toggle_descriptions(
+				&self.key_config,
+				self.show_descriptions,
+			),
+			true,
+			self.local,
+		));
+	}
+
+	fn edit_description(&self) {
+		self.queue.push(InternalEvent::EditBranchDescription(
+			self.branches[self.selection as usize].name.clone(),
+		));
+	}
+
+	/// first line of the description to draw under the branch
+	fn description_line(&self, branch: &BranchInfo) -> Option<&str> {
+		if !self.show_descriptions {
+			return None;
+		}
+
+		self.descriptions
+			.get(&branch.name)
+			.and_then(|description| description.lines().next())
+	}
+
+	/// lines taken by each branch row
+	fn lines_per_branch(&self) -> usize {
+		if self.show_descriptions
+			&& self
+				.branches
+				.iter()
+				.any(|branch| self.description_line(branch).is_some())
+		{
+			2
+		} else {
+			1
+		}
+	}
+
 	fn rename_branch(&mut self) {
 		let cur_branch = &self.branches[self.selection as usize];
 		self.queue.push(InternalEvent::RenameBranch(
@@ -658,4 +1219,244 @@ impl BranchListComponent {
 			),
 		));
 	}
+
+	fn title(&self) -> String {
+		if self.merged_filter == MergedFilter::All {
+			return strings::title_branches();
+		}
+
+		strings::title_branches_merged(
+			self.merged_filter == MergedFilter::Merged,
+			self.merged_base
+				.as_ref()
+				.map_or("HEAD", |base| base.name.as_str()),
+			self.async_merged.is_pending(),
+		)
+	}
+
+	/// branches not checked yet are only listed unfiltered
+	fn is_listed(&self, branch: &BranchInfo) -> bool {
+		let merged = branch
+			.top_commit
+			.and_then(|id| self.merged.get(&id).copied());
+
+		match self.merged_filter {
+			MergedFilter::All => true,
+			MergedFilter::Merged => merged == Some(true),
+			MergedFilter::Unmerged => merged == Some(false),
+		}
+	}
+
+	/// follows the base (or HEAD) moving, the cache is dropped if it did
+	fn update_merged_base(&mut self) {
+		if let Some(base) = &mut self.merged_base {
+			base.follow(&self.all_branches);
+		}
+
+		let base_id = self.merged_base.as_ref().map_or_else(
+			|| sync::get_head(CWD).ok(),
+			|base| Some(base.id),
+		);
+
+		if base_id != self.merged_base_id {
+			self.merged_base_id = base_id;
+			self.merged.clear();
+			self.merged_requested.clear();
+		}
+	}
+
+	/// checks the tips not known to be merged or not yet, only while
+	/// filtering or about to bulk delete
+	fn fetch_merged(&mut self) {
+		if self.async_merged.is_pending()
+			|| (self.merged_filter == MergedFilter::All
+				&& !self.delete_merged_pending)
+		{
+			return;
+		}
+
+		if let Some(base) = self.merged_base_id {
+			let tips: Vec<CommitId> = self
+				.all_branches
+				.iter()
+				.filter_map(|b| b.top_commit)
+				.filter(|id| {
+					!self.merged.contains_key(id)
+						&& !self.merged_requested.contains(id)
+				})
+				.collect::<HashSet<_>>()
+				.into_iter()
+				.collect();
+
+			if !tips.is_empty() {
+				self.merged_requested.extend(tips.iter().copied());
+				self.async_merged
+					.spawn(AsyncMergedTipsJob::new(base, tips));
+			}
+		}
+	}
+
+	fn update_merged(&mut self) -> Result<()> {
+		if let Some(job) = self.async_merged.take_last() {
+			match job.result() {
+				Some((base, Ok(merged)))
+					if Some(base) == self.merged_base_id =>
+				{
+					for id in self.merged_requested.drain() {
+						self.merged.insert(id, merged.contains(&id));
+					}
+				}
+				Some((_, Err(e))) => {
+					log::error!("merged branches error: {}", e);
+					self.delete_merged_pending = false;
+				}
+				// checked against a base that moved since
+				_ => self.merged_requested.clear(),
+			}
+		}
+
+		self.update_list()?;
+		self.fetch_merged();
+
+		if self.delete_merged_pending {
+			self.delete_merged();
+		}
+
+		Ok(())
+	}
+
+	/// the selected branch becomes the base, or HEAD again if it
+	/// already was
+	fn toggle_merged_base(&mut self) -> Result<()> {
+		let selected = &self.branches[self.selection as usize];
+
+		self.merged_base = if self
+			.merged_base
+			.as_ref()
+			.map_or(false, |base| base.is(selected))
+		{
+			None
+		} else {
+			MarkedBranch::new(selected)
+		};
+
+		if self.merged_filter == MergedFilter::All {
+			self.merged_filter = MergedFilter::Merged;
+		}
+
+		self.update_merged_base();
+		self.update_list()?;
+		self.fetch_merged();
+
+		Ok(())
+	}
+
+	/// local branches merged into the base except the current one,
+	/// the base itself and protected ones,
+	/// `None` while some are not checked yet
+	fn merged_to_delete(&self) -> Option<Vec<String>> {
+		let options = self.options.borrow();
+		let mut refs = Vec::new();
+
+		for b in &self.all_branches {
+			let is_head = b
+				.local_details()
+				.map(|details| details.is_head)
+				.unwrap_or_default();
+			let is_base = self
+				.merged_base
+				.as_ref()
+				.map_or(false, |base| base.is(b));
+
+			if is_head
+				|| is_base || options.is_protected_branch(&b.name)
+			{
+				continue;
+			}
+
+			if let Some(id) = b.top_commit {
+				if *self.merged.get(&id)? {
+					refs.push(b.reference.clone());
+				}
+			}
+		}
+
+		Some(refs)
+	}
+
+	fn delete_merged(&mut self) {
+		if let Some(refs) = self.merged_to_delete() {
+			self.delete_merged_pending = false;
+
+			if refs.is_empty() {
+				self.queue.push(InternalEvent::ShowToast(
+					Severity::Info,
+					strings::toast_no_merged_branches(),
+				));
+			} else {
+				self.queue.push(InternalEvent::ConfirmAction(
+					Action::DeleteMergedBranches(refs),
+				));
+			}
+		} else if !self.delete_merged_pending {
+			self.delete_merged_pending = true;
+			self.fetch_merged();
+		}
+	}
+
+	fn commands_merged(&self, out: &mut Vec<CommandInfo>) {
+		out.push(CommandInfo::new(
+			strings::commands::branch_merged_filter(
+				&self.key_config,
+				match self.merged_filter.next() {
+					MergedFilter::All => "all",
+					MergedFilter::Merged => "merged",
+					MergedFilter::Unmerged => "unmerged",
+				},
+			),
+			true,
+			true,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::branch_merged_base(&self.key_config),
+			self.valid_selection(),
+			true,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::branch_delete_merged(&self.key_config),
+			!self.delete_merged_pending,
+			self.local,
+		));
+	}
+
+	fn selected_is_marked(&self) -> bool {
+		self.compare_mark.as_ref().map_or(false, |mark| {
+			self.branches
+				.get(usize::from(self.selection))
+				.map_or(false, |b| mark.is(b))
+		})
+	}
+
+	fn toggle_compare_mark(&mut self) {
+		self.compare_mark = if self.selected_is_marked() {
+			None
+		} else {
+			self.branches
+				.get(usize::from(self.selection))
+				.and_then(MarkedBranch::new)
+		};
+	}
+
+	/// the selected branch if there is a mark to compare it with
+	fn compare_target(&self) -> Option<MarkedBranch> {
+		if self.compare_mark.is_none() || self.selected_is_marked() {
+			return None;
+		}
+
+		self.branches
+			.get(usize::from(self.selection))
+			.and_then(MarkedBranch::new)
+	}
 }
@@ -1,19 +1,24 @@
 use super::{
 	filetree::FileTreeComponent,
-	utils::filetree::{FileTreeItem, FileTreeItemKind},
+	utils::{
+		filetree::{FileTreeItem, FileTreeItemKind},
+		index_op::{notify_flagged, run_index_op},
+	},
 	CommandBlocking, DrawableComponent,
 };
 use crate::{
-	components::{CommandInfo, Component, EventState},
+	components::{CommandInfo, Component, EventState, SharedOptions},
 	keys::SharedKeyConfig,
-	queue::{Action, InternalEvent, NeedsUpdate, Queue, ResetItem},
-	strings, try_or_popup,
+	queue::{
+		Action, IndexOp, InternalEvent, NeedsUpdate, Queue, ResetItem,
+	},
+	strings,
 	ui::style::SharedTheme,
 };
 use anyhow::Result;
 use asyncgit::{sync, StatusItem, StatusItemType, CWD};
 use crossterm::event::Event;
-use std::path::Path;
+use std::{path::PathBuf, rc::Rc};
 use tui::{backend::Backend, layout::Rect, Frame};
 
 ///
@@ -22,6 +27,7 @@ pub struct ChangesComponent {
 	is_working_dir: bool,
 	queue: Queue,
 	key_config: SharedKeyConfig,
+	options: SharedOptions,
 }
 
 impl ChangesComponent {
@@ -33,6 +39,7 @@ impl ChangesComponent {
 		queue: Queue,
 		theme: SharedTheme,
 		key_config: SharedKeyConfig,
+		options: SharedOptions,
 	) -> Self {
 		Self {
 			files: FileTreeComponent::new(
@@ -45,6 +52,7 @@ impl ChangesComponent {
 			is_working_dir,
 			queue,
 			key_config,
+			options,
 		}
 	}
 
@@ -54,11 +62,21 @@ impl ChangesComponent {
 		Ok(())
 	}
 
+	///
+	pub fn set_title(&mut self, title: String) {
+		self.files.set_title(title);
+	}
+
 	///
 	pub fn selection(&self) -> Option<FileTreeItem> {
 		self.files.selection()
 	}
 
+	/// `false` if `path` is not listed
+	pub fn select_path(&mut self, path: &str) -> bool {
+		self.files.select_path(path)
+	}
+
 	///
 	pub fn focus_select(&mut self, focus: bool) {
 		self.files.focus(focus);
@@ -70,72 +88,215 @@ impl ChangesComponent {
 		self.files.is_empty()
 	}
 
+	/// the status is not loaded yet
+	pub const fn is_pending(&self) -> bool {
+		self.files.is_pending()
+	}
+
 	///
 	pub fn is_file_seleted(&self) -> bool {
 		self.files.is_file_seleted()
 	}
 
-	fn index_add_remove(&mut self) -> Result<bool> {
-		if let Some(tree_item) = self.selection() {
-			if self.is_working_dir {
-				if let FileTreeItemKind::File(i) = tree_item.kind {
-					let path = Path::new(i.path.as_str());
-					match i.status {
-						StatusItemType::Deleted => {
-							sync::stage_addremoved(CWD, path)?;
-						}
-						_ => sync::stage_add_file(CWD, path)?,
-					};
-
-					if self.is_empty() {
-						self.queue
-							.push(InternalEvent::StatusLastFileMoved);
-					}
+	/// stages or unstages the selected item
+	fn selection_index_op(&self) -> Option<IndexOp> {
+		let tree_item = self.selection()?;
+
+		if self.is_working_dir {
+			if let FileTreeItemKind::File(i) = tree_item.kind {
+				if i.status == StatusItemType::EmptyDir {
+					return Some(Rc::new(move || {
+						sync::add_gitkeep(CWD, &i.path)
+					}));
+				}
 
-					return Ok(true);
+				let path = PathBuf::from(i.path);
+				if let Some(old_path) = i.old_path {
+					// case-only rename, see `case_only_renames`
+					let old_path = PathBuf::from(old_path);
+					return Some(Rc::new(move || {
+						sync::stage_renamed(CWD, &old_path, &path)
+					}));
 				}
+				return Some(match i.status {
+					StatusItemType::Deleted => Rc::new(move || {
+						sync::stage_addremoved(CWD, &path)
+					}),
+					_ => Rc::new(move || {
+						sync::stage_add_file(CWD, &path)
+					}),
+				});
+			}
+
+			//TODO: check if we can handle the one file case with it aswell
+			let path = tree_item.info.full_path;
+			return Some(Rc::new(move || {
+				sync::stage_add_all(CWD, &path)
+			}));
+		}
 
-				//TODO: check if we can handle the one file case with it aswell
-				sync::stage_add_all(
-					CWD,
-					tree_item.info.full_path.as_str(),
-				)?;
+		if let FileTreeItemKind::File(StatusItem {
+			path,
+			old_path: Some(old_path),
+			..
+		}) = tree_item.kind
+		{
+			// unstage both sides of a rename at once
+			return Some(Rc::new(move || {
+				sync::reset_stage_renamed(CWD, &old_path, &path)
+			}));
+		}
+
+		let path = tree_item.info.full_path;
+		Some(Rc::new(move || sync::reset_stage(CWD, &path)))
+	}
 
-				return Ok(true);
+	/// `true` if staging `pattern` waits for the user to confirm
+	/// staging files that should go into lfs with their raw content
+	fn confirm_lfs_unconverted(
+		&self,
+		pattern: &str,
+		op: &IndexOp,
+	) -> bool {
+		match sync::lfs_unconverted(CWD, pattern) {
+			Ok(files) if !files.is_empty() => {
+				self.queue.push(InternalEvent::ConfirmAction(
+					Action::StageLfsUnconverted(files, Rc::clone(op)),
+				));
+				true
+			}
+			Ok(_) => false,
+			Err(e) => {
+				log::error!("lfs check error: {}", e);
+				false
 			}
+		}
+	}
 
-			let path = tree_item.info.full_path.as_str();
-			sync::reset_stage(CWD, path)?;
-			return Ok(true);
+	/// `true` if staging `pattern` waits for the user to confirm
+	/// files that are new or grew past the large file threshold
+	fn confirm_large_files(
+		&self,
+		pattern: &str,
+		op: &IndexOp,
+	) -> bool {
+		let threshold =
+			match self.options.borrow().large_file_threshold() {
+				Some(threshold) => threshold,
+				None => return false,
+			};
+
+		match sync::large_files_to_stage(CWD, pattern, threshold) {
+			Ok(files) if !files.is_empty() => {
+				self.queue.push(InternalEvent::ConfirmAction(
+					Action::StageLargeFiles(files, Rc::clone(op)),
+				));
+				true
+			}
+			Ok(_) => false,
+			Err(e) => {
+				log::error!("large files check error: {}", e);
+				false
+			}
 		}
+	}
 
-		Ok(false)
+	/// `true` if staging `pattern` waits for a confirmation
+	fn confirm_staging(&self, pattern: &str, op: &IndexOp) -> bool {
+		self.confirm_lfs_unconverted(pattern, op)
+			|| self.confirm_large_files(pattern, op)
 	}
 
-	fn index_add_all(&mut self) -> Result<()> {
-		sync::stage_add_all(CWD, "*")?;
+	fn index_add_remove(&self) -> bool {
+		let staged_file =
+			self.is_working_dir && self.is_file_seleted();
 
-		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+		let op = match self.selection_index_op() {
+			Some(op) => op,
+			None => return false,
+		};
 
-		Ok(())
+		if self.is_working_dir {
+			if let Some(tree_item) = self.selection() {
+				if self
+					.confirm_staging(&tree_item.info.full_path, &op)
+				{
+					return false;
+				}
+			}
+		}
+
+		let done = run_index_op(&self.queue, "staging error:", op);
+
+		if done && self.is_working_dir {
+			if let Some(tree_item) = self.selection() {
+				notify_flagged(
+					&self.queue,
+					&self.key_config,
+					&tree_item.info.full_path,
+				);
+			}
+		}
+
+		if done && staged_file && self.is_empty() {
+			self.queue.push(InternalEvent::StatusLastFileMoved);
+		}
+
+		done
 	}
 
-	fn stage_remove_all(&mut self) -> Result<()> {
-		sync::reset_stage(CWD, "*")?;
+	fn index_add_all(&self) {
+		let op: IndexOp = Rc::new(|| sync::stage_add_all(CWD, "*"));
+		if self.confirm_staging("*", &op) {
+			return;
+		}
+
+		if run_index_op(&self.queue, "staging all error:", op) {
+			notify_flagged(&self.queue, &self.key_config, "*");
+		}
 
 		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+	}
 
-		Ok(())
+	fn stage_remove_all(&self) {
+		run_index_op(
+			&self.queue,
+			"unstaging all error:",
+			Rc::new(|| sync::reset_stage(CWD, "*")),
+		);
+
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+	}
+
+	/// an empty directory git cannot track is selected
+	fn empty_dir_selected(&self) -> bool {
+		matches!(
+			self.selection().map(|item| item.kind),
+			Some(FileTreeItemKind::File(StatusItem {
+				status: StatusItemType::EmptyDir,
+				..
+			}))
+		)
 	}
 
 	fn dispatch_reset_workdir(&mut self) -> bool {
+		if self.empty_dir_selected() {
+			return false;
+		}
+
 		if let Some(tree_item) = self.selection() {
 			let is_folder =
 				matches!(tree_item.kind, FileTreeItemKind::Path(_));
+			let files = if is_folder {
+				self.files.file_count_in(&tree_item.info.full_path)
+			} else {
+				1
+			};
 			self.queue.push(InternalEvent::ConfirmAction(
 				Action::Reset(ResetItem {
 					path: tree_item.info.full_path,
 					is_folder,
+					files,
 				}),
 			));
 
@@ -195,14 +356,19 @@ impl Component for ChangesComponent {
 				some_selection,
 				self.focused(),
 			));
+			let empty_dir = self.empty_dir_selected();
 			out.push(CommandInfo::new(
-				strings::commands::stage_item(&self.key_config),
+				if empty_dir {
+					strings::commands::add_gitkeep(&self.key_config)
+				} else {
+					strings::commands::stage_item(&self.key_config)
+				},
 				some_selection,
 				self.focused(),
 			));
 			out.push(CommandInfo::new(
 				strings::commands::reset_item(&self.key_config),
-				some_selection,
+				some_selection && !empty_dir,
 				self.focused(),
 			));
 			out.push(CommandInfo::new(
@@ -224,7 +390,7 @@ impl Component for ChangesComponent {
 			out.push(
 				CommandInfo::new(
 					strings::commands::commit_open(&self.key_config),
-					!self.is_empty(),
+					true,
 					self.focused() || force_all,
 				)
 				.order(-1),
@@ -243,16 +409,11 @@ impl Component for ChangesComponent {
 			if let Event::Key(e) = ev {
 				return if e == self.key_config.open_commit
 					&& !self.is_working_dir
-					&& !self.is_empty()
 				{
 					self.queue.push(InternalEvent::OpenCommit);
 					Ok(EventState::Consumed)
 				} else if e == self.key_config.enter {
-					try_or_popup!(
-						self,
-						"staging error:",
-						self.index_add_remove()
-					);
+					self.index_add_remove();
 
 					self.queue.push(InternalEvent::Update(
 						NeedsUpdate::ALL,
@@ -262,13 +423,9 @@ impl Component for ChangesComponent {
 					&& !self.is_empty()
 				{
 					if self.is_working_dir {
-						try_or_popup!(
-							self,
-							"staging all error:",
-							self.index_add_all()
-						);
+						self.index_add_all();
 					} else {
-						self.stage_remove_all()?;
+						self.stage_remove_all();
 					}
 					self.queue
 						.push(InternalEvent::StatusLastFileMoved);
@@ -297,4 +454,8 @@ impl Component for ChangesComponent {
 	fn focus(&mut self, focus: bool) {
 		self.files.focus(focus);
 	}
+
+	fn selectable_text(&self) -> Option<Vec<String>> {
+		self.files.selectable_text()
+	}
 }
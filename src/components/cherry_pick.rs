@@ -0,0 +1,284 @@
+use super::{
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	messages::Severity,
+	queue::{Action, InternalEvent, NeedsUpdate, Queue},
+	strings, ui,
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+	asyncjob::AsyncSingleJob,
+	cherry_pick::{AsyncCherryPickJob, CherryPickRequest},
+	sync::{self, CherryPickProgress, CherryPickResult, RepoState},
+	AsyncGitNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use tui::{
+	backend::Backend,
+	layout::Rect,
+	text::Span,
+	widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+	Frame,
+};
+
+/// progress of a cherry-pick sequence, where it stopped at a conflict
+/// it offers to continue, skip the commit or abort
+pub struct CherryPickPopup {
+	visible: bool,
+	job: AsyncSingleJob<AsyncCherryPickJob>,
+	/// the pick being applied
+	progress: CherryPickProgress,
+	/// the pick the sequence stopped at
+	stopped: Option<CherryPickProgress>,
+	/// commits of the sequence
+	total: usize,
+	/// picks applied already, over all runs of the sequence
+	empty: usize,
+	skipped: usize,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for CherryPickPopup {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		_rect: Rect,
+	) -> Result<()> {
+		if self.visible {
+			let text = self.stopped.as_ref().map_or_else(
+				|| strings::cherry_pick_applying(&self.progress),
+				strings::cherry_pick_stopped,
+			);
+
+			let area = ui::centered_rect_absolute(60, 6, f.size());
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(text)
+					.style(if self.stopped.is_some() {
+						self.theme.text_warning()
+					} else {
+						self.theme.text(true, false)
+					})
+					.wrap(Wrap { trim: true })
+					.block(
+						Block::default()
+							.title(Span::styled(
+								strings::CHERRY_PICK_TITLE,
+								self.theme.title(true),
+							))
+							.borders(Borders::ALL)
+							.border_type(BorderType::Thick)
+							.border_style(self.theme.block(true)),
+					),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for CherryPickPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.stopped.is_some() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(CommandInfo::new(
+				strings::commands::continue_merge(
+					&self.key_config,
+					RepoState::CherryPick,
+				),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::cherry_pick_skip(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::abort_merge(
+					&self.key_config,
+					RepoState::CherryPick,
+				),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.visible {
+			if let Event::Key(e) = ev {
+				if self.stopped.is_some() {
+					if e == self.key_config.exit_popup {
+						// to resolve the conflicts
+						self.hide();
+					} else if e == self.key_config.continue_merge {
+						self.run(CherryPickRequest::Continue);
+					} else if e == self.key_config.cherry_pick_skip {
+						self.run(CherryPickRequest::Skip);
+					} else if e == self.key_config.abort_merge {
+						self.hide();
+						self.queue.push(
+							InternalEvent::ConfirmAction(
+								Action::AbortRepoState(
+									RepoState::CherryPick,
+								),
+							),
+						);
+					}
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
+
+impl CherryPickPopup {
+	///
+	pub fn new(
+		queue: &Queue,
+		sender: &Sender<AsyncGitNotification>,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			visible: false,
+			job: AsyncSingleJob::new(sender.clone()),
+			progress: CherryPickProgress::default(),
+			stopped: None,
+			total: 0,
+			empty: 0,
+			skipped: 0,
+			queue: queue.clone(),
+			theme,
+			key_config,
+		}
+	}
+
+	/// starts, continues or skips in the sequence, a sequence stopped
+	/// already (e.g. before a restart) is shown instead of starting
+	/// another one
+	pub fn open(&mut self, request: CherryPickRequest) -> Result<()> {
+		if let CherryPickRequest::Start(_) = request {
+			self.empty = 0;
+			self.skipped = 0;
+
+			if let Some(stopped) = sync::cherry_pick_progress(CWD)? {
+				self.total = stopped.total;
+				self.stopped = Some(stopped);
+				return self.show();
+			}
+		}
+
+		self.show()?;
+		self.run(request);
+
+		Ok(())
+	}
+
+	///
+	pub fn any_work_pending(&self) -> bool {
+		self.job.is_pending()
+	}
+
+	///
+	pub fn update_git(&mut self, ev: AsyncGitNotification) {
+		if ev != AsyncGitNotification::CherryPick {
+			return;
+		}
+
+		if let Some(job) = self.job.take_last() {
+			if let Some(result) = job.result() {
+				self.finished(result);
+			}
+		} else {
+			self.progress = self.job.progress().unwrap_or_default();
+			self.total = self.progress.total;
+		}
+	}
+
+	fn run(&mut self, request: CherryPickRequest) {
+		if request == CherryPickRequest::Skip {
+			self.skipped += 1;
+		}
+
+		self.stopped = None;
+		self.progress = CherryPickProgress::default();
+		self.job.spawn(AsyncCherryPickJob::new(request));
+	}
+
+	fn finished(
+		&mut self,
+		result: asyncgit::Result<CherryPickResult>,
+	) {
+		match result {
+			Ok(result) => {
+				self.empty += result.empty;
+
+				if let Some(stopped) = result.conflict {
+					self.total = stopped.total;
+					self.stopped = Some(stopped);
+				} else {
+					self.hide();
+					self.queue.push(InternalEvent::ShowToast(
+						Severity::Success,
+						strings::toast_cherry_picked(
+							self.total.saturating_sub(
+								self.empty + self.skipped,
+							),
+							self.empty,
+							self.skipped,
+						),
+					));
+				}
+			}
+			Err(e) => {
+				self.hide();
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("cherry-pick error:\n{}", e),
+				));
+			}
+		}
+
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+	}
+}
@@ -1,5 +1,8 @@
+use crossterm::event::KeyEvent;
+use std::cmp::Ordering;
+
 ///
-#[derive(Clone, PartialEq, PartialOrd, Ord, Eq)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct CommandText {
 	///
 	pub name: String,
@@ -9,6 +12,41 @@ pub struct CommandText {
 	pub group: &'static str,
 	///
 	pub hide_help: bool,
+	/// runs the command where it is available, `None` for commands
+	/// of several keys (e.g. navigation)
+	pub key: Option<KeyEvent>,
+	/// changes the repo, unavailable in read-only mode
+	pub mutating: bool,
+	/// talks to a remote, disabled in offline mode
+	pub remote: bool,
+}
+
+// `KeyEvent` has no order, the key is part of the name anyway
+impl Ord for CommandText {
+	fn cmp(&self, other: &Self) -> Ordering {
+		(
+			&self.name,
+			self.desc,
+			self.group,
+			self.hide_help,
+			self.mutating,
+			self.remote,
+		)
+			.cmp(&(
+				&other.name,
+				other.desc,
+				other.group,
+				other.hide_help,
+				other.mutating,
+				other.remote,
+			))
+	}
+}
+
+impl PartialOrd for CommandText {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
 }
 
 impl CommandText {
@@ -23,6 +61,9 @@ impl CommandText {
 			desc,
 			group,
 			hide_help: false,
+			key: None,
+			mutating: false,
+			remote: false,
 		}
 	}
 	///
@@ -31,6 +72,37 @@ impl CommandText {
 		tmp.hide_help = true;
 		tmp
 	}
+	///
+	pub const fn key(self, key: KeyEvent) -> Self {
+		let mut tmp = self;
+		tmp.key = Some(key);
+		tmp
+	}
+	///
+	pub const fn mutating(self) -> Self {
+		let mut tmp = self;
+		tmp.mutating = true;
+		tmp
+	}
+	///
+	pub const fn remote(self) -> Self {
+		let mut tmp = self;
+		tmp.remote = true;
+		tmp
+	}
+
+	/// the name without its key hint
+	pub fn label(&self) -> &str {
+		self.name
+			.rfind(" [")
+			.filter(|_| self.name.ends_with(']'))
+			.map_or(&self.name, |idx| &self.name[..idx])
+	}
+
+	/// stable across key remaps, unlike the name
+	pub fn id(&self) -> String {
+		format!("{} {}", self.group, self.label())
+	}
 }
 
 ///
@@ -83,3 +155,26 @@ impl CommandInfo {
 		self.quick_bar && self.available
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_label_without_key_hint() {
+		let text = CommandText::new(
+			String::from("Stage [s]"),
+			"",
+			"-- Changes --",
+		);
+		assert_eq!(text.label(), "Stage");
+		assert_eq!(text.id(), "-- Changes -- Stage");
+
+		let text = CommandText::new(
+			String::from("Open [..] file"),
+			"",
+			"-- General --",
+		);
+		assert_eq!(text.label(), "Open [..] file");
+	}
+}
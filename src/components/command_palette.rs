@@ -0,0 +1,436 @@
+//! every command by name, runs the selected one as if its key was
+//! pressed in the current context
+
+use super::{
+	utils::string_width_align, visibility_blocking, CommandBlocking,
+	CommandInfo, CommandText, Component, DrawableComponent,
+	EventState, ScrollType, TextInputComponent,
+};
+use crate::{
+	args::get_app_cache_path,
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use fuzzy_matcher::FuzzyMatcher;
+use ron::ser::{to_string_pretty, PrettyConfig};
+use std::{cmp::Reverse, fs, path::PathBuf};
+use tui::{
+	backend::Backend,
+	layout::{Constraint, Direction, Layout, Margin, Rect},
+	text::Span,
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// commands remembered as recently used
+const MAX_RECENT: usize = 20;
+
+/// whether a palette entry can run right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PaletteState {
+	///
+	Runnable,
+	/// available here, but not with the current selection
+	Disabled,
+	/// belongs to another tab or popup
+	Unavailable,
+	/// made of several keys (e.g. navigation), nothing to run
+	KeysOnly,
+}
+
+///
+#[derive(Clone)]
+pub struct PaletteEntry {
+	///
+	pub text: CommandText,
+	///
+	pub state: PaletteState,
+}
+
+///
+pub struct CommandPalettePopup {
+	queue: Queue,
+	visible: bool,
+	find_text: TextInputComponent,
+	entries: Vec<PaletteEntry>,
+	/// indices into `entries` matching the query, in display order
+	filtered: Vec<usize>,
+	selection: usize,
+	/// ids of the commands run from here, most recent first
+	recent: Vec<String>,
+	/// enter was pressed on an entry that cannot run
+	reason_shown: bool,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl CommandPalettePopup {
+	///
+	pub fn new(
+		queue: &Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		let mut find_text = TextInputComponent::new(
+			theme.clone(),
+			key_config.clone(),
+			"",
+			&strings::command_palette_hint(),
+			false,
+		);
+		find_text.embed();
+
+		Self {
+			queue: queue.clone(),
+			visible: false,
+			find_text,
+			entries: Vec::new(),
+			filtered: Vec::new(),
+			selection: 0,
+			recent: load_recent(),
+			reason_shown: false,
+			theme,
+			key_config,
+		}
+	}
+
+	///
+	pub fn open(&mut self, entries: Vec<PaletteEntry>) -> Result<()> {
+		self.show()?;
+		self.find_text.show()?;
+		self.find_text.set_text(String::new());
+		self.entries = entries;
+		self.update_filter();
+
+		Ok(())
+	}
+
+	fn update_filter(&mut self) {
+		let query = self.find_text.get_text();
+		let matcher = fuzzy_matcher::skim::SkimMatcherV2::default();
+
+		let mut hits: Vec<(usize, i64)> = self
+			.entries
+			.iter()
+			.enumerate()
+			.filter_map(|(idx, entry)| {
+				if query.is_empty() {
+					return Some((idx, 0));
+				}
+
+				matcher
+					.fuzzy_match(entry.text.label(), query)
+					.or_else(|| {
+						matcher
+							.fuzzy_match(entry.text.desc, query)
+							// the name matters more than the desc
+							.map(|score| score / 2)
+					})
+					.map(|score| (idx, score))
+			})
+			.collect();
+
+		let entries = &self.entries;
+		let recent = &self.recent;
+		hits.sort_by_key(|(idx, score)| {
+			let entry = &entries[*idx];
+			let id = entry.text.id();
+			(
+				recent
+					.iter()
+					.position(|recent| *recent == id)
+					.unwrap_or(MAX_RECENT),
+				Reverse(*score),
+				entry.state,
+			)
+		});
+
+		self.filtered =
+			hits.into_iter().map(|(idx, _)| idx).collect();
+		self.selection = 0;
+		self.reason_shown = false;
+	}
+
+	fn selected_entry(&self) -> Option<&PaletteEntry> {
+		self.filtered
+			.get(self.selection)
+			.and_then(|idx| self.entries.get(*idx))
+	}
+
+	fn move_selection(&mut self, move_type: ScrollType) {
+		let new_selection = match move_type {
+			ScrollType::Up => self.selection.saturating_sub(1),
+			ScrollType::Down => self.selection.saturating_add(1),
+			_ => self.selection,
+		}
+		.min(self.filtered.len().saturating_sub(1));
+
+		if new_selection != self.selection {
+			self.selection = new_selection;
+			self.reason_shown = false;
+		}
+	}
+
+	fn run_selected(&mut self) {
+		let entry = match self.selected_entry() {
+			Some(entry) => entry.clone(),
+			None => return,
+		};
+
+		if entry.state != PaletteState::Runnable {
+			self.reason_shown = true;
+			return;
+		}
+
+		let id = entry.text.id();
+		self.recent.retain(|recent| *recent != id);
+		self.recent.insert(0, id);
+		self.recent.truncate(MAX_RECENT);
+		if let Err(e) = save_recent(&self.recent) {
+			log::error!("save recent commands: {}", e);
+		}
+
+		self.hide();
+		self.queue.push(InternalEvent::RunCommand(entry.text));
+	}
+
+	fn footer(&self) -> (String, bool) {
+		self.selected_entry().map_or_else(
+			|| (strings::command_palette_no_hits(), false),
+			|entry| match entry.state {
+				PaletteState::Runnable => {
+					(entry.text.desc.to_string(), false)
+				}
+				state => (
+					strings::command_palette_reason(
+						state,
+						entry.text.group,
+					),
+					self.reason_shown,
+				),
+			},
+		)
+	}
+
+	fn draw_list<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+		let height = usize::from(area.height.saturating_sub(1));
+		let width = usize::from(area.width);
+		let name_width = (width / 2).max(1);
+
+		// keeps the selection in view
+		let scroll = (self.selection + 1).saturating_sub(height);
+
+		let items = self
+			.filtered
+			.iter()
+			.enumerate()
+			.skip(scroll)
+			.take(height)
+			.map(|(pos, idx)| {
+				let entry = &self.entries[*idx];
+				let line = format!(
+					"{} {}",
+					string_width_align(&entry.text.name, name_width),
+					entry.text.desc
+				);
+				Span::styled(
+					string_width_align(&line, width),
+					self.theme.text(
+						entry.state == PaletteState::Runnable,
+						pos == self.selection,
+					),
+				)
+			});
+
+		ui::draw_list_block(
+			f,
+			area,
+			Block::default()
+				.title(Span::styled(
+					strings::command_palette_hits(
+						self.filtered.len(),
+					),
+					self.theme.title(true),
+				))
+				.borders(Borders::TOP),
+			items,
+		);
+	}
+}
+
+impl DrawableComponent for CommandPalettePopup {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		if self.is_visible() {
+			const SIZE: (u16, u16) = (80, 22);
+
+			let area =
+				ui::centered_rect_absolute(SIZE.0, SIZE.1, rect);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Block::default()
+					.borders(Borders::all())
+					.style(self.theme.title(true))
+					.title(Span::styled(
+						strings::title_command_palette(),
+						self.theme.title(true),
+					)),
+				area,
+			);
+
+			let chunks = Layout::default()
+				.direction(Direction::Vertical)
+				.constraints(
+					[
+						Constraint::Length(1),
+						Constraint::Min(2),
+						Constraint::Length(1),
+					]
+					.as_ref(),
+				)
+				.split(area.inner(&Margin {
+					horizontal: 1,
+					vertical: 1,
+				}));
+
+			self.find_text.draw(f, chunks[0])?;
+			self.draw_list(f, chunks[1]);
+
+			let (footer, warn) = self.footer();
+			f.render_widget(
+				Paragraph::new(Span::styled(
+					footer,
+					if warn {
+						self.theme.text_warning()
+					} else {
+						self.theme.text(false, false)
+					},
+				)),
+				chunks[2],
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for CommandPalettePopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				true,
+				true,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::command_palette_run(
+					&self.key_config,
+				),
+				self.selected_entry().map_or(false, |entry| {
+					entry.state == PaletteState::Runnable
+				}),
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = &event {
+				if *key == self.key_config.exit_popup {
+					self.hide();
+					return Ok(EventState::Consumed);
+				} else if *key == self.key_config.enter {
+					self.run_selected();
+					return Ok(EventState::Consumed);
+				} else if *key == self.key_config.move_down {
+					self.move_selection(ScrollType::Down);
+					return Ok(EventState::Consumed);
+				} else if *key == self.key_config.move_up {
+					self.move_selection(ScrollType::Up);
+					return Ok(EventState::Consumed);
+				}
+			}
+
+			if self.find_text.event(event)?.is_consumed() {
+				self.update_filter();
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn paste(&mut self, text: &str) -> Result<EventState> {
+		if self.is_visible()
+			&& self.find_text.paste(text)?.is_consumed()
+		{
+			self.update_filter();
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+		Ok(())
+	}
+
+	fn on_resize(&self) {
+		self.find_text.on_resize();
+	}
+}
+
+fn recent_file() -> Result<PathBuf> {
+	Ok(get_app_cache_path()?.join("recent_commands.ron"))
+}
+
+fn load_recent() -> Vec<String> {
+	recent_file()
+		.ok()
+		.filter(|file| file.exists())
+		.and_then(|file| fs::read(file).ok())
+		.and_then(|data| ron::de::from_bytes(&data).ok())
+		.unwrap_or_default()
+}
+
+fn save_recent(recent: &[String]) -> Result<()> {
+	let data = to_string_pretty(&recent, PrettyConfig::default())?;
+	fs::write(recent_file()?, data)?;
+
+	Ok(())
+}
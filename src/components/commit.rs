@@ -1,22 +1,31 @@
 use super::{
-	textinput::TextInputComponent, visibility_blocking,
-	CommandBlocking, CommandInfo, Component, DrawableComponent,
-	EventState, ExternalEditorComponent,
+	textinput::{InputType, TextInputComponent},
+	utils::date_range::parse_date_time,
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState, ExternalEditorComponent,
+	HookOutputComponent, SharedOptions,
 };
 use crate::{
+	commit_lint::LintProblem,
 	keys::SharedKeyConfig,
-	queue::{InternalEvent, NeedsUpdate, Queue},
+	queue::{Action, InternalEvent, Queue},
+	refresh::RefreshDomains,
 	strings, try_or_popup,
 	ui::style::SharedTheme,
 };
 use anyhow::Result;
 use asyncgit::{
+	asyncjob::AsyncSingleJob,
 	cached,
+	commit_hooks::AsyncCommitHooksJob,
 	sync::{
-		self, get_config_string, CommitId, HookResult, RepoState,
+		self, get_config_string, status::StatusType, AmendDates,
+		CommitId, HookResult, RepoState,
 	},
-	CWD,
+	AsyncGitNotification, CWD,
 };
+use chrono::Local;
+use crossbeam_channel::Sender;
 use crossterm::event::Event;
 use easy_cast::Cast;
 use std::{
@@ -38,12 +47,33 @@ enum Mode {
 
 pub struct CommitComponent {
 	input: TextInputComponent,
+	hooks: HookOutputComponent,
+	git_hooks: AsyncSingleJob<AsyncCommitHooksJob>,
 	mode: Mode,
 	queue: Queue,
 	key_config: SharedKeyConfig,
 	git_branch_name: cached::BranchName,
-	commit_template: Option<String>,
+	/// what the empty message was filled with when opened
+	/// (branch prefill and `commit.template`)
+	initial_message: Option<String>,
 	theme: SharedTheme,
+	options: SharedOptions,
+	/// subject whose lint problems were confirmed by a first enter
+	lint_acknowledged: Option<String>,
+	/// setup is checked on the first commit attempt only
+	preflight_done: bool,
+	/// commits the branch is behind its upstream, as last compared
+	upstream_behind: usize,
+	/// `remote/branch` the current branch tracks
+	upstream_name: Option<String>,
+	/// the index matched `HEAD` when opened
+	nothing_staged: bool,
+	/// an empty commit was confirmed
+	allow_empty: bool,
+	/// dates of the amended commit
+	amend_dates: AmendDates,
+	/// asks for an explicit date to amend with
+	date_input: TextInputComponent,
 }
 
 const FIRST_LINE_LIMIT: usize = 50;
@@ -52,12 +82,20 @@ impl CommitComponent {
 	///
 	pub fn new(
 		queue: Queue,
+		sender: &Sender<AsyncGitNotification>,
 		theme: SharedTheme,
 		key_config: SharedKeyConfig,
+		options: SharedOptions,
 	) -> Self {
 		Self {
 			queue,
 			mode: Mode::Normal,
+			hooks: HookOutputComponent::new(
+				theme.clone(),
+				key_config.clone(),
+				false,
+			),
+			git_hooks: AsyncSingleJob::new(sender.clone()),
 
 			input: TextInputComponent::new(
 				theme.clone(),
@@ -66,16 +104,74 @@ impl CommitComponent {
 				&strings::commit_msg(&key_config),
 				true,
 			),
+			date_input: TextInputComponent::new(
+				theme.clone(),
+				key_config.clone(),
+				&strings::commit_amend_date_title(),
+				&strings::commit_amend_date_msg(),
+				false,
+			)
+			.with_input_type(InputType::Singleline),
 			key_config,
 			git_branch_name: cached::BranchName::new(CWD),
-			commit_template: None,
+			initial_message: None,
 			theme,
+			options,
+			lint_acknowledged: None,
+			preflight_done: false,
+			upstream_behind: 0,
+			upstream_name: None,
+			nothing_staged: false,
+			allow_empty: false,
+			amend_dates: AmendDates::Default,
 		}
 	}
 
 	///
 	pub fn update(&mut self) {
 		self.git_branch_name.lookup().ok();
+		self.update_upstream_name();
+	}
+
+	/// takes the ahead/behind the status tab compared last, which
+	/// is refreshed by (auto) fetches
+	pub fn set_upstream_behind(&mut self, behind: Option<usize>) {
+		self.upstream_behind = behind.unwrap_or_default();
+	}
+
+	fn update_upstream_name(&mut self) {
+		self.upstream_name =
+			self.git_branch_name.last().and_then(|branch| {
+				let remote =
+					sync::get_upstream_remote(CWD, &branch).ok()?;
+				let upstream =
+					sync::get_upstream_branch(CWD, &branch)
+						.ok()??;
+				Some(format!("{}/{}", remote, upstream))
+			});
+	}
+
+	///
+	pub fn update_git(
+		&mut self,
+		ev: AsyncGitNotification,
+	) -> Result<()> {
+		if ev == AsyncGitNotification::CommitHooks {
+			if let Some(job) = self.git_hooks.take_last() {
+				if let Some(result) = job.result() {
+					self.hooks_finished(result)?;
+				}
+			} else {
+				self.hooks.update();
+			}
+		}
+
+		Ok(())
+	}
+
+	///
+	pub fn any_work_pending(&self) -> bool {
+		self.git_hooks.is_pending()
 	}
 
 	fn draw_branch_name<B: Backend>(&self, f: &mut Frame<B>) {
@@ -94,6 +190,95 @@ impl CommitComponent {
 		}
 	}
 
+	/// protected branch and lint warnings
+	fn draw_subject_warnings<B: Backend>(&self, f: &mut Frame<B>) {
+		let mut warnings = Vec::new();
+
+		if let Some(name) = self.git_branch_name.last() {
+			if self.options.borrow().is_protected_branch(&name) {
+				warnings.push(
+					strings::commit_protected_branch_warning(&name),
+				);
+			}
+		}
+
+		let problems = self.lint_problems();
+		if !problems.is_empty() {
+			warnings.push(strings::commit_lint_warning(
+				&problems,
+				self.is_lint_acknowledged(),
+			));
+		}
+
+		if warnings.is_empty() {
+			return;
+		}
+
+		let msg = warnings.join(" ");
+		let msg_length: u16 = msg.chars().count().cast();
+		let w = Paragraph::new(msg).style(self.theme.text_warning());
+
+		let rect = {
+			let mut rect = self.input.get_area();
+			rect.y += rect.height.saturating_sub(1);
+			rect.height = 1;
+			rect.x += 1;
+			rect.width = rect.width.saturating_sub(2).min(msg_length);
+			rect
+		};
+
+		f.render_widget(w, rect);
+	}
+
+	/// shown at the bottom of the popup, committing is not held up
+	/// by them
+	fn notices(&self) -> Vec<String> {
+		let mut notices = Vec::new();
+
+		if self.is_nothing_staged() {
+			notices.push(strings::commit_nothing_staged_notice(
+				&self.key_config,
+			));
+		}
+
+		if matches!(self.mode, Mode::Amend(_)) {
+			notices.push(strings::commit_amend_dates_notice(
+				self.amend_dates,
+				&self.key_config,
+			));
+		}
+
+		if let Some(upstream) = &self.upstream_name {
+			if self.upstream_behind > 0 {
+				notices.push(strings::commit_behind_upstream_notice(
+					self.upstream_behind,
+					upstream,
+				));
+			}
+		}
+
+		notices
+	}
+
+	/// into the footer the input kept free for them
+	fn draw_notices<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		notices: Vec<String>,
+	) {
+		let area = self.input.get_footer_area();
+		for (idx, notice) in
+			notices.into_iter().take(area.height.into()).enumerate()
+		{
+			let offset: u16 = idx.cast();
+			f.render_widget(
+				Paragraph::new(notice)
+					.style(self.theme.text_warning()),
+				Rect::new(area.x, area.y + offset, area.width, 1),
+			);
+		}
+	}
+
 	fn draw_warnings<B: Backend>(&self, f: &mut Frame<B>) {
 		let first_line = self
 			.input
@@ -179,36 +364,48 @@ impl CommitComponent {
 			anyhow::bail!("config commit.gpgsign=true detected.\ngpg signing not supported.\ndeactivate in your repo/gitconfig to be able to commit without signing.");
 		}
 
-		let msg = self.input.get_text().to_string();
-		self.input.clear();
-		self.commit_with_msg(msg)
+		let msg = sync::apply_trailers(
+			self.input.get_text(),
+			&self.options.borrow().commit_trailers,
+		);
+
+		// `pre-commit` and `commit-msg` run in the background,
+		// the commit itself happens once they succeeded
+		let job = AsyncCommitHooksJob::new(msg);
+		self.hooks.start(job.clone());
+		self.git_hooks.spawn(job);
+
+		Ok(())
 	}
 
-	fn commit_with_msg(&mut self, msg: String) -> Result<()> {
-		if let HookResult::NotOk(e) = sync::hooks_pre_commit(CWD)? {
-			log::error!("pre-commit hook error: {}", e);
-			self.queue.push(InternalEvent::ShowErrorMsg(format!(
-				"pre-commit hook error:\n{}",
-				e
-			)));
-			return Ok(());
-		}
-		let mut msg = msg;
-		if let HookResult::NotOk(e) =
-			sync::hooks_commit_msg(CWD, &mut msg)?
-		{
-			log::error!("commit-msg hook error: {}", e);
-			self.queue.push(InternalEvent::ShowErrorMsg(format!(
-				"commit-msg hook error:\n{}",
-				e
-			)));
-			return Ok(());
+	fn hooks_finished(
+		&mut self,
+		result: asyncgit::Result<(HookResult, String)>,
+	) -> Result<()> {
+		match result {
+			Ok((HookResult::Ok, msg)) => {
+				self.hooks.hide();
+				self.commit_with_msg(&msg)
+			}
+			Ok((HookResult::NotOk(e), _)) => {
+				log::error!("commit hook error: {}", e);
+				self.hooks.fail();
+				Ok(())
+			}
+			Err(e) => {
+				self.hooks.hide();
+				Err(e.into())
+			}
 		}
+	}
 
+	fn commit_with_msg(&mut self, msg: &str) -> Result<()> {
 		let res = match &self.mode {
-			Mode::Normal => sync::commit(CWD, &msg),
-			Mode::Amend(amend) => sync::amend(CWD, *amend, &msg),
-			Mode::Merge(ids) => sync::merge_commit(CWD, &msg, ids),
+			Mode::Normal => sync::commit(CWD, msg, self.allow_empty),
+			Mode::Amend(amend) => {
+				sync::amend(CWD, *amend, msg, self.amend_dates)
+			}
+			Mode::Merge(ids) => sync::merge_commit(CWD, msg, ids),
 		};
 
 		if let Err(e) = res {
@@ -228,13 +425,139 @@ impl CommitComponent {
 			)));
 		}
 
+		self.input.clear();
+		self.lint_acknowledged = None;
+		self.allow_empty = false;
 		self.hide();
 
-		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+		// the stash list is the same, the rest keeps its place
+		self.queue.push(InternalEvent::RefreshInPlace(
+			RefreshDomains::STATUS
+				| RefreshDomains::LOG
+				| RefreshDomains::BRANCHES
+				| RefreshDomains::DIFF,
+		));
 
 		Ok(())
 	}
 
+	fn subject(&self) -> &str {
+		self.input.get_text().lines().next().unwrap_or_default()
+	}
+
+	/// merge commits keep their generated subject
+	fn lint_problems(&self) -> Vec<LintProblem> {
+		if matches!(self.mode, Mode::Merge(_)) {
+			return Vec::new();
+		}
+
+		self.options.borrow().lint_commit_subject(self.subject())
+	}
+
+	fn is_lint_acknowledged(&self) -> bool {
+		self.lint_acknowledged.as_deref() == Some(self.subject())
+	}
+
+	/// a subject with lint problems needs a second enter
+	fn confirm_commit(&mut self) -> Result<()> {
+		if !self.is_lint_acknowledged()
+			&& !self.lint_problems().is_empty()
+		{
+			self.lint_acknowledged = Some(self.subject().to_string());
+			return Ok(());
+		}
+
+		if self.is_nothing_staged() && !self.allow_empty {
+			self.queue.push(InternalEvent::ConfirmAction(
+				Action::CommitEmpty,
+			));
+			return Ok(());
+		}
+
+		if !self.preflight_done {
+			self.preflight_done = true;
+
+			let issues = sync::preflight_check(
+				CWD,
+				&ExternalEditorComponent::editor_program()?,
+			)?;
+			if !issues.is_empty() {
+				self.queue
+					.push(InternalEvent::OpenPreflight(Some(issues)));
+				return Ok(());
+			}
+		}
+
+		if self.confirm_large_files() {
+			return Ok(());
+		}
+
+		self.commit()
+	}
+
+	/// `true` if committing waits for the user to confirm staged
+	/// files that are new or grew past the large file threshold
+	fn confirm_large_files(&self) -> bool {
+		let threshold =
+			match self.options.borrow().large_file_threshold() {
+				Some(threshold) => threshold,
+				None => return false,
+			};
+
+		match sync::staged_large_files(CWD, threshold) {
+			Ok(files) if !files.is_empty() => {
+				self.queue.push(InternalEvent::ConfirmAction(
+					Action::CommitLargeFiles(files),
+				));
+				true
+			}
+			Ok(_) => false,
+			Err(e) => {
+				log::error!("large files check error: {}", e);
+				false
+			}
+		}
+	}
+
+	/// commits what was confirmed before the preflight check got in
+	/// the way, unless the popup was closed since
+	pub fn resume(&mut self) -> Result<()> {
+		if self.is_visible()
+			&& self.can_commit()
+			&& !self.confirm_large_files()
+		{
+			self.commit()?;
+		}
+
+		Ok(())
+	}
+
+	/// commits once the large files were confirmed, unless the popup
+	/// was closed since
+	pub fn commit_large_files(&mut self) -> Result<()> {
+		if self.is_visible() && self.can_commit() {
+			self.commit()?;
+		}
+
+		Ok(())
+	}
+
+	/// commits nothing once confirmed, unless the popup was closed
+	/// since
+	pub fn commit_empty(&mut self) -> Result<()> {
+		if self.is_visible() && self.can_commit() {
+			self.allow_empty = true;
+			self.confirm_commit()?;
+		}
+
+		Ok(())
+	}
+
+	/// amending with nothing newly staged is fine
+	const fn is_nothing_staged(&self) -> bool {
+		self.nothing_staged && matches!(self.mode, Mode::Normal)
+	}
+
 	fn can_commit(&self) -> bool {
 		!self.is_empty() && self.is_changed()
 	}
@@ -251,7 +574,69 @@ impl CommitComponent {
 
 	fn is_changed(&self) -> bool {
 		Some(self.input.get_text().trim())
-			!= self.commit_template.as_ref().map(|s| s.trim())
+			!= self.initial_message.as_ref().map(|s| s.trim())
+	}
+
+	fn signoff(&mut self) -> Result<()> {
+		let trailer = sync::signoff_trailer(CWD)?;
+		let msg =
+			sync::apply_trailers(self.input.get_text(), &[trailer]);
+		self.input.set_text(msg);
+
+		Ok(())
+	}
+
+	/// default, keep committer date, author date now and then asks
+	/// for an explicit date
+	fn cycle_amend_dates(&mut self) -> Result<()> {
+		self.amend_dates = match self.amend_dates {
+			AmendDates::Default => AmendDates::KeepCommitterDate,
+			AmendDates::KeepCommitterDate => {
+				AmendDates::AuthorDateNow
+			}
+			AmendDates::AuthorDateNow => {
+				self.date_input.clear();
+				self.date_input.show()?;
+				AmendDates::AuthorDateNow
+			}
+			AmendDates::Explicit { .. } => AmendDates::Default,
+		};
+
+		Ok(())
+	}
+
+	fn confirm_amend_date(&mut self) {
+		let input = self.date_input.get_text();
+
+		if let Some(time) = parse_date_time(input, Local::now()) {
+			self.amend_dates = AmendDates::Explicit {
+				time: time.timestamp(),
+				offset: time.offset().local_minus_utc() / 60,
+			};
+			self.date_input.hide();
+		} else {
+			self.queue.push(InternalEvent::ShowErrorMsg(
+				strings::commit_amend_date_invalid(input),
+			));
+		}
+	}
+
+	fn date_input_event(&mut self, ev: Event) -> Result<()> {
+		if let Event::Key(e) = ev {
+			if e == self.key_config.enter {
+				self.confirm_amend_date();
+				return Ok(());
+			}
+		}
+
+		self.date_input.event(ev)?;
+
+		// closed without a date
+		if !self.date_input.is_visible() {
+			self.amend_dates = AmendDates::Default;
+		}
+
+		Ok(())
 	}
 
 	fn amend(&mut self) -> Result<()> {
@@ -279,9 +664,16 @@ impl DrawableComponent for CommitComponent {
 		rect: Rect,
 	) -> Result<()> {
 		if self.is_visible() {
+			let notices = self.notices();
+			self.input.set_footer(notices.len().cast());
+
 			self.input.draw(f, rect)?;
 			self.draw_branch_name(f);
+			self.draw_subject_warnings(f);
 			self.draw_warnings(f);
+			self.draw_notices(f, notices);
+			self.date_input.draw(f, rect)?;
+			self.hooks.draw(f, rect)?;
 		}
 
 		Ok(())
@@ -294,6 +686,15 @@ impl Component for CommitComponent {
 		out: &mut Vec<CommandInfo>,
 		force_all: bool,
 	) -> CommandBlocking {
+		if self.hooks.is_visible() {
+			return self.hooks.commands(out, force_all);
+		}
+
+		if self.date_input.is_visible() {
+			self.date_input.commands(out, force_all);
+			return visibility_blocking(self);
+		}
+
 		self.input.commands(out, force_all);
 
 		if self.is_visible() || force_all {
@@ -309,6 +710,28 @@ impl Component for CommitComponent {
 				true,
 			));
 
+			out.push(CommandInfo::new(
+				strings::commands::commit_amend_dates(
+					&self.key_config,
+				),
+				true,
+				matches!(self.mode, Mode::Amend(_)) || force_all,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::commit_signoff(&self.key_config),
+				true,
+				true,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::commit_goto_changes(
+					&self.key_config,
+				),
+				true,
+				self.is_nothing_staged() || force_all,
+			));
+
 			out.push(CommandInfo::new(
 				strings::commands::commit_open_editor(
 					&self.key_config,
@@ -323,6 +746,15 @@ impl Component for CommitComponent {
 
 	fn event(&mut self, ev: Event) -> Result<EventState> {
 		if self.is_visible() {
+			if self.hooks.event(ev)?.is_consumed() {
+				return Ok(EventState::Consumed);
+			}
+
+			if self.date_input.is_visible() {
+				self.date_input_event(ev)?;
+				return Ok(EventState::Consumed);
+			}
+
 			if self.input.event(ev)?.is_consumed() {
 				return Ok(EventState::Consumed);
 			}
@@ -332,12 +764,27 @@ impl Component for CommitComponent {
 					try_or_popup!(
 						self,
 						"commit error:",
-						self.commit()
+						self.confirm_commit()
 					);
 				} else if e == self.key_config.commit_amend
 					&& self.can_amend()
 				{
 					self.amend()?;
+				} else if e == self.key_config.commit_amend_dates
+					&& matches!(self.mode, Mode::Amend(_))
+				{
+					self.cycle_amend_dates()?;
+				} else if e == self.key_config.commit_signoff {
+					try_or_popup!(
+						self,
+						"sign-off error:",
+						self.signoff()
+					);
+				} else if e == self.key_config.commit_goto_changes
+					&& self.is_nothing_staged()
+				{
+					self.hide();
+					self.queue.push(InternalEvent::FocusWorkdir);
 				} else if e == self.key_config.open_commit_editor {
 					self.queue.push(
 						InternalEvent::OpenExternalEditor(None),
@@ -353,40 +800,81 @@ impl Component for CommitComponent {
 		Ok(EventState::NotConsumed)
 	}
 
+	fn paste(&mut self, text: &str) -> Result<EventState> {
+		self.input.paste(text)
+	}
+
 	fn is_visible(&self) -> bool {
 		self.input.is_visible()
 	}
 
 	fn hide(&mut self) {
+		self.date_input.hide();
 		self.input.hide();
 	}
 
 	fn show(&mut self) -> Result<()> {
+		let repo_state = sync::repo_state(CWD)?;
+		// those are concluded by continuing them from the status tab
+		if repo_state.is_pending() && repo_state != RepoState::Merge {
+			self.queue.push(InternalEvent::ShowErrorMsg(
+				strings::repo_state_blocks(repo_state, "commit"),
+			));
+			return Ok(());
+		}
+
 		//only clear text if it was not a normal commit dlg before, so to preserve old commit msg that was edited
 		if !matches!(self.mode, Mode::Normal) {
 			self.input.clear();
 		}
 
 		self.mode = Mode::Normal;
+		self.allow_empty = false;
+		self.amend_dates = AmendDates::Default;
+		self.date_input.hide();
+		self.update();
 
-		self.mode = if sync::repo_state(CWD)? == RepoState::Merge {
+		self.mode = if repo_state == RepoState::Merge {
 			let ids = sync::mergehead_ids(CWD)?;
 			self.input.set_title(strings::commit_title_merge());
 			self.input.set_text(sync::merge_msg(CWD)?);
 			Mode::Merge(ids)
 		} else {
-			self.commit_template =
-				get_config_string(CWD, "commit.template")
-					.ok()
-					.flatten()
-					.and_then(|path| read_to_string(path).ok());
-
 			if self.is_empty() {
-				if let Some(s) = &self.commit_template {
-					self.input.set_text(s.clone());
+				let template =
+					get_config_string(CWD, "commit.template")
+						.ok()
+						.flatten()
+						.and_then(|path| read_to_string(path).ok());
+				let prefill = self
+					.git_branch_name
+					.lookup()
+					.ok()
+					.and_then(|branch| {
+						self.options.borrow().commit_prefill(&branch)
+					});
+
+				self.initial_message = match (prefill, template) {
+					(None, None) => None,
+					(prefill, template) => Some(format!(
+						"{}{}",
+						prefill.unwrap_or_default(),
+						template.unwrap_or_default()
+					)),
+				};
+
+				if let Some(msg) = &self.initial_message {
+					self.input.set_text(msg.clone());
 				}
 			}
 
+			self.nothing_staged = sync::status::get_status(
+				CWD,
+				StatusType::Stage,
+				None,
+			)?
+			.is_empty();
+
 			self.input.set_title(strings::commit_title());
 			Mode::Normal
 		};
@@ -395,4 +883,88 @@ impl Component for CommitComponent {
 
 		Ok(())
 	}
+
+	fn on_resize(&self) {
+		self.input.on_resize();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crossbeam_channel::unbounded;
+	use tui::{backend::TestBackend, Terminal};
+
+	fn component() -> CommitComponent {
+		let (tx, _rx) = unbounded();
+		let mut commit = CommitComponent::new(
+			Queue::new(),
+			&tx,
+			SharedTheme::default(),
+			SharedKeyConfig::default(),
+			SharedOptions::default(),
+		);
+		commit.upstream_name = Some(String::from("origin/master"));
+		commit
+	}
+
+	/// row of the drawn notice, if any
+	fn notice_row(commit: &CommitComponent) -> Option<u16> {
+		let mut terminal =
+			Terminal::new(TestBackend::new(80, 30)).unwrap();
+		terminal
+			.draw(|f| commit.draw(f, f.size()).unwrap())
+			.unwrap();
+
+		let buffer = terminal.backend().buffer();
+		(0..buffer.area.height).find(|&y| {
+			(0..buffer.area.width)
+				.map(|x| buffer.get(x, y).symbol.as_str())
+				.collect::<String>()
+				.contains("behind origin/master")
+		})
+	}
+
+	#[test]
+	fn test_behind_upstream_notice() {
+		let mut commit = component();
+		assert!(commit.notices().is_empty());
+
+		commit.set_upstream_behind(Some(2));
+		assert_eq!(
+			commit.notices(),
+			vec![strings::commit_behind_upstream_notice(
+				2,
+				"origin/master"
+			)]
+		);
+	}
+
+	#[test]
+	fn test_notice_updates_after_fetch() {
+		let mut commit = component();
+		commit.set_upstream_behind(Some(1));
+		assert_eq!(commit.notices().len(), 1);
+
+		// fetching and pulling reports the new distance
+		commit.set_upstream_behind(Some(0));
+		assert!(commit.notices().is_empty());
+
+		commit.set_upstream_behind(None);
+		assert!(commit.notices().is_empty());
+	}
+
+	#[test]
+	fn test_notice_drawn_inside_popup() {
+		let mut commit = component();
+		commit.input.show().unwrap();
+		commit.set_upstream_behind(Some(3));
+
+		let row = notice_row(&commit).unwrap();
+		let area = commit.input.get_area();
+		assert!(row > area.y && row < area.y + area.height - 1);
+
+		commit.set_upstream_behind(Some(0));
+		assert_eq!(notice_row(&commit), None);
+	}
 }
@@ -40,20 +40,13 @@ impl CompareDetailsComponent {
 		}
 	}
 
+	/// `ids` are (old, new)
 	pub fn set_commits(&mut self, ids: Option<(CommitId, CommitId)>) {
 		self.data = ids.and_then(|ids| {
 			let c1 = sync::get_commit_details(CWD, ids.0).ok();
 			let c2 = sync::get_commit_details(CWD, ids.1).ok();
 
-			c1.and_then(|c1| {
-				c2.map(|c2| {
-					if c1.author.time < c2.author.time {
-						(c1, c2)
-					} else {
-						(c2, c1)
-					}
-				})
-			})
+			c1.and_then(|c1| c2.map(|c2| (c1, c2)))
 		});
 	}
 
@@ -4,11 +4,16 @@ use crate::{
 		dialog_paragraph,
 		utils::{scroll_vertical::VerticalScroll, time_to_string},
 		CommandBlocking, CommandInfo, Component, DrawableComponent,
-		EventState, ScrollType,
+		EventState, ScrollType, SharedOptions,
 	},
+	file_manager,
 	keys::SharedKeyConfig,
+	message_markup::{
+		self, IssueReference, Link, MarkupKind, MessageMarkup,
+	},
+	queue::{InternalEvent, Queue},
 	strings::{self, order},
-	ui::style::SharedTheme,
+	ui::{self, style::SharedTheme},
 };
 use anyhow::Result;
 use asyncgit::{
@@ -32,36 +37,54 @@ use super::style::Detail;
 pub struct DetailsComponent {
 	data: Option<CommitDetails>,
 	tags: Vec<String>,
+	/// `git notes` of the commit
+	note: Option<String>,
+	/// link of the message opened on enter
+	selected_link: Option<usize>,
 	theme: SharedTheme,
 	focused: bool,
 	current_width: Cell<u16>,
+	current_height: Cell<u16>,
 	scroll: VerticalScroll,
 	scroll_to_bottom_next_draw: Cell<bool>,
+	queue: Queue,
 	key_config: SharedKeyConfig,
+	options: SharedOptions,
 }
 
-type WrappedCommitMessage<'a> =
-	(Vec<Cow<'a, str>>, Vec<Cow<'a, str>>);
+type WrappedCommitMessage<'a> = (Vec<Cow<'a, str>>, MessageMarkup);
 
 impl DetailsComponent {
 	///
-	pub const fn new(
+	pub fn new(
+		queue: &Queue,
 		theme: SharedTheme,
 		key_config: SharedKeyConfig,
+		options: SharedOptions,
 		focused: bool,
 	) -> Self {
 		Self {
 			data: None,
 			tags: Vec::new(),
+			note: None,
+			selected_link: None,
 			theme,
 			focused,
 			scroll_to_bottom_next_draw: Cell::new(false),
 			current_width: Cell::new(0),
+			current_height: Cell::new(0),
 			scroll: VerticalScroll::new(),
+			queue: queue.clone(),
 			key_config,
+			options,
 		}
 	}
 
+	/// the shown commit has more than one parent
+	pub fn is_merge(&self) -> bool {
+		self.data.as_ref().map_or(false, |data| data.is_merge)
+	}
+
 	pub fn set_commit(
 		&mut self,
 		id: Option<CommitId>,
@@ -71,51 +94,141 @@ impl DetailsComponent {
 
 		self.data =
 			id.and_then(|id| sync::get_commit_details(CWD, id).ok());
+		self.note = id.and_then(|id| {
+			sync::get_commit_note(CWD, id, None).ok().flatten()
+		});
 
 		self.scroll.reset();
+		self.selected_link = None;
 
 		if let Some(tags) = tags {
 			self.tags.extend(tags);
 		}
 	}
 
-	fn wrap_commit_details(
-		message: &CommitMessage,
+	fn wrap_commit_details<'a>(
+		message: &'a CommitMessage,
 		width: usize,
-	) -> WrappedCommitMessage<'_> {
+		references: &[IssueReference],
+	) -> WrappedCommitMessage<'a> {
 		let wrapped_title = textwrap::wrap(&message.subject, width);
 
 		if let Some(ref body) = message.body {
-			let wrapped_message: Vec<Cow<'_, str>> =
-				textwrap::wrap(body, width).into_iter().collect();
-
-			(wrapped_title, wrapped_message)
+			(
+				wrapped_title,
+				message_markup::wrap_body(body, width, references),
+			)
 		} else {
-			(wrapped_title, vec![])
+			(wrapped_title, MessageMarkup::default())
 		}
 	}
 
-	fn get_wrapped_lines(
-		data: &Option<CommitDetails>,
+	fn get_wrapped_lines<'a>(
+		data: &'a Option<CommitDetails>,
 		width: usize,
-	) -> WrappedCommitMessage<'_> {
+		references: &[IssueReference],
+	) -> WrappedCommitMessage<'a> {
 		if let Some(ref data) = data {
 			if let Some(ref message) = data.message {
-				return Self::wrap_commit_details(message, width);
+				return Self::wrap_commit_details(
+					message, width, references,
+				);
 			}
 		}
 
-		(vec![], vec![])
+		(vec![], MessageMarkup::default())
+	}
+
+	/// the notes section below the message, empty without a note
+	fn get_wrapped_note(
+		note: &Option<String>,
+		width: usize,
+	) -> Vec<Cow<'_, str>> {
+		note.as_ref().map_or_else(Vec::new, |note| {
+			let mut lines = vec![
+				Cow::from(""),
+				Cow::from(strings::commit::details_notes_title()),
+			];
+			lines.extend(textwrap::wrap(note.trim_end(), width));
+			lines
+		})
 	}
 
 	fn get_number_of_lines(
 		details: &Option<CommitDetails>,
+		note: &Option<String>,
 		width: usize,
+		references: &[IssueReference],
 	) -> usize {
 		let (wrapped_title, wrapped_message) =
-			Self::get_wrapped_lines(details, width);
+			Self::get_wrapped_lines(details, width, references);
+
+		wrapped_title.len()
+			+ wrapped_message.lines.len()
+			+ Self::get_wrapped_note(note, width).len()
+	}
+
+	fn number_of_lines(&self) -> usize {
+		Self::get_number_of_lines(
+			&self.data,
+			&self.note,
+			usize::from(self.current_width.get()),
+			self.options.borrow().issue_references(),
+		)
+	}
+
+	/// links of the message and the number of title lines above it
+	fn links(&self) -> (Vec<Link>, usize) {
+		let (wrapped_title, wrapped_message) =
+			Self::get_wrapped_lines(
+				&self.data,
+				usize::from(self.current_width.get()),
+				self.options.borrow().issue_references(),
+			);
 
-		wrapped_title.len() + wrapped_message.len()
+		(wrapped_message.links, wrapped_title.len())
+	}
+
+	/// selects the next or previous link and scrolls to it
+	fn select_link(&mut self, next: bool) -> bool {
+		let (links, title_lines) = self.links();
+		let count = links.len();
+		if count == 0 {
+			return false;
+		}
+
+		let idx = match (self.selected_link, next) {
+			(None, true) => 0,
+			(None, false) => count - 1,
+			(Some(idx), true) => (idx + 1) % count,
+			(Some(idx), false) => (idx + count - 1) % count,
+		};
+		self.selected_link = Some(idx);
+
+		self.scroll.update(
+			title_lines + links[idx].line,
+			self.number_of_lines(),
+			usize::from(self.current_height.get()),
+		);
+
+		true
+	}
+
+	fn open_selected_link(&self) -> bool {
+		let (links, _) = self.links();
+		let link =
+			match self.selected_link.and_then(|idx| links.get(idx)) {
+				Some(link) => link,
+				None => return false,
+			};
+
+		if let Err(e) = file_manager::open_url(&link.url) {
+			self.queue.push(InternalEvent::ShowErrorMsg(
+				strings::open_link_failed(&link.url, &e.to_string()),
+			));
+		}
+
+		true
 	}
 
 	fn get_theme_for_line(&self, bold: bool) -> Style {
@@ -126,26 +239,61 @@ impl DetailsComponent {
 		}
 	}
 
+	fn get_markup_style(&self, kind: MarkupKind) -> Style {
+		match kind {
+			MarkupKind::Text => self.get_theme_for_line(false),
+			MarkupKind::ListMarker => self.get_theme_for_line(true),
+			MarkupKind::Code => self.theme.commit_message_code(),
+			MarkupKind::Link(idx) => self.theme.commit_message_link(
+				self.focused && self.selected_link == Some(idx),
+			),
+		}
+	}
+
 	fn get_wrapped_text_message(
 		&self,
 		width: usize,
 		height: usize,
 	) -> Vec<Spans> {
 		let (wrapped_title, wrapped_message) =
-			Self::get_wrapped_lines(&self.data, width);
+			Self::get_wrapped_lines(
+				&self.data,
+				width,
+				self.options.borrow().issue_references(),
+			);
+		let wrapped_note = Self::get_wrapped_note(&self.note, width);
+
+		let plain = |line: &Cow<'_, str>, bold: bool| {
+			Spans::from(vec![Span::styled(
+				line.to_string(),
+				self.get_theme_for_line(bold),
+			)])
+		};
 
-		[&wrapped_title[..], &wrapped_message[..]]
-			.concat()
+		wrapped_title
 			.iter()
-			.enumerate()
+			.map(|line| plain(line, true))
+			.chain(wrapped_message.lines.into_iter().map(|line| {
+				Spans::from(
+					line.into_iter()
+						.map(|(text, kind)| {
+							Span::styled(
+								text,
+								self.get_markup_style(kind),
+							)
+						})
+						.collect::<Vec<_>>(),
+				)
+			}))
+			// the title line of the notes section is bold
+			.chain(
+				wrapped_note
+					.iter()
+					.enumerate()
+					.map(|(i, line)| plain(line, i == 1)),
+			)
 			.skip(self.scroll.get_top())
 			.take(height)
-			.map(|(i, line)| {
-				Spans::from(vec![Span::styled(
-					line.clone(),
-					self.get_theme_for_line(i < wrapped_title.len()),
-				)])
-			})
 			.collect()
 	}
 
@@ -283,9 +431,9 @@ impl DrawableComponent for DetailsComponent {
 		let height = chunks[1].height.saturating_sub(border_width);
 
 		self.current_width.set(width);
+		self.current_height.set(height);
 
-		let number_of_lines =
-			Self::get_number_of_lines(&self.data, usize::from(width));
+		let number_of_lines = self.number_of_lines();
 
 		self.scroll.update_no_selection(
 			number_of_lines,
@@ -336,9 +484,7 @@ impl Component for DetailsComponent {
 		out: &mut Vec<CommandInfo>,
 		force_all: bool,
 	) -> CommandBlocking {
-		let width = usize::from(self.current_width.get());
-		let number_of_lines =
-			Self::get_number_of_lines(&self.data, width);
+		let number_of_lines = self.number_of_lines();
 
 		out.push(
 			CommandInfo::new(
@@ -351,6 +497,20 @@ impl Component for DetailsComponent {
 			.order(order::NAV),
 		);
 
+		out.push(CommandInfo::new(
+			strings::commands::message_link_next_prev(
+				&self.key_config,
+			),
+			!self.links().0.is_empty(),
+			self.focused,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::message_link_open(&self.key_config),
+			self.selected_link.is_some(),
+			self.focused,
+		));
+
 		CommandBlocking::PassingOn
 	}
 
@@ -369,6 +529,12 @@ impl Component for DetailsComponent {
 					|| e == self.key_config.shift_down
 				{
 					self.move_scroll_top(ScrollType::End).into()
+				} else if e == self.key_config.message_link_next {
+					self.select_link(true).into()
+				} else if e == self.key_config.message_link_prev {
+					self.select_link(false).into()
+				} else if e == self.key_config.enter {
+					self.open_selected_link().into()
 				} else {
 					EventState::NotConsumed
 				});
@@ -391,6 +557,25 @@ impl Component for DetailsComponent {
 
 		self.focused = focus;
 	}
+
+	fn on_resize(&self) {
+		self.current_width.set(0);
+	}
+
+	/// the message, the info above it is at hand in the log already
+	fn selectable_text(&self) -> Option<Vec<String>> {
+		self.data.as_ref()?;
+
+		Some(
+			self.get_wrapped_text_message(
+				usize::from(self.current_width.get()),
+				usize::from(self.current_height.get()),
+			)
+			.iter()
+			.map(ui::spans_text)
+			.collect(),
+		)
+	}
 }
 
 #[cfg(test)]
@@ -400,11 +585,21 @@ mod tests {
 	fn get_wrapped_lines(
 		message: &CommitMessage,
 		width: usize,
-	) -> Vec<Cow<'_, str>> {
+	) -> Vec<String> {
 		let (wrapped_title, wrapped_message) =
-			DetailsComponent::wrap_commit_details(message, width);
+			DetailsComponent::wrap_commit_details(
+				message,
+				width,
+				&[],
+			);
 
-		[&wrapped_title[..], &wrapped_message[..]].concat()
+		wrapped_title
+			.iter()
+			.map(ToString::to_string)
+			.chain(wrapped_message.lines.into_iter().map(|line| {
+				line.into_iter().map(|(text, _)| text).collect()
+			}))
+			.collect()
 	}
 
 	#[test]
@@ -465,12 +660,27 @@ mod test_line_count {
 		};
 		let lines = DetailsComponent::get_number_of_lines(
 			&Some(commit.clone()),
+			&None,
 			50,
+			&[],
 		);
 		assert_eq!(lines, 2);
 
-		let lines =
-			DetailsComponent::get_number_of_lines(&Some(commit), 8);
+		let lines = DetailsComponent::get_number_of_lines(
+			&Some(commit.clone()),
+			&None,
+			8,
+			&[],
+		);
 		assert_eq!(lines, 4);
+
+		// blank line, title and the note
+		let lines = DetailsComponent::get_number_of_lines(
+			&Some(commit),
+			&Some(String::from("build ok\n")),
+			50,
+			&[],
+		);
+		assert_eq!(lines, 5);
 	}
 }
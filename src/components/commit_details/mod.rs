@@ -5,6 +5,7 @@ mod style;
 use super::{
 	command_pump, event_pump, CommandBlocking, CommandInfo,
 	Component, DrawableComponent, EventState, FileTreeComponent,
+	SharedOptions,
 };
 use crate::{
 	accessors, keys::SharedKeyConfig, queue::Queue, strings,
@@ -12,8 +13,8 @@ use crate::{
 };
 use anyhow::Result;
 use asyncgit::{
-	sync::CommitTags, AsyncCommitFiles, AsyncGitNotification,
-	CommitFilesParams,
+	sync::{CommitId, CommitTags, MergeDiffMode},
+	AsyncCommitFiles, AsyncGitNotification, CommitFilesParams,
 };
 use compare_details::CompareDetailsComponent;
 use crossbeam_channel::Sender;
@@ -27,10 +28,14 @@ use tui::{
 
 pub struct CommitDetailsComponent {
 	commit: Option<CommitFilesParams>,
+	/// applies to merge commits only
+	merge_mode: MergeDiffMode,
 	single_details: DetailsComponent,
 	compare_details: CompareDetailsComponent,
 	file_tree: FileTreeComponent,
 	git_commit_files: AsyncCommitFiles,
+	/// file to select once the files of the commit are loaded
+	select_file: Option<(CommitId, String)>,
 	visible: bool,
 	key_config: SharedKeyConfig,
 }
@@ -44,11 +49,14 @@ impl CommitDetailsComponent {
 		sender: &Sender<AsyncGitNotification>,
 		theme: SharedTheme,
 		key_config: SharedKeyConfig,
+		options: SharedOptions,
 	) -> Self {
 		Self {
 			single_details: DetailsComponent::new(
+				queue,
 				theme.clone(),
 				key_config.clone(),
+				options,
 				false,
 			),
 			compare_details: CompareDetailsComponent::new(
@@ -63,20 +71,29 @@ impl CommitDetailsComponent {
 				theme,
 				key_config.clone(),
 			),
+			select_file: None,
 			visible: false,
 			commit: None,
+			merge_mode: MergeDiffMode::default(),
 			key_config,
 		}
 	}
 
 	fn get_files_title(&self) -> String {
 		let files_count = self.file_tree.file_count();
-
-		format!(
-			"{} {}",
-			strings::commit::details_files_title(&self.key_config),
-			files_count
-		)
+		let title =
+			strings::commit::details_files_title(&self.key_config);
+
+		if self.is_merge() {
+			format!(
+				"{} {} ({})",
+				title,
+				files_count,
+				strings::commit::merge_diff_mode(self.merge_mode)
+			)
+		} else {
+			format!("{} {}", title, files_count)
+		}
 	}
 
 	///
@@ -90,8 +107,6 @@ impl CommitDetailsComponent {
 			self.compare_details.set_commits(None);
 		}
 
-		self.commit = params;
-
 		if let Some(id) = params {
 			if let Some(other) = id.other {
 				self.compare_details
@@ -99,13 +114,30 @@ impl CommitDetailsComponent {
 			} else {
 				self.single_details.set_commit(Some(id.id), tags);
 			}
+		}
 
+		let merge_mode = if params
+			.map_or(false, |p| p.other.is_none())
+			&& self.single_details.is_merge()
+		{
+			self.merge_mode
+		} else {
+			MergeDiffMode::default()
+		};
+
+		self.commit = params.map(|params| CommitFilesParams {
+			merge_mode,
+			..params
+		});
+
+		if let Some(id) = self.commit {
 			if let Some((fetched_id, res)) =
 				self.git_commit_files.current()?
 			{
 				if fetched_id == id {
 					self.file_tree.update(res.as_slice())?;
 					self.file_tree.set_title(self.get_files_title());
+					self.select_pending_file(id.id);
 
 					return Ok(());
 				}
@@ -120,6 +152,37 @@ impl CommitDetailsComponent {
 		Ok(())
 	}
 
+	/// fetches the files of the shown commits again, keeps the old
+	/// ones until they arrive
+	pub fn refresh_files(&mut self) -> Result<()> {
+		if let Some(params) = self.commit {
+			self.git_commit_files.fetch(params)?;
+		}
+
+		Ok(())
+	}
+
+	/// selects `path` in the files of `id` once they are loaded
+	pub fn select_file_on_update(
+		&mut self,
+		id: CommitId,
+		path: String,
+	) {
+		self.select_file = Some((id, path));
+	}
+
+	fn select_pending_file(&mut self, id: CommitId) {
+		if self
+			.select_file
+			.as_ref()
+			.map_or(false, |(commit, _)| *commit == id)
+		{
+			if let Some((_, path)) = self.select_file.take() {
+				self.file_tree.select_path(&path);
+			}
+		}
+	}
+
 	///
 	pub fn any_work_pending(&self) -> bool {
 		self.git_commit_files.is_pending()
@@ -130,6 +193,17 @@ impl CommitDetailsComponent {
 		&self.file_tree
 	}
 
+	/// false if `path` is not among the files or hidden in a
+	/// collapsed folder
+	pub fn select_file(&mut self, path: &str) -> bool {
+		self.file_tree.select_path(path)
+	}
+
+	///
+	pub fn select_adjacent_file(&mut self, next: bool) -> bool {
+		self.file_tree.select_adjacent_file(next)
+	}
+
 	fn details_focused(&self) -> bool {
 		self.single_details.focused()
 			|| self.compare_details.focused()
@@ -146,6 +220,38 @@ impl CommitDetailsComponent {
 	fn is_compare(&self) -> bool {
 		self.commit.map(|p| p.other.is_some()).unwrap_or_default()
 	}
+
+	fn is_merge(&self) -> bool {
+		!self.is_compare() && self.single_details.is_merge()
+	}
+
+	/// what the files and diffs of the shown commit compare against
+	pub fn merge_mode(&self) -> MergeDiffMode {
+		self.commit
+			.map_or_else(MergeDiffMode::default, |p| p.merge_mode)
+	}
+
+	fn cycle_merge_mode(&mut self) -> Result<()> {
+		self.merge_mode = match self.merge_mode {
+			MergeDiffMode::FirstParent => MergeDiffMode::SecondParent,
+			MergeDiffMode::SecondParent => MergeDiffMode::Combined,
+			MergeDiffMode::Combined => MergeDiffMode::FirstParent,
+		};
+
+		if let Some(params) = self.commit {
+			let params = CommitFilesParams {
+				merge_mode: self.merge_mode,
+				..params
+			};
+			self.commit = Some(params);
+
+			self.file_tree.clear()?;
+			self.git_commit_files.fetch(params)?;
+			self.file_tree.set_title(self.get_files_title());
+		}
+
+		Ok(())
+	}
 }
 
 impl DrawableComponent for CommitDetailsComponent {
@@ -200,6 +306,12 @@ impl Component for CommitDetailsComponent {
 				force_all,
 				self.components().as_slice(),
 			);
+
+			out.push(CommandInfo::new(
+				strings::commands::merge_diff_mode(&self.key_config),
+				true,
+				(self.focused() && self.is_merge()) || force_all,
+			));
 		}
 
 		CommandBlocking::PassingOn
@@ -227,6 +339,11 @@ impl Component for CommitDetailsComponent {
 					self.file_tree.focus(false);
 					self.set_details_focus(true);
 					Ok(EventState::Consumed)
+				} else if e == self.key_config.merge_diff_mode
+					&& self.is_merge()
+				{
+					self.cycle_merge_mode()?;
+					Ok(EventState::Consumed)
 				} else {
 					Ok(EventState::NotConsumed)
 				};
@@ -257,4 +374,18 @@ impl Component for CommitDetailsComponent {
 		self.file_tree.focus(focus);
 		self.file_tree.show_selection(true);
 	}
+
+	fn on_resize(&self) {
+		self.single_details.on_resize();
+	}
+
+	fn selectable_text(&self) -> Option<Vec<String>> {
+		if self.file_tree.focused() {
+			self.file_tree.selectable_text()
+		} else if self.is_compare() {
+			None
+		} else {
+			self.single_details.selectable_text()
+		}
+	}
 }
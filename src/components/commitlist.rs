@@ -1,20 +1,25 @@
-use super::utils::logitems::{ItemBatch, LogEntry};
+use super::utils::logitems::{
+	ItemBatch, LogDateFormat, LogDateSource, LogEntry,
+};
 use crate::{
 	components::{
 		utils::string_width_align, CommandBlocking, CommandInfo,
 		Component, DrawableComponent, EventState, ScrollType,
+		SharedOptions,
 	},
 	keys::SharedKeyConfig,
 	strings::{self, symbol},
 	ui::style::{SharedTheme, Theme},
-	ui::{calc_scroll_top, draw_scrollbar},
+	ui::{self, calc_scroll_top, draw_empty_state, draw_scrollbar},
 };
 use anyhow::Result;
 use asyncgit::sync::{CommitId, Tags};
+use chrono::FixedOffset;
 use chrono::{DateTime, Local};
 use crossterm::event::Event;
 use std::{
-	borrow::Cow, cell::Cell, cmp, convert::TryFrom, time::Instant,
+	borrow::Cow, cell::Cell, cmp, collections::HashSet,
+	convert::TryFrom, time::Instant,
 };
 use tui::{
 	backend::Backend,
@@ -26,6 +31,14 @@ use tui::{
 
 const ELEMENTS_PER_LINE: usize = 9;
 
+/// a line of the list
+enum Row<'a> {
+	/// starts a group of commits, never selected
+	Header(String),
+	/// index into the loaded items
+	Entry(usize, &'a LogEntry),
+}
+
 ///
 pub struct CommitList {
 	title: Box<str>,
@@ -34,12 +47,20 @@ pub struct CommitList {
 	count_total: usize,
 	items: ItemBatch,
 	marked: Vec<CommitId>,
+	/// the first entries are not on the upstream yet
+	unpushed: usize,
 	scroll_state: (Instant, f32),
 	tags: Option<Tags>,
+	/// loaded commits that have a note
+	notes: HashSet<CommitId>,
+	empty_text: Option<Vec<String>>,
 	current_size: Cell<(u16, u16)>,
 	scroll_top: Cell<usize>,
+	/// row in view the selection is kept in, see `pin_selection_row`
+	pinned_row: Cell<Option<usize>>,
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
+	options: SharedOptions,
 }
 
 impl CommitList {
@@ -48,23 +69,35 @@ impl CommitList {
 		title: &str,
 		theme: SharedTheme,
 		key_config: SharedKeyConfig,
+		options: SharedOptions,
 	) -> Self {
 		Self {
 			items: ItemBatch::default(),
 			marked: Vec::with_capacity(2),
+			unpushed: 0,
 			selection: 0,
 			branch: None,
 			count_total: 0,
 			scroll_state: (Instant::now(), 0_f32),
 			tags: None,
+			notes: HashSet::new(),
+			empty_text: None,
 			current_size: Cell::new((0, 0)),
 			scroll_top: Cell::new(0),
+			pinned_row: Cell::new(None),
 			theme,
 			key_config,
+			options,
 			title: title.into(),
 		}
 	}
 
+	/// lines to show instead of entries while the list is empty,
+	/// see `ui::draw_empty_state`
+	pub fn set_empty_text(&mut self, text: Option<Vec<String>>) {
+		self.empty_text = text;
+	}
+
 	///
 	pub fn items(&mut self) -> &mut ItemBatch {
 		&mut self.items
@@ -75,6 +108,12 @@ impl CommitList {
 		self.branch = name;
 	}
 
+	/// marks the first `count` entries as not pushed, the list is
+	/// expected to start at the head of the branch
+	pub fn set_unpushed(&mut self, count: usize) {
+		self.unpushed = count;
+	}
+
 	///
 	pub const fn selection(&self) -> usize {
 		self.selection
@@ -108,11 +147,21 @@ impl CommitList {
 		self.items.clear();
 	}
 
+	///
+	pub fn set_title(&mut self, title: &str) {
+		self.title = title.into();
+	}
+
 	///
 	pub fn set_tags(&mut self, tags: Tags) {
 		self.tags = Some(tags);
 	}
 
+	/// `notes` are looked up for the loaded entries only
+	pub fn set_notes(&mut self, notes: HashSet<CommitId>) {
+		self.notes = notes;
+	}
+
 	///
 	pub fn selected_entry(&self) -> Option<&LogEntry> {
 		self.items.iter().nth(
@@ -137,6 +186,11 @@ impl CommitList {
 		&self.marked
 	}
 
+	///
+	pub fn clear_marked(&mut self) {
+		self.marked.clear();
+	}
+
 	pub fn copy_entry_hash(&self) -> Result<()> {
 		if let Some(e) = self.items.iter().nth(
 			self.selection.saturating_sub(self.items.index_offset()),
@@ -232,17 +286,23 @@ impl CommitList {
 		tags: Option<String>,
 		theme: &Theme,
 		width: usize,
-		now: DateTime<Local>,
+		now: &DateTime<Local>,
+		date: (LogDateFormat, LogDateSource),
 		marked: Option<bool>,
+		unpushed: Option<bool>,
 	) -> Spans<'a> {
 		let mut txt: Vec<Span> = Vec::with_capacity(
-			ELEMENTS_PER_LINE + if marked.is_some() { 2 } else { 0 },
+			ELEMENTS_PER_LINE
+				+ if marked.is_some() { 2 } else { 0 }
+				+ usize::from(unpushed.is_some()),
 		);
 
 		let splitter_txt = Cow::from(symbol::EMPTY_SPACE);
 		let splitter =
 			Span::styled(splitter_txt, theme.text(true, selected));
 
+		txt.extend(theme.selection_marker(selected));
+
 		// marker
 		if let Some(marked) = marked {
 			txt.push(Span::styled(
@@ -256,6 +316,18 @@ impl CommitList {
 			txt.push(splitter.clone());
 		}
 
+		// not on the upstream yet
+		if let Some(unpushed) = unpushed {
+			txt.push(Span::styled(
+				Cow::from(if unpushed {
+					symbol::UNPUSHED
+				} else {
+					symbol::EMPTY_SPACE
+				}),
+				theme.log_unpushed(selected),
+			));
+		}
+
 		// commit hash
 		txt.push(Span::styled(
 			Cow::from(&*e.hash_short),
@@ -264,16 +336,24 @@ impl CommitList {
 
 		txt.push(splitter.clone());
 
+		let (date_format, date_source) = date;
+		let date_width = date_format.width();
+
 		// commit timestamp
 		txt.push(Span::styled(
-			Cow::from(e.time_to_string(now)),
+			Cow::from(format!(
+				"{: <w$}",
+				e.time_to_string(now, date_format, date_source),
+				w = date_width
+			)),
 			theme.commit_time(selected),
 		));
 
 		txt.push(splitter.clone());
 
+		// hash, timestamp and two splitters
 		let author_width =
-			(width.saturating_sub(19) / 3).max(3).min(20);
+			(width.saturating_sub(date_width + 9) / 3).max(3).min(20);
 		let author = string_width_align(&e.author, author_width);
 
 		// commit author
@@ -304,42 +384,158 @@ impl CommitList {
 		Spans::from(txt)
 	}
 
-	fn get_text(&self, height: usize, width: usize) -> Vec<Spans> {
+	/// the loaded entries with a header before each group of the
+	/// log grouping, the selection still counts entries only
+	fn rows(&self) -> Vec<Row> {
+		let grouping = self.options.borrow().log_grouping;
+		let local =
+			|time: &DateTime<FixedOffset>| time.with_timezone(&Local);
+
+		let mut last = self
+			.items
+			.preceding()
+			.and_then(|time| grouping.group(&local(time)));
+
+		let mut rows = Vec::with_capacity(self.items.iter().len());
+		for (idx, e) in self.items.iter().enumerate() {
+			let time = local(&e.time);
+			let group = grouping.group(&time);
+			if group.is_some() && group != last {
+				rows.push(Row::Header(grouping.header(&time)));
+			}
+			last = group;
+
+			rows.push(Row::Entry(idx, e));
+		}
+
+		rows
+	}
+
+	/// first row to draw to have the selection in view
+	/// (along with its header if it starts a group)
+	fn scroll_top(&self, rows: &[Row], height: usize) -> usize {
+		let selection_row = self.selection_row(rows);
+		// applied once the selected entry is loaded
+		let pinned =
+			selection_row.and_then(|_| self.pinned_row.take());
+		let selection_row = selection_row.unwrap_or_default();
+
+		let first_row = match selection_row.checked_sub(1) {
+			Some(above) if matches!(rows[above], Row::Header(_)) => {
+				above
+			}
+			_ => selection_row,
+		};
+
+		let top = pinned.map_or_else(
+			|| self.scroll_top.get(),
+			|row| selection_row.saturating_sub(row),
+		);
+
+		calc_scroll_top(top, height, selection_row).min(first_row)
+	}
+
+	fn selection_row(&self, rows: &[Row]) -> Option<usize> {
+		let selection = self.relative_selection();
+		rows.iter().position(
+			|row| matches!(row, Row::Entry(idx, _) if *idx == selection),
+		)
+	}
+
+	/// row of the view the selected entry was drawn in
+	pub fn selection_view_row(&self) -> Option<usize> {
+		self.selection_row(&self.rows())
+			.map(|row| row.saturating_sub(self.scroll_top.get()))
+	}
+
+	/// selects `position` drawn in `row` of the view once it is
+	/// loaded, commits added or removed above it move the view
+	/// along with the selection
+	pub fn select_entry_in_row(
+		&mut self,
+		position: usize,
+		row: usize,
+	) {
+		self.selection = position;
+		self.pinned_row.set(Some(row));
+	}
+
+	fn get_header_line(&self, header: &str, width: usize) -> Spans {
+		let line = format!("{} {} ", symbol::RULE.repeat(2), header);
+		let rule = width.saturating_sub(line.chars().count());
+
+		Spans::from(Span::styled(
+			format!("{}{}", line, symbol::RULE.repeat(rule)),
+			self.theme.commit_time(false),
+		))
+	}
+
+	fn get_text<'a>(
+		&'a self,
+		rows: &[Row<'a>],
+		height: usize,
+		width: usize,
+	) -> Vec<Spans<'a>> {
 		let selection = self.relative_selection();
 
 		let mut txt: Vec<Spans> = Vec::with_capacity(height);
 
 		let now = Local::now();
+		let date = {
+			let options = self.options.borrow();
+			(options.log_date_format, options.log_date_source)
+		};
 
 		let any_marked = !self.marked.is_empty();
 
-		for (idx, e) in self
-			.items
-			.iter()
-			.skip(self.scroll_top.get())
-			.take(height)
-			.enumerate()
+		for row in
+			rows.iter().skip(self.scroll_top.get()).take(height)
 		{
+			let (idx, e) = match row {
+				Row::Header(header) => {
+					txt.push(self.get_header_line(header, width));
+					continue;
+				}
+				Row::Entry(idx, e) => (*idx, *e),
+			};
+
 			let tags = self
 				.tags
 				.as_ref()
 				.and_then(|t| t.get(&e.id))
 				.map(|tags| tags.join(" "));
 
+			let tags = if self.notes.contains(&e.id) {
+				Some(tags.map_or_else(
+					|| symbol::NOTE.to_string(),
+					|tags| format!("{} {}", symbol::NOTE, tags),
+				))
+			} else {
+				tags
+			};
+
 			let marked = if any_marked {
 				self.is_marked(&e.id)
 			} else {
 				None
 			};
 
+			let unpushed = if self.unpushed > 0 {
+				Some(self.items.index_offset() + idx < self.unpushed)
+			} else {
+				None
+			};
+
 			txt.push(Self::get_entry_to_add(
 				e,
-				idx + self.scroll_top.get() == selection,
+				idx == selection,
 				tags,
 				&self.theme,
 				width,
-				now,
+				&now,
+				date,
 				marked,
+				unpushed,
 			));
 		}
 
@@ -369,13 +565,9 @@ impl DrawableComponent for CommitList {
 		self.current_size.set(current_size);
 
 		let height_in_lines = self.current_size.get().1 as usize;
-		let selection = self.relative_selection();
 
-		self.scroll_top.set(calc_scroll_top(
-			self.scroll_top.get(),
-			height_in_lines,
-			selection,
-		));
+		let rows = self.rows();
+		self.scroll_top.set(self.scroll_top(&rows, height_in_lines));
 
 		let branch_post_fix =
 			self.branch.as_ref().map(|b| format!("- {{{}}}", b));
@@ -388,13 +580,26 @@ impl DrawableComponent for CommitList {
 			branch_post_fix.as_deref().unwrap_or(""),
 		);
 
+		if let Some(empty_text) =
+			self.empty_text.as_ref().filter(|_| self.count_total == 0)
+		{
+			draw_empty_state(
+				f,
+				area,
+				&title,
+				empty_text,
+				true,
+				&self.theme,
+			);
+			return Ok(());
+		}
+
 		f.render_widget(
-			Paragraph::new(
-				self.get_text(
-					height_in_lines,
-					current_size.0 as usize,
-				),
-			)
+			Paragraph::new(self.get_text(
+				&rows,
+				height_in_lines,
+				current_size.0 as usize,
+			))
 			.block(
 				Block::default()
 					.borders(Borders::ALL)
@@ -442,6 +647,26 @@ impl Component for CommitList {
 			} else if k == self.key_config.log_mark_commit {
 				self.mark();
 				true
+			} else if k == self.key_config.log_date_format {
+				let format = self.options.borrow().log_date_format;
+				if let Err(e) = self
+					.options
+					.borrow_mut()
+					.set_log_date_format(format.next())
+				{
+					log::error!("save log date format: {}", e);
+				}
+				true
+			} else if k == self.key_config.log_grouping {
+				let grouping = self.options.borrow().log_grouping;
+				if let Err(e) = self
+					.options
+					.borrow_mut()
+					.set_log_grouping(grouping.next())
+				{
+					log::error!("save log grouping: {}", e);
+				}
+				true
 			} else {
 				false
 			};
@@ -469,13 +694,223 @@ impl Component for CommitList {
 			true,
 			true,
 		));
+		out.push(CommandInfo::new(
+			strings::commands::log_date_format(
+				&self.key_config,
+				self.options.borrow().log_date_format.name(),
+			),
+			true,
+			true,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::log_grouping(
+				&self.key_config,
+				self.options.borrow().log_grouping.name(),
+			),
+			true,
+			true,
+		));
 		CommandBlocking::PassingOn
 	}
+
+	fn on_resize(&self) {
+		self.current_size.set((0, 0));
+	}
+
+	fn selectable_text(&self) -> Option<Vec<String>> {
+		let (width, height) = self.current_size.get();
+		let rows = self.rows();
+
+		Some(
+			self.get_text(&rows, height.into(), width.into())
+				.iter()
+				.map(ui::spans_text)
+				.collect(),
+		)
+	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::components::{utils::logitems::LogGrouping, Options};
+	use asyncgit::sync::CommitInfo;
+	use std::{cell::RefCell, rc::Rc};
+
+	fn commit_at(n: usize, time: i64) -> CommitInfo {
+		CommitInfo {
+			message: format!("msg {}", n),
+			time,
+			time_offset: 0,
+			author_time: time,
+			author_time_offset: 0,
+			author: String::from("author"),
+			id: CommitId::from_hex(&format!("{:040}", n)).unwrap(),
+		}
+	}
+
+	fn list_at(times: &[i64]) -> CommitList {
+		let mut list = CommitList::new(
+			"",
+			SharedTheme::default(),
+			SharedKeyConfig::default(),
+			Rc::new(RefCell::new(Options::default())),
+		);
+
+		list.items().set_items(
+			0,
+			times
+				.iter()
+				.enumerate()
+				.map(|(n, time)| commit_at(n, *time))
+				.collect(),
+		);
+		list.set_count_total(times.len());
+
+		list
+	}
+
+	fn list_with(count: u8) -> CommitList {
+		list_at(&vec![0; usize::from(count)])
+	}
+
+	/// header rows as `None`, entries by their index
+	fn row_kinds(list: &CommitList) -> Vec<Option<usize>> {
+		list.rows()
+			.iter()
+			.map(|row| match row {
+				Row::Header(_) => None,
+				Row::Entry(idx, _) => Some(*idx),
+			})
+			.collect()
+	}
+
+	/// the selection marker is off by default
+	fn first_span(line: &Spans) -> String {
+		line.0[0].content.to_string()
+	}
+
+	#[test]
+	fn test_unpushed_marker() {
+		let mut list = list_with(3);
+
+		let txt = list.get_text(&list.rows(), 3, 80);
+		assert!(txt
+			.iter()
+			.all(|line| first_span(line) != symbol::UNPUSHED));
+
+		list.set_unpushed(2);
+		let txt = list.get_text(&list.rows(), 3, 80);
+		assert_eq!(first_span(&txt[0]), symbol::UNPUSHED);
+		assert_eq!(first_span(&txt[1]), symbol::UNPUSHED);
+		assert_eq!(first_span(&txt[2]), symbol::EMPTY_SPACE);
+	}
+
+	#[test]
+	fn test_note_marker() {
+		let mut list = list_with(2);
+		let noted = list.items().iter().next().unwrap().id;
+
+		list.set_notes(vec![noted].into_iter().collect());
+
+		let has_note = |line: &Spans| {
+			line.0
+				.iter()
+				.any(|span| span.content.contains(symbol::NOTE))
+		};
+		let txt = list.get_text(&list.rows(), 2, 80);
+		assert!(has_note(&txt[0]));
+		assert!(!has_note(&txt[1]));
+	}
+
+	#[test]
+	fn test_group_headers() {
+		const DAY: i64 = 24 * 60 * 60;
+		// noon, far from any day boundary in every timezone
+		let noon = 20_000 * DAY + DAY / 2;
+
+		let mut list = list_at(&[noon + 60, noon, noon - 2 * DAY]);
+		assert_eq!(row_kinds(&list), vec![Some(0), Some(1), Some(2)]);
+
+		list.options.borrow_mut().log_grouping = LogGrouping::Day;
+		assert_eq!(
+			row_kinds(&list),
+			vec![None, Some(0), Some(1), None, Some(2)]
+		);
+
+		let txt = list.get_text(&list.rows(), 5, 40);
+		assert!(first_span(&txt[0]).starts_with(symbol::RULE));
+		assert!(first_span(&txt[3]).starts_with(symbol::RULE));
+
+		// headers are skipped when moving the selection
+		list.move_selection(ScrollType::Down).unwrap();
+		list.move_selection(ScrollType::Down).unwrap();
+		assert_eq!(
+			list.selected_entry().unwrap().msg.as_ref(),
+			"msg 2"
+		);
+
+		// its header stays in view along with the selection
+		assert_eq!(list.scroll_top(&list.rows(), 2), 3);
+
+		list.options.borrow_mut().log_grouping = LogGrouping::Week;
+		assert_eq!(
+			row_kinds(&list),
+			vec![None, Some(0), Some(1), Some(2)]
+		);
+	}
+
+	#[test]
+	fn test_selection_keeps_row() {
+		let mut list = list_with(10);
+		let draw = |list: &CommitList| {
+			list.scroll_top.set(list.scroll_top(&list.rows(), 3));
+		};
+
+		list.select_entry(6);
+		draw(&list);
+		assert_eq!(list.scroll_top.get(), 4);
+		list.select_entry(5);
+		draw(&list);
+		let row = list.selection_view_row().unwrap();
+		assert_eq!(row, 1);
+
+		// a new commit on top, the selected one is one further down
+		list = list_with(11);
+		list.scroll_top.set(4);
+		list.select_entry_in_row(6, row);
+		draw(&list);
+		assert_eq!(list.scroll_top.get(), 5);
+		assert_eq!(list.selection_view_row(), Some(row));
+
+		// only applied once
+		list.select_entry(4);
+		draw(&list);
+		assert_eq!(list.scroll_top.get(), 4);
+	}
+
+	#[test]
+	fn test_group_headers_batch_start() {
+		const DAY: i64 = 24 * 60 * 60;
+		let noon = 20_000 * DAY + DAY / 2;
+
+		let mut list = list_with(0);
+		list.options.borrow_mut().log_grouping = LogGrouping::Day;
+
+		// a batch starting mid-day: no header before its first entry
+		list.items().set_items(
+			10,
+			vec![commit_at(10, noon), commit_at(11, noon - 2 * DAY)],
+		);
+		list.items().set_preceding(Some(&commit_at(9, noon + 60)));
+		assert_eq!(row_kinds(&list), vec![Some(0), None, Some(1)]);
+
+		list.items().set_preceding(Some(&commit_at(9, noon + DAY)));
+		assert_eq!(
+			row_kinds(&list),
+			vec![None, Some(0), None, Some(1)]
+		);
+	}
 
 	#[test]
 	fn test_string_width_align() {
@@ -1,15 +1,19 @@
 use super::{
 	command_pump, event_pump, visibility_blocking, CommandBlocking,
-	CommandInfo, CommitDetailsComponent, Component, DiffComponent,
-	DrawableComponent, EventState,
+	CommandInfo, CommitDetailsComponent, Component, DiffCommits,
+	DiffComponent, DrawableComponent, EventState, SharedOptions,
 };
 use crate::{
-	accessors, keys::SharedKeyConfig, queue::Queue, strings,
+	accessors,
+	keys::SharedKeyConfig,
+	messages::Severity,
+	queue::{InternalEvent, Queue},
+	strings, try_or_popup,
 	ui::style::SharedTheme,
 };
 use anyhow::Result;
 use asyncgit::{
-	sync::{self, diff::DiffOptions, CommitId},
+	sync::{self, diff::DiffOptions, BaseBranch, CommitId},
 	AsyncDiff, AsyncGitNotification, CommitFilesParams, DiffParams,
 	DiffType, CWD,
 };
@@ -18,16 +22,39 @@ use crossterm::event::Event;
 use tui::{
 	backend::Backend,
 	layout::{Constraint, Direction, Layout, Rect},
-	widgets::Clear,
+	text::Span,
+	widgets::{Clear, Paragraph},
 	Frame,
 };
 
+/// branches compared by their tips, (name, tip)
+struct BranchCompare {
+	base: (String, CommitId),
+	target: (String, CommitId),
+}
+
+/// what the current branch changed since it forked off `base`
+struct SinceBranching {
+	base: String,
+	/// uncommitted changes on top
+	workdir: bool,
+}
+
 pub struct CompareCommitsComponent {
+	/// (old, new)
 	commit_ids: Option<(CommitId, CommitId)>,
+	branches: Option<BranchCompare>,
+	/// follows `HEAD`, the base tip and (optionally) the working tree
+	since_branching: Option<SinceBranching>,
+	/// branches are compared from their merge base (`base...target`)
+	/// instead of the base tip (`base..target`)
+	use_merge_base: bool,
 	diff: DiffComponent,
 	details: CommitDetailsComponent,
 	git_diff: AsyncDiff,
 	visible: bool,
+	queue: Queue,
+	theme: SharedTheme,
 	key_config: SharedKeyConfig,
 }
 
@@ -38,6 +65,10 @@ impl DrawableComponent for CompareCommitsComponent {
 		rect: Rect,
 	) -> Result<()> {
 		if self.is_visible() {
+			f.render_widget(Clear, rect);
+
+			let rect = self.draw_branches_title(f, rect);
+
 			let percentages = if self.diff.focused() {
 				(30, 70)
 			} else {
@@ -55,8 +86,6 @@ impl DrawableComponent for CompareCommitsComponent {
 				)
 				.split(rect);
 
-			f.render_widget(Clear, rect);
-
 			self.details.draw(f, chunks[0])?;
 			self.diff.draw(f, chunks[1])?;
 		}
@@ -98,6 +127,34 @@ impl Component for CompareCommitsComponent {
 				true,
 				self.diff.focused() || force_all,
 			));
+
+			out.push(CommandInfo::new(
+				strings::commands::compare_merge_base(
+					&self.key_config,
+					self.use_merge_base,
+				),
+				true,
+				(self.branches.is_some()
+					&& self.since_branching.is_none())
+					|| force_all,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::compare_uncommitted(
+					&self.key_config,
+					self.is_workdir(),
+				),
+				true,
+				self.since_branching.is_some() || force_all,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::compare_next_base(
+					&self.key_config,
+				),
+				true,
+				self.since_branching.is_some() || force_all,
+			));
 		}
 
 		visibility_blocking(self)
@@ -114,6 +171,31 @@ impl Component for CompareCommitsComponent {
 			if let Event::Key(e) = ev {
 				if e == self.key_config.exit_popup {
 					self.hide();
+				} else if e == self.key_config.compare_uncommitted
+					&& self.since_branching.is_some()
+				{
+					try_or_popup!(
+						self,
+						"uncommitted changes error:",
+						self.toggle_workdir()
+					);
+				} else if e == self.key_config.compare_next_base
+					&& self.since_branching.is_some()
+				{
+					try_or_popup!(
+						self,
+						"base branch error:",
+						self.next_base()
+					);
+				} else if e == self.key_config.compare_merge_base
+					&& self.branches.is_some()
+					&& self.since_branching.is_none()
+				{
+					try_or_popup!(
+						self,
+						"merge base error:",
+						self.toggle_merge_base()
+					);
 				} else if e == self.key_config.focus_right
 					&& self.can_focus_diff()
 				{
@@ -124,6 +206,17 @@ impl Component for CompareCommitsComponent {
 				{
 					self.details.focus(true);
 					self.diff.focus(false);
+				} else if (e == self.key_config.diff_hunk_next
+					|| e == self.key_config.diff_hunk_prev)
+					&& self.diff.focused()
+				{
+					// diff ran out of hunks in that direction
+					let next = e == self.key_config.diff_hunk_next;
+					if self.details.select_adjacent_file(next)
+						&& !next
+					{
+						self.diff.select_last_hunk_on_update();
+					}
 				} else if e == self.key_config.focus_left {
 					self.hide();
 				}
@@ -149,6 +242,11 @@ impl Component for CompareCommitsComponent {
 		self.update()?;
 		Ok(())
 	}
+
+	fn on_resize(&self) {
+		self.details.on_resize();
+		self.diff.on_resize();
+	}
 }
 
 impl CompareCommitsComponent {
@@ -160,6 +258,7 @@ impl CompareCommitsComponent {
 		sender: &Sender<AsyncGitNotification>,
 		theme: SharedTheme,
 		key_config: SharedKeyConfig,
+		options: SharedOptions,
 	) -> Self {
 		Self {
 			details: CommitDetailsComponent::new(
@@ -167,21 +266,28 @@ impl CompareCommitsComponent {
 				sender,
 				theme.clone(),
 				key_config.clone(),
+				options.clone(),
 			),
 			diff: DiffComponent::new(
 				queue.clone(),
-				theme,
+				theme.clone(),
 				key_config.clone(),
+				options,
 				true,
 			),
 			commit_ids: None,
+			branches: None,
+			since_branching: None,
+			use_merge_base: true,
 			git_diff: AsyncDiff::new(sender),
 			visible: false,
+			queue: queue.clone(),
+			theme,
 			key_config,
 		}
 	}
 
-	///
+	/// the older commit is shown as the old side
 	pub fn open(
 		&mut self,
 		id: CommitId,
@@ -192,12 +298,197 @@ impl CompareCommitsComponent {
 		} else {
 			sync::get_head_tuple(CWD)?.id
 		};
-		self.commit_ids = Some((id, other));
+
+		let infos = sync::get_commits_info(CWD, &[id, other], 0)?;
+		let newer_first = infos.first().map(|info| info.time)
+			> infos.get(1).map(|info| info.time);
+
+		self.commit_ids = Some(if newer_first {
+			(other, id)
+		} else {
+			(id, other)
+		});
+		self.branches = None;
+		self.since_branching = None;
+		self.show()?;
+
+		Ok(())
+	}
+
+	/// what `target` has over `base`, `base` and `target` are
+	/// (name, tip)
+	pub fn open_branches(
+		&mut self,
+		base: (String, CommitId),
+		target: (String, CommitId),
+	) -> Result<()> {
+		self.branches = Some(BranchCompare { base, target });
+		self.since_branching = None;
+		self.commit_ids = self.branch_ids()?;
 		self.show()?;
 
 		Ok(())
 	}
 
+	/// what the current branch changed since it forked off the base
+	/// branch (`merge-base..HEAD`), see `sync::get_base_branch`
+	pub fn open_since_branching(&mut self) -> Result<()> {
+		let base = if let Some(base) = sync::get_base_branch(CWD)? {
+			base
+		} else {
+			self.queue.push(InternalEvent::ShowErrorMsg(
+				strings::BASE_BRANCH_NONE.to_string(),
+			));
+			return Ok(());
+		};
+
+		if let Some(missing) = &base.missing {
+			self.queue.push(InternalEvent::ShowToast(
+				Severity::Info,
+				strings::base_branch_missing(missing, &base.name),
+			));
+		}
+
+		self.use_merge_base = true;
+		self.set_base(base, false)?;
+		self.show()
+	}
+
+	/// follows new commits, a moved base tip and the working tree
+	pub fn update_since_branching(&mut self) -> Result<()> {
+		if !self.is_visible() || self.since_branching.is_none() {
+			return Ok(());
+		}
+
+		let workdir = self.is_workdir();
+
+		if let Some(base) = sync::get_base_branch(CWD)? {
+			self.set_base(base, workdir)?;
+
+			if workdir {
+				// same params, the working tree changed
+				self.details.refresh_files()?;
+				self.git_diff.refresh()?;
+			}
+		} else {
+			self.hide();
+			self.queue.push(InternalEvent::ShowErrorMsg(
+				strings::BASE_BRANCH_NONE.to_string(),
+			));
+		}
+
+		Ok(())
+	}
+
+	/// compares `base` from its merge base with `HEAD`
+	fn set_base(
+		&mut self,
+		base: BaseBranch,
+		workdir: bool,
+	) -> Result<()> {
+		let head = sync::get_head_tuple(CWD)?;
+		let target = head
+			.name
+			.strip_prefix("refs/heads/")
+			.unwrap_or(&head.name)
+			.to_string();
+
+		self.since_branching = Some(SinceBranching {
+			base: base.name.clone(),
+			workdir,
+		});
+		self.branches = Some(BranchCompare {
+			base: (base.name, base.tip),
+			target: (target, head.id),
+		});
+		self.commit_ids = self.branch_ids()?;
+		self.update()
+	}
+
+	fn toggle_workdir(&mut self) -> Result<()> {
+		if let Some(since) = &mut self.since_branching {
+			since.workdir = !since.workdir;
+		}
+
+		self.update()
+	}
+
+	/// picks the base after the current one and keeps it for the repo
+	fn next_base(&mut self) -> Result<()> {
+		let since = match &self.since_branching {
+			Some(since) => since,
+			None => return Ok(()),
+		};
+
+		let candidates = sync::base_branch_candidates(CWD)?;
+		let next = candidates
+			.iter()
+			.position(|name| *name == since.base)
+			.map_or(0, |idx| idx + 1);
+
+		let name = candidates
+			.get(next)
+			.or_else(|| candidates.first())
+			.filter(|name| **name != since.base);
+
+		if let Some(name) = name {
+			let workdir = since.workdir;
+			sync::set_base_branch(CWD, name)?;
+
+			if let Some(base) = sync::get_base_branch(CWD)? {
+				self.set_base(base, workdir)?;
+			}
+		} else {
+			self.queue.push(InternalEvent::ShowToast(
+				Severity::Info,
+				strings::BASE_BRANCH_NO_OTHER.to_string(),
+			));
+		}
+
+		Ok(())
+	}
+
+	fn is_workdir(&self) -> bool {
+		self.since_branching
+			.as_ref()
+			.map_or(false, |since| since.workdir)
+	}
+
+	fn branch_ids(&self) -> Result<Option<(CommitId, CommitId)>> {
+		self.branches
+			.as_ref()
+			.map(|branches| {
+				let old = if self.use_merge_base {
+					sync::merge_base(
+						CWD,
+						branches.base.1,
+						branches.target.1,
+					)?
+				} else {
+					branches.base.1
+				};
+
+				Ok((old, branches.target.1))
+			})
+			.transpose()
+	}
+
+	/// stays on the tip if there is no merge base
+	fn toggle_merge_base(&mut self) -> Result<()> {
+		self.use_merge_base = !self.use_merge_base;
+
+		match self.branch_ids() {
+			Ok(ids) => {
+				self.commit_ids = ids;
+				self.update()
+			}
+			Err(e) => {
+				self.use_merge_base = !self.use_merge_base;
+				Err(e)
+			}
+		}
+	}
+
 	///
 	pub fn any_work_pending(&self) -> bool {
 		self.git_diff.is_pending() || self.details.any_work_pending()
@@ -225,17 +516,31 @@ impl CompareCommitsComponent {
 			if let Some(ids) = self.commit_ids {
 				if let Some(f) = self.details.files().selection_file()
 				{
+					let workdir = self.is_workdir();
 					let diff_params = DiffParams {
 						path: f.path.clone(),
-						diff_type: DiffType::Commits(ids),
+						old_path: None,
+						diff_type: if workdir {
+							DiffType::CommitWorkdir(ids.0)
+						} else {
+							DiffType::Commits(ids)
+						},
 						options: DiffOptions::default(),
 					};
 
+					// uncommitted lines have nothing to blame
+					self.diff.set_commits(
+						(!workdir).then(|| {
+							DiffCommits::Compare(ids.0, ids.1)
+						}),
+					);
+
 					if let Some((params, last)) =
 						self.git_diff.last()?
 					{
 						if params == diff_params {
-							self.diff.update(f.path, false, last);
+							self.diff
+								.update(f.path, None, false, last);
 							return Ok(());
 						}
 					}
@@ -253,8 +558,12 @@ impl CompareCommitsComponent {
 	}
 
 	fn update(&mut self) -> Result<()> {
+		let workdir = self.is_workdir();
 		self.details.set_commits(
-			self.commit_ids.map(CommitFilesParams::from),
+			self.commit_ids.map(|ids| CommitFilesParams {
+				workdir,
+				..CommitFilesParams::from(ids)
+			}),
 			None,
 		)?;
 		self.update_diff()?;
@@ -262,6 +571,50 @@ impl CompareCommitsComponent {
 		Ok(())
 	}
 
+	/// `base...target` above the rest, returns the area left
+	fn draw_branches_title<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Rect {
+		self.branches.as_ref().map_or(rect, |branches| {
+			let chunks = Layout::default()
+				.direction(Direction::Vertical)
+				.constraints(
+					[Constraint::Length(1), Constraint::Min(1)]
+						.as_ref(),
+				)
+				.split(rect);
+
+			let title = self.since_branching.as_ref().map_or_else(
+				|| {
+					strings::compare_branches_title(
+						&branches.base.0,
+						&branches.target.0,
+						self.use_merge_base,
+					)
+				},
+				|since| {
+					strings::since_branching_title(
+						&since.base,
+						&branches.target.0,
+						since.workdir,
+					)
+				},
+			);
+
+			f.render_widget(
+				Paragraph::new(Span::styled(
+					title,
+					self.theme.title(true),
+				)),
+				chunks[0],
+			);
+
+			chunks[1]
+		})
+	}
+
 	fn can_focus_diff(&self) -> bool {
 		self.details.files().selection_file().is_some()
 	}
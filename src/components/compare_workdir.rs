@@ -0,0 +1,423 @@
+use super::{
+	utils::scroll_vertical::VerticalScroll, visibility_blocking,
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState, ScrollType,
+};
+use crate::{
+	keys::SharedKeyConfig, string_utils::tabs_to_spaces, strings,
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+	sync::{
+		self,
+		diff::{DiffLine, DiffLineType, FileDiff},
+		CommitId,
+	},
+	CWD,
+};
+use crossterm::event::Event;
+use tui::{
+	backend::Backend,
+	layout::{Constraint, Direction, Layout, Rect},
+	text::{Span, Spans},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// one line of either side
+struct CompareLine {
+	lineno: u32,
+	content: String,
+	line_type: DiffLineType,
+}
+
+impl CompareLine {
+	fn new(line: &DiffLine, lineno: Option<u32>) -> Self {
+		Self {
+			lineno: lineno.unwrap_or_default(),
+			content: tabs_to_spaces(line.content.to_string()),
+			line_type: line.line_type,
+		}
+	}
+}
+
+/// a row of both panes, `None` is a gap on that side
+type CompareRow = (Option<CompareLine>, Option<CompareLine>);
+
+/// pairs up deleted and added lines so changed blocks line up,
+/// the shorter side of a block is padded with gaps
+fn aligned_rows(diff: &FileDiff) -> Vec<CompareRow> {
+	fn flush(
+		rows: &mut Vec<CompareRow>,
+		deleted: &mut Vec<CompareLine>,
+		added: &mut Vec<CompareLine>,
+	) {
+		let mut deleted = deleted.drain(..);
+		let mut added = added.drain(..);
+
+		loop {
+			match (deleted.next(), added.next()) {
+				(None, None) => break,
+				row => rows.push(row),
+			}
+		}
+	}
+
+	let mut rows = Vec::with_capacity(diff.lines);
+	let mut deleted = Vec::new();
+	let mut added = Vec::new();
+
+	for line in diff.hunks.iter().flat_map(|hunk| hunk.lines.iter()) {
+		match line.line_type {
+			DiffLineType::Delete => deleted.push(CompareLine::new(
+				line,
+				line.position.old_lineno,
+			)),
+			DiffLineType::Add => added.push(CompareLine::new(
+				line,
+				line.position.new_lineno,
+			)),
+			DiffLineType::None => {
+				flush(&mut rows, &mut deleted, &mut added);
+				rows.push((
+					Some(CompareLine::new(
+						line,
+						line.position.old_lineno,
+					)),
+					Some(CompareLine::new(
+						line,
+						line.position.new_lineno,
+					)),
+				));
+			}
+			DiffLineType::Header => {
+				flush(&mut rows, &mut deleted, &mut added);
+			}
+		}
+	}
+
+	flush(&mut rows, &mut deleted, &mut added);
+
+	rows
+}
+
+/// file at a revision (left) next to its working copy (right),
+/// both panes scroll together
+pub struct CompareWorkdirComponent {
+	visible: bool,
+	path: String,
+	commit: String,
+	rows: Vec<CompareRow>,
+	/// shown instead of the lines of a side
+	notes: (Option<&'static str>, Option<&'static str>),
+	scroll: VerticalScroll,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl CompareWorkdirComponent {
+	///
+	pub const fn new(
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			visible: false,
+			path: String::new(),
+			commit: String::new(),
+			rows: Vec::new(),
+			notes: (None, None),
+			scroll: VerticalScroll::new(),
+			theme,
+			key_config,
+		}
+	}
+
+	///
+	pub fn open(
+		&mut self,
+		commit: CommitId,
+		path: String,
+	) -> Result<()> {
+		let res = sync::diff_blob_to_workdir(CWD, commit, &path)?;
+
+		self.notes = if res.binary {
+			(
+				Some(strings::COMPARE_WORKDIR_BINARY),
+				Some(strings::COMPARE_WORKDIR_BINARY),
+			)
+		} else {
+			(
+				res.old_missing
+					.then(|| strings::COMPARE_WORKDIR_NOT_IN_COMMIT),
+				res.new_missing
+					.then(|| strings::COMPARE_WORKDIR_DELETED),
+			)
+		};
+		self.rows = aligned_rows(&res.diff);
+		self.commit = commit.get_short_string();
+		self.path = path;
+		self.scroll.reset();
+
+		self.show()
+	}
+
+	fn lineno_width(&self) -> usize {
+		self.rows
+			.iter()
+			.filter_map(|(left, right)| {
+				left.as_ref()
+					.map(|line| line.lineno)
+					.max(right.as_ref().map(|line| line.lineno))
+			})
+			.max()
+			.map_or(1, |max| max.to_string().len())
+	}
+
+	fn get_text(
+		&self,
+		left: bool,
+		height: usize,
+		width: usize,
+	) -> Vec<Spans> {
+		let note = if left { self.notes.0 } else { self.notes.1 };
+		if let Some(note) = note {
+			return vec![Spans::from(Span::styled(
+				note,
+				self.theme.text(false, false),
+			))];
+		}
+
+		self.rows
+			.iter()
+			.skip(self.scroll.get_top())
+			.take(height)
+			.map(|(old, new)| {
+				let line = if left { old } else { new };
+
+				line.as_ref().map_or_else(
+					|| Spans::from(""),
+					|line| {
+						Spans::from(vec![
+							Span::styled(
+								format!(
+									"{:>w$} ",
+									line.lineno,
+									w = width
+								),
+								self.theme.text(false, false),
+							),
+							Span::styled(
+								line.content.clone(),
+								self.theme
+									.diff_line(line.line_type, false),
+							),
+						])
+					},
+				)
+			})
+			.collect()
+	}
+
+	fn draw_pane<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		area: Rect,
+		title: &str,
+		text: Vec<Spans>,
+	) {
+		f.render_widget(
+			Paragraph::new(text).block(
+				Block::default()
+					.borders(Borders::ALL)
+					.title(Span::styled(
+						title,
+						self.theme.title(true),
+					))
+					.border_style(self.theme.block(true)),
+			),
+			area,
+		);
+	}
+}
+
+impl DrawableComponent for CompareWorkdirComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		if self.is_visible() {
+			let chunks = Layout::default()
+				.direction(Direction::Horizontal)
+				.constraints(
+					[
+						Constraint::Percentage(50),
+						Constraint::Percentage(50),
+					]
+					.as_ref(),
+				)
+				.split(rect);
+
+			let height =
+				usize::from(chunks[0].height.saturating_sub(2));
+			self.scroll.update_no_selection(self.rows.len(), height);
+
+			let width = self.lineno_width();
+
+			f.render_widget(Clear, rect);
+
+			self.draw_pane(
+				f,
+				chunks[0],
+				&strings::compare_workdir_revision(
+					&self.path,
+					&self.commit,
+				),
+				self.get_text(true, height, width),
+			);
+			self.draw_pane(
+				f,
+				chunks[1],
+				strings::COMPARE_WORKDIR_CURRENT,
+				self.get_text(false, height, width),
+			);
+
+			self.scroll.draw(f, chunks[1], &self.theme);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for CompareWorkdirComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					!self.rows.is_empty(),
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = ev {
+				if key == self.key_config.exit_popup {
+					self.hide();
+				} else if key == self.key_config.move_up {
+					self.scroll.move_top(ScrollType::Up);
+				} else if key == self.key_config.move_down {
+					self.scroll.move_top(ScrollType::Down);
+				} else if key == self.key_config.shift_up
+					|| key == self.key_config.home
+				{
+					self.scroll.move_top(ScrollType::Home);
+				} else if key == self.key_config.shift_down
+					|| key == self.key_config.end
+				{
+					self.scroll.move_top(ScrollType::End);
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use asyncgit::sync::diff::{DiffLinePosition, Hunk};
+
+	fn line(
+		line_type: DiffLineType,
+		old: Option<u32>,
+		new: Option<u32>,
+	) -> DiffLine {
+		DiffLine {
+			content: "".into(),
+			line_type,
+			position: DiffLinePosition {
+				old_lineno: old,
+				new_lineno: new,
+			},
+		}
+	}
+
+	#[test]
+	fn test_aligned_rows() {
+		let diff = FileDiff {
+			hunks: vec![Hunk {
+				header_hash: 0,
+				split_from: None,
+				lines: vec![
+					line(DiffLineType::Header, None, None),
+					line(DiffLineType::None, Some(1), Some(1)),
+					line(DiffLineType::Delete, Some(2), None),
+					line(DiffLineType::Delete, Some(3), None),
+					line(DiffLineType::Add, None, Some(2)),
+					line(DiffLineType::None, Some(4), Some(3)),
+					line(DiffLineType::Add, None, Some(4)),
+				],
+			}],
+			..FileDiff::default()
+		};
+
+		let rows = aligned_rows(&diff)
+			.iter()
+			.map(|(left, right)| {
+				(
+					left.as_ref().map(|line| line.lineno),
+					right.as_ref().map(|line| line.lineno),
+				)
+			})
+			.collect::<Vec<_>>();
+
+		assert_eq!(
+			rows,
+			vec![
+				(Some(1), Some(1)),
+				(Some(2), Some(2)),
+				(Some(3), None),
+				(Some(4), Some(3)),
+				(None, Some(4)),
+			]
+		);
+	}
+}
@@ -0,0 +1,462 @@
+use super::{
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{Action, InternalEvent, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+	sync::{self, utils::repo_work_dir, ConflictSide},
+	CWD,
+};
+use crossterm::event::Event;
+use std::{cell::Cell, fs, io, path::Path};
+use tui::{
+	backend::Backend,
+	layout::{Constraint, Direction, Layout, Rect},
+	text::{Span, Spans},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// versions of a conflicted file, in the order they are shown
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Pane {
+	/// working copy with the conflict markers
+	Merged,
+	Base,
+	Ours,
+	Theirs,
+}
+
+const PANES: [Pane; 4] =
+	[Pane::Merged, Pane::Base, Pane::Ours, Pane::Theirs];
+
+/// content of one pane
+#[derive(Debug, PartialEq)]
+enum PaneText {
+	Lines(Vec<String>),
+	Binary,
+	/// the version does not exist, why is shown instead
+	Missing(&'static str),
+}
+
+impl PaneText {
+	fn new(content: Option<Vec<u8>>, missing: &'static str) -> Self {
+		match content {
+			None => Self::Missing(missing),
+			Some(content) if content.contains(&0) => Self::Binary,
+			Some(content) => Self::Lines(
+				String::from_utf8_lossy(&content)
+					.lines()
+					.map(String::from)
+					.collect(),
+			),
+		}
+	}
+
+	fn len(&self) -> usize {
+		match self {
+			Self::Lines(lines) => lines.len(),
+			_ => 0,
+		}
+	}
+}
+
+fn is_conflict_marker(line: &str) -> bool {
+	["<<<<<<<", "|||||||", "=======", ">>>>>>>"]
+		.iter()
+		.any(|marker| line.starts_with(marker))
+}
+
+/// a conflicted file of a stash apply or merge: the working copy with
+/// the markers next to base, ours and theirs from the index
+pub struct ConflictPopup {
+	visible: bool,
+	path: String,
+	/// in the order of `PANES`
+	panes: Vec<PaneText>,
+	pane: usize,
+	top: usize,
+	/// visible lines as of the last draw
+	height: Cell<usize>,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl ConflictPopup {
+	///
+	pub fn new(
+		queue: &Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			visible: false,
+			path: String::new(),
+			panes: Vec::new(),
+			pane: 0,
+			top: 0,
+			height: Cell::new(0),
+			queue: queue.clone(),
+			theme,
+			key_config,
+		}
+	}
+
+	/// shows the conflicted `path`, starting with the merged file
+	pub fn open(&mut self, path: String) -> Result<()> {
+		let stages = sync::conflict_stages(CWD, &path)?;
+
+		let merged = match fs::read(
+			Path::new(&repo_work_dir(CWD)?).join(&path),
+		) {
+			Ok(content) => Some(content),
+			Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+			Err(e) => return Err(e.into()),
+		};
+
+		self.panes = vec![
+			PaneText::new(merged, strings::CONFLICT_MERGED_DELETED),
+			PaneText::new(
+				stages.base,
+				strings::CONFLICT_BASE_MISSING,
+			),
+			PaneText::new(
+				stages.ours,
+				strings::CONFLICT_DELETED_BY_US,
+			),
+			PaneText::new(
+				stages.theirs,
+				strings::CONFLICT_DELETED_BY_THEM,
+			),
+		];
+		self.path = path;
+		self.pane = 0;
+		self.top = 0;
+
+		self.show()
+	}
+
+	fn current(&self) -> Option<&PaneText> {
+		self.panes.get(self.pane)
+	}
+
+	fn switch_pane(&mut self, next: bool) {
+		self.pane = if next {
+			(self.pane + 1) % PANES.len()
+		} else {
+			(self.pane + PANES.len() - 1) % PANES.len()
+		};
+		self.top = 0;
+	}
+
+	fn scroll_to(&mut self, top: usize) {
+		let len = self.current().map_or(0, PaneText::len);
+		self.top = top.min(len.saturating_sub(self.height.get()));
+	}
+
+	fn scroll_by(&mut self, lines: usize, up: bool) {
+		self.scroll_to(if up {
+			self.top.saturating_sub(lines)
+		} else {
+			self.top.saturating_add(lines)
+		});
+	}
+
+	fn take_side(&self, side: ConflictSide) {
+		self.queue.push(InternalEvent::ConfirmAction(
+			Action::ResolveConflict(self.path.clone(), side),
+		));
+	}
+
+	fn get_tabs(&self) -> Spans {
+		Spans::from(
+			strings::CONFLICT_PANES
+				.iter()
+				.enumerate()
+				.flat_map(|(idx, name)| {
+					vec![
+						Span::styled(
+							format!(" {} ", name),
+							self.theme.tab(idx == self.pane),
+						),
+						Span::raw(" "),
+					]
+				})
+				.collect::<Vec<_>>(),
+		)
+	}
+
+	fn get_text(&self, height: usize) -> Vec<Spans> {
+		let lines = match self.current() {
+			Some(PaneText::Lines(lines)) => lines,
+			Some(PaneText::Binary) => {
+				return vec![Spans::from(Span::styled(
+					strings::FILE_VIEW_BINARY,
+					self.theme.text(false, false),
+				))]
+			}
+			Some(PaneText::Missing(why)) => {
+				return vec![Spans::from(Span::styled(
+					format!("({})", why),
+					self.theme.text_warning(),
+				))]
+			}
+			None => return Vec::new(),
+		};
+
+		let end = self.top.saturating_add(height).min(lines.len());
+		let width = end.to_string().len();
+		let merged = PANES[self.pane] == Pane::Merged;
+
+		lines[self.top..end]
+			.iter()
+			.enumerate()
+			.map(|(idx, line)| {
+				let style = if merged && is_conflict_marker(line) {
+					self.theme.text_warning()
+				} else {
+					self.theme.text(true, false)
+				};
+
+				Spans::from(vec![
+					Span::styled(
+						format!(
+							"{:>w$} ",
+							self.top + idx + 1,
+							w = width
+						),
+						self.theme.text(false, false),
+					),
+					Span::styled(line.clone(), style),
+				])
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for ConflictPopup {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		if self.is_visible() {
+			f.render_widget(Clear, rect);
+
+			let block = Block::default()
+				.title(Span::styled(
+					strings::conflict_title(&self.path),
+					self.theme.title(true),
+				))
+				.borders(Borders::ALL)
+				.border_style(self.theme.block(true));
+			let inner = block.inner(rect);
+			f.render_widget(block, rect);
+
+			let chunks = Layout::default()
+				.direction(Direction::Vertical)
+				.constraints(
+					[Constraint::Length(2), Constraint::Min(1)]
+						.as_ref(),
+				)
+				.split(inner);
+
+			let height = usize::from(chunks[1].height);
+			self.height.set(height);
+
+			f.render_widget(
+				Paragraph::new(self.get_tabs()),
+				chunks[0],
+			);
+			f.render_widget(
+				Paragraph::new(self.get_text(height)),
+				chunks[1],
+			);
+
+			ui::draw_scrollbar(
+				f,
+				rect,
+				&self.theme,
+				self.current()
+					.map_or(0, PaneText::len)
+					.saturating_sub(height),
+				self.top,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for ConflictPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(CommandInfo::new(
+				strings::commands::conflict_switch_pane(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				self.current().map_or(0, PaneText::len) > 0,
+				true,
+			));
+			for side in &[ConflictSide::Ours, ConflictSide::Theirs] {
+				out.push(CommandInfo::new(
+					strings::commands::conflict_take_side(
+						&self.key_config,
+						*side,
+					),
+					true,
+					true,
+				));
+			}
+			out.push(CommandInfo::new(
+				strings::commands::conflict_mergetool(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = ev {
+				if key == self.key_config.exit_popup {
+					self.hide();
+				} else if key == self.key_config.tab_toggle
+					|| key == self.key_config.focus_right
+				{
+					self.switch_pane(true);
+				} else if key == self.key_config.tab_toggle_reverse
+					|| key == self.key_config.focus_left
+				{
+					self.switch_pane(false);
+				} else if key == self.key_config.move_up {
+					self.scroll_by(1, true);
+				} else if key == self.key_config.move_down {
+					self.scroll_by(1, false);
+				} else if key == self.key_config.page_up {
+					self.scroll_by(self.height.get(), true);
+				} else if key == self.key_config.page_down {
+					self.scroll_by(self.height.get(), false);
+				} else if key == self.key_config.shift_up
+					|| key == self.key_config.home
+				{
+					self.scroll_to(0);
+				} else if key == self.key_config.shift_down
+					|| key == self.key_config.end
+				{
+					self.scroll_to(usize::MAX);
+				} else if key == self.key_config.conflict_take_ours {
+					self.take_side(ConflictSide::Ours);
+				} else if key == self.key_config.conflict_take_theirs
+				{
+					self.take_side(ConflictSide::Theirs);
+				} else if key == self.key_config.conflict_mergetool {
+					self.queue.push(InternalEvent::OpenMergeTool(
+						self.path.clone(),
+					));
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+		self.panes.clear();
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_pane_text() {
+		assert_eq!(
+			PaneText::new(None, strings::CONFLICT_DELETED_BY_US),
+			PaneText::Missing(strings::CONFLICT_DELETED_BY_US)
+		);
+		assert_eq!(
+			PaneText::new(Some(b"a\0b".to_vec()), ""),
+			PaneText::Binary
+		);
+		assert_eq!(
+			PaneText::new(Some(b"a\nb\n".to_vec()), ""),
+			PaneText::Lines(vec![
+				String::from("a"),
+				String::from("b")
+			])
+		);
+	}
+
+	#[test]
+	fn test_conflict_markers() {
+		assert!(is_conflict_marker("<<<<<<< HEAD"));
+		assert!(is_conflict_marker("======="));
+		assert!(is_conflict_marker(">>>>>>> stash"));
+		assert!(!is_conflict_marker("== x"));
+	}
+
+	#[test]
+	fn test_missing_side_placeholder() {
+		let mut popup = ConflictPopup::new(
+			&Queue::new(),
+			SharedTheme::default(),
+			SharedKeyConfig::default(),
+		);
+		popup.panes = vec![
+			PaneText::Lines(vec![String::from("<<<<<<< HEAD")]),
+			PaneText::Lines(Vec::new()),
+			PaneText::Lines(Vec::new()),
+			PaneText::new(None, strings::CONFLICT_DELETED_BY_THEM),
+		];
+
+		popup.switch_pane(false);
+		assert_eq!(PANES[popup.pane], Pane::Theirs);
+
+		let text = popup.get_text(10);
+		assert_eq!(text.len(), 1);
+		assert_eq!(text[0].0[0].content, "(deleted by them)");
+	}
+}
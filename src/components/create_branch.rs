@@ -1,5 +1,6 @@
 use super::{
-	textinput::TextInputComponent, visibility_blocking,
+	textinput::TextInputComponent,
+	utils::branch_name::BranchNameCheck, visibility_blocking,
 	CommandBlocking, CommandInfo, Component, DrawableComponent,
 	EventState,
 };
@@ -12,13 +13,12 @@ use crate::{
 use anyhow::Result;
 use asyncgit::{sync, CWD};
 use crossterm::event::Event;
-use easy_cast::Cast;
-use tui::{
-	backend::Backend, layout::Rect, widgets::Paragraph, Frame,
-};
+use tui::{backend::Backend, layout::Rect, Frame};
 
 pub struct CreateBranchComponent {
 	input: TextInputComponent,
+	/// create a branch without history like `git checkout --orphan`
+	orphan: bool,
 	queue: Queue,
 	key_config: SharedKeyConfig,
 	theme: SharedTheme,
@@ -32,7 +32,12 @@ impl DrawableComponent for CreateBranchComponent {
 	) -> Result<()> {
 		if self.is_visible() {
 			self.input.draw(f, rect)?;
-			self.draw_warnings(f);
+			self.name_check().draw(
+				f,
+				self.input.get_area(),
+				&self.theme,
+				&self.key_config,
+			);
 		}
 
 		Ok(())
@@ -48,13 +53,29 @@ impl Component for CreateBranchComponent {
 		if self.is_visible() || force_all {
 			self.input.commands(out, force_all);
 
+			let check = self.name_check();
 			out.push(CommandInfo::new(
 				strings::commands::create_branch_confirm_msg(
 					&self.key_config,
 				),
+				check.is_valid(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::create_branch_toggle_orphan(
+					&self.key_config,
+					self.orphan,
+				),
 				true,
 				true,
 			));
+			out.push(CommandInfo::new(
+				strings::commands::branch_name_suggestion(
+					&self.key_config,
+				),
+				check.suggestion().is_some(),
+				true,
+			));
 		}
 
 		visibility_blocking(self)
@@ -62,13 +83,27 @@ impl Component for CreateBranchComponent {
 
 	fn event(&mut self, ev: Event) -> Result<EventState> {
 		if self.is_visible() {
+			if let Event::Key(e) = ev {
+				if e == self.key_config.create_branch_orphan {
+					self.set_orphan(!self.orphan);
+					return Ok(EventState::Consumed);
+				}
+			}
+
 			if self.input.event(ev)?.is_consumed() {
 				return Ok(EventState::Consumed);
 			}
 
 			if let Event::Key(e) = ev {
-				if e == self.key_config.enter {
+				let check = self.name_check();
+				if e == self.key_config.enter && check.is_valid() {
 					self.create_branch();
+				} else if e == self.key_config.branch_name_suggestion
+				{
+					if let Some(suggestion) = check.suggestion() {
+						self.input
+							.replace_text(suggestion.to_string());
+					}
 				}
 
 				return Ok(EventState::Consumed);
@@ -77,6 +112,10 @@ impl Component for CreateBranchComponent {
 		Ok(EventState::NotConsumed)
 	}
 
+	fn paste(&mut self, text: &str) -> Result<EventState> {
+		self.input.paste(text)
+	}
+
 	fn is_visible(&self) -> bool {
 		self.input.is_visible()
 	}
@@ -90,6 +129,10 @@ impl Component for CreateBranchComponent {
 
 		Ok(())
 	}
+
+	fn on_resize(&self) {
+		self.input.on_resize();
+	}
 }
 
 impl CreateBranchComponent {
@@ -104,10 +147,11 @@ impl CreateBranchComponent {
 			input: TextInputComponent::new(
 				theme.clone(),
 				key_config.clone(),
-				&strings::create_branch_popup_title(&key_config),
+				&strings::create_branch_popup_title(false),
 				&strings::create_branch_popup_msg(&key_config),
 				true,
 			),
+			orphan: false,
 			theme,
 			key_config,
 		}
@@ -115,22 +159,39 @@ impl CreateBranchComponent {
 
 	///
 	pub fn open(&mut self) -> Result<()> {
+		self.set_orphan(false);
 		self.show()?;
 
 		Ok(())
 	}
 
+	fn set_orphan(&mut self, orphan: bool) {
+		self.orphan = orphan;
+		self.input
+			.set_title(strings::create_branch_popup_title(orphan));
+	}
+
 	///
 	pub fn create_branch(&mut self) {
-		let res = sync::create_branch(CWD, self.input.get_text());
+		let res = if self.orphan {
+			sync::create_orphan_branch(CWD, self.input.get_text())
+		} else {
+			sync::create_branch(CWD, self.input.get_text())
+		};
 
 		self.input.clear();
 		self.hide();
 
 		match res {
 			Ok(_) => {
+				// an orphan branch changes what HEAD and thus the
+				// log and the staged changes are relative to
 				self.queue.push(InternalEvent::Update(
-					NeedsUpdate::BRANCHES,
+					if self.orphan {
+						NeedsUpdate::ALL
+					} else {
+						NeedsUpdate::BRANCHES
+					},
 				));
 			}
 			Err(e) => {
@@ -142,34 +203,7 @@ impl CreateBranchComponent {
 		}
 	}
 
-	fn draw_warnings<B: Backend>(&self, f: &mut Frame<B>) {
-		let current_text = self.input.get_text();
-
-		if !current_text.is_empty() {
-			let valid = sync::validate_branch_name(current_text)
-				.unwrap_or_default();
-
-			if !valid {
-				let msg = strings::branch_name_invalid();
-				let msg_length: u16 = msg.len().cast();
-				let w = Paragraph::new(msg)
-					.style(self.theme.text_danger());
-
-				let rect = {
-					let mut rect = self.input.get_area();
-					rect.y += rect.height.saturating_sub(1);
-					rect.height = 1;
-					let offset =
-						rect.width.saturating_sub(msg_length + 1);
-					rect.width =
-						rect.width.saturating_sub(offset + 1);
-					rect.x += offset;
-
-					rect
-				};
-
-				f.render_widget(w, rect);
-			}
-		}
+	fn name_check(&self) -> BranchNameCheck {
+		BranchNameCheck::new(self.input.get_text())
 	}
 }
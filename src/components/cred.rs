@@ -147,6 +147,17 @@ impl Component for CredComponent {
 		Ok(EventState::NotConsumed)
 	}
 
+	fn paste(&mut self, text: &str) -> Result<EventState> {
+		if self.visible
+			&& (self.input_username.paste(text)?.is_consumed()
+				|| self.input_password.paste(text)?.is_consumed())
+		{
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
 	fn is_visible(&self) -> bool {
 		self.visible
 	}
@@ -166,4 +177,9 @@ impl Component for CredComponent {
 			Ok(())
 		}
 	}
+
+	fn on_resize(&self) {
+		self.input_username.on_resize();
+		self.input_password.on_resize();
+	}
 }
@@ -1,24 +1,38 @@
 use super::{
-	utils::scroll_vertical::VerticalScroll, CommandBlocking,
-	Direction, DrawableComponent, ScrollType,
+	utils::{
+		diff_render::{render_line, trailing_whitespace, DiffRender},
+		hunk_text::{hunk_text, HunkCopy},
+		scroll_vertical::VerticalScroll,
+		text_search::TextSearch,
+	},
+	with_index_snapshot, CommandBlocking, Direction,
+	DrawableComponent, InputType, ScrollType, SharedOptions,
+	TextInputComponent,
 };
 use crate::{
 	components::{CommandInfo, Component, EventState},
 	keys::SharedKeyConfig,
-	queue::{Action, InternalEvent, NeedsUpdate, Queue, ResetItem},
-	string_utils::tabs_to_spaces,
+	messages::Severity,
+	queue::{
+		Action, FileLine, InternalEvent, NeedsUpdate, Queue,
+		ResetItem,
+	},
 	strings, try_or_popup,
-	ui::style::SharedTheme,
+	ui::{self, style::SharedTheme},
 };
 use anyhow::Result;
 use asyncgit::{
 	hash,
-	sync::{self, diff::DiffLinePosition},
+	sync::{
+		self,
+		diff::{DiffLinePosition, Hunk, LfsChange},
+		BlameLine, CommitId, LfsPointer, MovedBlock,
+	},
 	DiffLine, DiffLineType, FileDiff, CWD,
 };
 use bytesize::ByteSize;
 use crossterm::event::Event;
-use std::{borrow::Cow, cell::Cell, cmp, path::Path};
+use std::{borrow::Cow, cell::Cell, cmp, ops::Range, path::Path};
 use tui::{
 	backend::Backend,
 	layout::Rect,
@@ -28,9 +42,23 @@ use tui::{
 	Frame,
 };
 
+/// commits an immutable diff is between, to blame its lines
+#[derive(Clone, Copy)]
+pub enum DiffCommits {
+	/// changes of a commit against its first parent
+	Commit(CommitId),
+	/// changes from the first commit to the second
+	Compare(CommitId, CommitId),
+}
+
+/// enough of the sha256 to tell lfs objects apart
+const LFS_OID_LEN: usize = 12;
+
 #[derive(Default)]
 struct Current {
 	path: String,
+	/// set if `path` is staged as a rename
+	old_path: Option<String>,
 	is_stage: bool,
 	hash: u64,
 }
@@ -98,12 +126,54 @@ impl Selection {
 	}
 }
 
+/// where the selection goes once the next file is loaded
+#[derive(Clone, Copy)]
+enum PendingSelection {
+	LastHunk,
+	Line(usize),
+	/// line number in the old file for removed lines, else the new
+	FileLine {
+		lineno: u32,
+		removed: bool,
+	},
+}
+
+/// a line of the other side of a moved block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MovedLine {
+	pub path: String,
+	/// see `PendingSelection::FileLine`
+	pub lineno: u32,
+	pub removed: bool,
+}
+
+/// stop looking for more once reached, huge diffs stay responsive
+const MAX_SEARCH_MATCHES: usize = 10_000;
+
+struct SearchMatch {
+	line: usize,
+	/// bytes in the rendered line content
+	range: Range<usize>,
+}
+
+struct DiffSearch {
+	search: TextSearch,
+	matches: Vec<SearchMatch>,
+	/// `MAX_SEARCH_MATCHES` was hit
+	truncated: bool,
+	active: Option<usize>,
+}
+
 ///
 pub struct DiffComponent {
 	diff: Option<FileDiff>,
+	/// first line of each hunk, the hunk of a line is looked up in
+	/// here instead of walking the (possibly huge) diff
+	hunk_starts: Vec<usize>,
 	pending: bool,
 	selection: Selection,
 	selected_hunk: Option<usize>,
+	pending_selection: Option<PendingSelection>,
 	current_size: Cell<(u16, u16)>,
 	focused: bool,
 	current: Current,
@@ -111,7 +181,20 @@ pub struct DiffComponent {
 	queue: Queue,
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
+	options: SharedOptions,
 	is_immutable: bool,
+	search: Option<DiffSearch>,
+	search_input: TextInputComponent,
+	/// line ranges of the hunks that got split, on the side
+	/// (un)staging leaves as it is, so what remains of them after
+	/// staging a part is split again
+	split: Vec<(u32, u32)>,
+	/// raw lfs pointer file diff instead of the object it stands for
+	lfs_pointer: bool,
+	/// `None` for changes of the working tree or the index
+	commits: Option<DiffCommits>,
+	/// blocks the shown commit moves between files
+	moved: Vec<MovedBlock>,
 }
 
 impl DiffComponent {
@@ -120,29 +203,67 @@ impl DiffComponent {
 		queue: Queue,
 		theme: SharedTheme,
 		key_config: SharedKeyConfig,
+		options: SharedOptions,
 		is_immutable: bool,
 	) -> Self {
+		let mut search_input = TextInputComponent::new(
+			theme.clone(),
+			key_config.clone(),
+			"",
+			strings::DIFF_SEARCH_HINT,
+			false,
+		)
+		.with_input_type(InputType::Singleline);
+		search_input.embed();
+
 		Self {
 			focused: false,
 			queue,
 			current: Current::default(),
 			pending: false,
 			selected_hunk: None,
+			pending_selection: None,
 			diff: None,
+			hunk_starts: Vec::new(),
 			current_size: Cell::new((0, 0)),
 			selection: Selection::Single(0),
 			scroll: VerticalScroll::new(),
 			theme,
 			key_config,
+			options,
 			is_immutable,
+			search: None,
+			search_input,
+			split: Vec::new(),
+			lfs_pointer: false,
+			commits: None,
+			moved: Vec::new(),
 		}
 	}
 	///
 	fn can_scroll(&self) -> bool {
+		self.lines_count() > 1
+	}
+	/// the lfs object a pointer file diff stands for, unless the raw
+	/// pointer is asked for
+	fn lfs_object(&self) -> Option<&LfsChange> {
 		self.diff
 			.as_ref()
-			.map(|diff| diff.lines > 1)
-			.unwrap_or_default()
+			.and_then(|diff| diff.lfs_change.as_ref())
+			.filter(|_| !self.lfs_pointer)
+	}
+	/// commits the diff shown next is between, see `DiffCommits`
+	pub fn set_commits(&mut self, commits: Option<DiffCommits>) {
+		self.commits = commits;
+	}
+	/// blocks of the commit shown moved, found after its diff is
+	/// shown already
+	pub fn set_moved(&mut self, moved: Vec<MovedBlock>) {
+		self.moved = moved;
+	}
+	///
+	pub fn has_moved(&self) -> bool {
+		!self.moved.is_empty()
 	}
 	///
 	pub fn current(&self) -> (String, bool) {
@@ -152,15 +273,19 @@ impl DiffComponent {
 	pub fn clear(&mut self, pending: bool) {
 		self.current = Current::default();
 		self.diff = None;
+		self.hunk_starts.clear();
 		self.scroll.reset();
 		self.selection = Selection::Single(0);
 		self.selected_hunk = None;
 		self.pending = pending;
+		self.split.clear();
+		self.find_matches();
 	}
 	///
 	pub fn update(
 		&mut self,
 		path: String,
+		old_path: Option<String>,
 		is_stage: bool,
 		diff: FileDiff,
 	) {
@@ -171,18 +296,48 @@ impl DiffComponent {
 		if self.current.hash != hash {
 			let reset_selection = self.current.path != path;
 
+			if reset_selection || self.current.is_stage != is_stage {
+				self.split.clear();
+			}
+
 			self.current = Current {
 				path,
+				old_path,
 				is_stage,
 				hash,
 			};
 
-			self.diff = Some(diff);
+			self.diff = Some(self.apply_split(diff));
+			self.hunk_starts = self
+				.diff
+				.as_ref()
+				.map(Self::hunk_starts)
+				.unwrap_or_default();
 
 			if reset_selection {
+				self.lfs_pointer = false;
 				self.scroll.reset();
 				self.selection = Selection::Single(0);
 				self.update_selection(0);
+
+				match self.pending_selection.take() {
+					Some(PendingSelection::LastHunk) => {
+						let last = self.hunk_starts.last().copied();
+						self.update_selection(
+							last.unwrap_or_default(),
+						);
+					}
+					Some(PendingSelection::Line(line)) => {
+						self.update_selection(line);
+					}
+					Some(PendingSelection::FileLine {
+						lineno,
+						removed,
+					}) => {
+						self.select_file_line(lineno, removed);
+					}
+					None => (),
+				}
 			} else {
 				let old_selection = match self.selection {
 					Selection::Single(line) => line,
@@ -190,9 +345,112 @@ impl DiffComponent {
 				};
 				self.update_selection(old_selection);
 			}
+
+			self.find_matches();
+		}
+	}
+
+	/// typing into the search input
+	pub fn is_typing(&self) -> bool {
+		self.search_input.is_visible()
+	}
+
+	fn confirm_search(&mut self) {
+		self.search_input.hide();
+
+		self.search = TextSearch::new(self.search_input.get_text())
+			.map(|search| DiffSearch {
+				search,
+				matches: Vec::new(),
+				truncated: false,
+				active: None,
+			});
+
+		self.find_matches();
+		self.move_to_match(true);
+	}
+
+	/// matches in the rendered content of all lines
+	fn find_matches(&mut self) {
+		if let Some(search) = &mut self.search {
+			search.matches.clear();
+			search.truncated = false;
+			search.active = None;
+
+			let lines = self
+				.diff
+				.iter()
+				.flat_map(|diff| diff.hunks.iter())
+				.flat_map(|hunk| hunk.lines.iter());
+
+			// on the raw content, tabs are expanded when drawing
+			'lines: for (line_idx, line) in lines.enumerate() {
+				for range in search.search.find_all(&line.content) {
+					if search.matches.len() >= MAX_SEARCH_MATCHES {
+						search.truncated = true;
+						break 'lines;
+					}
+
+					search.matches.push(SearchMatch {
+						line: line_idx,
+						range,
+					});
+				}
+			}
 		}
 	}
 
+	/// moves to the next (or previous) match, relative to the active
+	/// one if it is still selected or to the selection otherwise
+	fn move_to_match(&mut self, next: bool) {
+		let cursor = self.selection.get_end();
+
+		let target = if let Some(search) = &mut self.search {
+			let count = search.matches.len();
+			if count == 0 {
+				return;
+			}
+
+			let active = search
+				.active
+				.filter(|idx| search.matches[*idx].line == cursor);
+
+			let idx = match (active, next) {
+				(Some(idx), true) => (idx + 1) % count,
+				(Some(idx), false) => (idx + count - 1) % count,
+				(None, true) => search
+					.matches
+					.iter()
+					.position(|m| m.line >= cursor)
+					.unwrap_or(0),
+				(None, false) => search
+					.matches
+					.iter()
+					.rposition(|m| m.line <= cursor)
+					.unwrap_or(count - 1),
+			};
+
+			search.active = Some(idx);
+			search.matches[idx].line
+		} else {
+			return;
+		};
+
+		self.update_selection(target);
+		self.scroll
+			.center(target, usize::from(self.current_size.get().1));
+	}
+
+	fn search_title(&self) -> Option<String> {
+		self.search.as_ref().map(|search| {
+			strings::diff_search_matches(
+				search.active,
+				search.matches.len(),
+				search.truncated,
+			)
+		})
+	}
+
 	fn move_selection(&mut self, move_type: ScrollType) {
 		if let Some(diff) = &self.diff {
 			let max = diff.lines.saturating_sub(1) as usize;
@@ -224,17 +482,242 @@ impl DiffComponent {
 		}
 	}
 
+	/// moves the selection to the start of the next (or previous)
+	/// hunk, returns `false` if there is none in that direction
+	fn move_hunk(&mut self, next: bool) -> bool {
+		let cursor = self.selection.get_end();
+		let starts = self.hunk_starts.iter().copied();
+
+		let target = if next {
+			starts.clone().find(|start| *start > cursor)
+		} else {
+			starts.rev().find(|start| *start < cursor)
+		};
+
+		if let Some(target) = target {
+			self.update_selection(target);
+			return true;
+		}
+
+		false
+	}
+
+	/// line range of `hunk` on the side (un)staging does not change
+	fn split_range(
+		hunk: &Hunk,
+		is_stage: bool,
+	) -> Option<(u32, u32)> {
+		let lines = hunk.lines.iter().filter_map(|line| {
+			if is_stage {
+				line.position.old_lineno
+			} else {
+				line.position.new_lineno
+			}
+		});
+
+		Some((lines.clone().min()?, lines.max()?))
+	}
+
+	/// splits the hunks of `diff` within a range split before
+	fn apply_split(&self, mut diff: FileDiff) -> FileDiff {
+		if self.split.is_empty() {
+			return diff;
+		}
+
+		let is_stage = self.is_stage();
+		diff.hunks = diff
+			.hunks
+			.into_iter()
+			.flat_map(|hunk| {
+				let split = Self::split_range(&hunk, is_stage)
+					.map_or(false, |(start, end)| {
+						self.split.iter().any(|range| {
+							range.0 <= start && end <= range.1
+						})
+					});
+
+				split
+					.then(|| sync::diff::split_hunk(&hunk))
+					.flatten()
+					.unwrap_or_else(|| vec![hunk])
+			})
+			.collect();
+		diff.lines =
+			diff.hunks.iter().map(|hunk| hunk.lines.len()).sum();
+
+		diff
+	}
+
+	fn can_split(&self) -> bool {
+		!self.is_immutable
+			&& self.selected_hunk.is_some()
+			&& self.current.old_path.is_none()
+			&& self
+				.diff
+				.as_ref()
+				.map_or(false, |diff| !diff.untracked)
+	}
+
+	/// splits the selected hunk at the context lines between its
+	/// changes, to stage the parts one by one
+	fn split_hunk(&mut self) {
+		let (diff, idx) = match (&mut self.diff, self.selected_hunk) {
+			(Some(diff), Some(idx)) => (diff, idx),
+			_ => return,
+		};
+
+		match sync::diff::split_hunk(&diff.hunks[idx]) {
+			Some(parts) => {
+				if let Some(range) = Self::split_range(
+					&diff.hunks[idx],
+					self.current.is_stage,
+				) {
+					self.split.push(range);
+				}
+
+				diff.hunks.splice(idx..=idx, parts);
+				diff.lines = diff
+					.hunks
+					.iter()
+					.map(|hunk| hunk.lines.len())
+					.sum();
+
+				self.hunk_starts = Self::hunk_starts(diff);
+				let start = self.hunk_starts[idx];
+				self.update_selection(start);
+				self.find_matches();
+			}
+			None => self.queue.push(InternalEvent::ShowToast(
+				Severity::Info,
+				strings::toast_cannot_split_hunk(),
+			)),
+		}
+	}
+
+	/// first line of each hunk
+	fn hunk_starts(diff: &FileDiff) -> Vec<usize> {
+		diff.hunks
+			.iter()
+			.scan(0_usize, |line, hunk| {
+				let start = *line;
+				*line += hunk.lines.len();
+				Some(start)
+			})
+			.collect()
+	}
+
+	/// the owner moved on to the previous file, start at its end
+	pub fn select_last_hunk_on_update(&mut self) {
+		self.pending_selection = Some(PendingSelection::LastHunk);
+	}
+
+	/// selects `line` of the diff of `path`, right away if it is
+	/// shown already or else once it is loaded
+	pub fn select_line(&mut self, path: &str, line: usize) {
+		if self.diff.is_some() && self.current.path == path {
+			self.update_selection(line);
+		} else {
+			self.pending_selection =
+				Some(PendingSelection::Line(line));
+		}
+	}
+
+	/// selects the line of the moved block `target` is in, right away
+	/// if its file is shown already or else once it is loaded
+	pub fn select_moved_line(&mut self, target: &MovedLine) {
+		if self.diff.is_some() && self.current.path == target.path {
+			self.select_file_line(target.lineno, target.removed);
+		} else {
+			self.pending_selection =
+				Some(PendingSelection::FileLine {
+					lineno: target.lineno,
+					removed: target.removed,
+				});
+		}
+	}
+
+	fn select_file_line(&mut self, lineno: u32, removed: bool) {
+		let index = self.diff.as_ref().and_then(|diff| {
+			diff.hunks
+				.iter()
+				.flat_map(|hunk| hunk.lines.iter())
+				.position(|line| {
+					Self::file_lineno(line, removed) == Some(lineno)
+				})
+		});
+
+		if let Some(index) = index {
+			self.update_selection(index);
+		}
+	}
+
+	/// number of a removed line in the old file or of an added line
+	/// in the new one
+	const fn file_lineno(
+		line: &DiffLine,
+		removed: bool,
+	) -> Option<u32> {
+		match line.line_type {
+			DiffLineType::Delete if removed => {
+				line.position.old_lineno
+			}
+			DiffLineType::Add if !removed => line.position.new_lineno,
+			_ => None,
+		}
+	}
+
+	fn moved_block(&self, line: &DiffLine) -> Option<&MovedBlock> {
+		let removed = line.line_type == DiffLineType::Delete;
+		let lineno = Self::file_lineno(line, removed)?;
+
+		self.moved.iter().find(|block| {
+			let side = if removed {
+				&block.removed
+			} else {
+				&block.added
+			};
+			side.contains(&self.current.path, lineno)
+		})
+	}
+
+	/// where the selected line was moved from or to
+	pub fn moved_counterpart(&self) -> Option<MovedLine> {
+		let line = self.selected_line()?;
+		let block = self.moved_block(line)?;
+		let removed = line.line_type == DiffLineType::Delete;
+		let (side, other) = if removed {
+			(&block.removed, &block.added)
+		} else {
+			(&block.added, &block.removed)
+		};
+		let offset = Self::file_lineno(line, removed)? - side.start;
+
+		Some(MovedLine {
+			path: other.path.clone(),
+			lineno: other.start + offset,
+			removed: !removed,
+		})
+	}
+
+	/// line the selection ends at
+	pub const fn selection_end(&self) -> usize {
+		self.selection.get_end()
+	}
+
 	fn update_selection(&mut self, new_start: usize) {
 		if let Some(diff) = &self.diff {
 			let max = diff.lines.saturating_sub(1) as usize;
 			let new_start = cmp::min(max, new_start);
 			self.selection = Selection::Single(new_start);
 			self.selected_hunk =
-				Self::find_selected_hunk(diff, new_start);
+				Self::hunk_at(&self.hunk_starts, new_start);
 		}
 	}
 
 	fn lines_count(&self) -> usize {
+		if self.lfs_object().is_some() {
+			return 0;
+		}
 		self.diff.as_ref().map_or(0, |diff| diff.lines)
 	}
 
@@ -272,33 +755,102 @@ impl DiffComponent {
 		}
 	}
 
-	fn find_selected_hunk(
-		diff: &FileDiff,
-		line_selected: usize,
-	) -> Option<usize> {
-		let mut line_cursor = 0_usize;
-		for (i, hunk) in diff.hunks.iter().enumerate() {
-			let hunk_len = hunk.lines.len();
-			let hunk_min = line_cursor;
-			let hunk_max = line_cursor + hunk_len;
+	fn copy_hunk(&self, copy: HunkCopy) {
+		let hunk = self.diff.as_ref().and_then(|diff| {
+			self.selected_hunk.and_then(|idx| diff.hunks.get(idx))
+		});
 
-			let hunk_selected =
-				hunk_min <= line_selected && hunk_max > line_selected;
+		if let Some(hunk) = hunk {
+			try_or_popup!(
+				self,
+				"copy to clipboard error:",
+				crate::clipboard::copy_string(&hunk_text(hunk, copy))
+			);
+		}
+	}
 
-			if hunk_selected {
-				return Some(i);
-			}
+	/// the last hunk starting at or before `line`
+	fn hunk_at(starts: &[usize], line: usize) -> Option<usize> {
+		Self::count_before(starts, |start| *start <= line)
+			.checked_sub(1)
+	}
 
-			line_cursor += hunk_len;
-		}
+	/// length of the prefix of the sorted `items` matching `pred`
+	fn count_before<T>(
+		items: &[T],
+		pred: impl Fn(&T) -> bool,
+	) -> usize {
+		items
+			.binary_search_by(|item| {
+				if pred(item) {
+					cmp::Ordering::Less
+				} else {
+					cmp::Ordering::Greater
+				}
+			})
+			.unwrap_or_else(|idx| idx)
+	}
 
-		None
+	fn lfs_text(&self, lfs: &LfsChange) -> Vec<Spans> {
+		let side = |pointer: Option<&LfsPointer>| {
+			pointer.map_or_else(
+				|| (String::from("-"), String::from("-")),
+				|pointer| {
+					(
+						ByteSize::b(pointer.size).to_string(),
+						pointer
+							.oid
+							.chars()
+							.take(LFS_OID_LEN)
+							.collect(),
+					)
+				},
+			)
+		};
+		let (old_size, old_oid) = side(lfs.old.as_ref());
+		let (new_size, new_oid) = side(lfs.new.as_ref());
+
+		vec![
+			Spans::from(Span::styled(
+				strings::DIFF_LFS_OBJECT,
+				self.theme.text(true, false),
+			)),
+			Spans::from(Span::styled(
+				strings::diff_lfs_change(
+					"size", &old_size, &new_size,
+				),
+				self.theme.text(false, false),
+			)),
+			Spans::from(Span::styled(
+				strings::diff_lfs_change("oid", &old_oid, &new_oid),
+				self.theme.text(false, false),
+			)),
+		]
 	}
 
 	fn get_text(&self, width: u16, height: u16) -> Vec<Spans> {
 		let mut res: Vec<Spans> = Vec::new();
 		if let Some(diff) = &self.diff {
-			if diff.hunks.is_empty() {
+			if let Some(error) = &diff.error {
+				res.push(Spans::from(Span::styled(
+					strings::diff_error(error),
+					self.theme.text_danger(),
+				)));
+			} else if let Some(link) = &diff.link_change {
+				res.push(Spans::from(Span::styled(
+					strings::diff_link_change(&link.old, &link.new),
+					self.theme.text(true, false),
+				)));
+			} else if let Some(lfs) = self.lfs_object() {
+				res.extend(self.lfs_text(lfs));
+			} else if let (true, Some(mode)) =
+				(diff.hunks.is_empty(), diff.mode_change)
+			{
+				res.push(Spans::from(Span::styled(
+					strings::diff_mode_change(mode.old, mode.new),
+					self.theme.text(true, false),
+				)));
+			} else if diff.hunks.is_empty() {
 				let is_positive = diff.size_delta >= 0;
 				let delta_byte_size =
 					ByteSize::b(diff.size_delta.abs() as u64);
@@ -338,65 +890,126 @@ impl DiffComponent {
 					Span::raw(Cow::from(")")),
 				])]);
 			} else {
-				let min = self.scroll.get_top();
-				let max = min + height as usize;
-
-				let mut line_cursor = 0_usize;
-				let mut lines_added = 0_usize;
+				res.extend(self.visible_lines(diff, width, height));
+			}
+		}
+		res
+	}
 
-				for (i, hunk) in diff.hunks.iter().enumerate() {
-					let hunk_selected = self.focused()
-						&& self
-							.selected_hunk
-							.map_or(false, |s| s == i);
+	/// styled lines of the window the diff is scrolled to only,
+	/// everything else stays raw in the hunks
+	fn visible_lines<'a>(
+		&'a self,
+		diff: &'a FileDiff,
+		width: u16,
+		height: u16,
+	) -> Vec<Spans<'a>> {
+		let top = self.scroll.get_top();
+		let end = cmp::min(top + usize::from(height), diff.lines);
 
-					if lines_added >= height as usize {
-						break;
-					}
+		let matches = self
+			.search
+			.as_ref()
+			.map_or(&[][..], |search| &search.matches[..]);
+		let active_match =
+			self.search.as_ref().and_then(|search| search.active);
+		let mut match_cursor =
+			Self::count_before(matches, |m| m.line < top);
+
+		let first = match Self::hunk_at(&self.hunk_starts, top) {
+			Some(first) => first,
+			None => return Vec::new(),
+		};
 
-					let hunk_len = hunk.lines.len();
-					let hunk_min = line_cursor;
-					let hunk_max = line_cursor + hunk_len;
+		let render = self.options.borrow().diff_render;
+		let mut res = Vec::with_capacity(end.saturating_sub(top));
+		for (i, hunk) in diff.hunks.iter().enumerate().skip(first) {
+			let hunk_start = self.hunk_starts[i];
+			if hunk_start >= end {
+				break;
+			}
 
-					if Self::hunk_visible(
-						hunk_min, hunk_max, min, max,
-					) {
-						for (i, line) in hunk.lines.iter().enumerate()
-						{
-							if line_cursor >= min
-								&& line_cursor <= max
-							{
-								res.push(Self::get_line_to_add(
-									width,
-									line,
-									self.focused()
-										&& self
-											.selection
-											.contains(line_cursor),
-									hunk_selected,
-									i == hunk_len as usize - 1,
-									&self.theme,
-								));
-								lines_added += 1;
-							}
-
-							line_cursor += 1;
-						}
-					} else {
-						line_cursor += hunk_len;
-					}
+			let hunk_selected = self.focused()
+				&& self.selected_hunk.map_or(false, |s| s == i);
+			let hunk_len = hunk.lines.len();
+			let from = top.saturating_sub(hunk_start);
+			let to = cmp::min(hunk_len, end - hunk_start);
+
+			for (idx, line) in hunk.lines[from..to].iter().enumerate()
+			{
+				let idx = from + idx;
+				let line_cursor = hunk_start + idx;
+
+				let mut highlights = Vec::new();
+				while let Some(m) = matches
+					.get(match_cursor)
+					.filter(|m| m.line == line_cursor)
+				{
+					highlights.push((
+						m.range.clone(),
+						active_match == Some(match_cursor),
+					));
+					match_cursor += 1;
 				}
+
+				res.push(Self::get_line_to_add(
+					width,
+					line,
+					self.focused()
+						&& self.selection.contains(line_cursor),
+					hunk_selected,
+					idx == hunk_len - 1,
+					self.moved_block(line).is_some(),
+					&highlights,
+					render,
+					&self.theme,
+				));
 			}
 		}
+
 		res
 	}
 
+	/// the lines `visible_lines` draws, as in a patch
+	fn plain_lines(
+		&self,
+		diff: &FileDiff,
+		height: usize,
+	) -> Vec<String> {
+		let tab_width = self.options.borrow().diff_render.tab_width;
+
+		diff.hunks
+			.iter()
+			.flat_map(|hunk| hunk.lines.iter())
+			.skip(self.scroll.get_top())
+			.take(height)
+			.map(|line| {
+				let prefix = match line.line_type {
+					DiffLineType::Add => "+",
+					DiffLineType::Delete => "-",
+					DiffLineType::Header => "",
+					DiffLineType::None => " ",
+				};
+				let text = render_line(&line.content, tab_width)
+					.into_iter()
+					.map(|(_, glyph)| glyph.to_char(false))
+					.collect::<String>();
+
+				format!("{}{}", prefix, text.trim_end())
+			})
+			.collect()
+	}
+
+	#[allow(clippy::too_many_arguments)]
 	fn get_line_to_add<'a>(
 		width: u16,
 		line: &'a DiffLine,
 		selected: bool,
 		selected_hunk: bool,
 		end_of_hunk: bool,
+		moved: bool,
+		highlights: &[(Range<usize>, bool)],
+		render: DiffRender,
 		theme: &SharedTheme,
 	) -> Spans<'a> {
 		let style = theme.diff_hunk_marker(selected_hunk);
@@ -416,49 +1029,133 @@ impl DiffComponent {
 			}
 		};
 
-		let filled = if selected {
-			// selected line
-			format!("{:w$}\n", line.content, w = width as usize)
+		let mut style = theme.diff_line(line.line_type, selected);
+		if moved {
+			style = style.patch(theme.diff_line_moved());
+		}
+
+		let mut spans = vec![left_side_of_line];
+		if theme.indicators().diff_glyphs {
+			spans.push(theme.diff_glyph(line.line_type, selected));
+		}
+
+		let changed = matches!(
+			line.line_type,
+			DiffLineType::Add | DiffLineType::Delete
+		);
+		let show_whitespace = render.show_whitespace && changed;
+
+		let glyphs = render_line(&line.content, render.tab_width);
+		let trailing = if render.trailing_whitespace
+			&& line.line_type == DiffLineType::Add
+		{
+			trailing_whitespace(&glyphs)
 		} else {
-			// weird eof missing eol line
-			format!("{}\n", line.content)
+			glyphs.len()
 		};
 
-		Spans::from(vec![
-			left_side_of_line,
-			Span::styled(
-				Cow::from(tabs_to_spaces(filled)),
-				theme.diff_line(line.line_type, selected),
-			),
-		])
-	}
+		// styled per column, highlight ranges are based on the raw
+		// content each column stems from
+		let mut text = String::new();
+		let mut text_style = style;
+		for (column, (source, glyph)) in glyphs.iter().enumerate() {
+			let mut glyph_style = style;
+			if column >= trailing {
+				glyph_style = glyph_style
+					.patch(theme.diff_trailing_whitespace());
+			}
+			if show_whitespace && glyph.is_whitespace() {
+				glyph_style =
+					glyph_style.patch(theme.diff_whitespace());
+			}
+			if let Some((_, active)) = highlights
+				.iter()
+				.find(|(range, _)| range.contains(source))
+			{
+				glyph_style =
+					glyph_style.patch(theme.search_match(*active));
+			}
 
-	const fn hunk_visible(
-		hunk_min: usize,
-		hunk_max: usize,
-		min: usize,
-		max: usize,
-	) -> bool {
-		// full overlap
-		if hunk_min <= min && hunk_max >= max {
-			return true;
+			if glyph_style != text_style && !text.is_empty() {
+				spans.push(Span::styled(
+					std::mem::take(&mut text),
+					text_style,
+				));
+			}
+			text_style = glyph_style;
+			text.push(glyph.to_char(show_whitespace));
 		}
 
-		// partly overlap
-		if (hunk_min >= min && hunk_min <= max)
-			|| (hunk_max >= min && hunk_max <= max)
-		{
-			return true;
+		if text_style != style {
+			spans.push(Span::styled(
+				std::mem::take(&mut text),
+				text_style,
+			));
 		}
 
-		false
+		if selected {
+			// selected line
+			let fill =
+				usize::from(width).saturating_sub(glyphs.len());
+			text.extend(std::iter::repeat(' ').take(fill));
+		}
+		// weird eof missing eol line
+		text.push('\n');
+
+		spans.push(Span::styled(text, style));
+
+		Spans::from(spans)
+	}
+
+	/// for all diffs, like in the options
+	fn toggle_show_whitespace(&self) {
+		let mut render = self.options.borrow().diff_render;
+		render.show_whitespace = !render.show_whitespace;
+
+		if let Err(e) =
+			self.options.borrow_mut().set_diff_render(render)
+		{
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"save diff render:\n{}",
+				e
+			)));
+		}
 	}
 
 	fn unstage_hunk(&mut self) -> Result<()> {
 		if let Some(diff) = &self.diff {
 			if let Some(hunk) = self.selected_hunk {
 				let hash = diff.hunks[hunk].header_hash;
-				sync::unstage_hunk(CWD, &self.current.path, hash)?;
+				with_index_snapshot(&self.queue, || {
+					if let Some(split_from) =
+						diff.hunks[hunk].split_from
+					{
+						sync::stage_hunk_part(
+							CWD,
+							&self.current.path,
+							true,
+							split_from,
+							hash,
+						)
+					} else if let Some(old_path) =
+						&self.current.old_path
+					{
+						sync::unstage_hunk_renamed(
+							CWD,
+							old_path,
+							&self.current.path,
+							hash,
+						)
+						.map(|_| ())
+					} else {
+						sync::unstage_hunk(
+							CWD,
+							&self.current.path,
+							hash,
+						)
+						.map(|_| ())
+					}
+				})?;
 				self.queue_update();
 			}
 		}
@@ -469,15 +1166,31 @@ impl DiffComponent {
 	fn stage_hunk(&mut self) -> Result<()> {
 		if let Some(diff) = &self.diff {
 			if let Some(hunk) = self.selected_hunk {
-				if diff.untracked {
-					sync::stage_add_file(
-						CWD,
-						Path::new(&self.current.path),
-					)?;
-				} else {
-					let hash = diff.hunks[hunk].header_hash;
-					sync::stage_hunk(CWD, &self.current.path, hash)?;
-				}
+				with_index_snapshot(&self.queue, || {
+					if diff.untracked {
+						sync::stage_add_file(
+							CWD,
+							Path::new(&self.current.path),
+						)
+					} else if let Some(split_from) =
+						diff.hunks[hunk].split_from
+					{
+						sync::stage_hunk_part(
+							CWD,
+							&self.current.path,
+							false,
+							split_from,
+							diff.hunks[hunk].header_hash,
+						)
+					} else {
+						let hash = diff.hunks[hunk].header_hash;
+						sync::stage_hunk(
+							CWD,
+							&self.current.path,
+							hash,
+						)
+					}
+				})?;
 
 				self.queue_update();
 			}
@@ -490,9 +1203,24 @@ impl DiffComponent {
 		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
 	}
 
+	/// the selected hunk is a part of a split one
+	fn is_part_selected(&self) -> bool {
+		self.diff.as_ref().map_or(false, |diff| {
+			self.selected_hunk
+				.and_then(|idx| diff.hunks.get(idx))
+				.map_or(false, |hunk| hunk.split_from.is_some())
+		})
+	}
+
 	fn reset_hunk(&self) {
 		if let Some(diff) = &self.diff {
 			if let Some(hunk) = self.selected_hunk {
+				// parts cannot be reverted on their own (yet),
+				// their lines can
+				if diff.hunks[hunk].split_from.is_some() {
+					return;
+				}
+
 				let hash = diff.hunks[hunk].header_hash;
 
 				self.queue.push(InternalEvent::ConfirmAction(
@@ -516,23 +1244,36 @@ impl DiffComponent {
 
 	fn stage_lines(&self) {
 		if let Some(diff) = &self.diff {
-			//TODO: support untracked files aswell
-			if !diff.untracked {
-				let selected_lines = self.selected_lines();
+			let selected_lines = self.selected_lines();
 
+			if diff.untracked {
+				try_or_popup!(
+					self,
+					"stage lines:",
+					with_index_snapshot(&self.queue, || {
+						sync::stage_lines_untracked(
+							CWD,
+							&self.current.path,
+							&selected_lines,
+						)
+					})
+				);
+			} else {
 				try_or_popup!(
 					self,
 					"(un)stage lines:",
-					sync::stage_lines(
-						CWD,
-						&self.current.path,
-						self.is_stage(),
-						&selected_lines,
-					)
+					with_index_snapshot(&self.queue, || {
+						sync::stage_lines(
+							CWD,
+							&self.current.path,
+							self.is_stage(),
+							&selected_lines,
+						)
+					})
 				);
-
-				self.queue_update();
 			}
+
+			self.queue_update();
 		}
 	}
 
@@ -561,11 +1302,86 @@ impl DiffComponent {
 			.unwrap_or_default()
 	}
 
+	fn selected_line(&self) -> Option<&DiffLine> {
+		self.diff
+			.as_ref()?
+			.hunks
+			.iter()
+			.flat_map(|hunk| hunk.lines.iter())
+			.nth(self.selection.get_end())
+	}
+
+	/// the selected line in the version of the file it is from:
+	/// removed lines are from the old side, all others from the new
+	fn selected_blame_line(&self) -> Option<(String, BlameLine)> {
+		let line = self.selected_line()?;
+		let removed = line.line_type == DiffLineType::Delete;
+		let lineno = if removed {
+			line.position.old_lineno
+		} else {
+			line.position.new_lineno
+		}? as usize;
+
+		let blame_line = match (self.commits, removed) {
+			(Some(DiffCommits::Commit(id)), false)
+			| (Some(DiffCommits::Compare(_, id)), false)
+			| (Some(DiffCommits::Compare(id, _)), true) => {
+				BlameLine::Commit(id, lineno)
+			}
+			(Some(DiffCommits::Commit(id)), true) => {
+				BlameLine::Parent(id, lineno)
+			}
+			(None, false) if self.is_stage() => {
+				BlameLine::Index(lineno)
+			}
+			(None, false) => BlameLine::WorkDir(lineno),
+			(None, true) if self.is_stage() => {
+				BlameLine::Head(lineno)
+			}
+			(None, true) => BlameLine::Index(lineno),
+		};
+
+		let path = if removed {
+			self.current.old_path.as_ref()
+		} else {
+			None
+		}
+		.unwrap_or(&self.current.path);
+
+		Some((path.clone(), blame_line))
+	}
+
+	/// opens the blame at the revision the selected line is from
+	fn blame_line(&self) {
+		let (path, line) = match self.selected_blame_line() {
+			Some(line) => line,
+			None => return,
+		};
+
+		match sync::blame_target(CWD, &path, line) {
+			Ok(Some((commit, line))) => {
+				self.queue.push(InternalEvent::BlameFile(FileLine {
+					path,
+					line: Some(line),
+					commit: Some(commit),
+				}));
+			}
+			Ok(None) => self.queue.push(InternalEvent::ShowToast(
+				Severity::Info,
+				strings::DIFF_LINE_NOT_COMMITTED.to_string(),
+			)),
+			Err(e) => self.queue.push(InternalEvent::ShowErrorMsg(
+				format!("blame error:\n{}", e),
+			)),
+		}
+	}
+
 	fn reset_untracked(&self) {
 		self.queue.push(InternalEvent::ConfirmAction(Action::Reset(
 			ResetItem {
 				path: self.current.path.clone(),
 				is_folder: false,
+				files: 1,
 			},
 		)));
 	}
@@ -604,12 +1420,27 @@ impl DrawableComponent for DiffComponent {
 			usize::from(current_height),
 		);
 
-		let title = format!(
+		let mut title = format!(
 			"{}{}",
 			strings::title_diff(&self.key_config),
 			self.current.path
 		);
 
+		// with hunks to show the mode change goes into the title
+		if let Some(mode) = self.diff.as_ref().and_then(|diff| {
+			diff.mode_change.filter(|_| !diff.hunks.is_empty())
+		}) {
+			title.push_str(&format!(
+				" [{}]",
+				strings::diff_mode_change(mode.old, mode.new)
+			));
+		}
+
+		if let Some(search) = self.search_title() {
+			title.push(' ');
+			title.push_str(&search);
+		}
+
 		let txt = if self.pending {
 			vec![Spans::from(vec![Span::styled(
 				Cow::from(strings::loading_text(&self.key_config)),
@@ -624,7 +1455,7 @@ impl DrawableComponent for DiffComponent {
 				Block::default()
 					.title(Span::styled(
 						title.as_str(),
-						self.theme.title(self.focused),
+						self.theme.pane_title(self.focused),
 					))
 					.borders(Borders::ALL)
 					.border_style(self.theme.block(self.focused)),
@@ -636,6 +1467,17 @@ impl DrawableComponent for DiffComponent {
 			self.scroll.draw(f, r, &self.theme);
 		}
 
+		// bottom row inside the border
+		self.search_input.draw(
+			f,
+			Rect::new(
+				r.x.saturating_add(1),
+				r.bottom().saturating_sub(2).max(r.y),
+				r.width.saturating_sub(2),
+				1.min(r.height),
+			),
+		)?;
+
 		Ok(())
 	}
 }
@@ -661,6 +1503,14 @@ impl Component for DiffComponent {
 			.hidden(),
 		);
 
+		out.push(CommandInfo::new(
+			strings::commands::diff_hunk_next_prev(&self.key_config),
+			self.diff.as_ref().map_or(false, |diff| {
+				diff.hunks.len() > 1 || self.is_immutable
+			}),
+			self.focused,
+		));
+
 		if !self.is_immutable {
 			out.push(CommandInfo::new(
 				strings::commands::diff_hunk_remove(&self.key_config),
@@ -674,9 +1524,15 @@ impl Component for DiffComponent {
 			));
 			out.push(CommandInfo::new(
 				strings::commands::diff_hunk_revert(&self.key_config),
-				self.selected_hunk.is_some(),
+				self.selected_hunk.is_some()
+					&& !self.is_part_selected(),
 				self.focused && !self.is_stage(),
 			));
+			out.push(CommandInfo::new(
+				strings::commands::diff_hunk_split(&self.key_config),
+				self.can_split(),
+				self.focused,
+			));
 			out.push(CommandInfo::new(
 				strings::commands::diff_lines_revert(
 					&self.key_config,
@@ -701,17 +1557,96 @@ impl Component for DiffComponent {
 			));
 		}
 
+		out.push(CommandInfo::new(
+			strings::commands::diff_lfs_pointer(
+				&self.key_config,
+				self.lfs_pointer,
+			),
+			true,
+			self.focused
+				&& self
+					.diff
+					.as_ref()
+					.map_or(false, |diff| diff.lfs_change.is_some()),
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::diff_show_whitespace(
+				&self.key_config,
+				self.options.borrow().diff_render.show_whitespace,
+			),
+			true,
+			self.focused && self.lines_count() > 0,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::diff_blame_line(&self.key_config),
+			self.selected_blame_line().is_some(),
+			self.focused,
+		));
+
 		out.push(CommandInfo::new(
 			strings::commands::copy(&self.key_config),
 			true,
 			self.focused,
 		));
 
+		out.push(CommandInfo::new(
+			strings::commands::diff_search(&self.key_config),
+			self.lines_count() > 0,
+			self.focused && !self.is_typing(),
+		));
+		out.push(CommandInfo::new(
+			strings::commands::diff_search_next_prev(
+				&self.key_config,
+			),
+			self.search
+				.as_ref()
+				.map_or(false, |search| !search.matches.is_empty()),
+			self.focused && self.search.is_some(),
+		));
+		out.push(CommandInfo::new(
+			strings::commands::diff_search_clear(&self.key_config),
+			true,
+			self.focused
+				&& self.search.is_some()
+				&& !self.is_typing(),
+		));
+		out.push(CommandInfo::new(
+			strings::commands::diff_search_confirm(&self.key_config),
+			true,
+			self.focused && self.is_typing(),
+		));
+
+		for text in vec![
+			strings::commands::diff_copy_hunk(&self.key_config),
+			strings::commands::diff_copy_old(&self.key_config),
+			strings::commands::diff_copy_new(&self.key_config),
+		] {
+			out.push(CommandInfo::new(
+				text,
+				true,
+				self.focused && self.selected_hunk.is_some(),
+			));
+		}
+
 		CommandBlocking::PassingOn
 	}
 
 	#[allow(clippy::cognitive_complexity)]
 	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.focused && self.is_typing() {
+			if let Event::Key(e) = ev {
+				if e == self.key_config.enter {
+					self.confirm_search();
+				} else {
+					self.search_input.event(ev)?;
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
 		if self.focused {
 			if let Event::Key(e) = ev {
 				return if e == self.key_config.move_down {
@@ -738,6 +1673,20 @@ impl Component for DiffComponent {
 				} else if e == self.key_config.page_down {
 					self.move_selection(ScrollType::PageDown);
 					Ok(EventState::Consumed)
+				} else if e == self.key_config.diff_hunk_next
+					|| e == self.key_config.diff_hunk_prev
+				{
+					let moved = self.move_hunk(
+						e == self.key_config.diff_hunk_next,
+					);
+
+					// in a commit the owner continues with the
+					// next/previous file
+					if moved || !self.is_immutable {
+						Ok(EventState::Consumed)
+					} else {
+						Ok(EventState::NotConsumed)
+					}
 				} else if e == self.key_config.enter
 					&& !self.is_immutable
 				{
@@ -760,6 +1709,11 @@ impl Component for DiffComponent {
 						}
 					}
 					Ok(EventState::Consumed)
+				} else if e == self.key_config.diff_split_hunk
+					&& self.can_split()
+				{
+					self.split_hunk();
+					Ok(EventState::Consumed)
 				} else if e == self.key_config.diff_stage_lines
 					&& !self.is_immutable
 				{
@@ -776,9 +1730,51 @@ impl Component for DiffComponent {
 						}
 					}
 					Ok(EventState::Consumed)
+				} else if e == self.key_config.diff_lfs_pointer
+					&& self.diff.as_ref().map_or(false, |diff| {
+						diff.lfs_change.is_some()
+					}) {
+					self.lfs_pointer = !self.lfs_pointer;
+					self.search = None;
+					Ok(EventState::Consumed)
+				} else if e == self.key_config.diff_show_whitespace {
+					self.toggle_show_whitespace();
+					Ok(EventState::Consumed)
+				} else if e == self.key_config.blame
+					&& self.selected_blame_line().is_some()
+				{
+					self.blame_line();
+					Ok(EventState::Consumed)
 				} else if e == self.key_config.copy {
 					self.copy_selection();
 					Ok(EventState::Consumed)
+				} else if e == self.key_config.diff_copy_hunk {
+					self.copy_hunk(HunkCopy::Unified);
+					Ok(EventState::Consumed)
+				} else if e == self.key_config.diff_copy_old {
+					self.copy_hunk(HunkCopy::Old);
+					Ok(EventState::Consumed)
+				} else if e == self.key_config.diff_copy_new {
+					self.copy_hunk(HunkCopy::New);
+					Ok(EventState::Consumed)
+				} else if e == self.key_config.diff_search
+					&& self.lines_count() > 0
+				{
+					self.search_input.show()?;
+					Ok(EventState::Consumed)
+				} else if (e == self.key_config.diff_search_next
+					|| e == self.key_config.diff_search_prev)
+					&& self.search.is_some()
+				{
+					self.move_to_match(
+						e == self.key_config.diff_search_next,
+					);
+					Ok(EventState::Consumed)
+				} else if e == self.key_config.exit_popup
+					&& self.search.is_some()
+				{
+					self.search = None;
+					Ok(EventState::Consumed)
 				} else {
 					Ok(EventState::NotConsumed)
 				};
@@ -793,5 +1789,401 @@ impl Component for DiffComponent {
 	}
 	fn focus(&mut self, focus: bool) {
 		self.focused = focus;
+		if !focus {
+			self.search_input.hide();
+		}
+	}
+
+	fn on_resize(&self) {
+		self.current_size.set((0, 0));
+	}
+
+	fn selectable_text(&self) -> Option<Vec<String>> {
+		let diff = self.diff.as_ref()?;
+		let (width, height) = self.current_size.get();
+
+		let plain = diff.hunks.is_empty()
+			|| diff.error.is_some()
+			|| diff.link_change.is_some()
+			|| self.lfs_object().is_some();
+		Some(if plain {
+			self.get_text(width, height)
+				.iter()
+				.map(ui::spans_text)
+				.collect()
+		} else {
+			self.plain_lines(diff, usize::from(height))
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::{Duration, Instant};
+
+	const LINES: usize = 100_000;
+	const HEIGHT: u16 = 40;
+
+	/// generous for debug builds, walking the whole diff on every
+	/// step (like rendering it up front did) takes way longer
+	const INITIAL_BUDGET: Duration = Duration::from_secs(1);
+	const STEP_BUDGET: Duration = Duration::from_millis(20);
+
+	/// `LINES` lines in hunks of `hunk_len` lines each
+	fn huge_diff(hunk_len: usize) -> FileDiff {
+		let hunks = (0..LINES / hunk_len)
+			.map(|hunk| Hunk {
+				header_hash: hunk as u64,
+				lines: (0..hunk_len)
+					.map(|line| DiffLine {
+						content: format!("{}:{}", hunk, line).into(),
+						line_type: match line {
+							0 => DiffLineType::Header,
+							l if l % 2 == 0 => DiffLineType::Add,
+							_ => DiffLineType::Delete,
+						},
+						position: DiffLinePosition::default(),
+					})
+					.collect(),
+				split_from: None,
+			})
+			.collect();
+
+		FileDiff {
+			hunks,
+			lines: LINES,
+			..FileDiff::default()
+		}
+	}
+
+	fn component() -> DiffComponent {
+		let mut diff = DiffComponent::new(
+			Queue::new(),
+			SharedTheme::default(),
+			SharedKeyConfig::default(),
+			SharedOptions::default(),
+			false,
+		);
+		diff.focus(true);
+		diff.current_size.set((80, HEIGHT));
+		diff
+	}
+
+	/// what `draw` does apart from the widgets
+	fn render(diff: &DiffComponent) -> Vec<Spans> {
+		diff.scroll.update(
+			diff.selection.get_end(),
+			diff.lines_count(),
+			usize::from(HEIGHT),
+		);
+		diff.get_text(82, HEIGHT)
+	}
+
+	/// line content without the hunk marker and padding
+	fn content(line: &Spans) -> String {
+		line.0[1..]
+			.iter()
+			.map(|span| span.content.as_ref())
+			.collect::<String>()
+			.trim()
+			.to_string()
+	}
+
+	#[test]
+	fn test_huge_diff_budget() {
+		for hunk_len in [LINES, 100].iter().copied() {
+			let mut diff = component();
+			let file_diff = huge_diff(hunk_len);
+
+			let start = Instant::now();
+			diff.update(String::from("lock"), None, false, file_diff);
+			let txt = render(&diff);
+			assert!(start.elapsed() < INITIAL_BUDGET);
+			assert_eq!(txt.len(), usize::from(HEIGHT));
+
+			for step in &[
+				ScrollType::End,
+				ScrollType::PageUp,
+				ScrollType::Up,
+				ScrollType::Home,
+				ScrollType::PageDown,
+			] {
+				let start = Instant::now();
+				diff.move_selection(*step);
+				render(&diff);
+				assert!(
+					start.elapsed() < STEP_BUDGET,
+					"step took {:?}",
+					start.elapsed()
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn test_visible_window() {
+		let mut diff = component();
+		diff.update(
+			String::from("lock"),
+			None,
+			false,
+			huge_diff(100),
+		);
+
+		let txt = render(&diff);
+		assert_eq!(content(&txt[0]), "0:0");
+		assert_eq!(diff.selected_hunk, Some(0));
+
+		diff.move_selection(ScrollType::End);
+		let txt = render(&diff);
+		assert_eq!(txt.len(), usize::from(HEIGHT));
+		assert_eq!(content(&txt[0]), "999:60");
+		assert_eq!(content(txt.last().unwrap()), "999:99");
+		assert_eq!(diff.selected_hunk, Some(999));
+
+		assert!(diff.move_hunk(false));
+		assert_eq!(diff.selection.get_end(), 99_900);
+		assert!(diff.move_hunk(false));
+		assert_eq!(diff.selection.get_end(), 99_800);
+		assert_eq!(diff.selected_hunk, Some(998));
+		let txt = render(&diff);
+		assert_eq!(content(&txt[0]), "998:0");
+	}
+
+	#[test]
+	fn test_render_whitespace() {
+		let mut diff = component();
+		diff.options.borrow_mut().diff_render = DiffRender {
+			tab_width: 4,
+			trailing_whitespace: true,
+			show_whitespace: true,
+			..DiffRender::default()
+		};
+		diff.update(
+			String::from("file"),
+			None,
+			false,
+			FileDiff {
+				hunks: vec![Hunk {
+					header_hash: 0,
+					lines: vec![
+						DiffLine {
+							content: "@@ -1 +1 @@".into(),
+							line_type: DiffLineType::Header,
+							position: DiffLinePosition::default(),
+						},
+						DiffLine {
+							content: "\tfoo \t".into(),
+							line_type: DiffLineType::Add,
+							position: DiffLinePosition::default(),
+						},
+						DiffLine {
+							content: "\tfoo".into(),
+							line_type: DiffLineType::None,
+							position: DiffLinePosition::default(),
+						},
+					],
+					split_from: None,
+				}],
+				lines: 3,
+				..FileDiff::default()
+			},
+		);
+		diff.search_input.set_text(String::from("foo"));
+		diff.confirm_search();
+
+		let txt = render(&diff);
+		let spans = |idx: usize| {
+			txt[idx].0[1..]
+				.iter()
+				.map(|span| span.content.to_string())
+				.collect::<Vec<_>>()
+		};
+
+		// marker, match, trailing whitespace and the padding of the
+		// selected line
+		assert_eq!(spans(1)[..3], ["→   ", "foo", "·→   "]);
+		assert_eq!(
+			txt[1].0[3].style,
+			diff.theme.diff_line(DiffLineType::Add, true).patch(
+				diff.theme
+					.diff_trailing_whitespace()
+					.patch(diff.theme.diff_whitespace())
+			)
+		);
+		// unchanged lines keep their whitespace blank
+		assert_eq!(spans(2), vec!["    ", "foo", "\n"]);
+	}
+
+	#[test]
+	fn test_selectable_text() {
+		let mut diff = component();
+		assert_eq!(diff.selectable_text(), None);
+
+		diff.options.borrow_mut().diff_render.tab_width = 4;
+		diff.update(
+			String::from("file"),
+			None,
+			false,
+			FileDiff {
+				hunks: vec![Hunk {
+					header_hash: 0,
+					lines: vec![
+						DiffLine {
+							content: "@@ -1,2 +1,2 @@".into(),
+							line_type: DiffLineType::Header,
+							position: DiffLinePosition::default(),
+						},
+						DiffLine {
+							content: "\tfoo \n".into(),
+							line_type: DiffLineType::Delete,
+							position: DiffLinePosition::default(),
+						},
+						DiffLine {
+							content: "\tbar\n".into(),
+							line_type: DiffLineType::Add,
+							position: DiffLinePosition::default(),
+						},
+						DiffLine {
+							content: "baz\n".into(),
+							line_type: DiffLineType::None,
+							position: DiffLinePosition::default(),
+						},
+					],
+					split_from: None,
+				}],
+				lines: 4,
+				..FileDiff::default()
+			},
+		);
+		render(&diff);
+		diff.current_size.set((80, 3));
+
+		// no markers or padding, just as in a patch
+		assert_eq!(
+			diff.selectable_text().unwrap(),
+			vec!["@@ -1,2 +1,2 @@", "-    foo", "+    bar"]
+		);
+	}
+
+	#[test]
+	fn test_search_in_window() {
+		let mut diff = component();
+		diff.update(
+			String::from("lock"),
+			None,
+			false,
+			huge_diff(100),
+		);
+		diff.search_input.set_text(String::from("999:2"));
+		diff.confirm_search();
+
+		// 999:2 and 999:20 to 999:29
+		assert_eq!(diff.search.as_ref().unwrap().matches.len(), 11);
+		assert_eq!(diff.selection.get_end(), 99_902);
+
+		// the highlighted part is a span of its own
+		let txt = render(&diff);
+		let visible = txt
+			.iter()
+			.filter(|line| content(line).starts_with("999:2"))
+			.count();
+		let highlighted = txt
+			.iter()
+			.filter(|line| {
+				line.0.iter().any(|s| s.content == "999:2")
+			})
+			.count();
+		assert!(visible > 0);
+		assert_eq!(highlighted, visible);
+	}
+
+	#[test]
+	fn test_moved_counterpart() {
+		let line = |line_type, old_lineno, new_lineno| DiffLine {
+			content: "moved\n".into(),
+			line_type,
+			position: DiffLinePosition {
+				old_lineno,
+				new_lineno,
+			},
+		};
+
+		let mut diff = component();
+		diff.update(
+			String::from("b.rs"),
+			None,
+			false,
+			FileDiff {
+				hunks: vec![Hunk {
+					header_hash: 0,
+					lines: vec![
+						line(DiffLineType::Header, None, None),
+						line(DiffLineType::None, Some(1), Some(1)),
+						line(DiffLineType::Add, None, Some(2)),
+						line(DiffLineType::Add, None, Some(3)),
+					],
+					split_from: None,
+				}],
+				lines: 4,
+				..FileDiff::default()
+			},
+		);
+		diff.set_moved(vec![MovedBlock {
+			removed: sync::MovedRange {
+				path: String::from("a.rs"),
+				start: 10,
+				len: 2,
+			},
+			added: sync::MovedRange {
+				path: String::from("b.rs"),
+				start: 2,
+				len: 2,
+			},
+		}]);
+
+		diff.update_selection(1);
+		assert_eq!(diff.moved_counterpart(), None);
+
+		diff.update_selection(3);
+		let target = diff.moved_counterpart().unwrap();
+		assert_eq!(
+			target,
+			MovedLine {
+				path: String::from("a.rs"),
+				lineno: 11,
+				removed: true,
+			}
+		);
+
+		// only the moved lines are drawn in the moved color
+		let moved = diff.theme.diff_line_moved().fg;
+		let txt = render(&diff);
+		assert!(txt[1].0.iter().all(|span| span.style.fg != moved));
+		assert!(txt[2].0.iter().any(|span| span.style.fg == moved));
+
+		// lands on the removed line once the other file is loaded
+		diff.select_moved_line(&target);
+		diff.update(
+			String::from("a.rs"),
+			None,
+			false,
+			FileDiff {
+				hunks: vec![Hunk {
+					header_hash: 1,
+					lines: vec![
+						line(DiffLineType::Header, None, None),
+						line(DiffLineType::Delete, Some(10), None),
+						line(DiffLineType::Delete, Some(11), None),
+					],
+					split_from: None,
+				}],
+				lines: 3,
+				..FileDiff::default()
+			},
+		);
+		assert_eq!(diff.selection.get_end(), 2);
+		assert_eq!(diff.moved_counterpart().unwrap().lineno, 3);
 	}
 }
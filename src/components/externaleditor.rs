@@ -48,6 +48,11 @@ impl ExternalEditorComponent {
 		}
 	}
 
+	/// program of the configured editor
+	pub fn editor_program() -> Result<String> {
+		split_editor(&configured_editor()).map(|(command, _)| command)
+	}
+
 	/// opens file at given `path` in an available editor
 	pub fn open_file_in_editor(path: &Path) -> Result<()> {
 		let work_dir = repo_work_dir(CWD)?;
@@ -67,40 +72,8 @@ impl ExternalEditorComponent {
 			io::stdout().execute(EnterAlternateScreen).expect("reset terminal");
 		}
 
-		let environment_options = ["GIT_EDITOR", "VISUAL", "EDITOR"];
-
-		let editor = env::var(environment_options[0])
-			.ok()
-			.or_else(|| get_config_string(CWD, "core.editor").ok()?)
-			.or_else(|| env::var(environment_options[1]).ok())
-			.or_else(|| env::var(environment_options[2]).ok())
-			.unwrap_or_else(|| String::from("vi"));
-
-		// TODO: proper handling arguments containing whitespaces
-		// This does not do the right thing if the input is `editor --something "with spaces"`
-
-		// deal with "editor name with spaces" p1 p2 p3
-		// and with "editor_no_spaces" p1 p2 p3
-		// does not address spaces in pn
-		let mut echars = editor.chars().peekable();
-
-		let first_char = *echars.peek().ok_or_else(|| {
-			anyhow!(
-				"editor env variable found empty: {}",
-				environment_options.join(" or ")
-			)
-		})?;
-		let command: String = if first_char == '\"' {
-			echars
-				.by_ref()
-				.skip(1)
-				.take_while(|c| *c != '\"')
-				.collect()
-		} else {
-			echars.by_ref().take_while(|c| *c != ' ').collect()
-		};
-
-		let remainder_str = echars.collect::<String>();
+		let editor = configured_editor();
+		let (command, remainder_str) = split_editor(&editor)?;
 		let remainder = remainder_str.split_whitespace();
 
 		let mut args: Vec<&OsStr> =
@@ -118,6 +91,44 @@ impl ExternalEditorComponent {
 	}
 }
 
+const ENVIRONMENT_OPTIONS: [&str; 3] =
+	["GIT_EDITOR", "VISUAL", "EDITOR"];
+
+/// editor command line as git would pick it, `vi` if none is set
+fn configured_editor() -> String {
+	env::var(ENVIRONMENT_OPTIONS[0])
+		.ok()
+		.or_else(|| get_config_string(CWD, "core.editor").ok()?)
+		.or_else(|| env::var(ENVIRONMENT_OPTIONS[1]).ok())
+		.or_else(|| env::var(ENVIRONMENT_OPTIONS[2]).ok())
+		.unwrap_or_else(|| String::from("vi"))
+}
+
+/// program and the rest (arguments) of an `editor` command line
+fn split_editor(editor: &str) -> Result<(String, String)> {
+	// TODO: proper handling arguments containing whitespaces
+	// This does not do the right thing if the input is `editor --something "with spaces"`
+
+	// deal with "editor name with spaces" p1 p2 p3
+	// and with "editor_no_spaces" p1 p2 p3
+	// does not address spaces in pn
+	let mut echars = editor.chars().peekable();
+
+	let first_char = *echars.peek().ok_or_else(|| {
+		anyhow!(
+			"editor env variable found empty: {}",
+			ENVIRONMENT_OPTIONS.join(" or ")
+		)
+	})?;
+	let command: String = if first_char == '"' {
+		echars.by_ref().skip(1).take_while(|c| *c != '"').collect()
+	} else {
+		echars.by_ref().take_while(|c| *c != ' ').collect()
+	};
+
+	Ok((command, echars.collect()))
+}
+
 impl DrawableComponent for ExternalEditorComponent {
 	fn draw<B: Backend>(
 		&self,
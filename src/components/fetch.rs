@@ -0,0 +1,544 @@
+use super::{
+	utils::string_width_align, visibility_blocking, CommandBlocking,
+	CommandInfo, Component, DrawableComponent, EventState,
+};
+use crate::{
+	args::get_app_config_path,
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, Queue},
+	strings, try_or_popup,
+	ui::{self, style::SharedTheme},
+	AsyncNotification,
+};
+use anyhow::Result;
+use asyncgit::{
+	asyncjob::AsyncSingleJob,
+	remote_branches::AsyncRemoteBranchesJob,
+	sync::{
+		self,
+		cred::{
+			extract_username_password_for_remote,
+			need_username_password_for_remote,
+		},
+	},
+	AsyncGitNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use ron::ser::{to_string_pretty, PrettyConfig};
+use std::{collections::HashMap, fs, path::PathBuf};
+use tui::{
+	backend::Backend,
+	layout::{Alignment, Rect},
+	text::{Span, Spans},
+	widgets::{Block, BorderType, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// what to fetch from the selected remote
+#[derive(Copy, Clone, PartialEq)]
+enum FetchScope {
+	CurrentBranch,
+	AllBranches,
+	Branch,
+}
+
+/// which row of the popup is selected
+#[derive(Copy, Clone, PartialEq)]
+enum Selection {
+	Remote,
+	Scope,
+	Branch(usize),
+}
+
+/// state of the list of branches the remote advertises
+enum RemoteBranches {
+	Loading,
+	Loaded(Vec<String>),
+	Failed(String),
+}
+
+const BRANCH_ROWS: usize = 8;
+
+///
+pub struct FetchComponent {
+	visible: bool,
+	current_branch: String,
+	remotes: Vec<String>,
+	remote: usize,
+	scope: FetchScope,
+	selection: Selection,
+	branches: RemoteBranches,
+	async_branches: AsyncSingleJob<AsyncRemoteBranchesJob>,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl FetchComponent {
+	///
+	pub fn new(
+		queue: &Queue,
+		sender: &Sender<AsyncGitNotification>,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			visible: false,
+			current_branch: String::new(),
+			remotes: Vec::new(),
+			remote: 0,
+			scope: FetchScope::CurrentBranch,
+			selection: Selection::Remote,
+			branches: RemoteBranches::Loading,
+			async_branches: AsyncSingleJob::new(sender.clone()),
+			queue: queue.clone(),
+			theme,
+			key_config,
+		}
+	}
+
+	///
+	pub fn open(&mut self, current_branch: String) -> Result<()> {
+		self.remotes = sync::get_remotes(CWD)?;
+
+		if self.remotes.is_empty() {
+			self.queue.push(InternalEvent::ShowErrorMsg(
+				strings::fetch_popup_no_remotes(),
+			));
+			return Ok(());
+		}
+
+		let preferred = last_remote()
+			.or_else(|| {
+				sync::get_upstream_remote(CWD, &current_branch).ok()
+			})
+			.or_else(|| sync::get_default_remote(CWD).ok());
+		self.remote = preferred
+			.and_then(|remote| {
+				self.remotes.iter().position(|r| *r == remote)
+			})
+			.unwrap_or_default();
+
+		self.current_branch = current_branch;
+		self.scope = FetchScope::CurrentBranch;
+		self.selection = Selection::Remote;
+		self.branches = RemoteBranches::Loading;
+
+		self.show()
+	}
+
+	///
+	pub fn update(&mut self, ev: AsyncNotification) {
+		if matches!(
+			ev,
+			AsyncNotification::Git(
+				AsyncGitNotification::RemoteBranches
+			)
+		) {
+			if let Some(job) = self.async_branches.take_last() {
+				if let Some((remote, result)) = job.result() {
+					// ignore results for a remote no longer selected
+					if Some(&remote) == self.selected_remote() {
+						self.branches = match result {
+							Ok(branches) => {
+								RemoteBranches::Loaded(branches)
+							}
+							Err(e) => {
+								RemoteBranches::Failed(e.to_string())
+							}
+						};
+					}
+				}
+			}
+		}
+	}
+
+	///
+	pub fn any_work_pending(&self) -> bool {
+		self.async_branches.is_pending()
+	}
+
+	fn selected_remote(&self) -> Option<&String> {
+		self.remotes.get(self.remote)
+	}
+
+	fn request_branches(&mut self) {
+		self.branches = RemoteBranches::Loading;
+
+		if let Some(remote) = self.selected_remote().cloned() {
+			let cred =
+				if need_username_password_for_remote(CWD, &remote)
+					.unwrap_or_default()
+				{
+					extract_username_password_for_remote(CWD, &remote)
+						.ok()
+				} else {
+					None
+				};
+
+			self.async_branches
+				.spawn(AsyncRemoteBranchesJob::new(remote, cred));
+		}
+	}
+
+	fn branch_count(&self) -> usize {
+		match &self.branches {
+			RemoteBranches::Loaded(branches) => branches.len(),
+			_ => 0,
+		}
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let branch_rows = if self.scope == FetchScope::Branch {
+			self.branch_count()
+		} else {
+			0
+		};
+
+		self.selection = match (self.selection, up) {
+			(Selection::Remote, false)
+			| (Selection::Branch(0), true) => Selection::Scope,
+			(Selection::Scope, true) => Selection::Remote,
+			(Selection::Scope, false) if branch_rows > 0 => {
+				Selection::Branch(0)
+			}
+			(Selection::Branch(idx), true) => {
+				Selection::Branch(idx - 1)
+			}
+			(Selection::Branch(idx), false) => Selection::Branch(
+				(idx + 1).min(branch_rows.saturating_sub(1)),
+			),
+			(sel, _) => sel,
+		};
+	}
+
+	fn switch_value(&mut self, right: bool) {
+		match self.selection {
+			Selection::Remote => {
+				let count = self.remotes.len();
+				self.remote = if right {
+					(self.remote + 1) % count
+				} else {
+					(self.remote + count - 1) % count
+				};
+
+				if self.scope == FetchScope::Branch {
+					self.request_branches();
+				}
+			}
+			Selection::Scope => {
+				self.scope = match (self.scope, right) {
+					(FetchScope::CurrentBranch, true)
+					| (FetchScope::Branch, false) => FetchScope::AllBranches,
+					(FetchScope::AllBranches, true)
+					| (FetchScope::CurrentBranch, false) => FetchScope::Branch,
+					(FetchScope::Branch, true)
+					| (FetchScope::AllBranches, false) => FetchScope::CurrentBranch,
+				};
+
+				if self.scope == FetchScope::Branch {
+					self.request_branches();
+				}
+			}
+			Selection::Branch(_) => (),
+		}
+	}
+
+	fn confirm(&mut self) {
+		let remote = match self.selected_remote() {
+			Some(remote) => remote.clone(),
+			None => return,
+		};
+
+		let event = match self.scope {
+			FetchScope::CurrentBranch => {
+				let upstream = sync::get_upstream_remote(
+					CWD,
+					&self.current_branch,
+				)
+				.ok();
+
+				// fetching the upstream of the current branch is a pull
+				if upstream.as_ref() == Some(&remote) {
+					InternalEvent::Pull(self.current_branch.clone())
+				} else {
+					InternalEvent::FetchRemote(
+						remote.clone(),
+						vec![self.current_branch.clone()],
+					)
+				}
+			}
+			FetchScope::AllBranches => {
+				InternalEvent::FetchRemote(remote.clone(), Vec::new())
+			}
+			FetchScope::Branch => {
+				let branch = match (&self.branches, self.selection) {
+					(
+						RemoteBranches::Loaded(branches),
+						Selection::Branch(idx),
+					) => branches.get(idx).cloned(),
+					_ => None,
+				};
+
+				match branch {
+					Some(branch) => InternalEvent::FetchRemote(
+						remote.clone(),
+						vec![branch],
+					),
+					None => return,
+				}
+			}
+		};
+
+		try_or_popup!(
+			self,
+			"failed to remember remote:",
+			save_last_remote(&remote)
+		);
+
+		self.queue.push(event);
+		self.hide();
+	}
+
+	fn prune(&mut self) {
+		if let Some(remote) = self.selected_remote().cloned() {
+			self.queue.push(InternalEvent::PruneRemote(remote));
+			self.hide();
+		}
+	}
+
+	fn get_text(&self, width: u16) -> Vec<Spans> {
+		let half = usize::from(width / 2);
+		let mut txt = Vec::with_capacity(BRANCH_ROWS + 4);
+
+		let entry = |label: &str, value: String, selected: bool| {
+			Spans::from(vec![
+				Span::styled(
+					string_width_align(label, half),
+					self.theme.text(true, false),
+				),
+				Span::styled(
+					format!("{:^w$}", value, w = half),
+					self.theme.text(true, selected),
+				),
+			])
+		};
+
+		txt.push(entry(
+			strings::fetch_popup_remote(),
+			format!(
+				"< {} >",
+				self.selected_remote().cloned().unwrap_or_default()
+			),
+			self.selection == Selection::Remote,
+		));
+		txt.push(entry(
+			strings::fetch_popup_scope(),
+			format!(
+				"< {} >",
+				match self.scope {
+					FetchScope::CurrentBranch => {
+						strings::fetch_popup_scope_current(
+							&self.current_branch,
+						)
+					}
+					FetchScope::AllBranches => {
+						strings::fetch_popup_scope_all()
+					}
+					FetchScope::Branch => {
+						strings::fetch_popup_scope_branch()
+					}
+				}
+			),
+			self.selection == Selection::Scope,
+		));
+
+		if self.scope == FetchScope::Branch {
+			txt.push(Spans::from(""));
+			self.add_branches(&mut txt, width);
+		}
+
+		txt
+	}
+
+	fn add_branches(&self, txt: &mut Vec<Spans>, width: u16) {
+		match &self.branches {
+			RemoteBranches::Loading => {
+				txt.push(Spans::from(strings::loading_text(
+					&self.key_config,
+				)));
+			}
+			RemoteBranches::Failed(err) => {
+				txt.push(Spans::from(Span::styled(
+					err.clone(),
+					self.theme.text_danger(),
+				)));
+			}
+			RemoteBranches::Loaded(branches) => {
+				let selected = match self.selection {
+					Selection::Branch(idx) => Some(idx),
+					_ => None,
+				};
+				let skip = selected.map_or(0, |idx| {
+					idx.saturating_sub(BRANCH_ROWS - 1)
+				});
+
+				for (idx, branch) in branches
+					.iter()
+					.enumerate()
+					.skip(skip)
+					.take(BRANCH_ROWS)
+				{
+					txt.push(Spans::from(Span::styled(
+						string_width_align(
+							branch,
+							usize::from(width),
+						),
+						self.theme.text(true, selected == Some(idx)),
+					)));
+				}
+			}
+		}
+	}
+}
+
+impl DrawableComponent for FetchComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		if self.is_visible() {
+			#[allow(clippy::cast_possible_truncation)]
+			const SIZE: (u16, u16) = (50, BRANCH_ROWS as u16 + 5);
+			let area =
+				ui::centered_rect_absolute(SIZE.0, SIZE.1, rect);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(
+					self.get_text(area.width.saturating_sub(2)),
+				)
+				.block(
+					Block::default()
+						.borders(Borders::ALL)
+						.border_type(BorderType::Thick)
+						.title(Span::styled(
+							strings::FETCH_POPUP_MSG,
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				)
+				.alignment(Alignment::Left),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for FetchComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::navigate_tree(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::fetch_confirm(&self.key_config),
+				self.scope != FetchScope::Branch
+					|| matches!(self.selection, Selection::Branch(_)),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::fetch_prune(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = ev {
+				if key == self.key_config.exit_popup {
+					self.hide();
+				} else if key == self.key_config.move_up {
+					self.move_selection(true);
+				} else if key == self.key_config.move_down {
+					self.move_selection(false);
+				} else if key == self.key_config.move_right {
+					self.switch_value(true);
+				} else if key == self.key_config.move_left {
+					self.switch_value(false);
+				} else if key == self.key_config.enter {
+					self.confirm();
+				} else if key == self.key_config.fetch_prune {
+					self.prune();
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
+
+fn last_remotes_file() -> Result<PathBuf> {
+	Ok(get_app_config_path()?.join("fetch_remotes.ron"))
+}
+
+fn read_last_remotes() -> Result<HashMap<String, String>> {
+	let file = last_remotes_file()?;
+	if !file.exists() {
+		return Ok(HashMap::new());
+	}
+
+	Ok(ron::de::from_bytes(&fs::read(file)?)?)
+}
+
+/// last remote used to fetch in the current repo
+fn last_remote() -> Option<String> {
+	let repo = sync::utils::repo_work_dir(CWD).ok()?;
+	read_last_remotes().ok()?.remove(&repo)
+}
+
+fn save_last_remote(remote: &str) -> Result<()> {
+	let repo = sync::utils::repo_work_dir(CWD)?;
+	let mut remotes = read_last_remotes().unwrap_or_default();
+	remotes.insert(repo, remote.to_string());
+
+	let data = to_string_pretty(&remotes, PrettyConfig::default())?;
+	fs::write(last_remotes_file()?, data)?;
+
+	Ok(())
+}
@@ -306,6 +306,17 @@ impl Component for FileFindPopup {
 		Ok(EventState::NotConsumed)
 	}
 
+	fn paste(&mut self, text: &str) -> Result<EventState> {
+		if self.is_visible()
+			&& self.find_text.paste(text)?.is_consumed()
+		{
+			self.update_query();
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
 	fn is_visible(&self) -> bool {
 		self.visible
 	}
@@ -318,4 +329,8 @@ impl Component for FileFindPopup {
 		self.visible = true;
 		Ok(())
 	}
+
+	fn on_resize(&self) {
+		self.find_text.on_resize();
+	}
 }
@@ -0,0 +1,568 @@
+use super::{
+	textinput::InputType,
+	utils::{lazy_lines::LazyLines, text_search::TextSearch},
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState, TextInputComponent,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+	sync::{self, utils::repo_work_dir, CommitId},
+	CWD,
+};
+use crossterm::event::Event;
+use std::{
+	cell::Cell,
+	fs::File,
+	io::{BufRead, BufReader, Cursor},
+	path::Path,
+};
+use tui::{
+	backend::Backend,
+	layout::Rect,
+	text::{Span, Spans},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// where the content of a viewed file comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileSource {
+	/// working copy of the (repo relative) path
+	WorkDir(String),
+	/// staged version of the path
+	Index(String),
+	/// path in the tree of a commit
+	Commit(CommitId, String),
+}
+
+impl FileSource {
+	///
+	pub fn path(&self) -> &str {
+		match self {
+			Self::WorkDir(path)
+			| Self::Index(path)
+			| Self::Commit(_, path) => path,
+		}
+	}
+
+	/// files in the working tree are streamed, blobs are read by
+	/// libgit2 as a whole anyway
+	fn reader(&self) -> Result<Box<dyn BufRead>> {
+		Ok(match self {
+			Self::WorkDir(path) => {
+				Box::new(BufReader::new(File::open(
+					Path::new(&repo_work_dir(CWD)?).join(path),
+				)?))
+			}
+			Self::Index(path) => Box::new(Cursor::new(
+				sync::index_file_blob(CWD, path)?,
+			)),
+			Self::Commit(id, path) => Box::new(Cursor::new(
+				sync::commit_file_blob(CWD, *id, path)?,
+			)),
+		})
+	}
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum InputMode {
+	Goto,
+	Search,
+}
+
+/// a file in full screen with line numbers, loaded further as it
+/// is scrolled through so huge files open instantly
+pub struct FileViewPopup {
+	visible: bool,
+	source: Option<FileSource>,
+	/// `None` for binary files
+	lines: Option<LazyLines>,
+	top: usize,
+	/// visible lines as of the last draw
+	height: Cell<usize>,
+	/// line jumped to or of the current match
+	mark: Option<usize>,
+	input: TextInputComponent,
+	input_mode: InputMode,
+	search: Option<TextSearch>,
+	no_match: bool,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl FileViewPopup {
+	///
+	pub fn new(
+		queue: &Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		let mut input = TextInputComponent::new(
+			theme.clone(),
+			key_config.clone(),
+			"",
+			"",
+			false,
+		)
+		.with_input_type(InputType::Singleline);
+		input.embed();
+
+		Self {
+			visible: false,
+			source: None,
+			lines: None,
+			top: 0,
+			height: Cell::new(0),
+			mark: None,
+			input,
+			input_mode: InputMode::Goto,
+			search: None,
+			no_match: false,
+			queue: queue.clone(),
+			theme,
+			key_config,
+		}
+	}
+
+	///
+	pub fn open(&mut self, source: FileSource) -> Result<()> {
+		let mut reader = source.reader()?;
+
+		// binary content is not shown
+		self.lines = if reader.fill_buf()?.contains(&0) {
+			None
+		} else {
+			let mut lines = LazyLines::new(reader);
+			lines.load_chunk()?;
+			Some(lines)
+		};
+		self.source = Some(source);
+		self.top = 0;
+		self.mark = None;
+		self.search = None;
+		self.no_match = false;
+		self.input.hide();
+
+		self.show()
+	}
+
+	fn line_count(&self) -> usize {
+		self.lines.as_ref().map_or(0, LazyLines::len)
+	}
+
+	/// scrolls to `top`, loading the lines needed to fill the view
+	fn scroll_to(&mut self, top: usize) -> Result<()> {
+		let height = self.height.get().max(1);
+
+		if let Some(lines) = &mut self.lines {
+			lines.load_to(top.saturating_add(height))?;
+		}
+
+		self.top = top.min(self.line_count().saturating_sub(height));
+
+		Ok(())
+	}
+
+	fn scroll_by(&mut self, lines: usize, up: bool) -> Result<()> {
+		self.scroll_to(if up {
+			self.top.saturating_sub(lines)
+		} else {
+			self.top.saturating_add(lines)
+		})
+	}
+
+	fn scroll_to_end(&mut self) -> Result<()> {
+		if let Some(lines) = &mut self.lines {
+			lines.load_all()?;
+		}
+
+		self.scroll_to(usize::MAX)
+	}
+
+	/// marks line `idx` and scrolls it into the middle of the view
+	fn jump_to(&mut self, idx: usize) -> Result<()> {
+		self.mark = Some(idx);
+		self.scroll_to(idx.saturating_sub(self.height.get() / 2))
+	}
+
+	fn open_input(&mut self, mode: InputMode) -> Result<()> {
+		self.input_mode = mode;
+		self.input.clear();
+		self.input.set_default_msg(String::from(match mode {
+			InputMode::Goto => strings::FILE_VIEW_GOTO_HINT,
+			InputMode::Search => strings::DIFF_SEARCH_HINT,
+		}));
+
+		self.input.show()
+	}
+
+	fn confirm_input(&mut self) -> Result<()> {
+		self.input.hide();
+		let text = self.input.get_text().trim().to_string();
+
+		match self.input_mode {
+			InputMode::Goto => match text.parse::<usize>() {
+				Ok(line) => {
+					if let Some(lines) = &mut self.lines {
+						lines.load_to(line)?;
+					}
+
+					let last = self.line_count().saturating_sub(1);
+					self.jump_to(line.saturating_sub(1).min(last))?;
+				}
+				Err(_) if text.is_empty() => (),
+				Err(_) => {
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						strings::file_view_no_line(&text),
+					));
+				}
+			},
+			InputMode::Search => {
+				self.search = TextSearch::new(&text);
+				self.mark = None;
+				self.move_to_match(true)?;
+			}
+		}
+
+		Ok(())
+	}
+
+	fn is_match(&self, idx: usize) -> bool {
+		match (&self.search, &self.lines) {
+			(Some(search), Some(lines)) => {
+				lines.get(idx).map_or(false, |line| {
+					!search.find_all(line).is_empty()
+				})
+			}
+			_ => false,
+		}
+	}
+
+	/// first match from line `from` on, loading more of the file
+	/// until one is found or it ends
+	fn find_forward(&mut self, from: usize) -> Result<Option<usize>> {
+		let mut idx = from;
+
+		loop {
+			let len = self.line_count();
+			if let Some(found) =
+				(idx..len).find(|i| self.is_match(*i))
+			{
+				return Ok(Some(found));
+			}
+			idx = idx.max(len);
+
+			match &mut self.lines {
+				Some(lines) if !lines.is_complete() => {
+					lines.load_chunk()?;
+				}
+				_ => return Ok(None),
+			}
+		}
+	}
+
+	/// moves to the next (or previous) match, wrapping around at the
+	/// end (or start) of the file
+	fn move_to_match(&mut self, next: bool) -> Result<()> {
+		if self.search.is_none() {
+			return Ok(());
+		}
+
+		let found = if next {
+			let from = self.mark.map_or(self.top, |mark| mark + 1);
+
+			let mut found = self.find_forward(from)?;
+			if found.is_none() {
+				found =
+					self.find_forward(0)?.filter(|idx| *idx < from);
+			}
+			found
+		} else {
+			let from = self.mark.unwrap_or(self.top);
+
+			let mut found =
+				(0..from).rev().find(|idx| self.is_match(*idx));
+			if found.is_none() {
+				if let Some(lines) = &mut self.lines {
+					lines.load_all()?;
+				}
+				found = (from..self.line_count())
+					.rev()
+					.find(|idx| self.is_match(*idx));
+			}
+			found
+		};
+
+		self.no_match = found.is_none();
+		if let Some(found) = found {
+			self.jump_to(found)?;
+		}
+
+		Ok(())
+	}
+
+	fn title(&self) -> String {
+		let title =
+			self.source.as_ref().map_or_else(String::new, |source| {
+				strings::file_view_title(
+					source,
+					self.line_count(),
+					self.lines
+						.as_ref()
+						.map_or(true, LazyLines::is_complete),
+				)
+			});
+
+		if self.no_match {
+			format!(
+				"{} {}",
+				title,
+				strings::diff_search_matches(None, 0, false)
+			)
+		} else {
+			title
+		}
+	}
+
+	fn get_text(&self, height: usize) -> Vec<Spans> {
+		let lines = match &self.lines {
+			Some(lines) => lines,
+			None => {
+				return vec![Spans::from(Span::styled(
+					strings::FILE_VIEW_BINARY,
+					self.theme.text(false, false),
+				))]
+			}
+		};
+
+		let end = self.top.saturating_add(height).min(lines.len());
+		let width = end.to_string().len();
+
+		(self.top..end)
+			.filter_map(|idx| lines.get(idx).map(|line| (idx, line)))
+			.map(|(idx, line)| {
+				let marked = self.mark == Some(idx);
+				let style = self.theme.text(true, marked);
+
+				let mut spans = vec![Span::styled(
+					format!("{:>w$} ", idx + 1, w = width),
+					self.theme.text(false, false),
+				)];
+				let mut pos = 0;
+
+				if let Some(search) = &self.search {
+					for range in search.find_all(line) {
+						spans.push(Span::styled(
+							line[pos..range.start].to_string(),
+							style,
+						));
+						spans.push(Span::styled(
+							line[range.clone()].to_string(),
+							style.patch(
+								self.theme.search_match(marked),
+							),
+						));
+						pos = range.end;
+					}
+				}
+
+				spans.push(Span::styled(
+					line[pos..].to_string(),
+					style,
+				));
+
+				Spans::from(spans)
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for FileViewPopup {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		if self.is_visible() {
+			let height = usize::from(rect.height.saturating_sub(2));
+			self.height.set(height);
+
+			f.render_widget(Clear, rect);
+			f.render_widget(
+				Paragraph::new(self.get_text(height)).block(
+					Block::default()
+						.title(Span::styled(
+							self.title(),
+							self.theme.title(true),
+						))
+						.borders(Borders::ALL)
+						.border_style(self.theme.block(true)),
+				),
+				rect,
+			);
+
+			ui::draw_scrollbar(
+				f,
+				rect,
+				&self.theme,
+				self.line_count().saturating_sub(height),
+				self.top,
+			);
+
+			// bottom row inside the border
+			self.input.draw(
+				f,
+				Rect::new(
+					rect.x.saturating_add(1),
+					rect.bottom().saturating_sub(2).max(rect.y),
+					rect.width.saturating_sub(2),
+					1.min(rect.height),
+				),
+			)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for FileViewPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			let typing = self.input.is_visible();
+			let any = self
+				.lines
+				.as_ref()
+				.map_or(false, |lines| !lines.is_empty());
+
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				any,
+				!typing,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::file_view_goto(&self.key_config),
+				any,
+				!typing,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::file_view_search(&self.key_config),
+				any,
+				!typing,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::diff_search_next_prev(
+					&self.key_config,
+				),
+				!self.no_match,
+				self.search.is_some() && !typing,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::file_view_confirm(
+					&self.key_config,
+				),
+				true,
+				typing,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = ev {
+				if self.input.is_visible() {
+					if key == self.key_config.exit_popup {
+						self.input.hide();
+					} else if key == self.key_config.enter {
+						self.confirm_input()?;
+					} else {
+						self.input.event(ev)?;
+					}
+				} else if key == self.key_config.exit_popup {
+					if self.search.is_some() {
+						self.search = None;
+						self.no_match = false;
+					} else {
+						self.hide();
+					}
+				} else if key == self.key_config.move_up {
+					self.scroll_by(1, true)?;
+				} else if key == self.key_config.move_down {
+					self.scroll_by(1, false)?;
+				} else if key == self.key_config.page_up {
+					self.scroll_by(self.height.get(), true)?;
+				} else if key == self.key_config.page_down {
+					self.scroll_by(self.height.get(), false)?;
+				} else if key == self.key_config.shift_up
+					|| key == self.key_config.home
+				{
+					self.scroll_to(0)?;
+				} else if key == self.key_config.shift_down
+					|| key == self.key_config.end
+				{
+					self.scroll_to_end()?;
+				} else if key == self.key_config.file_view_goto {
+					self.open_input(InputMode::Goto)?;
+				} else if key == self.key_config.diff_search {
+					self.open_input(InputMode::Search)?;
+				} else if key == self.key_config.diff_search_next
+					|| key == self.key_config.diff_search_prev
+				{
+					self.move_to_match(
+						key == self.key_config.diff_search_next,
+					)?;
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn paste(&mut self, text: &str) -> Result<EventState> {
+		if self.is_visible() {
+			if self.input.is_visible() {
+				self.input.paste(text)?;
+			}
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+		self.lines = None;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
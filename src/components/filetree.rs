@@ -1,6 +1,7 @@
 use super::{
 	utils::{
 		filetree::{FileTreeItem, FileTreeItemKind},
+		selected_path::SelectedPath,
 		statustree::{MoveSelection, StatusTree},
 	},
 	CommandBlocking, DrawableComponent,
@@ -8,16 +9,22 @@ use super::{
 use crate::{
 	components::{CommandInfo, Component, EventState},
 	keys::SharedKeyConfig,
-	queue::{InternalEvent, NeedsUpdate, Queue},
+	queue::{FileLine, InternalEvent, NeedsUpdate, Queue},
 	strings::{self, order},
 	ui,
 	ui::style::SharedTheme,
 };
 use anyhow::Result;
 use asyncgit::{hash, StatusItem, StatusItemType};
+use bytesize::ByteSize;
 use crossterm::event::Event;
 use std::{borrow::Cow, cell::Cell, convert::From, path::Path};
-use tui::{backend::Backend, layout::Rect, text::Span, Frame};
+use tui::{
+	backend::Backend,
+	layout::Rect,
+	text::{Span, Spans},
+	Frame,
+};
 
 //TODO: rename so that its clear this only works for Statuses
 //TODO: use new `filetreelist` crate
@@ -34,6 +41,7 @@ pub struct FileTreeComponent {
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
 	scroll_top: Cell<usize>,
+	current_height: Cell<usize>,
 }
 
 impl FileTreeComponent {
@@ -55,6 +63,7 @@ impl FileTreeComponent {
 			theme,
 			key_config,
 			scroll_top: Cell::new(0),
+			current_height: Cell::new(0),
 			pending: true,
 		}
 	}
@@ -76,6 +85,11 @@ impl FileTreeComponent {
 		self.tree.selected_item()
 	}
 
+	/// see `StatusTree::select_path`
+	pub fn select_path(&mut self, path: &str) -> bool {
+		self.tree.select_path(path)
+	}
+
 	///
 	pub fn selection_file(&self) -> Option<StatusItem> {
 		self.tree.selected_item().and_then(|f| {
@@ -97,11 +111,33 @@ impl FileTreeComponent {
 		self.tree.is_empty()
 	}
 
+	/// no items were set since the last `clear`
+	pub const fn is_pending(&self) -> bool {
+		self.pending
+	}
+
 	///
 	pub const fn file_count(&self) -> usize {
 		self.tree.tree.file_count()
 	}
 
+	/// files below the folder `path`
+	pub fn file_count_in(&self, path: &str) -> usize {
+		self.tree
+			.tree
+			.items()
+			.iter()
+			.filter(|item| {
+				matches!(item.kind, FileTreeItemKind::File(_))
+					&& item
+						.info
+						.full_path
+						.strip_prefix(path)
+						.map_or(false, |rest| rest.starts_with('/'))
+			})
+			.count()
+	}
+
 	///
 	pub fn set_title(&mut self, title: String) {
 		self.title = title;
@@ -124,6 +160,32 @@ impl FileTreeComponent {
 		})
 	}
 
+	/// selects the next (or previous) file skipping folders,
+	/// keeps the selection if there is none
+	pub fn select_adjacent_file(&mut self, next: bool) -> bool {
+		let dir = if next {
+			MoveSelection::Down
+		} else {
+			MoveSelection::Up
+		};
+		let old_selection = self.tree.selection;
+
+		while self.tree.move_selection(dir) {
+			if self.is_file_seleted() {
+				if let Some(ref queue) = self.queue {
+					queue.push(InternalEvent::Update(
+						NeedsUpdate::DIFF,
+					));
+				}
+				return true;
+			}
+		}
+
+		self.tree.selection = old_selection;
+
+		false
+	}
+
 	fn move_selection(&mut self, dir: MoveSelection) -> bool {
 		let changed = self.tree.move_selection(dir);
 
@@ -136,14 +198,22 @@ impl FileTreeComponent {
 		changed
 	}
 
-	const fn item_status_char(item_type: StatusItemType) -> char {
-		match item_type {
-			StatusItemType::Modified => 'M',
-			StatusItemType::New => '+',
-			StatusItemType::Deleted => '-',
-			StatusItemType::Renamed => 'R',
-			StatusItemType::Typechange => ' ',
-			StatusItemType::Conflicted => '!',
+	const fn item_status_char(
+		item_type: StatusItemType,
+		letters: bool,
+	) -> char {
+		match (item_type, letters) {
+			(StatusItemType::Modified, _) => 'M',
+			(StatusItemType::New, false) => '+',
+			(StatusItemType::New, true) => 'A',
+			(StatusItemType::Deleted, false) => '-',
+			(StatusItemType::Deleted, true) => 'D',
+			(StatusItemType::Renamed, _) => 'R',
+			(StatusItemType::Typechange, true) => 'T',
+			(StatusItemType::Conflicted, false) => '!',
+			(StatusItemType::Conflicted, true) => 'U',
+			(StatusItemType::Typechange, false)
+			| (StatusItemType::EmptyDir, _) => ' ',
 		}
 	}
 
@@ -155,7 +225,7 @@ impl FileTreeComponent {
 		width: u16,
 		selected: bool,
 		theme: &'b SharedTheme,
-	) -> Option<Span<'b>> {
+	) -> Option<Spans<'b>> {
 		let indent_str = if indent == 0 {
 			String::from("")
 		} else {
@@ -168,12 +238,36 @@ impl FileTreeComponent {
 
 		match file_item_kind {
 			FileTreeItemKind::File(status_item) => {
-				let status_char =
-					Self::item_status_char(status_item.status);
-				let file = Path::new(&status_item.path)
-					.file_name()
-					.and_then(std::ffi::OsStr::to_str)
-					.expect("invalid path.");
+				let status_char = Self::item_status_char(
+					status_item.status,
+					theme.indicators().status_letters,
+				);
+				let file_name = |path: &str| {
+					Path::new(path)
+						.file_name()
+						.and_then(std::ffi::OsStr::to_str)
+						.map(String::from)
+						.expect("invalid path.")
+				};
+
+				let file = status_item.old_path.as_ref().map_or_else(
+					|| file_name(&status_item.path),
+					|old_path| {
+						format!(
+							"{} \u{2192} {}",
+							file_name(old_path),
+							file_name(&status_item.path)
+						)
+					},
+				);
+				let file = match status_item.binary_size {
+					Some(size) => format!(
+						"{} ({})",
+						file,
+						ByteSize::b(size).to_string_as(true)
+					),
+					None => file,
+				};
 
 				let txt = if selected {
 					format!(
@@ -187,9 +281,13 @@ impl FileTreeComponent {
 					format!("{} {}{}", status_char, indent_str, file)
 				};
 
-				Some(Span::styled(
-					Cow::from(txt),
-					theme.item(status_item.status, selected),
+				Some(Self::with_selection_marker(
+					Span::styled(
+						Cow::from(txt),
+						theme.item(status_item.status, selected),
+					),
+					selected,
+					theme,
 				))
 			}
 
@@ -212,14 +310,30 @@ impl FileTreeComponent {
 					)
 				};
 
-				Some(Span::styled(
-					Cow::from(txt),
-					theme.text(true, selected),
+				Some(Self::with_selection_marker(
+					Span::styled(
+						Cow::from(txt),
+						theme.text(true, selected),
+					),
+					selected,
+					theme,
 				))
 			}
 		}
 	}
 
+	fn with_selection_marker<'b>(
+		item: Span<'b>,
+		selected: bool,
+		theme: &SharedTheme,
+	) -> Spans<'b> {
+		let mut spans: Vec<Span> =
+			theme.selection_marker(selected).into_iter().collect();
+		spans.push(item);
+
+		Spans::from(spans)
+	}
+
 	/// Returns a Vec<TextDrawInfo> which is used to draw the `FileTreeComponent` correctly,
 	/// allowing folders to be folded up if they are alone in their directory
 	fn build_vec_text_draw_info_for_drawing(
@@ -341,6 +455,7 @@ impl DrawableComponent for FileTreeComponent {
 				.map(|idx| idx.saturating_sub(selection_offset))
 				.unwrap_or_default();
 			let tree_height = r.height.saturating_sub(2) as usize;
+			self.current_height.set(tree_height);
 
 			self.scroll_top.set(ui::calc_scroll_top(
 				self.scroll_top.get(),
@@ -399,6 +514,13 @@ impl Component for FileTreeComponent {
 			)
 			.order(order::RARE_ACTION),
 		);
+		if self.queue.is_some() {
+			self.path_commands(
+				&self.key_config,
+				self.focused || force_all,
+				out,
+			);
+		}
 
 		CommandBlocking::PassingOn
 	}
@@ -406,11 +528,23 @@ impl Component for FileTreeComponent {
 	fn event(&mut self, ev: Event) -> Result<EventState> {
 		if self.focused {
 			if let Event::Key(e) = ev {
+				if let Some(queue) = &self.queue {
+					if self
+						.path_event(e, &self.key_config, queue)
+						.is_consumed()
+					{
+						return Ok(EventState::Consumed);
+					}
+				}
+
 				return if e == self.key_config.blame {
 					match (&self.queue, self.selection_file()) {
 						(Some(queue), Some(status_item)) => {
 							queue.push(InternalEvent::BlameFile(
-								status_item.path,
+								FileLine {
+									path: status_item.path,
+									..FileLine::default()
+								},
 							));
 
 							Ok(EventState::Consumed)
@@ -457,6 +591,40 @@ impl Component for FileTreeComponent {
 		self.focused = focus;
 		self.show_selection(focus);
 	}
+
+	fn selectable_text(&self) -> Option<Vec<String>> {
+		if self.pending {
+			return None;
+		}
+
+		let (items, _, _) =
+			self.build_vec_text_draw_info_for_drawing();
+		Some(
+			items
+				.iter()
+				.filter_map(|info| {
+					Self::item_to_text(
+						&info.name,
+						info.indent as usize,
+						info.visible,
+						info.item_kind,
+						0,
+						false,
+						&self.theme,
+					)
+				})
+				.skip(self.scroll_top.get())
+				.take(self.current_height.get())
+				.map(|spans| ui::spans_text(&spans))
+				.collect(),
+		)
+	}
+}
+
+impl SelectedPath for FileTreeComponent {
+	fn selected_path(&self) -> Option<String> {
+		self.selection_file().map(|file| file.path)
+	}
 }
 
 #[cfg(test)]
@@ -469,7 +637,9 @@ mod tests {
 			.iter()
 			.map(|a| StatusItem {
 				path: String::from(*a),
+				old_path: None,
 				status: StatusItemType::Modified,
+				binary_size: None,
 			})
 			.collect::<Vec<_>>()
 	}
@@ -555,4 +725,70 @@ mod tests {
 
 		assert_eq!(ftc.scroll_top.get(), 0); // should still be at top
 	}
+
+	#[test]
+	fn test_select_adjacent_file() {
+		let items = string_vec_to_status(&[
+			"a/b1", //
+			"a/b2", //
+			"c/d1", //
+		]);
+
+		//0 a/
+		//1   b1
+		//2   b2
+		//3 c/
+		//4   d1
+
+		let mut ftc = FileTreeComponent::new(
+			"title",
+			true,
+			None,
+			SharedTheme::default(),
+			SharedKeyConfig::default(),
+		);
+		ftc.update(&items)
+			.expect("Updating FileTreeComponent failed");
+
+		let selected = |ftc: &FileTreeComponent| {
+			ftc.selection_file().map(|f| f.path)
+		};
+
+		assert!(ftc.select_adjacent_file(true));
+		assert_eq!(selected(&ftc), Some(String::from("a/b1")));
+
+		assert!(ftc.select_adjacent_file(true));
+		// skips folder `c/`
+		assert!(ftc.select_adjacent_file(true));
+		assert_eq!(selected(&ftc), Some(String::from("c/d1")));
+
+		// no next file, selection stays
+		assert!(!ftc.select_adjacent_file(true));
+		assert_eq!(selected(&ftc), Some(String::from("c/d1")));
+
+		assert!(ftc.select_adjacent_file(false));
+		assert_eq!(selected(&ftc), Some(String::from("a/b2")));
+	}
+
+	#[test]
+	fn test_status_letters() {
+		let chars = |letters| {
+			[
+				StatusItemType::Modified,
+				StatusItemType::New,
+				StatusItemType::Deleted,
+				StatusItemType::Renamed,
+				StatusItemType::Typechange,
+				StatusItemType::Conflicted,
+			]
+			.iter()
+			.map(|typ| {
+				FileTreeComponent::item_status_char(*typ, letters)
+			})
+			.collect::<String>()
+		};
+
+		assert_eq!(chars(false), "M+-R !");
+		assert_eq!(chars(true), "MADRTU");
+	}
 }
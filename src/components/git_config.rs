@@ -0,0 +1,419 @@
+use super::{
+	textinput::InputType, utils::string_width_align,
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState, TextInputComponent,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+	sync::{
+		self,
+		config::{self, ScopedValue},
+		ConfigScope,
+	},
+	CWD,
+};
+use crossterm::event::Event;
+use std::convert::TryFrom;
+use tui::{
+	backend::Backend,
+	layout::{Alignment, Rect},
+	style::{Modifier, Style},
+	text::{Span, Spans},
+	widgets::{Block, BorderType, Borders, Clear, Paragraph},
+	Frame,
+};
+
+const WIDTH: u16 = 64;
+/// width of the key column
+const KEY_WIDTH: usize = 18;
+/// width of the scope column
+const SCOPE_WIDTH: usize = 8;
+
+/// the curated config keys, grouped by their header, `true` for
+/// boolean keys which are toggled instead of typed in
+const GROUPS: &[(&str, &[(&str, bool)])] = &[
+	("Identity", &[("user.name", false), ("user.email", false)]),
+	(
+		"Editing",
+		&[("core.editor", false), ("core.autocrlf", false)],
+	),
+	(
+		"Remotes",
+		&[
+			("pull.rebase", true),
+			("push.default", false),
+			("fetch.prune", true),
+		],
+	),
+	("Commits", &[("commit.gpgsign", true)]),
+];
+
+fn keys() -> impl Iterator<Item = (&'static str, bool)> {
+	GROUPS.iter().flat_map(|(_, keys)| keys.iter().copied())
+}
+
+/// effective values of a few common config keys with the scope
+/// they come from, editable in the local and the global config
+pub struct GitConfigPopup {
+	visible: bool,
+	/// effective value of each of `keys()`
+	values: Vec<Option<ScopedValue>>,
+	selection: usize,
+	/// where edits go, never `ConfigScope::System`
+	scope: ConfigScope,
+	input: TextInputComponent,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl GitConfigPopup {
+	///
+	pub fn new(
+		queue: &Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			visible: false,
+			values: Vec::new(),
+			selection: 0,
+			scope: ConfigScope::Local,
+			input: TextInputComponent::new(
+				theme.clone(),
+				key_config.clone(),
+				"",
+				"",
+				false,
+			)
+			.with_input_type(InputType::Singleline),
+			queue: queue.clone(),
+			theme,
+			key_config,
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.selection = 0;
+		self.scope = ConfigScope::Local;
+		self.update();
+
+		self.show()
+	}
+
+	fn update(&mut self) {
+		self.values = keys()
+			.map(|(key, _)| {
+				config::get_with_scope(CWD, key).unwrap_or_else(|e| {
+					log::error!("read config {}: {}", key, e);
+					None
+				})
+			})
+			.collect();
+	}
+
+	fn selected(&self) -> Option<(&'static str, bool)> {
+		keys().nth(self.selection)
+	}
+
+	fn selected_value(&self) -> Option<&ScopedValue> {
+		self.values.get(self.selection).and_then(Option::as_ref)
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let max = keys().count().saturating_sub(1);
+
+		self.selection = if up {
+			self.selection.saturating_sub(1)
+		} else {
+			self.selection.saturating_add(1).min(max)
+		};
+	}
+
+	fn toggle_scope(&mut self) {
+		self.scope = match self.scope {
+			ConfigScope::Local => ConfigScope::Global,
+			ConfigScope::Global | ConfigScope::System => {
+				ConfigScope::Local
+			}
+		};
+	}
+
+	/// toggles boolean keys, opens the input for the others
+	fn edit_selected(&mut self) -> Result<()> {
+		let (key, boolean) = match self.selected() {
+			Some(selected) => selected,
+			None => return Ok(()),
+		};
+
+		let current = self.selected_value().map(|v| v.value.clone());
+
+		if boolean {
+			// values like `pull.rebase = merges` count as enabled
+			let enabled = current.map_or(false, |value| {
+				config::parse_bool(&value).unwrap_or(true)
+			});
+			self.write(key, if enabled { "false" } else { "true" });
+			return Ok(());
+		}
+
+		self.input.set_title(strings::git_config_input_title(
+			key,
+			self.scope == ConfigScope::Global,
+		));
+		self.input.set_text(current.unwrap_or_default());
+		self.input.show()
+	}
+
+	fn confirm_input(&mut self) {
+		let key = match self.selected() {
+			Some((key, _)) => key,
+			None => return,
+		};
+
+		let value = self.input.get_text().trim().to_string();
+
+		if value.is_empty() {
+			self.queue.push(InternalEvent::ShowErrorMsg(
+				strings::git_config_empty_value(
+					key,
+					&self.key_config,
+				),
+			));
+			return;
+		}
+
+		self.input.hide();
+		self.write(key, &value);
+	}
+
+	fn write(&mut self, key: &str, value: &str) {
+		let res =
+			sync::set_string_scoped(CWD, key, value, self.scope);
+		self.written(key, res);
+	}
+
+	fn unset_selected(&mut self) {
+		if let Some((key, _)) = self.selected() {
+			let res = config::unset(CWD, key, self.scope);
+			self.written(key, res);
+		}
+	}
+
+	fn written(&mut self, key: &str, res: asyncgit::Result<()>) {
+		if let Err(e) = res {
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"write config {}:\n{}",
+				key, e
+			)));
+		}
+
+		self.update();
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+	}
+
+	fn get_text(&self) -> Vec<Spans> {
+		let value_width = usize::from(WIDTH)
+			.saturating_sub(KEY_WIDTH + SCOPE_WIDTH + 2);
+
+		let mut txt = Vec::new();
+		let mut idx = 0;
+
+		for (header, keys) in GROUPS {
+			if !txt.is_empty() {
+				txt.push(Spans::default());
+			}
+			txt.push(Spans::from(Span::styled(
+				*header,
+				//TODO: use style
+				Style::default().add_modifier(Modifier::UNDERLINED),
+			)));
+
+			for (key, _) in *keys {
+				let selected = idx == self.selection;
+				let value =
+					self.values.get(idx).and_then(Option::as_ref);
+
+				txt.push(Spans::from(vec![
+					Span::styled(
+						string_width_align(key, KEY_WIDTH),
+						self.theme.text(true, selected),
+					),
+					Span::styled(
+						string_width_align(
+							value.map_or(
+								strings::GIT_CONFIG_UNSET,
+								|v| v.value.as_str(),
+							),
+							value_width,
+						),
+						self.theme.text(value.is_some(), selected),
+					),
+					Span::styled(
+						value.map_or_else(String::new, |v| {
+							strings::git_config_scope(v.scope)
+						}),
+						self.theme.text(false, selected),
+					),
+				]));
+
+				idx += 1;
+			}
+		}
+
+		txt.push(Spans::default());
+		txt.push(Spans::from(Span::styled(
+			strings::git_config_target(
+				self.scope == ConfigScope::Global,
+				&self.key_config,
+			),
+			self.theme.text(true, false),
+		)));
+
+		txt
+	}
+}
+
+impl DrawableComponent for GitConfigPopup {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		if self.visible {
+			let text = self.get_text();
+			let height =
+				u16::try_from(text.len() + 2).unwrap_or(u16::MAX);
+			let area =
+				ui::centered_rect_absolute(WIDTH, height, rect);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(text)
+					.block(
+						Block::default()
+							.title(Span::styled(
+								strings::git_config_title(),
+								self.theme.title(true),
+							))
+							.borders(Borders::ALL)
+							.border_type(BorderType::Thick)
+							.border_style(self.theme.block(true)),
+					)
+					.alignment(Alignment::Left),
+				area,
+			);
+
+			self.input.draw(f, rect)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for GitConfigPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.input.is_visible() {
+			return self.input.commands(out, force_all);
+		}
+
+		if self.visible || force_all {
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::git_config_edit(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::git_config_unset(&self.key_config),
+				self.selected_value()
+					.map_or(false, |v| v.scope == self.scope),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::preflight_toggle_scope(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.visible {
+			if let Event::Key(key) = ev {
+				if self.input.is_visible() {
+					if key == self.key_config.enter {
+						self.confirm_input();
+					} else {
+						self.input.event(ev)?;
+					}
+				} else if key == self.key_config.exit_popup
+					|| key == self.key_config.open_git_config
+				{
+					self.hide();
+				} else if key == self.key_config.move_up {
+					self.move_selection(true);
+				} else if key == self.key_config.move_down {
+					self.move_selection(false);
+				} else if key == self.key_config.enter {
+					self.edit_selected()?;
+				} else if key == self.key_config.git_config_unset {
+					self.unset_selected();
+				} else if key == self.key_config.tab_toggle {
+					self.toggle_scope();
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn paste(&mut self, text: &str) -> Result<EventState> {
+		if self.input.is_visible() {
+			return self.input.paste(text);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.input.hide();
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
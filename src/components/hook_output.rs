@@ -0,0 +1,331 @@
+use super::{
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState,
+};
+use crate::{
+	keys::SharedKeyConfig, spinner::SPINNER_CHARS, strings, ui,
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+	commit_hooks::AsyncCommitHooksJob,
+	pre_push_hook::AsyncPrePushHookJob,
+};
+use crossterm::event::Event;
+use std::{
+	cell::Cell,
+	convert::TryFrom,
+	time::{Duration, Instant},
+};
+use tui::{
+	backend::Backend,
+	layout::Rect,
+	text::Span,
+	widgets::{Block, BorderType, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// hooks finishing quicker than this never show up
+const SHOW_DELAY: Duration = Duration::from_millis(300);
+
+/// a background job running hooks
+pub trait HookJob {
+	/// everything the hooks wrote so far
+	fn output(&self) -> String;
+	fn cancel(&self);
+	fn is_canceled(&self) -> bool;
+}
+
+impl HookJob for AsyncCommitHooksJob {
+	fn output(&self) -> String {
+		self.output()
+	}
+	fn cancel(&self) {
+		self.cancel();
+	}
+	fn is_canceled(&self) -> bool {
+		self.is_canceled()
+	}
+}
+
+impl HookJob for AsyncPrePushHookJob {
+	fn output(&self) -> String {
+		self.output()
+	}
+	fn cancel(&self) {
+		self.cancel();
+	}
+	fn is_canceled(&self) -> bool {
+		self.is_canceled()
+	}
+}
+
+enum HookRunState {
+	Running(Box<dyn HookJob>),
+	Failed,
+	Canceled,
+}
+
+/// streams the output of the commit (or `pre-push`) hooks while they
+/// run and keeps it visible if they fail
+pub struct HookOutputComponent {
+	state: Option<HookRunState>,
+	pre_push: bool,
+	started: Instant,
+	output: String,
+	/// `None` keeps following new output
+	scroll_top: Option<usize>,
+	current_height: Cell<usize>,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl HookOutputComponent {
+	///
+	pub fn new(
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+		pre_push: bool,
+	) -> Self {
+		Self {
+			state: None,
+			pre_push,
+			started: Instant::now(),
+			output: String::new(),
+			scroll_top: None,
+			current_height: Cell::new(0),
+			theme,
+			key_config,
+		}
+	}
+
+	///
+	pub fn start<J: HookJob + 'static>(&mut self, job: J) {
+		self.state = Some(HookRunState::Running(Box::new(job)));
+		self.started = Instant::now();
+		self.output.clear();
+		self.scroll_top = None;
+	}
+
+	/// fetches the latest output of the running hooks
+	pub fn update(&mut self) {
+		if let Some(HookRunState::Running(job)) = &self.state {
+			self.output = job.output();
+		}
+	}
+
+	/// keeps the output of the failed hooks visible
+	pub fn fail(&mut self) {
+		self.update();
+
+		let canceled = matches!(
+			&self.state,
+			Some(HookRunState::Running(job)) if job.is_canceled()
+		);
+
+		self.state = Some(if canceled {
+			HookRunState::Canceled
+		} else {
+			HookRunState::Failed
+		});
+	}
+
+	///
+	pub const fn is_running(&self) -> bool {
+		matches!(self.state, Some(HookRunState::Running(_)))
+	}
+
+	fn cancel(&self) {
+		if let Some(HookRunState::Running(job)) = &self.state {
+			job.cancel();
+		}
+	}
+
+	fn lines_count(&self) -> usize {
+		self.output.lines().count()
+	}
+
+	fn max_scroll(&self) -> usize {
+		self.lines_count().saturating_sub(self.current_height.get())
+	}
+
+	fn scroll(&mut self, up: bool, lines: usize) {
+		let top =
+			self.scroll_top.unwrap_or_else(|| self.max_scroll());
+
+		let top = if up {
+			top.saturating_sub(lines)
+		} else {
+			top.saturating_add(lines)
+		};
+
+		// back at the bottom keeps following
+		self.scroll_top = if top >= self.max_scroll() {
+			None
+		} else {
+			Some(top)
+		};
+	}
+
+	fn title(&self) -> String {
+		match &self.state {
+			Some(HookRunState::Running(_)) => {
+				let elapsed = self.started.elapsed();
+				let frame =
+					usize::try_from(elapsed.as_millis() / 100)
+						.unwrap_or_default();
+				let spinner =
+					SPINNER_CHARS[frame % SPINNER_CHARS.len()];
+
+				strings::hooks_running_title(
+					spinner,
+					elapsed.as_secs(),
+					self.pre_push,
+				)
+			}
+			Some(HookRunState::Failed) => {
+				strings::hooks_failed_title(
+					&self.key_config,
+					self.pre_push,
+				)
+			}
+			Some(HookRunState::Canceled) => {
+				strings::hooks_canceled_title(
+					&self.key_config,
+					self.pre_push,
+				)
+			}
+			None => String::new(),
+		}
+	}
+}
+
+impl DrawableComponent for HookOutputComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		_rect: Rect,
+	) -> Result<()> {
+		if !self.is_visible()
+			|| (self.is_running()
+				&& self.output.is_empty()
+				&& self.started.elapsed() < SHOW_DELAY)
+		{
+			return Ok(());
+		}
+
+		let area = ui::centered_rect(80, 70, f.size());
+		self.current_height
+			.set(usize::from(area.height.saturating_sub(2)));
+
+		let top =
+			self.scroll_top.unwrap_or_else(|| self.max_scroll());
+
+		let title_style = if self.is_running() {
+			self.theme.title(true)
+		} else {
+			self.theme.text_danger()
+		};
+
+		f.render_widget(Clear, area);
+		f.render_widget(
+			Paragraph::new(self.output.as_str())
+				.block(
+					Block::default()
+						.title(Span::styled(
+							self.title(),
+							title_style,
+						))
+						.borders(Borders::ALL)
+						.border_type(BorderType::Thick)
+						.border_style(if self.is_running() {
+							self.theme.block(true)
+						} else {
+							self.theme.text_danger()
+						}),
+				)
+				.scroll((u16::try_from(top).unwrap_or(u16::MAX), 0)),
+			area,
+		);
+
+		Ok(())
+	}
+}
+
+impl Component for HookOutputComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::hooks_cancel(&self.key_config),
+				true,
+				self.is_running() || force_all,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::hooks_close(&self.key_config),
+				true,
+				!self.is_running() || force_all,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(e) = ev {
+				let page =
+					self.current_height.get().saturating_sub(1);
+
+				if e == self.key_config.exit_popup {
+					if self.is_running() {
+						self.cancel();
+					} else {
+						self.hide();
+					}
+				} else if e == self.key_config.enter
+					&& !self.is_running()
+				{
+					self.hide();
+				} else if e == self.key_config.move_up {
+					self.scroll(true, 1);
+				} else if e == self.key_config.move_down {
+					self.scroll(false, 1);
+				} else if e == self.key_config.page_up {
+					self.scroll(true, page);
+				} else if e == self.key_config.page_down {
+					self.scroll(false, page);
+				} else if e == self.key_config.home {
+					self.scroll_top = Some(0);
+				} else if e == self.key_config.end {
+					self.scroll_top = None;
+				}
+			}
+
+			// hooks block everything else
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.state.is_some()
+	}
+
+	fn hide(&mut self) {
+		self.state = None;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		Ok(())
+	}
+}
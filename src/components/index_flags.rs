@@ -0,0 +1,252 @@
+//! files whose working dir changes are hidden by index flags
+
+use super::{
+	utils::scroll_vertical::VerticalScroll,
+	utils::string_width_align, visibility_blocking, CommandBlocking,
+	CommandInfo, Component, DrawableComponent, EventState,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings, ui,
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+	sync::{self, FlaggedFile, IndexFlag},
+	CWD,
+};
+use crossterm::event::Event;
+use std::cell::Cell;
+use tui::{
+	backend::Backend,
+	layout::Rect,
+	text::Span,
+	widgets::{Block, BorderType, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// lists index entries flagged `assume-unchanged` or
+/// `skip-worktree` and clears the flags of the selected one
+pub struct IndexFlagsPopup {
+	queue: Queue,
+	visible: bool,
+	files: Vec<FlaggedFile>,
+	selection: usize,
+	scroll: VerticalScroll,
+	current_height: Cell<usize>,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl IndexFlagsPopup {
+	///
+	pub fn new(
+		queue: &Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			queue: queue.clone(),
+			visible: false,
+			files: Vec::new(),
+			selection: 0,
+			scroll: VerticalScroll::new(),
+			current_height: Cell::new(0),
+			theme,
+			key_config,
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.files = sync::index_flagged_files(CWD)?;
+		self.move_selection(true, 0);
+
+		self.show()
+	}
+
+	fn move_selection(&mut self, up: bool, lines: usize) {
+		let max = self.files.len().saturating_sub(1);
+
+		self.selection = if up {
+			self.selection.saturating_sub(lines).min(max)
+		} else {
+			self.selection.saturating_add(lines).min(max)
+		};
+	}
+
+	fn clear_selected(&mut self) -> Result<()> {
+		let file = match self.files.get(self.selection) {
+			Some(file) => file.clone(),
+			None => return Ok(()),
+		};
+
+		for (flag, set) in [
+			(IndexFlag::AssumeUnchanged, file.assume_unchanged),
+			(IndexFlag::SkipWorktree, file.skip_worktree),
+		] {
+			if !set {
+				continue;
+			}
+
+			if let Err(e) =
+				sync::set_index_flag(CWD, &file.path, flag, false)
+			{
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					strings::index_flag_clear_failed(&e.to_string()),
+				));
+				break;
+			}
+		}
+
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+
+		self.open()
+	}
+
+	fn list_entry(&self, idx: usize, width: usize) -> Span<'static> {
+		let file = &self.files[idx];
+
+		Span::styled(
+			string_width_align(
+				&format!(
+					"{}{} {}",
+					if file.skip_worktree { "S" } else { " " },
+					if file.assume_unchanged { "A" } else { " " },
+					file.path
+				),
+				width,
+			),
+			self.theme.text(true, idx == self.selection),
+		)
+	}
+}
+
+impl DrawableComponent for IndexFlagsPopup {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		_rect: Rect,
+	) -> Result<()> {
+		if self.visible {
+			let area = ui::centered_rect(60, 50, f.size());
+
+			let height = usize::from(area.height.saturating_sub(2));
+			let width = usize::from(area.width.saturating_sub(2));
+			self.current_height.set(height);
+
+			self.scroll.update(
+				self.selection,
+				self.files.len(),
+				height,
+			);
+
+			let block = Block::default()
+				.title(Span::styled(
+					strings::index_flags_title(),
+					self.theme.title(true),
+				))
+				.borders(Borders::ALL)
+				.border_type(BorderType::Thick)
+				.border_style(self.theme.block(true));
+
+			f.render_widget(Clear, area);
+
+			if self.files.is_empty() {
+				f.render_widget(
+					Paragraph::new(strings::index_flags_empty())
+						.style(self.theme.text(false, false))
+						.block(block),
+					area,
+				);
+			} else {
+				let items = (0..self.files.len())
+					.skip(self.scroll.get_top())
+					.take(height)
+					.map(|idx| self.list_entry(idx, width));
+
+				ui::draw_list_block(f, area, block, items);
+
+				self.scroll.draw(f, area, &self.theme);
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for IndexFlagsPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.visible || force_all {
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::index_flag_clear(&self.key_config),
+				!self.files.is_empty(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.visible {
+			if let Event::Key(e) = ev {
+				let page =
+					self.current_height.get().saturating_sub(1);
+
+				if e == self.key_config.exit_popup
+					|| e == self.key_config.status_index_flags
+				{
+					self.hide();
+				} else if e == self.key_config.index_flag_clear {
+					self.clear_selected()?;
+				} else if e == self.key_config.move_up {
+					self.move_selection(true, 1);
+				} else if e == self.key_config.move_down {
+					self.move_selection(false, 1);
+				} else if e == self.key_config.page_up {
+					self.move_selection(true, page);
+				} else if e == self.key_config.page_down {
+					self.move_selection(false, page);
+				} else if e == self.key_config.home {
+					self.selection = 0;
+				} else if e == self.key_config.end {
+					self.move_selection(false, usize::MAX);
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
@@ -0,0 +1,197 @@
+use super::{
+	textinput::InputType, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState, TextInputComponent,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{sync, CWD};
+use crossterm::event::Event;
+use std::env;
+use tui::{
+	backend::Backend,
+	layout::{Constraint, Direction, Layout, Margin, Rect},
+	text::Span,
+	widgets::{Block, BorderType, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// width of `strings::INIT_REPO_BRANCH`
+const LABEL_WIDTH: u16 = 16;
+
+/// offered when gitui is started outside of a repo, creates one in
+/// the working dir
+pub struct InitRepoPopup {
+	path: String,
+	/// repo the working dir is part of (a bare one)
+	enclosing: Option<String>,
+	branch: TextInputComponent,
+	error: Option<String>,
+	/// `Some(true)` once initialized, `Some(false)` if declined
+	result: Option<bool>,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl InitRepoPopup {
+	///
+	pub fn new(
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Result<Self> {
+		let mut branch = TextInputComponent::new(
+			theme.clone(),
+			key_config.clone(),
+			"",
+			"",
+			false,
+		)
+		.with_input_type(InputType::Singleline);
+		branch.embed();
+		branch.set_text(sync::default_initial_branch());
+		branch.show()?;
+
+		Ok(Self {
+			path: env::current_dir()?.to_string_lossy().to_string(),
+			enclosing: sync::enclosing_repo(CWD)
+				.map(|path| path.to_string_lossy().to_string()),
+			branch,
+			error: None,
+			result: None,
+			theme,
+			key_config,
+		})
+	}
+
+	/// `None` until the user decided
+	pub const fn result(&self) -> Option<bool> {
+		self.result
+	}
+
+	fn init(&mut self) {
+		match sync::init_repo(CWD, self.branch.get_text().trim()) {
+			Ok(()) => self.result = Some(true),
+			Err(e) => self.error = Some(e.to_string()),
+		}
+	}
+}
+
+impl DrawableComponent for InitRepoPopup {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		area: Rect,
+	) -> Result<()> {
+		let lines: u16 = if self.enclosing.is_some() { 5 } else { 4 };
+		let area = ui::centered_rect_absolute(70, lines + 2, area);
+		let last = usize::from(lines) - 1;
+
+		f.render_widget(Clear, area);
+		f.render_widget(
+			Block::default()
+				.title(Span::styled(
+					strings::INIT_REPO_TITLE,
+					self.theme.title(true),
+				))
+				.borders(Borders::ALL)
+				.border_type(BorderType::Thick)
+				.border_style(self.theme.block(true)),
+			area,
+		);
+
+		let chunks = Layout::default()
+			.direction(Direction::Vertical)
+			.constraints(vec![Constraint::Length(1); last + 1])
+			.split(area.inner(&Margin {
+				horizontal: 1,
+				vertical: 1,
+			}));
+
+		f.render_widget(
+			Paragraph::new(Span::styled(
+				strings::init_repo_target(&self.path),
+				self.theme.text(true, false),
+			)),
+			chunks[0],
+		);
+
+		if let Some(enclosing) = &self.enclosing {
+			f.render_widget(
+				Paragraph::new(Span::styled(
+					strings::init_repo_nested(enclosing),
+					self.theme.text_danger(),
+				)),
+				chunks[1],
+			);
+		}
+
+		let field = Layout::default()
+			.direction(Direction::Horizontal)
+			.constraints(
+				[
+					Constraint::Length(LABEL_WIDTH),
+					Constraint::Percentage(100),
+				]
+				.as_ref(),
+			)
+			.split(chunks[last - 1]);
+
+		f.render_widget(
+			Paragraph::new(Span::styled(
+				strings::INIT_REPO_BRANCH,
+				self.theme.text(true, true),
+			)),
+			field[0],
+		);
+		self.branch.draw(f, field[1])?;
+
+		let hint = self.error.as_ref().map_or_else(
+			|| {
+				Span::styled(
+					strings::init_repo_hint(&self.key_config),
+					self.theme.text(false, false),
+				)
+			},
+			|error| {
+				Span::styled(error.as_str(), self.theme.text_danger())
+			},
+		);
+		f.render_widget(Paragraph::new(hint), chunks[last]);
+
+		Ok(())
+	}
+}
+
+impl Component for InitRepoPopup {
+	fn commands(
+		&self,
+		_out: &mut Vec<CommandInfo>,
+		_force_all: bool,
+	) -> CommandBlocking {
+		CommandBlocking::Blocking
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if let Event::Key(key) = ev {
+			if key == self.key_config.exit_popup
+				|| key == self.key_config.exit
+			{
+				self.result = Some(false);
+			} else if key == self.key_config.enter {
+				self.init();
+			} else if self.branch.event(ev)?.is_consumed() {
+				self.error = None;
+			}
+		}
+
+		Ok(EventState::Consumed)
+	}
+
+	fn paste(&mut self, text: &str) -> Result<EventState> {
+		self.error = None;
+		self.branch.paste(text)
+	}
+}
@@ -1,7 +1,7 @@
 use super::{
 	command_pump, event_pump, visibility_blocking, CommandBlocking,
-	CommandInfo, CommitDetailsComponent, Component, DiffComponent,
-	DrawableComponent, EventState,
+	CommandInfo, CommitDetailsComponent, Component, DiffCommits,
+	DiffComponent, DrawableComponent, EventState, SharedOptions,
 };
 use crate::{
 	accessors,
@@ -12,6 +12,8 @@ use crate::{
 };
 use anyhow::Result;
 use asyncgit::{
+	asyncjob::AsyncSingleJob,
+	moved_blocks::{AsyncMovedBlocksJob, MovedBlocksParams},
 	sync::{diff::DiffOptions, CommitId, CommitTags},
 	AsyncDiff, AsyncGitNotification, CommitFilesParams, DiffParams,
 	DiffType,
@@ -32,6 +34,10 @@ pub struct InspectCommitComponent {
 	diff: DiffComponent,
 	details: CommitDetailsComponent,
 	git_diff: AsyncDiff,
+	git_moved: AsyncSingleJob<AsyncMovedBlocksJob>,
+	/// what the moved blocks shown in the diff are searched for
+	moved_params: Option<MovedBlocksParams>,
+	options: SharedOptions,
 	visible: bool,
 	key_config: SharedKeyConfig,
 }
@@ -104,6 +110,13 @@ impl Component for InspectCommitComponent {
 				self.diff.focused() || force_all,
 			));
 
+			out.push(CommandInfo::new(
+				strings::commands::diff_jump_moved(&self.key_config),
+				self.diff.moved_counterpart().is_some(),
+				(self.diff.focused() && self.diff.has_moved())
+					|| force_all,
+			));
+
 			out.push(CommandInfo::new(
 				strings::commands::inspect_file_tree(
 					&self.key_config,
@@ -137,6 +150,21 @@ impl Component for InspectCommitComponent {
 				{
 					self.details.focus(true);
 					self.diff.focus(false);
+				} else if (e == self.key_config.diff_hunk_next
+					|| e == self.key_config.diff_hunk_prev)
+					&& self.diff.focused()
+				{
+					// diff ran out of hunks in that direction
+					let next = e == self.key_config.diff_hunk_next;
+					if self.details.select_adjacent_file(next)
+						&& !next
+					{
+						self.diff.select_last_hunk_on_update();
+					}
+				} else if e == self.key_config.diff_jump_moved
+					&& self.diff.focused()
+				{
+					self.jump_moved()?;
 				} else if e == self.key_config.open_file_tree {
 					if let Some(commit) = self.commit_id {
 						self.queue.push(InternalEvent::OpenFileTree(
@@ -169,6 +197,11 @@ impl Component for InspectCommitComponent {
 		self.update()?;
 		Ok(())
 	}
+
+	fn on_resize(&self) {
+		self.details.on_resize();
+		self.diff.on_resize();
+	}
 }
 
 impl InspectCommitComponent {
@@ -180,6 +213,7 @@ impl InspectCommitComponent {
 		sender: &Sender<AsyncGitNotification>,
 		theme: SharedTheme,
 		key_config: SharedKeyConfig,
+		options: SharedOptions,
 	) -> Self {
 		Self {
 			queue: queue.clone(),
@@ -188,16 +222,21 @@ impl InspectCommitComponent {
 				sender,
 				theme.clone(),
 				key_config.clone(),
+				options.clone(),
 			),
 			diff: DiffComponent::new(
 				queue.clone(),
 				theme,
 				key_config.clone(),
+				options.clone(),
 				true,
 			),
 			commit_id: None,
 			tags: None,
 			git_diff: AsyncDiff::new(sender),
+			git_moved: AsyncSingleJob::new(sender.clone()),
+			moved_params: None,
+			options,
 			visible: false,
 			key_config,
 		}
@@ -218,7 +257,9 @@ impl InspectCommitComponent {
 
 	///
 	pub fn any_work_pending(&self) -> bool {
-		self.git_diff.is_pending() || self.details.any_work_pending()
+		self.git_diff.is_pending()
+			|| self.git_moved.is_pending()
+			|| self.details.any_work_pending()
 	}
 
 	///
@@ -231,6 +272,8 @@ impl InspectCommitComponent {
 				self.update()?;
 			} else if let AsyncGitNotification::Diff = ev {
 				self.update_diff()?;
+			} else if ev == AsyncGitNotification::MovedBlocks {
+				self.update_moved();
 			}
 		}
 
@@ -243,17 +286,27 @@ impl InspectCommitComponent {
 			if let Some(id) = self.commit_id {
 				if let Some(f) = self.details.files().selection_file()
 				{
+					self.request_moved(id);
+
 					let diff_params = DiffParams {
 						path: f.path.clone(),
-						diff_type: DiffType::Commit(id),
+						old_path: None,
+						diff_type: DiffType::Commit(
+							id,
+							self.details.merge_mode(),
+						),
 						options: DiffOptions::default(),
 					};
 
+					self.diff
+						.set_commits(Some(DiffCommits::Commit(id)));
+
 					if let Some((params, last)) =
 						self.git_diff.last()?
 					{
 						if params == diff_params {
-							self.diff.update(f.path, false, last);
+							self.diff
+								.update(f.path, None, false, last);
 							return Ok(());
 						}
 					}
@@ -280,6 +333,53 @@ impl InspectCommitComponent {
 		Ok(())
 	}
 
+	/// searches the commit for moved blocks unless it was already,
+	/// the diff is shown without them meanwhile
+	fn request_moved(&mut self, id: CommitId) {
+		let params = MovedBlocksParams {
+			id,
+			merge_mode: self.details.merge_mode(),
+			min_lines: self
+				.options
+				.borrow()
+				.diff_render
+				.moved_min_lines,
+		};
+
+		if self.moved_params != Some(params) {
+			self.moved_params = Some(params);
+			self.diff.set_moved(Vec::new());
+			self.git_moved.spawn(AsyncMovedBlocksJob::new(params));
+		}
+	}
+
+	fn update_moved(&mut self) {
+		if let Some(job) = self.git_moved.take_last() {
+			if Some(job.params()) == self.moved_params {
+				match job.result() {
+					Some(Ok(moved)) => self.diff.set_moved(moved),
+					Some(Err(e)) => {
+						log::error!("moved blocks: {}", e);
+					}
+					None => (),
+				}
+			}
+		}
+	}
+
+	/// selects the other side of the selected moved line, in
+	/// whatever file it is
+	fn jump_moved(&mut self) -> Result<()> {
+		if let Some(target) = self.diff.moved_counterpart() {
+			if self.details.select_file(&target.path) {
+				self.diff.select_moved_line(&target);
+				self.update_diff()?;
+			}
+		}
+
+		Ok(())
+	}
+
 	fn can_focus_diff(&self) -> bool {
 		self.details.files().selection_file().is_some()
 	}
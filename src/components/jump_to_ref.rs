@@ -0,0 +1,303 @@
+//! picks a ref to select its commit in the log
+
+use super::{
+	utils::string_width_align, visibility_blocking, CommandBlocking,
+	CommandInfo, Component, DrawableComponent, EventState,
+	ScrollType, TextInputComponent,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+	sync::{self, RefTarget},
+	CWD,
+};
+use crossterm::event::Event;
+use fuzzy_matcher::FuzzyMatcher;
+use tui::{
+	backend::Backend,
+	layout::{Constraint, Direction, Layout, Margin, Rect},
+	text::Span,
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// width of the ref kind column
+const KIND_WIDTH: usize = 7;
+
+/// fuzzy filtered list of all refs, keeps their order (by kind,
+/// then name) while filtering
+pub struct JumpToRefPopup {
+	queue: Queue,
+	visible: bool,
+	find_text: TextInputComponent,
+	refs: Vec<RefTarget>,
+	/// indices into `refs` matching the query
+	filtered: Vec<usize>,
+	selection: usize,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl JumpToRefPopup {
+	///
+	pub fn new(
+		queue: &Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		let mut find_text = TextInputComponent::new(
+			theme.clone(),
+			key_config.clone(),
+			"",
+			&strings::jump_to_ref_hint(),
+			false,
+		);
+		find_text.embed();
+
+		Self {
+			queue: queue.clone(),
+			visible: false,
+			find_text,
+			refs: Vec::new(),
+			filtered: Vec::new(),
+			selection: 0,
+			theme,
+			key_config,
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.refs = sync::get_ref_targets(CWD)?;
+		self.show()?;
+		self.find_text.show()?;
+		self.find_text.set_text(String::new());
+		self.update_filter();
+
+		Ok(())
+	}
+
+	fn update_filter(&mut self) {
+		let query = self.find_text.get_text();
+		let matcher = fuzzy_matcher::skim::SkimMatcherV2::default();
+
+		self.filtered = self
+			.refs
+			.iter()
+			.enumerate()
+			.filter(|(_, target)| {
+				query.is_empty()
+					|| matcher
+						.fuzzy_match(&target.name, query)
+						.is_some()
+			})
+			.map(|(idx, _)| idx)
+			.collect();
+		self.selection = 0;
+	}
+
+	fn selected_ref(&self) -> Option<&RefTarget> {
+		self.filtered
+			.get(self.selection)
+			.and_then(|idx| self.refs.get(*idx))
+	}
+
+	fn move_selection(&mut self, move_type: ScrollType) {
+		self.selection = match move_type {
+			ScrollType::Up => self.selection.saturating_sub(1),
+			ScrollType::Down => self.selection.saturating_add(1),
+			_ => self.selection,
+		}
+		.min(self.filtered.len().saturating_sub(1));
+	}
+
+	fn jump(&mut self) {
+		if let Some(target) = self.selected_ref().cloned() {
+			self.hide();
+			self.queue.push(InternalEvent::JumpToRef(target));
+		}
+	}
+
+	fn draw_list<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+		let height = usize::from(area.height);
+		let width = usize::from(area.width);
+
+		if self.filtered.is_empty() {
+			f.render_widget(
+				Paragraph::new(Span::styled(
+					strings::jump_to_ref_no_hits(),
+					self.theme.text(false, false),
+				)),
+				area,
+			);
+			return;
+		}
+
+		// keeps the selection in view
+		let scroll = (self.selection + 1).saturating_sub(height);
+
+		let items = self
+			.filtered
+			.iter()
+			.enumerate()
+			.skip(scroll)
+			.take(height)
+			.map(|(pos, idx)| {
+				let target = &self.refs[*idx];
+				let line = format!(
+					"{} {} {}",
+					string_width_align(
+						strings::ref_kind(target.kind),
+						KIND_WIDTH
+					),
+					target.id.get_short_string(),
+					target.name,
+				);
+				Span::styled(
+					string_width_align(&line, width),
+					self.theme.text(true, pos == self.selection),
+				)
+			});
+
+		ui::draw_list_block(f, area, Block::default(), items);
+	}
+}
+
+impl DrawableComponent for JumpToRefPopup {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		if self.is_visible() {
+			const SIZE: (u16, u16) = (60, 20);
+
+			let area =
+				ui::centered_rect_absolute(SIZE.0, SIZE.1, rect);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Block::default()
+					.borders(Borders::all())
+					.style(self.theme.title(true))
+					.title(Span::styled(
+						strings::jump_to_ref_title(),
+						self.theme.title(true),
+					)),
+				area,
+			);
+
+			let chunks = Layout::default()
+				.direction(Direction::Vertical)
+				.constraints(
+					[Constraint::Length(1), Constraint::Min(1)]
+						.as_ref(),
+				)
+				.split(area.inner(&Margin {
+					horizontal: 1,
+					vertical: 1,
+				}));
+
+			self.find_text.draw(f, chunks[0])?;
+			self.draw_list(f, chunks[1]);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for JumpToRefPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				true,
+				true,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::jump_to_ref_select(
+					&self.key_config,
+				),
+				self.selected_ref().is_some(),
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = &event {
+				if *key == self.key_config.exit_popup {
+					self.hide();
+					return Ok(EventState::Consumed);
+				} else if *key == self.key_config.enter {
+					self.jump();
+					return Ok(EventState::Consumed);
+				} else if *key == self.key_config.move_down {
+					self.move_selection(ScrollType::Down);
+					return Ok(EventState::Consumed);
+				} else if *key == self.key_config.move_up {
+					self.move_selection(ScrollType::Up);
+					return Ok(EventState::Consumed);
+				}
+			}
+
+			if self.find_text.event(event)?.is_consumed() {
+				self.update_filter();
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn paste(&mut self, text: &str) -> Result<EventState> {
+		if self.is_visible()
+			&& self.find_text.paste(text)?.is_consumed()
+		{
+			self.update_filter();
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+		Ok(())
+	}
+
+	fn on_resize(&self) {
+		self.find_text.on_resize();
+	}
+}
@@ -0,0 +1,234 @@
+use super::{
+	utils::scroll_vertical::VerticalScroll, visibility_blocking,
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, Queue},
+	strings, ui,
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+	sync::{self, AuthorInfo},
+	CWD,
+};
+use crossterm::event::Event;
+use std::cell::Cell;
+use tui::{
+	backend::Backend,
+	layout::Rect,
+	text::Span,
+	widgets::{Block, BorderType, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// authors listed to pick from
+const TOP_AUTHORS: usize = 50;
+
+/// lists the authors with the most commits to filter the log by
+pub struct LogAuthorsPopup {
+	queue: Queue,
+	visible: bool,
+	authors: Vec<AuthorInfo>,
+	selection: usize,
+	scroll: VerticalScroll,
+	current_height: Cell<usize>,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl LogAuthorsPopup {
+	///
+	pub fn new(
+		queue: &Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			queue: queue.clone(),
+			visible: false,
+			authors: Vec::new(),
+			selection: 0,
+			scroll: VerticalScroll::new(),
+			current_height: Cell::new(0),
+			theme,
+			key_config,
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.authors = sync::get_top_authors(CWD, TOP_AUTHORS)?;
+		self.selection = 0;
+
+		self.show()
+	}
+
+	fn move_selection(&mut self, up: bool, lines: usize) {
+		let max = self.authors.len().saturating_sub(1);
+
+		self.selection = if up {
+			self.selection.saturating_sub(lines)
+		} else {
+			self.selection.saturating_add(lines).min(max)
+		};
+	}
+
+	fn select(&mut self) {
+		if let Some(author) = self.authors.get(self.selection) {
+			self.queue.push(InternalEvent::FilterLogAuthor(
+				author.email.clone(),
+			));
+			self.hide();
+		}
+	}
+
+	fn list_entry(
+		&self,
+		author: &AuthorInfo,
+		selected: bool,
+	) -> Span<'static> {
+		Span::styled(
+			format!(
+				"{:>6} {} <{}>",
+				author.commits, author.name, author.email
+			),
+			self.theme.text(true, selected),
+		)
+	}
+}
+
+impl DrawableComponent for LogAuthorsPopup {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		_rect: Rect,
+	) -> Result<()> {
+		if self.visible {
+			let area = ui::centered_rect(60, 60, f.size());
+
+			let height = usize::from(area.height.saturating_sub(2));
+			self.current_height.set(height);
+
+			self.scroll.update(
+				self.selection,
+				self.authors.len(),
+				height,
+			);
+
+			let block = Block::default()
+				.title(Span::styled(
+					strings::log_authors_title(&self.key_config),
+					self.theme.title(true),
+				))
+				.borders(Borders::ALL)
+				.border_type(BorderType::Thick)
+				.border_style(self.theme.block(true));
+
+			f.render_widget(Clear, area);
+
+			if self.authors.is_empty() {
+				f.render_widget(
+					Paragraph::new(strings::LOG_AUTHORS_EMPTY)
+						.style(self.theme.text(false, false))
+						.block(block),
+					area,
+				);
+				return Ok(());
+			}
+
+			let items = self
+				.authors
+				.iter()
+				.enumerate()
+				.skip(self.scroll.get_top())
+				.take(height)
+				.map(|(idx, author)| {
+					self.list_entry(author, idx == self.selection)
+				});
+
+			ui::draw_list_block(f, area, block, items);
+
+			self.scroll.draw(f, area, &self.theme);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for LogAuthorsPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.visible || force_all {
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::log_authors_select(
+					&self.key_config,
+				),
+				!self.authors.is_empty(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.visible {
+			if let Event::Key(e) = ev {
+				let page =
+					self.current_height.get().saturating_sub(1);
+
+				if e == self.key_config.exit_popup {
+					self.hide();
+				} else if e == self.key_config.enter {
+					self.select();
+				} else if e == self.key_config.move_up {
+					self.move_selection(true, 1);
+				} else if e == self.key_config.move_down {
+					self.move_selection(false, 1);
+				} else if e == self.key_config.page_up {
+					self.move_selection(true, page);
+				} else if e == self.key_config.page_down {
+					self.move_selection(false, page);
+				} else if e == self.key_config.home {
+					self.selection = 0;
+				} else if e == self.key_config.end {
+					self.move_selection(false, usize::MAX);
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
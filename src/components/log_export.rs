@@ -0,0 +1,297 @@
+use super::{
+	textinput::TextInputComponent, visibility_blocking,
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	log_export::{expand_tilde, AsyncLogExportJob, LogExportFormat},
+	messages::Severity,
+	queue::{Action, InternalEvent, Queue},
+	strings, ui,
+	ui::style::SharedTheme,
+	AsyncAppNotification, AsyncNotification,
+};
+use anyhow::Result;
+use asyncgit::{asyncjob::AsyncSingleJob, sync::CommitId};
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use std::path::{Path, PathBuf};
+use tui::{
+	backend::Backend,
+	layout::Rect,
+	text::Span,
+	widgets::{Block, BorderType, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// asks for the path to export the (filtered) log to and shows the
+/// progress of the export running in the background
+pub struct LogExportPopup {
+	input: TextInputComponent,
+	ids: Vec<CommitId>,
+	job: AsyncSingleJob<AsyncLogExportJob>,
+	/// path and number of commits of the running export
+	exporting: Option<(PathBuf, usize)>,
+	progress: usize,
+	show_progress: bool,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for LogExportPopup {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		if self.show_progress {
+			if let Some((path, total)) = &self.exporting {
+				let area =
+					ui::centered_rect_absolute(60, 4, f.size());
+
+				f.render_widget(Clear, area);
+				f.render_widget(
+					Paragraph::new(strings::log_export_progress(
+						self.progress,
+						*total,
+						&path.to_string_lossy(),
+					))
+					.style(self.theme.text(true, false))
+					.block(
+						Block::default()
+							.title(Span::styled(
+								strings::LOG_EXPORT_TITLE,
+								self.theme.title(true),
+							))
+							.borders(Borders::ALL)
+							.border_type(BorderType::Thick)
+							.border_style(self.theme.block(true)),
+					),
+					area,
+				);
+			}
+		} else {
+			self.input.draw(f, rect)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for LogExportPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.input.is_visible() || force_all {
+			self.input.commands(out, force_all);
+
+			out.push(CommandInfo::new(
+				strings::commands::log_export_confirm(
+					&self.key_config,
+				),
+				!self.input.get_text().is_empty(),
+				true,
+			));
+		}
+
+		if self.show_progress {
+			out.push(CommandInfo::new(
+				strings::commands::log_export_background(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.show_progress {
+			if let Event::Key(e) = ev {
+				if e == self.key_config.exit_popup {
+					self.show_progress = false;
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		if self.is_visible() {
+			if self.input.event(ev)?.is_consumed() {
+				self.update_title();
+				return Ok(EventState::Consumed);
+			}
+
+			if let Event::Key(e) = ev {
+				if e == self.key_config.enter {
+					self.confirm();
+				}
+
+				return Ok(EventState::Consumed);
+			}
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn paste(&mut self, text: &str) -> Result<EventState> {
+		self.input.paste(text)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.input.is_visible() || self.show_progress
+	}
+
+	fn hide(&mut self) {
+		self.input.hide();
+		self.show_progress = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.input.show()?;
+
+		Ok(())
+	}
+
+	fn on_resize(&self) {
+		self.input.on_resize();
+	}
+}
+
+impl LogExportPopup {
+	///
+	pub fn new(
+		queue: &Queue,
+		sender: &Sender<AsyncAppNotification>,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			input: TextInputComponent::new(
+				theme.clone(),
+				key_config.clone(),
+				strings::LOG_EXPORT_TITLE,
+				strings::LOG_EXPORT_HINT,
+				false,
+			),
+			ids: Vec::new(),
+			job: AsyncSingleJob::new(sender.clone()),
+			exporting: None,
+			progress: 0,
+			show_progress: false,
+			queue: queue.clone(),
+			theme,
+			key_config,
+		}
+	}
+
+	/// `ids` of the log as loaded (and filtered), shows the progress
+	/// instead while an export is running
+	pub fn open(&mut self, ids: Vec<CommitId>) -> Result<()> {
+		if self.exporting.is_some() {
+			self.show_progress = true;
+			return Ok(());
+		}
+
+		self.ids = ids;
+		self.update_title();
+		self.show()?;
+
+		Ok(())
+	}
+
+	///
+	pub fn any_work_pending(&self) -> bool {
+		self.job.is_pending()
+	}
+
+	///
+	pub fn update_async(&mut self, ev: AsyncNotification) {
+		if ev
+			!= AsyncNotification::App(AsyncAppNotification::LogExport)
+		{
+			return;
+		}
+
+		if let Some(job) = self.job.take_last() {
+			if let Some(result) = job.result() {
+				self.finished(result);
+			}
+		} else {
+			self.progress = self.job.progress().unwrap_or_default();
+		}
+	}
+
+	/// starts the export to `path`, overwriting it
+	pub fn export(&mut self, path: PathBuf) {
+		self.input.hide();
+		self.progress = 0;
+		self.exporting = Some((path.clone(), self.ids.len()));
+		self.show_progress = true;
+
+		self.job.spawn(AsyncLogExportJob::new(
+			std::mem::take(&mut self.ids),
+			path,
+		));
+	}
+
+	fn confirm(&mut self) {
+		let text = self.input.get_text().trim();
+		if text.is_empty() {
+			return;
+		}
+
+		let path = expand_tilde(text);
+		if path.exists() {
+			self.queue.push(InternalEvent::ConfirmAction(
+				Action::LogExportOverwrite(path),
+			));
+		} else {
+			self.export(path);
+		}
+	}
+
+	fn finished(&mut self, result: asyncgit::Result<PathBuf>) {
+		let total = self
+			.exporting
+			.take()
+			.map(|(_, total)| total)
+			.unwrap_or_default();
+		self.show_progress = false;
+
+		match result {
+			Ok(path) => {
+				self.queue.push(InternalEvent::ShowToast(
+					Severity::Success,
+					strings::toast_log_exported(
+						total,
+						&path.to_string_lossy(),
+					),
+				));
+			}
+			Err(e) => {
+				log::error!("log export: {}", e);
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("log export error:\n{}", e),
+				));
+			}
+		}
+	}
+
+	fn update_title(&mut self) {
+		let format = LogExportFormat::from_path(Path::new(
+			self.input.get_text().trim(),
+		));
+
+		self.input.set_title(strings::log_export_popup_title(
+			self.ids.len(),
+			format,
+		));
+	}
+}
@@ -0,0 +1,305 @@
+use super::{
+	utils::date_range::{
+		parse_date, DateBound, DateLimit, DateRange,
+	},
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState, TextInputComponent,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use chrono::Local;
+use crossterm::event::Event;
+use tui::{
+	backend::Backend,
+	layout::{Constraint, Direction, Layout, Margin, Rect},
+	text::Span,
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// edits the date range the log is limited to
+pub struct LogFilterPopup {
+	queue: Queue,
+	visible: bool,
+	since: TextInputComponent,
+	until: TextInputComponent,
+	focus: DateBound,
+	error: Option<String>,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl LogFilterPopup {
+	///
+	pub fn new(
+		queue: &Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		let input = || {
+			let mut input = TextInputComponent::new(
+				theme.clone(),
+				key_config.clone(),
+				"",
+				"",
+				false,
+			);
+			input.embed();
+			input
+		};
+
+		Self {
+			queue: queue.clone(),
+			visible: false,
+			since: input(),
+			until: input(),
+			focus: DateBound::Since,
+			error: None,
+			theme,
+			key_config,
+		}
+	}
+
+	///
+	pub fn open(&mut self, range: &DateRange) -> Result<()> {
+		let input_text = |limit: &Option<DateLimit>| {
+			limit
+				.as_ref()
+				.map_or_else(String::new, |limit| limit.input.clone())
+		};
+
+		self.since.set_text(input_text(&range.since));
+		self.until.set_text(input_text(&range.until));
+		self.focus = DateBound::Since;
+		self.error = None;
+
+		self.show()
+	}
+
+	fn focused_input(&mut self) -> &mut TextInputComponent {
+		match self.focus {
+			DateBound::Since => &mut self.since,
+			DateBound::Until => &mut self.until,
+		}
+	}
+
+	fn toggle_focus(&mut self) {
+		self.focus = match self.focus {
+			DateBound::Since => DateBound::Until,
+			DateBound::Until => DateBound::Since,
+		};
+	}
+
+	fn parse_limit(
+		input: &TextInputComponent,
+		bound: DateBound,
+	) -> Result<Option<DateLimit>, String> {
+		let text = input.get_text().trim();
+
+		if text.is_empty() {
+			return Ok(None);
+		}
+
+		parse_date(text, bound, Local::now())
+			.map(|time| {
+				Some(DateLimit {
+					input: text.to_string(),
+					time,
+				})
+			})
+			.ok_or_else(|| strings::log_filter_invalid_date(text))
+	}
+
+	fn apply(&mut self) {
+		let range = Self::parse_limit(&self.since, DateBound::Since)
+			.and_then(|since| {
+				Self::parse_limit(&self.until, DateBound::Until)
+					.map(|until| DateRange { since, until })
+			});
+
+		match range {
+			Ok(range) => {
+				self.queue.push(InternalEvent::FilterLog(range));
+				self.hide();
+			}
+			Err(e) => self.error = Some(e),
+		}
+	}
+
+	fn draw_field<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		area: Rect,
+		bound: DateBound,
+	) -> Result<()> {
+		let (label, input) = match bound {
+			DateBound::Since => {
+				(strings::LOG_FILTER_SINCE, &self.since)
+			}
+			DateBound::Until => {
+				(strings::LOG_FILTER_UNTIL, &self.until)
+			}
+		};
+
+		let chunks = Layout::default()
+			.direction(Direction::Horizontal)
+			.constraints(
+				[Constraint::Length(7), Constraint::Percentage(100)]
+					.as_ref(),
+			)
+			.split(area);
+
+		f.render_widget(
+			Paragraph::new(Span::styled(
+				label,
+				self.theme.text(true, self.focus == bound),
+			)),
+			chunks[0],
+		);
+
+		input.draw(f, chunks[1])
+	}
+}
+
+impl DrawableComponent for LogFilterPopup {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		area: Rect,
+	) -> Result<()> {
+		if self.is_visible() {
+			const SIZE: (u16, u16) = (50, 5);
+
+			let area =
+				ui::centered_rect_absolute(SIZE.0, SIZE.1, area);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Block::default()
+					.borders(Borders::all())
+					.style(self.theme.title(true))
+					.title(Span::styled(
+						strings::log_filter_title(&self.key_config),
+						self.theme.title(true),
+					)),
+				area,
+			);
+
+			let chunks = Layout::default()
+				.direction(Direction::Vertical)
+				.constraints(
+					[
+						Constraint::Length(1),
+						Constraint::Length(1),
+						Constraint::Length(1),
+					]
+					.as_ref(),
+				)
+				.split(area.inner(&Margin {
+					horizontal: 1,
+					vertical: 1,
+				}));
+
+			self.draw_field(f, chunks[0], DateBound::Since)?;
+			self.draw_field(f, chunks[1], DateBound::Until)?;
+
+			let hint = self.error.as_ref().map_or_else(
+				|| {
+					Span::styled(
+						strings::LOG_FILTER_HINT,
+						self.theme.text(false, false),
+					)
+				},
+				|error| {
+					Span::styled(
+						error.as_str(),
+						self.theme.text_danger(),
+					)
+				},
+			);
+			f.render_widget(Paragraph::new(hint), chunks[2]);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for LogFilterPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(CommandInfo::new(
+				strings::commands::log_filter_switch(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::log_filter_apply(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = ev {
+				if key == self.key_config.exit_popup {
+					self.hide();
+				} else if key == self.key_config.enter {
+					self.apply();
+				} else if key == self.key_config.tab_toggle
+					|| key == self.key_config.tab_toggle_reverse
+				{
+					self.toggle_focus();
+				} else if self
+					.focused_input()
+					.event(ev)?
+					.is_consumed()
+				{
+					self.error = None;
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+		self.since.show()?;
+		self.until.show()?;
+
+		Ok(())
+	}
+}
@@ -1,67 +1,123 @@
 mod blame_file;
+mod bookmarks;
+mod branch_description;
 mod branchlist;
 mod changes;
+mod cherry_pick;
 mod command;
+mod command_palette;
 mod commit;
 mod commit_details;
 mod commitlist;
 mod compare_commits;
+mod compare_workdir;
+mod conflict;
 mod create_branch;
 mod cred;
 mod diff;
 mod externaleditor;
+mod fetch;
 mod file_find_popup;
+mod file_view;
 mod filetree;
+mod git_config;
 mod help;
+mod hook_output;
+mod index_flags;
+mod init_repo;
 mod inspect_commit;
+mod jump_to_ref;
+mod log_authors;
+mod log_export;
+mod log_filter;
 mod msg;
+mod msg_history;
 mod options_popup;
+mod preflight;
+mod prune_remote;
 mod pull;
 mod push;
 mod push_tags;
+mod recent_branches;
 mod rename_branch;
+mod repo_maintenance;
+mod repo_switcher;
 mod reset;
 mod revision_files;
 mod revision_files_popup;
+mod stage_pattern;
 mod stashmsg;
 mod syntax_text;
 mod tag_commit;
 mod taglist;
 mod textinput;
+mod timings;
 mod utils;
 
 pub use self::filetree::FileTreeComponent;
 pub use blame_file::BlameFileComponent;
+pub use bookmarks::BookmarksPopup;
+pub use branch_description::BranchDescriptionPopup;
 pub use branchlist::BranchListComponent;
 pub use changes::ChangesComponent;
+pub use cherry_pick::CherryPickPopup;
 pub use command::{CommandInfo, CommandText};
+pub use command_palette::{
+	CommandPalettePopup, PaletteEntry, PaletteState,
+};
 pub use commit::CommitComponent;
 pub use commit_details::CommitDetailsComponent;
 pub use commitlist::CommitList;
 pub use compare_commits::CompareCommitsComponent;
+pub use compare_workdir::CompareWorkdirComponent;
+pub use conflict::ConflictPopup;
 pub use create_branch::CreateBranchComponent;
-pub use diff::DiffComponent;
+pub use diff::{DiffCommits, DiffComponent};
 pub use externaleditor::ExternalEditorComponent;
+pub use fetch::FetchComponent;
 pub use file_find_popup::FileFindPopup;
+pub use file_view::{FileSource, FileViewPopup};
+pub use git_config::GitConfigPopup;
 pub use help::HelpComponent;
+pub use hook_output::HookOutputComponent;
+pub use index_flags::IndexFlagsPopup;
+pub use init_repo::InitRepoPopup;
 pub use inspect_commit::InspectCommitComponent;
+pub use jump_to_ref::JumpToRefPopup;
+pub use log_authors::LogAuthorsPopup;
+pub use log_export::LogExportPopup;
+pub use log_filter::LogFilterPopup;
 pub use msg::MsgComponent;
+pub use msg_history::MsgHistoryComponent;
 pub use options_popup::{
-	AppOption, OptionsPopupComponent, SharedOptions,
+	AppOption, Options, OptionsPopupComponent, SharedOptions,
 };
+pub use preflight::PreflightPopup;
+pub use prune_remote::PruneRemoteComponent;
 pub use pull::PullComponent;
 pub use push::PushComponent;
 pub use push_tags::PushTagsComponent;
+pub use recent_branches::RecentBranchesComponent;
 pub use rename_branch::RenameBranchComponent;
+pub use repo_maintenance::RepoMaintenanceComponent;
+pub use repo_switcher::RepoSwitcherPopup;
 pub use reset::ConfirmComponent;
 pub use revision_files::RevisionFilesComponent;
 pub use revision_files_popup::RevisionFilesPopup;
+pub use stage_pattern::StagePatternPopup;
 pub use stashmsg::StashMsgComponent;
 pub use syntax_text::SyntaxTextComponent;
 pub use tag_commit::TagCommitComponent;
 pub use taglist::TagListComponent;
 pub use textinput::{InputType, TextInputComponent};
+pub use timings::TimingsComponent;
+pub use utils::date_range::DateRange;
 pub use utils::filetree::FileTreeItemKind;
+pub use utils::index_op::{
+	notify_flagged, run_index_op, with_index_snapshot,
+};
+pub use utils::selected_path::SelectedPath;
+pub use utils::time_to_string;
 
 use crate::ui::style::Theme;
 use anyhow::Result;
@@ -154,6 +210,27 @@ pub fn event_pump(
 	Ok(EventState::NotConsumed)
 }
 
+/// like `event_pump` for pasted text, stops at the first component
+/// that blocks the ones behind it
+pub fn paste_pump(
+	text: &str,
+	components: &mut [&mut dyn Component],
+) -> Result<EventState> {
+	for c in components {
+		if c.paste(text)?.is_consumed() {
+			return Ok(EventState::Consumed);
+		}
+
+		if c.commands(&mut Vec::new(), false)
+			== CommandBlocking::Blocking
+		{
+			break;
+		}
+	}
+
+	Ok(EventState::NotConsumed)
+}
+
 /// helper fn to simplify delegating command
 /// gathering down into child components
 /// see `event_pump`,`accessors`
@@ -250,6 +327,12 @@ pub trait Component {
 	///
 	fn event(&mut self, ev: Event) -> Result<EventState>;
 
+	/// text pasted in one piece, components that do not take it get
+	/// the keys one by one instead
+	fn paste(&mut self, _text: &str) -> Result<EventState> {
+		Ok(EventState::NotConsumed)
+	}
+
 	///
 	fn focused(&self) -> bool {
 		false
@@ -276,6 +359,16 @@ pub trait Component {
 			self.show()
 		}
 	}
+
+	/// resets state cached from the last draw (like the drawn area),
+	/// called after the terminal got resized
+	fn on_resize(&self) {}
+
+	/// the content shown as plain lines for the terminal to select
+	/// from in selection mode, `None` if there is nothing to select
+	fn selectable_text(&self) -> Option<Vec<String>> {
+		None
+	}
 }
 
 fn dialog_paragraph<'a>(
@@ -139,4 +139,18 @@ impl MsgComponent {
 
 		Ok(())
 	}
+
+	/// `msg` is on screen already
+	pub fn is_showing(&self, msg: &str) -> bool {
+		self.visible && self.msg == msg
+	}
+
+	///
+	pub fn show_info(&mut self, msg: &str) -> Result<()> {
+		self.title = strings::msg_title_info(&self.key_config);
+		self.msg = msg.to_string();
+		self.show()?;
+
+		Ok(())
+	}
 }
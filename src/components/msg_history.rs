@@ -0,0 +1,283 @@
+use super::{
+	utils::scroll_vertical::VerticalScroll, visibility_blocking,
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	messages::{Message, Severity},
+	queue::{InternalEvent, Queue},
+	strings, ui,
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use std::cell::Cell;
+use tui::{
+	backend::Backend,
+	layout::{Constraint, Direction, Layout, Rect},
+	text::Span,
+	widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+	Frame,
+};
+
+/// lists errors and toasts shown so far, newest first
+pub struct MsgHistoryComponent {
+	queue: Queue,
+	visible: bool,
+	selection: usize,
+	scroll: VerticalScroll,
+	current_height: Cell<usize>,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl MsgHistoryComponent {
+	///
+	pub fn new(
+		queue: &Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			queue: queue.clone(),
+			visible: false,
+			selection: 0,
+			scroll: VerticalScroll::new(),
+			current_height: Cell::new(0),
+			theme,
+			key_config,
+		}
+	}
+
+	fn move_selection(&mut self, up: bool, lines: usize) {
+		let max = self.queue.messages().len().saturating_sub(1);
+
+		self.selection = if up {
+			self.selection.saturating_sub(lines)
+		} else {
+			self.selection.saturating_add(lines).min(max)
+		};
+	}
+
+	fn copy_selected(&self) {
+		let text = self
+			.queue
+			.messages()
+			.get(self.selection)
+			.map(|msg| msg.text.clone());
+
+		if let Some(text) = text {
+			if let Err(e) = crate::clipboard::copy_string(&text) {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					strings::msg_history_copy_failed(&e.to_string()),
+				));
+			} else {
+				self.queue.push(InternalEvent::ShowToast(
+					Severity::Info,
+					strings::msg_history_copied(),
+				));
+			}
+		}
+	}
+
+	const fn severity_name(severity: Severity) -> &'static str {
+		match severity {
+			Severity::Info => "info",
+			Severity::Success => "success",
+			Severity::Error => "error",
+		}
+	}
+
+	fn list_entry(
+		&self,
+		msg: &Message,
+		selected: bool,
+	) -> Span<'static> {
+		Span::styled(
+			format!(
+				"{} {:<7} {}",
+				msg.time.format("%H:%M:%S"),
+				Self::severity_name(msg.severity),
+				msg.text.lines().next().unwrap_or_default()
+			),
+			self.theme.message(msg.severity, selected),
+		)
+	}
+
+	fn draw_list<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+		let height = usize::from(area.height.saturating_sub(2));
+		self.current_height.set(height);
+
+		let messages = self.queue.messages();
+		self.scroll.update(self.selection, messages.len(), height);
+
+		let block = Block::default()
+			.title(Span::styled(
+				strings::msg_history_title(&self.key_config),
+				self.theme.title(true),
+			))
+			.borders(Borders::ALL)
+			.border_type(BorderType::Thick)
+			.border_style(self.theme.block(true));
+
+		if messages.is_empty() {
+			f.render_widget(
+				Paragraph::new(strings::MSG_HISTORY_EMPTY)
+					.style(self.theme.text(false, false))
+					.block(block),
+				area,
+			);
+			return;
+		}
+
+		let items = messages
+			.iter()
+			.enumerate()
+			.skip(self.scroll.get_top())
+			.take(height)
+			.map(|(idx, msg)| {
+				self.list_entry(msg, idx == self.selection)
+			});
+
+		ui::draw_list_block(f, area, block, items);
+
+		self.scroll.draw(f, area, &self.theme);
+	}
+
+	fn draw_details<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+		let messages = self.queue.messages();
+		let msg = messages.get(self.selection);
+
+		let title = msg.map_or_else(String::new, |msg| {
+			msg.time.format("%Y-%m-%d %H:%M:%S").to_string()
+		});
+
+		f.render_widget(
+			Paragraph::new(
+				msg.map(|msg| msg.text.as_str()).unwrap_or_default(),
+			)
+			.style(msg.map_or_else(
+				|| self.theme.text(true, false),
+				|msg| self.theme.message(msg.severity, false),
+			))
+			.block(
+				Block::default()
+					.title(Span::styled(
+						title,
+						self.theme.title(false),
+					))
+					.borders(Borders::ALL)
+					.border_type(BorderType::Thick)
+					.border_style(self.theme.block(false)),
+			)
+			.wrap(Wrap { trim: false }),
+			area,
+		);
+	}
+}
+
+impl DrawableComponent for MsgHistoryComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		_rect: Rect,
+	) -> Result<()> {
+		if self.visible {
+			let area = ui::centered_rect(80, 70, f.size());
+
+			let chunks = Layout::default()
+				.direction(Direction::Vertical)
+				.constraints(
+					[
+						Constraint::Percentage(60),
+						Constraint::Percentage(40),
+					]
+					.as_ref(),
+				)
+				.split(area);
+
+			f.render_widget(Clear, area);
+			self.draw_list(f, chunks[0]);
+			self.draw_details(f, chunks[1]);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for MsgHistoryComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.visible || force_all {
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::msg_history_copy(&self.key_config),
+				!self.queue.messages().is_empty(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.visible {
+			if let Event::Key(e) = ev {
+				let page =
+					self.current_height.get().saturating_sub(1);
+
+				if e == self.key_config.exit_popup
+					|| e == self.key_config.open_msg_history
+				{
+					self.hide();
+				} else if e == self.key_config.move_up {
+					self.move_selection(true, 1);
+				} else if e == self.key_config.move_down {
+					self.move_selection(false, 1);
+				} else if e == self.key_config.page_up {
+					self.move_selection(true, page);
+				} else if e == self.key_config.page_down {
+					self.move_selection(false, page);
+				} else if e == self.key_config.home {
+					self.selection = 0;
+				} else if e == self.key_config.end {
+					self.move_selection(false, usize::MAX);
+				} else if e == self.key_config.copy {
+					self.copy_selected();
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.selection = 0;
+		self.visible = true;
+
+		Ok(())
+	}
+}
@@ -1,21 +1,40 @@
 #![allow(dead_code)]
 
-use std::{cell::RefCell, rc::Rc};
+use std::{
+	cell::{Cell, RefCell},
+	convert::TryFrom,
+	fs,
+	rc::Rc,
+	time::Duration,
+};
 
 use super::{
 	visibility_blocking, CommandBlocking, CommandInfo, Component,
-	DrawableComponent, EventState,
+	DrawableComponent, EventState, InputType, TextInputComponent,
 };
 use crate::{
-	components::utils::string_width_align,
+	args::get_app_config_path,
+	commit_lint::{CommitLint, CommitLintConfig, LintProblem},
+	commit_prefill::{CommitPrefill, CommitPrefillConfig},
+	components::utils::{
+		diff_render::DiffRender,
+		logitems::{LogDateFormat, LogDateSource, LogGrouping},
+		string_width_align,
+	},
+	confirm_policy::{ConfirmPolicy, DestructiveAction},
 	keys::SharedKeyConfig,
+	message_markup::{IssueReference, IssueReferenceConfig},
 	queue::{InternalEvent, Queue},
+	string_utils::glob_match,
 	strings::{self},
 	ui::{self, style::SharedTheme},
 };
 use anyhow::Result;
 use asyncgit::sync::{diff::DiffOptions, ShowUntrackedFilesConfig};
+use bytesize::ByteSize;
 use crossterm::event::Event;
+use ron::ser::{to_string_pretty, PrettyConfig};
+use serde::{de::DeserializeOwned, Serialize};
 use tui::{
 	backend::Backend,
 	layout::{Alignment, Rect},
@@ -28,15 +47,362 @@ use tui::{
 #[derive(Clone, Copy, PartialEq)]
 pub enum AppOption {
 	StatusShowUntracked,
+	StatusUntrackedLimit,
 	DiffIgnoreWhitespaces,
 	DiffContextLines,
 	DiffInterhunkLines,
+	DiffTabWidth,
+	DiffTrailingWhitespace,
+	DiffShowWhitespace,
+	DiffMovedMinLines,
+	LogDateFormat,
+	LogDateSource,
+	LogGrouping,
+	AutoFetchInterval,
+	StashShowList,
+	ProtectedBranches,
+	CommitLint,
+	CommitLintTypes,
+	CommitPrefillPattern,
+	CommitPrefillTemplate,
+	CommitTrailers,
+	LargeFileThreshold,
+	Confirm(DestructiveAction),
+	HighContrast,
+	DiffGlyphs,
+	SelectionMarker,
+	StatusLetters,
+	RememberUiState,
+	PersistBookmarks,
 }
 
-#[derive(Default, Copy, Clone)]
+#[derive(Clone)]
 pub struct Options {
 	pub status_show_untracked: Option<ShowUntrackedFilesConfig>,
+	/// max untracked files to collect, `0` means no limit
+	pub status_untracked_limit: usize,
 	pub diff: DiffOptions,
+	/// set with `set_diff_render` to persist it
+	pub diff_render: DiffRender,
+	/// set with `set_log_date_format` to persist it
+	pub log_date_format: LogDateFormat,
+	/// set with `set_log_date_source` to persist it
+	pub log_date_source: LogDateSource,
+	/// header rows between days, weeks or months in the log,
+	/// set with `set_log_grouping` to persist it
+	pub log_grouping: LogGrouping,
+	/// minutes between background fetches, `0` means off
+	pub auto_fetch_interval: u32,
+	/// switch to the stash list after creating a stash
+	pub stash_show_list: bool,
+	/// branch name globs that warn before committing or pushing
+	pub protected_branches: Vec<String>,
+	/// restore tab, selections and diff options on the next start
+	pub remember_ui_state: bool,
+	/// save the bookmarks with the ui state
+	pub persist_bookmarks: bool,
+	pub commit_lint: CommitLintConfig,
+	/// `None` if linting is off
+	commit_linter: Option<CommitLint>,
+	pub commit_prefill: CommitPrefillConfig,
+	/// `None` if prefilling is off
+	commit_prefiller: Option<CommitPrefill>,
+	/// appended to every commit message, e.g. `Signed-off-by: ..`
+	pub commit_trailers: Vec<String>,
+	pub confirm_policy: ConfirmPolicy,
+	/// bytes from which staging or committing new files warns,
+	/// `0` means off
+	pub large_file_threshold: u64,
+	/// linked in commit messages, only edited in the file
+	issue_references: Vec<IssueReference>,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Self {
+			status_show_untracked: None,
+			status_untracked_limit: UNTRACKED_LIMIT_STEP,
+			diff: DiffOptions::default(),
+			diff_render: DiffRender::default(),
+			log_date_format: LogDateFormat::default(),
+			log_date_source: LogDateSource::default(),
+			log_grouping: LogGrouping::default(),
+			auto_fetch_interval: 0,
+			stash_show_list: false,
+			protected_branches: vec![
+				String::from("main"),
+				String::from("master"),
+				String::from("release/*"),
+			],
+			remember_ui_state: true,
+			persist_bookmarks: false,
+			commit_lint: CommitLintConfig::default(),
+			commit_linter: None,
+			commit_prefill: CommitPrefillConfig::default(),
+			commit_prefiller: None,
+			commit_trailers: Vec::new(),
+			confirm_policy: ConfirmPolicy::default(),
+			large_file_threshold: LARGE_FILE_THRESHOLD_DEFAULT,
+			issue_references: Vec::new(),
+		}
+	}
+}
+
+impl Options {
+	/// defaults with the persisted settings applied
+	pub fn load() -> Self {
+		let mut options = Self::default();
+
+		if let Some(branches) = read_ron(PROTECTED_BRANCHES_FILE) {
+			options.protected_branches = branches;
+		}
+
+		if let Some(config) = read_ron(COMMIT_LINT_FILE) {
+			options.set_commit_lint(config);
+		}
+
+		if let Some(config) = read_ron(COMMIT_PREFILL_FILE) {
+			options.set_commit_prefill(config);
+		}
+
+		if let Some(policy) = read_ron(CONFIRM_POLICY_FILE) {
+			options.confirm_policy = policy;
+		}
+
+		if let Some(trailers) = read_ron(COMMIT_TRAILERS_FILE) {
+			options.commit_trailers = trailers;
+		}
+
+		if let Some(threshold) = read_ron(LARGE_FILE_THRESHOLD_FILE) {
+			options.large_file_threshold = threshold;
+		}
+
+		if let Some(render) = read_ron(DIFF_RENDER_FILE) {
+			options.diff_render = render;
+		}
+
+		if let Some(format) = read_ron(LOG_DATE_FORMAT_FILE) {
+			options.log_date_format = format;
+		}
+
+		if let Some(source) = read_ron(LOG_DATE_SOURCE_FILE) {
+			options.log_date_source = source;
+		}
+
+		if let Some(grouping) = read_ron(LOG_GROUPING_FILE) {
+			options.log_grouping = grouping;
+		}
+
+		if let Some(configs) = read_ron::<Vec<IssueReferenceConfig>>(
+			ISSUE_REFERENCES_FILE,
+		) {
+			options.set_issue_references(&configs);
+		}
+
+		options
+	}
+
+	/// saved for the next start as well
+	pub fn set_log_date_format(
+		&mut self,
+		format: LogDateFormat,
+	) -> Result<()> {
+		self.log_date_format = format;
+		save_ron(LOG_DATE_FORMAT_FILE, &format)
+	}
+
+	/// saved for the next start as well
+	pub fn set_log_date_source(
+		&mut self,
+		source: LogDateSource,
+	) -> Result<()> {
+		self.log_date_source = source;
+		save_ron(LOG_DATE_SOURCE_FILE, &source)
+	}
+
+	/// saved for the next start as well
+	pub fn set_log_grouping(
+		&mut self,
+		grouping: LogGrouping,
+	) -> Result<()> {
+		self.log_grouping = grouping;
+		save_ron(LOG_GROUPING_FILE, &grouping)
+	}
+
+	/// saved for the next start as well
+	pub fn set_diff_render(
+		&mut self,
+		render: DiffRender,
+	) -> Result<()> {
+		self.diff_render = render;
+		save_ron(DIFF_RENDER_FILE, &render)
+	}
+
+	/// invalid references are left out
+	pub fn set_issue_references(
+		&mut self,
+		configs: &[IssueReferenceConfig],
+	) {
+		self.issue_references = configs
+			.iter()
+			.filter_map(|config| {
+				IssueReference::new(config)
+					.map_err(|e| {
+						log::error!(
+							"issue reference {:?}: {}",
+							config.pattern,
+							e
+						);
+					})
+					.ok()
+			})
+			.collect();
+	}
+
+	pub fn issue_references(&self) -> &[IssueReference] {
+		&self.issue_references
+	}
+
+	/// an invalid pattern falls back to the default one
+	pub fn set_commit_lint(&mut self, config: CommitLintConfig) {
+		self.commit_linter = if config.enabled {
+			CommitLint::new(&config)
+				.or_else(|e| {
+					log::error!("commit lint pattern: {}", e);
+					CommitLint::new(&CommitLintConfig {
+						pattern: CommitLintConfig::default().pattern,
+						..config.clone()
+					})
+				})
+				.ok()
+		} else {
+			None
+		};
+		self.commit_lint = config;
+	}
+
+	/// an invalid config turns prefilling off
+	pub fn set_commit_prefill(
+		&mut self,
+		config: CommitPrefillConfig,
+	) {
+		self.commit_prefiller = if config.is_enabled() {
+			CommitPrefill::new(&config)
+				.map_err(|e| log::error!("commit prefill: {}", e))
+				.ok()
+		} else {
+			None
+		};
+		self.commit_prefill = config;
+	}
+
+	/// start of the subject for a new commit on `branch`, `None` if
+	/// prefilling is off or the branch does not match
+	pub fn commit_prefill(&self, branch: &str) -> Option<String> {
+		self.commit_prefiller
+			.as_ref()
+			.and_then(|prefill| prefill.prefill(branch))
+	}
+
+	/// empty if linting is off
+	pub fn lint_commit_subject(
+		&self,
+		subject: &str,
+	) -> Vec<LintProblem> {
+		self.commit_linter
+			.as_ref()
+			.map(|lint| lint.check(subject))
+			.unwrap_or_default()
+	}
+
+	/// `branch` matches any of the protected patterns
+	pub fn is_protected_branch(&self, branch: &str) -> bool {
+		self.protected_branches
+			.iter()
+			.any(|pattern| glob_match(pattern, branch))
+	}
+
+	/// untracked limit to pass to the status request
+	pub const fn untracked_limit(&self) -> Option<usize> {
+		if self.status_untracked_limit == 0 {
+			None
+		} else {
+			Some(self.status_untracked_limit)
+		}
+	}
+
+	/// `None` if the large file warning is off
+	pub const fn large_file_threshold(&self) -> Option<u64> {
+		if self.large_file_threshold == 0 {
+			None
+		} else {
+			Some(self.large_file_threshold)
+		}
+	}
+
+	/// `None` if auto fetch is off
+	pub fn auto_fetch_interval(&self) -> Option<Duration> {
+		if self.auto_fetch_interval == 0 {
+			None
+		} else {
+			Some(Duration::from_secs(
+				u64::from(self.auto_fetch_interval) * 60,
+			))
+		}
+	}
+}
+
+const UNTRACKED_LIMIT_STEP: usize = 1000;
+
+const MIB: u64 = 1024 * 1024;
+const LARGE_FILE_THRESHOLD_DEFAULT: u64 = 10 * MIB;
+const LARGE_FILE_THRESHOLD_STEP: u64 = 5 * MIB;
+
+/// files in the app config directory the options are saved to
+const PROTECTED_BRANCHES_FILE: &str = "protected_branches.ron";
+const COMMIT_LINT_FILE: &str = "commit_lint.ron";
+const COMMIT_PREFILL_FILE: &str = "commit_prefill.ron";
+const COMMIT_TRAILERS_FILE: &str = "commit_trailers.ron";
+const CONFIRM_POLICY_FILE: &str = "confirm_policy.ron";
+const LARGE_FILE_THRESHOLD_FILE: &str = "large_file_threshold.ron";
+const DIFF_RENDER_FILE: &str = "diff_render.ron";
+const LOG_DATE_FORMAT_FILE: &str = "log_date_format.ron";
+const LOG_DATE_SOURCE_FILE: &str = "log_date_source.ron";
+const LOG_GROUPING_FILE: &str = "log_grouping.ron";
+/// never written by gitui
+const ISSUE_REFERENCES_FILE: &str = "issue_references.ron";
+
+/// `None` if `name` was never saved or cannot be read, the latter is
+/// logged
+fn read_ron<T: DeserializeOwned>(name: &str) -> Option<T> {
+	let read = || -> Result<Option<T>> {
+		let file = get_app_config_path()?.join(name);
+		if !file.exists() {
+			return Ok(None);
+		}
+
+		Ok(Some(ron::de::from_bytes(&fs::read(file)?)?))
+	};
+
+	read().unwrap_or_else(|e| {
+		log::error!("read {}: {}", name, e);
+		None
+	})
+}
+
+fn save_ron<T: Serialize>(name: &str, value: &T) -> Result<()> {
+	let data = to_string_pretty(value, PrettyConfig::default())?;
+	fs::write(get_app_config_path()?.join(name), data)?;
+
+	Ok(())
+}
+
+/// comma or whitespace separated patterns or types
+fn parse_list(text: &str) -> Vec<String> {
+	text.split(|c: char| c == ',' || c.is_whitespace())
+		.filter(|pattern| !pattern.is_empty())
+		.map(String::from)
+		.collect()
 }
 
 pub type SharedOptions = Rc<RefCell<Options>>;
@@ -48,6 +414,9 @@ pub struct OptionsPopupComponent {
 	key_config: SharedKeyConfig,
 	options: SharedOptions,
 	theme: SharedTheme,
+	input: TextInputComponent,
+	/// line of the selected entry as last drawn
+	selected_line: Cell<usize>,
 }
 
 impl OptionsPopupComponent {
@@ -62,16 +431,288 @@ impl OptionsPopupComponent {
 			selection: AppOption::StatusShowUntracked,
 			queue: queue.clone(),
 			visible: false,
+			input: TextInputComponent::new(
+				theme.clone(),
+				key_config.clone(),
+				strings::OPTIONS_PROTECTED_BRANCHES_TITLE,
+				strings::OPTIONS_PROTECTED_BRANCHES_MSG,
+				false,
+			)
+			.with_input_type(InputType::Singleline),
+			selected_line: Cell::new(0),
 			key_config,
 			options,
 			theme,
 		}
 	}
 
+	/// options edited as text
+	const fn is_text_option(&self) -> bool {
+		matches!(
+			self.selection,
+			AppOption::ProtectedBranches
+				| AppOption::CommitLintTypes
+				| AppOption::CommitPrefillPattern
+				| AppOption::CommitPrefillTemplate
+				| AppOption::CommitTrailers
+		)
+	}
+
+	fn edit_text_option(&mut self) -> Result<()> {
+		let (title, msg, text) = match self.selection {
+			AppOption::CommitLintTypes => (
+				strings::OPTIONS_COMMIT_LINT_TYPES_TITLE,
+				strings::OPTIONS_COMMIT_LINT_TYPES_MSG,
+				self.options.borrow().commit_lint.types.join(", "),
+			),
+			AppOption::CommitPrefillPattern => (
+				strings::OPTIONS_COMMIT_PREFILL_PATTERN_TITLE,
+				strings::OPTIONS_COMMIT_PREFILL_PATTERN_MSG,
+				self.options.borrow().commit_prefill.pattern.clone(),
+			),
+			AppOption::CommitPrefillTemplate => (
+				strings::OPTIONS_COMMIT_PREFILL_TEMPLATE_TITLE,
+				strings::OPTIONS_COMMIT_PREFILL_TEMPLATE_MSG,
+				self.options.borrow().commit_prefill.template.clone(),
+			),
+			AppOption::CommitTrailers => (
+				strings::OPTIONS_COMMIT_TRAILERS_TITLE,
+				strings::OPTIONS_COMMIT_TRAILERS_MSG,
+				self.options.borrow().commit_trailers.join("; "),
+			),
+			_ => (
+				strings::OPTIONS_PROTECTED_BRANCHES_TITLE,
+				strings::OPTIONS_PROTECTED_BRANCHES_MSG,
+				self.options.borrow().protected_branches.join(", "),
+			),
+		};
+
+		self.input.set_title(title.to_string());
+		self.input.set_default_msg(msg.to_string());
+		self.input.set_text(text);
+		self.input.show()
+	}
+
+	fn confirm_text_option(&mut self) {
+		match self.selection {
+			AppOption::CommitPrefillPattern => {
+				let config = CommitPrefillConfig {
+					pattern: self.input.get_text().trim().to_string(),
+					..self.options.borrow().commit_prefill.clone()
+				};
+				self.update_commit_prefill(config);
+				return;
+			}
+			AppOption::CommitPrefillTemplate => {
+				// trailing whitespace is part of the template
+				let config = CommitPrefillConfig {
+					template: self.input.get_text().to_string(),
+					..self.options.borrow().commit_prefill.clone()
+				};
+				self.update_commit_prefill(config);
+				return;
+			}
+			AppOption::CommitTrailers => {
+				// trailer values may contain commas and spaces
+				let trailers = self
+					.input
+					.get_text()
+					.split(';')
+					.map(str::trim)
+					.filter(|trailer| !trailer.is_empty())
+					.map(String::from)
+					.collect();
+				self.update_commit_trailers(trailers);
+				return;
+			}
+			_ => (),
+		}
+
+		let list = parse_list(self.input.get_text());
+		self.input.hide();
+
+		if self.selection == AppOption::CommitLintTypes {
+			let config = CommitLintConfig {
+				types: list,
+				..self.options.borrow().commit_lint.clone()
+			};
+			self.update_commit_lint(config);
+			return;
+		}
+
+		if let Err(e) = save_ron(PROTECTED_BRANCHES_FILE, &list) {
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"save protected branches:\n{}",
+				e
+			)));
+		}
+
+		self.options.borrow_mut().protected_branches = list;
+
+		self.queue
+			.push(InternalEvent::OptionSwitched(self.selection));
+	}
+
+	fn update_commit_lint(&mut self, config: CommitLintConfig) {
+		if let Err(e) = save_ron(COMMIT_LINT_FILE, &config) {
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"save commit lint:\n{}",
+				e
+			)));
+		}
+
+		self.options.borrow_mut().set_commit_lint(config);
+
+		self.queue
+			.push(InternalEvent::OptionSwitched(self.selection));
+	}
+
+	/// an invalid config is reported and neither saved nor applied,
+	/// the input stays open to fix it
+	fn update_commit_prefill(&mut self, config: CommitPrefillConfig) {
+		if config.is_enabled() {
+			if let Err(e) = CommitPrefill::new(&config) {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("invalid commit prefill:\n{}", e),
+				));
+				return;
+			}
+		}
+
+		self.input.hide();
+
+		if let Err(e) = save_ron(COMMIT_PREFILL_FILE, &config) {
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"save commit prefill:\n{}",
+				e
+			)));
+		}
+
+		self.options.borrow_mut().set_commit_prefill(config);
+
+		self.queue
+			.push(InternalEvent::OptionSwitched(self.selection));
+	}
+
+	fn update_commit_trailers(&mut self, trailers: Vec<String>) {
+		self.input.hide();
+
+		if let Err(e) = save_ron(COMMIT_TRAILERS_FILE, &trailers) {
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"save commit trailers:\n{}",
+				e
+			)));
+		}
+
+		self.options.borrow_mut().commit_trailers = trailers;
+
+		self.queue
+			.push(InternalEvent::OptionSwitched(self.selection));
+	}
+
+	fn cycle_confirm_level(
+		&mut self,
+		action: DestructiveAction,
+		right: bool,
+	) {
+		let mut policy = self.options.borrow().confirm_policy.clone();
+		let level = policy.level(action);
+		policy.set_level(
+			action,
+			if right { level.next() } else { level.prev() },
+		);
+
+		if let Err(e) = save_ron(CONFIRM_POLICY_FILE, &policy) {
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"save confirm policy:\n{}",
+				e
+			)));
+		}
+
+		self.options.borrow_mut().confirm_policy = policy;
+	}
+
+	fn step_large_file_threshold(&self, right: bool) {
+		let old = self.options.borrow().large_file_threshold;
+		let threshold = if right {
+			old.saturating_add(LARGE_FILE_THRESHOLD_STEP)
+		} else {
+			old.saturating_sub(LARGE_FILE_THRESHOLD_STEP)
+		};
+
+		if let Err(e) =
+			save_ron(LARGE_FILE_THRESHOLD_FILE, &threshold)
+		{
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"save large file threshold:\n{}",
+				e
+			)));
+		}
+
+		self.options.borrow_mut().large_file_threshold = threshold;
+	}
+
+	fn set_diff_render(&self, render: DiffRender) {
+		if let Err(e) =
+			self.options.borrow_mut().set_diff_render(render)
+		{
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"save diff render:\n{}",
+				e
+			)));
+		}
+	}
+
+	fn set_log_date_format(&self, format: LogDateFormat) {
+		if let Err(e) =
+			self.options.borrow_mut().set_log_date_format(format)
+		{
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"save log date format:\n{}",
+				e
+			)));
+		}
+	}
+
+	fn set_log_date_source(&self, source: LogDateSource) {
+		if let Err(e) =
+			self.options.borrow_mut().set_log_date_source(source)
+		{
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"save log date source:\n{}",
+				e
+			)));
+		}
+	}
+
+	fn set_log_grouping(&self, grouping: LogGrouping) {
+		if let Err(e) =
+			self.options.borrow_mut().set_log_grouping(grouping)
+		{
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"save log grouping:\n{}",
+				e
+			)));
+		}
+	}
+
+	fn toggle_commit_lint(&mut self) {
+		let config = CommitLintConfig {
+			enabled: !self.options.borrow().commit_lint.enabled,
+			..self.options.borrow().commit_lint.clone()
+		};
+		self.update_commit_lint(config);
+	}
+
 	fn get_text(&self, width: u16) -> Vec<Spans> {
 		let mut txt: Vec<Spans> = Vec::with_capacity(10);
 
 		self.add_status(&mut txt, width);
+		self.add_branch(&mut txt, width);
+		self.add_commit(&mut txt, width);
+		self.add_confirm(&mut txt, width);
+		self.add_accessibility(&mut txt, width);
+		self.add_general(&mut txt, width);
 
 		txt
 	}
@@ -91,6 +732,18 @@ impl OptionsPopupComponent {
 			},
 			self.is_select(AppOption::StatusShowUntracked),
 		);
+		let limit = self.options.borrow().status_untracked_limit;
+		self.add_entry(
+			txt,
+			width,
+			"Untracked limit",
+			&if limit == 0 {
+				String::from("None")
+			} else {
+				limit.to_string()
+			},
+			self.is_select(AppOption::StatusUntrackedLimit),
+		);
 		Self::add_header(txt, "");
 
 		Self::add_header(txt, "Diff");
@@ -115,12 +768,296 @@ impl OptionsPopupComponent {
 			&self.options.borrow().diff.interhunk_lines.to_string(),
 			self.is_select(AppOption::DiffInterhunkLines),
 		);
+		let render = self.options.borrow().diff_render;
+		self.add_entry(
+			txt,
+			width,
+			"Tab width",
+			&render.tab_width.to_string(),
+			self.is_select(AppOption::DiffTabWidth),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Trailing whitespace",
+			&render.trailing_whitespace.to_string(),
+			self.is_select(AppOption::DiffTrailingWhitespace),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Show whitespace",
+			&render.show_whitespace.to_string(),
+			self.is_select(AppOption::DiffShowWhitespace),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Moved code",
+			&if render.moved_min_lines == 0 {
+				String::from("off")
+			} else {
+				format!("{} lines", render.moved_min_lines)
+			},
+			self.is_select(AppOption::DiffMovedMinLines),
+		);
+		Self::add_header(txt, "");
+
+		Self::add_header(txt, "Log");
+		self.add_entry(
+			txt,
+			width,
+			"Date format",
+			self.options.borrow().log_date_format.name(),
+			self.is_select(AppOption::LogDateFormat),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Date",
+			self.options.borrow().log_date_source.name(),
+			self.is_select(AppOption::LogDateSource),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Group by",
+			self.options.borrow().log_grouping.name(),
+			self.is_select(AppOption::LogGrouping),
+		);
+		Self::add_header(txt, "");
+
+		Self::add_header(txt, "Remote");
+		let interval = self.options.borrow().auto_fetch_interval;
+		self.add_entry(
+			txt,
+			width,
+			"Auto fetch",
+			&if interval == 0 {
+				String::from("Off")
+			} else {
+				format!("{} min", interval)
+			},
+			self.is_select(AppOption::AutoFetchInterval),
+		);
+		Self::add_header(txt, "");
+
+		Self::add_header(txt, "Stash");
+		self.add_entry(
+			txt,
+			width,
+			"Show list after stash",
+			&self.options.borrow().stash_show_list.to_string(),
+			self.is_select(AppOption::StashShowList),
+		);
+		Self::add_header(txt, "");
+	}
+
+	fn add_branch(&self, txt: &mut Vec<Spans>, width: u16) {
+		Self::add_header(txt, "Branch");
+		let protected =
+			self.options.borrow().protected_branches.join(", ");
+		self.add_entry(
+			txt,
+			width,
+			"Protected",
+			&string_width_align(
+				if protected.is_empty() {
+					"None"
+				} else {
+					&protected
+				},
+				usize::from(width / 2).saturating_sub(2),
+			),
+			self.is_select(AppOption::ProtectedBranches),
+		);
+		Self::add_header(txt, "");
+	}
+
+	fn add_commit(&self, txt: &mut Vec<Spans>, width: u16) {
+		Self::add_header(txt, "Commit");
+		self.add_entry(
+			txt,
+			width,
+			"Lint subject",
+			&self.options.borrow().commit_lint.enabled.to_string(),
+			self.is_select(AppOption::CommitLint),
+		);
+		let types =
+			self.options.borrow().commit_lint.types.join(", ");
+		self.add_entry(
+			txt,
+			width,
+			"Lint types",
+			&string_width_align(
+				if types.is_empty() { "Any" } else { &types },
+				usize::from(width / 2).saturating_sub(2),
+			),
+			self.is_select(AppOption::CommitLintTypes),
+		);
+		let prefill = self.options.borrow().commit_prefill.clone();
+		self.add_entry(
+			txt,
+			width,
+			"Prefill from branch",
+			&string_width_align(
+				if prefill.is_enabled() {
+					&prefill.pattern
+				} else {
+					"Off"
+				},
+				usize::from(width / 2).saturating_sub(2),
+			),
+			self.is_select(AppOption::CommitPrefillPattern),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Prefill template",
+			&string_width_align(
+				&format!("{:?}", prefill.template),
+				usize::from(width / 2).saturating_sub(2),
+			),
+			self.is_select(AppOption::CommitPrefillTemplate),
+		);
+		let trailers =
+			self.options.borrow().commit_trailers.join("; ");
+		self.add_entry(
+			txt,
+			width,
+			"Trailers",
+			&string_width_align(
+				if trailers.is_empty() {
+					"Off"
+				} else {
+					&trailers
+				},
+				usize::from(width / 2).saturating_sub(2),
+			),
+			self.is_select(AppOption::CommitTrailers),
+		);
+		let threshold = self.options.borrow().large_file_threshold;
+		self.add_entry(
+			txt,
+			width,
+			"Warn on large files",
+			&if threshold == 0 {
+				String::from("Off")
+			} else {
+				ByteSize::b(threshold).to_string_as(true)
+			},
+			self.is_select(AppOption::LargeFileThreshold),
+		);
+		Self::add_header(txt, "");
+	}
+
+	fn add_confirm(&self, txt: &mut Vec<Spans>, width: u16) {
+		Self::add_header(txt, "Confirm");
+		for action in DestructiveAction::ALL.iter().copied() {
+			self.add_entry(
+				txt,
+				width,
+				action.name(),
+				&self
+					.options
+					.borrow()
+					.confirm_policy
+					.level(action)
+					.name(),
+				self.is_select(AppOption::Confirm(action)),
+			);
+		}
+		Self::add_header(txt, "");
+	}
+
+	fn add_accessibility(&self, txt: &mut Vec<Spans>, width: u16) {
+		let indicators = self.theme.indicators();
+
+		Self::add_header(txt, "Accessibility");
+		self.add_entry(
+			txt,
+			width,
+			"High contrast",
+			&indicators.high_contrast.to_string(),
+			self.is_select(AppOption::HighContrast),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Diff +/- glyphs",
+			&indicators.diff_glyphs.to_string(),
+			self.is_select(AppOption::DiffGlyphs),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Selection marker",
+			&indicators.selection_marker.to_string(),
+			self.is_select(AppOption::SelectionMarker),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Status letters",
+			&indicators.status_letters.to_string(),
+			self.is_select(AppOption::StatusLetters),
+		);
+		Self::add_header(txt, "");
+	}
+
+	fn add_general(&self, txt: &mut Vec<Spans>, width: u16) {
+		Self::add_header(txt, "General");
+		self.add_entry(
+			txt,
+			width,
+			"Remember ui state",
+			&self.options.borrow().remember_ui_state.to_string(),
+			self.is_select(AppOption::RememberUiState),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Persist bookmarks",
+			&self.options.borrow().persist_bookmarks.to_string(),
+			self.is_select(AppOption::PersistBookmarks),
+		);
 	}
 
 	fn is_select(&self, kind: AppOption) -> bool {
 		self.selection == kind
 	}
 
+	/// left and right both toggle
+	fn toggle_diff_whitespace(&self) {
+		let mut render = self.options.borrow().diff_render;
+		if self.selection == AppOption::DiffTrailingWhitespace {
+			render.trailing_whitespace = !render.trailing_whitespace;
+		} else {
+			render.show_whitespace = !render.show_whitespace;
+		}
+
+		self.set_diff_render(render);
+	}
+
+	/// left and right both toggle
+	fn toggle_indicator(&self) {
+		let mut indicators = self.theme.indicators();
+		let value = match self.selection {
+			AppOption::HighContrast => &mut indicators.high_contrast,
+			AppOption::DiffGlyphs => &mut indicators.diff_glyphs,
+			AppOption::SelectionMarker => {
+				&mut indicators.selection_marker
+			}
+			AppOption::StatusLetters => {
+				&mut indicators.status_letters
+			}
+			_ => return,
+		};
+		*value = !*value;
+
+		self.theme.set_indicators(indicators);
+	}
+
 	fn add_header(txt: &mut Vec<Spans>, header: &'static str) {
 		txt.push(Spans::from(vec![Span::styled(
 			header,
@@ -137,6 +1074,10 @@ impl OptionsPopupComponent {
 		value: &str,
 		selected: bool,
 	) {
+		if selected {
+			self.selected_line.set(txt.len());
+		}
+
 		let half = usize::from(width / 2);
 		txt.push(Spans::from(vec![
 			Span::styled(
@@ -154,21 +1095,86 @@ impl OptionsPopupComponent {
 		if up {
 			self.selection = match self.selection {
 				AppOption::StatusShowUntracked => {
-					AppOption::DiffInterhunkLines
+					AppOption::PersistBookmarks
 				}
-				AppOption::DiffIgnoreWhitespaces => {
+				AppOption::StatusUntrackedLimit => {
 					AppOption::StatusShowUntracked
 				}
+				AppOption::DiffIgnoreWhitespaces => {
+					AppOption::StatusUntrackedLimit
+				}
 				AppOption::DiffContextLines => {
 					AppOption::DiffIgnoreWhitespaces
 				}
 				AppOption::DiffInterhunkLines => {
 					AppOption::DiffContextLines
 				}
+				AppOption::DiffTabWidth => {
+					AppOption::DiffInterhunkLines
+				}
+				AppOption::DiffTrailingWhitespace => {
+					AppOption::DiffTabWidth
+				}
+				AppOption::DiffShowWhitespace => {
+					AppOption::DiffTrailingWhitespace
+				}
+				AppOption::DiffMovedMinLines => {
+					AppOption::DiffShowWhitespace
+				}
+				AppOption::LogDateFormat => {
+					AppOption::DiffMovedMinLines
+				}
+				AppOption::LogDateSource => AppOption::LogDateFormat,
+				AppOption::LogGrouping => AppOption::LogDateSource,
+				AppOption::AutoFetchInterval => {
+					AppOption::LogGrouping
+				}
+				AppOption::StashShowList => {
+					AppOption::AutoFetchInterval
+				}
+				AppOption::ProtectedBranches => {
+					AppOption::StashShowList
+				}
+				AppOption::CommitLint => AppOption::ProtectedBranches,
+				AppOption::CommitLintTypes => AppOption::CommitLint,
+				AppOption::CommitPrefillPattern => {
+					AppOption::CommitLintTypes
+				}
+				AppOption::CommitPrefillTemplate => {
+					AppOption::CommitPrefillPattern
+				}
+				AppOption::CommitTrailers => {
+					AppOption::CommitPrefillTemplate
+				}
+				AppOption::LargeFileThreshold => {
+					AppOption::CommitTrailers
+				}
+				AppOption::Confirm(action) => action.prev().map_or(
+					AppOption::LargeFileThreshold,
+					AppOption::Confirm,
+				),
+				AppOption::HighContrast => AppOption::Confirm(
+					DestructiveAction::ALL
+						[DestructiveAction::ALL.len() - 1],
+				),
+				AppOption::DiffGlyphs => AppOption::HighContrast,
+				AppOption::SelectionMarker => AppOption::DiffGlyphs,
+				AppOption::StatusLetters => {
+					AppOption::SelectionMarker
+				}
+				AppOption::RememberUiState => {
+					AppOption::StatusLetters
+				}
+				AppOption::PersistBookmarks => {
+					AppOption::RememberUiState
+				}
 			};
 		} else {
 			self.selection = match self.selection {
 				AppOption::StatusShowUntracked => {
+					AppOption::StatusUntrackedLimit
+				}
+				AppOption::StatusUntrackedLimit => {
 					AppOption::DiffIgnoreWhitespaces
 				}
 				AppOption::DiffIgnoreWhitespaces => {
@@ -178,6 +1184,64 @@ impl OptionsPopupComponent {
 					AppOption::DiffInterhunkLines
 				}
 				AppOption::DiffInterhunkLines => {
+					AppOption::DiffTabWidth
+				}
+				AppOption::DiffTabWidth => {
+					AppOption::DiffTrailingWhitespace
+				}
+				AppOption::DiffTrailingWhitespace => {
+					AppOption::DiffShowWhitespace
+				}
+				AppOption::DiffShowWhitespace => {
+					AppOption::DiffMovedMinLines
+				}
+				AppOption::DiffMovedMinLines => {
+					AppOption::LogDateFormat
+				}
+				AppOption::LogDateFormat => AppOption::LogDateSource,
+				AppOption::LogDateSource => AppOption::LogGrouping,
+				AppOption::LogGrouping => {
+					AppOption::AutoFetchInterval
+				}
+				AppOption::AutoFetchInterval => {
+					AppOption::StashShowList
+				}
+				AppOption::StashShowList => {
+					AppOption::ProtectedBranches
+				}
+				AppOption::ProtectedBranches => AppOption::CommitLint,
+				AppOption::CommitLint => AppOption::CommitLintTypes,
+				AppOption::CommitLintTypes => {
+					AppOption::CommitPrefillPattern
+				}
+				AppOption::CommitPrefillPattern => {
+					AppOption::CommitPrefillTemplate
+				}
+				AppOption::CommitPrefillTemplate => {
+					AppOption::CommitTrailers
+				}
+				AppOption::CommitTrailers => {
+					AppOption::LargeFileThreshold
+				}
+				AppOption::LargeFileThreshold => {
+					AppOption::Confirm(DestructiveAction::ALL[0])
+				}
+				AppOption::Confirm(action) => action.next().map_or(
+					AppOption::HighContrast,
+					AppOption::Confirm,
+				),
+				AppOption::HighContrast => AppOption::DiffGlyphs,
+				AppOption::DiffGlyphs => AppOption::SelectionMarker,
+				AppOption::SelectionMarker => {
+					AppOption::StatusLetters
+				}
+				AppOption::StatusLetters => {
+					AppOption::RememberUiState
+				}
+				AppOption::RememberUiState => {
+					AppOption::PersistBookmarks
+				}
+				AppOption::PersistBookmarks => {
 					AppOption::StatusShowUntracked
 				}
 			};
@@ -207,6 +1271,14 @@ impl OptionsPopupComponent {
 					self.options.borrow_mut().status_show_untracked =
 						untracked;
 				}
+				AppOption::StatusUntrackedLimit => {
+					let old =
+						self.options.borrow().status_untracked_limit;
+					self.options
+						.borrow_mut()
+						.status_untracked_limit =
+						old.saturating_add(UNTRACKED_LIMIT_STEP);
+				}
 				AppOption::DiffIgnoreWhitespaces => {
 					let old =
 						self.options.borrow().diff.ignore_whitespace;
@@ -226,6 +1298,79 @@ impl OptionsPopupComponent {
 					self.options.borrow_mut().diff.interhunk_lines =
 						old.saturating_add(1);
 				}
+				AppOption::DiffTabWidth => {
+					let render = self.options.borrow().diff_render;
+					self.set_diff_render(DiffRender {
+						tab_width: render.next_tab_width(),
+						..render
+					});
+				}
+				AppOption::DiffMovedMinLines => {
+					let render = self.options.borrow().diff_render;
+					self.set_diff_render(DiffRender {
+						moved_min_lines: render
+							.next_moved_min_lines(),
+						..render
+					});
+				}
+				AppOption::DiffTrailingWhitespace
+				| AppOption::DiffShowWhitespace => {
+					self.toggle_diff_whitespace();
+				}
+				AppOption::LogDateFormat => {
+					let old = self.options.borrow().log_date_format;
+					self.set_log_date_format(old.next());
+				}
+				AppOption::LogDateSource => {
+					let old = self.options.borrow().log_date_source;
+					self.set_log_date_source(old.toggle());
+				}
+				AppOption::LogGrouping => {
+					let old = self.options.borrow().log_grouping;
+					self.set_log_grouping(old.next());
+				}
+				AppOption::AutoFetchInterval => {
+					let old =
+						self.options.borrow().auto_fetch_interval;
+					self.options.borrow_mut().auto_fetch_interval =
+						old.saturating_add(1);
+				}
+				AppOption::StashShowList => {
+					let old = self.options.borrow().stash_show_list;
+					self.options.borrow_mut().stash_show_list = !old;
+				}
+				AppOption::RememberUiState => {
+					let old = self.options.borrow().remember_ui_state;
+					self.options.borrow_mut().remember_ui_state =
+						!old;
+				}
+				AppOption::PersistBookmarks => {
+					let old = self.options.borrow().persist_bookmarks;
+					self.options.borrow_mut().persist_bookmarks =
+						!old;
+				}
+				AppOption::CommitLint => {
+					self.toggle_commit_lint();
+					return;
+				}
+				AppOption::LargeFileThreshold => {
+					self.step_large_file_threshold(true);
+				}
+				AppOption::Confirm(action) => {
+					self.cycle_confirm_level(action, true);
+				}
+				AppOption::HighContrast
+				| AppOption::DiffGlyphs
+				| AppOption::SelectionMarker
+				| AppOption::StatusLetters => {
+					self.toggle_indicator();
+				}
+				// edited as text
+				AppOption::ProtectedBranches
+				| AppOption::CommitLintTypes
+				| AppOption::CommitPrefillPattern
+				| AppOption::CommitPrefillTemplate
+				| AppOption::CommitTrailers => return,
 			};
 		} else {
 			match self.selection {
@@ -249,6 +1394,14 @@ impl OptionsPopupComponent {
 					self.options.borrow_mut().status_show_untracked =
 						untracked;
 				}
+				AppOption::StatusUntrackedLimit => {
+					let old =
+						self.options.borrow().status_untracked_limit;
+					self.options
+						.borrow_mut()
+						.status_untracked_limit =
+						old.saturating_sub(UNTRACKED_LIMIT_STEP);
+				}
 				AppOption::DiffIgnoreWhitespaces => {
 					let old =
 						self.options.borrow().diff.ignore_whitespace;
@@ -268,6 +1421,79 @@ impl OptionsPopupComponent {
 					self.options.borrow_mut().diff.interhunk_lines =
 						old.saturating_sub(1);
 				}
+				AppOption::DiffTabWidth => {
+					let render = self.options.borrow().diff_render;
+					self.set_diff_render(DiffRender {
+						tab_width: render.prev_tab_width(),
+						..render
+					});
+				}
+				AppOption::DiffMovedMinLines => {
+					let render = self.options.borrow().diff_render;
+					self.set_diff_render(DiffRender {
+						moved_min_lines: render
+							.prev_moved_min_lines(),
+						..render
+					});
+				}
+				AppOption::DiffTrailingWhitespace
+				| AppOption::DiffShowWhitespace => {
+					self.toggle_diff_whitespace();
+				}
+				AppOption::LogDateFormat => {
+					let old = self.options.borrow().log_date_format;
+					self.set_log_date_format(old.prev());
+				}
+				AppOption::LogDateSource => {
+					let old = self.options.borrow().log_date_source;
+					self.set_log_date_source(old.toggle());
+				}
+				AppOption::LogGrouping => {
+					let old = self.options.borrow().log_grouping;
+					self.set_log_grouping(old.prev());
+				}
+				AppOption::AutoFetchInterval => {
+					let old =
+						self.options.borrow().auto_fetch_interval;
+					self.options.borrow_mut().auto_fetch_interval =
+						old.saturating_sub(1);
+				}
+				AppOption::StashShowList => {
+					let old = self.options.borrow().stash_show_list;
+					self.options.borrow_mut().stash_show_list = !old;
+				}
+				AppOption::RememberUiState => {
+					let old = self.options.borrow().remember_ui_state;
+					self.options.borrow_mut().remember_ui_state =
+						!old;
+				}
+				AppOption::PersistBookmarks => {
+					let old = self.options.borrow().persist_bookmarks;
+					self.options.borrow_mut().persist_bookmarks =
+						!old;
+				}
+				AppOption::CommitLint => {
+					self.toggle_commit_lint();
+					return;
+				}
+				AppOption::LargeFileThreshold => {
+					self.step_large_file_threshold(false);
+				}
+				AppOption::Confirm(action) => {
+					self.cycle_confirm_level(action, false);
+				}
+				AppOption::HighContrast
+				| AppOption::DiffGlyphs
+				| AppOption::SelectionMarker
+				| AppOption::StatusLetters => {
+					self.toggle_indicator();
+				}
+				// edited as text
+				AppOption::ProtectedBranches
+				| AppOption::CommitLintTypes
+				| AppOption::CommitPrefillPattern
+				| AppOption::CommitPrefillTemplate
+				| AppOption::CommitTrailers => return,
 			};
 		}
 
@@ -283,15 +1509,26 @@ impl DrawableComponent for OptionsPopupComponent {
 		area: Rect,
 	) -> Result<()> {
 		if self.is_visible() {
-			const SIZE: (u16, u16) = (50, 10);
+			const WIDTH: u16 = 50;
+
+			let text = self.get_text(WIDTH);
+			let height =
+				u16::try_from(text.len() + 2).unwrap_or(u16::MAX);
 			let area =
-				ui::centered_rect_absolute(SIZE.0, SIZE.1, area);
+				ui::centered_rect_absolute(WIDTH, height, area);
 
-			let width = area.width;
+			// keep the selected entry visible on small terminals
+			let inner_height =
+				usize::from(area.height.saturating_sub(2)).max(1);
+			let scroll = u16::try_from(
+				(self.selected_line.get() + 1)
+					.saturating_sub(inner_height),
+			)
+			.unwrap_or_default();
 
 			f.render_widget(Clear, area);
 			f.render_widget(
-				Paragraph::new(self.get_text(width))
+				Paragraph::new(text)
 					.block(
 						Block::default()
 							.borders(Borders::ALL)
@@ -301,9 +1538,12 @@ impl DrawableComponent for OptionsPopupComponent {
 							))
 							.border_style(self.theme.block(true)),
 					)
-					.alignment(Alignment::Left),
+					.alignment(Alignment::Left)
+					.scroll((scroll, 0)),
 				area,
 			);
+
+			self.input.draw(f, area)?;
 		}
 
 		Ok(())
@@ -316,7 +1556,19 @@ impl Component for OptionsPopupComponent {
 		out: &mut Vec<CommandInfo>,
 		force_all: bool,
 	) -> CommandBlocking {
+		if self.input.is_visible() {
+			return self.input.commands(out, force_all);
+		}
+
 		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::options_edit(&self.key_config),
+					true,
+					self.is_text_option() || force_all,
+				)
+				.order(1),
+			);
 			out.push(
 				CommandInfo::new(
 					strings::commands::close_popup(&self.key_config),
@@ -345,8 +1597,24 @@ impl Component for OptionsPopupComponent {
 		event: crossterm::event::Event,
 	) -> Result<EventState> {
 		if self.is_visible() {
+			if self.input.is_visible() {
+				if let Event::Key(key) = &event {
+					if *key == self.key_config.enter {
+						self.confirm_text_option();
+					} else {
+						self.input.event(event)?;
+					}
+				}
+
+				return Ok(EventState::Consumed);
+			}
+
 			if let Event::Key(key) = &event {
-				if *key == self.key_config.exit_popup {
+				if *key == self.key_config.enter
+					&& self.is_text_option()
+				{
+					self.edit_text_option()?;
+				} else if *key == self.key_config.exit_popup {
 					self.hide();
 				} else if *key == self.key_config.move_up {
 					self.move_selection(true);
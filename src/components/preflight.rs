@@ -0,0 +1,370 @@
+use super::{
+	textinput::InputType, visibility_blocking, CommandBlocking,
+	CommandInfo, Component, DrawableComponent, EventState,
+	ExternalEditorComponent, TextInputComponent,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	messages::Severity,
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+	sync::{self, ConfigScope, PreflightIssue, SAFE_DIRECTORY},
+	CWD,
+};
+use crossterm::event::Event;
+use tui::{
+	backend::Backend,
+	layout::{Constraint, Direction, Layout, Margin, Rect},
+	text::Span,
+	widgets::{Block, BorderType, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// width of the config key labels (`strings::preflight_config_key`)
+const LABEL_WIDTH: u16 = 16;
+/// issue, config value, scope and hint
+const LINES: u16 = 4;
+
+/// walks through the problems found by `sync::preflight_check` one
+/// by one, each with an input for the config value fixing it
+pub struct PreflightPopup {
+	visible: bool,
+	issues: Vec<PreflightIssue>,
+	/// number of issues found, for the progress in the title
+	total: usize,
+	input: TextInputComponent,
+	scope: ConfigScope,
+	/// opened by a commit attempt that continues once all is fixed
+	resume_commit: bool,
+	error: Option<String>,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl PreflightPopup {
+	///
+	pub fn new(
+		queue: &Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		let mut input = TextInputComponent::new(
+			theme.clone(),
+			key_config.clone(),
+			"",
+			"",
+			false,
+		)
+		.with_input_type(InputType::Singleline);
+		input.embed();
+
+		Self {
+			visible: false,
+			issues: Vec::new(),
+			total: 0,
+			input,
+			scope: ConfigScope::Global,
+			resume_commit: false,
+			error: None,
+			queue: queue.clone(),
+			theme,
+			key_config,
+		}
+	}
+
+	/// `issues` found by a commit attempt, `None` runs the checks
+	pub fn open(
+		&mut self,
+		issues: Option<Vec<PreflightIssue>>,
+	) -> Result<()> {
+		self.resume_commit = issues.is_some();
+
+		let issues = match issues {
+			Some(issues) => issues,
+			None => sync::preflight_check(
+				CWD,
+				&ExternalEditorComponent::editor_program()?,
+			)?,
+		};
+
+		if issues.is_empty() {
+			self.queue.push(InternalEvent::ShowToast(
+				Severity::Success,
+				strings::toast_preflight_passed(),
+			));
+			return Ok(());
+		}
+
+		self.total = issues.len();
+		self.issues = issues;
+		self.scope = ConfigScope::Global;
+		self.show_issue()?;
+
+		self.show()
+	}
+
+	fn show_issue(&mut self) -> Result<()> {
+		self.error = None;
+		self.input.clear();
+
+		if let Some(PreflightIssue::UnsafeDirectory(path)) =
+			self.issues.first()
+		{
+			self.input.set_text(path.clone());
+		}
+
+		self.input.show()
+	}
+
+	const fn config_key(issue: &PreflightIssue) -> &'static str {
+		match issue {
+			PreflightIssue::MissingUserName => "user.name",
+			PreflightIssue::MissingUserEmail => "user.email",
+			PreflightIssue::UnusableEditor(_) => "core.editor",
+			PreflightIssue::UnsafeDirectory(_) => SAFE_DIRECTORY,
+		}
+	}
+
+	/// `safe.directory` is only read from the global config
+	fn scope_fixed(&self) -> bool {
+		matches!(
+			self.issues.first(),
+			Some(PreflightIssue::UnsafeDirectory(_))
+		)
+	}
+
+	fn toggle_scope(&mut self) {
+		if !self.scope_fixed() {
+			self.scope = match self.scope {
+				ConfigScope::Global => ConfigScope::Local,
+				ConfigScope::Local | ConfigScope::System => {
+					ConfigScope::Global
+				}
+			};
+		}
+	}
+
+	fn apply(&mut self) -> Result<()> {
+		let issue = match self.issues.first() {
+			Some(issue) => issue,
+			None => return Ok(()),
+		};
+
+		let key = Self::config_key(issue);
+		let value = self.input.get_text().trim().to_string();
+
+		if value.is_empty() {
+			self.error = Some(strings::preflight_empty_value(key));
+			return Ok(());
+		}
+
+		let res = if self.scope_fixed() {
+			sync::add_config_string(
+				CWD,
+				key,
+				&value,
+				ConfigScope::Global,
+			)
+		} else {
+			sync::set_string_scoped(CWD, key, &value, self.scope)
+		};
+
+		if let Err(e) = res {
+			self.error = Some(e.to_string());
+			return Ok(());
+		}
+
+		self.issues.remove(0);
+
+		if self.issues.is_empty() {
+			self.hide();
+
+			if self.resume_commit {
+				self.queue.push(InternalEvent::ResumeCommit);
+			}
+			self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+
+			return Ok(());
+		}
+
+		self.show_issue()
+	}
+
+	fn title(&self) -> String {
+		strings::preflight_title(
+			self.total - self.issues.len() + 1,
+			self.total,
+		)
+	}
+}
+
+impl DrawableComponent for PreflightPopup {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		let issue = match self.issues.first() {
+			Some(issue) if self.visible => issue,
+			_ => return Ok(()),
+		};
+
+		let area = ui::centered_rect_absolute(70, LINES + 2, rect);
+
+		f.render_widget(Clear, area);
+		f.render_widget(
+			Block::default()
+				.title(Span::styled(
+					self.title(),
+					self.theme.title(true),
+				))
+				.borders(Borders::ALL)
+				.border_type(BorderType::Thick)
+				.border_style(self.theme.block(true)),
+			area,
+		);
+
+		let chunks = Layout::default()
+			.direction(Direction::Vertical)
+			.constraints(vec![Constraint::Length(1); LINES.into()])
+			.split(area.inner(&Margin {
+				horizontal: 1,
+				vertical: 1,
+			}));
+
+		f.render_widget(
+			Paragraph::new(Span::styled(
+				strings::preflight_issue(issue),
+				self.theme.text_danger(),
+			)),
+			chunks[0],
+		);
+
+		let field = Layout::default()
+			.direction(Direction::Horizontal)
+			.constraints(
+				[
+					Constraint::Length(LABEL_WIDTH),
+					Constraint::Percentage(100),
+				]
+				.as_ref(),
+			)
+			.split(chunks[1]);
+
+		f.render_widget(
+			Paragraph::new(Span::styled(
+				strings::preflight_config_key(Self::config_key(
+					issue,
+				)),
+				self.theme.text(true, true),
+			)),
+			field[0],
+		);
+		self.input.draw(f, field[1])?;
+
+		f.render_widget(
+			Paragraph::new(Span::styled(
+				strings::preflight_scope(
+					self.scope_fixed()
+						|| self.scope == ConfigScope::Global,
+					&self.key_config,
+				),
+				self.theme.text(!self.scope_fixed(), false),
+			)),
+			chunks[2],
+		);
+
+		let hint = self.error.as_ref().map_or_else(
+			|| {
+				Span::styled(
+					strings::preflight_hint(&self.key_config),
+					self.theme.text(false, false),
+				)
+			},
+			|error| {
+				Span::styled(error.as_str(), self.theme.text_danger())
+			},
+		);
+		f.render_widget(Paragraph::new(hint), chunks[3]);
+
+		Ok(())
+	}
+}
+
+impl Component for PreflightPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.visible || force_all {
+			out.push(CommandInfo::new(
+				strings::commands::preflight_apply(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::preflight_toggle_scope(
+					&self.key_config,
+				),
+				!self.scope_fixed(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.visible {
+			if let Event::Key(key) = ev {
+				if key == self.key_config.exit_popup {
+					self.hide();
+				} else if key == self.key_config.enter {
+					self.apply()?;
+				} else if key == self.key_config.tab_toggle {
+					self.toggle_scope();
+				} else if self.input.event(ev)?.is_consumed() {
+					self.error = None;
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn paste(&mut self, text: &str) -> Result<EventState> {
+		if self.visible {
+			self.error = None;
+			return self.input.paste(text);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
@@ -0,0 +1,382 @@
+use super::{
+	utils::string_width_align, visibility_blocking, CommandBlocking,
+	CommandInfo, Component, DrawableComponent, EventState,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	messages::Severity,
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings::{self, symbol},
+	ui::{self, style::SharedTheme},
+	AsyncNotification,
+};
+use anyhow::Result;
+use asyncgit::{
+	asyncjob::AsyncSingleJob,
+	remote_branches::AsyncStaleBranchesJob,
+	sync::{
+		self,
+		cred::{
+			extract_username_password_for_remote,
+			need_username_password_for_remote,
+		},
+	},
+	AsyncGitNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use tui::{
+	backend::Backend,
+	layout::{Alignment, Rect},
+	text::{Span, Spans},
+	widgets::{Block, BorderType, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// a remote tracking ref and whether it is going to be deleted
+struct StaleBranch {
+	name: String,
+	checked: bool,
+}
+
+/// state of the list of refs that would be pruned
+enum StaleBranches {
+	Loading,
+	Loaded(Vec<StaleBranch>),
+	Failed(String),
+}
+
+const BRANCH_ROWS: usize = 10;
+
+/// previews the remote tracking branches `fetch --prune` would
+/// delete and deletes the checked ones
+pub struct PruneRemoteComponent {
+	visible: bool,
+	remote: String,
+	branches: StaleBranches,
+	selection: usize,
+	async_stale: AsyncSingleJob<AsyncStaleBranchesJob>,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl PruneRemoteComponent {
+	///
+	pub fn new(
+		queue: &Queue,
+		sender: &Sender<AsyncGitNotification>,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			visible: false,
+			remote: String::new(),
+			branches: StaleBranches::Loading,
+			selection: 0,
+			async_stale: AsyncSingleJob::new(sender.clone()),
+			queue: queue.clone(),
+			theme,
+			key_config,
+		}
+	}
+
+	///
+	pub fn open(&mut self, remote: String) -> Result<()> {
+		let cred = if need_username_password_for_remote(CWD, &remote)
+			.unwrap_or_default()
+		{
+			extract_username_password_for_remote(CWD, &remote).ok()
+		} else {
+			None
+		};
+
+		self.async_stale
+			.spawn(AsyncStaleBranchesJob::new(remote.clone(), cred));
+
+		self.remote = remote;
+		self.branches = StaleBranches::Loading;
+		self.selection = 0;
+
+		self.show()
+	}
+
+	///
+	pub fn update(&mut self, ev: AsyncNotification) {
+		if matches!(
+			ev,
+			AsyncNotification::Git(
+				AsyncGitNotification::StaleBranches
+			)
+		) {
+			if let Some(job) = self.async_stale.take_last() {
+				if let Some((remote, result)) = job.result() {
+					if remote == self.remote {
+						self.branches = match result {
+							Ok(names) => StaleBranches::Loaded(
+								names
+									.into_iter()
+									.map(|name| StaleBranch {
+										name,
+										checked: true,
+									})
+									.collect(),
+							),
+							Err(e) => {
+								StaleBranches::Failed(e.to_string())
+							}
+						};
+					}
+				}
+			}
+		}
+	}
+
+	///
+	pub fn any_work_pending(&self) -> bool {
+		self.async_stale.is_pending()
+	}
+
+	fn loaded(&self) -> &[StaleBranch] {
+		match &self.branches {
+			StaleBranches::Loaded(branches) => branches,
+			_ => &[],
+		}
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let max = self.loaded().len().saturating_sub(1);
+
+		self.selection = if up {
+			self.selection.saturating_sub(1)
+		} else {
+			self.selection.saturating_add(1).min(max)
+		};
+	}
+
+	fn toggle_selected(&mut self) {
+		if let StaleBranches::Loaded(branches) = &mut self.branches {
+			if let Some(branch) = branches.get_mut(self.selection) {
+				branch.checked = !branch.checked;
+			}
+		}
+	}
+
+	fn confirm(&mut self) {
+		let refs = self
+			.loaded()
+			.iter()
+			.filter(|branch| branch.checked)
+			.map(|branch| branch.name.clone())
+			.collect::<Vec<_>>();
+
+		if !refs.is_empty() {
+			match sync::delete_remote_tracking_refs(CWD, &refs) {
+				Ok(()) => {
+					self.queue.push(InternalEvent::ShowToast(
+						Severity::Success,
+						strings::toast_pruned(
+							&self.remote,
+							refs.len(),
+						),
+					));
+				}
+				Err(e) => {
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("prune error:\n{}", e),
+					));
+				}
+			}
+
+			self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+		}
+
+		self.hide();
+	}
+
+	fn get_text(&self, width: u16) -> Vec<Spans> {
+		let mut txt = Vec::with_capacity(BRANCH_ROWS + 2);
+
+		match &self.branches {
+			StaleBranches::Loading => {
+				txt.push(Spans::from(strings::loading_text(
+					&self.key_config,
+				)));
+			}
+			StaleBranches::Failed(err) => {
+				txt.push(Spans::from(Span::styled(
+					err.clone(),
+					self.theme.text_danger(),
+				)));
+			}
+			StaleBranches::Loaded(branches)
+				if branches.is_empty() =>
+			{
+				txt.push(Spans::from(Span::styled(
+					strings::PRUNE_POPUP_EMPTY,
+					self.theme.text(false, false),
+				)));
+			}
+			StaleBranches::Loaded(branches) => {
+				let skip =
+					self.selection.saturating_sub(BRANCH_ROWS - 1);
+
+				for (idx, branch) in branches
+					.iter()
+					.enumerate()
+					.skip(skip)
+					.take(BRANCH_ROWS)
+				{
+					let selected = idx == self.selection;
+					let name = branch
+						.name
+						.strip_prefix("refs/remotes/")
+						.unwrap_or(&branch.name);
+
+					txt.push(Spans::from(vec![
+						Span::styled(
+							if branch.checked {
+								symbol::CHECKMARK
+							} else {
+								symbol::EMPTY_SPACE
+							},
+							self.theme.log_marker(selected),
+						),
+						Span::styled(
+							symbol::EMPTY_SPACE,
+							self.theme.text(true, selected),
+						),
+						Span::styled(
+							string_width_align(
+								name,
+								usize::from(width.saturating_sub(2)),
+							),
+							self.theme.text(true, selected),
+						),
+					]));
+				}
+
+				txt.push(Spans::from(""));
+				txt.push(Spans::from(Span::styled(
+					strings::prune_popup_selected(
+						branches
+							.iter()
+							.filter(|branch| branch.checked)
+							.count(),
+						branches.len(),
+					),
+					self.theme.text(false, false),
+				)));
+			}
+		}
+
+		txt
+	}
+}
+
+impl DrawableComponent for PruneRemoteComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		if self.is_visible() {
+			#[allow(clippy::cast_possible_truncation)]
+			const SIZE: (u16, u16) = (60, BRANCH_ROWS as u16 + 4);
+			let area =
+				ui::centered_rect_absolute(SIZE.0, SIZE.1, rect);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(
+					self.get_text(area.width.saturating_sub(2)),
+				)
+				.block(
+					Block::default()
+						.borders(Borders::ALL)
+						.border_type(BorderType::Thick)
+						.title(Span::styled(
+							strings::prune_popup_title(&self.remote),
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				)
+				.alignment(Alignment::Left),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for PruneRemoteComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			let any = !self.loaded().is_empty();
+
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				any,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::prune_toggle(&self.key_config),
+				any,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::prune_confirm(&self.key_config),
+				self.loaded().iter().any(|branch| branch.checked),
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = ev {
+				if key == self.key_config.exit_popup {
+					self.hide();
+				} else if key == self.key_config.move_up {
+					self.move_selection(true);
+				} else if key == self.key_config.move_down {
+					self.move_selection(false);
+				} else if key == self.key_config.log_mark_commit {
+					self.toggle_selected();
+				} else if key == self.key_config.enter {
+					self.confirm();
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
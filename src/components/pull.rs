@@ -5,6 +5,7 @@ use crate::{
 		CommandInfo, Component, DrawableComponent, EventState,
 	},
 	keys::SharedKeyConfig,
+	messages::Severity,
 	queue::{Action, InternalEvent, Queue},
 	strings, try_or_popup,
 	ui::{self, style::SharedTheme},
@@ -14,10 +15,10 @@ use asyncgit::{
 	sync::{
 		self,
 		cred::{
-			extract_username_password, need_username_password,
-			BasicAuthCredential,
+			extract_username_password_for_remote,
+			need_username_password_for_remote, BasicAuthCredential,
 		},
-		get_default_remote,
+		get_default_remote, get_upstream_branch, get_upstream_remote,
 	},
 	AsyncFetch, AsyncGitNotification, FetchRequest, RemoteProgress,
 	CWD,
@@ -39,6 +40,10 @@ pub struct PullComponent {
 	progress: Option<RemoteProgress>,
 	pending: bool,
 	branch: String,
+	remote: String,
+	refspecs: Vec<String>,
+	/// merge `branch` with its upstream after fetching
+	merge: bool,
 	queue: Queue,
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
@@ -58,6 +63,9 @@ impl PullComponent {
 			pending: false,
 			visible: false,
 			branch: String::new(),
+			remote: String::new(),
+			refspecs: Vec::new(),
+			merge: false,
 			git_fetch: AsyncFetch::new(sender),
 			progress: None,
 			input_cred: CredComponent::new(
@@ -69,15 +77,39 @@ impl PullComponent {
 		}
 	}
 
-	///
+	/// fetch `branch` from its upstream and merge
 	pub fn fetch(&mut self, branch: String) -> Result<()> {
+		self.remote = get_upstream_remote(CWD, &branch)
+			.or_else(|_| get_default_remote(CWD))?;
+		// the upstream can be named differently than `branch`
+		let upstream_branch = get_upstream_branch(CWD, &branch)?
+			.unwrap_or_else(|| branch.clone());
+		self.refspecs = vec![upstream_branch];
 		self.branch = branch;
+		self.merge = true;
+		self.start()
+	}
+
+	/// fetch `refspecs` from `remote` without merging anything
+	pub fn fetch_remote(
+		&mut self,
+		remote: String,
+		refspecs: Vec<String>,
+	) -> Result<()> {
+		self.remote = remote;
+		self.refspecs = refspecs;
+		self.merge = false;
+		self.start()
+	}
+
+	fn start(&mut self) -> Result<()> {
 		self.show()?;
-		if need_username_password()? {
-			let cred =
-				extract_username_password().unwrap_or_else(|_| {
-					BasicAuthCredential::new(None, None)
-				});
+		if need_username_password_for_remote(CWD, &self.remote)? {
+			let cred = extract_username_password_for_remote(
+				CWD,
+				&self.remote,
+			)
+			.unwrap_or_else(|_| BasicAuthCredential::new(None, None));
 			if cred.is_complete() {
 				self.fetch_from_remote(Some(cred))
 			} else {
@@ -96,8 +128,8 @@ impl PullComponent {
 		self.pending = true;
 		self.progress = None;
 		self.git_fetch.request(FetchRequest {
-			remote: get_default_remote(CWD)?,
-			branch: self.branch.clone(),
+			remote: self.remote.clone(),
+			refspecs: self.refspecs.clone(),
 			basic_credential: cred,
 		})?;
 
@@ -132,14 +164,20 @@ impl PullComponent {
 			if let Some((_bytes, err)) =
 				self.git_fetch.last_result()?
 			{
-				if err.is_empty() {
-					self.try_ff_merge()?;
-				} else {
+				if !err.is_empty() {
 					self.pending = false;
 					self.hide();
 					self.queue.push(InternalEvent::ShowErrorMsg(
 						format!("fetch failed:\n{}", err),
 					));
+				} else if self.merge {
+					self.try_ff_merge()?;
+				} else {
+					self.queue.push(InternalEvent::ShowToast(
+						Severity::Success,
+						strings::toast_fetched(&self.remote),
+					));
+					self.hide();
 				}
 			}
 		}
@@ -214,7 +252,11 @@ impl DrawableComponent for PullComponent {
 					.block(
 						Block::default()
 							.title(Span::styled(
-								strings::PULL_POPUP_MSG,
+								if self.merge {
+									strings::PULL_POPUP_MSG
+								} else {
+									strings::FETCH_POPUP_MSG
+								},
 								self.theme.title(true),
 							))
 							.borders(Borders::ALL)
@@ -290,4 +332,8 @@ impl Component for PullComponent {
 
 		Ok(())
 	}
+
+	fn on_resize(&self) {
+		self.input_cred.on_resize();
+	}
 }
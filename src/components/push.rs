@@ -1,35 +1,65 @@
 use crate::{
 	components::{
-		cred::CredComponent, visibility_blocking, CommandBlocking,
-		CommandInfo, Component, DrawableComponent, EventState,
+		cred::CredComponent,
+		utils::{
+			scroll_vertical::VerticalScroll, string_width_align,
+		},
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState, HookOutputComponent,
+		ScrollType,
 	},
 	keys::SharedKeyConfig,
+	messages::Severity,
 	queue::{InternalEvent, Queue},
 	strings,
 	ui::{self, style::SharedTheme},
+	AsyncNotification,
 };
 use anyhow::Result;
 use asyncgit::{
+	asyncjob::AsyncSingleJob,
+	pre_push_hook::AsyncPrePushHookJob,
+	push_preview::AsyncPushPreviewJob,
 	sync::{
+		branches_compare_upstream,
 		cred::{
-			extract_username_password, need_username_password,
-			BasicAuthCredential,
+			extract_username_password_for_remote,
+			need_username_password_for_remote, BasicAuthCredential,
 		},
-		get_branch_remote, get_default_remote,
+		get_branch_remote, get_branches_info, get_default_remote,
+		get_remotes, push_preview, push_target_for_branch,
+		push_target_on_remote, upstream_gone, BranchCompare,
+		HookResult, PushPreview, PushTarget,
 	},
 	AsyncGitNotification, AsyncPush, PushRequest, RemoteProgress,
 	RemoteProgressState, CWD,
 };
 use crossbeam_channel::Sender;
-use crossterm::event::Event;
+use crossterm::event::{Event, KeyEvent};
 use tui::{
 	backend::Backend,
-	layout::Rect,
-	text::Span,
-	widgets::{Block, BorderType, Borders, Clear, Gauge},
+	layout::{Constraint, Direction, Layout, Rect},
+	text::{Span, Spans},
+	widgets::{Block, BorderType, Borders, Clear, Gauge, Paragraph},
 	Frame,
 };
 
+/// what the popup shows before anything is pushed
+enum Preview {
+	Loading,
+	Loaded(PushPreview),
+	Failed(String),
+}
+
+/// lines of the preview above the commit list
+const PREVIEW_HEADER_LINES: u16 = 4;
+
+/// local branches to choose the one to push from
+struct BranchSelect {
+	branches: Vec<(String, Option<BranchCompare>)>,
+	selection: usize,
+}
+
 ///
 #[derive(PartialEq, Eq)]
 enum PushComponentModifier {
@@ -55,7 +85,26 @@ pub struct PushComponent {
 	git_push: AsyncPush,
 	progress: Option<RemoteProgress>,
 	pending: bool,
+	/// local branch to push, not necessarily the checked out one
 	branch: String,
+	/// remote chosen instead of the one `branch` pushes to
+	remote: Option<String>,
+	target: PushTarget,
+	/// the configured upstream of `branch` is gone, pushing
+	/// recreates it
+	upstream_gone: Option<String>,
+	branch_select: Option<BranchSelect>,
+	branch_scroll: VerticalScroll,
+	/// `None` once the push was confirmed
+	preview: Option<Preview>,
+	preview_asked_remote: bool,
+	async_preview: AsyncSingleJob<AsyncPushPreviewJob>,
+	/// runs before anything is sent
+	pre_push_hook: AsyncSingleJob<AsyncPrePushHookJob>,
+	hooks: HookOutputComponent,
+	/// credentials the push goes on with once `pre-push` passed
+	cred: Option<BasicAuthCredential>,
+	scroll: VerticalScroll,
 	queue: Queue,
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
@@ -76,6 +125,25 @@ impl PushComponent {
 			pending: false,
 			visible: false,
 			branch: String::new(),
+			remote: None,
+			target: PushTarget {
+				remote: String::new(),
+				branch: String::new(),
+			},
+			upstream_gone: None,
+			branch_select: None,
+			branch_scroll: VerticalScroll::new(),
+			preview: None,
+			preview_asked_remote: false,
+			async_preview: AsyncSingleJob::new(sender.clone()),
+			pre_push_hook: AsyncSingleJob::new(sender.clone()),
+			hooks: HookOutputComponent::new(
+				theme.clone(),
+				key_config.clone(),
+				true,
+			),
+			cred: None,
+			scroll: VerticalScroll::new(),
 			git_push: AsyncPush::new(sender),
 			progress: None,
 			input_cred: CredComponent::new(
@@ -95,6 +163,8 @@ impl PushComponent {
 		delete: bool,
 	) -> Result<()> {
 		self.branch = branch;
+		self.remote = None;
+		self.branch_select = None;
 		self.modifier = match (force, delete) {
 			(true, true) => PushComponentModifier::ForceDelete,
 			(false, true) => PushComponentModifier::Delete,
@@ -102,54 +172,300 @@ impl PushComponent {
 			(false, false) => PushComponentModifier::None,
 		};
 
-		self.show()?;
+		self.target = self.resolve_target()?;
+		self.progress = None;
+
+		// deleting sends no commits, there is nothing to preview
+		if self.modifier.delete() {
+			self.upstream_gone = None;
+			self.preview = None;
+			self.show()?;
+			return self.start_push();
+		}
+
+		self.upstream_gone =
+			upstream_gone(CWD, &self.branch).ok().flatten();
+		self.preview_asked_remote = false;
+		self.load_preview();
+		self.show()
+	}
+
+	/// resolves where `branch` goes again after choosing another
+	/// branch or remote
+	fn retarget(&mut self) {
+		self.upstream_gone =
+			upstream_gone(CWD, &self.branch).ok().flatten();
+
+		match self.resolve_target() {
+			Ok(target) => {
+				self.target = target;
+				self.load_preview();
+			}
+			Err(e) => {
+				self.target = PushTarget {
+					remote: self.remote.clone().unwrap_or_else(
+						|| self.target.remote.clone(),
+					),
+					branch: self.branch.clone(),
+				};
+				self.scroll.reset();
+				self.preview = Some(Preview::Failed(e.to_string()));
+			}
+		}
+	}
+
+	fn open_branch_select(&mut self) -> Result<()> {
+		let compare = branches_compare_upstream(CWD)?;
+
+		let branches = get_branches_info(CWD, true)?
+			.into_iter()
+			.filter(|branch| branch.top_commit.is_some())
+			.map(|branch| {
+				let compare = compare.get(&branch.name).copied();
+				(branch.name, compare)
+			})
+			.collect::<Vec<_>>();
+
+		let selection = branches
+			.iter()
+			.position(|(name, _)| name == &self.branch)
+			.unwrap_or_default();
+
+		self.branch_select = Some(BranchSelect {
+			branches,
+			selection,
+		});
+
+		Ok(())
+	}
+
+	fn branch_select_event(&mut self, e: KeyEvent) {
+		let select = match &mut self.branch_select {
+			Some(select) => select,
+			None => return,
+		};
+		let max = select.branches.len().saturating_sub(1);
+
+		if e == self.key_config.exit_popup
+			|| e == self.key_config.push_select_branch
+		{
+			self.branch_select = None;
+		} else if e == self.key_config.enter {
+			if let Some((name, _)) =
+				select.branches.get(select.selection)
+			{
+				self.branch = name.clone();
+				self.remote = None;
+				self.branch_select = None;
+				self.retarget();
+			}
+		} else if e == self.key_config.move_up {
+			select.selection = select.selection.saturating_sub(1);
+		} else if e == self.key_config.move_down {
+			select.selection = (select.selection + 1).min(max);
+		} else if e == self.key_config.home {
+			select.selection = 0;
+		} else if e == self.key_config.end {
+			select.selection = max;
+		}
+	}
+
+	/// pushes to the next remote instead
+	fn next_remote(&mut self) -> Result<()> {
+		let remotes = get_remotes(CWD)?;
+
+		let next = remotes
+			.iter()
+			.position(|remote| remote == &self.target.remote)
+			.map_or(0, |idx| (idx + 1) % remotes.len());
+
+		if let Some(remote) = remotes.get(next) {
+			if remote != &self.target.remote {
+				self.remote = Some(remote.clone());
+				self.retarget();
+			}
+		}
+
+		Ok(())
+	}
+
+	fn load_preview(&mut self) {
+		self.scroll.reset();
 
-		if need_username_password()? {
+		self.preview = Some(if self.preview_asked_remote {
+			let remote = self.target.remote.clone();
 			let cred =
-				extract_username_password().unwrap_or_else(|_| {
-					BasicAuthCredential::new(None, None)
-				});
+				if need_username_password_for_remote(CWD, &remote)
+					.unwrap_or_default()
+				{
+					extract_username_password_for_remote(CWD, &remote)
+						.ok()
+				} else {
+					None
+				};
+
+			self.async_preview.spawn(AsyncPushPreviewJob::new(
+				remote,
+				self.branch.clone(),
+				cred,
+			));
+
+			Preview::Loading
+		} else {
+			match push_preview(CWD, &self.target.remote, &self.branch)
+			{
+				Ok(preview) => Preview::Loaded(preview),
+				Err(e) => Preview::Failed(e.to_string()),
+			}
+		});
+	}
+
+	///
+	pub fn update_preview(&mut self, ev: AsyncNotification) {
+		if matches!(
+			ev,
+			AsyncNotification::Git(AsyncGitNotification::PushPreview)
+		) {
+			if let Some(job) = self.async_preview.take_last() {
+				// ignore answers arriving after switching back
+				if let (Some(Preview::Loading), Some(result)) =
+					(&self.preview, job.result())
+				{
+					self.preview = Some(match result {
+						Ok(preview) => Preview::Loaded(preview),
+						Err(e) => Preview::Failed(e.to_string()),
+					});
+				}
+			}
+		}
+	}
+
+	fn preview_event(&mut self, e: KeyEvent) -> Result<()> {
+		if e == self.key_config.enter {
+			if matches!(self.preview, Some(Preview::Loaded(_))) {
+				self.preview = None;
+				self.start_push()?;
+			}
+		} else if e == self.key_config.push_preview_remote {
+			self.preview_asked_remote = !self.preview_asked_remote;
+			self.load_preview();
+		} else if e == self.key_config.push_select_branch {
+			self.open_branch_select()?;
+		} else if e == self.key_config.push_select_remote {
+			self.next_remote()?;
+		} else if e == self.key_config.move_up {
+			self.scroll.move_top(ScrollType::Up);
+		} else if e == self.key_config.move_down {
+			self.scroll.move_top(ScrollType::Down);
+		} else if e == self.key_config.home {
+			self.scroll.move_top(ScrollType::Home);
+		} else if e == self.key_config.end {
+			self.scroll.move_top(ScrollType::End);
+		}
+
+		Ok(())
+	}
+
+	fn start_push(&mut self) -> Result<()> {
+		if need_username_password_for_remote(
+			CWD,
+			&self.target.remote,
+		)? {
+			let cred = extract_username_password_for_remote(
+				CWD,
+				&self.target.remote,
+			)
+			.unwrap_or_else(|_| BasicAuthCredential::new(None, None));
 			if cred.is_complete() {
-				self.push_to_remote(Some(cred), force)
+				self.push_to_remote(Some(cred));
+				Ok(())
 			} else {
 				self.input_cred.set_cred(cred);
 				self.input_cred.show()
 			}
 		} else {
-			self.push_to_remote(None, force)
+			self.push_to_remote(None);
+			Ok(())
 		}
 	}
 
-	fn push_to_remote(
-		&mut self,
-		cred: Option<BasicAuthCredential>,
-		force: bool,
-	) -> Result<()> {
-		let remote = if let Ok(Some(remote)) =
-			get_branch_remote(CWD, &self.branch)
-		{
-			log::info!("push: branch '{}' has upstream for remote '{}' - using that",self.branch,remote);
-			remote
-		} else {
-			log::info!("push: branch '{}' has no upstream - looking up default remote",self.branch);
-			let remote = get_default_remote(CWD)?;
-			log::info!(
-				"push: branch '{}' to remote '{}'",
-				self.branch,
+	fn resolve_target(&self) -> Result<PushTarget> {
+		// `branch` names the remote branch to delete
+		if self.modifier.delete() {
+			let remote = if let Ok(Some(remote)) =
+				get_branch_remote(CWD, &self.branch)
+			{
 				remote
-			);
-			remote
+			} else {
+				get_default_remote(CWD)?
+			};
+
+			return Ok(PushTarget {
+				remote,
+				branch: self.branch.clone(),
+			});
+		}
+
+		let target = if let Some(remote) = &self.remote {
+			push_target_on_remote(CWD, &self.branch, remote)?
+		} else {
+			push_target_for_branch(CWD, &self.branch)?
 		};
+		log::info!(
+			"push: branch '{}' to '{}/{}'",
+			self.branch,
+			target.remote,
+			target.branch
+		);
+
+		Ok(target)
+	}
 
+	/// runs `pre-push` first, the push itself starts once it passed
+	fn push_to_remote(&mut self, cred: Option<BasicAuthCredential>) {
 		self.pending = true;
 		self.progress = None;
-		self.git_push.request(PushRequest {
-			remote,
-			branch: self.branch.clone(),
-			force,
-			delete: self.modifier.delete(),
-			basic_credential: cred,
-		})?;
+		self.cred = cred;
+
+		let job = AsyncPrePushHookJob::new(
+			self.target.remote.clone(),
+			self.branch.clone(),
+			self.modifier.delete(),
+		);
+		self.hooks.start(job.clone());
+		self.pre_push_hook.spawn(job);
+	}
+
+	fn pre_push_finished(
+		&mut self,
+		result: asyncgit::Result<HookResult>,
+	) -> Result<()> {
+		match result {
+			Ok(HookResult::Ok) => {
+				self.hooks.hide();
+				self.git_push.request(PushRequest {
+					remote: self.target.remote.clone(),
+					branch: self.branch.clone(),
+					force: self.modifier.force(),
+					delete: self.modifier.delete(),
+					basic_credential: self.cred.take(),
+				})?;
+			}
+			// the output stays visible, closing it closes the popup
+			Ok(HookResult::NotOk(_)) => {
+				self.pending = false;
+				self.hooks.fail();
+			}
+			Err(e) => {
+				self.pending = false;
+				self.hooks.hide();
+				self.hide();
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("pre-push hook failed:\n{}", e),
+				));
+			}
+		}
+
 		Ok(())
 	}
 
@@ -159,8 +475,19 @@ impl PushComponent {
 		ev: AsyncGitNotification,
 	) -> Result<()> {
 		if self.is_visible() {
-			if let AsyncGitNotification::Push = ev {
-				self.update()?;
+			match ev {
+				AsyncGitNotification::Push => self.update()?,
+				AsyncGitNotification::PrePushHook => {
+					if let Some(job) = self.pre_push_hook.take_last()
+					{
+						if let Some(result) = job.result() {
+							self.pre_push_finished(result)?;
+						}
+					} else {
+						self.hooks.update();
+					}
+				}
+				_ => (),
 			}
 		}
 
@@ -169,6 +496,10 @@ impl PushComponent {
 
 	///
 	fn update(&mut self) -> Result<()> {
+		if self.preview.is_some() || self.pre_push_hook.is_pending() {
+			return Ok(());
+		}
+
 		self.pending = self.git_push.is_pending()?;
 		self.progress = self.git_push.progress()?;
 
@@ -177,6 +508,18 @@ impl PushComponent {
 				self.queue.push(InternalEvent::ShowErrorMsg(
 					format!("push failed:\n{}", err),
 				));
+			} else {
+				let PushTarget { remote, branch } = &self.target;
+				self.queue.push(InternalEvent::ShowToast(
+					Severity::Success,
+					if self.modifier.delete() {
+						strings::toast_deleted_remote_branch(
+							remote, branch,
+						)
+					} else {
+						strings::toast_pushed(remote, branch)
+					},
+				));
 			}
 			self.hide();
 		}
@@ -185,8 +528,10 @@ impl PushComponent {
 	}
 
 	///
-	pub const fn any_work_pending(&self) -> bool {
+	pub fn any_work_pending(&self) -> bool {
 		self.pending
+			|| self.async_preview.is_pending()
+			|| self.pre_push_hook.is_pending()
 	}
 
 	///
@@ -226,6 +571,218 @@ impl PushComponent {
 	}
 }
 
+impl PushComponent {
+	fn title(&self) -> Span {
+		Span::styled(
+			strings::push_popup_title(
+				self.modifier.force(),
+				&self.target.remote,
+				&self.target.branch,
+			),
+			self.theme.title(true),
+		)
+	}
+
+	fn preview_header(&self, preview: &Preview) -> Vec<Spans> {
+		match preview {
+			Preview::Loading => {
+				vec![Spans::from(strings::loading_text(
+					&self.key_config,
+				))]
+			}
+			Preview::Failed(err) => vec![Spans::from(Span::styled(
+				strings::push_preview_failed(err),
+				self.theme.text_danger(),
+			))],
+			Preview::Loaded(preview) => {
+				let tip = preview
+					.remote_tip
+					.map(|tip| tip.get_short_string());
+				let kind_style = if preview.fast_forward {
+					self.theme.text(true, false)
+				} else {
+					self.theme.text_danger()
+				};
+
+				vec![
+					Spans::from(strings::push_preview_remote_tip(
+						tip.as_deref(),
+						self.preview_asked_remote,
+					)),
+					Spans::from(Span::styled(
+						strings::push_preview_kind(
+							preview.fast_forward,
+							preview.discarded,
+						),
+						kind_style,
+					)),
+					Spans::from(
+						self.upstream_gone
+							.as_ref()
+							.filter(|_| self.remote.is_none())
+							.map_or_else(String::new, |upstream| {
+								strings::push_preview_upstream_gone(
+									upstream,
+								)
+							}),
+					),
+					Spans::from(strings::push_preview_commits(
+						preview.commit_count,
+					)),
+				]
+			}
+		}
+	}
+
+	fn preview_commits(&self, width: usize) -> Vec<Spans> {
+		let preview = match &self.preview {
+			Some(Preview::Loaded(preview)) => preview,
+			_ => return Vec::new(),
+		};
+
+		let mut lines = preview
+			.commits
+			.iter()
+			.map(|commit| {
+				Spans::from(vec![
+					Span::styled(
+						commit.id.get_short_string(),
+						self.theme.commit_hash(false),
+					),
+					Span::raw(" "),
+					Span::styled(
+						string_width_align(
+							commit
+								.message
+								.lines()
+								.next()
+								.unwrap_or_default(),
+							width.saturating_sub(8),
+						),
+						self.theme.text(true, false),
+					),
+				])
+			})
+			.collect::<Vec<_>>();
+
+		if preview.commit_count > preview.commits.len() {
+			lines.push(Spans::from(strings::push_preview_more(
+				preview.commit_count - preview.commits.len(),
+			)));
+		}
+
+		lines
+	}
+
+	fn draw_preview<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		preview: &Preview,
+	) {
+		let area = ui::centered_rect(60, 60, f.size());
+
+		let block = Block::default()
+			.title(self.title())
+			.borders(Borders::ALL)
+			.border_type(BorderType::Thick)
+			.border_style(self.theme.block(true));
+		let inner = block.inner(area);
+
+		f.render_widget(Clear, area);
+		f.render_widget(block, area);
+
+		let chunks = Layout::default()
+			.direction(Direction::Vertical)
+			.constraints(
+				[
+					Constraint::Length(PREVIEW_HEADER_LINES),
+					Constraint::Min(0),
+				]
+				.as_ref(),
+			)
+			.split(inner);
+
+		f.render_widget(
+			Paragraph::new(self.preview_header(preview)),
+			chunks[0],
+		);
+
+		let commits =
+			self.preview_commits(usize::from(chunks[1].width));
+		let height = usize::from(chunks[1].height);
+		let top =
+			self.scroll.update_no_selection(commits.len(), height);
+
+		f.render_widget(
+			Paragraph::new(
+				commits
+					.into_iter()
+					.skip(top)
+					.take(height)
+					.collect::<Vec<_>>(),
+			),
+			chunks[1],
+		);
+		self.scroll.draw(f, area, &self.theme);
+
+		if let Some(select) = &self.branch_select {
+			self.draw_branch_select(f, select);
+		}
+	}
+
+	fn draw_branch_select<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		select: &BranchSelect,
+	) {
+		let area = ui::centered_rect(40, 40, f.size());
+		let height = usize::from(area.height.saturating_sub(2));
+		let width = usize::from(area.width.saturating_sub(2));
+
+		self.branch_scroll.update(
+			select.selection,
+			select.branches.len(),
+			height,
+		);
+
+		let block = Block::default()
+			.title(Span::styled(
+				strings::PUSH_SELECT_BRANCH_TITLE,
+				self.theme.title(true),
+			))
+			.borders(Borders::ALL)
+			.border_type(BorderType::Thick)
+			.border_style(self.theme.block(true));
+
+		let items = select
+			.branches
+			.iter()
+			.enumerate()
+			.skip(self.branch_scroll.get_top())
+			.take(height)
+			.map(|(idx, (name, compare))| {
+				let compare =
+					compare.map_or_else(String::new, |compare| {
+						format!(
+							" \u{2191}{} \u{2193}{}",
+							compare.ahead, compare.behind
+						)
+					});
+				Span::styled(
+					string_width_align(
+						&format!("{}{}", name, compare),
+						width,
+					),
+					self.theme.text(true, idx == select.selection),
+				)
+			});
+
+		f.render_widget(Clear, area);
+		ui::draw_list_block(f, area, block, items);
+		self.branch_scroll.draw(f, area, &self.theme);
+	}
+}
+
 impl DrawableComponent for PushComponent {
 	fn draw<B: Backend>(
 		&self,
@@ -233,10 +790,19 @@ impl DrawableComponent for PushComponent {
 		rect: Rect,
 	) -> Result<()> {
 		if self.visible {
-			let (state, progress) =
-				Self::get_progress(&self.progress);
+			if let Some(preview) = &self.preview {
+				self.draw_preview(f, preview);
+				return Ok(());
+			}
 
-			let area = ui::centered_rect_absolute(30, 3, f.size());
+			let (state, progress) = if self.pre_push_hook.is_pending()
+			{
+				(strings::PUSH_POPUP_STATES_PRE_PUSH.into(), 0)
+			} else {
+				Self::get_progress(&self.progress)
+			};
+
+			let area = ui::centered_rect_absolute(40, 3, f.size());
 
 			f.render_widget(Clear, area);
 			f.render_widget(
@@ -245,11 +811,11 @@ impl DrawableComponent for PushComponent {
 					.block(
 						Block::default()
 							.title(Span::styled(
-								if self.modifier.force() {
-									strings::FORCE_PUSH_POPUP_MSG
-								} else {
-									strings::PUSH_POPUP_MSG
-								},
+								strings::push_popup_title(
+									self.modifier.force(),
+									&self.target.remote,
+									&self.target.branch,
+								),
 								self.theme.title(true),
 							))
 							.borders(Borders::ALL)
@@ -261,6 +827,7 @@ impl DrawableComponent for PushComponent {
 				area,
 			);
 			self.input_cred.draw(f, rect)?;
+			self.hooks.draw(f, rect)?;
 		}
 
 		Ok(())
@@ -278,9 +845,69 @@ impl Component for PushComponent {
 				out.clear();
 			}
 
+			if self.hooks.is_visible() {
+				return self.hooks.commands(out, force_all);
+			}
 			if self.input_cred.is_visible() {
 				return self.input_cred.commands(out, force_all);
 			}
+			if self.branch_select.is_some() {
+				out.push(CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					true,
+					true,
+				));
+				out.push(CommandInfo::new(
+					strings::commands::push_choose_branch(
+						&self.key_config,
+					),
+					true,
+					true,
+				));
+				out.push(CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				));
+				return visibility_blocking(self);
+			}
+			if self.preview.is_some() {
+				out.push(CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					true,
+					true,
+				));
+				out.push(CommandInfo::new(
+					strings::commands::push_select_branch(
+						&self.key_config,
+					),
+					true,
+					true,
+				));
+				out.push(CommandInfo::new(
+					strings::commands::push_select_remote(
+						&self.key_config,
+					),
+					true,
+					true,
+				));
+				out.push(CommandInfo::new(
+					strings::commands::push_preview_remote(
+						&self.key_config,
+						self.preview_asked_remote,
+					),
+					true,
+					true,
+				));
+				out.push(CommandInfo::new(
+					strings::commands::push_preview_confirm(
+						&self.key_config,
+						self.modifier.force(),
+					),
+					matches!(self.preview, Some(Preview::Loaded(_))),
+					true,
+				));
+			}
 			out.push(CommandInfo::new(
 				strings::commands::close_msg(&self.key_config),
 				!self.pending,
@@ -293,23 +920,31 @@ impl Component for PushComponent {
 
 	fn event(&mut self, ev: Event) -> Result<EventState> {
 		if self.visible {
-			if let Event::Key(e) = ev {
+			if self.hooks.is_visible() {
+				self.hooks.event(ev)?;
+				if !self.hooks.is_visible() {
+					self.hide();
+				}
+			} else if let Event::Key(e) = ev {
 				if self.input_cred.is_visible() {
 					self.input_cred.event(ev)?;
 
 					if self.input_cred.get_cred().is_complete()
 						|| !self.input_cred.is_visible()
 					{
-						self.push_to_remote(
-							Some(self.input_cred.get_cred().clone()),
-							self.modifier.force(),
-						)?;
+						self.push_to_remote(Some(
+							self.input_cred.get_cred().clone(),
+						));
 						self.input_cred.hide();
 					}
+				} else if self.branch_select.is_some() {
+					self.branch_select_event(e);
 				} else if e == self.key_config.exit_popup
 					&& !self.pending
 				{
 					self.hide();
+				} else if self.preview.is_some() {
+					self.preview_event(e)?;
 				}
 			}
 			return Ok(EventState::Consumed);
@@ -330,4 +965,8 @@ impl Component for PushComponent {
 
 		Ok(())
 	}
+
+	fn on_resize(&self) {
+		self.input_cred.on_resize();
+	}
 }
@@ -4,6 +4,7 @@ use crate::{
 		CommandInfo, Component, DrawableComponent, EventState,
 	},
 	keys::SharedKeyConfig,
+	messages::Severity,
 	queue::{InternalEvent, Queue},
 	strings::{self},
 	ui::{self, style::SharedTheme},
@@ -120,6 +121,11 @@ impl PushTagsComponent {
 				self.queue.push(InternalEvent::ShowErrorMsg(
 					format!("push tags failed:\n{}", err),
 				));
+			} else {
+				self.queue.push(InternalEvent::ShowToast(
+					Severity::Success,
+					strings::toast_pushed_tags(),
+				));
 			}
 			self.hide();
 		}
@@ -260,4 +266,8 @@ impl Component for PushTagsComponent {
 
 		Ok(())
 	}
+
+	fn on_resize(&self) {
+		self.input_cred.on_resize();
+	}
 }
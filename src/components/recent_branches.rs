@@ -0,0 +1,209 @@
+use super::{
+	utils::string_width_align, visibility_blocking, CommandBlocking,
+	CommandInfo, Component, DrawableComponent, EventState,
+};
+use crate::{
+	branch_history::{self, SharedBranchHistory, MAX_BRANCHES},
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use tui::{
+	backend::Backend,
+	layout::{Alignment, Rect},
+	text::{Span, Spans},
+	widgets::{Block, BorderType, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// the last few checked out branches (most recent first) to
+/// switch to without the full branch list
+pub struct RecentBranchesComponent {
+	visible: bool,
+	branches: Vec<String>,
+	selection: usize,
+	history: SharedBranchHistory,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl RecentBranchesComponent {
+	///
+	pub fn new(
+		queue: &Queue,
+		history: SharedBranchHistory,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			visible: false,
+			branches: Vec::new(),
+			selection: 0,
+			history,
+			queue: queue.clone(),
+			theme,
+			key_config,
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.branches =
+			branch_history::existing_targets(&self.history.borrow())?;
+		self.selection = 0;
+
+		self.show()
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let max = self.branches.len().saturating_sub(1);
+
+		self.selection = if up {
+			self.selection.saturating_sub(1)
+		} else {
+			self.selection.saturating_add(1).min(max)
+		};
+	}
+
+	fn checkout_selected(&mut self) {
+		if let Some(branch) = self.branches.get(self.selection) {
+			if let Err(e) =
+				branch_history::checkout(&self.history, branch)
+			{
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("checkout error:\n{}", e),
+				));
+			}
+
+			self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+		}
+
+		self.hide();
+	}
+
+	fn get_text(&self, width: u16) -> Vec<Spans> {
+		if self.branches.is_empty() {
+			return vec![Spans::from(Span::styled(
+				strings::RECENT_BRANCHES_EMPTY,
+				self.theme.text(false, false),
+			))];
+		}
+
+		self.branches
+			.iter()
+			.enumerate()
+			.map(|(idx, branch)| {
+				Spans::from(Span::styled(
+					string_width_align(branch, usize::from(width)),
+					self.theme.text(true, idx == self.selection),
+				))
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for RecentBranchesComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		if self.is_visible() {
+			#[allow(clippy::cast_possible_truncation)]
+			const SIZE: (u16, u16) = (40, MAX_BRANCHES as u16 + 2);
+			let area =
+				ui::centered_rect_absolute(SIZE.0, SIZE.1, rect);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(
+					self.get_text(area.width.saturating_sub(2)),
+				)
+				.block(
+					Block::default()
+						.borders(Borders::ALL)
+						.border_type(BorderType::Thick)
+						.title(Span::styled(
+							strings::title_recent_branches(),
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				)
+				.alignment(Alignment::Left),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for RecentBranchesComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			let any = !self.branches.is_empty();
+
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				any,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::recent_branches_checkout(
+					&self.key_config,
+				),
+				any,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = ev {
+				if key == self.key_config.exit_popup {
+					self.hide();
+				} else if key == self.key_config.move_up {
+					self.move_selection(true);
+				} else if key == self.key_config.move_down {
+					self.move_selection(false);
+				} else if key == self.key_config.enter {
+					self.checkout_selected();
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
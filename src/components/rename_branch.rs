@@ -1,11 +1,12 @@
 use super::{
-	textinput::TextInputComponent, visibility_blocking,
+	textinput::TextInputComponent,
+	utils::branch_name::BranchNameCheck, visibility_blocking,
 	CommandBlocking, CommandInfo, Component, DrawableComponent,
 	EventState,
 };
 use crate::{
 	keys::SharedKeyConfig,
-	queue::{InternalEvent, NeedsUpdate, Queue},
+	queue::{Action, InternalEvent, NeedsUpdate, Queue},
 	strings,
 	ui::style::SharedTheme,
 };
@@ -22,6 +23,7 @@ pub struct RenameBranchComponent {
 	branch_ref: Option<String>,
 	queue: Queue,
 	key_config: SharedKeyConfig,
+	theme: SharedTheme,
 }
 
 impl DrawableComponent for RenameBranchComponent {
@@ -30,7 +32,15 @@ impl DrawableComponent for RenameBranchComponent {
 		f: &mut Frame<B>,
 		rect: Rect,
 	) -> Result<()> {
-		self.input.draw(f, rect)?;
+		if self.is_visible() {
+			self.input.draw(f, rect)?;
+			self.name_check().draw(
+				f,
+				self.input.get_area(),
+				&self.theme,
+				&self.key_config,
+			);
+		}
 
 		Ok(())
 	}
@@ -45,11 +55,19 @@ impl Component for RenameBranchComponent {
 		if self.is_visible() || force_all {
 			self.input.commands(out, force_all);
 
+			let check = self.name_check();
 			out.push(CommandInfo::new(
 				strings::commands::rename_branch_confirm_msg(
 					&self.key_config,
 				),
+				check.is_valid(),
 				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::branch_name_suggestion(
+					&self.key_config,
+				),
+				check.suggestion().is_some(),
 				true,
 			));
 		}
@@ -64,8 +82,15 @@ impl Component for RenameBranchComponent {
 			}
 
 			if let Event::Key(e) = ev {
-				if e == self.key_config.enter {
+				let check = self.name_check();
+				if e == self.key_config.enter && check.is_valid() {
 					self.rename_branch();
+				} else if e == self.key_config.branch_name_suggestion
+				{
+					if let Some(suggestion) = check.suggestion() {
+						self.input
+							.replace_text(suggestion.to_string());
+					}
 				}
 
 				return Ok(EventState::Consumed);
@@ -74,6 +99,10 @@ impl Component for RenameBranchComponent {
 		Ok(EventState::NotConsumed)
 	}
 
+	fn paste(&mut self, text: &str) -> Result<EventState> {
+		self.input.paste(text)
+	}
+
 	fn is_visible(&self) -> bool {
 		self.input.is_visible()
 	}
@@ -87,6 +116,10 @@ impl Component for RenameBranchComponent {
 
 		Ok(())
 	}
+
+	fn on_resize(&self) {
+		self.input.on_resize();
+	}
 }
 
 impl RenameBranchComponent {
@@ -99,7 +132,7 @@ impl RenameBranchComponent {
 		Self {
 			queue,
 			input: TextInputComponent::new(
-				theme,
+				theme.clone(),
 				key_config.clone(),
 				&strings::rename_branch_popup_title(&key_config),
 				&strings::rename_branch_popup_msg(&key_config),
@@ -107,6 +140,7 @@ impl RenameBranchComponent {
 			),
 			branch_ref: None,
 			key_config,
+			theme,
 		}
 	}
 
@@ -127,8 +161,12 @@ impl RenameBranchComponent {
 	///
 	pub fn rename_branch(&mut self) {
 		if let Some(br) = &self.branch_ref {
-			let res =
-				sync::rename_branch(CWD, br, self.input.get_text());
+			let res = sync::rename_branch(
+				CWD,
+				br,
+				self.input.get_text(),
+				false,
+			);
 
 			match res {
 				Ok(_) => {
@@ -138,6 +176,16 @@ impl RenameBranchComponent {
 					self.hide();
 					self.queue.push(InternalEvent::SelectBranch);
 				}
+				Err(asyncgit::Error::BranchCheckedOut(worktree)) => {
+					self.queue.push(InternalEvent::ConfirmAction(
+						Action::RenameCheckedOutBranch(
+							br.clone(),
+							self.input.get_text().to_string(),
+							worktree,
+						),
+					));
+					self.hide();
+				}
 				Err(e) => {
 					log::error!("create branch: {}", e,);
 					self.queue.push(InternalEvent::ShowErrorMsg(
@@ -155,4 +203,8 @@ impl RenameBranchComponent {
 
 		self.input.clear();
 	}
+
+	fn name_check(&self) -> BranchNameCheck {
+		BranchNameCheck::new(self.input.get_text())
+	}
 }
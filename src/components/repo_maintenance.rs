@@ -0,0 +1,235 @@
+use super::{
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{Action, InternalEvent, Queue},
+	strings, ui,
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+	sync::{self, StaleState},
+	CWD,
+};
+use crossterm::event::Event;
+use tui::{
+	backend::Backend,
+	layout::{Constraint, Direction, Layout, Rect},
+	text::Span,
+	widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+	Frame,
+};
+
+/// lists leftovers of crashed git processes (stale locks, orphaned
+/// merge/rebase state) and offers to clean them up one by one
+pub struct RepoMaintenanceComponent {
+	queue: Queue,
+	visible: bool,
+	items: Vec<StaleState>,
+	selection: usize,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl RepoMaintenanceComponent {
+	///
+	pub fn new(
+		queue: &Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			queue: queue.clone(),
+			visible: false,
+			items: Vec::new(),
+			selection: 0,
+			theme,
+			key_config,
+		}
+	}
+
+	/// detects stale state again, e.g. after a cleanup
+	pub fn update(&mut self) {
+		match sync::detect_stale_state(CWD) {
+			Ok(items) => self.items = items,
+			Err(e) => {
+				self.items.clear();
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("detect stale state error:\n{}", e),
+				));
+			}
+		}
+
+		self.selection =
+			self.selection.min(self.items.len().saturating_sub(1));
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let max = self.items.len().saturating_sub(1);
+
+		self.selection = if up {
+			self.selection.saturating_sub(1)
+		} else {
+			self.selection.saturating_add(1).min(max)
+		};
+	}
+
+	fn cleanup_selected(&self) {
+		if let Some(item) = self.items.get(self.selection) {
+			self.queue.push(InternalEvent::ConfirmAction(
+				Action::CleanupStaleState(item.clone()),
+			));
+		}
+	}
+
+	fn draw_list<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+		let block = Block::default()
+			.title(Span::styled(
+				strings::repo_maintenance_title(),
+				self.theme.title(true),
+			))
+			.borders(Borders::ALL)
+			.border_type(BorderType::Thick)
+			.border_style(self.theme.block(true));
+
+		if self.items.is_empty() {
+			f.render_widget(
+				Paragraph::new(strings::REPO_MAINTENANCE_EMPTY)
+					.style(self.theme.text(false, false))
+					.block(block),
+				area,
+			);
+			return;
+		}
+
+		let items =
+			self.items.iter().enumerate().map(|(idx, item)| {
+				Span::styled(
+					strings::stale_state_name(item),
+					self.theme.text(true, idx == self.selection),
+				)
+			});
+
+		ui::draw_list_block(f, area, block, items);
+	}
+
+	fn draw_details<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+		let text = self
+			.items
+			.get(self.selection)
+			.map(strings::stale_state_explanation)
+			.unwrap_or_default();
+
+		f.render_widget(
+			Paragraph::new(text)
+				.style(self.theme.text(true, false))
+				.block(
+					Block::default()
+						.borders(Borders::ALL)
+						.border_type(BorderType::Thick)
+						.border_style(self.theme.block(false)),
+				)
+				.wrap(Wrap { trim: false }),
+			area,
+		);
+	}
+}
+
+impl DrawableComponent for RepoMaintenanceComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		_rect: Rect,
+	) -> Result<()> {
+		if self.visible {
+			let area = ui::centered_rect(60, 50, f.size());
+
+			let chunks = Layout::default()
+				.direction(Direction::Vertical)
+				.constraints(
+					[
+						Constraint::Percentage(40),
+						Constraint::Percentage(60),
+					]
+					.as_ref(),
+				)
+				.split(area);
+
+			f.render_widget(Clear, area);
+			self.draw_list(f, chunks[0]);
+			self.draw_details(f, chunks[1]);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for RepoMaintenanceComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.visible || force_all {
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::repo_maintenance_cleanup(
+					&self.key_config,
+				),
+				!self.items.is_empty(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.visible {
+			if let Event::Key(e) = ev {
+				if e == self.key_config.exit_popup
+					|| e == self.key_config.open_repo_maintenance
+				{
+					self.hide();
+				} else if e == self.key_config.move_up {
+					self.move_selection(true);
+				} else if e == self.key_config.move_down {
+					self.move_selection(false);
+				} else if e == self.key_config.enter {
+					self.cleanup_selected();
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.selection = 0;
+		self.update();
+		self.visible = true;
+
+		Ok(())
+	}
+}
@@ -0,0 +1,282 @@
+use super::{
+	utils::string_width_align, visibility_blocking, CommandBlocking,
+	CommandInfo, Component, DrawableComponent, EventState,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, Queue},
+	repo_session::{RepoTarget, SharedRepoSession},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use std::convert::TryFrom;
+use tui::{
+	backend::Backend,
+	layout::{Alignment, Rect},
+	text::{Span, Spans},
+	widgets::{Block, BorderType, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// repos of the session with their branch and changes, switching
+/// keeps gitui running; repos that are gone are listed but skipped
+pub struct RepoSwitcherPopup {
+	visible: bool,
+	targets: Vec<RepoTarget>,
+	selection: usize,
+	session: SharedRepoSession,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl RepoSwitcherPopup {
+	///
+	pub fn new(
+		queue: &Queue,
+		session: SharedRepoSession,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			visible: false,
+			targets: Vec::new(),
+			selection: 0,
+			session,
+			queue: queue.clone(),
+			theme,
+			key_config,
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.targets = self.session.borrow().targets();
+		self.selection = self
+			.targets
+			.iter()
+			.position(|target| target.current)
+			.unwrap_or_default();
+
+		self.show()
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let next = if up {
+			self.targets[..self.selection]
+				.iter()
+				.rposition(RepoTarget::is_available)
+		} else {
+			self.targets
+				.iter()
+				.enumerate()
+				.skip(self.selection + 1)
+				.find(|(_, target)| target.is_available())
+				.map(|(idx, _)| idx)
+		};
+
+		if let Some(next) = next {
+			self.selection = next;
+		}
+	}
+
+	fn can_switch(&self) -> bool {
+		self.targets.get(self.selection).map_or(false, |target| {
+			target.is_available() && !target.current
+		})
+	}
+
+	fn switch_selected(&mut self) {
+		if self.can_switch() {
+			self.queue.push(InternalEvent::SwitchRepo(
+				self.targets[self.selection].path.clone(),
+			));
+		}
+
+		self.hide();
+	}
+
+	fn get_text(&self, width: usize) -> Vec<Spans> {
+		let path_width = self
+			.targets
+			.iter()
+			.map(|target| {
+				target.path.to_string_lossy().chars().count()
+			})
+			.max()
+			.unwrap_or_default()
+			.min(width / 2);
+
+		self.targets
+			.iter()
+			.enumerate()
+			.map(|(idx, target)| {
+				let selected = idx == self.selection;
+				let path = format!(
+					"{} {} ",
+					if target.current { '*' } else { ' ' },
+					string_width_align(
+						&target.path.to_string_lossy(),
+						path_width
+					)
+				);
+
+				let (summary, style) = match &target.summary {
+					Ok(summary) => (
+						strings::repo_switcher_summary(
+							summary.branch.as_deref(),
+							summary.changes,
+						),
+						self.theme.text(true, selected),
+					),
+					Err(e) => (
+						strings::repo_switcher_unavailable(e),
+						self.theme.text_danger(),
+					),
+				};
+
+				Spans::from(vec![
+					Span::styled(
+						path,
+						self.theme
+							.text(target.is_available(), selected),
+					),
+					Span::styled(
+						string_width_align(
+							&summary,
+							width.saturating_sub(path_width + 3),
+						),
+						style,
+					),
+				])
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for RepoSwitcherPopup {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		if self.is_visible() {
+			const MAX_HEIGHT: u16 = 12;
+
+			// only the open repo: explain how to get more
+			if self.targets.len() <= 1 {
+				let area = ui::centered_rect_absolute(60, 9, rect);
+				f.render_widget(Clear, area);
+				ui::draw_empty_state(
+					f,
+					area,
+					&strings::title_repo_switcher(),
+					&strings::repo_switcher_empty(),
+					true,
+					&self.theme,
+				);
+
+				return Ok(());
+			}
+
+			let height = u16::try_from(self.targets.len())
+				.unwrap_or(MAX_HEIGHT)
+				.min(MAX_HEIGHT)
+				+ 2;
+			let area = ui::centered_rect_absolute(80, height, rect);
+
+			// keeps the selection in view
+			let scroll = u16::try_from(self.selection)
+				.unwrap_or_default()
+				.saturating_sub(area.height.saturating_sub(3));
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(self.get_text(usize::from(
+					area.width.saturating_sub(2),
+				)))
+				.block(
+					Block::default()
+						.borders(Borders::ALL)
+						.border_type(BorderType::Thick)
+						.title(Span::styled(
+							strings::title_repo_switcher(),
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				)
+				.scroll((scroll, 0))
+				.alignment(Alignment::Left),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for RepoSwitcherPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				self.targets.len() > 1,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::repo_switcher_switch(
+					&self.key_config,
+				),
+				self.can_switch(),
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = ev {
+				if key == self.key_config.exit_popup {
+					self.hide();
+				} else if key == self.key_config.move_up {
+					self.move_selection(true);
+				} else if key == self.key_config.move_down {
+					self.move_selection(false);
+				} else if key == self.key_config.enter {
+					self.switch_selected();
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
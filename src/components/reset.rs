@@ -132,6 +132,7 @@ impl ConfirmComponent {
 		self.hide();
 	}
 
+	#[allow(clippy::too_many_lines)]
 	fn get_text(&self) -> (String, String) {
 		if let Some(ref a) = self.target {
 			return match a {
@@ -157,6 +158,20 @@ impl ConfirmComponent {
                     strings::confirm_title_reset(),
                     strings::confirm_msg_reset_lines(lines.len()),
                 ),
+                Action::ResolveConflict(path, side) => (
+                    strings::confirm_title_resolve_conflict(*side),
+                    strings::confirm_msg_resolve_conflict(path, *side),
+                ),
+                Action::RenameCheckedOutBranch(
+                    branch_ref,
+                    name,
+                    worktree,
+                ) => (
+                    strings::confirm_title_rename_checked_out(),
+                    strings::confirm_msg_rename_checked_out(
+                        branch_ref, name, worktree,
+                    ),
+                ),
                 Action::DeleteBranch(branch_ref, true) => (
                     strings::confirm_title_delete_branch(
                         &self.key_config,
@@ -175,6 +190,10 @@ impl ConfirmComponent {
                         branch_ref,
                     ),
                 ),
+                Action::DeleteMergedBranches(branch_refs) => (
+                    strings::confirm_title_delete_merged(),
+                    strings::confirm_msg_delete_merged(branch_refs),
+                ),
                 Action::DeleteTag(tag_name) => (
                     strings::confirm_title_delete_tag(
                         &self.key_config,
@@ -193,13 +212,67 @@ impl ConfirmComponent {
                         branch.rsplit('/').next().expect("There was no / in the head reference which is impossible in git"),
                     ),
                 ),
+                Action::PushProtected(branch, _force) => (
+                    strings::confirm_title_push_protected(),
+                    strings::confirm_msg_push_protected(branch),
+                ),
                 Action::PullMerge{incoming,rebase} => (
                     strings::confirm_title_merge(&self.key_config,*rebase),
                     strings::confirm_msg_merge(&self.key_config,*incoming,*rebase),
                 ),
-                Action::AbortMerge => (
-                    strings::confirm_title_abortmerge(),
-                    strings::confirm_msg_abortmerge(),
+                Action::AbortRepoState(state) => (
+                    strings::confirm_title_abort_repo_state(*state),
+                    strings::confirm_msg_abort_repo_state(),
+                ),
+                Action::RetryIndexOp(_) => (
+                    strings::confirm_title_index_locked(),
+                    strings::confirm_msg_index_locked(&self.key_config),
+                ),
+                Action::StageLfsUnconverted(files, _) => (
+                    strings::confirm_title_stage_lfs_unconverted(),
+                    strings::confirm_msg_stage_lfs_unconverted(files),
+                ),
+                Action::StageLargeFiles(files, _) => (
+                    strings::confirm_title_stage_large_files(),
+                    strings::confirm_msg_stage_large_files(files),
+                ),
+                Action::CommitEmpty => (
+                    strings::confirm_title_commit_empty(),
+                    strings::confirm_msg_commit_empty(),
+                ),
+                Action::CommitLargeFiles(files) => (
+                    strings::confirm_title_commit_large_files(),
+                    strings::confirm_msg_commit_large_files(files),
+                ),
+                Action::CleanupStaleState(state) => (
+                    strings::confirm_title_cleanup_stale_state(),
+                    strings::confirm_msg_cleanup_stale_state(state),
+                ),
+                Action::LogExportOverwrite(path) => (
+                    strings::confirm_title_log_export_overwrite(),
+                    strings::confirm_msg_log_export_overwrite(
+                        &path.to_string_lossy(),
+                    ),
+                ),
+                Action::AutosquashRebase(_, onto) => (
+                    strings::confirm_title_autosquash(),
+                    strings::confirm_msg_autosquash(onto),
+                ),
+                Action::BisectFound(_, commit) => (
+                    strings::confirm_title_bisect_found(),
+                    strings::confirm_msg_bisect_found(commit),
+                ),
+                Action::BisectReset => (
+                    strings::confirm_title_bisect_reset(),
+                    strings::confirm_msg_bisect_reset(),
+                ),
+                Action::CopyDiscardedHunk(_, err) => (
+                    strings::confirm_title_copy_discarded_hunk(),
+                    strings::confirm_msg_copy_discarded_hunk(err),
+                ),
+                Action::SkipWorktree(path) => (
+                    strings::confirm_title_skip_worktree(),
+                    strings::confirm_msg_skip_worktree(path),
                 ),
             };
 		}
@@ -1,35 +1,46 @@
 use super::{
-	utils::scroll_vertical::VerticalScroll, CommandBlocking,
-	CommandInfo, Component, DrawableComponent, EventState,
-	SyntaxTextComponent,
+	utils::{
+		scroll_vertical::VerticalScroll, selected_path::SelectedPath,
+	},
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState, FileSource, SyntaxTextComponent,
 };
 use crate::{
 	keys::SharedKeyConfig,
-	queue::{InternalEvent, Queue},
+	queue::{FileLine, InternalEvent, Queue},
 	strings::{self, order, symbol},
 	ui::{self, common_nav, style::SharedTheme},
 	AsyncAppNotification, AsyncNotification,
 };
 use anyhow::Result;
 use asyncgit::{
-	sync::{self, CommitId, TreeFile},
+	sync::{self, CommitId, TreeFile, TreeFileKind},
 	CWD,
 };
+use bytesize::ByteSize;
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
 use filetreelist::{FileTree, FileTreeItem};
 use std::{
-	collections::BTreeSet,
+	collections::{BTreeSet, HashMap, HashSet},
 	convert::From,
 	path::{Path, PathBuf},
 };
 use tui::{
 	backend::Backend,
 	layout::{Constraint, Direction, Layout, Rect},
-	text::Span,
+	style::Modifier,
+	text::{Span, Spans},
 	widgets::{Block, Borders},
 	Frame,
 };
+use unicode_truncate::UnicodeTruncateStr;
+use unicode_width::UnicodeWidthStr;
+
+/// mode and size columns are dropped when less is left for the name
+const MIN_NAME_WIDTH: usize = 16;
+/// fits `ByteSize` output like `1023.9 KB`
+const SIZE_WIDTH: usize = 9;
 
 enum Focus {
 	Tree,
@@ -41,6 +52,9 @@ pub struct RevisionFilesComponent {
 	theme: SharedTheme,
 	//TODO: store TreeFiles in `tree`
 	files: Vec<TreeFile>,
+	file_indices: HashMap<PathBuf, usize>,
+	ignored: HashSet<PathBuf>,
+	show_sizes: bool,
 	current_file: SyntaxTextComponent,
 	tree: FileTree,
 	scroll: VerticalScroll,
@@ -68,18 +82,42 @@ impl RevisionFilesComponent {
 			),
 			theme,
 			files: Vec::new(),
+			file_indices: HashMap::new(),
+			ignored: HashSet::new(),
+			show_sizes: true,
 			revision: None,
 			focus: Focus::Tree,
 			key_config,
 		}
 	}
 
+	/// whether the file content has the focus instead of the tree
+	pub const fn file_focused(&self) -> bool {
+		matches!(self.focus, Focus::File)
+	}
+
+	///
+	pub fn focus_file(&mut self, file: bool) {
+		self.focus = if file { Focus::File } else { Focus::Tree };
+		self.current_file.focus(file);
+	}
+
 	///
 	pub fn set_commit(&mut self, commit: CommitId) -> Result<()> {
 		let same_id =
 			self.revision.map(|c| c == commit).unwrap_or_default();
 		if !same_id {
 			self.files = sync::tree_files(CWD, commit)?;
+			self.file_indices = self
+				.files
+				.iter()
+				.enumerate()
+				.map(|(idx, f)| (f.path.clone(), idx))
+				.collect();
+			self.ignored = sync::paths_ignored(
+				CWD,
+				self.files.iter().map(|f| f.path.as_path()),
+			)?;
 			let filenames: Vec<&Path> =
 				self.files.iter().map(|f| f.path.as_path()).collect();
 			self.tree = FileTree::new(&filenames, &BTreeSet::new())?;
@@ -90,6 +128,11 @@ impl RevisionFilesComponent {
 		Ok(())
 	}
 
+	/// no files in the revision (e.g. an empty first commit)
+	pub fn is_empty(&self) -> bool {
+		self.files.is_empty()
+	}
+
 	///
 	pub fn update(&mut self, ev: AsyncNotification) {
 		self.current_file.update(ev);
@@ -100,11 +143,12 @@ impl RevisionFilesComponent {
 		self.current_file.any_work_pending()
 	}
 
-	fn tree_item_to_span<'a>(
-		item: &'a FileTreeItem,
-		theme: &SharedTheme,
+	fn tree_item_to_spans(
+		&self,
+		item: &FileTreeItem,
 		selected: bool,
-	) -> Span<'a> {
+		width: usize,
+	) -> Spans<'static> {
 		let path = item.info().path_str();
 		let indent = item.info().indent();
 
@@ -125,22 +169,104 @@ impl RevisionFilesComponent {
 			symbol::EMPTY_STR
 		};
 
-		let path = format!("{}{}{}", indent_str, path_arrow, path);
-		Span::styled(path, theme.file_tree_item(is_path, selected))
+		let full_path = item.info().full_path();
+		let file = self
+			.file_indices
+			.get(full_path)
+			.and_then(|idx| self.files.get(*idx));
+
+		let mut name =
+			format!("{}{}{}", indent_str, path_arrow, path);
+		if let Some(target) =
+			file.and_then(|file| file.link_target.as_ref())
+		{
+			name.push_str(symbol::SYMLINK_ARROW);
+			name.push_str(target);
+		}
+
+		let columns = self.columns(file);
+		let columns = if width >= MIN_NAME_WIDTH + columns.width() {
+			columns
+		} else {
+			String::new()
+		};
+
+		let name = truncate_pad(
+			&name,
+			width.saturating_sub(columns.width()),
+		);
+
+		let mut name_style =
+			self.theme.file_tree_item(is_path, selected);
+		let mut columns_style =
+			self.theme.file_tree_item(true, selected);
+		if self.ignored.contains(full_path) {
+			name_style = name_style.add_modifier(Modifier::DIM);
+			columns_style = columns_style.add_modifier(Modifier::DIM);
+		}
+
+		let mut spans: Vec<Span> = self
+			.theme
+			.selection_marker(selected)
+			.into_iter()
+			.collect();
+		spans.push(Span::styled(name, name_style));
+		spans.push(Span::styled(columns, columns_style));
+
+		Spans::from(spans)
+	}
+
+	/// right aligned mode and size of a file, blank for folders
+	fn columns(&self, file: Option<&TreeFile>) -> String {
+		let mode = match file.map(|file| file.kind) {
+			Some(TreeFileKind::Executable) => "x",
+			Some(TreeFileKind::Symlink) => "@",
+			Some(TreeFileKind::File) | None => " ",
+		};
+
+		if self.show_sizes {
+			let size = file
+				.map(|file| ByteSize::b(file.size as u64).to_string())
+				.unwrap_or_default();
+			format!(" {} {:>w$}", mode, size, w = SIZE_WIDTH)
+		} else {
+			format!(" {}", mode)
+		}
 	}
 
 	fn blame(&self) -> bool {
-		self.tree.selected_file().map_or(false, |file| {
-			self.queue.push(InternalEvent::BlameFile(
-				file.full_path_str()
-					.strip_prefix("./")
-					.unwrap_or_default()
-					.to_string(),
-			));
+		self.selected_path().map_or(false, |path| {
+			self.queue.push(InternalEvent::BlameFile(FileLine {
+				path,
+				..FileLine::default()
+			}));
 			true
 		})
 	}
 
+	fn compare_workdir(&self) -> bool {
+		match (self.revision, self.selected_path()) {
+			(Some(id), Some(path)) => {
+				self.queue
+					.push(InternalEvent::CompareWorkdir(id, path));
+				true
+			}
+			_ => false,
+		}
+	}
+
+	fn view_file(&self) -> bool {
+		match (self.revision, self.selected_path()) {
+			(Some(id), Some(path)) => {
+				self.queue.push(InternalEvent::ViewFile(
+					FileSource::Commit(id, path),
+				));
+				true
+			}
+			_ => false,
+		}
+	}
+
 	fn open_finder(&self) {
 		self.queue
 			.push(InternalEvent::OpenFileFinder(self.files.clone()));
@@ -164,8 +290,11 @@ impl RevisionFilesComponent {
 		{
 			log::info!("selected: {:?}", file);
 			let path = Path::new(&file);
-			if let Some(item) =
-				self.files.iter().find(|f| f.path == path)
+			let files = &self.files;
+			if let Some(item) = self
+				.file_indices
+				.get(path)
+				.and_then(|idx| files.get(*idx))
 			{
 				if let Ok(path) = path.strip_prefix("./") {
 					return self.current_file.load_file(
@@ -180,6 +309,7 @@ impl RevisionFilesComponent {
 
 	fn draw_tree<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
 		let tree_height = usize::from(area.height.saturating_sub(2));
+		let tree_width = usize::from(area.width.saturating_sub(2));
 
 		self.tree.visual_selection().map_or_else(
 			|| {
@@ -198,7 +328,7 @@ impl RevisionFilesComponent {
 			.tree
 			.iterate(self.scroll.get_top(), tree_height)
 			.map(|(item, selected)| {
-				Self::tree_item_to_span(item, &self.theme, selected)
+				self.tree_item_to_spans(item, selected, tree_width)
 			});
 
 		let is_tree_focused = matches!(self.focus, Focus::Tree);
@@ -206,7 +336,7 @@ impl RevisionFilesComponent {
 		let title = format!(
 			"Files at [{}]",
 			self.revision
-				.map(|c| c.get_short_string())
+				.map(|c| c.abbreviated(CWD))
 				.unwrap_or_default(),
 		);
 		ui::draw_list_block(
@@ -215,7 +345,7 @@ impl RevisionFilesComponent {
 			Block::default()
 				.title(Span::styled(
 					title,
-					self.theme.title(is_tree_focused),
+					self.theme.pane_title(is_tree_focused),
 				))
 				.borders(Borders::ALL)
 				.border_style(self.theme.block(is_tree_focused)),
@@ -271,7 +401,34 @@ impl Component for RevisionFilesComponent {
 				)
 				.order(order::NAV),
 			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::compare_workdir(
+						&self.key_config,
+					),
+					self.tree.selected_file().is_some(),
+					true,
+				)
+				.order(order::NAV),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::view_file(&self.key_config),
+					self.tree.selected_file().is_some(),
+					true,
+				)
+				.order(order::NAV),
+			);
+			out.push(CommandInfo::new(
+				strings::commands::tree_toggle_sizes(
+					&self.key_config,
+					self.show_sizes,
+				),
+				true,
+				true,
+			));
 			tree_nav_cmds(&self.tree, &self.key_config, out);
+			self.path_commands(&self.key_config, true, out);
 		} else {
 			self.current_file.commands(out, force_all);
 		}
@@ -290,23 +447,38 @@ impl Component for RevisionFilesComponent {
 			{
 				self.selection_changed();
 				return Ok(EventState::Consumed);
+			} else if is_tree_focused
+				&& self
+					.path_event(key, &self.key_config, &self.queue)
+					.is_consumed()
+			{
+				return Ok(EventState::Consumed);
 			} else if key == self.key_config.blame {
 				if self.blame() {
 					self.hide();
 					return Ok(EventState::Consumed);
 				}
+			} else if key == self.key_config.compare_workdir {
+				if is_tree_focused && self.compare_workdir() {
+					return Ok(EventState::Consumed);
+				}
+			} else if key == self.key_config.view_file {
+				if is_tree_focused && self.view_file() {
+					return Ok(EventState::Consumed);
+				}
 			} else if key == self.key_config.move_right {
 				if is_tree_focused {
-					self.focus = Focus::File;
-					self.current_file.focus(true);
-					self.focus(true);
+					self.focus_file(true);
 					return Ok(EventState::Consumed);
 				}
 			} else if key == self.key_config.move_left {
 				if !is_tree_focused {
-					self.focus = Focus::Tree;
-					self.current_file.focus(false);
-					self.focus(false);
+					self.focus_file(false);
+					return Ok(EventState::Consumed);
+				}
+			} else if key == self.key_config.tree_toggle_sizes {
+				if is_tree_focused {
+					self.show_sizes = !self.show_sizes;
 					return Ok(EventState::Consumed);
 				}
 			} else if key == self.key_config.file_find {
@@ -323,6 +495,29 @@ impl Component for RevisionFilesComponent {
 	}
 }
 
+impl SelectedPath for RevisionFilesComponent {
+	fn selected_path(&self) -> Option<String> {
+		self.tree.selected_file().map(|file| {
+			file.full_path_str()
+				.strip_prefix("./")
+				.unwrap_or_default()
+				.to_string()
+		})
+	}
+}
+
+/// cuts `text` to `width` columns marking the cut with an ellipsis,
+/// shorter text is padded with spaces
+fn truncate_pad(text: &str, width: usize) -> String {
+	if text.width() > width {
+		let (text, _) =
+			text.unicode_truncate(width.saturating_sub(1));
+		format!("{}{}", text, symbol::ELLIPSIS)
+	} else {
+		format!("{}{:w$}", text, "", w = width - text.width())
+	}
+}
+
 //TODO: reuse for other tree usages
 fn tree_nav_cmds(
 	tree: &FileTree,
@@ -357,3 +552,16 @@ fn tree_nav(
 		false
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_truncate_pad() {
+		assert_eq!(truncate_pad("foo", 5), "foo  ");
+		assert_eq!(truncate_pad("foo", 3), "foo");
+		assert_eq!(truncate_pad("foobar", 4), "foo\u{2026}");
+		assert_eq!(truncate_pad("foobar", 0), "\u{2026}");
+	}
+}
@@ -0,0 +1,204 @@
+use super::{
+	textinput::TextInputComponent, visibility_blocking,
+	with_index_snapshot, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	messages::Severity,
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings,
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{sync, CWD};
+use crossterm::event::Event;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// (un)stages all files matching a pathspec typed by the user,
+/// previewing the number of matches while typing
+pub struct StagePatternPopup {
+	input: TextInputComponent,
+	/// paths of the status list the pattern is previewed against
+	paths: Vec<String>,
+	unstage: bool,
+	matches: Option<usize>,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for StagePatternPopup {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		self.input.draw(f, rect)?;
+
+		Ok(())
+	}
+}
+
+impl Component for StagePatternPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			self.input.commands(out, force_all);
+
+			out.push(CommandInfo::new(
+				strings::commands::stage_pattern_confirm(
+					&self.key_config,
+					self.unstage,
+				),
+				self.matches.unwrap_or_default() > 0,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if self.input.event(ev)?.is_consumed() {
+				self.update_matches();
+				return Ok(EventState::Consumed);
+			}
+
+			if let Event::Key(e) = ev {
+				if e == self.key_config.enter {
+					self.confirm();
+				}
+
+				return Ok(EventState::Consumed);
+			}
+		}
+		Ok(EventState::NotConsumed)
+	}
+
+	fn paste(&mut self, text: &str) -> Result<EventState> {
+		self.input.paste(text)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.input.is_visible()
+	}
+
+	fn hide(&mut self) {
+		self.input.hide();
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.input.show()?;
+
+		Ok(())
+	}
+
+	fn on_resize(&self) {
+		self.input.on_resize();
+	}
+}
+
+impl StagePatternPopup {
+	///
+	pub fn new(
+		queue: Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			queue,
+			input: TextInputComponent::new(
+				theme,
+				key_config.clone(),
+				&strings::stage_pattern_popup_title(false, None),
+				strings::STAGE_PATTERN_HINT,
+				true,
+			),
+			paths: Vec::new(),
+			unstage: false,
+			matches: None,
+			key_config,
+		}
+	}
+
+	/// `paths` of the working dir (or stage if `unstage`) status
+	pub fn open(
+		&mut self,
+		paths: Vec<String>,
+		unstage: bool,
+	) -> Result<()> {
+		self.paths = paths;
+		self.unstage = unstage;
+		self.input.clear();
+		self.update_matches();
+		self.show()?;
+
+		Ok(())
+	}
+
+	fn update_matches(&mut self) {
+		let pattern = self.input.get_text();
+
+		self.matches = if pattern.is_empty() {
+			None
+		} else {
+			// an invalid pathspec simply matches nothing
+			Some(
+				sync::count_pathspec_matches(pattern, &self.paths)
+					.unwrap_or_default(),
+			)
+		};
+
+		self.input.set_title(strings::stage_pattern_popup_title(
+			self.unstage,
+			self.matches,
+		));
+	}
+
+	fn confirm(&mut self) {
+		let pattern = self.input.get_text().to_string();
+		if pattern.is_empty() {
+			return;
+		}
+
+		let res = with_index_snapshot(&self.queue, || {
+			if self.unstage {
+				sync::unstage_pathspec(CWD, &pattern)
+			} else {
+				sync::stage_pathspec(CWD, &pattern)
+			}
+		});
+
+		match res {
+			Ok(0) => {
+				self.queue.push(InternalEvent::ShowToast(
+					Severity::Error,
+					strings::stage_pattern_no_match(&pattern),
+				));
+			}
+			Ok(count) => {
+				self.hide();
+				self.queue
+					.push(InternalEvent::Update(NeedsUpdate::ALL));
+				self.queue.push(InternalEvent::ShowToast(
+					Severity::Success,
+					strings::toast_staged_pattern(
+						self.unstage,
+						count,
+					),
+				));
+			}
+			Err(e) => {
+				log::error!("stage pattern: {}", e);
+				self.hide();
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("stage pattern error:\n{}", e),
+				));
+			}
+		}
+	}
+}
@@ -1,24 +1,33 @@
 use super::{
 	textinput::TextInputComponent, visibility_blocking,
 	CommandBlocking, CommandInfo, Component, DrawableComponent,
-	EventState,
+	EventState, SharedOptions,
 };
 use crate::{
 	keys::SharedKeyConfig,
+	messages::Severity,
 	queue::{InternalEvent, NeedsUpdate, Queue},
 	strings,
 	tabs::StashingOptions,
-	ui::style::SharedTheme,
+	ui::{self, style::SharedTheme},
 };
 use anyhow::Result;
 use asyncgit::{sync, CWD};
 use crossterm::event::Event;
-use tui::{backend::Backend, layout::Rect, Frame};
+use tui::{
+	backend::Backend,
+	layout::{Constraint, Direction, Layout, Margin, Rect},
+	text::Span,
+	widgets::{Block, BorderType, Borders, Clear, Paragraph},
+	Frame,
+};
 
 pub struct StashMsgComponent {
 	options: StashingOptions,
 	input: TextInputComponent,
 	queue: Queue,
+	app_options: SharedOptions,
+	theme: SharedTheme,
 	key_config: SharedKeyConfig,
 }
 
@@ -28,7 +37,55 @@ impl DrawableComponent for StashMsgComponent {
 		f: &mut Frame<B>,
 		rect: Rect,
 	) -> Result<()> {
-		self.input.draw(f, rect)?;
+		if self.is_visible() {
+			let area = ui::rect_inside(
+				ui::Size::new(10, 6),
+				rect.into(),
+				ui::centered_rect(60, 20, rect),
+			);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Block::default()
+					.title(Span::styled(
+						strings::stash_popup_title(&self.key_config),
+						self.theme.title(true),
+					))
+					.borders(Borders::ALL)
+					.border_type(BorderType::Thick)
+					.border_style(self.theme.block(true)),
+				area,
+			);
+
+			let chunks = Layout::default()
+				.direction(Direction::Vertical)
+				.constraints(
+					[Constraint::Min(1), Constraint::Length(2)]
+						.as_ref(),
+				)
+				.split(area.inner(&Margin {
+					horizontal: 1,
+					vertical: 1,
+				}));
+
+			self.input.draw(f, chunks[0])?;
+
+			f.render_widget(
+				Paragraph::new(vec![
+					ui::checkbox(
+						strings::STASH_OPTION_UNTRACKED,
+						self.options.stash_untracked,
+						&self.theme,
+					),
+					ui::checkbox(
+						strings::STASH_OPTION_KEEP_INDEX,
+						self.options.keep_index,
+						&self.theme,
+					),
+				]),
+				chunks[1],
+			);
+		}
 
 		Ok(())
 	}
@@ -43,6 +100,20 @@ impl Component for StashMsgComponent {
 		if self.is_visible() || force_all {
 			self.input.commands(out, force_all);
 
+			out.push(CommandInfo::new(
+				strings::commands::stash_msg_toggle_untracked(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::stash_msg_toggle_index(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
 			out.push(CommandInfo::new(
 				strings::commands::stashing_confirm_msg(
 					&self.key_config,
@@ -57,6 +128,20 @@ impl Component for StashMsgComponent {
 
 	fn event(&mut self, ev: Event) -> Result<EventState> {
 		if self.is_visible() {
+			// the toggles share their keys with the line editing
+			if let Event::Key(e) = ev {
+				if e == self.key_config.stash_msg_toggle_untracked {
+					self.options.stash_untracked =
+						!self.options.stash_untracked;
+					return Ok(EventState::Consumed);
+				} else if e == self.key_config.stash_msg_toggle_index
+				{
+					self.options.keep_index =
+						!self.options.keep_index;
+					return Ok(EventState::Consumed);
+				}
+			}
+
 			if self.input.event(ev)?.is_consumed() {
 				return Ok(EventState::Consumed);
 			}
@@ -80,6 +165,21 @@ impl Component for StashMsgComponent {
 							self.queue.push(InternalEvent::Update(
 								NeedsUpdate::ALL,
 							));
+							self.queue.push(
+								InternalEvent::ShowToast(
+									Severity::Success,
+									strings::toast_stash_created(),
+								),
+							);
+							if self
+								.app_options
+								.borrow()
+								.stash_show_list
+							{
+								self.queue.push(
+									InternalEvent::ShowStashList,
+								);
+							}
 						}
 						Err(e) => {
 							self.hide();
@@ -105,6 +205,10 @@ impl Component for StashMsgComponent {
 		Ok(EventState::NotConsumed)
 	}
 
+	fn paste(&mut self, text: &str) -> Result<EventState> {
+		self.input.paste(text)
+	}
+
 	fn is_visible(&self) -> bool {
 		self.input.is_visible()
 	}
@@ -118,6 +222,10 @@ impl Component for StashMsgComponent {
 
 		Ok(())
 	}
+
+	fn on_resize(&self) {
+		self.input.on_resize();
+	}
 }
 
 impl StashMsgComponent {
@@ -126,17 +234,23 @@ impl StashMsgComponent {
 		queue: Queue,
 		theme: SharedTheme,
 		key_config: SharedKeyConfig,
+		app_options: SharedOptions,
 	) -> Self {
+		let mut input = TextInputComponent::new(
+			theme.clone(),
+			key_config.clone(),
+			"",
+			&strings::stash_popup_msg(&key_config),
+			true,
+		);
+		input.embed();
+
 		Self {
 			options: StashingOptions::default(),
 			queue,
-			input: TextInputComponent::new(
-				theme,
-				key_config.clone(),
-				&strings::stash_popup_title(&key_config),
-				&strings::stash_popup_msg(&key_config),
-				true,
-			),
+			input,
+			app_options,
+			theme,
 			key_config,
 		}
 	}
@@ -26,7 +26,7 @@ use std::{cell::Cell, convert::From, path::Path};
 use tui::{
 	backend::Backend,
 	layout::Rect,
-	text::Text,
+	text::{Span, Text},
 	widgets::{Block, Borders, Wrap},
 	Frame,
 };
@@ -214,7 +214,10 @@ impl DrawableComponent for SyntaxTextComponent {
 			.wrap(Wrap { trim: false })
 			.block(
 				Block::default()
-					.title(title)
+					.title(Span::styled(
+						title,
+						self.theme.pane_title(self.focused()),
+					))
 					.borders(Borders::ALL)
 					.border_style(self.theme.title(self.focused())),
 			);
@@ -74,6 +74,10 @@ impl Component for TagCommitComponent {
 		Ok(EventState::NotConsumed)
 	}
 
+	fn paste(&mut self, text: &str) -> Result<EventState> {
+		self.input.paste(text)
+	}
+
 	fn is_visible(&self) -> bool {
 		self.input.is_visible()
 	}
@@ -87,6 +91,10 @@ impl Component for TagCommitComponent {
 
 		Ok(())
 	}
+
+	fn on_resize(&self) {
+		self.input.on_resize();
+	}
 }
 
 impl TagCommitComponent {
@@ -245,6 +245,10 @@ impl Component for TagListComponent {
 
 		Ok(())
 	}
+
+	fn on_resize(&self) {
+		self.current_height.set(0);
+	}
 }
 
 impl TagListComponent {
@@ -41,6 +41,8 @@ pub struct TextInputComponent {
 	cursor_position: usize,
 	input_type: InputType,
 	current_area: Cell<Rect>,
+	/// rows at the bottom of the popup kept free of text
+	footer: Cell<u16>,
 	embed: bool,
 }
 
@@ -64,6 +66,7 @@ impl TextInputComponent {
 			cursor_position: 0,
 			input_type: InputType::Multiline,
 			current_area: Cell::new(Rect::default()),
+			footer: Cell::new(0),
 			embed: false,
 		}
 	}
@@ -92,6 +95,27 @@ impl TextInputComponent {
 		self.current_area.get()
 	}
 
+	/// keeps `rows` at the bottom inside the popup free for the
+	/// parent to draw into, see `get_footer_area`
+	pub fn set_footer(&self, rows: u16) {
+		self.footer.set(rows);
+	}
+
+	/// rows inside the popup below the text (last time we got
+	/// drawn), at least one row is left for the text
+	pub fn get_footer_area(&self) -> Rect {
+		let area = self.current_area.get();
+		let rows =
+			self.footer.get().min(area.height.saturating_sub(3));
+
+		Rect::new(
+			area.x + 1,
+			(area.y + area.height).saturating_sub(rows + 1),
+			area.width.saturating_sub(2),
+			rows,
+		)
+	}
+
 	/// embed into parent draw area
 	pub fn embed(&mut self) {
 		self.embed = true;
@@ -129,6 +153,93 @@ impl TextInputComponent {
 		Some(index)
 	}
 
+	/// start of the line the cursor is in
+	fn line_start(&self) -> usize {
+		self.msg[..self.cursor_position]
+			.rfind('\n')
+			.map_or(0, |pos| pos + 1)
+	}
+
+	/// end of the line the cursor is in (before its newline)
+	fn line_end(&self) -> usize {
+		self.msg[self.cursor_position..].find('\n').map_or_else(
+			|| self.msg.len(),
+			|pos| self.cursor_position + pos,
+		)
+	}
+
+	/// start of the word left of the cursor, whitespace in between
+	/// is skipped
+	fn word_left_position(&self) -> usize {
+		self.msg[..self.cursor_position]
+			.trim_end()
+			.char_indices()
+			.rev()
+			.find(|(_, c)| c.is_whitespace())
+			.map_or(0, |(pos, c)| pos + c.len_utf8())
+	}
+
+	/// end of the word right of the cursor, whitespace in between
+	/// is skipped
+	fn word_right_position(&self) -> usize {
+		let rest = &self.msg[self.cursor_position..];
+		let word = rest.trim_start();
+		let word_len =
+			word.find(char::is_whitespace).unwrap_or(word.len());
+
+		self.cursor_position + (rest.len() - word.len()) + word_len
+	}
+
+	/// removes `range` and leaves the cursor where it started
+	fn remove_range(&mut self, range: Range<usize>) {
+		self.cursor_position = range.start;
+		self.msg.replace_range(range, "");
+	}
+
+	fn delete_word_back(&mut self) {
+		self.remove_range(
+			self.word_left_position()..self.cursor_position,
+		);
+	}
+
+	/// at the end of a line the newline is removed instead
+	fn kill_to_end(&mut self) {
+		let end = self.line_end();
+		let end = if end == self.cursor_position {
+			self.next_char_position().unwrap_or(end)
+		} else {
+			end
+		};
+
+		self.remove_range(self.cursor_position..end);
+	}
+
+	/// at the start of a line the newline before is removed instead
+	fn kill_to_start(&mut self) {
+		let start = self.line_start();
+		let start = if start == self.cursor_position {
+			start.saturating_sub(1)
+		} else {
+			start
+		};
+
+		self.remove_range(start..self.cursor_position);
+	}
+
+	/// line breaks only survive in multiline inputs
+	fn insert_text(&mut self, text: &str) {
+		let text = if self.input_type == InputType::Multiline {
+			text.replace("\r\n", "\n").replace('\r', "\n")
+		} else {
+			text.chars()
+				.filter(|c| !matches!(c, '\r' | '\n'))
+				.collect()
+		};
+
+		self.msg.insert_str(self.cursor_position, &text);
+		self.cursor_position += text.len();
+	}
+
 	fn backspace(&mut self) {
 		if self.cursor_position > 0 {
 			self.decr_cursor();
@@ -142,11 +253,22 @@ impl TextInputComponent {
 		self.cursor_position = 0;
 	}
 
+	/// like `set_text` with the cursor after the text
+	pub fn replace_text(&mut self, msg: String) {
+		self.cursor_position = msg.len();
+		self.msg = msg;
+	}
+
 	/// Set the `title`.
 	pub fn set_title(&mut self, t: String) {
 		self.title = t;
 	}
 
+	/// Set the `default_msg` shown while empty.
+	pub fn set_default_msg(&mut self, msg: String) {
+		self.default_msg = msg;
+	}
+
 	fn get_draw_text(&self) -> Text {
 		let style = self.theme.text(true, false);
 
@@ -304,22 +426,52 @@ impl DrawableComponent for TextInputComponent {
 			};
 
 			f.render_widget(Clear, area);
-			f.render_widget(
-				popup_paragraph(
-					self.title.as_str(),
-					txt,
-					&self.theme,
-					true,
-					!self.embed,
-				),
-				area,
-			);
+			self.current_area.set(area);
+
+			let footer = self.get_footer_area();
+			if self.embed || footer.height == 0 {
+				f.render_widget(
+					popup_paragraph(
+						self.title.as_str(),
+						txt,
+						&self.theme,
+						true,
+						!self.embed,
+					),
+					area,
+				);
+			} else {
+				// the frame alone, the text ends above the footer
+				f.render_widget(
+					popup_paragraph(
+						self.title.as_str(),
+						"",
+						&self.theme,
+						true,
+						true,
+					),
+					area,
+				);
+				f.render_widget(
+					popup_paragraph(
+						"",
+						txt,
+						&self.theme,
+						true,
+						false,
+					),
+					Rect::new(
+						footer.x,
+						area.y + 1,
+						footer.width,
+						footer.y.saturating_sub(area.y + 1),
+					),
+				);
+			}
 
 			if self.show_char_count {
 				self.draw_char_count(f, area);
 			}
-
-			self.current_area.set(area);
 		}
 
 		Ok(())
@@ -340,6 +492,11 @@ impl Component for TextInputComponent {
 			)
 			.order(1),
 		);
+		out.push(CommandInfo::new(
+			strings::commands::new_line(&self.key_config),
+			true,
+			self.visible && self.input_type == InputType::Multiline,
+		));
 		visibility_blocking(self)
 	}
 
@@ -351,6 +508,31 @@ impl Component for TextInputComponent {
 					return Ok(EventState::Consumed);
 				}
 
+				if e == self.key_config.new_line
+					&& self.input_type == InputType::Multiline
+				{
+					self.msg.insert(self.cursor_position, '\n');
+					self.incr_cursor();
+					return Ok(EventState::Consumed);
+				}
+
+				if e == self.key_config.text_word_left {
+					self.cursor_position = self.word_left_position();
+					return Ok(EventState::Consumed);
+				} else if e == self.key_config.text_word_right {
+					self.cursor_position = self.word_right_position();
+					return Ok(EventState::Consumed);
+				} else if e == self.key_config.text_delete_word {
+					self.delete_word_back();
+					return Ok(EventState::Consumed);
+				} else if e == self.key_config.text_kill_to_end {
+					self.kill_to_end();
+					return Ok(EventState::Consumed);
+				} else if e == self.key_config.text_kill_to_start {
+					self.kill_to_start();
+					return Ok(EventState::Consumed);
+				}
+
 				let is_ctrl =
 					e.modifiers.contains(KeyModifiers::CONTROL);
 
@@ -379,11 +561,11 @@ impl Component for TextInputComponent {
 						return Ok(EventState::Consumed);
 					}
 					KeyCode::Home => {
-						self.cursor_position = 0;
+						self.cursor_position = self.line_start();
 						return Ok(EventState::Consumed);
 					}
 					KeyCode::End => {
-						self.cursor_position = self.msg.len();
+						self.cursor_position = self.line_end();
 						return Ok(EventState::Consumed);
 					}
 					_ => (),
@@ -393,6 +575,15 @@ impl Component for TextInputComponent {
 		Ok(EventState::NotConsumed)
 	}
 
+	fn paste(&mut self, text: &str) -> Result<EventState> {
+		if self.visible {
+			self.insert_text(text);
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
 	fn is_visible(&self) -> bool {
 		self.visible
 	}
@@ -406,11 +597,16 @@ impl Component for TextInputComponent {
 
 		Ok(())
 	}
+
+	fn on_resize(&self) {
+		self.current_area.set(Rect::default());
+	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crossterm::event::KeyEvent;
 	use tui::{style::Style, text::Span};
 
 	#[test]
@@ -552,6 +748,166 @@ mod tests {
 		assert_eq!(get_text(&txt.lines[1].0[0]), Some("b"));
 	}
 
+	#[test]
+	fn test_new_line() {
+		let key_config = SharedKeyConfig::default();
+		let mut comp = TextInputComponent::new(
+			SharedTheme::default(),
+			key_config.clone(),
+			"",
+			"",
+			false,
+		);
+		comp.show().unwrap();
+		comp.set_text(String::from("ab"));
+		comp.incr_cursor();
+
+		comp.event(Event::Key(key_config.new_line)).unwrap();
+		assert_eq!(comp.get_text(), "a\nb");
+		assert_eq!(comp.cursor_position, 2);
+
+		let mut comp = comp.with_input_type(InputType::Singleline);
+		comp.event(Event::Key(key_config.new_line)).unwrap();
+		assert_eq!(comp.get_text(), "a\nb");
+	}
+
+	fn input_at(text: &str, cursor: usize) -> TextInputComponent {
+		let mut comp = TextInputComponent::new(
+			SharedTheme::default(),
+			SharedKeyConfig::default(),
+			"",
+			"",
+			false,
+		);
+		comp.show().unwrap();
+		comp.set_text(String::from(text));
+		comp.cursor_position = cursor;
+		comp
+	}
+
+	fn key(comp: &mut TextInputComponent, key: KeyEvent) {
+		assert!(comp.event(Event::Key(key)).unwrap().is_consumed());
+	}
+
+	#[test]
+	fn test_word_navigation() {
+		let keys = SharedKeyConfig::default();
+		// "grüße" and "schön" have multi-byte chars
+		let mut comp = input_at("grüße  schön\nwelt", 0);
+
+		key(&mut comp, keys.text_word_right);
+		assert_eq!(&comp.get_text()[..comp.cursor_position], "grüße");
+		key(&mut comp, keys.text_word_right);
+		assert_eq!(
+			&comp.get_text()[..comp.cursor_position],
+			"grüße  schön"
+		);
+		key(&mut comp, keys.text_word_right);
+		assert_eq!(comp.cursor_position, comp.get_text().len());
+		key(&mut comp, keys.text_word_right);
+		assert_eq!(comp.cursor_position, comp.get_text().len());
+
+		key(&mut comp, keys.text_word_left);
+		assert_eq!(&comp.get_text()[comp.cursor_position..], "welt");
+		key(&mut comp, keys.text_word_left);
+		assert_eq!(
+			&comp.get_text()[comp.cursor_position..],
+			"schön\nwelt"
+		);
+		key(&mut comp, keys.text_word_left);
+		key(&mut comp, keys.text_word_left);
+		assert_eq!(comp.cursor_position, 0);
+	}
+
+	#[test]
+	fn test_delete_word() {
+		let keys = SharedKeyConfig::default();
+		let text = "fix: größe  ändern ";
+		let mut comp = input_at(text, text.len());
+
+		key(&mut comp, keys.text_delete_word);
+		assert_eq!(comp.get_text(), "fix: größe  ");
+		key(&mut comp, keys.text_delete_word);
+		assert_eq!(comp.get_text(), "fix: ");
+		assert_eq!(comp.cursor_position, comp.get_text().len());
+
+		// only what is left of the cursor
+		let mut comp = input_at("größe", "grö".len());
+		key(&mut comp, keys.text_delete_word);
+		assert_eq!(comp.get_text(), "ße");
+		assert_eq!(comp.cursor_position, 0);
+	}
+
+	#[test]
+	fn test_kill_to_end() {
+		let keys = SharedKeyConfig::default();
+		let mut comp = input_at("über\nälles", "ü".len());
+
+		key(&mut comp, keys.text_kill_to_end);
+		assert_eq!(comp.get_text(), "ü\nälles");
+		assert_eq!(comp.cursor_position, "ü".len());
+
+		// at the end of the line it joins the next one
+		key(&mut comp, keys.text_kill_to_end);
+		assert_eq!(comp.get_text(), "üälles");
+
+		key(&mut comp, keys.text_kill_to_end);
+		assert_eq!(comp.get_text(), "ü");
+		key(&mut comp, keys.text_kill_to_end);
+		assert_eq!(comp.get_text(), "ü");
+	}
+
+	#[test]
+	fn test_kill_to_start() {
+		let keys = SharedKeyConfig::default();
+		let text = "über\nälles";
+		let mut comp = input_at(text, text.len() - "es".len());
+
+		key(&mut comp, keys.text_kill_to_start);
+		assert_eq!(comp.get_text(), "über\nes");
+		assert_eq!(comp.cursor_position, "über\n".len());
+
+		// at the start of the line it joins the previous one
+		key(&mut comp, keys.text_kill_to_start);
+		assert_eq!(comp.get_text(), "überes");
+		assert_eq!(comp.cursor_position, "über".len());
+
+		key(&mut comp, keys.text_kill_to_start);
+		assert_eq!(comp.get_text(), "es");
+		key(&mut comp, keys.text_kill_to_start);
+		assert_eq!(comp.get_text(), "es");
+		assert_eq!(comp.cursor_position, 0);
+	}
+
+	#[test]
+	fn test_home_end() {
+		let mut comp = input_at("äb\ncdé\nf", "äb\nc".len());
+
+		key(&mut comp, KeyEvent::from(KeyCode::Home));
+		assert_eq!(comp.cursor_position, "äb\n".len());
+		key(&mut comp, KeyEvent::from(KeyCode::End));
+		assert_eq!(comp.cursor_position, "äb\ncdé".len());
+		key(&mut comp, KeyEvent::from(KeyCode::End));
+		assert_eq!(comp.cursor_position, "äb\ncdé".len());
+	}
+
+	#[test]
+	fn test_paste() {
+		let mut comp = input_at("ab", 1);
+
+		assert!(comp.paste("für\r\nalle\n").unwrap().is_consumed());
+		assert_eq!(comp.get_text(), "afür\nalle\nb");
+		assert_eq!(comp.cursor_position, "afür\nalle\n".len());
+
+		let mut comp =
+			input_at("ab", 1).with_input_type(InputType::Singleline);
+		comp.paste("für\nalle").unwrap();
+		assert_eq!(comp.get_text(), "afüralleb");
+
+		comp.hide();
+		assert!(!comp.paste("x").unwrap().is_consumed());
+	}
+
 	fn get_text<'a>(t: &'a Span) -> Option<&'a str> {
 		Some(&t.content)
 	}
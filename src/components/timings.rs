@@ -0,0 +1,269 @@
+use super::{
+	utils::scroll_vertical::VerticalScroll, visibility_blocking,
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState,
+};
+use crate::{
+	keys::SharedKeyConfig, strings, ui, ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{OperationTiming, Timings};
+use crossterm::event::Event;
+use std::{cell::Cell, time::Duration};
+use tui::{
+	backend::Backend,
+	layout::Rect,
+	text::Span,
+	widgets::{Block, BorderType, Borders, Clear, Paragraph},
+	Frame,
+};
+
+const NAME_WIDTH: usize = 26;
+
+/// runtime of the instrumented git operations, measured only while
+/// recording and never leaving the machine
+pub struct TimingsComponent {
+	timings: Timings,
+	list: Vec<OperationTiming>,
+	visible: bool,
+	selection: usize,
+	scroll: VerticalScroll,
+	current_height: Cell<usize>,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl TimingsComponent {
+	///
+	pub fn new(
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			timings: Timings::new(),
+			list: Vec::new(),
+			visible: false,
+			selection: 0,
+			scroll: VerticalScroll::new(),
+			current_height: Cell::new(0),
+			theme,
+			key_config,
+		}
+	}
+
+	/// takes in what was measured meanwhile, only while visible
+	pub fn update(&mut self) {
+		if self.visible {
+			self.timings.update();
+			self.list = self.timings.get();
+			self.selection =
+				self.selection.min(self.list.len().saturating_sub(1));
+		}
+	}
+
+	fn move_selection(&mut self, up: bool, lines: usize) {
+		let max = self.list.len().saturating_sub(1);
+
+		self.selection = if up {
+			self.selection.saturating_sub(lines)
+		} else {
+			self.selection.saturating_add(lines).min(max)
+		};
+	}
+
+	fn format_duration(duration: Duration) -> String {
+		if duration < Duration::from_secs(1) {
+			format!("{}ms", duration.as_millis())
+		} else {
+			format!("{:.2}s", duration.as_secs_f64())
+		}
+	}
+
+	fn header() -> String {
+		format!(
+			"{:<w$} {:>7} {:>9} {:>9} {:>9} {:>9}",
+			strings::timings::OPERATION,
+			strings::timings::COUNT,
+			strings::timings::TOTAL,
+			strings::timings::LAST,
+			strings::timings::MEDIAN,
+			strings::timings::MAX,
+			w = NAME_WIDTH,
+		)
+	}
+
+	fn list_entry(timing: &OperationTiming) -> String {
+		format!(
+			"{:<w$} {:>7} {:>9} {:>9} {:>9} {:>9}",
+			timing.name,
+			timing.count,
+			Self::format_duration(timing.total),
+			Self::format_duration(timing.last),
+			Self::format_duration(timing.median),
+			Self::format_duration(timing.max),
+			w = NAME_WIDTH,
+		)
+	}
+}
+
+impl DrawableComponent for TimingsComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		_rect: Rect,
+	) -> Result<()> {
+		if self.visible {
+			let area = ui::centered_rect(80, 60, f.size());
+
+			// header line is not part of the scrolled list
+			let height = usize::from(area.height.saturating_sub(3));
+			self.current_height.set(height);
+			self.scroll.update(
+				self.selection,
+				self.list.len(),
+				height,
+			);
+
+			let block = Block::default()
+				.title(Span::styled(
+					strings::timings_title(
+						self.timings.is_recording(),
+					),
+					self.theme.title(true),
+				))
+				.borders(Borders::ALL)
+				.border_type(BorderType::Thick)
+				.border_style(self.theme.block(true));
+
+			f.render_widget(Clear, area);
+
+			if self.list.is_empty() {
+				f.render_widget(
+					Paragraph::new(strings::timings_empty(
+						&self.key_config,
+						self.timings.is_recording(),
+					))
+					.style(self.theme.text(false, false))
+					.block(block),
+					area,
+				);
+				return Ok(());
+			}
+
+			let items = std::iter::once(Span::styled(
+				Self::header(),
+				self.theme.text(false, false),
+			))
+			.chain(
+				self.list
+					.iter()
+					.enumerate()
+					.skip(self.scroll.get_top())
+					.take(height)
+					.map(|(idx, timing)| {
+						Span::styled(
+							Self::list_entry(timing),
+							self.theme
+								.text(true, idx == self.selection),
+						)
+					}),
+			);
+
+			ui::draw_list_block(f, area, block, items);
+
+			self.scroll.draw(f, area, &self.theme);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for TimingsComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.visible || force_all {
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::timings_record(
+					&self.key_config,
+					self.timings.is_recording(),
+				),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::timings_reset(&self.key_config),
+				!self.list.is_empty(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.visible {
+			if let Event::Key(e) = ev {
+				let page =
+					self.current_height.get().saturating_sub(1);
+
+				if e == self.key_config.exit_popup
+					|| e == self.key_config.open_timings
+				{
+					self.hide();
+				} else if e == self.key_config.enter {
+					self.timings
+						.set_recording(!self.timings.is_recording());
+					self.update();
+				} else if e == self.key_config.timings_reset {
+					self.timings.reset();
+					self.update();
+				} else if e == self.key_config.move_up {
+					self.move_selection(true, 1);
+				} else if e == self.key_config.move_down {
+					self.move_selection(false, 1);
+				} else if e == self.key_config.page_up {
+					self.move_selection(true, page);
+				} else if e == self.key_config.page_down {
+					self.move_selection(false, page);
+				} else if e == self.key_config.home {
+					self.selection = 0;
+				} else if e == self.key_config.end {
+					self.move_selection(false, usize::MAX);
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.selection = 0;
+		self.visible = true;
+		self.update();
+
+		Ok(())
+	}
+}
@@ -0,0 +1,71 @@
+use crate::{keys::SharedKeyConfig, strings, ui::style::SharedTheme};
+use asyncgit::sync::{self, BranchNameError};
+use tui::{
+	backend::Backend,
+	layout::Rect,
+	widgets::{Clear, Paragraph},
+	Frame,
+};
+
+/// the name typed into the create and rename branch popups, checked
+/// as it is typed
+pub struct BranchNameCheck {
+	error: Option<BranchNameError>,
+	suggestion: Option<String>,
+}
+
+impl BranchNameCheck {
+	///
+	pub fn new(name: &str) -> Self {
+		Self {
+			error: sync::validate_branch_name(name).err(),
+			suggestion: sync::branch_name_slug(name),
+		}
+	}
+
+	///
+	pub const fn is_valid(&self) -> bool {
+		self.error.is_none()
+	}
+
+	/// valid variant of an invalid name
+	pub fn suggestion(&self) -> Option<&str> {
+		self.suggestion.as_deref()
+	}
+
+	/// the rule the name breaks and the suggestion, in the lines
+	/// below `input` (nothing while the name is empty)
+	pub fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		input: Rect,
+		theme: &SharedTheme,
+		key_config: &SharedKeyConfig,
+	) {
+		let error = match self.error {
+			Some(BranchNameError::Empty) | None => return,
+			Some(error) => error,
+		};
+
+		let mut lines = vec![(
+			strings::branch_name_error(error),
+			theme.text_danger(),
+		)];
+		if let Some(suggestion) = &self.suggestion {
+			lines.push((
+				strings::branch_name_suggestion(
+					suggestion, key_config,
+				),
+				theme.text_warning(),
+			));
+		}
+
+		for (y, (line, style)) in
+			(input.y + input.height..f.size().height).zip(lines)
+		{
+			let rect = Rect::new(input.x, y, input.width, 1);
+			f.render_widget(Clear, rect);
+			f.render_widget(Paragraph::new(line).style(style), rect);
+		}
+	}
+}
@@ -0,0 +1,288 @@
+use chrono::{
+	DateTime, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime,
+	TimeZone,
+};
+
+/// which end of a range a date is parsed for,
+/// dates without a time cover the whole day
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateBound {
+	Since,
+	Until,
+}
+
+/// one end of a `DateRange` as typed by the user
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateLimit {
+	pub input: String,
+	/// secs since epoch
+	pub time: i64,
+}
+
+/// limits the log to commits in between `since` and `until`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DateRange {
+	pub since: Option<DateLimit>,
+	pub until: Option<DateLimit>,
+}
+
+impl DateRange {
+	///
+	pub const fn is_empty(&self) -> bool {
+		self.since.is_none() && self.until.is_none()
+	}
+
+	/// secs since epoch of both ends
+	pub fn times(&self) -> (Option<i64>, Option<i64>) {
+		(
+			self.since.as_ref().map(|limit| limit.time),
+			self.until.as_ref().map(|limit| limit.time),
+		)
+	}
+}
+
+/// parses an absolute date (`2021-06-30`) or a relative one
+/// (`today`, `yesterday`, `3 weeks ago`) in the local timezone,
+/// a month counts as 30 and a year as 365 days
+pub fn parse_date(
+	input: &str,
+	bound: DateBound,
+	now: DateTime<Local>,
+) -> Option<i64> {
+	let input = input.trim().to_lowercase();
+
+	let day = match input.as_str() {
+		"now" => return Some(now.timestamp()),
+		"today" => Some(now.date().naive_local()),
+		"yesterday" => {
+			Some((now - Duration::days(1)).date().naive_local())
+		}
+		_ => NaiveDate::parse_from_str(&input, "%Y-%m-%d").ok(),
+	};
+
+	if let Some(day) = day {
+		return day_bound(day, bound);
+	}
+
+	parse_ago(&input).map(|ago| (now - ago).timestamp())
+}
+
+/// parses an ISO 8601 date and time (`2021-06-30T12:00:00+02:00`,
+/// `2021-06-30 12:00`) or anything `parse_date` does, times without
+/// an offset are local and dates without a time start the day
+pub fn parse_date_time(
+	input: &str,
+	now: DateTime<Local>,
+) -> Option<DateTime<FixedOffset>> {
+	let input = input.trim();
+
+	if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+		return Some(time);
+	}
+
+	let local = [
+		"%Y-%m-%dT%H:%M:%S",
+		"%Y-%m-%d %H:%M:%S",
+		"%Y-%m-%dT%H:%M",
+		"%Y-%m-%d %H:%M",
+	]
+	.iter()
+	.find_map(|format| {
+		NaiveDateTime::parse_from_str(input, format).ok()
+	})
+	.and_then(|time| Local.from_local_datetime(&time).earliest())
+	.or_else(|| {
+		parse_date(input, DateBound::Since, now)
+			.map(|time| Local.timestamp(time, 0))
+	})?;
+
+	Some(local.with_timezone(local.offset()))
+}
+
+/// first or last second of `day`
+fn day_bound(day: NaiveDate, bound: DateBound) -> Option<i64> {
+	let time = match bound {
+		DateBound::Since => day.and_hms(0, 0, 0),
+		DateBound::Until => day.and_hms(23, 59, 59),
+	};
+
+	Local
+		.from_local_datetime(&time)
+		.earliest()
+		.map(|time| time.timestamp())
+}
+
+/// `<count> <unit>[s] ago`
+fn parse_ago(input: &str) -> Option<Duration> {
+	let mut words = input.split_whitespace();
+
+	let count = words.next()?.parse::<i64>().ok()?;
+	let unit = words.next()?;
+
+	if words.next()? != "ago" || words.next().is_some() {
+		return None;
+	}
+
+	let unit = unit.strip_suffix('s').unwrap_or(unit);
+	let duration = match unit {
+		"minute" => Duration::minutes(count),
+		"hour" => Duration::hours(count),
+		"day" => Duration::days(count),
+		"week" => Duration::weeks(count),
+		"month" => Duration::days(count * 30),
+		"year" => Duration::days(count * 365),
+		_ => return None,
+	};
+
+	Some(duration)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	fn now() -> DateTime<Local> {
+		Local.ymd(2021, 6, 15).and_hms(14, 30, 0)
+	}
+
+	fn local(
+		year: i32,
+		month: u32,
+		day: u32,
+		hour: u32,
+		min: u32,
+		sec: u32,
+	) -> i64 {
+		Local
+			.ymd(year, month, day)
+			.and_hms(hour, min, sec)
+			.timestamp()
+	}
+
+	#[test]
+	fn test_absolute() {
+		assert_eq!(
+			parse_date("2021-06-01", DateBound::Since, now()),
+			Some(local(2021, 6, 1, 0, 0, 0))
+		);
+		assert_eq!(
+			parse_date(" 2021-06-01 ", DateBound::Until, now()),
+			Some(local(2021, 6, 1, 23, 59, 59))
+		);
+		assert_eq!(
+			parse_date("2021-13-01", DateBound::Since, now()),
+			None
+		);
+	}
+
+	#[test]
+	fn test_days() {
+		assert_eq!(
+			parse_date("today", DateBound::Since, now()),
+			Some(local(2021, 6, 15, 0, 0, 0))
+		);
+		assert_eq!(
+			parse_date("Yesterday", DateBound::Until, now()),
+			Some(local(2021, 6, 14, 23, 59, 59))
+		);
+		assert_eq!(
+			parse_date("now", DateBound::Until, now()),
+			Some(now().timestamp())
+		);
+	}
+
+	#[test]
+	fn test_ago() {
+		assert_eq!(
+			parse_date("2 weeks ago", DateBound::Since, now()),
+			Some(local(2021, 6, 1, 14, 30, 0))
+		);
+		assert_eq!(
+			parse_date("1 day ago", DateBound::Until, now()),
+			Some(local(2021, 6, 14, 14, 30, 0))
+		);
+		assert_eq!(
+			parse_date("3  hours   ago", DateBound::Since, now()),
+			Some(local(2021, 6, 15, 11, 30, 0))
+		);
+		assert_eq!(
+			parse_date("1 month ago", DateBound::Since, now()),
+			Some(local(2021, 5, 16, 14, 30, 0))
+		);
+	}
+
+	#[test]
+	fn test_date_time() {
+		let time = |input| {
+			parse_date_time(input, now()).map(|time| time.timestamp())
+		};
+
+		assert_eq!(
+			time("2021-06-01T10:20:30+02:00"),
+			Some(
+				FixedOffset::east(2 * 3600)
+					.ymd(2021, 6, 1)
+					.and_hms(10, 20, 30)
+					.timestamp()
+			)
+		);
+		assert_eq!(
+			time("2021-06-01T10:20:30Z"),
+			Some(
+				chrono::Utc
+					.ymd(2021, 6, 1)
+					.and_hms(10, 20, 30)
+					.timestamp()
+			)
+		);
+		assert_eq!(
+			time(" 2021-06-01 10:20 "),
+			Some(local(2021, 6, 1, 10, 20, 0))
+		);
+		assert_eq!(
+			time("2021-06-01T10:20:30"),
+			Some(local(2021, 6, 1, 10, 20, 30))
+		);
+		assert_eq!(
+			time("2021-06-01"),
+			Some(local(2021, 6, 1, 0, 0, 0))
+		);
+		assert_eq!(
+			time("1 day ago"),
+			Some(local(2021, 6, 14, 14, 30, 0))
+		);
+		assert_eq!(time("2021-06-01 25:00"), None);
+		assert_eq!(time("noon"), None);
+	}
+
+	#[test]
+	fn test_date_time_offset() {
+		let time =
+			parse_date_time("2021-06-01T10:20:30-05:30", now())
+				.unwrap();
+
+		assert_eq!(
+			time.offset().local_minus_utc(),
+			-(5 * 3600 + 1800)
+		);
+	}
+
+	#[test]
+	fn test_invalid() {
+		for input in &[
+			"",
+			"ago",
+			"2 weeks",
+			"two weeks ago",
+			"2 fortnights ago",
+			"2 weeks ago now",
+			"2021-06",
+		] {
+			assert_eq!(
+				parse_date(input, DateBound::Since, now()),
+				None
+			);
+		}
+	}
+}
@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+
+/// how diff lines are drawn, the patch data staging works on is
+/// never touched by it
+#[derive(
+	Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize,
+)]
+#[serde(default)]
+pub struct DiffRender {
+	/// columns up to the next tab stop: 2, 4 or 8
+	pub tab_width: usize,
+	/// background on trailing whitespace of added lines
+	pub trailing_whitespace: bool,
+	/// `→` for tabs and `·` for spaces in added and removed lines
+	pub show_whitespace: bool,
+	/// shortest block of a commit shown as moved between files,
+	/// 0 turns the search off
+	pub moved_min_lines: usize,
+}
+
+impl Default for DiffRender {
+	fn default() -> Self {
+		Self {
+			tab_width: 2,
+			trailing_whitespace: true,
+			show_whitespace: false,
+			moved_min_lines: 5,
+		}
+	}
+}
+
+const TAB_WIDTHS: [usize; 3] = [2, 4, 8];
+const MOVED_MIN_LINES: [usize; 5] = [0, 3, 5, 10, 20];
+
+impl DiffRender {
+	/// cycle to the next tab width
+	pub fn next_tab_width(self) -> usize {
+		TAB_WIDTHS
+			.iter()
+			.copied()
+			.find(|width| *width > self.tab_width)
+			.unwrap_or(TAB_WIDTHS[0])
+	}
+
+	/// cycle to the previous tab width
+	pub fn prev_tab_width(self) -> usize {
+		TAB_WIDTHS
+			.iter()
+			.rev()
+			.copied()
+			.find(|width| *width < self.tab_width)
+			.unwrap_or(TAB_WIDTHS[TAB_WIDTHS.len() - 1])
+	}
+
+	/// cycle to the next moved code threshold
+	pub fn next_moved_min_lines(self) -> usize {
+		MOVED_MIN_LINES
+			.iter()
+			.copied()
+			.find(|lines| *lines > self.moved_min_lines)
+			.unwrap_or(MOVED_MIN_LINES[0])
+	}
+
+	/// cycle to the previous moved code threshold
+	pub fn prev_moved_min_lines(self) -> usize {
+		MOVED_MIN_LINES
+			.iter()
+			.rev()
+			.copied()
+			.find(|lines| *lines < self.moved_min_lines)
+			.unwrap_or(MOVED_MIN_LINES[MOVED_MIN_LINES.len() - 1])
+	}
+}
+
+/// one column of a rendered line
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Glyph {
+	Char(char),
+	Space,
+	/// first column of an expanded tab
+	Tab,
+	/// remaining columns of an expanded tab
+	TabFill,
+}
+
+impl Glyph {
+	/// as drawn, whitespace either blank or marked
+	pub const fn to_char(self, show_whitespace: bool) -> char {
+		match (self, show_whitespace) {
+			(Self::Char(c), _) => c,
+			(Self::Space, true) => '·',
+			(Self::Tab, true) => '→',
+			_ => ' ',
+		}
+	}
+
+	pub const fn is_whitespace(self) -> bool {
+		!matches!(self, Self::Char(_))
+	}
+}
+
+/// the columns of `content` with tabs expanded up to the next tab
+/// stop, each along with the byte of `content` it stems from
+pub fn render_line(
+	content: &str,
+	tab_width: usize,
+) -> Vec<(usize, Glyph)> {
+	let tab_width = tab_width.max(1);
+	let mut res = Vec::with_capacity(content.len());
+
+	for (idx, c) in content.char_indices() {
+		match c {
+			'\t' => {
+				let fill = tab_width - res.len() % tab_width;
+				res.push((idx, Glyph::Tab));
+				res.extend(
+					std::iter::repeat((idx, Glyph::TabFill))
+						.take(fill - 1),
+				);
+			}
+			' ' => res.push((idx, Glyph::Space)),
+			c => res.push((idx, Glyph::Char(c))),
+		}
+	}
+
+	res
+}
+
+/// column the trailing whitespace of `glyphs` starts at,
+/// `glyphs.len()` if there is none
+pub fn trailing_whitespace(glyphs: &[(usize, Glyph)]) -> usize {
+	glyphs
+		.iter()
+		.rposition(|(_, glyph)| !glyph.is_whitespace())
+		.map_or(0, |last| last + 1)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	fn drawn(
+		content: &str,
+		tab_width: usize,
+		marked: bool,
+	) -> String {
+		render_line(content, tab_width)
+			.into_iter()
+			.map(|(_, glyph)| glyph.to_char(marked))
+			.collect()
+	}
+
+	#[test]
+	fn test_tab_stops() {
+		assert_eq!(drawn("\tx", 4, false), "    x");
+		assert_eq!(drawn("ab\tx", 4, false), "ab  x");
+		assert_eq!(drawn("abcd\tx", 4, false), "abcd    x");
+		assert_eq!(drawn("a\tb\tc", 2, false), "a b c");
+		assert_eq!(drawn("\t\tx", 8, false), "                x");
+	}
+
+	#[test]
+	fn test_show_whitespace() {
+		assert_eq!(drawn("a b\tc", 4, true), "a·b→c");
+		assert_eq!(drawn("\t x ", 4, true), "→   ·x·");
+	}
+
+	#[test]
+	fn test_source_bytes() {
+		let glyphs = render_line("ä\tb", 4);
+
+		assert_eq!(
+			glyphs,
+			vec![
+				(0, Glyph::Char('ä')),
+				(2, Glyph::Tab),
+				(2, Glyph::TabFill),
+				(2, Glyph::TabFill),
+				(3, Glyph::Char('b')),
+			]
+		);
+	}
+
+	#[test]
+	fn test_trailing_whitespace() {
+		let glyphs = render_line("a b \t", 4);
+		assert_eq!(trailing_whitespace(&glyphs), 3);
+
+		assert_eq!(trailing_whitespace(&render_line("ab", 4)), 2);
+		assert_eq!(trailing_whitespace(&render_line("  ", 4)), 0);
+		assert_eq!(trailing_whitespace(&[]), 0);
+	}
+
+	#[test]
+	fn test_tab_width_cycle() {
+		let render = |tab_width| DiffRender {
+			tab_width,
+			..DiffRender::default()
+		};
+
+		assert_eq!(render(2).next_tab_width(), 4);
+		assert_eq!(render(8).next_tab_width(), 2);
+		assert_eq!(render(2).prev_tab_width(), 8);
+		assert_eq!(render(4).prev_tab_width(), 2);
+	}
+
+	#[test]
+	fn test_moved_min_lines_cycle() {
+		let render = |moved_min_lines| DiffRender {
+			moved_min_lines,
+			..DiffRender::default()
+		};
+
+		assert_eq!(render(5).next_moved_min_lines(), 10);
+		assert_eq!(render(20).next_moved_min_lines(), 0);
+		assert_eq!(render(0).prev_moved_min_lines(), 20);
+		assert_eq!(render(7).prev_moved_min_lines(), 5);
+	}
+}
@@ -268,7 +268,9 @@ mod tests {
 			.iter()
 			.map(|a| StatusItem {
 				path: String::from(*a),
+				old_path: None,
 				status: StatusItemType::Modified,
+				binary_size: None,
 			})
 			.collect::<Vec<_>>()
 	}
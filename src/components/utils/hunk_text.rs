@@ -0,0 +1,124 @@
+use asyncgit::{
+	sync::diff::{DiffLine, Hunk},
+	DiffLineType,
+};
+
+/// what of a hunk to copy
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HunkCopy {
+	/// `@@` header and all lines prefixed like in a patch
+	Unified,
+	/// pre-image: context and deleted lines
+	Old,
+	/// post-image: context and added lines
+	New,
+}
+
+/// text of `hunk` to put on the clipboard, every line ends in `\n`
+pub fn hunk_text(hunk: &Hunk, copy: HunkCopy) -> String {
+	let mut res = String::new();
+
+	for line in &hunk.lines {
+		if let Some(prefix) = line_prefix(line, copy) {
+			res.push_str(prefix);
+			res.push_str(&line.content);
+			res.push('\n');
+		}
+	}
+
+	res
+}
+
+/// `None` if `line` is not part of `copy`
+fn line_prefix(
+	line: &DiffLine,
+	copy: HunkCopy,
+) -> Option<&'static str> {
+	// `\ No newline at end of file` has no line number on either side
+	let is_marker = line.line_type != DiffLineType::Header
+		&& line.position.old_lineno.is_none()
+		&& line.position.new_lineno.is_none();
+
+	if is_marker {
+		return if copy == HunkCopy::Unified {
+			Some("")
+		} else {
+			None
+		};
+	}
+
+	match (copy, line.line_type) {
+		(HunkCopy::Unified, DiffLineType::None) => Some(" "),
+		(HunkCopy::Unified, DiffLineType::Add) => Some("+"),
+		(HunkCopy::Unified, DiffLineType::Delete) => Some("-"),
+		(HunkCopy::Unified, DiffLineType::Header)
+		| (HunkCopy::Old, DiffLineType::None)
+		| (HunkCopy::Old, DiffLineType::Delete)
+		| (HunkCopy::New, DiffLineType::None)
+		| (HunkCopy::New, DiffLineType::Add) => Some(""),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use asyncgit::sync::diff::DiffLinePosition;
+	use pretty_assertions::assert_eq;
+
+	fn line(
+		content: &str,
+		line_type: DiffLineType,
+		old_lineno: Option<u32>,
+		new_lineno: Option<u32>,
+	) -> DiffLine {
+		DiffLine {
+			content: content.into(),
+			line_type,
+			position: DiffLinePosition {
+				old_lineno,
+				new_lineno,
+			},
+		}
+	}
+
+	fn hunk() -> Hunk {
+		Hunk {
+			header_hash: 0,
+			split_from: None,
+			lines: vec![
+				line(
+					"@@ -1,3 +1,3 @@",
+					DiffLineType::Header,
+					None,
+					None,
+				),
+				line("a", DiffLineType::None, Some(1), Some(1)),
+				line("b", DiffLineType::Delete, Some(2), None),
+				line("c", DiffLineType::Delete, Some(3), None),
+				line(
+					"\\ No newline at end of file",
+					DiffLineType::Delete,
+					None,
+					None,
+				),
+				line("B", DiffLineType::Add, None, Some(2)),
+				line("c", DiffLineType::Add, None, Some(3)),
+			],
+		}
+	}
+
+	#[test]
+	fn test_unified() {
+		assert_eq!(
+			hunk_text(&hunk(), HunkCopy::Unified),
+			"@@ -1,3 +1,3 @@\n a\n-b\n-c\n\\ No newline at end of file\n+B\n+c\n"
+		);
+	}
+
+	#[test]
+	fn test_sides() {
+		assert_eq!(hunk_text(&hunk(), HunkCopy::Old), "a\nb\nc\n");
+		assert_eq!(hunk_text(&hunk(), HunkCopy::New), "a\nB\nc\n");
+	}
+}
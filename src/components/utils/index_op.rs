@@ -0,0 +1,68 @@
+use crate::{
+	keys::SharedKeyConfig,
+	messages::Severity,
+	queue::{Action, IndexOp, InternalEvent, Queue},
+	strings,
+};
+use asyncgit::{sync, Error, CWD};
+
+/// runs an index change, if another git process keeps the index
+/// locked the user is offered to retry it
+pub fn run_index_op(queue: &Queue, msg: &str, op: IndexOp) -> bool {
+	match with_index_snapshot(queue, || op()) {
+		Ok(()) => true,
+		Err(Error::IndexLocked) => {
+			queue.push(InternalEvent::ConfirmAction(
+				Action::RetryIndexOp(op),
+			));
+			false
+		}
+		Err(e) => {
+			log::error!("{} {}", msg, e);
+			queue.push(InternalEvent::ShowErrorMsg(format!(
+				"{}\n{}",
+				msg, e
+			)));
+			false
+		}
+	}
+}
+
+/// runs an index change and hands the index as it was before to the
+/// undo history, unless nothing changed or the index could not be
+/// written as a tree (conflicts)
+pub fn with_index_snapshot<T>(
+	queue: &Queue,
+	op: impl FnOnce() -> asyncgit::Result<T>,
+) -> asyncgit::Result<T> {
+	let before = sync::snapshot_index(CWD).ok();
+
+	let res = op()?;
+
+	if let Some(before) = before {
+		if sync::snapshot_index(CWD).ok() != Some(before) {
+			queue.push(InternalEvent::IndexChanged(before));
+		}
+	}
+
+	Ok(res)
+}
+
+/// tells about files in `path` that staging or discarding left as
+/// they are because index flags hide their changes
+pub fn notify_flagged(
+	queue: &Queue,
+	key_config: &SharedKeyConfig,
+	path: &str,
+) {
+	let count = sync::index_flagged_files(CWD).map_or(0, |files| {
+		files.iter().filter(|file| file.is_in(path)).count()
+	});
+
+	if count > 0 {
+		queue.push(InternalEvent::ShowToast(
+			Severity::Info,
+			strings::toast_flagged_left_out(count, key_config),
+		));
+	}
+}
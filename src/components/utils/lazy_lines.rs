@@ -0,0 +1,126 @@
+use crate::string_utils::tabs_to_spaces;
+use std::io::{BufRead, Result};
+
+/// lines read per chunk
+pub const CHUNK_LINES: usize = 1000;
+
+/// lines of a (possibly huge) text read chunk by chunk on demand,
+/// so only the part scrolled to so far is held in memory
+pub struct LazyLines {
+	reader: Box<dyn BufRead>,
+	lines: Vec<String>,
+	eof: bool,
+}
+
+impl LazyLines {
+	///
+	pub fn new(reader: Box<dyn BufRead>) -> Self {
+		Self {
+			reader,
+			lines: Vec::new(),
+			eof: false,
+		}
+	}
+
+	/// reads chunks until `count` lines are loaded or the text ends
+	pub fn load_to(&mut self, count: usize) -> Result<()> {
+		while !self.eof && self.lines.len() < count {
+			self.load_chunk()?;
+		}
+
+		Ok(())
+	}
+
+	///
+	pub fn load_all(&mut self) -> Result<()> {
+		self.load_to(usize::MAX)
+	}
+
+	/// reads the next chunk, `false` once the text ended
+	pub fn load_chunk(&mut self) -> Result<bool> {
+		let mut buf = Vec::new();
+
+		for _ in 0..CHUNK_LINES {
+			buf.clear();
+
+			if self.reader.read_until(b'\n', &mut buf)? == 0 {
+				self.eof = true;
+				break;
+			}
+
+			let line = String::from_utf8_lossy(&buf);
+			let line = line.trim_end_matches(&['\n', '\r'][..]);
+			self.lines.push(tabs_to_spaces(line.to_string()));
+		}
+
+		// a text ending right with the chunk is complete as well
+		if !self.eof && self.reader.fill_buf()?.is_empty() {
+			self.eof = true;
+		}
+
+		Ok(!self.eof)
+	}
+
+	/// lines loaded so far
+	pub fn len(&self) -> usize {
+		self.lines.len()
+	}
+
+	///
+	pub fn is_empty(&self) -> bool {
+		self.lines.is_empty()
+	}
+
+	/// whether all lines are loaded
+	pub const fn is_complete(&self) -> bool {
+		self.eof
+	}
+
+	///
+	pub fn get(&self, idx: usize) -> Option<&str> {
+		self.lines.get(idx).map(String::as_str)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use std::io::Cursor;
+
+	fn lazy(text: String) -> LazyLines {
+		LazyLines::new(Box::new(Cursor::new(text.into_bytes())))
+	}
+
+	#[test]
+	fn test_chunks() {
+		let text = (0..CHUNK_LINES + 10)
+			.map(|idx| idx.to_string())
+			.collect::<Vec<_>>()
+			.join("\n");
+		let mut lines = lazy(text);
+
+		lines.load_to(1).unwrap();
+		assert_eq!(lines.len(), CHUNK_LINES);
+		assert!(!lines.is_complete());
+
+		lines.load_to(CHUNK_LINES + 1).unwrap();
+		assert_eq!(lines.len(), CHUNK_LINES + 10);
+		assert!(lines.is_complete());
+		assert_eq!(lines.get(CHUNK_LINES + 9), Some("1009"));
+		assert_eq!(lines.get(CHUNK_LINES + 10), None);
+	}
+
+	#[test]
+	fn test_line_endings() {
+		let mut lines = lazy(String::from("a\r\n\tb\n\nc"));
+		lines.load_all().unwrap();
+
+		assert_eq!(
+			(0..lines.len())
+				.filter_map(|idx| lines.get(idx))
+				.collect::<Vec<_>>(),
+			vec!["a", "  b", "", "c"]
+		);
+	}
+}
@@ -1,6 +1,13 @@
-use asyncgit::sync::{CommitId, CommitInfo};
-use chrono::{DateTime, Duration, Local, NaiveDateTime, Utc};
-use std::slice::Iter;
+use asyncgit::{
+	sync::{CommitId, CommitInfo},
+	CWD,
+};
+use chrono::{
+	DateTime, Datelike, Duration, FixedOffset, Local, NaiveDateTime,
+	TimeZone,
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, slice::Iter};
 
 use crate::components::utils::emojifi_string;
 
@@ -8,9 +15,231 @@ static SLICE_OFFSET_RELOAD_THRESHOLD: usize = 100;
 
 type BoxStr = Box<str>;
 
+/// how commit dates are rendered in the log
+#[derive(
+	Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize,
+)]
+pub enum LogDateFormat {
+	/// `3 days ago`
+	Relative,
+	/// `2024-05-01`
+	Short,
+	/// `2024-05-01 14:32 +0200`
+	Full,
+}
+
+impl Default for LogDateFormat {
+	fn default() -> Self {
+		Self::Relative
+	}
+}
+
+impl LogDateFormat {
+	/// cycle to the next format
+	pub const fn next(self) -> Self {
+		match self {
+			Self::Relative => Self::Short,
+			Self::Short => Self::Full,
+			Self::Full => Self::Relative,
+		}
+	}
+
+	/// cycle to the previous format
+	pub const fn prev(self) -> Self {
+		match self {
+			Self::Relative => Self::Full,
+			Self::Short => Self::Relative,
+			Self::Full => Self::Short,
+		}
+	}
+
+	/// column width needed to align entries of this format
+	pub const fn width(self) -> usize {
+		match self {
+			// longest is `59 minutes ago`
+			Self::Relative => 14,
+			Self::Short => 10,
+			Self::Full => 22,
+		}
+	}
+
+	///
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::Relative => "Relative",
+			Self::Short => "Short",
+			Self::Full => "Full",
+		}
+	}
+}
+
+/// which of the two commit timestamps to show
+#[derive(
+	Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize,
+)]
+pub enum LogDateSource {
+	Committer,
+	Author,
+}
+
+impl Default for LogDateSource {
+	fn default() -> Self {
+		Self::Committer
+	}
+}
+
+impl LogDateSource {
+	///
+	pub const fn toggle(self) -> Self {
+		match self {
+			Self::Committer => Self::Author,
+			Self::Author => Self::Committer,
+		}
+	}
+
+	///
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::Committer => "Committer",
+			Self::Author => "Author",
+		}
+	}
+}
+
+/// header rows between commits of different days, weeks or months
+#[derive(
+	Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize,
+)]
+pub enum LogGrouping {
+	Off,
+	Day,
+	Week,
+	Month,
+}
+
+impl Default for LogGrouping {
+	fn default() -> Self {
+		Self::Off
+	}
+}
+
+impl LogGrouping {
+	/// cycle to the next grouping
+	pub const fn next(self) -> Self {
+		match self {
+			Self::Off => Self::Day,
+			Self::Day => Self::Week,
+			Self::Week => Self::Month,
+			Self::Month => Self::Off,
+		}
+	}
+
+	/// cycle to the previous grouping
+	pub const fn prev(self) -> Self {
+		match self {
+			Self::Off => Self::Month,
+			Self::Day => Self::Off,
+			Self::Week => Self::Day,
+			Self::Month => Self::Week,
+		}
+	}
+
+	///
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::Off => "Off",
+			Self::Day => "Day",
+			Self::Week => "Week",
+			Self::Month => "Month",
+		}
+	}
+
+	/// equal for times in the same group, `None` while off
+	pub fn group<Tz: TimeZone>(
+		self,
+		time: &DateTime<Tz>,
+	) -> Option<(i32, u32)> {
+		match self {
+			Self::Off => None,
+			Self::Day => Some((time.year(), time.ordinal())),
+			Self::Week => {
+				let week = time.iso_week();
+				Some((week.year(), week.week()))
+			}
+			Self::Month => Some((time.year(), time.month())),
+		}
+	}
+
+	/// title of the group `time` is in, e.g. `Monday, 2024-05-06`
+	pub fn header<Tz: TimeZone>(self, time: &DateTime<Tz>) -> String
+	where
+		Tz::Offset: Display,
+	{
+		match self {
+			Self::Off => String::new(),
+			Self::Day => time.format("%A, %Y-%m-%d").to_string(),
+			Self::Week => {
+				let week = time.iso_week();
+				format!("Week {}, {}", week.week(), week.year())
+			}
+			Self::Month => time.format("%B %Y").to_string(),
+		}
+	}
+}
+
+fn to_datetime(
+	secs: i64,
+	offset_minutes: i32,
+) -> DateTime<FixedOffset> {
+	let offset = FixedOffset::east_opt(offset_minutes * 60)
+		.unwrap_or_else(|| FixedOffset::east(0));
+	DateTime::<FixedOffset>::from_utc(
+		NaiveDateTime::from_timestamp(secs, 0),
+		offset,
+	)
+}
+
+fn plural(count: i64, unit: &str) -> String {
+	if count == 1 {
+		format!("1 {} ago", unit)
+	} else {
+		format!("{} {}s ago", count, unit)
+	}
+}
+
+/// formats the time passed since a commit, e.g. `3 days ago`
+fn relative_time(delta: Duration) -> String {
+	let days = delta.num_days();
+
+	if delta < Duration::minutes(1) {
+		String::from("just now")
+	} else if delta < Duration::hours(1) {
+		plural(delta.num_minutes(), "minute")
+	} else if delta < Duration::days(1) {
+		plural(delta.num_hours(), "hour")
+	} else if days < 14 {
+		plural(days, "day")
+	} else if days < 60 {
+		plural(delta.num_weeks(), "week")
+	} else if days < 365 {
+		plural((days / 30).min(11), "month")
+	} else {
+		plural(days / 365, "year")
+	}
+}
+
+/// time passed since `secs` (unix time), e.g. `3 days ago`
+pub fn relative_time_since(secs: i64) -> String {
+	relative_time(
+		Local::now().signed_duration_since(to_datetime(secs, 0)),
+	)
+}
+
 pub struct LogEntry {
 	//TODO: cache string representation
-	pub time: DateTime<Local>,
+	/// committer time
+	pub time: DateTime<FixedOffset>,
+	pub author_time: DateTime<FixedOffset>,
 	//TODO: use tinyvec here
 	pub author: BoxStr,
 	pub msg: BoxStr,
@@ -21,11 +250,9 @@ pub struct LogEntry {
 
 impl From<CommitInfo> for LogEntry {
 	fn from(c: CommitInfo) -> Self {
-		let time =
-			DateTime::<Local>::from(DateTime::<Utc>::from_utc(
-				NaiveDateTime::from_timestamp(c.time, 0),
-				Utc,
-			));
+		let time = to_datetime(c.time, c.time_offset);
+		let author_time =
+			to_datetime(c.author_time, c.author_time_offset);
 
 		// Replace markdown emojis with Unicode equivalent
 		let author = c.author;
@@ -36,26 +263,41 @@ impl From<CommitInfo> for LogEntry {
 			author: author.into(),
 			msg: msg.into(),
 			time,
-			hash_short: c.id.get_short_string().into(),
+			author_time,
+			hash_short: c.id.abbreviated(CWD).into(),
 			id: c.id,
 		}
 	}
 }
 
 impl LogEntry {
-	pub fn time_to_string(&self, now: DateTime<Local>) -> String {
-		let delta = now - self.time;
-		if delta < Duration::minutes(30) {
-			let delta_str = if delta < Duration::minutes(1) {
-				"<1m ago".to_string()
-			} else {
-				format!("{:0>2}m ago", delta.num_minutes())
-			};
-			format!("{: <10}", delta_str)
-		} else if self.time.date() == now.date() {
-			self.time.format("%T  ").to_string()
-		} else {
-			self.time.format("%Y-%m-%d").to_string()
+	pub fn time_to_string<Tz: chrono::TimeZone>(
+		&self,
+		now: &DateTime<Tz>,
+		format: LogDateFormat,
+		source: LogDateSource,
+	) -> String {
+		let time = match source {
+			LogDateSource::Committer => &self.time,
+			LogDateSource::Author => &self.author_time,
+		};
+
+		format_time(time, now, format)
+	}
+}
+
+fn format_time<Tz: chrono::TimeZone>(
+	time: &DateTime<FixedOffset>,
+	now: &DateTime<Tz>,
+	format: LogDateFormat,
+) -> String {
+	match format {
+		LogDateFormat::Relative => {
+			relative_time(now.clone().signed_duration_since(*time))
+		}
+		LogDateFormat::Short => time.format("%Y-%m-%d").to_string(),
+		LogDateFormat::Full => {
+			time.format("%Y-%m-%d %H:%M %z").to_string()
 		}
 	}
 }
@@ -65,6 +307,8 @@ impl LogEntry {
 pub struct ItemBatch {
 	index_offset: usize,
 	items: Vec<LogEntry>,
+	/// committer time of the entry right before the batch
+	preceding: Option<DateTime<FixedOffset>>,
 }
 
 impl ItemBatch {
@@ -77,6 +321,18 @@ impl ItemBatch {
 		self.index_offset
 	}
 
+	/// `None` at the start of the list or if not loaded
+	pub const fn preceding(&self) -> Option<&DateTime<FixedOffset>> {
+		self.preceding.as_ref()
+	}
+
+	/// the entry right before the batch tells whether the first
+	/// entry of the batch starts a new group
+	pub fn set_preceding(&mut self, commit: Option<&CommitInfo>) {
+		self.preceding =
+			commit.map(|c| to_datetime(c.time, c.time_offset));
+	}
+
 	/// shortcut to get an `Iter` of our internal items
 	pub fn iter(&self) -> Iter<'_, LogEntry> {
 		self.items.iter()
@@ -96,6 +352,7 @@ impl ItemBatch {
 		self.items.clear();
 		self.items.extend(commits.into_iter().map(LogEntry::from));
 		self.index_offset = start_index;
+		self.preceding = None;
 	}
 
 	/// returns `true` if we should fetch updated list of items
@@ -139,6 +396,112 @@ mod tests {
 		assert_eq!(&test_conversion(":crab: rules!"), "🦀 rules!");
 	}
 
+	#[test]
+	fn test_relative_time_boundaries() {
+		assert_eq!(relative_time(Duration::seconds(59)), "just now");
+		assert_eq!(
+			relative_time(Duration::seconds(60)),
+			"1 minute ago"
+		);
+		assert_eq!(
+			relative_time(Duration::minutes(59)),
+			"59 minutes ago"
+		);
+		assert_eq!(
+			relative_time(
+				Duration::minutes(59) + Duration::seconds(59)
+			),
+			"59 minutes ago"
+		);
+		assert_eq!(
+			relative_time(Duration::minutes(60)),
+			"1 hour ago"
+		);
+		assert_eq!(
+			relative_time(Duration::hours(23)),
+			"23 hours ago"
+		);
+		assert_eq!(relative_time(Duration::hours(24)), "1 day ago");
+		assert_eq!(relative_time(Duration::days(13)), "13 days ago");
+		assert_eq!(relative_time(Duration::days(14)), "2 weeks ago");
+		assert_eq!(relative_time(Duration::days(60)), "2 months ago");
+		assert_eq!(
+			relative_time(Duration::days(364)),
+			"11 months ago"
+		);
+		assert_eq!(relative_time(Duration::days(365)), "1 year ago");
+		assert_eq!(
+			relative_time(Duration::days(3 * 365)),
+			"3 years ago"
+		);
+	}
+
+	#[test]
+	fn test_relative_time_future() {
+		assert_eq!(relative_time(Duration::minutes(-5)), "just now");
+	}
+
+	#[test]
+	fn test_format_time_year_rollover() {
+		// 2023-12-31 23:30:00 UTC
+		let time = to_datetime(1_704_065_400, 0);
+		// 2024-01-01 00:10:00 UTC
+		let now = to_datetime(1_704_067_800, 0);
+
+		assert_eq!(
+			format_time(&time, &now, LogDateFormat::Relative),
+			"40 minutes ago"
+		);
+		assert_eq!(
+			format_time(&time, &now, LogDateFormat::Short),
+			"2023-12-31"
+		);
+	}
+
+	#[test]
+	fn test_format_time_offsets() {
+		// 2024-05-01 12:32:00 UTC committed in +0200
+		let time = to_datetime(1_714_566_720, 120);
+		let now = to_datetime(1_714_570_320, -300);
+
+		let full = format_time(&time, &now, LogDateFormat::Full);
+		assert_eq!(full, "2024-05-01 14:32 +0200");
+		assert_eq!(full.len(), LogDateFormat::Full.width());
+
+		assert_eq!(
+			format_time(&time, &now, LogDateFormat::Relative),
+			"1 hour ago"
+		);
+	}
+
+	#[test]
+	fn test_grouping() {
+		// 2024-05-06 23:30 in +0200, still monday locally
+		let monday = to_datetime(1_715_031_000, 120);
+		// 2024-05-07 00:10 in +0200
+		let tuesday = to_datetime(1_715_033_400, 120);
+
+		assert_eq!(LogGrouping::Off.group(&monday), None);
+		assert_ne!(
+			LogGrouping::Day.group(&monday),
+			LogGrouping::Day.group(&tuesday)
+		);
+		assert_eq!(
+			LogGrouping::Week.group(&monday),
+			LogGrouping::Week.group(&tuesday)
+		);
+
+		assert_eq!(
+			LogGrouping::Day.header(&monday),
+			"Monday, 2024-05-06"
+		);
+		assert_eq!(
+			LogGrouping::Week.header(&monday),
+			"Week 19, 2024"
+		);
+		assert_eq!(LogGrouping::Month.header(&monday), "May 2024");
+	}
+
 	#[test]
 	fn test_emojifi_string_no_conversion_cases() {
 		assert_eq!(&test_conversion("123"), "123");
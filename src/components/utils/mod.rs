@@ -3,10 +3,18 @@ use lazy_static::lazy_static;
 use std::borrow::Cow;
 use unicode_width::UnicodeWidthStr;
 
+pub mod branch_name;
+pub mod date_range;
+pub mod diff_render;
 pub mod filetree;
+pub mod hunk_text;
+pub mod index_op;
+pub mod lazy_lines;
 pub mod logitems;
 pub mod scroll_vertical;
+pub mod selected_path;
 pub mod statustree;
+pub mod text_search;
 
 /// macro to simplify running code that might return Err.
 /// It will show a popup in that case
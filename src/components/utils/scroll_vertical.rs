@@ -75,6 +75,15 @@ impl VerticalScroll {
 		new_top
 	}
 
+	/// scrolls so that `line` ends up in the middle of the view,
+	/// kept by the next `update` as long as `line` is selected
+	pub fn center(&self, line: usize, visual_height: usize) {
+		self.top.set(
+			line.saturating_sub(visual_height / 2)
+				.min(self.max_top.get()),
+		);
+	}
+
 	pub fn update_no_selection(
 		&self,
 		line_count: usize,
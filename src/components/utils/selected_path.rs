@@ -0,0 +1,114 @@
+use crate::{
+	clipboard,
+	components::{CommandInfo, EventState},
+	file_manager,
+	keys::SharedKeyConfig,
+	messages::Severity,
+	queue::{InternalEvent, Queue},
+	strings::{self, order},
+};
+use anyhow::{anyhow, Result};
+use asyncgit::{sync::utils::repo_work_dir, CWD};
+use crossterm::event::KeyEvent;
+use std::path::{Path, PathBuf};
+
+/// components with a selected file get the actions to copy its path
+/// and to reveal it in the system file manager
+pub trait SelectedPath {
+	/// repo relative path of the selected file
+	fn selected_path(&self) -> Option<String>;
+
+	///
+	fn path_commands(
+		&self,
+		key_config: &SharedKeyConfig,
+		visible: bool,
+		out: &mut Vec<CommandInfo>,
+	) {
+		let enabled = self.selected_path().is_some();
+
+		for text in vec![
+			strings::commands::copy_path(key_config),
+			strings::commands::copy_absolute_path(key_config),
+			strings::commands::open_containing_dir(key_config),
+		] {
+			out.push(
+				CommandInfo::new(text, enabled, visible)
+					.order(order::RARE_ACTION),
+			);
+		}
+	}
+
+	/// runs the path action bound to `key`
+	fn path_event(
+		&self,
+		key: KeyEvent,
+		key_config: &SharedKeyConfig,
+		queue: &Queue,
+	) -> EventState {
+		let action = if key == key_config.copy {
+			copy_path
+		} else if key == key_config.copy_absolute_path {
+			copy_absolute_path
+		} else if key == key_config.open_containing_dir {
+			open_containing_dir
+		} else {
+			return EventState::NotConsumed;
+		};
+
+		self.selected_path()
+			.map_or(EventState::NotConsumed, |path| {
+				action(&path, queue);
+				EventState::Consumed
+			})
+	}
+}
+
+fn absolute_path(path: &str) -> Result<PathBuf> {
+	Ok(Path::new(&repo_work_dir(CWD)?).join(path))
+}
+
+fn copy_to_clipboard(text: &str, queue: &Queue) {
+	match clipboard::copy_string(text) {
+		Ok(_) => queue.push(InternalEvent::ShowToast(
+			Severity::Info,
+			strings::toast_copied_path(text),
+		)),
+		Err(e) => queue.push(InternalEvent::ShowErrorMsg(
+			strings::copy_path_failed(&e.to_string()),
+		)),
+	}
+}
+
+fn copy_path(path: &str, queue: &Queue) {
+	copy_to_clipboard(path, queue);
+}
+
+fn copy_absolute_path(path: &str, queue: &Queue) {
+	match absolute_path(path) {
+		Ok(path) => copy_to_clipboard(&path.to_string_lossy(), queue),
+		Err(e) => queue.push(InternalEvent::ShowErrorMsg(
+			strings::copy_path_failed(&e.to_string()),
+		)),
+	}
+}
+
+fn open_containing_dir(path: &str, queue: &Queue) {
+	let res = absolute_path(path).and_then(|path| {
+		file_manager::containing_dir(&path).map_or_else(
+			|| {
+				Err(anyhow!(
+					"no directory containing `{}`",
+					path.display()
+				))
+			},
+			file_manager::open_dir,
+		)
+	});
+
+	if let Err(e) = res {
+		queue.push(InternalEvent::ShowErrorMsg(
+			strings::open_containing_dir_failed(&e.to_string()),
+		));
+	}
+}
@@ -154,6 +154,22 @@ impl StatusTree {
 		})
 	}
 
+	/// selects the item at `path`, `false` if it is not in the tree
+	/// or folded away
+	pub fn select_path(&mut self, path: &str) -> bool {
+		let index = self.tree.items().binary_search_by(|e| {
+			e.info.full_path.as_str().cmp(path)
+		});
+
+		match index {
+			Ok(index) if self.is_visible_index(index) => {
+				self.selection = Some(index);
+				true
+			}
+			_ => false,
+		}
+	}
+
 	///
 	pub fn selected_item(&self) -> Option<FileTreeItem> {
 		self.selection.map(|i| self.tree[i].clone())
@@ -439,7 +455,9 @@ mod tests {
 			.iter()
 			.map(|a| StatusItem {
 				path: String::from(*a),
+				old_path: None,
 				status: StatusItemType::Modified,
+				binary_size: None,
 			})
 			.collect::<Vec<_>>()
 	}
@@ -470,6 +488,26 @@ mod tests {
 		assert_eq!(res.selection, Some(0));
 	}
 
+	#[test]
+	fn test_select_path() {
+		let items = string_vec_to_status(&[
+			"a/b", //
+			"c",
+		]);
+
+		let mut res = StatusTree::default();
+		res.update(&items).unwrap();
+
+		assert!(res.select_path("c"));
+		assert_eq!(res.selection, Some(2));
+
+		assert!(res.select_path("a/b"));
+		assert_eq!(res.selection, Some(1));
+
+		assert!(!res.select_path("d"));
+		assert_eq!(res.selection, Some(1));
+	}
+
 	#[test]
 	fn test_keep_selected_item() {
 		let mut res = StatusTree::default();
@@ -0,0 +1,128 @@
+use std::ops::Range;
+
+/// plain text search, case sensitive only if the query contains an
+/// uppercase char (smart-case)
+pub struct TextSearch {
+	query: String,
+	case_sensitive: bool,
+}
+
+impl TextSearch {
+	/// `None` for an empty query
+	pub fn new(query: &str) -> Option<Self> {
+		if query.is_empty() {
+			return None;
+		}
+
+		let case_sensitive = query.chars().any(char::is_uppercase);
+
+		Some(Self {
+			query: if case_sensitive {
+				query.to_string()
+			} else {
+				query.to_lowercase()
+			},
+			case_sensitive,
+		})
+	}
+
+	/// byte ranges of all non overlapping matches in `text`
+	pub fn find_all(&self, text: &str) -> Vec<Range<usize>> {
+		let mut res = Vec::new();
+		let mut search_from = 0;
+
+		while let Some(range) = self.find_from(text, search_from) {
+			search_from = range.end;
+			res.push(range);
+		}
+
+		res
+	}
+
+	fn find_from(
+		&self,
+		text: &str,
+		start: usize,
+	) -> Option<Range<usize>> {
+		let haystack = text.get(start..)?;
+
+		if self.case_sensitive {
+			return haystack.find(self.query.as_str()).map(|pos| {
+				start + pos..start + pos + self.query.len()
+			});
+		}
+
+		// lowercasing may change byte lengths, so compare char by
+		// char to keep the ranges valid for the original text
+		haystack.char_indices().find_map(|(pos, _)| {
+			self.match_len_at(&haystack[pos..])
+				.map(|len| start + pos..start + pos + len)
+		})
+	}
+
+	/// length in bytes of `text` consumed when it starts with the
+	/// (lowercase) query
+	fn match_len_at(&self, text: &str) -> Option<usize> {
+		let mut query = self.query.chars();
+		let mut expected = query.next();
+		let mut len = 0;
+
+		for c in text.chars() {
+			if expected.is_none() {
+				break;
+			}
+
+			for lower in c.to_lowercase() {
+				if Some(lower) == expected {
+					expected = query.next();
+				} else {
+					return None;
+				}
+			}
+
+			len += c.len_utf8();
+		}
+
+		if expected.is_none() {
+			Some(len)
+		} else {
+			None
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_empty_query() {
+		assert!(TextSearch::new("").is_none());
+	}
+
+	#[test]
+	fn test_smart_case() {
+		let search = TextSearch::new("foo").unwrap();
+		assert_eq!(
+			search.find_all("Foo foo FOO"),
+			vec![0..3, 4..7, 8..11]
+		);
+
+		let search = TextSearch::new("Foo").unwrap();
+		assert_eq!(search.find_all("Foo foo FOO"), vec![0..3]);
+	}
+
+	#[test]
+	fn test_no_overlap() {
+		let search = TextSearch::new("aa").unwrap();
+		assert_eq!(search.find_all("aaaaa"), vec![0..2, 2..4]);
+	}
+
+	#[test]
+	fn test_multibyte() {
+		let search = TextSearch::new("ä").unwrap();
+		assert_eq!(search.find_all("xÄyä"), vec![1..3, 4..6]);
+		assert!(search.find_all("abc").is_empty());
+	}
+}
@@ -0,0 +1,246 @@
+//! how much confirming each destructive action needs
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// `ConfirmLevel::WhenLarge` asks once more than this many items
+/// (files, stashes) are affected
+pub const LARGE_THRESHOLD: usize = 5;
+
+/// actions the policy can be set for
+#[derive(
+	Serialize,
+	Deserialize,
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	PartialOrd,
+	Ord,
+	Debug,
+)]
+pub enum DestructiveAction {
+	/// single file (or lines of it)
+	DiscardFile,
+	/// single hunk, can be undone for a few seconds after
+	DiscardHunk,
+	/// folder, all changes below it
+	DiscardAll,
+	/// aborting a merge/rebase resets hard
+	HardReset,
+	DeleteBranch,
+	ForcePush,
+	StashDrop,
+}
+
+impl DestructiveAction {
+	/// in the order listed in the options
+	pub const ALL: [Self; 7] = [
+		Self::DiscardFile,
+		Self::DiscardHunk,
+		Self::DiscardAll,
+		Self::HardReset,
+		Self::DeleteBranch,
+		Self::ForcePush,
+		Self::StashDrop,
+	];
+
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::DiscardFile => "Discard file",
+			Self::DiscardHunk => "Discard hunk",
+			Self::DiscardAll => "Discard folder",
+			Self::HardReset => "Hard reset (abort)",
+			Self::DeleteBranch => "Delete branch",
+			Self::ForcePush => "Force push",
+			Self::StashDrop => "Drop stash",
+		}
+	}
+
+	fn index(self) -> usize {
+		Self::ALL
+			.iter()
+			.position(|action| *action == self)
+			.unwrap_or_default()
+	}
+
+	/// `None` after the last one
+	pub fn next(self) -> Option<Self> {
+		Self::ALL.get(self.index() + 1).copied()
+	}
+
+	/// `None` before the first one
+	pub fn prev(self) -> Option<Self> {
+		self.index()
+			.checked_sub(1)
+			.and_then(|idx| Self::ALL.get(idx).copied())
+	}
+}
+
+///
+#[derive(
+	Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug,
+)]
+pub enum ConfirmLevel {
+	Always,
+	/// only if more than `LARGE_THRESHOLD` items are affected
+	WhenLarge,
+	Never,
+}
+
+impl Default for ConfirmLevel {
+	fn default() -> Self {
+		Self::Always
+	}
+}
+
+impl ConfirmLevel {
+	pub fn name(self) -> String {
+		match self {
+			Self::Always => String::from("Always"),
+			Self::WhenLarge => {
+				format!("More than {}", LARGE_THRESHOLD)
+			}
+			Self::Never => String::from("Never"),
+		}
+	}
+
+	pub const fn next(self) -> Self {
+		match self {
+			Self::Always => Self::WhenLarge,
+			Self::WhenLarge => Self::Never,
+			Self::Never => Self::Always,
+		}
+	}
+
+	pub const fn prev(self) -> Self {
+		match self {
+			Self::Always => Self::Never,
+			Self::WhenLarge => Self::Always,
+			Self::Never => Self::WhenLarge,
+		}
+	}
+}
+
+/// persisted levels, actions not listed are always confirmed
+#[derive(
+	Serialize, Deserialize, Clone, PartialEq, Eq, Default, Debug,
+)]
+#[serde(default)]
+pub struct ConfirmPolicy {
+	levels: BTreeMap<DestructiveAction, ConfirmLevel>,
+}
+
+impl ConfirmPolicy {
+	pub fn level(&self, action: DestructiveAction) -> ConfirmLevel {
+		self.levels.get(&action).copied().unwrap_or_default()
+	}
+
+	pub fn set_level(
+		&mut self,
+		action: DestructiveAction,
+		level: ConfirmLevel,
+	) {
+		if level == ConfirmLevel::default() {
+			self.levels.remove(&action);
+		} else {
+			self.levels.insert(action, level);
+		}
+	}
+
+	/// `magnitude` is the number of items `action` affects
+	pub fn needs_confirmation(
+		&self,
+		action: DestructiveAction,
+		magnitude: usize,
+	) -> bool {
+		match self.level(action) {
+			ConfirmLevel::Always => true,
+			ConfirmLevel::WhenLarge => magnitude > LARGE_THRESHOLD,
+			ConfirmLevel::Never => false,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn policy(
+		action: DestructiveAction,
+		level: ConfirmLevel,
+	) -> ConfirmPolicy {
+		let mut policy = ConfirmPolicy::default();
+		policy.set_level(action, level);
+		policy
+	}
+
+	#[test]
+	fn test_default_always() {
+		let policy = ConfirmPolicy::default();
+
+		for action in DestructiveAction::ALL.iter().copied() {
+			assert!(policy.needs_confirmation(action, 0));
+			assert!(policy.needs_confirmation(action, 1));
+			assert!(policy.needs_confirmation(action, 100));
+		}
+	}
+
+	#[test]
+	fn test_when_large() {
+		let policy = policy(
+			DestructiveAction::DiscardAll,
+			ConfirmLevel::WhenLarge,
+		);
+		let action = DestructiveAction::DiscardAll;
+
+		assert!(!policy.needs_confirmation(action, 1));
+		assert!(!policy.needs_confirmation(action, LARGE_THRESHOLD));
+		assert!(
+			policy.needs_confirmation(action, LARGE_THRESHOLD + 1)
+		);
+
+		// other actions are not affected
+		assert!(policy
+			.needs_confirmation(DestructiveAction::StashDrop, 1));
+	}
+
+	#[test]
+	fn test_never() {
+		let policy =
+			policy(DestructiveAction::ForcePush, ConfirmLevel::Never);
+
+		assert!(!policy
+			.needs_confirmation(DestructiveAction::ForcePush, 1));
+		assert!(!policy.needs_confirmation(
+			DestructiveAction::ForcePush,
+			LARGE_THRESHOLD + 1
+		));
+	}
+
+	#[test]
+	fn test_default_not_stored() {
+		let mut policy =
+			policy(DestructiveAction::StashDrop, ConfirmLevel::Never);
+		policy.set_level(
+			DestructiveAction::StashDrop,
+			ConfirmLevel::Always,
+		);
+
+		assert_eq!(policy, ConfirmPolicy::default());
+	}
+
+	#[test]
+	fn test_cycle() {
+		assert_eq!(DestructiveAction::ALL[0].prev(), None);
+		assert_eq!(DestructiveAction::StashDrop.next(), None);
+		assert_eq!(
+			DestructiveAction::DiscardAll.next(),
+			Some(DestructiveAction::HardReset)
+		);
+
+		let level = ConfirmLevel::Always;
+		assert_eq!(level.next().next().next(), level);
+		assert_eq!(level.next().prev(), level);
+	}
+}
@@ -0,0 +1,99 @@
+use crate::{args::get_app_cache_path, version::Version};
+use anyhow::Result;
+use lazy_static::lazy_static;
+use std::{
+	collections::VecDeque, fmt::Write as _, fs, path::PathBuf,
+	sync::Mutex,
+};
+
+/// internal events kept to put into a crash report
+const EVENT_HISTORY_LEN: usize = 50;
+
+lazy_static! {
+	static ref EVENT_HISTORY: Mutex<VecDeque<String>> =
+		Mutex::new(VecDeque::with_capacity(EVENT_HISTORY_LEN));
+}
+
+/// remembers an internal event, only the last
+/// `EVENT_HISTORY_LEN` are kept
+pub fn record_event(name: &str) {
+	if let Ok(mut history) = EVENT_HISTORY.lock() {
+		push_capped(&mut history, name.to_string());
+	}
+}
+
+fn push_capped(history: &mut VecDeque<String>, entry: String) {
+	if history.len() >= EVENT_HISTORY_LEN {
+		history.pop_front();
+	}
+	history.push_back(entry);
+}
+
+/// writes a crash report into the cache dir and returns its path
+pub fn write(message: &str, backtrace: &str) -> Result<PathBuf> {
+	// never block, the panic might have happened while recording
+	let events = EVENT_HISTORY
+		.try_lock()
+		.map(|history| history.iter().cloned().collect::<Vec<_>>())
+		.unwrap_or_default();
+
+	let path = get_app_cache_path()?.join(format!(
+		"crash-{}.log",
+		chrono::Local::now().format("%Y%m%d-%H%M%S")
+	));
+
+	fs::write(&path, report(message, backtrace, &events))?;
+
+	Ok(path)
+}
+
+fn report(
+	message: &str,
+	backtrace: &str,
+	events: &[String],
+) -> String {
+	let mut res = String::new();
+
+	let _ = writeln!(res, "gitui {}", Version::new());
+	let _ = writeln!(res, "\npanic: {}", message);
+	let _ = writeln!(res, "\nlast internal events (oldest first):");
+	for event in events {
+		let _ = writeln!(res, "  {}", event);
+	}
+	let _ = writeln!(res, "\ntrace:\n{}", backtrace);
+
+	res
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_history_capped() {
+		let mut history = VecDeque::new();
+
+		for i in 0..EVENT_HISTORY_LEN + 5 {
+			push_capped(&mut history, i.to_string());
+		}
+
+		assert_eq!(history.len(), EVENT_HISTORY_LEN);
+		assert_eq!(history.front().map(String::as_str), Some("5"));
+	}
+
+	#[test]
+	fn test_report() {
+		let report = report(
+			"boom",
+			"frame",
+			&[String::from("OpenCommit"), String::from("TabSwitch")],
+		);
+
+		assert!(
+			report.starts_with(&format!("gitui {}", Version::new()))
+		);
+		assert!(report.contains("panic: boom"));
+		assert!(report.contains("  OpenCommit\n  TabSwitch\n"));
+		assert!(report.contains("trace:\nframe"));
+	}
+}
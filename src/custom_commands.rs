@@ -0,0 +1,369 @@
+//! user defined shell commands (`custom_commands.ron`) run on the
+//! current selection, e.g. a formatter on the selected file
+
+use crate::{args::get_app_config_path, AsyncAppNotification};
+use anyhow::{anyhow, Result};
+use asyncgit::{
+	asyncjob::{AsyncJob, RunParams},
+	sync::utils::repo_work_dir,
+	CWD,
+};
+use crossterm::{
+	event::KeyEvent,
+	terminal::{
+		disable_raw_mode, enable_raw_mode, EnterAlternateScreen,
+		LeaveAlternateScreen,
+	},
+	ExecutableCommand,
+};
+use scopeguard::defer;
+use serde::{Deserialize, Serialize};
+use std::{
+	fs,
+	io::{self, Write},
+	path::PathBuf,
+	process::{Command, ExitStatus},
+	sync::{Arc, Mutex},
+};
+
+/// where a command is offered
+#[derive(
+	Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug,
+)]
+pub enum CustomCommandContext {
+	/// next to a list of files (status and files tab)
+	File,
+	/// next to the list of commits (log tab)
+	Commit,
+	/// everywhere outside of popups
+	Always,
+}
+
+impl Default for CustomCommandContext {
+	fn default() -> Self {
+		Self::Always
+	}
+}
+
+/// one entry of `custom_commands.ron`
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct CustomCommand {
+	/// shown in the command bar
+	pub name: String,
+	/// run by the shell, `{file}`, `{commit}`, `{branch}` and
+	/// `{repo_root}` are replaced with the (quoted) selection
+	pub command: String,
+	#[serde(default)]
+	pub context: CustomCommandContext,
+	#[serde(default)]
+	pub key: Option<KeyEvent>,
+	/// runs in the background and puts the output into the message
+	/// history instead of handing over the terminal
+	#[serde(default)]
+	pub capture_output: bool,
+}
+
+/// what the placeholders of a command are filled with
+#[derive(Default, Clone, Debug)]
+pub struct CommandSelection {
+	/// `File` commands are offered
+	pub file_list: bool,
+	/// `Commit` commands are offered
+	pub commit_list: bool,
+	/// repo relative path
+	pub file: Option<String>,
+	/// full hash
+	pub commit: Option<String>,
+	pub branch: Option<String>,
+	pub repo_root: Option<String>,
+}
+
+impl CommandSelection {
+	/// `None` for an unknown placeholder
+	fn value(&self, placeholder: &str) -> Option<&Option<String>> {
+		match placeholder {
+			"file" => Some(&self.file),
+			"commit" => Some(&self.commit),
+			"branch" => Some(&self.branch),
+			"repo_root" => Some(&self.repo_root),
+			_ => None,
+		}
+	}
+}
+
+impl CustomCommand {
+	/// offered in the current context
+	pub const fn is_available(
+		&self,
+		selection: &CommandSelection,
+	) -> bool {
+		match self.context {
+			CustomCommandContext::File => selection.file_list,
+			CustomCommandContext::Commit => selection.commit_list,
+			CustomCommandContext::Always => true,
+		}
+	}
+
+	/// the command line to run, `None` if a placeholder it uses has
+	/// no value in the current selection
+	pub fn expand(
+		&self,
+		selection: &CommandSelection,
+	) -> Option<String> {
+		let mut out = String::with_capacity(self.command.len());
+		let mut rest = self.command.as_str();
+
+		while let Some(start) = rest.find('{') {
+			out.push_str(&rest[..start]);
+			rest = &rest[start..];
+
+			// anything else in braces belongs to the shell
+			let placeholder = rest.find('}').and_then(|end| {
+				Some((selection.value(&rest[1..end])?, end))
+			});
+
+			if let Some((value, end)) = placeholder {
+				out.push_str(&shell_quote(value.as_ref()?));
+				rest = &rest[end + 1..];
+			} else {
+				out.push('{');
+				rest = &rest[1..];
+			}
+		}
+
+		out.push_str(rest);
+		Some(out)
+	}
+}
+
+/// `value` as one word of a shell command line
+#[cfg(not(windows))]
+fn shell_quote(value: &str) -> String {
+	format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// `value` as one word of a shell command line
+#[cfg(windows)]
+fn shell_quote(value: &str) -> String {
+	format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+/// `git mergetool` resolving the conflicted `path`
+pub fn mergetool_command(path: &str) -> String {
+	format!("git mergetool -- {}", shell_quote(path))
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+	let mut cmd = Command::new("sh");
+	cmd.arg("-c").arg(command);
+	cmd
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+	let mut cmd = Command::new("cmd");
+	cmd.arg("/C").arg(command);
+	cmd
+}
+
+fn custom_commands_file() -> Result<PathBuf> {
+	Ok(get_app_config_path()?.join("custom_commands.ron"))
+}
+
+/// the configured commands, none if there is no config file
+pub fn load() -> Result<Vec<CustomCommand>> {
+	let file = custom_commands_file()?;
+	if !file.exists() {
+		return Ok(Vec::new());
+	}
+
+	Ok(ron::de::from_bytes(&fs::read(file)?)?)
+}
+
+/// hands the terminal over to `command` and waits for enter after it
+/// is done so its output can be read
+pub fn run_in_terminal(command: &str) -> Result<ExitStatus> {
+	let work_dir = repo_work_dir(CWD)?;
+
+	io::stdout().execute(LeaveAlternateScreen)?;
+	disable_raw_mode()?;
+	defer! {
+		enable_raw_mode().expect("reset terminal");
+		io::stdout().execute(EnterAlternateScreen).expect("reset terminal");
+	}
+
+	let status = shell(command)
+		.current_dir(work_dir)
+		.status()
+		.map_err(|e| anyhow!("\"{}\": {}", command, e))?;
+
+	print!("\n{}", crate::strings::CUSTOM_COMMAND_RETURN);
+	io::stdout().flush()?;
+	io::stdin().read_line(&mut String::new())?;
+
+	Ok(status)
+}
+
+/// how a command run in the background ended
+#[derive(Debug)]
+pub struct CommandOutput {
+	pub success: bool,
+	pub stdout: String,
+	pub stderr: String,
+}
+
+enum JobState {
+	Request(String, String),
+	Response(String, asyncgit::Result<CommandOutput>),
+}
+
+/// runs a command capturing its output
+#[derive(Clone)]
+pub struct AsyncCustomCommandJob {
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+impl AsyncCustomCommandJob {
+	///
+	pub fn new(name: String, command: String) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request(
+				name, command,
+			)))),
+		}
+	}
+
+	/// name of the command and how it ended
+	pub fn result(
+		&self,
+	) -> Option<(String, asyncgit::Result<CommandOutput>)> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request(..) => None,
+					JobState::Response(name, result) => {
+						Some((name, result))
+					}
+				};
+			}
+		}
+
+		None
+	}
+
+	fn run_captured(
+		command: &str,
+	) -> asyncgit::Result<CommandOutput> {
+		let output = shell(command)
+			.current_dir(repo_work_dir(CWD)?)
+			.output()?;
+
+		Ok(CommandOutput {
+			success: output.status.success(),
+			stdout: String::from_utf8_lossy(&output.stdout)
+				.into_owned(),
+			stderr: String::from_utf8_lossy(&output.stderr)
+				.into_owned(),
+		})
+	}
+}
+
+impl AsyncJob for AsyncCustomCommandJob {
+	type Notification = AsyncAppNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> asyncgit::Result<Self::Notification> {
+		let request =
+			self.state.lock().ok().and_then(|mut state| state.take());
+
+		if let Some(JobState::Request(name, command)) = request {
+			let result = Self::run_captured(&command);
+
+			if let Ok(mut state) = self.state.lock() {
+				*state = Some(JobState::Response(name, result));
+			}
+		}
+
+		Ok(AsyncAppNotification::CustomCommand)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn command(command: &str) -> CustomCommand {
+		CustomCommand {
+			name: String::from("test"),
+			command: command.to_string(),
+			context: CustomCommandContext::File,
+			key: None,
+			capture_output: false,
+		}
+	}
+
+	fn selection() -> CommandSelection {
+		CommandSelection {
+			file_list: true,
+			file: Some(String::from("src/it's here.rs")),
+			branch: Some(String::from("master")),
+			..CommandSelection::default()
+		}
+	}
+
+	#[test]
+	#[cfg(not(windows))]
+	fn test_expand_quotes() {
+		assert_eq!(
+			command("rustfmt {file} && echo {branch}")
+				.expand(&selection())
+				.unwrap(),
+			r"rustfmt 'src/it'\''s here.rs' && echo 'master'"
+		);
+	}
+
+	#[test]
+	fn test_expand_missing() {
+		assert_eq!(
+			command("git show {commit}").expand(&selection()),
+			None
+		);
+		assert!(command("ls")
+			.expand(&CommandSelection::default())
+			.is_some());
+	}
+
+	#[test]
+	fn test_expand_keeps_shell_braces() {
+		assert_eq!(
+			command("echo ${HOME} {x} {")
+				.expand(&selection())
+				.unwrap(),
+			"echo ${HOME} {x} {"
+		);
+	}
+
+	#[test]
+	fn test_available() {
+		let cmd = command("ls");
+
+		assert!(cmd.is_available(&selection()));
+		assert!(!cmd.is_available(&CommandSelection::default()));
+	}
+
+	#[test]
+	fn test_config() {
+		let commands: Vec<CustomCommand> = ron::de::from_str(
+			"[(name: \"fmt\", command: \"cargo fmt\", key: Some((code: Char('F'), modifiers: (bits: 1))))]",
+		)
+		.unwrap();
+
+		assert_eq!(commands[0].context, CustomCommandContext::Always);
+		assert!(!commands[0].capture_output);
+		assert!(commands[0].key.is_some());
+	}
+}
@@ -0,0 +1,76 @@
+use anyhow::{anyhow, Result};
+use std::{
+	ffi::OsStr,
+	path::{Path, PathBuf},
+	process::{Command, Stdio},
+	thread,
+};
+
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+const OPEN_COMMAND: &str = "xdg-open";
+#[cfg(target_os = "macos")]
+const OPEN_COMMAND: &str = "open";
+#[cfg(windows)]
+const OPEN_COMMAND: &str = "explorer";
+
+/// opens `dir` in the system file manager without waiting for it
+pub fn open_dir(dir: &Path) -> Result<()> {
+	// rebuilding the path uses the native separators
+	let dir = dir.components().collect::<PathBuf>();
+
+	open(dir.as_os_str())
+}
+
+/// opens `url` in the default browser without waiting for it
+pub fn open_url(url: &str) -> Result<()> {
+	open(OsStr::new(url))
+}
+
+fn open(target: &OsStr) -> Result<()> {
+	// handed over as a single argument, no shell quoting involved
+	let mut child = Command::new(OPEN_COMMAND)
+		.arg(target)
+		.stdin(Stdio::null())
+		.stdout(Stdio::null())
+		.stderr(Stdio::null())
+		.spawn()
+		.map_err(|e| {
+			anyhow!(
+				"`{} {}`: {}",
+				OPEN_COMMAND,
+				target.to_string_lossy(),
+				e
+			)
+		})?;
+
+	// reap the process whenever it exits
+	thread::spawn(move || child.wait());
+
+	Ok(())
+}
+
+/// closest existing directory containing `path`,
+/// the file itself might be deleted or only exist in another revision
+pub fn containing_dir(path: &Path) -> Option<&Path> {
+	path.ancestors().skip(1).find(|dir| dir.is_dir())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_containing_dir() {
+		let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+		assert_eq!(
+			containing_dir(&root.join("src/main.rs")),
+			Some(root.join("src").as_path())
+		);
+		assert_eq!(
+			containing_dir(&root.join("src/gone/nä me.rs")),
+			Some(root.join("src").as_path())
+		);
+		assert_eq!(containing_dir(&root.join("src")), Some(root));
+	}
+}
@@ -1,7 +1,9 @@
 use crate::notify_mutex::NotifyableMutex;
 use anyhow::Result;
 use crossbeam_channel::{unbounded, Receiver, Sender};
-use crossterm::event::{self, Event};
+use crossterm::event::{
+	self, Event, KeyCode, KeyEvent, KeyModifiers,
+};
 use std::{
 	sync::{
 		atomic::{AtomicBool, Ordering},
@@ -13,18 +15,24 @@ use std::{
 
 static POLL_DURATION: Duration = Duration::from_millis(1000);
 
+/// shorter runs of text keys are taken as typed, a fast typist or a
+/// slow connection delivers a few keys in one read as well
+const PASTE_MIN_LEN: usize = 8;
+
 ///
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum InputState {
 	Paused,
 	Polling,
 }
 
 ///
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum InputEvent {
 	Input(Event),
 	State(InputState),
+	/// text keys that arrived in one read (see `split_paste`)
+	Paste(String),
 }
 
 ///
@@ -97,6 +105,22 @@ impl Input {
 		}
 	}
 
+	/// the event and everything that is already waiting behind it
+	fn poll_burst(dur: Duration) -> anyhow::Result<Vec<Event>> {
+		let mut events = Vec::new();
+
+		if let Some(e) = Self::poll(dur)? {
+			events.push(e);
+
+			while let Some(e) = Self::poll(Duration::from_millis(0))?
+			{
+				events.push(e);
+			}
+		}
+
+		Ok(events)
+	}
+
 	fn input_loop(
 		arc_desired: &Arc<NotifyableMutex<bool>>,
 		arc_current: &Arc<AtomicBool>,
@@ -111,8 +135,10 @@ impl Input {
 				}
 				arc_current.store(true, Ordering::Relaxed);
 
-				if let Some(e) = Self::poll(POLL_DURATION)? {
-					tx.send(InputEvent::Input(e))?;
+				let events = Self::poll_burst(POLL_DURATION)?;
+
+				for e in split_paste(events) {
+					tx.send(e)?;
 				}
 			} else {
 				if arc_current.load(Ordering::Relaxed) {
@@ -128,3 +154,147 @@ impl Input {
 		}
 	}
 }
+
+fn text_char(e: &Event) -> Option<char> {
+	match e {
+		Event::Key(KeyEvent {
+			code: KeyCode::Char(c),
+			modifiers,
+		}) if (*modifiers - KeyModifiers::SHIFT).is_empty() => Some(*c),
+		_ => None,
+	}
+}
+
+/// crossterm cannot report bracketed paste, but a terminal writes a
+/// paste in one go while typed keys trickle in one by one. so a long
+/// run of plain text keys in one burst is taken as pasted text, enter
+/// and tab end a run as they confirm or move on when typed
+fn split_paste(events: Vec<Event>) -> Vec<InputEvent> {
+	fn flush(run: &mut Vec<Event>, out: &mut Vec<InputEvent>) {
+		if run.len() >= PASTE_MIN_LEN {
+			out.push(InputEvent::Paste(
+				run.drain(..).filter_map(|e| text_char(&e)).collect(),
+			));
+		} else {
+			out.extend(run.drain(..).map(InputEvent::Input));
+		}
+	}
+
+	let mut out = Vec::with_capacity(events.len());
+	let mut run = Vec::new();
+
+	for e in events {
+		if text_char(&e).is_some() {
+			run.push(e);
+		} else {
+			flush(&mut run, &mut out);
+			out.push(InputEvent::Input(e));
+		}
+	}
+	flush(&mut run, &mut out);
+
+	out
+}
+
+/// the keys of pasted text that no one took as a whole, like
+/// crossterm reports them when typed
+pub fn paste_keys(text: &str) -> Vec<Event> {
+	text.chars()
+		.map(|c| {
+			let key = match c {
+				'\n' => KeyEvent::from(KeyCode::Enter),
+				'\t' => KeyEvent::from(KeyCode::Tab),
+				c if c.is_uppercase() => KeyEvent::new(
+					KeyCode::Char(c),
+					KeyModifiers::SHIFT,
+				),
+				c => KeyEvent::from(KeyCode::Char(c)),
+			};
+			Event::Key(key)
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn key(code: KeyCode, modifiers: KeyModifiers) -> Event {
+		Event::Key(KeyEvent::new(code, modifiers))
+	}
+
+	fn text_keys(text: &str) -> Vec<Event> {
+		text.chars()
+			.map(|c| key(KeyCode::Char(c), KeyModifiers::empty()))
+			.collect()
+	}
+
+	fn inputs(events: Vec<Event>) -> Vec<InputEvent> {
+		events.into_iter().map(InputEvent::Input).collect()
+	}
+
+	#[test]
+	fn test_paste_text() {
+		let mut events = text_keys("äbcdefg");
+		events
+			.insert(1, key(KeyCode::Char('B'), KeyModifiers::SHIFT));
+
+		assert_eq!(
+			split_paste(events),
+			vec![InputEvent::Paste(String::from("äBbcdefg"))]
+		);
+		assert_eq!(
+			paste_keys("äB\n\tc"),
+			vec![
+				key(KeyCode::Char('ä'), KeyModifiers::empty()),
+				key(KeyCode::Char('B'), KeyModifiers::SHIFT),
+				key(KeyCode::Enter, KeyModifiers::empty()),
+				key(KeyCode::Tab, KeyModifiers::empty()),
+				key(KeyCode::Char('c'), KeyModifiers::empty()),
+			]
+		);
+	}
+
+	#[test]
+	fn test_paste_split_at_enter_and_tab() {
+		for sep in &[KeyCode::Enter, KeyCode::Tab] {
+			let mut events = text_keys("first line");
+			events.push(key(*sep, KeyModifiers::empty()));
+			events.extend(text_keys("second line"));
+
+			assert_eq!(
+				split_paste(events),
+				vec![
+					InputEvent::Paste(String::from("first line")),
+					InputEvent::Input(key(
+						*sep,
+						KeyModifiers::empty()
+					)),
+					InputEvent::Paste(String::from("second line")),
+				]
+			);
+		}
+	}
+
+	#[test]
+	fn test_no_paste() {
+		// typed quickly, the enter still confirms
+		let mut events = text_keys("name");
+		events.push(key(KeyCode::Enter, KeyModifiers::empty()));
+		assert_eq!(split_paste(events.clone()), inputs(events));
+
+		// anything but text keys is handled key by key
+		for other in vec![
+			key(KeyCode::Char('c'), KeyModifiers::CONTROL),
+			key(KeyCode::Down, KeyModifiers::empty()),
+			key(KeyCode::Enter, KeyModifiers::ALT),
+			Event::Resize(80, 24),
+		] {
+			let events = vec![
+				key(KeyCode::Char('a'), KeyModifiers::empty()),
+				other,
+			];
+			assert_eq!(split_paste(events.clone()), inputs(events));
+		}
+	}
+}
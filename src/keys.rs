@@ -31,6 +31,10 @@ pub struct KeyConfig {
 	pub toggle_workarea: KeyEvent,
 	pub focus_right: KeyEvent,
 	pub focus_left: KeyEvent,
+	pub focus_next: KeyEvent,
+	pub focus_prev: KeyEvent,
+	pub status_split_left: KeyEvent,
+	pub status_split_right: KeyEvent,
 	pub focus_above: KeyEvent,
 	pub focus_below: KeyEvent,
 	pub exit: KeyEvent,
@@ -38,8 +42,23 @@ pub struct KeyConfig {
 	pub exit_popup: KeyEvent,
 	pub open_commit: KeyEvent,
 	pub open_commit_editor: KeyEvent,
+	pub new_line: KeyEvent,
+	pub text_word_left: KeyEvent,
+	pub text_word_right: KeyEvent,
+	pub text_delete_word: KeyEvent,
+	pub text_kill_to_end: KeyEvent,
+	pub text_kill_to_start: KeyEvent,
 	pub open_help: KeyEvent,
 	pub open_options: KeyEvent,
+	pub open_msg_history: KeyEvent,
+	pub open_repo_maintenance: KeyEvent,
+	pub open_preflight: KeyEvent,
+	pub open_repo_switcher: KeyEvent,
+	pub open_command_palette: KeyEvent,
+	pub open_git_config: KeyEvent,
+	pub open_timings: KeyEvent,
+	pub toggle_offline: KeyEvent,
+	pub selection_mode: KeyEvent,
 	pub move_left: KeyEvent,
 	pub move_right: KeyEvent,
 	pub tree_collapse_recursive: KeyEvent,
@@ -55,38 +74,116 @@ pub struct KeyConfig {
 	pub enter: KeyEvent,
 	pub blame: KeyEvent,
 	pub edit_file: KeyEvent,
+	pub view_file: KeyEvent,
 	pub status_stage_all: KeyEvent,
 	pub status_reset_item: KeyEvent,
 	pub status_ignore_file: KeyEvent,
+	pub status_load_all_untracked: KeyEvent,
+	pub status_stash: KeyEvent,
+	pub status_stage_pattern: KeyEvent,
 	pub diff_stage_lines: KeyEvent,
+	pub diff_split_hunk: KeyEvent,
 	pub diff_reset_lines: KeyEvent,
+	pub diff_hunk_next: KeyEvent,
+	pub diff_hunk_prev: KeyEvent,
+	pub diff_copy_hunk: KeyEvent,
+	pub diff_copy_old: KeyEvent,
+	pub diff_copy_new: KeyEvent,
+	pub diff_search: KeyEvent,
+	pub diff_search_next: KeyEvent,
+	pub diff_search_prev: KeyEvent,
+	pub diff_lfs_pointer: KeyEvent,
+	pub diff_show_whitespace: KeyEvent,
+	pub diff_jump_moved: KeyEvent,
+	pub file_view_goto: KeyEvent,
 	pub stashing_save: KeyEvent,
 	pub stashing_toggle_untracked: KeyEvent,
 	pub stashing_toggle_index: KeyEvent,
+	pub stash_msg_toggle_untracked: KeyEvent,
+	pub stash_msg_toggle_index: KeyEvent,
 	pub stash_apply: KeyEvent,
 	pub stash_open: KeyEvent,
 	pub stash_drop: KeyEvent,
 	pub cmd_bar_toggle: KeyEvent,
 	pub log_tag_commit: KeyEvent,
 	pub log_mark_commit: KeyEvent,
+	pub log_date_format: KeyEvent,
+	pub log_grouping: KeyEvent,
 	pub commit_amend: KeyEvent,
+	pub commit_signoff: KeyEvent,
+	pub commit_goto_changes: KeyEvent,
+	pub commit_amend_dates: KeyEvent,
 	pub copy: KeyEvent,
 	pub create_branch: KeyEvent,
+	pub create_branch_orphan: KeyEvent,
 	pub rename_branch: KeyEvent,
 	pub select_branch: KeyEvent,
+	pub checkout_previous_branch: KeyEvent,
+	pub recent_branches: KeyEvent,
+	pub branch_name_suggestion: KeyEvent,
+	pub branch_changes: KeyEvent,
 	pub delete_branch: KeyEvent,
 	pub merge_branch: KeyEvent,
 	pub rebase_branch: KeyEvent,
+	pub branch_sort: KeyEvent,
+	pub branch_edit_description: KeyEvent,
+	pub branch_toggle_descriptions: KeyEvent,
+	pub branch_merged_filter: KeyEvent,
+	pub branch_merged_base: KeyEvent,
+	pub branch_delete_merged: KeyEvent,
 	pub compare_commits: KeyEvent,
+	pub compare_merge_base: KeyEvent,
+	pub compare_uncommitted: KeyEvent,
+	pub compare_next_base: KeyEvent,
 	pub tags: KeyEvent,
 	pub delete_tag: KeyEvent,
+	pub git_config_unset: KeyEvent,
 	pub select_tag: KeyEvent,
 	pub push: KeyEvent,
 	pub open_file_tree: KeyEvent,
 	pub file_find: KeyEvent,
+	pub tree_toggle_sizes: KeyEvent,
+	pub merge_diff_mode: KeyEvent,
+	pub copy_absolute_path: KeyEvent,
+	pub open_containing_dir: KeyEvent,
+	pub log_filter: KeyEvent,
+	pub log_filter_author: KeyEvent,
+	pub log_authors: KeyEvent,
+	pub log_export: KeyEvent,
+	pub rebase_autosquash: KeyEvent,
+	pub cherry_pick: KeyEvent,
+	pub cherry_pick_skip: KeyEvent,
+	pub bisect: KeyEvent,
+	pub bisect_good: KeyEvent,
+	pub bisect_bad: KeyEvent,
+	pub bisect_skip: KeyEvent,
 	pub force_push: KeyEvent,
+	pub push_preview_remote: KeyEvent,
+	pub push_select_branch: KeyEvent,
+	pub push_select_remote: KeyEvent,
+	pub message_link_next: KeyEvent,
+	pub message_link_prev: KeyEvent,
+	pub undo_index_change: KeyEvent,
+	pub restore_discarded: KeyEvent,
+	pub undo_hunk_discard: KeyEvent,
+	pub log_jump_to_ref: KeyEvent,
+	pub bookmark_add: KeyEvent,
+	pub open_bookmarks: KeyEvent,
+	pub bookmark_delete: KeyEvent,
+	pub status_index_flags: KeyEvent,
+	pub status_skip_worktree: KeyEvent,
+	pub status_resolve_conflict: KeyEvent,
+	pub conflict_take_ours: KeyEvent,
+	pub conflict_take_theirs: KeyEvent,
+	pub conflict_mergetool: KeyEvent,
+	pub index_flag_clear: KeyEvent,
+	pub timings_reset: KeyEvent,
 	pub pull: KeyEvent,
+	pub fetch: KeyEvent,
+	pub fetch_prune: KeyEvent,
+	pub compare_workdir: KeyEvent,
 	pub abort_merge: KeyEvent,
+	pub continue_merge: KeyEvent,
 	pub undo_commit: KeyEvent,
 }
 
@@ -104,6 +201,10 @@ impl Default for KeyConfig {
 			toggle_workarea: KeyEvent { code: KeyCode::Char('w'), modifiers: KeyModifiers::empty()},
 			focus_right: KeyEvent { code: KeyCode::Right, modifiers: KeyModifiers::empty()},
 			focus_left: KeyEvent { code: KeyCode::Left, modifiers: KeyModifiers::empty()},
+			focus_next: KeyEvent { code: KeyCode::Char('n'), modifiers: KeyModifiers::CONTROL},
+			focus_prev: KeyEvent { code: KeyCode::Char('o'), modifiers: KeyModifiers::CONTROL},
+			status_split_left: KeyEvent { code: KeyCode::Char('{'), modifiers: KeyModifiers::empty()},
+			status_split_right: KeyEvent { code: KeyCode::Char('}'), modifiers: KeyModifiers::empty()},
 			focus_above: KeyEvent { code: KeyCode::Up, modifiers: KeyModifiers::empty()},
 			focus_below: KeyEvent { code: KeyCode::Down, modifiers: KeyModifiers::empty()},
 			exit: KeyEvent { code: KeyCode::Char('c'), modifiers: KeyModifiers::CONTROL},
@@ -111,8 +212,23 @@ impl Default for KeyConfig {
 			exit_popup: KeyEvent { code: KeyCode::Esc, modifiers: KeyModifiers::empty()},
 			open_commit: KeyEvent { code: KeyCode::Char('c'), modifiers: KeyModifiers::empty()},
 			open_commit_editor: KeyEvent { code: KeyCode::Char('e'), modifiers:KeyModifiers::CONTROL},
+			new_line: KeyEvent { code: KeyCode::Enter, modifiers: KeyModifiers::ALT},
+			text_word_left: KeyEvent { code: KeyCode::Left, modifiers: KeyModifiers::CONTROL},
+			text_word_right: KeyEvent { code: KeyCode::Right, modifiers: KeyModifiers::CONTROL},
+			text_delete_word: KeyEvent { code: KeyCode::Char('w'), modifiers: KeyModifiers::CONTROL},
+			text_kill_to_end: KeyEvent { code: KeyCode::Char('k'), modifiers: KeyModifiers::CONTROL},
+			text_kill_to_start: KeyEvent { code: KeyCode::Char('u'), modifiers: KeyModifiers::CONTROL},
 			open_help: KeyEvent { code: KeyCode::Char('h'), modifiers: KeyModifiers::empty()},
 			open_options: KeyEvent { code: KeyCode::Char('o'), modifiers: KeyModifiers::empty()},
+			open_msg_history: KeyEvent { code: KeyCode::Char('L'), modifiers: KeyModifiers::SHIFT},
+			open_repo_maintenance: KeyEvent { code: KeyCode::Char('r'), modifiers: KeyModifiers::CONTROL},
+			open_preflight: KeyEvent { code: KeyCode::Char('p'), modifiers: KeyModifiers::CONTROL},
+			open_repo_switcher: KeyEvent { code: KeyCode::Char('w'), modifiers: KeyModifiers::CONTROL},
+			open_command_palette: KeyEvent { code: KeyCode::Char('x'), modifiers: KeyModifiers::CONTROL},
+			open_git_config: KeyEvent { code: KeyCode::Char('g'), modifiers: KeyModifiers::CONTROL},
+			open_timings: KeyEvent { code: KeyCode::Char('t'), modifiers: KeyModifiers::CONTROL},
+			toggle_offline: KeyEvent { code: KeyCode::Char('o'), modifiers: KeyModifiers::ALT},
+			selection_mode: KeyEvent { code: KeyCode::Char('s'), modifiers: KeyModifiers::ALT},
 			move_left: KeyEvent { code: KeyCode::Left, modifiers: KeyModifiers::empty()},
 			move_right: KeyEvent { code: KeyCode::Right, modifiers: KeyModifiers::empty()},
 			tree_collapse_recursive: KeyEvent { code: KeyCode::Left, modifiers: KeyModifiers::SHIFT},
@@ -128,39 +244,117 @@ impl Default for KeyConfig {
 			enter: KeyEvent { code: KeyCode::Enter, modifiers: KeyModifiers::empty()},
 			blame: KeyEvent { code: KeyCode::Char('B'), modifiers: KeyModifiers::SHIFT},
 			edit_file: KeyEvent { code: KeyCode::Char('e'), modifiers: KeyModifiers::empty()},
+			view_file: KeyEvent { code: KeyCode::Char('v'), modifiers: KeyModifiers::empty()},
 			status_stage_all: KeyEvent { code: KeyCode::Char('a'), modifiers: KeyModifiers::empty()},
 			status_reset_item: KeyEvent { code: KeyCode::Char('D'), modifiers: KeyModifiers::SHIFT},
 			diff_reset_lines: KeyEvent { code: KeyCode::Char('d'), modifiers: KeyModifiers::empty()},
 			status_ignore_file: KeyEvent { code: KeyCode::Char('i'), modifiers: KeyModifiers::empty()},
+			status_load_all_untracked: KeyEvent { code: KeyCode::Char('X'), modifiers: KeyModifiers::SHIFT},
+			status_stash: KeyEvent { code: KeyCode::Char('S'), modifiers: KeyModifiers::SHIFT},
+			status_stage_pattern: KeyEvent { code: KeyCode::Char('*'), modifiers: KeyModifiers::empty()},
 			diff_stage_lines: KeyEvent { code: KeyCode::Char('s'), modifiers: KeyModifiers::empty()},
+			diff_split_hunk: KeyEvent { code: KeyCode::Char('|'), modifiers: KeyModifiers::empty()},
+			diff_hunk_next: KeyEvent { code: KeyCode::Char(']'), modifiers: KeyModifiers::empty()},
+			diff_hunk_prev: KeyEvent { code: KeyCode::Char('['), modifiers: KeyModifiers::empty()},
+			diff_copy_hunk: KeyEvent { code: KeyCode::Char('H'), modifiers: KeyModifiers::SHIFT},
+			diff_copy_old: KeyEvent { code: KeyCode::Char('<'), modifiers: KeyModifiers::empty()},
+			diff_copy_new: KeyEvent { code: KeyCode::Char('>'), modifiers: KeyModifiers::empty()},
+			diff_search: KeyEvent { code: KeyCode::Char('/'), modifiers: KeyModifiers::empty()},
+			diff_search_next: KeyEvent { code: KeyCode::Char('n'), modifiers: KeyModifiers::empty()},
+			diff_search_prev: KeyEvent { code: KeyCode::Char('N'), modifiers: KeyModifiers::SHIFT},
+			diff_lfs_pointer: KeyEvent { code: KeyCode::Char('R'), modifiers: KeyModifiers::SHIFT},
+			diff_show_whitespace: KeyEvent { code: KeyCode::Char('_'), modifiers: KeyModifiers::empty()},
+			diff_jump_moved: KeyEvent { code: KeyCode::Char('%'), modifiers: KeyModifiers::empty()},
+			file_view_goto: KeyEvent { code: KeyCode::Char(':'), modifiers: KeyModifiers::empty()},
 			stashing_save: KeyEvent { code: KeyCode::Char('s'), modifiers: KeyModifiers::empty()},
 			stashing_toggle_untracked: KeyEvent { code: KeyCode::Char('u'), modifiers: KeyModifiers::empty()},
 			stashing_toggle_index: KeyEvent { code: KeyCode::Char('i'), modifiers: KeyModifiers::empty()},
+			stash_msg_toggle_untracked: KeyEvent { code: KeyCode::Char('u'), modifiers: KeyModifiers::CONTROL},
+			stash_msg_toggle_index: KeyEvent { code: KeyCode::Char('k'), modifiers: KeyModifiers::CONTROL},
 			stash_apply: KeyEvent { code: KeyCode::Char('a'), modifiers: KeyModifiers::empty()},
 			stash_open: KeyEvent { code: KeyCode::Right, modifiers: KeyModifiers::empty()},
 			stash_drop: KeyEvent { code: KeyCode::Char('D'), modifiers: KeyModifiers::SHIFT},
 			cmd_bar_toggle: KeyEvent { code: KeyCode::Char('.'), modifiers: KeyModifiers::empty()},
 			log_tag_commit: KeyEvent { code: KeyCode::Char('t'), modifiers: KeyModifiers::empty()},
 			log_mark_commit: KeyEvent { code: KeyCode::Char(' '), modifiers: KeyModifiers::empty()},
+			log_date_format: KeyEvent { code: KeyCode::Char('d'), modifiers: KeyModifiers::empty()},
+			log_grouping: KeyEvent { code: KeyCode::Char('g'), modifiers: KeyModifiers::empty()},
 			commit_amend: KeyEvent { code: KeyCode::Char('a'), modifiers: KeyModifiers::CONTROL},
+			commit_signoff: KeyEvent { code: KeyCode::Char('s'), modifiers: KeyModifiers::CONTROL},
+			commit_goto_changes: KeyEvent { code: KeyCode::Char('g'), modifiers: KeyModifiers::CONTROL},
+			commit_amend_dates: KeyEvent { code: KeyCode::Char('d'), modifiers: KeyModifiers::CONTROL},
 			copy: KeyEvent { code: KeyCode::Char('y'), modifiers: KeyModifiers::empty()},
 			create_branch: KeyEvent { code: KeyCode::Char('c'), modifiers: KeyModifiers::empty()},
+			create_branch_orphan: KeyEvent { code: KeyCode::Char('o'), modifiers: KeyModifiers::CONTROL},
 			rename_branch: KeyEvent { code: KeyCode::Char('r'), modifiers: KeyModifiers::empty()},
 			select_branch: KeyEvent { code: KeyCode::Char('b'), modifiers: KeyModifiers::empty()},
+			checkout_previous_branch: KeyEvent { code: KeyCode::Char('-'), modifiers: KeyModifiers::empty()},
+			recent_branches: KeyEvent { code: KeyCode::Char('b'), modifiers: KeyModifiers::CONTROL},
+			branch_name_suggestion: KeyEvent { code: KeyCode::Char('t'), modifiers: KeyModifiers::CONTROL},
+			branch_changes: KeyEvent { code: KeyCode::Char('d'), modifiers: KeyModifiers::CONTROL},
 			delete_branch: KeyEvent { code: KeyCode::Char('D'), modifiers: KeyModifiers::SHIFT},
 			merge_branch: KeyEvent { code: KeyCode::Char('m'), modifiers: KeyModifiers::empty()},
 			rebase_branch: KeyEvent { code: KeyCode::Char('R'), modifiers: KeyModifiers::SHIFT},
+			branch_sort: KeyEvent { code: KeyCode::Char('s'), modifiers: KeyModifiers::empty()},
+			branch_edit_description: KeyEvent { code: KeyCode::Char('e'), modifiers: KeyModifiers::empty()},
+			branch_toggle_descriptions: KeyEvent { code: KeyCode::Char('E'), modifiers: KeyModifiers::SHIFT},
+			branch_merged_filter: KeyEvent { code: KeyCode::Char('M'), modifiers: KeyModifiers::SHIFT},
+			branch_merged_base: KeyEvent { code: KeyCode::Char('B'), modifiers: KeyModifiers::SHIFT},
+			branch_delete_merged: KeyEvent { code: KeyCode::Char('X'), modifiers: KeyModifiers::SHIFT},
 			compare_commits: KeyEvent { code: KeyCode::Char('C'), modifiers: KeyModifiers::SHIFT},
+			compare_merge_base: KeyEvent { code: KeyCode::Char('b'), modifiers: KeyModifiers::empty()},
+			compare_uncommitted: KeyEvent { code: KeyCode::Char('u'), modifiers: KeyModifiers::empty()},
+			compare_next_base: KeyEvent { code: KeyCode::Char('b'), modifiers: KeyModifiers::empty()},
 			tags: KeyEvent { code: KeyCode::Char('T'), modifiers: KeyModifiers::SHIFT},
 			delete_tag: KeyEvent { code: KeyCode::Char('D'), modifiers: KeyModifiers::SHIFT},
+			git_config_unset: KeyEvent { code: KeyCode::Char('D'), modifiers: KeyModifiers::SHIFT},
 			select_tag: KeyEvent { code: KeyCode::Enter, modifiers: KeyModifiers::empty()},
 			push: KeyEvent { code: KeyCode::Char('p'), modifiers: KeyModifiers::empty()},
 			force_push: KeyEvent { code: KeyCode::Char('P'), modifiers: KeyModifiers::SHIFT},
+			push_preview_remote: KeyEvent { code: KeyCode::Char('r'), modifiers: KeyModifiers::empty()},
+			push_select_branch: KeyEvent { code: KeyCode::Char('b'), modifiers: KeyModifiers::empty()},
+			push_select_remote: KeyEvent { code: KeyCode::Char('o'), modifiers: KeyModifiers::empty()},
+			message_link_next: KeyEvent { code: KeyCode::Char(']'), modifiers: KeyModifiers::empty()},
+			message_link_prev: KeyEvent { code: KeyCode::Char('['), modifiers: KeyModifiers::empty()},
+			undo_index_change: KeyEvent { code: KeyCode::Char('z'), modifiers: KeyModifiers::empty()},
+			restore_discarded: KeyEvent { code: KeyCode::Char('Z'), modifiers: KeyModifiers::SHIFT},
+			undo_hunk_discard: KeyEvent { code: KeyCode::Char('U'), modifiers: KeyModifiers::SHIFT},
+			log_jump_to_ref: KeyEvent { code: KeyCode::Char('r'), modifiers: KeyModifiers::empty()},
+			bookmark_add: KeyEvent { code: KeyCode::Char('m'), modifiers: KeyModifiers::empty()},
+			open_bookmarks: KeyEvent { code: KeyCode::Char('\''), modifiers: KeyModifiers::empty()},
+			bookmark_delete: KeyEvent { code: KeyCode::Char('D'), modifiers: KeyModifiers::SHIFT},
+			status_index_flags: KeyEvent { code: KeyCode::Char('I'), modifiers: KeyModifiers::SHIFT},
+			status_skip_worktree: KeyEvent { code: KeyCode::Char('K'), modifiers: KeyModifiers::SHIFT},
+			status_resolve_conflict: KeyEvent { code: KeyCode::Char('r'), modifiers: KeyModifiers::empty()},
+			conflict_take_ours: KeyEvent { code: KeyCode::Char('o'), modifiers: KeyModifiers::empty()},
+			conflict_take_theirs: KeyEvent { code: KeyCode::Char('t'), modifiers: KeyModifiers::empty()},
+			conflict_mergetool: KeyEvent { code: KeyCode::Char('m'), modifiers: KeyModifiers::empty()},
+			index_flag_clear: KeyEvent { code: KeyCode::Char('c'), modifiers: KeyModifiers::empty()},
+			timings_reset: KeyEvent { code: KeyCode::Char('R'), modifiers: KeyModifiers::SHIFT},
 			undo_commit: KeyEvent { code: KeyCode::Char('U'), modifiers: KeyModifiers::SHIFT},
 			pull: KeyEvent { code: KeyCode::Char('f'), modifiers: KeyModifiers::empty()},
+			fetch: KeyEvent { code: KeyCode::Char('F'), modifiers: KeyModifiers::SHIFT},
+			fetch_prune: KeyEvent { code: KeyCode::Char('P'), modifiers: KeyModifiers::SHIFT},
+			compare_workdir: KeyEvent { code: KeyCode::Char('W'), modifiers: KeyModifiers::SHIFT},
 			abort_merge: KeyEvent { code: KeyCode::Char('M'), modifiers: KeyModifiers::SHIFT},
+			continue_merge: KeyEvent { code: KeyCode::Char('C'), modifiers: KeyModifiers::SHIFT},
 			open_file_tree: KeyEvent { code: KeyCode::Char('F'), modifiers: KeyModifiers::SHIFT},
 			file_find: KeyEvent { code: KeyCode::Char('f'), modifiers: KeyModifiers::empty()},
+			tree_toggle_sizes: KeyEvent { code: KeyCode::Char('s'), modifiers: KeyModifiers::empty()},
+			merge_diff_mode: KeyEvent { code: KeyCode::Char('m'), modifiers: KeyModifiers::empty()},
+			copy_absolute_path: KeyEvent { code: KeyCode::Char('Y'), modifiers: KeyModifiers::SHIFT},
+			open_containing_dir: KeyEvent { code: KeyCode::Char('O'), modifiers: KeyModifiers::SHIFT},
+			log_filter: KeyEvent { code: KeyCode::Char(':'), modifiers: KeyModifiers::empty()},
+			log_filter_author: KeyEvent { code: KeyCode::Char('a'), modifiers: KeyModifiers::empty()},
+			log_authors: KeyEvent { code: KeyCode::Char('A'), modifiers: KeyModifiers::SHIFT},
+			log_export: KeyEvent { code: KeyCode::Char('x'), modifiers: KeyModifiers::empty()},
+			rebase_autosquash: KeyEvent { code: KeyCode::Char('S'), modifiers: KeyModifiers::SHIFT},
+			cherry_pick: KeyEvent { code: KeyCode::Char('V'), modifiers: KeyModifiers::SHIFT},
+			cherry_pick_skip: KeyEvent { code: KeyCode::Char('Z'), modifiers: KeyModifiers::SHIFT},
+			bisect: KeyEvent { code: KeyCode::Char('B'), modifiers: KeyModifiers::SHIFT},
+			bisect_good: KeyEvent { code: KeyCode::Char('G'), modifiers: KeyModifiers::SHIFT},
+			bisect_bad: KeyEvent { code: KeyCode::Char('X'), modifiers: KeyModifiers::SHIFT},
+			bisect_skip: KeyEvent { code: KeyCode::Char('Z'), modifiers: KeyModifiers::SHIFT},
 		}
 	}
 }
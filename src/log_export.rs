@@ -0,0 +1,307 @@
+//! writes the (filtered) log to a file as text or csv
+
+use crate::AsyncAppNotification;
+use asyncgit::{
+	asyncjob::{AsyncJob, RunParams},
+	sync::{self, CommitId, CommitInfo},
+	CWD,
+};
+use chrono::{FixedOffset, TimeZone, Utc};
+use std::{
+	borrow::Cow,
+	fs::File,
+	io::{self, BufWriter, Write},
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex},
+};
+
+/// commits looked up (and progress reported) at once
+const CHUNK_SIZE: usize = 500;
+
+/// the date written is the author date in the commit's own offset
+const DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%:z";
+
+/// csv is chosen by a `.csv` extension, text otherwise
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LogExportFormat {
+	/// one line per commit: `<short sha> <date> [<author>] <subject>`
+	Text,
+	/// RFC 4180 (comma separated, fields with `"`, `,` or line breaks
+	/// quoted, `"` doubled, CRLF line ends) with the header row
+	/// `sha,date,author,subject`
+	Csv,
+}
+
+impl LogExportFormat {
+	pub fn from_path(path: &Path) -> Self {
+		if path
+			.extension()
+			.and_then(std::ffi::OsStr::to_str)
+			.map_or(false, |ext| ext.eq_ignore_ascii_case("csv"))
+		{
+			Self::Csv
+		} else {
+			Self::Text
+		}
+	}
+}
+
+/// expands a leading `~` to the home dir
+pub fn expand_tilde(path: &str) -> PathBuf {
+	if path == "~" || path.starts_with("~/") {
+		if let Some(home) = dirs_next::home_dir() {
+			return home.join(path[1..].trim_start_matches('/'));
+		}
+	}
+
+	PathBuf::from(path)
+}
+
+fn format_date(time: i64, offset_minutes: i32) -> String {
+	let utc = Utc.timestamp(time, 0);
+
+	FixedOffset::east_opt(offset_minutes * 60).map_or_else(
+		|| utc.format(DATE_FORMAT).to_string(),
+		|offset| {
+			utc.with_timezone(&offset).format(DATE_FORMAT).to_string()
+		},
+	)
+}
+
+fn csv_field(field: &str) -> Cow<'_, str> {
+	if field.contains(&['"', ',', '\r', '\n'][..]) {
+		Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+	} else {
+		Cow::Borrowed(field)
+	}
+}
+
+/// line breaks would start a new entry in the text format
+fn single_line(text: &str) -> Cow<'_, str> {
+	if text.contains(&['\r', '\n'][..]) {
+		Cow::Owned(text.replace(&['\r', '\n'][..], " "))
+	} else {
+		Cow::Borrowed(text)
+	}
+}
+
+fn write_header(
+	out: &mut impl Write,
+	format: LogExportFormat,
+) -> io::Result<()> {
+	match format {
+		LogExportFormat::Text => Ok(()),
+		LogExportFormat::Csv => {
+			out.write_all(b"sha,date,author,subject\r\n")
+		}
+	}
+}
+
+fn write_entry(
+	out: &mut impl Write,
+	format: LogExportFormat,
+	info: &CommitInfo,
+) -> io::Result<()> {
+	let date = format_date(info.author_time, info.author_time_offset);
+
+	match format {
+		LogExportFormat::Text => writeln!(
+			out,
+			"{} {} [{}] {}",
+			info.id.get_short_string(),
+			date,
+			single_line(&info.author),
+			single_line(&info.message)
+		),
+		LogExportFormat::Csv => write!(
+			out,
+			"{},{},{},{}\r\n",
+			info.id.to_string(),
+			date,
+			csv_field(&info.author),
+			csv_field(&info.message)
+		),
+	}
+}
+
+/// writes `ids` (in order) to `out`, `progress` is called with the
+/// number of commits written so far
+pub fn export_log(
+	repo_path: &str,
+	ids: &[CommitId],
+	format: LogExportFormat,
+	out: &mut impl Write,
+	mut progress: impl FnMut(usize),
+) -> asyncgit::Result<()> {
+	write_header(out, format)?;
+
+	let mut written = 0;
+	for chunk in ids.chunks(CHUNK_SIZE) {
+		// the limit keeps the subject only
+		for info in
+			sync::get_commits_info(repo_path, chunk, usize::MAX)?
+		{
+			write_entry(out, format, &info)?;
+		}
+
+		written += chunk.len();
+		progress(written);
+	}
+
+	out.flush()?;
+
+	Ok(())
+}
+
+enum JobState {
+	Request(Vec<CommitId>, PathBuf),
+	Response(asyncgit::Result<PathBuf>),
+}
+
+/// exports the log in the background,
+/// progress is the number of commits written
+#[derive(Clone)]
+pub struct AsyncLogExportJob {
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+impl AsyncLogExportJob {
+	///
+	pub fn new(ids: Vec<CommitId>, path: PathBuf) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request(
+				ids, path,
+			)))),
+		}
+	}
+
+	/// path written to
+	pub fn result(&self) -> Option<asyncgit::Result<PathBuf>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request(..) => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+
+	fn export(
+		ids: &[CommitId],
+		path: &Path,
+		params: &RunParams<AsyncAppNotification, usize>,
+	) -> asyncgit::Result<()> {
+		let mut out = BufWriter::new(File::create(path)?);
+
+		export_log(
+			CWD,
+			ids,
+			LogExportFormat::from_path(path),
+			&mut out,
+			|written| {
+				params.set_progress(written).ok();
+				params.send(AsyncAppNotification::LogExport).ok();
+			},
+		)
+	}
+}
+
+impl AsyncJob for AsyncLogExportJob {
+	type Notification = AsyncAppNotification;
+	type Progress = usize;
+
+	fn run(
+		&mut self,
+		params: RunParams<Self::Notification, Self::Progress>,
+	) -> asyncgit::Result<Self::Notification> {
+		let request =
+			self.state.lock().ok().and_then(|mut state| state.take());
+
+		if let Some(JobState::Request(ids, path)) = request {
+			let result =
+				Self::export(&ids, &path, &params).map(|()| path);
+
+			if let Ok(mut state) = self.state.lock() {
+				*state = Some(JobState::Response(result));
+			}
+		}
+
+		Ok(AsyncAppNotification::LogExport)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn info(author: &str, message: &str) -> CommitInfo {
+		CommitInfo {
+			message: message.to_string(),
+			time: 0,
+			time_offset: 0,
+			author_time: 1_631_268_000,
+			author_time_offset: 120,
+			author: author.to_string(),
+			id: CommitId::from_hex(
+				"0123456789abcdef0123456789abcdef01234567",
+			)
+			.unwrap(),
+		}
+	}
+
+	fn written(format: LogExportFormat, info: &CommitInfo) -> String {
+		let mut out = Vec::new();
+		write_header(&mut out, format).unwrap();
+		write_entry(&mut out, format, info).unwrap();
+		String::from_utf8(out).unwrap()
+	}
+
+	#[test]
+	fn test_format_from_path() {
+		assert_eq!(
+			LogExportFormat::from_path(Path::new("log.CSV")),
+			LogExportFormat::Csv
+		);
+		assert_eq!(
+			LogExportFormat::from_path(Path::new("log.txt")),
+			LogExportFormat::Text
+		);
+		assert_eq!(
+			LogExportFormat::from_path(Path::new("csv")),
+			LogExportFormat::Text
+		);
+	}
+
+	#[test]
+	fn test_csv_quoting() {
+		assert_eq!(
+			written(LogExportFormat::Csv, &info("Doe, Jane", "say \"hi\"\nnow")),
+			"sha,date,author,subject\r\n0123456789abcdef0123456789abcdef01234567,2021-09-10T12:00:00+02:00,\"Doe, Jane\",\"say \"\"hi\"\"\nnow\"\r\n"
+		);
+		assert_eq!(
+			written(LogExportFormat::Csv, &info("Jane", "plain")),
+			"sha,date,author,subject\r\n0123456789abcdef0123456789abcdef01234567,2021-09-10T12:00:00+02:00,Jane,plain\r\n"
+		);
+	}
+
+	#[test]
+	fn test_text() {
+		assert_eq!(
+			written(LogExportFormat::Text, &info("Doe, Jane", "a\r\nb \"c\"")),
+			"0123456 2021-09-10T12:00:00+02:00 [Doe, Jane] a  b \"c\"\n"
+		);
+	}
+
+	#[test]
+	fn test_expand_tilde() {
+		let home = dirs_next::home_dir().unwrap();
+
+		assert_eq!(expand_tilde("~"), home);
+		assert_eq!(expand_tilde("~/a/b.csv"), home.join("a/b.csv"));
+		assert_eq!(expand_tilde("a/~b"), PathBuf::from("a/~b"));
+		assert_eq!(expand_tilde("~user/a"), PathBuf::from("~user/a"));
+	}
+}
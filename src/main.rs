@@ -20,28 +20,49 @@
 
 mod app;
 mod args;
+mod bookmarks;
+mod branch_history;
 mod bug_report;
 mod clipboard;
 mod cmdbar;
+mod commit_lint;
+mod commit_prefill;
 mod components;
+mod confirm_policy;
+mod crash_report;
+mod custom_commands;
+mod file_manager;
 mod input;
 mod keys;
+mod log_export;
+mod message_markup;
+mod messages;
 mod notify_mutex;
 mod profiler;
 mod queue;
+mod refresh;
+mod repo_session;
 mod spinner;
 mod string_utils;
 mod strings;
 mod tabs;
 mod ui;
+mod ui_state;
 mod version;
 
-use crate::{app::App, args::process_cmdline};
+use crate::{
+	app::App,
+	args::process_cmdline,
+	components::{Component, DrawableComponent, InitRepoPopup},
+	repo_session::RepoSession,
+};
 use anyhow::{bail, Result};
 use asyncgit::AsyncGitNotification;
 use backtrace::Backtrace;
 use crossbeam_channel::{tick, unbounded, Receiver, Select};
 use crossterm::{
+	cursor,
+	event::DisableMouseCapture,
 	terminal::{
 		disable_raw_mode, enable_raw_mode, EnterAlternateScreen,
 		LeaveAlternateScreen,
@@ -49,27 +70,36 @@ use crossterm::{
 	ExecutableCommand,
 };
 use input::{Input, InputEvent, InputState};
-use keys::KeyConfig;
+use keys::{KeyConfig, SharedKeyConfig};
 use profiler::Profiler;
 use scopeguard::defer;
 use scopetime::scope_time;
 use spinner::Spinner;
 use std::{
+	any::Any,
+	cell::RefCell,
+	env,
 	io::{self, Write},
 	panic, process,
+	rc::Rc,
 	time::{Duration, Instant},
 };
 use tui::{
 	backend::{Backend, CrosstermBackend},
 	Terminal,
 };
-use ui::style::Theme;
+use ui::style::{SharedTheme, Theme};
 
 static TICK_INTERVAL: Duration = Duration::from_secs(5);
 static SPINNER_INTERVAL: Duration = Duration::from_millis(80);
 
+/// set to panic right after startup, tests restoring the terminal
+/// and the crash report (debug builds only)
+#[cfg(debug_assertions)]
+static DEBUG_PANIC_ENV: &str = "GITUI_DEBUG_PANIC";
+
 ///
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub enum QueueEvent {
 	Tick,
 	SpinnerUpdate,
@@ -87,6 +117,10 @@ pub enum SyntaxHighlightProgress {
 pub enum AsyncAppNotification {
 	///
 	SyntaxHighlighting(SyntaxHighlightProgress),
+	/// progress or end of a log export
+	LogExport,
+	/// a custom command running in the background finished
+	CustomCommand,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -98,66 +132,145 @@ pub enum AsyncNotification {
 }
 
 fn main() -> Result<()> {
+	let mut phase_start = Instant::now();
+
 	let cliargs = process_cmdline()?;
 
 	let _profiler = Profiler::new();
 
 	asyncgit::register_tracing_logging();
 
-	if !valid_path()? {
-		eprintln!("invalid path\nplease run gitui inside of a non-bare git repository");
-		return Ok(());
-	}
+	let key_config = Rc::new(
+		KeyConfig::init(KeyConfig::get_config_file()?)
+			.map_err(|e| eprintln!("KeyConfig loading error: {}", e))
+			.unwrap_or_default(),
+	);
+	let theme = Rc::new(
+		Theme::init(cliargs.theme)
+			.map_err(|e| eprintln!("Theme loading error: {}", e))
+			.unwrap_or_default(),
+	);
 
-	let key_config = KeyConfig::init(KeyConfig::get_config_file()?)
-		.map_err(|e| eprintln!("KeyConfig loading error: {}", e))
-		.unwrap_or_default();
-	let theme = Theme::init(cliargs.theme)
-		.map_err(|e| eprintln!("Theme loading error: {}", e))
-		.unwrap_or_default();
+	log_startup_phase("config", &mut phase_start);
 
 	setup_terminal()?;
+	// also restores the terminal when unwinding from a panic
 	defer! {
 		shutdown_terminal();
 	}
-
 	set_panic_handlers()?;
 
 	let mut terminal = start_terminal(io::stdout())?;
 
+	log_startup_phase("terminal", &mut phase_start);
+
 	let (tx_git, rx_git) = unbounded();
 	let (tx_app, rx_app) = unbounded();
 
 	let input = Input::new();
 
 	let rx_input = input.receiver();
+
+	if !valid_path()? && cliargs.readonly {
+		bail!("not a git repository, nothing to browse in read-only mode");
+	}
+
+	if !valid_path()?
+		&& !init_repo_prompt(
+			&mut terminal,
+			&rx_input,
+			&theme,
+			&key_config,
+		)? {
+		return Ok(());
+	}
+
 	let ticker = tick(TICK_INTERVAL);
 	let spinner_ticker = tick(SPINNER_INTERVAL);
 
-	let mut app =
-		App::new(&tx_git, &tx_app, input, theme, key_config);
+	let repo_session = Rc::new(RefCell::new(RepoSession::new(
+		cliargs.repos,
+		cliargs.readonly,
+	)));
+	let mut input = input;
+
+	loop {
+		let mut app = App::new(
+			&tx_git,
+			&tx_app,
+			input,
+			theme.clone(),
+			key_config.clone(),
+			repo_session.clone(),
+		);
+
+		log_startup_phase("app", &mut phase_start);
+
+		run_app(
+			&mut terminal,
+			&mut app,
+			&rx_input,
+			&rx_git,
+			&rx_app,
+			&ticker,
+			&spinner_ticker,
+			&mut phase_start,
+		)?;
+
+		app.save_ui_state();
+
+		// the async jobs of the old repo go away with its app
+		match app.take_repo_to_open() {
+			Some(dir) => {
+				if let Err(e) = env::set_current_dir(&dir) {
+					log::error!("switch to {}: {}", dir.display(), e);
+				}
+				input = app.into_input();
+			}
+			None => return Ok(()),
+		}
+	}
+}
+
+/// runs the app of the open repo until it quits
+#[allow(clippy::too_many_arguments)]
+fn run_app<B: Backend>(
+	terminal: &mut Terminal<B>,
+	app: &mut App,
+	rx_input: &Receiver<InputEvent>,
+	rx_git: &Receiver<AsyncGitNotification>,
+	rx_app: &Receiver<AsyncAppNotification>,
+	ticker: &Receiver<Instant>,
+	spinner_ticker: &Receiver<Instant>,
+	phase_start: &mut Instant,
+) -> Result<()> {
+	// tabs show their loading state until the first update kicked
+	// off the async jobs feeding them
+	draw(terminal, app)?;
+
+	log_startup_phase("first draw", phase_start);
 
 	let mut spinner = Spinner::default();
 	let mut first_update = true;
+	let mut frames = FrameCounter::default();
 
 	loop {
 		let event = if first_update {
-			first_update = false;
 			QueueEvent::Tick
 		} else {
 			select_event(
-				&rx_input,
-				&rx_git,
-				&rx_app,
-				&ticker,
-				&spinner_ticker,
+				rx_input,
+				rx_git,
+				rx_app,
+				ticker,
+				spinner_ticker,
 			)?
 		};
 
 		{
 			if let QueueEvent::SpinnerUpdate = event {
 				spinner.update();
-				spinner.draw(&mut terminal)?;
+				spinner.draw(terminal)?;
 				continue;
 			}
 
@@ -165,12 +278,16 @@ fn main() -> Result<()> {
 
 			match event {
 				QueueEvent::InputEvent(ev) => {
-					if let InputEvent::State(InputState::Polling) = ev
-					{
-						//Note: external ed closed, we need to re-hide cursor
-						terminal.hide_cursor()?;
+					handle_input(terminal, app, ev)?;
+
+					// a burst of input (key repeat, pasting) is
+					// drawn once
+					while let Ok(ev) = rx_input.try_recv() {
+						if app.is_quit() {
+							break;
+						}
+						handle_input(terminal, app, ev)?;
 					}
-					app.event(ev)?;
 				}
 				QueueEvent::Tick => app.update()?,
 				QueueEvent::AsyncEvent(ev) => {
@@ -186,18 +303,64 @@ fn main() -> Result<()> {
 				QueueEvent::SpinnerUpdate => unreachable!(),
 			}
 
-			draw(&mut terminal, &app)?;
+			if app.needs_draw() {
+				draw(terminal, app)?;
+				frames.drawn();
+			}
+
+			if first_update {
+				first_update = false;
+				log_startup_phase("first update", phase_start);
+
+				#[cfg(debug_assertions)]
+				debug_panic();
+			}
 
 			spinner.set_state(app.any_work_pending());
-			spinner.draw(&mut terminal)?;
+			spinner.draw(terminal)?;
 
 			if app.is_quit() {
-				break;
+				return Ok(());
 			}
 		}
 	}
+}
 
-	Ok(())
+fn handle_input<B: Backend>(
+	terminal: &mut Terminal<B>,
+	app: &mut App,
+	ev: InputEvent,
+) -> Result<()> {
+	if let InputEvent::State(InputState::Polling) = ev {
+		//Note: external ed closed, we need to re-hide cursor
+		terminal.hide_cursor()?;
+	}
+
+	app.event(ev)
+}
+
+/// counts the frames drawn in the main loop (debug log only)
+#[derive(Default)]
+struct FrameCounter {
+	count: usize,
+	last: Option<Instant>,
+}
+
+impl FrameCounter {
+	fn drawn(&mut self) {
+		self.count += 1;
+		log::debug!(
+			"frame {} ({:?} since the last one)",
+			self.count,
+			self.last.map(|last| last.elapsed()).unwrap_or_default()
+		);
+		self.last = Some(Instant::now());
+	}
+}
+
+fn log_startup_phase(phase: &str, phase_start: &mut Instant) {
+	log::debug!("startup {}: {:?}", phase, phase_start.elapsed());
+	*phase_start = Instant::now();
 }
 
 fn setup_terminal() -> Result<()> {
@@ -207,8 +370,11 @@ fn setup_terminal() -> Result<()> {
 }
 
 fn shutdown_terminal() {
-	let leave_screen =
-		io::stdout().execute(LeaveAlternateScreen).map(|_f| ());
+	let leave_screen = io::stdout()
+		.execute(DisableMouseCapture)
+		.and_then(|stdout| stdout.execute(LeaveAlternateScreen))
+		.and_then(|stdout| stdout.execute(cursor::Show))
+		.map(|_f| ());
 
 	if let Err(e) = leave_screen {
 		eprintln!("leave_screen failed:\n{}", e);
@@ -221,6 +387,15 @@ fn shutdown_terminal() {
 	}
 }
 
+#[cfg(debug_assertions)]
+fn debug_panic() {
+	assert!(
+		std::env::var_os(DEBUG_PANIC_ENV).is_none(),
+		"deliberate panic ({} is set)",
+		DEBUG_PANIC_ENV
+	);
+}
+
 fn draw<B: Backend>(
 	terminal: &mut Terminal<B>,
 	app: &App,
@@ -229,8 +404,11 @@ fn draw<B: Backend>(
 		terminal.resize(terminal.size()?)?;
 	}
 
-	terminal.draw(|mut f| {
-		if let Err(e) = app.draw(&mut f) {
+	terminal.draw(|f| {
+		// nothing but the text, for the terminal to select from
+		if let Some(lines) = app.selection_text() {
+			ui::draw_selection_mode(f, lines, &app.selection_hint());
+		} else if let Err(e) = app.draw(f) {
 			log::error!("failed to draw: {:?}", e);
 		}
 	})?;
@@ -238,6 +416,40 @@ fn draw<B: Backend>(
 	Ok(())
 }
 
+/// runs until the user initialized a repo in the working dir
+/// (`true`) or quit
+fn init_repo_prompt<B: Backend>(
+	terminal: &mut Terminal<B>,
+	rx_input: &Receiver<InputEvent>,
+	theme: &SharedTheme,
+	key_config: &SharedKeyConfig,
+) -> Result<bool> {
+	let mut prompt =
+		InitRepoPopup::new(theme.clone(), key_config.clone())?;
+
+	loop {
+		terminal.draw(|f| {
+			if let Err(e) = prompt.draw(f, f.size()) {
+				log::error!("failed to draw: {:?}", e);
+			}
+		})?;
+
+		match rx_input.recv()? {
+			InputEvent::Input(ev) => {
+				prompt.event(ev)?;
+			}
+			InputEvent::Paste(text) => {
+				prompt.paste(&text)?;
+			}
+			InputEvent::State(_) => (),
+		}
+
+		if let Some(initialized) = prompt.result() {
+			return Ok(initialized);
+		}
+	}
+}
+
 fn valid_path() -> Result<bool> {
 	Ok(asyncgit::sync::is_repo(asyncgit::CWD)
 		&& !asyncgit::sync::is_bare_repo(asyncgit::CWD)?)
@@ -288,24 +500,43 @@ fn start_terminal<W: Write>(
 	Ok(terminal)
 }
 
+/// restores the terminal before printing, so the output is not lost
+/// on the alternate screen
+fn report_panic(message: &str) {
+	let backtrace = format!("{:?}", Backtrace::new());
+
+	shutdown_terminal();
+
+	//TODO: create macro to do both in one
+	log::error!("panic: {}\ntrace:\n{}", message, backtrace);
+	eprintln!("panic: {}\ntrace:\n{}", message, backtrace);
+
+	match crash_report::write(message, &backtrace) {
+		Ok(path) => {
+			eprintln!("crash report written to: {}", path.display());
+		}
+		Err(e) => eprintln!("failed to write crash report: {}", e),
+	}
+}
+
+fn panic_payload_message(payload: &(dyn Any + Send)) -> String {
+	payload
+		.downcast_ref::<&str>()
+		.map(|s| (*s).to_string())
+		.or_else(|| payload.downcast_ref::<String>().cloned())
+		.unwrap_or_else(|| String::from("unknown panic payload"))
+}
+
 fn set_panic_handlers() -> Result<()> {
 	// regular panic handler
 	panic::set_hook(Box::new(|e| {
-		let backtrace = Backtrace::new();
-		//TODO: create macro to do both in one
-		log::error!("panic: {:?}\ntrace:\n{:?}", e, backtrace);
-		eprintln!("panic: {:?}\ntrace:\n{:?}", e, backtrace);
-		shutdown_terminal();
+		report_panic(&e.to_string());
 	}));
 
 	// global threadpool
 	rayon_core::ThreadPoolBuilder::new()
 		.panic_handler(|e| {
-			let backtrace = Backtrace::new();
-			//TODO: create macro to do both in one
-			log::error!("panic: {:?}\ntrace:\n{:?}", e, backtrace);
-			eprintln!("panic: {:?}\ntrace:\n{:?}", e, backtrace);
-			shutdown_terminal();
+			report_panic(&panic_payload_message(e.as_ref()));
 			process::abort();
 		})
 		.num_threads(4)
@@ -0,0 +1,598 @@
+//! lightweight formatting of commit messages: list items, `code`
+//! spans and links for urls and issue references. only styles the
+//! text, every char of the message is kept where it is
+
+use crate::commit_prefill::{expand, placeholders};
+use anyhow::{bail, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+lazy_static! {
+	static ref URL: Regex =
+		Regex::new(r"\b(?:https?|ftp)://[^\s<>]+")
+			.expect("url regex");
+}
+
+/// persisted in `issue_references.ron`, e.g. `#(\d+)` linking to
+/// `https://github.com/org/repo/issues/{1}`
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct IssueReferenceConfig {
+	pub pattern: String,
+	/// `{n}` is replaced with capture group `n`
+	pub url: String,
+}
+
+/// compiled `IssueReferenceConfig`
+#[derive(Clone, Debug)]
+pub struct IssueReference {
+	pattern: Regex,
+	url: String,
+}
+
+impl IssueReference {
+	/// fails on an invalid pattern or if the url refers to a group
+	/// the pattern does not have
+	pub fn new(config: &IssueReferenceConfig) -> Result<Self> {
+		let pattern = Regex::new(&config.pattern)?;
+		let groups = pattern.captures_len() - 1;
+
+		if let Some(group) = placeholders(&config.url)
+			.into_iter()
+			.find(|group| *group > groups)
+		{
+			bail!(
+				"url uses {{{}}} but the pattern has {} capture group(s)",
+				group,
+				groups
+			);
+		}
+
+		Ok(Self {
+			pattern,
+			url: config.url.clone(),
+		})
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MarkupKind {
+	Text,
+	/// `-`, `*`, `+` or `1.` starting a list item
+	ListMarker,
+	/// including the backticks
+	Code,
+	/// index into the links of the message
+	Link(usize),
+}
+
+/// a link as found in the message
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Link {
+	pub url: String,
+	/// wrapped line the link starts on
+	pub line: usize,
+}
+
+/// the wrapped lines of a message split into their styled parts
+#[derive(Default, Debug)]
+pub struct MessageMarkup {
+	pub lines: Vec<Vec<(String, MarkupKind)>>,
+	pub links: Vec<Link>,
+}
+
+/// wraps `body` like `textwrap::wrap` does, continuation lines of a
+/// list item are indented to its text
+pub fn wrap_body(
+	body: &str,
+	width: usize,
+	references: &[IssueReference],
+) -> MessageMarkup {
+	let mut markup = MessageMarkup::default();
+
+	for line in body.split('\n') {
+		let (parts, urls) = markup_line(line, references);
+		let first_link = markup.links.len();
+
+		let indent = list_indent(line)
+			.filter(|indent| indent * 2 < width)
+			.unwrap_or(0);
+		let indent = " ".repeat(indent);
+		let options = textwrap::Options::new(width.max(1))
+			.subsequent_indent(&indent);
+
+		let mut offset = 0;
+		for (i, piece) in
+			textwrap::wrap(line, options).iter().enumerate()
+		{
+			let mut out = Vec::new();
+			let mut text: &str = piece;
+
+			if i > 0 && !indent.is_empty() {
+				out.push((indent.clone(), MarkupKind::Text));
+				text = text
+					.strip_prefix(indent.as_str())
+					.unwrap_or(text);
+			}
+
+			if let Some(pos) = line[offset..].find(text) {
+				let start = offset + pos;
+				let end = start + text.len();
+
+				for (range, kind) in &parts {
+					let (from, to) =
+						(range.start.max(start), range.end.min(end));
+					if from >= to {
+						continue;
+					}
+
+					let kind = match *kind {
+						MarkupKind::Link(idx) => {
+							let idx = first_link + idx;
+							if idx == markup.links.len() {
+								markup.links.push(Link {
+									url: urls[idx - first_link]
+										.clone(),
+									line: markup.lines.len(),
+								});
+							}
+							MarkupKind::Link(idx)
+						}
+						kind => kind,
+					};
+					out.push((line[from..to].to_string(), kind));
+				}
+
+				offset = end;
+			} else {
+				out.push((text.to_string(), MarkupKind::Text));
+			}
+
+			markup.lines.push(out);
+		}
+	}
+
+	markup
+}
+
+/// width of the marker of a list item including the whitespace
+/// around it, `None` if `line` is no list item
+fn list_indent(line: &str) -> Option<usize> {
+	let (start, end) = list_marker(line)?;
+	let rest = &line[end..];
+	let spaces = rest.len() - rest.trim_start_matches(' ').len();
+
+	Some(line[..start].chars().count() + (end - start) + spaces)
+}
+
+/// byte range of the list marker of `line`
+fn list_marker(line: &str) -> Option<(usize, usize)> {
+	let start = line.len() - line.trim_start_matches(' ').len();
+	let rest = &line[start..];
+
+	let len =
+		if rest.starts_with(|c| c == '-' || c == '*' || c == '+') {
+			1
+		} else {
+			let digits = rest.len()
+				- rest
+					.trim_start_matches(|c: char| c.is_ascii_digit())
+					.len();
+			let after = &rest[digits..];
+			if digits == 0
+				|| digits > 9
+				|| !(after.starts_with('.') || after.starts_with(')'))
+			{
+				return None;
+			}
+			digits + 1
+		};
+
+	// a marker needs text after it, `---` or `*emphasis*` are none
+	rest[len..]
+		.starts_with(' ')
+		.then(|| (start, start + len))
+		.filter(|_| !rest[len..].trim().is_empty())
+}
+
+/// splits `line` into consecutive ranges covering all of it and the
+/// urls of the links among them
+pub fn markup_line(
+	line: &str,
+	references: &[IssueReference],
+) -> (Vec<(Range<usize>, MarkupKind)>, Vec<String>) {
+	let mut marked = Vec::new();
+
+	let list_item = list_marker(line).map(|(start, end)| start..end);
+	if let Some(marker) = &list_item {
+		marked.push((marker.clone(), MarkupKind::ListMarker));
+	}
+
+	let code = code_spans(line);
+	marked.extend(
+		code.iter().map(|range| (range.clone(), MarkupKind::Code)),
+	);
+
+	let mut urls = Vec::new();
+	for (range, url) in find_links(line, references) {
+		if code
+			.iter()
+			.chain(&list_item)
+			.any(|other| overlaps(other, &range))
+		{
+			continue;
+		}
+
+		marked.push((range, MarkupKind::Link(urls.len())));
+		urls.push(url);
+	}
+
+	marked.sort_by_key(|(range, _)| range.start);
+
+	let mut parts = Vec::with_capacity(marked.len() * 2 + 1);
+	let mut offset = 0;
+	for (range, kind) in marked {
+		if range.start > offset {
+			parts.push((offset..range.start, MarkupKind::Text));
+		}
+		offset = range.end;
+		parts.push((range, kind));
+	}
+	if offset < line.len() {
+		parts.push((offset..line.len(), MarkupKind::Text));
+	}
+
+	(parts, urls)
+}
+
+const fn overlaps(a: &Range<usize>, b: &Range<usize>) -> bool {
+	a.start < b.end && b.start < a.end
+}
+
+/// like markdown a run of backticks is closed by a run of the same
+/// length, unclosed ones are plain text
+fn code_spans(line: &str) -> Vec<Range<usize>> {
+	let mut spans = Vec::new();
+	let mut pos = 0;
+
+	while let Some(start) = line[pos..].find('`').map(|i| pos + i) {
+		let ticks = backtick_run(&line[start..]);
+		let mut search = start + ticks;
+		pos = search;
+
+		while let Some(end) =
+			line[search..].find('`').map(|i| search + i)
+		{
+			let run = backtick_run(&line[end..]);
+			if run == ticks {
+				spans.push(start..end + run);
+				pos = end + run;
+				break;
+			}
+			search = end + run;
+		}
+	}
+
+	spans
+}
+
+fn backtick_run(text: &str) -> usize {
+	text.len() - text.trim_start_matches('`').len()
+}
+
+/// urls and issue references of `line` without overlaps: the one
+/// starting first wins, the longer one if they start together
+fn find_links(
+	line: &str,
+	references: &[IssueReference],
+) -> Vec<(Range<usize>, String)> {
+	let mut candidates = URL
+		.find_iter(line)
+		.map(|m| {
+			let url = trim_url(m.as_str());
+			(m.start()..m.start() + url.len(), url.to_string())
+		})
+		.collect::<Vec<_>>();
+
+	for reference in references {
+		candidates.extend(
+			reference.pattern.captures_iter(line).filter_map(
+				|captures| {
+					let m = captures.get(0)?;
+					(!m.as_str().is_empty()).then(|| {
+						(m.range(), expand(&reference.url, &captures))
+					})
+				},
+			),
+		);
+	}
+
+	// stable, so on a tie urls win over references and references
+	// over later ones
+	candidates.sort_by_key(|(range, _)| {
+		(range.start, std::cmp::Reverse(range.end))
+	});
+
+	let mut links: Vec<(Range<usize>, String)> = Vec::new();
+	for (range, url) in candidates {
+		if links
+			.last()
+			.map_or(true, |(last, _)| last.end <= range.start)
+		{
+			links.push((range, url));
+		}
+	}
+
+	links
+}
+
+/// drops punctuation following a url, closing parentheses only if
+/// they are not part of it (like in wikipedia links)
+fn trim_url(url: &str) -> &str {
+	let mut url = url;
+
+	loop {
+		let trimmed = url.trim_end_matches(|c| {
+			matches!(
+				c,
+				'.' | ',' | ';' | ':' | '!' | '?' | '\'' | '"'
+			)
+		});
+
+		let unbalanced = trimmed.ends_with(')')
+			&& trimmed.matches(')').count()
+				> trimmed.matches('(').count();
+
+		url = if unbalanced {
+			&trimmed[..trimmed.len() - 1]
+		} else {
+			trimmed
+		};
+
+		if url.len() == trimmed.len() {
+			return url;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	fn reference(pattern: &str, url: &str) -> IssueReference {
+		IssueReference::new(&IssueReferenceConfig {
+			pattern: pattern.to_string(),
+			url: url.to_string(),
+		})
+		.unwrap()
+	}
+
+	fn github() -> IssueReference {
+		reference(r"#(\d+)", "https://github.com/o/r/issues/{1}")
+	}
+
+	fn jira() -> IssueReference {
+		reference(r"\b([A-Z]+-\d+)\b", "https://jira/browse/{1}")
+	}
+
+	/// text of each part with its kind, links by url
+	fn parts(
+		line: &str,
+		references: &[IssueReference],
+	) -> Vec<(String, String)> {
+		let (parts, urls) = markup_line(line, references);
+
+		// nothing dropped or duplicated
+		assert_eq!(
+			parts
+				.iter()
+				.map(|(range, _)| &line[range.clone()])
+				.collect::<String>(),
+			line
+		);
+
+		parts
+			.into_iter()
+			.filter(|(_, kind)| *kind != MarkupKind::Text)
+			.map(|(range, kind)| {
+				let kind = match kind {
+					MarkupKind::Link(idx) => urls[idx].clone(),
+					kind => format!("{:?}", kind),
+				};
+				(line[range].to_string(), kind)
+			})
+			.collect()
+	}
+
+	fn part(text: &str, kind: &str) -> (String, String) {
+		(text.to_string(), kind.to_string())
+	}
+
+	#[test]
+	fn test_references() {
+		assert_eq!(
+			parts("fixes #12 and ABC-3.", &[github(), jira()]),
+			vec![
+				part("#12", "https://github.com/o/r/issues/12"),
+				part("ABC-3", "https://jira/browse/ABC-3"),
+			]
+		);
+		assert_eq!(parts("fixes #12", &[]), vec![]);
+		assert_eq!(
+			parts("nothing here", &[github(), jira()]),
+			vec![]
+		);
+	}
+
+	#[test]
+	fn test_urls() {
+		assert_eq!(
+			parts(
+				"see https://example.com/a?b=1#x, (https://en.wikipedia.org/wiki/Rust_(language)).",
+				&[]
+			),
+			vec![
+				part(
+					"https://example.com/a?b=1#x",
+					"https://example.com/a?b=1#x"
+				),
+				part(
+					"https://en.wikipedia.org/wiki/Rust_(language)",
+					"https://en.wikipedia.org/wiki/Rust_(language)"
+				),
+			]
+		);
+	}
+
+	#[test]
+	fn test_overlapping() {
+		// the url starts first
+		assert_eq!(
+			parts("https://github.com/o/r/pull/5#12", &[github()]),
+			vec![part(
+				"https://github.com/o/r/pull/5#12",
+				"https://github.com/o/r/pull/5#12"
+			)]
+		);
+
+		// same start, the longer match wins
+		let short = reference(r"AB-\d", "short/{0}");
+		let long = reference(r"AB-\d+", "long/{0}");
+		assert_eq!(
+			parts("AB-12", &[short.clone(), long.clone()]),
+			vec![part("AB-12", "long/AB-12")]
+		);
+
+		// same match, the first configured wins
+		assert_eq!(
+			parts("AB-1", &[short, long]),
+			vec![part("AB-1", "short/AB-1")]
+		);
+
+		// the earlier match hides the one starting inside it
+		assert_eq!(
+			parts(
+				"GH-#7",
+				&[
+					reference(r"GH-#\d", "gh/{0}"),
+					reference(r"#\d", "hash/{0}")
+				]
+			),
+			vec![part("GH-#7", "gh/GH-#7")]
+		);
+	}
+
+	#[test]
+	fn test_code_spans() {
+		assert_eq!(
+			parts("use `#1` or ``a ` #2`` for #3", &[github()]),
+			vec![
+				part("`#1`", "Code"),
+				part("``a ` #2``", "Code"),
+				part("#3", "https://github.com/o/r/issues/3"),
+			]
+		);
+
+		// a link reaching into a code span is no link
+		assert_eq!(
+			parts("https://x.org/`a` b", &[]),
+			vec![part("`a`", "Code")]
+		);
+
+		// unclosed backticks are text
+		assert_eq!(
+			parts("a ` b #4", &[github()]),
+			vec![part("#4", "https://github.com/o/r/issues/4")]
+		);
+	}
+
+	#[test]
+	fn test_list_markers() {
+		assert_eq!(
+			parts("- item `x`", &[]),
+			vec![part("-", "ListMarker"), part("`x`", "Code")]
+		);
+		assert_eq!(
+			parts("  12. item", &[]),
+			vec![part("12.", "ListMarker")]
+		);
+		assert_eq!(
+			parts("1) item", &[]),
+			vec![part("1)", "ListMarker")]
+		);
+		assert_eq!(parts("---", &[]), vec![]);
+		assert_eq!(parts("*bold*", &[]), vec![]);
+		assert_eq!(parts("-1 is negative", &[]), vec![]);
+	}
+
+	#[test]
+	fn test_invalid_reference() {
+		assert!(IssueReference::new(&IssueReferenceConfig {
+			pattern: String::from("(unclosed"),
+			url: String::new(),
+		})
+		.is_err());
+
+		assert!(IssueReference::new(&IssueReferenceConfig {
+			pattern: String::from(r"#(\d+)"),
+			url: String::from("{2}"),
+		})
+		.is_err());
+	}
+
+	fn wrapped(markup: &MessageMarkup) -> Vec<String> {
+		markup
+			.lines
+			.iter()
+			.map(|line| {
+				line.iter().map(|(text, _)| text.as_str()).collect()
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_wrap_like_textwrap() {
+		let body = "first line is long\n\nsee `a b c` #1 and more";
+		let markup = wrap_body(body, 10, &[github()]);
+
+		assert_eq!(wrapped(&markup), textwrap::wrap(body, 10));
+		assert_eq!(
+			markup.links,
+			vec![Link {
+				url: String::from("https://github.com/o/r/issues/1"),
+				line: 4,
+			}]
+		);
+	}
+
+	#[test]
+	fn test_wrap_list_item() {
+		let markup = wrap_body("- one two three\nfour", 8, &[]);
+
+		assert_eq!(
+			wrapped(&markup),
+			vec!["- one", "  two", "  three", "four"]
+		);
+		assert_eq!(markup.lines[0][0].1, MarkupKind::ListMarker);
+	}
+
+	#[test]
+	fn test_wrap_split_link() {
+		let markup = wrap_body("a https://x.org/abcdefgh", 12, &[]);
+
+		// both pieces belong to the same link
+		assert_eq!(markup.links.len(), 1);
+		assert_eq!(
+			markup
+				.lines
+				.iter()
+				.flatten()
+				.filter(|(_, kind)| *kind == MarkupKind::Link(0))
+				.map(|(text, _)| text.as_str())
+				.collect::<String>(),
+			"https://x.org/abcdefgh"
+		);
+	}
+}
@@ -0,0 +1,97 @@
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+
+/// oldest messages are dropped beyond this
+pub const MESSAGE_HISTORY_CAPACITY: usize = 200;
+
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+	Info,
+	Success,
+	Error,
+}
+
+///
+#[derive(Debug, Clone)]
+pub struct Message {
+	pub time: DateTime<Local>,
+	pub severity: Severity,
+	pub text: String,
+}
+
+/// bounded history of every message shown to the user
+pub struct MessageHistory {
+	entries: VecDeque<Message>,
+	capacity: usize,
+}
+
+impl MessageHistory {
+	///
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			entries: VecDeque::with_capacity(capacity),
+			capacity,
+		}
+	}
+
+	///
+	pub fn push(&mut self, severity: Severity, text: &str) {
+		if self.entries.len() >= self.capacity {
+			self.entries.pop_front();
+		}
+
+		self.entries.push_back(Message {
+			time: Local::now(),
+			severity,
+			text: text.to_string(),
+		});
+	}
+
+	///
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	///
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// `idx` counts from the newest message
+	pub fn get(&self, idx: usize) -> Option<&Message> {
+		self.entries.iter().rev().nth(idx)
+	}
+
+	/// newest first
+	pub fn iter(&self) -> impl Iterator<Item = &Message> {
+		self.entries.iter().rev()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_bounded() {
+		let mut history = MessageHistory::new(2);
+
+		history.push(Severity::Error, "a");
+		history.push(Severity::Info, "b");
+		history.push(Severity::Success, "c");
+
+		assert_eq!(history.len(), 2);
+		assert_eq!(history.get(0).unwrap().text, "c");
+		assert_eq!(
+			history.get(0).unwrap().severity,
+			Severity::Success
+		);
+		assert_eq!(history.get(1).unwrap().text, "b");
+		assert!(history.get(2).is_none());
+
+		let texts: Vec<_> =
+			history.iter().map(|m| m.text.as_str()).collect();
+		assert_eq!(texts, vec!["c", "b"]);
+	}
+}
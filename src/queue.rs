@@ -1,10 +1,26 @@
-use crate::{components::AppOption, tabs::StashingOptions};
-use asyncgit::sync::{
-	diff::DiffLinePosition, CommitId, CommitTags, TreeFile,
+use crate::{
+	bookmarks::Location,
+	components::{AppOption, CommandText, DateRange, FileSource},
+	confirm_policy::DestructiveAction,
+	crash_report,
+	messages::{MessageHistory, Severity, MESSAGE_HISTORY_CAPACITY},
+	refresh::RefreshDomains,
+	tabs::StashingOptions,
+};
+use asyncgit::{
+	cherry_pick::CherryPickRequest,
+	sync::{
+		diff::DiffLinePosition, CommitId, CommitTags, ConflictSide,
+		IndexSnapshot, LargeFile, PreflightIssue, RefTarget,
+		RepoState, StaleState, TreeFile,
+	},
 };
 use bitflags::bitflags;
 use std::{
-	cell::RefCell, collections::VecDeque, path::PathBuf, rc::Rc,
+	cell::{Ref, RefCell},
+	collections::VecDeque,
+	path::PathBuf,
+	rc::Rc,
 };
 
 bitflags! {
@@ -18,6 +34,8 @@ bitflags! {
 		const COMMANDS = 0b100;
 		/// branches have changed
 		const BRANCHES = 0b1000;
+		/// domains marked for refresh are due now (app::update)
+		const REFRESH = 0b1_0000;
 	}
 }
 
@@ -27,8 +45,25 @@ pub struct ResetItem {
 	pub path: String,
 	/// are talking about a folder here? otherwise it's a single file
 	pub is_folder: bool,
+	/// number of files reset, all below it for a folder
+	pub files: usize,
+}
+
+/// a line of a file at a revision, where a view navigated to opens
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileLine {
+	/// repo relative
+	pub path: String,
+	/// 1-based, `None` for the top of the file
+	pub line: Option<usize>,
+	/// `None` for HEAD
+	pub commit: Option<CommitId>,
 }
 
+/// index change that can be run again, e.g. after another
+/// git process released the index
+pub type IndexOp = Rc<dyn Fn() -> asyncgit::Result<()>>;
+
 ///
 pub enum Action {
 	Reset(ResetItem),
@@ -37,10 +72,94 @@ pub enum Action {
 	StashDrop(Vec<CommitId>),
 	StashPop(CommitId),
 	DeleteBranch(String, bool),
+	/// local branches by reference, deleted one after another
+	DeleteMergedBranches(Vec<String>),
 	DeleteTag(String),
 	ForcePush(String, bool),
-	PullMerge { incoming: usize, rebase: bool },
-	AbortMerge,
+	/// push (or force push) to a branch matching the protected patterns
+	PushProtected(String, bool),
+	PullMerge {
+		incoming: usize,
+		rebase: bool,
+	},
+	AbortRepoState(RepoState),
+	RetryIndexOp(IndexOp),
+	/// staging files that should go into lfs with their raw content,
+	/// see `lfs_unconverted`
+	StageLfsUnconverted(Vec<String>, IndexOp),
+	/// staging files of at least the large file threshold, see
+	/// `large_files_to_stage`
+	StageLargeFiles(Vec<LargeFile>, IndexOp),
+	/// committing staged files of at least the large file threshold,
+	/// see `staged_large_files`
+	CommitLargeFiles(Vec<LargeFile>),
+	/// committing with nothing staged
+	CommitEmpty,
+	CleanupStaleState(StaleState),
+	/// export the log to an existing file
+	LogExportOverwrite(PathBuf),
+	/// rebase onto the commit squashing fixups, the name is shown
+	/// when confirming
+	AutosquashRebase(CommitId, String),
+	/// first bad commit and how it is shown, confirming ends the
+	/// bisect and selects it in the log
+	BisectFound(CommitId, String),
+	BisectReset,
+	/// patch of a discarded hunk that could not be applied again and
+	/// why, confirming copies the patch to the clipboard
+	CopyDiscardedHunk(String, String),
+	/// hides the working dir changes of a file by flagging it
+	/// `skip-worktree`
+	SkipWorktree(String),
+	/// conflicted file resolved to the whole file of one side
+	ResolveConflict(String, ConflictSide),
+	/// branch (ref) checked out in another worktree (dir) renamed
+	/// to the name anyway
+	RenameCheckedOutBranch(String, String, String),
+}
+
+impl Action {
+	/// kind and number of affected items for the confirm policy,
+	/// `None` for actions that are always confirmed
+	pub fn destructive(&self) -> Option<(DestructiveAction, usize)> {
+		match self {
+			Self::Reset(item) if item.is_folder => {
+				Some((DestructiveAction::DiscardAll, item.files))
+			}
+			Self::Reset(_)
+			| Self::ResetLines(..)
+			| Self::ResolveConflict(..) => {
+				Some((DestructiveAction::DiscardFile, 1))
+			}
+			Self::ResetHunk(..) => {
+				Some((DestructiveAction::DiscardHunk, 1))
+			}
+			Self::AbortRepoState(_) => {
+				Some((DestructiveAction::HardReset, 1))
+			}
+			Self::DeleteBranch(..) => {
+				Some((DestructiveAction::DeleteBranch, 1))
+			}
+			Self::DeleteMergedBranches(refs) => {
+				Some((DestructiveAction::DeleteBranch, refs.len()))
+			}
+			Self::ForcePush(..) => {
+				Some((DestructiveAction::ForcePush, 1))
+			}
+			Self::StashDrop(ids) => {
+				Some((DestructiveAction::StashDrop, ids.len()))
+			}
+			_ => None,
+		}
+	}
+
+	/// changes the repo, rejected in read-only mode
+	pub const fn mutating(&self) -> bool {
+		!matches!(
+			self,
+			Self::LogExportOverwrite(_) | Self::CopyDiscardedHunk(..)
+		)
+	}
 }
 
 ///
@@ -52,7 +171,15 @@ pub enum InternalEvent {
 	///
 	ShowErrorMsg(String),
 	///
+	ShowInfoMsg(String),
+	/// briefly shown in the command bar
+	ShowToast(Severity, String),
+	///
 	Update(NeedsUpdate),
+	/// after a git action of our own: refreshes just these domains
+	/// and lists keep their place, the status follows the selected
+	/// file and the log the selected commit
+	RefreshInPlace(RefreshDomains),
 	///
 	StatusLastFileMoved,
 	/// open commit msg input
@@ -61,30 +188,60 @@ pub enum InternalEvent {
 	PopupStashing(StashingOptions),
 	///
 	TabSwitch,
+	/// status tab with the unstaged changes focused
+	FocusWorkdir,
+	/// after stashing, if enabled in the options
+	ShowStashList,
 	///
 	InspectCommit(CommitId, Option<CommitTags>),
 	///
 	CompareCommits(CommitId, Option<CommitId>),
+	/// base and target branch as (name, tip)
+	CompareBranches((String, CommitId), (String, CommitId)),
 	///
 	SelectCommitInRevlog(CommitId),
+	/// commit in the log with the file selected in its files
+	SelectFileInRevlog(CommitId, String),
 	///
 	TagCommit(CommitId),
 	///
 	Tags,
 	///
-	BlameFile(String),
+	BlameFile(FileLine),
+	/// file (path) at a commit side by side with its working copy
+	CompareWorkdir(CommitId, String),
+	/// file in the full screen pager
+	ViewFile(FileSource),
+	/// stages of a conflicted file (path) next to the merged one
+	OpenConflict(String),
+	/// `git mergetool` on a conflicted file (path)
+	OpenMergeTool(String),
 	///
 	CreateBranch,
 	///
 	RenameBranch(String, String),
+	/// local branch name
+	EditBranchDescription(String),
 	///
 	SelectBranch,
+	/// back to the branch checked out before (`git checkout -`)
+	CheckoutPreviousBranch,
+	///
+	RecentBranches,
+	/// changes of the current branch since it forked off its base
+	OpenSinceBranching,
 	///
 	OpenExternalEditor(Option<String>),
 	///
 	Push(String, bool, bool),
 	///
 	Pull(String),
+	/// open popup to choose what to fetch (arg: current branch)
+	FetchPopup(String),
+	/// fetch refspecs from remote (no merge)
+	FetchRemote(String, Vec<String>),
+	/// preview and delete stale remote tracking branches of a remote
+	PruneRemote(String),
 	///
 	PushTags,
 	///
@@ -95,22 +252,179 @@ pub enum InternalEvent {
 	OpenFileFinder(Vec<TreeFile>),
 	///
 	FileFinderChanged(Option<PathBuf>),
+	///
+	OpenLogFilter(DateRange),
+	///
+	FilterLog(DateRange),
+	///
+	OpenLogAuthors,
+	/// author email
+	FilterLogAuthor(String),
+	/// selects the location in its view, see `bookmarks::Location`
+	GotoLocation(Location),
+	///
+	OpenJumpToRef,
+	/// selects the commit of the ref in the log
+	JumpToRef(RefTarget),
+	///
+	OpenIndexFlags,
+	/// paths of the status list, unstage
+	OpenStagePattern(Vec<String>, bool),
+	/// commits of the log as loaded (and filtered)
+	OpenLogExport(Vec<CommitId>),
+	/// issues found by a commit attempt, `None` runs the checks
+	OpenPreflight(Option<Vec<PreflightIssue>>),
+	/// commit once the preflight issues are fixed
+	ResumeCommit,
+	/// the index before a change made from the ui, to undo it
+	IndexChanged(IndexSnapshot),
+	/// leaves the open repo for the one at the path
+	SwitchRepo(PathBuf),
+	/// runs a command picked in the command palette
+	RunCommand(CommandText),
+	/// starts or goes on with a cherry-pick sequence in its popup
+	CherryPick(CherryPickRequest),
+}
+
+impl InternalEvent {
+	/// variant name, recorded for crash reports
+	pub const fn name(&self) -> &'static str {
+		match self {
+			Self::ConfirmAction(..) => "ConfirmAction",
+			Self::ConfirmedAction(..) => "ConfirmedAction",
+			Self::ShowErrorMsg(..) => "ShowErrorMsg",
+			Self::ShowInfoMsg(..) => "ShowInfoMsg",
+			Self::ShowToast(..) => "ShowToast",
+			Self::Update(..) => "Update",
+			Self::RefreshInPlace(..) => "RefreshInPlace",
+			Self::StatusLastFileMoved => "StatusLastFileMoved",
+			Self::OpenCommit => "OpenCommit",
+			Self::PopupStashing(..) => "PopupStashing",
+			Self::TabSwitch => "TabSwitch",
+			Self::FocusWorkdir => "FocusWorkdir",
+			Self::ShowStashList => "ShowStashList",
+			Self::InspectCommit(..) => "InspectCommit",
+			Self::CompareCommits(..) => "CompareCommits",
+			Self::CompareBranches(..) => "CompareBranches",
+			Self::SelectCommitInRevlog(..) => "SelectCommitInRevlog",
+			Self::SelectFileInRevlog(..) => "SelectFileInRevlog",
+			Self::TagCommit(..) => "TagCommit",
+			Self::Tags => "Tags",
+			Self::BlameFile(..) => "BlameFile",
+			Self::CompareWorkdir(..) => "CompareWorkdir",
+			Self::ViewFile(..) => "ViewFile",
+			Self::OpenConflict(..) => "OpenConflict",
+			Self::OpenMergeTool(..) => "OpenMergeTool",
+			Self::CreateBranch => "CreateBranch",
+			Self::RenameBranch(..) => "RenameBranch",
+			Self::EditBranchDescription(..) => {
+				"EditBranchDescription"
+			}
+			Self::SelectBranch => "SelectBranch",
+			Self::CheckoutPreviousBranch => "CheckoutPreviousBranch",
+			Self::RecentBranches => "RecentBranches",
+			Self::OpenSinceBranching => "OpenSinceBranching",
+			Self::OpenExternalEditor(..) => "OpenExternalEditor",
+			Self::Push(..) => "Push",
+			Self::Pull(..) => "Pull",
+			Self::FetchPopup(..) => "FetchPopup",
+			Self::FetchRemote(..) => "FetchRemote",
+			Self::PruneRemote(..) => "PruneRemote",
+			Self::PushTags => "PushTags",
+			Self::OpenFileTree(..) => "OpenFileTree",
+			Self::OptionSwitched(..) => "OptionSwitched",
+			Self::OpenFileFinder(..) => "OpenFileFinder",
+			Self::FileFinderChanged(..) => "FileFinderChanged",
+			Self::OpenLogFilter(..) => "OpenLogFilter",
+			Self::FilterLog(..) => "FilterLog",
+			Self::OpenLogAuthors => "OpenLogAuthors",
+			Self::FilterLogAuthor(..) => "FilterLogAuthor",
+			Self::GotoLocation(..) => "GotoLocation",
+			Self::OpenJumpToRef => "OpenJumpToRef",
+			Self::JumpToRef(..) => "JumpToRef",
+			Self::OpenIndexFlags => "OpenIndexFlags",
+			Self::OpenStagePattern(..) => "OpenStagePattern",
+			Self::OpenLogExport(..) => "OpenLogExport",
+			Self::OpenPreflight(..) => "OpenPreflight",
+			Self::ResumeCommit => "ResumeCommit",
+			Self::IndexChanged(..) => "IndexChanged",
+			Self::SwitchRepo(..) => "SwitchRepo",
+			Self::RunCommand(..) => "RunCommand",
+			Self::CherryPick(..) => "CherryPick",
+		}
+	}
+
+	/// changes the repo (or leads to a change), rejected in
+	/// read-only mode
+	pub const fn mutating(&self) -> bool {
+		match self {
+			Self::ConfirmAction(action)
+			| Self::ConfirmedAction(action) => action.mutating(),
+			Self::OpenCommit
+			| Self::PopupStashing(..)
+			| Self::TagCommit(..)
+			| Self::CreateBranch
+			| Self::RenameBranch(..)
+			| Self::EditBranchDescription(..)
+			| Self::CheckoutPreviousBranch
+			| Self::OpenExternalEditor(..)
+			| Self::OpenMergeTool(..)
+			| Self::Push(..)
+			| Self::Pull(..)
+			| Self::FetchPopup(..)
+			| Self::FetchRemote(..)
+			| Self::PruneRemote(..)
+			| Self::PushTags
+			| Self::OpenStagePattern(..)
+			| Self::ResumeCommit
+			| Self::CherryPick(..) => true,
+			_ => false,
+		}
+	}
+
+	/// talks to a remote, rejected in offline mode
+	pub const fn remote(&self) -> bool {
+		matches!(
+			self,
+			Self::Push(..)
+				| Self::Pull(..)
+				| Self::FetchPopup(..)
+				| Self::FetchRemote(..)
+				| Self::PruneRemote(..)
+				| Self::PushTags
+		)
+	}
 }
 
 /// single threaded simple queue for components to communicate with each other
 #[derive(Clone)]
 pub struct Queue {
 	data: Rc<RefCell<VecDeque<InternalEvent>>>,
+	messages: Rc<RefCell<MessageHistory>>,
 }
 
 impl Queue {
 	pub fn new() -> Self {
 		Self {
 			data: Rc::new(RefCell::new(VecDeque::new())),
+			messages: Rc::new(RefCell::new(MessageHistory::new(
+				MESSAGE_HISTORY_CAPACITY,
+			))),
 		}
 	}
 
+	/// adds to the message history without showing anything
+	pub fn log_message(&self, severity: Severity, text: &str) {
+		self.messages.borrow_mut().push(severity, text);
+	}
+
+	///
+	pub fn messages(&self) -> Ref<'_, MessageHistory> {
+		self.messages.borrow()
+	}
+
 	pub fn push(&self, ev: InternalEvent) {
+		crash_report::record_event(ev.name());
 		self.data.borrow_mut().push_back(ev);
 	}
 
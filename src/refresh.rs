@@ -0,0 +1,249 @@
+use bitflags::bitflags;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+	time::{Duration, Instant, SystemTime},
+};
+
+bitflags! {
+	/// parts of the repo state that can be refreshed independently
+	pub struct RefreshDomains: u32 {
+		/// working dir and index status
+		const STATUS = 0b00001;
+		/// commit log and head tree
+		const LOG = 0b00010;
+		/// local and remote branches, upstream state
+		const BRANCHES = 0b00100;
+		/// stash list
+		const STASHES = 0b01000;
+		/// currently shown diff
+		const DIFF = 0b10000;
+	}
+}
+
+/// everything is refreshed at least this often,
+/// catches changes no watched file tells us about (like worktree edits)
+pub const MAX_STALENESS: Duration = Duration::from_secs(60);
+
+/// collects dirty domains and decides what is due for a refresh
+pub struct RefreshScheduler {
+	dirty: RefreshDomains,
+	last_full_refresh: Instant,
+	max_staleness: Duration,
+}
+
+impl RefreshScheduler {
+	/// everything starts out dirty
+	pub const fn new(now: Instant, max_staleness: Duration) -> Self {
+		Self {
+			dirty: RefreshDomains::all(),
+			last_full_refresh: now,
+			max_staleness,
+		}
+	}
+
+	///
+	pub fn mark(&mut self, domains: RefreshDomains) {
+		self.dirty.insert(domains);
+	}
+
+	/// returns the domains to re-fetch now and resets them
+	pub fn take_due(&mut self, now: Instant) -> RefreshDomains {
+		if now.saturating_duration_since(self.last_full_refresh)
+			>= self.max_staleness
+		{
+			self.dirty = RefreshDomains::all();
+		}
+
+		let due = self.dirty;
+		self.dirty = RefreshDomains::empty();
+
+		if due.is_all() {
+			self.last_full_refresh = now;
+		}
+
+		due
+	}
+}
+
+/// polls modification times of files inside the git dir that git
+/// rewrites on every mutation, this is cheap enough to do every tick.
+/// directories count as changed if any directory below them changed,
+/// git replaces ref files instead of writing into them
+pub struct GitDirWatcher {
+	files: Vec<(PathBuf, RefreshDomains, Option<SystemTime>)>,
+}
+
+impl GitDirWatcher {
+	///
+	pub fn new(git_dir: &Path) -> Self {
+		let refs = RefreshDomains::LOG
+			| RefreshDomains::BRANCHES
+			| RefreshDomains::STATUS
+			| RefreshDomains::DIFF;
+
+		let files = vec![
+			("index", RefreshDomains::STATUS | RefreshDomains::DIFF),
+			("HEAD", refs),
+			("logs/HEAD", refs),
+			("packed-refs", refs),
+			// ref updates replace files in here
+			("refs/heads", refs),
+			("FETCH_HEAD", RefreshDomains::BRANCHES),
+			("refs/remotes", RefreshDomains::BRANCHES),
+			// tags decorate the log
+			("refs/tags", RefreshDomains::LOG),
+			("logs/refs/stash", RefreshDomains::STASHES),
+		]
+		.into_iter()
+		.map(|(file, domains)| {
+			let path = git_dir.join(file);
+			let modified = Self::modified(&path);
+			(path, domains, modified)
+		})
+		.collect();
+
+		Self { files }
+	}
+
+	/// domains affected by files changed since the last poll
+	pub fn poll(&mut self) -> RefreshDomains {
+		let mut res = RefreshDomains::empty();
+
+		for (path, domains, last) in &mut self.files {
+			let modified = Self::modified(path);
+			if modified != *last {
+				*last = modified;
+				res.insert(*domains);
+			}
+		}
+
+		res
+	}
+
+	/// latest modification of `path` or any directory below it
+	fn modified(path: &Path) -> Option<SystemTime> {
+		let metadata = fs::metadata(path).ok()?;
+		let modified = metadata.modified().ok();
+
+		if !metadata.is_dir() {
+			return modified;
+		}
+
+		fs::read_dir(path)
+			.into_iter()
+			.flatten()
+			.flatten()
+			.filter(|entry| {
+				entry.file_type().map_or(false, |t| t.is_dir())
+			})
+			.filter_map(|entry| Self::modified(&entry.path()))
+			.chain(modified)
+			.max()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{fs::File, io::Write};
+
+	const TICK: Duration = Duration::from_secs(5);
+
+	#[test]
+	fn test_idle_minute() {
+		let start = Instant::now();
+		let mut scheduler =
+			RefreshScheduler::new(start, MAX_STALENESS);
+
+		// initial load
+		assert!(scheduler.take_due(start).is_all());
+
+		let mut get_status_calls = 0;
+		let mut now = start;
+		while now < start + MAX_STALENESS {
+			now += TICK;
+			if scheduler
+				.take_due(now)
+				.contains(RefreshDomains::STATUS)
+			{
+				get_status_calls += 1;
+			}
+		}
+
+		// only the staleness fallback refreshed
+		assert_eq!(get_status_calls, 1);
+	}
+
+	#[test]
+	fn test_dirty_domains_only() {
+		let start = Instant::now();
+		let mut scheduler =
+			RefreshScheduler::new(start, MAX_STALENESS);
+		scheduler.take_due(start);
+
+		scheduler.mark(RefreshDomains::STASHES);
+		scheduler.mark(RefreshDomains::LOG);
+
+		assert_eq!(
+			scheduler.take_due(start + TICK),
+			RefreshDomains::STASHES | RefreshDomains::LOG
+		);
+		assert!(scheduler.take_due(start + TICK * 2).is_empty());
+	}
+
+	#[test]
+	fn test_watcher() {
+		let dir = std::env::temp_dir().join(format!(
+			"gitui-refresh-test-{}",
+			std::process::id()
+		));
+		fs::create_dir_all(dir.join("logs/refs")).unwrap();
+
+		let mut watcher = GitDirWatcher::new(&dir);
+		assert!(watcher.poll().is_empty());
+
+		File::create(dir.join("logs/refs/stash"))
+			.unwrap()
+			.write_all(b"stash")
+			.unwrap();
+
+		assert_eq!(watcher.poll(), RefreshDomains::STASHES);
+		assert!(watcher.poll().is_empty());
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn test_watcher_nested_refs() {
+		let dir = std::env::temp_dir().join(format!(
+			"gitui-refresh-nested-test-{}",
+			std::process::id()
+		));
+		fs::create_dir_all(dir.join("refs/heads/feature")).unwrap();
+
+		let mut watcher = GitDirWatcher::new(&dir);
+		assert!(watcher.poll().is_empty());
+
+		// directory times are not necessarily finer than a second
+		std::thread::sleep(Duration::from_millis(1100));
+
+		// like git replacing the ref file via a lock file
+		File::create(dir.join("refs/heads/feature/x.lock"))
+			.unwrap()
+			.write_all(b"id")
+			.unwrap();
+		fs::rename(
+			dir.join("refs/heads/feature/x.lock"),
+			dir.join("refs/heads/feature/x"),
+		)
+		.unwrap();
+
+		assert!(watcher.poll().contains(
+			RefreshDomains::LOG | RefreshDomains::BRANCHES
+		));
+		assert!(watcher.poll().is_empty());
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+}
@@ -0,0 +1,158 @@
+//! the repos of one gitui session to switch between: the ones given
+//! with `--repo` and the worktrees of the open repo, plus the ui state
+//! of each visited repo to switch back to
+
+use crate::ui_state::{self, UiState};
+use asyncgit::{
+	sync::{self, utils::repo_work_dir, RepoSummary},
+	CWD,
+};
+use std::{
+	cell::RefCell, collections::HashMap, fs, path::PathBuf, rc::Rc,
+};
+
+///
+pub type SharedRepoSession = Rc<RefCell<RepoSession>>;
+
+/// survives switching repos, unlike the `App` of each repo
+#[derive(Default)]
+pub struct RepoSession {
+	/// absolute paths from the command line
+	repos: Vec<PathBuf>,
+	/// keyed like the saved ui state (`ui_state::repo_key`)
+	states: HashMap<String, UiState>,
+	/// `--readonly`, for every repo switched to
+	readonly: bool,
+	/// toggled by the user, never saved
+	offline: bool,
+}
+
+impl RepoSession {
+	pub fn new(repos: Vec<PathBuf>, readonly: bool) -> Self {
+		Self {
+			repos,
+			states: HashMap::new(),
+			readonly,
+			offline: false,
+		}
+	}
+
+	/// nothing that changes a repo can run
+	pub const fn readonly(&self) -> bool {
+		self.readonly
+	}
+
+	/// nothing talks to a remote, auto fetch included
+	pub const fn offline(&self) -> bool {
+		self.offline
+	}
+
+	///
+	pub fn toggle_offline(&mut self) {
+		self.offline = !self.offline;
+	}
+
+	/// ui state of the open repo, kept even if it is not saved
+	pub fn remember(&mut self, state: UiState) {
+		match ui_state::repo_key() {
+			Ok(key) => {
+				self.states.insert(key, state);
+			}
+			Err(e) => log::error!("remember ui state: {}", e),
+		}
+	}
+
+	/// ui state the open repo was left with in this session
+	pub fn state(&self) -> Option<UiState> {
+		ui_state::repo_key()
+			.ok()
+			.and_then(|key| self.states.get(&key).cloned())
+	}
+
+	/// command line repos followed by the worktrees of the open repo
+	pub fn targets(&self) -> Vec<RepoTarget> {
+		let worktrees =
+			sync::worktree_dirs(CWD).unwrap_or_else(|e| {
+				log::error!("worktrees: {}", e);
+				Vec::new()
+			});
+		let current = repo_work_dir(CWD).ok().map(canonical);
+
+		unique(self.repos.iter().cloned().chain(worktrees))
+			.into_iter()
+			.map(|path| RepoTarget {
+				current: current.as_ref() == Some(&canonical(&path)),
+				summary: path.to_str().map_or_else(
+					|| Err(String::from("invalid path")),
+					|path| {
+						sync::repo_summary(path)
+							.map_err(|e| e.to_string())
+					},
+				),
+				path,
+			})
+			.collect()
+	}
+}
+
+/// a repo to switch to
+pub struct RepoTarget {
+	pub path: PathBuf,
+	/// the open repo
+	pub current: bool,
+	/// the error if it is gone (e.g. on an unmounted disk)
+	pub summary: Result<RepoSummary, String>,
+}
+
+impl RepoTarget {
+	pub const fn is_available(&self) -> bool {
+		self.summary.is_ok()
+	}
+}
+
+/// `path` itself if it cannot be resolved (anymore)
+fn canonical<P: Into<PathBuf>>(path: P) -> PathBuf {
+	let path = path.into();
+	fs::canonicalize(&path).unwrap_or(path)
+}
+
+/// keeps the first of all paths to the same dir
+fn unique(paths: impl Iterator<Item = PathBuf>) -> Vec<PathBuf> {
+	let mut seen = Vec::new();
+	let mut res = Vec::new();
+
+	for path in paths {
+		let key = canonical(&path);
+		if !seen.contains(&key) {
+			seen.push(key);
+			res.push(path);
+		}
+	}
+
+	res
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::env;
+
+	#[test]
+	fn test_unique() {
+		let current = env::current_dir().unwrap();
+		let missing = PathBuf::from("/gitui-missing/repo");
+
+		assert_eq!(
+			unique(
+				vec![
+					current.clone(),
+					missing.clone(),
+					PathBuf::from("."),
+					missing.clone(),
+				]
+				.into_iter()
+			),
+			vec![current, missing]
+		);
+	}
+}
@@ -21,15 +21,71 @@ pub fn tabs_to_spaces(input: String) -> String {
 	}
 }
 
+/// matches `text` against a glob `pattern`: `*` matches any sequence
+/// of chars (including `/`), `?` any single char
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+	let pattern: Vec<char> = pattern.chars().collect();
+	let text: Vec<char> = text.chars().collect();
+
+	let (mut p, mut t) = (0, 0);
+	// position of the last `*` and the text position it matched up to
+	let mut backtrack: Option<(usize, usize)> = None;
+
+	while t < text.len() {
+		match pattern.get(p) {
+			Some('*') => {
+				backtrack = Some((p, t));
+				p += 1;
+			}
+			Some(c) if *c == '?' || *c == text[t] => {
+				p += 1;
+				t += 1;
+			}
+			_ => {
+				if let Some((star, star_t)) = backtrack {
+					// let the last `*` swallow one more char
+					p = star + 1;
+					t = star_t + 1;
+					backtrack = Some((star, star_t + 1));
+				} else {
+					return false;
+				}
+			}
+		}
+	}
+
+	pattern[p..].iter().all(|c| *c == '*')
+}
+
 #[cfg(test)]
 mod test {
 	use pretty_assertions::assert_eq;
 
-	use crate::string_utils::trim_length_left;
+	use crate::string_utils::{glob_match, trim_length_left};
 
 	#[test]
 	fn test_trim() {
 		assert_eq!(trim_length_left("👍foo", 3), "foo");
 		assert_eq!(trim_length_left("👍foo", 4), "foo");
 	}
+
+	#[test]
+	fn test_glob_match() {
+		assert!(glob_match("main", "main"));
+		assert!(!glob_match("main", "main2"));
+		assert!(!glob_match("main", "mai"));
+
+		assert!(glob_match("release/*", "release/1.0"));
+		assert!(glob_match("release/*", "release/"));
+		assert!(glob_match("release/*", "release/1.0/fix"));
+		assert!(!glob_match("release/*", "feature/release/1.0"));
+
+		assert!(glob_match("*-wip", "foo-wip"));
+		assert!(glob_match("*a*b", "xaxxab"));
+		assert!(!glob_match("*a*b", "xaxxa"));
+		assert!(glob_match("v?", "v1"));
+		assert!(!glob_match("v?", "v10"));
+		assert!(glob_match("*", ""));
+		assert!(!glob_match("", "main"));
+	}
 }
@@ -1,6 +1,20 @@
-use asyncgit::sync::CommitId;
+use asyncgit::{
+	sync::{
+		AmendDates, BisectStep, BranchNameError, CherryPickProgress,
+		CommitId, ConfigScope, ConflictSide, LargeFile,
+		PreflightIssue, RefKind, RepoState, StaleState, TodoEntry,
+	},
+	CWD,
+};
+use bytesize::ByteSize;
 
-use crate::keys::SharedKeyConfig;
+use crate::{
+	bookmarks::Location,
+	commit_lint::LintProblem,
+	components::{time_to_string, FileSource, PaletteState},
+	keys::SharedKeyConfig,
+	log_export::LogExportFormat,
+};
 
 pub mod order {
 	pub static NAV: i8 = 2;
@@ -10,13 +24,335 @@ pub mod order {
 pub static PUSH_POPUP_MSG: &str = "Push";
 pub static FORCE_PUSH_POPUP_MSG: &str = "Force Push";
 pub static PULL_POPUP_MSG: &str = "Pull";
+pub static FETCH_POPUP_MSG: &str = "Fetch";
 pub static PUSH_POPUP_PROGRESS_NONE: &str = "preparing...";
+pub static PUSH_POPUP_STATES_PRE_PUSH: &str = "pre-push hook";
 pub static PUSH_POPUP_STATES_ADDING: &str = "adding objects (1/3)";
 pub static PUSH_POPUP_STATES_DELTAS: &str = "deltas (2/3)";
 pub static PUSH_POPUP_STATES_PUSHING: &str = "pushing (3/3)";
 pub static PUSH_POPUP_STATES_TRANSFER: &str = "transfer";
 pub static PUSH_POPUP_STATES_DONE: &str = "done";
 
+pub static LOG_NO_COMMITS: &str = "no commits yet";
+pub static LOG_NO_COMMITS_IN_RANGE: &str = "no commits in date range";
+pub static LOG_TITLE_LOADING: &str = " [loading...]";
+pub static PUSH_SELECT_BRANCH_TITLE: &str = "Branch to push";
+pub static LOG_NO_COMMITS_FILTERED: &str =
+	"no commits match the filter";
+pub static LOG_AUTHORS_EMPTY: &str = "no authors";
+pub static STATUS_CLEAN: &str =
+	"nothing to commit, working tree clean";
+pub mod timings {
+	pub static OPERATION: &str = "operation";
+	pub static COUNT: &str = "count";
+	pub static TOTAL: &str = "total";
+	pub static LAST: &str = "last";
+	pub static MEDIAN: &str = "p50";
+	pub static MAX: &str = "max";
+}
+
+pub static STASHING_CLEAN: &str =
+	"nothing to stash, working tree clean";
+pub static LOG_FILTER_SINCE: &str = "since: ";
+pub static LOG_FILTER_UNTIL: &str = "until: ";
+pub static LOG_FILTER_HINT: &str =
+	"e.g. 2021-06-30, yesterday, 2 weeks ago";
+pub static MSG_HISTORY_EMPTY: &str = "no messages yet";
+pub static BRANCH_NO_COMMITS: &str = "(no commits)";
+pub static INDEX_RETRY_ERROR: &str = "retry error:";
+pub static STAGE_LFS_ERROR: &str = "staging error:";
+pub static STASH_OPTION_UNTRACKED: &str = "stash untracked";
+pub static STASH_OPTION_KEEP_INDEX: &str = "keep index";
+pub static DIFF_SEARCH_HINT: &str = "search (smart-case)";
+pub static OPTIONS_PROTECTED_BRANCHES_TITLE: &str =
+	"Protected branches";
+pub static OPTIONS_PROTECTED_BRANCHES_MSG: &str =
+	"globs, comma separated";
+pub static OPTIONS_COMMIT_LINT_TYPES_TITLE: &str = "Lint types";
+pub static OPTIONS_COMMIT_LINT_TYPES_MSG: &str =
+	"commit types, comma separated, empty allows any";
+pub static OPTIONS_COMMIT_PREFILL_PATTERN_TITLE: &str =
+	"Prefill from branch";
+pub static OPTIONS_COMMIT_PREFILL_PATTERN_MSG: &str = r"regex applied to the branch name, e.g. ([A-Z]+-\d+), empty turns it off";
+pub static OPTIONS_COMMIT_PREFILL_TEMPLATE_TITLE: &str =
+	"Prefill template";
+pub static OPTIONS_COMMIT_PREFILL_TEMPLATE_MSG: &str =
+	"{1} is replaced with the first capture group";
+pub static OPTIONS_COMMIT_TRAILERS_TITLE: &str = "Trailers";
+pub static OPTIONS_COMMIT_TRAILERS_MSG: &str =
+	"added to every commit, semicolon separated";
+pub static STAGE_PATTERN_HINT: &str = "pathspec, e.g. *.rs or src/";
+pub static LOG_EXPORT_TITLE: &str = "Export log";
+pub static CHERRY_PICK_TITLE: &str = "Cherry-pick";
+pub static LOG_EXPORT_HINT: &str =
+	"file path (~ for home), *.csv exports csv, otherwise text";
+pub static REPO_MAINTENANCE_EMPTY: &str =
+	"no stale locks or leftover state found";
+pub static INIT_REPO_TITLE: &str = "Initialize repository";
+pub static INIT_REPO_BRANCH: &str = "initial branch: ";
+pub static CUSTOM_COMMAND_RETURN: &str =
+	"press enter to return to gitui";
+
+pub fn push_popup_title(
+	force: bool,
+	remote: &str,
+	branch: &str,
+) -> String {
+	format!(
+		"{} to {}/{}",
+		if force {
+			FORCE_PUSH_POPUP_MSG
+		} else {
+			PUSH_POPUP_MSG
+		},
+		remote,
+		branch
+	)
+}
+
+pub fn push_preview_remote_tip(
+	tip: Option<&str>,
+	asked_remote: bool,
+) -> String {
+	let source = if asked_remote {
+		"as listed by the remote"
+	} else {
+		"as of the last fetch"
+	};
+	tip.map_or_else(
+		|| format!("remote branch: does not exist yet ({})", source),
+		|tip| format!("remote branch: at {} ({})", tip, source),
+	)
+}
+pub fn push_preview_kind(
+	fast_forward: bool,
+	discarded: Option<usize>,
+) -> String {
+	match (fast_forward, discarded) {
+		(true, _) => "fast-forward".to_string(),
+		(false, Some(discarded)) => format!(
+			"not a fast-forward: needs force, drops {} remote commits",
+			discarded
+		),
+		(false, None) => {
+			"not a fast-forward: needs force, the remote has commits not fetched yet"
+				.to_string()
+		}
+	}
+}
+pub fn push_preview_upstream_gone(upstream: &str) -> String {
+	format!("upstream {} is gone, pushing recreates it", upstream)
+}
+pub fn push_preview_commits(count: usize) -> String {
+	if count == 0 {
+		"nothing to push".to_string()
+	} else {
+		format!("{} commits to push:", count)
+	}
+}
+pub fn push_preview_more(count: usize) -> String {
+	format!("... and {} more", count)
+}
+pub fn push_preview_failed(err: &str) -> String {
+	format!("preview failed: {}", err)
+}
+pub fn custom_commands_load_failed(err: &str) -> String {
+	format!("invalid custom_commands.ron:\n{}", err)
+}
+pub fn custom_command_unavailable(name: &str) -> String {
+	format!("'{}' needs a selection not available here", name)
+}
+pub fn custom_command_started(name: &str) -> String {
+	format!("running '{}' ...", name)
+}
+pub fn custom_command_finished(name: &str) -> String {
+	format!(
+		"'{}' finished, see the message history for its output",
+		name
+	)
+}
+pub fn custom_command_output(name: &str, output: &str) -> String {
+	format!("'{}':\n{}", name, output)
+}
+pub fn custom_command_failed(name: &str, err: &str) -> String {
+	format!("'{}' failed:\n{}", name, err)
+}
+pub fn timings_title(recording: bool) -> String {
+	format!(
+		"Timings [{}]",
+		if recording { "recording" } else { "paused" }
+	)
+}
+pub fn timings_empty(
+	key_config: &SharedKeyConfig,
+	recording: bool,
+) -> String {
+	if recording {
+		"nothing measured yet".to_string()
+	} else {
+		format!(
+			"not recording, start with [{}], timings stay on this machine",
+			key_config.get_hint(key_config.enter),
+		)
+	}
+}
+pub fn msg_history_title(_key_config: &SharedKeyConfig) -> String {
+	"Messages".to_string()
+}
+pub fn msg_history_copied() -> String {
+	"copied message to clipboard".to_string()
+}
+pub fn msg_history_copy_failed(err: &str) -> String {
+	format!("copy to clipboard failed:\n{}", err)
+}
+pub fn toast_pushed(remote: &str, branch: &str) -> String {
+	format!("pushed to {}/{}", remote, branch)
+}
+pub fn toast_deleted_remote_branch(
+	remote: &str,
+	branch: &str,
+) -> String {
+	format!("deleted {}/{}", remote, branch)
+}
+pub fn toast_pushed_tags() -> String {
+	"pushed tags".to_string()
+}
+pub fn toast_fetched(remote: &str) -> String {
+	format!("fetched from {}", remote)
+}
+pub fn toast_pruned(remote: &str, count: usize) -> String {
+	format!("pruned {} remote tracking branches of {}", count, remote)
+}
+pub fn toast_deleted_merged(count: usize) -> String {
+	format!("deleted {} merged branches", count)
+}
+pub fn toast_no_merged_branches() -> String {
+	"no merged branches to delete".to_string()
+}
+pub fn toast_stash_created() -> String {
+	"stash created".to_string()
+}
+pub fn toast_staged_pattern(unstage: bool, count: usize) -> String {
+	format!(
+		"{} {} file{}",
+		if unstage { "unstaged" } else { "staged" },
+		count,
+		if count == 1 { "" } else { "s" }
+	)
+}
+pub fn stage_pattern_no_match(pattern: &str) -> String {
+	format!("no files match '{}'", pattern)
+}
+pub fn repo_maintenance_title() -> String {
+	"Repository maintenance".to_string()
+}
+pub fn stale_state_name(state: &StaleState) -> String {
+	match state {
+		StaleState::Lock { path, age } => format!(
+			"stale lock {} ({} min old)",
+			path,
+			age.as_secs() / 60
+		),
+		StaleState::Merge => "merge already committed".to_string(),
+		StaleState::Rebase(dir) => {
+			format!("incomplete rebase ({})", dir)
+		}
+		StaleState::Sequencer => {
+			"leftover cherry-pick/revert sequence".to_string()
+		}
+	}
+}
+pub fn stale_state_explanation(state: &StaleState) -> String {
+	match state {
+		StaleState::Lock { path, .. } => format!(
+			"git creates .git/{} while writing and removes it when done. No running process holds this one, so a git process probably crashed. Until it is gone git and gitui refuse to make changes it guards.\n\nCleanup deletes the lock file.",
+			path
+		),
+		StaleState::Merge => "MERGE_HEAD is left over from a merge that is part of HEAD already (or it is unreadable), so a merge is still considered in progress.\n\nCleanup removes MERGE_HEAD, MERGE_MSG and MERGE_MODE. Index and working tree stay untouched.".to_string(),
+		StaleState::Rebase(dir) => format!(
+			".git/{} lacks the files needed to continue or abort the rebase.\n\nCleanup removes the directory. HEAD, index and working tree stay as they are.",
+			dir
+		),
+		StaleState::Sequencer => ".git/sequencer is left over from a cherry-pick or revert that is no longer in progress, git refuses to start new ones while it exists.\n\nCleanup removes the directory.".to_string(),
+	}
+}
+pub fn toast_cleaned_up_stale_state(state: &StaleState) -> String {
+	format!("cleaned up {}", stale_state_name(state))
+}
+pub fn toast_copied_path(path: &str) -> String {
+	format!("copied {}", path)
+}
+pub fn copy_path_failed(err: &str) -> String {
+	format!("copy path to clipboard failed:\n{}", err)
+}
+pub fn open_containing_dir_failed(err: &str) -> String {
+	format!("opening the containing directory failed:\n{}", err)
+}
+
+pub fn open_link_failed(url: &str, err: &str) -> String {
+	format!("opening {} failed:\n{}", url, err)
+}
+
+pub fn undo_index_change_failed(err: &str) -> String {
+	format!("undo index change failed:\n{}", err)
+}
+pub fn toast_index_change_undone(left: usize) -> String {
+	format!("index change undone ({} left)", left)
+}
+pub fn restore_discarded_failed(err: &str) -> String {
+	format!("restoring discarded change failed:\n{}", err)
+}
+pub fn toast_discard_restored(count: usize) -> String {
+	format!(
+		"restored {} discarded file{}",
+		count,
+		if count == 1 { "" } else { "s" }
+	)
+}
+pub fn toast_hunk_discarded(
+	path: &str,
+	key_config: &SharedKeyConfig,
+) -> String {
+	format!(
+		"discarded hunk in {} \u{2014} press {} to undo",
+		path,
+		key_config.get_hint(key_config.undo_hunk_discard),
+	)
+}
+pub fn toast_hunk_discard_undone(path: &str) -> String {
+	format!("restored discarded hunk in {}", path)
+}
+
+pub fn commit_behind_upstream_notice(
+	behind: usize,
+	upstream: &str,
+) -> String {
+	format!(
+		"branch is {} commit{} behind {}, consider pulling first",
+		behind,
+		if behind == 1 { "" } else { "s" },
+		upstream,
+	)
+}
+pub fn commit_nothing_staged_notice(
+	key_config: &SharedKeyConfig,
+) -> String {
+	format!(
+		"no staged changes: back to changes [{}] or commit empty [{}]",
+		key_config.get_hint(key_config.commit_goto_changes),
+		key_config.get_hint(key_config.enter),
+	)
+}
+
+pub fn auto_fetch_notice(count: usize, branch: &str) -> String {
+	format!(
+		"auto fetch: {} new upstream commit{} for '{}'",
+		count,
+		if count == 1 { "" } else { "s" },
+		branch
+	)
+}
+
 pub static PUSH_TAGS_POPUP_MSG: &str = "Push Tags";
 pub static PUSH_TAGS_STATES_FETCHING: &str = "fetching";
 pub static PUSH_TAGS_STATES_PUSHING: &str = "pushing";
@@ -30,20 +366,394 @@ pub mod symbol {
 	pub const FOLDER_ICON_COLLAPSED: &str = "\u{25b8}"; //▸
 	pub const FOLDER_ICON_EXPANDED: &str = "\u{25be}"; //▾
 	pub const EMPTY_STR: &str = "";
+	pub const SYMLINK_ARROW: &str = " -> ";
+	pub const ELLIPSIS: &str = "\u{2026}"; //…
+	pub const ARROW_RIGHT: &str = "\u{2192}"; //→
+	pub const WARNING: &str = "\u{26a0}"; //⚠
+	pub const UNPUSHED: &str = "\u{2191}"; //↑
+	pub const NOTE: &str = "\u{270e}"; //✎
+	pub const RULE: &str = "\u{2500}"; //─
 }
 
+pub fn terminal_too_small(
+	width: u16,
+	height: u16,
+	min_width: u16,
+	min_height: u16,
+) -> String {
+	format!(
+		"terminal too small ({}x{}, need {}x{})",
+		width, height, min_width, min_height
+	)
+}
 pub fn title_branches() -> String {
 	"Branches".to_string()
 }
+pub fn title_recent_branches() -> String {
+	"Recent Branches".to_string()
+}
+pub static RECENT_BRANCHES_EMPTY: &str =
+	"no other branch checked out recently";
+pub fn status_empty(
+	branch: Option<&str>,
+	ahead_behind: Option<(usize, usize)>,
+) -> Vec<String> {
+	let mut lines = vec![STATUS_CLEAN.to_string()];
+	if let Some(branch) = branch {
+		lines.push(ahead_behind.map_or_else(
+			|| format!("on {}", branch),
+			|(ahead, behind)| {
+				format!(
+					"on {} \u{2191}{} \u{2193}{}",
+					branch, ahead, behind
+				)
+			},
+		));
+	}
+	lines
+}
+pub fn log_empty_unborn(key_config: &SharedKeyConfig) -> Vec<String> {
+	vec![
+		LOG_NO_COMMITS.to_string(),
+		format!(
+			"stage files on the Status tab [{}] and press [{}] to make the first commit",
+			key_config.get_hint(key_config.tab_status),
+			key_config.get_hint(key_config.open_commit),
+		),
+	]
+}
+pub fn stashlist_empty(key_config: &SharedKeyConfig) -> Vec<String> {
+	vec![format!(
+		"no stashes \u{2014} press [{}] on the Status tab [{}] to create one",
+		key_config.get_hint(key_config.status_stash),
+		key_config.get_hint(key_config.tab_status),
+	)]
+}
+pub fn files_empty(key_config: &SharedKeyConfig) -> Vec<String> {
+	vec![
+		"no files committed yet".to_string(),
+		format!(
+			"commit files on the Status tab [{}] to browse them here",
+			key_config.get_hint(key_config.tab_status),
+		),
+	]
+}
+pub fn repo_switcher_empty() -> Vec<String> {
+	vec![
+		"no other repos in this session".to_string(),
+		"start gitui with `--repo <path>` for each repo, or add a worktree (a second checkout sharing the history of this repo) with `git worktree add <path> <branch>`".to_string(),
+	]
+}
+pub fn title_repo_switcher() -> String {
+	"Repositories".to_string()
+}
+pub fn repo_switcher_summary(
+	branch: Option<&str>,
+	changes: usize,
+) -> String {
+	let branch = branch.unwrap_or("(detached)");
+	match changes {
+		0 => format!("{}, clean", branch),
+		1 => format!("{}, 1 change", branch),
+		_ => format!("{}, {} changes", branch, changes),
+	}
+}
+pub fn repo_switcher_unavailable(err: &str) -> String {
+	format!("not available: {}", err)
+}
+pub fn title_command_palette() -> String {
+	"Commands".to_string()
+}
+pub fn command_palette_hint() -> String {
+	"type a command..".to_string()
+}
+pub fn command_palette_hits(hits: usize) -> String {
+	format!("Hits: {}", hits)
+}
+pub fn command_palette_no_hits() -> String {
+	"no matching command".to_string()
+}
+pub fn command_palette_reason(
+	state: PaletteState,
+	group: &str,
+) -> String {
+	let group = group.trim_matches(|c| c == '-' || c == ' ');
+	match state {
+		PaletteState::Runnable => String::new(),
+		PaletteState::Disabled => {
+			"not possible with the current selection".to_string()
+		}
+		PaletteState::Unavailable => {
+			format!("not available here ({})", group)
+		}
+		PaletteState::KeysOnly => {
+			"no single action, use its keys instead".to_string()
+		}
+	}
+}
+pub static REPO_SWITCH_PENDING: &str =
+	"cannot switch repos while a remote operation is running";
+pub fn toast_no_previous_branch() -> String {
+	"no previously checked out branch".to_string()
+}
+pub fn toast_checked_out(branch: &str) -> String {
+	format!("switched to {}", branch)
+}
+pub fn toast_bookmark_added(
+	label: &str,
+	key_config: &SharedKeyConfig,
+) -> String {
+	format!(
+		"bookmark '{}' added, list them with [{}]",
+		label,
+		key_config.get_hint(key_config.open_bookmarks),
+	)
+}
+pub fn bookmarks_title() -> String {
+	"Bookmarks".to_string()
+}
+pub fn bookmarks_empty(key_config: &SharedKeyConfig) -> String {
+	format!(
+		"no bookmarks yet, add one to the selected commit or file with [{}]",
+		key_config.get_hint(key_config.bookmark_add),
+	)
+}
+pub fn bookmark_label_title() -> String {
+	"Bookmark label".to_string()
+}
+pub fn bookmark_label_hint(next: &str) -> String {
+	format!("empty for '{}'", next)
+}
+pub const fn bookmark_kind(location: &Location) -> &'static str {
+	match location {
+		Location::Commit(_) => "commit",
+		Location::StatusFile { .. } => "status",
+		Location::TreeFile(_) => "file  ",
+	}
+}
+pub fn bookmark_stale_commit(id: &str) -> String {
+	format!(
+		"commit {} is not in the log anymore (rewritten or deleted)",
+		id.get(..7).unwrap_or(id)
+	)
+}
+pub fn bookmark_stale_file(path: &str) -> String {
+	format!("file '{}' does not exist anymore", path)
+}
+pub fn index_flags_title() -> String {
+	"Hidden by Index Flags [S: skip-worktree, A: assume-unchanged]"
+		.to_string()
+}
+pub fn index_flags_empty() -> String {
+	"no files flagged skip-worktree or assume-unchanged".to_string()
+}
+pub fn index_flag_clear_failed(err: &str) -> String {
+	format!("clearing index flag failed:\n{}", err)
+}
+pub fn skip_worktree_failed(err: &str) -> String {
+	format!("setting skip-worktree failed:\n{}", err)
+}
+pub fn toast_flagged_left_out(
+	count: usize,
+	key_config: &SharedKeyConfig,
+) -> String {
+	format!(
+		"{} file{} left as is, hidden by index flags, list them with [{}]",
+		count,
+		if count == 1 { "" } else { "s" },
+		key_config.get_hint(key_config.status_index_flags),
+	)
+}
+pub fn toast_cannot_split_hunk() -> String {
+	"cannot split further".to_string()
+}
+pub fn toast_preflight_passed() -> String {
+	"preflight check passed".to_string()
+}
+pub fn preflight_title(current: usize, total: usize) -> String {
+	format!("Preflight check ({}/{})", current, total)
+}
+pub fn preflight_issue(issue: &PreflightIssue) -> String {
+	match issue {
+		PreflightIssue::MissingUserName => {
+			"no user name set, it is recorded in commits".to_string()
+		}
+		PreflightIssue::MissingUserEmail => {
+			"no user email set, committing fails without it"
+				.to_string()
+		}
+		PreflightIssue::UnusableEditor(editor) => {
+			format!("editor '{}' not found", editor)
+		}
+		PreflightIssue::UnsafeDirectory(_) => {
+			"repository owned by another user, git refuses to work in it"
+				.to_string()
+		}
+	}
+}
+pub fn preflight_config_key(key: &str) -> String {
+	format!("{}: ", key)
+}
+pub fn preflight_scope(
+	global: bool,
+	key_config: &SharedKeyConfig,
+) -> String {
+	format!(
+		"write to: {} config [{}]",
+		if global { "global" } else { "local" },
+		key_config.get_hint(key_config.tab_toggle),
+	)
+}
+pub fn preflight_hint(key_config: &SharedKeyConfig) -> String {
+	format!(
+		"apply [{}] skip [{}]",
+		key_config.get_hint(key_config.enter),
+		key_config.get_hint(key_config.exit_popup),
+	)
+}
+pub fn preflight_empty_value(key: &str) -> String {
+	format!("{} cannot be empty", key)
+}
+pub static GIT_CONFIG_UNSET: &str = "<not set>";
+pub fn git_config_title() -> String {
+	"Git config".to_string()
+}
+pub fn git_config_scope(scope: ConfigScope) -> String {
+	match scope {
+		ConfigScope::Local => "local",
+		ConfigScope::Global => "global",
+		ConfigScope::System => "system",
+	}
+	.to_string()
+}
+pub fn git_config_target(
+	global: bool,
+	key_config: &SharedKeyConfig,
+) -> String {
+	format!(
+		"edits go to: {} config [{}]",
+		if global { "global" } else { "local" },
+		key_config.get_hint(key_config.tab_toggle),
+	)
+}
+pub fn git_config_input_title(key: &str, global: bool) -> String {
+	format!("{} ({})", key, if global { "global" } else { "local" })
+}
+pub fn git_config_empty_value(
+	key: &str,
+	key_config: &SharedKeyConfig,
+) -> String {
+	format!(
+		"{} cannot be empty, unset it [{}] instead",
+		key,
+		key_config.get_hint(key_config.git_config_unset),
+	)
+}
+pub fn title_branches_merged(
+	merged: bool,
+	base: &str,
+	checking: bool,
+) -> String {
+	format!(
+		"Branches {}merged into {}{}",
+		if merged { "" } else { "not " },
+		base,
+		if checking { " (checking...)" } else { "" }
+	)
+}
+pub fn compare_branches_title(
+	base: &str,
+	target: &str,
+	merge_base: bool,
+) -> String {
+	format!(
+		"{}{}{}",
+		base,
+		if merge_base { "..." } else { ".." },
+		target
+	)
+}
+pub fn since_branching_title(
+	base: &str,
+	target: &str,
+	workdir: bool,
+) -> String {
+	format!(
+		"{} since branching off {}{}",
+		target,
+		base,
+		if workdir { " + uncommitted" } else { "" }
+	)
+}
+pub fn base_branch_missing(missing: &str, fallback: &str) -> String {
+	format!(
+		"base branch '{}' not found, using '{}'",
+		missing, fallback
+	)
+}
 pub fn title_tags() -> String {
 	"Tags".to_string()
 }
 pub fn title_status(_key_config: &SharedKeyConfig) -> String {
 	"Unstaged Changes".to_string()
 }
+pub fn title_status_untracked_truncated(
+	key_config: &SharedKeyConfig,
+	limit: usize,
+) -> String {
+	format!(
+		"Unstaged Changes [untracked files truncated ({}+), refine .gitignore or press {} to load all]",
+		limit,
+		key_config.get_hint(key_config.status_load_all_untracked),
+	)
+}
+pub fn title_status_index_flagged(
+	key_config: &SharedKeyConfig,
+	count: usize,
+) -> String {
+	format!(
+		"Unstaged Changes [{} hidden by index flags, {} to list]",
+		count,
+		key_config.get_hint(key_config.status_index_flags),
+	)
+}
+pub fn title_status_untracked_loading(
+	_key_config: &SharedKeyConfig,
+	count: usize,
+) -> String {
+	format!("Unstaged Changes [loading untracked files: {}]", count)
+}
 pub fn title_diff(_key_config: &SharedKeyConfig) -> String {
 	"Diff: ".to_string()
 }
+pub fn diff_mode_change(old: u32, new: u32) -> String {
+	format!("mode {:o} {} {:o}", old, symbol::ARROW_RIGHT, new)
+}
+pub fn diff_search_matches(
+	active: Option<usize>,
+	count: usize,
+	truncated: bool,
+) -> String {
+	let more = if truncated { "+" } else { "" };
+	match active {
+		Some(active) => {
+			format!("[{}/{}{} matches]", active + 1, count, more)
+		}
+		None if count == 0 => "[no matches]".to_string(),
+		None => format!("[{}{} matches]", count, more),
+	}
+}
+pub fn diff_link_change(old: &str, new: &str) -> String {
+	format!("link {} {} / {}", symbol::ARROW_RIGHT, old, new)
+}
+pub const DIFF_LFS_OBJECT: &str = "LFS object";
+pub const DIFF_LINE_NOT_COMMITTED: &str = "not committed yet";
+pub fn diff_lfs_change(label: &str, old: &str, new: &str) -> String {
+	format!("{}: {} {} {}", label, old, symbol::ARROW_RIGHT, new)
+}
+pub fn diff_error(error: &str) -> String {
+	format!("diff failed: {}", error)
+}
 pub fn title_index(_key_config: &SharedKeyConfig) -> String {
 	"Staged Changes".to_string()
 }
@@ -80,6 +790,9 @@ pub fn msg_opening_editor(_key_config: &SharedKeyConfig) -> String {
 pub fn msg_title_error(_key_config: &SharedKeyConfig) -> String {
 	"Error".to_string()
 }
+pub fn msg_title_info(_key_config: &SharedKeyConfig) -> String {
+	"Info".to_string()
+}
 pub fn commit_title() -> String {
 	"Commit".to_string()
 }
@@ -89,14 +802,182 @@ pub fn commit_title_merge() -> String {
 pub fn commit_title_amend() -> String {
 	"Commit (Amend)".to_string()
 }
+pub fn commit_amend_dates_notice(
+	dates: AmendDates,
+	key_config: &SharedKeyConfig,
+) -> String {
+	let dates = match dates {
+		AmendDates::Default => {
+			"keeps author date, committer date is now".to_string()
+		}
+		AmendDates::KeepCommitterDate => {
+			"keeps author and committer date".to_string()
+		}
+		AmendDates::AuthorDateNow => {
+			"author and committer date are now".to_string()
+		}
+		AmendDates::Explicit { time, .. } => format!(
+			"author and committer date are {}",
+			time_to_string(time, false)
+		),
+	};
+
+	format!(
+		"amend {} [{}]",
+		dates,
+		key_config.get_hint(key_config.commit_amend_dates),
+	)
+}
+pub fn commit_amend_date_title() -> String {
+	"Amend Date".to_string()
+}
+pub fn commit_amend_date_msg() -> String {
+	"e.g. 2021-06-30 12:00".to_string()
+}
+pub fn commit_amend_date_invalid(input: &str) -> String {
+	format!(
+		"invalid date '{}'\nuse ISO 8601 (2021-06-30T12:00:00+02:00), '2021-06-30 12:00', '2021-06-30', 'yesterday' or '2 hours ago'",
+		input
+	)
+}
 pub fn commit_msg(_key_config: &SharedKeyConfig) -> String {
 	"type commit message..".to_string()
 }
+pub fn hooks_running_title(
+	spinner: char,
+	secs: u64,
+	pre_push: bool,
+) -> String {
+	format!(
+		"{} Running {} ({}s)",
+		spinner,
+		if pre_push {
+			"pre-push hook"
+		} else {
+			"commit hooks"
+		},
+		secs
+	)
+}
+pub fn hooks_failed_title(
+	_key_config: &SharedKeyConfig,
+	pre_push: bool,
+) -> String {
+	if pre_push {
+		"Pre-push hook failed, nothing was pushed".to_string()
+	} else {
+		"Commit hook failed".to_string()
+	}
+}
+pub fn hooks_canceled_title(
+	_key_config: &SharedKeyConfig,
+	pre_push: bool,
+) -> String {
+	if pre_push {
+		"Pre-push hook canceled, nothing was pushed".to_string()
+	} else {
+		"Commit hook canceled".to_string()
+	}
+}
 pub fn commit_first_line_warning(count: usize) -> String {
 	format!("[subject length: {}]", count)
 }
-pub const fn branch_name_invalid() -> &'static str {
-	"[invalid name]"
+pub fn commit_lint_warning(
+	problems: &[LintProblem],
+	acknowledged: bool,
+) -> String {
+	let problems = problems
+		.iter()
+		.map(|problem| match problem {
+			LintProblem::MissingColon => {
+				String::from("missing ':' after the type")
+			}
+			LintProblem::MissingDescription => {
+				String::from("missing description after ':'")
+			}
+			LintProblem::UnknownType(kind) => {
+				format!("unknown type '{}'", kind)
+			}
+			LintProblem::NoMatch => String::from(
+				"subject does not match the lint pattern",
+			),
+			LintProblem::TooLong { length, max } => {
+				format!("subject too long ({}/{})", length, max)
+			}
+		})
+		.collect::<Vec<_>>()
+		.join(", ");
+
+	if acknowledged {
+		format!(
+			"{} {} (enter again to override)",
+			symbol::WARNING,
+			problems
+		)
+	} else {
+		format!("{} {}", symbol::WARNING, problems)
+	}
+}
+pub fn commit_protected_branch_warning(branch: &str) -> String {
+	format!("{} committing directly to {}", symbol::WARNING, branch)
+}
+pub fn unborn_branch_name(name: &str) -> String {
+	format!("{} (no commits yet)", name)
+}
+pub fn protected_branch_name(branch: &str) -> String {
+	format!("{} {}", symbol::WARNING, branch)
+}
+pub fn commits_to_push(count: usize) -> String {
+	format!(
+		"{} commit{} to push",
+		count,
+		if count == 1 { "" } else { "s" }
+	)
+}
+pub fn branch_name_error(error: BranchNameError) -> String {
+	let reason = match error {
+		BranchNameError::Empty => "it is empty".to_string(),
+		BranchNameError::Reserved => {
+			"'HEAD' and '@' are reserved".to_string()
+		}
+		BranchNameError::LeadingDash => {
+			"it must not start with '-'".to_string()
+		}
+		BranchNameError::InvalidChar(c) if c.is_whitespace() => {
+			"whitespace is not allowed".to_string()
+		}
+		BranchNameError::InvalidChar(c) => {
+			format!("'{}' is not allowed", c.escape_default())
+		}
+		BranchNameError::DoubleDot => {
+			"'..' is not allowed".to_string()
+		}
+		BranchNameError::AtBrace => "'@{' is not allowed".to_string(),
+		BranchNameError::EmptyComponent => {
+			"no leading, trailing or double '/'".to_string()
+		}
+		BranchNameError::LeadingDot => {
+			"no part may start with '.'".to_string()
+		}
+		BranchNameError::LockSuffix => {
+			"no part may end with '.lock'".to_string()
+		}
+		BranchNameError::TrailingDot => {
+			"it must not end with '.'".to_string()
+		}
+	};
+
+	format!("invalid name: {}", reason)
+}
+pub fn branch_name_suggestion(
+	suggestion: &str,
+	key_config: &SharedKeyConfig,
+) -> String {
+	format!(
+		"use '{}' [{}]",
+		suggestion,
+		key_config.get_hint(key_config.branch_name_suggestion),
+	)
 }
 pub fn commit_editor_msg(_key_config: &SharedKeyConfig) -> String {
 	r##"
@@ -146,26 +1027,226 @@ pub fn confirm_msg_merge(
 	}
 }
 
-pub fn confirm_title_abortmerge() -> String {
-	"Abort merge?".to_string()
+pub fn confirm_title_abort_repo_state(state: RepoState) -> String {
+	format!("Abort {}?", repo_state_name(state).to_lowercase())
 }
-pub fn confirm_msg_abortmerge() -> String {
+pub fn confirm_msg_abort_repo_state() -> String {
 	"This will revert all uncommitted changes. Are you sure?"
 		.to_string()
 }
-pub fn confirm_msg_reset() -> String {
-	"confirm file reset?".to_string()
+pub fn confirm_title_index_locked() -> String {
+	"Index locked".to_string()
 }
-pub fn confirm_msg_reset_lines(lines: usize) -> String {
+pub fn confirm_msg_index_locked(
+	key_config: &SharedKeyConfig,
+) -> String {
 	format!(
-		"are you sure you want to discard {} selected lines?",
-		lines
+		"Another git process is running and holds the index lock (.git/index.lock).\n\nRetry once it finished? If no git process is running, the lock can be removed in the repository maintenance [{}].",
+		key_config.get_hint(key_config.open_repo_maintenance)
 	)
 }
-pub fn confirm_msg_stashdrop(
-	_key_config: &SharedKeyConfig,
-	ids: &[CommitId],
-) -> String {
+pub fn confirm_title_log_export_overwrite() -> String {
+	"Overwrite file?".to_string()
+}
+pub fn confirm_msg_log_export_overwrite(path: &str) -> String {
+	format!("{} exists already. Overwrite it with the export?", path)
+}
+pub fn confirm_title_copy_discarded_hunk() -> String {
+	"Undo failed".to_string()
+}
+pub fn confirm_msg_copy_discarded_hunk(err: &str) -> String {
+	format!(
+		"The discarded hunk does not apply anymore:\n{}\n\nCopy its patch to the clipboard?",
+		err
+	)
+}
+pub fn confirm_title_skip_worktree() -> String {
+	"Skip worktree?".to_string()
+}
+pub fn confirm_msg_skip_worktree(path: &str) -> String {
+	format!(
+		"Flag '{}' skip-worktree?\n\nIts changes are hidden from the status, never staged and kept as they are by checkouts and resets, until the flag is cleared again.",
+		path
+	)
+}
+pub fn confirm_title_resolve_conflict(side: ConflictSide) -> String {
+	format!("Take {}?", conflict_side(side))
+}
+pub fn confirm_msg_resolve_conflict(
+	path: &str,
+	side: ConflictSide,
+) -> String {
+	format!(
+		"Resolve '{}' by taking the whole file from {} side?\n\nThe working copy and any resolution in it is overwritten. If {} side deleted the file it is deleted.",
+		path,
+		conflict_side(side),
+		conflict_side(side),
+	)
+}
+pub fn confirm_title_autosquash() -> String {
+	"Autosquash?".to_string()
+}
+pub fn confirm_msg_autosquash(onto: &str) -> String {
+	format!(
+		"Rebase the current branch onto {} squashing fixup!/squash! commits into the commits they name?",
+		onto
+	)
+}
+pub fn confirm_title_stage_lfs_unconverted() -> String {
+	"Stage without LFS?".to_string()
+}
+pub fn confirm_msg_stage_lfs_unconverted(files: &[String]) -> String {
+	const LISTED: usize = 5;
+
+	format!(
+		"These files are tracked by LFS in .gitattributes but are large binaries, not LFS pointers:\n\n{}{}\n\nStaging here runs no LFS filter and commits their raw content (`git add` on the command line converts them). Stage them anyway?",
+		files
+			.iter()
+			.take(LISTED)
+			.cloned()
+			.collect::<Vec<_>>()
+			.join("\n"),
+		if files.len() > LISTED {
+			format!("\nand {} more", files.len() - LISTED)
+		} else {
+			String::new()
+		}
+	)
+}
+pub fn confirm_title_stage_large_files() -> String {
+	"Stage large files?".to_string()
+}
+pub fn confirm_msg_stage_large_files(files: &[LargeFile]) -> String {
+	format!(
+		"These files are new or grew large since the last commit:\n\n{}\n\nLarge artifacts stay in the history once committed. Stage them anyway?",
+		large_files_list(files)
+	)
+}
+pub fn confirm_title_commit_empty() -> String {
+	"Create empty commit?".to_string()
+}
+pub fn confirm_msg_commit_empty() -> String {
+	"Nothing is staged, the commit will not change any file (e.g. to trigger CI). Create it anyway?".to_string()
+}
+pub fn confirm_title_commit_large_files() -> String {
+	"Commit large files?".to_string()
+}
+pub fn confirm_msg_commit_large_files(files: &[LargeFile]) -> String {
+	format!(
+		"These staged files are new or grew large since the last commit:\n\n{}\n\nLarge artifacts stay in the history once committed. Commit them anyway?",
+		large_files_list(files)
+	)
+}
+/// path and size, and the size in `HEAD` for grown files
+fn large_files_list(files: &[LargeFile]) -> String {
+	const LISTED: usize = 5;
+
+	let mut lines = files
+		.iter()
+		.take(LISTED)
+		.map(|file| {
+			let size = ByteSize::b(file.size).to_string_as(true);
+			file.head_size.map_or_else(
+				|| format!("{} ({}, new)", file.path, size),
+				|head_size| {
+					format!(
+						"{} ({}, was {})",
+						file.path,
+						size,
+						ByteSize::b(head_size).to_string_as(true)
+					)
+				},
+			)
+		})
+		.collect::<Vec<_>>();
+
+	if files.len() > LISTED {
+		lines.push(format!("and {} more", files.len() - LISTED));
+	}
+
+	lines.join("\n")
+}
+pub fn confirm_title_bisect_found() -> String {
+	"Bisect done".to_string()
+}
+pub fn confirm_msg_bisect_found(commit: &str) -> String {
+	format!(
+		"The first bad commit is:\n\n{}\n\nReset the bisect and select it in the log?",
+		commit
+	)
+}
+pub fn confirm_title_bisect_reset() -> String {
+	"Reset bisect?".to_string()
+}
+pub fn confirm_msg_bisect_reset() -> String {
+	"End the bisect and check out what was checked out before it started?"
+		.to_string()
+}
+pub fn confirm_title_cleanup_stale_state() -> String {
+	"Clean up?".to_string()
+}
+pub fn confirm_msg_cleanup_stale_state(state: &StaleState) -> String {
+	format!(
+		"{}\n\nMake sure no other git process is working on this repository. Clean up now?",
+		stale_state_name(state)
+	)
+}
+pub fn repo_state_name(state: RepoState) -> String {
+	match state {
+		RepoState::Clean => "Clean".to_string(),
+		RepoState::Merge => "Merge".to_string(),
+		RepoState::Rebase(_) => "Rebase".to_string(),
+		RepoState::CherryPick => "Cherry-pick".to_string(),
+		RepoState::Revert => "Revert".to_string(),
+		RepoState::Other => "Operation".to_string(),
+	}
+}
+pub fn repo_state_banner(
+	key_config: &SharedKeyConfig,
+	state: RepoState,
+	details: &str,
+) -> String {
+	let name = match state {
+		RepoState::Rebase(Some(progress)) => format!(
+			"{} {}/{}",
+			repo_state_name(state),
+			progress.current,
+			progress.total
+		),
+		_ => repo_state_name(state),
+	};
+	if state.is_pending() {
+		format!(
+			"{} in progress {}- resolve conflicts, then continue [{}] or abort [{}]",
+			name,
+			details,
+			key_config.get_hint(key_config.continue_merge),
+			key_config.get_hint(key_config.abort_merge),
+		)
+	} else {
+		format!("{} in progress {}", name, details)
+	}
+}
+pub fn repo_state_blocks(state: RepoState, action: &str) -> String {
+	format!(
+		"cannot {} while a {} is in progress, continue or abort it first",
+		action,
+		repo_state_name(state).to_lowercase()
+	)
+}
+pub fn confirm_msg_reset() -> String {
+	"confirm file reset?".to_string()
+}
+pub fn confirm_msg_reset_lines(lines: usize) -> String {
+	format!(
+		"are you sure you want to discard {} selected lines?",
+		lines
+	)
+}
+pub fn confirm_msg_stashdrop(
+	_key_config: &SharedKeyConfig,
+	ids: &[CommitId],
+) -> String {
 	format!(
 		"Sure you want to drop following {}stash{}?\n\n{}",
 		if ids.len() > 1 {
@@ -175,7 +1256,7 @@ pub fn confirm_msg_stashdrop(
 		},
 		if ids.len() > 1 { "es" } else { "" },
 		ids.iter()
-			.map(CommitId::get_short_string)
+			.map(|id| id.abbreviated(CWD))
 			.collect::<Vec<_>>()
 			.join(", ")
 	)
@@ -211,6 +1292,55 @@ pub fn confirm_msg_delete_remote_branch(
 ) -> String {
 	format!("Confirm deleting remote branch: '{}' ?", branch_ref)
 }
+pub fn confirm_title_rename_checked_out() -> String {
+	"Branch Checked Out".to_string()
+}
+pub fn confirm_msg_rename_checked_out(
+	branch_ref: &str,
+	new_name: &str,
+	worktree: &str,
+) -> String {
+	format!(
+		"'{}' is checked out in the worktree '{}'.\n\nRename it to '{}' anyway? The HEAD of that worktree follows the new name.",
+		branch_ref, worktree, new_name
+	)
+}
+pub fn confirm_title_delete_merged() -> String {
+	"Delete Merged Branches".to_string()
+}
+/// lists the first few names, counts the rest
+pub fn confirm_msg_delete_merged(branch_refs: &[String]) -> String {
+	const LISTED: usize = 10;
+
+	let names = branch_refs
+		.iter()
+		.take(LISTED)
+		.map(|r| r.strip_prefix("refs/heads/").unwrap_or(r))
+		.collect::<Vec<_>>()
+		.join(", ");
+
+	format!(
+		"Confirm deleting {} merged branches:\n\n{}{}",
+		branch_refs.len(),
+		names,
+		if branch_refs.len() > LISTED {
+			format!(" and {} more", branch_refs.len() - LISTED)
+		} else {
+			String::new()
+		}
+	)
+}
+pub fn delete_merged_failed(
+	failures: &[String],
+	total: usize,
+) -> String {
+	format!(
+		"failed to delete {} of {} merged branches:\n{}",
+		failures.len(),
+		total,
+		failures.join("\n")
+	)
+}
 pub fn confirm_title_delete_tag(
 	_key_config: &SharedKeyConfig,
 ) -> String {
@@ -236,9 +1366,198 @@ pub fn confirm_msg_force_push(
         branch_ref
     )
 }
+pub fn confirm_title_push_protected() -> String {
+	"Push to protected branch".to_string()
+}
+pub fn confirm_msg_push_protected(branch: &str) -> String {
+	format!(
+		"'{}' is a protected branch (see options), push anyway?",
+		branch
+	)
+}
 pub fn log_title(_key_config: &SharedKeyConfig) -> String {
 	"Commit".to_string()
 }
+pub fn log_title_filtered(
+	key_config: &SharedKeyConfig,
+	since: Option<&str>,
+	until: Option<&str>,
+	author: Option<&str>,
+) -> String {
+	let mut title = log_title(key_config);
+
+	let range = match (since, until) {
+		(Some(since), Some(until)) => {
+			Some(format!("{} .. {}", since, until))
+		}
+		(Some(since), None) => Some(format!("since {}", since)),
+		(None, Some(until)) => Some(format!("until {}", until)),
+		(None, None) => None,
+	};
+
+	if let Some(range) = range {
+		title.push_str(&format!(" [{}]", range));
+	}
+	if let Some(author) = author {
+		title.push_str(&format!(" [author: {}]", author));
+	}
+
+	title
+}
+pub fn log_authors_title(_key_config: &SharedKeyConfig) -> String {
+	"Top Authors".to_string()
+}
+pub fn jump_to_ref_title() -> String {
+	"Jump to Ref".to_string()
+}
+pub fn jump_to_ref_hint() -> String {
+	"type a ref name..".to_string()
+}
+pub fn jump_to_ref_no_hits() -> String {
+	"no matching ref".to_string()
+}
+pub const fn ref_kind(kind: RefKind) -> &'static str {
+	match kind {
+		RefKind::Head => "head",
+		RefKind::LocalBranch => "branch",
+		RefKind::RemoteBranch => "remote",
+		RefKind::Tag => "tag",
+		RefKind::Stash => "stash",
+	}
+}
+/// appended to the log title while waiting for the walk
+pub fn log_jump_progress(
+	name: &str,
+	searched: usize,
+	key_config: &SharedKeyConfig,
+) -> String {
+	format!(
+		" [jump to {}: {} commits searched, cancel [{}]]",
+		name,
+		searched,
+		key_config.get_hint(key_config.exit_popup),
+	)
+}
+pub fn log_jump_not_in_log(name: &str) -> String {
+	format!("'{}': commit not in current log", name)
+}
+/// documents the fields written
+pub fn log_export_popup_title(
+	count: usize,
+	format: LogExportFormat,
+) -> String {
+	format!(
+		"Export {} commit{} as {}",
+		count,
+		if count == 1 { "" } else { "s" },
+		match format {
+			LogExportFormat::Text => {
+				"text: short sha, date, [author], subject"
+			}
+			LogExportFormat::Csv => {
+				"csv (RFC 4180, header): sha,date,author,subject"
+			}
+		}
+	)
+}
+pub fn log_export_progress(
+	done: usize,
+	total: usize,
+	path: &str,
+) -> String {
+	format!("exporting {}/{} commits to\n{}", done, total, path)
+}
+pub fn toast_log_exported(count: usize, path: &str) -> String {
+	format!(
+		"exported {} commit{} to {}",
+		count,
+		if count == 1 { "" } else { "s" },
+		path
+	)
+}
+pub fn cherry_pick_applying(progress: &CherryPickProgress) -> String {
+	if progress.total == 0 {
+		return "preparing...".to_string();
+	}
+
+	format!(
+		"applying {}/{}: {}",
+		progress.current, progress.total, progress.subject
+	)
+}
+pub fn cherry_pick_stopped(progress: &CherryPickProgress) -> String {
+	format!(
+		"conflict at {}/{}: {}\n\nresolve it (close this to get to the changes) and continue, skip the commit or abort back to where the cherry-pick started",
+		progress.current, progress.total, progress.subject
+	)
+}
+pub fn toast_cherry_picked(
+	picked: usize,
+	empty: usize,
+	skipped: usize,
+) -> String {
+	let mut res = format!(
+		"cherry-picked {} commit{}",
+		picked,
+		if picked == 1 { "" } else { "s" }
+	);
+
+	let others = [(empty, "applied already"), (skipped, "skipped")]
+		.iter()
+		.filter(|(count, _)| *count > 0)
+		.map(|(count, what)| format!("{} {}", count, what))
+		.collect::<Vec<_>>();
+	if !others.is_empty() {
+		res = format!("{} ({})", res, others.join(", "));
+	}
+
+	res
+}
+pub fn toast_autosquash(conflict: bool) -> String {
+	if conflict {
+		"autosquash stopped at a conflict, resolve it and continue"
+			.to_string()
+	} else {
+		"rebased squashing fixups".to_string()
+	}
+}
+pub fn autosquash_unmatched(unmatched: &[TodoEntry]) -> String {
+	format!(
+		"no target found for these commits, they were kept as they are:\n\n{}",
+		unmatched
+			.iter()
+			.map(|entry| format!(
+				"{} {}",
+				entry.id.get_short_string(),
+				entry.subject
+			))
+			.collect::<Vec<_>>()
+			.join("\n")
+	)
+}
+pub fn init_repo_target(path: &str) -> String {
+	format!("no git repository in {}", path)
+}
+pub fn init_repo_nested(repo: &str) -> String {
+	format!(
+		"{} inside of the repository at {}, the new one is nested",
+		symbol::WARNING,
+		repo
+	)
+}
+pub fn init_repo_hint(key_config: &SharedKeyConfig) -> String {
+	format!(
+		"initialize [{}] quit [{}]",
+		key_config.get_hint(key_config.enter),
+		key_config.get_hint(key_config.exit_popup),
+	)
+}
+pub fn log_filter_title(_key_config: &SharedKeyConfig) -> String {
+	"Filter log".to_string()
+}
+pub fn log_filter_invalid_date(input: &str) -> String {
+	format!("invalid date: {}", input)
+}
 pub fn blame_title(_key_config: &SharedKeyConfig) -> String {
 	"Blame".to_string()
 }
@@ -256,6 +1575,9 @@ pub fn stashlist_title(_key_config: &SharedKeyConfig) -> String {
 pub fn help_title(_key_config: &SharedKeyConfig) -> String {
 	"Help: all commands".to_string()
 }
+pub fn title_files() -> String {
+	"Files".to_string()
+}
 pub fn stashing_files_title(_key_config: &SharedKeyConfig) -> String {
 	"Files to Stash".to_string()
 }
@@ -267,10 +1589,12 @@ pub fn stashing_options_title(
 pub fn loading_text(_key_config: &SharedKeyConfig) -> String {
 	"Loading ...".to_string()
 }
-pub fn create_branch_popup_title(
-	_key_config: &SharedKeyConfig,
-) -> String {
-	"Branch".to_string()
+pub fn create_branch_popup_title(orphan: bool) -> String {
+	if orphan {
+		"Orphan Branch (no history)".to_string()
+	} else {
+		"Branch".to_string()
+	}
 }
 pub fn create_branch_popup_msg(
 	_key_config: &SharedKeyConfig,
@@ -290,6 +1614,157 @@ pub fn password_popup_msg(_key_config: &SharedKeyConfig) -> String {
 	"type password".to_string()
 }
 
+pub fn fetch_popup_no_remotes() -> String {
+	"no remotes configured".to_string()
+}
+pub const fn fetch_popup_remote() -> &'static str {
+	"Remote"
+}
+pub const fn fetch_popup_scope() -> &'static str {
+	"Fetch"
+}
+pub fn fetch_popup_scope_current(branch: &str) -> String {
+	format!("current ({})", branch)
+}
+pub fn fetch_popup_scope_all() -> String {
+	"all branches".to_string()
+}
+pub fn fetch_popup_scope_branch() -> String {
+	"branch".to_string()
+}
+pub fn prune_popup_title(remote: &str) -> String {
+	format!("Prune {}", remote)
+}
+pub static PRUNE_POPUP_EMPTY: &str =
+	"nothing to prune, all remote tracking branches still exist on the remote";
+pub fn prune_popup_selected(selected: usize, total: usize) -> String {
+	format!("{} of {} will be deleted", selected, total)
+}
+pub fn compare_workdir_revision(path: &str, commit: &str) -> String {
+	format!("{} @ {}", path, commit)
+}
+pub static COMPARE_WORKDIR_CURRENT: &str = "working copy";
+pub static COMPARE_WORKDIR_DELETED: &str =
+	"deleted from the working tree";
+pub static COMPARE_WORKDIR_NOT_IN_COMMIT: &str =
+	"not part of this revision";
+pub static COMPARE_WORKDIR_BINARY: &str = "binary file";
+
+pub fn file_view_title(
+	source: &FileSource,
+	lines: usize,
+	complete: bool,
+) -> String {
+	let from = match source {
+		FileSource::WorkDir(_) => "working copy".to_string(),
+		FileSource::Index(_) => "staged".to_string(),
+		FileSource::Commit(id, _) => id.get_short_string(),
+	};
+	let more = if complete { "" } else { "+" };
+
+	format!("{} ({}) {}{} lines", source.path(), from, lines, more)
+}
+pub static FILE_VIEW_GOTO_HINT: &str = "go to line";
+pub static FILE_VIEW_BINARY: &str = "binary file";
+pub fn file_view_no_line(input: &str) -> String {
+	format!("not a line number: '{}'", input)
+}
+pub static BISECT_UNRELATED: &str =
+	"one of the marked commits has to be an ancestor of the other";
+pub static READONLY_BANNER: &str = "read-only";
+pub static OFFLINE_BANNER: &str = "offline";
+pub static SELECTION_MODE_EMPTY: &str =
+	"nothing to select in this panel";
+pub fn selection_mode_hint(key_config: &SharedKeyConfig) -> String {
+	format!(
+		"selection mode: select with the mouse to copy, [{}] or [{}] returns",
+		key_config.get_hint(key_config.exit_popup),
+		key_config.get_hint(key_config.selection_mode),
+	)
+}
+pub fn conflict_title(path: &str) -> String {
+	format!("Conflict: {}", path)
+}
+pub const fn conflict_side(side: ConflictSide) -> &'static str {
+	match side {
+		ConflictSide::Ours => "ours",
+		ConflictSide::Theirs => "theirs",
+	}
+}
+pub static CONFLICT_PANES: [&str; 4] =
+	["Merged", "Base", "Ours", "Theirs"];
+pub static CONFLICT_MERGED_DELETED: &str =
+	"deleted in the working tree";
+pub static CONFLICT_BASE_MISSING: &str =
+	"not in the merge base, added on both sides";
+pub static CONFLICT_DELETED_BY_US: &str = "deleted by us";
+pub static CONFLICT_DELETED_BY_THEM: &str = "deleted by them";
+pub static MERGETOOL_NAME: &str = "git mergetool";
+pub fn resolve_conflict_failed(err: &str) -> String {
+	format!("resolving the conflict failed:\n{}", err)
+}
+pub static BASE_BRANCH_NONE: &str =
+	"no base branch found, set one with `git config gitui.baseBranch <branch>`";
+pub static BASE_BRANCH_NO_OTHER: &str =
+	"no other base branch to pick";
+pub fn readonly_rejected(what: &str) -> String {
+	format!("{}: not available in read-only mode", what)
+}
+pub fn offline_rejected(what: &str) -> String {
+	format!("{}: not available in offline mode", what)
+}
+pub fn bisect_banner(step: &BisectStep) -> String {
+	match step {
+		BisectStep::Test { left, steps } => format!(
+			"bisecting: {} revisions left, ~{} steps",
+			left, steps
+		),
+		BisectStep::Incomplete => {
+			"bisecting: mark a good and a bad commit".to_string()
+		}
+		BisectStep::Found(id) => format!(
+			"bisecting: first bad commit is {}",
+			id.get_short_string()
+		),
+		BisectStep::Inconclusive(ids) => format!(
+			"bisecting: first bad commit is one of {} skipped",
+			ids.len()
+		),
+	}
+}
+pub fn bisect_inconclusive(ids: &[CommitId]) -> String {
+	format!(
+		"only skipped commits are left, the first bad commit is one of:\n\n{}",
+		ids.iter()
+			.map(CommitId::get_short_string)
+			.collect::<Vec<_>>()
+			.join("\n")
+	)
+}
+
+pub fn stage_pattern_popup_title(
+	unstage: bool,
+	matches: Option<usize>,
+) -> String {
+	let title = if unstage {
+		"Unstage by pattern"
+	} else {
+		"Stage by pattern"
+	};
+
+	matches.map_or_else(
+		|| title.to_string(),
+		|count| format!("{} [{} matching]", title, count),
+	)
+}
+
+pub fn branch_description_popup_title(branch: &str) -> String {
+	format!("Description of '{}'", branch)
+}
+pub fn branch_description_popup_msg() -> String {
+	"empty removes the description".to_string()
+}
+
 pub fn rename_branch_popup_title(
 	_key_config: &SharedKeyConfig,
 ) -> String {
@@ -303,6 +1778,7 @@ pub fn rename_branch_popup_msg(
 
 pub mod commit {
 	use crate::keys::SharedKeyConfig;
+	use asyncgit::sync::MergeDiffMode;
 	pub fn details_author() -> String {
 		"Author: ".to_string()
 	}
@@ -342,11 +1818,26 @@ pub mod commit {
 	) -> String {
 		"Files:".to_string()
 	}
+	pub fn details_notes_title() -> String {
+		"Notes:".to_string()
+	}
+	pub const fn merge_diff_mode(
+		mode: MergeDiffMode,
+	) -> &'static str {
+		match mode {
+			MergeDiffMode::FirstParent => "vs 1st parent",
+			MergeDiffMode::SecondParent => "vs 2nd parent",
+			MergeDiffMode::Combined => "combined",
+		}
+	}
 }
 
 pub mod commands {
+	use super::{conflict_side, repo_state_name, symbol};
 	use crate::components::CommandText;
 	use crate::keys::SharedKeyConfig;
+	use asyncgit::sync::{ConflictSide, RepoState};
+	use crossterm::event::KeyEvent;
 
 	static CMD_GROUP_GENERAL: &str = "-- General --";
 	static CMD_GROUP_DIFF: &str = "-- Diff --";
@@ -356,6 +1847,7 @@ pub mod commands {
 	static CMD_GROUP_STASHES: &str = "-- Stashes --";
 	static CMD_GROUP_LOG: &str = "-- Log --";
 	static CMD_GROUP_BRANCHES: &str = "-- Branches --";
+	static CMD_GROUP_CUSTOM: &str = "-- Custom --";
 
 	pub fn toggle_tabs(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
@@ -366,6 +1858,7 @@ pub mod commands {
 			"switch to next tab",
 			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.tab_toggle)
 	}
 	pub fn find_file(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
@@ -376,6 +1869,7 @@ pub mod commands {
 			"find file in tree",
 			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.file_find)
 	}
 	pub fn toggle_tabs_direct(
 		key_config: &SharedKeyConfig,
@@ -404,6 +1898,7 @@ pub mod commands {
 			"open options popup",
 			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.open_options)
 	}
 	pub fn help_open(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
@@ -414,6 +1909,7 @@ pub mod commands {
 			"open this help screen",
 			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.open_help)
 	}
 	pub fn navigate_commit_message(
 		key_config: &SharedKeyConfig,
@@ -428,6 +1924,43 @@ pub mod commands {
 			CMD_GROUP_GENERAL,
 		)
 	}
+	pub fn message_link_next_prev(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Next/Prev link [{}{}]",
+				key_config.get_hint(key_config.message_link_next),
+				key_config.get_hint(key_config.message_link_prev)
+			),
+			"select next/previous url or issue reference",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn message_link_open(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Open link [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"open the selected link in the browser",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.enter)
+	}
+	pub fn options_edit(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Edit [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"edit the selected option",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.enter)
+	}
 	pub fn navigate_tree(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -467,44 +2000,411 @@ pub mod commands {
 			"mark multiple commits",
 			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.log_mark_commit)
 	}
-	pub fn copy(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn log_date_format(
+		key_config: &SharedKeyConfig,
+		format: &str,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Copy [{}]",
-				key_config.get_hint(key_config.copy),
+				"Date: {} [{}]",
+				format,
+				key_config.get_hint(key_config.log_date_format),
 			),
-			"copy selected lines to clipboard",
-			CMD_GROUP_DIFF,
+			"cycle date format (relative, short, full)",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.log_date_format)
 	}
-	pub fn copy_hash(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn log_grouping(
+		key_config: &SharedKeyConfig,
+		grouping: &str,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Copy Hash [{}]",
-				key_config.get_hint(key_config.copy),
+				"Group: {} [{}]",
+				grouping,
+				key_config.get_hint(key_config.log_grouping),
 			),
-			"copy selected commit hash to clipboard",
-			CMD_GROUP_LOG,
+			"cycle header rows between days, weeks and months",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.log_grouping)
 	}
-	pub fn push_tags(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn copy(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Push Tags [{}]",
-				key_config.get_hint(key_config.push),
+				"Copy [{}]",
+				key_config.get_hint(key_config.copy),
 			),
-			"push tags to remote",
-			CMD_GROUP_LOG,
+			"copy selected lines to clipboard",
+			CMD_GROUP_DIFF,
 		)
+		.key(key_config.copy)
 	}
-	pub fn diff_home_end(
+	pub fn diff_copy_hunk(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Jump up/down [{},{},{},{}]",
-				key_config.get_hint(key_config.home),
+				"Copy hunk [{}]",
+				key_config.get_hint(key_config.diff_copy_hunk),
+			),
+			"copy hunk under the cursor as unified diff",
+			CMD_GROUP_DIFF,
+		)
+		.key(key_config.diff_copy_hunk)
+	}
+	pub fn diff_copy_old(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Copy old [{}]",
+				key_config.get_hint(key_config.diff_copy_old),
+			),
+			"copy old side (pre-image) of the hunk under the cursor",
+			CMD_GROUP_DIFF,
+		)
+		.key(key_config.diff_copy_old)
+	}
+	pub fn diff_copy_new(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Copy new [{}]",
+				key_config.get_hint(key_config.diff_copy_new),
+			),
+			"copy new side (post-image) of the hunk under the cursor",
+			CMD_GROUP_DIFF,
+		)
+		.key(key_config.diff_copy_new)
+	}
+	pub fn diff_search(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Search [{}]",
+				key_config.get_hint(key_config.diff_search),
+			),
+			"search within the diff",
+			CMD_GROUP_DIFF,
+		)
+		.key(key_config.diff_search)
+	}
+	pub fn diff_search_next_prev(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Next/Prev match [{}{}]",
+				key_config.get_hint(key_config.diff_search_next),
+				key_config.get_hint(key_config.diff_search_prev),
+			),
+			"jump to the next or previous search match",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_search_clear(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Clear search [{}]",
+				key_config.get_hint(key_config.exit_popup),
+			),
+			"clear the search highlights",
+			CMD_GROUP_DIFF,
+		)
+		.key(key_config.exit_popup)
+	}
+	pub fn diff_search_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Search [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"search for the entered text",
+			CMD_GROUP_DIFF,
+		)
+		.key(key_config.enter)
+	}
+	pub fn copy_hash(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Copy Hash [{}]",
+				key_config.get_hint(key_config.copy),
+			),
+			"copy selected commit hash to clipboard",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.copy)
+	}
+	pub fn copy_path(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Copy Path [{}]",
+				key_config.get_hint(key_config.copy),
+			),
+			"copy repo relative path of the selected file",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.copy)
+	}
+	pub fn copy_absolute_path(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Copy Abs. Path [{}]",
+				key_config.get_hint(key_config.copy_absolute_path),
+			),
+			"copy absolute path of the selected file",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.copy_absolute_path)
+	}
+	pub fn open_containing_dir(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Reveal [{}]",
+				key_config.get_hint(key_config.open_containing_dir),
+			),
+			"open the directory of the selected file in the file manager",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.open_containing_dir)
+	}
+	pub fn log_filter(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Filter [{}]",
+				key_config.get_hint(key_config.log_filter),
+			),
+			"limit the log to commits in a date range",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.log_filter)
+	}
+	pub fn log_filter_author(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Same Author [{}]",
+				key_config.get_hint(key_config.log_filter_author),
+			),
+			"limit the log to commits by the selected commit's author",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.log_filter_author)
+	}
+	pub fn log_filter_author_clear(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Any Author [{}]",
+				key_config.get_hint(key_config.exit_popup),
+			),
+			"remove the author filter",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.exit_popup)
+	}
+	pub fn log_authors(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Authors [{}]",
+				key_config.get_hint(key_config.log_authors),
+			),
+			"pick one of the top authors to filter the log by",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.log_authors)
+	}
+	pub fn log_jump_to_ref(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Jump to Ref [{}]",
+				key_config.get_hint(key_config.log_jump_to_ref),
+			),
+			"select the commit a branch, tag or stash points to",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.log_jump_to_ref)
+	}
+	pub fn log_jump_cancel(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Cancel Jump [{}]",
+				key_config.get_hint(key_config.exit_popup),
+			),
+			"stop waiting for the log to get to the ref",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.exit_popup)
+	}
+	pub fn jump_to_ref_select(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Jump [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"select the commit of the ref in the log",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.enter)
+	}
+	pub fn log_export(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Export [{}]",
+				key_config.get_hint(key_config.log_export),
+			),
+			"write the log as loaded (and filtered) to a text or csv file",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.log_export)
+	}
+	pub fn log_autosquash(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Autosquash [{}]",
+				key_config.get_hint(key_config.rebase_autosquash),
+			),
+			"rebase onto the selected commit squashing fixup!/squash! commits into their targets",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.rebase_autosquash)
+		.mutating()
+	}
+	pub fn log_cherry_pick(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Cherry-pick [{}]",
+				key_config.get_hint(key_config.cherry_pick),
+			),
+			"cherry-pick the selected commit, the marked one or the range between two marked commits onto the current branch",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.cherry_pick)
+		.mutating()
+	}
+	pub fn cherry_pick_skip(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Skip commit [{}]",
+				key_config.get_hint(key_config.cherry_pick_skip),
+			),
+			"drop the commit the cherry-pick stopped at and go on with the next one",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.cherry_pick_skip)
+		.mutating()
+	}
+	pub fn log_export_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Export [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"export the log to the path entered",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.enter)
+	}
+	pub fn log_export_background(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Continue in background [{}]",
+				key_config.get_hint(key_config.exit_popup),
+			),
+			"close the progress, the export keeps running",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.exit_popup)
+	}
+	pub fn log_authors_select(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Filter [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"limit the log to the selected author",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.enter)
+	}
+	pub fn log_filter_switch(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Since/Until [{}]",
+				key_config.get_hint(key_config.tab_toggle),
+			),
+			"switch between the since and until field",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.tab_toggle)
+	}
+	pub fn log_filter_apply(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Apply [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"filter the log, empty fields clear the filter",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.enter)
+	}
+	pub fn push_tags(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Push Tags [{}]",
+				key_config.get_hint(key_config.push),
+			),
+			"push tags to remote",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.push)
+		.mutating()
+		.remote()
+	}
+	pub fn diff_home_end(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Jump up/down [{},{},{},{}]",
+				key_config.get_hint(key_config.home),
 				key_config.get_hint(key_config.end),
 				key_config.get_hint(key_config.move_up),
 				key_config.get_hint(key_config.move_down)
@@ -512,709 +2412,2161 @@ pub mod commands {
 			"scroll to top or bottom of diff",
 			CMD_GROUP_DIFF,
 		)
+		.hide_help()
+	}
+	pub fn diff_hunk_next_prev(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Next/Prev hunk [{}{}]",
+				key_config.get_hint(key_config.diff_hunk_next),
+				key_config.get_hint(key_config.diff_hunk_prev),
+			),
+			"jump to next or previous hunk, in commits continues with the next or previous file",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_hunk_add(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Stage hunk [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"stage selected hunk (working dir to index)",
+			CMD_GROUP_DIFF,
+		)
+		.key(key_config.enter)
+		.mutating()
+	}
+	pub fn diff_hunk_revert(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Reset hunk [{}]",
+				key_config.get_hint(key_config.status_reset_item),
+			),
+			"reverts selected hunk",
+			CMD_GROUP_DIFF,
+		)
+		.key(key_config.status_reset_item)
+		.mutating()
+	}
+	pub fn diff_hunk_split(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Split hunk [{}]",
+				key_config.get_hint(key_config.diff_split_hunk),
+			),
+			"split selected hunk at the unchanged lines between its changes",
+			CMD_GROUP_DIFF,
+		)
+		.key(key_config.diff_split_hunk)
+	}
+	pub fn diff_blame_line(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Blame line [{}]",
+				key_config.get_hint(key_config.blame),
+			),
+			"blame the file at the revision the selected line is from",
+			CMD_GROUP_DIFF,
+		)
+		.key(key_config.blame)
+	}
+	pub fn diff_lfs_pointer(
+		key_config: &SharedKeyConfig,
+		raw: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"{} [{}]",
+				if raw { "LFS object" } else { "LFS pointer" },
+				key_config.get_hint(key_config.diff_lfs_pointer),
+			),
+			"toggle between the lfs object and the raw pointer file",
+			CMD_GROUP_DIFF,
+		)
+		.key(key_config.diff_lfs_pointer)
+	}
+	pub fn diff_show_whitespace(
+		key_config: &SharedKeyConfig,
+		shown: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"{} whitespace [{}]",
+				if shown { "Hide" } else { "Show" },
+				key_config.get_hint(key_config.diff_show_whitespace),
+			),
+			"mark tabs and spaces in added and removed lines",
+			CMD_GROUP_DIFF,
+		)
+		.key(key_config.diff_show_whitespace)
+	}
+	pub fn diff_jump_moved(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Jump moved [{}]",
+				key_config.get_hint(key_config.diff_jump_moved),
+			),
+			"go to where the selected moved line came from or went to",
+			CMD_GROUP_DIFF,
+		)
+		.key(key_config.diff_jump_moved)
+	}
+	pub fn diff_lines_revert(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Reset lines [{}]",
+				key_config.get_hint(key_config.diff_reset_lines),
+			),
+			"resets selected lines",
+			CMD_GROUP_DIFF,
+		)
+		.key(key_config.diff_reset_lines)
+		.mutating()
+	}
+	pub fn diff_lines_stage(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Stage lines [{}]",
+				key_config.get_hint(key_config.diff_stage_lines),
+			),
+			"stage selected lines",
+			CMD_GROUP_DIFF,
+		)
+		.key(key_config.diff_stage_lines)
+		.mutating()
+	}
+	pub fn diff_lines_unstage(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Unstage lines [{}]",
+				key_config.get_hint(key_config.diff_stage_lines),
+			),
+			"unstage selected lines",
+			CMD_GROUP_DIFF,
+		)
+		.key(key_config.diff_stage_lines)
+		.mutating()
+	}
+	pub fn diff_hunk_remove(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Unstage hunk [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"unstage selected hunk (index to working dir), the working dir stays untouched",
+			CMD_GROUP_DIFF,
+		)
+		.key(key_config.enter)
+		.mutating()
+	}
+	pub fn msg_history_open(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Messages [{}]",
+				key_config.get_hint(key_config.open_msg_history),
+			),
+			"show history of errors and notifications",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.open_msg_history)
+	}
+	pub fn timings_open(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Timings [{}]",
+				key_config.get_hint(key_config.open_timings),
+			),
+			"show how long git operations took while recording",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.open_timings)
+	}
+	pub fn status_resolve_conflict(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Resolve conflict [{}]",
+				key_config
+					.get_hint(key_config.status_resolve_conflict),
+			),
+			"show base, ours and theirs of the conflicted file",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key_config.status_resolve_conflict)
+	}
+	pub fn conflict_switch_pane(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Switch version [{}]",
+				key_config.get_hint(key_config.tab_toggle),
+			),
+			"switch between the merged file, base, ours and theirs",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key_config.tab_toggle)
+	}
+	pub fn conflict_take_side(
+		key_config: &SharedKeyConfig,
+		side: ConflictSide,
+	) -> CommandText {
+		let key = match side {
+			ConflictSide::Ours => key_config.conflict_take_ours,
+			ConflictSide::Theirs => key_config.conflict_take_theirs,
+		};
+
+		CommandText::new(
+			format!(
+				"Take {} [{}]",
+				conflict_side(side),
+				key_config.get_hint(key),
+			),
+			"resolve the conflict with the whole file of one side",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key)
+		.mutating()
+	}
+	pub fn conflict_mergetool(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Mergetool [{}]",
+				key_config.get_hint(key_config.conflict_mergetool),
+			),
+			"resolve the conflict in the configured git mergetool",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key_config.conflict_mergetool)
+		.mutating()
+	}
+	pub fn selection_mode(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Select Text [{}]",
+				key_config.get_hint(key_config.selection_mode),
+			),
+			"show the focused panel as plain text to select and copy with the mouse",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.selection_mode)
+	}
+	pub fn toggle_offline(
+		key_config: &SharedKeyConfig,
+		offline: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"{} [{}]",
+				if offline { "Go online" } else { "Go offline" },
+				key_config.get_hint(key_config.toggle_offline),
+			),
+			"stop or resume push, fetch, pull and auto fetch",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.toggle_offline)
+	}
+	pub fn timings_record(
+		key_config: &SharedKeyConfig,
+		recording: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"{} [{}]",
+				if recording { "Pause" } else { "Record" },
+				key_config.get_hint(key_config.enter),
+			),
+			"start or stop measuring git operations",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.enter)
+	}
+	pub fn timings_reset(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Reset [{}]",
+				key_config.get_hint(key_config.timings_reset),
+			),
+			"forget the timings measured so far",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.timings_reset)
+	}
+	pub fn repo_maintenance_open(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Maintenance [{}]",
+				key_config.get_hint(key_config.open_repo_maintenance),
+			),
+			"find and clean up stale locks and leftover state",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.open_repo_maintenance)
+	}
+	pub fn repo_switcher_open(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Repos [{}]",
+				key_config.get_hint(key_config.open_repo_switcher),
+			),
+			"switch to another repo or worktree without restarting",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.open_repo_switcher)
+	}
+	pub fn command_palette_open(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Commands [{}]",
+				key_config.get_hint(key_config.open_command_palette),
+			),
+			"find and run any command by name",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.open_command_palette)
+	}
+	pub fn command_palette_run(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Run [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"run the selected command",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.enter)
+	}
+	pub fn undo_index_change(
+		key_config: &SharedKeyConfig,
+		count: usize,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Undo Index [{}] ({})",
+				key_config.get_hint(key_config.undo_index_change),
+				count,
+			),
+			"restore the index as it was before the last stage/unstage",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key_config.undo_index_change)
+		.mutating()
+	}
+	pub fn undo_hunk_discard(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Undo Hunk Discard [{}]",
+				key_config.get_hint(key_config.undo_hunk_discard),
+			),
+			"apply the latest discarded hunk again, only for a few seconds after discarding it",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key_config.undo_hunk_discard)
+		.mutating()
+	}
+	pub fn restore_discarded(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Restore Discarded [{}]",
+				key_config.get_hint(key_config.restore_discarded),
+			),
+			"bring back the working dir changes of the last discard",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key_config.restore_discarded)
+		.mutating()
+	}
+	pub fn preflight_open(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Preflight [{}]",
+				key_config.get_hint(key_config.open_preflight),
+			),
+			"check git identity, editor and repo ownership",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.open_preflight)
+	}
+	pub fn bookmark_add(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Bookmark [{}]",
+				key_config.get_hint(key_config.bookmark_add),
+			),
+			"bookmark the selected commit or file to go back to it later",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.bookmark_add)
+	}
+	pub fn bookmarks_open(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Bookmarks [{}]",
+				key_config.get_hint(key_config.open_bookmarks),
+			),
+			"list the bookmarks to go back to one of them",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.open_bookmarks)
+	}
+	pub fn bookmark_goto(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Go to [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"select the bookmarked commit or file in its view",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.enter)
+	}
+	pub fn bookmark_delete(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Delete [{}]",
+				key_config.get_hint(key_config.bookmark_delete),
+			),
+			"forget the selected bookmark",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.bookmark_delete)
+	}
+	pub fn status_index_flags(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Index flags [{}]",
+				key_config.get_hint(key_config.status_index_flags),
+			),
+			"list files hidden by skip-worktree or assume-unchanged",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key_config.status_index_flags)
+	}
+	pub fn status_skip_worktree(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Skip worktree [{}]",
+				key_config.get_hint(key_config.status_skip_worktree),
+			),
+			"hide the changes of the selected file by flagging it skip-worktree",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key_config.status_skip_worktree)
+		.mutating()
+	}
+	pub fn index_flag_clear(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Clear flags [{}]",
+				key_config.get_hint(key_config.index_flag_clear),
+			),
+			"clear the index flags of the selected file to see its changes again",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key_config.index_flag_clear)
+		.mutating()
+	}
+	pub fn git_config_open(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Git config [{}]",
+				key_config.get_hint(key_config.open_git_config),
+			),
+			"view and edit common git config values",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.open_git_config)
+	}
+	pub fn git_config_edit(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Edit [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"toggle or edit the selected value",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.enter)
+		.mutating()
+	}
+	pub fn git_config_unset(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Unset [{}]",
+				key_config.get_hint(key_config.git_config_unset),
+			),
+			"remove the selected value from the edited config",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.git_config_unset)
+		.mutating()
+	}
+	pub fn preflight_apply(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Apply [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"write config value and continue",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.enter)
+		.mutating()
+	}
+	pub fn preflight_toggle_scope(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Global/Local [{}]",
+				key_config.get_hint(key_config.tab_toggle),
+			),
+			"write to the global or the repo config",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.tab_toggle)
+	}
+	pub fn repo_maintenance_cleanup(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Clean up [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"clean up selected item (asks to confirm)",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.enter)
+		.mutating()
+	}
+	pub fn msg_history_copy(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Copy [{}]",
+				key_config.get_hint(key_config.copy),
+			),
+			"copy selected message to clipboard",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.copy)
+	}
+	pub fn close_popup(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Close [{}]",
+				key_config.get_hint(key_config.exit_popup),
+			),
+			"close overlay (e.g commit, help)",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.exit_popup)
+	}
+	pub fn new_line(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"New Line [{}]",
+				key_config.get_hint(key_config.new_line),
+			),
+			"insert a line break",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.new_line)
+	}
+	pub fn close_msg(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Close [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"close msg popup (e.g msg)",
+			CMD_GROUP_GENERAL,
+		)
+		.hide_help()
+		.key(key_config.enter)
+	}
+	pub fn hooks_cancel(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Cancel [{}]",
+				key_config.get_hint(key_config.exit_popup),
+			),
+			"terminate the running commit hook",
+			CMD_GROUP_COMMIT,
+		)
+		.key(key_config.exit_popup)
+	}
+	pub fn hooks_close(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Back to message [{}]",
+				key_config.get_hint(key_config.exit_popup),
+			),
+			"close hook output and edit the commit message again",
+			CMD_GROUP_COMMIT,
+		)
+		.key(key_config.exit_popup)
+	}
+	pub fn validate_msg(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Validate [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"validate msg",
+			CMD_GROUP_GENERAL,
+		)
+		.hide_help()
+		.key(key_config.enter)
+	}
+
+	pub fn abort_merge(
+		key_config: &SharedKeyConfig,
+		state: RepoState,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Abort {} [{}]",
+				repo_state_name(state).to_lowercase(),
+				key_config.get_hint(key_config.abort_merge),
+			),
+			"abort ongoing merge, rebase, cherry-pick or revert",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.abort_merge)
+		.mutating()
+	}
+	pub fn continue_merge(
+		key_config: &SharedKeyConfig,
+		state: RepoState,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Continue {} [{}]",
+				repo_state_name(state).to_lowercase(),
+				key_config.get_hint(key_config.continue_merge),
+			),
+			"continue ongoing merge, rebase, cherry-pick or revert once conflicts are resolved",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.continue_merge)
+		.mutating()
+	}
+	pub fn select_staging(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"To stage [{}]",
+				key_config.get_hint(key_config.toggle_workarea),
+			),
+			"focus/select staging area",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.toggle_workarea)
+	}
+	pub fn select_unstaged(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"To unstaged [{}]",
+				key_config.get_hint(key_config.toggle_workarea),
+			),
+			"focus/select unstaged area",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.toggle_workarea)
+	}
+	pub fn status_split(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Resize [{}{}]",
+				key_config.get_hint(key_config.status_split_left),
+				key_config.get_hint(key_config.status_split_right),
+			),
+			"move the split between the file lists and the diff",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn focus_cycle(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Next/Prev Pane [{}{}]",
+				key_config.get_hint(key_config.focus_next),
+				key_config.get_hint(key_config.focus_prev),
+			),
+			"move the focus to the next/previous pane of the tab",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn undo_commit(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Undo Commit [{}]",
+				key_config.get_hint(key_config.undo_commit),
+			),
+			"undo last commit",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.undo_commit)
+		.mutating()
+	}
+	pub fn commit_open(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Commit [{}]",
+				key_config.get_hint(key_config.open_commit),
+			),
+			"open commit popup (available in non-empty stage)",
+			CMD_GROUP_COMMIT,
+		)
+		.key(key_config.open_commit)
+		.mutating()
+	}
+	pub fn commit_open_editor(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Open editor [{}]",
+				key_config.get_hint(key_config.open_commit_editor),
+			),
+			"open commit editor (available in commit popup)",
+			CMD_GROUP_COMMIT,
+		)
+		.key(key_config.open_commit_editor)
+		.mutating()
+	}
+	pub fn commit_enter(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Commit [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"commit (available when commit message is non-empty)",
+			CMD_GROUP_COMMIT,
+		)
+		.hide_help()
+		.key(key_config.enter)
+		.mutating()
+	}
+	pub fn commit_amend(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Amend [{}]",
+				key_config.get_hint(key_config.commit_amend),
+			),
+			"amend last commit (available in commit popup)",
+			CMD_GROUP_COMMIT,
+		)
+		.key(key_config.commit_amend)
+		.mutating()
+	}
+	pub fn commit_signoff(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Sign-off [{}]",
+				key_config.get_hint(key_config.commit_signoff),
+			),
+			"add Signed-off-by trailer (available in commit popup)",
+			CMD_GROUP_COMMIT,
+		)
+		.key(key_config.commit_signoff)
+	}
+	pub fn commit_amend_dates(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Amend dates [{}]",
+				key_config.get_hint(key_config.commit_amend_dates),
+			),
+			"change author and committer date of the amended commit (available when amending)",
+			CMD_GROUP_COMMIT,
+		)
+		.key(key_config.commit_amend_dates)
+	}
+	pub fn commit_goto_changes(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Back to changes [{}]",
+				key_config.get_hint(key_config.commit_goto_changes),
+			),
+			"close the commit popup to stage changes (nothing staged)",
+			CMD_GROUP_COMMIT,
+		)
+		.key(key_config.commit_goto_changes)
+	}
+	pub fn edit_item(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Edit [{}]",
+				key_config.get_hint(key_config.edit_file),
+			),
+			"edit the currently selected file in an external editor",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key_config.edit_file)
+		.mutating()
+	}
+	pub fn stage_item(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Stage [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"stage currently selected file or entire path",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key_config.enter)
+		.mutating()
+	}
+	pub fn add_gitkeep(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Add .gitkeep [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"add an empty .gitkeep to the selected directory and stage it, git cannot track empty directories",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key_config.enter)
+		.mutating()
+	}
+	pub fn stage_all(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Stage All [{}]",
+				key_config.get_hint(key_config.status_stage_all),
+			),
+			"stage all changes (in unstaged files)",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key_config.status_stage_all)
+		.mutating()
+	}
+	pub fn unstage_item(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Unstage [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"unstage currently selected file or entire path",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key_config.enter)
+		.mutating()
+	}
+	pub fn unstage_all(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Unstage all [{}]",
+				key_config.get_hint(key_config.status_stage_all),
+			),
+			"unstage all files (in staged files)",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key_config.status_stage_all)
+		.mutating()
+	}
+	pub fn reset_item(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Reset [{}]",
+				key_config.get_hint(key_config.status_reset_item),
+			),
+			"revert changes in selected file or entire path",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key_config.status_reset_item)
+		.mutating()
+	}
+	pub fn ignore_item(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Ignore [{}]",
+				key_config.get_hint(key_config.status_ignore_file),
+			),
+			"Add file or path to .gitignore",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key_config.status_ignore_file)
+		.mutating()
+	}
+	pub fn load_all_untracked(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Load all untracked [{}]",
+				key_config
+					.get_hint(key_config.status_load_all_untracked),
+			),
+			"load all untracked files ignoring the limit",
+			CMD_GROUP_CHANGES,
+		)
+		.key(key_config.status_load_all_untracked)
+	}
+
+	pub fn diff_focus_left(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Back [{}]",
+				key_config.get_hint(key_config.focus_left),
+			),
+			"view and select changed files",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.focus_left)
+	}
+	pub fn diff_focus_right(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Diff [{}]",
+				key_config.get_hint(key_config.focus_right),
+			),
+			"inspect file diff",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.focus_right)
+	}
+	pub fn quit(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Quit [{}]",
+				key_config.get_hint(key_config.exit),
+			),
+			"quit gitui application",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.exit)
+	}
+	pub fn confirm_action(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Confirm [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"confirm action",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.enter)
+	}
+	pub fn stashing_save(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Save [{}]",
+				key_config.get_hint(key_config.stashing_save),
+			),
+			"opens stash name input popup",
+			CMD_GROUP_STASHING,
+		)
+		.key(key_config.stashing_save)
+		.mutating()
+	}
+	pub fn stashing_toggle_indexed(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Toggle Staged [{}]",
+				key_config.get_hint(key_config.stashing_toggle_index),
+			),
+			"toggle including staged files into stash",
+			CMD_GROUP_STASHING,
+		)
+		.key(key_config.stashing_toggle_index)
+	}
+	pub fn stashing_toggle_untracked(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Toggle Untracked [{}]",
+				key_config
+					.get_hint(key_config.stashing_toggle_untracked),
+			),
+			"toggle including untracked files into stash",
+			CMD_GROUP_STASHING,
+		)
+		.key(key_config.stashing_toggle_untracked)
+	}
+	pub fn stash_msg_toggle_untracked(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Toggle Untracked [{}]",
+				key_config
+					.get_hint(key_config.stash_msg_toggle_untracked),
+			),
+			"toggle including untracked files into stash",
+			CMD_GROUP_STASHING,
+		)
+		.key(key_config.stash_msg_toggle_untracked)
+	}
+	pub fn stash_msg_toggle_index(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Toggle Keep Index [{}]",
+				key_config
+					.get_hint(key_config.stash_msg_toggle_index),
+			),
+			"toggle keeping staged changes in the index",
+			CMD_GROUP_STASHING,
+		)
+		.key(key_config.stash_msg_toggle_index)
+	}
+	pub fn stashing_confirm_msg(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Stash [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"save files to stash",
+			CMD_GROUP_STASHING,
+		)
+		.key(key_config.enter)
+		.mutating()
+	}
+	pub fn stashlist_apply(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Apply [{}]",
+				key_config.get_hint(key_config.stash_apply),
+			),
+			"apply selected stash",
+			CMD_GROUP_STASHES,
+		)
+		.key(key_config.stash_apply)
+		.mutating()
+	}
+	pub fn stashlist_drop(
+		key_config: &SharedKeyConfig,
+		marked: usize,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Drop{} [{}]",
+				if marked == 0 {
+					String::default()
+				} else {
+					format!(" {}", marked)
+				},
+				key_config.get_hint(key_config.stash_drop),
+			),
+			"drop selected stash",
+			CMD_GROUP_STASHES,
+		)
+		.key(key_config.stash_drop)
+		.mutating()
+	}
+	pub fn stashlist_pop(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Pop [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"pop selected stash",
+			CMD_GROUP_STASHES,
+		)
+		.key(key_config.enter)
+		.mutating()
+	}
+	pub fn stashlist_inspect(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Inspect [{}]",
+				key_config.get_hint(key_config.focus_right),
+			),
+			"open stash commit details (allows to diff files)",
+			CMD_GROUP_STASHES,
+		)
+		.key(key_config.focus_right)
+	}
+	pub fn log_details_toggle(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Details [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"open details of selected commit",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.enter)
+	}
+
+	pub fn commit_details_open(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Inspect [{}]",
+				key_config.get_hint(key_config.focus_right),
+			),
+			"inspect selected commit in detail",
+			CMD_GROUP_GENERAL,
+		)
+		.key(key_config.focus_right)
+	}
+
+	pub fn blame_select_in_log(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Show in Log [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"select the commit of the line in the log with the file selected in its files",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.enter)
+	}
+	pub fn blame_file(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Blame [{}]",
+				key_config.get_hint(key_config.blame),
+			),
+			"open blame view of selected file",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.blame)
+	}
+	pub fn log_tag_commit(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Tag [{}]",
+				key_config.get_hint(key_config.log_tag_commit),
+			),
+			"tag commit",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.log_tag_commit)
+		.mutating()
+	}
+	pub fn tree_toggle_sizes(
+		key_config: &SharedKeyConfig,
+		shown: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"{} sizes [{}]",
+				if shown { "Hide" } else { "Show" },
+				key_config.get_hint(key_config.tree_toggle_sizes),
+			),
+			"toggle file sizes in the tree",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.tree_toggle_sizes)
+	}
+	pub fn merge_diff_mode(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Merge diff [{}]",
+				key_config.get_hint(key_config.merge_diff_mode),
+			),
+			"switch the parent a merge commit is compared to",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.merge_diff_mode)
+	}
+	pub fn inspect_file_tree(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Files [{}]",
+				key_config.get_hint(key_config.open_file_tree),
+			),
+			"inspect file tree at specific revision",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.open_file_tree)
+	}
+	pub fn tag_commit_confirm_msg(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Tag [{}]",
+				key_config.get_hint(key_config.enter),
+			),
+			"tag commit",
+			CMD_GROUP_LOG,
+		)
+		.key(key_config.enter)
+		.mutating()
+	}
+	pub fn create_branch_toggle_orphan(
+		key_config: &SharedKeyConfig,
+		orphan: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Orphan: {} [{}]",
+				if orphan { "on" } else { "off" },
+				key_config.get_hint(key_config.create_branch_orphan),
+			),
+			"create the branch without history, the first commit contains what is staged",
+			CMD_GROUP_BRANCHES,
+		)
+		.key(key_config.create_branch_orphan)
 	}
-	pub fn diff_hunk_add(
+	pub fn create_branch_confirm_msg(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Add hunk [{}]",
+				"Create Branch [{}]",
 				key_config.get_hint(key_config.enter),
 			),
-			"adds selected hunk to stage",
-			CMD_GROUP_DIFF,
+			"create branch",
+			CMD_GROUP_BRANCHES,
 		)
+		.hide_help()
+		.key(key_config.enter)
+		.mutating()
 	}
-	pub fn diff_hunk_revert(
+	pub fn open_branch_create_popup(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Reset hunk [{}]",
-				key_config.get_hint(key_config.status_reset_item),
+				"Create [{}]",
+				key_config.get_hint(key_config.create_branch),
 			),
-			"reverts selected hunk",
-			CMD_GROUP_DIFF,
+			"open create branch popup",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.create_branch)
+		.mutating()
 	}
-	pub fn diff_lines_revert(
+	pub fn rename_branch_confirm_msg(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Reset lines [{}]",
-				key_config.get_hint(key_config.diff_reset_lines),
+				"Rename Branch [{}]",
+				key_config.get_hint(key_config.enter),
 			),
-			"resets selected lines",
-			CMD_GROUP_DIFF,
+			"rename branch",
+			CMD_GROUP_BRANCHES,
 		)
+		.hide_help()
+		.key(key_config.enter)
+		.mutating()
 	}
-	pub fn diff_lines_stage(
+	pub fn branch_name_suggestion(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Stage lines [{}]",
-				key_config.get_hint(key_config.diff_stage_lines),
+				"Use suggestion [{}]",
+				key_config
+					.get_hint(key_config.branch_name_suggestion),
 			),
-			"stage selected lines",
-			CMD_GROUP_DIFF,
+			"replace the invalid branch name with the suggested one",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.branch_name_suggestion)
 	}
-	pub fn diff_lines_unstage(
+	pub fn rename_branch_popup(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Unstage lines [{}]",
-				key_config.get_hint(key_config.diff_stage_lines),
+				"Rename Branch [{}]",
+				key_config.get_hint(key_config.rename_branch),
 			),
-			"unstage selected lines",
-			CMD_GROUP_DIFF,
+			"rename branch",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.rename_branch)
+		.mutating()
 	}
-	pub fn diff_hunk_remove(
+	pub fn delete_branch_popup(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Remove hunk [{}]",
-				key_config.get_hint(key_config.enter),
+				"Delete [{}]",
+				key_config.get_hint(key_config.delete_branch),
 			),
-			"removes selected hunk from stage",
-			CMD_GROUP_DIFF,
+			"delete a branch",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.delete_branch)
+		.mutating()
 	}
-	pub fn close_popup(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn merge_branch_popup(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Close [{}]",
-				key_config.get_hint(key_config.exit_popup),
+				"Merge [{}]",
+				key_config.get_hint(key_config.merge_branch),
 			),
-			"close overlay (e.g commit, help)",
-			CMD_GROUP_GENERAL,
+			"merge a branch",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.merge_branch)
+		.mutating()
 	}
-	pub fn close_msg(key_config: &SharedKeyConfig) -> CommandText {
+
+	pub fn branch_popup_rebase(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Close [{}]",
-				key_config.get_hint(key_config.enter),
+				"Rebase [{}]",
+				key_config.get_hint(key_config.rebase_branch),
 			),
-			"close msg popup (e.g msg)",
-			CMD_GROUP_GENERAL,
+			"rebase a branch",
+			CMD_GROUP_BRANCHES,
 		)
-		.hide_help()
+		.key(key_config.rebase_branch)
+		.mutating()
 	}
-	pub fn validate_msg(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn branch_popup_autosquash(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Validate [{}]",
-				key_config.get_hint(key_config.enter),
+				"Autosquash [{}]",
+				key_config.get_hint(key_config.rebase_autosquash),
 			),
-			"validate msg",
-			CMD_GROUP_GENERAL,
+			"rebase onto a branch squashing fixup!/squash! commits into their targets",
+			CMD_GROUP_BRANCHES,
 		)
-		.hide_help()
+		.key(key_config.rebase_autosquash)
+		.mutating()
 	}
 
-	pub fn abort_merge(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn compare_with_head(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Abort merge [{}]",
-				key_config.get_hint(key_config.abort_merge),
+				"Compare [{}]",
+				key_config.get_hint(key_config.compare_commits),
 			),
-			"abort ongoing merge",
-			CMD_GROUP_GENERAL,
+			"compare with head",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.compare_commits)
 	}
-	pub fn select_staging(
+	pub fn compare_with_marked(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"To stage [{}]",
-				key_config.get_hint(key_config.toggle_workarea),
+				"Compare with marked [{}]",
+				key_config.get_hint(key_config.compare_commits),
 			),
-			"focus/select staging area",
-			CMD_GROUP_GENERAL,
+			"list the files differing between the marked branch and the selected one",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.compare_commits)
 	}
-	pub fn select_unstaged(
+	pub fn branch_mark_compare(
 		key_config: &SharedKeyConfig,
+		marked: bool,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"To unstaged [{}]",
-				key_config.get_hint(key_config.toggle_workarea),
+				"{} [{}]",
+				if marked { "Unmark" } else { "Mark" },
+				key_config.get_hint(key_config.log_mark_commit),
 			),
-			"focus/select unstaged area",
-			CMD_GROUP_GENERAL,
+			"mark the branch to compare others with",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.log_mark_commit)
 	}
-	pub fn undo_commit(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn compare_merge_base(
+		key_config: &SharedKeyConfig,
+		merge_base: bool,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Undo Commit [{}]",
-				key_config.get_hint(key_config.undo_commit),
+				"From {} [{}]",
+				if merge_base { "base tip" } else { "merge base" },
+				key_config.get_hint(key_config.compare_merge_base),
 			),
-			"undo last commit",
+			"compare from the merge base (base...target) or the tip of the base (base..target)",
 			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.compare_merge_base)
 	}
-	pub fn commit_open(key_config: &SharedKeyConfig) -> CommandText {
+
+	pub fn compare_uncommitted(
+		key_config: &SharedKeyConfig,
+		workdir: bool,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Commit [{}]",
-				key_config.get_hint(key_config.open_commit),
+				"{} uncommitted [{}]",
+				if workdir { "Hide" } else { "Show" },
+				key_config.get_hint(key_config.compare_uncommitted),
 			),
-			"open commit popup (available in non-empty stage)",
-			CMD_GROUP_COMMIT,
+			"include staged and unstaged changes on top of the branch",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.compare_uncommitted)
 	}
-	pub fn commit_open_editor(
+	pub fn compare_next_base(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Open editor [{}]",
-				key_config.get_hint(key_config.open_commit_editor),
+				"Next base [{}]",
+				key_config.get_hint(key_config.compare_next_base),
 			),
-			"open commit editor (available in commit popup)",
-			CMD_GROUP_COMMIT,
+			"compare against the next base branch candidate and remember it",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.compare_next_base)
+		.mutating()
 	}
-	pub fn commit_enter(key_config: &SharedKeyConfig) -> CommandText {
+
+	pub fn compare_commits(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Commit [{}]",
-				key_config.get_hint(key_config.enter),
+				"Compare Commits [{}]",
+				key_config.get_hint(key_config.compare_commits),
 			),
-			"commit (available when commit message is non-empty)",
-			CMD_GROUP_COMMIT,
+			"compare two marked commits",
+			CMD_GROUP_LOG,
 		)
-		.hide_help()
+		.key(key_config.compare_commits)
 	}
-	pub fn commit_amend(key_config: &SharedKeyConfig) -> CommandText {
+
+	pub fn bisect_start(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Amend [{}]",
-				key_config.get_hint(key_config.commit_amend),
+				"Bisect [{}]",
+				key_config.get_hint(key_config.bisect),
 			),
-			"amend last commit (available in commit popup)",
-			CMD_GROUP_COMMIT,
+			"bisect between two marked commits, the older one is good",
+			CMD_GROUP_LOG,
 		)
+		.key(key_config.bisect)
+		.mutating()
 	}
-	pub fn edit_item(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn bisect_reset(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Edit [{}]",
-				key_config.get_hint(key_config.edit_file),
+				"Reset Bisect [{}]",
+				key_config.get_hint(key_config.bisect),
 			),
-			"edit the currently selected file in an external editor",
-			CMD_GROUP_CHANGES,
+			"end the bisect and check out the original HEAD again",
+			CMD_GROUP_LOG,
 		)
+		.key(key_config.bisect)
+		.mutating()
 	}
-	pub fn stage_item(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn bisect_good(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Stage [{}]",
-				key_config.get_hint(key_config.enter),
+				"Good [{}]",
+				key_config.get_hint(key_config.bisect_good),
 			),
-			"stage currently selected file or entire path",
-			CMD_GROUP_CHANGES,
+			"mark the checked out commit as good",
+			CMD_GROUP_LOG,
 		)
+		.key(key_config.bisect_good)
+		.mutating()
 	}
-	pub fn stage_all(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn bisect_bad(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Stage All [{}]",
-				key_config.get_hint(key_config.status_stage_all),
+				"Bad [{}]",
+				key_config.get_hint(key_config.bisect_bad),
 			),
-			"stage all changes (in unstaged files)",
-			CMD_GROUP_CHANGES,
+			"mark the checked out commit as bad",
+			CMD_GROUP_LOG,
 		)
+		.key(key_config.bisect_bad)
+		.mutating()
 	}
-	pub fn unstage_item(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn bisect_skip(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Unstage [{}]",
-				key_config.get_hint(key_config.enter),
+				"Skip [{}]",
+				key_config.get_hint(key_config.bisect_skip),
 			),
-			"unstage currently selected file or entire path",
-			CMD_GROUP_CHANGES,
+			"skip the checked out commit, it cannot be tested",
+			CMD_GROUP_LOG,
 		)
+		.key(key_config.bisect_skip)
+		.mutating()
 	}
-	pub fn unstage_all(key_config: &SharedKeyConfig) -> CommandText {
+
+	pub fn select_branch_popup(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Unstage all [{}]",
-				key_config.get_hint(key_config.status_stage_all),
+				"Checkout [{}]",
+				key_config.get_hint(key_config.enter),
 			),
-			"unstage all files (in staged files)",
-			CMD_GROUP_CHANGES,
+			"checkout branch",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.enter)
+		.mutating()
 	}
-	pub fn reset_item(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn toggle_branch_popup(
+		key_config: &SharedKeyConfig,
+		local: bool,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Reset [{}]",
-				key_config.get_hint(key_config.status_reset_item),
+				"{} [{}]",
+				if local { "Remote" } else { "Local" },
+				key_config.get_hint(key_config.tab_toggle),
 			),
-			"revert changes in selected file or entire path",
-			CMD_GROUP_CHANGES,
+			"toggle branch type (remote/local)",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.tab_toggle)
 	}
-	pub fn ignore_item(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn branch_popup_sort(
+		key_config: &SharedKeyConfig,
+		by_age: bool,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Ignore [{}]",
-				key_config.get_hint(key_config.status_ignore_file),
+				"Sort by {} [{}]",
+				if by_age { "name" } else { "age" },
+				key_config.get_hint(key_config.branch_sort),
 			),
-			"Add file or path to .gitignore",
-			CMD_GROUP_CHANGES,
+			"toggle sorting branches by name or last commit",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.branch_sort)
 	}
-
-	pub fn diff_focus_left(
+	pub fn branch_merged_filter(
 		key_config: &SharedKeyConfig,
+		next: &str,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Back [{}]",
-				key_config.get_hint(key_config.focus_left),
+				"Show {} [{}]",
+				next,
+				key_config.get_hint(key_config.branch_merged_filter),
 			),
-			"view and select changed files",
-			CMD_GROUP_GENERAL,
+			"cycle listing all, merged or unmerged branches",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.branch_merged_filter)
 	}
-	pub fn diff_focus_right(
+	pub fn branch_merged_base(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Diff [{}]",
-				key_config.get_hint(key_config.focus_right),
+				"Merge base [{}]",
+				key_config.get_hint(key_config.branch_merged_base),
 			),
-			"inspect file diff",
-			CMD_GROUP_GENERAL,
+			"check merging into the selected branch instead of HEAD (again to reset)",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.branch_merged_base)
 	}
-	pub fn quit(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn branch_delete_merged(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Quit [{}]",
-				key_config.get_hint(key_config.exit),
+				"Delete merged [{}]",
+				key_config.get_hint(key_config.branch_delete_merged),
 			),
-			"quit gitui application",
-			CMD_GROUP_GENERAL,
+			"delete all local branches merged into the base",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.branch_delete_merged)
+		.mutating()
 	}
-	pub fn confirm_action(
+	pub fn branch_edit_description(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Confirm [{}]",
-				key_config.get_hint(key_config.enter),
+				"Description [{}]",
+				key_config
+					.get_hint(key_config.branch_edit_description),
 			),
-			"confirm action",
-			CMD_GROUP_GENERAL,
+			"edit the description of the branch",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.branch_edit_description)
+		.mutating()
 	}
-	pub fn stashing_save(
+	pub fn branch_toggle_descriptions(
 		key_config: &SharedKeyConfig,
+		shown: bool,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Save [{}]",
-				key_config.get_hint(key_config.stashing_save),
+				"{} Descriptions [{}]",
+				if shown { "Hide" } else { "Show" },
+				key_config
+					.get_hint(key_config.branch_toggle_descriptions),
 			),
-			"opens stash name input popup",
-			CMD_GROUP_STASHING,
+			"toggle showing branch descriptions",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.branch_toggle_descriptions)
 	}
-	pub fn stashing_toggle_indexed(
+	pub fn branch_description_confirm(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Toggle Staged [{}]",
-				key_config.get_hint(key_config.stashing_toggle_index),
+				"Save [{}]",
+				key_config.get_hint(key_config.enter),
 			),
-			"toggle including staged files into stash",
-			CMD_GROUP_STASHING,
+			"save the description, empty removes it",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.enter)
+		.mutating()
 	}
-	pub fn stashing_toggle_untracked(
+	pub fn open_branch_select_popup(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Toggle Untracked [{}]",
-				key_config
-					.get_hint(key_config.stashing_toggle_untracked),
+				"Branches [{}]",
+				key_config.get_hint(key_config.select_branch),
 			),
-			"toggle including untracked files into stash",
-			CMD_GROUP_STASHING,
+			"open branch popup",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.select_branch)
 	}
-	pub fn stashing_confirm_msg(
+
+	pub fn checkout_previous_branch(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Stash [{}]",
-				key_config.get_hint(key_config.enter),
+				"Previous branch [{}]",
+				key_config
+					.get_hint(key_config.checkout_previous_branch),
 			),
-			"save files to stash",
-			CMD_GROUP_STASHING,
+			"check out the branch checked out before",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.checkout_previous_branch)
+		.mutating()
 	}
-	pub fn stashlist_apply(
+	pub fn open_recent_branches(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Apply [{}]",
-				key_config.get_hint(key_config.stash_apply),
+				"Recent branches [{}]",
+				key_config.get_hint(key_config.recent_branches),
 			),
-			"apply selected stash",
-			CMD_GROUP_STASHES,
+			"pick one of the last checked out branches",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.recent_branches)
 	}
-	pub fn stashlist_drop(
+	pub fn open_branch_changes(
 		key_config: &SharedKeyConfig,
-		marked: usize,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Drop{} [{}]",
-				if marked == 0 {
-					String::default()
-				} else {
-					format!(" {}", marked)
-				},
-				key_config.get_hint(key_config.stash_drop),
+				"Since branching [{}]",
+				key_config.get_hint(key_config.branch_changes),
 			),
-			"drop selected stash",
-			CMD_GROUP_STASHES,
+			"show what the current branch changed since it forked off its base",
+			CMD_GROUP_BRANCHES,
 		)
+		.key(key_config.branch_changes)
 	}
-	pub fn stashlist_pop(
+	pub fn repo_switcher_switch(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Pop [{}]",
+				"Switch [{}]",
 				key_config.get_hint(key_config.enter),
 			),
-			"pop selected stash",
-			CMD_GROUP_STASHES,
+			"open the selected repo",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.enter)
 	}
-	pub fn stashlist_inspect(
+	pub fn recent_branches_checkout(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Inspect [{}]",
-				key_config.get_hint(key_config.focus_right),
+				"Checkout [{}]",
+				key_config.get_hint(key_config.enter),
 			),
-			"open stash commit details (allows to diff files)",
-			CMD_GROUP_STASHES,
+			"check out the selected branch",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.enter)
+		.mutating()
 	}
-	pub fn log_details_toggle(
+
+	pub fn open_tags_popup(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Details [{}]",
-				key_config.get_hint(key_config.enter),
+				"Tags [{}]",
+				key_config.get_hint(key_config.tags),
 			),
-			"open details of selected commit",
-			CMD_GROUP_LOG,
+			"open tags popup",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.tags)
 	}
-
-	pub fn commit_details_open(
+	pub fn delete_tag_popup(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Inspect [{}]",
-				key_config.get_hint(key_config.focus_right),
+				"Delete [{}]",
+				key_config.get_hint(key_config.delete_tag),
 			),
-			"inspect selected commit in detail",
+			"delete a tag",
 			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.delete_tag)
+		.mutating()
 	}
-
-	pub fn blame_file(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn select_tag(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Blame [{}]",
-				key_config.get_hint(key_config.blame),
+				"Select commit [{}]",
+				key_config.get_hint(key_config.select_tag),
 			),
-			"open blame view of selected file",
+			"Select commit in revlog",
 			CMD_GROUP_LOG,
 		)
+		.key(key_config.select_tag)
 	}
-	pub fn log_tag_commit(
-		key_config: &SharedKeyConfig,
-	) -> CommandText {
+
+	pub fn status_push(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Tag [{}]",
-				key_config.get_hint(key_config.log_tag_commit),
+				"Push [{}]",
+				key_config.get_hint(key_config.push),
 			),
-			"tag commit",
-			CMD_GROUP_LOG,
+			"push to origin",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.push)
+		.mutating()
+		.remote()
 	}
-	pub fn inspect_file_tree(
-		key_config: &SharedKeyConfig,
-	) -> CommandText {
+	pub fn status_stash(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Files [{}]",
-				key_config.get_hint(key_config.open_file_tree),
+				"Stash [{}]",
+				key_config.get_hint(key_config.status_stash),
 			),
-			"inspect file tree at specific revision",
-			CMD_GROUP_LOG,
+			"stash changes without leaving the status tab",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.status_stash)
+		.mutating()
 	}
-	pub fn tag_commit_confirm_msg(
+	pub fn status_stage_pattern(
 		key_config: &SharedKeyConfig,
+		unstage: bool,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Tag [{}]",
-				key_config.get_hint(key_config.enter),
+				"{} Pattern [{}]",
+				if unstage { "Unstage" } else { "Stage" },
+				key_config.get_hint(key_config.status_stage_pattern),
 			),
-			"tag commit",
-			CMD_GROUP_LOG,
+			"(un)stage all files matching a pathspec",
+			CMD_GROUP_CHANGES,
 		)
+		.key(key_config.status_stage_pattern)
+		.mutating()
 	}
-	pub fn create_branch_confirm_msg(
+	pub fn stage_pattern_confirm(
 		key_config: &SharedKeyConfig,
+		unstage: bool,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Create Branch [{}]",
+				"{} [{}]",
+				if unstage { "Unstage" } else { "Stage" },
 				key_config.get_hint(key_config.enter),
 			),
-			"create branch",
-			CMD_GROUP_BRANCHES,
+			"(un)stage all matching files",
+			CMD_GROUP_CHANGES,
 		)
-		.hide_help()
+		.key(key_config.enter)
+		.mutating()
 	}
-	pub fn open_branch_create_popup(
+	pub fn custom_command(
+		key_config: &SharedKeyConfig,
+		name: &str,
+		key: Option<KeyEvent>,
+	) -> CommandText {
+		CommandText {
+			key,
+			..CommandText::new(
+				key.map_or_else(
+					|| name.to_string(),
+					|key| {
+						format!(
+							"{} [{}]",
+							name,
+							key_config.get_hint(key)
+						)
+					},
+				),
+				"custom command from custom_commands.ron",
+				CMD_GROUP_CUSTOM,
+			)
+		}
+		.mutating()
+	}
+	pub fn push_preview_confirm(
 		key_config: &SharedKeyConfig,
+		force: bool,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Create [{}]",
-				key_config.get_hint(key_config.create_branch),
+				"{} [{}]",
+				if force {
+					super::FORCE_PUSH_POPUP_MSG
+				} else {
+					super::PUSH_POPUP_MSG
+				},
+				key_config.get_hint(key_config.enter),
 			),
-			"open create branch popup",
-			CMD_GROUP_BRANCHES,
+			"push the previewed commits",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.enter)
+		.mutating()
 	}
-	pub fn rename_branch_confirm_msg(
+	pub fn push_select_branch(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Rename Branch [{}]",
-				key_config.get_hint(key_config.enter),
+				"Branch [{}]",
+				key_config.get_hint(key_config.push_select_branch),
 			),
-			"rename branch",
-			CMD_GROUP_BRANCHES,
+			"choose the local branch to push, it stays checked out as it is",
+			CMD_GROUP_GENERAL,
 		)
-		.hide_help()
+		.key(key_config.push_select_branch)
 	}
-	pub fn rename_branch_popup(
+	pub fn push_choose_branch(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Rename Branch [{}]",
-				key_config.get_hint(key_config.rename_branch),
+				"Choose [{}]",
+				key_config.get_hint(key_config.enter),
 			),
-			"rename branch",
-			CMD_GROUP_BRANCHES,
+			"push the selected branch",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.enter)
 	}
-	pub fn delete_branch_popup(
+	pub fn push_select_remote(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Delete [{}]",
-				key_config.get_hint(key_config.delete_branch),
+				"Next Remote [{}]",
+				key_config.get_hint(key_config.push_select_remote),
 			),
-			"delete a branch",
-			CMD_GROUP_BRANCHES,
+			"push to the next remote instead of the configured one",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.push_select_remote)
 	}
-	pub fn merge_branch_popup(
+	pub fn push_preview_remote(
 		key_config: &SharedKeyConfig,
+		asked_remote: bool,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Merge [{}]",
-				key_config.get_hint(key_config.merge_branch),
+				"{} [{}]",
+				if asked_remote {
+					"Last Fetch"
+				} else {
+					"Ask Remote"
+				},
+				key_config.get_hint(key_config.push_preview_remote),
 			),
-			"merge a branch",
-			CMD_GROUP_BRANCHES,
+			"compare against the remote branch as listed by the remote (like ls-remote) instead of as of the last fetch",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.push_preview_remote)
 	}
-
-	pub fn branch_popup_rebase(
+	pub fn status_force_push(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Rebase [{}]",
-				key_config.get_hint(key_config.rebase_branch),
+				"Force Push [{}]",
+				key_config.get_hint(key_config.force_push),
 			),
-			"rebase a branch",
-			CMD_GROUP_BRANCHES,
+			"force push to origin",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.force_push)
+		.mutating()
+		.remote()
 	}
-
-	pub fn compare_with_head(
-		key_config: &SharedKeyConfig,
-	) -> CommandText {
+	pub fn status_pull(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Compare [{}]",
-				key_config.get_hint(key_config.compare_commits),
+				"Pull [{}]",
+				key_config.get_hint(key_config.pull),
 			),
-			"compare with head",
-			CMD_GROUP_BRANCHES,
+			"fetch/merge",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.pull)
+		.mutating()
+		.remote()
 	}
-
-	pub fn compare_commits(
-		key_config: &SharedKeyConfig,
-	) -> CommandText {
+	pub fn status_fetch(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Compare Commits [{}]",
-				key_config.get_hint(key_config.compare_commits),
+				"Fetch [{}]",
+				key_config.get_hint(key_config.fetch),
 			),
-			"compare two marked commits",
-			CMD_GROUP_LOG,
+			"choose remote and branches to fetch",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.fetch)
+		.mutating()
+		.remote()
 	}
-
-	pub fn select_branch_popup(
+	pub fn fetch_confirm(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Checkout [{}]",
+				"Fetch [{}]",
 				key_config.get_hint(key_config.enter),
 			),
-			"checkout branch",
-			CMD_GROUP_BRANCHES,
+			"fetch selection",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.enter)
+		.mutating()
 	}
-	pub fn toggle_branch_popup(
-		key_config: &SharedKeyConfig,
-		local: bool,
-	) -> CommandText {
+	pub fn fetch_prune(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"{} [{}]",
-				if local { "Remote" } else { "Local" },
-				key_config.get_hint(key_config.tab_toggle),
+				"Prune{} [{}]",
+				symbol::ELLIPSIS,
+				key_config.get_hint(key_config.fetch_prune),
 			),
-			"toggle branch type (remote/local)",
-			CMD_GROUP_BRANCHES,
+			"choose remote tracking branches gone on the remote to delete",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.fetch_prune)
+		.mutating()
 	}
-	pub fn open_branch_select_popup(
-		key_config: &SharedKeyConfig,
-	) -> CommandText {
+	pub fn prune_toggle(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Branches [{}]",
-				key_config.get_hint(key_config.select_branch),
+				"Toggle [{}]",
+				key_config.get_hint(key_config.log_mark_commit),
 			),
-			"open branch popup",
-			CMD_GROUP_BRANCHES,
+			"keep or delete the selected branch",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.log_mark_commit)
 	}
-
-	pub fn open_tags_popup(
+	pub fn prune_confirm(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Tags [{}]",
-				key_config.get_hint(key_config.tags),
+				"Delete [{}]",
+				key_config.get_hint(key_config.enter),
 			),
-			"open tags popup",
+			"delete the checked remote tracking branches",
 			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.enter)
+		.mutating()
 	}
-	pub fn delete_tag_popup(
+	pub fn compare_workdir(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Delete [{}]",
-				key_config.get_hint(key_config.delete_tag),
+				"Compare [{}]",
+				key_config.get_hint(key_config.compare_workdir),
 			),
-			"delete a tag",
-			CMD_GROUP_GENERAL,
+			"compare the file at this revision with the working copy",
+			CMD_GROUP_LOG,
 		)
+		.key(key_config.compare_workdir)
 	}
-	pub fn select_tag(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn view_file(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Select commit [{}]",
-				key_config.get_hint(key_config.select_tag),
+				"View [{}]",
+				key_config.get_hint(key_config.view_file),
 			),
-			"Select commit in revlog",
-			CMD_GROUP_LOG,
+			"view the selected file in a full screen pager",
+			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.view_file)
 	}
-
-	pub fn status_push(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn file_view_goto(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Push [{}]",
-				key_config.get_hint(key_config.push),
+				"Go to line [{}]",
+				key_config.get_hint(key_config.file_view_goto),
 			),
-			"push to origin",
+			"jump to a line number",
 			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.file_view_goto)
 	}
-	pub fn status_force_push(
+	pub fn file_view_search(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Force Push [{}]",
-				key_config.get_hint(key_config.force_push),
+				"Search [{}]",
+				key_config.get_hint(key_config.diff_search),
 			),
-			"force push to origin",
+			"search forward in the file",
 			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.diff_search)
 	}
-	pub fn status_pull(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn file_view_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Pull [{}]",
-				key_config.get_hint(key_config.pull),
+				"Confirm [{}]",
+				key_config.get_hint(key_config.enter),
 			),
-			"fetch/merge",
+			"jump to the line or search",
 			CMD_GROUP_GENERAL,
 		)
+		.key(key_config.enter)
 	}
 }
@@ -4,16 +4,19 @@
 	clippy::unused_self
 )]
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::{
 	components::{
 		visibility_blocking, CommandBlocking, CommandInfo, Component,
 		DrawableComponent, EventState, RevisionFilesComponent,
+		SelectedPath,
 	},
 	keys::SharedKeyConfig,
 	queue::Queue,
-	ui::style::SharedTheme,
+	strings,
+	tabs::{Pane, PaneFocus},
+	ui::{self, style::SharedTheme},
 	AsyncAppNotification, AsyncNotification,
 };
 use anyhow::Result;
@@ -25,6 +28,8 @@ pub struct FilesTab {
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
 	files: RevisionFilesComponent,
+	/// nothing committed (yet), shown instead of the empty tree
+	empty: bool,
 }
 
 impl FilesTab {
@@ -45,15 +50,21 @@ impl FilesTab {
 			),
 			theme,
 			key_config,
+			empty: false,
 		}
 	}
 
 	///
 	pub fn update(&mut self) -> Result<()> {
 		if self.is_visible() {
-			if let Ok(head) = sync::get_head(CWD) {
-				self.files.set_commit(head)?;
-			}
+			// fails while the head is unborn
+			self.empty = match sync::get_head(CWD) {
+				Ok(head) => {
+					self.files.set_commit(head)?;
+					self.files.is_empty()
+				}
+				Err(_) => true,
+			};
 		}
 
 		Ok(())
@@ -74,6 +85,20 @@ impl FilesTab {
 	pub fn file_finder_update(&mut self, file: &Option<PathBuf>) {
 		self.files.find_file(file);
 	}
+
+	/// repo relative path of the selected file
+	pub fn selected_path(&self) -> Option<String> {
+		self.files.selected_path()
+	}
+
+	/// selects the file at the repo relative `path` if it is part of
+	/// the head tree
+	pub fn select_file(&mut self, path: &str) -> Result<()> {
+		self.update()?;
+		self.files.find_file(&Some(Path::new("./").join(path)));
+
+		Ok(())
+	}
 }
 
 impl DrawableComponent for FilesTab {
@@ -83,7 +108,18 @@ impl DrawableComponent for FilesTab {
 		rect: tui::layout::Rect,
 	) -> Result<()> {
 		if self.is_visible() {
-			self.files.draw(f, rect)?;
+			if self.empty {
+				ui::draw_empty_state(
+					f,
+					rect,
+					&strings::title_files(),
+					&strings::files_empty(&self.key_config),
+					true,
+					&self.theme,
+				);
+			} else {
+				self.files.draw(f, rect)?;
+			}
 		}
 		Ok(())
 	}
@@ -127,3 +163,27 @@ impl Component for FilesTab {
 		Ok(())
 	}
 }
+
+impl PaneFocus for FilesTab {
+	fn focusable_panes(&self) -> Vec<Pane> {
+		vec![Pane::Tree, Pane::File]
+	}
+
+	fn focused_pane(&self) -> Pane {
+		if self.files.file_focused() {
+			Pane::File
+		} else {
+			Pane::Tree
+		}
+	}
+
+	fn focus_pane(&mut self, pane: Pane) -> Result<bool> {
+		match pane {
+			Pane::Tree | Pane::File => {
+				self.files.focus_file(pane == Pane::File);
+				Ok(true)
+			}
+			_ => Ok(false),
+		}
+	}
+}
@@ -9,3 +9,106 @@ pub use revlog::Revlog;
 pub use stashing::{Stashing, StashingOptions};
 pub use stashlist::StashList;
 pub use status::Status;
+
+use anyhow::Result;
+
+/// part of a tab that can hold the focus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+	/// unstaged changes
+	WorkDir,
+	/// staged changes
+	Stage,
+	Diff,
+	/// the one list of a single pane tab
+	List,
+	/// file tree of a revision
+	Tree,
+	/// content of the file selected in the tree
+	File,
+}
+
+/// the pane of a tab that gets the keys, each tab owns exactly one
+pub trait PaneFocus {
+	/// panes that can take the focus right now, in cycle order
+	fn focusable_panes(&self) -> Vec<Pane>;
+
+	///
+	fn focused_pane(&self) -> Pane;
+
+	/// `false` if `pane` cannot take the focus right now
+	fn focus_pane(&mut self, pane: Pane) -> Result<bool>;
+
+	/// moves the focus on to the next (or previous) focusable pane
+	fn cycle_focus(&mut self, reverse: bool) -> Result<bool> {
+		let panes = self.focusable_panes();
+		if panes.len() < 2 {
+			return Ok(false);
+		}
+
+		let current = self.focused_pane();
+		let idx = panes
+			.iter()
+			.position(|pane| *pane == current)
+			.unwrap_or_default();
+		let next = if reverse {
+			(idx + panes.len() - 1) % panes.len()
+		} else {
+			(idx + 1) % panes.len()
+		};
+
+		self.focus_pane(panes[next])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	struct Panes {
+		focus: Pane,
+		diff_focusable: bool,
+	}
+
+	impl PaneFocus for Panes {
+		fn focusable_panes(&self) -> Vec<Pane> {
+			let mut panes = vec![Pane::WorkDir, Pane::Stage];
+			if self.diff_focusable {
+				panes.push(Pane::Diff);
+			}
+			panes
+		}
+
+		fn focused_pane(&self) -> Pane {
+			self.focus
+		}
+
+		fn focus_pane(&mut self, pane: Pane) -> Result<bool> {
+			self.focus = pane;
+			Ok(true)
+		}
+	}
+
+	#[test]
+	fn test_cycle_focus() {
+		let mut panes = Panes {
+			focus: Pane::WorkDir,
+			diff_focusable: true,
+		};
+
+		panes.cycle_focus(false).unwrap();
+		assert_eq!(panes.focus, Pane::Stage);
+		panes.cycle_focus(false).unwrap();
+		assert_eq!(panes.focus, Pane::Diff);
+		panes.cycle_focus(false).unwrap();
+		assert_eq!(panes.focus, Pane::WorkDir);
+
+		panes.cycle_focus(true).unwrap();
+		assert_eq!(panes.focus, Pane::Diff);
+
+		panes.diff_focusable = false;
+		panes.cycle_focus(true).unwrap();
+		assert_eq!(panes.focus, Pane::Stage);
+	}
+}
@@ -1,18 +1,20 @@
 use crate::{
 	components::{
 		visibility_blocking, CommandBlocking, CommandInfo,
-		CommitDetailsComponent, CommitList, Component,
-		DrawableComponent, EventState,
+		CommitDetailsComponent, CommitList, Component, DateRange,
+		DrawableComponent, EventState, SharedOptions,
 	},
 	keys::SharedKeyConfig,
-	queue::{InternalEvent, Queue},
+	queue::{Action, InternalEvent, NeedsUpdate, Queue},
 	strings,
+	tabs::{Pane, PaneFocus},
 	ui::style::SharedTheme,
 };
 use anyhow::Result;
 use asyncgit::{
 	cached,
-	sync::{self, CommitId},
+	cherry_pick::CherryPickRequest,
+	sync::{self, BisectMark, BisectStep, CommitId, RefTarget},
 	AsyncGitNotification, AsyncLog, AsyncTags, CommitFilesParams,
 	FetchStatus, CWD,
 };
@@ -37,6 +39,18 @@ pub struct Revlog {
 	queue: Queue,
 	visible: bool,
 	branch_name: cached::BranchName,
+	date_range: DateRange,
+	/// email the log is limited to
+	author: Option<String>,
+	/// selected before filtering, selected again once walked
+	reselect: Option<CommitId>,
+	/// row of the view `reselect` is drawn in once selected, kept
+	/// when HEAD moved
+	reselect_row: Option<usize>,
+	/// ref jumped to, selected once the walk got to its commit
+	jump: Option<RefTarget>,
+	/// `None` if no bisect is in progress
+	bisect: Option<BisectStep>,
 	key_config: SharedKeyConfig,
 }
 
@@ -47,6 +61,7 @@ impl Revlog {
 		sender: &Sender<AsyncGitNotification>,
 		theme: SharedTheme,
 		key_config: SharedKeyConfig,
+		options: SharedOptions,
 	) -> Self {
 		Self {
 			queue: queue.clone(),
@@ -55,16 +70,24 @@ impl Revlog {
 				sender,
 				theme.clone(),
 				key_config.clone(),
+				options.clone(),
 			),
 			list: CommitList::new(
 				&strings::log_title(&key_config),
 				theme,
 				key_config.clone(),
+				options,
 			),
-			git_log: AsyncLog::new(sender, None),
+			git_log: AsyncLog::new(CWD, sender, None),
 			git_tags: AsyncTags::new(sender),
 			visible: false,
 			branch_name: cached::BranchName::new(CWD),
+			date_range: DateRange::default(),
+			author: None,
+			reselect: None,
+			reselect_row: None,
+			jump: None,
+			bisect: None,
 			key_config,
 		}
 	}
@@ -81,8 +104,31 @@ impl Revlog {
 		if self.is_visible() {
 			let log_changed =
 				self.git_log.fetch()? == FetchStatus::Started;
+			if log_changed {
+				self.revalidate_selection();
+			}
+
+			let count = self.git_log.count()?;
+			self.list.set_count_total(count);
+			self.list.set_empty_text(
+				if count == 0 && !self.git_log.is_pending() {
+					Some(if self.author.is_some() {
+						vec![strings::LOG_NO_COMMITS_FILTERED.into()]
+					} else if self.date_range.is_empty() {
+						// the unfiltered log is only empty before
+						// the first commit
+						strings::log_empty_unborn(&self.key_config)
+					} else {
+						vec![strings::LOG_NO_COMMITS_IN_RANGE.into()]
+					})
+				} else {
+					None
+				},
+			);
 
-			self.list.set_count_total(self.git_log.count()?);
+			self.update_reselect()?;
+			self.update_jump()?;
+			self.update_title(count);
 
 			let selection = self.list.selection();
 			let selection_max = self.list.selection_max();
@@ -94,9 +140,10 @@ impl Revlog {
 
 			self.git_tags.request(Duration::from_secs(3), false)?;
 
-			self.list.set_branch(
-				self.branch_name.lookup().map(Some).unwrap_or(None),
-			);
+			let branch = self.branch_name.lookup().ok();
+			let unpushed = self.unpushed_count(branch.as_deref());
+			self.list.set_unpushed(unpushed);
+			self.list.set_branch(branch);
 
 			if self.commit_details.is_visible() {
 				let commit = self.selected_commit();
@@ -138,20 +185,57 @@ impl Revlog {
 		let want_min =
 			self.list.selection().saturating_sub(SLICE_SIZE / 2);
 
+		// the commit before the slice tells whether the first one
+		// starts a new group of the log grouping
+		let fetch_min = want_min.saturating_sub(1);
+		// evicted entries are walked again, `update` comes again
+		// once they are there
+		let ids = match self
+			.git_log
+			.get_slice(fetch_min, SLICE_SIZE + want_min - fetch_min)?
+		{
+			Some(ids) => ids,
+			None => return Ok(()),
+		};
 		let commits = sync::get_commits_info(
 			CWD,
-			&self.git_log.get_slice(want_min, SLICE_SIZE)?,
+			&ids,
 			self.list.current_size().0.into(),
 		);
 
-		if let Ok(commits) = commits {
+		if let Ok(mut commits) = commits {
+			let preceding = (fetch_min < want_min
+				&& !commits.is_empty())
+			.then(|| commits.remove(0));
+
 			self.list.items().set_items(want_min, commits);
+			self.list.items().set_preceding(preceding.as_ref());
+		}
+
+		match sync::commits_with_notes(CWD, &ids, None) {
+			Ok(notes) => self.list.set_notes(notes),
+			Err(e) => log::error!("commits with notes: {}", e),
 		}
 
 		Ok(())
 	}
 
-	fn selected_commit(&self) -> Option<CommitId> {
+	/// `branch` is ahead of its upstream by that many commits, they
+	/// come first in the log. `0` without an upstream or while the
+	/// log is filtered
+	fn unpushed_count(&self, branch: Option<&str>) -> usize {
+		if self.author.is_some() || !self.date_range.is_empty() {
+			return 0;
+		}
+
+		branch
+			.and_then(|branch| {
+				sync::branch_compare_upstream(CWD, branch).ok()
+			})
+			.map_or(0, |state| state.ahead)
+	}
+
+	pub fn selected_commit(&self) -> Option<CommitId> {
 		self.list.selected_entry().map(|e| e.id)
 	}
 
@@ -171,6 +255,352 @@ impl Revlog {
 		})
 	}
 
+	/// limits the log to commits in `range`,
+	/// an empty range restores the full log
+	pub fn set_date_range(&mut self, range: DateRange) -> Result<()> {
+		self.date_range = range;
+		self.apply_filter()
+	}
+
+	/// limits the log to commits authored by `email`
+	/// on top of the date range
+	pub fn set_author(
+		&mut self,
+		email: Option<String>,
+	) -> Result<()> {
+		self.author = email;
+		self.apply_filter()
+	}
+
+	fn filter_by_selected_author(&mut self) -> Result<()> {
+		if let Some(id) = self.selected_commit() {
+			let details = sync::get_commit_details(CWD, id)?;
+			self.set_author(Some(details.author.email))?;
+		}
+
+		Ok(())
+	}
+
+	/// exports what is walked so far, the walk might still be running
+	fn export_log(&mut self) -> Result<()> {
+		let ids = self.git_log.get_all()?;
+		self.queue.push(InternalEvent::OpenLogExport(ids));
+
+		Ok(())
+	}
+
+	/// compares one marked commit against head or two marked commits
+	fn compare_marked_commits(&self) -> bool {
+		let marked = self.list.marked();
+
+		match marked.len() {
+			1 => self
+				.queue
+				.push(InternalEvent::CompareCommits(marked[0], None)),
+			2 => self.queue.push(InternalEvent::CompareCommits(
+				marked[0],
+				Some(marked[1]),
+			)),
+			_ => return false,
+		}
+
+		true
+	}
+
+	/// the range between two marked commits, the marked commit or
+	/// the selected one if none is marked
+	fn cherry_pick(&mut self) -> bool {
+		let marked = self.list.marked();
+		let commits = match marked.len() {
+			0 => match self.selected_commit() {
+				Some(id) => Ok(vec![id]),
+				None => return false,
+			},
+			1 => Ok(vec![marked[0]]),
+			2 => sync::cherry_pick_range_commits(
+				CWD, marked[0], marked[1],
+			),
+			_ => return false,
+		};
+
+		match commits {
+			Ok(commits) => {
+				self.list.clear_marked();
+				self.queue.push(InternalEvent::CherryPick(
+					CherryPickRequest::Start(commits),
+				));
+			}
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("cherry-pick error:\n{}", e),
+				));
+			}
+		}
+
+		true
+	}
+
+	/// reads the bisect state from the repo, so a bisect started
+	/// on the command line (or before a restart) shows up as well
+	pub fn update_bisect(&mut self) {
+		self.bisect = sync::bisect_state(CWD).unwrap_or_else(|e| {
+			log::error!("bisect state error: {}", e);
+			None
+		});
+	}
+
+	///
+	pub const fn bisect(&self) -> Option<&BisectStep> {
+		self.bisect.as_ref()
+	}
+
+	const fn is_bisect_testing(&self) -> bool {
+		matches!(
+			self.bisect,
+			Some(BisectStep::Test { .. })
+				| Some(BisectStep::Incomplete)
+		)
+	}
+
+	/// starts bisecting between the two marked commits, the older
+	/// one is taken as good
+	fn start_bisect(&mut self) -> bool {
+		let marked = self.list.marked();
+		if marked.len() != 2 {
+			return false;
+		}
+
+		let (one, two) = (marked[0], marked[1]);
+		let res = sync::merge_base(CWD, one, two)
+			.map_err(anyhow::Error::from)
+			.and_then(|base| {
+				let (bad, good) = if base == one {
+					(two, one)
+				} else if base == two {
+					(one, two)
+				} else {
+					anyhow::bail!(strings::BISECT_UNRELATED);
+				};
+
+				Ok(sync::bisect_start(CWD, bad, good)?)
+			});
+
+		self.list.clear_marked();
+		self.on_bisect_step(res);
+
+		true
+	}
+
+	fn mark_bisect(&mut self, mark: BisectMark) {
+		let res = sync::bisect_mark(CWD, mark);
+		self.on_bisect_step(res.map_err(anyhow::Error::from));
+	}
+
+	fn on_bisect_step(&mut self, res: Result<BisectStep>) {
+		match res {
+			Ok(step) => {
+				match &step {
+					BisectStep::Found(id) => {
+						self.confirm_bisect_found(*id);
+					}
+					BisectStep::Inconclusive(ids) => {
+						self.queue.push(InternalEvent::ShowInfoMsg(
+							strings::bisect_inconclusive(ids),
+						));
+					}
+					BisectStep::Test { .. }
+					| BisectStep::Incomplete => (),
+				}
+				self.bisect = Some(step);
+			}
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("bisect error:\n{}", e),
+				));
+			}
+		}
+
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+	}
+
+	fn confirm_bisect_found(&self, id: CommitId) {
+		let subject = sync::get_commit_details(CWD, id)
+			.ok()
+			.and_then(|details| details.message)
+			.map(|message| message.subject)
+			.unwrap_or_default();
+
+		self.queue.push(InternalEvent::ConfirmAction(
+			Action::BisectFound(
+				id,
+				format!("{} {}", id.get_short_string(), subject),
+			),
+		));
+	}
+
+	fn apply_filter(&mut self) -> Result<()> {
+		let mut filters = Vec::new();
+		if !self.date_range.is_empty() {
+			let (since, until) = self.date_range.times();
+			filters.push(sync::filter_by_time(since, until));
+		}
+		if let Some(email) = &self.author {
+			filters.push(sync::filter_by_author_email(email.clone()));
+		}
+
+		self.reselect = self.selected_commit();
+		self.reselect_row = None;
+		self.git_log.set_filter(sync::filter_all(filters));
+		self.list.clear();
+		self.list.select_entry(0);
+
+		self.update()
+	}
+
+	/// HEAD moved (e.g. a checkout, commit or rebase in another
+	/// tool): the selected commit stays selected if it is still part
+	/// of the log, in the row of the view it was in, else the new
+	/// HEAD. a selected HEAD stays at HEAD
+	fn revalidate_selection(&mut self) {
+		if self.reselect.is_some()
+			|| self.jump.is_some()
+			|| self.list.selection() == 0
+		{
+			return;
+		}
+
+		if let Some(id) = self.selected_commit() {
+			// a commit gone with a gc is not reachable either
+			if sync::is_reachable_from_head(CWD, id)
+				.unwrap_or_default()
+			{
+				self.reselect = Some(id);
+				self.reselect_row = self.list.selection_view_row();
+			} else {
+				self.list.select_entry(0);
+			}
+		}
+	}
+
+	/// selects the commit selected before filtering as soon as the
+	/// walk got to it, gives up if it is not part of the filtered log
+	fn update_reselect(&mut self) -> Result<()> {
+		if let Some(id) = self.reselect {
+			self.git_log.locate(id)?;
+
+			if let Some(position) = self.git_log.position(id)? {
+				match self.reselect_row.take() {
+					Some(row) => {
+						self.list.select_entry_in_row(position, row);
+					}
+					None => self.list.select_entry(position),
+				}
+				self.reselect = None;
+			} else if !self.git_log.is_pending() {
+				self.reselect = None;
+				self.reselect_row = None;
+			}
+		}
+
+		Ok(())
+	}
+
+	/// shows the filter and the progress of a pending jump
+	fn update_title(&mut self, count: usize) {
+		let mut title = strings::log_title_filtered(
+			&self.key_config,
+			self.date_range
+				.since
+				.as_ref()
+				.map(|limit| limit.input.as_str()),
+			self.date_range
+				.until
+				.as_ref()
+				.map(|limit| limit.input.as_str()),
+			self.author.as_deref(),
+		);
+
+		if let Some(target) = &self.jump {
+			title.push_str(&strings::log_jump_progress(
+				&target.name,
+				count,
+				&self.key_config,
+			));
+		}
+
+		if self.git_log.is_loading() {
+			title.push_str(strings::LOG_TITLE_LOADING);
+		}
+
+		self.list.set_title(&title);
+	}
+
+	/// selects the commit of `target`, right away if it is loaded
+	/// already or else as soon as the walk got to it
+	pub fn jump_to(&mut self, target: RefTarget) -> Result<()> {
+		if let Some(position) = self.git_log.position(target.id)? {
+			self.jump = None;
+			self.list.select_entry(position);
+		} else if sync::is_reachable_from_head(CWD, target.id)? {
+			self.jump = Some(target);
+		} else {
+			self.jump = None;
+			self.queue.push(InternalEvent::ShowInfoMsg(
+				strings::log_jump_not_in_log(&target.name),
+			));
+		}
+
+		self.update()
+	}
+
+	/// like `update_reselect`, but tells if the walk ended without
+	/// getting to the commit (it is filtered out)
+	fn update_jump(&mut self) -> Result<()> {
+		if let Some(target) = &self.jump {
+			self.git_log.locate(target.id)?;
+
+			if let Some(position) =
+				self.git_log.position(target.id)?
+			{
+				self.list.select_entry(position);
+				self.jump = None;
+			} else if !self.git_log.is_pending() {
+				self.queue.push(InternalEvent::ShowInfoMsg(
+					strings::log_jump_not_in_log(&target.name),
+				));
+				self.jump = None;
+			}
+		}
+
+		Ok(())
+	}
+
+	/// selects `id` once the walk got to it, stays at `HEAD` if the
+	/// commit no longer exists
+	pub fn restore_selection(&mut self, id: CommitId) {
+		self.reselect = Some(id);
+		self.reselect_row = None;
+	}
+
+	/// the selected commit or the one about to be selected
+	pub fn selection(&self) -> Option<CommitId> {
+		self.reselect.or_else(|| self.selected_commit())
+	}
+
+	/// selects `id` once the walk got to it with `path` selected in
+	/// its files
+	pub fn select_commit_file(
+		&mut self,
+		id: CommitId,
+		path: String,
+	) -> Result<()> {
+		self.restore_selection(id);
+		self.commit_details.select_file_on_update(id, path);
+		self.commit_details.show()?;
+
+		self.update()
+	}
+
 	pub fn select_commit(&mut self, id: CommitId) -> Result<()> {
 		let position = self.git_log.position(id)?;
 
@@ -260,6 +690,15 @@ impl Component for Revlog {
 				} else if k == self.key_config.select_branch {
 					self.queue.push(InternalEvent::SelectBranch);
 					return Ok(EventState::Consumed);
+				} else if k
+					== self.key_config.checkout_previous_branch
+				{
+					self.queue
+						.push(InternalEvent::CheckoutPreviousBranch);
+					return Ok(EventState::Consumed);
+				} else if k == self.key_config.recent_branches {
+					self.queue.push(InternalEvent::RecentBranches);
+					return Ok(EventState::Consumed);
 				} else if k == self.key_config.open_file_tree {
 					return self.selected_commit().map_or(
 						Ok(EventState::NotConsumed),
@@ -273,27 +712,84 @@ impl Component for Revlog {
 				} else if k == self.key_config.tags {
 					self.queue.push(InternalEvent::Tags);
 					return Ok(EventState::Consumed);
-				} else if k == self.key_config.compare_commits
-					&& self.list.marked_count() > 0
-				{
-					if self.list.marked_count() == 1 {
-						// compare against head
+				} else if k == self.key_config.log_filter_author {
+					self.filter_by_selected_author()?;
+					return Ok(EventState::Consumed);
+				} else if k == self.key_config.log_authors {
+					self.queue.push(InternalEvent::OpenLogAuthors);
+					return Ok(EventState::Consumed);
+				} else if k == self.key_config.log_export {
+					self.export_log()?;
+					return Ok(EventState::Consumed);
+				} else if k == self.key_config.rebase_autosquash {
+					if let Some(id) = self.selected_commit() {
 						self.queue.push(
-							InternalEvent::CompareCommits(
-								self.list.marked()[0],
-								None,
+							InternalEvent::ConfirmAction(
+								Action::AutosquashRebase(
+									id,
+									id.get_short_string(),
+								),
 							),
 						);
-						return Ok(EventState::Consumed);
-					} else if self.list.marked_count() == 2 {
-						//compare two marked commits
-						let marked = self.list.marked();
-						self.queue.push(
-							InternalEvent::CompareCommits(
-								marked[0],
-								Some(marked[1]),
+					}
+					return Ok(EventState::Consumed);
+				} else if k == self.key_config.cherry_pick
+					&& self.cherry_pick()
+				{
+					return Ok(EventState::Consumed);
+				} else if k == self.key_config.exit_popup
+					&& self.jump.is_some()
+				{
+					self.jump = None;
+					self.update()?;
+					return Ok(EventState::Consumed);
+				} else if k == self.key_config.log_jump_to_ref {
+					self.queue.push(InternalEvent::OpenJumpToRef);
+					return Ok(EventState::Consumed);
+				} else if k == self.key_config.exit_popup
+					&& self.author.is_some()
+				{
+					self.set_author(None)?;
+					return Ok(EventState::Consumed);
+				} else if k == self.key_config.log_filter {
+					self.queue.push(InternalEvent::OpenLogFilter(
+						self.date_range.clone(),
+					));
+					return Ok(EventState::Consumed);
+				} else if k == self.key_config.compare_commits
+					&& self.compare_marked_commits()
+				{
+					return Ok(EventState::Consumed);
+				} else if k == self.key_config.bisect {
+					match self.bisect {
+						Some(BisectStep::Found(id)) => {
+							self.confirm_bisect_found(id);
+						}
+						Some(_) => self.queue.push(
+							InternalEvent::ConfirmAction(
+								Action::BisectReset,
 							),
-						);
+						),
+						None => {
+							if !self.start_bisect() {
+								return Ok(EventState::NotConsumed);
+							}
+						}
+					}
+					return Ok(EventState::Consumed);
+				} else if self.is_bisect_testing() {
+					let mark = if k == self.key_config.bisect_good {
+						Some(BisectMark::Good)
+					} else if k == self.key_config.bisect_bad {
+						Some(BisectMark::Bad)
+					} else if k == self.key_config.bisect_skip {
+						Some(BisectMark::Skip)
+					} else {
+						None
+					};
+
+					if let Some(mark) = mark {
+						self.mark_bisect(mark);
 						return Ok(EventState::Consumed);
 					}
 				}
@@ -332,6 +828,18 @@ impl Component for Revlog {
 			true,
 			self.visible || force_all,
 		));
+		out.push(CommandInfo::new(
+			strings::commands::checkout_previous_branch(
+				&self.key_config,
+			),
+			true,
+			self.visible || force_all,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::open_recent_branches(&self.key_config),
+			true,
+			self.visible || force_all,
+		));
 
 		out.push(CommandInfo::new(
 			strings::commands::compare_with_head(&self.key_config),
@@ -347,6 +855,35 @@ impl Component for Revlog {
 				|| force_all,
 		));
 
+		out.push(CommandInfo::new(
+			strings::commands::bisect_start(&self.key_config),
+			self.list.marked_count() == 2,
+			(self.visible && self.bisect.is_none()) || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::bisect_reset(&self.key_config),
+			true,
+			(self.visible && self.bisect.is_some()) || force_all,
+		));
+
+		let testing = self.visible && self.is_bisect_testing();
+		out.push(CommandInfo::new(
+			strings::commands::bisect_good(&self.key_config),
+			true,
+			testing || force_all,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::bisect_bad(&self.key_config),
+			true,
+			testing || force_all,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::bisect_skip(&self.key_config),
+			true,
+			testing || force_all,
+		));
+
 		out.push(CommandInfo::new(
 			strings::commands::copy_hash(&self.key_config),
 			self.selected_commit().is_some(),
@@ -377,6 +914,65 @@ impl Component for Revlog {
 			self.visible || force_all,
 		));
 
+		out.push(CommandInfo::new(
+			strings::commands::log_filter(&self.key_config),
+			true,
+			self.visible || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::log_filter_author(&self.key_config),
+			self.selected_commit().is_some(),
+			self.visible || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::log_filter_author_clear(
+				&self.key_config,
+			),
+			true,
+			(self.visible && self.author.is_some()) || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::log_authors(&self.key_config),
+			true,
+			self.visible || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::log_jump_to_ref(&self.key_config),
+			true,
+			self.visible || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::log_jump_cancel(&self.key_config),
+			true,
+			(self.visible && self.jump.is_some()) || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::log_autosquash(&self.key_config),
+			self.selected_commit().is_some(),
+			self.visible || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::log_cherry_pick(&self.key_config),
+			match self.list.marked_count() {
+				0 => self.selected_commit().is_some(),
+				count => count <= 2,
+			},
+			self.visible || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::log_export(&self.key_config),
+			self.list.selected_entry().is_some(),
+			self.visible || force_all,
+		));
+
 		visibility_blocking(self)
 	}
 
@@ -396,4 +992,32 @@ impl Component for Revlog {
 
 		Ok(())
 	}
+
+	fn on_resize(&self) {
+		self.commit_details.on_resize();
+		self.list.on_resize();
+	}
+
+	/// the message of the commit if its details are shown
+	fn selectable_text(&self) -> Option<Vec<String>> {
+		if self.commit_details.is_visible() {
+			self.commit_details.selectable_text()
+		} else {
+			self.list.selectable_text()
+		}
+	}
+}
+
+impl PaneFocus for Revlog {
+	fn focusable_panes(&self) -> Vec<Pane> {
+		vec![Pane::List]
+	}
+
+	fn focused_pane(&self) -> Pane {
+		Pane::List
+	}
+
+	fn focus_pane(&mut self, pane: Pane) -> Result<bool> {
+		Ok(pane == Pane::List)
+	}
 }
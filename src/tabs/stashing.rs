@@ -2,28 +2,34 @@ use crate::{
 	accessors,
 	components::{
 		command_pump, event_pump, visibility_blocking,
-		CommandBlocking, CommandInfo, Component, DrawableComponent,
-		EventState, FileTreeComponent,
+		CommandBlocking, CommandInfo, Component, DiffComponent,
+		DrawableComponent, EventState, FileTreeComponent,
+		SharedOptions,
 	},
 	keys::SharedKeyConfig,
 	queue::{InternalEvent, Queue},
 	strings,
-	ui::style::SharedTheme,
+	tabs::{Pane, PaneFocus},
+	ui::{self, style::SharedTheme},
 };
 use anyhow::Result;
 use asyncgit::{
-	sync::{self, status::StatusType},
-	AsyncGitNotification, AsyncStatus, StatusParams, CWD,
+	sync::{self, status::StatusType, ShowUntrackedFilesConfig},
+	AsyncDiff, AsyncGitNotification, AsyncStatus, DiffParams,
+	DiffType, StatusParams, CWD,
 };
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
-use std::borrow::Cow;
 use tui::{
-	layout::{Alignment, Constraint, Direction, Layout},
-	text::{Span, Spans},
+	layout::{Alignment, Constraint, Direction, Layout, Rect},
+	text::Spans,
 	widgets::{Block, Borders, Paragraph},
 };
 
+/// below this width the diff is only shown while focused, in place
+/// of the file list
+const DIFF_MIN_WIDTH: u16 = 100;
+
 #[derive(Default, Clone, Copy, Debug)]
 pub struct StashingOptions {
 	pub stash_untracked: bool,
@@ -32,16 +38,23 @@ pub struct StashingOptions {
 
 pub struct Stashing {
 	index: FileTreeComponent,
+	diff: DiffComponent,
+	/// the file list or the diff
+	focus: Pane,
 	visible: bool,
 	options: StashingOptions,
+	/// `status.showUntrackedFiles` of the repo
+	untracked_config: ShowUntrackedFilesConfig,
 	theme: SharedTheme,
 	git_status: AsyncStatus,
+	git_diff: AsyncDiff,
 	queue: Queue,
 	key_config: SharedKeyConfig,
+	app_options: SharedOptions,
 }
 
 impl Stashing {
-	accessors!(self, [index]);
+	accessors!(self, [index, diff]);
 
 	///
 	pub fn new(
@@ -49,6 +62,7 @@ impl Stashing {
 		queue: &Queue,
 		theme: SharedTheme,
 		key_config: SharedKeyConfig,
+		app_options: SharedOptions,
 	) -> Self {
 		Self {
 			index: FileTreeComponent::new(
@@ -58,24 +72,46 @@ impl Stashing {
 				theme.clone(),
 				key_config.clone(),
 			),
+			diff: DiffComponent::new(
+				queue.clone(),
+				theme.clone(),
+				key_config.clone(),
+				app_options.clone(),
+				true,
+			),
+			focus: Pane::WorkDir,
 			visible: false,
 			options: StashingOptions {
 				keep_index: false,
 				stash_untracked: true,
 			},
+			untracked_config: ShowUntrackedFilesConfig::Normal,
 			theme,
 			git_status: AsyncStatus::new(sender.clone()),
+			git_diff: AsyncDiff::new(sender),
 			queue: queue.clone(),
 			key_config,
+			app_options,
 		}
 	}
 
 	///
 	pub fn update(&mut self) -> Result<()> {
 		if self.is_visible() {
-			self.git_status
-				//TODO: support options
-				.fetch(&StatusParams::new(StatusType::Both, None))?;
+			// untracked files are listed if they get stashed, even
+			// if the config hides them in the status
+			let untracked = if !self.options.stash_untracked {
+				ShowUntrackedFilesConfig::No
+			} else if self.untracked_config.include_none() {
+				ShowUntrackedFilesConfig::Normal
+			} else {
+				self.untracked_config
+			};
+
+			self.git_status.fetch(&StatusParams::new(
+				StatusType::Both,
+				Some(untracked),
+			))?;
 		}
 
 		Ok(())
@@ -83,7 +119,7 @@ impl Stashing {
 
 	///
 	pub fn anything_pending(&self) -> bool {
-		self.git_status.is_pending()
+		self.git_status.is_pending() || self.git_diff.is_pending()
 	}
 
 	///
@@ -92,45 +128,128 @@ impl Stashing {
 		ev: AsyncGitNotification,
 	) -> Result<()> {
 		if self.is_visible() {
-			if let AsyncGitNotification::Status = ev {
-				let status = self.git_status.last()?;
-				self.index.update(&status.items)?;
+			match ev {
+				AsyncGitNotification::Status => {
+					let status = self.git_status.last()?;
+					self.index.update(&status.items)?;
+					self.update_diff()?;
+				}
+				AsyncGitNotification::Diff => self.update_diff()?,
+				_ => (),
 			}
 		}
 
 		Ok(())
 	}
 
-	fn get_option_text(&self) -> Vec<Spans> {
-		let bracket_open = Span::raw(Cow::from("["));
-		let bracket_close = Span::raw(Cow::from("]"));
-		let option_on =
-			Span::styled(Cow::from("x"), self.theme.option(true));
+	/// shows the working dir diff of the selected file,
+	/// untracked files show up as added entirely
+	pub fn update_diff(&mut self) -> Result<()> {
+		if !self.is_visible() {
+			return Ok(());
+		}
+
+		if let Some(item) = self.index.selection_file() {
+			let diff_params = DiffParams {
+				path: item.path.clone(),
+				old_path: None,
+				diff_type: DiffType::WorkDir,
+				options: self.app_options.borrow().diff,
+			};
+
+			if let Some((params, last)) = self.git_diff.last()? {
+				if params == diff_params {
+					self.diff.update(item.path, None, false, last);
+					return Ok(());
+				}
+			}
+
+			self.git_diff.request(diff_params)?;
+			self.diff.clear(true);
+		} else {
+			self.diff.clear(false);
+			if self.is_diff_focused() {
+				self.focus_diff(false);
+			}
+		}
+
+		Ok(())
+	}
+
+	fn can_focus_diff(&self) -> bool {
+		self.index.selection_file().is_some()
+	}
+
+	fn focus_diff(&mut self, focus: bool) {
+		self.focus = if focus { Pane::Diff } else { Pane::WorkDir };
+		self.diff.focus(focus);
+		self.index.focus(!focus);
+	}
+
+	fn is_diff_focused(&self) -> bool {
+		self.focus == Pane::Diff
+	}
+
+	fn draw_files<B: tui::backend::Backend>(
+		&self,
+		f: &mut tui::Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		if !self.index.is_pending() && self.index.is_empty() {
+			ui::draw_empty_state(
+				f,
+				rect,
+				&strings::stashing_files_title(&self.key_config),
+				&[strings::STASHING_CLEAN.to_string()],
+				true,
+				&self.theme,
+			);
+			return Ok(());
+		}
+
+		if rect.width < DIFF_MIN_WIDTH {
+			return if self.is_diff_focused() {
+				self.diff.draw(f, rect)
+			} else {
+				self.index.draw(f, rect)
+			};
+		}
+
+		let percentages = if self.is_diff_focused() {
+			(30, 70)
+		} else {
+			(50, 50)
+		};
+
+		let chunks = Layout::default()
+			.direction(Direction::Horizontal)
+			.constraints(
+				[
+					Constraint::Percentage(percentages.0),
+					Constraint::Percentage(percentages.1),
+				]
+				.as_ref(),
+			)
+			.split(rect);
+
+		self.index.draw(f, chunks[0])?;
+		self.diff.draw(f, chunks[1])?;
 
-		let option_off =
-			Span::styled(Cow::from("_"), self.theme.option(false));
+		Ok(())
+	}
 
+	fn get_option_text(&self) -> Vec<Spans> {
 		vec![
-			Spans::from(vec![
-				bracket_open.clone(),
-				if self.options.stash_untracked {
-					option_on.clone()
-				} else {
-					option_off.clone()
-				},
-				bracket_close.clone(),
-				Span::raw(Cow::from(" stash untracked")),
-			]),
-			Spans::from(vec![
-				bracket_open,
-				if self.options.keep_index {
-					option_on.clone()
-				} else {
-					option_off.clone()
-				},
-				bracket_close,
-				Span::raw(Cow::from(" keep index")),
-			]),
+			ui::checkbox(
+				strings::STASH_OPTION_UNTRACKED,
+				self.options.stash_untracked,
+				&self.theme,
+			),
+			ui::checkbox(
+				strings::STASH_OPTION_KEEP_INDEX,
+				self.options.keep_index,
+				&self.theme,
+			),
 		]
 	}
 }
@@ -164,7 +283,7 @@ impl DrawableComponent for Stashing {
 			right_chunks[0],
 		);
 
-		self.index.draw(f, chunks[0])?;
+		self.draw_files(f, chunks[0])?;
 
 		Ok(())
 	}
@@ -183,6 +302,18 @@ impl Component for Stashing {
 				self.components().as_slice(),
 			);
 
+			out.push(CommandInfo::new(
+				strings::commands::diff_focus_right(&self.key_config),
+				self.can_focus_diff(),
+				(self.visible && !self.is_diff_focused())
+					|| force_all,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::diff_focus_left(&self.key_config),
+				true,
+				(self.visible && self.is_diff_focused()) || force_all,
+			));
+
 			out.push(CommandInfo::new(
 				strings::commands::stashing_save(&self.key_config),
 				self.visible && !self.index.is_empty(),
@@ -219,7 +350,18 @@ impl Component for Stashing {
 			}
 
 			if let Event::Key(k) = ev {
-				return if k == self.key_config.stashing_save
+				return if k == self.key_config.focus_right
+					&& !self.is_diff_focused()
+					&& self.can_focus_diff()
+				{
+					self.focus_diff(true);
+					Ok(EventState::Consumed)
+				} else if k == self.key_config.focus_left
+					&& self.is_diff_focused()
+				{
+					self.focus_diff(false);
+					Ok(EventState::Consumed)
+				} else if k == self.key_config.stashing_save
 					&& !self.index.is_empty()
 				{
 					self.queue.push(InternalEvent::PopupStashing(
@@ -260,6 +402,7 @@ impl Component for Stashing {
 		let config_untracked_files =
 			sync::untracked_files_config(CWD)?;
 
+		self.untracked_config = config_untracked_files;
 		self.options.stash_untracked =
 			!config_untracked_files.include_none();
 
@@ -267,4 +410,36 @@ impl Component for Stashing {
 		self.update()?;
 		Ok(())
 	}
+
+	fn selectable_text(&self) -> Option<Vec<String>> {
+		if self.focus == Pane::Diff {
+			self.diff.selectable_text()
+		} else {
+			self.index.selectable_text()
+		}
+	}
+}
+
+impl PaneFocus for Stashing {
+	fn focusable_panes(&self) -> Vec<Pane> {
+		if self.is_diff_focused() || self.can_focus_diff() {
+			vec![Pane::WorkDir, Pane::Diff]
+		} else {
+			vec![Pane::WorkDir]
+		}
+	}
+
+	fn focused_pane(&self) -> Pane {
+		self.focus
+	}
+
+	fn focus_pane(&mut self, pane: Pane) -> Result<bool> {
+		if !self.focusable_panes().contains(&pane) {
+			return Ok(false);
+		}
+
+		self.focus_diff(pane == Pane::Diff);
+
+		Ok(true)
+	}
 }
@@ -2,10 +2,12 @@ use crate::{
 	components::{
 		visibility_blocking, CommandBlocking, CommandInfo,
 		CommitList, Component, DrawableComponent, EventState,
+		SharedOptions,
 	},
 	keys::SharedKeyConfig,
 	queue::{Action, InternalEvent, Queue},
 	strings,
+	tabs::{Pane, PaneFocus},
 	ui::style::SharedTheme,
 };
 use anyhow::Result;
@@ -28,6 +30,7 @@ impl StashList {
 		queue: &Queue,
 		theme: SharedTheme,
 		key_config: SharedKeyConfig,
+		options: SharedOptions,
 	) -> Self {
 		Self {
 			visible: false,
@@ -35,6 +38,7 @@ impl StashList {
 				&strings::stashlist_title(&key_config),
 				theme,
 				key_config.clone(),
+				options,
 			),
 			queue: queue.clone(),
 			key_config,
@@ -49,6 +53,9 @@ impl StashList {
 				sync::get_commits_info(CWD, stashes.as_slice(), 100)?;
 
 			self.list.set_count_total(commits.len());
+			self.list.set_empty_text(Some(strings::stashlist_empty(
+				&self.key_config,
+			)));
 			self.list.items().set_items(0, commits);
 		}
 
@@ -212,4 +219,26 @@ impl Component for StashList {
 		self.update()?;
 		Ok(())
 	}
+
+	fn on_resize(&self) {
+		self.list.on_resize();
+	}
+
+	fn selectable_text(&self) -> Option<Vec<String>> {
+		self.list.selectable_text()
+	}
+}
+
+impl PaneFocus for StashList {
+	fn focusable_panes(&self) -> Vec<Pane> {
+		vec![Pane::List]
+	}
+
+	fn focused_pane(&self) -> Pane {
+		Pane::List
+	}
+
+	fn focus_pane(&mut self, pane: Pane) -> Result<bool> {
+		Ok(pane == Pane::List)
+	}
 }
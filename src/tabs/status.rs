@@ -1,51 +1,61 @@
 use crate::{
 	accessors,
+	bookmarks::Location,
 	components::{
-		command_pump, event_pump, visibility_blocking,
-		ChangesComponent, CommandBlocking, CommandInfo, Component,
-		DiffComponent, DrawableComponent, EventState,
-		FileTreeItemKind, SharedOptions,
+		command_pump, event_pump, notify_flagged,
+		visibility_blocking, ChangesComponent, CommandBlocking,
+		CommandInfo, Component, DiffComponent, DrawableComponent,
+		EventState, FileSource, FileTreeItemKind, SharedOptions,
 	},
 	keys::SharedKeyConfig,
 	queue::{Action, InternalEvent, NeedsUpdate, Queue, ResetItem},
-	strings, try_or_popup,
-	ui::style::SharedTheme,
+	strings,
+	tabs::{Pane, PaneFocus, StashingOptions},
+	try_or_popup,
+	ui::{self, style::SharedTheme},
 };
 use anyhow::Result;
 use asyncgit::{
 	cached,
+	cherry_pick::CherryPickRequest,
 	sync::BranchCompare,
-	sync::{self, status::StatusType, RepoState},
-	AsyncDiff, AsyncGitNotification, AsyncStatus, DiffParams,
-	DiffType, StatusParams, CWD,
+	sync::{
+		self,
+		status::{StatusItemType, StatusType},
+		RepoState,
+	},
+	AsyncAutoFetch, AsyncDiff, AsyncGitNotification, AsyncStatus,
+	DiffParams, DiffType, StatusItem, StatusParams, CWD,
 };
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
 use itertools::Itertools;
 use std::convert::Into;
 use std::convert::TryFrom;
+use std::time::{Duration, Instant};
 use tui::{
 	layout::{Alignment, Constraint, Direction, Layout},
 	style::{Color, Style},
 	widgets::Paragraph,
 };
 
-/// what part of the screen is focused
-#[derive(PartialEq)]
-enum Focus {
-	WorkDir,
-	Diff,
-	Stage,
-}
+/// how long the auto fetch notice stays visible
+const AUTO_FETCH_NOTICE_DURATION: Duration = Duration::from_secs(30);
+
+/// share of the width the file lists take, in percent
+const SPLIT_DEFAULT: u16 = 50;
+const SPLIT_MIN: u16 = 20;
+const SPLIT_MAX: u16 = 80;
+const SPLIT_STEP: u16 = 5;
+/// the lists give up this much to a focused diff
+const SPLIT_DIFF_FOCUSED: u16 = 20;
 
 /// focus can toggle between workdir and stage
-impl Focus {
-	const fn toggled_focus(&self) -> Self {
-		match self {
-			Self::WorkDir => Self::Stage,
-			Self::Stage => Self::WorkDir,
-			Self::Diff => Self::Diff,
-		}
+const fn toggled_focus(focus: Pane) -> Pane {
+	match focus {
+		Pane::WorkDir => Pane::Stage,
+		Pane::Stage => Pane::WorkDir,
+		pane => pane,
 	}
 }
 
@@ -58,7 +68,9 @@ enum DiffTarget {
 
 pub struct Status {
 	visible: bool,
-	focus: Focus,
+	focus: Pane,
+	/// see `SPLIT_DEFAULT`
+	split: u16,
 	diff_target: DiffTarget,
 	index: ChangesComponent,
 	index_wd: ChangesComponent,
@@ -68,9 +80,26 @@ pub struct Status {
 	git_status_stage: AsyncStatus,
 	git_branch_state: Option<BranchCompare>,
 	git_branch_name: cached::BranchName,
+	git_auto_fetch: AsyncAutoFetch,
+	auto_fetch_new: usize,
+	auto_fetch_notice: Option<(String, Instant)>,
+	repo_state: RepoState,
 	queue: Queue,
 	git_action_executed: bool,
+	untracked_truncated: bool,
+	untracked_load_all: bool,
+	has_untracked: bool,
+	/// files hidden by `assume-unchanged` or `skip-worktree`
+	index_flagged: usize,
+	/// path selected in the last session, selected once loaded
+	restore_path: Option<String>,
+	/// path selected before our own git action, followed into the
+	/// other list if it left the focused one
+	follow_path: Option<String>,
+	/// probed once, case-only renames are only detected if set
+	case_insensitive_fs: bool,
 	options: SharedOptions,
+	theme: SharedTheme,
 	key_config: SharedKeyConfig,
 }
 
@@ -80,20 +109,46 @@ impl DrawableComponent for Status {
 		f: &mut tui::Frame<B>,
 		rect: tui::layout::Rect,
 	) -> Result<()> {
+		if self.is_clean() {
+			ui::draw_empty_state(
+				f,
+				rect,
+				&strings::title_status(&self.key_config),
+				&strings::status_empty(
+					self.git_branch_name.last().as_deref(),
+					self.git_branch_state
+						.as_ref()
+						.map(|state| (state.ahead, state.behind)),
+				),
+				true,
+				&self.theme,
+			);
+			return self.draw_auto_fetch_notice(f, rect);
+		}
+
+		let rect = if self.repo_state == RepoState::Clean {
+			rect
+		} else {
+			let chunks = Layout::default()
+				.direction(Direction::Vertical)
+				.constraints(
+					[Constraint::Length(1), Constraint::Min(0)]
+						.as_ref(),
+				)
+				.split(rect);
+
+			self.draw_repo_state(f, chunks[0]);
+
+			chunks[1]
+		};
+
 		let chunks = Layout::default()
 			.direction(Direction::Horizontal)
 			.constraints(
-				if self.focus == Focus::Diff {
-					[
-						Constraint::Percentage(30),
-						Constraint::Percentage(70),
-					]
-				} else {
-					[
-						Constraint::Percentage(50),
-						Constraint::Percentage(50),
-					]
-				}
+				[
+					Constraint::Percentage(self.lists_width()),
+					Constraint::Percentage(100 - self.lists_width()),
+				]
 				.as_ref(),
 			)
 			.split(rect);
@@ -120,7 +175,7 @@ impl DrawableComponent for Status {
 		self.index.draw(f, left_chunks[1])?;
 		self.diff.draw(f, chunks[1])?;
 		self.draw_branch_state(f, &left_chunks);
-		Self::draw_repo_state(f, left_chunks[0])?;
+		self.draw_auto_fetch_notice(f, left_chunks[1])?;
 
 		Ok(())
 	}
@@ -140,7 +195,8 @@ impl Status {
 		Self {
 			queue: queue.clone(),
 			visible: true,
-			focus: Focus::WorkDir,
+			focus: Pane::WorkDir,
+			split: SPLIT_DEFAULT,
 			diff_target: DiffTarget::WorkingDir,
 			index_wd: ChangesComponent::new(
 				&strings::title_status(&key_config),
@@ -149,6 +205,7 @@ impl Status {
 				queue.clone(),
 				theme.clone(),
 				key_config.clone(),
+				options.clone(),
 			),
 			index: ChangesComponent::new(
 				&strings::title_index(&key_config),
@@ -157,24 +214,49 @@ impl Status {
 				queue.clone(),
 				theme.clone(),
 				key_config.clone(),
+				options.clone(),
 			),
 			diff: DiffComponent::new(
 				queue.clone(),
-				theme,
+				theme.clone(),
 				key_config.clone(),
+				options.clone(),
 				false,
 			),
 			git_diff: AsyncDiff::new(sender),
 			git_status_workdir: AsyncStatus::new(sender.clone()),
 			git_status_stage: AsyncStatus::new(sender.clone()),
 			git_action_executed: false,
+			untracked_truncated: false,
+			untracked_load_all: false,
+			has_untracked: false,
+			index_flagged: 0,
+			restore_path: None,
+			follow_path: None,
+			case_insensitive_fs: sync::is_fs_case_insensitive(CWD)
+				.unwrap_or_default(),
 			git_branch_state: None,
 			git_branch_name: cached::BranchName::new(CWD),
+			git_auto_fetch: AsyncAutoFetch::new(sender),
+			auto_fetch_new: 0,
+			auto_fetch_notice: None,
+			repo_state: RepoState::Clean,
+			theme,
 			key_config,
 			options,
 		}
 	}
 
+	/// nothing staged or changed once the status is loaded, shown
+	/// instead of the empty lists
+	fn is_clean(&self) -> bool {
+		self.repo_state == RepoState::Clean
+			&& !self.index_wd.is_pending()
+			&& !self.index.is_pending()
+			&& self.index_wd.is_empty()
+			&& self.index.is_empty()
+	}
+
 	fn draw_branch_state<B: tui::backend::Backend>(
 		&self,
 		f: &mut tui::Frame<B>,
@@ -185,12 +267,46 @@ impl Status {
 				.git_branch_state
 				.as_ref()
 				.map_or_else(String::new, |state| {
+					let to_push = if state.ahead > 0 {
+						format!(
+							"{} ",
+							strings::commits_to_push(state.ahead)
+						)
+					} else {
+						String::new()
+					};
+
 					format!(
-						"\u{2191}{} \u{2193}{} ",
-						state.ahead, state.behind,
+						"{}\u{2191}{} \u{2193}{} ",
+						to_push, state.ahead, state.behind,
 					)
 				});
 
+			let ahead_behind = if self.auto_fetch_new > 0 {
+				format!(
+					"({} new) {}",
+					self.auto_fetch_new, ahead_behind
+				)
+			} else {
+				ahead_behind
+			};
+
+			let branch_name = if self
+				.options
+				.borrow()
+				.is_protected_branch(&branch_name)
+			{
+				strings::protected_branch_name(&branch_name)
+			} else {
+				branch_name
+			};
+
+			let branch_name = if self.git_branch_name.is_unborn() {
+				strings::unborn_branch_name(&branch_name)
+			} else {
+				branch_name
+			};
+
 			let w = Paragraph::new(format!(
 				"{}{{{}}}",
 				ahead_behind, branch_name
@@ -215,26 +331,48 @@ impl Status {
 		}
 	}
 
+	/// banner on top while a merge, rebase etc. is in progress
 	fn draw_repo_state<B: tui::backend::Backend>(
+		&self,
+		f: &mut tui::Frame<B>,
+		r: tui::layout::Rect,
+	) {
+		let ids = if self.repo_state == RepoState::Merge {
+			let ids = sync::mergehead_ids(CWD).unwrap_or_default();
+			format!(
+				"({}) ",
+				ids.iter().map(|id| id.abbreviated(CWD)).join(",")
+			)
+		} else {
+			String::new()
+		};
+
+		let txt = strings::repo_state_banner(
+			&self.key_config,
+			self.repo_state,
+			&ids,
+		);
+		let w = Paragraph::new(txt)
+			.style(Style::default().fg(Color::Red))
+			.alignment(Alignment::Left);
+
+		let mut rect = r;
+		rect.x += 1;
+		rect.width = rect.width.saturating_sub(2);
+
+		f.render_widget(w, rect);
+	}
+
+	fn draw_auto_fetch_notice<B: tui::backend::Backend>(
+		&self,
 		f: &mut tui::Frame<B>,
 		r: tui::layout::Rect,
 	) -> Result<()> {
-		if let Ok(state) = sync::repo_state(CWD) {
-			if state != RepoState::Clean {
-				let ids =
-					sync::mergehead_ids(CWD).unwrap_or_default();
-				let ids = format!(
-					"({})",
-					ids.iter()
-						.map(|id| sync::CommitId::get_short_string(
-							id
-						))
-						.join(",")
-				);
-				let txt = format!("{:?} {}", state, ids);
+		if let Some((txt, since)) = &self.auto_fetch_notice {
+			if since.elapsed() < AUTO_FETCH_NOTICE_DURATION {
 				let txt_len = u16::try_from(txt.len())?;
-				let w = Paragraph::new(txt)
-					.style(Style::default().fg(Color::Red))
+				let w = Paragraph::new(txt.as_str())
+					.style(Style::default().fg(Color::Yellow))
 					.alignment(Alignment::Left);
 
 				let mut rect = r;
@@ -255,37 +393,37 @@ impl Status {
 
 	fn can_focus_diff(&self) -> bool {
 		match self.focus {
-			Focus::WorkDir => self.index_wd.is_file_seleted(),
-			Focus::Stage => self.index.is_file_seleted(),
-			Focus::Diff => false,
+			Pane::WorkDir => self.index_wd.is_file_seleted(),
+			Pane::Stage => self.index.is_file_seleted(),
+			Pane::Diff | Pane::List | Pane::Tree | Pane::File => {
+				false
+			}
 		}
 	}
 
-	fn is_focus_on_diff(&self) -> bool {
-		self.focus == Focus::Diff
+	/// the stage list can not take the focus while empty, the commit
+	/// popup explains there is nothing to commit then
+	fn can_commit_nothing_staged(&self) -> bool {
+		self.focus == Pane::WorkDir
+			&& self.index.is_empty()
+			&& !self.index.is_pending()
 	}
 
-	fn switch_focus(&mut self, f: Focus) -> Result<bool> {
-		if self.focus != f {
-			self.focus = f;
+	/// unstaged changes, to stage something to commit
+	pub fn focus_workdir(&mut self) -> Result<()> {
+		self.switch_focus(Pane::WorkDir)?;
 
-			match self.focus {
-				Focus::WorkDir => {
-					self.set_diff_target(DiffTarget::WorkingDir);
-					self.diff.focus(false);
-				}
-				Focus::Stage => {
-					self.set_diff_target(DiffTarget::Stage);
-					self.diff.focus(false);
-				}
-				Focus::Diff => {
-					self.index.focus(false);
-					self.index_wd.focus(false);
+		Ok(())
+	}
 
-					self.diff.focus(true);
-				}
-			};
+	fn is_focus_on_diff(&self) -> bool {
+		self.focus == Pane::Diff
+	}
 
+	fn switch_focus(&mut self, f: Pane) -> Result<bool> {
+		if self.focus != f {
+			self.focus = f;
+			self.apply_focus();
 			self.update_diff()?;
 
 			return Ok(true);
@@ -294,6 +432,48 @@ impl Status {
 		Ok(false)
 	}
 
+	/// hands the focus to the components of the focused pane
+	fn apply_focus(&mut self) {
+		match self.focus {
+			Pane::WorkDir => {
+				self.set_diff_target(DiffTarget::WorkingDir);
+				self.diff.focus(false);
+			}
+			Pane::Stage => {
+				self.set_diff_target(DiffTarget::Stage);
+				self.diff.focus(false);
+			}
+			Pane::Diff => {
+				self.index.focus(false);
+				self.index_wd.focus(false);
+
+				self.diff.focus(true);
+			}
+			Pane::List | Pane::Tree | Pane::File => (),
+		}
+	}
+
+	/// percentage of the width taken by the file lists
+	fn lists_width(&self) -> u16 {
+		if self.focus == Pane::Diff {
+			self.split
+				.saturating_sub(SPLIT_DIFF_FOCUSED)
+				.max(SPLIT_MIN / 2)
+		} else {
+			self.split
+		}
+	}
+
+	/// share of the width the file lists take, in percent
+	pub const fn split(&self) -> u16 {
+		self.split
+	}
+
+	///
+	pub fn set_split(&mut self, split: u16) {
+		self.split = split.clamp(SPLIT_MIN, SPLIT_MAX);
+	}
+
 	fn set_diff_target(&mut self, target: DiffTarget) {
 		self.diff_target = target;
 		let is_stage = self.diff_target == DiffTarget::Stage;
@@ -302,7 +482,17 @@ impl Status {
 		self.index.focus_select(is_stage);
 	}
 
+	/// name of the checked out branch as of the last update
+	pub fn branch_name(&self) -> Option<String> {
+		self.git_branch_name.last()
+	}
+
 	pub fn selected_path(&self) -> Option<(String, bool)> {
+		self.selected_file()
+			.map(|(item, is_stage)| (item.path, is_stage))
+	}
+
+	fn selected_file(&self) -> Option<(StatusItem, bool)> {
 		let (idx, is_stage) = match self.diff_target {
 			DiffTarget::Stage => (&self.index, true),
 			DiffTarget::WorkingDir => (&self.index_wd, false),
@@ -310,7 +500,7 @@ impl Status {
 
 		if let Some(item) = idx.selection() {
 			if let FileTreeItemKind::File(i) = item.kind {
-				return Some((i.path, is_stage));
+				return Some((i, is_stage));
 			}
 		}
 		None
@@ -319,19 +509,30 @@ impl Status {
 	///
 	pub fn update(&mut self) -> Result<()> {
 		self.git_branch_name.lookup().map(Some).unwrap_or(None);
+		self.repo_state =
+			sync::repo_state(CWD).unwrap_or(RepoState::Clean);
 
 		if self.is_visible() {
 			let config = self.options.borrow().status_show_untracked;
+			// loading everything still goes through the limited
+			// path to get progress reported
+			let untracked_limit = if self.untracked_load_all {
+				Some(usize::MAX)
+			} else {
+				self.options.borrow().untracked_limit()
+			};
 
 			self.git_diff.refresh()?;
-			self.git_status_workdir.fetch(&StatusParams::new(
-				StatusType::WorkingDir,
-				config,
-			))?;
-			self.git_status_stage.fetch(&StatusParams::new(
-				StatusType::Stage,
-				config,
-			))?;
+			self.git_status_workdir.fetch(
+				&StatusParams::new(StatusType::WorkingDir, config)
+					.untracked_limit(untracked_limit)
+					.case_insensitive(self.case_insensitive_fs)
+					.empty_dirs(true),
+			)?;
+			self.git_status_stage.fetch(
+				&StatusParams::new(StatusType::Stage, config)
+					.case_insensitive(self.case_insensitive_fs),
+			)?;
 
 			self.branch_compare();
 		}
@@ -339,6 +540,15 @@ impl Status {
 		Ok(())
 	}
 
+	/// refreshes branch name and upstream state only
+	pub fn update_branch_state(&mut self) {
+		self.git_branch_name.lookup().ok();
+
+		if self.is_visible() {
+			self.branch_compare();
+		}
+	}
+
 	///
 	pub fn anything_pending(&self) -> bool {
 		self.git_diff.is_pending()
@@ -346,6 +556,17 @@ impl Status {
 			|| self.git_status_workdir.is_pending()
 	}
 
+	/// commits the branch is behind its upstream, as of the last
+	/// compare (no fetch)
+	pub fn upstream_behind(&self) -> Option<usize> {
+		self.git_branch_state.as_ref().map(|state| state.behind)
+	}
+
+	/// typing into the diff search
+	pub fn is_typing(&self) -> bool {
+		self.visible && self.diff.is_typing()
+	}
+
 	///
 	pub fn update_git(
 		&mut self,
@@ -354,17 +575,71 @@ impl Status {
 		match ev {
 			AsyncGitNotification::Diff => self.update_diff()?,
 			AsyncGitNotification::Status => self.update_status()?,
+			AsyncGitNotification::Fetch => {
+				// a manual fetch makes the new commits known anyway
+				self.auto_fetch_new = 0;
+				self.branch_compare();
+			}
 			AsyncGitNotification::Push
-			| AsyncGitNotification::Fetch
 			| AsyncGitNotification::CommitFiles => {
 				self.branch_compare();
 			}
+			AsyncGitNotification::AutoFetch => {
+				self.update_auto_fetch();
+			}
 			_ => (),
 		}
 
 		Ok(())
 	}
 
+	/// applies the auto fetch interval from the options
+	pub fn update_auto_fetch_interval(&self) -> Result<()> {
+		self.git_auto_fetch.set_interval(
+			self.options.borrow().auto_fetch_interval(),
+		)?;
+		Ok(())
+	}
+
+	/// skip auto fetches while a user initiated remote op is running
+	pub fn pause_auto_fetch(&self, paused: bool) {
+		self.git_auto_fetch.set_paused(paused);
+	}
+
+	/// `true` if the notice was shown until now
+	pub fn expire_auto_fetch_notice(&mut self) -> bool {
+		let expired =
+			self.auto_fetch_notice.as_ref().map_or(false, |notice| {
+				notice.1.elapsed() >= AUTO_FETCH_NOTICE_DURATION
+			});
+		if expired {
+			self.auto_fetch_notice = None;
+		}
+
+		expired
+	}
+
+	fn update_auto_fetch(&mut self) {
+		let count = self.git_auto_fetch.take_new_commits();
+		if count == 0 {
+			return;
+		}
+
+		self.auto_fetch_new += count;
+		self.auto_fetch_notice =
+			self.git_branch_name.last().map(|branch| {
+				(
+					strings::auto_fetch_notice(
+						self.auto_fetch_new,
+						&branch,
+					),
+					Instant::now(),
+				)
+			});
+
+		self.branch_compare();
+	}
+
 	fn update_status(&mut self) -> Result<()> {
 		let stage_status = self.git_status_stage.last()?;
 		self.index.set_items(&stage_status.items)?;
@@ -372,37 +647,179 @@ impl Status {
 		let workdir_status = self.git_status_workdir.last()?;
 		self.index_wd.set_items(&workdir_status.items)?;
 
+		self.untracked_truncated = workdir_status.untracked_truncated;
+		self.has_untracked = workdir_status
+			.items
+			.iter()
+			.any(|item| item.status == StatusItemType::New);
+		self.index_flagged = sync::index_flagged_files(CWD)
+			.map_or(0, |files| files.len());
+		self.update_workdir_title();
+
+		self.update_restore_selection()?;
+
 		self.update_diff()?;
 
 		if self.git_action_executed {
 			self.git_action_executed = false;
 
-			if self.focus == Focus::WorkDir
+			if self.focus == Pane::WorkDir
 				&& workdir_status.items.is_empty()
 				&& !stage_status.items.is_empty()
 			{
-				self.switch_focus(Focus::Stage)?;
-			} else if self.focus == Focus::Stage
+				self.switch_focus(Pane::Stage)?;
+			} else if self.focus == Pane::Stage
 				&& stage_status.items.is_empty()
 			{
-				self.switch_focus(Focus::WorkDir)?;
+				self.switch_focus(Pane::WorkDir)?;
 			}
 		}
 
 		Ok(())
 	}
 
+	/// selects `path` as soon as the status is loaded
+	pub fn restore_selection(&mut self, path: String) {
+		self.restore_path = Some(path);
+	}
+
+	/// keeps the selected file selected through the next status
+	/// update, in the other list if it left the focused one (e.g.
+	/// a commit leaves its unstaged changes in the working dir)
+	pub fn keep_selection(&mut self) {
+		if self.restore_path.is_none() {
+			self.follow_path =
+				self.selected_path().map(|(path, _)| path);
+		}
+	}
+
+	/// the selected file with the line its diff is at
+	pub fn location(&self) -> Option<Location> {
+		self.selected_path().map(|(path, _)| Location::StatusFile {
+			path,
+			line: self.diff.selection_end(),
+		})
+	}
+
+	/// selects `path` with its diff at `line`, once the status and
+	/// the diff are loaded
+	pub fn goto_file(&mut self, path: String, line: usize) {
+		self.diff.select_line(&path, line);
+		if self.selected_path().map(|(selected, _)| selected)
+			!= Some(path.clone())
+		{
+			self.restore_selection(path);
+		}
+	}
+
+	/// the selected path or the one about to be selected
+	pub fn selection(&self) -> Option<String> {
+		self.restore_path
+			.clone()
+			.or_else(|| self.selected_path().map(|(path, _)| path))
+	}
+
+	/// stays at the top if the path is no longer listed
+	fn update_restore_selection(&mut self) -> Result<()> {
+		if self.git_status_stage.is_pending()
+			|| self.git_status_workdir.is_pending()
+		{
+			return Ok(());
+		}
+
+		if let Some(path) = self.restore_path.take() {
+			if !self.index_wd.select_path(&path)
+				&& self.index.select_path(&path)
+			{
+				self.switch_focus(Pane::Stage)?;
+			}
+		}
+
+		if let Some(path) = self.follow_path.take() {
+			let (focused, other, target, pane) =
+				match self.diff_target {
+					DiffTarget::Stage => (
+						&mut self.index,
+						&mut self.index_wd,
+						DiffTarget::WorkingDir,
+						Pane::WorkDir,
+					),
+					DiffTarget::WorkingDir => (
+						&mut self.index_wd,
+						&mut self.index,
+						DiffTarget::Stage,
+						Pane::Stage,
+					),
+				};
+
+			if !focused.select_path(&path) && other.select_path(&path)
+			{
+				if self.focus == Pane::Diff {
+					// the diff stays focused, showing the other side
+					self.set_diff_target(target);
+				} else {
+					self.switch_focus(pane)?;
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	fn update_workdir_title(&mut self) {
+		let title = if self.untracked_load_all
+			&& self.git_status_workdir.is_pending()
+		{
+			strings::title_status_untracked_loading(
+				&self.key_config,
+				self.git_status_workdir.untracked_progress(),
+			)
+		} else if self.untracked_truncated {
+			strings::title_status_untracked_truncated(
+				&self.key_config,
+				self.options.borrow().status_untracked_limit,
+			)
+		} else if self.index_flagged > 0 {
+			strings::title_status_index_flagged(
+				&self.key_config,
+				self.index_flagged,
+			)
+		} else {
+			strings::title_status(&self.key_config)
+		};
+
+		self.index_wd.set_title(title);
+	}
+
+	fn load_all_untracked(&mut self) -> Result<()> {
+		self.untracked_load_all = true;
+		self.update()?;
+		self.update_workdir_title();
+
+		Ok(())
+	}
+
 	///
 	pub fn update_diff(&mut self) -> Result<()> {
-		if let Some((path, is_stage)) = self.selected_path() {
+		if let Some((item, is_stage)) = self.selected_file() {
+			// nothing to diff until it has a `.gitkeep`
+			if item.status == StatusItemType::EmptyDir {
+				self.diff.clear(false);
+				return Ok(());
+			}
+
+			let path = item.path;
 			let diff_type = if is_stage {
 				DiffType::Stage
 			} else {
 				DiffType::WorkDir
 			};
+			// renames only pair up old and new path in the index
+			let old_path = item.old_path.filter(|_| is_stage);
 
 			let diff_params = DiffParams {
 				path: path.clone(),
+				old_path: old_path.clone(),
 				diff_type,
 				options: self.options.borrow().diff,
 			};
@@ -413,19 +830,16 @@ impl Status {
 				if let Some((params, last)) = self.git_diff.last()? {
 					if params == diff_params {
 						// all params match, so we might need to update
-						self.diff.update(path, is_stage, last);
+						self.diff
+							.update(path, old_path, is_stage, last);
 					} else {
 						// params changed, we need to request the right diff
-						self.request_diff(
-							diff_params,
-							path,
-							is_stage,
-						)?;
+						self.request_diff(diff_params, is_stage)?;
 					}
 				}
 			} else {
 				// we dont show the right diff right now, so we need to request
-				self.request_diff(diff_params, path, is_stage)?;
+				self.request_diff(diff_params, is_stage)?;
 			}
 		} else {
 			self.diff.clear(false);
@@ -437,11 +851,12 @@ impl Status {
 	fn request_diff(
 		&mut self,
 		diff_params: DiffParams,
-		path: String,
 		is_stage: bool,
 	) -> Result<(), anyhow::Error> {
+		let path = diff_params.path.clone();
+		let old_path = diff_params.old_path.clone();
 		if let Some(diff) = self.git_diff.request(diff_params)? {
-			self.diff.update(path, is_stage, diff);
+			self.diff.update(path, old_path, is_stage, diff);
 		} else {
 			self.diff.clear(true);
 		}
@@ -459,13 +874,14 @@ impl Status {
 
 			false
 		} else {
+			notify_flagged(&self.queue, &self.key_config, &item.path);
 			true
 		}
 	}
 
 	pub fn last_file_moved(&mut self) -> Result<()> {
 		if !self.is_focus_on_diff() && self.is_visible() {
-			self.switch_focus(self.focus.toggled_focus())?;
+			self.switch_focus(toggled_focus(self.focus))?;
 		}
 		Ok(())
 	}
@@ -473,7 +889,12 @@ impl Status {
 	fn push(&self, force: bool) {
 		if self.can_push() {
 			if let Some(branch) = self.git_branch_name.last() {
-				if force {
+				if self.options.borrow().is_protected_branch(&branch)
+				{
+					self.queue.push(InternalEvent::ConfirmAction(
+						Action::PushProtected(branch, force),
+					));
+				} else if force {
 					self.queue.push(InternalEvent::ConfirmAction(
 						Action::ForcePush(branch, force),
 					));
@@ -492,6 +913,12 @@ impl Status {
 		}
 	}
 
+	fn fetch(&self) {
+		if let Some(branch) = self.git_branch_name.last() {
+			self.queue.push(InternalEvent::FetchPopup(branch));
+		}
+	}
+
 	fn undo_last_commit(&self) {
 		try_or_popup!(
 			self,
@@ -506,6 +933,69 @@ impl Status {
 				sync::branch_compare_upstream(CWD, branch.as_str())
 					.ok()
 			});
+
+		if self
+			.git_branch_state
+			.as_ref()
+			.map_or(true, |state| state.behind == 0)
+		{
+			self.auto_fetch_new = 0;
+		}
+	}
+
+	fn can_stash(&self) -> bool {
+		!self.index.is_empty() || !self.index_wd.is_empty()
+	}
+
+	/// stash untracked files only if there are any to see
+	fn stash(&self) {
+		self.queue.push(InternalEvent::PopupStashing(
+			StashingOptions {
+				stash_untracked: self.has_untracked,
+				keep_index: false,
+			},
+		));
+	}
+
+	/// unstages by pattern if the stage list is focused
+	fn stage_pattern(&mut self) -> Result<()> {
+		let unstage = self.focus == Pane::Stage;
+		let status = if unstage {
+			self.git_status_stage.last()?
+		} else {
+			self.git_status_workdir.last()?
+		};
+
+		self.queue.push(InternalEvent::OpenStagePattern(
+			status.items.into_iter().map(|item| item.path).collect(),
+			unstage,
+		));
+
+		Ok(())
+	}
+
+	/// a tracked file with changes in the working dir
+	fn skip_worktree_path(&self) -> Option<String> {
+		if self.focus != Pane::WorkDir {
+			return None;
+		}
+
+		self.selected_file()
+			.filter(|(item, _)| {
+				!matches!(
+					item.status,
+					StatusItemType::New | StatusItemType::EmptyDir
+				)
+			})
+			.map(|(item, _)| item.path)
+	}
+
+	fn conflicted_path(&self) -> Option<String> {
+		self.selected_file()
+			.filter(|(item, _)| {
+				item.status == StatusItemType::Conflicted
+			})
+			.map(|(item, _)| item.path)
 	}
 
 	fn can_push(&self) -> bool {
@@ -514,13 +1004,34 @@ impl Status {
 			.map_or(true, |state| state.ahead > 0)
 	}
 
-	fn can_abort_merge() -> bool {
-		sync::repo_state(CWD).unwrap_or(RepoState::Clean)
-			== RepoState::Merge
+	const fn repo_state_pending(&self) -> bool {
+		self.repo_state.is_pending()
 	}
 
-	pub fn abort_merge(&self) {
-		try_or_popup!(self, "abort merge", sync::abort_merge(CWD));
+	pub fn abort_repo_state(&self) {
+		try_or_popup!(
+			self,
+			"abort error:",
+			sync::abort_pending_state(CWD)
+		);
+	}
+
+	fn continue_repo_state(&self) {
+		// a cherry-pick sequence goes on in its popup
+		if self.repo_state == RepoState::CherryPick
+			&& matches!(sync::cherry_pick_progress(CWD), Ok(Some(_)))
+		{
+			self.queue.push(InternalEvent::CherryPick(
+				CherryPickRequest::Continue,
+			));
+			return;
+		}
+
+		try_or_popup!(
+			self,
+			"continue error:",
+			sync::continue_pending_state(CWD)
+		);
 	}
 
 	fn commands_nav(
@@ -550,7 +1061,8 @@ impl Status {
 				strings::commands::select_staging(&self.key_config),
 				!focus_on_diff,
 				(self.visible
-					&& !focus_on_diff && self.focus == Focus::WorkDir)
+					&& !focus_on_diff
+					&& self.focus == Pane::WorkDir)
 					|| force_all,
 			)
 			.order(strings::order::NAV),
@@ -560,11 +1072,20 @@ impl Status {
 				strings::commands::select_unstaged(&self.key_config),
 				!focus_on_diff,
 				(self.visible
-					&& !focus_on_diff && self.focus == Focus::Stage)
+					&& !focus_on_diff
+					&& self.focus == Pane::Stage)
 					|| force_all,
 			)
 			.order(strings::order::NAV),
 		);
+		out.push(
+			CommandInfo::new(
+				strings::commands::status_split(&self.key_config),
+				true,
+				self.visible || force_all,
+			)
+			.order(strings::order::NAV),
+		);
 	}
 }
 
@@ -590,6 +1111,35 @@ impl Component for Status {
 				true,
 				!focus_on_diff,
 			));
+			out.push(CommandInfo::new(
+				strings::commands::checkout_previous_branch(
+					&self.key_config,
+				),
+				true,
+				!focus_on_diff,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::open_recent_branches(
+					&self.key_config,
+				),
+				true,
+				!focus_on_diff,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::open_branch_changes(
+					&self.key_config,
+				),
+				true,
+				!focus_on_diff,
+			));
+			out.push(
+				CommandInfo::new(
+					strings::commands::commit_open(&self.key_config),
+					true,
+					self.can_commit_nothing_staged(),
+				)
+				.order(-1),
+			);
 
 			out.push(CommandInfo::new(
 				strings::commands::status_push(&self.key_config),
@@ -608,6 +1158,51 @@ impl Component for Status {
 				true,
 				!focus_on_diff,
 			));
+			out.push(CommandInfo::new(
+				strings::commands::status_fetch(&self.key_config),
+				true,
+				!focus_on_diff,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::status_stash(&self.key_config),
+				self.can_stash(),
+				!focus_on_diff,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::status_stage_pattern(
+					&self.key_config,
+					self.focus == Pane::Stage,
+				),
+				true,
+				!focus_on_diff,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::status_index_flags(
+					&self.key_config,
+				),
+				true,
+				!focus_on_diff,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::status_skip_worktree(
+					&self.key_config,
+				),
+				self.skip_worktree_path().is_some(),
+				!focus_on_diff,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::status_resolve_conflict(
+					&self.key_config,
+				),
+				self.conflicted_path().is_some(),
+				!focus_on_diff
+					&& (self.conflicted_path().is_some()
+						|| force_all),
+			));
 
 			out.push(CommandInfo::new(
 				strings::commands::undo_commit(&self.key_config),
@@ -616,9 +1211,28 @@ impl Component for Status {
 			));
 
 			out.push(CommandInfo::new(
-				strings::commands::abort_merge(&self.key_config),
+				strings::commands::abort_merge(
+					&self.key_config,
+					self.repo_state,
+				),
+				true,
+				self.repo_state_pending() || force_all,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::continue_merge(
+					&self.key_config,
+					self.repo_state,
+				),
+				true,
+				self.repo_state_pending() || force_all,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::load_all_untracked(
+					&self.key_config,
+				),
 				true,
-				Self::can_abort_merge() || force_all,
+				self.untracked_truncated || force_all,
 			));
 		}
 
@@ -632,6 +1246,11 @@ impl Component for Status {
 				},
 				self.visible || force_all,
 			));
+			out.push(CommandInfo::new(
+				strings::commands::view_file(&self.key_config),
+				focus_on_diff || self.can_focus_diff(),
+				self.visible || force_all,
+			));
 
 			self.commands_nav(out, force_all);
 		}
@@ -664,36 +1283,83 @@ impl Component for Status {
 						);
 					}
 					Ok(EventState::Consumed)
+				} else if k == self.key_config.view_file
+					&& (self.can_focus_diff()
+						|| self.is_focus_on_diff())
+				{
+					if let Some((path, is_stage)) =
+						self.selected_path()
+					{
+						self.queue.push(InternalEvent::ViewFile(
+							if is_stage {
+								FileSource::Index(path)
+							} else {
+								FileSource::WorkDir(path)
+							},
+						));
+					}
+					Ok(EventState::Consumed)
+				} else if k == self.key_config.status_split_left {
+					self.set_split(
+						self.split.saturating_sub(SPLIT_STEP),
+					);
+					Ok(EventState::Consumed)
+				} else if k == self.key_config.status_split_right {
+					self.set_split(self.split + SPLIT_STEP);
+					Ok(EventState::Consumed)
 				} else if k == self.key_config.toggle_workarea
 					&& !self.is_focus_on_diff()
 				{
-					self.switch_focus(self.focus.toggled_focus())
+					self.switch_focus(toggled_focus(self.focus))
 						.map(Into::into)
 				} else if k == self.key_config.focus_right
 					&& self.can_focus_diff()
 				{
-					self.switch_focus(Focus::Diff).map(Into::into)
+					self.switch_focus(Pane::Diff).map(Into::into)
 				} else if k == self.key_config.focus_left {
 					self.switch_focus(match self.diff_target {
-						DiffTarget::Stage => Focus::Stage,
-						DiffTarget::WorkingDir => Focus::WorkDir,
+						DiffTarget::Stage => Pane::Stage,
+						DiffTarget::WorkingDir => Pane::WorkDir,
 					})
 					.map(Into::into)
 				} else if k == self.key_config.move_down
-					&& self.focus == Focus::WorkDir
+					&& self.focus == Pane::WorkDir
 					&& !self.index.is_empty()
 				{
-					self.switch_focus(Focus::Stage).map(Into::into)
+					self.switch_focus(Pane::Stage).map(Into::into)
 				} else if k == self.key_config.move_up
-					&& self.focus == Focus::Stage
+					&& self.focus == Pane::Stage
 					&& !self.index_wd.is_empty()
 				{
-					self.switch_focus(Focus::WorkDir).map(Into::into)
+					self.switch_focus(Pane::WorkDir).map(Into::into)
 				} else if k == self.key_config.select_branch
 					&& !self.is_focus_on_diff()
 				{
 					self.queue.push(InternalEvent::SelectBranch);
 					Ok(EventState::Consumed)
+				} else if k
+					== self.key_config.checkout_previous_branch
+					&& !self.is_focus_on_diff()
+				{
+					self.queue
+						.push(InternalEvent::CheckoutPreviousBranch);
+					Ok(EventState::Consumed)
+				} else if k == self.key_config.recent_branches
+					&& !self.is_focus_on_diff()
+				{
+					self.queue.push(InternalEvent::RecentBranches);
+					Ok(EventState::Consumed)
+				} else if k == self.key_config.open_commit
+					&& self.can_commit_nothing_staged()
+				{
+					self.queue.push(InternalEvent::OpenCommit);
+					Ok(EventState::Consumed)
+				} else if k == self.key_config.branch_changes
+					&& !self.is_focus_on_diff()
+				{
+					self.queue
+						.push(InternalEvent::OpenSinceBranching);
+					Ok(EventState::Consumed)
 				} else if k == self.key_config.force_push
 					&& !self.is_focus_on_diff()
 					&& self.can_push()
@@ -710,6 +1376,43 @@ impl Component for Status {
 				{
 					self.pull();
 					Ok(EventState::Consumed)
+				} else if k == self.key_config.fetch
+					&& !self.is_focus_on_diff()
+				{
+					self.fetch();
+					Ok(EventState::Consumed)
+				} else if k == self.key_config.status_stash
+					&& !self.is_focus_on_diff()
+					&& self.can_stash()
+				{
+					self.stash();
+					Ok(EventState::Consumed)
+				} else if k == self.key_config.status_stage_pattern
+					&& !self.is_focus_on_diff()
+				{
+					self.stage_pattern()?;
+					Ok(EventState::Consumed)
+				} else if k == self.key_config.status_index_flags
+					&& !self.is_focus_on_diff()
+				{
+					self.queue.push(InternalEvent::OpenIndexFlags);
+					Ok(EventState::Consumed)
+				} else if let (true, Some(path)) = (
+					k == self.key_config.status_skip_worktree,
+					self.skip_worktree_path(),
+				) {
+					self.queue.push(InternalEvent::ConfirmAction(
+						Action::SkipWorktree(path),
+					));
+					Ok(EventState::Consumed)
+				} else if let (true, false, Some(path)) = (
+					k == self.key_config.status_resolve_conflict,
+					self.is_focus_on_diff(),
+					self.conflicted_path(),
+				) {
+					self.queue
+						.push(InternalEvent::OpenConflict(path));
+					Ok(EventState::Consumed)
 				} else if k == self.key_config.undo_commit
 					&& !self.is_focus_on_diff()
 				{
@@ -719,12 +1422,27 @@ impl Component for Status {
 					));
 					Ok(EventState::Consumed)
 				} else if k == self.key_config.abort_merge
-					&& Self::can_abort_merge()
+					&& self.repo_state_pending()
 				{
 					self.queue.push(InternalEvent::ConfirmAction(
-						Action::AbortMerge,
+						Action::AbortRepoState(self.repo_state),
+					));
+
+					Ok(EventState::Consumed)
+				} else if k == self.key_config.continue_merge
+					&& self.repo_state_pending()
+				{
+					self.continue_repo_state();
+					self.queue.push(InternalEvent::Update(
+						NeedsUpdate::ALL,
 					));
 
+					Ok(EventState::Consumed)
+				} else if k
+					== self.key_config.status_load_all_untracked
+					&& self.untracked_truncated
+				{
+					self.load_all_untracked()?;
 					Ok(EventState::Consumed)
 				} else {
 					Ok(EventState::NotConsumed)
@@ -749,4 +1467,45 @@ impl Component for Status {
 
 		Ok(())
 	}
+
+	fn on_resize(&self) {
+		self.diff.on_resize();
+	}
+
+	fn selectable_text(&self) -> Option<Vec<String>> {
+		match self.focus {
+			Pane::WorkDir => self.index_wd.selectable_text(),
+			Pane::Stage => self.index.selectable_text(),
+			Pane::Diff => self.diff.selectable_text(),
+			_ => None,
+		}
+	}
+}
+
+impl PaneFocus for Status {
+	fn focusable_panes(&self) -> Vec<Pane> {
+		let mut panes = vec![Pane::WorkDir, Pane::Stage];
+		if self.is_focus_on_diff() || self.can_focus_diff() {
+			panes.push(Pane::Diff);
+		}
+
+		panes
+	}
+
+	fn focused_pane(&self) -> Pane {
+		self.focus
+	}
+
+	fn focus_pane(&mut self, pane: Pane) -> Result<bool> {
+		if !self.focusable_panes().contains(&pane) {
+			return Ok(false);
+		}
+
+		// the components might have lost it while a popup was open
+		if !self.switch_focus(pane)? {
+			self.apply_focus();
+		}
+
+		Ok(true)
+	}
 }
@@ -0,0 +1,115 @@
+use super::style::{SharedTheme, Theme};
+use std::convert::TryFrom;
+use tui::{
+	backend::Backend,
+	layout::{Alignment, Rect},
+	text::{Span, Spans},
+	widgets::{Block, Borders, Paragraph, Wrap},
+	Frame,
+};
+
+/// draws `lines` centered in the pane instead of an empty list, the
+/// `[key]` hints in them (see `KeyConfig::get_hint`) are highlighted
+pub fn draw_empty_state<B: Backend>(
+	f: &mut Frame<B>,
+	r: Rect,
+	title: &str,
+	lines: &[String],
+	selected: bool,
+	theme: &SharedTheme,
+) {
+	let block = Block::default()
+		.title(Span::styled(title, theme.pane_title(selected)))
+		.borders(Borders::ALL)
+		.border_style(theme.block(selected));
+
+	let inner = block.inner(r);
+	f.render_widget(block, r);
+
+	// rows of the wrapped lines, roughly (wrapping breaks at words)
+	let width = usize::from(inner.width.max(1));
+	let rows: usize = lines
+		.iter()
+		.map(|line| (line.chars().count() + width - 1) / width)
+		.map(|rows| rows.max(1))
+		.sum();
+	let height =
+		u16::try_from(rows).unwrap_or(u16::MAX).min(inner.height);
+	let area = Rect {
+		y: inner.y + (inner.height - height) / 2,
+		height,
+		..inner
+	};
+
+	f.render_widget(
+		Paragraph::new(
+			lines
+				.iter()
+				.map(|line| hint_spans(line, theme))
+				.collect::<Vec<_>>(),
+		)
+		.alignment(Alignment::Center)
+		.wrap(Wrap { trim: true }),
+		area,
+	);
+}
+
+/// splits off the `[...]` key hints to style them
+fn hint_spans<'a>(line: &'a str, theme: &Theme) -> Spans<'a> {
+	let mut spans = Vec::new();
+	let mut rest = line;
+
+	while let Some(start) = rest.find('[') {
+		let end = match rest[start..].find(']') {
+			Some(end) => start + end + 1,
+			None => break,
+		};
+
+		if start > 0 {
+			spans.push(Span::styled(
+				&rest[..start],
+				theme.text(true, false),
+			));
+		}
+		spans.push(Span::styled(&rest[start..end], theme.key_hint()));
+		rest = &rest[end..];
+	}
+
+	if !rest.is_empty() {
+		spans.push(Span::styled(rest, theme.text(true, false)));
+	}
+
+	Spans::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn contents(spans: &Spans) -> Vec<String> {
+		spans
+			.0
+			.iter()
+			.map(|span| span.content.to_string())
+			.collect()
+	}
+
+	#[test]
+	fn test_hint_spans() {
+		let theme = Theme::default();
+
+		let spans =
+			hint_spans("press [S] on the Status tab [1]", &theme);
+		assert_eq!(
+			contents(&spans),
+			vec!["press ", "[S]", " on the Status tab ", "[1]"]
+		);
+		assert_eq!(spans.0[1].style, theme.key_hint());
+		assert_eq!(spans.0[0].style, theme.text(true, false));
+
+		assert_eq!(
+			contents(&hint_spans("no [hint", &theme)),
+			vec!["no [hint"]
+		);
+	}
+}
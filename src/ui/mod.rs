@@ -1,18 +1,26 @@
+mod empty_state;
 mod reflow;
 mod scrollbar;
 mod scrolllist;
+mod selection_mode;
 mod stateful_paragraph;
 pub mod style;
 mod syntax_text;
 
+pub use empty_state::draw_empty_state;
 use filetreelist::MoveSelection;
 pub use scrollbar::draw_scrollbar;
 pub use scrolllist::{draw_list, draw_list_block};
+pub use selection_mode::{draw_selection_mode, spans_text};
 pub use stateful_paragraph::{
 	ParagraphState, ScrollPos, StatefulParagraph,
 };
+use style::Theme;
 pub use syntax_text::{AsyncSyntaxJob, SyntaxText};
-use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::{
+	layout::{Constraint, Direction, Layout, Rect},
+	text::{Span, Spans},
+};
 
 use crate::keys::SharedKeyConfig;
 
@@ -85,7 +93,8 @@ pub fn centered_rect(
 		.split(popup_layout[1])[1]
 }
 
-/// makes sure Rect `r` at least stays as big as min and not bigger than max
+/// makes sure Rect `r` at least stays as big as min and not bigger than max,
+/// the result never reaches outside of `max`
 pub fn rect_inside(min: Size, max: Size, r: Rect) -> Rect {
 	let new_width = r.width.max(min.width).min(max.width);
 	let new_height = r.height.max(min.height).min(max.height);
@@ -93,8 +102,10 @@ pub fn rect_inside(min: Size, max: Size, r: Rect) -> Rect {
 	let diff_height = new_height.saturating_sub(r.height);
 
 	Rect::new(
-		r.x.saturating_sub(diff_width / 2),
-		r.y.saturating_sub(diff_height / 2),
+		r.x.saturating_sub(diff_width / 2)
+			.min(max.width.saturating_sub(new_width)),
+		r.y.saturating_sub(diff_height / 2)
+			.min(max.height.saturating_sub(new_height)),
 		new_width,
 		new_height,
 	)
@@ -106,13 +117,30 @@ pub fn centered_rect_absolute(
 	r: Rect,
 ) -> Rect {
 	Rect::new(
-		(r.width.saturating_sub(width)) / 2,
-		(r.height.saturating_sub(height)) / 2,
+		r.x + (r.width.saturating_sub(width)) / 2,
+		r.y + (r.height.saturating_sub(height)) / 2,
 		width.min(r.width),
 		height.min(r.height),
 	)
 }
 
+/// `[x] label` or `[_] label`
+pub fn checkbox<'a>(
+	label: &'a str,
+	checked: bool,
+	theme: &Theme,
+) -> Spans<'a> {
+	Spans::from(vec![
+		Span::raw("["),
+		Span::styled(
+			if checked { "x" } else { "_" },
+			theme.option(checked),
+		),
+		Span::raw("] "),
+		Span::raw(label),
+	])
+}
+
 ///
 pub fn common_nav(
 	key: crossterm::event::KeyEvent,
@@ -5,7 +5,7 @@ use tui::{
 	buffer::Buffer,
 	layout::Rect,
 	style::Style,
-	text::Span,
+	text::{Span, Text},
 	widgets::{Block, Borders, List, ListItem, Widget},
 	Frame,
 };
@@ -13,7 +13,8 @@ use tui::{
 ///
 struct ScrollableList<'b, L>
 where
-	L: Iterator<Item = Span<'b>>,
+	L: Iterator,
+	L::Item: Into<Text<'b>>,
 {
 	block: Option<Block<'b>>,
 	/// Items to be displayed
@@ -24,7 +25,8 @@ where
 
 impl<'b, L> ScrollableList<'b, L>
 where
-	L: Iterator<Item = Span<'b>>,
+	L: Iterator,
+	L::Item: Into<Text<'b>>,
 {
 	fn new(items: L) -> Self {
 		Self {
@@ -42,7 +44,8 @@ where
 
 impl<'b, L> Widget for ScrollableList<'b, L>
 where
-	L: Iterator<Item = Span<'b>>,
+	L: Iterator,
+	L::Item: Into<Text<'b>>,
 {
 	fn render(self, area: Rect, buf: &mut Buffer) {
 		// Render items
@@ -63,11 +66,12 @@ pub fn draw_list<'b, B: Backend, L>(
 	selected: bool,
 	theme: &SharedTheme,
 ) where
-	L: Iterator<Item = Span<'b>>,
+	L: Iterator,
+	L::Item: Into<Text<'b>>,
 {
 	let list = ScrollableList::new(items).block(
 		Block::default()
-			.title(Span::styled(title, theme.title(selected)))
+			.title(Span::styled(title, theme.pane_title(selected)))
 			.borders(Borders::ALL)
 			.border_style(theme.block(selected)),
 	);
@@ -80,7 +84,8 @@ pub fn draw_list_block<'b, B: Backend, L>(
 	block: Block<'b>,
 	items: L,
 ) where
-	L: Iterator<Item = Span<'b>>,
+	L: Iterator,
+	L::Item: Into<Text<'b>>,
 {
 	let list = ScrollableList::new(items).block(block);
 	f.render_widget(list, r);
@@ -0,0 +1,98 @@
+use tui::{
+	backend::Backend,
+	layout::Rect,
+	style::{Modifier, Style},
+	text::Spans,
+	widgets::Paragraph,
+	Frame,
+};
+
+/// the content of `spans` without any of its styling
+pub fn spans_text(spans: &Spans) -> String {
+	spans
+		.0
+		.iter()
+		.map(|span| span.content.as_ref())
+		.collect::<String>()
+		.trim_end()
+		.to_string()
+}
+
+/// `lines` as they are, without borders or colors getting in the way
+/// of the terminal selecting them, `hint` in the last row
+pub fn draw_selection_mode<B: Backend>(
+	f: &mut Frame<B>,
+	lines: &[String],
+	hint: &str,
+) {
+	let r = f.size();
+	if r.height == 0 {
+		return;
+	}
+
+	let text_height = r.height - 1;
+	f.render_widget(
+		Paragraph::new(
+			lines
+				.iter()
+				.take(usize::from(text_height))
+				.map(|line| Spans::from(line.as_str()))
+				.collect::<Vec<_>>(),
+		),
+		Rect {
+			height: text_height,
+			..r
+		},
+	);
+	f.render_widget(
+		Paragraph::new(hint)
+			.style(Style::default().add_modifier(Modifier::REVERSED)),
+		Rect {
+			y: r.y + text_height,
+			height: 1,
+			..r
+		},
+	);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tui::{backend::TestBackend, text::Span, Terminal};
+
+	#[test]
+	fn test_spans_text() {
+		let spans = Spans::from(vec![
+			Span::styled("│", Style::default()),
+			Span::raw("+fn main() {"),
+			Span::raw("   "),
+		]);
+
+		assert_eq!(spans_text(&spans), "│+fn main() {");
+	}
+
+	#[test]
+	fn test_draw_selection_mode() {
+		let mut terminal =
+			Terminal::new(TestBackend::new(10, 3)).unwrap();
+		let lines = vec![
+			String::from("one"),
+			String::from("two"),
+			String::from("three"),
+		];
+
+		terminal
+			.draw(|f| draw_selection_mode(f, &lines, "esc"))
+			.unwrap();
+
+		let buffer = terminal.backend().buffer();
+		let row = |y: u16| {
+			(0..10)
+				.map(|x| buffer.get(x, y).symbol.as_str())
+				.collect::<String>()
+		};
+		assert_eq!(row(0), "one       ");
+		assert_eq!(row(1), "two       ");
+		assert_eq!(row(2), "esc       ");
+	}
+}
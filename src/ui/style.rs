@@ -1,6 +1,7 @@
 //TODO: remove once fixed https://github.com/rust-lang/rust-clippy/issues/6818
 #![allow(clippy::use_self)]
 
+use crate::messages::Severity;
 use anyhow::Result;
 use asyncgit::{DiffLineType, StatusItemType};
 use ron::{
@@ -9,15 +10,89 @@ use ron::{
 };
 use serde::{Deserialize, Serialize};
 use std::{
+	cell::Cell,
 	fs::{self, File},
 	io::{Read, Write},
 	path::PathBuf,
 	rc::Rc,
 };
-use tui::style::{Color, Modifier, Style};
+use tui::{
+	style::{Color, Modifier, Style},
+	text::Span,
+};
 
 pub type SharedTheme = Rc<Theme>;
 
+/// indicators beyond color, the theme sets the defaults and each
+/// one is switched at runtime in the options
+#[derive(
+	Serialize, Deserialize, Default, Clone, Copy, PartialEq, Debug,
+)]
+#[serde(default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Indicators {
+	/// fixed high contrast colors instead of the theme's
+	pub high_contrast: bool,
+	/// bold `+`/`-` in front of added and removed diff lines
+	pub diff_glyphs: bool,
+	/// `>` in front of the selected row and underlining it
+	pub selection_marker: bool,
+	/// `M`, `A`, `D`, `R`, `T` and `U` (conflicted) as file status
+	pub status_letters: bool,
+}
+
+/// colors used for drawing, see `Theme` for their meaning
+#[derive(Clone, Copy)]
+struct Palette {
+	selected_tab: Color,
+	command_fg: Color,
+	selection_bg: Color,
+	cmdbar_extra_lines_bg: Color,
+	disabled_fg: Color,
+	diff_line_add: Color,
+	diff_line_delete: Color,
+	diff_file_added: Color,
+	diff_file_removed: Color,
+	diff_file_moved: Color,
+	diff_file_modified: Color,
+	commit_hash: Color,
+	commit_time: Color,
+	commit_author: Color,
+	danger_fg: Color,
+	push_gauge_bg: Color,
+	push_gauge_fg: Color,
+	focused_title: Color,
+	diff_trailing_whitespace: Color,
+	diff_whitespace: Color,
+	diff_line_moved: Color,
+}
+
+/// black on white selection, added and removed told apart by
+/// cyan and magenta instead of green and red
+const HIGH_CONTRAST: Palette = Palette {
+	selected_tab: Color::White,
+	command_fg: Color::Black,
+	selection_bg: Color::White,
+	cmdbar_extra_lines_bg: Color::Gray,
+	disabled_fg: Color::Gray,
+	diff_line_add: Color::LightCyan,
+	diff_line_delete: Color::LightMagenta,
+	diff_file_added: Color::LightCyan,
+	diff_file_removed: Color::LightMagenta,
+	diff_file_moved: Color::LightBlue,
+	diff_file_modified: Color::LightYellow,
+	commit_hash: Color::LightYellow,
+	commit_time: Color::White,
+	commit_author: Color::LightCyan,
+	danger_fg: Color::LightRed,
+	push_gauge_bg: Color::White,
+	push_gauge_fg: Color::Black,
+	focused_title: Color::White,
+	diff_trailing_whitespace: Color::LightMagenta,
+	diff_whitespace: Color::Gray,
+	diff_line_moved: Color::LightBlue,
+};
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Theme {
 	selected_tab: Color,
@@ -53,18 +128,99 @@ pub struct Theme {
 	push_gauge_bg: Color,
 	#[serde(with = "Color")]
 	push_gauge_fg: Color,
+	/// title of the pane of a tab that has the focus
+	#[serde(with = "Color", default = "default_focused_title")]
+	focused_title: Color,
+	/// background of trailing whitespace on added diff lines
+	#[serde(
+		with = "Color",
+		default = "default_diff_trailing_whitespace"
+	)]
+	diff_trailing_whitespace: Color,
+	/// `→` and `·` shown for whitespace in diffs
+	#[serde(with = "Color", default = "default_diff_whitespace")]
+	diff_whitespace: Color,
+	/// added and removed lines a commit moves between files
+	#[serde(with = "Color", default = "default_diff_line_moved")]
+	diff_line_moved: Color,
+	#[serde(default)]
+	indicators: Indicators,
+	/// `indicators` as switched at runtime
+	#[serde(skip)]
+	active_indicators: Cell<Indicators>,
 }
 
 impl Theme {
+	pub fn indicators(&self) -> Indicators {
+		self.active_indicators.get()
+	}
+
+	pub fn set_indicators(&self, indicators: Indicators) {
+		self.active_indicators.set(indicators);
+	}
+
+	fn palette(&self) -> Palette {
+		if self.indicators().high_contrast {
+			return HIGH_CONTRAST;
+		}
+
+		Palette {
+			selected_tab: self.selected_tab,
+			command_fg: self.command_fg,
+			selection_bg: self.selection_bg,
+			cmdbar_extra_lines_bg: self.cmdbar_extra_lines_bg,
+			disabled_fg: self.disabled_fg,
+			diff_line_add: self.diff_line_add,
+			diff_line_delete: self.diff_line_delete,
+			diff_file_added: self.diff_file_added,
+			diff_file_removed: self.diff_file_removed,
+			diff_file_moved: self.diff_file_moved,
+			diff_file_modified: self.diff_file_modified,
+			commit_hash: self.commit_hash,
+			commit_time: self.commit_time,
+			commit_author: self.commit_author,
+			danger_fg: self.danger_fg,
+			push_gauge_bg: self.push_gauge_bg,
+			push_gauge_fg: self.push_gauge_fg,
+			focused_title: self.focused_title,
+			diff_trailing_whitespace: self.diff_trailing_whitespace,
+			diff_whitespace: self.diff_whitespace,
+			diff_line_moved: self.diff_line_moved,
+		}
+	}
+
+	/// gutter in front of list rows, `None` unless the selection
+	/// marker is on
+	pub fn selection_marker(
+		&self,
+		selected: bool,
+	) -> Option<Span<'static>> {
+		if !self.indicators().selection_marker {
+			return None;
+		}
+
+		Some(if selected {
+			Span::styled(
+				"> ",
+				self.apply_select(
+					Style::default().add_modifier(Modifier::BOLD),
+					true,
+				),
+			)
+		} else {
+			Span::raw("  ")
+		})
+	}
+
 	pub fn scroll_bar_pos(&self) -> Style {
-		Style::default().fg(self.selection_bg)
+		Style::default().fg(self.palette().selection_bg)
 	}
 
 	pub fn block(&self, focus: bool) -> Style {
 		if focus {
 			Style::default()
 		} else {
-			Style::default().fg(self.disabled_fg)
+			Style::default().fg(self.palette().disabled_fg)
 		}
 	}
 
@@ -72,7 +228,18 @@ impl Theme {
 		if focused {
 			Style::default().add_modifier(Modifier::BOLD)
 		} else {
-			Style::default().fg(self.disabled_fg)
+			Style::default().fg(self.palette().disabled_fg)
+		}
+	}
+
+	/// like `title` but highlighting the pane of a tab that has
+	/// the focus
+	pub fn pane_title(&self, focused: bool) -> Style {
+		let style = self.title(focused);
+		if focused {
+			style.fg(self.palette().focused_title)
+		} else {
+			style
 		}
 	}
 
@@ -83,17 +250,13 @@ impl Theme {
 			Style::default()
 		};
 
-		if selected {
-			branch.patch(Style::default().bg(self.selection_bg))
-		} else {
-			branch
-		}
+		self.apply_select(branch, selected)
 	}
 
 	pub fn tab(&self, selected: bool) -> Style {
 		if selected {
 			self.text(true, false)
-				.fg(self.selected_tab)
+				.fg(self.palette().selected_tab)
 				.add_modifier(Modifier::UNDERLINED)
 		} else {
 			self.text(false, false)
@@ -101,45 +264,47 @@ impl Theme {
 	}
 
 	pub fn tags(&self, selected: bool) -> Style {
-		Style::default()
-			.fg(self.selected_tab)
-			.add_modifier(Modifier::BOLD)
-			.bg(if selected {
-				self.selection_bg
-			} else {
-				Color::Reset
-			})
+		self.apply_select(
+			Style::default()
+				.fg(self.palette().selected_tab)
+				.add_modifier(Modifier::BOLD)
+				.bg(Color::Reset),
+			selected,
+		)
 	}
 
 	pub fn text(&self, enabled: bool, selected: bool) -> Style {
 		match (enabled, selected) {
-			(false, _) => Style::default().fg(self.disabled_fg),
+			(false, _) => {
+				Style::default().fg(self.palette().disabled_fg)
+			}
 			(true, false) => Style::default(),
-			(true, true) => Style::default()
-				.fg(self.command_fg)
-				.bg(self.selection_bg),
+			(true, true) => self.apply_select(
+				Style::default().fg(self.palette().command_fg),
+				true,
+			),
 		}
 	}
 
 	pub fn item(&self, typ: StatusItemType, selected: bool) -> Style {
-		let style = match typ {
-			StatusItemType::New => {
-				Style::default().fg(self.diff_file_added)
-			}
-			StatusItemType::Modified => {
-				Style::default().fg(self.diff_file_modified)
-			}
-			StatusItemType::Deleted => {
-				Style::default().fg(self.diff_file_removed)
-			}
-			StatusItemType::Renamed => {
-				Style::default().fg(self.diff_file_moved)
-			}
-			StatusItemType::Conflicted => Style::default()
-				.fg(self.diff_file_modified)
-				.add_modifier(Modifier::BOLD),
-			StatusItemType::Typechange => Style::default(),
-		};
+		let style =
+			match typ {
+				StatusItemType::New => Style::default()
+					.fg(self.palette().diff_file_added),
+				StatusItemType::Modified => Style::default()
+					.fg(self.palette().diff_file_modified),
+				StatusItemType::Deleted => Style::default()
+					.fg(self.palette().diff_file_removed),
+				StatusItemType::Renamed => Style::default()
+					.fg(self.palette().diff_file_moved),
+				StatusItemType::Conflicted => Style::default()
+					.fg(self.palette().diff_file_modified)
+					.add_modifier(Modifier::BOLD),
+				StatusItemType::Typechange => Style::default(),
+				StatusItemType::EmptyDir => {
+					Style::default().fg(self.palette().disabled_fg)
+				}
+			};
 
 		self.apply_select(style, selected)
 	}
@@ -152,33 +317,48 @@ impl Theme {
 		let style = if is_folder {
 			Style::default()
 		} else {
-			Style::default().fg(self.diff_file_modified)
+			Style::default().fg(self.palette().diff_file_modified)
 		};
 
 		self.apply_select(style, selected)
 	}
 
 	fn apply_select(&self, style: Style, selected: bool) -> Style {
-		if selected {
-			style.bg(self.selection_bg)
-		} else {
-			style
+		if !selected {
+			return style;
 		}
+
+		let indicators = self.indicators();
+		let palette = self.palette();
+		let mut style = style.bg(palette.selection_bg);
+
+		// colored text is hard to read on the light selection
+		if indicators.high_contrast {
+			style = style
+				.fg(palette.command_fg)
+				.add_modifier(Modifier::BOLD);
+		}
+
+		if indicators.selection_marker {
+			style = style.add_modifier(Modifier::UNDERLINED);
+		}
+
+		style
 	}
 
 	pub fn option(&self, on: bool) -> Style {
 		if on {
-			Style::default().fg(self.diff_line_add)
+			Style::default().fg(self.palette().diff_line_add)
 		} else {
-			Style::default().fg(self.diff_line_delete)
+			Style::default().fg(self.palette().diff_line_delete)
 		}
 	}
 
 	pub fn diff_hunk_marker(&self, selected: bool) -> Style {
 		if selected {
-			Style::default().bg(self.selection_bg)
+			Style::default().bg(self.palette().selection_bg)
 		} else {
-			Style::default().fg(self.disabled_fg)
+			Style::default().fg(self.palette().disabled_fg)
 		}
 	}
 
@@ -189,16 +369,16 @@ impl Theme {
 	) -> Style {
 		let style = match typ {
 			DiffLineType::Add => {
-				Style::default().fg(self.diff_line_add)
+				Style::default().fg(self.palette().diff_line_add)
 			}
 			DiffLineType::Delete => {
-				Style::default().fg(self.diff_line_delete)
+				Style::default().fg(self.palette().diff_line_delete)
 			}
 			DiffLineType::Header => Style::default()
-				.fg(self.disabled_fg)
+				.fg(self.palette().disabled_fg)
 				.add_modifier(Modifier::BOLD),
 			DiffLineType::None => Style::default().fg(if selected {
-				self.command_fg
+				self.palette().command_fg
 			} else {
 				Color::Reset
 			}),
@@ -207,33 +387,126 @@ impl Theme {
 		self.apply_select(style, selected)
 	}
 
+	/// bold `+`/`-` in front of the line, blank for other lines
+	pub fn diff_glyph(
+		&self,
+		typ: DiffLineType,
+		selected: bool,
+	) -> Span<'static> {
+		let glyph = match typ {
+			DiffLineType::Add => "+",
+			DiffLineType::Delete => "-",
+			DiffLineType::Header | DiffLineType::None => " ",
+		};
+
+		Span::styled(
+			glyph,
+			self.diff_line(typ, selected)
+				.add_modifier(Modifier::BOLD),
+		)
+	}
+
+	/// patched onto trailing whitespace of added lines
+	pub fn diff_trailing_whitespace(&self) -> Style {
+		Style::default().bg(self.palette().diff_trailing_whitespace)
+	}
+
+	/// patched onto added and removed lines moved between files
+	pub fn diff_line_moved(&self) -> Style {
+		Style::default().fg(self.palette().diff_line_moved)
+	}
+
+	/// patched onto the markers of whitespace
+	pub fn diff_whitespace(&self) -> Style {
+		Style::default().fg(self.palette().diff_whitespace)
+	}
+
+	/// patched onto the style of the text containing the match
+	#[allow(clippy::unused_self)]
+	pub fn search_match(&self, active: bool) -> Style {
+		let style = Style::default().add_modifier(Modifier::REVERSED);
+
+		if active {
+			style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+		} else {
+			style
+		}
+	}
+
+	pub fn text_warning(&self) -> Style {
+		Style::default().fg(self.palette().diff_file_modified)
+	}
+
 	pub fn text_danger(&self) -> Style {
-		Style::default().fg(self.danger_fg)
+		Style::default().fg(self.palette().danger_fg)
+	}
+
+	pub fn message(
+		&self,
+		severity: Severity,
+		selected: bool,
+	) -> Style {
+		let style = match severity {
+			Severity::Info => Style::default(),
+			Severity::Success => {
+				Style::default().fg(self.palette().diff_line_add)
+			}
+			Severity::Error => {
+				Style::default().fg(self.palette().danger_fg)
+			}
+		};
+
+		self.apply_select(style, selected)
 	}
 
 	pub fn commandbar(&self, enabled: bool, line: usize) -> Style {
 		if enabled {
-			Style::default().fg(self.command_fg)
+			Style::default().fg(self.palette().command_fg)
 		} else {
-			Style::default().fg(self.disabled_fg)
+			Style::default().fg(self.palette().disabled_fg)
 		}
 		.bg(if line == 0 {
-			self.selection_bg
+			self.palette().selection_bg
 		} else {
-			self.cmdbar_extra_lines_bg
+			self.palette().cmdbar_extra_lines_bg
 		})
 	}
 
 	pub fn commit_hash(&self, selected: bool) -> Style {
 		self.apply_select(
-			Style::default().fg(self.commit_hash),
+			Style::default().fg(self.palette().commit_hash),
 			selected,
 		)
 	}
 
+	/// `code` spans in commit messages
+	/// key hints in the message of an empty pane
+	pub fn key_hint(&self) -> Style {
+		Style::default()
+			.fg(self.palette().focused_title)
+			.add_modifier(Modifier::BOLD)
+	}
+
+	pub fn commit_message_code(&self) -> Style {
+		Style::default().fg(self.palette().commit_time)
+	}
+
+	/// urls and issue references in commit messages
+	pub fn commit_message_link(&self, selected: bool) -> Style {
+		let style = Style::default()
+			.fg(self.palette().commit_author)
+			.add_modifier(Modifier::UNDERLINED);
+
+		if selected {
+			style.add_modifier(Modifier::REVERSED)
+		} else {
+			style
+		}
+	}
+
 	pub fn log_marker(&self, selected: bool) -> Style {
 		let mut style = Style::default()
-			.fg(self.commit_author)
+			.fg(self.palette().commit_author)
 			.add_modifier(Modifier::BOLD);
 
 		style = self.apply_select(style, selected);
@@ -241,16 +514,26 @@ impl Theme {
 		style
 	}
 
+	/// commits that are not on the upstream yet
+	pub fn log_unpushed(&self, selected: bool) -> Style {
+		self.apply_select(
+			Style::default()
+				.fg(self.palette().diff_file_modified)
+				.add_modifier(Modifier::BOLD),
+			selected,
+		)
+	}
+
 	pub fn commit_time(&self, selected: bool) -> Style {
 		self.apply_select(
-			Style::default().fg(self.commit_time),
+			Style::default().fg(self.palette().commit_time),
 			selected,
 		)
 	}
 
 	pub fn commit_author(&self, selected: bool) -> Style {
 		self.apply_select(
-			Style::default().fg(self.commit_author),
+			Style::default().fg(self.palette().commit_author),
 			selected,
 		)
 	}
@@ -261,17 +544,17 @@ impl Theme {
 	) -> Style {
 		if is_blamed_commit {
 			Style::default()
-				.fg(self.commit_hash)
+				.fg(self.palette().commit_hash)
 				.add_modifier(Modifier::BOLD)
 		} else {
-			Style::default().fg(self.commit_hash)
+			Style::default().fg(self.palette().commit_hash)
 		}
 	}
 
 	pub fn push_gauge(&self) -> Style {
 		Style::default()
-			.fg(self.push_gauge_fg)
-			.bg(self.push_gauge_bg)
+			.fg(self.palette().push_gauge_fg)
+			.bg(self.palette().push_gauge_bg)
 	}
 
 	// This will only be called when theme.ron doesn't already exists
@@ -306,7 +589,10 @@ impl Theme {
 					Err(anyhow::anyhow!("{}\n Old file was renamed to {:?}.\n Defaults loaded and saved as {:?}",
                         e,config_path_old,config_path.to_string_lossy()))
 				}
-				Ok(res) => Ok(res),
+				Ok(res) => {
+					res.set_indicators(res.indicators);
+					Ok(res)
+				}
 			}
 		} else {
 			Self::default().save(file)?;
@@ -315,6 +601,22 @@ impl Theme {
 	}
 }
 
+const fn default_focused_title() -> Color {
+	Color::Cyan
+}
+
+const fn default_diff_trailing_whitespace() -> Color {
+	Color::Red
+}
+
+const fn default_diff_whitespace() -> Color {
+	Color::DarkGray
+}
+
+const fn default_diff_line_moved() -> Color {
+	Color::Cyan
+}
+
 impl Default for Theme {
 	fn default() -> Self {
 		Self {
@@ -335,6 +637,13 @@ impl Default for Theme {
 			danger_fg: Color::Red,
 			push_gauge_bg: Color::Blue,
 			push_gauge_fg: Color::Reset,
+			focused_title: default_focused_title(),
+			diff_trailing_whitespace:
+				default_diff_trailing_whitespace(),
+			diff_whitespace: default_diff_whitespace(),
+			diff_line_moved: default_diff_line_moved(),
+			indicators: Indicators::default(),
+			active_indicators: Cell::new(Indicators::default()),
 		}
 	}
 }
@@ -0,0 +1,227 @@
+use crate::{args::get_app_cache_path, bookmarks::Bookmark};
+use anyhow::Result;
+use asyncgit::{
+	sync::{self, diff::DiffOptions},
+	CWD,
+};
+use ron::ser::{to_string_pretty, PrettyConfig};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::PathBuf};
+
+/// bumped on incompatible changes, state of other versions is
+/// dropped. new fields only need a default to stay compatible
+const VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct DiffState {
+	pub ignore_whitespace: bool,
+	pub context: u32,
+	pub interhunk_lines: u32,
+}
+
+impl From<DiffOptions> for DiffState {
+	fn from(options: DiffOptions) -> Self {
+		Self {
+			ignore_whitespace: options.ignore_whitespace,
+			context: options.context,
+			interhunk_lines: options.interhunk_lines,
+		}
+	}
+}
+
+impl From<DiffState> for DiffOptions {
+	fn from(state: DiffState) -> Self {
+		Self {
+			ignore_whitespace: state.ignore_whitespace,
+			context: state.context,
+			interhunk_lines: state.interhunk_lines,
+		}
+	}
+}
+
+/// lightweight ui state of a single repo,
+/// references in here might be stale by the time it is loaded
+#[derive(
+	Serialize, Deserialize, Default, Clone, PartialEq, Debug,
+)]
+#[serde(default)]
+pub struct UiState {
+	pub tab: usize,
+	/// id of the commit selected in the log
+	pub revlog_selection: Option<String>,
+	/// path selected in the status tab
+	pub status_selection: Option<String>,
+	/// branch list shows remote branches
+	pub branches_remote: bool,
+	pub diff: Option<DiffState>,
+	/// percentage of the status tab taken by the file lists
+	pub status_split: Option<u16>,
+	/// only saved if enabled in the options, kept for the session
+	/// either way
+	pub bookmarks: Vec<Bookmark>,
+}
+
+/// what is remembered for the next start, set in the options
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Remember {
+	pub ui_state: bool,
+	/// needs `ui_state`
+	pub bookmarks: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct UiStateFile {
+	version: u32,
+	/// remembering the ui state is opt-out (in options)
+	enabled: bool,
+	/// saving bookmarks is opt-in
+	bookmarks: bool,
+	/// keyed by canonical repo path
+	repos: HashMap<String, UiState>,
+}
+
+impl Default for UiStateFile {
+	fn default() -> Self {
+		Self {
+			version: VERSION,
+			enabled: true,
+			bookmarks: false,
+			repos: HashMap::new(),
+		}
+	}
+}
+
+impl UiStateFile {
+	fn parse(data: &[u8]) -> Result<Self> {
+		let mut file: Self = ron::de::from_bytes(data)?;
+
+		if file.version != VERSION {
+			log::info!(
+				"dropping ui state of version {} (expected {})",
+				file.version,
+				VERSION
+			);
+			file.version = VERSION;
+			file.repos.clear();
+		}
+
+		Ok(file)
+	}
+}
+
+fn ui_state_file() -> Result<PathBuf> {
+	Ok(get_app_cache_path()?.join("ui_state.ron"))
+}
+
+fn read_file() -> Result<UiStateFile> {
+	let file = ui_state_file()?;
+	if !file.exists() {
+		return Ok(UiStateFile::default());
+	}
+
+	UiStateFile::parse(&fs::read(file)?)
+}
+
+pub fn repo_key() -> Result<String> {
+	Ok(fs::canonicalize(sync::repo_dir(CWD)?)?
+		.to_string_lossy()
+		.to_string())
+}
+
+/// what is remembered and the state of the current repo if any was
+/// saved
+pub fn load() -> Result<(Remember, Option<UiState>)> {
+	let mut file = read_file()?;
+	let state = file.repos.remove(&repo_key()?);
+
+	Ok((
+		Remember {
+			ui_state: file.enabled,
+			bookmarks: file.bookmarks,
+		},
+		state,
+	))
+}
+
+/// stores the state of the current repo,
+/// `None` forgets it (opted out)
+pub fn save(state: Option<UiState>, bookmarks: bool) -> Result<()> {
+	let mut file = read_file().unwrap_or_default();
+	let key = repo_key()?;
+
+	file.enabled = state.is_some();
+	file.bookmarks = bookmarks;
+	if let Some(mut state) = state {
+		if !bookmarks {
+			state.bookmarks.clear();
+		}
+
+		file.repos.insert(key, state);
+	} else {
+		file.repos.remove(&key);
+	}
+
+	let data = to_string_pretty(&file, PrettyConfig::default())?;
+	fs::write(ui_state_file()?, data)?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bookmarks::Location;
+
+	#[test]
+	fn test_missing_fields() {
+		let file = UiStateFile::parse(
+			br#"(version: 1, repos: { "/repo": (tab: 2, new: 1) })"#,
+		)
+		.unwrap();
+
+		assert!(file.enabled);
+		assert!(!file.bookmarks);
+		assert_eq!(
+			file.repos.get("/repo"),
+			Some(&UiState {
+				tab: 2,
+				..UiState::default()
+			})
+		);
+	}
+
+	#[test]
+	fn test_other_version_dropped() {
+		let file = UiStateFile::parse(
+			br#"(version: 99, enabled: false, repos: { "/repo": (tab: 2, future: true) })"#,
+		)
+		.unwrap();
+
+		assert!(!file.enabled);
+		assert!(file.repos.is_empty());
+	}
+
+	#[test]
+	fn test_bookmarks() {
+		let file = UiStateFile::parse(
+			br#"(version: 1, bookmarks: true, repos: { "/repo": (bookmarks: [
+				(label: "1", location: StatusFile(path: "a.txt", line: 3), context: "a.txt"),
+			]) })"#,
+		)
+		.unwrap();
+
+		assert!(file.bookmarks);
+		assert_eq!(
+			file.repos.get("/repo").unwrap().bookmarks,
+			vec![Bookmark {
+				label: String::from("1"),
+				location: Location::StatusFile {
+					path: String::from("a.txt"),
+					line: 3,
+				},
+				context: String::from("a.txt"),
+			}]
+		);
+	}
+}